@@ -62,12 +62,20 @@ fn main() {
             match value.transport {
                 Some(Icmpv4(value)) => println!(" Icmpv4 {:?}", value),
                 Some(Icmpv6(value)) => println!(" Icmpv6 {:?}", value),
+                Some(Igmp(value)) => println!(" Igmp {:?}", value.header()),
                 Some(Udp(value)) => println!("  UDP {:?} -> {:?}", value.source_port(), value.destination_port()),
                 Some(Tcp(value)) => {
                     println!("  TCP {:?} -> {:?}", value.source_port(), value.destination_port());
                     let options: Vec<Result<TcpOptionElement, TcpOptionReadError>> = value.options_iterator().collect();
                     println!("    {:?}", options);
                 }
+                Some(Sctp(sctp)) => {
+                    println!("  SCTP {:?} -> {:?}", sctp.source_port(), sctp.destination_port());
+                    let chunks: Vec<Result<SctpChunkSlice, SctpChunkReadError>> = SctpChunkIterator::from_slice(value.payload).collect();
+                    println!("    {:?}", chunks);
+                }
+                Some(UdpLite(value)) => println!("  UDP-Lite {:?} -> {:?}", value.source_port(), value.destination_port()),
+                Some(Dccp(value)) => println!("  DCCP {:?} -> {:?} {:?}", value.source_port(), value.destination_port(), value.dccp_type()),
                 Some(Unknown(ip_protocol)) => println!("  Unknwon Protocol (ip protocol number {:?}", ip_protocol),
                 None => {}
             }
@@ -0,0 +1,328 @@
+use super::super::*;
+
+/// Limits enforced while decoding a (possibly compressed) DNS name.
+///
+/// The defaults follow the limits defined in RFC 1035 (maximum 255 octets
+/// for the uncompressed name) and allow up to 16 compression pointer jumps.
+/// The limits can be lowered or raised for protocols that reuse the DNS
+/// name encoding (mDNS, LLMNR, NetBIOS ...).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DnsNameLimits {
+    /// Maximum number of compression pointers that are followed while decoding a name.
+    pub max_pointer_jumps: usize,
+    /// Maximum length of the uncompressed name in octets (length octets,
+    /// label octets and the terminating zero octet).
+    pub max_name_len: usize,
+    /// Maximum number of labels (not counting the terminating root label).
+    pub max_label_count: usize,
+}
+
+impl DnsNameLimits {
+    /// Maximum uncompressed name length defined in RFC 1035.
+    pub const RFC_MAX_NAME_LEN: usize = 255;
+
+    /// Maximum length of a single label defined in RFC 1035.
+    pub const RFC_MAX_LABEL_LEN: usize = 63;
+}
+
+impl Default for DnsNameLimits {
+    fn default() -> DnsNameLimits {
+        DnsNameLimits {
+            max_pointer_jumps: 16,
+            max_name_len: DnsNameLimits::RFC_MAX_NAME_LEN,
+            // every label needs at least 2 octets (length + 1 character)
+            max_label_count: DnsNameLimits::RFC_MAX_NAME_LEN/2,
+        }
+    }
+}
+
+/// Errors that can occur while decoding a DNS name.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DnsNameError {
+    /// The name continues past the end of the message. The value is
+    /// the offset in the message at which more data was expected.
+    UnexpectedEndOfMessage(usize),
+    /// A compression pointer points outside of the message.
+    PointerOutOfBounds{ pointer: u16, message_len: usize },
+    /// More compression pointers were encountered than allowed by the limits
+    /// (this includes pointer loops).
+    TooManyPointerJumps(usize),
+    /// The uncompressed name is longer than allowed by the limits.
+    NameTooLong(usize),
+    /// The name contains more labels than allowed by the limits.
+    TooManyLabels(usize),
+    /// A label starts with the reserved bit combinations `0b01` or `0b10`
+    /// (extended label types are not supported). The value is the raw length octet.
+    UnsupportedLabelType(u8),
+}
+
+impl Error for DnsNameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for DnsNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DnsNameError::*;
+        match self {
+            UnexpectedEndOfMessage(offset) => {
+                write!(f, "DnsNameError: Unexpected end of message. The DNS name continues past the end of the message (at offset {}).", offset)
+            },
+            PointerOutOfBounds{ pointer, message_len } => {
+                write!(f, "DnsNameError: Compression pointer {} points outside of the message ({} bytes).", pointer, message_len)
+            },
+            TooManyPointerJumps(max) => {
+                write!(f, "DnsNameError: Too many compression pointers (more then the allowed maximum of {} jumps).", max)
+            },
+            NameTooLong(max) => {
+                write!(f, "DnsNameError: DNS name is longer then the allowed maximum of {} octets.", max)
+            },
+            TooManyLabels(max) => {
+                write!(f, "DnsNameError: DNS name contains more then the allowed maximum of {} labels.", max)
+            },
+            UnsupportedLabelType(value) => {
+                write!(f, "DnsNameError: Unsupported label type in length octet {:#04x}.", value)
+            },
+        }
+    }
+}
+
+/// A validated (possibly compressed) DNS name located in a DNS message.
+///
+/// The name is validated when the slice is created, which allows the
+/// label iterator to be used without any further error handling. No
+/// allocations are performed, the labels are returned as sub-slices
+/// of the message.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{DnsNameSlice, DnsNameLimits};
+///
+/// // "example.com" at offset 0 & "www" + pointer to offset 0 after it
+/// let message = [
+///     7, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
+///     3, b'c', b'o', b'm',
+///     0,
+///     3, b'w', b'w', b'w',
+///     0xc0, 0,
+/// ];
+/// let name = DnsNameSlice::from_message(&message, 13, DnsNameLimits::default()).unwrap();
+/// assert_eq!(6, name.encoded_len());
+/// assert_eq!("www.example.com", name.to_string());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DnsNameSlice<'a> {
+    /// Complete message (required to resolve pointers).
+    message: &'a [u8],
+    /// Offset of the first length octet of the name in the message.
+    offset: usize,
+    /// Number of octets the name occupies at `offset`.
+    encoded_len: usize,
+    /// Uncompressed length of the name.
+    name_len: usize,
+    /// Number of labels (without the root label).
+    label_count: usize,
+}
+
+impl<'a> DnsNameSlice<'a> {
+    /// Decodes & validates the name starting at `offset` in `message`
+    /// while enforcing the given limits.
+    ///
+    /// `message` has to contain the complete DNS message (starting with
+    /// the DNS header) as the compression pointers are offsets relative to the
+    /// start of the message.
+    pub fn from_message(message: &'a [u8], offset: usize, limits: DnsNameLimits) -> Result<DnsNameSlice<'a>, DnsNameError> {
+        use DnsNameError::*;
+
+        let mut pos = offset;
+        let mut jumps = 0;
+        let mut encoded_len = None;
+        let mut name_len = 0;
+        let mut label_count = 0;
+
+        loop {
+            let len_octet = *message.get(pos).ok_or(UnexpectedEndOfMessage(pos))?;
+            match len_octet & 0b1100_0000 {
+                0b1100_0000 => {
+                    let second = *message.get(pos + 1).ok_or(UnexpectedEndOfMessage(pos + 1))?;
+                    if encoded_len.is_none() {
+                        encoded_len = Some(pos + 2 - offset);
+                    }
+                    jumps += 1;
+                    if jumps > limits.max_pointer_jumps {
+                        return Err(TooManyPointerJumps(limits.max_pointer_jumps));
+                    }
+                    let pointer = u16::from_be_bytes([len_octet & 0b0011_1111, second]);
+                    if usize::from(pointer) >= message.len() {
+                        return Err(PointerOutOfBounds{ pointer, message_len: message.len() });
+                    }
+                    pos = usize::from(pointer);
+                },
+                0 => {
+                    let label_len = usize::from(len_octet);
+                    name_len += 1 + label_len;
+                    if name_len > limits.max_name_len {
+                        return Err(NameTooLong(limits.max_name_len));
+                    }
+                    if 0 == label_len {
+                        return Ok(DnsNameSlice {
+                            message,
+                            offset,
+                            encoded_len: encoded_len.unwrap_or(pos + 1 - offset),
+                            name_len,
+                            label_count,
+                        });
+                    }
+                    label_count += 1;
+                    if label_count > limits.max_label_count {
+                        return Err(TooManyLabels(limits.max_label_count));
+                    }
+                    if message.len() < pos + 1 + label_len {
+                        return Err(UnexpectedEndOfMessage(message.len()));
+                    }
+                    pos += 1 + label_len;
+                },
+                _ => return Err(UnsupportedLabelType(len_octet)),
+            }
+        }
+    }
+
+    /// Returns the complete message the name is located in.
+    #[inline]
+    pub fn message(&self) -> &'a [u8] {
+        self.message
+    }
+
+    /// Offset of the name in the message.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Number of octets the (compressed) name occupies at its offset in the
+    /// message. Data following the name starts at `offset() + encoded_len()`.
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        self.encoded_len
+    }
+
+    /// Returns the slice containing the encoded name at its offset
+    /// (including the trailing compression pointer if present).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        &self.message[self.offset..self.offset + self.encoded_len]
+    }
+
+    /// Length of the name in octets if it were serialized without
+    /// compression (including the terminating zero octet).
+    #[inline]
+    pub fn name_len(&self) -> usize {
+        self.name_len
+    }
+
+    /// Number of labels in the name (not counting the root label).
+    #[inline]
+    pub fn label_count(&self) -> usize {
+        self.label_count
+    }
+
+    /// Returns true if the name is the root name (no labels).
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        0 == self.label_count
+    }
+
+    /// Returns an iterator over the labels of the name (compression
+    /// pointers are followed transparently).
+    #[inline]
+    pub fn labels(&self) -> DnsNameLabels<'a> {
+        DnsNameLabels {
+            message: self.message,
+            pos: self.offset,
+            remaining: self.label_count,
+        }
+    }
+
+    /// Writes the uncompressed wire format of the name to the given slice and
+    /// returns the unused part of the slice.
+    pub fn write_uncompressed_to_slice<'b>(&self, slice: &'b mut [u8]) -> Result<&'b mut [u8], WriteError> {
+        if slice.len() < self.name_len {
+            return Err(WriteError::SliceTooSmall(self.name_len));
+        }
+        let mut pos = 0;
+        for label in self.labels() {
+            slice[pos] = label.len() as u8;
+            slice[pos + 1..pos + 1 + label.len()].copy_from_slice(label);
+            pos += 1 + label.len();
+        }
+        slice[pos] = 0;
+        Ok(&mut slice[self.name_len..])
+    }
+}
+
+impl<'a> fmt::Display for DnsNameSlice<'a> {
+    /// Writes the name in dotted presentation format. The root name is written
+    /// as a single ".". Dots, backslashes & non printable characters in labels are
+    /// escaped as described in RFC 4343.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return write!(f, ".");
+        }
+        for (i, label) in self.labels().enumerate() {
+            if 0 != i {
+                write!(f, ".")?;
+            }
+            for c in label {
+                match c {
+                    b'.' | b'\\' => write!(f, "\\{}", *c as char)?,
+                    0x21..=0x7e => write!(f, "{}", *c as char)?,
+                    _ => write!(f, "\\{:03}", c)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the labels of a [`DnsNameSlice`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DnsNameLabels<'a> {
+    message: &'a [u8],
+    pos: usize,
+    remaining: usize,
+}
+
+impl<'a> Iterator for DnsNameLabels<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if 0 == self.remaining {
+            return None;
+        }
+        // the name was validated during the construction of the
+        // DnsNameSlice so indexing can not fail here
+        loop {
+            let len_octet = self.message[self.pos];
+            if 0b1100_0000 == len_octet & 0b1100_0000 {
+                self.pos = usize::from(u16::from_be_bytes([
+                    len_octet & 0b0011_1111,
+                    self.message[self.pos + 1]
+                ]));
+            } else {
+                let start = self.pos + 1;
+                let end = start + usize::from(len_octet);
+                self.pos = end;
+                self.remaining -= 1;
+                return Some(&self.message[start..end]);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for DnsNameLabels<'a> {}
@@ -0,0 +1,437 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::fmt::{Debug, Formatter};
+use std::slice::from_raw_parts;
+
+/// Generic Network Virtualization Encapsulation header (RFC 8926).
+///
+/// The header is carried in udp packets (destination port 6081) and is
+/// followed by the encapsulated packet identified by `protocol_type` (usually
+/// an ethernet II frame, `ether_type::TRANSPARENT_ETHERNET_BRIDGING`). If the
+/// udp port is assigned to [`ApplicationProtocol::Geneve`] in the
+/// [`ParserRegistry`] the geneve header & the inner packet are decoded by
+/// [`SlicedPacket`] & [`PacketHeaders`] (see the `geneve` & `inner` fields).
+///
+/// The variable length options can be accessed via [`GeneveHeader::options_iterator`]
+/// & set via [`GeneveHeader::set_options`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, GeneveHeader, GeneveOption, PacketBuilder};
+///
+/// let mut geneve = GeneveHeader::new(ether_type::TRANSPARENT_ETHERNET_BRIDGING, 1234).unwrap();
+/// geneve.set_options(&[
+///     GeneveOption{ class: 0x0102, option_type: 0x80, data: &[1, 2, 3, 4] },
+/// ]).unwrap();
+///
+/// let option = geneve.options_iterator().next().unwrap();
+/// assert!(option.critical());
+/// assert_eq!(&[1, 2, 3, 4], option.data);
+///
+/// // encapsulate an (empty) ethernet frame
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(50000, GeneveHeader::UDP_PORT)
+///     .geneve(geneve)
+///     .write(&mut packet, &[0; 14])
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct GeneveHeader {
+    /// OAM flag (the packet contains a control message).
+    pub oam: bool,
+    /// Critical options present flag (has to be set if one of the options
+    /// has the critical bit set).
+    pub critical: bool,
+    /// Ether type of the encapsulated packet.
+    pub protocol_type: u16,
+    /// Virtual network identifier (24 bit value).
+    pub vni: u32,
+    /// Length of the options in the options_buffer in bytes.
+    options_len: u8,
+    options_buffer: [u8; GeneveHeader::MAX_OPTIONS_LEN],
+}
+
+impl GeneveHeader {
+    /// Udp port assigned to GENEVE by the IANA.
+    pub const UDP_PORT: u16 = 6081;
+
+    /// Length of a geneve header without options.
+    pub const MIN_LEN: usize = 8;
+
+    /// Maximum length of the options of a geneve header.
+    pub const MAX_OPTIONS_LEN: usize = 0b0011_1111 * 4;
+
+    /// Maximum length of a geneve header (including options).
+    pub const MAX_LEN: usize = GeneveHeader::MIN_LEN + GeneveHeader::MAX_OPTIONS_LEN;
+
+    /// Maximum value of the VNI.
+    pub const MAX_VNI: u32 = 0xff_ffff;
+
+    const OAM_FLAG: u8 = 0b1000_0000;
+    const CRITICAL_FLAG: u8 = 0b0100_0000;
+
+    /// Creates a header without options & flags.
+    pub fn new(protocol_type: u16, vni: u32) -> Result<GeneveHeader, ValueError> {
+        max_check_u32(vni, GeneveHeader::MAX_VNI, ErrorField::GeneveVni)?;
+        Ok(GeneveHeader {
+            oam: false,
+            critical: false,
+            protocol_type,
+            vni,
+            options_len: 0,
+            options_buffer: [0; GeneveHeader::MAX_OPTIONS_LEN],
+        })
+    }
+
+    /// Read a geneve header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(GeneveHeader, &[u8]), ReadError> {
+        let header = GeneveHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Reads a geneve header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<GeneveHeader, ReadError> {
+        let mut buffer = [0u8; GeneveHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..GeneveHeader::MIN_LEN])?;
+        let len = GeneveHeaderSlice::header_len_from_start(&buffer)?;
+        reader.read_exact(&mut buffer[GeneveHeader::MIN_LEN..len])?;
+        GeneveHeaderSlice::validate_options(&buffer[GeneveHeader::MIN_LEN..len])?;
+
+        // SAFETY:
+        // Length, version & options were validated above.
+        Ok(unsafe { GeneveHeaderSlice::from_slice_unchecked(&buffer[..len]) }.to_header())
+    }
+
+    /// Writes the geneve header to the current position.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        GeneveHeader::MIN_LEN + usize::from(self.options_len)
+    }
+
+    /// Returns a slice containing the serialized options.
+    #[inline]
+    pub fn options(&self) -> &[u8] {
+        &self.options_buffer[..usize::from(self.options_len)]
+    }
+
+    /// Returns an iterator over the options.
+    #[inline]
+    pub fn options_iterator(&self) -> GeneveOptionsIterator<'_> {
+        GeneveOptionsIterator { rest: self.options() }
+    }
+
+    /// Sets the options of the header.
+    ///
+    /// The `critical` flag is set if one of the options has the critical bit
+    /// set. If the options are not fitting into the header or the data of an
+    /// option is not valid no data is set and an error is returned.
+    pub fn set_options(&mut self, options: &[GeneveOption]) -> Result<(), ValueError> {
+        use crate::ValueError::*;
+
+        let mut len = 0;
+        for option in options {
+            let data_len = option.data.len();
+            if GeneveOption::MAX_DATA_LEN < data_len || 0 != data_len % 4 {
+                return Err(GeneveOptionDataLengthBad(data_len));
+            }
+            len += option.header_len();
+        }
+        if GeneveHeader::MAX_OPTIONS_LEN < len {
+            return Err(GeneveOptionsLengthBad(len));
+        }
+
+        let mut offset = 0;
+        for option in options {
+            let class = option.class.to_be_bytes();
+            self.options_buffer[offset..offset + 4].copy_from_slice(&[
+                class[0],
+                class[1],
+                option.option_type,
+                (option.data.len() / 4) as u8,
+            ]);
+            self.options_buffer[offset + 4..offset + option.header_len()].copy_from_slice(option.data);
+            offset += option.header_len();
+        }
+        self.options_len = len as u8;
+        self.critical = options.iter().any(|option| option.critical());
+        Ok(())
+    }
+
+    /// Returns the serialized form of the header (fails if the VNI
+    /// exceeds 24 bits).
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { GeneveHeader::MAX_LEN }>, ValueError> {
+        max_check_u32(self.vni, GeneveHeader::MAX_VNI, ErrorField::GeneveVni)?;
+
+        let mut flags = 0u8;
+        if self.oam {
+            flags |= GeneveHeader::OAM_FLAG;
+        }
+        if self.critical {
+            flags |= GeneveHeader::CRITICAL_FLAG;
+        }
+        let protocol_type = self.protocol_type.to_be_bytes();
+        let vni = self.vni.to_be_bytes();
+
+        let mut result = ArrayVec::new();
+        result.try_extend_from_slice(&[
+            // version 0
+            self.options_len / 4,
+            flags,
+            protocol_type[0],
+            protocol_type[1],
+            vni[1],
+            vni[2],
+            vni[3],
+            // reserved
+            0,
+        ]).unwrap();
+        result.try_extend_from_slice(self.options()).unwrap();
+        Ok(result)
+    }
+}
+
+impl Debug for GeneveHeader {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "GeneveHeader {{ oam: {}, critical: {}, protocol_type: {}, vni: {}, options: {:?} }}",
+            self.oam,
+            self.critical,
+            self.protocol_type,
+            self.vni,
+            self.options())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for GeneveHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "GeneveHeader {{ oam: {}, critical: {}, protocol_type: {}, vni: {}, options: {=[u8]} }}",
+            self.oam,
+            self.critical,
+            self.protocol_type,
+            self.vni,
+            self.options())
+    }
+}
+
+impl std::cmp::PartialEq for GeneveHeader {
+    fn eq(&self, other: &GeneveHeader) -> bool {
+        self.oam == other.oam &&
+        self.critical == other.critical &&
+        self.protocol_type == other.protocol_type &&
+        self.vni == other.vni &&
+        self.options() == other.options()
+    }
+}
+
+impl std::cmp::Eq for GeneveHeader {}
+
+impl std::hash::Hash for GeneveHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.oam.hash(state);
+        self.critical.hash(state);
+        self.protocol_type.hash(state);
+        self.vni.hash(state);
+        self.options().hash(state);
+    }
+}
+
+/// Slice containing a geneve header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct GeneveHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GeneveHeaderSlice<'a> {
+    /// Creates a geneve header slice from a slice (the version & the
+    /// length of the options are validated).
+    pub fn from_slice(slice: &'a [u8]) -> Result<GeneveHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < GeneveHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GeneveHeader::MIN_LEN));
+        }
+        let len = GeneveHeaderSlice::header_len_from_start(slice)?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        GeneveHeaderSlice::validate_options(&slice[GeneveHeader::MIN_LEN..len])?;
+
+        Ok(GeneveHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this
+            // code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Creates a geneve header slice from a slice (assumes slice size & content
+    /// was validated before).
+    ///
+    /// # Safety
+    ///
+    /// This method assumes that the slice was previously validated to contain
+    /// a valid geneve header. This means the slice length must be exactly the
+    /// header length indicated by the options length field & the options must
+    /// have been checked with `validate_options`.
+    unsafe fn from_slice_unchecked(slice: &'a [u8]) -> GeneveHeaderSlice<'a> {
+        GeneveHeaderSlice { slice }
+    }
+
+    /// Checks the version and returns the header length based on the
+    /// options length field in the first byte.
+    fn header_len_from_start(start: &[u8]) -> Result<usize, ReadError> {
+        let version = start[0] >> 6;
+        if 0 != version {
+            return Err(ReadError::GeneveUnsupportedVersion(version));
+        }
+        Ok(GeneveHeader::MIN_LEN + usize::from(start[0] & 0b0011_1111) * 4)
+    }
+
+    /// Checks that all options are located within the given options slice.
+    fn validate_options(options: &[u8]) -> Result<(), ReadError> {
+        let mut offset = 0;
+        while offset < options.len() {
+            // the options length is a multiple of 4, so the option
+            // header is always present
+            let len = 4 + usize::from(options[offset + 3] & 0b0001_1111) * 4;
+            if options.len() < offset + len {
+                return Err(ReadError::GeneveOptionLengthTooBig(offset));
+            }
+            offset += len;
+        }
+        Ok(())
+    }
+
+    /// Returns the slice containing the geneve header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the version field (always 0).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 6
+    }
+
+    /// Read the OAM flag.
+    #[inline]
+    pub fn oam(&self) -> bool {
+        0 != self.slice[1] & GeneveHeader::OAM_FLAG
+    }
+
+    /// Read the critical options present flag.
+    #[inline]
+    pub fn critical(&self) -> bool {
+        0 != self.slice[1] & GeneveHeader::CRITICAL_FLAG
+    }
+
+    /// Read the protocol type field (ether type of the encapsulated packet).
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the virtual network identifier (24 bit value).
+    #[inline]
+    pub fn vni(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[4], self.slice[5], self.slice[6]])
+    }
+
+    /// Returns a slice containing the options.
+    #[inline]
+    pub fn options(&self) -> &'a [u8] {
+        &self.slice[GeneveHeader::MIN_LEN..]
+    }
+
+    /// Returns an iterator over the options.
+    #[inline]
+    pub fn options_iterator(&self) -> GeneveOptionsIterator<'a> {
+        GeneveOptionsIterator { rest: self.options() }
+    }
+
+    /// Decode all the fields and copy the results to a [`GeneveHeader`] struct.
+    pub fn to_header(&self) -> GeneveHeader {
+        let options = self.options();
+        GeneveHeader {
+            oam: self.oam(),
+            critical: self.critical(),
+            protocol_type: self.protocol_type(),
+            vni: self.vni(),
+            options_len: options.len() as u8,
+            options_buffer: {
+                let mut values = [0; GeneveHeader::MAX_OPTIONS_LEN];
+                values[..options.len()].copy_from_slice(options);
+                values
+            },
+        }
+    }
+}
+
+/// Option of a geneve header (type-length-value).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct GeneveOption<'a> {
+    /// Namespace of the option type.
+    pub class: u16,
+    /// Type of the option (the most significant bit is the critical bit).
+    pub option_type: u8,
+    /// Option data (a multiple of 4 bytes & at most 124 bytes).
+    pub data: &'a [u8],
+}
+
+impl<'a> GeneveOption<'a> {
+    /// Bit in the `option_type` indicating that the option is critical.
+    pub const CRITICAL_BIT: u8 = 0b1000_0000;
+
+    /// Maximum length of the option data.
+    pub const MAX_DATA_LEN: usize = 0b0001_1111 * 4;
+
+    /// Returns true if the critical bit is set in the `option_type`
+    /// (the packet has to be dropped if the option is not supported).
+    #[inline]
+    pub fn critical(&self) -> bool {
+        0 != self.option_type & GeneveOption::CRITICAL_BIT
+    }
+
+    /// Length of the serialized option in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        4 + self.data.len()
+    }
+}
+
+/// Iterator over the options of a geneve header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GeneveOptionsIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for GeneveOptionsIterator<'a> {
+    type Item = GeneveOption<'a>;
+
+    fn next(&mut self) -> Option<GeneveOption<'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        // the length of the options were validated when the header
+        // was created
+        let len = 4 + usize::from(self.rest[3] & 0b0001_1111) * 4;
+        let result = GeneveOption {
+            class: u16::from_be_bytes([self.rest[0], self.rest[1]]),
+            option_type: self.rest[2],
+            data: &self.rest[4..len],
+        };
+        self.rest = &self.rest[len..];
+        Some(result)
+    }
+}
@@ -0,0 +1,594 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+
+use std::fmt::{Debug, Formatter};
+use std::slice::from_raw_parts;
+
+/// Message types of GTP-U headers (3GPP TS 29.281).
+pub mod gtpu_message_type {
+    /// Echo request.
+    pub const ECHO_REQUEST: u8 = 1;
+    /// Echo response.
+    pub const ECHO_RESPONSE: u8 = 2;
+    /// Error indication.
+    pub const ERROR_INDICATION: u8 = 26;
+    /// Supported extension headers notification.
+    pub const SUPPORTED_EXTENSION_HEADERS_NOTIFICATION: u8 = 31;
+    /// End marker.
+    pub const END_MARKER: u8 = 254;
+    /// Encapsulated user data (T-PDU).
+    pub const G_PDU: u8 = 255;
+}
+
+/// Types of GTP-U extension headers (3GPP TS 29.281).
+pub mod gtpu_extension_type {
+    /// No more extension headers.
+    pub const NO_MORE_EXTENSION_HEADERS: u8 = 0x00;
+    /// Service class indicator.
+    pub const SERVICE_CLASS_INDICATOR: u8 = 0x20;
+    /// UDP port (error indications).
+    pub const UDP_PORT: u8 = 0x40;
+    /// RAN container.
+    pub const RAN_CONTAINER: u8 = 0x81;
+    /// Long PDCP PDU number.
+    pub const LONG_PDCP_PDU_NUMBER: u8 = 0x82;
+    /// Xw RAN container.
+    pub const XW_RAN_CONTAINER: u8 = 0x83;
+    /// NR RAN container.
+    pub const NR_RAN_CONTAINER: u8 = 0x84;
+    /// PDU session container (5G, contains the QoS flow identifier).
+    pub const PDU_SESSION_CONTAINER: u8 = 0x85;
+    /// PDCP PDU number.
+    pub const PDCP_PDU_NUMBER: u8 = 0xc0;
+}
+
+/// GTP user plane header (GTPv1-U, 3GPP TS 29.281) including the optional
+/// sequence number, N-PDU number & extension headers.
+///
+/// The header is carried in udp packets (port 2152). If the udp port is
+/// assigned to [`ApplicationProtocol::GtpU`] in the [`ParserRegistry`] the
+/// GTP-U header & the encapsulated ip packet of G-PDUs are decoded by
+/// [`SlicedPacket`] & [`PacketHeaders`] (see the `gtpu` & `inner` fields).
+///
+/// The extension headers can be accessed via [`GtpuHeader::extensions_iterator`]
+/// & set via [`GtpuHeader::set_extensions`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{gtpu_extension_type, GtpuExtension, GtpuHeader, PacketBuilder, ParserRegistry, SlicedPacket};
+///
+/// // inner ip packet
+/// let mut inner = Vec::new();
+/// PacketBuilder::ipv4([10,0,0,1], [10,0,0,2], 20)
+///     .udp(1234, 5678)
+///     .write(&mut inner, &[1,2,3,4])
+///     .unwrap();
+///
+/// // gtp-u header with a pdu session container (qos flow identifier 9)
+/// let mut gtpu = GtpuHeader::g_pdu(0x1234_5678);
+/// gtpu.set_extensions(&[
+///     GtpuExtension{ extension_type: gtpu_extension_type::PDU_SESSION_CONTAINER, content: &[0x10, 9] },
+/// ]).unwrap();
+/// gtpu.length = (gtpu.header_len() - GtpuHeader::MIN_LEN + inner.len()) as u16;
+///
+/// // outer packet
+/// let mut udp_payload = gtpu.to_bytes().unwrap().to_vec();
+/// udp_payload.extend_from_slice(&inner);
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(GtpuHeader::UDP_PORT, GtpuHeader::UDP_PORT)
+///     .write(&mut packet, &udp_payload)
+///     .unwrap();
+///
+/// let registry = ParserRegistry::with_well_known_ports();
+/// let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+/// let gtpu = sliced.gtpu.unwrap();
+/// assert_eq!(0x1234_5678, gtpu.teid());
+/// assert_eq!(&[0x10, 9], gtpu.extensions_iterator().next().unwrap().content);
+/// assert_eq!(&[1,2,3,4], sliced.inner.unwrap().payload);
+/// ```
+#[derive(Clone)]
+pub struct GtpuHeader {
+    /// Type of the message ([`gtpu_message_type::G_PDU`] for encapsulated user data).
+    pub message_type: u8,
+    /// Length of the data following the mandatory 8 bytes of the header
+    /// (optional fields, extension headers & payload).
+    pub length: u16,
+    /// Tunnel endpoint identifier.
+    pub teid: u32,
+    /// Sequence number (S flag).
+    pub sequence_number: Option<u16>,
+    /// N-PDU number (PN flag).
+    pub n_pdu_number: Option<u8>,
+    /// Type of the first extension header (if extension headers are present).
+    first_extension_type: u8,
+    /// Length of the serialized extension headers in the extensions_buffer in bytes.
+    extensions_len: u8,
+    extensions_buffer: [u8; GtpuHeader::MAX_EXTENSIONS_LEN],
+}
+
+impl GtpuHeader {
+    /// Udp port assigned to GTP-U by the IANA.
+    pub const UDP_PORT: u16 = 2152;
+
+    /// Length of the mandatory part of the header.
+    pub const MIN_LEN: usize = 8;
+
+    /// Length of the optional fields (sequence number, N-PDU number &
+    /// next extension header type).
+    pub const OPTIONAL_FIELDS_LEN: usize = 4;
+
+    /// Maximum length of the extension headers supported by etherparse.
+    pub const MAX_EXTENSIONS_LEN: usize = 252;
+
+    /// Maximum length of a GTP-U header supported by etherparse (including
+    /// the optional fields & extension headers).
+    pub const MAX_LEN: usize = GtpuHeader::MIN_LEN + GtpuHeader::OPTIONAL_FIELDS_LEN + GtpuHeader::MAX_EXTENSIONS_LEN;
+
+    const PROTOCOL_TYPE_FLAG: u8 = 0b0001_0000;
+    const EXTENSION_FLAG: u8 = 0b0000_0100;
+    const SEQUENCE_NUMBER_FLAG: u8 = 0b0000_0010;
+    const N_PDU_NUMBER_FLAG: u8 = 0b0000_0001;
+
+    /// Creates a header with the given message type & TEID without
+    /// optional fields & extension headers (length 0).
+    pub fn new(message_type: u8, teid: u32) -> GtpuHeader {
+        GtpuHeader {
+            message_type,
+            length: 0,
+            teid,
+            sequence_number: None,
+            n_pdu_number: None,
+            first_extension_type: 0,
+            extensions_len: 0,
+            extensions_buffer: [0; GtpuHeader::MAX_EXTENSIONS_LEN],
+        }
+    }
+
+    /// Creates a G-PDU header (encapsulated user data) with the given TEID.
+    #[inline]
+    pub fn g_pdu(teid: u32) -> GtpuHeader {
+        GtpuHeader::new(gtpu_message_type::G_PDU, teid)
+    }
+
+    /// Read a GTP-U header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(GtpuHeader, &[u8]), ReadError> {
+        let header = GtpuHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Reads a GTP-U header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<GtpuHeader, ReadError> {
+        let mut buffer = [0u8; GtpuHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..GtpuHeader::MIN_LEN])?;
+        GtpuHeaderSlice::check_version(buffer[0])?;
+        let mut len = GtpuHeader::MIN_LEN;
+        if GtpuHeaderSlice::has_optional_fields(buffer[0]) {
+            reader.read_exact(&mut buffer[len..len + GtpuHeader::OPTIONAL_FIELDS_LEN])?;
+            len += GtpuHeader::OPTIONAL_FIELDS_LEN;
+            if 0 != buffer[0] & GtpuHeader::EXTENSION_FLAG {
+                let mut next_type = buffer[len - 1];
+                while 0 != next_type {
+                    if GtpuHeader::MAX_LEN < len + 1 {
+                        return Err(ReadError::GtpuExtensionLengthBad(len));
+                    }
+                    reader.read_exact(&mut buffer[len..len + 1])?;
+                    let extension_len = usize::from(buffer[len])*4;
+                    if 0 == extension_len || GtpuHeader::MAX_LEN < len + extension_len {
+                        return Err(ReadError::GtpuExtensionLengthBad(len));
+                    }
+                    reader.read_exact(&mut buffer[len + 1..len + extension_len])?;
+                    len += extension_len;
+                    next_type = buffer[len - 1];
+                }
+            }
+        }
+        // SAFETY:
+        // Version & extension header lengths were validated above.
+        Ok(unsafe { GtpuHeaderSlice::from_slice_unchecked(&buffer[..len]) }.to_header())
+    }
+
+    /// Writes the GTP-U header to the current position.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes (including the optional
+    /// fields & extension headers).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        if self.has_optional_fields() {
+            GtpuHeader::MIN_LEN + GtpuHeader::OPTIONAL_FIELDS_LEN + usize::from(self.extensions_len)
+        } else {
+            GtpuHeader::MIN_LEN
+        }
+    }
+
+    /// Returns true if the optional fields are present in the serialized
+    /// header (sequence number, N-PDU number or extension headers set).
+    #[inline]
+    fn has_optional_fields(&self) -> bool {
+        self.sequence_number.is_some() || self.n_pdu_number.is_some() || 0 != self.extensions_len
+    }
+
+    /// Returns a slice containing the serialized extension headers.
+    #[inline]
+    pub fn extensions(&self) -> &[u8] {
+        &self.extensions_buffer[..usize::from(self.extensions_len)]
+    }
+
+    /// Returns an iterator over the extension headers.
+    #[inline]
+    pub fn extensions_iterator(&self) -> GtpuExtensionsIterator<'_> {
+        GtpuExtensionsIterator {
+            next_type: self.first_extension_type,
+            rest: self.extensions(),
+        }
+    }
+
+    /// Sets the extension headers of the header.
+    ///
+    /// If the extension headers are not fitting into the header or the
+    /// content of an extension header has an invalid length no data is set
+    /// and an error is returned. The `length` field is not updated.
+    pub fn set_extensions(&mut self, extensions: &[GtpuExtension]) -> Result<(), ValueError> {
+        use crate::ValueError::*;
+
+        let mut len = 0;
+        for extension in extensions {
+            if 0 != extension.header_len() % 4 {
+                return Err(GtpuExtensionContentLengthBad(extension.content.len()));
+            }
+            len += extension.header_len();
+        }
+        if GtpuHeader::MAX_EXTENSIONS_LEN < len {
+            return Err(GtpuExtensionsLengthBad(len));
+        }
+
+        let mut offset = 0;
+        for (index, extension) in extensions.iter().enumerate() {
+            let end = offset + extension.header_len();
+            self.extensions_buffer[offset] = (extension.header_len() / 4) as u8;
+            self.extensions_buffer[offset + 1..end - 1].copy_from_slice(extension.content);
+            self.extensions_buffer[end - 1] = match extensions.get(index + 1) {
+                Some(next) => next.extension_type,
+                None => gtpu_extension_type::NO_MORE_EXTENSION_HEADERS,
+            };
+            offset = end;
+        }
+        self.first_extension_type = match extensions.first() {
+            Some(first) => first.extension_type,
+            None => 0,
+        };
+        self.extensions_len = len as u8;
+        Ok(())
+    }
+
+    /// Returns the serialized form of the header.
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { GtpuHeader::MAX_LEN }>, ValueError> {
+        // version 1 & protocol type GTP
+        let mut flags = (1 << 5) | GtpuHeader::PROTOCOL_TYPE_FLAG;
+        if 0 != self.extensions_len {
+            flags |= GtpuHeader::EXTENSION_FLAG;
+        }
+        if self.sequence_number.is_some() {
+            flags |= GtpuHeader::SEQUENCE_NUMBER_FLAG;
+        }
+        if self.n_pdu_number.is_some() {
+            flags |= GtpuHeader::N_PDU_NUMBER_FLAG;
+        }
+        let length = self.length.to_be_bytes();
+        let teid = self.teid.to_be_bytes();
+
+        let mut result = ArrayVec::new();
+        result.try_extend_from_slice(&[
+            flags,
+            self.message_type,
+            length[0],
+            length[1],
+            teid[0],
+            teid[1],
+            teid[2],
+            teid[3],
+        ]).unwrap();
+        if self.has_optional_fields() {
+            let sequence_number = self.sequence_number.unwrap_or(0).to_be_bytes();
+            result.try_extend_from_slice(&[
+                sequence_number[0],
+                sequence_number[1],
+                self.n_pdu_number.unwrap_or(0),
+                if 0 == self.extensions_len {
+                    0
+                } else {
+                    self.first_extension_type
+                },
+            ]).unwrap();
+            result.try_extend_from_slice(self.extensions()).unwrap();
+        }
+        Ok(result)
+    }
+}
+
+impl Debug for GtpuHeader {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "GtpuHeader {{ message_type: {}, length: {}, teid: {}, sequence_number: {:?}, n_pdu_number: {:?}, extensions: {:?} }}",
+            self.message_type,
+            self.length,
+            self.teid,
+            self.sequence_number,
+            self.n_pdu_number,
+            self.extensions_iterator().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for GtpuHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "GtpuHeader {{ message_type: {}, length: {}, teid: {}, sequence_number: {}, n_pdu_number: {}, first_extension_type: {}, extensions: {=[u8]} }}",
+            self.message_type,
+            self.length,
+            self.teid,
+            self.sequence_number,
+            self.n_pdu_number,
+            self.first_extension_type,
+            self.extensions())
+    }
+}
+
+impl std::cmp::PartialEq for GtpuHeader {
+    fn eq(&self, other: &GtpuHeader) -> bool {
+        self.message_type == other.message_type &&
+        self.length == other.length &&
+        self.teid == other.teid &&
+        self.sequence_number == other.sequence_number &&
+        self.n_pdu_number == other.n_pdu_number &&
+        self.extensions_iterator().eq(other.extensions_iterator())
+    }
+}
+
+impl std::cmp::Eq for GtpuHeader {}
+
+impl std::hash::Hash for GtpuHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.message_type.hash(state);
+        self.length.hash(state);
+        self.teid.hash(state);
+        self.sequence_number.hash(state);
+        self.n_pdu_number.hash(state);
+        for extension in self.extensions_iterator() {
+            extension.hash(state);
+        }
+    }
+}
+
+/// Slice containing a GTP-U header (including the optional fields &
+/// extension headers).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct GtpuHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GtpuHeaderSlice<'a> {
+    /// Creates a GTP-U header slice from a slice (the version & the
+    /// lengths of the extension headers are validated).
+    pub fn from_slice(slice: &'a [u8]) -> Result<GtpuHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < GtpuHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GtpuHeader::MIN_LEN));
+        }
+        GtpuHeaderSlice::check_version(slice[0])?;
+
+        let mut len = GtpuHeader::MIN_LEN;
+        if GtpuHeaderSlice::has_optional_fields(slice[0]) {
+            len += GtpuHeader::OPTIONAL_FIELDS_LEN;
+            if slice.len() < len {
+                return Err(UnexpectedEndOfSlice(len));
+            }
+            if 0 != slice[0] & GtpuHeader::EXTENSION_FLAG {
+                let mut next_type = slice[len - 1];
+                while 0 != next_type {
+                    if slice.len() < len + 1 {
+                        return Err(UnexpectedEndOfSlice(len + 1));
+                    }
+                    let extension_len = usize::from(slice[len])*4;
+                    if 0 == extension_len || GtpuHeader::MAX_LEN < len + extension_len {
+                        return Err(GtpuExtensionLengthBad(len));
+                    }
+                    if slice.len() < len + extension_len {
+                        return Err(UnexpectedEndOfSlice(len + extension_len));
+                    }
+                    len += extension_len;
+                    next_type = slice[len - 1];
+                }
+            }
+        }
+
+        Ok(GtpuHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this
+            // code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Creates a GTP-U header slice from a slice (assumes slice size & content
+    /// was validated before).
+    ///
+    /// # Safety
+    ///
+    /// This method assumes that the slice was previously validated to contain
+    /// a valid GTP-U header. This means the slice length must be exactly the
+    /// header length including the optional fields & the extension headers.
+    unsafe fn from_slice_unchecked(slice: &'a [u8]) -> GtpuHeaderSlice<'a> {
+        GtpuHeaderSlice { slice }
+    }
+
+    /// Checks that the version in the first byte is 1.
+    fn check_version(first: u8) -> Result<(), ReadError> {
+        let version = first >> 5;
+        if 1 != version {
+            Err(ReadError::GtpuUnsupportedVersion(version))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns true if one of the flags indicating the presence of the
+    /// optional fields is set in the first byte.
+    #[inline]
+    fn has_optional_fields(first: u8) -> bool {
+        0 != first & (GtpuHeader::EXTENSION_FLAG | GtpuHeader::SEQUENCE_NUMBER_FLAG | GtpuHeader::N_PDU_NUMBER_FLAG)
+    }
+
+    /// Returns the slice containing the GTP-U header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the version field (always 1).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 5
+    }
+
+    /// Read the protocol type flag (true for GTP, false for GTP').
+    #[inline]
+    pub fn protocol_type(&self) -> bool {
+        0 != self.slice[0] & GtpuHeader::PROTOCOL_TYPE_FLAG
+    }
+
+    /// Read the message type field.
+    #[inline]
+    pub fn message_type(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the length field (length of the data following the mandatory
+    /// 8 bytes of the header).
+    #[inline]
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the tunnel endpoint identifier.
+    #[inline]
+    pub fn teid(&self) -> u32 {
+        u32::from_be_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]])
+    }
+
+    /// Read the sequence number (`None` if the S flag is not set).
+    pub fn sequence_number(&self) -> Option<u16> {
+        if 0 != self.slice[0] & GtpuHeader::SEQUENCE_NUMBER_FLAG {
+            Some(u16::from_be_bytes([self.slice[8], self.slice[9]]))
+        } else {
+            None
+        }
+    }
+
+    /// Read the N-PDU number (`None` if the PN flag is not set).
+    pub fn n_pdu_number(&self) -> Option<u8> {
+        if 0 != self.slice[0] & GtpuHeader::N_PDU_NUMBER_FLAG {
+            Some(self.slice[10])
+        } else {
+            None
+        }
+    }
+
+    /// Returns a slice containing the serialized extension headers.
+    #[inline]
+    pub fn extensions(&self) -> &'a [u8] {
+        if 0 != self.slice[0] & GtpuHeader::EXTENSION_FLAG {
+            &self.slice[GtpuHeader::MIN_LEN + GtpuHeader::OPTIONAL_FIELDS_LEN..]
+        } else {
+            &[]
+        }
+    }
+
+    /// Returns an iterator over the extension headers.
+    pub fn extensions_iterator(&self) -> GtpuExtensionsIterator<'a> {
+        let extensions = self.extensions();
+        GtpuExtensionsIterator {
+            next_type: if extensions.is_empty() {
+                0
+            } else {
+                self.slice[GtpuHeader::MIN_LEN + 3]
+            },
+            rest: extensions,
+        }
+    }
+
+    /// Decode all the fields and copy the results to a [`GtpuHeader`] struct.
+    pub fn to_header(&self) -> GtpuHeader {
+        let extensions = self.extensions();
+        GtpuHeader {
+            message_type: self.message_type(),
+            length: self.length(),
+            teid: self.teid(),
+            sequence_number: self.sequence_number(),
+            n_pdu_number: self.n_pdu_number(),
+            first_extension_type: if extensions.is_empty() {
+                0
+            } else {
+                self.slice[GtpuHeader::MIN_LEN + 3]
+            },
+            extensions_len: extensions.len() as u8,
+            extensions_buffer: {
+                let mut values = [0; GtpuHeader::MAX_EXTENSIONS_LEN];
+                values[..extensions.len()].copy_from_slice(extensions);
+                values
+            },
+        }
+    }
+}
+
+/// Extension header of a GTP-U header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct GtpuExtension<'a> {
+    /// Type of the extension header (see [`gtpu_extension_type`]).
+    pub extension_type: u8,
+    /// Content of the extension header (without the length & next extension
+    /// header type fields, the length plus 2 has to be a multiple of 4).
+    pub content: &'a [u8],
+}
+
+impl<'a> GtpuExtension<'a> {
+    /// Length of the serialized extension header in bytes (including the
+    /// length & next extension header type fields).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        2 + self.content.len()
+    }
+}
+
+/// Iterator over the extension headers of a GTP-U header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GtpuExtensionsIterator<'a> {
+    next_type: u8,
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for GtpuExtensionsIterator<'a> {
+    type Item = GtpuExtension<'a>;
+
+    fn next(&mut self) -> Option<GtpuExtension<'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        // the lengths of the extension headers were validated when the
+        // header was created
+        let len = usize::from(self.rest[0])*4;
+        let result = GtpuExtension {
+            extension_type: self.next_type,
+            content: &self.rest[1..len - 1],
+        };
+        self.next_type = self.rest[len - 1];
+        self.rest = &self.rest[len..];
+        Some(result)
+    }
+}
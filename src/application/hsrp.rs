@@ -0,0 +1,384 @@
+use super::super::*;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Op codes of HSRP messages (identical for version 1 & 2).
+pub mod hsrp_op_code {
+    /// Periodic message sent by the active & standby routers.
+    pub const HELLO: u8 = 0;
+    /// Sent by a router that wishes to become the active router.
+    pub const COUP: u8 = 1;
+    /// Sent by a router that no longer wishes to be the active router.
+    pub const RESIGN: u8 = 2;
+    /// Interface state advertisement (version 1 only).
+    pub const ADVERTISE: u8 = 3;
+}
+
+/// State of the sending router in an HSRP message.
+///
+/// Version 1 & 2 encode the states with different values (see
+/// [`HsrpState::from_v1`] & [`HsrpState::from_v2`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum HsrpState {
+    /// No HSRP group is configured (version 2 only).
+    Disabled,
+    /// Initial state after the configuration changed or the interface came up.
+    Initial,
+    /// The router has not yet determined the virtual ip address.
+    Learn,
+    /// The router knows the virtual ip address but is neither active nor standby.
+    Listen,
+    /// The router sends periodic hellos and takes part in the election.
+    Speak,
+    /// The router is the candidate to become the next active router.
+    Standby,
+    /// The router forwards the packets sent to the virtual mac address.
+    Active,
+}
+
+impl HsrpState {
+    /// Decodes the state value of an HSRP version 1 message (`None` if the value is unknown).
+    pub fn from_v1(value: u8) -> Option<HsrpState> {
+        use HsrpState::*;
+        match value {
+            0 => Some(Initial),
+            1 => Some(Learn),
+            2 => Some(Listen),
+            4 => Some(Speak),
+            8 => Some(Standby),
+            16 => Some(Active),
+            _ => None,
+        }
+    }
+
+    /// Decodes the state value of an HSRP version 2 group state TLV (`None` if the value is unknown).
+    pub fn from_v2(value: u8) -> Option<HsrpState> {
+        use HsrpState::*;
+        match value {
+            0 => Some(Disabled),
+            1 => Some(Initial),
+            2 => Some(Learn),
+            3 => Some(Listen),
+            4 => Some(Speak),
+            5 => Some(Standby),
+            6 => Some(Active),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur while decoding an HSRP message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HsrpError {
+    /// The slice is too short. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+    /// The message is neither an HSRP version 1 message nor starts with an
+    /// HSRP version 2 group state TLV. The value is the first byte of the message.
+    UnsupportedVersion(u8),
+    /// The length of an HSRP version 2 TLV is invalid.
+    InvalidTlvLength{
+        /// Type of the TLV.
+        tlv_type: u8,
+        /// Length given in the TLV.
+        len: u8,
+    },
+    /// The version 2 group state TLV contains an ip version other than 4 or 6.
+    UnsupportedIpVersion(u8),
+}
+
+impl Error for HsrpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for HsrpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use HsrpError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "HsrpError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+            UnsupportedVersion(value) => {
+                write!(f, "HsrpError: Unsupported message version (first byte {:#04x}). Only HSRP version 1 messages & version 2 messages starting with a group state TLV are supported.", value)
+            },
+            InvalidTlvLength{ tlv_type, len } => {
+                write!(f, "HsrpError: Invalid length {} of the TLV with the type {}.", len, tlv_type)
+            },
+            UnsupportedIpVersion(value) => {
+                write!(f, "HsrpError: Unsupported ip version {} in the group state TLV (only 4 & 6 are supported).", value)
+            },
+        }
+    }
+}
+
+/// HSRP version 1 message (RFC 2281).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct HsrpV1Header {
+    /// Type of the message (see [`hsrp_op_code`]).
+    pub op_code: u8,
+    /// Raw state value (see [`HsrpState::from_v1`]).
+    pub state: u8,
+    /// Interval between hello messages in seconds.
+    pub hello_time: u8,
+    /// Time in seconds after which the sender is considered down.
+    pub hold_time: u8,
+    /// Priority of the sender (the router with the highest priority becomes active).
+    pub priority: u8,
+    /// Standby group number.
+    pub group: u8,
+    /// Reserved field.
+    pub reserved: u8,
+    /// Clear text authentication data (`"cisco"` padded with zeros by default).
+    pub authentication_data: [u8;8],
+    /// Virtual ip address of the group.
+    pub virtual_ip: [u8;4],
+}
+
+impl HsrpV1Header {
+    /// Serialized size of the message in bytes.
+    pub const SERIALIZED_SIZE: usize = 20;
+
+    /// Version number contained in the first byte of the message.
+    pub const VERSION: u8 = 0;
+
+    /// Decodes the message from a slice and returns the rest of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(HsrpV1Header, &[u8]), HsrpError> {
+        if slice.len() < HsrpV1Header::SERIALIZED_SIZE {
+            return Err(HsrpError::UnexpectedEndOfSlice(HsrpV1Header::SERIALIZED_SIZE));
+        }
+        if HsrpV1Header::VERSION != slice[0] {
+            return Err(HsrpError::UnsupportedVersion(slice[0]));
+        }
+        Ok((
+            HsrpV1Header {
+                op_code: slice[1],
+                state: slice[2],
+                hello_time: slice[3],
+                hold_time: slice[4],
+                priority: slice[5],
+                group: slice[6],
+                reserved: slice[7],
+                authentication_data: [
+                    slice[8], slice[9], slice[10], slice[11],
+                    slice[12], slice[13], slice[14], slice[15],
+                ],
+                virtual_ip: [slice[16], slice[17], slice[18], slice[19]],
+            },
+            &slice[HsrpV1Header::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Decoded state of the sender (`None` if the state value is unknown).
+    pub fn hsrp_state(&self) -> Option<HsrpState> {
+        HsrpState::from_v1(self.state)
+    }
+}
+
+/// Group state TLV of an HSRP version 2 message.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct HsrpV2Header {
+    /// Type of the message (see [`hsrp_op_code`]).
+    pub op_code: u8,
+    /// Raw state value (see [`HsrpState::from_v2`]).
+    pub state: u8,
+    /// Standby group number.
+    pub group: u16,
+    /// Mac address of the interface sending the message.
+    pub identifier: [u8;6],
+    /// Priority of the sender (the router with the highest priority becomes active).
+    pub priority: u32,
+    /// Interval between hello messages in milliseconds.
+    pub hello_time: u32,
+    /// Time in milliseconds after which the sender is considered down.
+    pub hold_time: u32,
+    /// Virtual ip address of the group (the ip version is encoded in the TLV).
+    pub virtual_ip: IpAddr,
+    /// Clear text authentication data if a text authentication TLV is present.
+    pub authentication_data: Option<[u8;8]>,
+}
+
+impl HsrpV2Header {
+    /// TLV type of the group state TLV.
+    pub const GROUP_STATE_TLV_TYPE: u8 = 1;
+
+    /// Length of the group state TLV value in bytes.
+    pub const GROUP_STATE_TLV_LEN: u8 = 40;
+
+    /// TLV type of the text authentication TLV.
+    pub const TEXT_AUTHENTICATION_TLV_TYPE: u8 = 3;
+
+    /// Version number contained in the group state TLV.
+    pub const VERSION: u8 = 2;
+
+    /// Decodes the TLVs of the message.
+    ///
+    /// The message has to start with the group state TLV. Text authentication
+    /// TLVs are decoded, all other TLVs (e.g. MD5 authentication) are skipped.
+    pub fn from_slice(slice: &[u8]) -> Result<HsrpV2Header, HsrpError> {
+        use HsrpError::*;
+
+        if slice.len() < 2 {
+            return Err(UnexpectedEndOfSlice(2));
+        }
+        if HsrpV2Header::GROUP_STATE_TLV_TYPE != slice[0] {
+            return Err(UnsupportedVersion(slice[0]));
+        }
+        if HsrpV2Header::GROUP_STATE_TLV_LEN != slice[1] {
+            return Err(InvalidTlvLength{ tlv_type: slice[0], len: slice[1] });
+        }
+        let tlv_end = 2 + usize::from(HsrpV2Header::GROUP_STATE_TLV_LEN);
+        if slice.len() < tlv_end {
+            return Err(UnexpectedEndOfSlice(tlv_end));
+        }
+
+        let value = &slice[2..tlv_end];
+        if HsrpV2Header::VERSION != value[0] {
+            return Err(UnsupportedVersion(value[0]));
+        }
+        let virtual_ip = match value[3] {
+            4 => IpAddr::V4(Ipv4Addr::new(value[24], value[25], value[26], value[27])),
+            6 => {
+                let mut octets = [0u8;16];
+                octets.copy_from_slice(&value[24..40]);
+                IpAddr::V6(Ipv6Addr::from(octets))
+            },
+            version => return Err(UnsupportedIpVersion(version)),
+        };
+        let read_u32 = |offset: usize| u32::from_be_bytes([
+            value[offset], value[offset + 1], value[offset + 2], value[offset + 3]
+        ]);
+        let mut result = HsrpV2Header {
+            op_code: value[1],
+            state: value[2],
+            group: u16::from_be_bytes([value[4], value[5]]),
+            identifier: [value[6], value[7], value[8], value[9], value[10], value[11]],
+            priority: read_u32(12),
+            hello_time: read_u32(16),
+            hold_time: read_u32(20),
+            virtual_ip,
+            authentication_data: None,
+        };
+
+        // decode the following tlvs
+        let mut rest = &slice[tlv_end..];
+        while false == rest.is_empty() {
+            if rest.len() < 2 {
+                return Err(UnexpectedEndOfSlice(slice.len() - rest.len() + 2));
+            }
+            let (tlv_type, len) = (rest[0], rest[1]);
+            let end = 2 + usize::from(len);
+            if rest.len() < end {
+                return Err(UnexpectedEndOfSlice(slice.len() - rest.len() + end));
+            }
+            if HsrpV2Header::TEXT_AUTHENTICATION_TLV_TYPE == tlv_type {
+                if 8 != len {
+                    return Err(InvalidTlvLength{ tlv_type, len });
+                }
+                let mut data = [0u8;8];
+                data.copy_from_slice(&rest[2..end]);
+                result.authentication_data = Some(data);
+            }
+            rest = &rest[end..];
+        }
+        Ok(result)
+    }
+
+    /// Decoded state of the sender (`None` if the state value is unknown).
+    pub fn hsrp_state(&self) -> Option<HsrpState> {
+        HsrpState::from_v2(self.state)
+    }
+}
+
+/// HSRP version 1 or version 2 message.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{HsrpMessage, HsrpState, SlicedPacket, TransportSlice};
+/// # use etherparse::PacketBuilder;
+/// # let hsrp = [
+/// #     0, 0, 16, 3, 10, 120, 1, 0,
+/// #     b'c', b'i', b's', b'c', b'o', 0, 0, 0,
+/// #     192, 168, 1, 254,
+/// # ];
+/// # let builder = PacketBuilder::ipv4([192,168,1,1], [224,0,0,2], 1)
+/// #     .udp(HsrpMessage::UDP_PORT, HsrpMessage::UDP_PORT);
+/// # let mut packet = Vec::new();
+/// # builder.write(&mut packet, &hsrp).unwrap();
+///
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// if let Some(TransportSlice::Udp(udp)) = &sliced.transport {
+///     if HsrpMessage::UDP_PORT == udp.destination_port() {
+///         let message = HsrpMessage::from_slice(sliced.payload).unwrap();
+///         assert_eq!(Some(HsrpState::Active), message.state());
+///         assert_eq!(120, message.priority());
+///         assert_eq!(1, message.group());
+///         assert_eq!(std::net::IpAddr::from([192,168,1,254]), message.virtual_ip());
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum HsrpMessage {
+    /// HSRP version 1 message.
+    V1(HsrpV1Header),
+    /// HSRP version 2 message.
+    V2(HsrpV2Header),
+}
+
+impl HsrpMessage {
+    /// UDP port used by HSRP (version 1 & version 2 over ipv4).
+    pub const UDP_PORT: u16 = 1985;
+
+    /// UDP port used by HSRP version 2 over ipv6.
+    pub const IPV6_UDP_PORT: u16 = 2029;
+
+    /// Decodes an HSRP version 1 or version 2 message (UDP payload).
+    pub fn from_slice(slice: &[u8]) -> Result<HsrpMessage, HsrpError> {
+        match slice.first() {
+            None => Err(HsrpError::UnexpectedEndOfSlice(1)),
+            Some(&HsrpV1Header::VERSION) => Ok(HsrpMessage::V1(HsrpV1Header::from_slice(slice)?.0)),
+            Some(_) => Ok(HsrpMessage::V2(HsrpV2Header::from_slice(slice)?)),
+        }
+    }
+
+    /// Type of the message (see [`hsrp_op_code`]).
+    pub fn op_code(&self) -> u8 {
+        match self {
+            HsrpMessage::V1(header) => header.op_code,
+            HsrpMessage::V2(header) => header.op_code,
+        }
+    }
+
+    /// Decoded state of the sender (`None` if the state value is unknown).
+    pub fn state(&self) -> Option<HsrpState> {
+        match self {
+            HsrpMessage::V1(header) => header.hsrp_state(),
+            HsrpMessage::V2(header) => header.hsrp_state(),
+        }
+    }
+
+    /// Priority of the sender.
+    pub fn priority(&self) -> u32 {
+        match self {
+            HsrpMessage::V1(header) => u32::from(header.priority),
+            HsrpMessage::V2(header) => header.priority,
+        }
+    }
+
+    /// Standby group number.
+    pub fn group(&self) -> u16 {
+        match self {
+            HsrpMessage::V1(header) => u16::from(header.group),
+            HsrpMessage::V2(header) => header.group,
+        }
+    }
+
+    /// Virtual ip address of the group.
+    pub fn virtual_ip(&self) -> IpAddr {
+        match self {
+            HsrpMessage::V1(header) => IpAddr::from(header.virtual_ip),
+            HsrpMessage::V2(header) => header.virtual_ip,
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod dns_name;
+pub mod geneve;
+pub mod gtpu;
+pub mod hsrp;
+pub mod quic;
+pub mod rip;
+pub mod stamp;
+pub mod vxlan;
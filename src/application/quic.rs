@@ -0,0 +1,158 @@
+use super::super::*;
+
+/// Slice containing the version independent invariants of a QUIC packet
+/// header (RFC 8999).
+///
+/// The invariants are the header form, the version (long headers only) &
+/// the connection ids. They are sufficient for load balancers routing
+/// packets based on the destination connection id. The rest of the header
+/// is version specific (& mostly protected) and not decoded.
+///
+/// Short headers don't contain the length of the destination connection
+/// id, as it is chosen by the receiving endpoint. The length therefore has
+/// to be passed to [`QuicHeaderSlice::from_slice`].
+///
+/// If the udp port is assigned to [`ApplicationProtocol::Quic`] in the
+/// [`ParserRegistry`], [`SlicedPacket`] sets the `application` field &
+/// the QUIC header can be sliced from the payload.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ApplicationProtocol, ParserRegistry, PacketBuilder, QuicHeaderSlice, SlicedPacket};
+///
+/// // long header (QUIC version 1 initial packet)
+/// let mut quic = vec![0xc0, 0, 0, 0, 1];
+/// quic.push(4);
+/// quic.extend_from_slice(&[1, 2, 3, 4]); // destination connection id
+/// quic.push(2);
+/// quic.extend_from_slice(&[5, 6]); // source connection id
+/// quic.extend_from_slice(&[0, 0, 0]); // version specific data
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(50000, QuicHeaderSlice::UDP_PORT)
+///     .write(&mut packet, &quic)
+///     .unwrap();
+///
+/// let registry = ParserRegistry::with_well_known_ports();
+/// let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+/// assert_eq!(Some(ApplicationProtocol::Quic), sliced.application);
+///
+/// // the connection id length of short headers is only known by the endpoint
+/// let header = QuicHeaderSlice::from_slice(sliced.payload, 8).unwrap();
+/// assert!(header.is_long_header());
+/// assert_eq!(Some(1), header.version());
+/// assert_eq!(&[1, 2, 3, 4], header.destination_connection_id());
+/// assert_eq!(Some(&[5, 6][..]), header.source_connection_id());
+/// assert_eq!(&[0, 0, 0], &sliced.payload[header.slice().len()..]);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct QuicHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> QuicHeaderSlice<'a> {
+    /// Udp port assigned to QUIC based HTTP/3.
+    pub const UDP_PORT: u16 = 443;
+
+    /// Header form bit (set for long headers).
+    pub const FLAG_LONG_HEADER: u8 = 0b1000_0000;
+
+    /// Version of version negotiation packets.
+    pub const VERSION_NEGOTIATION: u32 = 0;
+
+    /// Minimum length of a long header (first byte, version & the two
+    /// connection id lengths).
+    pub const MIN_LONG_HEADER_LEN: usize = 7;
+
+    /// Creates a slice containing the header invariants of the QUIC packet
+    /// at the start of the given slice (the version specific part of the
+    /// header & the payload are not part of the slice).
+    ///
+    /// `short_header_dcid_len` is the length of the destination connection
+    /// id of short headers (ignored for long headers).
+    pub fn from_slice(slice: &'a [u8], short_header_dcid_len: usize) -> Result<QuicHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.is_empty() {
+            return Err(UnexpectedEndOfSlice(1));
+        }
+
+        let len = if 0 != slice[0] & QuicHeaderSlice::FLAG_LONG_HEADER {
+            if slice.len() < QuicHeaderSlice::MIN_LONG_HEADER_LEN {
+                return Err(UnexpectedEndOfSlice(QuicHeaderSlice::MIN_LONG_HEADER_LEN));
+            }
+            // dcid length at byte 5, the scid length follows the dcid
+            let scid_len_offset = 6 + usize::from(slice[5]);
+            if slice.len() < scid_len_offset + 1 {
+                return Err(UnexpectedEndOfSlice(scid_len_offset + 1));
+            }
+            scid_len_offset + 1 + usize::from(slice[scid_len_offset])
+        } else {
+            1 + short_header_dcid_len
+        };
+
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(QuicHeaderSlice {
+            slice: &slice[..len],
+        })
+    }
+
+    /// Returns the slice containing the header invariants.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns true if the packet has a long header.
+    #[inline]
+    pub fn is_long_header(&self) -> bool {
+        0 != self.slice[0] & QuicHeaderSlice::FLAG_LONG_HEADER
+    }
+
+    /// Returns the version specific bits of the first byte (lower 7 bits).
+    #[inline]
+    pub fn version_specific_bits(&self) -> u8 {
+        self.slice[0] & 0b0111_1111
+    }
+
+    /// Returns the version of long headers (`None` for short headers).
+    #[inline]
+    pub fn version(&self) -> Option<u32> {
+        if self.is_long_header() {
+            Some(u32::from_be_bytes([self.slice[1], self.slice[2], self.slice[3], self.slice[4]]))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the packet is a version negotiation packet (long
+    /// header with the version 0).
+    #[inline]
+    pub fn is_version_negotiation(&self) -> bool {
+        Some(QuicHeaderSlice::VERSION_NEGOTIATION) == self.version()
+    }
+
+    /// Returns the destination connection id.
+    #[inline]
+    pub fn destination_connection_id(&self) -> &'a [u8] {
+        if self.is_long_header() {
+            &self.slice[6..6 + usize::from(self.slice[5])]
+        } else {
+            &self.slice[1..]
+        }
+    }
+
+    /// Returns the source connection id of long headers (`None` for short headers).
+    #[inline]
+    pub fn source_connection_id(&self) -> Option<&'a [u8]> {
+        if self.is_long_header() {
+            Some(&self.slice[7 + usize::from(self.slice[5])..])
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use super::super::*;
+
+/// Commands of RIP messages.
+pub mod rip_command {
+    /// Request for all or parts of the routing table of the receiver.
+    pub const REQUEST: u8 = 1;
+    /// Message containing all or parts of the routing table of the sender.
+    pub const RESPONSE: u8 = 2;
+}
+
+/// Errors that can occur while decoding a RIP message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RipError {
+    /// The slice is shorter then the RIP header. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+    /// The length of the data after the header is not a multiple of the
+    /// route entry size. The value is the length of the message.
+    InvalidLength(usize),
+}
+
+impl Error for RipError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for RipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RipError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "RipError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+            InvalidLength(len) => {
+                write!(f, "RipError: Invalid message length {} (the entries after the 4 byte header have to be a multiple of 20 bytes).", len)
+            },
+        }
+    }
+}
+
+/// Route entry of a RIP message.
+///
+/// Version 1 messages only use the address family, ip address & metric
+/// fields (all other fields are zero).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RipRouteEntry {
+    /// Address family of the ip address (2 for ipv4, 0 in requests for the complete routing table).
+    pub address_family: u16,
+    /// Route tag (version 2 only).
+    pub route_tag: u16,
+    /// Destination ip address.
+    pub ip_address: [u8;4],
+    /// Subnet mask of the destination (version 2 only).
+    pub subnet_mask: [u8;4],
+    /// Next hop the packets to the destination should be forwarded to
+    /// (version 2 only, 0.0.0.0 if the sender of the message is the next hop).
+    pub next_hop: [u8;4],
+    /// Metric of the route (1 to 15, 16 means unreachable).
+    pub metric: u32,
+}
+
+/// Entry of a RIP message.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RipEntry {
+    /// Route entry.
+    Route(RipRouteEntry),
+    /// Authentication entry (version 2 only, address family 0xffff).
+    Authentication{
+        /// Type of the authentication (2 for simple password authentication).
+        authentication_type: u16,
+        /// Authentication data (e.g. the password padded with zeros).
+        data: [u8;16],
+    },
+}
+
+impl RipEntry {
+    /// Address family value identifying authentication entries.
+    pub const AUTHENTICATION_ADDRESS_FAMILY: u16 = 0xffff;
+
+    /// Decodes an entry from the first 20 bytes of the given slice.
+    fn from_bytes(data: &[u8]) -> RipEntry {
+        let read_u16 = |offset: usize| u16::from_be_bytes([data[offset], data[offset + 1]]);
+        let read_4 = |offset: usize| [data[offset], data[offset + 1], data[offset + 2], data[offset + 3]];
+
+        let address_family = read_u16(0);
+        if RipEntry::AUTHENTICATION_ADDRESS_FAMILY == address_family {
+            let mut auth_data = [0u8;16];
+            auth_data.copy_from_slice(&data[4..20]);
+            RipEntry::Authentication {
+                authentication_type: read_u16(2),
+                data: auth_data,
+            }
+        } else {
+            RipEntry::Route(RipRouteEntry {
+                address_family,
+                route_tag: read_u16(2),
+                ip_address: read_4(4),
+                subnet_mask: read_4(8),
+                next_hop: read_4(12),
+                metric: u32::from_be_bytes(read_4(16)),
+            })
+        }
+    }
+}
+
+/// Slice containing a RIP version 1 (RFC 1058) or version 2 (RFC 2453) message.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{RipMessageSlice, RipEntry, rip_command};
+///
+/// let message = [
+///     2, 2, 0, 0, // response, version 2
+///     0, 2, 0, 0, // address family ipv4, route tag 0
+///     10, 0, 0, 0, // ip address
+///     255, 0, 0, 0, // subnet mask
+///     0, 0, 0, 0, // next hop
+///     0, 0, 0, 1, // metric
+/// ];
+/// let rip = RipMessageSlice::from_slice(&message).unwrap();
+/// assert_eq!(rip_command::RESPONSE, rip.command());
+/// assert_eq!(2, rip.version());
+/// for entry in rip.entries() {
+///     if let RipEntry::Route(route) = entry {
+///         assert_eq!([10, 0, 0, 0], route.ip_address);
+///         assert_eq!(1, route.metric);
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RipMessageSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> RipMessageSlice<'a> {
+    /// UDP port used by RIP.
+    pub const UDP_PORT: u16 = 520;
+
+    /// Length of the header in bytes.
+    pub const HEADER_LEN: usize = 4;
+
+    /// Length of an entry in bytes.
+    pub const ENTRY_LEN: usize = 20;
+
+    /// Decodes a RIP message (UDP payload).
+    pub fn from_slice(slice: &'a [u8]) -> Result<RipMessageSlice<'a>, RipError> {
+        if slice.len() < RipMessageSlice::HEADER_LEN {
+            return Err(RipError::UnexpectedEndOfSlice(RipMessageSlice::HEADER_LEN));
+        }
+        if false == (slice.len() - RipMessageSlice::HEADER_LEN).is_multiple_of(RipMessageSlice::ENTRY_LEN) {
+            return Err(RipError::InvalidLength(slice.len()));
+        }
+        Ok(RipMessageSlice { slice })
+    }
+
+    /// Returns the slice containing the message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Command of the message (see [`rip_command`]).
+    #[inline]
+    pub fn command(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Version of the message (1 or 2).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Number of entries in the message.
+    #[inline]
+    pub fn entry_count(&self) -> usize {
+        (self.slice.len() - RipMessageSlice::HEADER_LEN) / RipMessageSlice::ENTRY_LEN
+    }
+
+    /// Returns an iterator over the entries of the message.
+    pub fn entries(&self) -> RipEntryIterator<'a> {
+        RipEntryIterator {
+            rest: &self.slice[RipMessageSlice::HEADER_LEN..],
+        }
+    }
+}
+
+/// Iterator over the entries of a RIP message.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RipEntryIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for RipEntryIterator<'a> {
+    type Item = RipEntry;
+
+    fn next(&mut self) -> Option<RipEntry> {
+        // the length was validated to be a multiple of the entry length
+        if self.rest.len() < RipMessageSlice::ENTRY_LEN {
+            None
+        } else {
+            let (entry, rest) = self.rest.split_at(RipMessageSlice::ENTRY_LEN);
+            self.rest = rest;
+            Some(RipEntry::from_bytes(entry))
+        }
+    }
+}
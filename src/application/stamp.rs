@@ -0,0 +1,334 @@
+use super::super::*;
+
+use std::io;
+use std::time::Duration;
+
+/// Errors that can occur while decoding a STAMP or TWAMP-Test packet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StampError {
+    /// The slice is shorter then the packet. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+}
+
+impl Error for StampError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for StampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use StampError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "StampError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+        }
+    }
+}
+
+/// Timestamp in the 64 bit NTP format (seconds since 1900-01-01 & fraction
+/// of a second) as used by STAMP & TWAMP.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd, Default)]
+pub struct NtpTimestamp {
+    /// Seconds since 1900-01-01 00:00 UTC.
+    pub seconds: u32,
+    /// Fraction of a second in units of 2^-32 seconds.
+    pub fraction: u32,
+}
+
+impl NtpTimestamp {
+    /// Seconds between the NTP epoch (1900) and the unix epoch (1970).
+    pub const UNIX_EPOCH_OFFSET: u64 = 2_208_988_800;
+
+    /// Decodes the timestamp from the 64 bit value contained in a packet.
+    #[inline]
+    pub fn from_u64(value: u64) -> NtpTimestamp {
+        NtpTimestamp {
+            seconds: (value >> 32) as u32,
+            fraction: value as u32,
+        }
+    }
+
+    /// Returns the 64 bit value used in packets.
+    #[inline]
+    pub fn to_u64(&self) -> u64 {
+        (u64::from(self.seconds) << 32) | u64::from(self.fraction)
+    }
+
+    /// Converts a duration since the unix epoch (e.g. the result of
+    /// `SystemTime::now().duration_since(UNIX_EPOCH)`) to a NTP timestamp
+    /// (the seconds wrap around in 2036 as defined by NTP).
+    pub fn from_unix_duration(duration: Duration) -> NtpTimestamp {
+        NtpTimestamp {
+            seconds: (duration.as_secs() + NtpTimestamp::UNIX_EPOCH_OFFSET) as u32,
+            fraction: ((u64::from(duration.subsec_nanos()) << 32) / 1_000_000_000) as u32,
+        }
+    }
+
+    /// Converts the timestamp to a duration since the unix epoch (`None` if
+    /// the timestamp is before 1970, era 0 of the NTP timestamps is assumed).
+    pub fn to_unix_duration(&self) -> Option<Duration> {
+        let seconds = u64::from(self.seconds).checked_sub(NtpTimestamp::UNIX_EPOCH_OFFSET)?;
+        let nanos = (u64::from(self.fraction) * 1_000_000_000) >> 32;
+        Some(Duration::new(seconds, nanos as u32))
+    }
+}
+
+/// Error estimate of a STAMP or TWAMP-Test packet (RFC 4656 section 4.1.2).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct StampErrorEstimate {
+    /// True if the clock of the sender is synchronized to an external source ("S" bit).
+    pub synchronized: bool,
+    /// True if the timestamps use the PTPv2 format instead of the NTP format ("Z" bit).
+    pub ptp_format: bool,
+    /// Scale of the error estimate (6 bit value).
+    pub scale: u8,
+    /// Multiplier of the error estimate.
+    pub multiplier: u8,
+}
+
+impl StampErrorEstimate {
+    /// Decodes the error estimate from its 16 bit representation.
+    #[inline]
+    pub fn from_u16(value: u16) -> StampErrorEstimate {
+        StampErrorEstimate {
+            synchronized: 0 != value & 0x8000,
+            ptp_format: 0 != value & 0x4000,
+            scale: ((value >> 8) & 0x3f) as u8,
+            multiplier: value as u8,
+        }
+    }
+
+    /// Returns the 16 bit representation of the error estimate (the scale
+    /// is limited to 6 bits).
+    #[inline]
+    pub fn to_u16(&self) -> u16 {
+        (if self.synchronized { 0x8000 } else { 0 })
+            | (if self.ptp_format { 0x4000 } else { 0 })
+            | (u16::from(self.scale & 0x3f) << 8)
+            | u16::from(self.multiplier)
+    }
+
+    /// Estimated error of the timestamps (`multiplier * 2^(-32) * 2^scale` seconds).
+    pub fn error(&self) -> Duration {
+        let value = u128::from(self.multiplier) << (self.scale & 0x3f);
+        let nanos = (value * 1_000_000_000) >> 32;
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+/// Unauthenticated STAMP session-sender test packet (RFC 8762), also matching
+/// the unauthenticated TWAMP-Test sender packet (RFC 5357).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{NtpTimestamp, StampErrorEstimate, StampSenderPacket};
+/// use std::time::Duration;
+///
+/// let packet = StampSenderPacket {
+///     sequence_number: 1,
+///     timestamp: NtpTimestamp::from_unix_duration(Duration::from_secs(1_700_000_000)).to_u64(),
+///     error_estimate: StampErrorEstimate {
+///         synchronized: true,
+///         ptp_format: false,
+///         scale: 0,
+///         multiplier: 1,
+///     },
+/// };
+/// // udp payload
+/// let bytes = packet.to_bytes();
+/// assert_eq!(StampSenderPacket::SERIALIZED_SIZE, bytes.len());
+///
+/// let (decoded, _padding) = StampSenderPacket::from_slice(&bytes).unwrap();
+/// assert_eq!(packet, decoded);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub struct StampSenderPacket {
+    /// Sequence number of the packet (starting with 0, incremented per packet).
+    pub sequence_number: u32,
+    /// Time the packet was sent (NTP or PTPv2 format, see [`NtpTimestamp`] &
+    /// [`StampErrorEstimate::ptp_format`]).
+    pub timestamp: u64,
+    /// Error estimate of the sender clock.
+    pub error_estimate: StampErrorEstimate,
+}
+
+impl StampSenderPacket {
+    /// UDP port of STAMP session-reflectors (TWAMP-Test uses the ports negotiated via TWAMP-Control).
+    pub const UDP_PORT: u16 = 862;
+
+    /// Minimum length of the packet in bytes (TWAMP-Test packets without padding).
+    pub const MIN_LEN: usize = 14;
+
+    /// Serialized size of the STAMP packet (including the zero bytes making it
+    /// as long as a reflector packet).
+    pub const SERIALIZED_SIZE: usize = 44;
+
+    /// Decodes a sender packet (UDP payload) and returns the rest of the slice
+    /// (padding & for STAMP the extension TLVs after the first 44 bytes).
+    pub fn from_slice(slice: &[u8]) -> Result<(StampSenderPacket, &[u8]), StampError> {
+        if slice.len() < StampSenderPacket::MIN_LEN {
+            return Err(StampError::UnexpectedEndOfSlice(StampSenderPacket::MIN_LEN));
+        }
+        Ok((
+            StampSenderPacket {
+                sequence_number: read_u32(slice, 0),
+                timestamp: read_u64(slice, 4),
+                error_estimate: StampErrorEstimate::from_u16(u16::from_be_bytes([slice[12], slice[13]])),
+            },
+            &slice[StampSenderPacket::MIN_LEN..]
+        ))
+    }
+
+    /// Returns the serialized packet (all "must be zero" fields set to zero).
+    pub fn to_bytes(&self) -> [u8;StampSenderPacket::SERIALIZED_SIZE] {
+        let mut result = [0u8;StampSenderPacket::SERIALIZED_SIZE];
+        result[0..4].copy_from_slice(&self.sequence_number.to_be_bytes());
+        result[4..12].copy_from_slice(&self.timestamp.to_be_bytes());
+        result[12..14].copy_from_slice(&self.error_estimate.to_u16().to_be_bytes());
+        result
+    }
+
+    /// Writes the packet ([`StampSenderPacket::SERIALIZED_SIZE`] bytes).
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+/// Unauthenticated STAMP session-reflector test packet (RFC 8762), also
+/// matching the unauthenticated TWAMP-Test reflector packet (RFC 5357).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{StampReflectorPacket, StampSenderPacket};
+///
+/// let sender = StampSenderPacket {
+///     sequence_number: 1,
+///     timestamp: 0x1234,
+///     ..Default::default()
+/// };
+///
+/// // answer of the reflector
+/// let reflected = StampReflectorPacket::reflect(&sender, 7, 0x1240, 0x1250, 63);
+/// let bytes = reflected.to_bytes();
+///
+/// let (decoded, _) = StampReflectorPacket::from_slice(&bytes).unwrap();
+/// assert_eq!(1, decoded.sender_sequence_number);
+/// assert_eq!(7, decoded.sequence_number);
+/// assert_eq!(0x1240, decoded.receive_timestamp);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub struct StampReflectorPacket {
+    /// Sequence number of the reflector.
+    pub sequence_number: u32,
+    /// Time the packet was sent by the reflector.
+    pub timestamp: u64,
+    /// Error estimate of the reflector clock.
+    pub error_estimate: StampErrorEstimate,
+    /// Time the sender packet was received by the reflector.
+    pub receive_timestamp: u64,
+    /// Sequence number of the reflected sender packet.
+    pub sender_sequence_number: u32,
+    /// Timestamp of the reflected sender packet.
+    pub sender_timestamp: u64,
+    /// Error estimate of the reflected sender packet.
+    pub sender_error_estimate: StampErrorEstimate,
+    /// Time to live (ipv4) or hop limit (ipv6) of the received sender packet.
+    pub sender_ttl: u8,
+}
+
+impl StampReflectorPacket {
+    /// Minimum length of the packet in bytes (TWAMP-Test packets without padding).
+    pub const MIN_LEN: usize = 41;
+
+    /// Serialized size of the STAMP packet.
+    pub const SERIALIZED_SIZE: usize = 44;
+
+    /// Creates the reflector packet answering the given sender packet
+    /// (with the error estimate of the sender used for the reflector).
+    pub fn reflect(sender: &StampSenderPacket, sequence_number: u32, receive_timestamp: u64, timestamp: u64, sender_ttl: u8) -> StampReflectorPacket {
+        StampReflectorPacket {
+            sequence_number,
+            timestamp,
+            error_estimate: sender.error_estimate,
+            receive_timestamp,
+            sender_sequence_number: sender.sequence_number,
+            sender_timestamp: sender.timestamp,
+            sender_error_estimate: sender.error_estimate,
+            sender_ttl,
+        }
+    }
+
+    /// Decodes a reflector packet (UDP payload) and returns the rest of the slice
+    /// (padding & for STAMP the extension TLVs after the first 44 bytes).
+    pub fn from_slice(slice: &[u8]) -> Result<(StampReflectorPacket, &[u8]), StampError> {
+        if slice.len() < StampReflectorPacket::MIN_LEN {
+            return Err(StampError::UnexpectedEndOfSlice(StampReflectorPacket::MIN_LEN));
+        }
+        Ok((
+            StampReflectorPacket {
+                sequence_number: read_u32(slice, 0),
+                timestamp: read_u64(slice, 4),
+                error_estimate: StampErrorEstimate::from_u16(u16::from_be_bytes([slice[12], slice[13]])),
+                receive_timestamp: read_u64(slice, 16),
+                sender_sequence_number: read_u32(slice, 24),
+                sender_timestamp: read_u64(slice, 28),
+                sender_error_estimate: StampErrorEstimate::from_u16(u16::from_be_bytes([slice[36], slice[37]])),
+                sender_ttl: slice[40],
+            },
+            &slice[StampReflectorPacket::MIN_LEN..]
+        ))
+    }
+
+    /// Returns the serialized packet (all "must be zero" fields set to zero).
+    pub fn to_bytes(&self) -> [u8;StampReflectorPacket::SERIALIZED_SIZE] {
+        let mut result = [0u8;StampReflectorPacket::SERIALIZED_SIZE];
+        result[0..4].copy_from_slice(&self.sequence_number.to_be_bytes());
+        result[4..12].copy_from_slice(&self.timestamp.to_be_bytes());
+        result[12..14].copy_from_slice(&self.error_estimate.to_u16().to_be_bytes());
+        result[16..24].copy_from_slice(&self.receive_timestamp.to_be_bytes());
+        result[24..28].copy_from_slice(&self.sender_sequence_number.to_be_bytes());
+        result[28..36].copy_from_slice(&self.sender_timestamp.to_be_bytes());
+        result[36..38].copy_from_slice(&self.sender_error_estimate.to_u16().to_be_bytes());
+        result[40] = self.sender_ttl;
+        result
+    }
+
+    /// Writes the packet ([`StampReflectorPacket::SERIALIZED_SIZE`] bytes).
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Round trip time excluding the processing time of the reflector
+    /// (`None` if the timestamps are not in the NTP format or inconsistent).
+    ///
+    /// `receive_timestamp` is the time the reflector packet was received by
+    /// the sender.
+    pub fn round_trip_time(&self, receive_timestamp: u64) -> Option<Duration> {
+        if self.error_estimate.ptp_format || self.sender_error_estimate.ptp_format {
+            return None;
+        }
+        let total = receive_timestamp.checked_sub(self.sender_timestamp)?;
+        let processing = self.timestamp.checked_sub(self.receive_timestamp)?;
+        let value = total.checked_sub(processing)?;
+        let nanos = (u128::from(value) * 1_000_000_000) >> 32;
+        Some(Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32))
+    }
+}
+
+#[inline]
+fn read_u32(slice: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([slice[offset], slice[offset + 1], slice[offset + 2], slice[offset + 3]])
+}
+
+#[inline]
+fn read_u64(slice: &[u8], offset: usize) -> u64 {
+    let mut bytes = [0u8;8];
+    bytes.copy_from_slice(&slice[offset..offset + 8]);
+    u64::from_be_bytes(bytes)
+}
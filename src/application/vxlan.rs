@@ -0,0 +1,183 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Virtual eXtensible Local Area Network header (RFC 7348).
+///
+/// The header is carried in udp packets (destination port 4789) and is
+/// followed by the encapsulated ethernet II frame. If the udp port is
+/// assigned to [`ApplicationProtocol::Vxlan`] in the [`ParserRegistry`] the
+/// vxlan header & the inner frame are decoded by [`SlicedPacket`] &
+/// [`PacketHeaders`] (see the `vxlan` & `inner` fields).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ParserRegistry, PacketBuilder, SlicedPacket, VxlanHeader};
+///
+/// // inner ethernet frame
+/// let mut inner = Vec::new();
+/// PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([10,0,0,1], [10,0,0,2], 20)
+///     .udp(1234, 5678)
+///     .write(&mut inner, &[1,2,3,4])
+///     .unwrap();
+///
+/// // outer packet
+/// let mut udp_payload = VxlanHeader::new(1234).unwrap().to_bytes().unwrap().to_vec();
+/// udp_payload.extend_from_slice(&inner);
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(50000, VxlanHeader::UDP_PORT)
+///     .write(&mut packet, &udp_payload)
+///     .unwrap();
+///
+/// let registry = ParserRegistry::with_well_known_ports();
+/// let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+/// assert_eq!(1234, sliced.vxlan.unwrap().vni());
+/// assert_eq!(&[1,2,3,4], sliced.inner.unwrap().payload);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VxlanHeader {
+    /// Flags (only the "VNI valid" flag [`VxlanHeader::FLAG_VNI_VALID`] is defined).
+    pub flags: u8,
+    /// VXLAN network identifier (24 bit value).
+    pub vni: u32,
+}
+
+impl SerializedSize for VxlanHeader {
+    /// Size of the header itself in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl VxlanHeader {
+    /// Udp port assigned to VXLAN by the IANA.
+    pub const UDP_PORT: u16 = 4789;
+
+    /// Flag indicating that the VNI is valid (I flag).
+    pub const FLAG_VNI_VALID: u8 = 0b0000_1000;
+
+    /// Maximum value of the VNI.
+    pub const MAX_VNI: u32 = 0xff_ffff;
+
+    /// Creates a header with the given VNI & the "VNI valid" flag set.
+    pub fn new(vni: u32) -> Result<VxlanHeader, ValueError> {
+        max_check_u32(vni, VxlanHeader::MAX_VNI, ErrorField::VxlanVni)?;
+        Ok(VxlanHeader {
+            flags: VxlanHeader::FLAG_VNI_VALID,
+            vni,
+        })
+    }
+
+    /// Read a vxlan header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(VxlanHeader, &[u8]), ReadError> {
+        Ok((
+            VxlanHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[VxlanHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Reads a vxlan header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<VxlanHeader, io::Error> {
+        let mut buffer = [0u8; VxlanHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(
+            // SAFETY: Safe as the buffer has the size of a vxlan header.
+            unsafe {
+                VxlanHeaderSlice::from_slice_unchecked(&buffer)
+            }.to_header()
+        )
+    }
+
+    /// Writes the header to the given writer.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Returns true if the "VNI valid" flag is set.
+    #[inline]
+    pub fn vni_valid(&self) -> bool {
+        0 != self.flags & VxlanHeader::FLAG_VNI_VALID
+    }
+
+    /// Returns the serialized form of the header (fails if the VNI
+    /// exceeds 24 bits).
+    pub fn to_bytes(&self) -> Result<[u8; VxlanHeader::SERIALIZED_SIZE], ValueError> {
+        max_check_u32(self.vni, VxlanHeader::MAX_VNI, ErrorField::VxlanVni)?;
+        let vni = self.vni.to_be_bytes();
+        Ok([
+            self.flags, 0, 0, 0,
+            vni[1], vni[2], vni[3], 0,
+        ])
+    }
+}
+
+/// Slice containing a vxlan header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct VxlanHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> VxlanHeaderSlice<'a> {
+    /// Creates a vxlan header slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<VxlanHeaderSlice<'a>, ReadError> {
+        if slice.len() < VxlanHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(VxlanHeader::SERIALIZED_SIZE));
+        }
+        Ok(VxlanHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // VxlanHeader::SERIALIZED_SIZE (8) before this code can be reached.
+            slice: unsafe {
+                from_raw_parts(slice.as_ptr(), VxlanHeader::SERIALIZED_SIZE)
+            }
+        })
+    }
+
+    /// Creates a vxlan header slice from a slice (assumes the slice has
+    /// at least the length of a vxlan header).
+    ///
+    /// # Safety
+    ///
+    /// The slice length must be at least `VxlanHeader::SERIALIZED_SIZE` (8).
+    #[inline]
+    unsafe fn from_slice_unchecked(slice: &'a [u8]) -> VxlanHeaderSlice<'a> {
+        VxlanHeaderSlice {
+            slice: from_raw_parts(slice.as_ptr(), VxlanHeader::SERIALIZED_SIZE)
+        }
+    }
+
+    /// Returns the slice containing the vxlan header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the flags field.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Returns true if the "VNI valid" flag is set.
+    #[inline]
+    pub fn vni_valid(&self) -> bool {
+        0 != self.flags() & VxlanHeader::FLAG_VNI_VALID
+    }
+
+    /// Read the VXLAN network identifier (24 bit value).
+    #[inline]
+    pub fn vni(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[4], self.slice[5], self.slice[6]])
+    }
+
+    /// Decode all the fields and copy the results to a [`VxlanHeader`] struct.
+    pub fn to_header(&self) -> VxlanHeader {
+        VxlanHeader {
+            flags: self.flags(),
+            vni: self.vni(),
+        }
+    }
+}
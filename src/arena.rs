@@ -0,0 +1,237 @@
+/// Handle referencing a packet stored in a [`PacketArena`].
+///
+/// Handles of removed packets stay invalid even if their slot gets reused
+/// by a later packet (the arena then returns `None` for the old handle).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct PacketHandle {
+    index: u32,
+    generation: u32,
+}
+
+impl PacketHandle {
+    /// Index of the slot the packet is stored in.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+/// State of a slot in a [`PacketArena`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct Slot {
+    /// Length of the stored packet (`None` if the slot is free).
+    len: Option<usize>,
+    /// Incremented every time the slot gets freed.
+    generation: u32,
+}
+
+/// Slab storage for owned packets with index based handles.
+///
+/// All packets are stored in one contiguous buffer, divided into slots of
+/// a fixed length (e.g. the snap length of a capture). Slots of removed
+/// packets are reused by the following packets, so after warming up no
+/// further allocations are needed. This reduces the allocator pressure &
+/// improves the cache locality compared to one `Vec<u8>` per packet when
+/// processing captures with high packet rates.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketArena, PacketBuilder, SlicedPacket};
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .write(&mut packet, &[1,2,3,4])
+///     .unwrap();
+///
+/// let mut arena = PacketArena::with_capacity(1500, 1024);
+/// let handle = arena.insert(&packet).unwrap();
+///
+/// let sliced = SlicedPacket::from_ip(arena.get(handle).unwrap()).unwrap();
+/// assert_eq!(&[1,2,3,4], sliced.payload);
+///
+/// // the slot gets reused after the packet was removed
+/// assert!(arena.remove(handle));
+/// assert_eq!(None, arena.get(handle));
+/// assert_eq!(handle.index(), arena.insert(&packet).unwrap().index());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PacketArena {
+    slot_len: usize,
+    data: Vec<u8>,
+    slots: Vec<Slot>,
+    /// Indices of the free slots (the last freed slot is reused first).
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl PacketArena {
+    /// Creates an empty arena with slots of the given length (no memory
+    /// is allocated till the first packet is inserted).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot_len` is 0.
+    pub fn new(slot_len: usize) -> PacketArena {
+        PacketArena::with_capacity(slot_len, 0)
+    }
+
+    /// Creates an empty arena with memory for `slots` packets preallocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `slot_len` is 0.
+    pub fn with_capacity(slot_len: usize, slots: usize) -> PacketArena {
+        assert!(0 != slot_len, "PacketArena: the slot length must not be zero");
+        PacketArena {
+            slot_len,
+            data: Vec::with_capacity(slot_len*slots),
+            slots: Vec::with_capacity(slots),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Maximum length of a packet stored in the arena.
+    #[inline]
+    pub fn slot_len(&self) -> usize {
+        self.slot_len
+    }
+
+    /// Number of packets stored in the arena.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no packets are stored in the arena.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+
+    /// Number of allocated slots (used & free).
+    #[inline]
+    pub fn slots(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Copies a packet into a free slot & returns its handle.
+    ///
+    /// Returns `None` if the packet is longer then the slot length.
+    pub fn insert(&mut self, packet: &[u8]) -> Option<PacketHandle> {
+        if packet.len() > self.slot_len {
+            return None;
+        }
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = u32::try_from(self.slots.len()).ok()?;
+                self.data.resize(self.data.len() + self.slot_len, 0);
+                self.slots.push(Slot{ len: None, generation: 0 });
+                index
+            }
+        };
+        let start = (index as usize)*self.slot_len;
+        self.data[start..start + packet.len()].copy_from_slice(packet);
+
+        let slot = &mut self.slots[index as usize];
+        slot.len = Some(packet.len());
+        self.len += 1;
+        Some(PacketHandle{ index, generation: slot.generation })
+    }
+
+    /// Returns the packet referenced by the handle (`None` if the packet was removed).
+    pub fn get(&self, handle: PacketHandle) -> Option<&[u8]> {
+        let len = self.slot(handle)?;
+        let start = handle.index()*self.slot_len;
+        Some(&self.data[start..start + len])
+    }
+
+    /// Returns the packet referenced by the handle as a mutable slice
+    /// (`None` if the packet was removed).
+    pub fn get_mut(&mut self, handle: PacketHandle) -> Option<&mut [u8]> {
+        let len = self.slot(handle)?;
+        let start = handle.index()*self.slot_len;
+        Some(&mut self.data[start..start + len])
+    }
+
+    /// Returns true if the handle references a stored packet.
+    #[inline]
+    pub fn contains(&self, handle: PacketHandle) -> bool {
+        self.slot(handle).is_some()
+    }
+
+    /// Removes the packet & frees its slot for reuse. Returns false if the
+    /// packet was already removed.
+    pub fn remove(&mut self, handle: PacketHandle) -> bool {
+        if self.slot(handle).is_none() {
+            return false;
+        }
+        let slot = &mut self.slots[handle.index()];
+        slot.len = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        self.len -= 1;
+        true
+    }
+
+    /// Removes all packets (the allocated memory is kept for reuse).
+    pub fn clear(&mut self) {
+        self.free.clear();
+        for (index, slot) in self.slots.iter_mut().enumerate().rev() {
+            if slot.len.is_some() {
+                slot.len = None;
+                slot.generation = slot.generation.wrapping_add(1);
+            }
+            self.free.push(index as u32);
+        }
+        self.len = 0;
+    }
+
+    /// Iterates over the handles & stored packets (in slot order).
+    pub fn iter(&self) -> PacketArenaIter<'_> {
+        PacketArenaIter {
+            arena: self,
+            index: 0,
+        }
+    }
+
+    /// length of the packet referenced by the handle (`None` if it was removed)
+    fn slot(&self, handle: PacketHandle) -> Option<usize> {
+        let slot = self.slots.get(handle.index())?;
+        if slot.generation == handle.generation {
+            slot.len
+        } else {
+            None
+        }
+    }
+}
+
+/// Iterator over the packets of a [`PacketArena`] (see [`PacketArena::iter`]).
+#[derive(Clone, Debug)]
+pub struct PacketArenaIter<'a> {
+    arena: &'a PacketArena,
+    index: usize,
+}
+
+impl<'a> Iterator for PacketArenaIter<'a> {
+    type Item = (PacketHandle, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.arena.slots.len() {
+            let index = self.index;
+            self.index += 1;
+            let slot = &self.arena.slots[index];
+            if let Some(len) = slot.len {
+                let start = index*self.arena.slot_len;
+                return Some((
+                    PacketHandle{ index: index as u32, generation: slot.generation },
+                    &self.arena.data[start..start + len]
+                ));
+            }
+        }
+        None
+    }
+}
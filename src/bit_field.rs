@@ -0,0 +1,199 @@
+use super::*;
+
+/// Reads big endian bit fields (most significant bit first) from a slice.
+///
+/// This can be used to implement decoders for experimental or proprietary
+/// headers that contain fields not aligned to byte boundaries. All reads are
+/// bounds checked and return an [`ReadError::UnexpectedEndOfSlice`] error
+/// if the slice is too short.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::BitReader;
+///
+/// // vlan tag control information: 3 bit priority, 1 bit drop eligible, 12 bit id
+/// let data = [0b1011_0001, 0x23];
+/// let mut reader = BitReader::new(&data);
+/// assert_eq!(0b101, reader.read(3).unwrap());
+/// assert!(reader.read_bool().unwrap());
+/// assert_eq!(0x123, reader.read(12).unwrap());
+/// assert_eq!(0, reader.remaining_bits());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitReader<'a> {
+    slice: &'a [u8],
+    /// Position of the next bit to read.
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// Creates a reader starting at the first bit of the slice.
+    pub fn new(slice: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            slice,
+            position: 0,
+        }
+    }
+
+    /// Position of the next bit that will be read (in bits from the start of the slice).
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of bits that are left to be read.
+    #[inline]
+    pub fn remaining_bits(&self) -> usize {
+        self.slice.len()*8 - self.position
+    }
+
+    /// Reads `bit_len` bits and returns them as an unsigned integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_len` is greater than 64.
+    pub fn read(&mut self, bit_len: u8) -> Result<u64, ReadError> {
+        assert!(bit_len <= 64, "BitReader::read: bit_len {} is greater than 64", bit_len);
+
+        let end = self.position + usize::from(bit_len);
+        if end > self.slice.len()*8 {
+            return Err(ReadError::UnexpectedEndOfSlice(end.div_ceil(8)));
+        }
+
+        let mut result = 0u64;
+        let mut remaining = bit_len;
+        while remaining > 0 {
+            let byte = self.slice[self.position/8];
+            let available = 8 - (self.position % 8) as u8;
+            let take = std::cmp::min(available, remaining);
+            let bits = (byte >> (available - take)) & bit_mask(take);
+            // take is at most 8, so the shift is fine even if a value
+            // with 64 bits is read
+            result = (result << take) | u64::from(bits);
+            self.position += usize::from(take);
+            remaining -= take;
+        }
+        Ok(result)
+    }
+
+    /// Reads a single bit (true if the bit is set).
+    #[inline]
+    pub fn read_bool(&mut self) -> Result<bool, ReadError> {
+        Ok(0 != self.read(1)?)
+    }
+
+    /// Skips the given number of bits.
+    pub fn skip(&mut self, bit_len: usize) -> Result<(), ReadError> {
+        let end = self.position + bit_len;
+        if end > self.slice.len()*8 {
+            return Err(ReadError::UnexpectedEndOfSlice(end.div_ceil(8)));
+        }
+        self.position = end;
+        Ok(())
+    }
+}
+
+/// Writes big endian bit fields (most significant bit first) into a slice.
+///
+/// Counterpart to [`BitReader`]. Bits of the slice that are not written to
+/// keep their previous value. Writes are bounds checked and values are checked
+/// to fit into the given number of bits.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::BitWriter;
+///
+/// let mut data = [0u8;2];
+/// let mut writer = BitWriter::new(&mut data);
+/// writer.write(0b101, 3).unwrap();
+/// writer.write_bool(true).unwrap();
+/// writer.write(0x123, 12).unwrap();
+/// assert_eq!([0b1011_0001, 0x23], data);
+/// ```
+#[derive(Debug, Eq, PartialEq)]
+pub struct BitWriter<'a> {
+    slice: &'a mut [u8],
+    /// Position of the next bit to write.
+    position: usize,
+}
+
+impl<'a> BitWriter<'a> {
+    /// Creates a writer starting at the first bit of the slice.
+    pub fn new(slice: &'a mut [u8]) -> BitWriter<'a> {
+        BitWriter {
+            slice,
+            position: 0,
+        }
+    }
+
+    /// Position of the next bit that will be written (in bits from the start of the slice).
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of bits that are left to be written.
+    #[inline]
+    pub fn remaining_bits(&self) -> usize {
+        self.slice.len()*8 - self.position
+    }
+
+    /// Writes the lowest `bit_len` bits of `value`.
+    ///
+    /// Returns a [`ValueError::BitFieldValueTooLarge`] error if the value
+    /// can not be represented with `bit_len` bits and a
+    /// [`WriteError::SliceTooSmall`] error if the slice is too short.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bit_len` is greater than 64.
+    pub fn write(&mut self, value: u64, bit_len: u8) -> Result<(), WriteError> {
+        assert!(bit_len <= 64, "BitWriter::write: bit_len {} is greater than 64", bit_len);
+
+        if bit_len < 64 && 0 != (value >> bit_len) {
+            return Err(WriteError::ValueError(ValueError::BitFieldValueTooLarge{ value, bit_len }));
+        }
+        let end = self.position + usize::from(bit_len);
+        if end > self.slice.len()*8 {
+            return Err(WriteError::SliceTooSmall(end.div_ceil(8)));
+        }
+
+        let mut remaining = bit_len;
+        while remaining > 0 {
+            let index = self.position/8;
+            let available = 8 - (self.position % 8) as u8;
+            let take = std::cmp::min(available, remaining);
+            let shift = available - take;
+            let bits = ((value >> (remaining - take)) as u8) & bit_mask(take);
+            let mask = bit_mask(take) << shift;
+            self.slice[index] = (self.slice[index] & !mask) | (bits << shift);
+            self.position += usize::from(take);
+            remaining -= take;
+        }
+        Ok(())
+    }
+
+    /// Writes a single bit (1 if `value` is true).
+    #[inline]
+    pub fn write_bool(&mut self, value: bool) -> Result<(), WriteError> {
+        self.write(u64::from(value), 1)
+    }
+
+    /// Skips the given number of bits (the skipped bits keep their value).
+    pub fn skip(&mut self, bit_len: usize) -> Result<(), WriteError> {
+        let end = self.position + bit_len;
+        if end > self.slice.len()*8 {
+            return Err(WriteError::SliceTooSmall(end.div_ceil(8)));
+        }
+        self.position = end;
+        Ok(())
+    }
+}
+
+/// Mask with the lowest `bit_len` (at most 8) bits set.
+#[inline]
+fn bit_mask(bit_len: u8) -> u8 {
+    ((1u16 << bit_len) - 1) as u8
+}
@@ -0,0 +1,171 @@
+use super::*;
+
+/// Transmit hash policies of the Linux bonding driver (`xmit_hash_policy`)
+/// selecting the member link of an aggregation a packet is sent over.
+///
+/// The hashes are calculated the same way as by the Linux kernel running on a
+/// little endian system (e.g. x86 or arm). The member link is the hash modulo
+/// the number of member links (see [`BondHashPolicy::member_index`]).
+///
+/// Some details of the kernel implementation are replicated as well:
+///
+/// * Vlan tags are expected to be offloaded when a packet is transmitted, so
+///   the ether type after the vlan headers is used for the layer 2 hash.
+/// * Packets without an ip header fall back to the layer 2 hash.
+/// * Ports are only used for udp, tcp, udp-lite, sctp & dccp packets (the
+///   protocols the kernel flow dissector reads ports of) that are not
+///   fragmented and contain no ip extension headers (otherwise the ports are
+///   treated as 0).
+/// * For `Layer3And4` the identifier of ICMP echo (& timestamp) messages is
+///   used instead of ports (supported by kernels since version 5.17).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{BondHashPolicy, PacketBuilder, SlicedPacket};
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ethernet2([0,0,0,0,0,1], [0,0,0,0,0,2])
+///     .ipv4([192,168,1,1], [192,168,1,2], 64)
+///     .udp(1000, 53)
+///     .write(&mut packet, &[])
+///     .unwrap();
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+///
+/// // layer 2: last bytes of the mac addresses xor the ether type
+/// assert_eq!(1 ^ 2 ^ 0x0800, BondHashPolicy::Layer2.hash(&sliced));
+///
+/// // member link selected out of 2 member links
+/// let member = BondHashPolicy::Layer3And4.member_index(&sliced, 2);
+/// assert!(member < 2);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum BondHashPolicy {
+    /// Hash over the mac addresses & the ether type (`layer2`).
+    Layer2,
+    /// Hash over the mac addresses, the ether type & the ip addresses (`layer2+3`).
+    Layer2And3,
+    /// Hash over the ip addresses & the ports (`layer3+4`).
+    Layer3And4,
+}
+
+impl BondHashPolicy {
+    /// Calculates the transmit hash of a packet.
+    pub fn hash(self, packet: &SlicedPacket) -> u32 {
+        use BondHashPolicy::*;
+        let ip = match (self, &packet.ip) {
+            (Layer2, _) | (_, None) => return BondHashPolicy::ethernet_hash(packet),
+            (_, Some(ip)) => ip,
+        };
+
+        let hash = if Layer2And3 == self {
+            BondHashPolicy::ethernet_hash(packet)
+        } else {
+            BondHashPolicy::ports_hash(packet, ip)
+        };
+
+        let (source, destination) = match ip {
+            InternetSlice::Ipv4(header, _) => (
+                u32::from_le_bytes(header.source()),
+                u32::from_le_bytes(header.destination()),
+            ),
+            InternetSlice::Ipv6(header, _) => (
+                BondHashPolicy::ipv6_addr_hash(header.source()),
+                BondHashPolicy::ipv6_addr_hash(header.destination()),
+            ),
+        };
+        let mut hash = hash ^ source ^ destination;
+        hash ^= hash >> 16;
+        hash ^= hash >> 8;
+        if Layer3And4 == self {
+            // the kernel discards the lowest bit (even port numbers are common)
+            hash >> 1
+        } else {
+            hash
+        }
+    }
+
+    /// Returns the index of the member link (out of `member_count` links) the
+    /// packet is sent over (hash modulo the number of member links).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `member_count` is 0.
+    pub fn member_index(self, packet: &SlicedPacket, member_count: usize) -> usize {
+        (self.hash(packet) as usize) % member_count
+    }
+
+    /// Layer 2 hash (0 if the packet has no ethernet II header).
+    fn ethernet_hash(packet: &SlicedPacket) -> u32 {
+        let ethernet = match &packet.link {
+            Some(LinkSlice::Ethernet2(value)) => value,
+            None => return 0,
+        };
+        let ether_type = match &packet.vlan {
+            Some(VlanSlice::SingleVlan(vlan)) => vlan.ether_type(),
+            Some(VlanSlice::DoubleVlan(vlan)) => vlan.inner().ether_type(),
+            None => ethernet.ether_type(),
+        };
+        u32::from(ethernet.destination()[5] ^ ethernet.source()[5]) ^ u32::from(ether_type)
+    }
+
+    /// Ports (or ICMP echo identifier) as read by the kernel into a 32 bit value.
+    fn ports_hash(packet: &SlicedPacket, ip: &InternetSlice) -> u32 {
+        let has_ports = match ip {
+            InternetSlice::Ipv4(header, extensions) => {
+                false == header.is_fragmenting_payload() && extensions.is_empty()
+            },
+            InternetSlice::Ipv6(_, extensions) => extensions.is_empty(),
+        };
+        if false == has_ports {
+            return 0;
+        }
+        use TransportSlice::*;
+        match &packet.transport {
+            Some(Udp(udp)) => BondHashPolicy::ports_value(udp.source_port(), udp.destination_port()),
+            Some(Tcp(tcp)) => BondHashPolicy::ports_value(tcp.source_port(), tcp.destination_port()),
+            Some(Sctp(sctp)) => BondHashPolicy::ports_value(sctp.source_port(), sctp.destination_port()),
+            Some(UdpLite(udp)) => BondHashPolicy::ports_value(udp.source_port(), udp.destination_port()),
+            Some(Dccp(dccp)) => BondHashPolicy::ports_value(dccp.source_port(), dccp.destination_port()),
+            Some(Icmpv4(icmp)) => {
+                // echo reply, echo request, timestamp & timestamp reply
+                let has_id = matches!(icmp.type_u8(), 0 | 8 | 13 | 14);
+                BondHashPolicy::icmp_value(icmp.type_u8(), icmp.code_u8(), has_id, icmp.bytes5to8())
+            },
+            Some(Icmpv6(icmp)) => {
+                // echo request & echo reply
+                let has_id = matches!(icmp.type_u8(), 128 | 129);
+                BondHashPolicy::icmp_value(icmp.type_u8(), icmp.code_u8(), has_id, icmp.bytes5to8())
+            },
+            Some(Igmp(_)) | Some(Unknown(_)) | None => 0,
+        }
+    }
+
+    /// Source & destination port in network byte order read as little endian value.
+    fn ports_value(source_port: u16, destination_port: u16) -> u32 {
+        let source = source_port.to_be_bytes();
+        let destination = destination_port.to_be_bytes();
+        u32::from_le_bytes([source[0], source[1], destination[0], destination[1]])
+    }
+
+    /// ICMP type, code & identifier as stored in the kernel flow keys (the
+    /// identifier is replaced by 1 if it is 0, messages without identifier
+    /// result in 0).
+    fn icmp_value(type_u8: u8, code_u8: u8, has_id: bool, bytes5to8: [u8;4]) -> u32 {
+        if false == has_id {
+            return 0;
+        }
+        let id = match u16::from_be_bytes([bytes5to8[0], bytes5to8[1]]) {
+            0 => 1,
+            value => value,
+        };
+        u32::from(type_u8) | (u32::from(code_u8) << 8) | (u32::from(id) << 16)
+    }
+
+    /// Xor of the 32 bit words of an ipv6 address (read as little endian values).
+    fn ipv6_addr_hash(addr: [u8;16]) -> u32 {
+        addr.chunks_exact(4)
+            .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+            .fold(0, |hash, word| hash ^ word)
+    }
+}
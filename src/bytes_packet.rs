@@ -0,0 +1,192 @@
+use super::*;
+
+use bytes::Bytes;
+use std::ops::Range;
+
+/// Owned packet view based on [`bytes::Bytes`] (requires the `bytes` feature).
+///
+/// The packet is sliced the same way as [`SlicedPacket`] is, but instead of
+/// borrowing the data the view keeps a reference counted [`Bytes`] buffer and
+/// only the positions of the headers. The accessors return cheap [`Bytes`]
+/// sub-slices of the buffer (no data is copied), which makes the view easy
+/// to move across tasks & threads.
+///
+/// If the decoded field values are needed [`BytesSlicedPacket::sliced`] returns
+/// a regular [`SlicedPacket`] borrowing the buffer.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6],     //source mac
+/// #               [7,8,9,10,11,12]) //destionation mac
+/// #    .ipv4([192,168,1,1], //source ip
+/// #          [192,168,1,2], //desitionation ip
+/// #          20)            //time to life
+/// #    .udp(21,    //source port
+/// #         1234); //desitnation port
+/// # let payload = [1,2,3,4,5,6,7,8];
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(payload.len()));
+/// # builder.write(&mut packet, &payload).unwrap();
+/// use bytes::Bytes;
+/// use etherparse::BytesSlicedPacket;
+///
+/// let packet = BytesSlicedPacket::from_ethernet(Bytes::from(packet)).unwrap();
+///
+/// // owned sub-slices of the original buffer
+/// let payload: Bytes = packet.payload();
+/// assert_eq!(&[1,2,3,4,5,6,7,8], &payload[..]);
+/// assert_eq!(8, packet.transport().unwrap().len());
+///
+/// // decoded values
+/// println!("{:?}", packet.sliced().transport);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BytesSlicedPacket {
+    data: Bytes,
+    start: BytesSliceStart,
+    link: Option<Range<usize>>,
+    vlan: Option<Range<usize>>,
+    ip: Option<Range<usize>>,
+    transport: Option<Range<usize>>,
+    payload: Range<usize>,
+}
+
+/// Function used to slice the data of a [`BytesSlicedPacket`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum BytesSliceStart {
+    Ethernet2,
+    EtherType(u16),
+    Ip,
+}
+
+impl BytesSliceStart {
+    fn slice(self, data: &[u8]) -> Result<SlicedPacket<'_>, ReadError> {
+        use BytesSliceStart::*;
+        match self {
+            Ethernet2 => SlicedPacket::from_ethernet(data),
+            EtherType(ether_type) => SlicedPacket::from_ether_type(ether_type, data),
+            Ip => SlicedPacket::from_ip(data),
+        }
+    }
+}
+
+impl BytesSlicedPacket {
+    /// Slices a packet starting with an ethernet II header (see [`SlicedPacket::from_ethernet`]).
+    pub fn from_ethernet(data: Bytes) -> Result<BytesSlicedPacket, ReadError> {
+        BytesSlicedPacket::new(BytesSliceStart::Ethernet2, data)
+    }
+
+    /// Slices a packet starting after an ethernet II header with the given ether type
+    /// (see [`SlicedPacket::from_ether_type`]).
+    pub fn from_ether_type(ether_type: u16, data: Bytes) -> Result<BytesSlicedPacket, ReadError> {
+        BytesSlicedPacket::new(BytesSliceStart::EtherType(ether_type), data)
+    }
+
+    /// Slices a packet starting with an ipv4 or ipv6 header (see [`SlicedPacket::from_ip`]).
+    pub fn from_ip(data: Bytes) -> Result<BytesSlicedPacket, ReadError> {
+        BytesSlicedPacket::new(BytesSliceStart::Ip, data)
+    }
+
+    fn new(start: BytesSliceStart, data: Bytes) -> Result<BytesSlicedPacket, ReadError> {
+        let (link, vlan, ip, transport, payload) = {
+            let sliced = start.slice(&data)?;
+
+            // offsets of the layers in the buffer
+            let offset = |part: &[u8]| part.as_ptr() as usize - data.as_ptr() as usize;
+            let link_start = sliced.link.as_ref().map(|link| match link {
+                LinkSlice::Ethernet2(eth) => offset(eth.slice()),
+            });
+            let vlan_start = sliced.vlan.as_ref().map(|vlan| match vlan {
+                VlanSlice::SingleVlan(s) => offset(s.slice()),
+                VlanSlice::DoubleVlan(d) => offset(d.slice()),
+            });
+            let ip_start = sliced.ip.as_ref().map(|ip| match ip {
+                InternetSlice::Ipv4(header, _) => offset(header.slice()),
+                InternetSlice::Ipv6(header, _) => offset(header.slice()),
+            });
+            let transport_start = sliced.transport.as_ref().and_then(|transport| {
+                use TransportSlice::*;
+                match transport {
+                    Icmpv4(s) => Some(offset(s.slice())),
+                    Icmpv6(s) => Some(offset(s.slice())),
+                    Igmp(s) => Some(offset(s.slice())),
+                    Udp(s) => Some(offset(s.slice())),
+                    Tcp(s) => Some(offset(s.slice())),
+                    Sctp(s) => Some(offset(s.slice())),
+                    UdpLite(s) => Some(offset(s.slice())),
+                    Dccp(s) => Some(offset(s.slice())),
+                    Unknown(_) => None,
+                }
+            });
+            let payload_start = offset(sliced.payload);
+            let payload = payload_start..payload_start + sliced.payload.len();
+
+            // every layer ends where the next present layer starts
+            let starts = [link_start, vlan_start, ip_start, transport_start];
+            let range = |index: usize| -> Option<Range<usize>> {
+                let start = starts[index]?;
+                let end = starts[index + 1..]
+                    .iter()
+                    .find_map(|s| *s)
+                    .unwrap_or(payload_start);
+                Some(start..end)
+            };
+            (range(0), range(1), range(2), range(3), payload)
+        };
+        Ok(BytesSlicedPacket {
+            data,
+            start,
+            link,
+            vlan,
+            ip,
+            transport,
+            payload,
+        })
+    }
+
+    /// Returns the complete buffer the packet was sliced from.
+    #[inline]
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Consumes the view & returns the buffer the packet was sliced from.
+    #[inline]
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+
+    /// Bytes of the link layer header (if present).
+    pub fn link(&self) -> Option<Bytes> {
+        self.link.clone().map(|r| self.data.slice(r))
+    }
+
+    /// Bytes of the vlan header(s) (if present).
+    pub fn vlan(&self) -> Option<Bytes> {
+        self.vlan.clone().map(|r| self.data.slice(r))
+    }
+
+    /// Bytes of the ip header including the ip extension headers (if present).
+    pub fn ip(&self) -> Option<Bytes> {
+        self.ip.clone().map(|r| self.data.slice(r))
+    }
+
+    /// Bytes of the transport header (if a known transport header is present).
+    pub fn transport(&self) -> Option<Bytes> {
+        self.transport.clone().map(|r| self.data.slice(r))
+    }
+
+    /// Bytes of the payload (see [`SlicedPacket::payload`] for what the payload contains).
+    pub fn payload(&self) -> Bytes {
+        self.data.slice(self.payload.clone())
+    }
+
+    /// Returns a [`SlicedPacket`] borrowing the buffer to access the decoded header values.
+    pub fn sliced(&self) -> SlicedPacket<'_> {
+        // the data was already successfully sliced with the same function
+        // during the construction and is immutable, so this can not fail
+        self.start.slice(&self.data).unwrap()
+    }
+}
@@ -0,0 +1,249 @@
+use super::*;
+
+use crate::checksum::Sum16BitWords;
+
+/// Policy deciding which checksums get verified when verifying the
+/// checksums of a batch of packets (see [`PacketSet::verify_checksums`]).
+///
+/// Verifying a checksum that covers the transport payload (TCP, UDP, ICMP, ...)
+/// requires reading every byte of a packet. For very high packet rates these
+/// checksums can be skipped completely ("headers only") or be verified for
+/// only every n-th packet. Header checksums (IPv4 header) are always verified.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::VerifyOptions;
+///
+/// // full verification of every 100th packet,
+/// // header checksums only for all others
+/// let options = VerifyOptions::sampled(100);
+/// assert!(options.is_full_verification(0));
+/// assert!(false == options.is_full_verification(1));
+/// assert!(options.is_full_verification(200));
+///
+/// assert!(false == VerifyOptions::headers_only().is_full_verification(0));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct VerifyOptions {
+    /// If true the payload covering checksums are skipped for all packets.
+    pub headers_only: bool,
+    /// Interval at which packets get a full verification (including the
+    /// payload covering checksums). Starting with the first packet only every
+    /// n-th packet is fully verified, 0 & 1 fully verify every packet.
+    pub full_sample_interval: usize,
+}
+
+impl VerifyOptions {
+    /// Options verifying all checksums of all packets.
+    pub const fn full() -> VerifyOptions {
+        VerifyOptions {
+            headers_only: false,
+            full_sample_interval: 1,
+        }
+    }
+
+    /// Options only verifying the header checksums of all packets.
+    pub const fn headers_only() -> VerifyOptions {
+        VerifyOptions {
+            headers_only: true,
+            full_sample_interval: 1,
+        }
+    }
+
+    /// Options fully verifying every `interval`-th packet & only the header
+    /// checksums of the other packets.
+    pub const fn sampled(interval: usize) -> VerifyOptions {
+        VerifyOptions {
+            headers_only: false,
+            full_sample_interval: interval,
+        }
+    }
+
+    /// Returns true if the packet with the given index in a batch has to be
+    /// fully verified (including the payload covering checksums).
+    pub fn is_full_verification(&self, index: usize) -> bool {
+        false == self.headers_only && (
+            self.full_sample_interval <= 1 ||
+            index.is_multiple_of(self.full_sample_interval)
+        )
+    }
+}
+
+impl Default for VerifyOptions {
+    fn default() -> VerifyOptions {
+        VerifyOptions::full()
+    }
+}
+
+/// Result of verifying the checksums of the outermost headers of a sliced packet.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ChecksumReport, PacketBuilder, SlicedPacket};
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .write(&mut packet, &[1,2,3,4])
+///     .unwrap();
+/// // corrupt the payload
+/// let last = packet.len() - 1;
+/// packet[last] = 0;
+///
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// let report = ChecksumReport::verify(&packet, &sliced);
+/// assert_eq!(Some(true), report.ipv4_header);
+/// assert_eq!(Some(false), report.transport);
+/// assert!(false == report.is_valid());
+///
+/// // the payload is not touched if only the headers are verified
+/// let report = ChecksumReport::verify_headers(&sliced);
+/// assert_eq!(Some(true), report.ipv4_header);
+/// assert_eq!(None, report.transport);
+/// assert!(report.payload_skipped);
+/// assert!(report.is_valid());
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct ChecksumReport {
+    /// Result of the IPv4 header checksum verification (`None` if no IPv4
+    /// header is present).
+    pub ipv4_header: Option<bool>,
+    /// Result of the transport checksum verification (TCP, UDP, UDP-Lite,
+    /// DCCP, ICMPv4, ICMPv6, IGMP or SCTP).
+    ///
+    /// `None` if the verification was skipped, no or an unknown transport
+    /// protocol is present, the checksum is disabled (UDP over IPv4 with a
+    /// checksum of 0) or the data covered by the checksum is truncated.
+    pub transport: Option<bool>,
+    /// True if the transport checksum was not verified because only the
+    /// header checksums were requested.
+    pub payload_skipped: bool,
+}
+
+impl ChecksumReport {
+    /// Verifies the header & the payload covering checksums of a packet that was
+    /// sliced from `data`.
+    ///
+    /// `packet` has to be the result of slicing `data` as the data covered by
+    /// the transport checksum is determined based on the ip payload length (the
+    /// payload of the sliced packet can contain trailing bytes). If `packet`
+    /// was not sliced from `data` the transport checksum is not verified.
+    ///
+    /// The pseudo header of the transport checksums is built from the source &
+    /// destination address of the ip header.
+    pub fn verify(data: &[u8], packet: &SlicedPacket) -> ChecksumReport {
+        ChecksumReport {
+            ipv4_header: verify_ipv4_header(packet),
+            transport: verify_transport(data, packet),
+            payload_skipped: false,
+        }
+    }
+
+    /// Only verifies the checksums that do not cover the payload (IPv4 header).
+    pub fn verify_headers(packet: &SlicedPacket) -> ChecksumReport {
+        ChecksumReport {
+            ipv4_header: verify_ipv4_header(packet),
+            transport: None,
+            payload_skipped: true,
+        }
+    }
+
+    /// Returns true if none of the verified checksums were invalid.
+    pub fn is_valid(&self) -> bool {
+        Some(false) != self.ipv4_header && Some(false) != self.transport
+    }
+}
+
+fn verify_ipv4_header(packet: &SlicedPacket) -> Option<bool> {
+    match &packet.ip {
+        Some(InternetSlice::Ipv4(header, _)) => Some(
+            0 == Sum16BitWords::new().add_slice(header.slice()).ones_complement()
+        ),
+        _ => None,
+    }
+}
+
+fn verify_transport(data: &[u8], packet: &SlicedPacket) -> Option<bool> {
+    use crate::InternetSlice::*;
+    use crate::TransportSlice::*;
+
+    let ip = packet.ip.as_ref()?;
+    let transport = packet.transport.as_ref()?;
+    let transport_slice = match transport {
+        Icmpv4(slice) => slice.slice(),
+        Icmpv6(slice) => slice.slice(),
+        Udp(slice) => slice.slice(),
+        Tcp(slice) => slice.slice(),
+        Igmp(slice) => slice.slice(),
+        Sctp(slice) => slice.slice(),
+        UdpLite(slice) => slice.slice(),
+        Dccp(slice) => slice.slice(),
+        Unknown(_) => return None,
+    };
+
+    // determine the data covered by the transport checksum
+    let ip_start = match ip {
+        Ipv4(header, _) => offset_in(data, header.slice())?,
+        Ipv6(header, _) => offset_in(data, header.slice())?,
+    };
+    let ip_end = match ip {
+        Ipv4(header, _) => ip_start + usize::from(header.total_len()),
+        // payload length 0 (jumbogram or segmentation offloading)
+        Ipv6(header, _) if 0 == header.payload_length() => data.len(),
+        Ipv6(header, _) => ip_start + header.slice().len() + usize::from(header.payload_length()),
+    };
+    let start = offset_in(data, transport_slice)?;
+    if ip_end > data.len() || ip_end < start + transport_slice.len() {
+        return None;
+    }
+    let segment = &data[start..ip_end];
+
+    let pseudo_header = |ip_number: u8| -> Sum16BitWords {
+        match ip {
+            Ipv4(header, _) => Sum16BitWords::new()
+                .add_4bytes(header.source())
+                .add_4bytes(header.destination())
+                .add_2bytes([0, ip_number])
+                .add_2bytes((segment.len() as u16).to_be_bytes()),
+            Ipv6(header, _) => Sum16BitWords::new()
+                .add_16bytes(header.source())
+                .add_16bytes(header.destination())
+                .add_4bytes((segment.len() as u32).to_be_bytes())
+                .add_2bytes([0, ip_number]),
+        }
+    };
+    // the sum over the data including the checksum field has to be 0xffff
+    let is_valid = |sum: Sum16BitWords| 0 == sum.add_slice(segment).ones_complement();
+
+    match transport {
+        Udp(udp) if 0 == udp.checksum() => match ip {
+            // checksum is optional for udp over ipv4
+            Ipv4(_, _) => None,
+            Ipv6(_, _) => Some(false),
+        },
+        Udp(_) => Some(is_valid(pseudo_header(ip_number::UDP))),
+        Tcp(_) => Some(is_valid(pseudo_header(ip_number::TCP))),
+        Icmpv6(_) => Some(is_valid(pseudo_header(ip_number::IPV6_ICMP))),
+        Icmpv4(_) | Igmp(_) => Some(is_valid(Sum16BitWords::new())),
+        UdpLite(udp) => {
+            let header = udp.to_header();
+            let payload = &segment[UdpLiteHeader::SIZE..];
+            Some(match ip {
+                Ipv4(ip, _) => header.is_checksum_valid_ipv4(ip.source(), ip.destination(), payload),
+                Ipv6(ip, _) => header.is_checksum_valid_ipv6(ip.source(), ip.destination(), payload),
+            })
+        },
+        Sctp(sctp) => Some(sctp.is_checksum_valid(&segment[sctp.slice().len()..])),
+        Dccp(dccp) => {
+            let header = dccp.to_header();
+            let payload = &segment[dccp.slice().len()..];
+            Some(match ip {
+                Ipv4(ip, _) => header.is_checksum_valid_ipv4(ip.source(), ip.destination(), payload),
+                Ipv6(ip, _) => header.is_checksum_valid_ipv6(ip.source(), ip.destination(), payload),
+            })
+        },
+        Unknown(_) => None,
+    }
+}
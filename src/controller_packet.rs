@@ -0,0 +1,153 @@
+use super::*;
+
+/// Raw frame sent by a switch to its controller (e.g. the data of an OpenFlow
+/// `OFPT_PACKET_IN` message or the payload of a P4Runtime `PacketIn`) together
+/// with the metadata of the controller protocol (e.g. buffer id, in port,
+/// match fields or P4 packet metadata).
+///
+/// The metadata type is chosen by the user, so the controller protocol itself
+/// is not decoded. Switches often only send the first bytes of a frame to the
+/// controller (e.g. the OpenFlow `miss_send_len`), the length of the original
+/// frame can be recorded via [`PacketIn::with_total_len`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketBuilder, PacketIn};
+///
+/// # let mut frame = Vec::new();
+/// # PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+/// #     .ipv4([192,168,1,1], [192,168,1,2], 20)
+/// #     .udp(21, 1234)
+/// #     .write(&mut frame, &[1,2,3,4])
+/// #     .unwrap();
+/// // in port & buffer id of an OpenFlow packet-in message
+/// let packet_in = PacketIn::new((3u32, 0xffff_ffffu32), &frame);
+///
+/// let sliced = packet_in.sliced().unwrap();
+/// assert!(sliced.transport.is_some());
+///
+/// // send the frame back out of port 1
+/// let packet_out = packet_in.packet_out(1u32);
+/// assert_eq!(frame, packet_out.frame);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PacketIn<'a, M> {
+    /// Metadata of the controller protocol.
+    pub metadata: M,
+    /// Raw bytes of the frame (starting with the ethernet II header).
+    pub frame: &'a [u8],
+    /// Length of the frame as received by the switch (can be bigger then the
+    /// length of `frame` if the switch only sent a part of the frame).
+    pub total_len: usize,
+}
+
+impl<'a, M> PacketIn<'a, M> {
+    /// Bundles a completely received frame with its metadata.
+    pub fn new(metadata: M, frame: &'a [u8]) -> PacketIn<'a, M> {
+        PacketIn {
+            metadata,
+            frame,
+            total_len: frame.len(),
+        }
+    }
+
+    /// Sets the length of the frame as received by the switch.
+    #[inline]
+    pub fn with_total_len(mut self, total_len: usize) -> PacketIn<'a, M> {
+        self.total_len = total_len;
+        self
+    }
+
+    /// Returns true if the switch only sent a part of the frame.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.frame.len() < self.total_len
+    }
+
+    /// Slices the frame with [`SlicedPacket::from_ethernet`].
+    pub fn sliced(&self) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_ethernet(self.frame)
+    }
+
+    /// Slices the frame with [`SlicedPacket::from_ethernet_with_registry`].
+    pub fn sliced_with_registry(&self, registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_ethernet_with_registry(self.frame, registry)
+    }
+
+    /// Decodes the frame with [`PacketHeaders::from_ethernet_slice`].
+    pub fn headers(&self) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::from_ethernet_slice(self.frame)
+    }
+
+    /// Converts the metadata and keeps the frame.
+    pub fn map_metadata<N, F: FnOnce(M) -> N>(self, f: F) -> PacketIn<'a, N> {
+        PacketIn {
+            metadata: f(self.metadata),
+            frame: self.frame,
+            total_len: self.total_len,
+        }
+    }
+
+    /// Returns a packet-out containing a copy of the frame (e.g. to forward
+    /// the frame after inspecting it).
+    pub fn packet_out<N>(&self, metadata: N) -> PacketOut<N> {
+        PacketOut::new(metadata, self.frame.to_vec())
+    }
+}
+
+/// Raw frame sent by a controller to a switch (e.g. the data of an OpenFlow
+/// `OFPT_PACKET_OUT` message or the payload of a P4Runtime `PacketOut`)
+/// together with the metadata of the controller protocol (e.g. the actions or
+/// the egress port).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketBuilder, PacketOut};
+///
+/// let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234);
+///
+/// // egress port as metadata
+/// let packet_out = PacketOut::write_with(2u32, |frame| {
+///     builder.write(frame, &[1,2,3,4])
+/// }).unwrap();
+///
+/// assert_eq!(2, packet_out.metadata);
+/// assert_eq!(14 + 20 + 8 + 4, packet_out.frame.len());
+/// assert!(packet_out.sliced().unwrap().ip.is_some());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PacketOut<M> {
+    /// Metadata of the controller protocol.
+    pub metadata: M,
+    /// Raw bytes of the frame (starting with the ethernet II header).
+    pub frame: Vec<u8>,
+}
+
+impl<M> PacketOut<M> {
+    /// Bundles a serialized frame with its metadata.
+    #[inline]
+    pub fn new(metadata: M, frame: Vec<u8>) -> PacketOut<M> {
+        PacketOut { metadata, frame }
+    }
+
+    /// Serializes a frame with the given function (e.g. the `write` method
+    /// of a [`PacketBuilder`]) & bundles it with its metadata.
+    pub fn write_with<F>(metadata: M, f: F) -> Result<PacketOut<M>, WriteError>
+    where
+        F: FnOnce(&mut Vec<u8>) -> Result<(), WriteError>,
+    {
+        let mut frame = Vec::new();
+        f(&mut frame)?;
+        Ok(PacketOut { metadata, frame })
+    }
+
+    /// Slices the frame with [`SlicedPacket::from_ethernet`] (e.g. to check
+    /// the frame before sending it).
+    pub fn sliced(&self) -> Result<SlicedPacket<'_>, ReadError> {
+        SlicedPacket::from_ethernet(&self.frame)
+    }
+}
@@ -0,0 +1,259 @@
+use super::*;
+
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+/// Configuration which parts of a packet are ignored when calculating the
+/// digest used to detect duplicates (see [`Deduplicator`]).
+///
+/// Duplicates introduced by mirror ports (SPAN) or network taps often differ
+/// in the fields that are changed while forwarding the packet (e.g. if the
+/// same packet is mirrored before & after a router). By default all of these
+/// fields are ignored.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct DedupConfig {
+    /// Ignore the ipv4 time to live & the ipv6 hop limit.
+    pub ignore_ttl: bool,
+    /// Ignore the ipv4 header checksum and the udp, tcp, icmpv4 & icmpv6 checksums.
+    pub ignore_checksums: bool,
+    /// Ignore the source & destination mac addresses of the ethernet II header.
+    pub ignore_link_addresses: bool,
+}
+
+impl Default for DedupConfig {
+    fn default() -> DedupConfig {
+        DedupConfig {
+            ignore_ttl: true,
+            ignore_checksums: true,
+            ignore_link_addresses: true,
+        }
+    }
+}
+
+impl DedupConfig {
+    /// Key of the hash function used for the digests (fixed, so digests are stable).
+    const DIGEST_KEY: [u64;2] = [0x6574_6865_7270_6172, 0x7365_6465_6475_7000];
+
+    /// Calculates the digest of a packet starting with an ethernet II header.
+    ///
+    /// If the packet can not be decoded the digest is calculated over all bytes.
+    pub fn digest_ethernet(&self, data: &[u8]) -> u64 {
+        self.digest(SlicedPacket::from_ethernet(data).ok(), data, &mut Vec::new())
+    }
+
+    /// Calculates the digest of a packet starting with an ipv4 or ipv6 header.
+    ///
+    /// If the packet can not be decoded the digest is calculated over all bytes.
+    pub fn digest_ip(&self, data: &[u8]) -> u64 {
+        self.digest(SlicedPacket::from_ip(data).ok(), data, &mut Vec::new())
+    }
+
+    /// Calculates the digest with the ignored fields set to zero (using the
+    /// given buffer for the modified copy of the packet).
+    fn digest(&self, sliced: Option<SlicedPacket>, data: &[u8], buffer: &mut Vec<u8>) -> u64 {
+        buffer.clear();
+        buffer.extend_from_slice(data);
+        if let Some(sliced) = sliced {
+            // offset of a part of the packet
+            let offset = |part: &[u8]| part.as_ptr() as usize - data.as_ptr() as usize;
+            let mut clear = |start: usize, end: usize| {
+                for value in &mut buffer[start..end] {
+                    *value = 0;
+                }
+            };
+
+            if self.ignore_link_addresses {
+                if let Some(LinkSlice::Ethernet2(ethernet)) = &sliced.link {
+                    let start = offset(ethernet.slice());
+                    clear(start, start + 12);
+                }
+            }
+            match &sliced.ip {
+                Some(InternetSlice::Ipv4(header, _)) => {
+                    let start = offset(header.slice());
+                    if self.ignore_ttl {
+                        clear(start + 8, start + 9);
+                    }
+                    if self.ignore_checksums {
+                        clear(start + 10, start + 12);
+                    }
+                },
+                Some(InternetSlice::Ipv6(header, _)) if self.ignore_ttl => {
+                    let start = offset(header.slice());
+                    clear(start + 7, start + 8);
+                },
+                _ => {},
+            }
+            if self.ignore_checksums {
+                use TransportSlice::*;
+                let checksum_range = match &sliced.transport {
+                    Some(Udp(udp)) => Some((offset(udp.slice()), 6, 2)),
+                    Some(Tcp(tcp)) => Some((offset(tcp.slice()), 16, 2)),
+                    Some(Sctp(sctp)) => Some((offset(sctp.slice()), 8, 4)),
+                    Some(UdpLite(udp)) => Some((offset(udp.slice()), 6, 2)),
+                    Some(Dccp(dccp)) => Some((offset(dccp.slice()), 6, 2)),
+                    Some(Icmpv4(icmp)) => Some((offset(icmp.slice()), 2, 2)),
+                    Some(Icmpv6(icmp)) => Some((offset(icmp.slice()), 2, 2)),
+                    Some(Igmp(igmp)) => Some((offset(igmp.slice()), 2, 2)),
+                    Some(Unknown(_)) | None => None,
+                };
+                if let Some((start, checksum_offset, checksum_len)) = checksum_range {
+                    clear(start + checksum_offset, start + checksum_offset + checksum_len);
+                }
+            }
+        }
+        siphash::siphash24(DedupConfig::DIGEST_KEY, buffer)
+    }
+}
+
+/// Sliding window cache removing duplicate packets (e.g. introduced by
+/// mirror ports) based on the digests defined by a [`DedupConfig`].
+///
+/// A packet is considered a duplicate if a packet with the same digest was
+/// seen within the time window before it. The number of remembered digests is
+/// limited by the capacity (the oldest digests are dropped first).
+///
+/// The timestamps of the packets are expected to be non decreasing.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Deduplicator, DedupConfig, PacketBuilder};
+/// use std::time::Duration;
+///
+/// let mut dedup = Deduplicator::new(DedupConfig::default(), Duration::from_millis(50), 1024);
+///
+/// let packet = |ttl: u8| {
+///     let mut result = Vec::new();
+///     PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///         .ipv4([192,168,1,1], [192,168,1,2], ttl)
+///         .udp(21, 1234)
+///         .write(&mut result, &[1,2,3,4])
+///         .unwrap();
+///     result
+/// };
+///
+/// assert!(false == dedup.is_duplicate(Duration::from_millis(1000), &packet(64)));
+/// // same packet mirrored after a router (ttl & ip checksum differ)
+/// assert!(dedup.is_duplicate(Duration::from_millis(1001), &packet(63)));
+/// // outside of the window the packet is not a duplicate anymore
+/// assert!(false == dedup.is_duplicate(Duration::from_millis(1100), &packet(64)));
+/// assert_eq!(1, dedup.duplicates());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Deduplicator {
+    config: DedupConfig,
+    window: Duration,
+    capacity: usize,
+    /// Timestamp & digest of the remembered packets (oldest first).
+    entries: VecDeque<(Duration, u64)>,
+    digests: HashSet<u64>,
+    duplicates: u64,
+    /// Buffer used to calculate the digests.
+    buffer: Vec<u8>,
+}
+
+impl Deduplicator {
+    /// Creates an empty cache remembering up to `capacity` digests for
+    /// the duration `window`.
+    pub fn new(config: DedupConfig, window: Duration, capacity: usize) -> Deduplicator {
+        Deduplicator {
+            config,
+            window,
+            capacity,
+            entries: VecDeque::new(),
+            digests: HashSet::new(),
+            duplicates: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Configuration used to calculate the digests.
+    #[inline]
+    pub fn config(&self) -> &DedupConfig {
+        &self.config
+    }
+
+    /// Duration packets are remembered.
+    #[inline]
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Maximum number of remembered digests.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of currently remembered digests.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no digests are remembered.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of packets detected as duplicates.
+    #[inline]
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// Forgets all remembered digests (the duplicates counter is kept).
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.digests.clear();
+    }
+
+    /// Returns true if the packet (starting with an ethernet II header) is a
+    /// duplicate of a packet seen within the window, otherwise the packet is
+    /// remembered & false is returned.
+    pub fn is_duplicate(&mut self, timestamp: Duration, data: &[u8]) -> bool {
+        let digest = self.config.digest(SlicedPacket::from_ethernet(data).ok(), data, &mut self.buffer);
+        self.is_duplicate_digest(timestamp, digest)
+    }
+
+    /// Returns true if the packet (starting with an ipv4 or ipv6 header) is a
+    /// duplicate of a packet seen within the window, otherwise the packet is
+    /// remembered & false is returned.
+    pub fn is_duplicate_ip(&mut self, timestamp: Duration, data: &[u8]) -> bool {
+        let digest = self.config.digest(SlicedPacket::from_ip(data).ok(), data, &mut self.buffer);
+        self.is_duplicate_digest(timestamp, digest)
+    }
+
+    /// Returns true if the digest was seen within the window, otherwise the
+    /// digest is remembered & false is returned.
+    pub fn is_duplicate_digest(&mut self, timestamp: Duration, digest: u64) -> bool {
+        // drop digests that left the window
+        while let Some((oldest, oldest_digest)) = self.entries.front() {
+            if timestamp.saturating_sub(*oldest) > self.window {
+                self.digests.remove(oldest_digest);
+                self.entries.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if self.digests.contains(&digest) {
+            self.duplicates += 1;
+            return true;
+        }
+
+        if 0 == self.capacity {
+            return false;
+        }
+        if self.entries.len() >= self.capacity {
+            if let Some((_, oldest_digest)) = self.entries.pop_front() {
+                self.digests.remove(&oldest_digest);
+            }
+        }
+        self.entries.push_back((timestamp, digest));
+        self.digests.insert(digest);
+        false
+    }
+}
@@ -0,0 +1,96 @@
+use super::*;
+
+/// Policy deciding how the type/length field of an ethernet header gets
+/// interpreted (ether type of an Ethernet II frame or length of an IEEE 802.3
+/// frame).
+///
+/// The policy is set in a [`ParserRegistry`] (see
+/// [`ParserRegistry::set_ether_type_length_policy`]) and applied by
+/// [`SlicedPacket`] & [`PacketHeaders`] when the `*_with_registry` functions
+/// are used.
+///
+/// IEEE 802.3 defines values up to 1500 ([`EtherTypeLengthPolicy::MAX_LENGTH`])
+/// as the length of the payload & values starting from 1536
+/// ([`EtherTypeLengthPolicy::MIN_ETHER_TYPE`]) as ether types. The values in
+/// between are undefined.
+///
+/// If a value gets interpreted as length the ethernet header is still set in
+/// the `link` field (with the length in the `ether_type` field). The payload
+/// is cut to the length (removing the padding of short frames) & if it starts
+/// with an LLC/SNAP header carrying an ether type the encapsulated packet is
+/// decoded. Otherwise (e.g. for spanning tree BPDUs) the LLC header &
+/// everything following it is left in the payload. If the frame is shorter
+/// then the length all remaining bytes are used as payload.
+///
+/// By default ([`EtherTypeLengthPolicy::ForceEthernet2`]) the field is always
+/// interpreted as ether type.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{EtherTypeLengthPolicy, ParserRegistry, SlicedPacket};
+///
+/// let mut registry = ParserRegistry::new();
+/// registry.set_ether_type_length_policy(EtherTypeLengthPolicy::Ieee8023);
+///
+/// let packet = [
+///     0x01, 0x80, 0xc2, 0, 0, 0, // destination
+///     0, 0, 0, 0, 0, 0,          // source
+///     0, 7,                      // length
+///     0x42, 0x42, 0x03,          // LLC (spanning tree)
+///     0, 0, 0, 0,                // BPDU (shortened)
+///     0, 0, 0,                   // padding
+/// ];
+/// let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+/// assert_eq!(&packet[14..21], sliced.payload);
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum EtherTypeLengthPolicy {
+    /// The field is always interpreted as ether type (Ethernet II), independent
+    /// of its value. Values that are not known ether types are passed to the
+    /// registered parsers & otherwise left in the payload.
+    #[default]
+    ForceEthernet2,
+    /// Values up to 1500 are interpreted as length (IEEE 802.3) & values
+    /// starting from 1536 as ether type. Frames with a value in between
+    /// are not decoded any further (the data after the ethernet header is set
+    /// as payload).
+    Ieee8023,
+    /// Same as [`EtherTypeLengthPolicy::Ieee8023`] but values between 1501 &
+    /// 1535 are rejected with a [`ReadError::EtherTypeLengthUndefined`] error.
+    Ieee8023Strict,
+}
+
+/// Interpretation of the type/length field of an ethernet header based on a
+/// [`EtherTypeLengthPolicy`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum EtherTypeLength {
+    EtherType(u16),
+    Length(usize),
+    Undefined,
+}
+
+impl EtherTypeLengthPolicy {
+    /// Maximum value of the type/length field that IEEE 802.3 interprets as length.
+    pub const MAX_LENGTH: u16 = 1500;
+
+    /// Minimum value of the type/length field that IEEE 802.3 interprets as ether type.
+    pub const MIN_ETHER_TYPE: u16 = 0x0600;
+
+    /// Interprets the value of the type/length field of an ethernet header.
+    pub(crate) fn classify(&self, value: u16) -> Result<EtherTypeLength, ReadError> {
+        use EtherTypeLengthPolicy::*;
+        match self {
+            ForceEthernet2 => Ok(EtherTypeLength::EtherType(value)),
+            Ieee8023 | Ieee8023Strict => if value <= EtherTypeLengthPolicy::MAX_LENGTH {
+                Ok(EtherTypeLength::Length(usize::from(value)))
+            } else if value >= EtherTypeLengthPolicy::MIN_ETHER_TYPE {
+                Ok(EtherTypeLength::EtherType(value))
+            } else if Ieee8023Strict == *self {
+                Err(ReadError::EtherTypeLengthUndefined(value))
+            } else {
+                Ok(EtherTypeLength::Undefined)
+            },
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use super::*;
+
+use arrayvec::ArrayVec;
+use std::net::IpAddr;
+
+/// Flow hash algorithms as used by switch & router ASICs to select
+/// an equal-cost multi-path (ECMP) / link aggregation member.
+///
+/// All algorithms are calculated over the 5-tuple of a [`FlowKey`] serialized
+/// in the following order (all values in network byte order):
+///
+/// 1. source address (4 bytes for ipv4, 16 bytes for ipv6)
+/// 2. destination address (4 bytes for ipv4, 16 bytes for ipv6)
+/// 3. ip number (1 byte)
+/// 4. source port (2 bytes)
+/// 5. destination port (2 bytes)
+///
+/// Vendors differ in the exact fields, order & seeds they feed into the hash
+/// function. The results only match a device if it is configured to hash
+/// over the same 5-tuple layout without a seed.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{FlowKey, FlowHashAlgorithm, ip_number};
+/// use std::net::IpAddr;
+///
+/// let key = FlowKey {
+///     source_addr: IpAddr::from([192,168,1,1]),
+///     destination_addr: IpAddr::from([192,168,1,2]),
+///     ip_number: ip_number::UDP,
+///     source_port: 21,
+///     destination_port: 1234,
+/// };
+///
+/// let hash = key.flow_hash(FlowHashAlgorithm::Crc32Lo);
+/// assert!(hash <= 0xffff);
+///
+/// // index of the path the packet takes if 4 equal-cost paths are available
+/// let path = key.ecmp_index(FlowHashAlgorithm::Crc32Lo, 4);
+/// assert_eq!(hash as usize % 4, path);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum FlowHashAlgorithm {
+    /// Complete 32 bit CRC32 (IEEE 802.3 polynomial, as used by ethernet).
+    Crc32,
+    /// Lower 16 bits of the CRC32.
+    Crc32Lo,
+    /// Upper 16 bits of the CRC32.
+    Crc32Hi,
+    /// CRC16-CCITT (polynomial 0x1021, initial value 0xffff).
+    Crc16Ccitt,
+    /// All 16 bit words XORed together (an odd number of bytes is padded with 0).
+    Xor16,
+    /// All bytes XORed together.
+    Xor8,
+}
+
+impl FlowHashAlgorithm {
+    /// Calculates the hash of the given data.
+    pub fn hash_slice(self, data: &[u8]) -> u32 {
+        use FlowHashAlgorithm::*;
+        match self {
+            Crc32 => crc32(data),
+            Crc32Lo => crc32(data) & 0xffff,
+            Crc32Hi => crc32(data) >> 16,
+            Crc16Ccitt => u32::from(crc16_ccitt(data)),
+            Xor16 => u32::from(xor16(data)),
+            Xor8 => {
+                let value = xor16(data);
+                u32::from((value >> 8) ^ (value & 0xff))
+            },
+        }
+    }
+}
+
+impl FlowKey {
+    /// Maximum length of the serialized 5-tuple used as input of the flow hashes.
+    const MAX_TUPLE_LEN: usize = 16 + 16 + 1 + 2 + 2;
+
+    /// Calculates the flow hash of the 5-tuple with the given algorithm
+    /// (see [`FlowHashAlgorithm`] for the layout of the hashed data).
+    pub fn flow_hash(&self, algorithm: FlowHashAlgorithm) -> u32 {
+        let mut tuple = ArrayVec::<u8, { FlowKey::MAX_TUPLE_LEN }>::new();
+        for addr in [self.source_addr, self.destination_addr] {
+            match addr {
+                IpAddr::V4(value) => tuple.try_extend_from_slice(&value.octets()).unwrap(),
+                IpAddr::V6(value) => tuple.try_extend_from_slice(&value.octets()).unwrap(),
+            }
+        }
+        tuple.push(self.ip_number);
+        tuple.try_extend_from_slice(&self.source_port.to_be_bytes()).unwrap();
+        tuple.try_extend_from_slice(&self.destination_port.to_be_bytes()).unwrap();
+        algorithm.hash_slice(&tuple)
+    }
+
+    /// Returns the index of the path (out of `path_count` equal-cost paths) a
+    /// device using the given hash algorithm selects for the flow
+    /// (hash modulo number of paths).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path_count` is 0.
+    pub fn ecmp_index(&self, algorithm: FlowHashAlgorithm, path_count: usize) -> usize {
+        (self.flow_hash(algorithm) as usize) % path_count
+    }
+}
+
+/// Computes the CRC32 (IEEE 802.3, reflected polynomial 0xedb88320).
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if 0 != crc & 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Computes the CRC16-CCITT (polynomial 0x1021, initial value 0xffff, not reflected).
+fn crc16_ccitt(data: &[u8]) -> u16 {
+    let mut crc = 0xffffu16;
+    for byte in data {
+        crc ^= u16::from(*byte) << 8;
+        for _ in 0..8 {
+            crc = if 0 != crc & 0x8000 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// XORs all 16 bit words (big endian) together.
+fn xor16(data: &[u8]) -> u16 {
+    data.chunks(2).fold(0, |acc, chunk| {
+        acc ^ u16::from_be_bytes([chunk[0], *chunk.get(1).unwrap_or(&0)])
+    })
+}
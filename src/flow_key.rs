@@ -0,0 +1,129 @@
+use super::*;
+
+use std::net::IpAddr;
+
+/// Five tuple (addresses, ip number & ports) identifying the flow a packet belongs to.
+///
+/// For transport protocols without ports (e.g. ICMP) both port fields are set to 0.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::{SlicedPacket, PacketBuilder};
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6],     //source mac
+/// #               [7,8,9,10,11,12]) //destionation mac
+/// #    .ipv4([192,168,1,1], //source ip
+/// #          [192,168,1,2], //desitionation ip
+/// #          20)            //time to life
+/// #    .udp(21,    //source port
+/// #         1234); //desitnation port
+/// # let payload = [1,2,3,4,5,6,7,8];
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(payload.len()));
+/// # builder.write(&mut packet, &payload).unwrap();
+/// use etherparse::{FlowKey, ip_number};
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// let key = FlowKey::from_sliced(&sliced).unwrap();
+/// assert_eq!(ip_number::UDP, key.ip_number);
+/// assert_eq!(21, key.source_port);
+///
+/// // answers belong to the same bidirectional flow
+/// assert_eq!(key.bidirectional(), key.reversed().bidirectional());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct FlowKey {
+    /// Source ip address.
+    pub source_addr: IpAddr,
+    /// Destination ip address.
+    pub destination_addr: IpAddr,
+    /// Ip number of the transport protocol (see [`ip_number`] for known values).
+    pub ip_number: u8,
+    /// Source port (0 if the transport protocol has no ports).
+    pub source_port: u16,
+    /// Destination port (0 if the transport protocol has no ports).
+    pub destination_port: u16,
+}
+
+impl FlowKey {
+    /// Determines the flow key of a sliced packet.
+    ///
+    /// Returns `None` if the packet has no ip header or if the transport
+    /// protocol could not be determined (e.g. for fragmented packets).
+    pub fn from_sliced(packet: &SlicedPacket) -> Option<FlowKey> {
+        use TransportSlice::*;
+        let ip = packet.ip.as_ref()?;
+        let (ip_number, source_port, destination_port) = match packet.transport.as_ref()? {
+            Icmpv4(_) => (ip_number::ICMP, 0, 0),
+            Icmpv6(_) => (ip_number::IPV6_ICMP, 0, 0),
+            Igmp(_) => (ip_number::IGMP, 0, 0),
+            Udp(udp) => (ip_number::UDP, udp.source_port(), udp.destination_port()),
+            Tcp(tcp) => (ip_number::TCP, tcp.source_port(), tcp.destination_port()),
+            Sctp(sctp) => (ip_number::SCTP, sctp.source_port(), sctp.destination_port()),
+            UdpLite(udp) => (ip_number::UDP_LITE, udp.source_port(), udp.destination_port()),
+            Dccp(dccp) => (ip_number::DCCP, dccp.source_port(), dccp.destination_port()),
+            Unknown(value) => (*value, 0, 0),
+        };
+        Some(FlowKey {
+            source_addr: ip.source_addr(),
+            destination_addr: ip.destination_addr(),
+            ip_number,
+            source_port,
+            destination_port,
+        })
+    }
+
+    /// Determines the flow key of a packet that is being built from its ip &
+    /// transport headers.
+    ///
+    /// If no transport header is given the last next header value of the ip
+    /// header (& its extension headers) is used as ip number & the ports are
+    /// set to 0. An error is returned if the next header values of the
+    /// extension headers are inconsistent.
+    pub fn from_headers(ip: &IpHeader, transport: Option<&TransportHeader>) -> Result<FlowKey, ValueError> {
+        use TransportHeader::*;
+        let (ip_number, source_port, destination_port) = match transport {
+            Some(Icmpv4(_)) => (ip_number::ICMP, 0, 0),
+            Some(Icmpv6(_)) => (ip_number::IPV6_ICMP, 0, 0),
+            Some(Udp(udp)) => (ip_number::UDP, udp.source_port, udp.destination_port),
+            Some(Tcp(tcp)) => (ip_number::TCP, tcp.source_port, tcp.destination_port),
+            Some(UdpLite(udp)) => (ip_number::UDP_LITE, udp.source_port, udp.destination_port),
+            Some(Dccp(dccp)) => (ip_number::DCCP, dccp.source_port, dccp.destination_port),
+            Some(Sctp(sctp)) => (ip_number::SCTP, sctp.source_port, sctp.destination_port),
+            None => (ip.next_header()?, 0, 0),
+        };
+        let (source_addr, destination_addr) = match ip {
+            IpHeader::Version4(header, _) => (IpAddr::from(header.source), IpAddr::from(header.destination)),
+            IpHeader::Version6(header, _) => (IpAddr::from(header.source), IpAddr::from(header.destination)),
+        };
+        Ok(FlowKey {
+            source_addr,
+            destination_addr,
+            ip_number,
+            source_port,
+            destination_port,
+        })
+    }
+
+    /// Returns the key of the packets flowing in the opposite direction
+    /// (source & destination swapped).
+    pub fn reversed(&self) -> FlowKey {
+        FlowKey {
+            source_addr: self.destination_addr,
+            destination_addr: self.source_addr,
+            ip_number: self.ip_number,
+            source_port: self.destination_port,
+            destination_port: self.source_port,
+        }
+    }
+
+    /// Returns a key that is identical for both directions of a flow
+    /// (the endpoint with the smaller address & port is used as source).
+    pub fn bidirectional(&self) -> FlowKey {
+        if (self.destination_addr, self.destination_port) < (self.source_addr, self.source_port) {
+            self.reversed()
+        } else {
+            *self
+        }
+    }
+}
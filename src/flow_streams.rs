@@ -0,0 +1,230 @@
+use super::*;
+
+use std::collections::HashMap;
+
+/// Packets grouped into per flow streams (in arrival order).
+///
+/// Every stream contains the packets of one flow in the order they were
+/// added & the streams themselves are ordered by the arrival of their first
+/// packet. This is the step between reading a capture & feeding the packets
+/// of a connection to a per flow analysis (e.g. [`TcpFlowState`]).
+///
+/// If the streams are bidirectional, the packets of both directions of a flow
+/// are placed in the same stream & the stream key is the key of its first
+/// packet (usually the direction of the connection initiator). Packets for
+/// which no [`FlowKey`] can be determined (e.g. non ip packets or fragments)
+/// are collected separately (see [`FlowStreams::unclassified`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{FlowStreams, PacketBuilder, SlicedPacket};
+///
+/// let packet = |source: [u8;4], destination: [u8;4], source_port: u16, destination_port: u16| {
+///     let mut result = Vec::new();
+///     PacketBuilder::ipv4(source, destination, 20)
+///         .udp(source_port, destination_port)
+///         .write(&mut result, &[1,2,3,4])
+///         .unwrap();
+///     result
+/// };
+/// let client = [192,168,1,1];
+/// let server = [192,168,1,2];
+/// let packets = [
+///     packet(client, server, 1000, 53),
+///     packet(client, server, 1001, 53),
+///     packet(server, client, 53, 1000),
+/// ];
+///
+/// let streams = FlowStreams::from_sliced(
+///     packets.iter().filter_map(|p| SlicedPacket::from_ip(p).ok()),
+///     true // bidirectional
+/// );
+/// assert_eq!(2, streams.len());
+///
+/// let (key, stream) = streams.into_iter().next().unwrap();
+/// assert_eq!(1000, key.source_port);
+/// // request & answer
+/// assert_eq!(2, stream.count());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlowStreams<T> {
+    bidirectional: bool,
+    /// Key & packets of the streams (ordered by the first packet).
+    streams: Vec<(FlowKey, Vec<T>)>,
+    /// Indices of the streams in `streams` (by the normalized keys).
+    index: HashMap<FlowKey, usize>,
+    unclassified: Vec<T>,
+}
+
+impl<T> FlowStreams<T> {
+    /// Creates an empty set of streams (if `bidirectional` is true both
+    /// directions of a flow are grouped into the same stream).
+    pub fn new(bidirectional: bool) -> FlowStreams<T> {
+        FlowStreams {
+            bidirectional,
+            streams: Vec::new(),
+            index: HashMap::new(),
+            unclassified: Vec::new(),
+        }
+    }
+
+    /// Groups the given items using a function determining the flow key of
+    /// an item (`None` if the item belongs to no flow).
+    pub fn group_by<I, F>(items: I, bidirectional: bool, mut key: F) -> FlowStreams<T>
+    where
+        I: IntoIterator<Item = T>,
+        F: FnMut(&T) -> Option<FlowKey>,
+    {
+        let mut result = FlowStreams::new(bidirectional);
+        for item in items {
+            let flow_key = key(&item);
+            result.push_with_key(flow_key, item);
+        }
+        result
+    }
+
+    /// Returns true if both directions of a flow are grouped into the same stream.
+    #[inline]
+    pub fn is_bidirectional(&self) -> bool {
+        self.bidirectional
+    }
+
+    /// Number of streams.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Returns true if there are no streams.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Appends an item to the stream of the given flow (or to the
+    /// unclassified items if the key is `None`).
+    pub fn push_with_key(&mut self, key: Option<FlowKey>, item: T) {
+        let key = match key {
+            Some(key) => key,
+            None => {
+                self.unclassified.push(item);
+                return;
+            }
+        };
+        let normalized = self.normalize(&key);
+        match self.index.get(&normalized) {
+            Some(index) => self.streams[*index].1.push(item),
+            None => {
+                self.index.insert(normalized, self.streams.len());
+                self.streams.push((key, vec![item]));
+            }
+        }
+    }
+
+    /// Returns the stream the given flow key belongs to.
+    pub fn get(&self, key: &FlowKey) -> Option<&[T]> {
+        self.index.get(&self.normalize(key))
+            .map(|index| &self.streams[*index].1[..])
+    }
+
+    /// Items for which no flow key could be determined (in arrival order).
+    #[inline]
+    pub fn unclassified(&self) -> &[T] {
+        &self.unclassified
+    }
+
+    /// Iterator over the keys & packets of the streams (ordered by the
+    /// arrival of the first packet of each stream).
+    pub fn iter(&self) -> FlowStreamsIter<'_, T> {
+        FlowStreamsIter {
+            streams: self.streams.iter(),
+        }
+    }
+
+    /// Key under which the stream of a flow is referenced.
+    fn normalize(&self, key: &FlowKey) -> FlowKey {
+        if self.bidirectional {
+            key.bidirectional()
+        } else {
+            *key
+        }
+    }
+}
+
+impl<'a> FlowStreams<SlicedPacket<'a>> {
+    /// Groups sliced packets into streams based on their [`FlowKey`].
+    pub fn from_sliced<I>(packets: I, bidirectional: bool) -> FlowStreams<SlicedPacket<'a>>
+    where
+        I: IntoIterator<Item = SlicedPacket<'a>>,
+    {
+        FlowStreams::group_by(packets, bidirectional, FlowKey::from_sliced)
+    }
+
+    /// Appends a sliced packet to the stream of its flow.
+    pub fn push(&mut self, packet: SlicedPacket<'a>) {
+        let key = FlowKey::from_sliced(&packet);
+        self.push_with_key(key, packet);
+    }
+}
+
+impl<'a, T> IntoIterator for &'a FlowStreams<T> {
+    type Item = (&'a FlowKey, &'a [T]);
+    type IntoIter = FlowStreamsIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for FlowStreams<T> {
+    type Item = (FlowKey, std::vec::IntoIter<T>);
+    type IntoIter = FlowStreamsIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        FlowStreamsIntoIter {
+            streams: self.streams.into_iter(),
+        }
+    }
+}
+
+/// Iterator over the streams of [`FlowStreams`] returning the key & an
+/// iterator over the packets of each stream.
+#[derive(Clone, Debug)]
+pub struct FlowStreamsIntoIter<T> {
+    streams: std::vec::IntoIter<(FlowKey, Vec<T>)>,
+}
+
+impl<T> Iterator for FlowStreamsIntoIter<T> {
+    type Item = (FlowKey, std::vec::IntoIter<T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.streams.next().map(|(key, items)| (key, items.into_iter()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.streams.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for FlowStreamsIntoIter<T> {}
+
+/// Iterator over the keys & packets of the streams of [`FlowStreams`] (see [`FlowStreams::iter`]).
+#[derive(Clone, Debug)]
+pub struct FlowStreamsIter<'a, T> {
+    streams: std::slice::Iter<'a, (FlowKey, Vec<T>)>,
+}
+
+impl<'a, T> Iterator for FlowStreamsIter<'a, T> {
+    type Item = (&'a FlowKey, &'a [T]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.streams.next().map(|(key, items)| (key, &items[..]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.streams.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for FlowStreamsIter<'a, T> {}
@@ -0,0 +1,201 @@
+//! Parser for textual hex dumps of packets (e.g. `tcpdump -xx` or the
+//! Wireshark "Copy as Hex + ASCII Dump" output).
+//!
+//! This makes it easy to turn capture snippets pasted into bug reports
+//! back into raw packet bytes that can be sliced or decoded.
+//!
+//! Every line of a dump starts with the hex offset of its first byte
+//! (optionally prefixed with `0x` and followed by a `:`), followed by the
+//! bytes in groups of 2 or 4 hex digits. An ASCII column at the end of a line
+//! is ignored. Lines that do not start with an offset (e.g. the tcpdump packet
+//! summary lines) are skipped. A line with the offset 0 starts a new packet.
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::{hex_dump, SlicedPacket};
+//!
+//! // output of "tcpdump -xx"
+//! let text = "
+//! 12:00:00.000000 IP 192.168.1.1.21 > 192.168.1.2.1234: UDP, length 4
+//! \t0x0000:  0708 090a 0b0c 0102 0304 0506 0800 4500
+//! \t0x0010:  0020 0000 4000 1411 e379 c0a8 0101 c0a8
+//! \t0x0020:  0102 0015 04d2 000c 7395 0102 0304
+//! ";
+//!
+//! let packet = hex_dump::parse(text).unwrap();
+//! assert_eq!(46, packet.len());
+//!
+//! let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+//! assert_eq!(&[1,2,3,4], sliced.payload);
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+/// Errors that can occur while parsing a hex dump.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HexDumpError {
+    /// The text contains no hex dump lines.
+    NoData,
+    /// The text contains more than one packet (only returned by [`parse`]).
+    /// The value is the number of packets found.
+    MultiplePackets(usize),
+    /// The offset of a line does not match the number of bytes
+    /// read before the line (e.g. lines are missing or the data
+    /// of a line is incomplete).
+    OffsetMismatch{
+        /// Line number (starting at 1) of the line with the unexpected offset.
+        line: usize,
+        /// Offset expected based on the bytes read so far.
+        expected: usize,
+        /// Offset present in the line.
+        actual: usize,
+    },
+}
+
+impl Error for HexDumpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for HexDumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use HexDumpError::*;
+        match self {
+            NoData => {
+                write!(f, "HexDumpError: No hex dump lines found in the text.")
+            },
+            MultiplePackets(count) => {
+                write!(f, "HexDumpError: Expected one packet but the text contains {} packets.", count)
+            },
+            OffsetMismatch{ line, expected, actual } => {
+                write!(f, "HexDumpError: Unexpected offset {:#06x} in line {} (expected offset {:#06x} based on the bytes read before).", actual, line, expected)
+            },
+        }
+    }
+}
+
+/// Parses a hex dump containing exactly one packet.
+pub fn parse(text: &str) -> Result<Vec<u8>, HexDumpError> {
+    let mut packets = parse_packets(text)?;
+    if packets.len() > 1 {
+        Err(HexDumpError::MultiplePackets(packets.len()))
+    } else {
+        // parse_packets returns at least one packet
+        Ok(packets.remove(0))
+    }
+}
+
+/// Parses a hex dump containing one or more packets (each packet starting at offset 0).
+pub fn parse_packets(text: &str) -> Result<Vec<Vec<u8>>, HexDumpError> {
+    let mut packets = Vec::new();
+    let mut current: Option<PacketState> = None;
+
+    for (index, line) in text.lines().enumerate() {
+        let (offset, bytes) = match parse_line(line) {
+            Some(value) => value,
+            None => continue,
+        };
+
+        let state = match current.as_mut() {
+            Some(state) if 0 != offset => state,
+            _ => {
+                // start of a new packet
+                if let Some(state) = current.take() {
+                    packets.push(state.finish());
+                }
+                if 0 != offset {
+                    return Err(HexDumpError::OffsetMismatch{ line: index + 1, expected: 0, actual: offset });
+                }
+                current.insert(PacketState{ data: Vec::new(), line_start: 0, line_width: None })
+            }
+        };
+
+        if 0 != offset {
+            // the previous line has to contain at least the bytes up to the offset
+            // (everything after it is treated as part of the ascii column)
+            if offset <= state.line_start || state.data.len() < offset {
+                return Err(HexDumpError::OffsetMismatch{ line: index + 1, expected: state.data.len(), actual: offset });
+            }
+            let width = offset - state.line_start;
+            state.line_width = Some(std::cmp::max(width, state.line_width.unwrap_or(0)));
+            state.data.truncate(offset);
+            state.line_start = offset;
+        }
+        state.data.extend_from_slice(&bytes);
+    }
+
+    if let Some(state) = current.take() {
+        packets.push(state.finish());
+    }
+    if packets.is_empty() {
+        Err(HexDumpError::NoData)
+    } else {
+        Ok(packets)
+    }
+}
+
+/// Data of the packet that is currently parsed.
+struct PacketState {
+    data: Vec<u8>,
+    /// Offset of the last line.
+    line_start: usize,
+    /// Maximum number of bytes per line seen so far.
+    line_width: Option<usize>,
+}
+
+impl PacketState {
+    fn finish(mut self) -> Vec<u8> {
+        // the last line can not contain more bytes than the lines before
+        if let Some(width) = self.line_width {
+            self.data.truncate(self.line_start + width);
+        }
+        self.data
+    }
+}
+
+/// Parses the offset & bytes of a line (`None` if the line does not start with an offset).
+fn parse_line(line: &str) -> Option<(usize, Vec<u8>)> {
+    let line = line.trim_start();
+    let offset_end = line.find(char::is_whitespace).unwrap_or(line.len());
+    let offset = {
+        let value = &line[..offset_end];
+        let value = value.strip_suffix(':').unwrap_or(value);
+        let value = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+        if value.is_empty() || false == value.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        usize::from_str_radix(value, 16).ok()?
+    };
+
+    let mut bytes = Vec::new();
+    let mut rest = line[offset_end..].trim_start();
+    loop {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let group = &rest[..end];
+        if false == is_hex_group(group) {
+            break;
+        }
+        for i in (0..group.len()).step_by(2) {
+            // is_hex_group guarantees valid hex digits
+            bytes.push(u8::from_str_radix(&group[i..i + 2], 16).unwrap());
+        }
+
+        // a gap of 2 or more whitespaces separates the ascii column
+        // (except the gap wireshark inserts after the first 8 bytes)
+        let remaining = &rest[end..];
+        let trimmed = remaining.trim_start();
+        if remaining.len() - trimmed.len() >= 2 && 8 != bytes.len() {
+            break;
+        }
+        rest = trimmed;
+    }
+    Some((offset, bytes))
+}
+
+/// Returns true if the value is a group of 2 or 4 hex digits.
+fn is_hex_group(value: &str) -> bool {
+    (2 == value.len() || 4 == value.len()) && value.bytes().all(|c| c.is_ascii_hexdigit())
+}
@@ -0,0 +1,470 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::slice::from_raw_parts;
+
+/// Encapsulated Remote Switch Port Analyzer header (ERSPAN type II & III).
+///
+/// ERSPAN headers are carried in GRE packets with the protocol type
+/// `ether_type::ERSPAN_TYPE_2` (0x88BE) or `ether_type::ERSPAN_TYPE_3`
+/// (0x22EB) and are followed by the mirrored ethernet II frame. They are
+/// decoded by [`SlicedPacket`] & [`PacketHeaders`] (see the `erspan` &
+/// `inner` fields).
+///
+/// ERSPAN type I packets (protocol type 0x88BE in a GRE header without a
+/// sequence number) don't contain an ERSPAN header, the mirrored frame
+/// directly follows the GRE header.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ErspanHeader, ErspanType, GreHeader, PacketBuilder, SlicedPacket};
+///
+/// // mirrored frame
+/// let mut mirrored = Vec::new();
+/// PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([10,0,0,1], [10,0,0,2], 20)
+///     .udp(1234, 5678)
+///     .write(&mut mirrored, &[1,2,3,4])
+///     .unwrap();
+///
+/// // gre & erspan header
+/// let erspan = ErspanHeader {
+///     vlan: 100,
+///     cos: 0,
+///     encapsulation_type: 0,
+///     truncated: false,
+///     session_id: 42,
+///     erspan_type: ErspanType::Type2{ index: 0 },
+/// };
+/// let gre = GreHeader {
+///     sequence_number: Some(1),
+///     ..GreHeader::new(erspan.protocol_type())
+/// };
+/// let mut gre_payload = gre.to_bytes().to_vec();
+/// gre_payload.extend_from_slice(&erspan.to_bytes().unwrap());
+/// gre_payload.extend_from_slice(&mirrored);
+///
+/// // outer packet
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .write(&mut packet, etherparse::ip_number::GRE, &gre_payload)
+///     .unwrap();
+///
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// let erspan = sliced.erspan.unwrap();
+/// assert_eq!(42, erspan.session_id());
+/// assert_eq!(100, erspan.vlan());
+/// assert_eq!(&[1,2,3,4], sliced.inner.unwrap().payload);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ErspanHeader {
+    /// Vlan of the mirrored frame (12 bits).
+    pub vlan: u16,
+    /// Class of service of the mirrored frame (3 bits).
+    pub cos: u8,
+    /// Encapsulation type of the mirrored frame (2 bits, "En" field of type
+    /// II headers & "BSO" field of type III headers).
+    pub encapsulation_type: u8,
+    /// Set if the mirrored frame was truncated (T bit).
+    pub truncated: bool,
+    /// Identifier of the ERSPAN session (10 bits).
+    pub session_id: u16,
+    /// Type specific fields.
+    pub erspan_type: ErspanType,
+}
+
+/// Type specific fields of an ERSPAN header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ErspanType {
+    /// ERSPAN type II (version 1).
+    Type2 {
+        /// Index of the port the frame was mirrored on (20 bits).
+        index: u32,
+    },
+    /// ERSPAN type III (version 2).
+    Type3 {
+        /// Timestamp (granularity depends on the `granularity` field).
+        timestamp: u32,
+        /// Security group tag.
+        security_group_tag: u16,
+        /// P bit (set if the mirrored frame is an ethernet protocol frame).
+        p_flag: bool,
+        /// Type of the mirrored frame (5 bits, see [`ErspanHeader::FRAME_TYPE_ETHERNET`]).
+        frame_type: u8,
+        /// Identifier of the hardware that mirrored the frame (6 bits).
+        hardware_id: u8,
+        /// Direction of the mirrored traffic (D bit, `false` for ingress &
+        /// `true` for egress traffic).
+        egress: bool,
+        /// Granularity of the timestamp (2 bits).
+        granularity: u8,
+        /// Optional platform specific sub-header (O bit).
+        platform_specific: Option<[u8;8]>,
+    },
+}
+
+impl ErspanHeader {
+    /// Length of an ERSPAN type II header.
+    pub const TYPE_2_LEN: usize = 8;
+
+    /// Length of an ERSPAN type III header without the platform specific
+    /// sub-header.
+    pub const TYPE_3_LEN: usize = 12;
+
+    /// Length of the optional platform specific sub-header of type III headers.
+    pub const PLATFORM_SPECIFIC_LEN: usize = 8;
+
+    /// Maximum length of an ERSPAN header.
+    pub const MAX_LEN: usize = ErspanHeader::TYPE_3_LEN + ErspanHeader::PLATFORM_SPECIFIC_LEN;
+
+    /// Value of the version field of type II headers.
+    pub const VERSION_TYPE_2: u8 = 1;
+
+    /// Value of the version field of type III headers.
+    pub const VERSION_TYPE_3: u8 = 2;
+
+    /// Frame type of mirrored ethernet frames (type III).
+    pub const FRAME_TYPE_ETHERNET: u8 = 0;
+
+    /// Maximum value of the vlan field.
+    pub const MAX_VLAN: u16 = 0xfff;
+
+    /// Maximum value of the cos field.
+    pub const MAX_COS: u8 = 0b111;
+
+    /// Maximum value of the encapsulation type field.
+    pub const MAX_ENCAPSULATION_TYPE: u8 = 0b11;
+
+    /// Maximum value of the session id field.
+    pub const MAX_SESSION_ID: u16 = 0x3ff;
+
+    /// Maximum value of the type II index field.
+    pub const MAX_INDEX: u32 = 0xf_ffff;
+
+    /// Maximum value of the type III frame type field.
+    pub const MAX_FRAME_TYPE: u8 = 0x1f;
+
+    /// Maximum value of the type III hardware id field.
+    pub const MAX_HARDWARE_ID: u8 = 0x3f;
+
+    /// Maximum value of the type III granularity field.
+    pub const MAX_GRANULARITY: u8 = 0b11;
+
+    /// Read an ERSPAN header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(ErspanHeader, &[u8]), ReadError> {
+        let header = ErspanHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Reads an ERSPAN header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<ErspanHeader, ReadError> {
+        let mut buffer = [0u8; ErspanHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..ErspanHeader::TYPE_2_LEN])?;
+        let len = match buffer[0] >> 4 {
+            ErspanHeader::VERSION_TYPE_2 => ErspanHeader::TYPE_2_LEN,
+            ErspanHeader::VERSION_TYPE_3 => {
+                reader.read_exact(&mut buffer[ErspanHeader::TYPE_2_LEN..ErspanHeader::TYPE_3_LEN])?;
+                if 0 != buffer[11] & 1 {
+                    reader.read_exact(&mut buffer[ErspanHeader::TYPE_3_LEN..])?;
+                    ErspanHeader::MAX_LEN
+                } else {
+                    ErspanHeader::TYPE_3_LEN
+                }
+            },
+            version => return Err(ReadError::ErspanUnsupportedVersion(version)),
+        };
+        Ok(ErspanHeaderSlice::from_slice(&buffer[..len])?.to_header())
+    }
+
+    /// Writes the ERSPAN header to the current position.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        match &self.erspan_type {
+            ErspanType::Type2{ .. } => ErspanHeader::TYPE_2_LEN,
+            ErspanType::Type3{ platform_specific: None, .. } => ErspanHeader::TYPE_3_LEN,
+            ErspanType::Type3{ platform_specific: Some(_), .. } => ErspanHeader::MAX_LEN,
+        }
+    }
+
+    /// GRE protocol type identifying the header type.
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        match &self.erspan_type {
+            ErspanType::Type2{ .. } => ether_type::ERSPAN_TYPE_2,
+            ErspanType::Type3{ .. } => ether_type::ERSPAN_TYPE_3,
+        }
+    }
+
+    /// Returns the serialized form of the header or an value error in case
+    /// the header contains values that are outside of range.
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { ErspanHeader::MAX_LEN }>, ValueError> {
+        max_check_u16(self.vlan, ErspanHeader::MAX_VLAN, ErrorField::ErspanVlan)?;
+        max_check_u8(self.cos, ErspanHeader::MAX_COS, ErrorField::ErspanCos)?;
+        max_check_u8(self.encapsulation_type, ErspanHeader::MAX_ENCAPSULATION_TYPE, ErrorField::ErspanEncapsulationType)?;
+        max_check_u16(self.session_id, ErspanHeader::MAX_SESSION_ID, ErrorField::ErspanSessionId)?;
+
+        let version = match &self.erspan_type {
+            ErspanType::Type2{ .. } => ErspanHeader::VERSION_TYPE_2,
+            ErspanType::Type3{ .. } => ErspanHeader::VERSION_TYPE_3,
+        };
+        let vlan = self.vlan.to_be_bytes();
+        let session_id = self.session_id.to_be_bytes();
+
+        let mut result = ArrayVec::new();
+        result.try_extend_from_slice(&[
+            (version << 4) | vlan[0],
+            vlan[1],
+            (self.cos << 5) | (self.encapsulation_type << 3) | if self.truncated { 0b100 } else { 0 } | session_id[0],
+            session_id[1],
+        ]).unwrap();
+
+        match &self.erspan_type {
+            ErspanType::Type2{ index } => {
+                max_check_u32(*index, ErspanHeader::MAX_INDEX, ErrorField::ErspanIndex)?;
+                result.try_extend_from_slice(&index.to_be_bytes()).unwrap();
+            },
+            ErspanType::Type3{
+                timestamp,
+                security_group_tag,
+                p_flag,
+                frame_type,
+                hardware_id,
+                egress,
+                granularity,
+                platform_specific,
+            } => {
+                max_check_u8(*frame_type, ErspanHeader::MAX_FRAME_TYPE, ErrorField::ErspanFrameType)?;
+                max_check_u8(*hardware_id, ErspanHeader::MAX_HARDWARE_ID, ErrorField::ErspanHardwareId)?;
+                max_check_u8(*granularity, ErspanHeader::MAX_GRANULARITY, ErrorField::ErspanGranularity)?;
+
+                result.try_extend_from_slice(&timestamp.to_be_bytes()).unwrap();
+                result.try_extend_from_slice(&security_group_tag.to_be_bytes()).unwrap();
+                result.try_extend_from_slice(&[
+                    if *p_flag { 0x80 } else { 0 } | (frame_type << 2) | (hardware_id >> 4),
+                    ((hardware_id & 0xf) << 4) |
+                    if *egress { 0b1000 } else { 0 } |
+                    (granularity << 1) |
+                    if platform_specific.is_some() { 1 } else { 0 },
+                ]).unwrap();
+                if let Some(platform_specific) = platform_specific {
+                    result.try_extend_from_slice(platform_specific).unwrap();
+                }
+            },
+        }
+        Ok(result)
+    }
+}
+
+/// Slice containing an ERSPAN type II or III header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ErspanHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> ErspanHeaderSlice<'a> {
+    /// Creates an ERSPAN header slice from a slice (the type of the header
+    /// is determined by the version field).
+    pub fn from_slice(slice: &'a [u8]) -> Result<ErspanHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < ErspanHeader::TYPE_2_LEN {
+            return Err(UnexpectedEndOfSlice(ErspanHeader::TYPE_2_LEN));
+        }
+        let len = match slice[0] >> 4 {
+            ErspanHeader::VERSION_TYPE_2 => ErspanHeader::TYPE_2_LEN,
+            ErspanHeader::VERSION_TYPE_3 => {
+                if slice.len() < ErspanHeader::TYPE_3_LEN {
+                    return Err(UnexpectedEndOfSlice(ErspanHeader::TYPE_3_LEN));
+                }
+                if 0 != slice[11] & 1 {
+                    ErspanHeader::MAX_LEN
+                } else {
+                    ErspanHeader::TYPE_3_LEN
+                }
+            },
+            version => return Err(ErspanUnsupportedVersion(version)),
+        };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        Ok(ErspanHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this
+            // code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Returns the slice containing the ERSPAN header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the version field (1 for type II & 2 for type III headers).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 4
+    }
+
+    /// Read the vlan of the mirrored frame.
+    #[inline]
+    pub fn vlan(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0] & 0xf, self.slice[1]])
+    }
+
+    /// Read the class of service of the mirrored frame.
+    #[inline]
+    pub fn cos(&self) -> u8 {
+        self.slice[2] >> 5
+    }
+
+    /// Read the encapsulation type ("En" field of type II & "BSO" field of
+    /// type III headers).
+    #[inline]
+    pub fn encapsulation_type(&self) -> u8 {
+        (self.slice[2] >> 3) & 0b11
+    }
+
+    /// Returns true if the mirrored frame was truncated (T bit).
+    #[inline]
+    pub fn truncated(&self) -> bool {
+        0 != self.slice[2] & 0b100
+    }
+
+    /// Read the identifier of the ERSPAN session.
+    #[inline]
+    pub fn session_id(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2] & 0b11, self.slice[3]])
+    }
+
+    /// Returns true if the header is a type III header.
+    #[inline]
+    fn is_type_3(&self) -> bool {
+        ErspanHeader::VERSION_TYPE_3 == self.version()
+    }
+
+    /// Read the port index of type II headers.
+    #[inline]
+    pub fn index(&self) -> Option<u32> {
+        if self.is_type_3() {
+            None
+        } else {
+            Some(u32::from_be_bytes([0, self.slice[5] & 0xf, self.slice[6], self.slice[7]]))
+        }
+    }
+
+    /// Read the timestamp of type III headers.
+    #[inline]
+    pub fn timestamp(&self) -> Option<u32> {
+        if self.is_type_3() {
+            Some(u32::from_be_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]]))
+        } else {
+            None
+        }
+    }
+
+    /// Read the security group tag of type III headers.
+    #[inline]
+    pub fn security_group_tag(&self) -> Option<u16> {
+        if self.is_type_3() {
+            Some(u16::from_be_bytes([self.slice[8], self.slice[9]]))
+        } else {
+            None
+        }
+    }
+
+    /// Read the frame type of type III headers.
+    #[inline]
+    pub fn frame_type(&self) -> Option<u8> {
+        if self.is_type_3() {
+            Some((self.slice[10] >> 2) & 0x1f)
+        } else {
+            None
+        }
+    }
+
+    /// Read the hardware id of type III headers.
+    #[inline]
+    pub fn hardware_id(&self) -> Option<u8> {
+        if self.is_type_3() {
+            Some(((self.slice[10] & 0b11) << 4) | (self.slice[11] >> 4))
+        } else {
+            None
+        }
+    }
+
+    /// Read the direction bit of type III headers (`false` for ingress &
+    /// `true` for egress traffic).
+    #[inline]
+    pub fn egress(&self) -> Option<bool> {
+        if self.is_type_3() {
+            Some(0 != self.slice[11] & 0b1000)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the platform specific sub-header of type III headers (if present).
+    #[inline]
+    pub fn platform_specific(&self) -> Option<&'a [u8]> {
+        if ErspanHeader::MAX_LEN == self.slice.len() {
+            Some(&self.slice[ErspanHeader::TYPE_3_LEN..])
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the header is followed by a mirrored ethernet II
+    /// frame (always true for type II headers).
+    #[inline]
+    pub fn is_ethernet_frame(&self) -> bool {
+        match self.frame_type() {
+            Some(frame_type) => ErspanHeader::FRAME_TYPE_ETHERNET == frame_type,
+            None => true,
+        }
+    }
+
+    /// Decode all the fields and copy the results to a [`ErspanHeader`] struct.
+    pub fn to_header(&self) -> ErspanHeader {
+        let s = self.slice;
+        ErspanHeader {
+            vlan: self.vlan(),
+            cos: self.cos(),
+            encapsulation_type: self.encapsulation_type(),
+            truncated: self.truncated(),
+            session_id: self.session_id(),
+            erspan_type: if self.is_type_3() {
+                ErspanType::Type3 {
+                    timestamp: u32::from_be_bytes([s[4], s[5], s[6], s[7]]),
+                    security_group_tag: u16::from_be_bytes([s[8], s[9]]),
+                    p_flag: 0 != s[10] & 0x80,
+                    frame_type: (s[10] >> 2) & 0x1f,
+                    hardware_id: ((s[10] & 0b11) << 4) | (s[11] >> 4),
+                    egress: 0 != s[11] & 0b1000,
+                    granularity: (s[11] >> 1) & 0b11,
+                    platform_specific: self.platform_specific().map(|value| {
+                        let mut result = [0u8;8];
+                        result.copy_from_slice(value);
+                        result
+                    }),
+                }
+            } else {
+                ErspanType::Type2 {
+                    index: u32::from_be_bytes([0, s[5] & 0xf, s[6], s[7]]),
+                }
+            },
+        }
+    }
+}
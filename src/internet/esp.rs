@@ -0,0 +1,121 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Encapsulating security payload header (ip number 50, RFC 4303).
+///
+/// Only the unencrypted security parameters index (SPI) & sequence number
+/// are decoded. The encrypted data following the header (including the
+/// padding, the next header field & the integrity check value) is treated as
+/// opaque payload, so the `payload` of a [`SlicedPacket`] or [`PacketHeaders`]
+/// with an ESP header starts directly after the sequence number.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ip_number, EspHeader, PacketHeaders, PacketBuilder};
+///
+/// let esp = EspHeader{ spi: 0x1234, sequence_number: 1 };
+/// let mut payload = esp.to_bytes().to_vec();
+/// payload.extend_from_slice(&[0xab;16]); // encrypted data
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .write(&mut packet, ip_number::ENCAP_SEC, &payload)
+///     .unwrap();
+///
+/// let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+/// assert_eq!(Some(esp), decoded.esp);
+/// assert_eq!(&[0xab;16], decoded.payload);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EspHeader {
+    /// Security parameters index identifying the security association.
+    pub spi: u32,
+    /// Sequence number (anti-replay counter).
+    pub sequence_number: u32,
+}
+
+impl SerializedSize for EspHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl EspHeader {
+    /// Read an ESP header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(EspHeader, &[u8]), ReadError> {
+        Ok((
+            EspHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[EspHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Returns the serialized form of the header.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;8] {
+        let spi = self.spi.to_be_bytes();
+        let sequence_number = self.sequence_number.to_be_bytes();
+        [
+            spi[0], spi[1], spi[2], spi[3],
+            sequence_number[0], sequence_number[1], sequence_number[2], sequence_number[3],
+        ]
+    }
+
+    /// Writes the header to the given writer.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+/// Slice containing an ESP header (security parameters index & sequence number).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EspHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> EspHeaderSlice<'a> {
+    /// Creates an ESP header slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<EspHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < EspHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(EspHeader::SERIALIZED_SIZE));
+        }
+        Ok(EspHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // EspHeader::SERIALIZED_SIZE (8) before this code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), EspHeader::SERIALIZED_SIZE) },
+        })
+    }
+
+    /// Returns the slice containing the ESP header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the security parameters index.
+    #[inline]
+    pub fn spi(&self) -> u32 {
+        u32::from_be_bytes([self.slice[0], self.slice[1], self.slice[2], self.slice[3]])
+    }
+
+    /// Read the sequence number.
+    #[inline]
+    pub fn sequence_number(&self) -> u32 {
+        u32::from_be_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]])
+    }
+
+    /// Decode all the fields and copy the results to a [`EspHeader`] struct.
+    #[inline]
+    pub fn to_header(&self) -> EspHeader {
+        EspHeader {
+            spi: self.spi(),
+            sequence_number: self.sequence_number(),
+        }
+    }
+}
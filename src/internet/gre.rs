@@ -0,0 +1,293 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::slice::from_raw_parts;
+
+/// Generic Routing Encapsulation header (ip number 47, RFC 2784 & RFC 2890).
+///
+/// Only version 0 headers without routing information are supported. The
+/// `protocol_type` field contains the ether type of the encapsulated packet
+/// (e.g. `ether_type::IPV4` or `ether_type::TRANSPARENT_ETHERNET_BRIDGING`
+/// for encapsulated ethernet II frames).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, GreHeader};
+///
+/// let header = GreHeader {
+///     key: Some(1234),
+///     ..GreHeader::new(ether_type::IPV4)
+/// };
+/// assert_eq!(8, header.header_len());
+///
+/// let bytes = header.to_bytes();
+/// let (decoded, rest) = GreHeader::from_slice(&bytes).unwrap();
+/// assert_eq!(header, decoded);
+/// assert!(rest.is_empty());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GreHeader {
+    /// Ether type of the encapsulated packet.
+    ///
+    /// See [EtherType] or [ether_type] for a definition of the known values.
+    pub protocol_type: u16,
+    /// Checksum over the GRE header & payload (present if the C bit is set).
+    pub checksum: Option<u16>,
+    /// Key identifying the flow the packet belongs to (present if the K bit is set).
+    pub key: Option<u32>,
+    /// Sequence number (present if the S bit is set).
+    pub sequence_number: Option<u32>,
+}
+
+impl GreHeader {
+    /// Minimum length of a GRE header in bytes (no optional fields).
+    pub const MIN_LEN: usize = 4;
+
+    /// Maximum length of a GRE header in bytes (all optional fields present).
+    pub const MAX_LEN: usize = 16;
+
+    /// Creates a GRE header without any of the optional fields.
+    pub const fn new(protocol_type: u16) -> GreHeader {
+        GreHeader {
+            protocol_type,
+            checksum: None,
+            key: None,
+            sequence_number: None,
+        }
+    }
+
+    /// Read a GRE header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(GreHeader, &[u8]), ReadError> {
+        let s = GreHeaderSlice::from_slice(slice)?;
+        let rest = &slice[s.slice().len()..];
+        Ok((s.to_header(), rest))
+    }
+
+    /// Read a GRE header from the current reader position.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<GreHeader, ReadError> {
+        let mut buffer = [0u8; GreHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..GreHeader::MIN_LEN])?;
+        let len = GreHeaderSlice::header_len_from_start(&buffer)?;
+        reader.read_exact(&mut buffer[GreHeader::MIN_LEN..len])?;
+
+        // SAFETY:
+        // Length & flags were validated in header_len_from_start.
+        Ok(unsafe { GreHeaderSlice::from_slice_unchecked(&buffer[..len]) }.to_header())
+    }
+
+    /// Writes the GRE header to the current position.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), std::io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        GreHeader::MIN_LEN
+            + if self.checksum.is_some() { 4 } else { 0 }
+            + if self.key.is_some() { 4 } else { 0 }
+            + if self.sequence_number.is_some() { 4 } else { 0 }
+    }
+
+    /// Returns the serialized form of the header.
+    pub fn to_bytes(&self) -> ArrayVec<u8, { GreHeader::MAX_LEN }> {
+        let mut flags = 0u8;
+        if self.checksum.is_some() {
+            flags |= GreHeaderSlice::CHECKSUM_FLAG;
+        }
+        if self.key.is_some() {
+            flags |= GreHeaderSlice::KEY_FLAG;
+        }
+        if self.sequence_number.is_some() {
+            flags |= GreHeaderSlice::SEQUENCE_NUMBER_FLAG;
+        }
+
+        let mut result = ArrayVec::new();
+        result.push(flags);
+        // version 0
+        result.push(0);
+        result.try_extend_from_slice(&self.protocol_type.to_be_bytes()).unwrap();
+        if let Some(checksum) = self.checksum {
+            result.try_extend_from_slice(&checksum.to_be_bytes()).unwrap();
+            // reserved1
+            result.try_extend_from_slice(&[0, 0]).unwrap();
+        }
+        if let Some(key) = self.key {
+            result.try_extend_from_slice(&key.to_be_bytes()).unwrap();
+        }
+        if let Some(sequence_number) = self.sequence_number {
+            result.try_extend_from_slice(&sequence_number.to_be_bytes()).unwrap();
+        }
+        result
+    }
+
+    /// Calculates the checksum of the header & the given payload (the
+    /// value of the `checksum` field itself is ignored).
+    pub fn calc_checksum(&self, payload: &[u8]) -> u16 {
+        let checksum = GreHeader {
+            checksum: Some(0),
+            ..self.clone()
+        };
+        checksum::Sum16BitWords::new()
+            .add_slice(&checksum.to_bytes())
+            .add_slice(payload)
+            .ones_complement()
+            .to_be()
+    }
+}
+
+/// Slice containing a GRE header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct GreHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> GreHeaderSlice<'a> {
+    const CHECKSUM_FLAG: u8 = 0b1000_0000;
+    const ROUTING_FLAG: u8 = 0b0100_0000;
+    const KEY_FLAG: u8 = 0b0010_0000;
+    const SEQUENCE_NUMBER_FLAG: u8 = 0b0001_0000;
+
+    /// Creates a GRE header slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<GreHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < GreHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(GreHeader::MIN_LEN));
+        }
+        let len = GreHeaderSlice::header_len_from_start(slice)?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        Ok(GreHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this
+            // code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Creates a GRE header slice from a slice (assumes slice size & content
+    /// was validated before).
+    ///
+    /// # Safety
+    ///
+    /// This method assumes that the slice was previously validated to contain
+    /// a valid GRE header. This means the slice length must at least be at
+    /// least the header length indicated by the flags in the first byte.
+    unsafe fn from_slice_unchecked(slice: &'a [u8]) -> GreHeaderSlice<'a> {
+        GreHeaderSlice { slice }
+    }
+
+    /// Checks the version & routing bit and returns the header length
+    /// based on the flags in the first 4 bytes.
+    fn header_len_from_start(start: &[u8]) -> Result<usize, ReadError> {
+        use crate::ReadError::*;
+
+        let version = start[1] & 0b0000_0111;
+        if 0 != version {
+            return Err(GreUnsupportedVersion(version));
+        }
+        if 0 != start[0] & GreHeaderSlice::ROUTING_FLAG {
+            return Err(GreRoutingNotSupported);
+        }
+        Ok(GreHeader::MIN_LEN
+            + if 0 != start[0] & GreHeaderSlice::CHECKSUM_FLAG { 4 } else { 0 }
+            + if 0 != start[0] & GreHeaderSlice::KEY_FLAG { 4 } else { 0 }
+            + if 0 != start[0] & GreHeaderSlice::SEQUENCE_NUMBER_FLAG { 4 } else { 0 })
+    }
+
+    /// Returns the slice containing the GRE header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns true if the checksum present (C) flag is set.
+    #[inline]
+    pub fn checksum_present(&self) -> bool {
+        0 != self.slice[0] & GreHeaderSlice::CHECKSUM_FLAG
+    }
+
+    /// Returns true if the key present (K) flag is set.
+    #[inline]
+    pub fn key_present(&self) -> bool {
+        0 != self.slice[0] & GreHeaderSlice::KEY_FLAG
+    }
+
+    /// Returns true if the sequence number present (S) flag is set.
+    #[inline]
+    pub fn sequence_number_present(&self) -> bool {
+        0 != self.slice[0] & GreHeaderSlice::SEQUENCE_NUMBER_FLAG
+    }
+
+    /// Read the version field (always 0 as other versions are rejected by `from_slice`).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[1] & 0b0000_0111
+    }
+
+    /// Ether type of the encapsulated packet.
+    ///
+    /// See [EtherType] or [ether_type] for a definition of the known values.
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Checksum if the C flag is set.
+    pub fn checksum(&self) -> Option<u16> {
+        if self.checksum_present() {
+            Some(u16::from_be_bytes([self.slice[4], self.slice[5]]))
+        } else {
+            None
+        }
+    }
+
+    /// Key if the K flag is set.
+    pub fn key(&self) -> Option<u32> {
+        if self.key_present() {
+            Some(self.read_u32(self.key_offset()))
+        } else {
+            None
+        }
+    }
+
+    /// Sequence number if the S flag is set.
+    pub fn sequence_number(&self) -> Option<u32> {
+        if self.sequence_number_present() {
+            let offset = self.key_offset() + if self.key_present() { 4 } else { 0 };
+            Some(self.read_u32(offset))
+        } else {
+            None
+        }
+    }
+
+    /// Decode all the fields and copy the results to a [`GreHeader`] struct.
+    pub fn to_header(&self) -> GreHeader {
+        GreHeader {
+            protocol_type: self.protocol_type(),
+            checksum: self.checksum(),
+            key: self.key(),
+            sequence_number: self.sequence_number(),
+        }
+    }
+
+    /// offset of the key field (if present)
+    #[inline]
+    fn key_offset(&self) -> usize {
+        GreHeader::MIN_LEN + if self.checksum_present() { 4 } else { 0 }
+    }
+
+    #[inline]
+    fn read_u32(&self, offset: usize) -> u32 {
+        u32::from_be_bytes([
+            self.slice[offset],
+            self.slice[offset + 1],
+            self.slice[offset + 2],
+            self.slice[offset + 3],
+        ])
+    }
+}
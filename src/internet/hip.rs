@@ -0,0 +1,232 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Packet types of the Host Identity Protocol (RFC 7401 & RFC 8046).
+pub mod hip_packet_type {
+    /// Initiator packet starting a base exchange.
+    pub const I1: u8 = 1;
+    /// Responder packet starting the puzzle.
+    pub const R1: u8 = 2;
+    /// Second initiator packet containing the puzzle solution.
+    pub const I2: u8 = 3;
+    /// Second responder packet finishing the base exchange.
+    pub const R2: u8 = 4;
+    /// Update packet (e.g. for rekeying or mobility).
+    pub const UPDATE: u8 = 16;
+    /// Notification packet.
+    pub const NOTIFY: u8 = 17;
+    /// Closing of a HIP association.
+    pub const CLOSE: u8 = 18;
+    /// Acknowledgment of a close packet.
+    pub const CLOSE_ACK: u8 = 19;
+}
+
+/// Slice containing a Host Identity Protocol header (ip number 139, RFC 7401).
+///
+/// The header consists of a fixed 40 byte part (containing the packet type,
+/// the controls and the host identity tags of the sender & receiver) followed
+/// by the HIP parameters.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{HipHeaderSlice, hip_packet_type};
+///
+/// let mut header = [0u8;48];
+/// header[0] = 59; // no next header
+/// header[1] = 5; // (5 + 1)*8 = 48 bytes
+/// header[2] = hip_packet_type::I1;
+/// header[3] = 0x21; // version 2
+/// // ECHO_REQUEST_UNSIGNED parameter (type 897) with 4 bytes of content
+/// header[40..48].copy_from_slice(&[0x03, 0x81, 0, 4, 1, 2, 3, 4]);
+///
+/// let hip = HipHeaderSlice::from_slice(&header).unwrap();
+/// assert_eq!(hip_packet_type::I1, hip.packet_type());
+/// assert_eq!(2, hip.version());
+///
+/// let parameter = hip.parameters().next().unwrap();
+/// assert_eq!(897, parameter.parameter_type);
+/// assert_eq!(&[1, 2, 3, 4], parameter.contents);
+/// assert!(parameter.is_critical());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HipHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> HipHeaderSlice<'a> {
+    /// Length of the fixed part of the header (before the parameters).
+    pub const FIXED_LEN: usize = 40;
+
+    /// Creates a slice containing a HIP header & validates the
+    /// header length and the lengths of the contained parameters.
+    pub fn from_slice(slice: &'a [u8]) -> Result<HipHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        // check that the fixed part is present
+        if slice.len() < HipHeaderSlice::FIXED_LEN {
+            return Err(UnexpectedEndOfSlice(HipHeaderSlice::FIXED_LEN));
+        }
+
+        // the header must contain at least the fixed part
+        let header_length = slice[1];
+        if usize::from(header_length) < HipHeaderSlice::FIXED_LEN/8 - 1 {
+            return Err(HipHeaderLengthTooSmall(header_length));
+        }
+        let len = (usize::from(header_length) + 1)*8;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        // check that all parameters fit into the header
+        let mut offset = HipHeaderSlice::FIXED_LEN;
+        while offset < len {
+            if len - offset < 4 {
+                return Err(HipParameterLengthTooBig(offset));
+            }
+            let contents_len = usize::from(u16::from_be_bytes([slice[offset + 2], slice[offset + 3]]));
+            let parameter_len = HipParameter::padded_len(contents_len);
+            if len - offset < parameter_len {
+                return Err(HipParameterLengthTooBig(offset));
+            }
+            offset += parameter_len;
+        }
+
+        Ok(HipHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // len before this code can be reached.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    len
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the HIP header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the IP protocol number of the next header (usually
+    /// 59, which indicates that no next header is present).
+    ///
+    /// See [IpNumber] or [ip_number] for a definition of the known values.
+    #[inline]
+    pub fn next_header(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Length of the header in 8 octets (minus the first 8 octets).
+    #[inline]
+    pub fn header_length(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Packet type of the HIP packet (see [hip_packet_type]).
+    #[inline]
+    pub fn packet_type(&self) -> u8 {
+        self.slice[2] & 0x7f
+    }
+
+    /// HIP version (4 bit value).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[3] >> 4
+    }
+
+    /// Checksum of the HIP header (calculated with the ip pseudo header).
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[4], self.slice[5]])
+    }
+
+    /// Control flags of the packet.
+    #[inline]
+    pub fn controls(&self) -> u16 {
+        u16::from_be_bytes([self.slice[6], self.slice[7]])
+    }
+
+    /// Returns true if the "anonymous" control flag is set (the host identity
+    /// of the sender is anonymous).
+    #[inline]
+    pub fn is_anonymous(&self) -> bool {
+        0 != self.controls() & 0x1
+    }
+
+    /// Host identity tag of the sender.
+    pub fn sender_hit(&self) -> [u8;16] {
+        let mut result = [0u8;16];
+        result.copy_from_slice(&self.slice[8..24]);
+        result
+    }
+
+    /// Host identity tag of the receiver (all zero if the receiver is
+    /// not known, e.g. in opportunistic mode).
+    pub fn receiver_hit(&self) -> [u8;16] {
+        let mut result = [0u8;16];
+        result.copy_from_slice(&self.slice[24..40]);
+        result
+    }
+
+    /// Returns an iterator over the parameters of the HIP header.
+    pub fn parameters(&self) -> HipParameterIterator<'a> {
+        HipParameterIterator {
+            rest: &self.slice[HipHeaderSlice::FIXED_LEN..],
+        }
+    }
+}
+
+/// Parameter (TLV) contained in a HIP header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct HipParameter<'a> {
+    /// Type of the parameter.
+    pub parameter_type: u16,
+    /// Contents of the parameter (without the padding).
+    pub contents: &'a [u8],
+}
+
+impl<'a> HipParameter<'a> {
+    /// Returns true if the parameter is critical (lowest bit of the type is
+    /// set). A receiver has to reject packets with unknown critical parameters.
+    #[inline]
+    pub fn is_critical(&self) -> bool {
+        0 != self.parameter_type & 0x1
+    }
+
+    /// Length of a parameter with the given contents length including the
+    /// type & length fields and the padding to a multiple of 8 bytes.
+    #[inline]
+    fn padded_len(contents_len: usize) -> usize {
+        (4 + contents_len + 7) & !7
+    }
+}
+
+/// Iterator over the parameters of a HIP header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HipParameterIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for HipParameterIterator<'a> {
+    type Item = HipParameter<'a>;
+
+    fn next(&mut self) -> Option<HipParameter<'a>> {
+        // the parameter lengths were validated when the header slice was created
+        if self.rest.len() < 4 {
+            None
+        } else {
+            let contents_len = usize::from(u16::from_be_bytes([self.rest[2], self.rest[3]]));
+            let result = HipParameter {
+                parameter_type: u16::from_be_bytes([self.rest[0], self.rest[1]]),
+                contents: &self.rest[4..4 + contents_len],
+            };
+            self.rest = &self.rest[HipParameter::padded_len(contents_len)..];
+            Some(result)
+        }
+    }
+}
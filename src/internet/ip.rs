@@ -1,7 +1,8 @@
 use super::super::*;
 
 ///Internet protocol headers version 4 & 6
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[allow(clippy::large_enum_variant)]
 pub enum IpHeader {
     Version4(Ipv4Header, Ipv4Extensions),
@@ -159,6 +160,22 @@ impl IpHeader {
             },
         }
     }
+
+    /// Recalculates the fields that depend on other fields after the header
+    /// or the extension headers have been modified in bulk.
+    ///
+    /// `payload_len` is the length of the data after the ip header & extension
+    /// headers. The length fields of the ip header are set based on it (including
+    /// the length of the extension headers) and the ipv4 header checksum is
+    /// recalculated. Use [`IpHeader::set_next_headers`] if extension headers
+    /// were added or removed.
+    pub fn recompute(&mut self, payload_len: usize) -> Result<(), ValueError> {
+        self.set_payload_len(payload_len)?;
+        if let IpHeader::Version4(header, _) = self {
+            header.recompute()?;
+        }
+        Ok(())
+    }
 }
 
 /// This type has been deprecated please use [IpNumber] instead.
@@ -183,7 +200,8 @@ pub type IpTrafficClass = IpNumber;
 /// `u8` contants of the ip numbers can be found in the module [`ip_number`].
 ///
 /// The list was extracted from <https://www.iana.org/assignments/protocol-numbers/protocol-numbers.xhtml>
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum IpNumber {
     ///IPv6 Hop-by-Hop Option \[[RFC8200](https://datatracker.ietf.org/doc/html/rfc8200)\]
     IPv6HeaderHopByHop = 0,
@@ -520,12 +538,16 @@ pub mod ip_number {
     pub const TCP: u8 = Tcp as u8; //6
     ///User Datagram \[[RFC768](https://datatracker.ietf.org/doc/html/rfc768)\] \[Jon_Postel\]
     pub const UDP: u8 = Udp as u8; //17
+    ///Datagram Congestion Control Protocol \[[RFC4340](https://datatracker.ietf.org/doc/html/rfc4340)\]
+    pub const DCCP: u8 = Dccp as u8; //33
     ///IPv6 encapsulation \[[RFC2473](https://datatracker.ietf.org/doc/html/rfc2473)\]
     pub const IPV6: u8 = Ipv6 as u8; //41
     ///Routing Header for IPv6 \[Steve_Deering\]
     pub const IPV6_ROUTE: u8 = IPv6RouteHeader as u8; //43
     ///Fragment Header for IPv6 \[Steve_Deering\]
     pub const IPV6_FRAG: u8 = IPv6FragmentationHeader as u8; //44
+    ///Generic Routing Encapsulation \[[RFC2784](https://datatracker.ietf.org/doc/html/rfc2784)\]
+    pub const GRE: u8 = Gre as u8; //47
     ///Encapsulating Security Payload \[[RFC4303](https://datatracker.ietf.org/doc/html/rfc4303)\]
     pub const ENCAP_SEC: u8 = EncapsulatingSecurityPayload as u8; //50
     ///Authentication Header \[[RFC4302](https://datatracker.ietf.org/doc/html/rfc4302)\]
@@ -534,8 +556,14 @@ pub mod ip_number {
     pub const IPV6_ICMP: u8 = IPv6Icmp as u8; // 58
     ///Destination Options for IPv6 \[[RFC8200](https://datatracker.ietf.org/doc/html/rfc8200)\]
     pub const IPV6_DEST_OPTIONS: u8 = IPv6DestinationOptions as u8; //60
+    ///Virtual Router Redundancy Protocol \[[RFC5798](https://datatracker.ietf.org/doc/html/rfc5798)\]
+    pub const VRRP: u8 = Vrrp as u8; //112
+    ///Stream Control Transmission Protocol \[[RFC9260](https://datatracker.ietf.org/doc/html/rfc9260)\]
+    pub const SCTP: u8 = Sctp as u8; //132
     ///MobilityHeader \[[RFC6275](https://datatracker.ietf.org/doc/html/rfc6275)\]
     pub const MOBILITY: u8 = MobilityHeader as u8; //135
+    ///UDPLite \[[RFC3828](https://datatracker.ietf.org/doc/html/rfc3828)\]
+    pub const UDP_LITE: u8 = UdpLite as u8; //136
     ///Host Identity Protocol \[[RFC7401](https://datatracker.ietf.org/doc/html/rfc7401)\]
     pub const HIP: u8 = Hip as u8; //139
     ///Shim6 Protocol \[[RFC5533](https://datatracker.ietf.org/doc/html/rfc5533)\]
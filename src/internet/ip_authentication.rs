@@ -1,5 +1,6 @@
 use super::super::*;
 
+use arrayvec::ArrayVec;
 use std::fmt::{Debug, Formatter};
 use std::slice::from_raw_parts;
 
@@ -40,6 +41,17 @@ impl Debug for IpAuthenticationHeader {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for IpAuthenticationHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "IpAuthenticationHeader {{ next_header: {}, spi: {}, sequence_number: {}, raw_icv: {=[u8]} }}",
+            self.next_header,
+            self.spi,
+            self.sequence_number,
+            self.raw_icv())
+    }
+}
+
 impl PartialEq for IpAuthenticationHeader {
     fn eq(&self, other: &Self) -> bool {
         self.next_header == other.next_header &&
@@ -51,10 +63,25 @@ impl PartialEq for IpAuthenticationHeader {
 
 impl Eq for IpAuthenticationHeader {}
 
+impl std::hash::Hash for IpAuthenticationHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.next_header.hash(state);
+        self.spi.hash(state);
+        self.sequence_number.hash(state);
+        self.raw_icv().hash(state);
+    }
+}
+
 impl<'a> IpAuthenticationHeader {
 
     pub const MAX_ICV_LEN: usize = 0xfe*4;
 
+    /// Minimum length of a serialized authentication header in bytes (header without icv).
+    pub const MIN_SERIALIZED_SIZE: usize = 12;
+
+    /// Maximum length of a serialized authentication header in bytes.
+    pub const MAX_SERIALIZED_SIZE: usize = 12 + IpAuthenticationHeader::MAX_ICV_LEN;
+
     /// Create a new authentication header with the given parameters.
     ///
     /// Note: The length of the raw_icv slice must be a multiple of 4
@@ -195,10 +222,19 @@ impl<'a> IpAuthenticationHeader {
     pub fn header_len(&self) -> usize {
         12 + usize::from(self.raw_icv_len)*4
     }
+
+    /// Returns the serialized header in a stack allocated buffer.
+    pub fn to_bytes(&self) -> ArrayVec<u8, { IpAuthenticationHeader::MAX_SERIALIZED_SIZE }> {
+        let mut result = ArrayVec::new();
+        // the buffer has the capacity of the maximum header length,
+        // so writing the header into it can not fail
+        self.write(&mut result).unwrap();
+        result
+    }
 }
 
 /// A slice containing an IP Authentication Header (rfc4302)
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct IpAuthenticationHeaderSlice<'a> {
     slice: &'a [u8]
 }
@@ -1,5 +1,6 @@
 use super::super::*;
 
+use arrayvec::ArrayVec;
 use std::net::Ipv4Addr;
 use std::fmt::{Debug, Formatter};
 use std::slice::from_raw_parts;
@@ -39,6 +40,12 @@ impl SerializedSize for Ipv4Header {
 const IPV4_MAX_OPTIONS_LENGTH: usize = 10*4;
 
 impl Ipv4Header {
+    /// Minimum length of an IPv4 header in bytes (header without options).
+    pub const MIN_SERIALIZED_SIZE: usize = 20;
+
+    /// Maximum length of an IPv4 header in bytes (header with 40 bytes of options).
+    pub const MAX_SERIALIZED_SIZE: usize = 60;
+
     ///Constructs an Ipv4Header with standard values for non specified values.
     pub fn new(payload_len: u16, time_to_live: u8, protocol: u8, source: [u8;4], destination: [u8;4]) -> Ipv4Header {
         Ipv4Header {
@@ -59,6 +66,29 @@ impl Ipv4Header {
         }
     }
 
+    /// Constructs an Ipv4Header with standard values for non specified values
+    /// & checks that the `payload_len` can be represented in the `total_length`
+    /// field of the header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ipv4Header, ip_number, ValueError, ErrorField};
+    ///
+    /// let header = Ipv4Header::try_new(1000, 64, ip_number::UDP, [1,2,3,4], [5,6,7,8]).unwrap();
+    /// assert_eq!(1000, header.payload_len);
+    ///
+    /// assert_eq!(
+    ///     Err(ValueError::U16TooLarge{ value: 0xffff, max: 0xffff - 20, field: ErrorField::Ipv4PayloadLength }),
+    ///     Ipv4Header::try_new(0xffff, 64, ip_number::UDP, [1,2,3,4], [5,6,7,8])
+    /// );
+    /// ```
+    pub fn try_new(payload_len: u16, time_to_live: u8, protocol: u8, source: [u8;4], destination: [u8;4]) -> Result<Ipv4Header, ValueError> {
+        let result = Ipv4Header::new(payload_len, time_to_live, protocol, source, destination);
+        max_check_u16(payload_len, result.max_payload_len(), ErrorField::Ipv4PayloadLength)?;
+        Ok(result)
+    }
+
     ///Length of the header in 4 bytes (often also called IHL - Internet Header Lenght). 
     ///
     ///The minimum allowed length of a header is 5 (= 20 bytes) and the maximum length is 15 (= 60 bytes).
@@ -120,6 +150,93 @@ impl Ipv4Header {
         }
     }
 
+    /// Sets the time to live & updates the header checksum.
+    ///
+    /// The checksum is updated incrementally (RFC 1624), so a checksum that
+    /// was valid before stays valid (e.g. when decrementing the ttl while
+    /// forwarding a packet). Use [`Ipv4Header::recompute`] to calculate the
+    /// checksum from scratch.
+    pub fn set_time_to_live(&mut self, time_to_live: u8) {
+        let old = [self.time_to_live, self.protocol];
+        self.time_to_live = time_to_live;
+        self.update_header_checksum(&old, &[self.time_to_live, self.protocol]);
+    }
+
+    /// Sets the protocol number & updates the header checksum incrementally
+    /// (see [`Ipv4Header::set_time_to_live`]).
+    pub fn set_protocol(&mut self, protocol: u8) {
+        let old = [self.time_to_live, self.protocol];
+        self.protocol = protocol;
+        self.update_header_checksum(&old, &[self.time_to_live, self.protocol]);
+    }
+
+    /// Sets the identification & updates the header checksum incrementally
+    /// (see [`Ipv4Header::set_time_to_live`]).
+    pub fn set_identification(&mut self, identification: u16) {
+        let old = self.identification.to_be_bytes();
+        self.identification = identification;
+        self.update_header_checksum(&old, &identification.to_be_bytes());
+    }
+
+    /// Sets the source address & updates the header checksum incrementally
+    /// (see [`Ipv4Header::set_time_to_live`]).
+    pub fn set_source(&mut self, source: [u8;4]) {
+        let old = self.source;
+        self.source = source;
+        self.update_header_checksum(&old, &source);
+    }
+
+    /// Sets the destination address & updates the header checksum incrementally
+    /// (see [`Ipv4Header::set_time_to_live`]).
+    pub fn set_destination(&mut self, destination: [u8;4]) {
+        let old = self.destination;
+        self.destination = destination;
+        self.update_header_checksum(&old, &destination);
+    }
+
+    /// Recalculates the header checksum after the fields of the header have
+    /// been modified directly (e.g. after multiple changes in bulk).
+    ///
+    /// The ihl & total length are always derived from the options & the payload
+    /// length, so the checksum is the only field that has to be updated. An error
+    /// is returned (and nothing is changed) if a field contains a value that
+    /// can not be serialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ipv4Header, ip_number};
+    ///
+    /// let mut header = Ipv4Header::new(100, 64, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+    /// header.set_options(&[1,2,3,4]).unwrap();
+    /// header.set_payload_len(200).unwrap();
+    /// header.recompute().unwrap();
+    ///
+    /// assert_eq!(6, header.ihl());
+    /// assert_eq!(224, header.total_len());
+    /// assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+    /// ```
+    pub fn recompute(&mut self) -> Result<(), ValueError> {
+        self.header_checksum = self.calc_header_checksum()?;
+        Ok(())
+    }
+
+    /// Updates the header checksum after the bytes `old` of the header have
+    /// been replaced with `new` (RFC 1624, both at most 4 bytes long & at the
+    /// same 16 bit aligned position).
+    fn update_header_checksum(&mut self, old: &[u8], new: &[u8]) {
+        let mut inverted = [0u8;4];
+        for (target, value) in inverted.iter_mut().zip(old) {
+            *target = !value;
+        }
+        self.header_checksum = checksum::Sum16BitWords::new()
+            .add_2bytes((!self.header_checksum).to_be_bytes())
+            .add_slice(&inverted[..old.len()])
+            .add_slice(new)
+            .ones_complement()
+            .to_be();
+    }
+
     /// Renamed to `Ipv4Header::from_slice`
     #[deprecated(
         since = "0.10.1",
@@ -246,6 +363,29 @@ impl Ipv4Header {
         self.write_ipv4_header_internal(writer, self.header_checksum)
     }
 
+    /// Returns the serialized header (including the options) in a stack
+    /// allocated buffer (this method automatically calculates the header
+    /// length and checksum).
+    ///
+    /// ```
+    /// use etherparse::Ipv4Header;
+    ///
+    /// let header = Ipv4Header::new(0, 64, 17, [192,168,1,1], [192,168,1,2]);
+    /// let bytes = header.to_bytes().unwrap();
+    /// assert_eq!(header.header_len(), bytes.len());
+    /// ```
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { Ipv4Header::MAX_SERIALIZED_SIZE }>, ValueError> {
+        //check ranges
+        self.check_ranges()?;
+
+        let mut result = ArrayVec::new();
+        // the buffer has the capacity of the maximum header length,
+        // so writing the header into it can not fail
+        self.write_ipv4_header_internal(&mut result, self.calc_header_checksum_unchecked())
+            .unwrap();
+        Ok(result)
+    }
+
     /// Write the given header with the  checksum and header length specified in the seperate arguments
     fn write_ipv4_header_internal<T: io::Write>(&self, write: &mut T, header_checksum: u16) -> Result<(), WriteError> {
         let total_len_be = self.total_len().to_be_bytes();
@@ -411,6 +551,27 @@ impl Debug for Ipv4Header {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Ipv4Header {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Ipv4Header {{ ihl: {}, differentiated_services_code_point: {}, explicit_congestion_notification: {}, payload_len: {}, identification: {}, dont_fragment: {}, more_fragments: {}, fragments_offset: {}, time_to_live: {}, protocol: {}, header_checksum: {}, source: {}, destination: {}, options: {=[u8]} }}",
+            self.ihl(),
+            self.differentiated_services_code_point,
+            self.explicit_congestion_notification,
+            self.payload_len,
+            self.identification,
+            self.dont_fragment,
+            self.more_fragments,
+            self.fragments_offset,
+            self.time_to_live,
+            self.protocol,
+            self.header_checksum,
+            self.source,
+            self.destination,
+            self.options())
+    }
+}
+
 impl std::cmp::PartialEq for Ipv4Header {
     fn eq(&self, other: &Ipv4Header) -> bool {
         self.differentiated_services_code_point == other.differentiated_services_code_point &&
@@ -432,8 +593,26 @@ impl std::cmp::PartialEq for Ipv4Header {
 
 impl std::cmp::Eq for Ipv4Header {}
 
+impl std::hash::Hash for Ipv4Header {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.differentiated_services_code_point.hash(state);
+        self.explicit_congestion_notification.hash(state);
+        self.payload_len.hash(state);
+        self.identification.hash(state);
+        self.dont_fragment.hash(state);
+        self.more_fragments.hash(state);
+        self.fragments_offset.hash(state);
+        self.time_to_live.hash(state);
+        self.protocol.hash(state);
+        self.header_checksum.hash(state);
+        self.source.hash(state);
+        self.destination.hash(state);
+        self.options().hash(state);
+    }
+}
+
 /// A slice containing an ipv4 header of a network package.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Ipv4HeaderSlice<'a> {
     slice: &'a [u8]
 }
@@ -441,7 +620,43 @@ pub struct Ipv4HeaderSlice<'a> {
 impl<'a> Ipv4HeaderSlice<'a> {
 
     /// Creates a slice containing an ipv4 header (including header options).
+    #[inline]
     pub fn from_slice(slice: &'a[u8]) -> Result<Ipv4HeaderSlice<'a>, ReadError> {
+        Ipv4HeaderSlice::from_slice_impl(slice, false)
+    }
+
+    /// Creates a slice containing an ipv4 header, accepting headers whose
+    /// options are cut off by the end of the slice (e.g. captures with a
+    /// snap length ending in the options of the header).
+    ///
+    /// The first 20 bytes of the header still have to be present. If the
+    /// options are incomplete the returned slice contains all the available
+    /// option bytes & [`Ipv4HeaderSlice::is_options_truncated`] returns true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ipv4Header, Ipv4HeaderSlice, ip_number};
+    ///
+    /// let mut header = Ipv4Header::new(0, 64, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+    /// header.set_options(&[1,2,3,4,5,6,7,8]).unwrap();
+    /// let bytes = header.to_bytes().unwrap();
+    ///
+    /// // capture ending in the middle of the options
+    /// let truncated = &bytes[..23];
+    /// assert!(Ipv4HeaderSlice::from_slice(truncated).is_err());
+    ///
+    /// let slice = Ipv4HeaderSlice::from_slice_truncated_options(truncated).unwrap();
+    /// assert!(slice.is_options_truncated());
+    /// assert_eq!(&[1,2,3], slice.options());
+    /// assert_eq!(7, slice.ihl());
+    /// ```
+    #[inline]
+    pub fn from_slice_truncated_options(slice: &'a[u8]) -> Result<Ipv4HeaderSlice<'a>, ReadError> {
+        Ipv4HeaderSlice::from_slice_impl(slice, true)
+    }
+
+    fn from_slice_impl(slice: &'a[u8], allow_truncated_options: bool) -> Result<Ipv4HeaderSlice<'a>, ReadError> {
 
         //check length
         use crate::ReadError::*;
@@ -468,7 +683,7 @@ impl<'a> Ipv4HeaderSlice<'a> {
 
         //check that the slice contains enough data for the entire header + options
         let header_length = (usize::from(ihl))*4;
-        if slice.len() < header_length {
+        if slice.len() < header_length && false == allow_truncated_options {
             return Err(UnexpectedEndOfSlice(header_length));
         }
 
@@ -489,11 +704,12 @@ impl<'a> Ipv4HeaderSlice<'a> {
         Ok(Ipv4HeaderSlice {
             // SAFETY:
             // Safe as the slice length is checked to be at least
-            // header_length or greater above.
+            // header_length or greater above (or the length of the
+            // slice is used if truncated options are allowed).
             slice: unsafe {
                 from_raw_parts(
                     slice.as_ptr(),
-                    header_length
+                    header_length.min(slice.len())
                 )
             }
         })
@@ -694,6 +910,13 @@ impl<'a> Ipv4HeaderSlice<'a> {
         }
     }
 
+    /// Returns true if the options are incomplete (only possible for slices
+    /// created via [`Ipv4HeaderSlice::from_slice_truncated_options`]).
+    #[inline]
+    pub fn is_options_truncated(&self) -> bool {
+        self.slice.len() < usize::from(self.ihl())*4
+    }
+
     /// Returns true if the payload is fragmented.
     ///
     /// Either data is missing (more_fragments set) or there is
@@ -705,6 +928,9 @@ impl<'a> Ipv4HeaderSlice<'a> {
     }
 
     /// Decode all the fields and copy the results to a Ipv4Header struct
+    ///
+    /// Truncated options are filled up with zeros (the header length of
+    /// the result matches the ihl).
     pub fn to_header(&self) -> Ipv4Header {
         let options = self.options();
         Ipv4Header {
@@ -720,7 +946,7 @@ impl<'a> Ipv4HeaderSlice<'a> {
             header_checksum: self.header_checksum(),
             source: self.source(),
             destination: self.destination(),
-            options_len: options.len() as u8,
+            options_len: (usize::from(self.ihl())*4 - Ipv4Header::SERIALIZED_SIZE) as u8,
             options_buffer: {
                 let mut result: [u8;40] = [0;40];
                 result[..options.len()].copy_from_slice(options);
@@ -7,7 +7,8 @@ use super::super::*;
 ///
 /// Currently not supported:
 /// - Encapsulating Security Payload Header (ESP)
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ipv4Extensions {
     pub auth: Option<IpAuthenticationHeader>,
 }
@@ -19,7 +20,7 @@ pub struct Ipv4Extensions {
 ///
 /// Currently not supported:
 /// * Encapsulating Security Payload Header (ESP)
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
 pub struct Ipv4ExtensionsSlice<'a> {
     pub auth: Option<IpAuthenticationHeaderSlice<'a>>,
 }
@@ -4,7 +4,8 @@ use std::net::Ipv6Addr;
 use std::slice::from_raw_parts;
 
 ///IPv6 header according to rfc8200.
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ipv6Header {
     pub traffic_class: u8,
     ///If non 0 serves as a hint to router and switches with multiple outbound paths that these packets should stay on the same path, so that they will not be reordered.
@@ -30,6 +31,43 @@ impl SerializedSize for Ipv6Header {
 
 impl Ipv6Header {
 
+    /// Constructs an Ipv6Header & checks that the `flow_label` can be
+    /// represented in the 20 bits available in the header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ipv6Header, ip_number, ValueError, ErrorField};
+    ///
+    /// let header = Ipv6Header::new(0, 0x12345, 8, ip_number::UDP, 64, [1;16], [2;16]).unwrap();
+    /// assert_eq!(0x12345, header.flow_label);
+    ///
+    /// assert_eq!(
+    ///     Err(ValueError::U32TooLarge{ value: 0x100000, max: 0xfffff, field: ErrorField::Ipv6FlowLabel }),
+    ///     Ipv6Header::new(0, 0x100000, 8, ip_number::UDP, 64, [1;16], [2;16])
+    /// );
+    /// ```
+    pub fn new(
+        traffic_class: u8,
+        flow_label: u32,
+        payload_length: u16,
+        next_header: u8,
+        hop_limit: u8,
+        source: [u8;16],
+        destination: [u8;16]
+    ) -> Result<Ipv6Header, ValueError> {
+        max_check_u32(flow_label, 0xfffff, ErrorField::Ipv6FlowLabel)?;
+        Ok(Ipv6Header {
+            traffic_class,
+            flow_label,
+            payload_length,
+            next_header,
+            hop_limit,
+            source,
+            destination,
+        })
+    }
+
     /// Renamed to `Ipv6Header::from_slice`
     #[deprecated(
         since = "0.10.1",
@@ -218,21 +256,6 @@ impl Ipv6Header {
     ///Writes a given IPv6 header to the current position.
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
         use crate::ErrorField::*;
-        fn max_check_u32(value: u32, max: u32, field: ErrorField) -> Result<(), WriteError> {
-            if value <= max {
-                Ok(())
-            } else {
-                Err(
-                    WriteError::ValueError(
-                        ValueError::U32TooLarge{
-                            value, 
-                            max, 
-                            field
-                        }
-                    )
-                )
-            }
-        }
 
         // check value ranges
         max_check_u32(self.flow_label, 0xfffff, Ipv6FlowLabel)?;
@@ -290,7 +313,7 @@ impl Ipv6Header {
 }
 
 ///A slice containing an ipv6 header of a network package.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Ipv6HeaderSlice<'a> {
     slice: &'a [u8]
 }
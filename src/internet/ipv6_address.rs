@@ -0,0 +1,168 @@
+use std::net::Ipv6Addr;
+
+/// Scope of an IPv6 address (RFC 4291 & RFC 7346).
+///
+/// For multicast addresses the scope is taken from the scope field of the
+/// address, for unicast addresses it is derived from the address prefix
+/// (see [`Ipv6AddressExt::scope`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Ipv6AddressScope {
+    /// Interface-local scope (multicast scope 1 & the loopback address).
+    InterfaceLocal,
+    /// Link-local scope (multicast scope 2 & unicast addresses in `fe80::/10`).
+    LinkLocal,
+    /// Realm-local scope (multicast scope 3).
+    RealmLocal,
+    /// Admin-local scope (multicast scope 4).
+    AdminLocal,
+    /// Site-local scope (multicast scope 5).
+    SiteLocal,
+    /// Organization-local scope (multicast scope 8).
+    OrganizationLocal,
+    /// Global scope (multicast scope 14 & all other unicast addresses).
+    Global,
+    /// Reserved or unassigned multicast scope. The value is the scope field
+    /// of the address.
+    Other(u8),
+}
+
+impl Ipv6AddressScope {
+    /// Converts the scope field of a multicast address (lower 4 bits of the
+    /// second byte) to the corresponding scope.
+    pub fn from_multicast_scope(value: u8) -> Ipv6AddressScope {
+        use Ipv6AddressScope::*;
+        match value {
+            1 => InterfaceLocal,
+            2 => LinkLocal,
+            3 => RealmLocal,
+            4 => AdminLocal,
+            5 => SiteLocal,
+            8 => OrganizationLocal,
+            0xe => Global,
+            value => Other(value),
+        }
+    }
+}
+
+/// Classification helpers for IPv6 addresses as they are stored in the
+/// headers (e.g. [`crate::Ipv6Header::source`]).
+///
+/// The trait is implemented for `[u8;16]` & [`std::net::Ipv6Addr`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ipv6AddressExt, Ipv6AddressScope, Ipv6Header};
+///
+/// let header = Ipv6Header {
+///     source: [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+///     // all routers (link-local scope)
+///     destination: [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2],
+///     ..Default::default()
+/// };
+///
+/// assert!(header.source.is_unicast_link_local());
+/// assert!(header.destination.is_multicast());
+/// assert_eq!(Some(Ipv6AddressScope::LinkLocal), header.destination.multicast_scope());
+/// ```
+pub trait Ipv6AddressExt {
+    /// Returns the 16 bytes of the address.
+    fn ipv6_octets(&self) -> [u8; 16];
+
+    /// Returns true for the unspecified address `::`.
+    fn is_unspecified(&self) -> bool {
+        self.ipv6_octets() == [0; 16]
+    }
+
+    /// Returns true for the loopback address `::1`.
+    fn is_loopback(&self) -> bool {
+        let mut loopback = [0; 16];
+        loopback[15] = 1;
+        self.ipv6_octets() == loopback
+    }
+
+    /// Returns true for link-local unicast addresses (`fe80::/10`).
+    fn is_unicast_link_local(&self) -> bool {
+        let octets = self.ipv6_octets();
+        octets[0] == 0xfe && (octets[1] & 0xc0) == 0x80
+    }
+
+    /// Returns true for unique local addresses (`fc00::/7`, RFC 4193).
+    fn is_unique_local(&self) -> bool {
+        (self.ipv6_octets()[0] & 0xfe) == 0xfc
+    }
+
+    /// Returns true for multicast addresses (`ff00::/8`).
+    fn is_multicast(&self) -> bool {
+        self.ipv6_octets()[0] == 0xff
+    }
+
+    /// Returns the scope of a multicast address (`None` if the address is
+    /// not a multicast address).
+    fn multicast_scope(&self) -> Option<Ipv6AddressScope> {
+        if self.is_multicast() {
+            Some(Ipv6AddressScope::from_multicast_scope(self.ipv6_octets()[1] & 0xf))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true for IPv4-mapped addresses (`::ffff:0:0/96`).
+    fn is_ipv4_mapped(&self) -> bool {
+        let octets = self.ipv6_octets();
+        octets[..10] == [0; 10] && octets[10] == 0xff && octets[11] == 0xff
+    }
+
+    /// Returns the IPv4 address of an IPv4-mapped address (`None` if the
+    /// address is not an IPv4-mapped address).
+    fn to_ipv4_mapped(&self) -> Option<[u8; 4]> {
+        if self.is_ipv4_mapped() {
+            let octets = self.ipv6_octets();
+            Some([octets[12], octets[13], octets[14], octets[15]])
+        } else {
+            None
+        }
+    }
+
+    /// Returns true for addresses reserved for documentation (`2001:db8::/32`,
+    /// RFC 3849 & `3fff::/20`, RFC 9637).
+    fn is_documentation(&self) -> bool {
+        let octets = self.ipv6_octets();
+        octets[..4] == [0x20, 0x01, 0x0d, 0xb8]
+            || (octets[0] == 0x3f && octets[1] == 0xff && (octets[2] & 0xf0) == 0)
+    }
+
+    /// Returns the scope of the address.
+    ///
+    /// Multicast addresses return the scope contained in the address, the
+    /// loopback address [`Ipv6AddressScope::InterfaceLocal`], link-local unicast
+    /// addresses [`Ipv6AddressScope::LinkLocal`] & all other addresses
+    /// (including unique local addresses, see RFC 4193 section 3.3)
+    /// [`Ipv6AddressScope::Global`].
+    fn scope(&self) -> Ipv6AddressScope {
+        if let Some(scope) = self.multicast_scope() {
+            scope
+        } else if self.is_loopback() {
+            Ipv6AddressScope::InterfaceLocal
+        } else if self.is_unicast_link_local() {
+            Ipv6AddressScope::LinkLocal
+        } else {
+            Ipv6AddressScope::Global
+        }
+    }
+}
+
+impl Ipv6AddressExt for [u8; 16] {
+    #[inline]
+    fn ipv6_octets(&self) -> [u8; 16] {
+        *self
+    }
+}
+
+impl Ipv6AddressExt for Ipv6Addr {
+    #[inline]
+    fn ipv6_octets(&self) -> [u8; 16] {
+        self.octets()
+    }
+}
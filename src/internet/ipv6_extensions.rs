@@ -11,18 +11,25 @@ use std::slice::from_raw_parts;
 /// * Routing Header
 /// * Fragment
 /// * Authentication Header
+/// * Site Multihoming by IPv6 Intermediation (SHIM6)
 ///
 /// Currently not supported:
 ////
 /// * Encapsulating Security Payload Header (ESP)
-/// * Host Identity Protocol (HIP)
+/// * Host Identity Protocol (HIP), ends the chain & can be decoded with [`HipHeaderSlice`]
 /// * IP Mobility
-/// * Site Multihoming by IPv6 Intermediation (SHIM6)
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ipv6Extensions {
     pub hop_by_hop_options: Option<Ipv6RawExtensionHeader>,
     pub destination_options: Option<Ipv6RawExtensionHeader>,
     pub routing: Option<Ipv6RoutingExtensions>,
+    /// Shim6 header (payload extension header or control message).
+    ///
+    /// Boxed as shim6 headers are rare & the raw extension header buffer
+    /// would otherwise grow every ip header by its maximum length.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub shim6: Option<Box<Ipv6RawExtensionHeader>>,
     pub fragment: Option<Ipv6FragmentHeader>,
     pub auth: Option<IpAuthenticationHeader>,
 }
@@ -117,6 +124,17 @@ impl Ipv6Extensions {
                         );
                     }
                 },
+                SHIM6 => {
+                    if result.shim6.is_some() {
+                        // more then one header of this type found -> abort parsing
+                        return Ok((result, next_header, rest))
+                    } else {
+                        let slice = Shim6HeaderSlice::from_slice(rest)?;
+                        rest = &rest[slice.slice().len()..];
+                        next_header = slice.next_header();
+                        result.shim6 = Some(Box::new(slice.to_header()));
+                    }
+                },
                 IPV6_FRAG => {
                     if result.fragment.is_some() {
                         // more then one header of this type found -> abort parsing
@@ -232,6 +250,16 @@ impl Ipv6Extensions {
                         );
                     }
                 },
+                SHIM6 => {
+                    if result.shim6.is_some() {
+                        // more then one header of this type found -> abort parsing
+                        return Ok((result, next_protocol));
+                    } else {
+                        let header = Ipv6RawExtensionHeader::read(reader)?;
+                        next_protocol = header.next_header;
+                        result.shim6 = Some(Box::new(header));
+                    }
+                },
                 IPV6_FRAG => {
                     if result.fragment.is_some() {
                         // more then one header of this type found -> abort parsing
@@ -277,6 +305,7 @@ impl Ipv6Extensions {
             pub hop_by_hop_options: bool,
             pub destination_options: bool,
             pub routing: bool,
+            pub shim6: bool,
             pub fragment: bool,
             pub auth: bool,
             pub final_destination_options: bool
@@ -286,6 +315,7 @@ impl Ipv6Extensions {
             hop_by_hop_options: self.hop_by_hop_options.is_some(),
             destination_options: self.destination_options.is_some(),
             routing: self.routing.is_some(),
+            shim6: self.shim6.is_some(),
             fragment: self.fragment.is_some(),
             auth: self.auth.is_some(),
             final_destination_options: if let Some(ref routing) = self.routing {
@@ -356,6 +386,16 @@ impl Ipv6Extensions {
                         break;
                     }
                 },
+                SHIM6 => {
+                    if needs_write.shim6 {
+                        let header = &self.shim6.as_ref().unwrap();
+                        header.write(writer)?;
+                        next_header = header.next_header;
+                        needs_write.shim6 = false;
+                    } else {
+                        break;
+                    }
+                },
                 IPV6_FRAG => {
                     if needs_write.fragment {
                         let header = &self.fragment.as_ref().unwrap();
@@ -390,6 +430,8 @@ impl Ipv6Extensions {
             Err(Ipv6ExtensionNotReferenced(IPv6DestinationOptions).into())
         } else if needs_write.routing {
             Err(Ipv6ExtensionNotReferenced(IPv6RouteHeader).into())
+        } else if needs_write.shim6 {
+            Err(Ipv6ExtensionNotReferenced(Shim6).into())
         } else if needs_write.fragment {
             Err(Ipv6ExtensionNotReferenced(IPv6FragmentationHeader).into())
         } else if needs_write.auth {
@@ -417,6 +459,9 @@ impl Ipv6Extensions {
                 result += header.header_len();
             }
         }
+        if let Some(ref header) = self.shim6 {
+            result += header.header_len();
+        }
         if let Some(ref header) = self.fragment {
             result += header.header_len();
         }
@@ -445,6 +490,7 @@ impl Ipv6Extensions {
         // * Hop-by-Hop Options header
         // * Destination Options header
         // * Routing header
+        // * (Shim6 header, see RFC 5533)
         // * Fragment header
         // * Authentication header
         // * Encapsulating Security Payload header
@@ -465,6 +511,10 @@ impl Ipv6Extensions {
             header.next_header = next;
             next = IPV6_FRAG;
         }
+        if let Some(ref mut header) = self.shim6 {
+            header.next_header = next;
+            next = SHIM6;
+        }
         if let Some(ref mut routing) = self.routing {
             routing.routing.next_header = next;
             next = IPV6_ROUTE;
@@ -492,6 +542,7 @@ impl Ipv6Extensions {
             pub hop_by_hop_options: bool,
             pub destination_options: bool,
             pub routing: bool,
+            pub shim6: bool,
             pub fragment: bool,
             pub auth: bool,
             pub final_destination_options: bool
@@ -501,6 +552,7 @@ impl Ipv6Extensions {
             hop_by_hop_options: self.hop_by_hop_options.is_some(),
             destination_options: self.destination_options.is_some(),
             routing: self.routing.is_some(),
+            shim6: self.shim6.is_some(),
             fragment: self.fragment.is_some(),
             auth: self.auth.is_some(),
             final_destination_options: if let Some(ref routing) = self.routing {
@@ -568,6 +620,15 @@ impl Ipv6Extensions {
                         break;
                     }
                 },
+                SHIM6 => {
+                    if outstanding_refs.shim6 {
+                        let header = &self.shim6.as_ref().unwrap();
+                        next = header.next_header;
+                        outstanding_refs.shim6 = false;
+                    } else {
+                        break;
+                    }
+                },
                 IPV6_FRAG => {
                     if outstanding_refs.fragment {
                         let header = &self.fragment.as_ref().unwrap();
@@ -606,6 +667,11 @@ impl Ipv6Extensions {
                 Ipv6ExtensionNotReferenced(IpNumber::IPv6RouteHeader)
             );
         }
+        if outstanding_refs.shim6 {
+            return Err(
+                Ipv6ExtensionNotReferenced(IpNumber::Shim6)
+            );
+        }
         if outstanding_refs.fragment {
             return Err(
                 Ipv6ExtensionNotReferenced(IpNumber::IPv6FragmentationHeader)
@@ -648,6 +714,7 @@ impl Ipv6Extensions {
         self.hop_by_hop_options.is_none() &&
         self.destination_options.is_none() &&
         self.routing.is_none() &&
+        self.shim6.is_none() &&
         self.fragment.is_none() &&
         self.auth.is_none()
     }
@@ -655,7 +722,8 @@ impl Ipv6Extensions {
 
 /// In case a route header is present it is also possible
 /// to attach a "final destination" header.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ipv6RoutingExtensions {
     pub routing: Ipv6RawExtensionHeader,
     pub final_destination_options: Option<Ipv6RawExtensionHeader>
@@ -670,13 +738,13 @@ pub struct Ipv6RoutingExtensions {
 /// * Routing Header
 /// * Fragment
 /// * Authentication Header
+/// * Site Multihoming by IPv6 Intermediation (SHIM6)
 ///
 /// Currently not supported:
 /// * Encapsulating Security Payload Header (ESP)
-/// * Host Identity Protocol (HIP)
+/// * Host Identity Protocol (HIP), ends the chain & can be decoded with [`HipHeaderSlice`]
 /// * IP Mobility
-/// * Site Multihoming by IPv6 Intermediation (SHIM6)
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
 pub struct Ipv6ExtensionsSlice<'a> {
     /// IP protocol number of the first header present in the slice.
     first_header: Option<u8>,
@@ -710,7 +778,7 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
                 IPV6_HOP_BY_HOP => {
                     return Err(Ipv6HopByHopHeaderNotAtStart);
                 },
-                IPV6_DEST_OPTIONS | IPV6_ROUTE => {
+                IPV6_DEST_OPTIONS | IPV6_ROUTE | SHIM6 => {
                     let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
                     // SAFETY:
                     // Ipv6RawExtensionHeaderSlice::from_slice always generates
@@ -822,10 +890,9 @@ impl<'a> Ipv6ExtensionsSlice<'a> {
 /// * Encapsulating Security Payload \[[RFC4303](https://datatracker.ietf.org/doc/html/rfc4303)\]
 /// * Mobility Header \[[RFC6275](https://datatracker.ietf.org/doc/html/rfc6275)\]
 /// * Host Identity Protocol \[[RFC7401](https://datatracker.ietf.org/doc/html/rfc7401)\]
-/// * Shim6 Protocol \[[RFC5533](https://datatracker.ietf.org/doc/html/rfc5533)\]
 /// * 253 Use for experimentation and testing \[[RFC3692](https://datatracker.ietf.org/doc/html/rfc3692)\]\[[RFC4727](https://datatracker.ietf.org/doc/html/rfc4727)\]
 /// * 254 Use for experimentation and testing \[[RFC3692](https://datatracker.ietf.org/doc/html/rfc3692)\]\[[RFC4727](https://datatracker.ietf.org/doc/html/rfc4727)\]
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum Ipv6ExtensionSlice<'a> {
     /// IPv6 Hop-by-Hop Option \[[RFC8200](https://datatracker.ietf.org/doc/html/rfc8200)\]
     HopByHop(Ipv6RawExtensionHeaderSlice<'a>),
@@ -837,6 +904,8 @@ pub enum Ipv6ExtensionSlice<'a> {
     DestinationOptions(Ipv6RawExtensionHeaderSlice<'a>),
     /// Authentication Header \[[RFC4302](https://datatracker.ietf.org/doc/html/rfc4302)\]
     Authentication(IpAuthenticationHeaderSlice<'a>),
+    /// Shim6 Protocol \[[RFC5533](https://datatracker.ietf.org/doc/html/rfc5533)\]
+    Shim6(Shim6HeaderSlice<'a>),
 }
 
 impl<'a> IntoIterator for Ipv6ExtensionsSlice<'a> {
@@ -935,6 +1004,16 @@ impl<'a> Iterator for Ipv6ExtensionSliceIter<'a> {
                 self.next_header = slice.next_header();
                 Some(Authentication(slice))
             },
+            SHIM6 => unsafe {
+                let slice = Shim6HeaderSlice::from_slice_unchecked(self.rest);
+                let len = slice.slice().len();
+                self.rest = from_raw_parts(
+                    self.rest.as_ptr().add(len),
+                    self.rest.len() - len
+                );
+                self.next_header = slice.next_header();
+                Some(Shim6(slice))
+            },
             // done parsing, the next header is not a known/supported header extension
             _ => None,
         }
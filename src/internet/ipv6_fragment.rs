@@ -3,7 +3,8 @@ use super::super::*;
 use std::slice::from_raw_parts;
 
 /// IPv6 fragment header.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ipv6FragmentHeader {
     /// IP protocol number specifying the next header or transport layer protocol.
     ///
@@ -33,6 +34,27 @@ impl Ipv6FragmentHeader {
         }
     }
 
+    /// Create a new fragmentation header with the given parameters & checks
+    /// that the `fragment_offset` fits into the 13 bits available in the header.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ipv6FragmentHeader, ip_number, ValueError, ErrorField};
+    ///
+    /// let header = Ipv6FragmentHeader::try_new(ip_number::UDP, 0x1fff, true, 1234).unwrap();
+    /// assert_eq!(0x1fff, header.fragment_offset);
+    ///
+    /// assert_eq!(
+    ///     Err(ValueError::U16TooLarge{ value: 0x2000, max: 0x1fff, field: ErrorField::Ipv6FragmentOffset }),
+    ///     Ipv6FragmentHeader::try_new(ip_number::UDP, 0x2000, true, 1234)
+    /// );
+    /// ```
+    pub fn try_new(next_header: u8, fragment_offset: u16, more_fragments: bool, identification: u32) -> Result<Ipv6FragmentHeader, ValueError> {
+        max_check_u16(fragment_offset, 0b0001_1111_1111_1111u16, ErrorField::Ipv6FragmentOffset)?;
+        Ok(Ipv6FragmentHeader::new(next_header, fragment_offset, more_fragments, identification))
+    }
+
     /// Read an Ipv6FragmentHeader from a slice and return the header & unused parts of the slice.
     pub fn from_slice(slice: &[u8]) -> Result<(Ipv6FragmentHeader, &[u8]), ReadError> {
         let s = Ipv6FragmentHeaderSlice::from_slice(slice)?;
@@ -175,7 +197,7 @@ impl Ipv6FragmentHeader {
 }
 
 /// Slice containing an IPv6 fragment header.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Ipv6FragmentHeaderSlice<'a> {
     /// Slice containing the packet data.
     slice: &'a [u8]
@@ -1,5 +1,6 @@
 use super::super::*;
 
+use arrayvec::ArrayVec;
 use std::fmt::{Debug, Formatter};
 use std::slice::from_raw_parts;
 
@@ -40,6 +41,15 @@ impl Debug for Ipv6RawExtensionHeader {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for Ipv6RawExtensionHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Ipv6RawExtensionHeader {{ next_header: {}, payload: {=[u8]} }}",
+            self.next_header,
+            self.payload())
+    }
+}
+
 impl PartialEq for Ipv6RawExtensionHeader {
     fn eq(&self, other: &Self) -> bool {
         self.next_header == other.next_header &&
@@ -49,6 +59,13 @@ impl PartialEq for Ipv6RawExtensionHeader {
 
 impl Eq for Ipv6RawExtensionHeader {}
 
+impl std::hash::Hash for Ipv6RawExtensionHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.next_header.hash(state);
+        self.payload().hash(state);
+    }
+}
+
 impl Ipv6RawExtensionHeader {
 
     /// Minimum length of a [Ipv6RawExtensionHeader] payload
@@ -57,6 +74,12 @@ impl Ipv6RawExtensionHeader {
     /// Maximum length of a [Ipv6RawExtensionHeader] the payload
     pub const MAX_PAYLOAD_LEN: usize = 0xff*8 + 6;
 
+    /// Minimum length of a serialized [Ipv6RawExtensionHeader] in bytes.
+    pub const MIN_SERIALIZED_SIZE: usize = 2 + Ipv6RawExtensionHeader::MIN_PAYLOAD_LEN;
+
+    /// Maximum length of a serialized [Ipv6RawExtensionHeader] in bytes.
+    pub const MAX_SERIALIZED_SIZE: usize = 2 + Ipv6RawExtensionHeader::MAX_PAYLOAD_LEN;
+
     /// Returns true if the given header type ip number can be represented in an `Ipv6ExtensionHeader`.
     pub fn header_type_supported(next_header: u8) -> bool {
         use crate::ip_number::*;
@@ -164,6 +187,16 @@ impl Ipv6RawExtensionHeader {
     pub fn header_len(&self) -> usize {
         2 + (6 + usize::from(self.header_length)*8)
     }
+
+    /// Returns the serialized header in a stack allocated buffer.
+    pub fn to_bytes(&self) -> ArrayVec<u8, { Ipv6RawExtensionHeader::MAX_SERIALIZED_SIZE }> {
+        let mut result = ArrayVec::new();
+        result.push(self.next_header);
+        result.push(self.header_length);
+        // the payload is at most MAX_PAYLOAD_LEN long, so it always fits
+        result.try_extend_from_slice(self.payload()).unwrap();
+        result
+    }
 }
 
 /// Slice containing an IPv6 extension header without specific decoding methods (fallback in case no specific implementation is available).
@@ -180,7 +213,7 @@ impl Ipv6RawExtensionHeader {
 /// * Mobility
 /// * Host Identity Protocol
 /// * Shim6 Protocol
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Ipv6RawExtensionHeaderSlice<'a> {
     /// Slice containing the packet data.
     slice: &'a [u8],
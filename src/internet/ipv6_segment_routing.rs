@@ -0,0 +1,551 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::fmt::{Debug, Formatter};
+use std::slice::from_raw_parts;
+
+/// IPv6 segment routing header (SRH, routing type 4, RFC 8754).
+///
+/// The segment routing header is an IPv6 routing extension header. It is
+/// stored as an [`Ipv6RawExtensionHeader`] in [`Ipv6Extensions::routing`]
+/// and can be converted from & to one with [`Ipv6SegmentRoutingHeader::from_raw`]
+/// & [`Ipv6SegmentRoutingHeader::to_raw`]. Zero-copy access to the segment
+/// list of a received packet is possible via [`Ipv6SegmentRoutingHeaderSlice`]
+/// & in place modifications via [`Ipv6SegmentRoutingHeaderSliceMut`].
+///
+/// Note that the segment list is encoded in reverse order, the first
+/// segment of the path is the last entry of the list (`Segment List[0]`
+/// is the final destination).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ip_number, IpHeader, Ipv6SegmentRoutingHeader, PacketBuilder, PacketHeaders};
+///
+/// let srh = Ipv6SegmentRoutingHeader::new(
+///     ip_number::UDP, // will be replaced during write
+///     1, // segments left
+///     &[[1;16], [2;16]]
+/// ).unwrap();
+///
+/// let builder = PacketBuilder::
+///     ipv6([0;16], [2;16], 20)
+///     .ipv6_segment_routing(srh.clone())
+///     .udp(21, 1234);
+/// let mut packet = Vec::new();
+/// builder.write(&mut packet, &[1,2,3,4]).unwrap();
+///
+/// let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+/// let exts = match decoded.ip {
+///     Some(IpHeader::Version6(_, exts)) => exts,
+///     _ => panic!("expected an ipv6 header"),
+/// };
+/// let routing = Ipv6SegmentRoutingHeader::from_raw(&exts.routing.unwrap().routing).unwrap();
+/// assert_eq!(srh.segments().collect::<Vec<_>>(), routing.segments().collect::<Vec<_>>());
+/// assert_eq!(Some(&[2;16]), routing.active_segment());
+/// ```
+#[derive(Clone)]
+pub struct Ipv6SegmentRoutingHeader {
+    /// IP protocol number specifying the next header or transport layer protocol.
+    ///
+    /// See [IpNumber] or [ip_number] for a definition of the known values.
+    pub next_header: u8,
+    /// Index (in the segment list) of the next segment to inspect (the
+    /// currently active segment).
+    pub segments_left: u8,
+    /// Flags (no flags are defined in RFC 8754).
+    pub flags: u8,
+    /// Tag used to mark a packet as part of a class or group of packets.
+    pub tag: u16,
+    /// Index of the last element of the segment list.
+    last_entry: u8,
+    /// Length of the header in 8 octets (minus the first 8 octets).
+    header_length: u8,
+    /// Segment list followed by the TLVs (`header_length*8` bytes, stored
+    /// on the heap as the segment list can be up to MAX_DATA_LEN bytes long).
+    data: Vec<u8>,
+}
+
+impl Debug for Ipv6SegmentRoutingHeader {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        formatter.debug_struct("Ipv6SegmentRoutingHeader")
+            .field("next_header", &self.next_header)
+            .field("segments_left", &self.segments_left)
+            .field("flags", &self.flags)
+            .field("tag", &self.tag)
+            .field("segments", &self.segment_list())
+            .field("tlvs", &self.tlvs())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Ipv6SegmentRoutingHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Ipv6SegmentRoutingHeader {{ next_header: {}, segments_left: {}, flags: {}, tag: {}, segments: {=[u8]}, tlvs: {=[u8]} }}",
+            self.next_header,
+            self.segments_left,
+            self.flags,
+            self.tag,
+            self.segment_list(),
+            self.tlvs())
+    }
+}
+
+impl PartialEq for Ipv6SegmentRoutingHeader {
+    fn eq(&self, other: &Self) -> bool {
+        self.next_header == other.next_header &&
+        self.segments_left == other.segments_left &&
+        self.flags == other.flags &&
+        self.tag == other.tag &&
+        self.last_entry == other.last_entry &&
+        self.data() == other.data()
+    }
+}
+
+impl Eq for Ipv6SegmentRoutingHeader {}
+
+impl std::hash::Hash for Ipv6SegmentRoutingHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.next_header.hash(state);
+        self.segments_left.hash(state);
+        self.flags.hash(state);
+        self.tag.hash(state);
+        self.last_entry.hash(state);
+        self.data().hash(state);
+    }
+}
+
+impl Ipv6SegmentRoutingHeader {
+    /// Routing type of the segment routing header.
+    pub const ROUTING_TYPE: u8 = 4;
+
+    /// Length of the fixed part of the header (before the segment list) in bytes.
+    pub const FIXED_LEN: usize = 8;
+
+    /// Length of a segment (IPv6 address) in bytes.
+    pub const SEGMENT_LEN: usize = 16;
+
+    /// Maximum length of the segment list & the TLVs in bytes.
+    pub const MAX_DATA_LEN: usize = 0xff * 8;
+
+    /// Maximum number of segments that fit into a segment routing header.
+    pub const MAX_SEGMENTS: usize = Ipv6SegmentRoutingHeader::MAX_DATA_LEN / Ipv6SegmentRoutingHeader::SEGMENT_LEN;
+
+    /// Minimum length of a serialized segment routing header (a single segment) in bytes.
+    pub const MIN_LEN: usize = Ipv6SegmentRoutingHeader::FIXED_LEN + Ipv6SegmentRoutingHeader::SEGMENT_LEN;
+
+    /// Maximum length of a serialized segment routing header in bytes.
+    pub const MAX_LEN: usize = Ipv6SegmentRoutingHeader::FIXED_LEN + Ipv6SegmentRoutingHeader::MAX_DATA_LEN;
+
+    /// Creates a segment routing header with the given segment list (no flags,
+    /// tag or TLVs).
+    ///
+    /// The segments have to be given in the encoded order (`segments[0]`
+    /// is the final destination). A [`ValueError`] is returned if the list
+    /// is empty or too many segments are given.
+    pub fn new(next_header: u8, segments_left: u8, segments: &[[u8;16]]) -> Result<Ipv6SegmentRoutingHeader, ValueError> {
+        let mut result = Ipv6SegmentRoutingHeader {
+            next_header,
+            segments_left,
+            flags: 0,
+            tag: 0,
+            last_entry: 0,
+            header_length: 2,
+            data: vec![0; Ipv6SegmentRoutingHeader::SEGMENT_LEN],
+        };
+        result.set_segments(segments)?;
+        Ok(result)
+    }
+
+    /// Read a segment routing header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(Ipv6SegmentRoutingHeader, &[u8]), ReadError> {
+        let s = Ipv6SegmentRoutingHeaderSlice::from_slice(slice)?;
+        let rest = &slice[s.slice().len()..];
+        Ok((s.to_header(), rest))
+    }
+
+    /// Decodes the segment routing header contained in a raw IPv6 routing
+    /// extension header (e.g. the routing header of [`Ipv6Extensions`]).
+    ///
+    /// Returns [`ReadError::Ipv6SegmentRoutingUnexpectedType`] if the routing
+    /// header is not a segment routing header.
+    pub fn from_raw(raw: &Ipv6RawExtensionHeader) -> Result<Ipv6SegmentRoutingHeader, ReadError> {
+        let bytes = raw.to_bytes();
+        Ok(Ipv6SegmentRoutingHeaderSlice::from_slice(&bytes)?.to_header())
+    }
+
+    /// Converts the segment routing header to a raw IPv6 extension header
+    /// which can be set as the routing header of [`Ipv6Extensions`].
+    pub fn to_raw(&self) -> Ipv6RawExtensionHeader {
+        // the header length is always 8 octet aligned & within the
+        // limits of a raw extension header
+        Ipv6RawExtensionHeader::new_raw(self.next_header, &self.to_bytes()[2..]).unwrap()
+    }
+
+    /// Index of the last element of the segment list.
+    #[inline]
+    pub fn last_entry(&self) -> u8 {
+        self.last_entry
+    }
+
+    /// Returns the encoded segment list (16 bytes per segment).
+    #[inline]
+    pub fn segment_list(&self) -> &[u8] {
+        &self.data[..self.segment_list_len()]
+    }
+
+    /// Returns an iterator over the segments (in the encoded order).
+    #[inline]
+    pub fn segments(&self) -> Ipv6SegmentsIterator<'_> {
+        Ipv6SegmentsIterator::new(self.segment_list())
+    }
+
+    /// Returns the segment at the given index of the segment list.
+    #[inline]
+    pub fn segment(&self, index: usize) -> Option<&[u8;16]> {
+        segment_at(self.segment_list(), index)
+    }
+
+    /// Returns the segment referenced by `segments_left` (the segment the
+    /// packet is currently forwarded to). `None` is returned if
+    /// `segments_left` is bigger then `last_entry`.
+    #[inline]
+    pub fn active_segment(&self) -> Option<&[u8;16]> {
+        self.segment(usize::from(self.segments_left))
+    }
+
+    /// Replaces the segment list (the TLVs are kept).
+    ///
+    /// A [`ValueError`] is returned & the header is not modified if the
+    /// list is empty or the segments & TLVs do not fit into the header.
+    pub fn set_segments(&mut self, segments: &[[u8;16]]) -> Result<(), ValueError> {
+        use ValueError::*;
+        if segments.is_empty() {
+            return Err(Ipv6SegmentRoutingNoSegments);
+        }
+        let tlvs_len = self.tlvs().len();
+        let segments_len = segments.len()*Ipv6SegmentRoutingHeader::SEGMENT_LEN;
+        if segments_len + tlvs_len > Ipv6SegmentRoutingHeader::MAX_DATA_LEN {
+            return Err(Ipv6ExtensionPayloadTooLarge(6 + segments_len + tlvs_len));
+        }
+        // the tlvs are kept behind the new segment list
+        let mut data = Vec::with_capacity(segments_len + tlvs_len);
+        for segment in segments {
+            data.extend_from_slice(segment);
+        }
+        data.extend_from_slice(self.tlvs());
+        self.data = data;
+        self.last_entry = (segments.len() - 1) as u8;
+        self.header_length = ((segments_len + tlvs_len) / 8) as u8;
+        Ok(())
+    }
+
+    /// Returns the TLVs following the segment list.
+    #[inline]
+    pub fn tlvs(&self) -> &[u8] {
+        &self.data()[self.segment_list_len()..]
+    }
+
+    /// Sets the TLVs following the segment list.
+    ///
+    /// The length of the TLVs has to be a multiple of 8 bytes (add padding
+    /// TLVs if needed). If the length is unaligned or the segments & TLVs
+    /// do not fit into the header a [`ValueError`] is returned & the header
+    /// is not modified.
+    pub fn set_tlvs(&mut self, tlvs: &[u8]) -> Result<(), ValueError> {
+        use ValueError::*;
+        let segments_len = self.segment_list_len();
+        let payload_len = 6 + segments_len + tlvs.len();
+        if segments_len + tlvs.len() > Ipv6SegmentRoutingHeader::MAX_DATA_LEN {
+            Err(Ipv6ExtensionPayloadTooLarge(payload_len))
+        } else if false == tlvs.len().is_multiple_of(8) {
+            Err(Ipv6ExtensionPayloadLengthUnaligned(payload_len))
+        } else {
+            self.data.truncate(segments_len);
+            self.data.extend_from_slice(tlvs);
+            self.header_length = ((segments_len + tlvs.len()) / 8) as u8;
+            Ok(())
+        }
+    }
+
+    /// Length of the header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        Ipv6SegmentRoutingHeader::FIXED_LEN + usize::from(self.header_length)*8
+    }
+
+    /// Returns the serialized header in a stack allocated buffer.
+    pub fn to_bytes(&self) -> ArrayVec<u8, { Ipv6SegmentRoutingHeader::MAX_LEN }> {
+        let tag = self.tag.to_be_bytes();
+        let mut result = ArrayVec::new();
+        // the fixed part & data never exceed MAX_LEN
+        result.try_extend_from_slice(&[
+            self.next_header,
+            self.header_length,
+            Ipv6SegmentRoutingHeader::ROUTING_TYPE,
+            self.segments_left,
+            self.last_entry,
+            self.flags,
+            tag[0],
+            tag[1],
+        ]).unwrap();
+        result.try_extend_from_slice(self.data()).unwrap();
+        result
+    }
+
+    /// Writes the header to the given writer.
+    pub fn write<W: io::Write + Sized>(&self, writer: &mut W) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Segment list & TLVs.
+    #[inline]
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    #[inline]
+    fn segment_list_len(&self) -> usize {
+        (usize::from(self.last_entry) + 1)*Ipv6SegmentRoutingHeader::SEGMENT_LEN
+    }
+}
+
+/// Iterator over the segments of a segment routing header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ipv6SegmentsIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Ipv6SegmentsIterator<'a> {
+    /// Creates an iterator over the given encoded segment list (trailing
+    /// bytes not forming a complete segment are ignored).
+    pub fn new(segment_list: &'a [u8]) -> Ipv6SegmentsIterator<'a> {
+        Ipv6SegmentsIterator { rest: segment_list }
+    }
+}
+
+impl<'a> Iterator for Ipv6SegmentsIterator<'a> {
+    type Item = &'a [u8;16];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = segment_at(self.rest, 0)?;
+        self.rest = &self.rest[Ipv6SegmentRoutingHeader::SEGMENT_LEN..];
+        Some(result)
+    }
+}
+
+/// Returns the segment at the given index of an encoded segment list.
+#[inline]
+fn segment_at(segment_list: &[u8], index: usize) -> Option<&[u8;16]> {
+    let start = index.checked_mul(Ipv6SegmentRoutingHeader::SEGMENT_LEN)?;
+    segment_list
+        .get(start..start.checked_add(Ipv6SegmentRoutingHeader::SEGMENT_LEN)?)
+        .map(|s| s.try_into().unwrap())
+}
+
+/// Checks that the slice starts with a complete segment routing header &
+/// returns the length of the header.
+fn segment_routing_header_len(slice: &[u8]) -> Result<usize, ReadError> {
+    use crate::ReadError::*;
+    let len = Ipv6RawExtensionHeaderSlice::from_slice(slice)?.slice().len();
+    if Ipv6SegmentRoutingHeader::ROUTING_TYPE != slice[2] {
+        return Err(Ipv6SegmentRoutingUnexpectedType(slice[2]));
+    }
+    let segments_len = (usize::from(slice[4]) + 1)*Ipv6SegmentRoutingHeader::SEGMENT_LEN;
+    if segments_len > len - Ipv6SegmentRoutingHeader::FIXED_LEN {
+        return Err(Ipv6SegmentRoutingLastEntryTooLarge(slice[4]));
+    }
+    Ok(len)
+}
+
+/// Slice containing an IPv6 segment routing header (zero-copy access to the
+/// segment list).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ipv6SegmentRoutingHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> Ipv6SegmentRoutingHeaderSlice<'a> {
+    /// Creates a segment routing header slice from a slice (the routing
+    /// type & the last entry field are validated).
+    pub fn from_slice(slice: &'a [u8]) -> Result<Ipv6SegmentRoutingHeaderSlice<'a>, ReadError> {
+        let len = segment_routing_header_len(slice)?;
+        Ok(Ipv6SegmentRoutingHeaderSlice {
+            // SAFETY:
+            // Safe as segment_routing_header_len checks that the slice
+            // has at least the length len.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Returns the slice containing the segment routing header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the IP protocol number of the next header or transport layer protocol.
+    ///
+    /// See [IpNumber] or [ip_number] for a definition of the known values.
+    #[inline]
+    pub fn next_header(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the segments left field.
+    #[inline]
+    pub fn segments_left(&self) -> u8 {
+        self.slice[3]
+    }
+
+    /// Read the last entry field (index of the last element of the segment list).
+    #[inline]
+    pub fn last_entry(&self) -> u8 {
+        self.slice[4]
+    }
+
+    /// Read the flags field.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.slice[5]
+    }
+
+    /// Read the tag field.
+    #[inline]
+    pub fn tag(&self) -> u16 {
+        u16::from_be_bytes([self.slice[6], self.slice[7]])
+    }
+
+    /// Returns the encoded segment list (16 bytes per segment).
+    #[inline]
+    pub fn segment_list(&self) -> &'a [u8] {
+        let end = Ipv6SegmentRoutingHeader::FIXED_LEN +
+            (usize::from(self.last_entry()) + 1)*Ipv6SegmentRoutingHeader::SEGMENT_LEN;
+        &self.slice[Ipv6SegmentRoutingHeader::FIXED_LEN..end]
+    }
+
+    /// Returns an iterator over the segments (in the encoded order).
+    #[inline]
+    pub fn segments(&self) -> Ipv6SegmentsIterator<'a> {
+        Ipv6SegmentsIterator::new(self.segment_list())
+    }
+
+    /// Returns the segment at the given index of the segment list.
+    #[inline]
+    pub fn segment(&self, index: usize) -> Option<&'a [u8;16]> {
+        segment_at(self.segment_list(), index)
+    }
+
+    /// Returns the segment referenced by `segments_left`.
+    #[inline]
+    pub fn active_segment(&self) -> Option<&'a [u8;16]> {
+        self.segment(usize::from(self.segments_left()))
+    }
+
+    /// Returns the TLVs following the segment list.
+    #[inline]
+    pub fn tlvs(&self) -> &'a [u8] {
+        &self.slice[Ipv6SegmentRoutingHeader::FIXED_LEN + self.segment_list().len()..]
+    }
+
+    /// Decode all the fields and copy the results to a [`Ipv6SegmentRoutingHeader`] struct.
+    pub fn to_header(&self) -> Ipv6SegmentRoutingHeader {
+        Ipv6SegmentRoutingHeader {
+            next_header: self.next_header(),
+            segments_left: self.segments_left(),
+            flags: self.flags(),
+            tag: self.tag(),
+            last_entry: self.last_entry(),
+            header_length: self.slice[1],
+            data: self.slice[Ipv6SegmentRoutingHeader::FIXED_LEN..].to_vec(),
+        }
+    }
+}
+
+/// Mutable slice containing an IPv6 segment routing header (allows modifying
+/// the segment list of a packet in place).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ipv6SegmentRoutingHeader, Ipv6SegmentRoutingHeaderSliceMut};
+///
+/// let mut bytes = Ipv6SegmentRoutingHeader::new(0, 1, &[[1;16], [2;16]])
+///     .unwrap()
+///     .to_bytes();
+///
+/// let mut srh = Ipv6SegmentRoutingHeaderSliceMut::from_slice(&mut bytes).unwrap();
+/// // SRv6 endpoint: move on to the next segment
+/// assert_eq!(Some([1;16]), srh.advance());
+/// assert_eq!(0, srh.segments_left());
+/// assert_eq!(None, srh.advance());
+/// ```
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct Ipv6SegmentRoutingHeaderSliceMut<'a> {
+    slice: &'a mut [u8],
+}
+
+impl<'a> Ipv6SegmentRoutingHeaderSliceMut<'a> {
+    /// Creates a mutable segment routing header slice from a slice (the
+    /// routing type & the last entry field are validated).
+    pub fn from_slice(slice: &'a mut [u8]) -> Result<Ipv6SegmentRoutingHeaderSliceMut<'a>, ReadError> {
+        let len = segment_routing_header_len(slice)?;
+        Ok(Ipv6SegmentRoutingHeaderSliceMut {
+            slice: &mut slice[..len],
+        })
+    }
+
+    /// Returns a read only view of the header.
+    #[inline]
+    pub fn as_slice(&self) -> Ipv6SegmentRoutingHeaderSlice<'_> {
+        Ipv6SegmentRoutingHeaderSlice { slice: self.slice }
+    }
+
+    /// Read the segments left field.
+    #[inline]
+    pub fn segments_left(&self) -> u8 {
+        self.slice[3]
+    }
+
+    /// Sets the segments left field.
+    #[inline]
+    pub fn set_segments_left(&mut self, segments_left: u8) {
+        self.slice[3] = segments_left;
+    }
+
+    /// Sets the flags field.
+    #[inline]
+    pub fn set_flags(&mut self, flags: u8) {
+        self.slice[5] = flags;
+    }
+
+    /// Sets the tag field.
+    #[inline]
+    pub fn set_tag(&mut self, tag: u16) {
+        self.slice[6..8].copy_from_slice(&tag.to_be_bytes());
+    }
+
+    /// Returns a mutable reference to the segment at the given index of the
+    /// segment list.
+    pub fn segment_mut(&mut self, index: usize) -> Option<&mut [u8;16]> {
+        if index > usize::from(self.slice[4]) {
+            return None;
+        }
+        let start = Ipv6SegmentRoutingHeader::FIXED_LEN + index*Ipv6SegmentRoutingHeader::SEGMENT_LEN;
+        Some((&mut self.slice[start..start + Ipv6SegmentRoutingHeader::SEGMENT_LEN]).try_into().unwrap())
+    }
+
+    /// Decrements the segments left field & returns the new active segment
+    /// (the new destination address of the packet).
+    ///
+    /// `None` is returned & the header is not modified if no segments are
+    /// left or `segments_left` is bigger then `last_entry + 1`.
+    pub fn advance(&mut self) -> Option<[u8;16]> {
+        let segments_left = self.segments_left().checked_sub(1)?;
+        let result = *self.as_slice().segment(usize::from(segments_left))?;
+        self.set_segments_left(segments_left);
+        Some(result)
+    }
+}
@@ -0,0 +1,183 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Message types of Shim6 control messages (RFC 5533).
+pub mod shim6_message_type {
+    /// First message of the context establishment exchange.
+    pub const I1: u8 = 1;
+    /// Response to an I1 message.
+    pub const R1: u8 = 2;
+    /// Second message of the context establishment exchange.
+    pub const I2: u8 = 3;
+    /// Response to an I2 message (completes the context establishment).
+    pub const R2: u8 = 4;
+    /// Response to a message referencing an unknown context.
+    pub const R1BIS: u8 = 5;
+    /// Response to an R1bis message.
+    pub const I2BIS: u8 = 6;
+    /// Request for an update of the locator set.
+    pub const UPDATE_REQUEST: u8 = 64;
+    /// Acknowledgement of an update request.
+    pub const UPDATE_ACKNOWLEDGEMENT: u8 = 65;
+    /// Keepalive message (failure detection).
+    pub const KEEPALIVE: u8 = 66;
+    /// Probe message (failure detection & recovery).
+    pub const PROBE: u8 = 67;
+}
+
+/// Slice containing a Shim6 header (ip number 140, RFC 5533).
+///
+/// A Shim6 header is either a payload extension header (P bit set), which
+/// carries the receiver context tag & is followed by the upper layer data,
+/// or a control message (P bit not set) used to establish & maintain the
+/// Shim6 context.
+///
+/// Both use the "next header" & "header length" layout of the other ipv6
+/// extension headers (see [`Ipv6RawExtensionHeaderSlice`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Shim6HeaderSlice, ip_number};
+///
+/// let header = [
+///     ip_number::UDP, 0, // next header & header length
+///     0x80 | 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, // p bit & receiver context tag
+/// ];
+/// let shim6 = Shim6HeaderSlice::from_slice(&header).unwrap();
+/// assert_eq!(ip_number::UDP, shim6.next_header());
+/// assert!(shim6.is_payload_extension());
+/// assert_eq!(Some(0x1234_5678_9abc), shim6.receiver_context_tag());
+/// assert_eq!(None, shim6.message_type());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Shim6HeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> Shim6HeaderSlice<'a> {
+    /// Creates a Shim6 header slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<Shim6HeaderSlice<'a>, ReadError> {
+        // the shim6 header uses the same length encoding as the raw extension headers
+        let raw = Ipv6RawExtensionHeaderSlice::from_slice(slice)?;
+        Ok(Shim6HeaderSlice {
+            slice: raw.slice(),
+        })
+    }
+
+    /// Creates a Shim6 header slice from a slice (assumes slice size & content
+    /// was validated before).
+    ///
+    /// # Safety
+    ///
+    /// This method assumes that the slice was previously validated to contain
+    /// a valid Shim6 header. This means the slice length must at least be at
+    /// least 8 and `(slice[1] + 1)*8`.
+    pub unsafe fn from_slice_unchecked(slice: &'a [u8]) -> Shim6HeaderSlice<'a> {
+        Shim6HeaderSlice {
+            slice: from_raw_parts(
+                slice.as_ptr(),
+                ((*slice.get_unchecked(1) as usize) + 1)*8
+            )
+        }
+    }
+
+    /// Returns the slice containing the Shim6 header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the IP protocol number of the next header (59 "no next header"
+    /// for control messages).
+    ///
+    /// See [IpNumber] or [ip_number] for a definition of the known values.
+    #[inline]
+    pub fn next_header(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Length of the header in 8 octets (minus the first 8 octets).
+    #[inline]
+    pub fn header_length(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Returns true if the header is a payload extension header (P bit is set)
+    /// and false if it is a control message.
+    #[inline]
+    pub fn is_payload_extension(&self) -> bool {
+        0 != self.slice[2] & 0x80
+    }
+
+    /// Receiver context tag (47 bit value) if the header is a payload extension header.
+    pub fn receiver_context_tag(&self) -> Option<u64> {
+        if self.is_payload_extension() {
+            Some(u64::from_be_bytes([
+                0,
+                0,
+                self.slice[2] & 0x7f,
+                self.slice[3],
+                self.slice[4],
+                self.slice[5],
+                self.slice[6],
+                self.slice[7],
+            ]))
+        } else {
+            None
+        }
+    }
+
+    /// Message type (see [shim6_message_type]) if the header is a control message.
+    #[inline]
+    pub fn message_type(&self) -> Option<u8> {
+        if self.is_payload_extension() {
+            None
+        } else {
+            Some(self.slice[2] & 0x7f)
+        }
+    }
+
+    /// Message type specific bits (7 bit value) if the header is a control message.
+    #[inline]
+    pub fn type_specific(&self) -> Option<u8> {
+        if self.is_payload_extension() {
+            None
+        } else {
+            Some(self.slice[3] >> 1)
+        }
+    }
+
+    /// Checksum if the header is a control message.
+    #[inline]
+    pub fn checksum(&self) -> Option<u16> {
+        if self.is_payload_extension() {
+            None
+        } else {
+            Some(u16::from_be_bytes([self.slice[4], self.slice[5]]))
+        }
+    }
+
+    /// Message type specific data & options of a control message (starting
+    /// after the checksum). For payload extension headers the data after the
+    /// receiver context tag is returned (usually empty).
+    #[inline]
+    pub fn message_data(&self) -> &'a [u8] {
+        if self.is_payload_extension() {
+            &self.slice[8..]
+        } else {
+            &self.slice[6..]
+        }
+    }
+
+    /// Decode some of the fields and copy the results to a
+    /// [`Ipv6RawExtensionHeader`] struct.
+    pub fn to_header(&self) -> Ipv6RawExtensionHeader {
+        // SAFETY:
+        // Length & content validated in from_slice.
+        unsafe {
+            Ipv6RawExtensionHeaderSlice::from_slice_unchecked(self.slice)
+        }.to_header()
+    }
+}
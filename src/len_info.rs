@@ -0,0 +1,235 @@
+use super::*;
+
+/// Length accounting of a sliced packet (wire, captured & per layer lengths).
+///
+/// Collects all the lengths that are relevant when reasoning about truncated
+/// captures, ethernet padding or offloading (e.g. TSO/GRO packets with
+/// length fields that do not match the data) in one place. The lengths are
+/// computed from the outermost headers of a [`SlicedPacket`] & the data it
+/// was sliced from. Inconsistencies between the declared & the actually
+/// present lengths are reported via [`LenInfo::discrepancies`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{LenInfo, PacketBuilder, PacketMeta, SlicedPacket};
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .write(&mut packet, &[1,2,3,4])
+///     .unwrap();
+/// // ethernet padding to the minimum frame size
+/// packet.resize(60, 0);
+///
+/// // capture data from e.g. a pcap record
+/// let meta = PacketMeta::from_captured(&packet);
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// let info = LenInfo::new(meta.original_len, &packet, &sliced);
+/// assert_eq!(Some(14), info.link_headers_len);
+/// assert_eq!(Some(20), info.ip_header_len);
+/// assert_eq!(Some(8), info.transport_header_len);
+/// assert_eq!(Some(12), info.l3_payload_declared_len);
+/// assert_eq!(Some(26), info.l3_payload_available_len);
+///
+/// let discrepancies = info.discrepancies();
+/// assert!(discrepancies.l3_trailing_bytes);
+/// assert!(false == discrepancies.capture_truncated);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct LenInfo {
+    /// Length of the packet on the wire in bytes.
+    pub wire_len: usize,
+    /// Number of bytes of the packet that were captured (length of the data).
+    pub captured_len: usize,
+    /// Length of all headers before the ip header (ethernet, vlan, mpls, ...)
+    /// in bytes (`None` if no ip header is present).
+    pub link_headers_len: Option<usize>,
+    /// Length of the ip header (including IPv4 options but without extension
+    /// headers) in bytes.
+    pub ip_header_len: Option<usize>,
+    /// Length of the ip extension headers in bytes.
+    pub ip_extensions_len: Option<usize>,
+    /// Length of the transport header in bytes (`None` if no or an
+    /// unknown transport protocol is present).
+    pub transport_header_len: Option<usize>,
+    /// Payload length declared in the ip header (IPv4 total length minus the
+    /// header length or the IPv6 payload length) in bytes. The payload
+    /// includes the ip extension headers.
+    pub l3_payload_declared_len: Option<usize>,
+    /// Number of captured bytes after the ip header (the actually available
+    /// l3 payload, including ip extension headers & trailing bytes).
+    pub l3_payload_available_len: Option<usize>,
+    /// Length declared in the transport header (UDP length field) in bytes.
+    pub transport_declared_len: Option<usize>,
+    /// Length of the payload of the sliced packet in bytes.
+    pub payload_len: usize,
+}
+
+/// Inconsistencies between the declared & the present lengths of a packet
+/// (see [`LenInfo::discrepancies`]).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct LenDiscrepancies {
+    /// Less bytes were captured then were present on the wire.
+    pub capture_truncated: bool,
+    /// Less bytes are available after the ip header then declared in the ip header.
+    pub l3_payload_truncated: bool,
+    /// More bytes are available after the ip header then declared in the
+    /// ip header (e.g. ethernet padding or trailers).
+    pub l3_trailing_bytes: bool,
+    /// The ip header declares a payload length of zero while payload is
+    /// present (e.g. segmentation offloading or IPv6 jumbograms).
+    pub l3_declared_len_zero: bool,
+    /// The transport length field (UDP length) does not match the number
+    /// of bytes available for the transport layer.
+    pub transport_len_mismatch: bool,
+}
+
+impl LenDiscrepancies {
+    /// Returns true if no discrepancy was detected.
+    pub fn is_empty(&self) -> bool {
+        *self == LenDiscrepancies::default()
+    }
+}
+
+impl LenInfo {
+    /// Computes the length information of a packet that was sliced from
+    /// `data`, with `wire_len` as the length of the packet on the wire
+    /// (e.g. [`PacketMeta::original_len`]).
+    ///
+    /// `packet` has to be the result of slicing `data`, otherwise the
+    /// layer lengths that depend on the position of the headers in the
+    /// data are set to `None`.
+    pub fn new(wire_len: usize, data: &[u8], packet: &SlicedPacket) -> LenInfo {
+        use crate::InternetSlice::*;
+        use crate::TransportSlice::*;
+
+        let captured_len = data.len();
+        let mut result = LenInfo {
+            wire_len,
+            captured_len,
+            payload_len: packet.payload.len(),
+            ..Default::default()
+        };
+
+        if let Some(ip) = &packet.ip {
+            let (ip_slice, extensions_len, declared) = match ip {
+                Ipv4(header, extensions) => (
+                    header.slice(),
+                    extensions.auth.as_ref().map(|a| a.slice().len()).unwrap_or(0),
+                    usize::from(header.total_len()).saturating_sub(header.slice().len()),
+                ),
+                Ipv6(header, extensions) => (
+                    header.slice(),
+                    extensions.slice().len(),
+                    usize::from(header.payload_length()),
+                ),
+            };
+            result.ip_header_len = Some(ip_slice.len());
+            result.ip_extensions_len = Some(extensions_len);
+            result.l3_payload_declared_len = Some(declared);
+            if let Some(start) = offset_in(data, ip_slice) {
+                result.link_headers_len = Some(start);
+                result.l3_payload_available_len = Some(captured_len.saturating_sub(start + ip_slice.len()));
+            }
+        }
+
+        if let Some(transport) = &packet.transport {
+            result.transport_header_len = match transport {
+                Icmpv4(slice) => Some(slice.header_len()),
+                Icmpv6(slice) => Some(slice.header_len()),
+                Udp(slice) => {
+                    result.transport_declared_len = Some(usize::from(slice.length()));
+                    Some(slice.slice().len())
+                },
+                Tcp(slice) => Some(slice.slice().len()),
+                Igmp(slice) => Some(slice.slice().len()),
+                Sctp(slice) => Some(slice.slice().len()),
+                UdpLite(slice) => Some(slice.slice().len()),
+                Dccp(slice) => Some(slice.slice().len()),
+                Unknown(_) => None,
+            };
+        }
+
+        result
+    }
+
+    /// Computes the length information of a packet that was captured
+    /// completely (the wire length is the length of `data`).
+    #[inline]
+    pub fn from_sliced(data: &[u8], packet: &SlicedPacket) -> LenInfo {
+        LenInfo::new(data.len(), data, packet)
+    }
+
+    /// Returns true if less bytes were captured then were present on the wire.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.captured_len < self.wire_len
+    }
+
+    /// Number of bytes declared in the ip header that are missing in the
+    /// captured data.
+    pub fn l3_payload_missing_len(&self) -> Option<usize> {
+        Some(self.l3_payload_declared_len?.saturating_sub(self.l3_payload_available_len?))
+    }
+
+    /// Number of bytes present after the ip payload declared in the ip
+    /// header (e.g. ethernet padding).
+    pub fn l3_trailing_len(&self) -> Option<usize> {
+        let declared = self.l3_payload_declared_len?;
+        if 0 == declared {
+            // a zero length is not checked for trailing bytes (see
+            // LenDiscrepancies::l3_declared_len_zero)
+            Some(0)
+        } else {
+            Some(self.l3_payload_available_len?.saturating_sub(declared))
+        }
+    }
+
+    /// Returns the inconsistencies between the declared & the present lengths.
+    pub fn discrepancies(&self) -> LenDiscrepancies {
+        let mut result = LenDiscrepancies {
+            capture_truncated: self.is_truncated(),
+            ..Default::default()
+        };
+        if let (Some(declared), Some(available)) = (self.l3_payload_declared_len, self.l3_payload_available_len) {
+            if 0 == declared && 0 < available {
+                result.l3_declared_len_zero = true;
+            } else {
+                result.l3_payload_truncated = available < declared;
+                result.l3_trailing_bytes = available > declared;
+            }
+        }
+        if let Some(declared) = self.transport_declared_len {
+            if let Some(available) = self.transport_available_len() {
+                result.transport_len_mismatch = declared != available;
+            }
+        }
+        result
+    }
+
+    /// Number of bytes available for the transport layer (limited by the
+    /// declared ip payload length if it is not zero).
+    fn transport_available_len(&self) -> Option<usize> {
+        let available = self.l3_payload_available_len?;
+        let declared = self.l3_payload_declared_len?;
+        let l3_len = if 0 == declared {
+            available
+        } else {
+            available.min(declared)
+        };
+        Some(l3_len.saturating_sub(self.ip_extensions_len?))
+    }
+}
+
+/// Returns the offset of `slice` in `data` (`None` if the slice is not part of data).
+pub(crate) fn offset_in(data: &[u8], slice: &[u8]) -> Option<usize> {
+    let offset = (slice.as_ptr() as usize).checked_sub(data.as_ptr() as usize)?;
+    if offset + slice.len() <= data.len() {
+        Some(offset)
+    } else {
+        None
+    }
+}
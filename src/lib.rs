@@ -18,6 +18,17 @@
 //! etherparse = "0.13"
 //! ```
 //!
+//! ## Optional Features
+//!
+//! * `arena`: Adds `PacketArena`, a slab storage keeping owned packets in one contiguous buffer (referenced via `PacketHandle`).
+//! * `base64`: Adds `PacketWithMeta::from_base64` to decode base64 encoded packets.
+//! * `bytes`: Adds `BytesSlicedPacket`, an owned packet view based on `bytes::Bytes`.
+//! * `defmt`: Implements `defmt::Format` for the header types.
+//! * `hex`: Adds `PacketWithMeta::from_hex_str` to decode hex encoded packets (e.g. the wireshark "Copy as Hex Stream" output).
+//! * `names`: Adds the `names` module with IANA based name tables for ether types, ip numbers, well known ports & ICMP messages (also used by `PacketSummary`).
+//! * `pacing`: Adds `Pacer`, an iterator wrapper that replays packets with a limited rate (`TokenBucket`) or the recorded inter-arrival times.
+//! * `zerocopy`: Adds fixed size header overlays (e.g. `Ethernet2HeaderOverlay`) implementing the `zerocopy` traits.
+//!
 //! # What is etherparse?
 //! Etherparse is intended to provide the basic network parsing functions that allow for easy analysis, transformation or generation of recorded network data.
 //! 
@@ -66,6 +77,9 @@
 //! * [`SlicedPacket::from_ethernet`] for parsing from an Ethernet II header downwards
 //! * [`SlicedPacket::from_ether_type`] for parsing a slice starting after an Ethernet II header
 //! * [`SlicedPacket::from_ip`] for parsing from an IPv4 or IPv6 downwards
+//! * [`SlicedPacket::from_linux_sll`] & [`SlicedPacket::from_linux_sll2`] for parsing from a Linux cooked capture header downwards
+//! * [`SlicedPacket::from_null_loopback`] for parsing from a BSD loopback header downwards
+//! * [`SlicedPacket::from_radiotap`] & [`SlicedPacket::from_ieee80211`] for parsing from a radiotap or IEEE 802.11 header downwards
 //!
 //! ## Deserializing all headers into structs
 //! This option deserializes all known headers and transferes their contents to header structs.
@@ -102,6 +116,9 @@
 //! * [`PacketHeaders::from_ethernet_slice`] for parsing from an Ethernet II header downwards
 //! * [`PacketHeaders::from_ether_type`] for parsing a slice starting after an Ethernet II header
 //! * [`PacketHeaders::from_ip_slice`] for parsing from an IPv4 or IPv6 downwards
+//! * [`PacketHeaders::from_linux_sll_slice`] & [`PacketHeaders::from_linux_sll2_slice`] for parsing from a Linux cooked capture header downwards
+//! * [`PacketHeaders::from_null_loopback_slice`] for parsing from a BSD loopback header downwards
+//! * [`PacketHeaders::from_radiotap_slice`] & [`PacketHeaders::from_ieee80211_slice`] for parsing from a radiotap or IEEE 802.11 header downwards
 //!
 //! ## Manually slicing & parsing packets
 //! It is also possible to manually slice & parse a packet. For each header type there is are metods that create a slice or struct from a memory slice. 
@@ -118,10 +135,16 @@
 //! * [`Ipv6RawExtensionHeaderSlice::from_slice`]
 //! * [`IpAuthenticationHeaderSlice::from_slice`]
 //! * [`Ipv6FragmentHeaderSlice::from_slice`]
+//! * [`Ipv6SegmentRoutingHeaderSlice::from_slice`]
 //! * [`UdpHeaderSlice::from_slice`]
+//! * [`UdpLiteHeaderSlice::from_slice`]
+//! * [`DccpHeaderSlice::from_slice`]
 //! * [`TcpHeaderSlice::from_slice`]
+//! * [`SctpHeaderSlice::from_slice`]
 //! * [`Icmpv4Slice::from_slice`]
 //! * [`Icmpv6Slice::from_slice`]
+//! * [`IgmpSlice::from_slice`]
+//! * [`VrrpSlice::from_slice_ipv4`] & [`VrrpSlice::from_slice_ipv6`]
 //!
 //! And for deserialization into the corresponding header structs have a look at:
 //!
@@ -136,8 +159,12 @@
 //! * [`Ipv6RawExtensionHeader::read`] & [`Ipv6RawExtensionHeader::from_slice`]
 //! * [`IpAuthenticationHeader::read`] & [`IpAuthenticationHeader::from_slice`]
 //! * [`Ipv6FragmentHeader::read`] & [`Ipv6FragmentHeader::from_slice`]
+//! * [`Ipv6SegmentRoutingHeader::from_slice`]
 //! * [`UdpHeader::read`] & [`UdpHeader::from_slice`]
+//! * [`UdpLiteHeader::read`] & [`UdpLiteHeader::from_slice`]
+//! * [`DccpHeader::read`] & [`DccpHeader::from_slice`]
 //! * [`TcpHeader::read`] & [`TcpHeader::from_slice`]
+//! * [`SctpHeader::read`] & [`SctpHeader::from_slice`]
 //! * [`Icmpv4Header::read`] & [`Icmpv4Header::from_slice`]
 //! * [`Icmpv6Header::read`] & [`Icmpv6Header::from_slice`]
 //!
@@ -190,10 +217,15 @@
 //! * [`Ipv6RawExtensionHeader::write`]
 //! * [`IpAuthenticationHeader::write`]
 //! * [`Ipv6FragmentHeader::write`]
+//! * [`Ipv6SegmentRoutingHeader::write`]
 //! * [`UdpHeader::write`]
+//! * [`UdpLiteHeader::write`]
+//! * [`DccpHeader::write`]
 //! * [`TcpHeader::write`]
+//! * [`SctpHeader::write`]
 //! * [`Icmpv4Header::write`]
 //! * [`Icmpv6Header::write`]
+//! * [`VrrpHeader::write_ipv4`] & [`VrrpHeader::write_ipv6`]
 //!
 //! # Roadmap
 //! * Documentation
@@ -210,6 +242,8 @@
 //! * [Internet Protocol Version 6 (IPv6) Parameters](https://www.iana.org/assignments/ipv6-parameters/ipv6-parameters.xhtml)
 //! * [Wikipedia IEEE_802.1Q](https://en.wikipedia.org/w/index.php?title=IEEE_802.1Q&oldid=820983900)
 //! * User Datagram Protocol (UDP) [RFC 768](https://tools.ietf.org/html/rfc768)
+//! * The Lightweight User Datagram Protocol (UDP-Lite) [RFC 3828](https://tools.ietf.org/html/rfc3828)
+//! * Datagram Congestion Control Protocol (DCCP) [RFC 4340](https://tools.ietf.org/html/rfc4340)
 //! * Transmission Control Protocol [RFC 793](https://tools.ietf.org/html/rfc793)
 //! * TCP Extensions for High Performance [RFC 7323](https://tools.ietf.org/html/rfc7323)
 //! * The Addition of Explicit Congestion Notification (ECN) to IP [RFC 3168](https://tools.ietf.org/html/rfc3168)
@@ -228,6 +262,9 @@
 //! * [Internet Control Message Protocol version 6 (ICMPv6) Parameters](https://www.iana.org/assignments/icmpv6-parameters/icmpv6-parameters.xhtml)
 //! * Multicast Listener Discovery (MLD) for IPv6 [RFC 2710](https://datatracker.ietf.org/doc/html/rfc2710)
 //! * Neighbor Discovery for IP version 6 (IPv6) [RFC 4861](https://datatracker.ietf.org/doc/html/rfc4861)
+//! * Internet Group Management Protocol, Version 2 [RFC 2236](https://datatracker.ietf.org/doc/html/rfc2236)
+//! * Internet Group Management Protocol, Version 3 [RFC 3376](https://datatracker.ietf.org/doc/html/rfc3376)
+//! * Stream Control Transmission Protocol [RFC 9260](https://datatracker.ietf.org/doc/html/rfc9260)
 
 // # Reason for 'bool_comparison' disable:
  //
@@ -255,28 +292,79 @@ mod link;
 pub use crate::link::LinkSlice;
 pub use crate::link::ethernet::*;
 pub use crate::link::vlan_tagging::*;
+pub use crate::link::redundancy_tag::*;
+pub use crate::link::mac_control::*;
+pub use crate::link::mpls::*;
+pub use crate::link::arp::*;
+pub use crate::link::isis::*;
+pub use crate::link::ppp::*;
+pub use crate::link::nsh::*;
+pub use crate::link::pppoe::*;
+pub use crate::link::macsec::*;
+pub use crate::link::linux_sll::*;
+pub use crate::link::null_loopback::*;
+pub use crate::link::vj_compression::*;
+pub use crate::link::llc_snap::*;
+pub use crate::link::radiotap::*;
+pub use crate::link::ieee802_11::*;
+pub use crate::link::lldp::*;
+pub use crate::link::preemption::*;
+pub use crate::link::ipx::*;
+pub use crate::link::appletalk::*;
+pub use crate::link::eapol::*;
 
 mod internet;
+pub use crate::internet::esp::*;
+pub use crate::internet::erspan::*;
+pub use crate::internet::gre::*;
+pub use crate::internet::hip::*;
 pub use crate::internet::ip::*;
 pub use crate::internet::ip_authentication::*;
 pub use crate::internet::ipv4::*;
 pub use crate::internet::ipv4_extensions::*;
 pub use crate::internet::ipv6::*;
+pub use crate::internet::ipv6_address::*;
 pub use crate::internet::ipv6_extensions::*;
 pub use crate::internet::ipv6_raw_extension::*;
 pub use crate::internet::ipv6_fragment::*;
+pub use crate::internet::ipv6_segment_routing::*;
+pub use crate::internet::shim6::*;
 
 mod transport;
 pub use crate::transport::icmp::*;
 pub use crate::transport::icmpv4_impl::*;
 pub use crate::transport::icmpv6_impl::*;
+pub use crate::transport::icmpv6_ndp::*;
+pub use crate::transport::igmp_impl::*;
+pub use crate::transport::ping_payload::*;
+pub use crate::transport::sctp::*;
 pub use crate::transport::tcp::*;
+pub use crate::transport::tcp_analysis::*;
+pub use crate::transport::tcp_syn_cookie::*;
 pub use crate::transport::udp::*;
+pub use crate::transport::udp_lite::*;
+pub use crate::transport::dccp::*;
+pub use crate::transport::vrrp_impl::*;
 pub use crate::transport::TransportHeader;
 
+mod application;
+pub use crate::application::dns_name::*;
+pub use crate::application::geneve::*;
+pub use crate::application::gtpu::*;
+pub use crate::application::hsrp::*;
+pub use crate::application::quic::*;
+pub use crate::application::rip::*;
+pub use crate::application::stamp::*;
+pub use crate::application::vxlan::*;
+
 /// Helpers for calculating checksums.
 pub mod checksum;
 
+mod bit_field;
+pub use crate::bit_field::*;
+
+pub mod hex_dump;
+
 mod packet_builder;
 pub use crate::packet_builder::*;
 
@@ -286,8 +374,94 @@ pub use crate::packet_decoder::*;
 mod packet_slicing;
 pub use crate::packet_slicing::*;
 
+mod packet_meta;
+pub use crate::packet_meta::*;
+
+mod len_info;
+pub use crate::len_info::*;
+
+mod packet_summary;
+pub use crate::packet_summary::*;
+
+mod checksum_verify;
+pub use crate::checksum_verify::*;
+
+mod controller_packet;
+pub use crate::controller_packet::*;
+
+mod flow_key;
+pub use crate::flow_key::*;
+
+mod flow_streams;
+pub use crate::flow_streams::*;
+
+mod flow_hash;
+pub use crate::flow_hash::*;
+
+mod bond_hash;
+pub use crate::bond_hash::*;
+
+mod packet_set;
+pub use crate::packet_set::*;
+
+mod resumable_slicing;
+pub use crate::resumable_slicing::*;
+
+mod path_mtu;
+pub use crate::path_mtu::*;
+
+mod parser_registry;
+pub use crate::parser_registry::*;
+
+mod parse_limits;
+pub use crate::parse_limits::*;
+
+mod ether_type_length_policy;
+pub use crate::ether_type_length_policy::*;
+
+mod siphash;
+
+mod dedup;
+pub use crate::dedup::*;
+
+#[cfg(any(feature = "hex", feature = "base64"))]
+mod packet_encoding;
+#[cfg(any(feature = "hex", feature = "base64"))]
+pub use crate::packet_encoding::*;
+
+#[cfg(feature = "bytes")]
+mod bytes_packet;
+#[cfg(feature = "bytes")]
+pub use crate::bytes_packet::*;
+
+#[cfg(feature = "zerocopy")]
+mod zerocopy_overlay;
+#[cfg(feature = "zerocopy")]
+pub use crate::zerocopy_overlay::*;
+
+#[cfg(feature = "pacing")]
+mod pacing;
+#[cfg(feature = "pacing")]
+pub use crate::pacing::*;
+
+#[cfg(feature = "arena")]
+mod arena;
+#[cfg(feature = "arena")]
+pub use crate::arena::*;
+
+#[cfg(feature = "names")]
+pub mod names;
+
 pub mod packet_filter;
 
+pub mod roundtrip;
+
+pub mod repair;
+
+pub mod rewrite;
+
+pub mod replay;
+
 ///Contains the size when serialized.
 pub trait SerializedSize {
     const SERIALIZED_SIZE: usize;
@@ -328,6 +502,58 @@ pub enum ReadError {
     /// This error can be triggered by
     /// * `Icmpv6Slice::from_slice`
     Icmpv6PacketTooBig(usize),
+    ///Error if the header length of a HIP header is smaller then the minimum size of 4 (40 bytes).
+    HipHeaderLengthTooSmall(u8),
+    ///Error if a parameter of a HIP header exceeds the header length. The value is the offset of the parameter in the header.
+    HipParameterLengthTooBig(usize),
+    ///Error if the version field of a GRE header is not 0 (only version 0 is supported). The value is the version that was received.
+    GreUnsupportedVersion(u8),
+    ///Error if the routing present bit of a GRE header is set (the routing fields of RFC 1701 are not supported).
+    GreRoutingNotSupported,
+    ///Error if the version field of a GENEVE header is not 0 (only version 0 is supported). The value is the version that was received.
+    GeneveUnsupportedVersion(u8),
+    ///Error if an option of a GENEVE header exceeds the options length. The value is the offset of the option in the options.
+    GeneveOptionLengthTooBig(usize),
+    ///Error if the version field of a NSH header is not 0 (only version 0 is supported). The value is the version that was received.
+    NshUnsupportedVersion(u8),
+    ///Error if the length field of a NSH header is too small or does not match the MD type (MD type 1 headers have a fixed length of 6).
+    NshLengthBad{ md_type: u8, length: u8 },
+    ///Error if a metadata TLV of a NSH header exceeds the header length. The value is the offset of the TLV in the metadata.
+    NshMetadataLengthTooBig(usize),
+    ///Error if the version or type field of a PPPoE header is not 1 (only RFC 2516 headers are supported).
+    PppoeUnsupportedVersion{ version: u8, pppoe_type: u8 },
+    ///Error if the payload length of a PPPoE session data packet is smaller then 2 (the length of the PPP protocol field). The value is the payload length that was received.
+    PppoePayloadLengthTooSmall(u16),
+    ///Error if the version field of a radiotap header is not 0 (only version 0 is supported). The value is the version that was received.
+    RadiotapUnsupportedVersion(u8),
+    ///Error if the length field of a radiotap header is smaller then the minimum header length of 8 or too small to contain the present flags. The value is the length that was received.
+    RadiotapLengthTooSmall(u16),
+    ///Error if the protocol version in the frame control of an IEEE 802.11 header is not 0. The value is the version that was received.
+    Ieee80211UnsupportedVersion(u8),
+    ///Error if an 802.2 LLC header is not a SNAP header (DSAP & SSAP 0xaa, control 0x03).
+    LlcNotSnap{ dsap: u8, ssap: u8, control: u8 },
+    ///Error if the version field of a GTP-U header is not 1 (only GTPv1-U is supported). The value is the version that was received.
+    GtpuUnsupportedVersion(u8),
+    ///Error if the length field of a GTP-U extension header is 0 or the extension headers exceed the maximum supported length of 252 bytes. The value is the offset of the extension header in the GTP-U header.
+    GtpuExtensionLengthBad(usize),
+    ///Error if the routing type of an IPv6 routing header is not 4 when decoding it as a segment routing header. The value is the routing type that was received.
+    Ipv6SegmentRoutingUnexpectedType(u8),
+    ///Error if the segment list of an IPv6 segment routing header (as indicated by the last entry field) does not fit into the header. The value is the last entry field that was received.
+    Ipv6SegmentRoutingLastEntryTooLarge(u8),
+    ///Error if the type field of a DCCP header contains a reserved value (10-15). The value is the type that was received.
+    DccpPacketTypeReserved(u8),
+    ///Error if the data offset field of a DCCP header is smaller then the length of the fixed part of the header (depends on the packet type & sequence number length). The value is the data offset that was received.
+    DccpDataOffsetTooSmall(u8),
+    ///Error if the version of a VRRP message is not supported (2 & 3 for IPv4, only 3 for IPv6). The value is the version that was received.
+    VrrpUnsupportedVersion(u8),
+    ///Error if the version of an ERSPAN header is not supported (only 1 for type II & 2 for type III headers are supported). The value is the version that was received.
+    ErspanUnsupportedVersion(u8),
+    ///Error if the version bit of a MACsec SecTAG is set (only version 0 is supported).
+    MacsecUnsupportedVersion,
+    ///Error if a packet exceeds one of the limits of the [`ParseLimits`] set in the [`ParserRegistry`].
+    ParseLimitExceeded(ParseLimit),
+    ///Error if the type/length field of an ethernet header contains a value between 1501 & 1535 (neither a length nor an ether type) & the [`EtherTypeLengthPolicy::Ieee8023Strict`] policy is used. The value is the one that was received.
+    EtherTypeLengthUndefined(u16),
 }
 
 impl ReadError {
@@ -402,6 +628,84 @@ impl fmt::Display for ReadError {
             },
             Icmpv6PacketTooBig(size) => {
                 write!(f, "ReadError: ICMPv6 packet length {} is bigger then can be represented in an u32.", size)
+            },
+            HipHeaderLengthTooSmall(header_length) => { //u8
+                write!(f, "ReadError: HIP header length value {} is smaller then the minimum header length of 4 (40 bytes).", header_length)
+            },
+            HipParameterLengthTooBig(offset) => { //usize
+                write!(f, "ReadError: The length of the HIP parameter at offset {} exceeds the HIP header length.", offset)
+            },
+            GreUnsupportedVersion(version_number) => { //u8
+                write!(f, "ReadError: Unsupported GRE version number. The GRE header contained the unsupported version number {}.", version_number)
+            },
+            GreRoutingNotSupported => {
+                write!(f, "ReadError: GRE header with routing present bit set. GRE routing fields (RFC 1701) are not supported.")
+            },
+            GeneveUnsupportedVersion(version_number) => { //u8
+                write!(f, "ReadError: Unsupported GENEVE version number. The GENEVE header contained the unsupported version number {}.", version_number)
+            },
+            GeneveOptionLengthTooBig(offset) => { //usize
+                write!(f, "ReadError: The length of the GENEVE option at offset {} exceeds the GENEVE options length.", offset)
+            },
+            NshUnsupportedVersion(version_number) => { //u8
+                write!(f, "ReadError: Unsupported NSH version number. The NSH header contained the unsupported version number {}.", version_number)
+            },
+            NshLengthBad{ md_type, length } => {
+                write!(f, "ReadError: The NSH header length of {} (in 4 byte units) is not valid for the MD type {}.", length, md_type)
+            },
+            NshMetadataLengthTooBig(offset) => { //usize
+                write!(f, "ReadError: The length of the NSH metadata TLV at offset {} exceeds the NSH header length.", offset)
+            },
+            PppoeUnsupportedVersion{ version, pppoe_type } => {
+                write!(f, "ReadError: Unsupported PPPoE version {} & type {} (only version 1 & type 1 are supported).", version, pppoe_type)
+            },
+            PppoePayloadLengthTooSmall(length) => { //u16
+                write!(f, "ReadError: The PPPoE payload length of {} is too small to contain the PPP protocol field of a session data packet.", length)
+            },
+            RadiotapUnsupportedVersion(version_number) => { //u8
+                write!(f, "ReadError: Unsupported radiotap version number. The radiotap header contained the unsupported version number {}.", version_number)
+            },
+            RadiotapLengthTooSmall(length) => { //u16
+                write!(f, "ReadError: The radiotap header length of {} is too small to contain the header & its present flags.", length)
+            },
+            Ieee80211UnsupportedVersion(version_number) => { //u8
+                write!(f, "ReadError: Unsupported IEEE 802.11 protocol version. The frame control contained the unsupported version number {}.", version_number)
+            },
+            LlcNotSnap{ dsap, ssap, control } => {
+                write!(f, "ReadError: The LLC header with DSAP {:#04x}, SSAP {:#04x} & control {:#04x} is not a SNAP header.", dsap, ssap, control)
+            },
+            GtpuUnsupportedVersion(version_number) => { //u8
+                write!(f, "ReadError: Unsupported GTP-U version number. The GTP-U header contained the unsupported version number {}.", version_number)
+            },
+            GtpuExtensionLengthBad(offset) => { //usize
+                write!(f, "ReadError: The length of the GTP-U extension header at offset {} is zero or exceeds the maximum supported extension headers length of 252 bytes.", offset)
+            },
+            Ipv6SegmentRoutingUnexpectedType(routing_type) => { //u8
+                write!(f, "ReadError: Unexpected IPv6 routing type {} (expected 4 for a segment routing header).", routing_type)
+            },
+            Ipv6SegmentRoutingLastEntryTooLarge(last_entry) => { //u8
+                write!(f, "ReadError: The segment list of the IPv6 segment routing header with the last entry {} does not fit into the header.", last_entry)
+            },
+            DccpPacketTypeReserved(packet_type) => { //u8
+                write!(f, "ReadError: Reserved DCCP packet type {}.", packet_type)
+            },
+            DccpDataOffsetTooSmall(data_offset) => { //u8
+                write!(f, "ReadError: The DCCP data offset {} is too small to contain the header for the given packet type.", data_offset)
+            },
+            VrrpUnsupportedVersion(version) => { //u8
+                write!(f, "ReadError: Unsupported VRRP version {} (only versions 2 & 3 are supported via IPv4 and version 3 via IPv6).", version)
+            },
+            ErspanUnsupportedVersion(version) => { //u8
+                write!(f, "ReadError: Unsupported ERSPAN version {} (only version 1 for type II & version 2 for type III headers are supported).", version)
+            },
+            MacsecUnsupportedVersion => {
+                write!(f, "ReadError: Unsupported MACsec SecTAG version (the version bit is set, only version 0 is supported).")
+            },
+            ParseLimitExceeded(limit) => {
+                write!(f, "ReadError: The packet exceeds the parse limit {:?}.", limit)
+            },
+            EtherTypeLengthUndefined(value) => { //u16
+                write!(f, "ReadError: The ethernet type/length field value {} is neither a length (at most 1500) nor an ether type (at least 1536).", value)
             }
         }
     }
@@ -536,6 +840,66 @@ pub enum ValueError {
     U32TooLarge{value: u32, max: u32, field: ErrorField},
     /// Error when an Icmpv6 payload is found in an IPv4 packet.
     Icmpv6InIpv4,
+    /// Error when the ip packet is larger then the maximum transmission unit
+    /// configured in the packet builder.
+    IpPacketExceedsMtu{ip_packet_len: usize, mtu: usize},
+    /// Error when a value written with a [`BitWriter`] does not fit into
+    /// the given number of bits.
+    BitFieldValueTooLarge{value: u64, bit_len: u8},
+    /// Error when the sender & target addresses of the same type (hardware or
+    /// protocol) in an ARP packet have different lengths.
+    ArpAddressLengthMismatch{sender_len: usize, target_len: usize},
+    /// Error when an address of an ARP packet is longer then 255 bytes (the
+    /// maximum that can be represented in the address length fields).
+    ArpAddressTooLong(usize),
+    /// Error when a NDP option is longer then 2040 bytes (the maximum that
+    /// can be represented in the option length field).
+    NdpOptionTooLong(usize),
+    /// Error when the options of a GENEVE header are longer then 252 bytes (the
+    /// maximum that can be represented in the options length field).
+    GeneveOptionsLengthBad(usize),
+    /// Error when the data of a GENEVE option is not a multiple of 4 bytes or
+    /// longer then 124 bytes (the maximum that can be represented in the option
+    /// length field).
+    GeneveOptionDataLengthBad(usize),
+    /// Error when the metadata of a NSH header is longer then 244 bytes (the
+    /// maximum that can be represented in the length field).
+    NshMetadataLengthBad(usize),
+    /// Error when the data of a NSH metadata TLV is longer then 127 bytes (the
+    /// maximum that can be represented in the TLV length field).
+    NshTlvDataTooLong(usize),
+    /// Error when the payload of a PPPoE header is larger then can be
+    /// represented in the payload length field.
+    PppoePayloadLengthTooLarge(usize),
+    /// Error when the extension headers of a GTP-U header are longer then
+    /// 252 bytes (the maximum supported by etherparse).
+    GtpuExtensionsLengthBad(usize),
+    /// Error when the content of a GTP-U extension header plus the 2 bytes of
+    /// the length & next type fields is not a multiple of 4 bytes.
+    GtpuExtensionContentLengthBad(usize),
+    /// Error when an IPv6 segment routing header is created without any
+    /// segments (the segment list has to contain at least one segment).
+    Ipv6SegmentRoutingNoSegments,
+    /// Error when the checksum coverage of an UDP-Lite header is between
+    /// 1 & 7 or larger then the length of the datagram (`len`).
+    UdpLiteChecksumCoverageBad{coverage: u16, len: usize},
+    /// Error when a u64 field in a header has a larger value then supported.
+    U64TooLarge{value: u64, max: u64, field: ErrorField},
+    /// Error when the options of a DCCP header are not a multiple of 4 bytes or
+    /// the header including the options is longer then 1020 bytes (the maximum
+    /// that can be represented in the data offset field).
+    DccpOptionsLengthBad(usize),
+    /// Error when the checksum coverage of a DCCP header exceeds the payload
+    /// (`payload_len`).
+    DccpChecksumCoverageBad{coverage: u8, payload_len: usize},
+    /// Error when the length of a DCCP header & payload is larger then can be
+    /// represented in the ip pseudo header of the checksum.
+    DccpLengthTooLarge(usize),
+    /// Error when a VRRP version 2 message is sent via IPv6 (only defined for IPv4).
+    VrrpV2ViaIpv6,
+    /// Error when the length of a VRRP message is larger then can be
+    /// represented in the ip pseudo header of the checksum.
+    VrrpLengthTooLarge(usize),
 }
 
 impl Error for ValueError {
@@ -597,6 +961,66 @@ impl fmt::Display for ValueError {
             Icmpv6InIpv4 => {
                 write!(f, "ICMPv6 packet can not be combined with IPv4 headers.")
             },
+            IpPacketExceedsMtu{ip_packet_len, mtu} => {
+                write!(f, "The ip packet length of {} bytes exceeds the maximum transmission unit (MTU) of {} bytes.", ip_packet_len, mtu)
+            },
+            BitFieldValueTooLarge{value, bit_len} => {
+                write!(f, "The value {} can not be represented in a bit field with a length of {} bits.", value, bit_len)
+            },
+            ArpAddressLengthMismatch{sender_len, target_len} => {
+                write!(f, "The ARP sender address length of {} bytes differs from the target address length of {} bytes.", sender_len, target_len)
+            },
+            ArpAddressTooLong(len) => {
+                write!(f, "The ARP address length of {} bytes is larger then the maximum of 255 bytes.", len)
+            },
+            NdpOptionTooLong(len) => {
+                write!(f, "The NDP option length of {} bytes is larger then the maximum of 2040 bytes.", len)
+            },
+            GeneveOptionsLengthBad(len) => {
+                write!(f, "The GENEVE options length of {} bytes is larger then the maximum of 252 bytes.", len)
+            },
+            GeneveOptionDataLengthBad(len) => {
+                write!(f, "The GENEVE option data length of {} bytes is either not a multiple of 4 bytes or larger then the maximum of 124 bytes.", len)
+            },
+            NshMetadataLengthBad(len) => {
+                write!(f, "The NSH metadata length of {} bytes is larger then the maximum of 244 bytes.", len)
+            },
+            NshTlvDataTooLong(len) => {
+                write!(f, "The NSH metadata TLV data length of {} bytes is larger then the maximum of 127 bytes.", len)
+            },
+            PppoePayloadLengthTooLarge(len) => {
+                write!(f, "The PPPoE payload length of {} bytes is larger then can be represented in the payload length field.", len)
+            },
+            GtpuExtensionsLengthBad(len) => {
+                write!(f, "The GTP-U extension headers length of {} bytes is larger then the maximum of 252 bytes.", len)
+            },
+            GtpuExtensionContentLengthBad(len) => {
+                write!(f, "The GTP-U extension header content length of {} bytes plus 2 is not a multiple of 4 bytes.", len)
+            },
+            Ipv6SegmentRoutingNoSegments => {
+                write!(f, "An IPv6 segment routing header has to contain at least one segment.")
+            },
+            UdpLiteChecksumCoverageBad{ coverage, len } => {
+                write!(f, "The UDP-Lite checksum coverage of {} bytes is invalid for a datagram with a length of {} bytes (has to be 0 or between 8 and the datagram length).", coverage, len)
+            },
+            U64TooLarge{value, max, field} => {
+                write!(f, "The value {} of the field '{}' is larger then the allowed maximum of {}.", value, field, max)
+            },
+            DccpOptionsLengthBad(len) => {
+                write!(f, "The DCCP options length of {} bytes is not a multiple of 4 or the header exceeds the maximum header length of 1020 bytes.", len)
+            },
+            DccpChecksumCoverageBad{ coverage, payload_len } => {
+                write!(f, "The DCCP checksum coverage {} exceeds the payload length of {} bytes.", coverage, payload_len)
+            },
+            DccpLengthTooLarge(len) => {
+                write!(f, "The DCCP length of {} bytes is larger then can be represented in the checksum pseudo header.", len)
+            },
+            VrrpV2ViaIpv6 => {
+                write!(f, "VRRP version 2 messages can only be sent via IPv4.")
+            },
+            VrrpLengthTooLarge(len) => {
+                write!(f, "The VRRP message length of {} bytes is larger then can be represented in the checksum pseudo header.", len)
+            },
         }
     }
 }
@@ -615,6 +1039,62 @@ pub enum ErrorField {
     VlanTagPriorityCodePoint,
     ///VlanTaggingHeader.vlan_identifier
     VlanTagVlanId,
+    /// MplsHeader.label
+    MplsLabel,
+    /// MplsHeader.traffic_class
+    MplsTrafficClass,
+    /// VxlanHeader.vni
+    VxlanVni,
+    /// GeneveHeader.vni
+    GeneveVni,
+    /// NshHeader.ttl
+    NshTtl,
+    /// NshHeader.service_path_id
+    NshServicePathId,
+    /// PppoeHeader.version
+    PppoeVersion,
+    /// PppoeHeader.pppoe_type
+    PppoeType,
+    /// DdpHeader.hop_count
+    DdpHopCount,
+    /// DdpHeader.length
+    DdpLength,
+    /// DccpHeader.ccval
+    DccpCcval,
+    /// DccpHeader.checksum_coverage
+    DccpChecksumCoverage,
+    /// DccpHeader.sequence_number
+    DccpSequenceNumber,
+    /// DccpType acknowledgement_number
+    DccpAcknowledgementNumber,
+    /// VrrpHeader.vrrp_type
+    VrrpType,
+    /// VrrpVersion::V3 max_advertisement_interval
+    VrrpMaxAdvertisementInterval,
+    /// ErspanHeader.vlan
+    ErspanVlan,
+    /// ErspanHeader.cos
+    ErspanCos,
+    /// ErspanHeader.encapsulation_type
+    ErspanEncapsulationType,
+    /// ErspanHeader.session_id
+    ErspanSessionId,
+    /// ErspanType::Type2 index
+    ErspanIndex,
+    /// ErspanType::Type3 frame_type
+    ErspanFrameType,
+    /// ErspanType::Type3 hardware_id
+    ErspanHardwareId,
+    /// ErspanType::Type3 granularity
+    ErspanGranularity,
+    /// MacsecHeader.association_number
+    MacsecAssociationNumber,
+    /// MacsecHeader.short_length
+    MacsecShortLength,
+    /// EapolKeyInformation.descriptor_version
+    EapolKeyDescriptorVersion,
+    /// EapolKeyInformation.key_index
+    EapolKeyIndex,
 }
 
 impl fmt::Display for ErrorField {
@@ -628,7 +1108,35 @@ impl fmt::Display for ErrorField {
             Ipv6FlowLabel => write!(f, "Ipv6Header.flow_label"),
             Ipv6FragmentOffset => write!(f, "Ipv6FragmentHeader.fragment_offset"),
             VlanTagPriorityCodePoint => write!(f, "SingleVlanHeader.priority_code_point"),
-            VlanTagVlanId => write!(f, "SingleVlanHeader.vlan_identifier")
+            VlanTagVlanId => write!(f, "SingleVlanHeader.vlan_identifier"),
+            MplsLabel => write!(f, "MplsHeader.label"),
+            MplsTrafficClass => write!(f, "MplsHeader.traffic_class"),
+            VxlanVni => write!(f, "VxlanHeader.vni"),
+            GeneveVni => write!(f, "GeneveHeader.vni"),
+            NshTtl => write!(f, "NshHeader.ttl"),
+            NshServicePathId => write!(f, "NshHeader.service_path_id"),
+            PppoeVersion => write!(f, "PppoeHeader.version"),
+            PppoeType => write!(f, "PppoeHeader.pppoe_type"),
+            DdpHopCount => write!(f, "DdpHeader.hop_count"),
+            DdpLength => write!(f, "DdpHeader.length"),
+            DccpCcval => write!(f, "DccpHeader.ccval"),
+            DccpChecksumCoverage => write!(f, "DccpHeader.checksum_coverage"),
+            DccpSequenceNumber => write!(f, "DccpHeader.sequence_number"),
+            DccpAcknowledgementNumber => write!(f, "DccpType.acknowledgement_number"),
+            VrrpType => write!(f, "VrrpHeader.vrrp_type"),
+            VrrpMaxAdvertisementInterval => write!(f, "VrrpVersion.max_advertisement_interval"),
+            ErspanVlan => write!(f, "ErspanHeader.vlan"),
+            ErspanCos => write!(f, "ErspanHeader.cos"),
+            ErspanEncapsulationType => write!(f, "ErspanHeader.encapsulation_type"),
+            ErspanSessionId => write!(f, "ErspanHeader.session_id"),
+            ErspanIndex => write!(f, "ErspanType.index"),
+            ErspanFrameType => write!(f, "ErspanType.frame_type"),
+            ErspanHardwareId => write!(f, "ErspanType.hardware_id"),
+            ErspanGranularity => write!(f, "ErspanType.granularity"),
+            MacsecAssociationNumber => write!(f, "MacsecHeader.association_number"),
+            MacsecShortLength => write!(f, "MacsecHeader.short_length"),
+            EapolKeyDescriptorVersion => write!(f, "EapolKeyInformation.descriptor_version"),
+            EapolKeyIndex => write!(f, "EapolKeyInformation.key_index")
         }
     }
 }
@@ -659,6 +1167,32 @@ fn max_check_u16(value: u16, max: u16, field: ErrorField) -> Result<(), ValueErr
     }
 }
 
+fn max_check_u32(value: u32, max: u32, field: ErrorField) -> Result<(), ValueError> {
+    use crate::ValueError::U32TooLarge;
+    if value <= max {
+        Ok(())
+    } else {
+        Err(U32TooLarge{ 
+            value, 
+            max, 
+            field
+        })
+    }
+}
+
+fn max_check_u64(value: u64, max: u64, field: ErrorField) -> Result<(), ValueError> {
+    use crate::ValueError::U64TooLarge;
+    if value <= max {
+        Ok(())
+    } else {
+        Err(U64TooLarge{
+            value,
+            max,
+            field
+        })
+    }
+}
+
 /// Helper function for reading big endian u16 values from a ptr unchecked.
 ///
 /// # Safety
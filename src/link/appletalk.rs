@@ -0,0 +1,259 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// AppleTalk DDP types identifying the protocol of the DDP payload.
+pub mod ddp_type {
+    /// Routing table maintenance protocol response or data.
+    pub const RTMP_RESPONSE: u8 = 1;
+    /// Name binding protocol.
+    pub const NBP: u8 = 2;
+    /// AppleTalk transaction protocol.
+    pub const ATP: u8 = 3;
+    /// AppleTalk echo protocol.
+    pub const AEP: u8 = 4;
+    /// Routing table maintenance protocol request.
+    pub const RTMP_REQUEST: u8 = 5;
+    /// Zone information protocol.
+    pub const ZIP: u8 = 6;
+    /// AppleTalk data stream protocol.
+    pub const ADSP: u8 = 7;
+}
+
+/// AppleTalk datagram delivery protocol header (extended DDP header of
+/// AppleTalk phase 2, ether type 0x809B).
+///
+/// DDP datagrams are carried in ethernet II frames with the ether type
+/// [`ether_type::APPLETALK`] or in 802.3 frames with a LLC/SNAP header
+/// (OUI 08-00-07). [`SlicedPacket`] & [`PacketHeaders`] leave the datagram
+/// in the payload, it can be decoded with [`DdpHeaderSlice::from_slice`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ddp_type, ether_type, DdpHeader, DdpHeaderSlice, Ethernet2Header, SerializedSize, SlicedPacket};
+///
+/// let header = DdpHeader {
+///     length: (DdpHeader::SERIALIZED_SIZE + 2) as u16,
+///     destination_network: 1,
+///     source_network: 2,
+///     destination_node: 3,
+///     source_node: 4,
+///     destination_socket: 4,
+///     source_socket: 0xfd,
+///     ddp_type: ddp_type::AEP,
+///     ..Default::default()
+/// };
+/// let mut packet = Vec::new();
+/// Ethernet2Header {
+///     source: [1,2,3,4,5,6],
+///     destination: [0xff;6],
+///     ether_type: ether_type::APPLETALK,
+/// }.write(&mut packet).unwrap();
+/// header.write(&mut packet).unwrap();
+/// packet.extend_from_slice(&[1, 0]);
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// let ddp = DdpHeaderSlice::from_slice(sliced.payload).unwrap();
+/// assert_eq!(header, ddp.to_header());
+/// assert_eq!(&[1, 0], ddp.payload());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DdpHeader {
+    /// Number of routers the datagram passed (4 bits).
+    pub hop_count: u8,
+    /// Length of the datagram including the header in bytes (10 bits).
+    pub length: u16,
+    /// Checksum (0 if no checksum is used).
+    pub checksum: u16,
+    /// Destination network number.
+    pub destination_network: u16,
+    /// Source network number.
+    pub source_network: u16,
+    /// Destination node id.
+    pub destination_node: u8,
+    /// Source node id.
+    pub source_node: u8,
+    /// Destination socket number.
+    pub destination_socket: u8,
+    /// Source socket number.
+    pub source_socket: u8,
+    /// Type of the payload (see [`ddp_type`]).
+    pub ddp_type: u8,
+}
+
+impl SerializedSize for DdpHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 13;
+}
+
+impl DdpHeader {
+    /// Maximum value of the hop count field (4 bits).
+    pub const MAX_HOP_COUNT: u8 = 0b1111;
+
+    /// Maximum value of the length field (10 bits).
+    pub const MAX_LENGTH: u16 = 0b11_1111_1111;
+
+    /// Read a DDP header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(DdpHeader, &[u8]), ReadError> {
+        Ok((
+            DdpHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[DdpHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Returns the serialized form of the header (fails if the hop count
+    /// exceeds 4 bits or the length exceeds 10 bits).
+    pub fn to_bytes(&self) -> Result<[u8;13], ValueError> {
+        max_check_u8(self.hop_count, DdpHeader::MAX_HOP_COUNT, ErrorField::DdpHopCount)?;
+        max_check_u16(self.length, DdpHeader::MAX_LENGTH, ErrorField::DdpLength)?;
+
+        let length = self.length.to_be_bytes();
+        let checksum = self.checksum.to_be_bytes();
+        let destination_network = self.destination_network.to_be_bytes();
+        let source_network = self.source_network.to_be_bytes();
+        Ok([
+            (self.hop_count << 2) | length[0],
+            length[1],
+            checksum[0],
+            checksum[1],
+            destination_network[0],
+            destination_network[1],
+            source_network[0],
+            source_network[1],
+            self.destination_node,
+            self.source_node,
+            self.destination_socket,
+            self.source_socket,
+            self.ddp_type,
+        ])
+    }
+
+    /// Writes the header to the given writer.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+/// Slice containing an AppleTalk DDP header & the following payload.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DdpHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> DdpHeaderSlice<'a> {
+    /// Creates a DDP header slice from a slice.
+    ///
+    /// The slice is cut to the length given in the length field if the
+    /// length is between the header size & the slice length (ethernet
+    /// padding is not part of the slice).
+    pub fn from_slice(slice: &'a [u8]) -> Result<DdpHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < DdpHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(DdpHeader::SERIALIZED_SIZE));
+        }
+        let length = usize::from(u16::from_be_bytes([slice[0] & 0b11, slice[1]]));
+        let len = if DdpHeader::SERIALIZED_SIZE <= length && length <= slice.len() {
+            length
+        } else {
+            slice.len()
+        };
+        Ok(DdpHeaderSlice {
+            // SAFETY:
+            // Safe as len is smaller or equal to the slice length.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Returns the slice containing the DDP header & payload.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the slice containing the payload following the header.
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.slice[DdpHeader::SERIALIZED_SIZE..]
+    }
+
+    /// Read the hop count field (4 bits).
+    #[inline]
+    pub fn hop_count(&self) -> u8 {
+        (self.slice[0] >> 2) & DdpHeader::MAX_HOP_COUNT
+    }
+
+    /// Read the length field (10 bits).
+    #[inline]
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0] & 0b11, self.slice[1]])
+    }
+
+    /// Read the checksum field.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the destination network number.
+    #[inline]
+    pub fn destination_network(&self) -> u16 {
+        u16::from_be_bytes([self.slice[4], self.slice[5]])
+    }
+
+    /// Read the source network number.
+    #[inline]
+    pub fn source_network(&self) -> u16 {
+        u16::from_be_bytes([self.slice[6], self.slice[7]])
+    }
+
+    /// Read the destination node id.
+    #[inline]
+    pub fn destination_node(&self) -> u8 {
+        self.slice[8]
+    }
+
+    /// Read the source node id.
+    #[inline]
+    pub fn source_node(&self) -> u8 {
+        self.slice[9]
+    }
+
+    /// Read the destination socket number.
+    #[inline]
+    pub fn destination_socket(&self) -> u8 {
+        self.slice[10]
+    }
+
+    /// Read the source socket number.
+    #[inline]
+    pub fn source_socket(&self) -> u8 {
+        self.slice[11]
+    }
+
+    /// Read the DDP type field.
+    #[inline]
+    pub fn ddp_type(&self) -> u8 {
+        self.slice[12]
+    }
+
+    /// Decode all the fields and copy the results to a [`DdpHeader`] struct.
+    pub fn to_header(&self) -> DdpHeader {
+        DdpHeader {
+            hop_count: self.hop_count(),
+            length: self.length(),
+            checksum: self.checksum(),
+            destination_network: self.destination_network(),
+            source_network: self.source_network(),
+            destination_node: self.destination_node(),
+            source_node: self.source_node(),
+            destination_socket: self.destination_socket(),
+            source_socket: self.source_socket(),
+            ddp_type: self.ddp_type(),
+        }
+    }
+}
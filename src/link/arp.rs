@@ -0,0 +1,456 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::fmt::{Debug, Formatter};
+use std::io;
+
+/// `u16` constants of the most used ARP operation values.
+pub mod arp_operation {
+    /// ARP request (who has the target protocol address).
+    pub const REQUEST: u16 = 1;
+    /// ARP reply (containing the requested hardware address as the sender hardware address).
+    pub const REPLY: u16 = 2;
+}
+
+/// `u16` constants of the most used ARP hardware type values.
+pub mod arp_hardware_type {
+    /// Ethernet (10Mb) hardware type (also used for all faster ethernet variants).
+    pub const ETHERNET: u16 = 1;
+    /// IEEE 1394 (firewire) hardware type (RFC 2734).
+    pub const IEEE1394: u16 = 24;
+    /// InfiniBand hardware type (RFC 4391).
+    pub const INFINIBAND: u16 = 32;
+}
+
+/// Address Resolution Protocol packet (RFC 826), e.g. for the resolution of
+/// ipv4 addresses to ethernet MAC addresses (ether type 0x0806).
+///
+/// The lengths of the hardware & protocol addresses are given by the
+/// address fields (e.g. 6 byte MAC & 4 byte ipv4 addresses for ethernet,
+/// 20 byte hardware addresses for InfiniBand).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{arp_operation, ArpPacket, Ethernet2Header, ether_type, SlicedPacket};
+///
+/// let request = ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2]);
+///
+/// let mut frame = Vec::new();
+/// Ethernet2Header {
+///     source: [1,2,3,4,5,6],
+///     destination: [0xff;6],
+///     ether_type: ether_type::ARP,
+/// }.write(&mut frame).unwrap();
+/// request.write(&mut frame).unwrap();
+///
+/// let sliced = SlicedPacket::from_ethernet(&frame).unwrap();
+/// let arp = sliced.arp.unwrap();
+/// assert_eq!(arp_operation::REQUEST, arp.operation());
+/// assert_eq!(&[192,168,1,2], arp.target_protocol_address());
+///
+/// // answer the request
+/// let reply = arp.to_packet().reply(&[7,8,9,10,11,12]).unwrap();
+/// assert_eq!(arp_operation::REPLY, reply.operation);
+/// assert_eq!(&[1,2,3,4,5,6], reply.target_hardware_address());
+/// ```
+#[derive(Clone)]
+pub struct ArpPacket {
+    /// Type of the hardware addresses (see [`arp_hardware_type`]).
+    pub hardware_type: u16,
+    /// Ether type of the protocol addresses (usually `ether_type::IPV4`).
+    pub protocol_type: u16,
+    /// Operation of the packet (see [`arp_operation`]).
+    pub operation: u16,
+    /// Length of the hardware addresses in bytes.
+    hardware_address_len: u8,
+    /// Length of the protocol addresses in bytes.
+    protocol_address_len: u8,
+    /// Sender hardware, sender protocol, target hardware & target protocol address.
+    address_buffer: [u8;4*0xff],
+}
+
+impl Debug for ArpPacket {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "ArpPacket {{ hardware_type: {}, protocol_type: {}, operation: {}, sender_hardware_address: {:?}, sender_protocol_address: {:?}, target_hardware_address: {:?}, target_protocol_address: {:?} }}",
+            self.hardware_type,
+            self.protocol_type,
+            self.operation,
+            self.sender_hardware_address(),
+            self.sender_protocol_address(),
+            self.target_hardware_address(),
+            self.target_protocol_address())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ArpPacket {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "ArpPacket {{ hardware_type: {}, protocol_type: {}, operation: {}, sender_hardware_address: {=[u8]}, sender_protocol_address: {=[u8]}, target_hardware_address: {=[u8]}, target_protocol_address: {=[u8]} }}",
+            self.hardware_type,
+            self.protocol_type,
+            self.operation,
+            self.sender_hardware_address(),
+            self.sender_protocol_address(),
+            self.target_hardware_address(),
+            self.target_protocol_address())
+    }
+}
+
+impl PartialEq for ArpPacket {
+    fn eq(&self, other: &Self) -> bool {
+        self.hardware_type == other.hardware_type &&
+        self.protocol_type == other.protocol_type &&
+        self.operation == other.operation &&
+        self.hardware_address_len == other.hardware_address_len &&
+        self.addresses() == other.addresses()
+    }
+}
+
+impl Eq for ArpPacket {}
+
+impl std::hash::Hash for ArpPacket {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hardware_type.hash(state);
+        self.protocol_type.hash(state);
+        self.operation.hash(state);
+        self.hardware_address_len.hash(state);
+        self.addresses().hash(state);
+    }
+}
+
+impl Default for ArpPacket {
+    fn default() -> ArpPacket {
+        ArpPacket {
+            hardware_type: 0,
+            protocol_type: 0,
+            operation: 0,
+            hardware_address_len: 0,
+            protocol_address_len: 0,
+            address_buffer: [0;4*0xff],
+        }
+    }
+}
+
+impl ArpPacket {
+    /// Length of the fixed part of the packet (before the addresses).
+    pub const MIN_LEN: usize = 8;
+
+    /// Maximum length of a serialized ARP packet.
+    pub const MAX_SERIALIZED_SIZE: usize = ArpPacket::MIN_LEN + 4*0xff;
+
+    /// Creates an ARP packet with the given addresses.
+    ///
+    /// The sender & target addresses of the same type must have the same
+    /// length & can be at most 255 bytes long.
+    pub fn new(
+        hardware_type: u16,
+        protocol_type: u16,
+        operation: u16,
+        sender_hardware_address: &[u8],
+        sender_protocol_address: &[u8],
+        target_hardware_address: &[u8],
+        target_protocol_address: &[u8],
+    ) -> Result<ArpPacket, ValueError> {
+        let mut result = ArpPacket {
+            hardware_type,
+            protocol_type,
+            operation,
+            ..Default::default()
+        };
+        result.set_addresses(
+            sender_hardware_address,
+            sender_protocol_address,
+            target_hardware_address,
+            target_protocol_address
+        )?;
+        Ok(result)
+    }
+
+    /// Creates an ARP packet for the resolution of ipv4 addresses to
+    /// ethernet MAC addresses.
+    pub fn ethernet_ipv4(
+        operation: u16,
+        sender_hardware_address: [u8;6],
+        sender_protocol_address: [u8;4],
+        target_hardware_address: [u8;6],
+        target_protocol_address: [u8;4],
+    ) -> ArpPacket {
+        let mut result = ArpPacket {
+            hardware_type: arp_hardware_type::ETHERNET,
+            protocol_type: ether_type::IPV4,
+            operation,
+            hardware_address_len: 6,
+            protocol_address_len: 4,
+            address_buffer: [0;4*0xff],
+        };
+        result.address_buffer[..6].copy_from_slice(&sender_hardware_address);
+        result.address_buffer[6..10].copy_from_slice(&sender_protocol_address);
+        result.address_buffer[10..16].copy_from_slice(&target_hardware_address);
+        result.address_buffer[16..20].copy_from_slice(&target_protocol_address);
+        result
+    }
+
+    /// Creates an ethernet/ipv4 ARP request asking for the hardware address
+    /// of the target protocol address.
+    pub fn request(sender_hardware_address: [u8;6], sender_protocol_address: [u8;4], target_protocol_address: [u8;4]) -> ArpPacket {
+        ArpPacket::ethernet_ipv4(
+            arp_operation::REQUEST,
+            sender_hardware_address,
+            sender_protocol_address,
+            [0;6],
+            target_protocol_address
+        )
+    }
+
+    /// Creates a reply to this packet with the given hardware address for
+    /// the target protocol address of this packet (the hardware address must
+    /// have the same length as the hardware addresses of this packet).
+    pub fn reply(&self, hardware_address: &[u8]) -> Result<ArpPacket, ValueError> {
+        let mut result = ArpPacket {
+            hardware_type: self.hardware_type,
+            protocol_type: self.protocol_type,
+            operation: arp_operation::REPLY,
+            ..Default::default()
+        };
+        result.set_addresses(
+            hardware_address,
+            self.target_protocol_address(),
+            self.sender_hardware_address(),
+            self.sender_protocol_address()
+        )?;
+        Ok(result)
+    }
+
+    /// Length of the hardware addresses in bytes.
+    #[inline]
+    pub fn hardware_address_len(&self) -> u8 {
+        self.hardware_address_len
+    }
+
+    /// Length of the protocol addresses in bytes.
+    #[inline]
+    pub fn protocol_address_len(&self) -> u8 {
+        self.protocol_address_len
+    }
+
+    /// Hardware address of the sender.
+    #[inline]
+    pub fn sender_hardware_address(&self) -> &[u8] {
+        let (start, len) = (0, usize::from(self.hardware_address_len));
+        &self.address_buffer[start..start + len]
+    }
+
+    /// Protocol address of the sender.
+    #[inline]
+    pub fn sender_protocol_address(&self) -> &[u8] {
+        let (start, len) = (usize::from(self.hardware_address_len), usize::from(self.protocol_address_len));
+        &self.address_buffer[start..start + len]
+    }
+
+    /// Hardware address of the target (ignored in requests).
+    #[inline]
+    pub fn target_hardware_address(&self) -> &[u8] {
+        let start = usize::from(self.hardware_address_len) + usize::from(self.protocol_address_len);
+        &self.address_buffer[start..start + usize::from(self.hardware_address_len)]
+    }
+
+    /// Protocol address of the target.
+    #[inline]
+    pub fn target_protocol_address(&self) -> &[u8] {
+        let start = 2*usize::from(self.hardware_address_len) + usize::from(self.protocol_address_len);
+        &self.address_buffer[start..start + usize::from(self.protocol_address_len)]
+    }
+
+    /// Replaces all addresses (the sender & target addresses of the same
+    /// type must have the same length & can be at most 255 bytes long).
+    pub fn set_addresses(
+        &mut self,
+        sender_hardware_address: &[u8],
+        sender_protocol_address: &[u8],
+        target_hardware_address: &[u8],
+        target_protocol_address: &[u8],
+    ) -> Result<(), ValueError> {
+        use ValueError::*;
+        for (sender, target) in [
+            (sender_hardware_address, target_hardware_address),
+            (sender_protocol_address, target_protocol_address),
+        ] {
+            if sender.len() != target.len() {
+                return Err(ArpAddressLengthMismatch{ sender_len: sender.len(), target_len: target.len() });
+            }
+            if sender.len() > 0xff {
+                return Err(ArpAddressTooLong(sender.len()));
+            }
+        }
+        self.hardware_address_len = sender_hardware_address.len() as u8;
+        self.protocol_address_len = sender_protocol_address.len() as u8;
+        self.address_buffer = [0;4*0xff];
+        let mut offset = 0;
+        for address in [
+            sender_hardware_address,
+            sender_protocol_address,
+            target_hardware_address,
+            target_protocol_address,
+        ] {
+            self.address_buffer[offset..offset + address.len()].copy_from_slice(address);
+            offset += address.len();
+        }
+        Ok(())
+    }
+
+    /// Read an ArpPacket from a slice and return the packet & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(ArpPacket, &[u8]), ReadError> {
+        let packet = ArpPacketSlice::from_slice(slice)?;
+        Ok((
+            packet.to_packet(),
+            &slice[packet.slice().len()..]
+        ))
+    }
+
+    /// Reads an ARP packet from the current position of the read argument.
+    pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<ArpPacket, ReadError> {
+        let mut buffer = [0u8;ArpPacket::MAX_SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer[..ArpPacket::MIN_LEN])?;
+        let len = ArpPacket::MIN_LEN + 2*usize::from(buffer[4]) + 2*usize::from(buffer[5]);
+        reader.read_exact(&mut buffer[ArpPacket::MIN_LEN..len])?;
+        Ok(ArpPacketSlice::from_slice(&buffer[..len])?.to_packet())
+    }
+
+    /// Writes a given ARP packet to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized packet in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        ArpPacket::MIN_LEN + self.addresses().len()
+    }
+
+    /// Returns the serialized form of the packet in a stack allocated buffer.
+    pub fn to_bytes(&self) -> ArrayVec<u8, { ArpPacket::MAX_SERIALIZED_SIZE }> {
+        let mut result = ArrayVec::new();
+        result.extend(self.hardware_type.to_be_bytes());
+        result.extend(self.protocol_type.to_be_bytes());
+        result.push(self.hardware_address_len);
+        result.push(self.protocol_address_len);
+        result.extend(self.operation.to_be_bytes());
+        // can not fail as the buffer has the maximum size of the addresses
+        result.try_extend_from_slice(self.addresses()).unwrap();
+        result
+    }
+
+    /// All addresses in the order they are serialized.
+    #[inline]
+    fn addresses(&self) -> &[u8] {
+        let len = 2*usize::from(self.hardware_address_len) + 2*usize::from(self.protocol_address_len);
+        &self.address_buffer[..len]
+    }
+}
+
+/// A slice containing an ARP packet of a network package.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ArpPacketSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> ArpPacketSlice<'a> {
+    /// Creates a ARP packet slice from a slice (the length of the slice is
+    /// determined by the address length fields).
+    pub fn from_slice(slice: &'a [u8]) -> Result<ArpPacketSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < ArpPacket::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(ArpPacket::MIN_LEN));
+        }
+        let len = ArpPacket::MIN_LEN + 2*usize::from(slice[4]) + 2*usize::from(slice[5]);
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(ArpPacketSlice {
+            slice: &slice[..len]
+        })
+    }
+
+    /// Returns the slice containing the ARP packet.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "hardware type" field from the slice.
+    #[inline]
+    pub fn hardware_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the "protocol type" field from the slice.
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "hardware address length" field from the slice.
+    #[inline]
+    pub fn hardware_address_len(&self) -> u8 {
+        self.slice[4]
+    }
+
+    /// Read the "protocol address length" field from the slice.
+    #[inline]
+    pub fn protocol_address_len(&self) -> u8 {
+        self.slice[5]
+    }
+
+    /// Read the "operation" field from the slice.
+    #[inline]
+    pub fn operation(&self) -> u16 {
+        u16::from_be_bytes([self.slice[6], self.slice[7]])
+    }
+
+    /// Slice containing the "sender hardware address".
+    #[inline]
+    pub fn sender_hardware_address(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN;
+        &self.slice[start..start + usize::from(self.hardware_address_len())]
+    }
+
+    /// Slice containing the "sender protocol address".
+    #[inline]
+    pub fn sender_protocol_address(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN + usize::from(self.hardware_address_len());
+        &self.slice[start..start + usize::from(self.protocol_address_len())]
+    }
+
+    /// Slice containing the "target hardware address".
+    #[inline]
+    pub fn target_hardware_address(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN + usize::from(self.hardware_address_len()) + usize::from(self.protocol_address_len());
+        &self.slice[start..start + usize::from(self.hardware_address_len())]
+    }
+
+    /// Slice containing the "target protocol address".
+    #[inline]
+    pub fn target_protocol_address(&self) -> &'a [u8] {
+        let start = ArpPacket::MIN_LEN + 2*usize::from(self.hardware_address_len()) + usize::from(self.protocol_address_len());
+        &self.slice[start..start + usize::from(self.protocol_address_len())]
+    }
+
+    /// Decode all the fields and copy the results to a ArpPacket struct
+    pub fn to_packet(&self) -> ArpPacket {
+        let mut result = ArpPacket {
+            hardware_type: self.hardware_type(),
+            protocol_type: self.protocol_type(),
+            operation: self.operation(),
+            hardware_address_len: self.hardware_address_len(),
+            protocol_address_len: self.protocol_address_len(),
+            address_buffer: [0;4*0xff],
+        };
+        let addresses = &self.slice[ArpPacket::MIN_LEN..];
+        result.address_buffer[..addresses.len()].copy_from_slice(addresses);
+        result
+    }
+}
@@ -0,0 +1,522 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// EAPOL packet types (IEEE 802.1X).
+pub mod eapol_packet_type {
+    /// EAP packet (the body contains an EAP packet).
+    pub const EAP_PACKET: u8 = 0;
+    /// EAPOL-Start.
+    pub const START: u8 = 1;
+    /// EAPOL-Logoff.
+    pub const LOGOFF: u8 = 2;
+    /// EAPOL-Key (the body contains a key descriptor, see [`crate::EapolKeySlice`]).
+    pub const KEY: u8 = 3;
+    /// EAPOL-Encapsulated-ASF-Alert.
+    pub const ENCAPSULATED_ASF_ALERT: u8 = 4;
+    /// EAPOL-MKA (MACsec key agreement).
+    pub const MKA: u8 = 5;
+    /// EAPOL-Announcement (Generic).
+    pub const ANNOUNCEMENT_GENERIC: u8 = 6;
+    /// EAPOL-Announcement (Specific).
+    pub const ANNOUNCEMENT_SPECIFIC: u8 = 7;
+    /// EAPOL-Announcement-Req.
+    pub const ANNOUNCEMENT_REQ: u8 = 8;
+}
+
+/// Descriptor types of EAPOL-Key frames.
+pub mod eapol_key_descriptor_type {
+    /// RC4 key descriptor (deprecated).
+    pub const RC4: u8 = 1;
+    /// IEEE 802.11 key descriptor (used by WPA2 & WPA3).
+    pub const IEEE80211: u8 = 2;
+    /// WPA key descriptor (used by the original WPA).
+    pub const WPA: u8 = 254;
+}
+
+/// EAPOL header (IEEE 802.1X) of frames with the ether type 0x888E.
+///
+/// [`SlicedPacket`] & [`PacketHeaders`] leave EAPOL frames in the payload,
+/// they can be decoded with [`EapolHeaderSlice::from_slice`]. The body of
+/// EAPOL-Key frames (e.g. the 4-way handshake of WPA2) can be accessed via
+/// [`EapolHeaderSlice::key`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{eapol_packet_type, ether_type, EapolHeader, EapolHeaderSlice, Ethernet2Header, SlicedPacket};
+///
+/// let header = EapolHeader {
+///     version: EapolHeader::VERSION_2004,
+///     packet_type: eapol_packet_type::START,
+///     body_length: 0,
+/// };
+/// let mut packet = Vec::new();
+/// Ethernet2Header {
+///     source: [1,2,3,4,5,6],
+///     destination: [0x01,0x80,0xc2,0,0,0x03],
+///     ether_type: ether_type::EAPOL,
+/// }.write(&mut packet).unwrap();
+/// header.write(&mut packet).unwrap();
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// let eapol = EapolHeaderSlice::from_slice(sliced.payload).unwrap();
+/// assert_eq!(header, eapol.to_header());
+/// assert!(eapol.key().is_none());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EapolHeader {
+    /// Protocol version (see the `VERSION_*` constants).
+    pub version: u8,
+    /// Type of the packet (see [`eapol_packet_type`]).
+    pub packet_type: u8,
+    /// Length of the body following the header in bytes.
+    pub body_length: u16,
+}
+
+impl SerializedSize for EapolHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 4;
+}
+
+impl EapolHeader {
+    /// Protocol version of IEEE 802.1X-2001.
+    pub const VERSION_2001: u8 = 1;
+
+    /// Protocol version of IEEE 802.1X-2004.
+    pub const VERSION_2004: u8 = 2;
+
+    /// Protocol version of IEEE 802.1X-2010.
+    pub const VERSION_2010: u8 = 3;
+
+    /// Read an EAPOL header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(EapolHeader, &[u8]), ReadError> {
+        if slice.len() < EapolHeader::SERIALIZED_SIZE {
+            return Err(ReadError::UnexpectedEndOfSlice(EapolHeader::SERIALIZED_SIZE));
+        }
+        Ok((
+            EapolHeader {
+                version: slice[0],
+                packet_type: slice[1],
+                body_length: u16::from_be_bytes([slice[2], slice[3]]),
+            },
+            &slice[EapolHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Returns the serialized form of the header.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;4] {
+        let body_length = self.body_length.to_be_bytes();
+        [self.version, self.packet_type, body_length[0], body_length[1]]
+    }
+
+    /// Writes the header to the given writer.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+/// Slice containing an EAPOL header & its body.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EapolHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> EapolHeaderSlice<'a> {
+    /// Creates an EAPOL header slice from a slice.
+    ///
+    /// The slice is cut to the header & the body length (ethernet padding
+    /// is not part of the slice). An error is returned if the slice is
+    /// shorter then the body length.
+    pub fn from_slice(slice: &'a [u8]) -> Result<EapolHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < EapolHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(EapolHeader::SERIALIZED_SIZE));
+        }
+        let len = EapolHeader::SERIALIZED_SIZE + usize::from(u16::from_be_bytes([slice[2], slice[3]]));
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(EapolHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // len before this code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Returns the slice containing the EAPOL header & body.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the slice containing the body following the header.
+    #[inline]
+    pub fn body(&self) -> &'a [u8] {
+        &self.slice[EapolHeader::SERIALIZED_SIZE..]
+    }
+
+    /// Read the protocol version.
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the packet type.
+    #[inline]
+    pub fn packet_type(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the body length.
+    #[inline]
+    pub fn body_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Decodes the key descriptor of EAPOL-Key frames (`None` if the packet
+    /// type is not [`eapol_packet_type::KEY`]).
+    ///
+    /// The offsets of the returned errors are relative to the start of the
+    /// EAPOL header.
+    pub fn key(&self) -> Option<Result<EapolKeySlice<'a>, ReadError>> {
+        if eapol_packet_type::KEY == self.packet_type() {
+            Some(
+                EapolKeySlice::from_slice(self.body())
+                    .map_err(|err| err.add_slice_offset(EapolHeader::SERIALIZED_SIZE))
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Decode all the fields and copy the results to a [`EapolHeader`] struct.
+    pub fn to_header(&self) -> EapolHeader {
+        EapolHeader {
+            version: self.version(),
+            packet_type: self.packet_type(),
+            body_length: self.body_length(),
+        }
+    }
+}
+
+/// Key information field of an EAPOL-Key frame.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EapolKeyInformation {
+    /// Key descriptor version (3 bit value, 1 for HMAC-MD5 & RC4, 2 for
+    /// HMAC-SHA1 & AES key wrap, 3 for AES-128-CMAC & AES key wrap, 0 if
+    /// the version is defined by the AKM).
+    pub descriptor_version: u8,
+    /// Key type bit (set for pairwise & cleared for group keys).
+    pub pairwise: bool,
+    /// Key index (2 bit value, only used by WPA key descriptors).
+    pub key_index: u8,
+    /// Install bit.
+    pub install: bool,
+    /// Key ack bit (set by the authenticator if a response is expected).
+    pub key_ack: bool,
+    /// Key MIC bit (set if the key MIC field contains a MIC).
+    pub key_mic: bool,
+    /// Secure bit.
+    pub secure: bool,
+    /// Error bit (set by the supplicant on MIC failures).
+    pub error: bool,
+    /// Request bit.
+    pub request: bool,
+    /// Encrypted key data bit.
+    pub encrypted_key_data: bool,
+    /// SMK message bit.
+    pub smk_message: bool,
+}
+
+impl EapolKeyInformation {
+    /// Maximum value of the key descriptor version.
+    pub const MAX_DESCRIPTOR_VERSION: u8 = 0b111;
+
+    /// Maximum value of the key index.
+    pub const MAX_KEY_INDEX: u8 = 0b11;
+
+    /// Decodes the key information from its raw value.
+    pub fn from_u16(value: u16) -> EapolKeyInformation {
+        let bit = |index: u16| 0 != value & (1 << index);
+        EapolKeyInformation {
+            descriptor_version: (value & 0b111) as u8,
+            pairwise: bit(3),
+            key_index: ((value >> 4) & 0b11) as u8,
+            install: bit(6),
+            key_ack: bit(7),
+            key_mic: bit(8),
+            secure: bit(9),
+            error: bit(10),
+            request: bit(11),
+            encrypted_key_data: bit(12),
+            smk_message: bit(13),
+        }
+    }
+
+    /// Returns the raw value of the key information (fails if the descriptor
+    /// version or key index exceed their bit widths).
+    pub fn to_u16(&self) -> Result<u16, ValueError> {
+        use crate::ErrorField::*;
+        max_check_u8(self.descriptor_version, EapolKeyInformation::MAX_DESCRIPTOR_VERSION, EapolKeyDescriptorVersion)?;
+        max_check_u8(self.key_index, EapolKeyInformation::MAX_KEY_INDEX, EapolKeyIndex)?;
+        let bit = |value: bool, index: u16| if value { 1 << index } else { 0 };
+        Ok(
+            u16::from(self.descriptor_version) |
+            bit(self.pairwise, 3) |
+            (u16::from(self.key_index) << 4) |
+            bit(self.install, 6) |
+            bit(self.key_ack, 7) |
+            bit(self.key_mic, 8) |
+            bit(self.secure, 9) |
+            bit(self.error, 10) |
+            bit(self.request, 11) |
+            bit(self.encrypted_key_data, 12) |
+            bit(self.smk_message, 13)
+        )
+    }
+}
+
+/// Key descriptor contained in the body of EAPOL-Key frames (without the
+/// key data following it).
+///
+/// Only key descriptors with a 16 byte key MIC are supported (all AKMs
+/// except the ones with SHA-384 based MICs).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{eapol_key_descriptor_type, eapol_packet_type, EapolHeader, EapolHeaderSlice, EapolKey, EapolKeyInformation};
+///
+/// // first message of the 4-way handshake
+/// let key = EapolKey {
+///     descriptor_type: eapol_key_descriptor_type::IEEE80211,
+///     key_information: EapolKeyInformation {
+///         descriptor_version: 2,
+///         pairwise: true,
+///         key_ack: true,
+///         ..Default::default()
+///     },
+///     key_length: 16,
+///     replay_counter: 1,
+///     nonce: [0xab; 32],
+///     ..Default::default()
+/// };
+/// let mut packet = Vec::new();
+/// EapolHeader {
+///     version: EapolHeader::VERSION_2004,
+///     packet_type: eapol_packet_type::KEY,
+///     body_length: key.header_len() as u16,
+/// }.write(&mut packet).unwrap();
+/// key.write(&mut packet).unwrap();
+///
+/// let eapol = EapolHeaderSlice::from_slice(&packet).unwrap();
+/// let decoded = eapol.key().unwrap().unwrap();
+/// assert_eq!(1, decoded.replay_counter());
+/// assert_eq!(&[0xab; 32], decoded.nonce());
+/// assert!(decoded.key_information().key_ack);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EapolKey {
+    /// Key descriptor type (see [`eapol_key_descriptor_type`]).
+    pub descriptor_type: u8,
+    /// Key information field.
+    pub key_information: EapolKeyInformation,
+    /// Length of the pairwise temporal key in bytes.
+    pub key_length: u16,
+    /// Replay counter.
+    pub replay_counter: u64,
+    /// Nonce (ANonce or SNonce in the 4-way handshake).
+    pub nonce: [u8;32],
+    /// EAPOL-Key IV.
+    pub iv: [u8;16],
+    /// Receive sequence counter of the group key.
+    pub rsc: u64,
+    /// Reserved field (key identifier in WPA key descriptors).
+    pub reserved: u64,
+    /// Message integrity code.
+    pub mic: [u8;16],
+    /// Length of the key data following the key descriptor in bytes.
+    pub key_data_length: u16,
+}
+
+impl EapolKey {
+    /// Length of the key descriptor without the key data.
+    pub const LEN: usize = 95;
+
+    /// Read a key descriptor from a slice and return the descriptor & the
+    /// rest of the slice (starting with the key data).
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(EapolKey, &[u8]), ReadError> {
+        let key = EapolKeySlice::from_slice(slice)?;
+        Ok((key.to_header(), &slice[EapolKey::LEN..]))
+    }
+
+    /// Length of the serialized key descriptor (without the key data) in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        EapolKey::LEN
+    }
+
+    /// Returns the serialized form of the key descriptor (without the key data).
+    pub fn to_bytes(&self) -> Result<[u8;EapolKey::LEN], ValueError> {
+        let mut result = [0u8;EapolKey::LEN];
+        result[0] = self.descriptor_type;
+        result[1..3].copy_from_slice(&self.key_information.to_u16()?.to_be_bytes());
+        result[3..5].copy_from_slice(&self.key_length.to_be_bytes());
+        result[5..13].copy_from_slice(&self.replay_counter.to_be_bytes());
+        result[13..45].copy_from_slice(&self.nonce);
+        result[45..61].copy_from_slice(&self.iv);
+        result[61..69].copy_from_slice(&self.rsc.to_be_bytes());
+        result[69..77].copy_from_slice(&self.reserved.to_be_bytes());
+        result[77..93].copy_from_slice(&self.mic);
+        result[93..95].copy_from_slice(&self.key_data_length.to_be_bytes());
+        Ok(result)
+    }
+
+    /// Writes the key descriptor (without the key data) to the given writer.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+}
+
+/// Slice containing the key descriptor & key data of an EAPOL-Key frame.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct EapolKeySlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> EapolKeySlice<'a> {
+    /// Creates a key descriptor slice from the body of an EAPOL-Key frame.
+    ///
+    /// The slice is cut to the key descriptor & the key data length. An
+    /// error is returned if the slice is shorter.
+    pub fn from_slice(slice: &'a [u8]) -> Result<EapolKeySlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < EapolKey::LEN {
+            return Err(UnexpectedEndOfSlice(EapolKey::LEN));
+        }
+        let len = EapolKey::LEN + usize::from(u16::from_be_bytes([slice[93], slice[94]]));
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(EapolKeySlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // len before this code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Returns the slice containing the key descriptor & key data.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the key descriptor type.
+    #[inline]
+    pub fn descriptor_type(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the raw key information field.
+    #[inline]
+    pub fn key_information_raw(&self) -> u16 {
+        u16::from_be_bytes([self.slice[1], self.slice[2]])
+    }
+
+    /// Read & decode the key information field.
+    #[inline]
+    pub fn key_information(&self) -> EapolKeyInformation {
+        EapolKeyInformation::from_u16(self.key_information_raw())
+    }
+
+    /// Read the key length.
+    #[inline]
+    pub fn key_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[3], self.slice[4]])
+    }
+
+    /// Read the replay counter.
+    #[inline]
+    pub fn replay_counter(&self) -> u64 {
+        self.u64_at(5)
+    }
+
+    /// Returns the nonce.
+    #[inline]
+    pub fn nonce(&self) -> &'a [u8;32] {
+        self.slice[13..45].try_into().unwrap()
+    }
+
+    /// Returns the EAPOL-Key IV.
+    #[inline]
+    pub fn iv(&self) -> &'a [u8;16] {
+        self.slice[45..61].try_into().unwrap()
+    }
+
+    /// Read the receive sequence counter.
+    #[inline]
+    pub fn rsc(&self) -> u64 {
+        self.u64_at(61)
+    }
+
+    /// Read the reserved field (key identifier in WPA key descriptors).
+    #[inline]
+    pub fn reserved(&self) -> u64 {
+        self.u64_at(69)
+    }
+
+    /// Returns the message integrity code.
+    #[inline]
+    pub fn mic(&self) -> &'a [u8;16] {
+        self.slice[77..93].try_into().unwrap()
+    }
+
+    /// Read the key data length.
+    #[inline]
+    pub fn key_data_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[93], self.slice[94]])
+    }
+
+    /// Returns the key data (e.g. the RSN information element or the
+    /// encrypted group key).
+    #[inline]
+    pub fn key_data(&self) -> &'a [u8] {
+        &self.slice[EapolKey::LEN..]
+    }
+
+    /// Decode all the fields of the key descriptor and copy the results to
+    /// a [`EapolKey`] struct.
+    pub fn to_header(&self) -> EapolKey {
+        EapolKey {
+            descriptor_type: self.descriptor_type(),
+            key_information: self.key_information(),
+            key_length: self.key_length(),
+            replay_counter: self.replay_counter(),
+            nonce: *self.nonce(),
+            iv: *self.iv(),
+            rsc: self.rsc(),
+            reserved: self.reserved(),
+            mic: *self.mic(),
+            key_data_length: self.key_data_length(),
+        }
+    }
+
+    fn u64_at(&self, offset: usize) -> u64 {
+        let mut value = [0u8;8];
+        value.copy_from_slice(&self.slice[offset..offset + 8]);
+        u64::from_be_bytes(value)
+    }
+}
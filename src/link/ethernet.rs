@@ -4,7 +4,8 @@ use std::slice::from_raw_parts;
 use std::io;
 
 /// Ether type enum present in ethernet II header.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum EtherType {
     Ipv4 = 0x0800,
     Ipv6 = 0x86dd,
@@ -12,7 +13,22 @@ pub enum EtherType {
     WakeOnLan = 0x0842,
     VlanTaggedFrame = 0x8100,
     ProviderBridging = 0x88A8,
-    VlanDoubleTaggedFrame = 0x9100
+    VlanDoubleTaggedFrame = 0x9100,
+    RedundancyTag = 0xF1C1,
+    MacControl = 0x8808,
+    MplsUnicast = 0x8847,
+    MplsMulticast = 0x8848,
+    TransparentEthernetBridging = 0x6558,
+    Nsh = 0x894F,
+    PppoeDiscovery = 0x8863,
+    PppoeSession = 0x8864,
+    Lldp = 0x88CC,
+    Ipx = 0x8137,
+    AppleTalk = 0x809B,
+    ErspanType2 = 0x88BE,
+    ErspanType3 = 0x22EB,
+    Macsec = 0x88E5,
+    Eapol = 0x888E
 }
 
 impl EtherType {
@@ -27,6 +43,21 @@ impl EtherType {
             0x88A8 => Some(ProviderBridging),
             0x8100 => Some(VlanTaggedFrame),
             0x9100 => Some(VlanDoubleTaggedFrame),
+            0xF1C1 => Some(RedundancyTag),
+            0x8808 => Some(MacControl),
+            0x8847 => Some(MplsUnicast),
+            0x8848 => Some(MplsMulticast),
+            0x6558 => Some(TransparentEthernetBridging),
+            0x894F => Some(Nsh),
+            0x8863 => Some(PppoeDiscovery),
+            0x8864 => Some(PppoeSession),
+            0x88CC => Some(Lldp),
+            0x8137 => Some(Ipx),
+            0x809B => Some(AppleTalk),
+            0x88BE => Some(ErspanType2),
+            0x22EB => Some(ErspanType3),
+            0x88E5 => Some(Macsec),
+            0x888E => Some(Eapol),
             _ => None
         }
     }
@@ -54,10 +85,26 @@ pub mod ether_type {
     pub const VLAN_TAGGED_FRAME: u16 = VlanTaggedFrame as u16;
     pub const PROVIDER_BRIDGING: u16 = ProviderBridging as u16;
     pub const VLAN_DOUBLE_TAGGED_FRAME: u16 = VlanDoubleTaggedFrame as u16;
+    pub const REDUNDANCY_TAG: u16 = RedundancyTag as u16;
+    pub const MAC_CONTROL: u16 = MacControl as u16;
+    pub const MPLS_UNICAST: u16 = MplsUnicast as u16;
+    pub const MPLS_MULTICAST: u16 = MplsMulticast as u16;
+    pub const TRANSPARENT_ETHERNET_BRIDGING: u16 = TransparentEthernetBridging as u16;
+    pub const NSH: u16 = Nsh as u16;
+    pub const PPPOE_DISCOVERY: u16 = PppoeDiscovery as u16;
+    pub const PPPOE_SESSION: u16 = PppoeSession as u16;
+    pub const LLDP: u16 = Lldp as u16;
+    pub const IPX: u16 = Ipx as u16;
+    pub const APPLETALK: u16 = AppleTalk as u16;
+    pub const ERSPAN_TYPE_2: u16 = ErspanType2 as u16;
+    pub const ERSPAN_TYPE_3: u16 = ErspanType3 as u16;
+    pub const MACSEC: u16 = Macsec as u16;
+    pub const EAPOL: u16 = Eapol as u16;
 }
 
 ///Ethernet II header.
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Ethernet2Header {
     pub source: [u8;6],
     pub destination: [u8;6],
@@ -179,10 +226,45 @@ impl Ethernet2Header {
             ether_type_be[1],
         ]
     }
+
+    /// Sets the source mac address.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{Ethernet2Header, ether_type};
+    ///
+    /// let header = Ethernet2Header::default()
+    ///     .with_source([1,2,3,4,5,6])
+    ///     .with_destination([7,8,9,10,11,12])
+    ///     .with_ether_type(ether_type::IPV4);
+    /// assert_eq!([1,2,3,4,5,6], header.source);
+    /// assert_eq!([7,8,9,10,11,12], header.destination);
+    /// assert_eq!(ether_type::IPV4, header.ether_type);
+    /// ```
+    #[inline]
+    pub fn with_source(mut self, source: [u8;6]) -> Ethernet2Header {
+        self.source = source;
+        self
+    }
+
+    /// Sets the destination mac address.
+    #[inline]
+    pub fn with_destination(mut self, destination: [u8;6]) -> Ethernet2Header {
+        self.destination = destination;
+        self
+    }
+
+    /// Sets the ether type of the payload.
+    #[inline]
+    pub fn with_ether_type(mut self, ether_type: u16) -> Ethernet2Header {
+        self.ether_type = ether_type;
+        self
+    }
 }
 
 ///A slice containing an ethernet 2 header of a network package.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Ethernet2HeaderSlice<'a> {
     slice: &'a [u8]
 }
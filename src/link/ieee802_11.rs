@@ -0,0 +1,432 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Values of the type field in the frame control of an IEEE 802.11 frame.
+pub mod ieee80211_frame_type {
+    /// Management frames (beacons, probes, authentication, association, ...).
+    pub const MANAGEMENT: u8 = 0;
+    /// Control frames (RTS, CTS, ACK, block ack, ...).
+    pub const CONTROL: u8 = 1;
+    /// Data frames.
+    pub const DATA: u8 = 2;
+    /// Extension frames (DMG & S1G beacons).
+    pub const EXTENSION: u8 = 3;
+}
+
+/// Frame control field of an IEEE 802.11 MAC header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ieee80211FrameControl {
+    /// Protocol version (2 bits, always 0).
+    pub protocol_version: u8,
+    /// Frame type (2 bits, see [`ieee80211_frame_type`]).
+    pub frame_type: u8,
+    /// Subtype of the frame (4 bits).
+    pub subtype: u8,
+    /// The frame is sent to the distribution system.
+    pub to_ds: bool,
+    /// The frame is sent from the distribution system.
+    pub from_ds: bool,
+    /// Further fragments of the frame follow.
+    pub more_fragments: bool,
+    /// The frame is a retransmission.
+    pub retry: bool,
+    /// The sender enters the power save mode after the frame.
+    pub power_management: bool,
+    /// Further buffered frames are available for the receiver.
+    pub more_data: bool,
+    /// The frame body is protected (encrypted).
+    pub protected: bool,
+    /// HT control field present (QoS data & management frames).
+    pub order: bool,
+}
+
+impl Ieee80211FrameControl {
+    /// Decodes the frame control from its serialized form.
+    pub fn from_bytes(bytes: [u8;2]) -> Ieee80211FrameControl {
+        Ieee80211FrameControl {
+            protocol_version: bytes[0] & 0x3,
+            frame_type: (bytes[0] >> 2) & 0x3,
+            subtype: bytes[0] >> 4,
+            to_ds: 0 != bytes[1] & 0x01,
+            from_ds: 0 != bytes[1] & 0x02,
+            more_fragments: 0 != bytes[1] & 0x04,
+            retry: 0 != bytes[1] & 0x08,
+            power_management: 0 != bytes[1] & 0x10,
+            more_data: 0 != bytes[1] & 0x20,
+            protected: 0 != bytes[1] & 0x40,
+            order: 0 != bytes[1] & 0x80,
+        }
+    }
+
+    /// Returns the serialized form of the frame control (values exceeding
+    /// the field sizes are truncated).
+    pub fn to_bytes(&self) -> [u8;2] {
+        let flag = |value: bool, bit: u8| if value { bit } else { 0 };
+        [
+            (self.protocol_version & 0x3) | ((self.frame_type & 0x3) << 2) | (self.subtype << 4),
+            flag(self.to_ds, 0x01) |
+            flag(self.from_ds, 0x02) |
+            flag(self.more_fragments, 0x04) |
+            flag(self.retry, 0x08) |
+            flag(self.power_management, 0x10) |
+            flag(self.more_data, 0x20) |
+            flag(self.protected, 0x40) |
+            flag(self.order, 0x80),
+        ]
+    }
+
+    /// Returns true if the frame is a data frame with a QoS control field.
+    #[inline]
+    pub fn is_qos_data(&self) -> bool {
+        ieee80211_frame_type::DATA == self.frame_type && 0 != self.subtype & 0x8
+    }
+
+    /// Returns true if the frame is a data frame with a frame body (not a
+    /// null function frame).
+    #[inline]
+    pub fn has_data(&self) -> bool {
+        ieee80211_frame_type::DATA == self.frame_type && 0 == self.subtype & 0x4
+    }
+
+    /// Returns true if the header contains the fourth address (data frames
+    /// sent within the distribution system).
+    #[inline]
+    pub fn has_address4(&self) -> bool {
+        ieee80211_frame_type::DATA == self.frame_type && self.to_ds && self.from_ds
+    }
+
+    /// Returns true if the header contains a HT control field.
+    #[inline]
+    pub fn has_ht_control(&self) -> bool {
+        self.order && (self.is_qos_data() || ieee80211_frame_type::MANAGEMENT == self.frame_type)
+    }
+
+    /// Length of the MAC header of a frame with this frame control.
+    pub fn header_len(&self) -> usize {
+        use self::ieee80211_frame_type::*;
+        let base = match self.frame_type {
+            MANAGEMENT => 24,
+            // cts, ack & control wrapper frames only contain the receiver
+            // address (the carried frame control & HT control of control
+            // wrapper frames are part of the payload)
+            CONTROL => match self.subtype {
+                0x7 | 0xc | 0xd => 10,
+                _ => 16,
+            },
+            DATA => 24,
+            _ => 10,
+        };
+        base +
+            if self.has_address4() { 6 } else { 0 } +
+            if self.is_qos_data() { 2 } else { 0 } +
+            if self.has_ht_control() { 4 } else { 0 }
+    }
+}
+
+/// IEEE 802.11 MAC header (Wi-Fi).
+///
+/// The fields present depend on the frame control: control frames only
+/// contain the first one or two addresses, `address4` is only present in
+/// data frames with `to_ds` & `from_ds` set & `qos_control` in QoS data
+/// frames.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ieee80211_frame_type, Ieee80211FrameControl, Ieee80211Header};
+///
+/// let header = Ieee80211Header {
+///     frame_control: Ieee80211FrameControl {
+///         frame_type: ieee80211_frame_type::DATA,
+///         to_ds: true,
+///         ..Default::default()
+///     },
+///     duration_id: 0,
+///     address1: [1;6],
+///     address2: Some([2;6]),
+///     address3: Some([3;6]),
+///     sequence_control: Some(0x10),
+///     address4: None,
+///     qos_control: None,
+///     ht_control: None,
+/// };
+/// assert_eq!(24, header.header_len());
+/// assert_eq!(Some([1;6]), header.bssid());
+/// assert_eq!(Some([2;6]), header.source());
+/// assert_eq!(Some([3;6]), header.destination());
+///
+/// let mut serialized = Vec::new();
+/// header.write(&mut serialized).unwrap();
+/// let (decoded, _) = Ieee80211Header::from_slice(&serialized).unwrap();
+/// assert_eq!(header, decoded);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Ieee80211Header {
+    /// Frame control (type, subtype & flags).
+    pub frame_control: Ieee80211FrameControl,
+    /// Duration in microseconds or association id (PS-Poll frames).
+    pub duration_id: u16,
+    /// Receiver address.
+    pub address1: [u8;6],
+    /// Transmitter address (not present in CTS & ACK frames).
+    pub address2: Option<[u8;6]>,
+    /// Third address (management & data frames).
+    pub address3: Option<[u8;6]>,
+    /// Fragment number (4 bits) & sequence number (12 bits) (management & data frames).
+    pub sequence_control: Option<u16>,
+    /// Fourth address (data frames with `to_ds` & `from_ds` set).
+    pub address4: Option<[u8;6]>,
+    /// QoS control (QoS data frames).
+    pub qos_control: Option<u16>,
+    /// HT control (QoS data & management frames with the order flag set).
+    pub ht_control: Option<u32>,
+}
+
+impl Ieee80211Header {
+    /// Read an Ieee80211Header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(Ieee80211Header, &[u8]), ReadError> {
+        let header = Ieee80211HeaderSlice::from_slice(slice)?;
+        Ok((
+            header.to_header(),
+            &slice[header.slice().len()..]
+        ))
+    }
+
+    /// Writes the header to the current position of the write argument.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.frame_control.to_bytes())?;
+        writer.write_all(&self.duration_id.to_le_bytes())?;
+        writer.write_all(&self.address1)?;
+        if let Some(address2) = &self.address2 {
+            writer.write_all(address2)?;
+        }
+        if let Some(address3) = &self.address3 {
+            writer.write_all(address3)?;
+        }
+        if let Some(sequence_control) = self.sequence_control {
+            writer.write_all(&sequence_control.to_le_bytes())?;
+        }
+        if let Some(address4) = &self.address4 {
+            writer.write_all(address4)?;
+        }
+        if let Some(qos_control) = self.qos_control {
+            writer.write_all(&qos_control.to_le_bytes())?;
+        }
+        if let Some(ht_control) = self.ht_control {
+            writer.write_all(&ht_control.to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        10 +
+            self.address2.map_or(0, |_| 6) +
+            self.address3.map_or(0, |_| 6) +
+            self.sequence_control.map_or(0, |_| 2) +
+            self.address4.map_or(0, |_| 6) +
+            self.qos_control.map_or(0, |_| 2) +
+            self.ht_control.map_or(0, |_| 4)
+    }
+
+    /// Returns the destination, source & bssid addresses of management &
+    /// data frames based on the `to_ds` & `from_ds` flags.
+    fn ds_addresses(&self) -> [Option<[u8;6]>;3] {
+        use self::ieee80211_frame_type::*;
+        let fc = &self.frame_control;
+        match (fc.frame_type, fc.to_ds, fc.from_ds) {
+            (MANAGEMENT, _, _) | (DATA, false, false) => [Some(self.address1), self.address2, self.address3],
+            (DATA, true, false) => [self.address3, self.address2, Some(self.address1)],
+            (DATA, false, true) => [Some(self.address1), self.address3, self.address2],
+            (DATA, true, true) => [self.address3, self.address4, None],
+            _ => [None, None, None],
+        }
+    }
+
+    /// Destination address of management & data frames.
+    #[inline]
+    pub fn destination(&self) -> Option<[u8;6]> {
+        self.ds_addresses()[0]
+    }
+
+    /// Source address of management & data frames.
+    #[inline]
+    pub fn source(&self) -> Option<[u8;6]> {
+        self.ds_addresses()[1]
+    }
+
+    /// BSSID of management & data frames (`None` for frames sent within
+    /// the distribution system).
+    #[inline]
+    pub fn bssid(&self) -> Option<[u8;6]> {
+        self.ds_addresses()[2]
+    }
+
+    /// Traffic identifier from the QoS control field.
+    #[inline]
+    pub fn tid(&self) -> Option<u8> {
+        self.qos_control.map(|value| (value & 0xf) as u8)
+    }
+}
+
+/// A slice containing an IEEE 802.11 MAC header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ieee80211HeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> Ieee80211HeaderSlice<'a> {
+
+    /// Creates an IEEE 802.11 MAC header slice from an other slice (the
+    /// header length is determined by the frame control).
+    pub fn from_slice(slice: &'a [u8]) -> Result<Ieee80211HeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < 2 {
+            return Err(UnexpectedEndOfSlice(2));
+        }
+        let frame_control = Ieee80211FrameControl::from_bytes([slice[0], slice[1]]);
+        if 0 != frame_control.protocol_version {
+            return Err(Ieee80211UnsupportedVersion(frame_control.protocol_version));
+        }
+        let len = frame_control.header_len();
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        Ok(Ieee80211HeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // the header length before this.
+            slice: unsafe {
+                from_raw_parts(slice.as_ptr(), len)
+            }
+        })
+    }
+
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Decode the frame control field.
+    #[inline]
+    pub fn frame_control(&self) -> Ieee80211FrameControl {
+        Ieee80211FrameControl::from_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the duration or association id field.
+    #[inline]
+    pub fn duration_id(&self) -> u16 {
+        u16::from_le_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Returns the address at the given offset (if the header is long enough).
+    fn address_at(&self, offset: usize) -> Option<[u8;6]> {
+        let mut result = [0;6];
+        result.copy_from_slice(self.slice.get(offset..offset + 6)?);
+        Some(result)
+    }
+
+    /// Read the receiver address.
+    #[inline]
+    pub fn address1(&self) -> [u8;6] {
+        let mut result = [0;6];
+        result.copy_from_slice(&self.slice[4..10]);
+        result
+    }
+
+    /// Read the transmitter address (not present in CTS & ACK frames).
+    #[inline]
+    pub fn address2(&self) -> Option<[u8;6]> {
+        self.address_at(10)
+    }
+
+    /// Read the third address (management & data frames).
+    #[inline]
+    pub fn address3(&self) -> Option<[u8;6]> {
+        match self.frame_control().frame_type {
+            ieee80211_frame_type::MANAGEMENT | ieee80211_frame_type::DATA => self.address_at(16),
+            _ => None,
+        }
+    }
+
+    /// Read the sequence control field (management & data frames).
+    #[inline]
+    pub fn sequence_control(&self) -> Option<u16> {
+        self.address3().map(|_| u16::from_le_bytes([self.slice[22], self.slice[23]]))
+    }
+
+    /// Read the sequence number (upper 12 bits of the sequence control).
+    #[inline]
+    pub fn sequence_number(&self) -> Option<u16> {
+        self.sequence_control().map(|value| value >> 4)
+    }
+
+    /// Read the fragment number (lower 4 bits of the sequence control).
+    #[inline]
+    pub fn fragment_number(&self) -> Option<u8> {
+        self.sequence_control().map(|value| (value & 0xf) as u8)
+    }
+
+    /// Read the fourth address (data frames with `to_ds` & `from_ds` set).
+    #[inline]
+    pub fn address4(&self) -> Option<[u8;6]> {
+        if self.frame_control().has_address4() {
+            self.address_at(24)
+        } else {
+            None
+        }
+    }
+
+    /// Read the QoS control field (QoS data frames).
+    pub fn qos_control(&self) -> Option<u16> {
+        let frame_control = self.frame_control();
+        if frame_control.is_qos_data() {
+            let offset = if frame_control.has_address4() { 30 } else { 24 };
+            Some(u16::from_le_bytes([self.slice[offset], self.slice[offset + 1]]))
+        } else {
+            None
+        }
+    }
+
+    /// Read the HT control field (QoS data & management frames with the
+    /// order flag set).
+    pub fn ht_control(&self) -> Option<u32> {
+        if self.frame_control().has_ht_control() {
+            let o = self.slice.len() - 4;
+            Some(u32::from_le_bytes([self.slice[o], self.slice[o + 1], self.slice[o + 2], self.slice[o + 3]]))
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the frame body starts with an 802.2 LLC header
+    /// (unprotected data frames with a body that is not an A-MSDU).
+    pub fn has_llc_payload(&self) -> bool {
+        let frame_control = self.frame_control();
+        frame_control.has_data() &&
+        false == frame_control.protected &&
+        // a-msdu present flag
+        0 == self.qos_control().unwrap_or(0) & 0x80
+    }
+
+    /// Decode all the fields and copy the results to a [`Ieee80211Header`] struct.
+    pub fn to_header(&self) -> Ieee80211Header {
+        Ieee80211Header {
+            frame_control: self.frame_control(),
+            duration_id: self.duration_id(),
+            address1: self.address1(),
+            address2: self.address2(),
+            address3: self.address3(),
+            sequence_control: self.sequence_control(),
+            address4: self.address4(),
+            qos_control: self.qos_control(),
+            ht_control: self.ht_control(),
+        }
+    }
+}
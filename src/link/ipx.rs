@@ -0,0 +1,262 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// IPX packet types.
+pub mod ipx_packet_type {
+    /// Unknown packet type.
+    pub const UNKNOWN: u8 = 0;
+    /// Routing information protocol.
+    pub const RIP: u8 = 1;
+    /// Echo packet.
+    pub const ECHO: u8 = 2;
+    /// Error packet.
+    pub const ERROR: u8 = 3;
+    /// Packet exchange protocol (also used for SAP).
+    pub const PEP: u8 = 4;
+    /// Sequenced packet exchange.
+    pub const SPX: u8 = 5;
+    /// NetWare core protocol.
+    pub const NCP: u8 = 17;
+    /// NetBIOS.
+    pub const NETBIOS: u8 = 20;
+}
+
+/// Address of an IPX node (network, node & socket number).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IpxAddress {
+    /// Network number (0 for the local network).
+    pub network: u32,
+    /// Node number (usually the MAC address of the node).
+    pub node: [u8;6],
+    /// Socket number.
+    pub socket: u16,
+}
+
+impl IpxAddress {
+    /// Decodes an address from its serialized form.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;12]) -> IpxAddress {
+        IpxAddress {
+            network: u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            node: [bytes[4], bytes[5], bytes[6], bytes[7], bytes[8], bytes[9]],
+            socket: u16::from_be_bytes([bytes[10], bytes[11]]),
+        }
+    }
+
+    /// Returns the serialized form of the address.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;12] {
+        let network = self.network.to_be_bytes();
+        let socket = self.socket.to_be_bytes();
+        [
+            network[0], network[1], network[2], network[3],
+            self.node[0], self.node[1], self.node[2],
+            self.node[3], self.node[4], self.node[5],
+            socket[0], socket[1],
+        ]
+    }
+}
+
+/// Novell IPX header (ether type 0x8137).
+///
+/// IPX packets are carried in ethernet II frames with the ether type
+/// [`ether_type::IPX`] or in 802.3 frames with a LLC/SNAP or raw 802.3
+/// header. [`SlicedPacket`] & [`PacketHeaders`] leave the IPX packet in the
+/// payload, it can be decoded with [`IpxHeaderSlice::from_slice`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, Ethernet2Header, IpxAddress, IpxHeader, IpxHeaderSlice, ipx_packet_type, SerializedSize, SlicedPacket};
+///
+/// let header = IpxHeader {
+///     length: (IpxHeader::SERIALIZED_SIZE + 2) as u16,
+///     packet_type: ipx_packet_type::PEP,
+///     destination: IpxAddress{ network: 1, node: [0xff;6], socket: 0x0452 },
+///     source: IpxAddress{ network: 1, node: [1,2,3,4,5,6], socket: 0x0452 },
+///     ..Default::default()
+/// };
+/// let mut packet = Vec::new();
+/// Ethernet2Header {
+///     source: [1,2,3,4,5,6],
+///     destination: [0xff;6],
+///     ether_type: ether_type::IPX,
+/// }.write(&mut packet).unwrap();
+/// header.write(&mut packet).unwrap();
+/// packet.extend_from_slice(&[0, 1]);
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// let ipx = IpxHeaderSlice::from_slice(sliced.payload).unwrap();
+/// assert_eq!(header, ipx.to_header());
+/// assert_eq!(&[0, 1], ipx.payload());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IpxHeader {
+    /// Checksum (usually 0xffff as checksums are not used).
+    pub checksum: u16,
+    /// Length of the packet including the header in bytes.
+    pub length: u16,
+    /// Number of routers the packet passed.
+    pub transport_control: u8,
+    /// Type of the packet (see [`ipx_packet_type`]).
+    pub packet_type: u8,
+    /// Destination address.
+    pub destination: IpxAddress,
+    /// Source address.
+    pub source: IpxAddress,
+}
+
+impl SerializedSize for IpxHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 30;
+}
+
+impl Default for IpxHeader {
+    fn default() -> IpxHeader {
+        IpxHeader {
+            checksum: IpxHeader::NO_CHECKSUM,
+            length: IpxHeader::SERIALIZED_SIZE as u16,
+            transport_control: 0,
+            packet_type: ipx_packet_type::UNKNOWN,
+            destination: Default::default(),
+            source: Default::default(),
+        }
+    }
+}
+
+impl IpxHeader {
+    /// Value of the checksum field if no checksum is used.
+    pub const NO_CHECKSUM: u16 = 0xffff;
+
+    /// Read an IPX header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(IpxHeader, &[u8]), ReadError> {
+        Ok((
+            IpxHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[IpxHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Returns the serialized form of the header.
+    pub fn to_bytes(&self) -> [u8;30] {
+        let checksum = self.checksum.to_be_bytes();
+        let length = self.length.to_be_bytes();
+        let mut result = [0u8;30];
+        result[..6].copy_from_slice(&[
+            checksum[0], checksum[1],
+            length[0], length[1],
+            self.transport_control,
+            self.packet_type,
+        ]);
+        result[6..18].copy_from_slice(&self.destination.to_bytes());
+        result[18..].copy_from_slice(&self.source.to_bytes());
+        result
+    }
+
+    /// Writes the header to the given writer.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+/// Slice containing an IPX header & the following payload.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IpxHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IpxHeaderSlice<'a> {
+    /// Creates an IPX header slice from a slice.
+    ///
+    /// The slice is cut to the length given in the length field if the
+    /// length is between the header size & the slice length (ethernet
+    /// padding is not part of the slice).
+    pub fn from_slice(slice: &'a [u8]) -> Result<IpxHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < IpxHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(IpxHeader::SERIALIZED_SIZE));
+        }
+        let length = usize::from(u16::from_be_bytes([slice[2], slice[3]]));
+        let len = if IpxHeader::SERIALIZED_SIZE <= length && length <= slice.len() {
+            length
+        } else {
+            slice.len()
+        };
+        Ok(IpxHeaderSlice {
+            // SAFETY:
+            // Safe as len is smaller or equal to the slice length.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Returns the slice containing the IPX header & payload.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the slice containing the payload following the header.
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.slice[IpxHeader::SERIALIZED_SIZE..]
+    }
+
+    /// Read the checksum field.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the length field.
+    #[inline]
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the transport control field (number of routers passed).
+    #[inline]
+    pub fn transport_control(&self) -> u8 {
+        self.slice[4]
+    }
+
+    /// Read the packet type field.
+    #[inline]
+    pub fn packet_type(&self) -> u8 {
+        self.slice[5]
+    }
+
+    /// Read the destination address.
+    #[inline]
+    pub fn destination(&self) -> IpxAddress {
+        IpxAddress::from_bytes(self.address(6))
+    }
+
+    /// Read the source address.
+    #[inline]
+    pub fn source(&self) -> IpxAddress {
+        IpxAddress::from_bytes(self.address(18))
+    }
+
+    /// Decode all the fields and copy the results to a [`IpxHeader`] struct.
+    pub fn to_header(&self) -> IpxHeader {
+        IpxHeader {
+            checksum: self.checksum(),
+            length: self.length(),
+            transport_control: self.transport_control(),
+            packet_type: self.packet_type(),
+            destination: self.destination(),
+            source: self.source(),
+        }
+    }
+
+    fn address(&self, offset: usize) -> [u8;12] {
+        let mut result = [0u8;12];
+        result.copy_from_slice(&self.slice[offset..offset + 12]);
+        result
+    }
+}
@@ -0,0 +1,355 @@
+use super::super::*;
+
+/// IS-IS PDU types (ISO 10589).
+pub mod isis_pdu_type {
+    /// Level 1 LAN IS to IS hello.
+    pub const L1_LAN_HELLO: u8 = 15;
+    /// Level 2 LAN IS to IS hello.
+    pub const L2_LAN_HELLO: u8 = 16;
+    /// Point to point IS to IS hello.
+    pub const P2P_HELLO: u8 = 17;
+    /// Level 1 link state PDU.
+    pub const L1_LSP: u8 = 18;
+    /// Level 2 link state PDU.
+    pub const L2_LSP: u8 = 20;
+    /// Level 1 complete sequence numbers PDU.
+    pub const L1_CSNP: u8 = 24;
+    /// Level 2 complete sequence numbers PDU.
+    pub const L2_CSNP: u8 = 25;
+    /// Level 1 partial sequence numbers PDU.
+    pub const L1_PSNP: u8 = 26;
+    /// Level 2 partial sequence numbers PDU.
+    pub const L2_PSNP: u8 = 27;
+}
+
+/// Errors that can occur while decoding an IS-IS PDU.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IsisError {
+    /// The slice is too short. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+    /// The first byte is not the IS-IS protocol discriminator (0x83).
+    InvalidProtocolDiscriminator(u8),
+    /// The PDU type is not a hello, link state or sequence numbers PDU.
+    UnsupportedPduType(u8),
+    /// The length indicator does not match the header length of the PDU type.
+    InvalidHeaderLength{
+        /// Type of the PDU.
+        pdu_type: u8,
+        /// Header length given in the length indicator.
+        header_len: u8,
+    },
+    /// The PDU length field is smaller than the header length.
+    InvalidPduLength(u16),
+    /// A TLV extends past the end of the PDU. The value is the offset of
+    /// the TLV in the PDU.
+    InvalidTlvLength(usize),
+}
+
+impl Error for IsisError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for IsisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use IsisError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "IsisError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+            InvalidProtocolDiscriminator(value) => {
+                write!(f, "IsisError: Invalid protocol discriminator {:#04x} (expected 0x83).", value)
+            },
+            UnsupportedPduType(value) => {
+                write!(f, "IsisError: Unsupported PDU type {}.", value)
+            },
+            InvalidHeaderLength{ pdu_type, header_len } => {
+                write!(f, "IsisError: Invalid header length {} for the PDU type {}.", header_len, pdu_type)
+            },
+            InvalidPduLength(value) => {
+                write!(f, "IsisError: PDU length {} is smaller then the header length.", value)
+            },
+            InvalidTlvLength(offset) => {
+                write!(f, "IsisError: The TLV at offset {} extends past the end of the PDU.", offset)
+            },
+        }
+    }
+}
+
+/// TLV contained in an IS-IS PDU.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IsisTlv<'a> {
+    /// Type (code) of the TLV.
+    pub tlv_type: u8,
+    /// Value of the TLV.
+    pub value: &'a [u8],
+}
+
+/// Slice containing an IS-IS hello, link state or sequence numbers PDU.
+///
+/// The slice has to start with the IS-IS header. When IS-IS is carried over
+/// 802.3 the 3 byte LLC header (DSAP & SSAP 0xfe, control 0x03) has to be
+/// skipped first. Padding after the PDU (e.g. the padding of hellos to the
+/// MTU or the ethernet minimum frame size) is not included in the slice.
+///
+/// The TLVs are validated when the slice is created, so the TLV iterator
+/// can be used without further error handling.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{IsisPduSlice, isis_pdu_type};
+///
+/// let pdu = [
+///     0x83, 17, 1, 0, isis_pdu_type::L1_PSNP, 1, 0, 0, // common header
+///     0, 20, // pdu length
+///     1, 2, 3, 4, 5, 6, 0, // source id
+///     129, 1, 0xcc, // protocols supported TLV (ipv4)
+///     0, 0, 0, 0, // padding
+/// ];
+/// let isis = IsisPduSlice::from_slice(&pdu).unwrap();
+/// assert_eq!(isis_pdu_type::L1_PSNP, isis.pdu_type());
+/// assert_eq!([1, 2, 3, 4, 5, 6], isis.source_id());
+///
+/// let tlv = isis.tlvs().next().unwrap();
+/// assert_eq!(129, tlv.tlv_type);
+/// assert_eq!(&[0xcc], tlv.value);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IsisPduSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IsisPduSlice<'a> {
+    /// Protocol discriminator of IS-IS PDUs.
+    pub const PROTOCOL_DISCRIMINATOR: u8 = 0x83;
+
+    /// Value of the LLC DSAP & SSAP identifying IS-IS over 802.3.
+    pub const LLC_SAP: u8 = 0xfe;
+
+    /// Length of the header common to all PDU types.
+    pub const COMMON_HEADER_LEN: usize = 8;
+
+    /// Returns the header length (including the common header) of the given PDU type
+    /// (`None` if the PDU type is unknown).
+    pub fn header_len_of(pdu_type: u8) -> Option<usize> {
+        use isis_pdu_type::*;
+        match pdu_type {
+            L1_LAN_HELLO | L2_LAN_HELLO => Some(27),
+            P2P_HELLO => Some(20),
+            L1_LSP | L2_LSP => Some(27),
+            L1_CSNP | L2_CSNP => Some(33),
+            L1_PSNP | L2_PSNP => Some(17),
+            _ => None,
+        }
+    }
+
+    /// Decodes & validates an IS-IS PDU.
+    pub fn from_slice(slice: &'a [u8]) -> Result<IsisPduSlice<'a>, IsisError> {
+        use IsisError::*;
+
+        if slice.len() < IsisPduSlice::COMMON_HEADER_LEN {
+            return Err(UnexpectedEndOfSlice(IsisPduSlice::COMMON_HEADER_LEN));
+        }
+        if IsisPduSlice::PROTOCOL_DISCRIMINATOR != slice[0] {
+            return Err(InvalidProtocolDiscriminator(slice[0]));
+        }
+        let pdu_type = slice[4] & 0b1_1111;
+        let header_len = match IsisPduSlice::header_len_of(pdu_type) {
+            Some(value) => value,
+            None => return Err(UnsupportedPduType(pdu_type)),
+        };
+        if header_len != usize::from(slice[1]) {
+            return Err(InvalidHeaderLength{ pdu_type, header_len: slice[1] });
+        }
+        if slice.len() < header_len {
+            return Err(UnexpectedEndOfSlice(header_len));
+        }
+
+        let pdu_len_offset = IsisPduSlice::pdu_len_offset(pdu_type);
+        let pdu_len = u16::from_be_bytes([slice[pdu_len_offset], slice[pdu_len_offset + 1]]);
+        if usize::from(pdu_len) < header_len {
+            return Err(InvalidPduLength(pdu_len));
+        }
+        if slice.len() < usize::from(pdu_len) {
+            return Err(UnexpectedEndOfSlice(usize::from(pdu_len)));
+        }
+        let slice = &slice[..usize::from(pdu_len)];
+
+        // validate the tlvs
+        let mut offset = header_len;
+        while offset < slice.len() {
+            if offset + 2 > slice.len() || offset + 2 + usize::from(slice[offset + 1]) > slice.len() {
+                return Err(InvalidTlvLength(offset));
+            }
+            offset += 2 + usize::from(slice[offset + 1]);
+        }
+
+        Ok(IsisPduSlice { slice })
+    }
+
+    /// Offset of the PDU length field in the header.
+    fn pdu_len_offset(pdu_type: u8) -> usize {
+        use isis_pdu_type::*;
+        match pdu_type {
+            // after circuit type, source id & holding time
+            L1_LAN_HELLO | L2_LAN_HELLO | P2P_HELLO => 17,
+            _ => 8,
+        }
+    }
+
+    /// Returns the slice containing the PDU (without padding).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Type of the PDU (see [`isis_pdu_type`]).
+    #[inline]
+    pub fn pdu_type(&self) -> u8 {
+        self.slice[4] & 0b1_1111
+    }
+
+    /// Length of the header (value of the length indicator field).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        usize::from(self.slice[1])
+    }
+
+    /// Length of the system id (the value 0 of the id length field is decoded as 6).
+    #[inline]
+    pub fn id_len(&self) -> u8 {
+        match self.slice[3] {
+            0 => 6,
+            value => value,
+        }
+    }
+
+    /// Maximum number of area addresses (the value 0 of the field is decoded as 3).
+    #[inline]
+    pub fn max_area_addresses(&self) -> u8 {
+        match self.slice[7] {
+            0 => 3,
+            value => value,
+        }
+    }
+
+    /// Returns true if the PDU is a hello PDU.
+    pub fn is_hello(&self) -> bool {
+        use isis_pdu_type::*;
+        matches!(self.pdu_type(), L1_LAN_HELLO | L2_LAN_HELLO | P2P_HELLO)
+    }
+
+    /// Returns true if the PDU is a link state PDU.
+    pub fn is_lsp(&self) -> bool {
+        use isis_pdu_type::*;
+        matches!(self.pdu_type(), L1_LSP | L2_LSP)
+    }
+
+    /// Returns true if the PDU is a (complete or partial) sequence numbers PDU.
+    pub fn is_snp(&self) -> bool {
+        use isis_pdu_type::*;
+        matches!(self.pdu_type(), L1_CSNP | L2_CSNP | L1_PSNP | L2_PSNP)
+    }
+
+    /// Length of the PDU in bytes (value of the PDU length field).
+    #[inline]
+    pub fn pdu_len(&self) -> u16 {
+        self.slice.len() as u16
+    }
+
+    /// Returns the system id of the sender (hellos & sequence numbers PDUs)
+    /// or the system id part of the LSP id (link state PDUs).
+    pub fn source_id(&self) -> [u8;6] {
+        let offset = if self.is_hello() {
+            // after the circuit type
+            IsisPduSlice::COMMON_HEADER_LEN + 1
+        } else if self.is_lsp() {
+            // after pdu length & remaining lifetime
+            IsisPduSlice::COMMON_HEADER_LEN + 4
+        } else {
+            // after the pdu length
+            IsisPduSlice::COMMON_HEADER_LEN + 2
+        };
+        let mut result = [0u8;6];
+        result.copy_from_slice(&self.slice[offset..offset + 6]);
+        result
+    }
+
+    /// Holding time in seconds (hellos only).
+    pub fn holding_time(&self) -> Option<u16> {
+        if self.is_hello() {
+            Some(u16::from_be_bytes([self.slice[15], self.slice[16]]))
+        } else {
+            None
+        }
+    }
+
+    /// Remaining lifetime in seconds (link state PDUs only).
+    pub fn remaining_lifetime(&self) -> Option<u16> {
+        if self.is_lsp() {
+            Some(u16::from_be_bytes([self.slice[10], self.slice[11]]))
+        } else {
+            None
+        }
+    }
+
+    /// LSP id (system id, pseudonode id & fragment number, link state PDUs only).
+    pub fn lsp_id(&self) -> Option<[u8;8]> {
+        if self.is_lsp() {
+            let mut result = [0u8;8];
+            result.copy_from_slice(&self.slice[12..20]);
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Sequence number (link state PDUs only).
+    pub fn sequence_number(&self) -> Option<u32> {
+        if self.is_lsp() {
+            Some(u32::from_be_bytes([self.slice[20], self.slice[21], self.slice[22], self.slice[23]]))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the slice containing the PDU specific part of the header (after the common header).
+    #[inline]
+    pub fn pdu_header(&self) -> &'a [u8] {
+        &self.slice[IsisPduSlice::COMMON_HEADER_LEN..self.header_len()]
+    }
+
+    /// Returns an iterator over the TLVs of the PDU.
+    pub fn tlvs(&self) -> IsisTlvIterator<'a> {
+        IsisTlvIterator {
+            rest: &self.slice[self.header_len()..],
+        }
+    }
+}
+
+/// Iterator over the TLVs of an IS-IS PDU.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IsisTlvIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for IsisTlvIterator<'a> {
+    type Item = IsisTlv<'a>;
+
+    fn next(&mut self) -> Option<IsisTlv<'a>> {
+        // the tlvs were validated in IsisPduSlice::from_slice
+        if self.rest.len() < 2 {
+            None
+        } else {
+            let end = 2 + usize::from(self.rest[1]);
+            let result = IsisTlv {
+                tlv_type: self.rest[0],
+                value: &self.rest[2..end],
+            };
+            self.rest = &self.rest[end..];
+            Some(result)
+        }
+    }
+}
@@ -0,0 +1,573 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+use std::io;
+
+/// `u16` constants of the packet type values of Linux cooked capture headers
+/// (see [`LinuxSllHeader::packet_type`] & [`LinuxSll2Header::packet_type`]).
+pub mod linux_sll_packet_type {
+    /// Packet addressed to the capturing host.
+    pub const HOST: u16 = 0;
+    /// Packet broadcasted by another host.
+    pub const BROADCAST: u16 = 1;
+    /// Packet multicasted by another host.
+    pub const MULTICAST: u16 = 2;
+    /// Packet sent by another host to another host (promiscuous mode).
+    pub const OTHERHOST: u16 = 3;
+    /// Packet sent by the capturing host.
+    pub const OUTGOING: u16 = 4;
+}
+
+/// Linux cooked capture header (`LINKTYPE_LINUX_SLL`), used by libpcap
+/// for packets captured on the Linux "any" device & devices without a
+/// link layer header.
+///
+/// The protocol type contains the ether type of the following header for
+/// most captured packets (Linux specific values below 0x0600, like 0x0004
+/// for 802.2 LLC frames or netlink families of `ARPHRD_NETLINK` devices,
+/// are left in the payload).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, LinuxSllHeader, linux_sll_packet_type, PacketBuilder, SlicedPacket};
+///
+/// let header = LinuxSllHeader {
+///     packet_type: linux_sll_packet_type::OUTGOING,
+///     arp_hardware_type: 1,
+///     address_len: 6,
+///     address: [1,2,3,4,5,6,0,0],
+///     protocol_type: ether_type::IPV4,
+/// };
+/// let mut packet = Vec::new();
+/// header.write(&mut packet).unwrap();
+/// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .write(&mut packet, &[])
+///     .unwrap();
+///
+/// let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+/// let sll = sliced.linux_sll.unwrap();
+/// assert_eq!(&[1,2,3,4,5,6], sll.link_address());
+/// assert_eq!(ether_type::IPV4, sll.protocol_type());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LinuxSllHeader {
+    /// Direction & addressing of the packet (see [`linux_sll_packet_type`]).
+    pub packet_type: u16,
+    /// Linux `ARPHRD_` type of the capturing device (see [`arp_hardware_type`]).
+    pub arp_hardware_type: u16,
+    /// Length of the link layer address of the sender (can be bigger then
+    /// the 8 bytes of the `address` field).
+    pub address_len: u16,
+    /// Link layer address of the sender (the first 8 bytes, zero padded).
+    pub address: [u8;8],
+    /// Protocol type of the following header (usually an ether type).
+    pub protocol_type: u16,
+}
+
+impl SerializedSize for LinuxSllHeader {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 16;
+}
+
+impl LinuxSllHeader {
+
+    /// Read a LinuxSllHeader from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(LinuxSllHeader, &[u8]), ReadError> {
+        Ok((
+            LinuxSllHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[LinuxSllHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Reads a Linux cooked capture header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<LinuxSllHeader, io::Error> {
+        let mut buffer = [0;LinuxSllHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(LinuxSllHeaderSlice{
+            slice: &buffer
+        }.to_header())
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        LinuxSllHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns the used part of the link layer address (at most 8 bytes).
+    #[inline]
+    pub fn link_address(&self) -> &[u8] {
+        &self.address[..usize::from(self.address_len).min(8)]
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    pub fn to_bytes(&self) -> [u8;16] {
+        let packet_type = self.packet_type.to_be_bytes();
+        let arp_hardware_type = self.arp_hardware_type.to_be_bytes();
+        let address_len = self.address_len.to_be_bytes();
+        let protocol_type = self.protocol_type.to_be_bytes();
+        [
+            packet_type[0],
+            packet_type[1],
+            arp_hardware_type[0],
+            arp_hardware_type[1],
+            address_len[0],
+            address_len[1],
+            self.address[0],
+            self.address[1],
+            self.address[2],
+            self.address[3],
+            self.address[4],
+            self.address[5],
+            self.address[6],
+            self.address[7],
+            protocol_type[0],
+            protocol_type[1],
+        ]
+    }
+}
+
+/// A slice containing a Linux cooked capture header (`LINKTYPE_LINUX_SLL`).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LinuxSllHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> LinuxSllHeaderSlice<'a> {
+
+    /// Creates a Linux cooked capture header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<LinuxSllHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < LinuxSllHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(LinuxSllHeader::SERIALIZED_SIZE));
+        }
+
+        Ok(LinuxSllHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // LinuxSllHeader::SERIALIZED_SIZE (16) before this.
+            slice: unsafe {
+                from_raw_parts(slice.as_ptr(), LinuxSllHeader::SERIALIZED_SIZE)
+            }
+        })
+    }
+
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the packet type field (see [`linux_sll_packet_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the `ARPHRD_` type of the capturing device.
+    #[inline]
+    pub fn arp_hardware_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the length of the link layer address of the sender.
+    #[inline]
+    pub fn address_len(&self) -> u16 {
+        u16::from_be_bytes([self.slice[4], self.slice[5]])
+    }
+
+    /// Read the address field (first 8 bytes of the link layer address).
+    #[inline]
+    pub fn address(&self) -> [u8;8] {
+        let mut result = [0;8];
+        result.copy_from_slice(&self.slice[6..14]);
+        result
+    }
+
+    /// Returns the used part of the link layer address (at most 8 bytes).
+    #[inline]
+    pub fn link_address(&self) -> &'a [u8] {
+        &self.slice[6..6 + usize::from(self.address_len()).min(8)]
+    }
+
+    /// Read the protocol type of the following header (usually an ether type).
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[14], self.slice[15]])
+    }
+
+    /// Decode all the fields and copy the results to a [`LinuxSllHeader`] struct.
+    pub fn to_header(&self) -> LinuxSllHeader {
+        LinuxSllHeader {
+            packet_type: self.packet_type(),
+            arp_hardware_type: self.arp_hardware_type(),
+            address_len: self.address_len(),
+            address: self.address(),
+            protocol_type: self.protocol_type(),
+        }
+    }
+}
+
+/// Linux cooked capture v2 header (`LINKTYPE_LINUX_SLL2`), the successor
+/// of the [`LinuxSllHeader`] additionally containing the index of the
+/// capturing interface.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, LinuxSll2Header, PacketBuilder, PacketHeaders};
+///
+/// let header = LinuxSll2Header {
+///     protocol_type: ether_type::IPV6,
+///     interface_index: 3,
+///     ..Default::default()
+/// };
+/// let mut packet = Vec::new();
+/// header.write(&mut packet).unwrap();
+/// PacketBuilder::ipv6([1;16], [2;16], 20)
+///     .udp(21, 1234)
+///     .write(&mut packet, &[])
+///     .unwrap();
+///
+/// let decoded = PacketHeaders::from_linux_sll2_slice(&packet).unwrap();
+/// assert_eq!(Some(3), decoded.linux_sll.unwrap().interface_index());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LinuxSll2Header {
+    /// Protocol type of the following header (usually an ether type).
+    pub protocol_type: u16,
+    /// Reserved (should be zero).
+    pub reserved: u16,
+    /// Index of the interface the packet was captured on.
+    pub interface_index: u32,
+    /// Linux `ARPHRD_` type of the capturing device (see [`arp_hardware_type`]).
+    pub arp_hardware_type: u16,
+    /// Direction & addressing of the packet (see [`linux_sll_packet_type`]).
+    pub packet_type: u8,
+    /// Length of the link layer address of the sender (can be bigger then
+    /// the 8 bytes of the `address` field).
+    pub address_len: u8,
+    /// Link layer address of the sender (the first 8 bytes, zero padded).
+    pub address: [u8;8],
+}
+
+impl SerializedSize for LinuxSll2Header {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 20;
+}
+
+impl LinuxSll2Header {
+
+    /// Read a LinuxSll2Header from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(LinuxSll2Header, &[u8]), ReadError> {
+        Ok((
+            LinuxSll2HeaderSlice::from_slice(slice)?.to_header(),
+            &slice[LinuxSll2Header::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Reads a Linux cooked capture v2 header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<LinuxSll2Header, io::Error> {
+        let mut buffer = [0;LinuxSll2Header::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(LinuxSll2HeaderSlice{
+            slice: &buffer
+        }.to_header())
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        LinuxSll2Header::SERIALIZED_SIZE
+    }
+
+    /// Returns the used part of the link layer address (at most 8 bytes).
+    #[inline]
+    pub fn link_address(&self) -> &[u8] {
+        &self.address[..usize::from(self.address_len).min(8)]
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    pub fn to_bytes(&self) -> [u8;20] {
+        let protocol_type = self.protocol_type.to_be_bytes();
+        let reserved = self.reserved.to_be_bytes();
+        let interface_index = self.interface_index.to_be_bytes();
+        let arp_hardware_type = self.arp_hardware_type.to_be_bytes();
+        [
+            protocol_type[0],
+            protocol_type[1],
+            reserved[0],
+            reserved[1],
+            interface_index[0],
+            interface_index[1],
+            interface_index[2],
+            interface_index[3],
+            arp_hardware_type[0],
+            arp_hardware_type[1],
+            self.packet_type,
+            self.address_len,
+            self.address[0],
+            self.address[1],
+            self.address[2],
+            self.address[3],
+            self.address[4],
+            self.address[5],
+            self.address[6],
+            self.address[7],
+        ]
+    }
+}
+
+/// A slice containing a Linux cooked capture v2 header (`LINKTYPE_LINUX_SLL2`).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LinuxSll2HeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> LinuxSll2HeaderSlice<'a> {
+
+    /// Creates a Linux cooked capture v2 header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<LinuxSll2HeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < LinuxSll2Header::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(LinuxSll2Header::SERIALIZED_SIZE));
+        }
+
+        Ok(LinuxSll2HeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // LinuxSll2Header::SERIALIZED_SIZE (20) before this.
+            slice: unsafe {
+                from_raw_parts(slice.as_ptr(), LinuxSll2Header::SERIALIZED_SIZE)
+            }
+        })
+    }
+
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the protocol type of the following header (usually an ether type).
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the reserved field.
+    #[inline]
+    pub fn reserved(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the index of the interface the packet was captured on.
+    #[inline]
+    pub fn interface_index(&self) -> u32 {
+        u32::from_be_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]])
+    }
+
+    /// Read the `ARPHRD_` type of the capturing device.
+    #[inline]
+    pub fn arp_hardware_type(&self) -> u16 {
+        u16::from_be_bytes([self.slice[8], self.slice[9]])
+    }
+
+    /// Read the packet type field (see [`linux_sll_packet_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u8 {
+        self.slice[10]
+    }
+
+    /// Read the length of the link layer address of the sender.
+    #[inline]
+    pub fn address_len(&self) -> u8 {
+        self.slice[11]
+    }
+
+    /// Read the address field (first 8 bytes of the link layer address).
+    #[inline]
+    pub fn address(&self) -> [u8;8] {
+        let mut result = [0;8];
+        result.copy_from_slice(&self.slice[12..20]);
+        result
+    }
+
+    /// Returns the used part of the link layer address (at most 8 bytes).
+    #[inline]
+    pub fn link_address(&self) -> &'a [u8] {
+        &self.slice[12..12 + usize::from(self.address_len()).min(8)]
+    }
+
+    /// Decode all the fields and copy the results to a [`LinuxSll2Header`] struct.
+    pub fn to_header(&self) -> LinuxSll2Header {
+        LinuxSll2Header {
+            protocol_type: self.protocol_type(),
+            reserved: self.reserved(),
+            interface_index: self.interface_index(),
+            arp_hardware_type: self.arp_hardware_type(),
+            packet_type: self.packet_type(),
+            address_len: self.address_len(),
+            address: self.address(),
+        }
+    }
+}
+
+/// Linux cooked capture header of version 1 or 2.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LinuxCookedHeader {
+    /// Linux cooked capture header (`LINKTYPE_LINUX_SLL`).
+    Sll(LinuxSllHeader),
+    /// Linux cooked capture v2 header (`LINKTYPE_LINUX_SLL2`).
+    Sll2(LinuxSll2Header),
+}
+
+impl LinuxCookedHeader {
+    /// Protocol type of the following header (usually an ether type).
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        match self {
+            LinuxCookedHeader::Sll(header) => header.protocol_type,
+            LinuxCookedHeader::Sll2(header) => header.protocol_type,
+        }
+    }
+
+    /// Sets the protocol type of the following header.
+    #[inline]
+    pub fn set_protocol_type(&mut self, value: u16) {
+        match self {
+            LinuxCookedHeader::Sll(header) => header.protocol_type = value,
+            LinuxCookedHeader::Sll2(header) => header.protocol_type = value,
+        }
+    }
+
+    /// Packet type (see [`linux_sll_packet_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u16 {
+        match self {
+            LinuxCookedHeader::Sll(header) => header.packet_type,
+            LinuxCookedHeader::Sll2(header) => header.packet_type.into(),
+        }
+    }
+
+    /// Index of the capturing interface (only present in v2 headers).
+    #[inline]
+    pub fn interface_index(&self) -> Option<u32> {
+        match self {
+            LinuxCookedHeader::Sll(_) => None,
+            LinuxCookedHeader::Sll2(header) => Some(header.interface_index),
+        }
+    }
+
+    /// Returns the used part of the link layer address (at most 8 bytes).
+    #[inline]
+    pub fn link_address(&self) -> &[u8] {
+        match self {
+            LinuxCookedHeader::Sll(header) => header.link_address(),
+            LinuxCookedHeader::Sll2(header) => header.link_address(),
+        }
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        match self {
+            LinuxCookedHeader::Sll(header) => header.header_len(),
+            LinuxCookedHeader::Sll2(header) => header.header_len(),
+        }
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        match self {
+            LinuxCookedHeader::Sll(header) => header.write(writer),
+            LinuxCookedHeader::Sll2(header) => header.write(writer),
+        }
+    }
+}
+
+/// A slice containing a Linux cooked capture header of version 1 or 2.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum LinuxCookedSlice<'a> {
+    /// A slice containing a Linux cooked capture header (`LINKTYPE_LINUX_SLL`).
+    Sll(LinuxSllHeaderSlice<'a>),
+    /// A slice containing a Linux cooked capture v2 header (`LINKTYPE_LINUX_SLL2`).
+    Sll2(LinuxSll2HeaderSlice<'a>),
+}
+
+impl<'a> LinuxCookedSlice<'a> {
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        match self {
+            LinuxCookedSlice::Sll(slice) => slice.slice(),
+            LinuxCookedSlice::Sll2(slice) => slice.slice(),
+        }
+    }
+
+    /// Read the protocol type of the following header (usually an ether type).
+    #[inline]
+    pub fn protocol_type(&self) -> u16 {
+        match self {
+            LinuxCookedSlice::Sll(slice) => slice.protocol_type(),
+            LinuxCookedSlice::Sll2(slice) => slice.protocol_type(),
+        }
+    }
+
+    /// Read the packet type (see [`linux_sll_packet_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u16 {
+        match self {
+            LinuxCookedSlice::Sll(slice) => slice.packet_type(),
+            LinuxCookedSlice::Sll2(slice) => slice.packet_type().into(),
+        }
+    }
+
+    /// Read the index of the capturing interface (only present in v2 headers).
+    #[inline]
+    pub fn interface_index(&self) -> Option<u32> {
+        match self {
+            LinuxCookedSlice::Sll(_) => None,
+            LinuxCookedSlice::Sll2(slice) => Some(slice.interface_index()),
+        }
+    }
+
+    /// Returns the used part of the link layer address (at most 8 bytes).
+    #[inline]
+    pub fn link_address(&self) -> &'a [u8] {
+        match self {
+            LinuxCookedSlice::Sll(slice) => slice.link_address(),
+            LinuxCookedSlice::Sll2(slice) => slice.link_address(),
+        }
+    }
+
+    /// Decode all the fields and copy the results to a [`LinuxCookedHeader`].
+    pub fn to_header(&self) -> LinuxCookedHeader {
+        match self {
+            LinuxCookedSlice::Sll(slice) => LinuxCookedHeader::Sll(slice.to_header()),
+            LinuxCookedSlice::Sll2(slice) => LinuxCookedHeader::Sll2(slice.to_header()),
+        }
+    }
+}
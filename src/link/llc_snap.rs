@@ -0,0 +1,120 @@
+use super::super::*;
+
+/// IEEE 802.2 LLC header with a SNAP extension (DSAP & SSAP `0xaa`,
+/// control `0x03`), used to carry ether type payloads over 802.11, 802.3
+/// & other IEEE 802 networks.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, LlcSnapHeader};
+///
+/// let (header, rest) = LlcSnapHeader::from_slice(
+///     &[0xaa, 0xaa, 0x03, 0, 0, 0, 0x08, 0x00, 1, 2]
+/// ).unwrap();
+/// assert_eq!(Some(ether_type::IPV4), header.ether_type());
+/// assert_eq!(&[1, 2], rest);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LlcSnapHeader {
+    /// Organizationally unique identifier of the organization defining the protocol id.
+    pub oui: [u8;3],
+    /// Protocol id (an ether type if the oui is [`LlcSnapHeader::OUI_RFC1042`]
+    /// or [`LlcSnapHeader::OUI_BRIDGE_TUNNEL`]).
+    pub protocol_id: u16,
+}
+
+impl SerializedSize for LlcSnapHeader {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl LlcSnapHeader {
+    /// Value of the DSAP & SSAP identifying a SNAP extension.
+    pub const SAP_SNAP: u8 = 0xaa;
+
+    /// Value of the control field (unnumbered information).
+    pub const CONTROL_UI: u8 = 0x03;
+
+    /// OUI of SNAP headers containing an ether type (RFC 1042).
+    pub const OUI_RFC1042: [u8;3] = [0, 0, 0];
+
+    /// OUI of SNAP headers containing an ether type bridged from an
+    /// Ethernet II network (802.1H bridge tunnel).
+    pub const OUI_BRIDGE_TUNNEL: [u8;3] = [0, 0, 0xf8];
+
+    /// Creates a SNAP header containing an ether type.
+    pub fn with_ether_type(ether_type: u16) -> LlcSnapHeader {
+        LlcSnapHeader {
+            oui: LlcSnapHeader::OUI_RFC1042,
+            protocol_id: ether_type,
+        }
+    }
+
+    /// Returns true if the slice starts with the LLC fields of a SNAP header.
+    #[inline]
+    pub fn is_snap(slice: &[u8]) -> bool {
+        slice.len() >= 3 &&
+        LlcSnapHeader::SAP_SNAP == slice[0] &&
+        LlcSnapHeader::SAP_SNAP == slice[1] &&
+        LlcSnapHeader::CONTROL_UI == slice[2]
+    }
+
+    /// Read a LlcSnapHeader from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(LlcSnapHeader, &[u8]), ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < LlcSnapHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(LlcSnapHeader::SERIALIZED_SIZE));
+        }
+        if false == LlcSnapHeader::is_snap(slice) {
+            return Err(LlcNotSnap{ dsap: slice[0], ssap: slice[1], control: slice[2] });
+        }
+        Ok((
+            LlcSnapHeader {
+                oui: [slice[3], slice[4], slice[5]],
+                protocol_id: u16::from_be_bytes([slice[6], slice[7]]),
+            },
+            &slice[LlcSnapHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        LlcSnapHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns the ether type of the payload (`None` if the protocol id
+    /// is defined by an other organization).
+    pub fn ether_type(&self) -> Option<u16> {
+        if LlcSnapHeader::OUI_RFC1042 == self.oui || LlcSnapHeader::OUI_BRIDGE_TUNNEL == self.oui {
+            Some(self.protocol_id)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;8] {
+        let protocol_id_be = self.protocol_id.to_be_bytes();
+        [
+            LlcSnapHeader::SAP_SNAP,
+            LlcSnapHeader::SAP_SNAP,
+            LlcSnapHeader::CONTROL_UI,
+            self.oui[0],
+            self.oui[1],
+            self.oui[2],
+            protocol_id_be[0],
+            protocol_id_be[1],
+        ]
+    }
+}
@@ -0,0 +1,402 @@
+use super::super::*;
+
+/// LLDP TLV types (IEEE 802.1AB).
+pub mod lldp_tlv_type {
+    /// End of LLDPDU.
+    pub const END: u8 = 0;
+    /// Chassis ID (mandatory, first TLV).
+    pub const CHASSIS_ID: u8 = 1;
+    /// Port ID (mandatory, second TLV).
+    pub const PORT_ID: u8 = 2;
+    /// Time to live (mandatory, third TLV).
+    pub const TIME_TO_LIVE: u8 = 3;
+    /// Port description.
+    pub const PORT_DESCRIPTION: u8 = 4;
+    /// System name.
+    pub const SYSTEM_NAME: u8 = 5;
+    /// System description.
+    pub const SYSTEM_DESCRIPTION: u8 = 6;
+    /// System capabilities.
+    pub const SYSTEM_CAPABILITIES: u8 = 7;
+    /// Management address.
+    pub const MANAGEMENT_ADDRESS: u8 = 8;
+    /// Organizationally specific TLV.
+    pub const ORGANIZATION_SPECIFIC: u8 = 127;
+}
+
+/// Subtypes of the LLDP chassis ID TLV.
+pub mod lldp_chassis_id_subtype {
+    /// Entity physical alias (RFC 6933).
+    pub const CHASSIS_COMPONENT: u8 = 1;
+    /// Interface alias (RFC 2863).
+    pub const INTERFACE_ALIAS: u8 = 2;
+    /// Port entity physical alias (RFC 6933).
+    pub const PORT_COMPONENT: u8 = 3;
+    /// MAC address (IEEE 802).
+    pub const MAC_ADDRESS: u8 = 4;
+    /// Network address (first byte is the IANA address family).
+    pub const NETWORK_ADDRESS: u8 = 5;
+    /// Interface name (RFC 2863).
+    pub const INTERFACE_NAME: u8 = 6;
+    /// Locally assigned.
+    pub const LOCALLY_ASSIGNED: u8 = 7;
+}
+
+/// Subtypes of the LLDP port ID TLV.
+pub mod lldp_port_id_subtype {
+    /// Interface alias (RFC 2863).
+    pub const INTERFACE_ALIAS: u8 = 1;
+    /// Port entity physical alias (RFC 6933).
+    pub const PORT_COMPONENT: u8 = 2;
+    /// MAC address (IEEE 802).
+    pub const MAC_ADDRESS: u8 = 3;
+    /// Network address (first byte is the IANA address family).
+    pub const NETWORK_ADDRESS: u8 = 4;
+    /// Interface name (RFC 2863).
+    pub const INTERFACE_NAME: u8 = 5;
+    /// Agent circuit ID (RFC 3046).
+    pub const AGENT_CIRCUIT_ID: u8 = 6;
+    /// Locally assigned.
+    pub const LOCALLY_ASSIGNED: u8 = 7;
+}
+
+/// Errors that can occur while decoding an LLDPDU.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LldpError {
+    /// The slice is too short. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+    /// A TLV extends past the end of the slice or is shorter then the
+    /// minimum length of its type. The value is the offset of the TLV
+    /// in the LLDPDU.
+    InvalidTlvLength(usize),
+    /// One of the mandatory TLVs (chassis ID, port ID & time to live) is
+    /// missing or not in the mandatory order. The value is the expected
+    /// TLV type.
+    MissingMandatoryTlv(u8),
+}
+
+impl Error for LldpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for LldpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use LldpError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "LldpError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+            InvalidTlvLength(offset) => {
+                write!(f, "LldpError: The TLV at offset {} has an invalid length.", offset)
+            },
+            MissingMandatoryTlv(tlv_type) => {
+                write!(f, "LldpError: Missing mandatory TLV of type {}.", tlv_type)
+            },
+        }
+    }
+}
+
+/// TLV contained in an LLDPDU.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum LldpTlv<'a> {
+    /// Chassis ID TLV.
+    ChassisId {
+        /// Subtype of the id (see [`lldp_chassis_id_subtype`]).
+        subtype: u8,
+        /// Id of the chassis.
+        id: &'a [u8],
+    },
+    /// Port ID TLV.
+    PortId {
+        /// Subtype of the id (see [`lldp_port_id_subtype`]).
+        subtype: u8,
+        /// Id of the port.
+        id: &'a [u8],
+    },
+    /// Time to live in seconds.
+    TimeToLive(u16),
+    /// Description of the port (usually an alphanumeric string).
+    PortDescription(&'a [u8]),
+    /// Administratively assigned name of the system (usually an alphanumeric string).
+    SystemName(&'a [u8]),
+    /// Description of the system (usually an alphanumeric string).
+    SystemDescription(&'a [u8]),
+    /// System capabilities TLV.
+    SystemCapabilities {
+        /// Bitmap of the supported capabilities.
+        capabilities: u16,
+        /// Bitmap of the enabled capabilities.
+        enabled: u16,
+    },
+    /// Value of a management address TLV.
+    ManagementAddress(&'a [u8]),
+    /// Organizationally specific TLV.
+    OrganizationSpecific {
+        /// Organizationally unique identifier of the organization defining the TLV.
+        oui: [u8;3],
+        /// Subtype defined by the organization.
+        subtype: u8,
+        /// Information string of the TLV.
+        info: &'a [u8],
+    },
+    /// TLV with a reserved type.
+    Unknown {
+        /// Type of the TLV.
+        tlv_type: u8,
+        /// Value of the TLV.
+        value: &'a [u8],
+    },
+}
+
+impl<'a> LldpTlv<'a> {
+    /// Returns the minimum length of the value of the given TLV type.
+    pub fn min_value_len_of(tlv_type: u8) -> usize {
+        use lldp_tlv_type::*;
+        match tlv_type {
+            // subtype & at least one byte id
+            CHASSIS_ID | PORT_ID => 2,
+            TIME_TO_LIVE => 2,
+            SYSTEM_CAPABILITIES => 4,
+            // address string length, subtype & at least one byte address,
+            // interface numbering subtype, interface number & oid string length
+            MANAGEMENT_ADDRESS => 9,
+            // oui & subtype
+            ORGANIZATION_SPECIFIC => 4,
+            _ => 0,
+        }
+    }
+
+    /// Decodes the value of a TLV. The value has to be at least
+    /// [`LldpTlv::min_value_len_of`] bytes long.
+    fn from_value(tlv_type: u8, value: &'a [u8]) -> LldpTlv<'a> {
+        use lldp_tlv_type::*;
+        use LldpTlv::*;
+        match tlv_type {
+            CHASSIS_ID => ChassisId { subtype: value[0], id: &value[1..] },
+            PORT_ID => PortId { subtype: value[0], id: &value[1..] },
+            TIME_TO_LIVE => TimeToLive(u16::from_be_bytes([value[0], value[1]])),
+            PORT_DESCRIPTION => PortDescription(value),
+            SYSTEM_NAME => SystemName(value),
+            SYSTEM_DESCRIPTION => SystemDescription(value),
+            SYSTEM_CAPABILITIES => SystemCapabilities {
+                capabilities: u16::from_be_bytes([value[0], value[1]]),
+                enabled: u16::from_be_bytes([value[2], value[3]]),
+            },
+            MANAGEMENT_ADDRESS => ManagementAddress(value),
+            ORGANIZATION_SPECIFIC => OrganizationSpecific {
+                oui: [value[0], value[1], value[2]],
+                subtype: value[3],
+                info: &value[4..],
+            },
+            _ => Unknown { tlv_type, value },
+        }
+    }
+
+    /// Returns the type of the TLV (see [`lldp_tlv_type`]).
+    pub fn tlv_type(&self) -> u8 {
+        use lldp_tlv_type::*;
+        use LldpTlv::*;
+        match self {
+            ChassisId{ .. } => CHASSIS_ID,
+            PortId{ .. } => PORT_ID,
+            TimeToLive(_) => TIME_TO_LIVE,
+            PortDescription(_) => PORT_DESCRIPTION,
+            SystemName(_) => SYSTEM_NAME,
+            SystemDescription(_) => SYSTEM_DESCRIPTION,
+            SystemCapabilities{ .. } => SYSTEM_CAPABILITIES,
+            ManagementAddress(_) => MANAGEMENT_ADDRESS,
+            OrganizationSpecific{ .. } => ORGANIZATION_SPECIFIC,
+            Unknown{ tlv_type, .. } => *tlv_type,
+        }
+    }
+}
+
+/// Slice containing an LLDPDU (ether type 0x88CC).
+///
+/// The TLVs are validated when the slice is created (including the order
+/// of the mandatory chassis ID, port ID & time to live TLVs), so the TLV
+/// iterator can be used without further error handling. The LLDPDU ends
+/// with the end of LLDPDU TLV or at the end of the given slice. Padding
+/// after the end of LLDPDU TLV is not included in the slice.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{LldpSlice, LldpTlv, lldp_chassis_id_subtype};
+///
+/// let lldpdu = [
+///     0x02, 0x07, 0x04, 1, 2, 3, 4, 5, 6, // chassis id (mac address)
+///     0x04, 0x03, 0x05, b'p', b'1', // port id (interface name)
+///     0x06, 0x02, 0x00, 0x78, // ttl (120s)
+///     0x0a, 0x02, b's', b'w', // system name
+///     0x00, 0x00, // end of LLDPDU
+///     0, 0, 0, 0, // padding
+/// ];
+/// let lldp = LldpSlice::from_slice(&lldpdu).unwrap();
+/// assert_eq!(&lldpdu[..24], lldp.slice());
+/// assert_eq!((lldp_chassis_id_subtype::MAC_ADDRESS, &[1, 2, 3, 4, 5, 6][..]), lldp.chassis_id());
+/// assert_eq!(120, lldp.ttl());
+/// assert_eq!(Some(&b"sw"[..]), lldp.system_name());
+///
+/// let mut tlvs = lldp.tlvs().skip(3);
+/// assert_eq!(Some(LldpTlv::SystemName(b"sw")), tlvs.next());
+/// assert_eq!(None, tlvs.next());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LldpSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> LldpSlice<'a> {
+    /// Destination MAC address of LLDPDUs that are not forwarded by any bridge.
+    pub const NEAREST_BRIDGE_MAC: [u8;6] = [0x01, 0x80, 0xc2, 0x00, 0x00, 0x0e];
+
+    /// Minimum length of an LLDPDU (chassis ID, port ID & time to live
+    /// TLVs with 2 byte values each).
+    pub const MIN_LEN: usize = 3*(2 + 2);
+
+    /// Decodes & validates an LLDPDU.
+    pub fn from_slice(slice: &'a [u8]) -> Result<LldpSlice<'a>, LldpError> {
+        use LldpError::*;
+        use lldp_tlv_type::*;
+
+        if slice.len() < LldpSlice::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(LldpSlice::MIN_LEN));
+        }
+
+        let mandatory = [CHASSIS_ID, PORT_ID, TIME_TO_LIVE];
+        let mut offset = 0;
+        let mut index = 0;
+        while offset < slice.len() {
+            if offset + 2 > slice.len() {
+                return Err(InvalidTlvLength(offset));
+            }
+            let (tlv_type, value_len) = LldpSlice::tlv_header(&slice[offset..]);
+            if index < mandatory.len() && mandatory[index] != tlv_type {
+                return Err(MissingMandatoryTlv(mandatory[index]));
+            }
+            if END == tlv_type {
+                if 0 != value_len {
+                    return Err(InvalidTlvLength(offset));
+                }
+                offset += 2;
+                break;
+            }
+            if offset + 2 + value_len > slice.len() ||
+               value_len < LldpTlv::min_value_len_of(tlv_type)
+            {
+                return Err(InvalidTlvLength(offset));
+            }
+            offset += 2 + value_len;
+            index += 1;
+        }
+        if index < mandatory.len() {
+            return Err(MissingMandatoryTlv(mandatory[index]));
+        }
+
+        Ok(LldpSlice {
+            slice: &slice[..offset],
+        })
+    }
+
+    /// Returns the type & value length of the TLV at the start of the slice.
+    #[inline]
+    fn tlv_header(slice: &[u8]) -> (u8, usize) {
+        (
+            slice[0] >> 1,
+            (usize::from(slice[0] & 1) << 8) | usize::from(slice[1])
+        )
+    }
+
+    /// Returns the slice containing the LLDPDU (including the end of
+    /// LLDPDU TLV, if present, but without padding).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the subtype & id of the chassis ID TLV.
+    pub fn chassis_id(&self) -> (u8, &'a [u8]) {
+        // the first tlv is the chassis id (validated in from_slice)
+        let (_, value_len) = LldpSlice::tlv_header(self.slice);
+        (self.slice[2], &self.slice[3..2 + value_len])
+    }
+
+    /// Returns the subtype & id of the port ID TLV.
+    pub fn port_id(&self) -> (u8, &'a [u8]) {
+        // the second tlv is the port id (validated in from_slice)
+        let (_, chassis_len) = LldpSlice::tlv_header(self.slice);
+        let rest = &self.slice[2 + chassis_len..];
+        let (_, value_len) = LldpSlice::tlv_header(rest);
+        (rest[2], &rest[3..2 + value_len])
+    }
+
+    /// Time to live in seconds (value of the time to live TLV).
+    pub fn ttl(&self) -> u16 {
+        match self.tlvs().nth(2) {
+            Some(LldpTlv::TimeToLive(value)) => value,
+            // the third tlv is the ttl (validated in from_slice)
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the value of the first port description TLV.
+    pub fn port_description(&self) -> Option<&'a [u8]> {
+        self.tlvs().find_map(|tlv| match tlv {
+            LldpTlv::PortDescription(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the first system name TLV.
+    pub fn system_name(&self) -> Option<&'a [u8]> {
+        self.tlvs().find_map(|tlv| match tlv {
+            LldpTlv::SystemName(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns the value of the first system description TLV.
+    pub fn system_description(&self) -> Option<&'a [u8]> {
+        self.tlvs().find_map(|tlv| match tlv {
+            LldpTlv::SystemDescription(value) => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the TLVs of the LLDPDU (excluding the
+    /// end of LLDPDU TLV).
+    pub fn tlvs(&self) -> LldpTlvIterator<'a> {
+        LldpTlvIterator {
+            rest: self.slice,
+        }
+    }
+}
+
+/// Iterator over the TLVs of an LLDPDU.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LldpTlvIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for LldpTlvIterator<'a> {
+    type Item = LldpTlv<'a>;
+
+    fn next(&mut self) -> Option<LldpTlv<'a>> {
+        // the tlvs were validated in LldpSlice::from_slice
+        if self.rest.len() < 2 {
+            return None;
+        }
+        let (tlv_type, value_len) = LldpSlice::tlv_header(self.rest);
+        if lldp_tlv_type::END == tlv_type {
+            self.rest = &self.rest[self.rest.len()..];
+            return None;
+        }
+        let end = 2 + value_len;
+        let result = LldpTlv::from_value(tlv_type, &self.rest[2..end]);
+        self.rest = &self.rest[end..];
+        Some(result)
+    }
+}
@@ -0,0 +1,186 @@
+use super::super::*;
+
+use std::io;
+use std::time::Duration;
+
+/// `u16` constants of the opcodes of MAC control frames (see [`MacControlFrame`]).
+pub mod mac_control_opcode {
+    /// IEEE 802.3x pause frame.
+    pub const PAUSE: u16 = 0x0001;
+    /// IEEE 802.1Qbb priority flow control frame.
+    pub const PRIORITY_FLOW_CONTROL: u16 = 0x0101;
+}
+
+/// Payload of an ethernet MAC control frame (ether type 0x8808), such as
+/// IEEE 802.3x pause frames & IEEE 802.1Qbb priority flow control (PFC) frames.
+///
+/// Pause times are given in quanta of 512 bit times (see
+/// [`MacControlFrame::quanta_to_duration`]). A pause time of 0 resumes the
+/// transmission.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{MacControlFrame, SlicedPacket};
+///
+/// let frame = MacControlFrame::PriorityFlowControl {
+///     priority_enable: 0b0000_1000,
+///     pause_times: [0, 0, 0, 0xffff, 0, 0, 0, 0],
+/// };
+///
+/// // build the complete ethernet frame
+/// let mut packet = Vec::new();
+/// frame.ethernet2_header([1,2,3,4,5,6]).write(&mut packet).unwrap();
+/// frame.write(&mut packet).unwrap();
+/// assert_eq!(14 + 46, packet.len());
+///
+/// // and parse it again
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// assert_eq!(frame, MacControlFrame::from_slice(sliced.payload).unwrap());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MacControlFrame {
+    /// IEEE 802.3x pause frame pausing all traffic.
+    Pause {
+        /// Pause time in quanta of 512 bit times.
+        pause_time: u16,
+    },
+    /// IEEE 802.1Qbb priority flow control frame pausing individual priorities.
+    PriorityFlowControl {
+        /// Bit n is set if the pause time of priority n is valid (the upper
+        /// 8 bits of the class-enable vector are reserved & ignored).
+        priority_enable: u8,
+        /// Pause times of the priorities 0 to 7 in quanta of 512 bit times.
+        pause_times: [u16;8],
+    },
+    /// MAC control frame with another opcode (the parameters are not decoded).
+    Unknown {
+        /// Opcode of the frame.
+        opcode: u16,
+    },
+}
+
+impl MacControlFrame {
+    /// Serialized length of a MAC control frame payload (minimum ethernet
+    /// payload, unused bytes are padded with zeros).
+    pub const SERIALIZED_SIZE: usize = 46;
+
+    /// Reserved multicast destination address of MAC control frames.
+    pub const DESTINATION: [u8;6] = [0x01, 0x80, 0xc2, 0x00, 0x00, 0x01];
+
+    /// Length of the opcode & parameters of a pause frame.
+    const PAUSE_LEN: usize = 4;
+
+    /// Length of the opcode & parameters of a priority flow control frame.
+    const PRIORITY_FLOW_CONTROL_LEN: usize = 20;
+
+    /// Decodes a MAC control frame from the payload of an ethernet II frame
+    /// with the ether type 0x8808 (the padding is ignored).
+    pub fn from_slice(slice: &[u8]) -> Result<MacControlFrame, ReadError> {
+        use crate::ReadError::UnexpectedEndOfSlice;
+        if slice.len() < 2 {
+            return Err(UnexpectedEndOfSlice(2));
+        }
+        let read_u16 = |offset: usize| u16::from_be_bytes([slice[offset], slice[offset + 1]]);
+        match read_u16(0) {
+            mac_control_opcode::PAUSE => {
+                if slice.len() < MacControlFrame::PAUSE_LEN {
+                    return Err(UnexpectedEndOfSlice(MacControlFrame::PAUSE_LEN));
+                }
+                Ok(MacControlFrame::Pause {
+                    pause_time: read_u16(2),
+                })
+            },
+            mac_control_opcode::PRIORITY_FLOW_CONTROL => {
+                if slice.len() < MacControlFrame::PRIORITY_FLOW_CONTROL_LEN {
+                    return Err(UnexpectedEndOfSlice(MacControlFrame::PRIORITY_FLOW_CONTROL_LEN));
+                }
+                let mut pause_times = [0u16;8];
+                for (priority, value) in pause_times.iter_mut().enumerate() {
+                    *value = read_u16(4 + priority*2);
+                }
+                Ok(MacControlFrame::PriorityFlowControl {
+                    priority_enable: slice[3],
+                    pause_times,
+                })
+            },
+            opcode => Ok(MacControlFrame::Unknown { opcode }),
+        }
+    }
+
+    /// Returns the opcode of the frame.
+    pub fn opcode(&self) -> u16 {
+        use MacControlFrame::*;
+        match self {
+            Pause { .. } => mac_control_opcode::PAUSE,
+            PriorityFlowControl { .. } => mac_control_opcode::PRIORITY_FLOW_CONTROL,
+            Unknown { opcode } => *opcode,
+        }
+    }
+
+    /// Returns the pause time of the given priority (`None` if the priority
+    /// is not enabled). Pause frames apply their pause time to all priorities.
+    pub fn pause_time(&self, priority: u8) -> Option<u16> {
+        use MacControlFrame::*;
+        match self {
+            Pause { pause_time } if priority < 8 => Some(*pause_time),
+            PriorityFlowControl { priority_enable, pause_times } if priority < 8 => {
+                if 0 != priority_enable & (1 << priority) {
+                    Some(pause_times[usize::from(priority)])
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the ethernet II header for sending the frame from the given
+    /// source address (to the reserved MAC control destination address).
+    pub fn ethernet2_header(&self, source: [u8;6]) -> Ethernet2Header {
+        Ethernet2Header {
+            source,
+            destination: MacControlFrame::DESTINATION,
+            ether_type: ether_type::MAC_CONTROL,
+        }
+    }
+
+    /// Returns the serialized form of the frame payload (including the padding).
+    pub fn to_bytes(&self) -> [u8;MacControlFrame::SERIALIZED_SIZE] {
+        let mut result = [0u8;MacControlFrame::SERIALIZED_SIZE];
+        result[..2].copy_from_slice(&self.opcode().to_be_bytes());
+        use MacControlFrame::*;
+        match self {
+            Pause { pause_time } => {
+                result[2..4].copy_from_slice(&pause_time.to_be_bytes());
+            },
+            PriorityFlowControl { priority_enable, pause_times } => {
+                result[3] = *priority_enable;
+                for (priority, value) in pause_times.iter().enumerate() {
+                    result[4 + priority*2..6 + priority*2].copy_from_slice(&value.to_be_bytes());
+                }
+            },
+            Unknown { .. } => {},
+        }
+        result
+    }
+
+    /// Writes the frame payload (including the padding).
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Converts a pause time in quanta (512 bit times) to a duration for a
+    /// link with the given speed in bits per second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits_per_second` is 0.
+    pub fn quanta_to_duration(quanta: u16, bits_per_second: u64) -> Duration {
+        let nanos = u128::from(quanta) * 512 * 1_000_000_000 / u128::from(bits_per_second);
+        Duration::from_nanos(nanos as u64)
+    }
+}
@@ -0,0 +1,309 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::slice::from_raw_parts;
+
+/// MAC security tag (SecTAG, IEEE 802.1AE) of a frame with the ether type
+/// `ether_type::MACSEC`.
+///
+/// If neither the `encrypted` (E) nor the `changed` (C) bit is set the secure
+/// data is transmitted in cleartext & starts with the ether type of the
+/// protected frame. The ether type is stored in the `ether_type` field and
+/// [`SlicedPacket`] & [`PacketHeaders`] continue decoding the protected frame
+/// (e.g. vlan, ip & transport headers). Otherwise the `payload` contains the
+/// encrypted data.
+///
+/// The integrity check value (ICV) at the end of the frame is not decoded
+/// and part of the payload.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, MacsecHeader};
+///
+/// let macsec = MacsecHeader {
+///     sci: Some(0x0102_0304_0506_0001),
+///     ..MacsecHeader::new(1, Some(ether_type::IPV4))
+/// };
+/// let bytes = macsec.to_bytes().unwrap();
+/// assert_eq!(macsec.header_len(), bytes.len());
+///
+/// let (decoded, _) = MacsecHeader::from_slice(&bytes).unwrap();
+/// assert_eq!(macsec, decoded);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MacsecHeader {
+    /// End station bit (ES, set if the secure channel identifier equals the
+    /// source mac address & port 1).
+    pub end_station: bool,
+    /// Single copy broadcast bit (SCB).
+    pub single_copy_broadcast: bool,
+    /// Encryption bit (E, set if the user data is encrypted).
+    pub encrypted: bool,
+    /// Changed text bit (C, set if the user data differs from the cleartext).
+    pub changed: bool,
+    /// Association number (2 bit value).
+    pub association_number: u8,
+    /// Short length (6 bit value, length of the secure data if it is shorter
+    /// then 48 bytes & 0 otherwise).
+    pub short_length: u8,
+    /// Packet number (used for replay protection).
+    pub packet_number: u32,
+    /// Secure channel identifier (SCI, the SC bit is set if present).
+    pub sci: Option<u64>,
+    /// Ether type of the protected frame (only present if neither the
+    /// `encrypted` nor the `changed` bit are set).
+    pub ether_type: Option<u16>,
+}
+
+impl MacsecHeader {
+    /// Length of a SecTAG without the secure channel identifier.
+    pub const MIN_LEN: usize = 6;
+
+    /// Length of the secure channel identifier.
+    pub const SCI_LEN: usize = 8;
+
+    /// Length of a SecTAG including the secure channel identifier & the
+    /// ether type of the cleartext data.
+    pub const MAX_LEN: usize = MacsecHeader::MIN_LEN + MacsecHeader::SCI_LEN + 2;
+
+    /// Maximum value of the association number.
+    pub const MAX_ASSOCIATION_NUMBER: u8 = 0b11;
+
+    /// Maximum value of the short length field.
+    pub const MAX_SHORT_LENGTH: u8 = 0x3f;
+
+    /// Version bit of the TCI (must be zero).
+    pub const TCI_VERSION: u8 = 0b1000_0000;
+
+    /// End station bit of the TCI.
+    pub const TCI_END_STATION: u8 = 0b0100_0000;
+
+    /// Secure channel bit of the TCI (set if the SCI is present).
+    pub const TCI_SCI_PRESENT: u8 = 0b0010_0000;
+
+    /// Single copy broadcast bit of the TCI.
+    pub const TCI_SINGLE_COPY_BROADCAST: u8 = 0b0001_0000;
+
+    /// Encryption bit of the TCI.
+    pub const TCI_ENCRYPTED: u8 = 0b0000_1000;
+
+    /// Changed text bit of the TCI.
+    pub const TCI_CHANGED: u8 = 0b0000_0100;
+
+    /// Creates a SecTAG without SCI. If an ether type is given the data is
+    /// marked as cleartext, otherwise as encrypted.
+    pub fn new(packet_number: u32, ether_type: Option<u16>) -> MacsecHeader {
+        let encrypted = ether_type.is_none();
+        MacsecHeader {
+            end_station: false,
+            single_copy_broadcast: false,
+            encrypted,
+            changed: encrypted,
+            association_number: 0,
+            short_length: 0,
+            packet_number,
+            sci: None,
+            ether_type,
+        }
+    }
+
+    /// Read a SecTAG from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(MacsecHeader, &[u8]), ReadError> {
+        let header = MacsecHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Reads a SecTAG from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<MacsecHeader, ReadError> {
+        let mut buffer = [0u8; MacsecHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..MacsecHeader::MIN_LEN])?;
+        let len = MacsecHeaderSlice::header_len_from_tci(buffer[0])?;
+        reader.read_exact(&mut buffer[MacsecHeader::MIN_LEN..len])?;
+        Ok(MacsecHeaderSlice::from_slice(&buffer[..len])?.to_header())
+    }
+
+    /// Writes the SecTAG to the current position.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        MacsecHeader::MIN_LEN +
+        if self.sci.is_some() { MacsecHeader::SCI_LEN } else { 0 } +
+        if self.ether_type.is_some() { 2 } else { 0 }
+    }
+
+    /// Returns the serialized form of the header (fails if the association
+    /// number or short length exceed their bit widths).
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { MacsecHeader::MAX_LEN }>, ValueError> {
+        max_check_u8(self.association_number, MacsecHeader::MAX_ASSOCIATION_NUMBER, ErrorField::MacsecAssociationNumber)?;
+        max_check_u8(self.short_length, MacsecHeader::MAX_SHORT_LENGTH, ErrorField::MacsecShortLength)?;
+
+        let tci_an =
+            if self.end_station { MacsecHeader::TCI_END_STATION } else { 0 } |
+            if self.sci.is_some() { MacsecHeader::TCI_SCI_PRESENT } else { 0 } |
+            if self.single_copy_broadcast { MacsecHeader::TCI_SINGLE_COPY_BROADCAST } else { 0 } |
+            if self.encrypted { MacsecHeader::TCI_ENCRYPTED } else { 0 } |
+            if self.changed { MacsecHeader::TCI_CHANGED } else { 0 } |
+            self.association_number;
+
+        let mut result = ArrayVec::new();
+        result.try_extend_from_slice(&[tci_an, self.short_length]).unwrap();
+        result.try_extend_from_slice(&self.packet_number.to_be_bytes()).unwrap();
+        if let Some(sci) = self.sci {
+            result.try_extend_from_slice(&sci.to_be_bytes()).unwrap();
+        }
+        if let Some(ether_type) = self.ether_type {
+            result.try_extend_from_slice(&ether_type.to_be_bytes()).unwrap();
+        }
+        Ok(result)
+    }
+}
+
+/// Slice containing a MACsec SecTAG (including the ether type of
+/// cleartext data).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MacsecHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> MacsecHeaderSlice<'a> {
+    /// Creates a SecTAG slice from a slice (the version bit is validated).
+    pub fn from_slice(slice: &'a [u8]) -> Result<MacsecHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < MacsecHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(MacsecHeader::MIN_LEN));
+        }
+        let len = MacsecHeaderSlice::header_len_from_tci(slice[0])?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        Ok(MacsecHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // len before this code can be reached.
+            slice: unsafe {
+                from_raw_parts(slice.as_ptr(), len)
+            }
+        })
+    }
+
+    /// Validates the TCI & returns the header length.
+    fn header_len_from_tci(tci_an: u8) -> Result<usize, ReadError> {
+        if 0 != tci_an & MacsecHeader::TCI_VERSION {
+            return Err(ReadError::MacsecUnsupportedVersion);
+        }
+        let sci_len = if 0 != tci_an & MacsecHeader::TCI_SCI_PRESENT {
+            MacsecHeader::SCI_LEN
+        } else {
+            0
+        };
+        let ether_type_len = if 0 == tci_an & (MacsecHeader::TCI_ENCRYPTED | MacsecHeader::TCI_CHANGED) {
+            2
+        } else {
+            0
+        };
+        Ok(MacsecHeader::MIN_LEN + sci_len + ether_type_len)
+    }
+
+    /// Returns the slice containing the SecTAG.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the end station bit (ES).
+    #[inline]
+    pub fn end_station(&self) -> bool {
+        0 != self.slice[0] & MacsecHeader::TCI_END_STATION
+    }
+
+    /// Read the secure channel bit (SC, set if the SCI is present).
+    #[inline]
+    pub fn sci_present(&self) -> bool {
+        0 != self.slice[0] & MacsecHeader::TCI_SCI_PRESENT
+    }
+
+    /// Read the single copy broadcast bit (SCB).
+    #[inline]
+    pub fn single_copy_broadcast(&self) -> bool {
+        0 != self.slice[0] & MacsecHeader::TCI_SINGLE_COPY_BROADCAST
+    }
+
+    /// Read the encryption bit (E).
+    #[inline]
+    pub fn encrypted(&self) -> bool {
+        0 != self.slice[0] & MacsecHeader::TCI_ENCRYPTED
+    }
+
+    /// Read the changed text bit (C).
+    #[inline]
+    pub fn changed(&self) -> bool {
+        0 != self.slice[0] & MacsecHeader::TCI_CHANGED
+    }
+
+    /// Read the association number (2 bit value).
+    #[inline]
+    pub fn association_number(&self) -> u8 {
+        self.slice[0] & MacsecHeader::MAX_ASSOCIATION_NUMBER
+    }
+
+    /// Read the short length (6 bit value).
+    #[inline]
+    pub fn short_length(&self) -> u8 {
+        self.slice[1] & MacsecHeader::MAX_SHORT_LENGTH
+    }
+
+    /// Read the packet number.
+    #[inline]
+    pub fn packet_number(&self) -> u32 {
+        u32::from_be_bytes([self.slice[2], self.slice[3], self.slice[4], self.slice[5]])
+    }
+
+    /// Read the secure channel identifier (if present).
+    #[inline]
+    pub fn sci(&self) -> Option<u64> {
+        if self.sci_present() {
+            let mut value = [0u8;8];
+            value.copy_from_slice(&self.slice[MacsecHeader::MIN_LEN..MacsecHeader::MIN_LEN + MacsecHeader::SCI_LEN]);
+            Some(u64::from_be_bytes(value))
+        } else {
+            None
+        }
+    }
+
+    /// Read the ether type of the protected frame (only present if the
+    /// data is transmitted in cleartext).
+    #[inline]
+    pub fn ether_type(&self) -> Option<u16> {
+        if self.encrypted() || self.changed() {
+            None
+        } else {
+            let len = self.slice.len();
+            Some(u16::from_be_bytes([self.slice[len - 2], self.slice[len - 1]]))
+        }
+    }
+
+    /// Decode all the fields and copy the results to a [`MacsecHeader`] struct.
+    pub fn to_header(&self) -> MacsecHeader {
+        MacsecHeader {
+            end_station: self.end_station(),
+            single_copy_broadcast: self.single_copy_broadcast(),
+            encrypted: self.encrypted(),
+            changed: self.changed(),
+            association_number: self.association_number(),
+            short_length: self.short_length(),
+            packet_number: self.packet_number(),
+            sci: self.sci(),
+            ether_type: self.ether_type(),
+        }
+    }
+}
@@ -1,8 +1,28 @@
 pub mod ethernet;
 pub mod vlan_tagging;
+pub mod redundancy_tag;
+pub mod mac_control;
+pub mod mpls;
+pub mod arp;
+pub mod isis;
+pub mod ppp;
+pub mod nsh;
+pub mod pppoe;
+pub mod macsec;
+pub mod linux_sll;
+pub mod null_loopback;
+pub mod vj_compression;
+pub mod llc_snap;
+pub mod radiotap;
+pub mod ieee802_11;
+pub mod lldp;
+pub mod preemption;
+pub mod ipx;
+pub mod appletalk;
+pub mod eapol;
 
 /// A slice containing the link layer header (currently only Ethernet II is supported).
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum LinkSlice<'a> {
     /// A slice containing an Ethernet II header.
     Ethernet2(ethernet::Ethernet2HeaderSlice<'a>)
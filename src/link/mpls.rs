@@ -0,0 +1,487 @@
+use super::super::*;
+
+use std::io;
+
+/// MPLS label stack entry (RFC 3032) as carried by packets with the ether
+/// types 0x8847 (unicast) & 0x8848 (multicast).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::MplsHeader;
+///
+/// let header = MplsHeader::new(1000, 5, true, 64).unwrap();
+/// let bytes = header.to_bytes().unwrap();
+/// assert_eq!([0x00, 0x3e, 0x8b, 0x40], bytes);
+///
+/// let (decoded, rest) = MplsHeader::from_slice(&bytes).unwrap();
+/// assert_eq!(header, decoded);
+/// assert!(rest.is_empty());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MplsHeader {
+    /// 20 bit label value.
+    pub label: u32,
+    /// 3 bit traffic class (formerly "experimental" bits).
+    pub traffic_class: u8,
+    /// Set if this is the last entry of the label stack.
+    pub bottom_of_stack: bool,
+    /// Time to live.
+    pub ttl: u8,
+}
+
+impl SerializedSize for MplsHeader {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 4;
+}
+
+impl MplsHeader {
+    /// Maximum value of the 20 bit label.
+    pub const MAX_LABEL: u32 = 0xf_ffff;
+
+    /// Maximum value of the 3 bit traffic class.
+    pub const MAX_TRAFFIC_CLASS: u8 = 0b111;
+
+    /// Creates a new header & checks that the label & traffic class are in range.
+    pub fn new(label: u32, traffic_class: u8, bottom_of_stack: bool, ttl: u8) -> Result<MplsHeader, ValueError> {
+        use crate::ErrorField::*;
+        max_check_u32(label, MplsHeader::MAX_LABEL, MplsLabel)?;
+        max_check_u8(traffic_class, MplsHeader::MAX_TRAFFIC_CLASS, MplsTrafficClass)?;
+        Ok(MplsHeader {
+            label,
+            traffic_class,
+            bottom_of_stack,
+            ttl,
+        })
+    }
+
+    /// Read a MplsHeader from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(MplsHeader, &[u8]), ReadError> {
+        Ok((
+            MplsHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[MplsHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a MplsHeader from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;4]) -> MplsHeader {
+        MplsHeader {
+            label: u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 4,
+            traffic_class: (bytes[2] >> 1) & 0b111,
+            bottom_of_stack: 0 != bytes[2] & 1,
+            ttl: bytes[3],
+        }
+    }
+
+    /// Read a MPLS label stack entry.
+    pub fn read<T: io::Read + io::Seek + Sized >(reader: &mut T) -> Result<MplsHeader, io::Error> {
+        let mut buffer = [0u8;MplsHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(MplsHeader::from_bytes(buffer))
+    }
+
+    /// Write the MPLS label stack entry.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        MplsHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns true if the label is in the special purpose range (0-15).
+    #[inline]
+    pub fn is_reserved(&self) -> bool {
+        self.label < MplsLabelValue::FIRST_UNRESERVED
+    }
+
+    /// Returns the decoded special purpose label or None if the label is
+    /// not reserved.
+    pub fn reserved_label(&self) -> Option<MplsLabelValue> {
+        if self.is_reserved() {
+            MplsLabelValue::from_u32(self.label)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the serialized form of the header or an value error in case
+    /// the header values are outside of range.
+    pub fn to_bytes(&self) -> Result<[u8;4], ValueError> {
+        use crate::ErrorField::*;
+        max_check_u32(self.label, MplsHeader::MAX_LABEL, MplsLabel)?;
+        max_check_u8(self.traffic_class, MplsHeader::MAX_TRAFFIC_CLASS, MplsTrafficClass)?;
+
+        let label_be = (self.label << 4).to_be_bytes();
+        Ok([
+            label_be[1],
+            label_be[2],
+            label_be[3] | (self.traffic_class << 1) | u8::from(self.bottom_of_stack),
+            self.ttl,
+        ])
+    }
+
+    /// Computes the entropy label value of a flow (RFC 6790) by mapping the
+    /// flow hash into the unreserved label range (16 to [`MplsHeader::MAX_LABEL`]).
+    pub fn entropy_label_value(key: &FlowKey, algorithm: FlowHashAlgorithm) -> u32 {
+        let range = MplsHeader::MAX_LABEL + 1 - MplsLabelValue::FIRST_UNRESERVED;
+        MplsLabelValue::FIRST_UNRESERVED + key.flow_hash(algorithm) % range
+    }
+
+    /// Returns the entropy label indicator (ELI, label 7) & the entropy label
+    /// (EL) carrying the entropy of the given flow (RFC 6790). The pair has
+    /// to be inserted directly below `self`, the label of the LSP the entropy
+    /// applies to.
+    ///
+    /// The ELI copies the traffic class & ttl of the LSP label, the EL
+    /// copies the traffic class & uses a ttl of 0. The bottom of stack flags
+    /// of both entries are cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{FlowHashAlgorithm, FlowKey, MplsHeader, MplsLabelValue, ip_number};
+    /// use std::net::IpAddr;
+    ///
+    /// let key = FlowKey {
+    ///     source_addr: IpAddr::from([192,168,1,1]),
+    ///     destination_addr: IpAddr::from([192,168,1,2]),
+    ///     ip_number: ip_number::UDP,
+    ///     source_port: 21,
+    ///     destination_port: 1234,
+    /// };
+    /// let lsp = MplsHeader::new(1000, 0, false, 64).unwrap();
+    /// let [eli, el] = lsp.entropy_label_pair(&key, FlowHashAlgorithm::Crc32);
+    /// assert_eq!(Some(MplsLabelValue::EntropyLabelIndicator), eli.reserved_label());
+    /// assert_eq!(MplsHeader::entropy_label_value(&key, FlowHashAlgorithm::Crc32), el.label);
+    /// assert_eq!(0, el.ttl);
+    /// ```
+    pub fn entropy_label_pair(&self, key: &FlowKey, algorithm: FlowHashAlgorithm) -> [MplsHeader;2] {
+        [
+            MplsHeader {
+                label: MplsLabelValue::EntropyLabelIndicator.to_u32(),
+                traffic_class: self.traffic_class,
+                bottom_of_stack: false,
+                ttl: self.ttl,
+            },
+            MplsHeader {
+                label: MplsHeader::entropy_label_value(key, algorithm),
+                traffic_class: self.traffic_class,
+                bottom_of_stack: false,
+                ttl: 0,
+            },
+        ]
+    }
+}
+
+/// Label value of a MPLS label stack entry with the special purpose labels
+/// 0-15 (see the IANA "Special-Purpose MPLS Label Values" registry) decoded.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::MplsLabelValue;
+///
+/// assert_eq!(Some(MplsLabelValue::RouterAlert), MplsLabelValue::from_u32(1));
+/// assert_eq!(Some(MplsLabelValue::Label(1000)), MplsLabelValue::from_u32(1000));
+/// assert_eq!(None, MplsLabelValue::from_u32(0x10_0000));
+/// assert_eq!(2, MplsLabelValue::Ipv6ExplicitNull.to_u32());
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MplsLabelValue {
+    /// IPv4 Explicit NULL Label (0, RFC 3032).
+    Ipv4ExplicitNull,
+    /// Router Alert Label (1, RFC 3032).
+    RouterAlert,
+    /// IPv6 Explicit NULL Label (2, RFC 3032).
+    Ipv6ExplicitNull,
+    /// Implicit NULL Label (3, RFC 3032). Only used in label distribution
+    /// protocols and not expected in packets.
+    ImplicitNull,
+    /// Entropy Label Indicator (7, RFC 6790).
+    EntropyLabelIndicator,
+    /// Generic Associated Channel Label (13, RFC 5586).
+    GenericAssociatedChannel,
+    /// OAM Alert Label (14, RFC 3429).
+    OamAlert,
+    /// Extension Label (15, RFC 7274).
+    ExtensionLabel,
+    /// Special purpose label without an assigned meaning (4-6 & 8-12).
+    Unassigned(u8),
+    /// Label value outside of the special purpose range (16 and above).
+    Label(u32),
+}
+
+impl MplsLabelValue {
+    /// Labels below this value are reserved for special purposes.
+    pub const FIRST_UNRESERVED: u32 = 16;
+
+    /// Converts a raw label value. Returns None if the value is bigger
+    /// then [`MplsHeader::MAX_LABEL`].
+    pub fn from_u32(value: u32) -> Option<MplsLabelValue> {
+        use self::MplsLabelValue::*;
+        match value {
+            0 => Some(Ipv4ExplicitNull),
+            1 => Some(RouterAlert),
+            2 => Some(Ipv6ExplicitNull),
+            3 => Some(ImplicitNull),
+            7 => Some(EntropyLabelIndicator),
+            13 => Some(GenericAssociatedChannel),
+            14 => Some(OamAlert),
+            15 => Some(ExtensionLabel),
+            4..=15 => Some(Unassigned(value as u8)),
+            16..=MplsHeader::MAX_LABEL => Some(Label(value)),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw label value.
+    pub fn to_u32(&self) -> u32 {
+        use self::MplsLabelValue::*;
+        match self {
+            Ipv4ExplicitNull => 0,
+            RouterAlert => 1,
+            Ipv6ExplicitNull => 2,
+            ImplicitNull => 3,
+            EntropyLabelIndicator => 7,
+            GenericAssociatedChannel => 13,
+            OamAlert => 14,
+            ExtensionLabel => 15,
+            Unassigned(value) => u32::from(*value),
+            Label(value) => *value,
+        }
+    }
+
+    /// Returns true if the label is in the special purpose range (0-15).
+    #[inline]
+    pub fn is_reserved(&self) -> bool {
+        self.to_u32() < MplsLabelValue::FIRST_UNRESERVED
+    }
+}
+
+/// A slice containing a single MPLS label stack entry of a network package.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MplsHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> MplsHeaderSlice<'a> {
+    /// Creates a MPLS label stack entry slice from a slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<MplsHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < MplsHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(MplsHeader::SERIALIZED_SIZE));
+        }
+        Ok(MplsHeaderSlice {
+            slice: &slice[..MplsHeader::SERIALIZED_SIZE]
+        })
+    }
+
+    /// Returns the slice containing the label stack entry.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the 20 bit "label" field from the slice.
+    #[inline]
+    pub fn label(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[0], self.slice[1], self.slice[2]]) >> 4
+    }
+
+    /// Returns true if the label is in the special purpose range (0-15).
+    #[inline]
+    pub fn is_reserved(&self) -> bool {
+        self.label() < MplsLabelValue::FIRST_UNRESERVED
+    }
+
+    /// Returns the decoded special purpose label or None if the label is
+    /// not reserved.
+    pub fn reserved_label(&self) -> Option<MplsLabelValue> {
+        if self.is_reserved() {
+            MplsLabelValue::from_u32(self.label())
+        } else {
+            None
+        }
+    }
+
+    /// Read the 3 bit "traffic class" field from the slice.
+    #[inline]
+    pub fn traffic_class(&self) -> u8 {
+        (self.slice[2] >> 1) & 0b111
+    }
+
+    /// Read the "bottom of stack" flag from the slice.
+    #[inline]
+    pub fn bottom_of_stack(&self) -> bool {
+        0 != self.slice[2] & 1
+    }
+
+    /// Read the "time to live" field from the slice.
+    #[inline]
+    pub fn ttl(&self) -> u8 {
+        self.slice[3]
+    }
+
+    /// Decode all the fields and copy the results to a MplsHeader struct
+    #[inline]
+    pub fn to_header(&self) -> MplsHeader {
+        MplsHeader {
+            label: self.label(),
+            traffic_class: self.traffic_class(),
+            bottom_of_stack: self.bottom_of_stack(),
+            ttl: self.ttl(),
+        }
+    }
+}
+
+/// A slice containing a complete MPLS label stack (all entries up to &
+/// including the entry with the bottom of stack flag set).
+///
+/// MPLS contains no field identifying the payload after the label stack, so
+/// [`SlicedPacket`] & [`PacketHeaders`] determine it based on the version
+/// field of the first payload byte (see [`MplsStackSlice::payload_ether_type`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{MplsHeader, MplsStackSlice};
+///
+/// let mut packet = Vec::new();
+/// MplsHeader::new(16, 0, false, 64).unwrap().write(&mut packet).unwrap();
+/// MplsHeader::new(1000, 0, true, 64).unwrap().write(&mut packet).unwrap();
+/// // start of an ipv4 header
+/// packet.extend_from_slice(&[0x45, 0]);
+///
+/// let stack = MplsStackSlice::from_slice(&packet).unwrap();
+/// assert_eq!(2, stack.len());
+/// assert_eq!(16, stack.top().label());
+/// assert_eq!(1000, stack.bottom().label());
+/// assert_eq!(vec![16, 1000], stack.iter().map(|e| e.label()).collect::<Vec<_>>());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MplsStackSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> MplsStackSlice<'a> {
+    /// Reads label stack entries until an entry with the bottom of stack
+    /// flag set is found.
+    pub fn from_slice(slice: &'a [u8]) -> Result<MplsStackSlice<'a>, ReadError> {
+        let mut len = 0;
+        loop {
+            let entry = MplsHeaderSlice::from_slice(&slice[len..])
+                .map_err(|err| err.add_slice_offset(len))?;
+            len += MplsHeader::SERIALIZED_SIZE;
+            if entry.bottom_of_stack() {
+                return Ok(MplsStackSlice {
+                    slice: &slice[..len]
+                });
+            }
+        }
+    }
+
+    /// Returns the slice containing the label stack.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Number of entries in the label stack (at least 1).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.slice.len() / MplsHeader::SERIALIZED_SIZE
+    }
+
+    /// Always false as a label stack contains at least one entry.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.slice.is_empty()
+    }
+
+    /// Returns the first (outermost) entry of the stack.
+    #[inline]
+    pub fn top(&self) -> MplsHeaderSlice<'a> {
+        MplsHeaderSlice {
+            slice: &self.slice[..MplsHeader::SERIALIZED_SIZE]
+        }
+    }
+
+    /// Returns the last (innermost) entry of the stack with the bottom of stack flag set.
+    #[inline]
+    pub fn bottom(&self) -> MplsHeaderSlice<'a> {
+        MplsHeaderSlice {
+            slice: &self.slice[self.slice.len() - MplsHeader::SERIALIZED_SIZE..]
+        }
+    }
+
+    /// Iterator over the entries of the stack (starting with the top entry).
+    #[inline]
+    pub fn iter(&self) -> MplsStackIter<'a> {
+        MplsStackIter {
+            rest: self.slice
+        }
+    }
+
+    /// Decodes all entries of the stack.
+    pub fn to_headers(&self) -> Vec<MplsHeader> {
+        self.iter().map(|entry| entry.to_header()).collect()
+    }
+
+    /// Returns the ether type of the payload following the label stack based
+    /// on the ip version in the first byte (`None` if the payload does not
+    /// start with the version of an ipv4 or ipv6 header).
+    pub fn payload_ether_type(payload: &[u8]) -> Option<u16> {
+        match payload.first().map(|value| value >> 4) {
+            Some(4) => Some(ether_type::IPV4),
+            Some(6) => Some(ether_type::IPV6),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &MplsStackSlice<'a> {
+    type Item = MplsHeaderSlice<'a>;
+    type IntoIter = MplsStackIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the entries of a [`MplsStackSlice`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MplsStackIter<'a> {
+    rest: &'a [u8]
+}
+
+impl<'a> Iterator for MplsStackIter<'a> {
+    type Item = MplsHeaderSlice<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            None
+        } else {
+            let (entry, rest) = self.rest.split_at(MplsHeader::SERIALIZED_SIZE);
+            self.rest = rest;
+            Some(MplsHeaderSlice { slice: entry })
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.rest.len() / MplsHeader::SERIALIZED_SIZE;
+        (len, Some(len))
+    }
+}
+
+impl<'a> ExactSizeIterator for MplsStackIter<'a> {}
@@ -0,0 +1,551 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::fmt::{Debug, Formatter};
+use std::slice::from_raw_parts;
+
+/// Values of the `next_protocol` field of a [`NshHeader`] (RFC 8300).
+///
+/// ```
+/// use etherparse::{nsh_next_protocol, NshHeader};
+///
+/// let header = NshHeader::new(1234, 255, nsh_next_protocol::IPV4).unwrap();
+/// assert_eq!(nsh_next_protocol::IPV4, header.next_protocol);
+/// ```
+pub mod nsh_next_protocol {
+    pub const IPV4: u8 = 0x1;
+    pub const IPV6: u8 = 0x2;
+    pub const ETHERNET: u8 = 0x3;
+    pub const NSH: u8 = 0x4;
+    pub const MPLS: u8 = 0x5;
+}
+
+/// Network Service Header (RFC 8300) used for service function chaining.
+///
+/// The header is identified by `ether_type::NSH` and consists of the base
+/// header, the service path header (service path identifier & service index)
+/// and the metadata. The metadata is either a fixed context of 16 bytes (MD
+/// type 1, see [`NshHeader::set_fixed_context`]) or a list of variable length
+/// TLVs (MD type 2, see [`NshHeader::set_metadata_tlvs`]).
+///
+/// [`SlicedPacket`] & [`PacketHeaders`] decode the packet following the header
+/// based on the `next_protocol` field (see the `nsh` & `inner` fields).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{nsh_next_protocol, NshHeader, NshTlv};
+///
+/// let mut nsh = NshHeader::new(1234, 255, nsh_next_protocol::ETHERNET).unwrap();
+/// nsh.set_metadata_tlvs(&[
+///     NshTlv{ class: 0x0102, tlv_type: 3, data: &[1, 2, 3, 4, 5] },
+/// ]).unwrap();
+///
+/// let bytes = nsh.to_bytes().unwrap();
+/// let (decoded, _) = NshHeader::from_slice(&bytes).unwrap();
+/// assert_eq!(nsh, decoded);
+///
+/// let tlv = decoded.metadata_tlvs().next().unwrap();
+/// assert_eq!(&[1, 2, 3, 4, 5], tlv.data);
+/// ```
+#[derive(Clone)]
+pub struct NshHeader {
+    /// OAM flag (the packet contains a control message).
+    pub oam: bool,
+    /// Time to live (6 bit value, decremented by the service functions).
+    pub ttl: u8,
+    /// Protocol type of the packet following the header (see [`nsh_next_protocol`]).
+    pub next_protocol: u8,
+    /// Service path identifier (24 bit value).
+    pub service_path_id: u32,
+    /// Service index (position of the packet in the service path).
+    pub service_index: u8,
+    /// Metadata type (see [`NshHeader::MD_TYPE_1`] & [`NshHeader::MD_TYPE_2`]).
+    md_type: u8,
+    /// Length of the metadata in the metadata_buffer in bytes.
+    metadata_len: u8,
+    metadata_buffer: [u8; NshHeader::MAX_METADATA_LEN],
+}
+
+impl NshHeader {
+    /// Length of the base header & the service path header (header without metadata).
+    pub const MIN_LEN: usize = 8;
+
+    /// Maximum length of the metadata of a NSH header.
+    pub const MAX_METADATA_LEN: usize = NshHeader::MAX_LEN - NshHeader::MIN_LEN;
+
+    /// Maximum length of a NSH header (including metadata).
+    pub const MAX_LEN: usize = 0b0011_1111 * 4;
+
+    /// Maximum value of the TTL.
+    pub const MAX_TTL: u8 = 0b0011_1111;
+
+    /// Maximum value of the service path identifier.
+    pub const MAX_SERVICE_PATH_ID: u32 = 0xff_ffff;
+
+    /// Metadata type with a fixed context of 16 bytes.
+    pub const MD_TYPE_1: u8 = 0x1;
+
+    /// Metadata type with variable length TLVs.
+    pub const MD_TYPE_2: u8 = 0x2;
+
+    /// Length of the fixed context of MD type 1 headers.
+    pub const FIXED_CONTEXT_LEN: usize = 16;
+
+    const OAM_FLAG: u8 = 0b0010_0000;
+
+    /// Creates a MD type 2 header without metadata & the default TTL of 63.
+    pub fn new(service_path_id: u32, service_index: u8, next_protocol: u8) -> Result<NshHeader, ValueError> {
+        max_check_u32(service_path_id, NshHeader::MAX_SERVICE_PATH_ID, ErrorField::NshServicePathId)?;
+        Ok(NshHeader {
+            oam: false,
+            ttl: NshHeader::MAX_TTL,
+            next_protocol,
+            service_path_id,
+            service_index,
+            md_type: NshHeader::MD_TYPE_2,
+            metadata_len: 0,
+            metadata_buffer: [0; NshHeader::MAX_METADATA_LEN],
+        })
+    }
+
+    /// Read a NSH header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(NshHeader, &[u8]), ReadError> {
+        let header = NshHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Reads a NSH header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<NshHeader, ReadError> {
+        let mut buffer = [0u8; NshHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..NshHeader::MIN_LEN])?;
+        let len = NshHeaderSlice::header_len_from_start(&buffer)?;
+        reader.read_exact(&mut buffer[NshHeader::MIN_LEN..len])?;
+        NshHeaderSlice::validate_metadata(buffer[2] & 0xf, &buffer[NshHeader::MIN_LEN..len])?;
+
+        // SAFETY:
+        // Length, version & metadata were validated above.
+        Ok(unsafe { NshHeaderSlice::from_slice_unchecked(&buffer[..len]) }.to_header())
+    }
+
+    /// Writes the NSH header to the current position.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        NshHeader::MIN_LEN + usize::from(self.metadata_len)
+    }
+
+    /// Metadata type of the header.
+    #[inline]
+    pub fn md_type(&self) -> u8 {
+        self.md_type
+    }
+
+    /// Returns a slice containing the serialized metadata.
+    #[inline]
+    pub fn metadata(&self) -> &[u8] {
+        &self.metadata_buffer[..usize::from(self.metadata_len)]
+    }
+
+    /// Returns the fixed context if the header has the MD type 1.
+    pub fn fixed_context(&self) -> Option<[u8; NshHeader::FIXED_CONTEXT_LEN]> {
+        if NshHeader::MD_TYPE_1 == self.md_type {
+            let mut result = [0; NshHeader::FIXED_CONTEXT_LEN];
+            result.copy_from_slice(self.metadata());
+            Some(result)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the metadata TLVs (empty if the header
+    /// does not have the MD type 2).
+    #[inline]
+    pub fn metadata_tlvs(&self) -> NshTlvIterator<'_> {
+        NshTlvIterator::new(self.md_type, self.metadata())
+    }
+
+    /// Sets the MD type to 1 & the metadata to the given fixed context.
+    pub fn set_fixed_context(&mut self, context: [u8; NshHeader::FIXED_CONTEXT_LEN]) {
+        self.md_type = NshHeader::MD_TYPE_1;
+        self.metadata_buffer[..NshHeader::FIXED_CONTEXT_LEN].copy_from_slice(&context);
+        self.metadata_len = NshHeader::FIXED_CONTEXT_LEN as u8;
+    }
+
+    /// Sets the MD type to 2 & the metadata to the given TLVs.
+    ///
+    /// If the TLVs are not fitting into the header or the data of a TLV
+    /// is too long no data is set and an error is returned.
+    pub fn set_metadata_tlvs(&mut self, tlvs: &[NshTlv]) -> Result<(), ValueError> {
+        use crate::ValueError::*;
+
+        let mut len = 0;
+        for tlv in tlvs {
+            if NshTlv::MAX_DATA_LEN < tlv.data.len() {
+                return Err(NshTlvDataTooLong(tlv.data.len()));
+            }
+            len += tlv.header_len();
+        }
+        if NshHeader::MAX_METADATA_LEN < len {
+            return Err(NshMetadataLengthBad(len));
+        }
+
+        let mut offset = 0;
+        for tlv in tlvs {
+            let class = tlv.class.to_be_bytes();
+            self.metadata_buffer[offset..offset + 4].copy_from_slice(&[
+                class[0],
+                class[1],
+                tlv.tlv_type,
+                tlv.data.len() as u8,
+            ]);
+            let data_start = offset + 4;
+            self.metadata_buffer[data_start..data_start + tlv.data.len()].copy_from_slice(tlv.data);
+            // zero the padding
+            for value in &mut self.metadata_buffer[data_start + tlv.data.len()..offset + tlv.header_len()] {
+                *value = 0;
+            }
+            offset += tlv.header_len();
+        }
+        self.md_type = NshHeader::MD_TYPE_2;
+        self.metadata_len = len as u8;
+        Ok(())
+    }
+
+    /// Returns the ether type matching the `next_protocol` field (`None` if
+    /// the next protocol has no ether type or is unknown).
+    pub fn next_ether_type(&self) -> Option<u16> {
+        NshHeader::next_protocol_ether_type(self.next_protocol)
+    }
+
+    fn next_protocol_ether_type(next_protocol: u8) -> Option<u16> {
+        use crate::nsh_next_protocol::*;
+        match next_protocol {
+            IPV4 => Some(ether_type::IPV4),
+            IPV6 => Some(ether_type::IPV6),
+            ETHERNET => Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING),
+            NSH => Some(ether_type::NSH),
+            MPLS => Some(ether_type::MPLS_UNICAST),
+            _ => None,
+        }
+    }
+
+    /// Returns the serialized form of the header (fails if the TTL exceeds
+    /// 6 bits or the service path identifier exceeds 24 bits).
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { NshHeader::MAX_LEN }>, ValueError> {
+        max_check_u8(self.ttl, NshHeader::MAX_TTL, ErrorField::NshTtl)?;
+        max_check_u32(self.service_path_id, NshHeader::MAX_SERVICE_PATH_ID, ErrorField::NshServicePathId)?;
+
+        let len = (self.header_len() / 4) as u8;
+        let spi = self.service_path_id.to_be_bytes();
+
+        let mut result = ArrayVec::new();
+        result.try_extend_from_slice(&[
+            // version 0
+            (if self.oam { NshHeader::OAM_FLAG } else { 0 }) | (self.ttl >> 2),
+            (self.ttl << 6) | len,
+            self.md_type,
+            self.next_protocol,
+            spi[1],
+            spi[2],
+            spi[3],
+            self.service_index,
+        ]).unwrap();
+        result.try_extend_from_slice(self.metadata()).unwrap();
+        Ok(result)
+    }
+}
+
+impl Debug for NshHeader {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "NshHeader {{ oam: {}, ttl: {}, next_protocol: {}, service_path_id: {}, service_index: {}, md_type: {}, metadata: {:?} }}",
+            self.oam,
+            self.ttl,
+            self.next_protocol,
+            self.service_path_id,
+            self.service_index,
+            self.md_type,
+            self.metadata())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for NshHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "NshHeader {{ oam: {}, ttl: {}, next_protocol: {}, service_path_id: {}, service_index: {}, md_type: {}, metadata: {=[u8]} }}",
+            self.oam,
+            self.ttl,
+            self.next_protocol,
+            self.service_path_id,
+            self.service_index,
+            self.md_type,
+            self.metadata())
+    }
+}
+
+impl std::cmp::PartialEq for NshHeader {
+    fn eq(&self, other: &NshHeader) -> bool {
+        self.oam == other.oam &&
+        self.ttl == other.ttl &&
+        self.next_protocol == other.next_protocol &&
+        self.service_path_id == other.service_path_id &&
+        self.service_index == other.service_index &&
+        self.md_type == other.md_type &&
+        self.metadata() == other.metadata()
+    }
+}
+
+impl std::cmp::Eq for NshHeader {}
+
+impl std::hash::Hash for NshHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.oam.hash(state);
+        self.ttl.hash(state);
+        self.next_protocol.hash(state);
+        self.service_path_id.hash(state);
+        self.service_index.hash(state);
+        self.md_type.hash(state);
+        self.metadata().hash(state);
+    }
+}
+
+/// Slice containing a NSH header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NshHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> NshHeaderSlice<'a> {
+    /// Creates a NSH header slice from a slice (the version, the length
+    /// & the metadata TLVs are validated).
+    pub fn from_slice(slice: &'a [u8]) -> Result<NshHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < NshHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(NshHeader::MIN_LEN));
+        }
+        let len = NshHeaderSlice::header_len_from_start(slice)?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        NshHeaderSlice::validate_metadata(slice[2] & 0xf, &slice[NshHeader::MIN_LEN..len])?;
+
+        Ok(NshHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this
+            // code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Creates a NSH header slice from a slice (assumes slice size & content
+    /// was validated before).
+    ///
+    /// # Safety
+    ///
+    /// This method assumes that the slice was previously validated to contain
+    /// a valid NSH header. This means the slice length must be exactly the
+    /// header length indicated by the length field & the metadata must
+    /// have been checked with `validate_metadata`.
+    unsafe fn from_slice_unchecked(slice: &'a [u8]) -> NshHeaderSlice<'a> {
+        NshHeaderSlice { slice }
+    }
+
+    /// Checks the version & the length field and returns the header length.
+    fn header_len_from_start(start: &[u8]) -> Result<usize, ReadError> {
+        let version = start[0] >> 6;
+        if 0 != version {
+            return Err(ReadError::NshUnsupportedVersion(version));
+        }
+        let length = start[1] & 0b0011_1111;
+        let md_type = start[2] & 0xf;
+        let valid = if NshHeader::MD_TYPE_1 == md_type {
+            usize::from(length) * 4 == NshHeader::MIN_LEN + NshHeader::FIXED_CONTEXT_LEN
+        } else {
+            usize::from(length) * 4 >= NshHeader::MIN_LEN
+        };
+        if false == valid {
+            return Err(ReadError::NshLengthBad{ md_type, length });
+        }
+        Ok(usize::from(length) * 4)
+    }
+
+    /// Checks that all TLVs of MD type 2 metadata are located within the
+    /// given metadata slice.
+    fn validate_metadata(md_type: u8, metadata: &[u8]) -> Result<(), ReadError> {
+        if NshHeader::MD_TYPE_2 != md_type {
+            return Ok(());
+        }
+        let mut offset = 0;
+        while offset < metadata.len() {
+            // the metadata length is a multiple of 4, so the tlv
+            // header is always present
+            let len = NshTlv::padded_len(metadata[offset + 3]);
+            if metadata.len() < offset + len {
+                return Err(ReadError::NshMetadataLengthTooBig(offset));
+            }
+            offset += len;
+        }
+        Ok(())
+    }
+
+    /// Returns the slice containing the NSH header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the version field (always 0).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 6
+    }
+
+    /// Read the OAM flag.
+    #[inline]
+    pub fn oam(&self) -> bool {
+        0 != self.slice[0] & NshHeader::OAM_FLAG
+    }
+
+    /// Read the time to live field (6 bit value).
+    #[inline]
+    pub fn ttl(&self) -> u8 {
+        ((self.slice[0] & 0xf) << 2) | (self.slice[1] >> 6)
+    }
+
+    /// Read the length field (length of the header in 4 byte units).
+    #[inline]
+    pub fn length(&self) -> u8 {
+        self.slice[1] & 0b0011_1111
+    }
+
+    /// Read the metadata type field.
+    #[inline]
+    pub fn md_type(&self) -> u8 {
+        self.slice[2] & 0xf
+    }
+
+    /// Read the next protocol field (see [`nsh_next_protocol`]).
+    #[inline]
+    pub fn next_protocol(&self) -> u8 {
+        self.slice[3]
+    }
+
+    /// Returns the ether type matching the `next_protocol` field (`None` if
+    /// the next protocol has no ether type or is unknown).
+    #[inline]
+    pub fn next_ether_type(&self) -> Option<u16> {
+        NshHeader::next_protocol_ether_type(self.next_protocol())
+    }
+
+    /// Read the service path identifier (24 bit value).
+    #[inline]
+    pub fn service_path_id(&self) -> u32 {
+        u32::from_be_bytes([0, self.slice[4], self.slice[5], self.slice[6]])
+    }
+
+    /// Read the service index.
+    #[inline]
+    pub fn service_index(&self) -> u8 {
+        self.slice[7]
+    }
+
+    /// Returns a slice containing the metadata.
+    #[inline]
+    pub fn metadata(&self) -> &'a [u8] {
+        &self.slice[NshHeader::MIN_LEN..]
+    }
+
+    /// Returns an iterator over the metadata TLVs (empty if the header
+    /// does not have the MD type 2).
+    #[inline]
+    pub fn metadata_tlvs(&self) -> NshTlvIterator<'a> {
+        NshTlvIterator::new(self.md_type(), self.metadata())
+    }
+
+    /// Decode all the fields and copy the results to a [`NshHeader`] struct.
+    pub fn to_header(&self) -> NshHeader {
+        let metadata = self.metadata();
+        NshHeader {
+            oam: self.oam(),
+            ttl: self.ttl(),
+            next_protocol: self.next_protocol(),
+            service_path_id: self.service_path_id(),
+            service_index: self.service_index(),
+            md_type: self.md_type(),
+            metadata_len: metadata.len() as u8,
+            metadata_buffer: {
+                let mut values = [0; NshHeader::MAX_METADATA_LEN];
+                values[..metadata.len()].copy_from_slice(metadata);
+                values
+            },
+        }
+    }
+}
+
+/// Metadata TLV of a MD type 2 NSH header (type-length-value).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NshTlv<'a> {
+    /// Namespace of the metadata type.
+    pub class: u16,
+    /// Type of the metadata.
+    pub tlv_type: u8,
+    /// Metadata (at most 127 bytes, padded to a multiple of 4 bytes when serialized).
+    pub data: &'a [u8],
+}
+
+impl<'a> NshTlv<'a> {
+    /// Maximum length of the data.
+    pub const MAX_DATA_LEN: usize = 0b0111_1111;
+
+    /// Length of the serialized TLV in bytes (including padding).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        4 + self.data.len().div_ceil(4) * 4
+    }
+
+    /// length of a tlv including padding based on the length byte
+    fn padded_len(length: u8) -> usize {
+        4 + usize::from(length & 0b0111_1111).div_ceil(4) * 4
+    }
+}
+
+/// Iterator over the metadata TLVs of a NSH header.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NshTlvIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> NshTlvIterator<'a> {
+    fn new(md_type: u8, metadata: &'a [u8]) -> NshTlvIterator<'a> {
+        NshTlvIterator {
+            rest: if NshHeader::MD_TYPE_2 == md_type { metadata } else { &[] },
+        }
+    }
+}
+
+impl<'a> Iterator for NshTlvIterator<'a> {
+    type Item = NshTlv<'a>;
+
+    fn next(&mut self) -> Option<NshTlv<'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        // the length of the tlvs were validated when the header
+        // was created
+        let data_len = usize::from(self.rest[3] & 0b0111_1111);
+        let result = NshTlv {
+            class: u16::from_be_bytes([self.rest[0], self.rest[1]]),
+            tlv_type: self.rest[2],
+            data: &self.rest[4..4 + data_len],
+        };
+        self.rest = &self.rest[NshTlv::padded_len(self.rest[3])..];
+        Some(result)
+    }
+}
@@ -0,0 +1,123 @@
+use super::super::*;
+
+use std::io;
+
+/// `u32` constants of the address family values used in BSD loopback
+/// headers (the IPv6 value differs between the operating systems).
+pub mod null_address_family {
+    /// IPv4 (`AF_INET` on all BSDs & macOS).
+    pub const INET: u32 = 2;
+    /// IPv6 on NetBSD & OpenBSD (`AF_INET6`).
+    pub const INET6_BSD: u32 = 24;
+    /// IPv6 on FreeBSD & DragonFly BSD (`AF_INET6`).
+    pub const INET6_FREEBSD: u32 = 28;
+    /// IPv6 on macOS & iOS (`AF_INET6`).
+    pub const INET6_DARWIN: u32 = 30;
+}
+
+/// BSD loopback header (`LINKTYPE_NULL` & `LINKTYPE_LOOP`), used for
+/// packets captured on the loopback interface of macOS & the BSDs (`lo0`).
+///
+/// The header only consists of the 4 byte address family of the following
+/// packet. `LINKTYPE_NULL` headers are written in the byte order of the
+/// capturing host, so the byte order is detected while reading (address
+/// families are always smaller then 65536) & kept for serializing the
+/// header again.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, NullHeader, null_address_family};
+///
+/// // little endian header captured on macOS
+/// let (header, _) = NullHeader::from_slice(&[30, 0, 0, 0]).unwrap();
+/// assert_eq!(null_address_family::INET6_DARWIN, header.family);
+/// assert_eq!(false, header.big_endian);
+/// assert_eq!(Some(ether_type::IPV6), header.ether_type());
+/// assert_eq!([30, 0, 0, 0], header.to_bytes());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NullHeader {
+    /// Address family of the following packet (see [`null_address_family`]).
+    pub family: u32,
+    /// True if the family is serialized in big endian byte order.
+    pub big_endian: bool,
+}
+
+impl SerializedSize for NullHeader {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 4;
+}
+
+impl NullHeader {
+
+    /// Read a NullHeader from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(NullHeader, &[u8]), ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < NullHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(NullHeader::SERIALIZED_SIZE));
+        }
+        Ok((
+            NullHeader::from_bytes([slice[0], slice[1], slice[2], slice[3]]),
+            &slice[NullHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a NullHeader from a static sized byte array (detecting the byte order).
+    pub fn from_bytes(bytes: [u8;4]) -> NullHeader {
+        // the upper two bytes of an address family are always zero
+        if 0 == bytes[0] && 0 == bytes[1] {
+            NullHeader {
+                family: u32::from_be_bytes(bytes),
+                big_endian: true,
+            }
+        } else {
+            NullHeader {
+                family: u32::from_le_bytes(bytes),
+                big_endian: false,
+            }
+        }
+    }
+
+    /// Reads a BSD loopback header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<NullHeader, io::Error> {
+        let mut buffer = [0;NullHeader::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(NullHeader::from_bytes(buffer))
+    }
+
+    /// Writes the header to the current position of the write argument.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), io::Error> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        NullHeader::SERIALIZED_SIZE
+    }
+
+    /// Returns the ether type matching the address family (`None` if the
+    /// family is neither IPv4 nor IPv6).
+    pub fn ether_type(&self) -> Option<u16> {
+        use null_address_family::*;
+        match self.family {
+            INET => Some(ether_type::IPV4),
+            INET6_BSD | INET6_FREEBSD | INET6_DARWIN => Some(ether_type::IPV6),
+            _ => None,
+        }
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;4] {
+        if self.big_endian {
+            self.family.to_be_bytes()
+        } else {
+            self.family.to_le_bytes()
+        }
+    }
+}
@@ -0,0 +1,286 @@
+use super::super::*;
+
+/// PPP protocol field values (RFC 1661) of commonly used protocols.
+pub mod ppp_protocol {
+    /// Internet Protocol version 4.
+    pub const IPV4: u16 = 0x0021;
+    /// Internet Protocol version 6.
+    pub const IPV6: u16 = 0x0057;
+    /// Van Jacobson compressed TCP/IP (RFC 1144).
+    pub const VJ_COMPRESSED_TCP: u16 = 0x002d;
+    /// Van Jacobson uncompressed TCP/IP (RFC 1144).
+    pub const VJ_UNCOMPRESSED_TCP: u16 = 0x002f;
+    /// MPLS unicast.
+    pub const MPLS_UNICAST: u16 = 0x0281;
+    /// MPLS multicast.
+    pub const MPLS_MULTICAST: u16 = 0x0283;
+    /// IPv4 control protocol.
+    pub const IPCP: u16 = 0x8021;
+    /// IPv6 control protocol.
+    pub const IPV6CP: u16 = 0x8057;
+    /// Link control protocol.
+    pub const LCP: u16 = 0xc021;
+    /// Password authentication protocol.
+    pub const PAP: u16 = 0xc023;
+    /// Challenge handshake authentication protocol.
+    pub const CHAP: u16 = 0xc223;
+}
+
+/// Ether type corresponding to a PPP protocol (`None` if there is no
+/// corresponding ether type).
+pub(crate) fn ppp_protocol_ether_type(protocol: u16) -> Option<u16> {
+    match protocol {
+        ppp_protocol::IPV4 => Some(ether_type::IPV4),
+        ppp_protocol::IPV6 => Some(ether_type::IPV6),
+        ppp_protocol::MPLS_UNICAST => Some(ether_type::MPLS_UNICAST),
+        ppp_protocol::MPLS_MULTICAST => Some(ether_type::MPLS_MULTICAST),
+        _ => None,
+    }
+}
+
+/// Errors that can occur while decoding a PPP frame in HDLC-like framing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PppHdlcError {
+    /// The frame is too short. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+    /// A control escape byte (0x7d) is not followed by an escaped byte (end
+    /// of the frame or abort sequence). The value is the offset of the control
+    /// escape byte in the stuffed frame.
+    InvalidEscape(usize),
+    /// A flag byte (0x7e) is located inside the frame. The value is the offset
+    /// of the flag byte in the stuffed frame.
+    UnexpectedFlag(usize),
+    /// The frame check sequence does not match the frame content.
+    FcsMismatch{
+        /// FCS calculated over the frame.
+        expected: u16,
+        /// FCS contained in the frame.
+        actual: u16,
+    },
+}
+
+impl Error for PppHdlcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for PppHdlcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PppHdlcError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "PppHdlcError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+            InvalidEscape(offset) => {
+                write!(f, "PppHdlcError: The control escape byte at offset {} is not followed by an escaped byte.", offset)
+            },
+            UnexpectedFlag(offset) => {
+                write!(f, "PppHdlcError: Unexpected flag byte at offset {} inside the frame.", offset)
+            },
+            FcsMismatch{ expected, actual } => {
+                write!(f, "PppHdlcError: Frame check sequence {:#06x} does not match the calculated value {:#06x}.", actual, expected)
+            },
+        }
+    }
+}
+
+/// Slice containing a PPP frame in HDLC-like framing (RFC 1662) after
+/// the byte unstuffing.
+///
+/// The slice contains the address & control field (if not compressed),
+/// the protocol field (1 or 2 bytes), the information field & the 16 bit
+/// frame check sequence. The flag bytes are not part of the slice. The FCS
+/// is verified when the slice is created.
+///
+/// Frames captured from a serial line (e.g. `DLT_PPP_SERIAL`) still contain
+/// the escaped control characters and have to be unstuffed with
+/// [`PppHdlcSlice::unstuff`] first. The information field can then be sliced
+/// using the ether type returned by [`PppHdlcSlice::ether_type`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, ppp_protocol, PppHdlcSlice, SlicedPacket};
+///
+/// // build a frame containing an (empty) ipv4 payload
+/// let mut frame = vec![0xff, 0x03, 0x00, 0x21];
+/// let fcs = PppHdlcSlice::fcs16(&frame);
+/// frame.extend_from_slice(&fcs.to_le_bytes());
+/// let stuffed = PppHdlcSlice::stuff(&frame);
+///
+/// // decode it again
+/// let unstuffed = PppHdlcSlice::unstuff(&stuffed).unwrap();
+/// let ppp = PppHdlcSlice::from_slice(&unstuffed).unwrap();
+/// assert_eq!(ppp_protocol::IPV4, ppp.protocol());
+/// assert_eq!(Some(ether_type::IPV4), ppp.ether_type());
+/// assert!(ppp.information().is_empty());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PppHdlcSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> PppHdlcSlice<'a> {
+    /// Flag byte delimiting the frames.
+    pub const FLAG: u8 = 0x7e;
+
+    /// Control escape byte.
+    pub const CONTROL_ESCAPE: u8 = 0x7d;
+
+    /// Value of the address field (all stations).
+    pub const ADDRESS: u8 = 0xff;
+
+    /// Value of the control field (unnumbered information).
+    pub const CONTROL: u8 = 0x03;
+
+    /// Length of the frame check sequence.
+    pub const FCS_LEN: usize = 2;
+
+    /// Creates a slice from an unstuffed frame (without flag bytes) & verifies
+    /// the frame check sequence.
+    pub fn from_slice(slice: &'a [u8]) -> Result<PppHdlcSlice<'a>, PppHdlcError> {
+        use PppHdlcError::*;
+
+        let result = PppHdlcSlice { slice };
+        // at least one protocol byte & the fcs has to be present
+        let min_len = result.protocol_offset() + 1 + PppHdlcSlice::FCS_LEN;
+        if slice.len() < min_len {
+            return Err(UnexpectedEndOfSlice(min_len));
+        }
+        let min_len = result.information_offset() + PppHdlcSlice::FCS_LEN;
+        if slice.len() < min_len {
+            return Err(UnexpectedEndOfSlice(min_len));
+        }
+
+        let expected = PppHdlcSlice::fcs16(&slice[..slice.len() - PppHdlcSlice::FCS_LEN]);
+        let actual = result.fcs();
+        if expected != actual {
+            return Err(FcsMismatch{ expected, actual });
+        }
+        Ok(result)
+    }
+
+    /// Removes the flag bytes at the start & end of a stuffed frame and
+    /// reverts the escaping of control characters.
+    pub fn unstuff(stuffed: &[u8]) -> Result<Vec<u8>, PppHdlcError> {
+        use PppHdlcError::*;
+
+        let start = stuffed.iter().position(|&b| b != PppHdlcSlice::FLAG).unwrap_or(stuffed.len());
+        let end = stuffed.iter().rposition(|&b| b != PppHdlcSlice::FLAG).map(|i| i + 1).unwrap_or(start);
+
+        let mut result = Vec::with_capacity(end - start);
+        let mut iter = stuffed[start..end].iter().enumerate();
+        while let Some((offset, &value)) = iter.next() {
+            match value {
+                PppHdlcSlice::CONTROL_ESCAPE => match iter.next() {
+                    Some((_, &escaped)) if escaped != PppHdlcSlice::FLAG => result.push(escaped ^ 0x20),
+                    _ => return Err(InvalidEscape(start + offset)),
+                },
+                PppHdlcSlice::FLAG => return Err(UnexpectedFlag(start + offset)),
+                value => result.push(value),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Escapes the flag & control escape bytes as well as all control
+    /// characters (async control character map 0xffffffff) and adds a flag
+    /// byte at the start & end of the frame.
+    pub fn stuff(frame: &[u8]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(frame.len() + 2);
+        result.push(PppHdlcSlice::FLAG);
+        for &value in frame {
+            if value < 0x20 || value == PppHdlcSlice::FLAG || value == PppHdlcSlice::CONTROL_ESCAPE {
+                result.push(PppHdlcSlice::CONTROL_ESCAPE);
+                result.push(value ^ 0x20);
+            } else {
+                result.push(value);
+            }
+        }
+        result.push(PppHdlcSlice::FLAG);
+        result
+    }
+
+    /// Calculates the 16 bit frame check sequence (RFC 1662 appendix C.2)
+    /// of the given data.
+    ///
+    /// The FCS is transmitted least significant byte first.
+    pub fn fcs16(data: &[u8]) -> u16 {
+        let mut fcs = 0xffffu16;
+        for &value in data {
+            fcs ^= u16::from(value);
+            for _ in 0..8 {
+                fcs = if 0 != fcs & 1 {
+                    (fcs >> 1) ^ 0x8408
+                } else {
+                    fcs >> 1
+                };
+            }
+        }
+        !fcs
+    }
+
+    /// Returns the slice containing the frame (including the FCS).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns true if the address & control field are present (not
+    /// compressed via address-and-control-field-compression).
+    #[inline]
+    pub fn has_address_control(&self) -> bool {
+        self.slice.len() >= 2
+            && self.slice[0] == PppHdlcSlice::ADDRESS
+            && self.slice[1] == PppHdlcSlice::CONTROL
+    }
+
+    /// Protocol of the information field (see [ppp_protocol] for the known values).
+    pub fn protocol(&self) -> u16 {
+        let offset = self.protocol_offset();
+        if self.protocol_compressed() {
+            u16::from(self.slice[offset])
+        } else {
+            u16::from_be_bytes([self.slice[offset], self.slice[offset + 1]])
+        }
+    }
+
+    /// Ether type corresponding to the protocol of the information field
+    /// (`None` if there is no corresponding ether type).
+    pub fn ether_type(&self) -> Option<u16> {
+        ppp_protocol_ether_type(self.protocol())
+    }
+
+    /// Information field of the frame (between the protocol field & the FCS).
+    #[inline]
+    pub fn information(&self) -> &'a [u8] {
+        &self.slice[self.information_offset()..self.slice.len() - PppHdlcSlice::FCS_LEN]
+    }
+
+    /// Frame check sequence contained in the frame.
+    #[inline]
+    pub fn fcs(&self) -> u16 {
+        let len = self.slice.len();
+        u16::from_le_bytes([self.slice[len - 2], self.slice[len - 1]])
+    }
+
+    /// offset of the protocol field
+    #[inline]
+    fn protocol_offset(&self) -> usize {
+        if self.has_address_control() { 2 } else { 0 }
+    }
+
+    /// true if the protocol field is compressed to one byte (only the
+    /// last byte of a protocol number has the least significant bit set)
+    #[inline]
+    fn protocol_compressed(&self) -> bool {
+        0 != self.slice[self.protocol_offset()] & 1
+    }
+
+    /// offset of the information field
+    #[inline]
+    fn information_offset(&self) -> usize {
+        self.protocol_offset() + if self.protocol_compressed() { 1 } else { 2 }
+    }
+}
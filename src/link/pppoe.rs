@@ -0,0 +1,292 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::slice::from_raw_parts;
+
+/// Values of the `code` field of a [`PppoeHeader`] (RFC 2516).
+pub mod pppoe_code {
+    /// Session data (ether type `ether_type::PPPOE_SESSION`).
+    pub const SESSION_DATA: u8 = 0x00;
+    /// PPPoE Active Discovery Offer.
+    pub const PADO: u8 = 0x07;
+    /// PPPoE Active Discovery Initiation.
+    pub const PADI: u8 = 0x09;
+    /// PPPoE Active Discovery Request.
+    pub const PADR: u8 = 0x19;
+    /// PPPoE Active Discovery Session-confirmation.
+    pub const PADS: u8 = 0x65;
+    /// PPPoE Active Discovery Terminate.
+    pub const PADT: u8 = 0xa7;
+}
+
+/// PPP over Ethernet header (RFC 2516) of the discovery (ether type
+/// `ether_type::PPPOE_DISCOVERY`) & session stage (ether type
+/// `ether_type::PPPOE_SESSION`).
+///
+/// Session data packets (code [`pppoe_code::SESSION_DATA`]) additionally
+/// contain the PPP protocol field identifying the payload. [`SlicedPacket`]
+/// & [`PacketHeaders`] continue with decoding the IPv4 or IPv6 packet if the
+/// PPP protocol is [`ppp_protocol::IPV4`] or [`ppp_protocol::IPV6`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ether_type, ppp_protocol, PppoeHeader, SlicedPacket};
+///
+/// let mut pppoe = PppoeHeader::new_session(0x1234, ppp_protocol::IPV6);
+/// pppoe.set_payload_len(40).unwrap();
+/// assert_eq!(42, pppoe.payload_length);
+///
+/// let bytes = pppoe.to_bytes().unwrap();
+/// let (decoded, _) = PppoeHeader::from_slice(&bytes).unwrap();
+/// assert_eq!(pppoe, decoded);
+/// assert_eq!(Some(ether_type::IPV6), decoded.ether_type());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PppoeHeader {
+    /// Version (4 bit value, 1 for RFC 2516).
+    pub version: u8,
+    /// Type (4 bit value, 1 for RFC 2516).
+    pub pppoe_type: u8,
+    /// Code identifying the discovery packet type or session data (see [`pppoe_code`]).
+    pub code: u8,
+    /// Session identifier (0 before the session is established).
+    pub session_id: u16,
+    /// Length of the PPPoE payload (including the PPP protocol field of
+    /// session data packets).
+    pub payload_length: u16,
+    /// PPP protocol field (only present in session data packets, see [`ppp_protocol`]).
+    pub ppp_protocol: Option<u16>,
+}
+
+impl PppoeHeader {
+    /// Length of a header without the PPP protocol field.
+    pub const MIN_LEN: usize = 6;
+
+    /// Length of a header including the PPP protocol field.
+    pub const MAX_LEN: usize = 8;
+
+    /// Version & type defined in RFC 2516.
+    pub const VERSION: u8 = 1;
+
+    /// Maximum value of the version & type fields.
+    pub const MAX_VERSION: u8 = 0xf;
+
+    /// Creates a session data header with the given PPP protocol & an empty payload.
+    pub fn new_session(session_id: u16, ppp_protocol: u16) -> PppoeHeader {
+        PppoeHeader {
+            version: PppoeHeader::VERSION,
+            pppoe_type: PppoeHeader::VERSION,
+            code: pppoe_code::SESSION_DATA,
+            session_id,
+            payload_length: 2,
+            ppp_protocol: Some(ppp_protocol),
+        }
+    }
+
+    /// Creates a discovery stage header with the given code & an empty payload.
+    pub fn new_discovery(code: u8, session_id: u16) -> PppoeHeader {
+        PppoeHeader {
+            version: PppoeHeader::VERSION,
+            pppoe_type: PppoeHeader::VERSION,
+            code,
+            session_id,
+            payload_length: 0,
+            ppp_protocol: None,
+        }
+    }
+
+    /// Read a PPPoE header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(PppoeHeader, &[u8]), ReadError> {
+        let header = PppoeHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Reads a PPPoE header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<PppoeHeader, ReadError> {
+        let mut buffer = [0u8; PppoeHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..PppoeHeader::MIN_LEN])?;
+        let len = PppoeHeaderSlice::header_len_from_start(&buffer)?;
+        reader.read_exact(&mut buffer[PppoeHeader::MIN_LEN..len])?;
+        Ok(PppoeHeaderSlice::from_slice(&buffer[..len])?.to_header())
+    }
+
+    /// Writes the PPPoE header to the current position.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        if self.ppp_protocol.is_some() {
+            PppoeHeader::MAX_LEN
+        } else {
+            PppoeHeader::MIN_LEN
+        }
+    }
+
+    /// Sets the payload length field based on the length of the data
+    /// following the header (the PPP protocol field is added automatically).
+    pub fn set_payload_len(&mut self, value: usize) -> Result<(), ValueError> {
+        let len = value + self.header_len() - PppoeHeader::MIN_LEN;
+        if usize::from(u16::MAX) < len {
+            Err(ValueError::PppoePayloadLengthTooLarge(value))
+        } else {
+            self.payload_length = len as u16;
+            Ok(())
+        }
+    }
+
+    /// Returns the ether type matching the PPP protocol field (`None` for
+    /// discovery packets or if the protocol has no ether type).
+    #[inline]
+    pub fn ether_type(&self) -> Option<u16> {
+        self.ppp_protocol.and_then(ppp_protocol_ether_type)
+    }
+
+    /// Returns the serialized form of the header (fails if the version
+    /// or type exceed 4 bits).
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { PppoeHeader::MAX_LEN }>, ValueError> {
+        max_check_u8(self.version, PppoeHeader::MAX_VERSION, ErrorField::PppoeVersion)?;
+        max_check_u8(self.pppoe_type, PppoeHeader::MAX_VERSION, ErrorField::PppoeType)?;
+
+        let session_id = self.session_id.to_be_bytes();
+        let payload_length = self.payload_length.to_be_bytes();
+
+        let mut result = ArrayVec::new();
+        result.try_extend_from_slice(&[
+            (self.version << 4) | self.pppoe_type,
+            self.code,
+            session_id[0],
+            session_id[1],
+            payload_length[0],
+            payload_length[1],
+        ]).unwrap();
+        if let Some(protocol) = self.ppp_protocol {
+            result.try_extend_from_slice(&protocol.to_be_bytes()).unwrap();
+        }
+        Ok(result)
+    }
+}
+
+/// Slice containing a PPPoE header (including the PPP protocol field
+/// of session data packets).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PppoeHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> PppoeHeaderSlice<'a> {
+    /// Creates a PPPoE header slice from a slice (the version & type
+    /// are validated).
+    pub fn from_slice(slice: &'a [u8]) -> Result<PppoeHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < PppoeHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(PppoeHeader::MIN_LEN));
+        }
+        let len = PppoeHeaderSlice::header_len_from_start(slice)?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        Ok(PppoeHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least
+            // len before this code can be reached.
+            slice: unsafe {
+                from_raw_parts(slice.as_ptr(), len)
+            }
+        })
+    }
+
+    /// Validates the first 6 bytes of a header & returns the header length.
+    fn header_len_from_start(start: &[u8]) -> Result<usize, ReadError> {
+        use crate::ReadError::*;
+
+        let version = start[0] >> 4;
+        let pppoe_type = start[0] & 0xf;
+        if PppoeHeader::VERSION != version || PppoeHeader::VERSION != pppoe_type {
+            return Err(PppoeUnsupportedVersion{ version, pppoe_type });
+        }
+        if pppoe_code::SESSION_DATA == start[1] {
+            let payload_length = u16::from_be_bytes([start[4], start[5]]);
+            if payload_length < 2 {
+                return Err(PppoePayloadLengthTooSmall(payload_length));
+            }
+            Ok(PppoeHeader::MAX_LEN)
+        } else {
+            Ok(PppoeHeader::MIN_LEN)
+        }
+    }
+
+    /// Returns the slice containing the PPPoE header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the version field (4 bit value).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0] >> 4
+    }
+
+    /// Read the type field (4 bit value).
+    #[inline]
+    pub fn pppoe_type(&self) -> u8 {
+        self.slice[0] & 0xf
+    }
+
+    /// Read the code field (see [`pppoe_code`]).
+    #[inline]
+    pub fn code(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Read the session identifier.
+    #[inline]
+    pub fn session_id(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the payload length field (including the PPP protocol field of
+    /// session data packets).
+    #[inline]
+    pub fn payload_length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[4], self.slice[5]])
+    }
+
+    /// Read the PPP protocol field (only present in session data packets).
+    #[inline]
+    pub fn ppp_protocol(&self) -> Option<u16> {
+        if PppoeHeader::MAX_LEN == self.slice.len() {
+            Some(u16::from_be_bytes([self.slice[6], self.slice[7]]))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ether type matching the PPP protocol field (`None` for
+    /// discovery packets or if the protocol has no ether type).
+    #[inline]
+    pub fn ether_type(&self) -> Option<u16> {
+        self.ppp_protocol().and_then(ppp_protocol_ether_type)
+    }
+
+    /// Decode all the fields and copy the results to a [`PppoeHeader`] struct.
+    pub fn to_header(&self) -> PppoeHeader {
+        PppoeHeader {
+            version: self.version(),
+            pppoe_type: self.pppoe_type(),
+            code: self.code(),
+            session_id: self.session_id(),
+            payload_length: self.payload_length(),
+            ppp_protocol: self.ppp_protocol(),
+        }
+    }
+}
@@ -0,0 +1,418 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// Start mPacket delimiters (SMD) of IEEE 802.3br frame preemption.
+pub mod smd {
+    /// Express frame (identical to the ethernet start frame delimiter).
+    pub const EXPRESS: u8 = 0xd5;
+    /// Start of a preemptable frame (frame numbers 0 to 3).
+    pub const START: [u8;4] = [0xe6, 0x4c, 0x7f, 0xb3];
+    /// Continuation fragment of a preemptable frame (frame numbers 0 to 3).
+    pub const CONTINUATION: [u8;4] = [0x61, 0x52, 0x9e, 0x2a];
+    /// Verify mPacket (preemption capability verification).
+    pub const VERIFY: u8 = 0x07;
+    /// Respond mPacket (preemption capability verification).
+    pub const RESPOND: u8 = 0x19;
+    /// Values of the fragment count of continuation fragments (counts 0 to 3).
+    pub const FRAG_COUNT: [u8;4] = [0xe6, 0x4c, 0x7f, 0xb3];
+}
+
+/// Value the CRC of a frame is XOR'ed with to get the mCRC of a fragment
+/// that is followed by further fragments.
+const MCRC_XOR: u32 = 0x0000_ffff;
+
+/// Errors that can occur while decoding or reassembling mPackets.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PreemptionError {
+    /// The slice is too short. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+    /// The start mPacket delimiter is not known.
+    UnknownSmd(u8),
+    /// The fragment count of a continuation fragment is not known.
+    UnknownFragCount(u8),
+    /// A continuation fragment was received without a preceding start fragment.
+    MissingStart {
+        /// Frame number of the continuation fragment.
+        frame: u8,
+    },
+    /// The fragment count of a continuation fragment does not match the
+    /// number of previously received fragments of the frame.
+    UnexpectedFragCount {
+        /// Frame number of the continuation fragment.
+        frame: u8,
+        /// Expected fragment count.
+        expected: u8,
+        /// Fragment count of the fragment.
+        actual: u8,
+    },
+    /// The CRC of the mPacket matches neither the FCS nor the mCRC of the frame.
+    CrcMismatch,
+}
+
+impl Error for PreemptionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for PreemptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PreemptionError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "PreemptionError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+            UnknownSmd(value) => {
+                write!(f, "PreemptionError: Unknown start mPacket delimiter {:#04x}.", value)
+            },
+            UnknownFragCount(value) => {
+                write!(f, "PreemptionError: Unknown fragment count {:#04x}.", value)
+            },
+            MissingStart{ frame } => {
+                write!(f, "PreemptionError: Received a continuation fragment of the frame {} without a start fragment.", frame)
+            },
+            UnexpectedFragCount{ frame, expected, actual } => {
+                write!(f, "PreemptionError: Unexpected fragment count {} of the frame {} (expected {}).", actual, frame, expected)
+            },
+            CrcMismatch => {
+                write!(f, "PreemptionError: The CRC of the mPacket matches neither the FCS nor the mCRC.")
+            },
+        }
+    }
+}
+
+/// Kind of an mPacket (identified by the start mPacket delimiter).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MPacketKind {
+    /// Express (not preemptable) frame.
+    Express,
+    /// Start of a preemptable frame (complete frame if it was not preempted).
+    Start {
+        /// Frame number (0 to 3).
+        frame: u8,
+    },
+    /// Continuation fragment of a preempted frame.
+    Continuation {
+        /// Frame number (0 to 3).
+        frame: u8,
+        /// Fragment count (0 to 3, wrapping).
+        frag_count: u8,
+    },
+    /// Verify mPacket.
+    Verify,
+    /// Respond mPacket.
+    Respond,
+}
+
+/// Slice containing an IEEE 802.3br mPacket (express frame, fragment of a
+/// preemptable frame or verify/respond packet).
+///
+/// The slice has to start with the preamble as captured by TSN capture
+/// devices exposing the mPackets (the preamble bytes are skipped, a shortened
+/// preamble is tolerated) & end with the CRC (FCS or mCRC). Fragments of
+/// preempted frames can be reassembled with a [`PreemptionReassembler`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{MPacketKind, MPacketSlice};
+///
+/// let frame = [0xff;60];
+/// let fragments = MPacketSlice::fragment_frame(&frame, 1, 40);
+/// assert_eq!(2, fragments.len());
+///
+/// let start = MPacketSlice::from_slice(&fragments[0]).unwrap();
+/// assert_eq!(MPacketKind::Start{ frame: 1 }, start.kind());
+/// assert_eq!(&frame[..40], start.data());
+///
+/// let continuation = MPacketSlice::from_slice(&fragments[1]).unwrap();
+/// assert_eq!(MPacketKind::Continuation{ frame: 1, frag_count: 0 }, continuation.kind());
+/// assert_eq!(&frame[40..], continuation.data());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MPacketSlice<'a> {
+    slice: &'a [u8],
+    kind: MPacketKind,
+    data_offset: usize,
+}
+
+impl<'a> MPacketSlice<'a> {
+    /// Value of the preamble bytes.
+    pub const PREAMBLE: u8 = 0x55;
+
+    /// Length of the preamble before the SMD of start & express mPackets
+    /// (continuation fragments have a preamble of 6 bytes).
+    pub const PREAMBLE_LEN: usize = 7;
+
+    /// Length of the CRC at the end of an mPacket.
+    pub const CRC_LEN: usize = 4;
+
+    /// Decodes an mPacket.
+    pub fn from_slice(slice: &'a [u8]) -> Result<MPacketSlice<'a>, PreemptionError> {
+        use PreemptionError::*;
+
+        // skip the preamble
+        let preamble_len = slice.iter()
+            .take(MPacketSlice::PREAMBLE_LEN)
+            .take_while(|value| MPacketSlice::PREAMBLE == **value)
+            .count();
+        if slice.len() < preamble_len + 1 + MPacketSlice::CRC_LEN {
+            return Err(UnexpectedEndOfSlice(preamble_len + 1 + MPacketSlice::CRC_LEN));
+        }
+
+        let value = slice[preamble_len];
+        let (kind, data_offset) = if smd::EXPRESS == value {
+            (MPacketKind::Express, preamble_len + 1)
+        } else if smd::VERIFY == value {
+            (MPacketKind::Verify, preamble_len + 1)
+        } else if smd::RESPOND == value {
+            (MPacketKind::Respond, preamble_len + 1)
+        } else if let Some(frame) = smd::START.iter().position(|v| *v == value) {
+            (MPacketKind::Start{ frame: frame as u8 }, preamble_len + 1)
+        } else if let Some(frame) = smd::CONTINUATION.iter().position(|v| *v == value) {
+            if slice.len() < preamble_len + 2 + MPacketSlice::CRC_LEN {
+                return Err(UnexpectedEndOfSlice(preamble_len + 2 + MPacketSlice::CRC_LEN));
+            }
+            let frag_count = slice[preamble_len + 1];
+            match smd::FRAG_COUNT.iter().position(|v| *v == frag_count) {
+                Some(frag_count) => (
+                    MPacketKind::Continuation{ frame: frame as u8, frag_count: frag_count as u8 },
+                    preamble_len + 2
+                ),
+                None => return Err(UnknownFragCount(frag_count)),
+            }
+        } else {
+            return Err(UnknownSmd(value));
+        };
+
+        Ok(MPacketSlice {
+            slice,
+            kind,
+            data_offset,
+        })
+    }
+
+    /// Returns the slice containing the mPacket (including preamble & CRC).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Kind of the mPacket.
+    #[inline]
+    pub fn kind(&self) -> MPacketKind {
+        self.kind
+    }
+
+    /// Returns the data between the SMD (or fragment count) & the CRC.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        // SAFETY:
+        // Safe as from_slice verified that the slice contains the
+        // data offset & the crc.
+        unsafe {
+            from_raw_parts(
+                self.slice.as_ptr().add(self.data_offset),
+                self.slice.len() - self.data_offset - MPacketSlice::CRC_LEN
+            )
+        }
+    }
+
+    /// CRC at the end of the mPacket (FCS of the complete frame or mCRC
+    /// of a fragment followed by further fragments).
+    #[inline]
+    pub fn crc(&self) -> u32 {
+        let end = self.slice.len();
+        u32::from_le_bytes([
+            self.slice[end - 4],
+            self.slice[end - 3],
+            self.slice[end - 2],
+            self.slice[end - 1],
+        ])
+    }
+
+    /// Returns true if the CRC of an express frame or a start fragment is
+    /// the FCS of the data (the frame was not preempted).
+    pub fn is_complete_frame(&self) -> bool {
+        match self.kind {
+            MPacketKind::Express | MPacketKind::Start{ .. } => {
+                crate::flow_hash::crc32(self.data()) == self.crc()
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns the express mPacket (preamble, SMD, frame & FCS) of a frame
+    /// (without FCS).
+    pub fn express_frame(frame: &[u8]) -> Vec<u8> {
+        let mut result = Vec::with_capacity(MPacketSlice::PREAMBLE_LEN + 1 + frame.len() + MPacketSlice::CRC_LEN);
+        result.extend_from_slice(&[MPacketSlice::PREAMBLE; MPacketSlice::PREAMBLE_LEN]);
+        result.push(smd::EXPRESS);
+        result.extend_from_slice(frame);
+        result.extend_from_slice(&crate::flow_hash::crc32(frame).to_le_bytes());
+        result
+    }
+
+    /// Splits a frame (without FCS) into a start fragment & continuation
+    /// fragments (mPackets including preamble & CRC) containing at most
+    /// `fragment_len` bytes of the frame each.
+    ///
+    /// The frame number is masked to the values 0 to 3. A `fragment_len`
+    /// of 0 is treated as 1.
+    pub fn fragment_frame(frame: &[u8], frame_number: u8, fragment_len: usize) -> Vec<Vec<u8>> {
+        let frame_number = usize::from(frame_number & 0b11);
+        let fragment_len = fragment_len.max(1);
+        let mut result = Vec::new();
+        let mut offset = 0;
+        loop {
+            let end = (offset + fragment_len).min(frame.len());
+            let mut fragment = Vec::with_capacity(MPacketSlice::PREAMBLE_LEN + 2 + end - offset + MPacketSlice::CRC_LEN);
+            if 0 == offset {
+                fragment.extend_from_slice(&[MPacketSlice::PREAMBLE; MPacketSlice::PREAMBLE_LEN]);
+                fragment.push(smd::START[frame_number]);
+            } else {
+                fragment.extend_from_slice(&[MPacketSlice::PREAMBLE; MPacketSlice::PREAMBLE_LEN - 1]);
+                fragment.push(smd::CONTINUATION[frame_number]);
+                fragment.push(smd::FRAG_COUNT[(result.len() - 1) % 4]);
+            }
+            fragment.extend_from_slice(&frame[offset..end]);
+            let crc = crate::flow_hash::crc32(&frame[..end]);
+            let crc = if end == frame.len() {
+                crc
+            } else {
+                crc ^ MCRC_XOR
+            };
+            fragment.extend_from_slice(&crc.to_le_bytes());
+            result.push(fragment);
+
+            offset = end;
+            if offset == frame.len() {
+                return result;
+            }
+        }
+    }
+}
+
+/// State of a preemptable frame that is being reassembled.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct PendingFrame {
+    data: Vec<u8>,
+    next_frag_count: u8,
+}
+
+/// Reassembles preempted frames from the mPackets of an IEEE 802.3br link.
+///
+/// The mPackets have to be pushed in the order they were received. Complete
+/// frames (express frames, start fragments of frames that were not preempted
+/// & the last fragment of a preempted frame) are returned without the FCS.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{MPacketSlice, PreemptionReassembler};
+///
+/// let preemptable = [0x11;100];
+/// let express = [0x22;60];
+/// let express_mpacket = MPacketSlice::express_frame(&express);
+///
+/// let fragments = MPacketSlice::fragment_frame(&preemptable, 0, 64);
+/// let mut reassembler = PreemptionReassembler::new();
+/// assert_eq!(
+///     None,
+///     reassembler.push(&MPacketSlice::from_slice(&fragments[0]).unwrap()).unwrap()
+/// );
+/// // express frame preempting the frame
+/// assert_eq!(
+///     Some(express.to_vec()),
+///     reassembler.push(&MPacketSlice::from_slice(&express_mpacket).unwrap()).unwrap()
+/// );
+/// assert_eq!(
+///     Some(preemptable.to_vec()),
+///     reassembler.push(&MPacketSlice::from_slice(&fragments[1]).unwrap()).unwrap()
+/// );
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PreemptionReassembler {
+    pending: [Option<PendingFrame>;4],
+}
+
+impl PreemptionReassembler {
+    /// Creates a reassembler without pending frames.
+    pub fn new() -> PreemptionReassembler {
+        Default::default()
+    }
+
+    /// Returns true if a preempted frame with the given frame number (0 to 3)
+    /// is waiting for further fragments.
+    pub fn is_pending(&self, frame: u8) -> bool {
+        self.pending[usize::from(frame & 0b11)].is_some()
+    }
+
+    /// Discards all pending frames.
+    pub fn clear(&mut self) {
+        self.pending = Default::default();
+    }
+
+    /// Processes an mPacket & returns the frame (without FCS) if it is complete.
+    ///
+    /// A start fragment discards a pending frame with the same frame number.
+    /// If an error is returned the pending frame referenced by the mPacket
+    /// is discarded.
+    pub fn push(&mut self, mpacket: &MPacketSlice) -> Result<Option<Vec<u8>>, PreemptionError> {
+        use PreemptionError::*;
+        let crc = mpacket.crc();
+        match mpacket.kind() {
+            MPacketKind::Verify | MPacketKind::Respond => Ok(None),
+            MPacketKind::Express => {
+                if mpacket.is_complete_frame() {
+                    Ok(Some(mpacket.data().to_vec()))
+                } else {
+                    Err(CrcMismatch)
+                }
+            },
+            MPacketKind::Start{ frame } => {
+                let index = usize::from(frame);
+                self.pending[index] = None;
+                let data = mpacket.data().to_vec();
+                let value = crate::flow_hash::crc32(&data);
+                if value == crc {
+                    Ok(Some(data))
+                } else if value ^ MCRC_XOR == crc {
+                    self.pending[index] = Some(PendingFrame {
+                        data,
+                        next_frag_count: 0,
+                    });
+                    Ok(None)
+                } else {
+                    Err(CrcMismatch)
+                }
+            },
+            MPacketKind::Continuation{ frame, frag_count } => {
+                let index = usize::from(frame);
+                let mut pending = match self.pending[index].take() {
+                    Some(pending) => pending,
+                    None => return Err(MissingStart{ frame }),
+                };
+                if pending.next_frag_count != frag_count {
+                    return Err(UnexpectedFragCount{
+                        frame,
+                        expected: pending.next_frag_count,
+                        actual: frag_count,
+                    });
+                }
+                pending.data.extend_from_slice(mpacket.data());
+                let value = crate::flow_hash::crc32(&pending.data);
+                if value == crc {
+                    Ok(Some(pending.data))
+                } else if value ^ MCRC_XOR == crc {
+                    pending.next_frag_count = (pending.next_frag_count + 1) % 4;
+                    self.pending[index] = Some(pending);
+                    Ok(None)
+                } else {
+                    Err(CrcMismatch)
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use super::super::*;
+
+/// Bit indices of the fields in the present flags of a radiotap header
+/// (see <https://www.radiotap.org/fields/defined>).
+pub mod radiotap_field {
+    /// Value of the TSF timer in microseconds (`u64`).
+    pub const TSFT: u8 = 0;
+    /// Properties of the frame (`u8`, see [`super::radiotap_flags`]).
+    pub const FLAGS: u8 = 1;
+    /// TX/RX data rate in 500 kbps (`u8`).
+    pub const RATE: u8 = 2;
+    /// Channel frequency in MHz & channel flags (`u16`, `u16`).
+    pub const CHANNEL: u8 = 3;
+    /// Hop set & pattern of frequency hopping radios (`u8`, `u8`).
+    pub const FHSS: u8 = 4;
+    /// Antenna signal power in dBm (`i8`).
+    pub const DBM_ANTENNA_SIGNAL: u8 = 5;
+    /// Antenna noise power in dBm (`i8`).
+    pub const DBM_ANTENNA_NOISE: u8 = 6;
+    /// Quality of the Barker code lock (`u16`).
+    pub const LOCK_QUALITY: u8 = 7;
+    /// Transmit power as unitless distance from the maximum power (`u16`).
+    pub const TX_ATTENUATION: u8 = 8;
+    /// Transmit power as dB distance from the maximum power (`u16`).
+    pub const DB_TX_ATTENUATION: u8 = 9;
+    /// Transmit power in dBm (`i8`).
+    pub const DBM_TX_POWER: u8 = 10;
+    /// Index of the antenna used to transmit or receive the frame (`u8`).
+    pub const ANTENNA: u8 = 11;
+    /// Antenna signal power in dB from an arbitrary reference (`u8`).
+    pub const DB_ANTENNA_SIGNAL: u8 = 12;
+    /// Antenna noise power in dB from an arbitrary reference (`u8`).
+    pub const DB_ANTENNA_NOISE: u8 = 13;
+    /// Properties of received frames (`u16`).
+    pub const RX_FLAGS: u8 = 14;
+    /// Properties of transmitted frames (`u16`).
+    pub const TX_FLAGS: u8 = 15;
+    /// Number of RTS retries (`u8`).
+    pub const RTS_RETRIES: u8 = 16;
+    /// Number of data retries (`u8`).
+    pub const DATA_RETRIES: u8 = 17;
+    /// Extended channel information (`u32` flags, `u16` frequency, `u8` channel, `u8` max power).
+    pub const XCHANNEL: u8 = 18;
+    /// 802.11n MCS rate information (`u8` known, `u8` flags, `u8` mcs).
+    pub const MCS: u8 = 19;
+    /// A-MPDU status (`u32` reference number, `u16` flags, `u8` delimiter crc, `u8` reserved).
+    pub const AMPDU_STATUS: u8 = 20;
+    /// 802.11ac VHT information (12 bytes).
+    pub const VHT: u8 = 21;
+    /// Frame timestamp (12 bytes).
+    pub const TIMESTAMP: u8 = 22;
+    /// 802.11ax HE information (12 bytes).
+    pub const HE: u8 = 23;
+    /// 802.11ax HE-MU information (12 bytes).
+    pub const HE_MU: u8 = 24;
+    /// 802.11ax HE-MU per user information (6 bytes).
+    pub const HE_MU_OTHER_USER: u8 = 25;
+    /// Type of a PPDU without PSDU (`u8`).
+    pub const ZERO_LENGTH_PSDU: u8 = 26;
+    /// L-SIG contents (`u16`, `u16`).
+    pub const L_SIG: u8 = 27;
+    /// Type-length-value fields following the fields of the present flags.
+    pub const TLV: u8 = 28;
+    /// The next present flags restart the radiotap namespace.
+    pub const RADIOTAP_NAMESPACE: u8 = 29;
+    /// The next present flags belong to a vendor namespace.
+    pub const VENDOR_NAMESPACE: u8 = 30;
+    /// Further present flags follow.
+    pub const EXT: u8 = 31;
+}
+
+/// Bits of the [`radiotap_field::FLAGS`] field.
+pub mod radiotap_flags {
+    /// Sent/received during the contention free period.
+    pub const CFP: u8 = 0x01;
+    /// Sent/received with a short preamble.
+    pub const SHORT_PREAMBLE: u8 = 0x02;
+    /// Sent/received with WEP encryption.
+    pub const WEP: u8 = 0x04;
+    /// Sent/received with fragmentation.
+    pub const FRAGMENTATION: u8 = 0x08;
+    /// The frame includes the frame check sequence (FCS) at its end.
+    pub const FCS: u8 = 0x10;
+    /// The 802.11 header is padded to a 4 byte boundary.
+    pub const DATA_PAD: u8 = 0x20;
+    /// The frame failed the FCS check.
+    pub const BAD_FCS: u8 = 0x40;
+    /// The frame used a short guard interval (HT).
+    pub const SHORT_GI: u8 = 0x80;
+}
+
+/// Returns the alignment & size of a field in the radiotap namespace
+/// (`None` if the field is not known).
+fn radiotap_field_align_size(field: u8) -> Option<(usize, usize)> {
+    use self::radiotap_field::*;
+    match field {
+        TSFT => Some((8, 8)),
+        FLAGS | RATE | DBM_ANTENNA_SIGNAL | DBM_ANTENNA_NOISE | DBM_TX_POWER |
+        ANTENNA | DB_ANTENNA_SIGNAL | DB_ANTENNA_NOISE | RTS_RETRIES |
+        DATA_RETRIES | ZERO_LENGTH_PSDU => Some((1, 1)),
+        CHANNEL | L_SIG => Some((2, 4)),
+        FHSS | LOCK_QUALITY | TX_ATTENUATION | DB_TX_ATTENUATION | RX_FLAGS |
+        TX_FLAGS => Some((2, 2)),
+        XCHANNEL | AMPDU_STATUS => Some((4, 8)),
+        MCS => Some((1, 3)),
+        VHT | HE | HE_MU => Some((2, 12)),
+        TIMESTAMP => Some((8, 12)),
+        HE_MU_OTHER_USER => Some((2, 6)),
+        _ => None,
+    }
+}
+
+/// A slice containing a radiotap header (`LINKTYPE_IEEE802_11_RADIOTAP`),
+/// the capture metadata preceding 802.11 frames captured in monitor mode.
+///
+/// The header consists of the version, the header length & one or more 32
+/// bit present flags, followed by the fields marked as present (each aligned
+/// to its natural alignment). The fields can be iterated via
+/// [`RadiotapHeaderSlice::fields`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{RadiotapHeaderSlice, radiotap_field, radiotap_flags};
+///
+/// let header = RadiotapHeaderSlice::from_slice(&[
+///     0, 0, 16, 0, // version, pad & length
+///     0x0e, 0, 0, 0, // present flags (flags, rate & channel)
+///     radiotap_flags::FCS, 0x02, // flags & rate
+///     0x6c, 0x09, 0xa0, 0x00, // channel (2412 MHz)
+///     0, 0, // padding of the 802.11 frame alignment
+/// ]).unwrap();
+/// assert_eq!(Some(radiotap_flags::FCS), header.flags());
+/// assert_eq!(Some((2412, 0xa0)), header.channel());
+///
+/// let fields: Vec<u8> = header.fields().map(|field| field.kind).collect();
+/// assert_eq!(vec![radiotap_field::FLAGS, radiotap_field::RATE, radiotap_field::CHANNEL], fields);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RadiotapHeaderSlice<'a> {
+    slice: &'a [u8],
+    /// offset of the first field (after the present flags)
+    fields_offset: usize,
+}
+
+impl<'a> RadiotapHeaderSlice<'a> {
+    /// Minimum length of a radiotap header (version, pad, length & one present flags word).
+    pub const MIN_LEN: usize = 8;
+
+    /// Creates a radiotap header slice from an other slice.
+    pub fn from_slice(slice: &'a [u8]) -> Result<RadiotapHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        if slice.len() < RadiotapHeaderSlice::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(RadiotapHeaderSlice::MIN_LEN));
+        }
+        if 0 != slice[0] {
+            return Err(RadiotapUnsupportedVersion(slice[0]));
+        }
+        let length = u16::from_le_bytes([slice[2], slice[3]]);
+        if usize::from(length) < RadiotapHeaderSlice::MIN_LEN {
+            return Err(RadiotapLengthTooSmall(length));
+        }
+        if slice.len() < usize::from(length) {
+            return Err(UnexpectedEndOfSlice(length.into()));
+        }
+
+        // find the end of the present flags
+        let mut fields_offset = 8;
+        while 0 != slice[fields_offset - 1] & 0x80 {
+            fields_offset += 4;
+            if usize::from(length) < fields_offset {
+                return Err(RadiotapLengthTooSmall(length));
+            }
+        }
+
+        Ok(RadiotapHeaderSlice {
+            slice: &slice[..usize::from(length)],
+            fields_offset,
+        })
+    }
+
+    /// Returns the slice containing the header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the version field (always 0).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Read the header length (including the fields).
+    #[inline]
+    pub fn header_length(&self) -> u16 {
+        u16::from_le_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the first present flags word (bit `n` is set if the field `n`
+    /// of [`radiotap_field`] is present).
+    #[inline]
+    pub fn present_flags(&self) -> u32 {
+        u32::from_le_bytes([self.slice[4], self.slice[5], self.slice[6], self.slice[7]])
+    }
+
+    /// Returns the slice containing all present flags words.
+    #[inline]
+    pub fn present_flags_slice(&self) -> &'a [u8] {
+        &self.slice[4..self.fields_offset]
+    }
+
+    /// Iterates over the present fields.
+    pub fn fields(&self) -> RadiotapFieldIter<'a> {
+        RadiotapFieldIter {
+            header: self.slice,
+            present_offset: 4,
+            bit: 0,
+            offset: self.fields_offset,
+            namespace: RadiotapNamespace::Radiotap,
+            next_namespace: None,
+            extended: false,
+            done: false,
+        }
+    }
+
+    /// Returns the data of the first field of the given kind in the
+    /// radiotap namespace (`None` if the field is not present).
+    pub fn field(&self, kind: u8) -> Option<&'a [u8]> {
+        self.fields()
+            .find(|field| field.kind == kind)
+            .map(|field| field.data)
+    }
+
+    /// Read the flags field (see [`radiotap_flags`]).
+    pub fn flags(&self) -> Option<u8> {
+        self.field(radiotap_field::FLAGS).map(|data| data[0])
+    }
+
+    /// Read the TSF timer value in microseconds.
+    pub fn tsft(&self) -> Option<u64> {
+        self.field(radiotap_field::TSFT).map(|data| {
+            let mut bytes = [0;8];
+            bytes.copy_from_slice(data);
+            u64::from_le_bytes(bytes)
+        })
+    }
+
+    /// Read the data rate in 500 kbps.
+    pub fn rate(&self) -> Option<u8> {
+        self.field(radiotap_field::RATE).map(|data| data[0])
+    }
+
+    /// Read the channel frequency in MHz & the channel flags.
+    pub fn channel(&self) -> Option<(u16, u16)> {
+        self.field(radiotap_field::CHANNEL).map(|data| (
+            u16::from_le_bytes([data[0], data[1]]),
+            u16::from_le_bytes([data[2], data[3]]),
+        ))
+    }
+
+    /// Read the antenna signal power in dBm.
+    pub fn dbm_antenna_signal(&self) -> Option<i8> {
+        self.field(radiotap_field::DBM_ANTENNA_SIGNAL).map(|data| data[0] as i8)
+    }
+
+    /// Read the antenna noise power in dBm.
+    pub fn dbm_antenna_noise(&self) -> Option<i8> {
+        self.field(radiotap_field::DBM_ANTENNA_NOISE).map(|data| data[0] as i8)
+    }
+}
+
+/// A field of a radiotap header (see [`RadiotapHeaderSlice::fields`]).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RadiotapField<'a> {
+    /// Bit index of the field in the present flags (see [`radiotap_field`]).
+    ///
+    /// Vendor namespaces are returned as [`radiotap_field::VENDOR_NAMESPACE`]
+    /// with the 6 bytes of the namespace (OUI, sub namespace & skip length)
+    /// followed by the vendor defined data. Type-length-value fields are
+    /// returned as one [`radiotap_field::TLV`] field containing the rest of
+    /// the header.
+    pub kind: u8,
+    /// Data of the field (without alignment padding).
+    pub data: &'a [u8],
+}
+
+/// Namespace the present flags word currently iterated belongs to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+enum RadiotapNamespace {
+    Radiotap,
+    Vendor,
+}
+
+/// Iterator over the fields of a radiotap header.
+///
+/// The iteration ends at the first field of the radiotap namespace with an
+/// unknown alignment (fields defined after this implementation) or at a
+/// field exceeding the header length.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RadiotapFieldIter<'a> {
+    header: &'a [u8],
+    /// offset of the present flags word currently iterated
+    present_offset: usize,
+    /// next bit to check in the current present flags word
+    bit: u8,
+    /// offset of the next field data
+    offset: usize,
+    namespace: RadiotapNamespace,
+    /// namespace of the next present flags word (if it gets reset)
+    next_namespace: Option<RadiotapNamespace>,
+    /// true if the current word extends the radiotap namespace (bits 32 & above)
+    extended: bool,
+    done: bool,
+}
+
+impl<'a> RadiotapFieldIter<'a> {
+    /// Returns `len` bytes of data at the next offset aligned to `align`.
+    fn take(&mut self, align: usize, len: usize) -> Option<&'a [u8]> {
+        let start = (self.offset + align - 1) & !(align - 1);
+        if self.header.len() < start + len {
+            self.done = true;
+            return None;
+        }
+        self.offset = start + len;
+        Some(&self.header[start..start + len])
+    }
+}
+
+impl<'a> Iterator for RadiotapFieldIter<'a> {
+    type Item = RadiotapField<'a>;
+
+    fn next(&mut self) -> Option<RadiotapField<'a>> {
+        use self::radiotap_field::*;
+
+        while false == self.done {
+            let p = self.present_offset;
+            let word = u32::from_le_bytes([
+                self.header[p], self.header[p + 1], self.header[p + 2], self.header[p + 3]
+            ]);
+
+            while self.bit < EXT {
+                let bit = self.bit;
+                self.bit += 1;
+                if 0 == word & (1 << bit) {
+                    continue;
+                }
+                match (self.namespace, bit) {
+                    (_, RADIOTAP_NAMESPACE) => {
+                        self.next_namespace = Some(RadiotapNamespace::Radiotap);
+                    },
+                    (_, VENDOR_NAMESPACE) => {
+                        // the skip length allows skipping the vendor defined data
+                        let start = (self.offset + 1) & !1;
+                        let skip_len = match self.header.get(start + 4..start + 6) {
+                            Some(value) => usize::from(u16::from_le_bytes([value[0], value[1]])),
+                            None => {
+                                self.done = true;
+                                return None;
+                            }
+                        };
+                        self.next_namespace = Some(RadiotapNamespace::Vendor);
+                        return self.take(2, 6 + skip_len).map(|data| RadiotapField{ kind: bit, data });
+                    },
+                    (RadiotapNamespace::Vendor, _) => {
+                        // vendor fields are part of the vendor namespace data
+                    },
+                    (RadiotapNamespace::Radiotap, TLV) if false == self.extended => {
+                        self.done = true;
+                        let start = ((self.offset + 3) & !3).min(self.header.len());
+                        return Some(RadiotapField{ kind: bit, data: &self.header[start..] });
+                    },
+                    (RadiotapNamespace::Radiotap, _) => {
+                        let (align, size) = match radiotap_field_align_size(bit) {
+                            Some(value) if false == self.extended => value,
+                            _ => {
+                                self.done = true;
+                                return None;
+                            }
+                        };
+                        return self.take(align, size).map(|data| RadiotapField{ kind: bit, data });
+                    },
+                }
+            }
+
+            // continue with the next present flags word
+            if 0 == word & (1 << EXT) {
+                self.done = true;
+            } else {
+                self.present_offset += 4;
+                self.bit = 0;
+                match self.next_namespace.take() {
+                    Some(namespace) => {
+                        self.namespace = namespace;
+                        self.extended = false;
+                    },
+                    None => {
+                        self.extended = true;
+                    }
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,299 @@
+use super::super::*;
+
+use std::io;
+use std::slice::from_raw_parts;
+
+/// IEEE 802.1CB redundancy tag ("R-TAG", ether type 0xF1C1) used by the
+/// "Frame Replication and Elimination for Reliability" (FRER) of time
+/// sensitive networks.
+///
+/// The tag contains the sequence number used to detect & eliminate duplicate
+/// frames and the ether type of the following data (the 16 reserved bits at the
+/// start of the tag are ignored when reading and written as zero).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{RedundancyTag, ether_type};
+///
+/// let tag = RedundancyTag {
+///     sequence_number: 1234,
+///     ether_type: ether_type::IPV4,
+/// };
+/// let bytes = tag.to_bytes();
+/// assert_eq!([0, 0, 0x04, 0xd2, 0x08, 0x00], bytes);
+///
+/// let (decoded, rest) = RedundancyTag::from_slice(&bytes).unwrap();
+/// assert_eq!(tag, decoded);
+/// assert!(rest.is_empty());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RedundancyTag {
+    /// Sequence number of the frame (identical in all replicas of a frame).
+    pub sequence_number: u16,
+    /// Type id of content after this header. Refer to the "EtherType" for a list of possible supported values.
+    pub ether_type: u16,
+}
+
+impl SerializedSize for RedundancyTag {
+    /// Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 6;
+}
+
+impl RedundancyTag {
+
+    /// Read a RedundancyTag from a slice and return the header & unused parts of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(RedundancyTag, &[u8]), ReadError> {
+        Ok((
+            RedundancyTagSlice::from_slice(slice)?.to_header(),
+            &slice[RedundancyTag::SERIALIZED_SIZE .. ]
+        ))
+    }
+
+    /// Read a RedundancyTag from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;6]) -> RedundancyTag {
+        RedundancyTag{
+            sequence_number: u16::from_be_bytes([bytes[2], bytes[3]]),
+            ether_type: u16::from_be_bytes([bytes[4], bytes[5]]),
+        }
+    }
+
+    /// Read a IEEE 802.1CB redundancy tag.
+    pub fn read<T: io::Read + io::Seek + Sized >(reader: &mut T) -> Result<RedundancyTag, io::Error> {
+        let mut buffer = [0u8;RedundancyTag::SERIALIZED_SIZE];
+        reader.read_exact(&mut buffer)?;
+        Ok(RedundancyTag::from_bytes(buffer))
+    }
+
+    /// Write the IEEE 802.1CB redundancy tag.
+    #[inline]
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        RedundancyTag::SERIALIZED_SIZE
+    }
+
+    /// Returns the serialized form of the header.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;6] {
+        let seq_be = self.sequence_number.to_be_bytes();
+        let eth_type_be = self.ether_type.to_be_bytes();
+        [
+            0,
+            0,
+            seq_be[0],
+            seq_be[1],
+            eth_type_be[0],
+            eth_type_be[1],
+        ]
+    }
+}
+
+/// A slice containing an IEEE 802.1CB redundancy tag of a network package.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RedundancyTagSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> RedundancyTagSlice<'a> {
+    /// Creates a redundancy tag slice from a slice.
+    pub fn from_slice(slice: &'a[u8]) -> Result<RedundancyTagSlice<'a>, ReadError>{
+        // check length
+        use crate::ReadError::*;
+        if slice.len() < RedundancyTag::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(RedundancyTag::SERIALIZED_SIZE));
+        }
+
+        Ok(RedundancyTagSlice {
+            // SAFETY:
+            // Safe as the slice length is checked before to have
+            // at least the length of RedundancyTag::SERIALIZED_SIZE (6)
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    RedundancyTag::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the redundancy tag.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "sequence number" field from the slice.
+    #[inline]
+    pub fn sequence_number(&self) -> u16 {
+        // SAFETY:
+        // Slice len checked in constructor to be at least 6.
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(2))
+        }
+    }
+
+    /// Read the "ether type" field of the following data from the slice.
+    #[inline]
+    pub fn ether_type(&self) -> u16 {
+        // SAFETY:
+        // Slice len checked in constructor to be at least 6.
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(4))
+        }
+    }
+
+    /// Decode all the fields and copy the results to a RedundancyTag struct
+    #[inline]
+    pub fn to_header(&self) -> RedundancyTag {
+        RedundancyTag {
+            sequence_number: self.sequence_number(),
+            ether_type: self.ether_type(),
+        }
+    }
+}
+
+/// Sequence recovery of IEEE 802.1CB (vector recovery algorithm), which
+/// decides based on the sequence numbers of the redundancy tags which frames
+/// are passed on & which frames are discarded as duplicates.
+///
+/// The recovery keeps a history of the last `history_length` sequence numbers.
+/// Frames with a sequence number that was already received are discarded,
+/// as well as frames with a sequence number outside of the history window
+/// ("rogue" frames). The first frame after creation or a [`SequenceRecovery::reset`]
+/// is always passed.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::SequenceRecovery;
+///
+/// let mut recovery = SequenceRecovery::new(32).unwrap();
+/// assert!(recovery.accept(10));
+/// // replica received via the second path
+/// assert!(false == recovery.accept(10));
+/// // out of order frames within the history are passed
+/// assert!(recovery.accept(12));
+/// assert!(recovery.accept(11));
+/// assert!(false == recovery.accept(11));
+///
+/// assert_eq!(3, recovery.passed_packets());
+/// assert_eq!(2, recovery.discarded_packets());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SequenceRecovery {
+    history_length: u16,
+    /// Highest sequence number received (`None` if the next frame should be taken in any case).
+    recovery_sequence_number: Option<u16>,
+    /// Bit n is set if the frame with the sequence number `recovery_sequence_number - n` was received.
+    history: u64,
+    passed_packets: u64,
+    discarded_packets: u64,
+    rogue_packets: u64,
+}
+
+impl SequenceRecovery {
+    /// Maximum supported history length.
+    pub const MAX_HISTORY_LENGTH: u16 = 64;
+
+    /// Creates a sequence recovery with the given history length (returns
+    /// `None` if the history length is 0 or bigger then [`SequenceRecovery::MAX_HISTORY_LENGTH`]).
+    pub fn new(history_length: u16) -> Option<SequenceRecovery> {
+        if 0 == history_length || history_length > SequenceRecovery::MAX_HISTORY_LENGTH {
+            None
+        } else {
+            Some(SequenceRecovery {
+                history_length,
+                recovery_sequence_number: None,
+                history: 0,
+                passed_packets: 0,
+                discarded_packets: 0,
+                rogue_packets: 0,
+            })
+        }
+    }
+
+    /// Length of the sequence number history.
+    #[inline]
+    pub fn history_length(&self) -> u16 {
+        self.history_length
+    }
+
+    /// Highest sequence number that was passed since the last reset.
+    #[inline]
+    pub fn recovery_sequence_number(&self) -> Option<u16> {
+        self.recovery_sequence_number
+    }
+
+    /// Number of frames that were passed.
+    #[inline]
+    pub fn passed_packets(&self) -> u64 {
+        self.passed_packets
+    }
+
+    /// Number of frames that were discarded (duplicates & rogue frames).
+    #[inline]
+    pub fn discarded_packets(&self) -> u64 {
+        self.discarded_packets
+    }
+
+    /// Number of discarded frames with a sequence number outside of the history window.
+    #[inline]
+    pub fn rogue_packets(&self) -> u64 {
+        self.rogue_packets
+    }
+
+    /// Resets the history, so the next frame is passed independent of its
+    /// sequence number (e.g. after the recovery timeout expired). The
+    /// counters are not reset.
+    pub fn reset(&mut self) {
+        self.recovery_sequence_number = None;
+        self.history = 0;
+    }
+
+    /// Processes the sequence number of a received frame and returns true if
+    /// the frame should be passed & false if it should be discarded.
+    pub fn accept(&mut self, sequence_number: u16) -> bool {
+        let recovery_sequence_number = match self.recovery_sequence_number {
+            Some(value) => value,
+            None => {
+                self.recovery_sequence_number = Some(sequence_number);
+                self.history = 1;
+                self.passed_packets += 1;
+                return true;
+            }
+        };
+
+        // distance to the highest sequence number (in the 16 bit sequence number space)
+        let delta = sequence_number.wrapping_sub(recovery_sequence_number) as i16;
+        if delta.unsigned_abs() >= self.history_length {
+            self.rogue_packets += 1;
+            self.discarded_packets += 1;
+            false
+        } else if delta <= 0 {
+            let bit = 1u64 << delta.unsigned_abs();
+            if 0 != self.history & bit {
+                self.discarded_packets += 1;
+                false
+            } else {
+                self.history |= bit;
+                self.passed_packets += 1;
+                true
+            }
+        } else {
+            // delta is smaller then 64 (checked via the history length)
+            self.history = (self.history << delta) | 1;
+            self.recovery_sequence_number = Some(sequence_number);
+            self.passed_packets += 1;
+            true
+        }
+    }
+}
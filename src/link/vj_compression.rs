@@ -0,0 +1,589 @@
+use super::super::*;
+
+/// Bits of the change mask (first byte) of a compressed TCP/IP header.
+mod change_mask {
+    /// Connection (slot) id present.
+    pub const C: u8 = 0x40;
+    /// IP identification delta present (otherwise incremented by 1).
+    pub const I: u8 = 0x20;
+    /// TCP push flag.
+    pub const P: u8 = 0x10;
+    /// Sequence number delta present.
+    pub const S: u8 = 0x08;
+    /// Acknowledgment number delta present.
+    pub const A: u8 = 0x04;
+    /// Window delta present.
+    pub const W: u8 = 0x02;
+    /// Urgent pointer present.
+    pub const U: u8 = 0x01;
+    /// Echoed interactive traffic (sequence & ack number increased by the
+    /// data length of the previous packet).
+    pub const SPECIAL_I: u8 = S | W | U;
+    /// Unidirectional data transfer (sequence number increased by the
+    /// data length of the previous packet).
+    pub const SPECIAL_D: u8 = S | A | W | U;
+    /// Bits used to encode the special cases.
+    pub const SPECIALS_MASK: u8 = S | A | W | U;
+}
+
+/// TCP flags relevant for the compression.
+mod tcp_flag {
+    pub const FIN: u8 = 0x01;
+    pub const SYN: u8 = 0x02;
+    pub const RST: u8 = 0x04;
+    pub const PSH: u8 = 0x08;
+    pub const ACK: u8 = 0x10;
+    pub const URG: u8 = 0x20;
+}
+
+/// Type of a packet produced by a [`VjCompressor`].
+///
+/// On PPP links the type is transmitted via the PPP protocol field (see
+/// [`VjPacketType::ppp_protocol`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum VjPacketType {
+    /// Unmodified ip packet.
+    Ip,
+    /// IPv4/TCP packet with the protocol field of the ipv4 header replaced
+    /// by the slot id of the connection (refreshes the saved header).
+    UncompressedTcp,
+    /// Compressed TCP/IP header followed by the TCP payload.
+    CompressedTcp,
+}
+
+impl VjPacketType {
+    /// PPP protocol identifying the packet type.
+    pub fn ppp_protocol(&self) -> u16 {
+        match self {
+            VjPacketType::Ip => ppp_protocol::IPV4,
+            VjPacketType::UncompressedTcp => ppp_protocol::VJ_UNCOMPRESSED_TCP,
+            VjPacketType::CompressedTcp => ppp_protocol::VJ_COMPRESSED_TCP,
+        }
+    }
+
+    /// Packet type identified by a PPP protocol (`None` if the protocol
+    /// is not used by the compression).
+    pub fn from_ppp_protocol(protocol: u16) -> Option<VjPacketType> {
+        match protocol {
+            ppp_protocol::IPV4 => Some(VjPacketType::Ip),
+            ppp_protocol::VJ_UNCOMPRESSED_TCP => Some(VjPacketType::UncompressedTcp),
+            ppp_protocol::VJ_COMPRESSED_TCP => Some(VjPacketType::CompressedTcp),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur while decompressing a packet with a [`VjDecompressor`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VjError {
+    /// The compressed header is too short. The value is the minimum expected length.
+    UnexpectedEndOfSlice(usize),
+    /// The slot id is bigger then the maximum slot id of the decompressor.
+    InvalidSlotId(u8),
+    /// No header is saved for the slot of a compressed packet (or compressed
+    /// packets are discarded after an error till the next packet with an
+    /// explicit slot id arrives).
+    MissingState,
+    /// The headers of an uncompressed TCP packet could not be decoded as
+    /// ipv4 & tcp header.
+    InvalidHeader,
+    /// The decompressed packet would be longer then the maximum ipv4
+    /// total length. The value is the length of the decompressed packet.
+    PacketTooLarge(usize),
+}
+
+impl Error for VjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for VjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use VjError::*;
+        match self {
+            UnexpectedEndOfSlice(len) => {
+                write!(f, "VjError: Unexpected end of slice. The given slice contained less then minimum required {} bytes.", len)
+            },
+            InvalidSlotId(id) => {
+                write!(f, "VjError: Slot id {} is bigger then the maximum slot id.", id)
+            },
+            MissingState => {
+                write!(f, "VjError: No saved header for the compressed packet.")
+            },
+            InvalidHeader => {
+                write!(f, "VjError: The headers of the uncompressed packet are not valid ipv4 & tcp headers.")
+            },
+            PacketTooLarge(len) => {
+                write!(f, "VjError: The decompressed packet length {} is bigger then the maximum ipv4 total length.", len)
+            },
+        }
+    }
+}
+
+/// Returns the length of the ipv4 header & the combined length of the ipv4
+/// & tcp headers (`None` if the packet does not start with both headers).
+fn tcp_header_lens(packet: &[u8]) -> Option<(usize, usize)> {
+    let ip = Ipv4HeaderSlice::from_slice(packet).ok()?;
+    let ip_len = ip.slice().len();
+    let tcp = TcpHeaderSlice::from_slice(&packet[ip_len..]).ok()?;
+    let len = ip_len + tcp.slice().len();
+    if usize::from(ip.total_len()) < len || packet.len() < usize::from(ip.total_len()) {
+        None
+    } else {
+        Some((ip_len, len))
+    }
+}
+
+/// Appends a delta (one byte for values 1 to 255, otherwise a zero byte
+/// followed by the 16 bit value).
+fn encode_delta(target: &mut Vec<u8>, value: u16) {
+    if 0 == value || 0xff < value {
+        target.push(0);
+        target.extend_from_slice(&value.to_be_bytes());
+    } else {
+        target.push(value as u8);
+    }
+}
+
+fn get_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn get_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Compressor for TCP/IP headers as described in RFC 1144 (Van Jacobson
+/// header compression).
+///
+/// The compressor saves the headers of the last packet of up to 256 TCP
+/// connections (slots) & replaces the 40 or more bytes of ipv4 & tcp headers
+/// of following packets by the changed fields (usually 3 to 5 bytes). The
+/// packets have to be decompressed in order by a [`VjDecompressor`] with the
+/// same number of slots.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketBuilder, VjCompressor, VjDecompressor, VjPacketType};
+///
+/// let packet = |sequence_number: u32, payload: &[u8]| {
+///     let mut result = Vec::new();
+///     PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///         .tcp(1234, 23, sequence_number, 1024)
+///         .ack(1)
+///         .write(&mut result, payload)
+///         .unwrap();
+///     result
+/// };
+///
+/// let mut compressor = VjCompressor::new(15);
+/// let mut decompressor = VjDecompressor::new(15);
+///
+/// // the first packet of a connection is sent uncompressed
+/// let first = packet(100, &[1,2,3,4]);
+/// let (packet_type, data) = compressor.compress(&first);
+/// assert_eq!(VjPacketType::UncompressedTcp, packet_type);
+/// assert_eq!(first, decompressor.decompress(packet_type, &data).unwrap());
+///
+/// // the following packets only contain the changes
+/// let second = packet(104, &[5,6,7,8]);
+/// let (packet_type, data) = compressor.compress(&second);
+/// assert_eq!(VjPacketType::CompressedTcp, packet_type);
+/// assert!(data.len() < 20);
+/// assert_eq!(second, decompressor.decompress(packet_type, &data).unwrap());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct VjCompressor {
+    /// Saved ipv4 & tcp headers per slot (empty if the slot is unused).
+    slots: Vec<Vec<u8>>,
+    /// Slot ids ordered from the most to the least recently used.
+    recently_used: Vec<u8>,
+    /// Slot id of the last sent packet.
+    last_slot: Option<u8>,
+    /// Omit the slot id if it did not change since the last packet.
+    compress_slot_id: bool,
+}
+
+impl VjCompressor {
+    /// Creates a compressor with the slots `0..=max_slot_id` (negotiated via
+    /// the "Max-Slot-Id" of the IPCP compression option on PPP links).
+    pub fn new(max_slot_id: u8) -> VjCompressor {
+        VjCompressor {
+            slots: vec![Vec::new(); usize::from(max_slot_id) + 1],
+            recently_used: (0..=max_slot_id).collect(),
+            last_slot: None,
+            compress_slot_id: true,
+        }
+    }
+
+    /// Sets if the slot id can be omitted when it did not change since the
+    /// last packet ("Comp-Slot-Id" of the IPCP compression option, enabled
+    /// by default).
+    pub fn with_slot_id_compression(mut self, value: bool) -> VjCompressor {
+        self.compress_slot_id = value;
+        self
+    }
+
+    /// Highest slot id used by the compressor.
+    #[inline]
+    pub fn max_slot_id(&self) -> u8 {
+        (self.slots.len() - 1) as u8
+    }
+
+    /// Compresses an ip packet & returns the packet type & the data to send.
+    ///
+    /// Packets that are not IPv4/TCP packets, fragments & packets with the
+    /// SYN, FIN or RST flag set (or without the ACK flag) are returned
+    /// unmodified with the type [`VjPacketType::Ip`]. Bytes after the total
+    /// length of the ipv4 header are not included in compressed packets.
+    pub fn compress(&mut self, packet: &[u8]) -> (VjPacketType, Vec<u8>) {
+        use self::change_mask::*;
+
+        let (ip_len, hlen) = match tcp_header_lens(packet) {
+            Some(lens) if 0 == get_u16(packet, 6) & 0x3fff => lens,
+            _ => return (VjPacketType::Ip, packet.to_vec()),
+        };
+        let tcp = &packet[ip_len..hlen];
+        let flags = tcp[13];
+        if tcp_flag::ACK != flags & (tcp_flag::SYN | tcp_flag::FIN | tcp_flag::RST | tcp_flag::ACK) {
+            return (VjPacketType::Ip, packet.to_vec());
+        }
+        let packet = &packet[..usize::from(get_u16(packet, 2))];
+
+        // search the slot of the connection (or replace the least recently used slot)
+        let position = self.recently_used.iter().position(|&id| {
+            let saved = &self.slots[usize::from(id)];
+            if saved.is_empty() {
+                return false;
+            }
+            let saved_ip_len = usize::from(saved[0] & 0xf)*4;
+            saved[12..20] == packet[12..20] && saved[saved_ip_len..saved_ip_len + 4] == tcp[..4]
+        });
+        let found = position.is_some();
+        let id = self.recently_used.remove(position.unwrap_or(self.recently_used.len() - 1));
+        self.recently_used.insert(0, id);
+
+        let compressed = if found {
+            self.compress_changes(id, packet, ip_len, hlen)
+        } else {
+            None
+        };
+        self.slots[usize::from(id)] = packet[..hlen].to_vec();
+
+        match compressed {
+            Some(header) => {
+                let mut result = Vec::with_capacity(header.len() + 2 + packet.len() - hlen);
+                if self.compress_slot_id && Some(id) == self.last_slot {
+                    result.push(header[0]);
+                } else {
+                    result.push(header[0] | C);
+                    result.push(id);
+                }
+                result.extend_from_slice(&tcp[16..18]);
+                result.extend_from_slice(&header[1..]);
+                result.extend_from_slice(&packet[hlen..]);
+                self.last_slot = Some(id);
+                (VjPacketType::CompressedTcp, result)
+            },
+            None => {
+                let mut result = packet.to_vec();
+                result[9] = id;
+                self.last_slot = Some(id);
+                (VjPacketType::UncompressedTcp, result)
+            }
+        }
+    }
+
+    /// Returns the change mask followed by the encoded deltas to the saved
+    /// header of the slot (`None` if the packet has to be sent uncompressed).
+    fn compress_changes(&self, id: u8, packet: &[u8], ip_len: usize, hlen: usize) -> Option<Vec<u8>> {
+        use self::change_mask::*;
+
+        let saved = &self.slots[usize::from(id)];
+        let tcp = &packet[ip_len..hlen];
+        let saved_tcp = &saved[ip_len.min(saved.len())..];
+
+        // fields that are not transmitted have to stay the same
+        if saved.len() != hlen ||
+            saved[..2] != packet[..2] ||
+            saved[6..10] != packet[6..10] ||
+            saved[20..ip_len] != packet[20..ip_len] ||
+            saved_tcp[20..] != tcp[20..]
+        {
+            return None;
+        }
+
+        let mut changes = 0;
+        let mut deltas = vec![0];
+        let flags = tcp[13];
+        if 0 != flags & tcp_flag::URG {
+            encode_delta(&mut deltas, get_u16(tcp, 18));
+            changes |= U;
+        } else if get_u16(tcp, 18) != get_u16(saved_tcp, 18) {
+            return None;
+        }
+
+        let window = get_u16(tcp, 14).wrapping_sub(get_u16(saved_tcp, 14));
+        if 0 != window {
+            encode_delta(&mut deltas, window);
+            changes |= W;
+        }
+
+        let ack = get_u32(tcp, 8).wrapping_sub(get_u32(saved_tcp, 8));
+        if 0 != ack {
+            if 0xffff < ack {
+                return None;
+            }
+            encode_delta(&mut deltas, ack as u16);
+            changes |= A;
+        }
+
+        let sequence = get_u32(tcp, 4).wrapping_sub(get_u32(saved_tcp, 4));
+        if 0 != sequence {
+            if 0xffff < sequence {
+                return None;
+            }
+            encode_delta(&mut deltas, sequence as u16);
+            changes |= S;
+        }
+
+        let total_len = usize::from(get_u16(packet, 2));
+        let saved_total_len = usize::from(get_u16(saved, 2));
+        let saved_data_len = (saved_total_len - hlen) as u32;
+        match changes {
+            // data following a pure ack is compressed, everything else is
+            // probably a retransmission & sent uncompressed
+            0 if total_len == saved_total_len || saved_total_len != hlen => return None,
+            // the changes would be misinterpreted as a special case
+            SPECIAL_I | SPECIAL_D => return None,
+            _ if S | A == changes && sequence == ack && sequence == saved_data_len => {
+                changes = SPECIAL_I;
+                deltas.truncate(1);
+            },
+            S if sequence == saved_data_len => {
+                changes = SPECIAL_D;
+                deltas.truncate(1);
+            },
+            _ => {},
+        }
+
+        let identification = get_u16(packet, 4).wrapping_sub(get_u16(saved, 4));
+        if 1 != identification {
+            encode_delta(&mut deltas, identification);
+            changes |= I;
+        }
+        if 0 != flags & tcp_flag::PSH {
+            changes |= P;
+        }
+        deltas[0] = changes;
+        Some(deltas)
+    }
+}
+
+/// Decompressor for packets compressed by a [`VjCompressor`] (RFC 1144).
+///
+/// After a transmission error (e.g. a frame with a wrong checksum) the
+/// receiver has to call [`VjDecompressor::toss`], compressed packets are
+/// then discarded till the compressor sends a packet with an explicit slot
+/// id (TCP retransmits lost segments uncompressed).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct VjDecompressor {
+    /// Saved ipv4 & tcp headers per slot (empty if the slot is unused).
+    slots: Vec<Vec<u8>>,
+    /// Slot id of the last received packet.
+    last_slot: Option<u8>,
+    /// Discard compressed packets without slot id.
+    toss: bool,
+}
+
+impl VjDecompressor {
+    /// Creates a decompressor with the slots `0..=max_slot_id`.
+    pub fn new(max_slot_id: u8) -> VjDecompressor {
+        VjDecompressor {
+            slots: vec![Vec::new(); usize::from(max_slot_id) + 1],
+            last_slot: None,
+            toss: false,
+        }
+    }
+
+    /// Highest slot id used by the decompressor.
+    #[inline]
+    pub fn max_slot_id(&self) -> u8 {
+        (self.slots.len() - 1) as u8
+    }
+
+    /// Discards the following compressed packets till a packet with an
+    /// explicit slot id is received (call after a lost or corrupted packet).
+    #[inline]
+    pub fn toss(&mut self) {
+        self.toss = true;
+    }
+
+    /// Returns true if compressed packets without slot id are discarded.
+    #[inline]
+    pub fn is_tossing(&self) -> bool {
+        self.toss
+    }
+
+    /// Decompresses a received packet & returns the original ip packet.
+    ///
+    /// The decompressor discards the following compressed packets without
+    /// slot id if an error is returned for a compressed packet.
+    pub fn decompress(&mut self, packet_type: VjPacketType, data: &[u8]) -> Result<Vec<u8>, VjError> {
+        match packet_type {
+            VjPacketType::Ip => Ok(data.to_vec()),
+            VjPacketType::UncompressedTcp => self.decompress_uncompressed(data),
+            VjPacketType::CompressedTcp => {
+                let result = self.decompress_compressed(data);
+                if result.is_err() {
+                    self.toss = true;
+                }
+                result
+            },
+        }
+    }
+
+    fn decompress_uncompressed(&mut self, data: &[u8]) -> Result<Vec<u8>, VjError> {
+        if data.len() < Ipv4Header::SERIALIZED_SIZE {
+            self.toss = true;
+            return Err(VjError::InvalidHeader);
+        }
+        let id = data[9];
+        if id > self.max_slot_id() {
+            self.toss = true;
+            return Err(VjError::InvalidSlotId(id));
+        }
+        let mut result = data.to_vec();
+        result[9] = ip_number::TCP;
+        let (_, hlen) = match tcp_header_lens(&result) {
+            Some(lens) => lens,
+            None => {
+                self.toss = true;
+                return Err(VjError::InvalidHeader);
+            }
+        };
+        self.slots[usize::from(id)] = result[..hlen].to_vec();
+        self.last_slot = Some(id);
+        self.toss = false;
+        Ok(result)
+    }
+
+    fn decompress_compressed(&mut self, data: &[u8]) -> Result<Vec<u8>, VjError> {
+        use self::change_mask::*;
+
+        let mut reader = DeltaReader{ data, offset: 0 };
+        let changes = reader.read_u8()?;
+        if 0 != changes & C {
+            let id = reader.read_u8()?;
+            if id > self.max_slot_id() {
+                return Err(VjError::InvalidSlotId(id));
+            }
+            self.last_slot = Some(id);
+            self.toss = false;
+        } else if self.toss {
+            return Err(VjError::MissingState);
+        }
+        let id = usize::from(self.last_slot.ok_or(VjError::MissingState)?);
+        if self.slots[id].is_empty() {
+            return Err(VjError::MissingState);
+        }
+
+        let mut header = self.slots[id].clone();
+        let hlen = header.len();
+        let ip_len = usize::from(header[0] & 0xf)*4;
+        let checksum = [reader.read_u8()?, reader.read_u8()?];
+        let saved_data_len = u32::from(get_u16(&header, 2)) - hlen as u32;
+
+        let tcp = &mut header[ip_len..];
+        tcp[16..18].copy_from_slice(&checksum);
+        if 0 != changes & P {
+            tcp[13] |= tcp_flag::PSH;
+        } else {
+            tcp[13] &= !tcp_flag::PSH;
+        }
+        let mut sequence = get_u32(tcp, 4);
+        let mut ack = get_u32(tcp, 8);
+        match changes & SPECIALS_MASK {
+            SPECIAL_I => {
+                sequence = sequence.wrapping_add(saved_data_len);
+                ack = ack.wrapping_add(saved_data_len);
+            },
+            SPECIAL_D => {
+                sequence = sequence.wrapping_add(saved_data_len);
+            },
+            _ => {
+                if 0 != changes & U {
+                    tcp[13] |= tcp_flag::URG;
+                    let urgent_pointer = reader.read_delta()?;
+                    tcp[18..20].copy_from_slice(&urgent_pointer.to_be_bytes());
+                } else {
+                    tcp[13] &= !tcp_flag::URG;
+                }
+                if 0 != changes & W {
+                    let window = get_u16(tcp, 14).wrapping_add(reader.read_delta()?);
+                    tcp[14..16].copy_from_slice(&window.to_be_bytes());
+                }
+                if 0 != changes & A {
+                    ack = ack.wrapping_add(reader.read_delta()?.into());
+                }
+                if 0 != changes & S {
+                    sequence = sequence.wrapping_add(reader.read_delta()?.into());
+                }
+            },
+        }
+        tcp[4..8].copy_from_slice(&sequence.to_be_bytes());
+        tcp[8..12].copy_from_slice(&ack.to_be_bytes());
+
+        let identification_delta = if 0 != changes & I {
+            reader.read_delta()?
+        } else {
+            1
+        };
+        let identification = get_u16(&header, 4).wrapping_add(identification_delta);
+        header[4..6].copy_from_slice(&identification.to_be_bytes());
+
+        // update the ipv4 total length & checksum
+        let payload = &data[reader.offset..];
+        let total_len = hlen + payload.len();
+        if usize::from(u16::MAX) < total_len {
+            return Err(VjError::PacketTooLarge(total_len));
+        }
+        header[2..4].copy_from_slice(&(total_len as u16).to_be_bytes());
+        header[10..12].copy_from_slice(&[0, 0]);
+        let ip_checksum = checksum::Sum16BitWords::new()
+            .add_slice(&header[..ip_len])
+            .ones_complement()
+            .to_be();
+        header[10..12].copy_from_slice(&ip_checksum.to_be_bytes());
+
+        let mut result = Vec::with_capacity(total_len);
+        result.extend_from_slice(&header);
+        result.extend_from_slice(payload);
+        self.slots[id] = header;
+        Ok(result)
+    }
+}
+
+/// Reads the fields of a compressed header.
+struct DeltaReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DeltaReader<'a> {
+    fn read_u8(&mut self) -> Result<u8, VjError> {
+        let value = *self.data.get(self.offset).ok_or(VjError::UnexpectedEndOfSlice(self.offset + 1))?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    /// reads a delta encoded as one byte or a zero byte followed by a 16 bit value
+    fn read_delta(&mut self) -> Result<u16, VjError> {
+        match self.read_u8()? {
+            0 => Ok(u16::from_be_bytes([self.read_u8()?, self.read_u8()?])),
+            value => Ok(value.into()),
+        }
+    }
+}
@@ -4,7 +4,8 @@ use std::io;
 use std::slice::from_raw_parts;
 
 /// IEEE 802.1Q VLAN Tagging Header (can be single or double tagged).
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum VlanHeader {
     /// IEEE 802.1Q VLAN Tagging Header
     Single(SingleVlanHeader),
@@ -42,7 +43,7 @@ impl VlanHeader {
 }
 
 /// A slice containing a single or double vlan header.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum VlanSlice<'a> {
     SingleVlan(SingleVlanHeaderSlice<'a>),
     DoubleVlan(DoubleVlanHeaderSlice<'a>),
@@ -62,7 +63,8 @@ impl<'a> VlanSlice<'a> {
 }
 
 /// IEEE 802.1Q VLAN Tagging Header
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct SingleVlanHeader {
     /// A 3 bit number which refers to the IEEE 802.1p class of service and maps to the frame priority level.
     pub priority_code_point: u8,
@@ -81,6 +83,34 @@ impl SerializedSize for SingleVlanHeader {
 
 impl SingleVlanHeader {
 
+    /// Constructs a SingleVlanHeader & checks that the `priority_code_point` fits
+    /// into 3 bits & the `vlan_identifier` fits into 12 bits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{SingleVlanHeader, ether_type, ValueError, ErrorField};
+    ///
+    /// let header = SingleVlanHeader::new(7, false, 0xfff, ether_type::IPV4).unwrap();
+    /// assert_eq!(0xfff, header.vlan_identifier);
+    ///
+    /// assert_eq!(
+    ///     Err(ValueError::U16TooLarge{ value: 0x1000, max: 0xfff, field: ErrorField::VlanTagVlanId }),
+    ///     SingleVlanHeader::new(7, false, 0x1000, ether_type::IPV4)
+    /// );
+    /// ```
+    pub fn new(priority_code_point: u8, drop_eligible_indicator: bool, vlan_identifier: u16, ether_type: u16) -> Result<SingleVlanHeader, ValueError> {
+        use crate::ErrorField::*;
+        max_check_u8(priority_code_point, 0x7, VlanTagPriorityCodePoint)?;
+        max_check_u16(vlan_identifier, 0xfff, VlanTagVlanId)?;
+        Ok(SingleVlanHeader {
+            priority_code_point,
+            drop_eligible_indicator,
+            vlan_identifier,
+            ether_type,
+        })
+    }
+
     /// Read an SingleVlanHeader from a slice and return the header & unused parts of the slice.
     #[deprecated(
         since = "0.10.1",
@@ -174,10 +204,62 @@ impl SingleVlanHeader {
             ]
         )
     }
+
+    /// Sets the priority code point (the range is checked when the header
+    /// gets serialized).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{SingleVlanHeader, ether_type};
+    ///
+    /// let header = SingleVlanHeader::default()
+    ///     .with_priority_code_point(5)
+    ///     .with_drop_eligible_indicator(true)
+    ///     .with_vlan_id(100)
+    ///     .with_ether_type(ether_type::IPV6);
+    /// assert_eq!(
+    ///     SingleVlanHeader::new(5, true, 100, ether_type::IPV6).unwrap(),
+    ///     header
+    /// );
+    /// ```
+    #[inline]
+    pub fn with_priority_code_point(mut self, priority_code_point: u8) -> SingleVlanHeader {
+        self.priority_code_point = priority_code_point;
+        self
+    }
+
+    /// Sets the drop eligible indicator.
+    #[inline]
+    pub fn with_drop_eligible_indicator(mut self, drop_eligible_indicator: bool) -> SingleVlanHeader {
+        self.drop_eligible_indicator = drop_eligible_indicator;
+        self
+    }
+
+    /// Sets the vlan identifier (the range is checked when the header gets
+    /// serialized).
+    #[inline]
+    pub fn with_vlan_id(mut self, vlan_identifier: u16) -> SingleVlanHeader {
+        self.vlan_identifier = vlan_identifier;
+        self
+    }
+
+    /// Sets the ether type of the content after the header.
+    #[inline]
+    pub fn with_ether_type(mut self, ether_type: u16) -> SingleVlanHeader {
+        self.ether_type = ether_type;
+        self
+    }
 }
 
 /// IEEE 802.1Q double VLAN Tagging Header
-#[derive(Clone, Debug, Eq, PartialEq)]
+///
+/// All vlan ether types (see [`VlanHeader::VLAN_ETHER_TYPES`]) are accepted
+/// for both tags. Frames where the outer tag is identified by 0x8100
+/// instead of 0x88A8 (a common misconfiguration) are therefore also decoded
+/// as double tagged frames.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct DoubleVlanHeader {
     /// The outer vlan tagging header
     pub outer: SingleVlanHeader,
@@ -262,6 +344,49 @@ impl DoubleVlanHeader {
             ]
         )
     }
+
+    /// Sets the outer vlan header.
+    #[inline]
+    pub fn with_outer(mut self, outer: SingleVlanHeader) -> DoubleVlanHeader {
+        self.outer = outer;
+        self
+    }
+
+    /// Sets the inner vlan header.
+    #[inline]
+    pub fn with_inner(mut self, inner: SingleVlanHeader) -> DoubleVlanHeader {
+        self.inner = inner;
+        self
+    }
+
+    /// Sets the vlan identifier of the outer header (the range is checked
+    /// when the header gets serialized).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{DoubleVlanHeader, ether_type};
+    ///
+    /// let header = DoubleVlanHeader::default()
+    ///     .with_outer_vlan_id(10)
+    ///     .with_inner_vlan_id(20);
+    /// assert_eq!(10, header.outer.vlan_identifier);
+    /// assert_eq!(20, header.inner.vlan_identifier);
+    /// assert_eq!(ether_type::VLAN_TAGGED_FRAME, header.outer.ether_type);
+    /// ```
+    #[inline]
+    pub fn with_outer_vlan_id(mut self, vlan_identifier: u16) -> DoubleVlanHeader {
+        self.outer.vlan_identifier = vlan_identifier;
+        self
+    }
+
+    /// Sets the vlan identifier of the inner header (the range is checked
+    /// when the header gets serialized).
+    #[inline]
+    pub fn with_inner_vlan_id(mut self, vlan_identifier: u16) -> DoubleVlanHeader {
+        self.inner.vlan_identifier = vlan_identifier;
+        self
+    }
 }
 
 impl Default for DoubleVlanHeader {
@@ -279,7 +404,7 @@ impl Default for DoubleVlanHeader {
 }
 
 ///A slice containing a single vlan header of a network package.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct SingleVlanHeaderSlice<'a> {
     slice: &'a [u8]
 }
@@ -372,7 +497,7 @@ impl<'a> SingleVlanHeaderSlice<'a> {
 }
 
 /// A slice containing an double vlan header of a network package.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct DoubleVlanHeaderSlice<'a> {
     slice: &'a [u8]
 }
@@ -0,0 +1,373 @@
+//! Name tables for ether types, ip numbers, well known ports & ICMP messages
+//! (only available with the `names` feature).
+//!
+//! The names are based on the IANA registries ("IEEE 802 Numbers",
+//! "Assigned Internet Protocol Numbers", "Service Name and Transport Protocol
+//! Port Number Registry", "ICMP Type Numbers" & "ICMPv6 Parameters"). For the
+//! ports only a selection of commonly seen services is included. The ICMP
+//! names use the lowercase & hyphenated style known from tools like tcpdump
+//! or nftables (e.g. `time-exceeded`).
+//!
+//! All tables are sorted by their numeric value and can also be iterated
+//! directly. If the `names` feature is enabled the names are also used by
+//! [`crate::PacketSummary`].
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::names;
+//!
+//! assert_eq!(Some("IPv6"), names::ether_type(0x86dd));
+//! assert_eq!(Some("UDP"), names::ip_number(17));
+//! assert_eq!(Some("https"), names::tcp_port(443));
+//! assert_eq!(Some("time-exceeded"), names::icmpv4_type(11));
+//! assert_eq!(Some("ttl-exceeded"), names::icmpv4_code(11, 0));
+//! assert_eq!(None, names::tcp_port(51234));
+//! ```
+
+/// Names of ether types (sorted by the ether type).
+pub const ETHER_TYPES: &[(u16, &str)] = &[
+    (0x0800, "IPv4"),
+    (0x0806, "ARP"),
+    (0x0842, "Wake-on-LAN"),
+    (0x22f0, "AVTP"),
+    (0x22f3, "TRILL"),
+    (0x6558, "Transparent-Ethernet-Bridging"),
+    (0x8035, "RARP"),
+    (0x809b, "AppleTalk"),
+    (0x80f3, "AARP"),
+    (0x8100, "802.1Q"),
+    (0x8137, "IPX"),
+    (0x86dd, "IPv6"),
+    (0x8808, "MAC-Control"),
+    (0x8809, "Slow-Protocols"),
+    (0x8847, "MPLS"),
+    (0x8848, "MPLS-Multicast"),
+    (0x8863, "PPPoE-Discovery"),
+    (0x8864, "PPPoE-Session"),
+    (0x886d, "802.11-Mgmt"),
+    (0x888e, "EAPOL"),
+    (0x8892, "PROFINET"),
+    (0x88a8, "802.1ad"),
+    (0x88cc, "LLDP"),
+    (0x88e5, "MACsec"),
+    (0x88e7, "PBB"),
+    (0x88f7, "PTP"),
+    (0x88fb, "PRP"),
+    (0x8902, "CFM"),
+    (0x8906, "FCoE"),
+    (0x8914, "FIP"),
+    (0x8915, "RoCE"),
+    (0x892f, "HSR"),
+    (0x894f, "NSH"),
+    (0x9000, "Loopback"),
+    (0x9100, "802.1Q-Double-Tagged"),
+    (0xf1c1, "R-TAG"),
+];
+
+/// Keywords of the IANA "Assigned Internet Protocol Numbers" registry
+/// (sorted by the ip number).
+pub const IP_NUMBERS: &[(u8, &str)] = &[
+    (0, "HOPOPT"),
+    (1, "ICMP"),
+    (2, "IGMP"),
+    (3, "GGP"),
+    (4, "IPv4"),
+    (5, "ST"),
+    (6, "TCP"),
+    (8, "EGP"),
+    (9, "IGP"),
+    (17, "UDP"),
+    (27, "RDP"),
+    (33, "DCCP"),
+    (41, "IPv6"),
+    (43, "IPv6-Route"),
+    (44, "IPv6-Frag"),
+    (46, "RSVP"),
+    (47, "GRE"),
+    (50, "ESP"),
+    (51, "AH"),
+    (58, "IPv6-ICMP"),
+    (59, "IPv6-NoNxt"),
+    (60, "IPv6-Opts"),
+    (88, "EIGRP"),
+    (89, "OSPFIGP"),
+    (94, "IPIP"),
+    (97, "ETHERIP"),
+    (98, "ENCAP"),
+    (103, "PIM"),
+    (108, "IPComp"),
+    (112, "VRRP"),
+    (115, "L2TP"),
+    (124, "ISIS"),
+    (132, "SCTP"),
+    (135, "Mobility-Header"),
+    (136, "UDPLite"),
+    (137, "MPLS-in-IP"),
+    (139, "HIP"),
+    (140, "Shim6"),
+    (141, "WESP"),
+    (142, "ROHC"),
+    (143, "Ethernet"),
+];
+
+/// Service names of well known TCP ports (sorted by the port).
+pub const TCP_PORTS: &[(u16, &str)] = &[
+    (20, "ftp-data"),
+    (21, "ftp"),
+    (22, "ssh"),
+    (23, "telnet"),
+    (25, "smtp"),
+    (43, "whois"),
+    (53, "domain"),
+    (70, "gopher"),
+    (79, "finger"),
+    (80, "http"),
+    (88, "kerberos"),
+    (110, "pop3"),
+    (111, "sunrpc"),
+    (113, "ident"),
+    (119, "nntp"),
+    (135, "epmap"),
+    (139, "netbios-ssn"),
+    (143, "imap"),
+    (179, "bgp"),
+    (389, "ldap"),
+    (443, "https"),
+    (445, "microsoft-ds"),
+    (465, "submissions"),
+    (513, "login"),
+    (514, "shell"),
+    (515, "printer"),
+    (543, "klogin"),
+    (544, "kshell"),
+    (548, "afpovertcp"),
+    (554, "rtsp"),
+    (587, "submission"),
+    (631, "ipp"),
+    (636, "ldaps"),
+    (646, "ldp"),
+    (853, "domain-s"),
+    (873, "rsync"),
+    (989, "ftps-data"),
+    (990, "ftps"),
+    (992, "telnets"),
+    (993, "imaps"),
+    (995, "pop3s"),
+    (1433, "ms-sql-s"),
+    (1723, "pptp"),
+    (1883, "mqtt"),
+    (2049, "nfs"),
+    (3260, "iscsi-target"),
+    (3306, "mysql"),
+    (3389, "ms-wbt-server"),
+    (5060, "sip"),
+    (5061, "sips"),
+    (5222, "xmpp-client"),
+    (5269, "xmpp-server"),
+    (5432, "postgresql"),
+    (5672, "amqp"),
+    (5900, "rfb"),
+    (6379, "redis"),
+    (8080, "http-alt"),
+    (8883, "secure-mqtt"),
+];
+
+/// Service names of well known UDP ports (sorted by the port).
+pub const UDP_PORTS: &[(u16, &str)] = &[
+    (7, "echo"),
+    (53, "domain"),
+    (67, "bootps"),
+    (68, "bootpc"),
+    (69, "tftp"),
+    (88, "kerberos"),
+    (111, "sunrpc"),
+    (123, "ntp"),
+    (137, "netbios-ns"),
+    (138, "netbios-dgm"),
+    (161, "snmp"),
+    (162, "snmptrap"),
+    (443, "https"),
+    (500, "isakmp"),
+    (514, "syslog"),
+    (520, "router"),
+    (521, "ripng"),
+    (546, "dhcpv6-client"),
+    (547, "dhcpv6-server"),
+    (623, "asf-rmcp"),
+    (646, "ldp"),
+    (853, "domain-s"),
+    (862, "twamp-control"),
+    (1701, "l2tp"),
+    (1812, "radius"),
+    (1813, "radius-acct"),
+    (1900, "ssdp"),
+    (2049, "nfs"),
+    (2123, "gtp-control"),
+    (2152, "gtp-user"),
+    (3478, "stun"),
+    (3784, "bfd-control"),
+    (3785, "bfd-echo"),
+    (4500, "ipsec-nat-t"),
+    (4789, "vxlan"),
+    (5060, "sip"),
+    (5353, "mdns"),
+    (5355, "llmnr"),
+    (6081, "geneve"),
+    (6343, "sflow"),
+];
+
+/// Names of ICMPv4 types (sorted by the type).
+pub const ICMPV4_TYPES: &[(u8, &str)] = &[
+    (0, "echo-reply"),
+    (3, "destination-unreachable"),
+    (4, "source-quench"),
+    (5, "redirect"),
+    (6, "alternate-host-address"),
+    (8, "echo-request"),
+    (9, "router-advertisement"),
+    (10, "router-solicitation"),
+    (11, "time-exceeded"),
+    (12, "parameter-problem"),
+    (13, "timestamp-request"),
+    (14, "timestamp-reply"),
+    (15, "info-request"),
+    (16, "info-reply"),
+    (17, "address-mask-request"),
+    (18, "address-mask-reply"),
+    (30, "traceroute"),
+    (42, "extended-echo-request"),
+    (43, "extended-echo-reply"),
+];
+
+/// Names of ICMPv4 codes (sorted by the type & code).
+pub const ICMPV4_CODES: &[((u8, u8), &str)] = &[
+    ((3, 0), "net-unreachable"),
+    ((3, 1), "host-unreachable"),
+    ((3, 2), "protocol-unreachable"),
+    ((3, 3), "port-unreachable"),
+    ((3, 4), "fragmentation-needed"),
+    ((3, 5), "source-route-failed"),
+    ((3, 6), "net-unknown"),
+    ((3, 7), "host-unknown"),
+    ((3, 8), "host-isolated"),
+    ((3, 9), "net-prohibited"),
+    ((3, 10), "host-prohibited"),
+    ((3, 11), "net-tos-unreachable"),
+    ((3, 12), "host-tos-unreachable"),
+    ((3, 13), "admin-prohibited"),
+    ((3, 14), "host-precedence-violation"),
+    ((3, 15), "precedence-cutoff"),
+    ((5, 0), "redirect-net"),
+    ((5, 1), "redirect-host"),
+    ((5, 2), "redirect-tos-net"),
+    ((5, 3), "redirect-tos-host"),
+    ((11, 0), "ttl-exceeded"),
+    ((11, 1), "reassembly-time-exceeded"),
+    ((12, 0), "pointer-indicates-error"),
+    ((12, 1), "missing-required-option"),
+    ((12, 2), "bad-length"),
+];
+
+/// Names of ICMPv6 types (sorted by the type).
+pub const ICMPV6_TYPES: &[(u8, &str)] = &[
+    (1, "destination-unreachable"),
+    (2, "packet-too-big"),
+    (3, "time-exceeded"),
+    (4, "parameter-problem"),
+    (128, "echo-request"),
+    (129, "echo-reply"),
+    (130, "mld-listener-query"),
+    (131, "mld-listener-report"),
+    (132, "mld-listener-done"),
+    (133, "nd-router-solicit"),
+    (134, "nd-router-advert"),
+    (135, "nd-neighbor-solicit"),
+    (136, "nd-neighbor-advert"),
+    (137, "nd-redirect"),
+    (138, "router-renumbering"),
+    (141, "ind-neighbor-solicit"),
+    (142, "ind-neighbor-advert"),
+    (143, "mld2-listener-report"),
+    (160, "extended-echo-request"),
+    (161, "extended-echo-reply"),
+];
+
+/// Names of ICMPv6 codes (sorted by the type & code).
+pub const ICMPV6_CODES: &[((u8, u8), &str)] = &[
+    ((1, 0), "no-route"),
+    ((1, 1), "admin-prohibited"),
+    ((1, 2), "beyond-scope"),
+    ((1, 3), "address-unreachable"),
+    ((1, 4), "port-unreachable"),
+    ((1, 5), "policy-fail"),
+    ((1, 6), "reject-route"),
+    ((3, 0), "hop-limit-exceeded"),
+    ((3, 1), "reassembly-time-exceeded"),
+    ((4, 0), "erroneous-header-field"),
+    ((4, 1), "unrecognized-next-header"),
+    ((4, 2), "unrecognized-option"),
+    ((4, 3), "incomplete-header-chain"),
+    ((4, 4), "sr-upper-layer-header-error"),
+    ((4, 5), "unrecognized-next-header-by-intermediate-node"),
+    ((4, 6), "extension-header-too-big"),
+    ((4, 7), "extension-header-chain-too-long"),
+    ((4, 8), "too-many-extension-headers"),
+    ((4, 9), "too-many-options"),
+    ((4, 10), "option-too-big"),
+];
+
+/// Looks up a value in one of the sorted tables.
+#[inline]
+fn lookup<T: Ord + Copy>(table: &[(T, &'static str)], value: T) -> Option<&'static str> {
+    table
+        .binary_search_by_key(&value, |(key, _)| *key)
+        .ok()
+        .map(|index| table[index].1)
+}
+
+/// Returns the name of an ether type (e.g. `"IPv6"` for `0x86dd`).
+pub fn ether_type(value: u16) -> Option<&'static str> {
+    lookup(ETHER_TYPES, value)
+}
+
+/// Returns the IANA keyword of an ip number (e.g. `"GRE"` for `47`).
+pub fn ip_number(value: u8) -> Option<&'static str> {
+    lookup(IP_NUMBERS, value)
+}
+
+/// Returns the service name of a well known TCP port (e.g. `"https"` for `443`).
+pub fn tcp_port(port: u16) -> Option<&'static str> {
+    lookup(TCP_PORTS, port)
+}
+
+/// Returns the service name of a well known UDP port (e.g. `"domain"` for `53`).
+pub fn udp_port(port: u16) -> Option<&'static str> {
+    lookup(UDP_PORTS, port)
+}
+
+/// Returns the name of an ICMPv4 type (e.g. `"time-exceeded"` for `11`).
+pub fn icmpv4_type(type_u8: u8) -> Option<&'static str> {
+    lookup(ICMPV4_TYPES, type_u8)
+}
+
+/// Returns the name of an ICMPv4 code (e.g. `"port-unreachable"` for type `3`
+/// & code `3`).
+///
+/// Types that have only a single code (e.g. echo requests) have no code names.
+pub fn icmpv4_code(type_u8: u8, code_u8: u8) -> Option<&'static str> {
+    lookup(ICMPV4_CODES, (type_u8, code_u8))
+}
+
+/// Returns the name of an ICMPv6 type (e.g. `"packet-too-big"` for `2`).
+pub fn icmpv6_type(type_u8: u8) -> Option<&'static str> {
+    lookup(ICMPV6_TYPES, type_u8)
+}
+
+/// Returns the name of an ICMPv6 code (e.g. `"hop-limit-exceeded"` for type
+/// `3` & code `0`).
+///
+/// Types that have only a single code (e.g. echo requests) have no code names.
+pub fn icmpv6_code(type_u8: u8, code_u8: u8) -> Option<&'static str> {
+    lookup(ICMPV6_CODES, (type_u8, code_u8))
+}
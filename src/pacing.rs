@@ -0,0 +1,221 @@
+use super::*;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// Token bucket limiting the number of bytes emitted per second.
+///
+/// The bucket starts full. Every emitted packet consumes tokens equal
+/// to its length, the tokens refill with the configured rate up to the
+/// burst size. Packets bigger then the burst size are emitted as soon as
+/// the bucket is full (the bucket then goes into debt).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TokenBucket {
+    bytes_per_second: u64,
+    burst_bytes: u64,
+    /// Available tokens in byte nanoseconds (can be negative after big packets).
+    tokens: i128,
+    /// Offset at which the tokens were last updated.
+    last_update: Duration,
+}
+
+impl TokenBucket {
+    /// Creates a full token bucket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_second` is 0.
+    pub fn new(bytes_per_second: u64, burst_bytes: u64) -> TokenBucket {
+        assert!(0 != bytes_per_second, "TokenBucket: the rate must not be zero");
+        TokenBucket {
+            bytes_per_second,
+            burst_bytes,
+            tokens: i128::from(burst_bytes)*(NANOS_PER_SECOND as i128),
+            last_update: Duration::ZERO,
+        }
+    }
+
+    /// Configured rate in bytes per second.
+    #[inline]
+    pub fn bytes_per_second(&self) -> u64 {
+        self.bytes_per_second
+    }
+
+    /// Configured maximum number of bytes that can be emitted at once.
+    #[inline]
+    pub fn burst_bytes(&self) -> u64 {
+        self.burst_bytes
+    }
+
+    /// Consumes the tokens for a packet with the given length & returns the
+    /// offset at which the packet can be emitted.
+    ///
+    /// `earliest` is the offset at which the packet is ready to be emitted.
+    /// Offsets are durations from an arbitrary starting point (the same for
+    /// all calls) and earlier offsets then in previous calls are treated as
+    /// the offset of the previous call.
+    pub fn send_offset(&mut self, earliest: Duration, len: usize) -> Duration {
+        let rate = i128::from(self.bytes_per_second);
+        let capacity = i128::from(self.burst_bytes)*(NANOS_PER_SECOND as i128);
+
+        // refill the bucket
+        let now = earliest.max(self.last_update);
+        let elapsed = (now - self.last_update).as_nanos() as i128;
+        self.tokens = (self.tokens + elapsed*rate).min(capacity);
+        self.last_update = now;
+
+        // wait till enough tokens are available (or the bucket is full)
+        let required = (len as i128*(NANOS_PER_SECOND as i128)).min(capacity);
+        let mut offset = now;
+        if self.tokens < required {
+            let wait_nanos = (required - self.tokens + rate - 1)/rate;
+            offset += Duration::from_nanos(wait_nanos as u64);
+            self.tokens = (self.tokens + wait_nanos*rate).min(capacity);
+            self.last_update = offset;
+        }
+        self.tokens -= len as i128*(NANOS_PER_SECOND as i128);
+        offset
+    }
+}
+
+/// Timing used by a [`Pacer`] to emit packets.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PacingMode {
+    /// Limits the rate of the emitted packets (based on
+    /// [`PacketMeta::original_len`]).
+    Rate(TokenBucket),
+    /// Emits the packets with the inter-arrival times recorded in
+    /// [`PacketMeta::timestamp`] (divided by the speed factor, e.g. 2.0
+    /// replays twice as fast as recorded).
+    Recorded {
+        /// Factor by which the replay is faster then the recording.
+        speed: f64,
+    },
+}
+
+/// Iterator wrapper that yields packets according to a configured rate
+/// or the recorded inter-arrival times.
+///
+/// The pacer sleeps the current thread in [`Iterator::next`] till the
+/// emission time of the next packet is reached. Packets that are already
+/// late (e.g. because the consumer was slow) are yielded immediately.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+/// use std::time::{Duration, Instant};
+///
+/// let packets = (0..3u64).map(|i| PacketWithMeta::new(
+///     PacketMeta::new(60, 60).with_timestamp(Duration::from_millis(1000 + i)),
+///     vec![0u8; 60],
+/// ));
+///
+/// // replay with the recorded timing (packets 1ms apart)
+/// let start = Instant::now();
+/// assert_eq!(3, Pacer::with_recorded_timing(packets, 1.0).count());
+/// assert!(start.elapsed() >= Duration::from_millis(2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pacer<I> {
+    packets: I,
+    mode: PacingMode,
+    /// Time at which the first packet was emitted.
+    start: Option<Instant>,
+    /// Offset of the last scheduled packet relative to the first packet.
+    offset: Duration,
+    /// Timestamp of the first packet with a timestamp (recorded timing).
+    first_timestamp: Option<Duration>,
+}
+
+impl<I> Pacer<I> {
+    /// Creates a pacer limiting the emitted bytes with a token bucket.
+    pub fn with_rate(packets: I, bucket: TokenBucket) -> Pacer<I> {
+        Pacer::new(packets, PacingMode::Rate(bucket))
+    }
+
+    /// Creates a pacer emitting the packets with the recorded inter-arrival
+    /// times divided by `speed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `speed` is not a positive finite number.
+    pub fn with_recorded_timing(packets: I, speed: f64) -> Pacer<I> {
+        Pacer::new(packets, PacingMode::Recorded{ speed })
+    }
+
+    /// Creates a pacer with the given mode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the speed of [`PacingMode::Recorded`] is not a positive
+    /// finite number.
+    pub fn new(packets: I, mode: PacingMode) -> Pacer<I> {
+        if let PacingMode::Recorded{ speed } = mode {
+            assert!(speed.is_finite() && speed > 0.0, "Pacer: the speed must be a positive finite number");
+        }
+        Pacer {
+            packets,
+            mode,
+            start: None,
+            offset: Duration::ZERO,
+            first_timestamp: None,
+        }
+    }
+
+    /// Mode used to pace the packets.
+    #[inline]
+    pub fn mode(&self) -> &PacingMode {
+        &self.mode
+    }
+
+    /// Returns the wrapped iterator.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.packets
+    }
+
+    /// Schedules the next packet & returns its emission offset relative to
+    /// the first packet.
+    ///
+    /// `now` is the current offset relative to the first packet & allows
+    /// the token bucket to credit idle times. Recorded timings ignore `now`
+    /// (late packets are not moved, the following packets keep the recorded
+    /// timing). Packets without timestamp or with a timestamp before the
+    /// previous packet are scheduled together with the previous packet.
+    pub fn schedule(&mut self, meta: &PacketMeta, now: Duration) -> Duration {
+        let offset = match &mut self.mode {
+            PacingMode::Rate(bucket) => bucket.send_offset(now.max(self.offset), meta.original_len),
+            PacingMode::Recorded{ speed } => match meta.timestamp {
+                Some(timestamp) => {
+                    let first = *self.first_timestamp.get_or_insert(timestamp);
+                    timestamp.saturating_sub(first).div_f64(*speed)
+                },
+                None => self.offset,
+            },
+        };
+        self.offset = offset.max(self.offset);
+        self.offset
+    }
+}
+
+impl<T, I: Iterator<Item = PacketWithMeta<T>>> Iterator for Pacer<I> {
+    type Item = PacketWithMeta<T>;
+
+    fn next(&mut self) -> Option<PacketWithMeta<T>> {
+        let packet = self.packets.next()?;
+        let start = *self.start.get_or_insert_with(Instant::now);
+        let offset = self.schedule(&packet.meta, start.elapsed());
+        let elapsed = start.elapsed();
+        if elapsed < offset {
+            thread::sleep(offset - elapsed);
+        }
+        Some(packet)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.packets.size_hint()
+    }
+}
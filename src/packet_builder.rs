@@ -1,6 +1,7 @@
 use super::*;
 
 use std::{io, marker};
+use arrayvec::ArrayVec;
 
 /// Helper for building packets.
 ///
@@ -38,6 +39,32 @@ use std::{io, marker};
 /// println!("{:?}", result);
 /// ```
 ///
+/// Packets can also be written without any heap allocations into a fixed
+/// size buffer (e.g. on embedded targets):
+///
+/// ```
+/// use etherparse::PacketBuilder;
+///
+/// let payload = [1,2,3,4,5,6,7,8];
+///
+/// // into a slice
+/// let mut buffer = [0u8;128];
+/// let rest_len = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .write_to_slice(&mut buffer, &payload)
+///     .unwrap()
+///     .len();
+/// let packet = &buffer[..buffer.len() - rest_len];
+/// assert_eq!(20 + 8 + 8, packet.len());
+///
+/// // into an array vec with a fixed capacity
+/// let packet = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .to_array_vec::<128>(&payload)
+///     .unwrap();
+/// assert_eq!(20 + 8 + 8, packet.len());
+/// ```
+///
 /// # Options
 ///
 /// * Starting Options:
@@ -45,6 +72,8 @@ use std::{io, marker};
 ///     * [`PacketBuilder::ip`]
 ///     * [`PacketBuilder::ipv4`]
 ///     * [`PacketBuilder::ipv6`]
+/// * Options available after every step:
+///     * [`PacketBuilderStep::mtu`]
 /// * Options after an Ethernet2 header was added:
 ///     * [`PacketBuilderStep<Ethernet2Header>::vlan`]
 ///     * [`PacketBuilderStep<Ethernet2Header>::single_vlan`]
@@ -58,18 +87,24 @@ use std::{io, marker};
 ///     * [`PacketBuilderStep<VlanHeader>::ipv6`]
 /// * Options after an IP header was added:
 ///     * [`PacketBuilderStep<IpHeader>::write`]
+///     * [`PacketBuilderStep<IpHeader>::write_to_slice`]
+///     * [`PacketBuilderStep<IpHeader>::to_array_vec`]
 ///     * [`PacketBuilderStep<IpHeader>::tcp`]
 ///     * [`PacketBuilderStep<IpHeader>::udp`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv4`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv4_raw`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv4_echo_request`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv4_echo_reply`]
+///     * [`PacketBuilderStep<IpHeader>::icmpv4_fragmentation_needed`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv6`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv6_raw`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv6_echo_request`]
 ///     * [`PacketBuilderStep<IpHeader>::icmpv6_echo_reply`]
+///     * [`PacketBuilderStep<IpHeader>::icmpv6_packet_too_big`]
 /// * Options after an TCP header was added:
 ///     * [`PacketBuilderStep<TcpHeader>::write`]
+///     * [`PacketBuilderStep<TcpHeader>::write_to_slice`]
+///     * [`PacketBuilderStep<TcpHeader>::to_array_vec`]
 ///     * [`PacketBuilderStep<TcpHeader>::size`]
 ///     * [`PacketBuilderStep<TcpHeader>::ns`]
 ///     * [`PacketBuilderStep<TcpHeader>::fin`]
@@ -84,12 +119,18 @@ use std::{io, marker};
 ///     * [`PacketBuilderStep<TcpHeader>::options_raw`]
 /// * Options after an UDP header was added:
 ///     * [`PacketBuilderStep<UdpHeader>::write`]
+///     * [`PacketBuilderStep<UdpHeader>::write_to_slice`]
+///     * [`PacketBuilderStep<UdpHeader>::to_array_vec`]
 ///     * [`PacketBuilderStep<UdpHeader>::size`]
 /// * Options after an ICMPv4 header was added:
 ///     * [`PacketBuilderStep<Icmpv4Header>::write`]
+///     * [`PacketBuilderStep<Icmpv4Header>::write_to_slice`]
+///     * [`PacketBuilderStep<Icmpv4Header>::to_array_vec`]
 ///     * [`PacketBuilderStep<Icmpv4Header>::size`]
 /// * Options after an ICMPv6 header was added:
 ///     * [`PacketBuilderStep<Icmpv6Header>::write`]
+///     * [`PacketBuilderStep<Icmpv6Header>::write_to_slice`]
+///     * [`PacketBuilderStep<Icmpv6Header>::to_array_vec`]
 ///     * [`PacketBuilderStep<Icmpv6Header>::size`]
 ///
 pub struct PacketBuilder {}
@@ -132,8 +173,13 @@ impl PacketBuilder {
                     ether_type: 0 //the type identifier 
                 }),
                 vlan_header: None,
+                outer_vlan_tpid: None,
+                mpls_labels: Vec::new(),
+                mpls_entropy_label: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                tunnel_header: None,
+                mtu: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }
@@ -170,8 +216,13 @@ impl PacketBuilder {
             state: PacketImpl {
                 ethernet2_header: None,
                 vlan_header: None,
+                outer_vlan_tpid: None,
+                mpls_labels: Vec::new(),
+                mpls_entropy_label: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                tunnel_header: None,
+                mtu: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ipv4(source, destination, time_to_live)
@@ -212,8 +263,13 @@ impl PacketBuilder {
             state: PacketImpl {
                 ethernet2_header: None,
                 vlan_header: None,
+                outer_vlan_tpid: None,
+                mpls_labels: Vec::new(),
+                mpls_entropy_label: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                tunnel_header: None,
+                mtu: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ipv6(source, destination, hop_limit)
@@ -288,27 +344,207 @@ impl PacketBuilder {
             state: PacketImpl {
                 ethernet2_header: None,
                 vlan_header: None,
+                outer_vlan_tpid: None,
+                mpls_labels: Vec::new(),
+                mpls_entropy_label: None,
                 ip_header: None,
-                transport_header: None
+                transport_header: None,
+                tunnel_header: None,
+                mtu: None
             },
             _marker: marker::PhantomData::<Ethernet2Header>{}
         }.ip(ip_header)
     }
 }
 
+#[derive(Clone, Debug)]
 struct PacketImpl {
     ethernet2_header: Option<Ethernet2Header>,
     ip_header: Option<IpHeader>,
     vlan_header: Option<VlanHeader>,
-    transport_header: Option<TransportHeader>
+    /// Ether type identifying the (outer) vlan tag in the Ethernet II header
+    /// (`None` if the default ether type should be used).
+    outer_vlan_tpid: Option<u16>,
+    mpls_labels: Vec<MplsHeader>,
+    /// Index of the label after which an entropy label indicator & entropy
+    /// label get inserted & the algorithm used to compute the entropy.
+    mpls_entropy_label: Option<(usize, FlowHashAlgorithm)>,
+    transport_header: Option<TransportHeader>,
+    /// Tunnel header written in front of the payload of the transport header.
+    tunnel_header: Option<GeneveHeader>,
+    mtu: Option<usize>
 }
 
 ///An unfinished packet that is build with the packet builder
+///
+/// Steps can be cloned, which allows them to be used as reusable "profiles"
+/// or templates: the invariant layers (e.g. Ethernet II, vlan & ip header
+/// of a traffic generator) are configured once and every packet starts from
+/// a clone of the step. Header fields that differ between the packets can
+/// be patched on the clone via the `*_header_mut` methods (lengths & checksums
+/// are calculated when the packet gets written). Cloning only allocates if
+/// the step contains a mpls label stack.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::{PacketBuilder, IpHeader};
+/// #
+/// // profile with the source context of all packets
+/// let profile = PacketBuilder::
+///     ethernet2([1,2,3,4,5,6],     //source mac
+///               [7,8,9,10,11,12]) //destionation mac
+///    .single_vlan(0x123)
+///    .ipv6([11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26], //source
+///          [31,32,33,34,35,36,37,38,39,40,41,42,43,44,45,46], //destination
+///          47);                                               //hop limit
+///
+/// let mut packets = Vec::new();
+/// for i in 0..4u8 {
+///     // clone the profile & patch the fields that differ per packet
+///     let mut builder = profile.clone();
+///     if let Some(IpHeader::Version6(ipv6, _)) = builder.ip_header_mut() {
+///         ipv6.destination[15] = i;
+///     }
+///     let mut packet = Vec::new();
+///     builder
+///         .udp(21, 1234)
+///         .write(&mut packet, &[1,2,3,4,5,6,7,8])
+///         .unwrap();
+///     packets.push(packet);
+/// }
+/// assert_eq!(4, packets.len());
+/// ```
+#[derive(Clone, Debug)]
 pub struct PacketBuilderStep<LastStep> {
     state: PacketImpl,
     _marker: marker::PhantomData<LastStep>
 }
 
+impl<LastStep> PacketBuilderStep<LastStep> {
+    /// Sets the maximum transmission unit (MTU) of the link the packet will be sent over.
+    ///
+    /// The MTU limits the size of the ip packet (ip header, extension headers,
+    /// transport header & payload). Ethernet II, vlan & mpls headers are not counted.
+    /// If the packet would exceed the MTU the write methods return an
+    /// [`ValueError::IpPacketExceedsMtu`] error & nothing is written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, ValueError, WriteError};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     //source mac
+    ///               [7,8,9,10,11,12]) //destionation mac
+    ///    .mtu(1500)
+    ///    .ipv4([192,168,1,1], //source ip
+    ///          [192,168,1,2], //desitionation ip
+    ///          20)            //time to life
+    ///    .udp(21,    //source port
+    ///         1234); //desitnation port
+    ///
+    /// let payload = [0u8;1500];
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// assert_eq!(
+    ///     Some(ValueError::IpPacketExceedsMtu{ ip_packet_len: 20 + 8 + 1500, mtu: 1500 }),
+    ///     match builder.write(&mut result, &payload) {
+    ///         Err(WriteError::ValueError(err)) => Some(err),
+    ///         _ => None,
+    ///     }
+    /// );
+    /// assert!(result.is_empty());
+    /// ```
+    pub fn mtu(mut self, mtu: usize) -> PacketBuilderStep<LastStep> {
+        self.state.mtu = Some(mtu);
+        self
+    }
+
+    /// Returns the maximum transmission unit if one was set.
+    #[inline]
+    pub fn get_mtu(&self) -> Option<usize> {
+        self.state.mtu
+    }
+
+    /// Returns the Ethernet II header if one was added.
+    #[inline]
+    pub fn ethernet2_header(&self) -> Option<&Ethernet2Header> {
+        self.state.ethernet2_header.as_ref()
+    }
+
+    /// Returns a mutable reference to the Ethernet II header (if one was
+    /// added), e.g. to patch the addresses of a cloned step.
+    ///
+    /// The ether type gets overwritten when the packet is written.
+    #[inline]
+    pub fn ethernet2_header_mut(&mut self) -> Option<&mut Ethernet2Header> {
+        self.state.ethernet2_header.as_mut()
+    }
+
+    /// Returns the vlan header if one was added.
+    #[inline]
+    pub fn vlan_header(&self) -> Option<&VlanHeader> {
+        self.state.vlan_header.as_ref()
+    }
+
+    /// Returns a mutable reference to the vlan header (if one was added),
+    /// e.g. to patch the vlan identifier of a cloned step.
+    ///
+    /// The ether types get overwritten when the packet is written.
+    #[inline]
+    pub fn vlan_header_mut(&mut self) -> Option<&mut VlanHeader> {
+        self.state.vlan_header.as_mut()
+    }
+
+    /// Returns the mpls label stack entries (empty if no label stack was added).
+    #[inline]
+    pub fn mpls_labels(&self) -> &[MplsHeader] {
+        &self.state.mpls_labels
+    }
+
+    /// Returns a mutable reference to the mpls label stack entries, e.g. to
+    /// patch the labels of a cloned step.
+    ///
+    /// The bottom of stack flags get overwritten when the packet is written.
+    #[inline]
+    pub fn mpls_labels_mut(&mut self) -> &mut [MplsHeader] {
+        &mut self.state.mpls_labels
+    }
+
+    /// Returns the ip header if one was added.
+    #[inline]
+    pub fn ip_header(&self) -> Option<&IpHeader> {
+        self.state.ip_header.as_ref()
+    }
+
+    /// Returns a mutable reference to the ip header (if one was added),
+    /// e.g. to patch the addresses of a cloned step.
+    ///
+    /// The payload length, the next header fields & the header checksum get
+    /// overwritten when the packet is written.
+    #[inline]
+    pub fn ip_header_mut(&mut self) -> Option<&mut IpHeader> {
+        self.state.ip_header.as_mut()
+    }
+
+    /// Returns the transport header if one was added.
+    #[inline]
+    pub fn transport_header(&self) -> Option<&TransportHeader> {
+        self.state.transport_header.as_ref()
+    }
+
+    /// Returns a mutable reference to the transport header (if one was
+    /// added), e.g. to patch the ports of a cloned step.
+    ///
+    /// The length & checksum fields get overwritten when the packet is written.
+    #[inline]
+    pub fn transport_header_mut(&mut self) -> Option<&mut TransportHeader> {
+        self.state.transport_header.as_mut()
+    }
+}
+
 impl PacketBuilderStep<Ethernet2Header> {
     /// Add an IPv4 header
     ///
@@ -596,10 +832,142 @@ impl PacketBuilderStep<Ethernet2Header> {
             _marker: marker::PhantomData::<VlanHeader>{}
         }
     }
+
+    /// Adds a mpls label stack (the bottom of stack flag is set automatically on
+    /// the last entry & cleared on all other entries).
+    ///
+    /// The ether type is set to `ether_type::MPLS_MULTICAST` if the destination
+    /// mac address is a multicast address & to `ether_type::MPLS_UNICAST`
+    /// otherwise. If the given slice is empty no label stack is added.
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, MplsHeader, SlicedPacket};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     //source mac
+    ///               [7,8,9,10,11,12]) //destionation mac
+    ///     .mpls(&[
+    ///         MplsHeader::new(16, 0, false, 64).unwrap(),  // transport label
+    ///         MplsHeader::new(1000, 0, false, 64).unwrap() // service label
+    ///     ])
+    ///     .ipv4([192,168,1,1], //source ip
+    ///           [192,168,1,2], //desitionation ip
+    ///           20)            //time to life
+    ///     .udp(21,    //source port 
+    ///          1234); //desitnation port
+    ///
+    /// //payload of the udp packet
+    /// let payload = [1,2,3,4,5,6,7,8];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_ethernet(&result).unwrap();
+    /// assert_eq!(2, sliced.mpls.unwrap().len());
+    /// ```
+    pub fn mpls(mut self, labels: &[MplsHeader]) -> PacketBuilderStep<MplsHeader> {
+        self.state.mpls_labels = labels.to_vec();
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<MplsHeader>{}
+        }
+    }
 }
 
 impl PacketBuilderStep<VlanHeader> {
 
+    /// Overrides the ether type in the Ethernet II header that identifies
+    /// the (outer) vlan tag.
+    ///
+    /// By default `ether_type::VLAN_TAGGED_FRAME` (0x8100) is used for single
+    /// and `ether_type::PROVIDER_BRIDGING` (0x88A8) for double tagged frames.
+    /// This allows reproducing misconfigured devices that send double tagged
+    /// frames with an outer tag of 0x8100 (or the legacy 0x9100) for interop
+    /// testing. The ether type between the outer & inner tag is always set
+    /// to `ether_type::VLAN_TAGGED_FRAME`.
+    ///
+    /// Such frames are still decoded as double vlan tagged frames, as the
+    /// parsers accept all vlan ether types for both tags.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::{ether_type, PacketBuilder, SlicedPacket, VlanSlice};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     //source mac
+    ///               [7,8,9,10,11,12]) //destionation mac
+    ///     .double_vlan(0x123, // outer vlan identifier
+    ///                  0x234) // inner vlan identifier
+    ///     .outer_vlan_tpid(ether_type::VLAN_TAGGED_FRAME)
+    ///     .ipv4([192,168,1,1], //source ip
+    ///           [192,168,1,2], //desitionation ip
+    ///           20)            //time to life
+    ///     .udp(21,    //source port
+    ///          1234); //desitnation port
+    ///
+    /// let mut result = Vec::<u8>::new();
+    /// builder.write(&mut result, &[1,2,3,4]).unwrap();
+    /// assert_eq!([0x81, 0x00], [result[12], result[13]]);
+    ///
+    /// // still classified as a double tagged (QinQ) frame
+    /// let sliced = SlicedPacket::from_ethernet(&result).unwrap();
+    /// assert!(matches!(sliced.vlan, Some(VlanSlice::DoubleVlan(_))));
+    /// ```
+    pub fn outer_vlan_tpid(mut self, tpid: u16) -> PacketBuilderStep<VlanHeader> {
+        self.state.outer_vlan_tpid = Some(tpid);
+        self
+    }
+
+    /// Adds a mpls label stack after the vlan header(s) (the bottom of stack
+    /// flag & the ether type are set the same way as by the `mpls` method
+    /// following the Ethernet II header).
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, MplsHeader};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     //source mac
+    ///               [7,8,9,10,11,12]) //destionation mac
+    ///     .single_vlan(0x123) // vlan identifier
+    ///     .mpls(&[MplsHeader::new(16, 0, true, 64).unwrap()])
+    ///     .ipv4([192,168,1,1], //source ip
+    ///           [192,168,1,2], //desitionation ip
+    ///           20)            //time to life
+    ///     .udp(21,    //source port 
+    ///          1234); //desitnation port
+    ///
+    /// //payload of the udp packet
+    /// let payload = [1,2,3,4,5,6,7,8];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn mpls(self, labels: &[MplsHeader]) -> PacketBuilderStep<MplsHeader> {
+        //use the method from the Ethernet2Header implementation
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<Ethernet2Header>{}
+        }.mpls(labels)
+    }
+
     ///Add an ip header (length, protocol/next_header & checksum fields will be overwritten based on the rest of the packet).
     ///
     /// # Example IPv4
@@ -664,7 +1032,170 @@ impl PacketBuilderStep<VlanHeader> {
     /// let builder = PacketBuilder::
     ///     ethernet2([1,2,3,4,5,6],     //source mac
     ///               [7,8,9,10,11,12]) //destionation mac
-    ///     .single_vlan(0x123) // vlan identifier
+    ///     .single_vlan(0x123) // vlan identifier
+    ///     .ipv6(
+    ///         //source
+    ///         [11,12,13,14,15,16,17,18,19,10,21,22,23,24,25,26],
+    ///         //destination
+    ///         [31,32,33,34,35,36,37,38,39,40,41,42,43,44,45,46],
+    ///         //hop_limit
+    ///         47)
+    ///     .udp(21,    //source port 
+    ///          1234); //desitnation port
+    ///
+    /// //payload of the udp packet
+    /// let payload = [1,2,3,4,5,6,7,8];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn ipv6(self, source: [u8;16], destination: [u8;16], hop_limit: u8) -> PacketBuilderStep<IpHeader> {
+        //use the method from the Ethernet2Header implementation
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<Ethernet2Header>{}
+        }.ipv6(source, destination, hop_limit)
+    }
+
+    /// Add an IPv4 header
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, SingleVlanHeader, VlanHeader};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     //source mac
+    ///               [7,8,9,10,11,12]) //destionation mac
+    ///     .single_vlan(0x123) // vlan identifier
+    ///     .ipv4([192,168,1,1], //source ip
+    ///           [192,168,1,2], //desitionation ip
+    ///           20)            //time to life
+    ///     .udp(21,    //source port 
+    ///          1234); //desitnation port
+    ///
+    /// //payload of the udp packet
+    /// let payload = [1,2,3,4,5,6,7,8];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn ipv4(self, source: [u8;4], destination: [u8;4], time_to_live: u8) -> PacketBuilderStep<IpHeader> {
+        //use the method from the Ethernet2Header implementation
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<Ethernet2Header>{}
+        }.ipv4(source, destination, time_to_live)
+    }
+}
+
+impl PacketBuilderStep<MplsHeader> {
+
+    /// Inserts an entropy label indicator (ELI) & an entropy label (EL)
+    /// (RFC 6790) directly after the label at `lsp_index` of the label stack.
+    ///
+    /// The entropy is computed from the [`FlowKey`] of the ip & transport
+    /// headers with the given algorithm when the packet gets written (see
+    /// [`MplsHeader::entropy_label_pair`] for the values of the inserted
+    /// entries). This allows label switching routers to load balance the
+    /// packets of different flows without looking past the label stack.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lsp_index` is not the index of a label of the stack.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::{FlowHashAlgorithm, PacketBuilder, MplsHeader, MplsLabelValue, SlicedPacket};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     //source mac
+    ///               [7,8,9,10,11,12]) //destionation mac
+    ///     .mpls(&[
+    ///         MplsHeader::new(16, 0, false, 64).unwrap(),  // transport label
+    ///         MplsHeader::new(1000, 0, false, 64).unwrap() // service label
+    ///     ])
+    ///     .entropy_label(0, FlowHashAlgorithm::Crc32)
+    ///     .ipv4([192,168,1,1], //source ip
+    ///           [192,168,1,2], //desitionation ip
+    ///           20)            //time to life
+    ///     .udp(21,    //source port
+    ///          1234); //desitnation port
+    ///
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(4));
+    /// builder.write(&mut result, &[1,2,3,4]).unwrap();
+    ///
+    /// let labels = SlicedPacket::from_ethernet(&result).unwrap().mpls.unwrap().to_headers();
+    /// assert_eq!(4, labels.len());
+    /// assert_eq!(Some(MplsLabelValue::EntropyLabelIndicator), labels[1].reserved_label());
+    /// assert_eq!(0, labels[2].ttl);
+    /// assert_eq!(1000, labels[3].label);
+    /// ```
+    pub fn entropy_label(mut self, lsp_index: usize, algorithm: FlowHashAlgorithm) -> PacketBuilderStep<MplsHeader> {
+        assert!(
+            lsp_index < self.state.mpls_labels.len(),
+            "entropy label lsp index {} outside of the label stack (length {})",
+            lsp_index,
+            self.state.mpls_labels.len()
+        );
+        self.state.mpls_entropy_label = Some((lsp_index, algorithm));
+        self
+    }
+
+    ///Add an ip header (length, protocol/next_header & checksum fields will be overwritten based on the rest of the packet).
+    ///
+    /// # Example
+    /// ```
+    /// # use etherparse::*;
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],
+    ///               [7,8,9,10,11,12])
+    ///    .mpls(&[MplsHeader::new(16, 0, true, 64).unwrap()])
+    ///    //payload_len, protocol & checksum will be replaced during write
+    ///    .ip(IpHeader::Version4(
+    ///         Ipv4Header::new(
+    ///             0, //payload_len will be replaced during write
+    ///             12, //time_to_live
+    ///             ip_number::UDP, //will be replaced during write
+    ///             [0,1,2,3], //source
+    ///             [4,5,6,7] //destination
+    ///         ),
+    ///         Default::default() // IPv4 extension headers (default is none)
+    ///     ));
+    /// ```
+    pub fn ip(self, ip_header: IpHeader) -> PacketBuilderStep<IpHeader> {
+        //use the method from the Ethernet2Header implementation
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<Ethernet2Header>{}
+        }.ip(ip_header)
+    }
+
+    /// Add an IPv6 header
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, MplsHeader};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     //source mac
+    ///               [7,8,9,10,11,12]) //destionation mac
+    ///     .mpls(&[MplsHeader::new(16, 0, true, 64).unwrap()])
     ///     .ipv6(
     ///         //source
     ///         [11,12,13,14,15,16,17,18,19,10,21,22,23,24,25,26],
@@ -700,12 +1231,12 @@ impl PacketBuilderStep<VlanHeader> {
     /// Basic usage: 
     ///
     /// ```
-    /// # use etherparse::{PacketBuilder, SingleVlanHeader, VlanHeader};
+    /// # use etherparse::{PacketBuilder, MplsHeader};
     /// #
     /// let builder = PacketBuilder::
     ///     ethernet2([1,2,3,4,5,6],     //source mac
     ///               [7,8,9,10,11,12]) //destionation mac
-    ///     .single_vlan(0x123) // vlan identifier
+    ///     .mpls(&[MplsHeader::new(16, 0, true, 64).unwrap()])
     ///     .ipv4([192,168,1,1], //source ip
     ///           [192,168,1,2], //desitionation ip
     ///           20)            //time to life
@@ -733,6 +1264,44 @@ impl PacketBuilderStep<VlanHeader> {
 
 impl PacketBuilderStep<IpHeader> {
 
+    /// Adds an IPv6 segment routing header (SRH) as the routing extension
+    /// header of the IPv6 header (replacing an existing routing header).
+    ///
+    /// The `next_header` field of the segment routing header will be
+    /// overwritten based on the rest of the packet. Note that the destination
+    /// address of the IPv6 header is not modified, it should be set to the
+    /// active segment (see [`Ipv6SegmentRoutingHeader::active_segment`]).
+    /// If the ip header is an IPv4 header the packet is not modified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use etherparse::{Ipv6SegmentRoutingHeader, PacketBuilder};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6([0;16], [2;16], 20)
+    ///     .ipv6_segment_routing(
+    ///         Ipv6SegmentRoutingHeader::new(0, 1, &[[1;16], [2;16]]).unwrap()
+    ///     )
+    ///     .udp(21, 1234);
+    ///
+    /// let payload = [1,2,3,4,5,6,7,8];
+    /// let mut result = Vec::<u8>::with_capacity(builder.size(payload.len()));
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn ipv6_segment_routing(mut self, header: Ipv6SegmentRoutingHeader) -> PacketBuilderStep<IpHeader> {
+        if let Some(IpHeader::Version6(_, ref mut extensions)) = self.state.ip_header {
+            let final_destination_options = extensions.routing
+                .take()
+                .and_then(|routing| routing.final_destination_options);
+            extensions.routing = Some(Ipv6RoutingExtensions {
+                routing: header.to_raw(),
+                final_destination_options,
+            });
+        }
+        self
+    }
+
     /// Adds an ICMPv4 header of the given [`Icmpv4Type`] to the packet.
     ///
     /// If an ICMPv4 header gets added the payload used during the builders `write` 
@@ -918,6 +1487,50 @@ impl PacketBuilderStep<IpHeader> {
         }
     }
 
+    /// Adds an ICMPv4 "fragmentation needed" (destination unreachable) packet
+    /// used for path MTU discovery.
+    ///
+    /// The payload should contain the start of the packet that was too big
+    /// (see [`PathMtuInfo::icmpv4_embedded_payload`]).
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, PathMtuInfo};
+    /// #
+    /// let builder = PacketBuilder::
+    ///    ipv4([192,168,1,1],  //source ip
+    ///          [192,168,1,2], //desitionation ip
+    ///          20)            //time to life
+    ///    .icmpv4_fragmentation_needed(
+    ///         1280, // mtu of the next hop
+    ///     );
+    ///
+    /// // start of the packet that was too big
+    /// # let original_packet = [0x45,0,0,20,0,0,0,0,20,17,0,0,1,2,3,4,5,6,7,8];
+    /// let payload = PathMtuInfo::icmpv4_embedded_payload(&original_packet);
+    ///
+    /// // get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// // serialize
+    /// builder.write(&mut result, payload).unwrap();
+    /// ```
+    pub fn icmpv4_fragmentation_needed(mut self, next_hop_mtu: u16) -> PacketBuilderStep<Icmpv4Header> {
+        let icmpv4 = Icmpv4Header::new(Icmpv4Type::DestinationUnreachable(
+            icmpv4::DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu }
+        ));
+        self.state.transport_header = Some(TransportHeader::Icmpv4(icmpv4));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<Icmpv4Header>{}
+        }
+    }
+
     /// Adds an ICMPv6 header of the given [`Icmpv6Type`] to the packet.
     ///
     /// If an ICMPv6 header gets added the payload used during the builders `write` 
@@ -1119,6 +1732,52 @@ impl PacketBuilderStep<IpHeader> {
         }
     }
 
+    /// Adds an ICMPv6 "packet too big" packet used for path MTU discovery.
+    ///
+    /// The payload should contain the start of the packet that was too big
+    /// (see [`PathMtuInfo::icmpv6_embedded_payload`]).
+    ///
+    /// # Example
+    ///
+    /// Basic usage: 
+    ///
+    /// ```
+    /// # use etherparse::{PacketBuilder, PathMtuInfo};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6(
+    ///         //source
+    ///         [11,12,13,14,15,16,17,18,19,10,21,22,23,24,25,26],
+    ///         //destination
+    ///         [31,32,33,34,35,36,37,38,39,40,41,42,43,44,45,46],
+    ///         //hop_limit
+    ///         47)
+    ///    .icmpv6_packet_too_big(
+    ///         1280, // mtu of the next hop
+    ///     );
+    ///
+    /// // start of the packet that was too big
+    /// # let original_packet = [0u8;1500];
+    /// let payload = PathMtuInfo::icmpv6_embedded_payload(&original_packet);
+    ///
+    /// // get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// // serialize
+    /// builder.write(&mut result, payload).unwrap();
+    /// assert_eq!(1280, result.len());
+    /// ```
+    pub fn icmpv6_packet_too_big(mut self, mtu: u32) -> PacketBuilderStep<Icmpv6Header> {
+        let icmpv6 = Icmpv6Header::new(Icmpv6Type::PacketTooBig{ mtu });
+        self.state.transport_header = Some(TransportHeader::Icmpv6(icmpv6));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<Icmpv6Header>{}
+        }
+    }
+
     /// Adds an UDP header.
     ///
     /// # Example
@@ -1161,6 +1820,85 @@ impl PacketBuilderStep<IpHeader> {
         }
     }
 
+    /// Adds an UDP-Lite header with the given checksum coverage (0 to cover
+    /// the complete datagram). The checksum is calculated when the packet
+    /// is written.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use etherparse::PacketBuilder;
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv6([1;16], // source ip
+    ///          [2;16], // destination ip
+    ///          20)     // hop limit
+    ///    .udp_lite(21,    // source port
+    ///              1234,  // destination port
+    ///              8 + 4); // checksum coverage (header + 4 payload bytes)
+    ///
+    /// //payload of the udp-lite packet
+    /// let payload = [1,2,3,4,5,6,7,8];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn udp_lite(mut self, source_port: u16, destination_port: u16, checksum_coverage: u16) -> PacketBuilderStep<UdpLiteHeader> {
+        self.state.transport_header = Some(TransportHeader::UdpLite(
+            UdpLiteHeader::without_checksum(source_port, destination_port, checksum_coverage)
+        ));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<UdpLiteHeader>{}
+        }
+    }
+
+    /// Adds a DCCP header (the checksum is calculated when the packet is written).
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use etherparse::{DccpHeader, DccpType, PacketBuilder};
+    /// #
+    /// let builder = PacketBuilder::
+    ///     ipv4([192,168,1,1], // source ip
+    ///          [192,168,1,2], // destination ip
+    ///          20)            // time to life
+    ///    .dccp(DccpHeader::new(
+    ///        1234, // source port
+    ///        5001, // destination port
+    ///        1,    // sequence number
+    ///        DccpType::DataAck{ acknowledgement_number: 2 }
+    ///    ));
+    ///
+    /// //payload of the dccp packet
+    /// let payload = [1,2,3,4,5,6,7,8];
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(payload.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &payload).unwrap();
+    /// ```
+    pub fn dccp(mut self, header: DccpHeader) -> PacketBuilderStep<DccpHeader> {
+        self.state.transport_header = Some(TransportHeader::Dccp(header));
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<DccpHeader>{}
+        }
+    }
+
     /// Adds an TCP header.
     ///
     /// # Example
@@ -1212,6 +1950,25 @@ impl PacketBuilderStep<IpHeader> {
         final_write(self, writer, payload)
     }
 
+    /// Write all the headers and the payload with the given ip number to the start
+    /// of the given slice and return the unused part of the slice (no allocations
+    /// are performed).
+    ///
+    /// See [`PacketBuilderStep<IpHeader>::write`] for details on `last_next_header_ip_number`.
+    pub fn write_to_slice<'a>(mut self, slice: &'a mut [u8], last_next_header_ip_number: u8, payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+        self.state.ip_header.as_mut().unwrap().set_next_headers(last_next_header_ip_number);
+        final_write_to_slice(self, slice, payload)
+    }
+
+    /// Write all the headers and the payload with the given ip number into a
+    /// stack allocated buffer with a capacity of `N` bytes.
+    ///
+    /// See [`PacketBuilderStep<IpHeader>::write`] for details on `last_next_header_ip_number`.
+    pub fn to_array_vec<const N: usize>(mut self, last_next_header_ip_number: u8, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+        self.state.ip_header.as_mut().unwrap().set_next_headers(last_next_header_ip_number);
+        final_to_array_vec(self, payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1224,6 +1981,24 @@ impl PacketBuilderStep<Icmpv4Header> {
         final_write(self, writer, payload)
     }
 
+    ///Write all the headers and the payload to the start of the given slice
+    ///and return the unused part of the slice (no allocations are performed).
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///slice can not hold the complete packet.
+    pub fn write_to_slice<'a>(self, slice: &'a mut [u8], payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Write all the headers and the payload into a stack allocated buffer
+    ///with a capacity of `N` bytes.
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///packet does not fit into `N` bytes.
+    pub fn to_array_vec<const N: usize>(self, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+        final_to_array_vec(self, payload)
+    }
+
     /// Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1237,6 +2012,54 @@ impl PacketBuilderStep<Icmpv6Header> {
         final_write(self, writer, payload)
     }
 
+    ///Write all the headers and the payload to the start of the given slice
+    ///and return the unused part of the slice (no allocations are performed).
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///slice can not hold the complete packet.
+    pub fn write_to_slice<'a>(self, slice: &'a mut [u8], payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Write all the headers and the payload into a stack allocated buffer
+    ///with a capacity of `N` bytes.
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///packet does not fit into `N` bytes.
+    pub fn to_array_vec<const N: usize>(self, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+        final_to_array_vec(self, payload)
+    }
+
+    ///Returns the size of the packet when it is serialized
+    pub fn size(&self, payload_size: usize) -> usize {
+        final_size(self, payload_size)
+    }
+}
+
+impl PacketBuilderStep<UdpHeader> {
+    ///Write all the headers and the payload.
+    pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+        final_write(self, writer, payload)
+    }
+
+    ///Write all the headers and the payload to the start of the given slice
+    ///and return the unused part of the slice (no allocations are performed).
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///slice can not hold the complete packet.
+    pub fn write_to_slice<'a>(self, slice: &'a mut [u8], payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Write all the headers and the payload into a stack allocated buffer
+    ///with a capacity of `N` bytes.
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///packet does not fit into `N` bytes.
+    pub fn to_array_vec<const N: usize>(self, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+        final_to_array_vec(self, payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1244,11 +2067,128 @@ impl PacketBuilderStep<Icmpv6Header> {
 }
 
 impl PacketBuilderStep<UdpHeader> {
+    /// Adds a GENEVE header after the udp header. The payload passed to the
+    /// write call is the encapsulated packet (e.g. an ethernet II frame).
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// # use etherparse::{ether_type, GeneveHeader, PacketBuilder};
+    /// #
+    /// // encapsulated ethernet frame
+    /// let mut inner = Vec::new();
+    /// PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+    ///     .ipv4([10,0,0,1], [10,0,0,2], 20)
+    ///     .udp(1234, 5678)
+    ///     .write(&mut inner, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// let builder = PacketBuilder::
+    ///     ethernet2([1,2,3,4,5,6],     // source mac
+    ///               [7,8,9,10,11,12]) // destionation mac
+    ///    .ipv4([192,168,1,1], // source ip
+    ///          [192,168,1,2], // desitionation ip
+    ///          20)            // time to life
+    ///    .udp(50000, GeneveHeader::UDP_PORT)
+    ///    .geneve(GeneveHeader::new(ether_type::TRANSPARENT_ETHERNET_BRIDGING, 1234).unwrap());
+    ///
+    /// //get some memory to store the result
+    /// let mut result = Vec::<u8>::with_capacity(
+    ///                     builder.size(inner.len()));
+    ///
+    /// //serialize
+    /// builder.write(&mut result, &inner).unwrap();
+    /// ```
+    pub fn geneve(mut self, header: GeneveHeader) -> PacketBuilderStep<GeneveHeader> {
+        self.state.tunnel_header = Some(header);
+        //return for next step
+        PacketBuilderStep {
+            state: self.state,
+            _marker: marker::PhantomData::<GeneveHeader>{}
+        }
+    }
+}
+
+impl PacketBuilderStep<UdpLiteHeader> {
+    ///Write all the headers and the payload.
+    pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+        final_write(self, writer, payload)
+    }
+
+    ///Write all the headers and the payload to the start of the given slice
+    ///and return the unused part of the slice (no allocations are performed).
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///slice can not hold the complete packet.
+    pub fn write_to_slice<'a>(self, slice: &'a mut [u8], payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Write all the headers and the payload into a stack allocated buffer
+    ///with a capacity of `N` bytes.
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///packet does not fit into `N` bytes.
+    pub fn to_array_vec<const N: usize>(self, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+        final_to_array_vec(self, payload)
+    }
+
+    ///Returns the size of the packet when it is serialized
+    pub fn size(&self, payload_size: usize) -> usize {
+        final_size(self, payload_size)
+    }
+}
+
+impl PacketBuilderStep<DccpHeader> {
     ///Write all the headers and the payload.
     pub fn write<T: io::Write + Sized>(self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
         final_write(self, writer, payload)
     }
 
+    ///Write all the headers and the payload to the start of the given slice
+    ///and return the unused part of the slice (no allocations are performed).
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///slice can not hold the complete packet.
+    pub fn write_to_slice<'a>(self, slice: &'a mut [u8], payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Write all the headers and the payload into a stack allocated buffer
+    ///with a capacity of `N` bytes.
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///packet does not fit into `N` bytes.
+    pub fn to_array_vec<const N: usize>(self, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+        final_to_array_vec(self, payload)
+    }
+
+    ///Returns the size of the packet when it is serialized
+    pub fn size(&self, payload_size: usize) -> usize {
+        final_size(self, payload_size)
+    }
+}
+
+impl PacketBuilderStep<GeneveHeader> {
+    ///Write all the headers and the payload (the encapsulated packet).
+    ///
+    ///The geneve header & the payload are copied into a temporary buffer
+    ///before writing, as both are covered by the udp checksum.
+    pub fn write<T: io::Write + Sized>(mut self, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+        let udp_payload = match self.state.tunnel_header.take() {
+            Some(geneve) => {
+                let mut udp_payload = Vec::with_capacity(geneve.header_len() + payload.len());
+                geneve.write(&mut udp_payload)?;
+                udp_payload.extend_from_slice(payload);
+                udp_payload
+            },
+            None => payload.to_vec(),
+        };
+        final_write(self, writer, &udp_payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1334,6 +2274,24 @@ impl PacketBuilderStep<TcpHeader> {
         final_write(self, writer, payload)
     }
 
+    ///Write all the headers and the payload to the start of the given slice
+    ///and return the unused part of the slice (no allocations are performed).
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///slice can not hold the complete packet.
+    pub fn write_to_slice<'a>(self, slice: &'a mut [u8], payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+        final_write_to_slice(self, slice, payload)
+    }
+
+    ///Write all the headers and the payload into a stack allocated buffer
+    ///with a capacity of `N` bytes.
+    ///
+    ///Returns [`WriteError::SliceTooSmall`] with the required size if the
+    ///packet does not fit into `N` bytes.
+    pub fn to_array_vec<const N: usize>(self, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+        final_to_array_vec(self, payload)
+    }
+
     ///Returns the size of the packet when it is serialized
     pub fn size(&self, payload_size: usize) -> usize {
         final_size(self, payload_size)
@@ -1342,6 +2300,14 @@ impl PacketBuilderStep<TcpHeader> {
 
 ///Write all the headers and the payload.
 fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &mut T, payload: &[u8]) -> Result<(),WriteError> {
+
+    //check that the ip packet fits into the mtu before anything gets written
+    if let Some(mtu) = builder.state.mtu {
+        let ip_packet_len = final_ip_packet_size(&builder, payload.len());
+        if ip_packet_len > mtu {
+            return Err(WriteError::ValueError(ValueError::IpPacketExceedsMtu{ ip_packet_len, mtu }));
+        }
+    }
     
     let ip_ether_type = {
         use crate::IpHeader::*;
@@ -1352,6 +2318,16 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
         }
     };
 
+    //ether type of the data after the ethernet & vlan headers
+    let payload_ether_type = if builder.state.mpls_labels.is_empty() {
+        ip_ether_type
+    } else {
+        match &builder.state.ethernet2_header {
+            Some(eth) if 0 != eth.destination[0] & 1 => ether_type::MPLS_MULTICAST,
+            _ => ether_type::MPLS_UNICAST
+        }
+    };
+
     //ethernetII header
     if let Some(mut eth) = builder.state.ethernet2_header {
         eth.ether_type = {
@@ -1359,10 +2335,10 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
             use crate::VlanHeader::*;
             //determine the ether type depending on if there is a vlan tagging header
             match builder.state.vlan_header {
-                Some(Single(_)) => ether_type::VLAN_TAGGED_FRAME,
-                Some(Double(_)) => ether_type::PROVIDER_BRIDGING,
-                //if no vlan header exists, the id is purely defined by the ip type
-                None => ip_ether_type
+                Some(Single(_)) => builder.state.outer_vlan_tpid.unwrap_or(ether_type::VLAN_TAGGED_FRAME),
+                Some(Double(_)) => builder.state.outer_vlan_tpid.unwrap_or(ether_type::PROVIDER_BRIDGING),
+                //if no vlan header exists, the id is purely defined by the payload type
+                None => payload_ether_type
             }
         };
         eth.write(writer)?;
@@ -1373,20 +2349,38 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
     match builder.state.vlan_header {
         Some(Single(mut value)) => {
             //set ether types
-            value.ether_type = ip_ether_type;
+            value.ether_type = payload_ether_type;
             //serialize
             value.write(writer)?;
         },
         Some(Double(mut value)) => {
             //set ether types
             value.outer.ether_type = ether_type::VLAN_TAGGED_FRAME;
-            value.inner.ether_type = ip_ether_type;
+            value.inner.ether_type = payload_ether_type;
             //serialize
             value.write(writer)?;
         },
         None => {}
     }
 
+    //insert the entropy label indicator & entropy label of the flow
+    let mut mpls_labels = builder.state.mpls_labels;
+    if let Some((lsp_index, algorithm)) = builder.state.mpls_entropy_label {
+        let key = FlowKey::from_headers(
+            builder.state.ip_header.as_ref().unwrap(),
+            builder.state.transport_header.as_ref()
+        )?;
+        let pair = mpls_labels[lsp_index].entropy_label_pair(&key, algorithm);
+        mpls_labels.splice(lsp_index + 1..lsp_index + 1, pair);
+    }
+
+    //write the mpls label stack (bottom of stack set on the last entry)
+    let label_count = mpls_labels.len();
+    for (index, mut label) in mpls_labels.into_iter().enumerate() {
+        label.bottom_of_stack = index + 1 == label_count;
+        label.write(writer)?;
+    }
+
 
     //ip header
     use crate::IpHeader::*;
@@ -1424,6 +2418,9 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                         Icmpv6(_) => {},
                         Udp(ref mut udp) => { udp.length = transport_size as u16; }
                         Tcp(_) => {},
+                        UdpLite(_) => {},
+                        Dccp(_) => {},
+                        Sctp(_) => {},
                     }
         
                     //ip protocol number & next header values of the extension header
@@ -1432,7 +2429,10 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                             Icmpv4(_) => ip_number::ICMP,
                             Icmpv6(_) => ip_number::IPV6_ICMP,
                             Udp(_) => ip_number::UDP,
-                            Tcp(_) => ip_number::TCP
+                            Tcp(_) => ip_number::TCP,
+                            UdpLite(_) => ip_number::UDP_LITE,
+                            Dccp(_) => ip_number::DCCP,
+                            Sctp(_) => ip_number::SCTP,
                         }
                     );
         
@@ -1452,7 +2452,10 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                         Icmpv4(_) => {},
                         Icmpv6(_) => {},
                         Udp(ref mut udp) => { udp.length = transport_size as u16; }
-                        Tcp(_) => {}
+                        Tcp(_) => {},
+                        UdpLite(_) => {},
+                        Dccp(_) => {},
+                        Sctp(_) => {},
                     }
         
                     //set the protocol
@@ -1461,7 +2464,10 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
                             Icmpv4(_) => ip_number::ICMP,
                             Icmpv6(_) => ip_number::IPV6_ICMP,
                             Udp(_) => ip_number::UDP,
-                            Tcp(_) => ip_number::TCP
+                            Tcp(_) => ip_number::TCP,
+                            UdpLite(_) => ip_number::UDP_LITE,
+                            Dccp(_) => ip_number::DCCP,
+                            Sctp(_) => ip_number::SCTP,
                         }
                     );
         
@@ -1482,11 +2488,33 @@ fn final_write<T: io::Write + Sized, B>(builder: PacketBuilderStep<B>, writer: &
     Ok(())
 }
 
+///Write all the headers and the payload to the start of the slice & return the rest of the slice.
+fn final_write_to_slice<'a, B>(builder: PacketBuilderStep<B>, slice: &'a mut [u8], payload: &[u8]) -> Result<&'a mut [u8], WriteError> {
+    let size = final_size(&builder, payload.len());
+    if slice.len() < size {
+        return Err(WriteError::SliceTooSmall(size));
+    }
+    {
+        let mut writer = &mut slice[..size];
+        final_write(builder, &mut writer, payload)?;
+    }
+    Ok(&mut slice[size..])
+}
+
+///Write all the headers and the payload into an array vec.
+fn final_to_array_vec<const N: usize, B>(builder: PacketBuilderStep<B>, payload: &[u8]) -> Result<ArrayVec<u8, N>, WriteError> {
+    let size = final_size(&builder, payload.len());
+    if N < size {
+        return Err(WriteError::SliceTooSmall(size));
+    }
+    let mut result = ArrayVec::new();
+    final_write(builder, &mut result, payload)?;
+    Ok(result)
+}
+
 ///Returns the size of the packet when it is serialized
 fn final_size<B>(builder: &PacketBuilderStep<B>, payload_size: usize) -> usize {
-    use crate::IpHeader::*;
     use crate::VlanHeader::*;
-    use crate::TransportHeader::*;
     (match builder.state.ethernet2_header {
         Some(_) => Ethernet2Header::SERIALIZED_SIZE,
         None => 0
@@ -1494,15 +2522,33 @@ fn final_size<B>(builder: &PacketBuilderStep<B>, payload_size: usize) -> usize {
         Some(Single(_)) => SingleVlanHeader::SERIALIZED_SIZE,
         Some(Double(_)) => DoubleVlanHeader::SERIALIZED_SIZE,
         None => 0 
-    } + match builder.state.ip_header {
+    } + builder.state.mpls_labels.len()*MplsHeader::SERIALIZED_SIZE
+      + match builder.state.mpls_entropy_label {
+        Some(_) => 2*MplsHeader::SERIALIZED_SIZE,
+        None => 0
+    }
+      + final_ip_packet_size(builder, payload_size)
+}
+
+///Returns the size of the ip packet (ip headers, transport header & payload) when it is serialized
+fn final_ip_packet_size<B>(builder: &PacketBuilderStep<B>, payload_size: usize) -> usize {
+    use crate::IpHeader::*;
+    use crate::TransportHeader::*;
+    (match builder.state.ip_header {
         Some(Version4(ref value, ref ext)) => value.header_len() + ext.header_len(),
         Some(Version6(_, ref ext)) => Ipv6Header::SERIALIZED_SIZE + ext.header_len(),
         None => 0
-    } + match builder.state.transport_header {
+    }) + match builder.state.transport_header {
         Some(Icmpv4(ref value)) => value.header_len(),
         Some(Icmpv6(ref value)) => value.header_len(),
         Some(Udp(_)) => UdpHeader::SERIALIZED_SIZE,
         Some(Tcp(ref value)) => value.header_len() as usize,
+        Some(UdpLite(_)) => UdpLiteHeader::SIZE,
+        Some(Dccp(ref value)) => value.header_len(),
+        Some(Sctp(ref value)) => value.header_len(),
+        None => 0
+    } + match builder.state.tunnel_header {
+        Some(ref value) => value.header_len(),
         None => 0
     } + payload_size
 }
@@ -1520,7 +2566,12 @@ mod whitebox_tests {
                 ethernet2_header: None,
                 ip_header: None,
                 vlan_header: None,
-                transport_header: None
+                outer_vlan_tpid: None,
+                mpls_labels: Vec::new(),
+                mpls_entropy_label: None,
+                transport_header: None,
+                tunnel_header: None,
+                mtu: None
             },
             _marker: marker::PhantomData::<UdpHeader>{}
         }.size(0));
@@ -1536,7 +2587,12 @@ mod whitebox_tests {
                     ethernet2_header: None,
                     ip_header: None,
                     vlan_header: None,
-                    transport_header: None
+                    outer_vlan_tpid: None,
+                    mpls_labels: Vec::new(),
+                    mpls_entropy_label: None,
+                    transport_header: None,
+                    tunnel_header: None,
+                    mtu: None
                 },
                 _marker: marker::PhantomData::<UdpHeader>{}
             },
@@ -7,19 +7,112 @@ use super::*;
 /// * [`PacketHeaders::from_ethernet_slice`]
 /// * [`PacketHeaders::from_ether_type`]
 /// * [`PacketHeaders::from_ip_slice`]
+/// * [`PacketHeaders::from_linux_sll_slice`]
+/// * [`PacketHeaders::from_linux_sll2_slice`]
+/// * [`PacketHeaders::from_null_loopback_slice`]
+/// * [`PacketHeaders::from_ieee80211_slice`]
+/// * [`PacketHeaders::from_radiotap_slice`]
 ///
 /// depending on your starting header to parse the headers in a slice and get this
 /// struct as a result.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct PacketHeaders<'a> {
     /// Ethernet II header if present.
     pub link: Option<Ethernet2Header>,
+    /// Linux cooked capture header if present (only set when decoding with
+    /// [`PacketHeaders::from_linux_sll_slice`] or [`PacketHeaders::from_linux_sll2_slice`]).
+    pub linux_sll: Option<LinuxCookedHeader>,
+    /// MACsec SecTAG if present (ether type `ether_type::MACSEC`).
+    ///
+    /// Cleartext frames are decoded further based on the ether type following
+    /// the SecTAG, otherwise the `payload` field contains the encrypted data.
+    /// SecTAGs following a vlan header are only sliced by [`SlicedPacket`],
+    /// `PacketHeaders` leaves them in the `payload` field.
+    pub macsec: Option<MacsecHeader>,
     /// Single or double vlan headers if present.
     pub vlan: Option<VlanHeader>,
+    /// MPLS label stack if present.
+    pub mpls: Option<MplsStackSlice<'a>>,
+    /// PPPoE header if present (ether types `ether_type::PPPOE_DISCOVERY` &
+    /// `ether_type::PPPOE_SESSION`).
+    ///
+    /// IPv4 & IPv6 packets contained in session data packets are decoded into the
+    /// `ip` & `transport` fields, otherwise the `payload` field contains the PPPoE
+    /// payload.
+    pub pppoe: Option<PppoeHeader>,
+    /// ARP packet if present.
+    pub arp: Option<ArpPacket>,
+    /// Custom link layer header decoded by a user registered parser (see [`ParserRegistry`]).
+    pub link_extension: Option<LinkExtensionSlice<'a>>,
     /// IPv4 or IPv6 header and IP extension headers if present.
     pub ip: Option<IpHeader>,
-    /// TCP or UDP header if present.
+    /// Layers with a known length that could not be decoded, in the order
+    /// they appear in the packet (see [`RawLayer`]).
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub raw_layers: Vec<RawLayer<'a>>,
+    /// TCP, UDP, UDP-Lite, DCCP, SCTP, ICMPv4 or ICMPv6 header if present.
+    ///
+    /// The chunks following a SCTP common header are contained in the
+    /// `payload` field (see [`SctpChunkIterator`]).
+    ///
+    /// Note that IGMP messages are only sliced by [`SlicedPacket`] (see
+    /// [`TransportSlice::Igmp`]), `PacketHeaders` leaves them in the `payload`
+    /// field.
     pub transport: Option<TransportHeader>,
+    /// Custom transport layer header decoded by a user registered parser (see [`ParserRegistry`]).
+    pub transport_extension: Option<TransportExtensionSlice<'a>>,
+    /// ESP header if present (ip number `ip_number::ENCAP_SEC`).
+    ///
+    /// If present the `payload` field contains the encrypted data following
+    /// the sequence number.
+    pub esp: Option<EspHeader>,
+    /// GRE header if present.
+    ///
+    /// If present the `payload` field contains the encapsulated packet.
+    /// GRE headers with a version other then 0 (e.g. enhanced GRE used by
+    /// PPTP) or routing information are not decoded & left in the payload.
+    pub gre: Option<GreHeader>,
+    /// ERSPAN type II or III header if present (GRE protocol type
+    /// `ether_type::ERSPAN_TYPE_2` or `ether_type::ERSPAN_TYPE_3`).
+    ///
+    /// If present the `payload` field contains the mirrored frame.
+    pub erspan: Option<ErspanHeader>,
+    /// VXLAN header if present (udp port assigned to [`ApplicationProtocol::Vxlan`]
+    /// in the [`ParserRegistry`]).
+    ///
+    /// If present the `payload` field contains the encapsulated ethernet II frame.
+    pub vxlan: Option<VxlanHeader>,
+    /// GENEVE header if present (udp port assigned to [`ApplicationProtocol::Geneve`]
+    /// in the [`ParserRegistry`]).
+    ///
+    /// If present the `payload` field contains the encapsulated packet.
+    pub geneve: Option<GeneveHeader>,
+    /// GTP-U header if present (udp port assigned to [`ApplicationProtocol::GtpU`]
+    /// in the [`ParserRegistry`]).
+    ///
+    /// If present the `payload` field contains the data following the header
+    /// (the encapsulated ip packet for G-PDUs).
+    pub gtpu: Option<GtpuHeader>,
+    /// NSH header if present (ether type `ether_type::NSH`).
+    ///
+    /// If present the `payload` field contains the packet following the header.
+    pub nsh: Option<NshHeader>,
+    /// Encapsulated packet of a `gre`, `vxlan`, `geneve`, `gtpu` or `nsh` header. GRE & GENEVE
+    /// payloads are decoded based on the protocol type of the header (ethernet II
+    /// frames for `ether_type::TRANSPARENT_ETHERNET_BRIDGING`), VXLAN payloads
+    /// are decoded as ethernet II frames, GTP-U payloads as ip packets (only for
+    /// G-PDUs) & NSH payloads based on the next protocol field. The mirrored
+    /// ethernet II frames of ERSPAN packets are decoded after the `erspan` header.
+    ///
+    /// Tunnels are only decoded up to the `max_tunnel_depth` of the
+    /// [`ParseLimits`] (4 by default), deeper nested tunnel headers are left in
+    /// the payload.
+    ///
+    /// Encapsulated packets that can not be decoded (e.g. because they are
+    /// truncated) are also left in the payload & `inner` is set to `None`.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub inner: Option<Box<PacketHeaders<'a>>>,
     /// Rest of the packet that could not be decoded as a header (usually the payload).
     pub payload: &'a [u8]
 }
@@ -67,103 +160,235 @@ impl<'a> PacketHeaders<'a> {
     /// }
     /// ```
     pub fn from_ethernet_slice(packet: &[u8]) -> Result<PacketHeaders, ReadError> {
-        
-        let (ethernet, mut rest) = Ethernet2Header::from_slice(packet)?;
-        let mut ether_type = ethernet.ether_type;
-
-        let mut result = PacketHeaders{
-            link: Some(ethernet),
-            vlan: None,
-            ip: None,
-            transport: None,
-            payload: &[]
-        };
+        PacketHeaders::from_ethernet_slice_with_registry(packet, &ParserRegistry::new())
+    }
 
-        //parse vlan header(s)
-        use ether_type::*;
+    /// Same as [`PacketHeaders::from_ethernet_slice`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_ethernet_slice_with_registry(packet: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::decode_ethernet(packet, registry, 0)
+    }
 
-        result.vlan = match ether_type {
-            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
-                use crate::VlanHeader::*;
-                let (outer, outer_rest) = SingleVlanHeader::from_slice(rest)?;
+    /// Decodes a network packet into different headers from a slice that starts with a
+    /// Linux cooked capture header (`LINKTYPE_LINUX_SLL`).
+    ///
+    /// Packets captured on the Linux "any" device start with this header instead of an
+    /// ethernet II header. The header is set in the `linux_sll` field & the following
+    /// headers are decoded based on the protocol type of the header (see
+    /// [`PacketHeaders::from_ether_type`] for the supported ether types).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{ether_type, LinuxSllHeader, PacketBuilder, PacketHeaders};
+    ///
+    /// let mut packet = Vec::new();
+    /// LinuxSllHeader {
+    ///     protocol_type: ether_type::IPV6,
+    ///     ..Default::default()
+    /// }.write(&mut packet).unwrap();
+    /// PacketBuilder::ipv6([1;16], [2;16], 20)
+    ///     .udp(21, 1234)
+    ///     .write(&mut packet, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// let decoded = PacketHeaders::from_linux_sll_slice(&packet).unwrap();
+    /// assert_eq!(ether_type::IPV6, decoded.linux_sll.unwrap().protocol_type());
+    /// assert_eq!(1234, decoded.transport.unwrap().udp().unwrap().destination_port);
+    /// ```
+    pub fn from_linux_sll_slice(packet: &'a [u8]) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::from_linux_sll_slice_with_registry(packet, &ParserRegistry::new())
+    }
 
-                //set the rest & ether_type for the following operations
-                rest = outer_rest;
-                ether_type = outer.ether_type;
+    /// Same as [`PacketHeaders::from_linux_sll_slice`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_linux_sll_slice_with_registry(packet: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        let (sll, rest) = LinuxSllHeader::from_slice(packet)?;
+        let mut result = PacketHeaders::decode_ether_type(sll.protocol_type, rest, registry, 0)?;
+        result.linux_sll = Some(LinuxCookedHeader::Sll(sll));
+        Ok(result)
+    }
 
-                //parse second vlan header if present
-                match ether_type {
-                    //second vlan tagging header
-                    VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
+    /// Same as [`PacketHeaders::from_linux_sll_slice`] but for packets starting with a
+    /// Linux cooked capture v2 header (`LINKTYPE_LINUX_SLL2`).
+    pub fn from_linux_sll2_slice(packet: &'a [u8]) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::from_linux_sll2_slice_with_registry(packet, &ParserRegistry::new())
+    }
 
-                        let (inner, inner_rest) = SingleVlanHeader::from_slice(rest)?;
+    /// Same as [`PacketHeaders::from_linux_sll2_slice`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_linux_sll2_slice_with_registry(packet: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        let (sll, rest) = LinuxSll2Header::from_slice(packet)?;
+        let mut result = PacketHeaders::decode_ether_type(sll.protocol_type, rest, registry, 0)?;
+        result.linux_sll = Some(LinuxCookedHeader::Sll2(sll));
+        Ok(result)
+    }
 
-                        //set the rest & ether_type for the following operations
-                        rest = inner_rest;
-                        ether_type = inner.ether_type;
+    /// Decodes a network packet into different headers from a slice that starts with a
+    /// BSD loopback header (`LINKTYPE_NULL` & `LINKTYPE_LOOP`).
+    ///
+    /// Packets captured on the loopback interface of macOS & the BSDs (`lo0`) start with
+    /// the 4 byte address family of the packet (see [`NullHeader`], the byte order is
+    /// detected automatically). IPv4 & IPv6 packets are decoded into the `ip` & `transport`
+    /// fields, packets of other address families are set as `payload`. The loopback header
+    /// itself is not stored in the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{PacketBuilder, PacketHeaders};
+    ///
+    /// // AF_INET6 of macOS in network byte order
+    /// let mut packet = vec![0, 0, 0, 30];
+    /// PacketBuilder::ipv6([1;16], [1;16], 20)
+    ///     .udp(21, 1234)
+    ///     .write(&mut packet, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// let decoded = PacketHeaders::from_null_loopback_slice(&packet).unwrap();
+    /// assert_eq!(1234, decoded.transport.unwrap().udp().unwrap().destination_port);
+    /// ```
+    pub fn from_null_loopback_slice(packet: &'a [u8]) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::from_null_loopback_slice_with_registry(packet, &ParserRegistry::new())
+    }
 
-                        Some(Double(DoubleVlanHeader{
-                            outer,
-                            inner
-                        }))
-                    },
-                    //no second vlan header detected -> single vlan header
-                    _ => Some(Single(outer))
-                }
-            },
-            //no vlan header
-            _ => None
-        };
+    /// Same as [`PacketHeaders::from_null_loopback_slice`] but additionally calls the parsers
+    /// registered in the given registry for ip numbers not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `transport_extension` field.
+    pub fn from_null_loopback_slice_with_registry(packet: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        let (header, rest) = NullHeader::from_slice(packet)?;
+        match header.ether_type() {
+            Some(ether_type) => PacketHeaders::decode_ether_type(ether_type, rest, registry, 0),
+            None => Ok(PacketHeaders{
+                payload: rest,
+                ..Default::default()
+            }),
+        }
+    }
 
-        //parse ip (if present)
-        match ether_type {
-            IPV4 => {
-                let (ip, ip_rest) = Ipv4Header::from_slice(rest)?;
-                let fragmented = ip.is_fragmenting_payload();
-                let (ip_ext, ip_protocol, ip_ext_rest) = Ipv4Extensions::from_slice(ip.protocol, ip_rest)?;
+    /// Decodes a network packet into different headers from the IEEE 802.11 MAC
+    /// header (`LINKTYPE_IEEE802_11`) downwards.
+    ///
+    /// Unprotected data frames starting with a LLC/SNAP header carrying an ether type are
+    /// decoded from the ether type downwards, the bodies of all other frames are set as
+    /// `payload`. The MAC & LLC/SNAP headers are not stored in the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{ether_type, ieee80211_frame_type, Ieee80211FrameControl, Ieee80211Header, LlcSnapHeader, PacketBuilder, PacketHeaders};
+    ///
+    /// let mut packet = Vec::new();
+    /// Ieee80211Header {
+    ///     frame_control: Ieee80211FrameControl {
+    ///         frame_type: ieee80211_frame_type::DATA,
+    ///         to_ds: true,
+    ///         ..Default::default()
+    ///     },
+    ///     address2: Some([2;6]),
+    ///     address3: Some([3;6]),
+    ///     sequence_control: Some(0),
+    ///     ..Default::default()
+    /// }.write(&mut packet).unwrap();
+    /// LlcSnapHeader::with_ether_type(ether_type::IPV6).write(&mut packet).unwrap();
+    /// PacketBuilder::ipv6([1;16], [2;16], 20)
+    ///     .udp(21, 1234)
+    ///     .write(&mut packet, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// let decoded = PacketHeaders::from_ieee80211_slice(&packet).unwrap();
+    /// assert_eq!(1234, decoded.transport.unwrap().udp().unwrap().destination_port);
+    /// ```
+    pub fn from_ieee80211_slice(packet: &'a [u8]) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::from_ieee80211_slice_with_registry(packet, &ParserRegistry::new())
+    }
 
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version4(ip, ip_ext));
+    /// Same as [`PacketHeaders::from_ieee80211_slice`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_ieee80211_slice_with_registry(packet: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::decode_ieee80211(packet, false, registry)
+    }
 
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(ip_protocol, rest)?;
+    /// Decodes a network packet into different headers from the radiotap header
+    /// (`LINKTYPE_IEEE802_11_RADIOTAP`) downwards.
+    ///
+    /// The IEEE 802.11 frame after the radiotap header is decoded like in
+    /// [`PacketHeaders::from_ieee80211_slice`]. A frame check sequence at the end of the
+    /// frame is not included in the `payload` & the padding after the MAC header is
+    /// skipped (radiotap flags [`radiotap_flags::FCS`] & [`radiotap_flags::DATA_PAD`]).
+    /// The radiotap header is not stored in the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{ieee80211_frame_type, Ieee80211FrameControl, Ieee80211Header, PacketHeaders};
+    ///
+    /// // radiotap header without fields
+    /// let mut packet = vec![0, 0, 8, 0, 0, 0, 0, 0];
+    /// // acknowledgment
+    /// Ieee80211Header {
+    ///     frame_control: Ieee80211FrameControl {
+    ///         frame_type: ieee80211_frame_type::CONTROL,
+    ///         subtype: 0xd,
+    ///         ..Default::default()
+    ///     },
+    ///     address1: [1;6],
+    ///     ..Default::default()
+    /// }.write(&mut packet).unwrap();
+    ///
+    /// let decoded = PacketHeaders::from_radiotap_slice(&packet).unwrap();
+    /// assert!(decoded.payload.is_empty());
+    /// ```
+    pub fn from_radiotap_slice(packet: &'a [u8]) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::from_radiotap_slice_with_registry(packet, &ParserRegistry::new())
+    }
 
-                    //assign to the output
-                    rest = transport_rest;
-                    result.transport = transport;
-                }
-            },
-            IPV6 => {
-                let (ip, ip_rest) = Ipv6Header::from_slice(rest)?;
-                let (ip_ext, next_header, ip_ext_rest) = Ipv6Extensions::from_slice(ip.next_header, ip_rest)?;
-                let fragmented = ip_ext.is_fragmenting_payload();
+    /// Same as [`PacketHeaders::from_radiotap_slice`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_radiotap_slice_with_registry(packet: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        let radiotap = RadiotapHeaderSlice::from_slice(packet)?;
+        let flags = radiotap.flags().unwrap_or(0);
+        let mut frame = &packet[radiotap.slice().len()..];
 
-                //set the ip result & rest
-                rest = ip_ext_rest;
-                result.ip = Some(IpHeader::Version6(ip, ip_ext));
+        // the frame check sequence is not part of the payload
+        if 0 != flags & radiotap_flags::FCS {
+            frame = &frame[..frame.len().saturating_sub(4)];
+        }
+        PacketHeaders::decode_ieee80211(frame, 0 != flags & radiotap_flags::DATA_PAD, registry)
+            .map_err(|err| err.add_slice_offset(radiotap.slice().len()))
+    }
 
-                // only try to decode the transport layer if the payload
-                // is not fragmented
-                if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(next_header, rest)?;
+    /// Decodes an IEEE 802.11 frame (optionally padded after the MAC header).
+    fn decode_ieee80211(frame: &'a [u8], data_pad: bool, registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        let header = Ieee80211HeaderSlice::from_slice(frame)?;
+        let mut body = &frame[header.slice().len()..];
+        if data_pad {
+            let pad = ((4 - header.slice().len() % 4) % 4).min(body.len());
+            body = &body[pad..];
+        }
 
-                    rest = transport_rest;
-                    result.transport = transport;
+        if header.has_llc_payload() {
+            if let Ok((snap, rest)) = LlcSnapHeader::from_slice(body) {
+                if let Some(ether_type) = snap.ether_type() {
+                    return PacketHeaders::decode_ether_type(ether_type, rest, registry, 0);
                 }
-
-            },
-            _ => {}
+            }
         }
-
-        //finally update the rest slice based on the cursor position
-        result.payload = rest;
-
-        Ok(result)
+        Ok(PacketHeaders{
+            payload: body,
+            ..Default::default()
+        })
     }
 
     /// Tries to decode a network packet into different headers using the
@@ -177,6 +402,12 @@ impl<'a> PacketHeaders<'a> {
     /// * `ether_type::VLAN_TAGGED_FRAME`
     /// * `ether_type::PROVIDER_BRIDGING`
     /// * `ether_type::VLAN_DOUBLE_TAGGED_FRAME`
+    /// * `ether_type::MPLS_UNICAST`
+    /// * `ether_type::MPLS_MULTICAST`
+    /// * `ether_type::NSH`
+    /// * `ether_type::PPPOE_DISCOVERY`
+    /// * `ether_type::PPPOE_SESSION`
+    /// * `ether_type::MACSEC`
     ///
     /// If an unsupported ether type is given the given slice will be set as payload
     /// and all other fields will be set to `None`.
@@ -218,19 +449,92 @@ impl<'a> PacketHeaders<'a> {
     ///     }
     /// }
     /// ```
-    pub fn from_ether_type(mut ether_type: u16, data: &'a [u8]) -> Result<PacketHeaders, ReadError> {
+    pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> Result<PacketHeaders, ReadError> {
+        PacketHeaders::from_ether_type_with_registry(ether_type, data, &ParserRegistry::new())
+    }
+
+    /// Same as [`PacketHeaders::from_ether_type`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_ether_type_with_registry(ether_type: u16, data: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
+        PacketHeaders::decode_ether_type(ether_type, data, registry, 0)
+    }
+
+    /// decodes a packet starting with an ethernet II header (`depth` is the number of enclosing tunnels)
+    fn decode_ethernet(packet: &'a [u8], registry: &ParserRegistry, depth: usize) -> Result<PacketHeaders<'a>, ReadError> {
+        let (ethernet, rest) = Ethernet2Header::from_slice(packet)?;
+        let mut result = match registry.ether_type_length_policy().classify(ethernet.ether_type)? {
+            EtherTypeLength::EtherType(ether_type) => PacketHeaders::decode_ether_type(ether_type, rest, registry, depth)?,
+            EtherTypeLength::Length(len) => {
+                // IEEE 802.3 frame, only LLC/SNAP payloads can be decoded further
+                let payload = &rest[..len.min(rest.len())];
+                match LlcSnapHeader::from_slice(payload) {
+                    Ok((snap, snap_rest)) if snap.ether_type().is_some() => {
+                        PacketHeaders::decode_ether_type(snap.protocol_id, snap_rest, registry, depth)?
+                    },
+                    _ => PacketHeaders{
+                        payload,
+                        ..Default::default()
+                    },
+                }
+            },
+            EtherTypeLength::Undefined => PacketHeaders{
+                payload: rest,
+                ..Default::default()
+            },
+        };
+        result.link = Some(ethernet);
+        Ok(result)
+    }
+
+    /// decodes a packet starting with the header identified by `ether_type` (`depth` is the number of enclosing tunnels)
+    fn decode_ether_type(mut ether_type: u16, data: &'a [u8], registry: &ParserRegistry, depth: usize) -> Result<PacketHeaders<'a>, ReadError> {
         let mut rest = data;
         let mut result = PacketHeaders{
             link: None,
+            linux_sll: None,
+            macsec: None,
             vlan: None,
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: None,
+            raw_layers: Vec::new(),
             transport: None,
+            transport_extension: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &[]
         };
 
-        //parse vlan header(s)
         use ether_type::*;
 
+        //parse macsec sectag (continue with the protected frame of cleartext data)
+        if MACSEC == ether_type {
+            let (macsec, macsec_rest) = MacsecHeader::from_slice(rest)?;
+            rest = macsec_rest;
+            let next_ether_type = macsec.ether_type;
+            result.macsec = Some(macsec);
+            match next_ether_type {
+                // nested sectags are left in the payload
+                Some(MACSEC) | None => {
+                    result.payload = rest;
+                    return Ok(result);
+                },
+                Some(value) => ether_type = value,
+            }
+        }
+
+        //parse vlan header(s)
+
         result.vlan = match ether_type {
             VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => {
                 use crate::VlanHeader::*;
@@ -264,9 +568,52 @@ impl<'a> PacketHeaders<'a> {
             _ => None
         };
 
+        //capture additional vlan tags that can not be stored in the vlan field
+        if result.vlan.is_some() {
+            read_raw_vlan_tags(&mut ether_type, &mut rest, &mut result.raw_layers);
+            result.check_vlan_limit(registry)?;
+        }
+
+        let ip_ether_type = if let MPLS_UNICAST | MPLS_MULTICAST = ether_type {
+            //parse mpls label stack
+            let mpls = MplsStackSlice::from_slice(rest)?;
+            registry.limits().check(ParseLimit::MplsLabels, mpls.len())?;
+            rest = &rest[mpls.slice().len()..];
+            result.mpls = Some(mpls);
+            MplsStackSlice::payload_ether_type(rest)
+        } else if let PPPOE_DISCOVERY | PPPOE_SESSION = ether_type {
+            //parse pppoe header (continue with the ip packet of session data)
+            let (pppoe, pppoe_rest) = PppoeHeader::from_slice(rest)?;
+            rest = pppoe_rest;
+            let next_ether_type = pppoe.ether_type();
+            result.pppoe = Some(pppoe);
+            next_ether_type
+        } else if ARP == ether_type {
+            //parse arp packet (the rest is padding)
+            let (arp, arp_rest) = ArpPacket::from_slice(rest)?;
+            rest = arp_rest;
+            result.arp = Some(arp);
+            None
+        } else if NSH == ether_type && depth < registry.limits().max_tunnel_depth {
+            //parse nsh header & the encapsulated packet
+            rest = result.decode_nsh(rest, registry, depth)?;
+            None
+        } else {
+            //parse custom header (if a parser is registered for the ether type)
+            if let Some(extension) = registry.parse_ether_type(ether_type, rest) {
+                let extension = extension?;
+                rest = &rest[extension.header.len()..];
+                result.link_extension = Some(extension);
+            }
+            match &result.link_extension {
+                Some(extension) => extension.next_ether_type,
+                None => Some(ether_type),
+            }
+        };
+
         //parse ip (if present)
-        match ether_type {
-            IPV4 => {
+        match ip_ether_type {
+            Some(IPV4) => {
                 let (ip, ip_rest) = Ipv4Header::from_slice(rest)?;
                 let fragmented = ip.is_fragmenting_payload();
                 let (ip_ext, ip_protocol, ip_ext_rest) = Ipv4Extensions::from_slice(ip.protocol, ip_rest)?;
@@ -274,21 +621,18 @@ impl<'a> PacketHeaders<'a> {
                 //set the ip result & rest
                 rest = ip_ext_rest;
                 result.ip = Some(IpHeader::Version4(ip, ip_ext));
+                result.check_ip_limits(registry)?;
 
                 // only try to decode the transport layer if the payload
                 // is not fragmented
                 if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(ip_protocol, rest)?;
-
-                    //assign to the output
-                    rest = transport_rest;
-                    result.transport = transport;
+                    //parse the transport layer (or tunnel)
+                    rest = result.decode_ip_payload(ip_protocol, rest, registry, depth)?;
                 }
             },
-            IPV6 => {
+            Some(IPV6) => {
                 let (ip, ip_rest) = Ipv6Header::from_slice(rest)?;
-                let (ip_ext, next_header, ip_ext_rest) = Ipv6Extensions::from_slice(ip.next_header, ip_rest)?;
+                let (ip_ext, mut next_header, ip_ext_rest) = Ipv6Extensions::from_slice(ip.next_header, ip_rest)?;
                 let fragmented = ip_ext.is_fragmenting_payload();
 
                 //set the ip result & rest
@@ -298,11 +642,15 @@ impl<'a> PacketHeaders<'a> {
                 // only try to decode the transport layer if the payload
                 // is not fragmented
                 if false == fragmented {
-                    //parse the transport layer
-                    let (transport, transport_rest) = read_transport(next_header, rest)?;
+                    //capture extension headers that can not be stored in the ipv6 extensions
+                    read_raw_ipv6_extensions(&mut next_header, &mut rest, &mut result.raw_layers)?;
 
-                    rest = transport_rest;
-                    result.transport = transport;
+                    result.check_ip_limits(registry)?;
+
+                    //parse the transport layer (or tunnel)
+                    rest = result.decode_ip_payload(next_header, rest, registry, depth)?;
+                } else {
+                    result.check_ip_limits(registry)?;
                 }
 
             },
@@ -356,43 +704,220 @@ impl<'a> PacketHeaders<'a> {
     /// }
     /// ```
     pub fn from_ip_slice(packet: &[u8]) -> Result<PacketHeaders, ReadError> {
+        PacketHeaders::from_ip_slice_with_registry(packet, &ParserRegistry::new())
+    }
+
+    /// Same as [`PacketHeaders::from_ip_slice`] but additionally calls the parsers
+    /// registered in the given registry for ip numbers not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `transport_extension` field.
+    pub fn from_ip_slice_with_registry(packet: &'a [u8], registry: &ParserRegistry) -> Result<PacketHeaders<'a>, ReadError> {
         let mut result = PacketHeaders {
             link: None,
+            linux_sll: None,
+            macsec: None,
             vlan: None,
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: None,
+            raw_layers: Vec::new(),
             transport: None,
+            transport_extension: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &[],
         };
 
         let (transport_proto, rest) = {
-            let (ip, transport_proto, rest) = IpHeader::from_slice(packet)?;
+            let (ip, mut transport_proto, mut rest) = IpHeader::from_slice(packet)?;
+
+            //capture extension headers that can not be stored in the ipv6 extensions
+            if let IpHeader::Version6(_, ext) = &ip {
+                if false == ext.is_fragmenting_payload() {
+                    read_raw_ipv6_extensions(&mut transport_proto, &mut rest, &mut result.raw_layers)?;
+                }
+            }
+
             // update output
             result.ip = Some(ip);
+            result.check_ip_limits(registry)?;
             (transport_proto, rest)
         };
 
-        // try to parse the transport header
-        let (transport, rest) = read_transport(transport_proto, rest)?;
-
-        // update output
-        result.transport = transport;
-        result.payload = rest;
+        // try to parse the transport header (or tunnel)
+        result.payload = result.decode_ip_payload(transport_proto, rest, registry, 0)?;
 
         Ok(result)
     }
 
+    /// checks the number of vlan tags (including the raw layers) against the limits
+    fn check_vlan_limit(&self, registry: &ParserRegistry) -> Result<(), ReadError> {
+        let decoded = match &self.vlan {
+            Some(VlanHeader::Single(_)) => 1,
+            Some(VlanHeader::Double(_)) => 2,
+            None => 0,
+        };
+        let raw = self.raw_layers.iter().filter(|raw| matches!(raw.kind, RawLayerKind::VlanTag(_))).count();
+        registry.limits().check(ParseLimit::VlanTags, decoded + raw)
+    }
+
+    /// checks the ipv4 options & the number of ipv6 extension headers (including the raw layers) against the limits
+    fn check_ip_limits(&self, registry: &ParserRegistry) -> Result<(), ReadError> {
+        match &self.ip {
+            Some(IpHeader::Version4(header, _)) => {
+                registry.limits().check(ParseLimit::OptionBytes, header.options().len())
+            },
+            Some(IpHeader::Version6(_, ext)) => {
+                let raw = self.raw_layers.iter().filter(|raw| matches!(raw.kind, RawLayerKind::Ipv6Extension(_))).count();
+                registry.limits().check(ParseLimit::Ipv6ExtensionHeaders, ipv6_extensions_len(ext) + raw)
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// decodes the transport header or tunnel after the ip header(s) & returns the rest of the slice
+    fn decode_ip_payload(&mut self, protocol: u8, rest: &'a [u8], registry: &ParserRegistry, depth: usize) -> Result<&'a [u8], ReadError> {
+        let decode_tunnel = depth < registry.limits().max_tunnel_depth;
+
+        // ether type & start of the encapsulated packet (decoded with a single
+        // call at the end to keep the stack usage of the recursion low)
+        let (inner_ether_type, payload) = if ip_number::GRE == protocol && decode_tunnel {
+            let (gre, mut gre_rest) = match GreHeader::from_slice(rest) {
+                Ok(value) => value,
+                // unsupported versions (e.g. enhanced GRE used by PPTP) &
+                // headers with routing information are left in the payload
+                Err(ReadError::GreUnsupportedVersion(_)) | Err(ReadError::GreRoutingNotSupported) => return Ok(rest),
+                Err(err) => return Err(err),
+            };
+            use ether_type::{ERSPAN_TYPE_2, ERSPAN_TYPE_3, TRANSPARENT_ETHERNET_BRIDGING};
+            let inner_ether_type = match gre.protocol_type {
+                // erspan type I (no erspan header) is only identified by the missing sequence number
+                ERSPAN_TYPE_2 if gre.sequence_number.is_none() => Some(TRANSPARENT_ETHERNET_BRIDGING),
+                ERSPAN_TYPE_2 | ERSPAN_TYPE_3 => {
+                    let erspan = ErspanHeaderSlice::from_slice(gre_rest)?;
+                    gre_rest = &gre_rest[erspan.slice().len()..];
+                    self.erspan = Some(erspan.to_header());
+                    if erspan.is_ethernet_frame() {
+                        Some(TRANSPARENT_ETHERNET_BRIDGING)
+                    } else {
+                        None
+                    }
+                },
+                protocol_type => Some(protocol_type),
+            };
+            self.gre = Some(gre);
+            // the payload contains the encapsulated packet
+            (inner_ether_type, gre_rest)
+        } else if ip_number::ENCAP_SEC == protocol {
+            let (esp, esp_rest) = EspHeader::from_slice(rest)?;
+            self.esp = Some(esp);
+            // the payload contains the encrypted data
+            return Ok(esp_rest);
+        } else {
+            let (transport, transport_extension, transport_rest) = read_transport(protocol, rest, registry)?;
+            self.transport = transport;
+            self.transport_extension = transport_extension;
+
+            let application = match &self.transport {
+                Some(TransportHeader::Udp(udp)) if decode_tunnel => {
+                    registry.udp_port_protocol(udp.destination_port)
+                        .or_else(|| registry.udp_port_protocol(udp.source_port))
+                },
+                _ => None,
+            };
+            match application {
+                Some(ApplicationProtocol::Vxlan) => {
+                    let (vxlan, vxlan_rest) = VxlanHeader::from_slice(transport_rest)?;
+                    self.vxlan = Some(vxlan);
+                    // the payload contains the encapsulated frame
+                    (Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING), vxlan_rest)
+                },
+                Some(ApplicationProtocol::Geneve) => {
+                    let (geneve, geneve_rest) = GeneveHeader::from_slice(transport_rest)?;
+                    let protocol_type = geneve.protocol_type;
+                    self.geneve = Some(geneve);
+                    // the payload contains the encapsulated packet
+                    (Some(protocol_type), geneve_rest)
+                },
+                Some(ApplicationProtocol::GtpU) => return self.decode_gtpu(transport_rest, registry, depth),
+                _ => return Ok(transport_rest)
+            }
+        };
+
+        if let Some(ether_type) = inner_ether_type {
+            self.inner = PacketHeaders::decode_inner(ether_type, payload, registry, depth)?;
+        }
+        Ok(payload)
+    }
+
+    /// decodes a nsh header & the packet following it & returns the rest of the slice
+    fn decode_nsh(&mut self, rest: &'a [u8], registry: &ParserRegistry, depth: usize) -> Result<&'a [u8], ReadError> {
+        let (nsh, nsh_rest) = NshHeader::from_slice(rest)?;
+        if let Some(next_ether_type) = nsh.next_ether_type() {
+            self.inner = PacketHeaders::decode_inner(next_ether_type, nsh_rest, registry, depth)?;
+        }
+        self.nsh = Some(nsh);
+        // the payload contains the encapsulated packet
+        Ok(nsh_rest)
+    }
+
+    /// decodes a gtp-u header & the ip packet of G-PDUs & returns the rest of the slice
+    fn decode_gtpu(&mut self, rest: &'a [u8], registry: &ParserRegistry, depth: usize) -> Result<&'a [u8], ReadError> {
+        let (gtpu, gtpu_rest) = GtpuHeader::from_slice(rest)?;
+        if gtpu_message_type::G_PDU == gtpu.message_type {
+            let inner_ether_type = match gtpu_rest.first().map(|value| value >> 4) {
+                Some(4) => Some(ether_type::IPV4),
+                Some(6) => Some(ether_type::IPV6),
+                _ => None,
+            };
+            if let Some(inner_ether_type) = inner_ether_type {
+                self.inner = PacketHeaders::decode_inner(inner_ether_type, gtpu_rest, registry, depth)?;
+            }
+        }
+        self.gtpu = Some(gtpu);
+        // the payload contains the encapsulated packet
+        Ok(gtpu_rest)
+    }
+
+    /// decodes the encapsulated packet of a tunnel based on its protocol type
+    /// (packets that can not be decoded are left in the payload, only exceeded
+    /// parse limits are returned as errors)
+    fn decode_inner(protocol_type: u16, rest: &'a [u8], registry: &ParserRegistry, depth: usize) -> Result<Option<Box<PacketHeaders<'a>>>, ReadError> {
+        let result = if ether_type::TRANSPARENT_ETHERNET_BRIDGING == protocol_type {
+            PacketHeaders::decode_ethernet(rest, registry, depth + 1)
+        } else {
+            PacketHeaders::decode_ether_type(protocol_type, rest, registry, depth + 1)
+        };
+        match result {
+            Ok(inner) => Ok(Some(Box::new(inner))),
+            Err(err @ ReadError::ParseLimitExceeded(_)) => Err(err),
+            Err(_) => Ok(None),
+        }
+    }
+
     /// If the slice in the `payload` field contains an ethernet payload
     /// this method returns the ether type number describing the payload type.
     ///
-    /// The ether type number can come from an ethernet II header or a
-    /// VLAN header depending on which headers are present.
+    /// The ether type number can come from an ethernet II header, a Linux
+    /// cooked capture header, a MACsec SecTAG or a VLAN header depending on
+    /// which headers are present.
     ///
     /// In case that `ip` and/or `transport` fields are the filled None
     /// is returned, as the payload contents then are defined by a
     /// lower layer protocol described in these fields.
     pub fn payload_ether_type(&self) -> Option<u16> {
-        if self.ip.is_some() || self.transport.is_some() {
+        if self.ip.is_some() || self.transport.is_some() || self.mpls.is_some() || self.pppoe.is_some() || self.arp.is_some() {
             None
+        } else if let Some(extension) = &self.link_extension {
+            extension.next_ether_type
         } else {
             if let Some(vlan) = &self.vlan {
                 use VlanHeader::*;
@@ -404,36 +929,170 @@ impl<'a> PacketHeaders<'a> {
                         Some(d.inner.ether_type)
                     }
                 }
+            } else if let Some(macsec) = &self.macsec {
+                macsec.ether_type
             } else {
                 if let Some(link) = &self.link {
                     Some(link.ether_type)
                 } else {
-                    None
+                    self.linux_sll.as_ref().map(|sll| sll.protocol_type())
                 }
             }
         }
     }
 }
 
+/// Type of a [`RawLayer`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum RawLayerKind {
+    /// Additional vlan tag after a double vlan header (the value is the
+    /// ether type identifying the tag).
+    VlanTag(u16),
+    /// IPv6 extension header that could not be stored in the [`Ipv6Extensions`]
+    /// (e.g. a mobility header or a second fragmentation header). The value is
+    /// the ip number of the header.
+    Ipv6Extension(u8),
+}
+
+/// Layer with a known length that [`PacketHeaders`] could not decode.
+///
+/// Instead of being merged into the payload the original bytes of these layers
+/// are kept, so the packet can be serialized again byte for byte. Vlan tags are
+/// located after the `vlan` headers & ipv6 extension headers after the
+/// extension headers of the `ip` field. The ether type or next header fields of
+/// the preceding headers still reference the raw layers.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ip_number, Ipv6Header, PacketHeaders, RawLayerKind};
+///
+/// let mut packet = Vec::new();
+/// Ipv6Header {
+///     traffic_class: 0,
+///     flow_label: 0,
+///     payload_length: 8,
+///     next_header: ip_number::MOBILITY,
+///     hop_limit: 64,
+///     source: [0;16],
+///     destination: [0;16],
+/// }.write(&mut packet).unwrap();
+/// // mobility header (not supported by Ipv6Extensions)
+/// packet.extend_from_slice(&[59, 0, 0, 0, 0, 0, 0, 0]);
+///
+/// let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+/// assert_eq!(RawLayerKind::Ipv6Extension(ip_number::MOBILITY), headers.raw_layers[0].kind);
+/// assert_eq!(&packet[40..], headers.raw_layers[0].data);
+/// assert!(headers.payload.is_empty());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RawLayer<'a> {
+    /// Type of the layer.
+    pub kind: RawLayerKind,
+    /// Original bytes of the layer.
+    pub data: &'a [u8],
+}
+
+/// captures vlan tags after a double vlan header as raw layers
+fn read_raw_vlan_tags<'a>(ether_type: &mut u16, rest: &mut &'a [u8], raw_layers: &mut Vec<RawLayer<'a>>) {
+    use ether_type::*;
+    while let VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME = *ether_type {
+        // truncated tags stay in the payload
+        if rest.len() < SingleVlanHeader::SERIALIZED_SIZE {
+            break;
+        }
+        let (data, tag_rest) = rest.split_at(SingleVlanHeader::SERIALIZED_SIZE);
+        raw_layers.push(RawLayer {
+            kind: RawLayerKind::VlanTag(*ether_type),
+            data,
+        });
+        *ether_type = u16::from_be_bytes([data[2], data[3]]);
+        *rest = tag_rest;
+    }
+}
+
+/// captures ipv6 extension headers that were not decoded by `Ipv6Extensions::from_slice` as raw layers
+fn read_raw_ipv6_extensions<'a>(next_header: &mut u8, rest: &mut &'a [u8], raw_layers: &mut Vec<RawLayer<'a>>) -> Result<(), ReadError> {
+    use ip_number::*;
+    loop {
+        let (len, header_next) = match *next_header {
+            IPV6_HOP_BY_HOP => return Err(ReadError::Ipv6HopByHopHeaderNotAtStart),
+            IPV6_FRAG => {
+                let slice = Ipv6FragmentHeaderSlice::from_slice(rest)?;
+                (slice.slice().len(), slice.next_header())
+            },
+            AUTH => {
+                let slice = IpAuthenticationHeaderSlice::from_slice(rest)?;
+                (slice.slice().len(), slice.next_header())
+            },
+            value if Ipv6RawExtensionHeader::header_type_supported(value) => {
+                let slice = Ipv6RawExtensionHeaderSlice::from_slice(rest)?;
+                (slice.slice().len(), slice.next_header())
+            },
+            _ => return Ok(()),
+        };
+        let (data, layer_rest) = rest.split_at(len);
+        raw_layers.push(RawLayer {
+            kind: RawLayerKind::Ipv6Extension(*next_header),
+            data,
+        });
+        *next_header = header_next;
+        *rest = layer_rest;
+    }
+}
+
+/// number of extension headers decoded into the ipv6 extensions
+fn ipv6_extensions_len(ext: &Ipv6Extensions) -> usize {
+    [
+        ext.hop_by_hop_options.is_some(),
+        ext.destination_options.is_some(),
+        ext.routing.is_some(),
+        ext.routing.as_ref().is_some_and(|routing| routing.final_destination_options.is_some()),
+        ext.shim6.is_some(),
+        ext.fragment.is_some(),
+        ext.auth.is_some(),
+    ].iter().filter(|present| **present).count()
+}
+
+/// decoded transport header, custom transport header & the rest of the slice
+type TransportParts<'a> = (Option<TransportHeader>, Option<TransportExtensionSlice<'a>>, &'a [u8]);
+
 /// helper function to process transport headers
-fn read_transport(
+fn read_transport<'a>(
     protocol: u8,
-    rest: &[u8],
-) -> Result<(Option<TransportHeader>, &[u8]), ReadError> {
+    rest: &'a [u8],
+    registry: &ParserRegistry,
+) -> Result<TransportParts<'a>, ReadError> {
     use crate::ip_number::*;
     match protocol {
         ICMP => {
             Ok(Icmpv4Header::from_slice(rest)?)
-            .map( |value| (Some(TransportHeader::Icmpv4(value.0)), value.1))
+            .map( |value| (Some(TransportHeader::Icmpv4(value.0)), None, value.1))
         },
         IPV6_ICMP => {
             Ok(Icmpv6Header::from_slice(rest)?)
-            .map( |value| (Some(TransportHeader::Icmpv6(value.0)), value.1))
+            .map( |value| (Some(TransportHeader::Icmpv6(value.0)), None, value.1))
         },
         UDP => Ok(UdpHeader::from_slice(rest)
-            .map(|value| (Some(TransportHeader::Udp(value.0)), value.1))?),
-        TCP => Ok(TcpHeader::from_slice(rest)
-            .map(|value| (Some(TransportHeader::Tcp(value.0)), value.1))?),
-        _ => Ok((None, rest)),
+            .map(|value| (Some(TransportHeader::Udp(value.0)), None, value.1))?),
+        TCP => {
+            let (tcp, tcp_rest) = TcpHeader::from_slice(rest)?;
+            registry.limits().check(ParseLimit::OptionBytes, tcp.options_len())?;
+            Ok((Some(TransportHeader::Tcp(tcp)), None, tcp_rest))
+        },
+        UDP_LITE => Ok(UdpLiteHeader::from_slice(rest)
+            .map(|value| (Some(TransportHeader::UdpLite(value.0)), None, value.1))?),
+        DCCP => Ok(DccpHeader::from_slice(rest)
+            .map(|value| (Some(TransportHeader::Dccp(value.0)), None, value.1))?),
+        SCTP => Ok(SctpHeader::from_slice(rest)
+            .map(|value| (Some(TransportHeader::Sctp(value.0)), None, value.1))?),
+        value => match registry.parse_ip_number(value, rest) {
+            Some(extension) => {
+                let extension = extension?;
+                let extension_rest = &rest[extension.header.len()..];
+                Ok((None, Some(extension), extension_rest))
+            },
+            None => Ok((None, None, rest)),
+        },
     }
 }
\ No newline at end of file
@@ -0,0 +1,145 @@
+use super::*;
+
+#[cfg(feature = "base64")]
+use base64::Engine;
+
+/// Errors that can occur while decoding a hex or base64 encoded packet
+/// (see `PacketWithMeta::from_hex_str` & `PacketWithMeta::from_base64`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PacketEncodingError {
+    /// The hex string contains a character that is neither a hex digit nor
+    /// an allowed separator (requires the `hex` feature).
+    #[cfg(feature = "hex")]
+    HexInvalidCharacter{
+        /// Position of the character in the string (in bytes).
+        index: usize,
+        /// The invalid character.
+        character: char,
+    },
+    /// The hex string contains an odd number of hex digits (requires the `hex` feature).
+    /// The value is the number of hex digits found.
+    #[cfg(feature = "hex")]
+    HexOddLength(usize),
+    /// The base64 string could not be decoded (requires the `base64` feature).
+    #[cfg(feature = "base64")]
+    Base64(base64::DecodeError),
+}
+
+impl Error for PacketEncodingError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "base64")]
+            PacketEncodingError::Base64(err) => Some(err),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PacketEncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use PacketEncodingError::*;
+        match self {
+            #[cfg(feature = "hex")]
+            HexInvalidCharacter{ index, character } => {
+                write!(f, "PacketEncodingError: Invalid character '{}' at position {} in hex string.", character.escape_debug(), index)
+            },
+            #[cfg(feature = "hex")]
+            HexOddLength(len) => {
+                write!(f, "PacketEncodingError: Hex string contains an odd number of hex digits ({}).", len)
+            },
+            #[cfg(feature = "base64")]
+            Base64(err) => {
+                write!(f, "PacketEncodingError: Invalid base64 string: {}", err)
+            },
+        }
+    }
+}
+
+impl PacketWithMeta<Vec<u8>> {
+    /// Decodes a hex encoded packet (requires the `hex` feature).
+    ///
+    /// The string can optionally start with `0x`. Whitespaces as well as `:`
+    /// and `-` separators between the bytes are ignored, so strings like
+    /// the wireshark "Copy as Hex Stream" output (`"4500001c..."`) or
+    /// `"45:00:00:1c"` are supported.
+    ///
+    /// The metadata is initialized with [`PacketMeta::from_captured`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{PacketWithMeta, SlicedPacket};
+    ///
+    /// let packet = PacketWithMeta::from_hex_str(
+    ///     "4500001c000040001411e37dc0a80101c0a80102001504d2000877a3"
+    /// ).unwrap();
+    /// assert_eq!(28, packet.meta.captured_len);
+    ///
+    /// let sliced = SlicedPacket::from_ip(packet.data()).unwrap();
+    /// assert!(sliced.transport.is_some());
+    /// ```
+    #[cfg(feature = "hex")]
+    pub fn from_hex_str(text: &str) -> Result<PacketWithMeta<Vec<u8>>, PacketEncodingError> {
+        use PacketEncodingError::*;
+
+        let trimmed = text.trim_start();
+        let (start, digits) = match trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")) {
+            Some(rest) => (text.len() - rest.len(), rest),
+            None => (text.len() - trimmed.len(), trimmed),
+        };
+
+        let mut data = Vec::with_capacity(digits.len() / 2);
+        let mut high: Option<u8> = None;
+        let mut digit_count = 0;
+        for (index, character) in digits.char_indices() {
+            if character.is_whitespace() || ':' == character || '-' == character {
+                continue;
+            }
+            let value = match character.to_digit(16) {
+                Some(value) => value as u8,
+                None => return Err(HexInvalidCharacter{ index: start + index, character }),
+            };
+            digit_count += 1;
+            high = match high {
+                Some(high) => {
+                    data.push((high << 4) | value);
+                    None
+                },
+                None => Some(value),
+            };
+        }
+        if high.is_some() {
+            return Err(HexOddLength(digit_count));
+        }
+        Ok(PacketWithMeta::new(PacketMeta::from_captured(&data), data))
+    }
+
+    /// Decodes a base64 encoded packet (standard alphabet with padding,
+    /// requires the `base64` feature).
+    ///
+    /// Whitespaces (e.g. line breaks in wrapped base64 strings) are ignored.
+    /// The metadata is initialized with [`PacketMeta::from_captured`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{PacketWithMeta, SlicedPacket};
+    ///
+    /// let packet = PacketWithMeta::from_base64(
+    ///     "RQAAHAAAQAAUEeN9wKgBAcCoAQIAFQTSAAh3ow=="
+    /// ).unwrap();
+    /// assert_eq!(28, packet.meta.captured_len);
+    ///
+    /// let sliced = SlicedPacket::from_ip(packet.data()).unwrap();
+    /// assert!(sliced.transport.is_some());
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn from_base64(text: &str) -> Result<PacketWithMeta<Vec<u8>>, PacketEncodingError> {
+        let compact: String = text.chars().filter(|c| false == c.is_whitespace()).collect();
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(compact)
+            .map_err(PacketEncodingError::Base64)?;
+        Ok(PacketWithMeta::new(PacketMeta::from_captured(&data), data))
+    }
+}
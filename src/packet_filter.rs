@@ -234,4 +234,396 @@ impl Filter {
             }
         })
     }
-}
\ No newline at end of file
+}
+/// Classic BPF instruction with the same memory layout as the
+/// `struct sock_filter` of the linux kernel (so a slice of instructions can
+/// be passed to `SO_ATTACH_FILTER` on a raw socket).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct BpfInstruction {
+    pub code: u16,
+    pub jt: u8,
+    pub jf: u8,
+    pub k: u32,
+}
+
+/// Opcodes of the classic BPF instructions generated by [`Filter::to_bpf`].
+pub mod bpf_code {
+    /// `ld [k]` (load 32 bit word at the absolute offset k).
+    pub const LD_W_ABS: u16 = 0x20;
+    /// `ldh [k]` (load 16 bit half word at the absolute offset k).
+    pub const LD_H_ABS: u16 = 0x28;
+    /// `ldb [k]` (load byte at the absolute offset k).
+    pub const LD_B_ABS: u16 = 0x30;
+    /// `ldh [x + k]` (load 16 bit half word at the offset x + k).
+    pub const LD_H_IND: u16 = 0x48;
+    /// `ldx 4*([k]&0xf)` (load the ipv4 header length at offset k into x).
+    pub const LDX_B_MSH: u16 = 0xb1;
+    /// `and #k`
+    pub const ALU_AND_K: u16 = 0x54;
+    /// `jeq #k, jt, jf`
+    pub const JMP_JEQ_K: u16 = 0x15;
+    /// `jset #k, jt, jf`
+    pub const JMP_JSET_K: u16 = 0x45;
+    /// `ret #k`
+    pub const RET_K: u16 = 0x06;
+}
+
+/// Errors that can occur when compiling a [`Filter`] to classic BPF.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum BpfCompileError {
+    /// The filter only matches packets without a link layer which can not
+    /// be expressed for the ethernet frames received via raw sockets.
+    NoLinkLayer,
+    /// Matching packets without a transport layer (`ElementFilter::No`) is
+    /// not supported.
+    NoTransportLayer,
+}
+
+impl Error for BpfCompileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for BpfCompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use BpfCompileError::*;
+        match self {
+            NoLinkLayer => write!(f, "BpfCompileError: Filters for packets without a link layer can not be compiled (classic BPF programs operate on ethernet II frames)."),
+            NoTransportLayer => write!(f, "BpfCompileError: Filters for packets without a transport layer can not be compiled."),
+        }
+    }
+}
+
+impl Filter {
+    /// Compiles the filter to a classic BPF program operating on ethernet II
+    /// frames (e.g. to attach it to an `AF_PACKET` raw socket), so packets
+    /// can be pre-filtered in the kernel & post-filtered in userspace with
+    /// [`Filter::applies_to_slice`] using the same filter.
+    ///
+    /// The program returns `u32::MAX` for matching packets and 0 otherwise.
+    /// Packets are only checked at fixed offsets, so there are some cases in
+    /// which the program rejects packets that [`Filter::applies_to_slice`]
+    /// would accept:
+    ///
+    /// * ip & transport filters don't match packets with a MPLS label stack.
+    /// * transport filters don't match packets containing ipv4 or ipv6
+    ///   extension headers.
+    /// * vlan tags are expected to be part of the packet data (linux strips
+    ///   them on receive if vlan offloading is enabled).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::packet_filter::*;
+    ///
+    /// let filter = Filter {
+    ///     transport: ElementFilter::Some(TransportFilter::Udp {
+    ///         source_port: None,
+    ///         destination_port: Some(53),
+    ///     }),
+    ///     ..Default::default()
+    /// };
+    /// let program = filter.to_bpf().unwrap();
+    /// assert_eq!(bpf_code::RET_K, program.last().unwrap().code);
+    /// ```
+    pub fn to_bpf(&self) -> Result<Vec<BpfInstruction>, BpfCompileError> {
+        use BpfCompileError::*;
+
+        if let ElementFilter::No = self.link {
+            return Err(NoLinkLayer);
+        }
+        if let ElementFilter::No = self.transport {
+            return Err(NoTransportLayer);
+        }
+
+        // the layer 3 offset depends on the vlan headers & the transport
+        // offsets on the ip version, so an instruction block is generated
+        // for every combination that can match
+        let l3_unchecked = matches!(
+            (&self.ip, &self.transport),
+            (ElementFilter::Any, ElementFilter::Any)
+        );
+        let vlans = match &self.vlan {
+            ElementFilter::Any => if l3_unchecked {
+                vec![BpfVlan::Unchecked]
+            } else {
+                vec![BpfVlan::No, BpfVlan::Single(None), BpfVlan::Double(None, None)]
+            },
+            ElementFilter::No => vec![BpfVlan::No],
+            ElementFilter::Some(VlanFilter::Single(id)) => vec![BpfVlan::Single(*id)],
+            ElementFilter::Some(VlanFilter::Double{ outer_identifier, inner_identifier }) => {
+                vec![BpfVlan::Double(*outer_identifier, *inner_identifier)]
+            },
+        };
+        let ips = match &self.ip {
+            ElementFilter::Any => match self.transport {
+                ElementFilter::Any => vec![BpfIp::Unchecked],
+                _ => vec![
+                    BpfIp::Ipv4{ source: None, destination: None },
+                    BpfIp::Ipv6{ source: None, destination: None }
+                ],
+            },
+            ElementFilter::No => match self.transport {
+                ElementFilter::Any => vec![BpfIp::No],
+                // a transport layer requires an ip layer
+                _ => Vec::new(),
+            },
+            ElementFilter::Some(IpFilter::Ipv4{ source, destination }) => {
+                vec![BpfIp::Ipv4{ source: *source, destination: *destination }]
+            },
+            ElementFilter::Some(IpFilter::Ipv6{ source, destination }) => {
+                vec![BpfIp::Ipv6{ source: *source, destination: *destination }]
+            },
+        };
+
+        let mut program = Vec::new();
+        for vlan in &vlans {
+            for ip in &ips {
+                let mut block = BpfBlock::default();
+                if let ElementFilter::Some(LinkFilter::Ethernet2{ source, destination }) = &self.link {
+                    if let Some(destination) = destination {
+                        block.mac_eq(0, destination);
+                    }
+                    if let Some(source) = source {
+                        block.mac_eq(6, source);
+                    }
+                }
+                let ether_type_offset = block.vlan(vlan);
+                let l3_offset = ether_type_offset + 2;
+                block.ip(ip, ether_type_offset, l3_offset);
+                if let ElementFilter::Some(transport) = &self.transport {
+                    block.transport(transport, ip, l3_offset);
+                }
+                block.append_to(&mut program);
+            }
+        }
+        program.push(BpfInstruction {
+            code: bpf_code::RET_K,
+            jt: 0,
+            jf: 0,
+            k: 0,
+        });
+        Ok(program)
+    }
+}
+
+const BPF_VLAN_ETHER_TYPES: [u32;3] = [
+    ether_type::VLAN_TAGGED_FRAME as u32,
+    ether_type::PROVIDER_BRIDGING as u32,
+    ether_type::VLAN_DOUBLE_TAGGED_FRAME as u32,
+];
+
+const BPF_IP_ETHER_TYPES: [u32;2] = [
+    ether_type::IPV4 as u32,
+    ether_type::IPV6 as u32,
+];
+
+/// Vlan layout checked by a single block of a compiled filter.
+enum BpfVlan {
+    Unchecked,
+    No,
+    Single(Option<u16>),
+    Double(Option<u16>, Option<u16>),
+}
+
+/// Ip layout checked by a single block of a compiled filter.
+enum BpfIp {
+    Unchecked,
+    No,
+    Ipv4 {
+        source: Option<[u8;4]>,
+        destination: Option<[u8;4]>
+    },
+    Ipv6 {
+        source: Option<[u8;16]>,
+        destination: Option<[u8;16]>
+    },
+}
+
+/// Jump target of an instruction in a [`BpfBlock`].
+#[derive(Clone, Copy)]
+enum BpfJump {
+    Next,
+    Skip(u8),
+    Fail,
+}
+
+/// Sequence of checks that accepts the packet if all of them succeed and
+/// otherwise continues with the next block.
+#[derive(Default)]
+struct BpfBlock {
+    instructions: Vec<(u16, BpfJump, BpfJump, u32)>,
+}
+
+impl BpfBlock {
+    fn push(&mut self, code: u16, k: u32) {
+        self.instructions.push((code, BpfJump::Next, BpfJump::Next, k));
+    }
+
+    fn jeq(&mut self, jt: BpfJump, jf: BpfJump, k: u32) {
+        self.instructions.push((bpf_code::JMP_JEQ_K, jt, jf, k));
+    }
+
+    fn load_eq(&mut self, code: u16, offset: u32, value: u32) {
+        self.push(code, offset);
+        self.jeq(BpfJump::Next, BpfJump::Fail, value);
+    }
+
+    fn load_in(&mut self, code: u16, offset: u32, values: &[u32]) {
+        self.push(code, offset);
+        for (index, value) in values.iter().enumerate() {
+            let remaining = values.len() - index - 1;
+            if 0 == remaining {
+                self.jeq(BpfJump::Next, BpfJump::Fail, *value);
+            } else {
+                self.jeq(BpfJump::Skip(remaining as u8), BpfJump::Next, *value);
+            }
+        }
+    }
+
+    fn load_not_in(&mut self, code: u16, offset: u32, values: &[u32]) {
+        self.push(code, offset);
+        for value in values {
+            self.jeq(BpfJump::Fail, BpfJump::Next, *value);
+        }
+    }
+
+    fn mac_eq(&mut self, offset: u32, mac: &[u8;6]) {
+        self.load_eq(bpf_code::LD_W_ABS, offset, u32::from_be_bytes([mac[0], mac[1], mac[2], mac[3]]));
+        self.load_eq(bpf_code::LD_H_ABS, offset + 4, u32::from(u16::from_be_bytes([mac[4], mac[5]])));
+    }
+
+    fn vlan_id_eq(&mut self, offset: u32, id: Option<u16>) {
+        if let Some(id) = id {
+            self.push(bpf_code::LD_H_ABS, offset);
+            self.push(bpf_code::ALU_AND_K, 0xfff);
+            self.jeq(BpfJump::Next, BpfJump::Fail, u32::from(id));
+        }
+    }
+
+    fn words_eq(&mut self, offset: u32, bytes: &[u8]) {
+        for (index, word) in bytes.chunks(4).enumerate() {
+            self.load_eq(
+                bpf_code::LD_W_ABS,
+                offset + (index as u32)*4,
+                u32::from_be_bytes([word[0], word[1], word[2], word[3]])
+            );
+        }
+    }
+
+    /// Adds the vlan checks & returns the offset of the ether type
+    /// identifying the layer 3 protocol.
+    fn vlan(&mut self, vlan: &BpfVlan) -> u32 {
+        use bpf_code::*;
+        match vlan {
+            BpfVlan::Unchecked => 12,
+            BpfVlan::No => {
+                self.load_not_in(LD_H_ABS, 12, &BPF_VLAN_ETHER_TYPES);
+                12
+            },
+            BpfVlan::Single(id) => {
+                self.load_in(LD_H_ABS, 12, &BPF_VLAN_ETHER_TYPES);
+                self.load_not_in(LD_H_ABS, 16, &BPF_VLAN_ETHER_TYPES);
+                self.vlan_id_eq(14, *id);
+                16
+            },
+            BpfVlan::Double(outer, inner) => {
+                self.load_in(LD_H_ABS, 12, &BPF_VLAN_ETHER_TYPES);
+                self.load_in(LD_H_ABS, 16, &BPF_VLAN_ETHER_TYPES);
+                self.vlan_id_eq(14, *outer);
+                self.vlan_id_eq(18, *inner);
+                20
+            },
+        }
+    }
+
+    fn ip(&mut self, ip: &BpfIp, ether_type_offset: u32, l3_offset: u32) {
+        use bpf_code::*;
+        match ip {
+            BpfIp::Unchecked => {},
+            BpfIp::No => {
+                self.load_not_in(LD_H_ABS, ether_type_offset, &BPF_IP_ETHER_TYPES);
+            },
+            BpfIp::Ipv4{ source, destination } => {
+                self.load_eq(LD_H_ABS, ether_type_offset, u32::from(ether_type::IPV4));
+                if let Some(source) = source {
+                    self.words_eq(l3_offset + 12, source);
+                }
+                if let Some(destination) = destination {
+                    self.words_eq(l3_offset + 16, destination);
+                }
+            },
+            BpfIp::Ipv6{ source, destination } => {
+                self.load_eq(LD_H_ABS, ether_type_offset, u32::from(ether_type::IPV6));
+                if let Some(source) = source {
+                    self.words_eq(l3_offset + 8, source);
+                }
+                if let Some(destination) = destination {
+                    self.words_eq(l3_offset + 24, destination);
+                }
+            },
+        }
+    }
+
+    fn transport(&mut self, transport: &TransportFilter, ip: &BpfIp, l3_offset: u32) {
+        use bpf_code::*;
+        let (protocol, source_port, destination_port) = match transport {
+            TransportFilter::Udp{ source_port, destination_port } => (ip_number::UDP, source_port, destination_port),
+            TransportFilter::Tcp{ source_port, destination_port } => (ip_number::TCP, source_port, destination_port),
+        };
+        let (load_code, l4_offset) = match ip {
+            BpfIp::Ipv4{ .. } => {
+                self.load_eq(LD_B_ABS, l3_offset + 9, u32::from(protocol));
+                // fragmented payloads are not decoded (more fragments flag & fragment offset)
+                self.push(LD_H_ABS, l3_offset + 6);
+                self.instructions.push((JMP_JSET_K, BpfJump::Fail, BpfJump::Next, 0x3fff));
+                if source_port.is_some() || destination_port.is_some() {
+                    self.push(LDX_B_MSH, l3_offset);
+                }
+                (LD_H_IND, l3_offset)
+            },
+            BpfIp::Ipv6{ .. } => {
+                self.load_eq(LD_B_ABS, l3_offset + 6, u32::from(protocol));
+                (LD_H_ABS, l3_offset + Ipv6Header::SERIALIZED_SIZE as u32)
+            },
+            // transport filters always come with an ip version
+            BpfIp::Unchecked | BpfIp::No => return,
+        };
+        if let Some(port) = source_port {
+            self.load_eq(load_code, l4_offset, u32::from(*port));
+        }
+        if let Some(port) = destination_port {
+            self.load_eq(load_code, l4_offset + 2, u32::from(*port));
+        }
+    }
+
+    /// Resolves the jumps & appends the block followed by an accepting
+    /// return. Failing checks jump to the instruction after the return.
+    fn append_to(self, program: &mut Vec<BpfInstruction>) {
+        let len = self.instructions.len();
+        let resolve = |index: usize, jump: BpfJump| -> u8 {
+            match jump {
+                BpfJump::Next => 0,
+                BpfJump::Skip(value) => value,
+                // blocks are at most a few dozen instructions long
+                BpfJump::Fail => (len - index) as u8,
+            }
+        };
+        for (index, (code, jt, jf, k)) in self.instructions.iter().enumerate() {
+            program.push(BpfInstruction {
+                code: *code,
+                jt: resolve(index, *jt),
+                jf: resolve(index, *jf),
+                k: *k,
+            });
+        }
+        program.push(BpfInstruction {
+            code: bpf_code::RET_K,
+            jt: 0,
+            jf: 0,
+            k: u32::MAX,
+        });
+    }
+}
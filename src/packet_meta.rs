@@ -0,0 +1,176 @@
+use super::*;
+
+use std::time::Duration;
+
+/// Direction in which a packet was captured relative to the capturing interface.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum PacketDirection {
+    /// Packet was received by the interface.
+    Inbound,
+    /// Packet was sent by the interface.
+    Outbound,
+}
+
+/// Capture metadata of a packet (timestamp, interface, lengths).
+///
+/// The metadata is not part of the packet itself and is usually provided by
+/// the capture source (e.g. the pcap record header or a socket). It can
+/// be bundled with a parsed packet via [`PacketWithMeta`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketMeta, PacketDirection};
+/// use std::time::Duration;
+///
+/// let meta = PacketMeta::new(100, 60)
+///     .with_timestamp(Duration::from_micros(1_600_000_000_000_123))
+///     .with_interface_index(2)
+///     .with_direction(PacketDirection::Inbound);
+///
+/// assert!(meta.is_truncated());
+/// assert_eq!(40, meta.truncated_len());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct PacketMeta {
+    /// Capture time of the packet as duration since the unix epoch (if known).
+    pub timestamp: Option<Duration>,
+    /// Index of the interface the packet was captured on (if known).
+    pub interface_index: Option<u32>,
+    /// Direction of the packet relative to the capturing interface (if known).
+    pub direction: Option<PacketDirection>,
+    /// Length of the packet on the wire in bytes.
+    pub original_len: usize,
+    /// Number of bytes of the packet that were actually captured.
+    pub captured_len: usize,
+}
+
+impl PacketMeta {
+    /// Creates metadata with the given lengths and all other values set to `None`.
+    pub fn new(original_len: usize, captured_len: usize) -> PacketMeta {
+        PacketMeta {
+            timestamp: None,
+            interface_index: None,
+            direction: None,
+            original_len,
+            captured_len,
+        }
+    }
+
+    /// Creates metadata for a packet that was captured completely.
+    pub fn from_captured(data: &[u8]) -> PacketMeta {
+        PacketMeta::new(data.len(), data.len())
+    }
+
+    /// Sets the timestamp (duration since the unix epoch).
+    #[inline]
+    pub fn with_timestamp(mut self, timestamp: Duration) -> PacketMeta {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Sets the interface index.
+    #[inline]
+    pub fn with_interface_index(mut self, interface_index: u32) -> PacketMeta {
+        self.interface_index = Some(interface_index);
+        self
+    }
+
+    /// Sets the direction.
+    #[inline]
+    pub fn with_direction(mut self, direction: PacketDirection) -> PacketMeta {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Returns true if less bytes were captured then were present on the wire.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.captured_len < self.original_len
+    }
+
+    /// Number of bytes that were present on the wire but were not captured.
+    #[inline]
+    pub fn truncated_len(&self) -> usize {
+        self.original_len.saturating_sub(self.captured_len)
+    }
+}
+
+/// A parsed packet (e.g. [`SlicedPacket`] or [`PacketHeaders`]) together with its capture metadata.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6],     //source mac
+/// #               [7,8,9,10,11,12]) //destionation mac
+/// #    .ipv4([192,168,1,1], //source ip
+/// #          [192,168,1,2], //desitionation ip
+/// #          20)            //time to life
+/// #    .udp(21,    //source port
+/// #         1234); //desitnation port
+/// # let payload = [1,2,3,4,5,6,7,8];
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(payload.len()));
+/// # builder.write(&mut packet, &payload).unwrap();
+/// use etherparse::{PacketMeta, PacketWithMeta, SlicedPacket};
+///
+/// let meta = PacketMeta::from_captured(&packet).with_interface_index(1);
+/// let value = PacketWithMeta::sliced_from_ethernet(meta, &packet).unwrap();
+/// println!("{:?} {:?}", value.meta, value.packet.ip);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PacketWithMeta<T> {
+    /// Capture metadata.
+    pub meta: PacketMeta,
+    /// Parsed packet.
+    pub packet: T,
+}
+
+impl<T> PacketWithMeta<T> {
+    /// Bundles a packet with its metadata.
+    #[inline]
+    pub fn new(meta: PacketMeta, packet: T) -> PacketWithMeta<T> {
+        PacketWithMeta { meta, packet }
+    }
+
+    /// Converts the packet and keeps the metadata.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> PacketWithMeta<U> {
+        PacketWithMeta {
+            meta: self.meta,
+            packet: f(self.packet),
+        }
+    }
+}
+
+impl PacketWithMeta<Vec<u8>> {
+    /// Returns the captured bytes of the packet.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.packet
+    }
+}
+
+impl<'a> PacketWithMeta<SlicedPacket<'a>> {
+    /// Slices the given data with [`SlicedPacket::from_ethernet`] & bundles the result with the metadata.
+    pub fn sliced_from_ethernet(meta: PacketMeta, data: &'a [u8]) -> Result<PacketWithMeta<SlicedPacket<'a>>, ReadError> {
+        Ok(PacketWithMeta::new(meta, SlicedPacket::from_ethernet(data)?))
+    }
+
+    /// Slices the given data with [`SlicedPacket::from_ip`] & bundles the result with the metadata.
+    pub fn sliced_from_ip(meta: PacketMeta, data: &'a [u8]) -> Result<PacketWithMeta<SlicedPacket<'a>>, ReadError> {
+        Ok(PacketWithMeta::new(meta, SlicedPacket::from_ip(data)?))
+    }
+}
+
+impl<'a> PacketWithMeta<PacketHeaders<'a>> {
+    /// Decodes the given data with [`PacketHeaders::from_ethernet_slice`] & bundles the result with the metadata.
+    pub fn headers_from_ethernet(meta: PacketMeta, data: &'a [u8]) -> Result<PacketWithMeta<PacketHeaders<'a>>, ReadError> {
+        Ok(PacketWithMeta::new(meta, PacketHeaders::from_ethernet_slice(data)?))
+    }
+
+    /// Decodes the given data with [`PacketHeaders::from_ip_slice`] & bundles the result with the metadata.
+    pub fn headers_from_ip(meta: PacketMeta, data: &'a [u8]) -> Result<PacketWithMeta<PacketHeaders<'a>>, ReadError> {
+        Ok(PacketWithMeta::new(meta, PacketHeaders::from_ip_slice(data)?))
+    }
+}
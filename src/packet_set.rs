@@ -0,0 +1,280 @@
+use super::*;
+
+use std::collections::BTreeMap;
+
+/// Layer at which the packets stored in a [`PacketSet`] start.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PacketSetLayer {
+    /// Packets start with an ethernet II header (sliced with [`SlicedPacket::from_ethernet`]).
+    Ethernet2,
+    /// Packets start with an ipv4 or ipv6 header (sliced with [`SlicedPacket::from_ip`]).
+    Ip,
+}
+
+/// In-memory collection of captured packets for batch analysis.
+///
+/// The packets are stored as byte buffers together with their [`PacketMeta`].
+/// Parsing is done lazily whenever a view of a packet is requested, so
+/// no lifetimes have to be carried around when collecting the packets.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6],     //source mac
+/// #               [7,8,9,10,11,12]) //destionation mac
+/// #    .ipv4([192,168,1,1], //source ip
+/// #          [192,168,1,2], //desitionation ip
+/// #          20)            //time to life
+/// #    .udp(21,    //source port
+/// #         1234); //desitnation port
+/// # let payload = [1,2,3,4,5,6,7,8];
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(payload.len()));
+/// # builder.write(&mut packet, &payload).unwrap();
+/// use etherparse::{PacketSet, PacketSetLayer, PacketMeta, FlowKey, ip_number};
+/// use std::time::Duration;
+///
+/// let mut set = PacketSet::new(PacketSetLayer::Ethernet2);
+/// set.push(PacketMeta::from_captured(&packet).with_timestamp(Duration::from_secs(2)), packet.clone());
+/// set.push(PacketMeta::from_captured(&packet).with_timestamp(Duration::from_secs(1)), packet.clone());
+///
+/// let key = FlowKey::from_sliced(&set.slice(0).unwrap().unwrap()).unwrap();
+/// assert_eq!(2, set.filter_flow(key).count());
+/// assert_eq!(2, set.by_protocol()[&Some(ip_number::UDP)].len());
+/// assert_eq!(
+///     Some(Duration::from_secs(1)),
+///     set.sorted_by_time()[0].meta.timestamp
+/// );
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PacketSet {
+    layer: PacketSetLayer,
+    packets: Vec<PacketWithMeta<Vec<u8>>>,
+}
+
+impl PacketSet {
+    /// Creates an empty set of packets starting at the given layer.
+    pub fn new(layer: PacketSetLayer) -> PacketSet {
+        PacketSet {
+            layer,
+            packets: Vec::new(),
+        }
+    }
+
+    /// Creates an empty set with space for at least `capacity` packets.
+    pub fn with_capacity(layer: PacketSetLayer, capacity: usize) -> PacketSet {
+        PacketSet {
+            layer,
+            packets: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Layer at which the stored packets start.
+    #[inline]
+    pub fn layer(&self) -> PacketSetLayer {
+        self.layer
+    }
+
+    /// Number of packets in the set.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// Returns true if the set contains no packets.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+
+    /// Adds a packet to the end of the set.
+    pub fn push(&mut self, meta: PacketMeta, data: Vec<u8>) {
+        self.packets.push(PacketWithMeta::new(meta, data));
+    }
+
+    /// Returns the packet at the given index.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&PacketWithMeta<Vec<u8>>> {
+        self.packets.get(index)
+    }
+
+    /// Returns all packets in the order they were added.
+    #[inline]
+    pub fn packets(&self) -> &[PacketWithMeta<Vec<u8>>] {
+        &self.packets
+    }
+
+    /// Iterator over the packets in the order they were added.
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, PacketWithMeta<Vec<u8>>> {
+        self.packets.iter()
+    }
+
+    /// Slices the packet at the given index (`None` if the index is out of bounds).
+    pub fn slice(&self, index: usize) -> Option<Result<SlicedPacket<'_>, ReadError>> {
+        self.packets.get(index).map(|p| slice_packet(self.layer, p.data()))
+    }
+
+    /// Iterator over the sliced packets (packets are sliced on demand).
+    pub fn sliced(&self) -> PacketSetSlicedIter<'_> {
+        PacketSetSlicedIter {
+            layer: self.layer,
+            packets: self.packets.iter(),
+        }
+    }
+
+    /// Iterator over all packets that belong to the given flow in either
+    /// direction. Packets that can not be sliced are skipped.
+    pub fn filter_flow(&self, key: FlowKey) -> PacketSetFlowIter<'_> {
+        PacketSetFlowIter {
+            key: key.bidirectional(),
+            inner: self.sliced(),
+        }
+    }
+
+    /// Groups the sliced packets into per flow streams (in arrival order).
+    ///
+    /// Packets that can not be sliced are skipped and packets without a
+    /// flow key are collected in [`FlowStreams::unclassified`].
+    pub fn flows(&self, bidirectional: bool) -> FlowStreams<(&PacketMeta, SlicedPacket<'_>)> {
+        FlowStreams::group_by(
+            self.sliced().filter_map(|(meta, sliced)| sliced.ok().map(|sliced| (meta, sliced))),
+            bidirectional,
+            |(_, sliced)| FlowKey::from_sliced(sliced)
+        )
+    }
+
+    /// Groups the packets by the ip number of their transport protocol.
+    ///
+    /// Packets without an ip header, packets that could not be sliced & packets
+    /// whose transport protocol is unknown (e.g. fragments) are grouped under `None`.
+    pub fn by_protocol(&self) -> BTreeMap<Option<u8>, Vec<&PacketWithMeta<Vec<u8>>>> {
+        let mut result: BTreeMap<Option<u8>, Vec<&PacketWithMeta<Vec<u8>>>> = BTreeMap::new();
+        for packet in &self.packets {
+            let ip_number = slice_packet(self.layer, packet.data())
+                .ok()
+                .and_then(|sliced| FlowKey::from_sliced(&sliced))
+                .map(|key| key.ip_number);
+            result.entry(ip_number).or_default().push(packet);
+        }
+        result
+    }
+
+    /// Iterator verifying the checksums of the packets according to the given
+    /// options (packets are sliced on demand).
+    ///
+    /// The index of a packet in the set decides if it is sampled for a full
+    /// verification (see [`VerifyOptions::is_full_verification`]).
+    pub fn verify_checksums(&self, options: VerifyOptions) -> PacketSetChecksumIter<'_> {
+        PacketSetChecksumIter {
+            layer: self.layer,
+            options,
+            packets: self.packets.iter().enumerate(),
+        }
+    }
+
+    /// Returns the packets sorted by their capture timestamp.
+    ///
+    /// Packets without a timestamp are placed first. The sort is stable, packets
+    /// with identical timestamps keep the order in which they were added.
+    pub fn sorted_by_time(&self) -> Vec<&PacketWithMeta<Vec<u8>>> {
+        let mut result: Vec<_> = self.packets.iter().collect();
+        result.sort_by_key(|p| p.meta.timestamp);
+        result
+    }
+}
+
+impl<'a> IntoIterator for &'a PacketSet {
+    type Item = &'a PacketWithMeta<Vec<u8>>;
+    type IntoIter = std::slice::Iter<'a, PacketWithMeta<Vec<u8>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.packets.iter()
+    }
+}
+
+pub(crate) fn slice_packet(layer: PacketSetLayer, data: &[u8]) -> Result<SlicedPacket<'_>, ReadError> {
+    match layer {
+        PacketSetLayer::Ethernet2 => SlicedPacket::from_ethernet(data),
+        PacketSetLayer::Ip => SlicedPacket::from_ip(data),
+    }
+}
+
+/// Iterator over the sliced packets of a [`PacketSet`] (see [`PacketSet::sliced`]).
+#[derive(Clone, Debug)]
+pub struct PacketSetSlicedIter<'a> {
+    layer: PacketSetLayer,
+    packets: std::slice::Iter<'a, PacketWithMeta<Vec<u8>>>,
+}
+
+impl<'a> Iterator for PacketSetSlicedIter<'a> {
+    type Item = (&'a PacketMeta, Result<SlicedPacket<'a>, ReadError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let packet = self.packets.next()?;
+        Some((&packet.meta, slice_packet(self.layer, packet.data())))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.packets.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for PacketSetSlicedIter<'a> {}
+
+/// Iterator over the checksum verification results of the packets of a
+/// [`PacketSet`] (see [`PacketSet::verify_checksums`]).
+#[derive(Clone, Debug)]
+pub struct PacketSetChecksumIter<'a> {
+    layer: PacketSetLayer,
+    options: VerifyOptions,
+    packets: std::iter::Enumerate<std::slice::Iter<'a, PacketWithMeta<Vec<u8>>>>,
+}
+
+impl<'a> Iterator for PacketSetChecksumIter<'a> {
+    type Item = (&'a PacketMeta, Result<ChecksumReport, ReadError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, packet) = self.packets.next()?;
+        let report = slice_packet(self.layer, packet.data()).map(|sliced| {
+            if self.options.is_full_verification(index) {
+                ChecksumReport::verify(packet.data(), &sliced)
+            } else {
+                ChecksumReport::verify_headers(&sliced)
+            }
+        });
+        Some((&packet.meta, report))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.packets.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for PacketSetChecksumIter<'a> {}
+
+/// Iterator over the packets of a [`PacketSet`] belonging to one flow (see [`PacketSet::filter_flow`]).
+#[derive(Clone, Debug)]
+pub struct PacketSetFlowIter<'a> {
+    key: FlowKey,
+    inner: PacketSetSlicedIter<'a>,
+}
+
+impl<'a> Iterator for PacketSetFlowIter<'a> {
+    type Item = (&'a PacketMeta, SlicedPacket<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for (meta, sliced) in &mut self.inner {
+            if let Ok(sliced) = sliced {
+                let matches = FlowKey::from_sliced(&sliced)
+                    .map(|k| k.bidirectional() == self.key)
+                    .unwrap_or(false);
+                if matches {
+                    return Some((meta, sliced));
+                }
+            }
+        }
+        None
+    }
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum InternetSlice<'a> {
     /// The ipv4 header & the decoded extension headers.
     Ipv4(Ipv4HeaderSlice<'a>, Ipv4ExtensionsSlice<'a>),
@@ -34,7 +34,7 @@ impl<'a> InternetSlice<'a> {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum TransportSlice<'a> {
     /// A slice containing an Icmp4 header
     Icmpv4(Icmpv4Slice<'a>),
@@ -44,6 +44,15 @@ pub enum TransportSlice<'a> {
     Udp(UdpHeaderSlice<'a>),
     /// A slice containing a TCP header.
     Tcp(TcpHeaderSlice<'a>),
+    /// A slice containing an IGMP message (only decoded in IPv4 packets).
+    Igmp(IgmpSlice<'a>),
+    /// A slice containing a SCTP common header (the chunks are contained
+    /// in the payload, see [`SctpChunkIterator`]).
+    Sctp(SctpHeaderSlice<'a>),
+    /// A slice containing an UDP-Lite header.
+    UdpLite(UdpLiteHeaderSlice<'a>),
+    /// A slice containing a DCCP header (including the options).
+    Dccp(DccpHeaderSlice<'a>),
     /// Unknonwn transport layer protocol. The value is the last parsed ip protocol number.
     Unknown(u8),
 }
@@ -57,6 +66,11 @@ pub enum TransportSlice<'a> {
 /// * [`SlicedPacket::from_ethernet`]
 /// * [`SlicedPacket::from_ether_type`]
 /// * [`SlicedPacket::from_ip`]
+/// * [`SlicedPacket::from_linux_sll`]
+/// * [`SlicedPacket::from_linux_sll2`]
+/// * [`SlicedPacket::from_null_loopback`]
+/// * [`SlicedPacket::from_ieee80211`]
+/// * [`SlicedPacket::from_radiotap`]
 ///
 /// depending on your starting header to slice a packet.
 ///
@@ -90,16 +104,102 @@ pub enum TransportSlice<'a> {
 ///     }
 /// }
 /// ```
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct SlicedPacket<'a> {
     /// Ethernet II header if present.
     pub link: Option<LinkSlice<'a>>,
+    /// Linux cooked capture header if present (only set when slicing with
+    /// [`SlicedPacket::from_linux_sll`] or [`SlicedPacket::from_linux_sll2`]).
+    pub linux_sll: Option<LinuxCookedSlice<'a>>,
+    /// MACsec SecTAG if present (ether type `ether_type::MACSEC` following the
+    /// link layer or the vlan header).
+    ///
+    /// Cleartext frames are sliced further based on the ether type following
+    /// the SecTAG, otherwise the `payload` field contains the encrypted data.
+    /// SecTAGs following a vlan header are left in the payload if they are
+    /// nested or protect further vlan headers.
+    pub macsec: Option<MacsecHeaderSlice<'a>>,
     /// Single or double vlan headers if present.
     pub vlan: Option<VlanSlice<'a>>,
+    /// MPLS label stack if present.
+    pub mpls: Option<MplsStackSlice<'a>>,
+    /// PPPoE header if present (ether types `ether_type::PPPOE_DISCOVERY` &
+    /// `ether_type::PPPOE_SESSION`).
+    ///
+    /// IPv4 & IPv6 packets contained in session data packets are sliced into the
+    /// `ip` & `transport` fields, otherwise the `payload` field contains the PPPoE
+    /// payload.
+    pub pppoe: Option<PppoeHeaderSlice<'a>>,
+    /// ARP packet if present.
+    pub arp: Option<ArpPacketSlice<'a>>,
+    /// Custom link layer header decoded by a user registered parser (see [`ParserRegistry`]).
+    pub link_extension: Option<LinkExtensionSlice<'a>>,
     /// IPv4 or IPv6 header and IP extension headers if present.
     pub ip: Option<InternetSlice<'a>>,
     /// TCP or UDP header if present.
     pub transport: Option<TransportSlice<'a>>,
+    /// Custom transport layer header decoded by a user registered parser (see [`ParserRegistry`]).
+    ///
+    /// If present the `transport` field contains `TransportSlice::Unknown` with the ip number
+    /// of the header.
+    pub transport_extension: Option<TransportExtensionSlice<'a>>,
+    /// Application layer protocol assigned to the udp or tcp ports of the packet
+    /// in the [`ParserRegistry`] (always `None` if no registry is used).
+    ///
+    /// If present the `payload` field contains the application layer data.
+    /// The destination port takes precedence over the source port.
+    pub application: Option<ApplicationProtocol>,
+    /// ESP header if present (ip number `ip_number::ENCAP_SEC`).
+    ///
+    /// If present the `transport` field contains `TransportSlice::Unknown(ip_number::ENCAP_SEC)`
+    /// and the `payload` field the encrypted data following the sequence number.
+    pub esp: Option<EspHeaderSlice<'a>>,
+    /// GRE header if present.
+    ///
+    /// If present the `transport` field contains `TransportSlice::Unknown(ip_number::GRE)`
+    /// and the `payload` field the encapsulated packet.
+    /// GRE headers with a version other then 0 (e.g. enhanced GRE used by
+    /// PPTP) or routing information are not decoded & left in the payload.
+    pub gre: Option<GreHeaderSlice<'a>>,
+    /// ERSPAN type II or III header if present (GRE protocol type
+    /// `ether_type::ERSPAN_TYPE_2` or `ether_type::ERSPAN_TYPE_3`).
+    ///
+    /// If present the `payload` field contains the mirrored frame.
+    pub erspan: Option<ErspanHeaderSlice<'a>>,
+    /// VXLAN header if present (udp port assigned to [`ApplicationProtocol::Vxlan`]
+    /// in the [`ParserRegistry`]).
+    ///
+    /// If present the `payload` field contains the encapsulated ethernet II frame.
+    pub vxlan: Option<VxlanHeaderSlice<'a>>,
+    /// GENEVE header if present (udp port assigned to [`ApplicationProtocol::Geneve`]
+    /// in the [`ParserRegistry`]).
+    ///
+    /// If present the `payload` field contains the encapsulated packet.
+    pub geneve: Option<GeneveHeaderSlice<'a>>,
+    /// GTP-U header if present (udp port assigned to [`ApplicationProtocol::GtpU`]
+    /// in the [`ParserRegistry`]).
+    ///
+    /// If present the `payload` field contains the data following the header
+    /// (the encapsulated ip packet for G-PDUs).
+    pub gtpu: Option<GtpuHeaderSlice<'a>>,
+    /// NSH header if present (ether type `ether_type::NSH`).
+    ///
+    /// If present the `payload` field contains the packet following the header.
+    pub nsh: Option<NshHeaderSlice<'a>>,
+    /// Encapsulated packet of a `gre`, `vxlan`, `geneve`, `gtpu` or `nsh` header. GRE & GENEVE
+    /// payloads are sliced based on the protocol type of the header (ethernet II
+    /// frames for `ether_type::TRANSPARENT_ETHERNET_BRIDGING`), VXLAN payloads
+    /// are sliced as ethernet II frames, GTP-U payloads as ip packets (only for
+    /// G-PDUs) & NSH payloads based on the next protocol field. The mirrored
+    /// ethernet II frames of ERSPAN packets are sliced after the `erspan` header.
+    ///
+    /// Tunnels are only decoded up to the `max_tunnel_depth` of the
+    /// [`ParseLimits`] (4 by default), deeper nested tunnel headers are left in
+    /// the payload.
+    ///
+    /// Encapsulated packets that can not be sliced (e.g. because they are
+    /// truncated) are also left in the payload & `inner` is set to `None`.
+    pub inner: Option<Box<SlicedPacket<'a>>>,
     /// The payload field points to the rest of the packet that could not be parsed by etherparse.
     ///
     /// Depending on what other fields contain a "Some" values the payload contains the corresponding 
@@ -148,7 +248,208 @@ impl<'a> SlicedPacket<'a> {
     /// }
     /// ```
     pub fn from_ethernet(data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        CursorSlice::new(data).slice_ethernet2()
+        SlicedPacket::from_ethernet_with_registry(data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_ethernet`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_ethernet_with_registry(data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, registry).slice_ethernet2()
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers from
+    /// the Linux cooked capture header (`LINKTYPE_LINUX_SLL`) downwards.
+    ///
+    /// Packets captured on the Linux "any" device start with this header instead of an
+    /// ethernet II header. The header is set in the `linux_sll` field & the following
+    /// headers are sliced based on the protocol type of the header (see
+    /// [`SlicedPacket::from_ether_type`] for the supported ether types).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{ether_type, LinuxSllHeader, PacketBuilder, SlicedPacket};
+    ///
+    /// let mut packet = Vec::new();
+    /// LinuxSllHeader {
+    ///     protocol_type: ether_type::IPV4,
+    ///     ..Default::default()
+    /// }.write(&mut packet).unwrap();
+    /// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+    ///     .udp(21, 1234)
+    ///     .write(&mut packet, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+    /// assert!(sliced.linux_sll.is_some());
+    /// assert!(sliced.ip.is_some());
+    /// assert_eq!(&[1,2,3,4], sliced.payload);
+    /// ```
+    pub fn from_linux_sll(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_linux_sll_with_registry(data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_linux_sll`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_linux_sll_with_registry(data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, registry).slice_linux_sll()
+    }
+
+    /// Same as [`SlicedPacket::from_linux_sll`] but for packets starting with a Linux
+    /// cooked capture v2 header (`LINKTYPE_LINUX_SLL2`).
+    pub fn from_linux_sll2(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_linux_sll2_with_registry(data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_linux_sll2`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_linux_sll2_with_registry(data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, registry).slice_linux_sll2()
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers from
+    /// the BSD loopback header (`LINKTYPE_NULL` & `LINKTYPE_LOOP`) downwards.
+    ///
+    /// Packets captured on the loopback interface of macOS & the BSDs (`lo0`) start with
+    /// the 4 byte address family of the packet (see [`NullHeader`], the byte order is
+    /// detected automatically). IPv4 & IPv6 packets are sliced into the `ip` & `transport`
+    /// fields, packets of other address families are set as `payload`. The loopback header
+    /// itself is not stored in the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{PacketBuilder, SlicedPacket};
+    ///
+    /// // AF_INET in host byte order (little endian)
+    /// let mut packet = vec![2, 0, 0, 0];
+    /// PacketBuilder::ipv4([127,0,0,1], [127,0,0,1], 20)
+    ///     .udp(21, 1234)
+    ///     .write(&mut packet, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_null_loopback(&packet).unwrap();
+    /// assert!(sliced.ip.is_some());
+    /// assert_eq!(&[1,2,3,4], sliced.payload);
+    /// ```
+    pub fn from_null_loopback(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_null_loopback_with_registry(data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_null_loopback`] but additionally calls the parsers
+    /// registered in the given registry for ip numbers not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `transport_extension` field.
+    pub fn from_null_loopback_with_registry(data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        use ether_type::*;
+
+        let (header, rest) = NullHeader::from_slice(data)?;
+        let mut cursor = CursorSlice::new(data, registry);
+        cursor.move_to_slice(rest);
+        match header.ether_type() {
+            Some(IPV4) => cursor.slice_ipv4(),
+            Some(IPV6) => cursor.slice_ipv6(),
+            _ => cursor.slice_payload(),
+        }
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers from
+    /// the IEEE 802.11 MAC header (`LINKTYPE_IEEE802_11`) downwards.
+    ///
+    /// Unprotected data frames starting with a LLC/SNAP header carrying an ether type are
+    /// sliced from the ether type downwards, the bodies of all other frames (management,
+    /// control & protected frames, A-MSDUs) are set as `payload`. The MAC & LLC/SNAP headers
+    /// are not stored in the result (they can be decoded via [`Ieee80211HeaderSlice`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{ether_type, ieee80211_frame_type, Ieee80211FrameControl, Ieee80211Header, LlcSnapHeader, PacketBuilder, SlicedPacket};
+    ///
+    /// let mut packet = Vec::new();
+    /// Ieee80211Header {
+    ///     frame_control: Ieee80211FrameControl {
+    ///         frame_type: ieee80211_frame_type::DATA,
+    ///         from_ds: true,
+    ///         ..Default::default()
+    ///     },
+    ///     address2: Some([2;6]),
+    ///     address3: Some([3;6]),
+    ///     sequence_control: Some(0),
+    ///     ..Default::default()
+    /// }.write(&mut packet).unwrap();
+    /// LlcSnapHeader::with_ether_type(ether_type::IPV4).write(&mut packet).unwrap();
+    /// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+    ///     .udp(21, 1234)
+    ///     .write(&mut packet, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_ieee80211(&packet).unwrap();
+    /// assert!(sliced.ip.is_some());
+    /// assert_eq!(&[1,2,3,4], sliced.payload);
+    /// ```
+    pub fn from_ieee80211(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_ieee80211_with_registry(data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_ieee80211`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_ieee80211_with_registry(data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, registry).slice_ieee80211(false)
+    }
+
+    /// Seperates a network packet slice into different slices containing the headers from
+    /// the radiotap header (`LINKTYPE_IEEE802_11_RADIOTAP`) downwards.
+    ///
+    /// The radiotap header is followed by an IEEE 802.11 frame that is sliced like in
+    /// [`SlicedPacket::from_ieee80211`]. A frame check sequence at the end of the frame
+    /// (radiotap flag [`radiotap_flags::FCS`]) is not included in the `payload` & the padding
+    /// after the MAC header (flag [`radiotap_flags::DATA_PAD`]) is skipped. The radiotap
+    /// header is not stored in the result (it can be decoded via [`RadiotapHeaderSlice`]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::{ieee80211_frame_type, Ieee80211FrameControl, Ieee80211Header, SlicedPacket};
+    ///
+    /// // radiotap header without fields
+    /// let mut packet = vec![0, 0, 8, 0, 0, 0, 0, 0];
+    /// // beacon
+    /// Ieee80211Header {
+    ///     frame_control: Ieee80211FrameControl {
+    ///         frame_type: ieee80211_frame_type::MANAGEMENT,
+    ///         subtype: 8,
+    ///         ..Default::default()
+    ///     },
+    ///     address1: [0xff;6],
+    ///     address2: Some([2;6]),
+    ///     address3: Some([2;6]),
+    ///     sequence_control: Some(0),
+    ///     ..Default::default()
+    /// }.write(&mut packet).unwrap();
+    /// packet.extend_from_slice(&[1,2,3,4]);
+    ///
+    /// let sliced = SlicedPacket::from_radiotap(&packet).unwrap();
+    /// assert_eq!(&[1,2,3,4], sliced.payload);
+    /// ```
+    pub fn from_radiotap(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        SlicedPacket::from_radiotap_with_registry(data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_radiotap`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_radiotap_with_registry(data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, registry).slice_radiotap()
     }
 
     /// Seperates a network packet slice into different slices containing the headers using
@@ -162,6 +463,12 @@ impl<'a> SlicedPacket<'a> {
     /// * `ether_type::VLAN_TAGGED_FRAME`
     /// * `ether_type::PROVIDER_BRIDGING`
     /// * `ether_type::VLAN_DOUBLE_TAGGED_FRAME`
+    /// * `ether_type::MPLS_UNICAST`
+    /// * `ether_type::MPLS_MULTICAST`
+    /// * `ether_type::NSH`
+    /// * `ether_type::PPPOE_DISCOVERY`
+    /// * `ether_type::PPPOE_SESSION`
+    /// * `ether_type::MACSEC`
     ///
     /// If an unsupported ether type is given the given slice will be set as payload
     /// and all other fields will be set to `None`.
@@ -204,21 +511,27 @@ impl<'a> SlicedPacket<'a> {
     /// }
     /// ```
     pub fn from_ether_type(ether_type: u16, data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
+        SlicedPacket::from_ether_type_with_registry(ether_type, data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_ether_type`] but additionally calls the parsers
+    /// registered in the given registry for ether types not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `link_extension` field.
+    pub fn from_ether_type_with_registry(ether_type: u16, data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
         use ether_type::*;
+        let cursor = CursorSlice::new(data, registry);
         match ether_type {
-            IPV4 => CursorSlice::new(data).slice_ipv4(),
-            IPV6 => CursorSlice::new(data).slice_ipv6(),
+            IPV4 => cursor.slice_ipv4(),
+            IPV6 => cursor.slice_ipv6(),
             VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME =>
-                CursorSlice::new(data).slice_vlan(),
-            _ => Ok(
-                SlicedPacket {
-                    link: None,
-                    vlan: None,
-                    ip: None,
-                    transport: None,
-                    payload: data
-                }
-            ),
+                cursor.slice_vlan(),
+            MPLS_UNICAST | MPLS_MULTICAST => cursor.slice_mpls(),
+            ARP => cursor.slice_arp(),
+            NSH => cursor.slice_nsh(),
+            PPPOE_DISCOVERY | PPPOE_SESSION => cursor.slice_pppoe(),
+            MACSEC => cursor.slice_macsec(),
+            _ => cursor.slice_link_extension(ether_type),
         }
     }
 
@@ -259,32 +572,68 @@ impl<'a> SlicedPacket<'a> {
     /// }
     /// ```
     pub fn from_ip(data: &'a [u8]) -> Result<SlicedPacket, ReadError> {
-        CursorSlice::new(data).slice_ip()
+        SlicedPacket::from_ip_with_registry(data, &ParserRegistry::new())
+    }
+
+    /// Same as [`SlicedPacket::from_ip`] but additionally calls the parsers
+    /// registered in the given registry for ip numbers not supported by etherparse.
+    ///
+    /// Headers decoded by a registered parser are set in the `transport_extension` field.
+    pub fn from_ip_with_registry(data: &'a [u8], registry: &ParserRegistry) -> Result<SlicedPacket<'a>, ReadError> {
+        CursorSlice::new(data, registry).slice_ip()
+    }
+
+    /// Slices the original datagram embedded in an ICMP error message.
+    ///
+    /// Same as [`SlicedPacket::from_ip`] but transport headers cut off by the
+    /// end of the slice are tolerated (ICMPv4 errors only contain the first 8
+    /// bytes of the original transport header). In this case the `transport`
+    /// field is `None` & the `payload` field contains the incomplete header.
+    pub(crate) fn from_icmp_embedded(data: &'a [u8]) -> Result<SlicedPacket<'a>, ReadError> {
+        let registry = ParserRegistry::new();
+        let mut cursor = CursorSlice::new(data, &registry);
+        cursor.truncated_transport = true;
+        cursor.slice_ip()
     }
 
     /// If the slice in the `payload` field contains an ethernet payload
     /// this method returns the ether type number describing the payload type.
     ///
-    /// The ether type number can come from an ethernet II header or a
-    /// VLAN header depending on which headers are present.
+    /// The ether type number can come from an ethernet II header, a Linux
+    /// cooked capture header, a MACsec SecTAG or a VLAN header depending on
+    /// which headers are present.
     ///
     /// In case that `ip` and/or `transport` fields are the filled None
     /// is returned, as the payload contents then are defined by a
     /// lower layer protocol described in these fields.
     pub fn payload_ether_type(&self) -> Option<u16> {
-        if self.ip.is_some() || self.transport.is_some() {
+        if self.ip.is_some() || self.transport.is_some() || self.mpls.is_some() || self.pppoe.is_some() || self.arp.is_some() {
             None
+        } else if let Some(extension) = &self.link_extension {
+            extension.next_ether_type
         } else {
             if let Some(vlan) = &self.vlan {
                 use VlanSlice::*;
-                match vlan {
+                let vlan_ether_type = match vlan {
                     SingleVlan(s) => {
-                        Some(s.ether_type())
+                        s.ether_type()
                     },
                     DoubleVlan(d) => {
-                        Some(d.inner().ether_type())
+                        d.inner().ether_type()
                     }
+                };
+                //a sectag following the vlan header defines the payload type
+                //(sectags preceding the vlan header are followed by it)
+                use ether_type::*;
+                match &self.macsec {
+                    Some(macsec) if MACSEC == vlan_ether_type => match macsec.ether_type() {
+                        Some(VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME) => Some(vlan_ether_type),
+                        value => value,
+                    },
+                    _ => Some(vlan_ether_type),
                 }
+            } else if let Some(macsec) = &self.macsec {
+                macsec.ether_type()
             } else {
                 if let Some(link) = &self.link {
                     use LinkSlice::*;
@@ -294,7 +643,7 @@ impl<'a> SlicedPacket<'a> {
                         }
                     }
                 } else {
-                    None
+                    self.linux_sll.as_ref().map(|sll| sll.protocol_type())
                 }
             }
         }
@@ -302,23 +651,47 @@ impl<'a> SlicedPacket<'a> {
 }
 
 ///Helper class for slicing packets
-struct CursorSlice<'a> {
+struct CursorSlice<'a, 'r> {
     pub slice: &'a [u8],
     pub offset: usize,
+    pub registry: &'r ParserRegistry,
+    /// Number of tunnels the sliced packet is nested in.
+    pub depth: usize,
+    /// Tolerate transport headers cut off by the end of the slice.
+    pub truncated_transport: bool,
     pub result: SlicedPacket<'a>
 }
 
-impl<'a> CursorSlice<'a> {
+impl<'a, 'r> CursorSlice<'a, 'r> {
 
-    pub fn new(slice: &'a [u8]) -> CursorSlice<'a> {
+    pub fn new(slice: &'a [u8], registry: &'r ParserRegistry) -> CursorSlice<'a, 'r> {
         CursorSlice {
             offset: 0,
             slice,
+            registry,
+            depth: 0,
+            truncated_transport: false,
             result: SlicedPacket {
                 link: None,
+                linux_sll: None,
+                macsec: None,
                 vlan: None,
+                mpls: None,
+                pppoe: None,
+                arp: None,
+                link_extension: None,
                 ip: None,
                 transport: None,
+                transport_extension: None,
+                application: None,
+                esp: None,
+                gre: None,
+                erspan: None,
+                vxlan: None,
+                geneve: None,
+                gtpu: None,
+                nsh: None,
+                inner: None,
                 payload: slice
             }
         }
@@ -350,19 +723,130 @@ impl<'a> CursorSlice<'a> {
                      )?;
 
         //cache the ether_type for later
-        let ether_type = result.ether_type();
+        let type_length = self.registry.ether_type_length_policy()
+                              .classify(result.ether_type())?;
 
         //set the new data
         self.move_by_slice(result.slice());
         self.result.link = Some(Ethernet2(result));
 
+        let ether_type = match type_length {
+            EtherTypeLength::EtherType(value) => value,
+            EtherTypeLength::Length(len) => {
+                // IEEE 802.3 frame, only LLC/SNAP payloads can be decoded further
+                self.slice = &self.slice[..len.min(self.slice.len())];
+                if let Ok((snap, rest)) = LlcSnapHeader::from_slice(self.slice) {
+                    if let Some(ether_type) = snap.ether_type() {
+                        self.move_to_slice(rest);
+                        return self.slice_ether_type_payload(ether_type);
+                    }
+                }
+                return self.slice_payload();
+            },
+            EtherTypeLength::Undefined => return self.slice_payload(),
+        };
+
         //continue parsing (if required)
         match ether_type {
             IPV4 => self.slice_ipv4(),
             IPV6 => self.slice_ipv6(),
             VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => self.slice_vlan(),
-            _ => self.slice_payload()
+            MPLS_UNICAST | MPLS_MULTICAST => self.slice_mpls(),
+            ARP => self.slice_arp(),
+            NSH => self.slice_nsh(),
+            PPPOE_DISCOVERY | PPPOE_SESSION => self.slice_pppoe(),
+            MACSEC => self.slice_macsec(),
+            value => self.slice_link_extension(value)
+        }
+    }
+
+    pub fn slice_linux_sll(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = LinuxSllHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the protocol type for later
+        let protocol_type = result.protocol_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.linux_sll = Some(LinuxCookedSlice::Sll(result));
+
+        self.slice_ether_type_payload(protocol_type)
+    }
+
+    pub fn slice_linux_sll2(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = LinuxSll2HeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the protocol type for later
+        let protocol_type = result.protocol_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.linux_sll = Some(LinuxCookedSlice::Sll2(result));
+
+        self.slice_ether_type_payload(protocol_type)
+    }
+
+    /// Continues slicing based on an ether type of a header that is not
+    /// stored in the `link` field (linux cooked capture & LLC/SNAP headers).
+    fn slice_ether_type_payload(self, protocol_type: u16) -> Result<SlicedPacket<'a>, ReadError> {
+        use ether_type::*;
+
+        match protocol_type {
+            IPV4 => self.slice_ipv4(),
+            IPV6 => self.slice_ipv6(),
+            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => self.slice_vlan(),
+            MPLS_UNICAST | MPLS_MULTICAST => self.slice_mpls(),
+            ARP => self.slice_arp(),
+            NSH => self.slice_nsh(),
+            PPPOE_DISCOVERY | PPPOE_SESSION => self.slice_pppoe(),
+            MACSEC => self.slice_macsec(),
+            value => self.slice_link_extension(value)
+        }
+    }
+
+    pub fn slice_radiotap(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let radiotap = RadiotapHeaderSlice::from_slice(self.slice)
+                       .map_err(|err|
+                          err.add_slice_offset(self.offset)
+                       )?;
+        let flags = radiotap.flags().unwrap_or(0);
+        self.move_by_slice(radiotap.slice());
+
+        // the frame check sequence is not part of the payload
+        if 0 != flags & radiotap_flags::FCS {
+            self.slice = &self.slice[..self.slice.len().saturating_sub(4)];
+        }
+        self.slice_ieee80211(0 != flags & radiotap_flags::DATA_PAD)
+    }
+
+    pub fn slice_ieee80211(mut self, data_pad: bool) -> Result<SlicedPacket<'a>, ReadError> {
+        let header = Ieee80211HeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+        self.move_by_slice(header.slice());
+
+        // skip the padding to the next 4 byte boundary
+        if data_pad {
+            let pad = ((4 - header.slice().len() % 4) % 4).min(self.slice.len());
+            self.move_to_slice(&self.slice[pad..]);
         }
+
+        if header.has_llc_payload() {
+            if let Ok((snap, rest)) = LlcSnapHeader::from_slice(self.slice) {
+                if let Some(ether_type) = snap.ether_type() {
+                    self.move_to_slice(rest);
+                    return self.slice_ether_type_payload(ether_type);
+                }
+            }
+        }
+        self.slice_payload()
     }
 
     pub fn slice_vlan(mut self) -> Result<SlicedPacket<'a>, ReadError> {
@@ -379,15 +863,12 @@ impl<'a> CursorSlice<'a> {
             //in case of a double vlan header continue with the inner
             VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => self.slice_double_vlan(),
             value => {
+                self.registry.limits().check(ParseLimit::VlanTags, 1)?;
+
                 //set the vlan header and continue the normal parsing
                 self.move_by_slice(single.slice());
                 self.result.vlan = Some(SingleVlan(single));
-
-                match value {
-                    IPV4 => self.slice_ipv4(),
-                    IPV6 => self.slice_ipv6(),
-                    _ => self.slice_payload()
-                }
+                self.slice_ether_type_payload(value)
             }
         }
     }
@@ -400,6 +881,7 @@ impl<'a> CursorSlice<'a> {
                      .map_err(|err| 
                         err.add_slice_offset(self.offset)
                      )?;
+        self.registry.limits().check(ParseLimit::VlanTags, 2)?;
 
         //cache ether_type for later
         let ether_type = result.inner().ether_type();
@@ -412,6 +894,121 @@ impl<'a> CursorSlice<'a> {
         match ether_type {
             IPV4 => self.slice_ipv4(),
             IPV6 => self.slice_ipv6(),
+            MPLS_UNICAST | MPLS_MULTICAST => self.slice_mpls(),
+            ARP => self.slice_arp(),
+            NSH => self.slice_nsh(),
+            PPPOE_DISCOVERY | PPPOE_SESSION => self.slice_pppoe(),
+            MACSEC => self.slice_macsec(),
+            value => self.slice_link_extension(value)
+        }
+    }
+
+    pub fn slice_mpls(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use ether_type::*;
+
+        let result = MplsStackSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+        self.registry.limits().check(ParseLimit::MplsLabels, result.len())?;
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.mpls = Some(result);
+
+        //continue parsing (if the payload looks like an ip packet)
+        match MplsStackSlice::payload_ether_type(self.slice) {
+            Some(IPV4) => self.slice_ipv4(),
+            Some(IPV6) => self.slice_ipv6(),
+            _ => self.slice_payload()
+        }
+    }
+
+    pub fn slice_macsec(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use ether_type::*;
+
+        let result = MacsecHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the ether type of cleartext frames for later
+        let ether_type = result.ether_type();
+
+        //SecTAGs following a vlan header are left in the payload if they are
+        //nested or followed by further vlan headers (only one vlan field exists)
+        if self.result.macsec.is_some() || (self.result.vlan.is_some() && matches!(ether_type, Some(VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME))) {
+            return self.slice_payload();
+        }
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.macsec = Some(result);
+
+        //continue parsing cleartext frames (nested SecTAGs are left in the payload)
+        match ether_type {
+            Some(MACSEC) | None => self.slice_payload(),
+            Some(value) => self.slice_ether_type_payload(value)
+        }
+    }
+
+    pub fn slice_pppoe(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use ether_type::*;
+
+        let result = PppoeHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the ether type of the ppp protocol for later
+        let ether_type = result.ether_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.pppoe = Some(result);
+
+        //continue parsing (if required)
+        match ether_type {
+            Some(IPV4) => self.slice_ipv4(),
+            Some(IPV6) => self.slice_ipv6(),
+            _ => self.slice_payload()
+        }
+    }
+
+    pub fn slice_arp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = ArpPacketSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //set the new data (the rest is padding)
+        self.move_by_slice(result.slice());
+        self.result.arp = Some(result);
+        self.slice_payload()
+    }
+
+    pub fn slice_link_extension(mut self, ether_type: u16) -> Result<SlicedPacket<'a>, ReadError> {
+        use ether_type::*;
+
+        let result = match self.registry.parse_ether_type(ether_type, self.slice) {
+            Some(result) => result.map_err(|err| 
+                                err.add_slice_offset(self.offset)
+                            )?,
+            // no parser registered
+            None => return self.slice_payload()
+        };
+
+        //cache the next ether type for later
+        let next_ether_type = result.next_ether_type;
+
+        //set the new data
+        self.move_by_slice(result.header);
+        self.result.link_extension = Some(result);
+
+        //continue parsing (if required)
+        match next_ether_type {
+            Some(IPV4) => self.slice_ipv4(),
+            Some(IPV6) => self.slice_ipv6(),
             _ => self.slice_payload()
         }
     }
@@ -437,6 +1034,7 @@ impl<'a> CursorSlice<'a> {
                         .map_err(|err| 
                             err.add_slice_offset(self.offset)
                         )?;
+        self.registry.limits().check(ParseLimit::OptionBytes, ip_header.options().len())?;
         let fragmented = ip_header.is_fragmenting_payload();
 
         // move the slice
@@ -460,10 +1058,16 @@ impl<'a> CursorSlice<'a> {
                 ip_number::TCP => self.slice_tcp(),
                 ip_number::ICMP => self.slice_icmp4(),
                 ip_number::IPV6_ICMP => self.slice_icmp6(),
+                ip_number::IGMP => self.slice_igmp(),
+                ip_number::SCTP => self.slice_sctp(),
+                ip_number::UDP_LITE => self.slice_udp_lite(),
+                ip_number::DCCP => self.slice_dccp(),
+                ip_number::GRE if self.depth < self.registry.limits().max_tunnel_depth => self.slice_gre(),
+                ip_number::ENCAP_SEC => self.slice_esp(),
                 value => {
                     use TransportSlice::*;
                     self.result.transport = Some(Unknown(value));
-                    self.slice_payload()
+                    self.slice_transport_extension(value)
                 }
             }
         }
@@ -486,6 +1090,7 @@ impl<'a> CursorSlice<'a> {
                                               err.add_slice_offset(self.offset)
                                           )?;
         let fragmented = ip_ext.is_fragmenting_payload();
+        self.registry.limits().check(ParseLimit::Ipv6ExtensionHeaders, ip_ext.clone().into_iter().count())?;
 
         // set the new data 
         self.move_to_slice(rest);
@@ -502,10 +1107,15 @@ impl<'a> CursorSlice<'a> {
                 ip_number::UDP => self.slice_udp(),
                 ip_number::TCP => self.slice_tcp(),
                 ip_number::IPV6_ICMP => self.slice_icmp6(),
+                ip_number::SCTP => self.slice_sctp(),
+                ip_number::UDP_LITE => self.slice_udp_lite(),
+                ip_number::DCCP => self.slice_dccp(),
+                ip_number::GRE if self.depth < self.registry.limits().max_tunnel_depth => self.slice_gre(),
+                ip_number::ENCAP_SEC => self.slice_esp(),
                 value => {
                     use TransportSlice::*;
                     self.result.transport = Some(Unknown(value));
-                    self.slice_payload()
+                    self.slice_transport_extension(value)
                 }
             }
         }
@@ -514,10 +1124,10 @@ impl<'a> CursorSlice<'a> {
     pub fn slice_icmp4(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         use crate::TransportSlice::*;
 
-        let result = Icmpv4Slice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
+        let result = match Icmpv4Slice::from_slice(self.slice) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
 
         //set the new data
         self.move_by_slice(result.slice());
@@ -530,10 +1140,10 @@ impl<'a> CursorSlice<'a> {
     pub fn slice_icmp6(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         use crate::TransportSlice::*;
 
-        let result = Icmpv6Slice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
+        let result = match Icmpv6Slice::from_slice(self.slice) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
 
         //set the new data
         self.move_by_slice(result.slice());
@@ -543,38 +1153,329 @@ impl<'a> CursorSlice<'a> {
         self.slice_payload()
     }
 
-    pub fn slice_udp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+    pub fn slice_igmp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         use crate::TransportSlice::*;
 
-        let result = UdpHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
-                        err.add_slice_offset(self.offset)
-                     )?;
+        //bound the message by the ip payload length (otherwise ethernet
+        //padding would be interpreted as part of an IGMPv3 query)
+        let len = match &self.result.ip {
+            Some(InternetSlice::Ipv4(header, ext)) => usize::from(header.payload_len())
+                .saturating_sub(ext.auth.as_ref().map(|auth| auth.slice().len()).unwrap_or(0)),
+            _ => self.slice.len(),
+        };
+        let result = match IgmpSlice::from_slice(&self.slice[..len.min(self.slice.len())]) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
 
         //set the new data
         self.move_by_slice(result.slice());
-        self.result.transport = Some(Udp(result));
+        self.result.transport = Some(Igmp(result));
 
         //done
         self.slice_payload()
     }
 
+    pub fn slice_udp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use crate::TransportSlice::*;
+
+        let result = match UdpHeaderSlice::from_slice(self.slice) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        let transport = Udp(result);
+        self.result.application = self.registry.transport_protocol(&transport);
+        self.result.transport = Some(transport);
+
+        match self.result.application {
+            Some(ApplicationProtocol::Vxlan) if self.depth < self.registry.limits().max_tunnel_depth => self.slice_vxlan(),
+            Some(ApplicationProtocol::Geneve) if self.depth < self.registry.limits().max_tunnel_depth => self.slice_geneve(),
+            Some(ApplicationProtocol::GtpU) if self.depth < self.registry.limits().max_tunnel_depth => self.slice_gtpu(),
+            _ => self.slice_payload()
+        }
+    }
+
     pub fn slice_tcp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         use crate::TransportSlice::*;
 
-        let result = TcpHeaderSlice::from_slice(self.slice)
-                     .map_err(|err| 
+        let result = match TcpHeaderSlice::from_slice(self.slice) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
+        self.registry.limits().check(ParseLimit::OptionBytes, result.options().len())?;
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        let transport = Tcp(result);
+        self.result.application = self.registry.transport_protocol(&transport);
+        self.result.transport = Some(transport);
+
+        //done
+        self.slice_payload()
+    }
+
+    pub fn slice_sctp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use crate::TransportSlice::*;
+
+        let result = match SctpHeaderSlice::from_slice(self.slice) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.transport = Some(Sctp(result));
+
+        //done
+        self.slice_payload()
+    }
+
+    pub fn slice_udp_lite(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use crate::TransportSlice::*;
+
+        let result = match UdpLiteHeaderSlice::from_slice(self.slice) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.transport = Some(UdpLite(result));
+
+        //done
+        self.slice_payload()
+    }
+
+    pub fn slice_dccp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use crate::TransportSlice::*;
+
+        let result = match DccpHeaderSlice::from_slice(self.slice) {
+            Ok(result) => result,
+            Err(err) => return self.transport_error(err),
+        };
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.transport = Some(Dccp(result));
+
+        //done
+        self.slice_payload()
+    }
+
+    pub fn slice_esp(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = EspHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.transport = Some(TransportSlice::Unknown(ip_number::ENCAP_SEC));
+        self.result.esp = Some(result);
+
+        //the payload contains the encrypted data
+        self.slice_payload()
+    }
+
+    pub fn slice_gre(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        use ReadError::*;
+        let result = match GreHeaderSlice::from_slice(self.slice) {
+            Ok(result) => result,
+            // unsupported versions (e.g. enhanced GRE used by PPTP) &
+            // headers with routing information are left in the payload
+            Err(GreUnsupportedVersion(_)) | Err(GreRoutingNotSupported) => {
+                self.result.transport = Some(TransportSlice::Unknown(ip_number::GRE));
+                return self.slice_payload();
+            },
+            Err(err) => return Err(err.add_slice_offset(self.offset)),
+        };
+
+        //cache the protocol type & sequence number flag for later
+        let protocol_type = result.protocol_type();
+        let sequence_number_present = result.sequence_number_present();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.transport = Some(TransportSlice::Unknown(ip_number::GRE));
+        self.result.gre = Some(result);
+
+        let inner_ether_type = match protocol_type {
+            // erspan type I (no erspan header) is only identified by the missing sequence number
+            ether_type::ERSPAN_TYPE_2 if false == sequence_number_present => Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING),
+            ether_type::ERSPAN_TYPE_2 | ether_type::ERSPAN_TYPE_3 => {
+                let erspan = ErspanHeaderSlice::from_slice(self.slice)
+                             .map_err(|err|
+                                err.add_slice_offset(self.offset)
+                             )?;
+                self.move_by_slice(erspan.slice());
+                self.result.erspan = Some(erspan.clone());
+                if erspan.is_ethernet_frame() {
+                    Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING)
+                } else {
+                    None
+                }
+            },
+            _ => Some(protocol_type),
+        };
+        //slice the encapsulated packet
+        if let Some(ether_type) = inner_ether_type {
+            self.result.inner = self.slice_inner(ether_type)?;
+        }
+
+        //the payload contains the encapsulated packet
+        self.slice_payload()
+    }
+
+    /// Slices the encapsulated packet of a tunnel based on its protocol type.
+    ///
+    /// If the encapsulated packet can not be sliced `None` is returned (the
+    /// packet stays in the payload of the tunnel), only exceeded parse limits
+    /// are returned as errors.
+    fn slice_inner(&self, protocol_type: u16) -> Result<Option<Box<SlicedPacket<'a>>>, ReadError> {
+        use ether_type::*;
+
+        let mut inner = CursorSlice::new(self.slice, self.registry);
+        inner.offset = self.offset;
+        inner.depth = self.depth + 1;
+        let result = match protocol_type {
+            TRANSPARENT_ETHERNET_BRIDGING => inner.slice_ethernet2(),
+            IPV4 => inner.slice_ipv4(),
+            IPV6 => inner.slice_ipv6(),
+            VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME => inner.slice_vlan(),
+            MPLS_UNICAST | MPLS_MULTICAST => inner.slice_mpls(),
+            ARP => inner.slice_arp(),
+            NSH => inner.slice_nsh(),
+            PPPOE_DISCOVERY | PPPOE_SESSION => inner.slice_pppoe(),
+            MACSEC => inner.slice_macsec(),
+            value => inner.slice_link_extension(value)
+        };
+        match result {
+            Ok(inner) => Ok(Some(Box::new(inner))),
+            Err(err @ ReadError::ParseLimitExceeded(_)) => Err(err),
+            Err(_) => Ok(None),
+        }
+    }
+
+    pub fn slice_vxlan(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = VxlanHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.vxlan = Some(result);
+
+        //slice the encapsulated ethernet frame
+        self.result.inner = self.slice_inner(ether_type::TRANSPARENT_ETHERNET_BRIDGING)?;
+
+        //the payload contains the encapsulated frame
+        self.slice_payload()
+    }
+
+    pub fn slice_geneve(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = GeneveHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the protocol type for later
+        let protocol_type = result.protocol_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.geneve = Some(result);
+
+        //slice the encapsulated packet
+        self.result.inner = self.slice_inner(protocol_type)?;
+
+        //the payload contains the encapsulated packet
+        self.slice_payload()
+    }
+
+    pub fn slice_gtpu(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = GtpuHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
+                        err.add_slice_offset(self.offset)
+                     )?;
+
+        //cache the message type for later
+        let message_type = result.message_type();
+
+        //set the new data
+        self.move_by_slice(result.slice());
+        self.result.gtpu = Some(result);
+
+        //slice the encapsulated ip packet of G-PDUs
+        if gtpu_message_type::G_PDU == message_type {
+            match self.slice.first().map(|value| value >> 4) {
+                Some(4) => self.result.inner = self.slice_inner(ether_type::IPV4)?,
+                Some(6) => self.result.inner = self.slice_inner(ether_type::IPV6)?,
+                _ => {},
+            }
+        }
+
+        //the payload contains the encapsulated packet
+        self.slice_payload()
+    }
+
+    pub fn slice_nsh(mut self) -> Result<SlicedPacket<'a>, ReadError> {
+        //nsh headers nested deeper then the tunnel limit are left in the payload
+        if self.depth >= self.registry.limits().max_tunnel_depth {
+            return self.slice_payload();
+        }
+
+        let result = NshHeaderSlice::from_slice(self.slice)
+                     .map_err(|err|
                         err.add_slice_offset(self.offset)
                      )?;
 
+        //cache the next ether type for later
+        let next_ether_type = result.next_ether_type();
+
         //set the new data
         self.move_by_slice(result.slice());
-        self.result.transport = Some(Tcp(result));
+        self.result.nsh = Some(result);
+
+        //slice the encapsulated packet (if the next protocol is known)
+        if let Some(ether_type) = next_ether_type {
+            self.result.inner = self.slice_inner(ether_type)?;
+        }
+
+        //the payload contains the encapsulated packet
+        self.slice_payload()
+    }
+
+    pub fn slice_transport_extension(mut self, ip_number: u8) -> Result<SlicedPacket<'a>, ReadError> {
+        let result = match self.registry.parse_ip_number(ip_number, self.slice) {
+            Some(result) => result.map_err(|err| 
+                                err.add_slice_offset(self.offset)
+                            )?,
+            // no parser registered
+            None => return self.slice_payload()
+        };
+
+        //set the new data
+        self.move_by_slice(result.header);
+        self.result.transport_extension = Some(result);
 
         //done
         self.slice_payload()
     }
 
+    /// Returns the error of a transport header or slices the rest as payload
+    /// if the header is cut off & truncated transport headers are tolerated.
+    fn transport_error(self, err: ReadError) -> Result<SlicedPacket<'a>, ReadError> {
+        use ReadError::*;
+        match err {
+            UnexpectedEndOfSlice(_) | UnexpectedLenOfSlice{ .. } if self.truncated_transport => self.slice_payload(),
+            err => Err(err.add_slice_offset(self.offset)),
+        }
+    }
+
     pub fn slice_payload(mut self) -> Result<SlicedPacket<'a>, ReadError> {
         self.result.payload = self.slice;
         Ok(self.result)
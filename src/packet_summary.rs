@@ -0,0 +1,428 @@
+use super::*;
+
+use std::fmt;
+use std::net::IpAddr;
+
+/// Single line human readable summary of the outer headers of a sliced packet.
+///
+/// The summary is written via [`fmt::Display`] or [`PacketSummary::write_to`]
+/// directly to any [`core::fmt::Write`] implementation. No heap allocations
+/// are performed, so the summary can also be written to fixed size buffers
+/// (e.g. for logging on constrained targets).
+///
+/// [`PacketSummary::compact`] returns a compact single token form of the
+/// summary that is suited for structured logs.
+///
+/// If the `names` feature is enabled the summary contains the names of well
+/// known ports, ICMP types & codes, ether types and ip numbers (see the
+/// `names` module). The compact form always contains the numeric values.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketBuilder, PacketSummary, SlicedPacket};
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .ipv4([10,0,0,1], [10,0,0,2], 20)
+///     .tcp(443, 51234, 1, 1024)
+///     .syn()
+///     .write(&mut packet, &[1,2,3,4])
+///     .unwrap();
+///
+/// let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+/// let summary = PacketSummary::from_sliced(&sliced);
+/// #[cfg(not(feature = "names"))]
+/// assert_eq!(
+///     "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, IPv4 10.0.0.1 > 10.0.0.2, TCP 443 > 51234 [S], payload 4 bytes",
+///     summary.to_string()
+/// );
+/// // with the "names" feature the service name replaces the port number
+/// #[cfg(feature = "names")]
+/// assert_eq!(
+///     "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, IPv4 10.0.0.1 > 10.0.0.2, TCP https > 51234 [S], payload 4 bytes",
+///     summary.to_string()
+/// );
+/// assert_eq!(
+///     "v4:tcp:10.0.0.1:443>10.0.0.2:51234:S",
+///     summary.compact().to_string()
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct PacketSummary<'a> {
+    packet: &'a SlicedPacket<'a>,
+}
+
+/// Compact single token summary of a sliced packet (see [`PacketSummary::compact`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct CompactPacketSummary<'a> {
+    packet: &'a SlicedPacket<'a>,
+}
+
+impl<'a> PacketSummary<'a> {
+    /// Creates a summary of the given sliced packet.
+    #[inline]
+    pub fn from_sliced(packet: &'a SlicedPacket<'a>) -> PacketSummary<'a> {
+        PacketSummary { packet }
+    }
+
+    /// Returns the compact single token form of the summary.
+    ///
+    /// The token has the format
+    /// `<ip version>:<protocol>:<source>[:<port>]><destination>[:<port>][:<details>]`
+    /// with
+    ///
+    /// * `v4` or `v6` as ip version (IPv6 addresses are enclosed in brackets),
+    /// * `tcp`, `udp`, `udplite`, `sctp`, `icmp`, `icmp6`, `igmp`, `frag`
+    ///   (fragmented payload), `trunc` (truncated transport header) or the
+    ///   decimal ip number as protocol,
+    /// * the TCP flags in the style of tcpdump (e.g. `S.` for SYN & ACK), the
+    ///   ICMP `<type>.<code>` or the IGMP type as details.
+    ///
+    /// Packets without an ip header are written as `eth:<ether type>` (or
+    /// `raw` if no link layer is present).
+    ///
+    /// ```
+    /// use etherparse::{PacketBuilder, PacketSummary, SlicedPacket};
+    ///
+    /// let mut packet = Vec::new();
+    /// PacketBuilder::ipv6([0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,1], [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2], 20)
+    ///     .udp(53, 1234)
+    ///     .write(&mut packet, &[])
+    ///     .unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    /// assert_eq!(
+    ///     "v6:udp:[2001:db8::1]:53>[2001:db8::2]:1234",
+    ///     PacketSummary::from_sliced(&sliced).compact().to_string()
+    /// );
+    /// ```
+    #[inline]
+    pub fn compact(&self) -> CompactPacketSummary<'a> {
+        CompactPacketSummary { packet: self.packet }
+    }
+
+    /// Writes the summary to the given writer (without any heap allocations).
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result {
+        let packet = self.packet;
+        let mut separator = Separator::default();
+
+        if let Some(LinkSlice::Ethernet2(eth)) = &packet.link {
+            separator.write(writer)?;
+            writer.write_str("Ethernet2 ")?;
+            write_mac(writer, eth.source())?;
+            writer.write_str(" > ")?;
+            write_mac(writer, eth.destination())?;
+        }
+
+        match &packet.vlan {
+            Some(VlanSlice::SingleVlan(vlan)) => {
+                separator.write(writer)?;
+                write!(writer, "VLAN {}", vlan.vlan_identifier())?;
+            },
+            Some(VlanSlice::DoubleVlan(vlan)) => {
+                separator.write(writer)?;
+                write!(writer, "VLAN {}.{}", vlan.outer().vlan_identifier(), vlan.inner().vlan_identifier())?;
+            },
+            None => {},
+        }
+
+        match &packet.ip {
+            Some(InternetSlice::Ipv4(header, _)) => {
+                separator.write(writer)?;
+                write!(writer, "IPv4 {} > {}", header.source_addr(), header.destination_addr())?;
+                if header.is_fragmenting_payload() {
+                    writer.write_str(" fragment")?;
+                }
+            },
+            Some(InternetSlice::Ipv6(header, extensions)) => {
+                separator.write(writer)?;
+                write!(writer, "IPv6 {} > {}", header.source_addr(), header.destination_addr())?;
+                if extensions.is_fragmenting_payload() {
+                    writer.write_str(" fragment")?;
+                }
+            },
+            None => if let Some(ether_type) = last_ether_type(packet) {
+                separator.write(writer)?;
+                write!(writer, "ether type 0x{:04x}", ether_type)?;
+                write_name_suffix(writer, Named::EtherType(ether_type))?;
+            },
+        }
+
+        if let Some(transport) = &packet.transport {
+            use TransportSlice::*;
+            separator.write(writer)?;
+            match transport {
+                Icmpv4(icmp) => {
+                    writer.write_str("ICMPv4 ")?;
+                    write_icmp(writer, icmp.type_u8(), icmp.code_u8(), Named::Icmpv4Type, Named::Icmpv4Code)?;
+                },
+                Icmpv6(icmp) => {
+                    writer.write_str("ICMPv6 ")?;
+                    write_icmp(writer, icmp.type_u8(), icmp.code_u8(), Named::Icmpv6Type, Named::Icmpv6Code)?;
+                },
+                Igmp(igmp) => write!(writer, "IGMP type 0x{:02x}", igmp.type_u8())?,
+                Udp(udp) => {
+                    writer.write_str("UDP ")?;
+                    write_ports(writer, udp.source_port(), udp.destination_port(), Named::UdpPort)?;
+                },
+                UdpLite(udp) => {
+                    writer.write_str("UDP-Lite ")?;
+                    write_ports(writer, udp.source_port(), udp.destination_port(), Named::UdpPort)?;
+                },
+                Dccp(dccp) => write!(writer, "DCCP {} > {}", dccp.source_port(), dccp.destination_port())?,
+                Sctp(sctp) => write!(writer, "SCTP {} > {}", sctp.source_port(), sctp.destination_port())?,
+                Tcp(tcp) => {
+                    writer.write_str("TCP ")?;
+                    write_ports(writer, tcp.source_port(), tcp.destination_port(), Named::TcpPort)?;
+                    writer.write_str(" [")?;
+                    write_tcp_flags(writer, tcp)?;
+                    writer.write_char(']')?;
+                },
+                Unknown(ip_number) => {
+                    write!(writer, "ip number {}", ip_number)?;
+                    write_name_suffix(writer, Named::IpNumber(*ip_number))?;
+                },
+            }
+        }
+
+        separator.write(writer)?;
+        write!(writer, "payload {} bytes", packet.payload.len())
+    }
+}
+
+impl<'a> fmt::Display for PacketSummary<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
+
+impl<'a> CompactPacketSummary<'a> {
+    /// Writes the compact summary to the given writer (without any heap allocations).
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, writer: &mut W) -> fmt::Result {
+        use TransportSlice::*;
+
+        let packet = self.packet;
+        let (version, source, destination, fragmented) = match &packet.ip {
+            Some(InternetSlice::Ipv4(header, _)) => (
+                "v4",
+                IpAddr::V4(header.source_addr()),
+                IpAddr::V4(header.destination_addr()),
+                header.is_fragmenting_payload(),
+            ),
+            Some(InternetSlice::Ipv6(header, extensions)) => (
+                "v6",
+                IpAddr::V6(header.source_addr()),
+                IpAddr::V6(header.destination_addr()),
+                extensions.is_fragmenting_payload(),
+            ),
+            None => {
+                return match last_ether_type(packet) {
+                    Some(ether_type) => write!(writer, "eth:0x{:04x}", ether_type),
+                    None => writer.write_str("raw"),
+                };
+            },
+        };
+
+        // protocol & ports
+        let ports = match &packet.transport {
+            Some(Udp(udp)) => Some((udp.source_port(), udp.destination_port())),
+            Some(UdpLite(udp)) => Some((udp.source_port(), udp.destination_port())),
+            Some(Dccp(dccp)) => Some((dccp.source_port(), dccp.destination_port())),
+            Some(Tcp(tcp)) => Some((tcp.source_port(), tcp.destination_port())),
+            Some(Sctp(sctp)) => Some((sctp.source_port(), sctp.destination_port())),
+            _ => None,
+        };
+        write!(writer, "{}:", version)?;
+        match &packet.transport {
+            Some(Icmpv4(_)) => writer.write_str("icmp")?,
+            Some(Icmpv6(_)) => writer.write_str("icmp6")?,
+            Some(Igmp(_)) => writer.write_str("igmp")?,
+            Some(Udp(_)) => writer.write_str("udp")?,
+            Some(UdpLite(_)) => writer.write_str("udplite")?,
+            Some(Dccp(_)) => writer.write_str("dccp")?,
+            Some(Tcp(_)) => writer.write_str("tcp")?,
+            Some(Sctp(_)) => writer.write_str("sctp")?,
+            Some(Unknown(ip_number)) => write!(writer, "{}", ip_number)?,
+            None if fragmented => writer.write_str("frag")?,
+            // transport header cut off (e.g. in the datagram embedded in an icmp error)
+            None => writer.write_str("trunc")?,
+        }
+
+        // addresses
+        writer.write_char(':')?;
+        write_compact_addr(writer, source)?;
+        if let Some((source_port, _)) = ports {
+            write!(writer, ":{}", source_port)?;
+        }
+        writer.write_char('>')?;
+        write_compact_addr(writer, destination)?;
+        if let Some((_, destination_port)) = ports {
+            write!(writer, ":{}", destination_port)?;
+        }
+
+        // details
+        match &packet.transport {
+            Some(Tcp(tcp)) => {
+                writer.write_char(':')?;
+                write_tcp_flags(writer, tcp)
+            },
+            Some(Icmpv4(icmp)) => write!(writer, ":{}.{}", icmp.type_u8(), icmp.code_u8()),
+            Some(Icmpv6(icmp)) => write!(writer, ":{}.{}", icmp.type_u8(), icmp.code_u8()),
+            Some(Igmp(igmp)) => write!(writer, ":0x{:02x}", igmp.type_u8()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'a> fmt::Display for CompactPacketSummary<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
+
+/// Writes ", " before all but the first element.
+#[derive(Default)]
+struct Separator {
+    written: bool,
+}
+
+impl Separator {
+    fn write<W: fmt::Write + ?Sized>(&mut self, writer: &mut W) -> fmt::Result {
+        if self.written {
+            writer.write_str(", ")?;
+        }
+        self.written = true;
+        Ok(())
+    }
+}
+
+/// Numeric protocol values for which the name is written in the summary
+/// (only if the `names` feature is enabled).
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "names"), allow(dead_code))]
+enum Named {
+    EtherType(u16),
+    IpNumber(u8),
+    TcpPort(u16),
+    UdpPort(u16),
+    Icmpv4Type(u8),
+    Icmpv4Code(u8, u8),
+    Icmpv6Type(u8),
+    Icmpv6Code(u8, u8),
+}
+
+impl Named {
+    #[cfg(feature = "names")]
+    fn name(self) -> Option<&'static str> {
+        use Named::*;
+        match self {
+            EtherType(value) => names::ether_type(value),
+            IpNumber(value) => names::ip_number(value),
+            TcpPort(port) => names::tcp_port(port),
+            UdpPort(port) => names::udp_port(port),
+            Icmpv4Type(type_u8) => names::icmpv4_type(type_u8),
+            Icmpv4Code(type_u8, code_u8) => names::icmpv4_code(type_u8, code_u8),
+            Icmpv6Type(type_u8) => names::icmpv6_type(type_u8),
+            Icmpv6Code(type_u8, code_u8) => names::icmpv6_code(type_u8, code_u8),
+        }
+    }
+
+    #[cfg(not(feature = "names"))]
+    #[inline]
+    fn name(self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Writes " (<name>)" if a name is known for the value.
+fn write_name_suffix<W: fmt::Write + ?Sized>(writer: &mut W, value: Named) -> fmt::Result {
+    match value.name() {
+        Some(name) => write!(writer, " ({})", name),
+        None => Ok(()),
+    }
+}
+
+/// Writes "<source> > <destination>" with the service names replacing the
+/// port numbers if known.
+fn write_ports<W: fmt::Write + ?Sized>(
+    writer: &mut W,
+    source: u16,
+    destination: u16,
+    named: fn(u16) -> Named
+) -> fmt::Result {
+    for (port, suffix) in [(source, " > "), (destination, "")] {
+        match named(port).name() {
+            Some(name) => writer.write_str(name)?,
+            None => write!(writer, "{}", port)?,
+        }
+        writer.write_str(suffix)?;
+    }
+    Ok(())
+}
+
+/// Writes the icmp type & code (the names replace "type <type>" & "code <code>"
+/// if known).
+fn write_icmp<W: fmt::Write + ?Sized>(
+    writer: &mut W,
+    type_u8: u8,
+    code_u8: u8,
+    named_type: fn(u8) -> Named,
+    named_code: fn(u8, u8) -> Named
+) -> fmt::Result {
+    match named_type(type_u8).name() {
+        Some(name) => writer.write_str(name)?,
+        None => write!(writer, "type {}", type_u8)?,
+    }
+    match named_code(type_u8, code_u8).name() {
+        Some(name) => write!(writer, " {}", name),
+        None => write!(writer, " code {}", code_u8),
+    }
+}
+
+/// Ether type of the last link layer header (`None` if no link layer header is present).
+fn last_ether_type(packet: &SlicedPacket) -> Option<u16> {
+    match (&packet.vlan, &packet.link) {
+        (Some(VlanSlice::SingleVlan(vlan)), _) => Some(vlan.ether_type()),
+        (Some(VlanSlice::DoubleVlan(vlan)), _) => Some(vlan.inner().ether_type()),
+        (None, Some(LinkSlice::Ethernet2(eth))) => Some(eth.ether_type()),
+        (None, None) => None,
+    }
+}
+
+fn write_mac<W: fmt::Write + ?Sized>(writer: &mut W, mac: [u8;6]) -> fmt::Result {
+    write!(
+        writer,
+        "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+        mac[0], mac[1], mac[2], mac[3], mac[4], mac[5]
+    )
+}
+
+/// Writes the address with IPv6 addresses enclosed in brackets.
+fn write_compact_addr<W: fmt::Write + ?Sized>(writer: &mut W, addr: IpAddr) -> fmt::Result {
+    match addr {
+        IpAddr::V4(addr) => write!(writer, "{}", addr),
+        IpAddr::V6(addr) => write!(writer, "[{}]", addr),
+    }
+}
+
+/// Writes the tcp flags in the style of tcpdump ("none" if no flag is set).
+fn write_tcp_flags<W: fmt::Write + ?Sized>(writer: &mut W, tcp: &TcpHeaderSlice) -> fmt::Result {
+    let flags = [
+        (tcp.fin(), 'F'),
+        (tcp.syn(), 'S'),
+        (tcp.rst(), 'R'),
+        (tcp.psh(), 'P'),
+        (tcp.ack(), '.'),
+        (tcp.urg(), 'U'),
+        (tcp.ece(), 'E'),
+        (tcp.cwr(), 'W'),
+    ];
+    if flags.iter().all(|(set, _)| false == *set) {
+        return writer.write_str("none");
+    }
+    for (_, c) in flags.iter().filter(|(set, _)| *set) {
+        writer.write_char(*c)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use super::*;
+
+/// Limits the amount of headers & options that get decoded per packet.
+///
+/// The limits are set in a [`ParserRegistry`] (see [`ParserRegistry::set_limits`])
+/// and enforced by [`SlicedPacket`] & [`PacketHeaders`] when the `*_with_registry`
+/// functions are used. This allows services parsing untrusted traffic to bound
+/// the worst-case work per packet.
+///
+/// If a packet exceeds one of the counting limits a
+/// [`ReadError::ParseLimitExceeded`] error is returned. Tunnels nested deeper
+/// then `max_tunnel_depth` are not an error, instead the encapsulated packet is
+/// left undecoded in the payload.
+///
+/// The default limits don't restrict the decoding (besides the tunnel depth of 4).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ParseLimit, ParseLimits, ParserRegistry, PacketBuilder, ReadError, SlicedPacket};
+///
+/// let mut registry = ParserRegistry::new();
+/// registry.set_limits(ParseLimits {
+///     max_vlan_tags: 1,
+///     ..Default::default()
+/// });
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+///     .double_vlan(1, 2)
+///     .ipv4([192,168,1,1], [192,168,1,2], 20)
+///     .udp(21, 1234)
+///     .write(&mut packet, &[1,2,3,4])
+///     .unwrap();
+///
+/// assert!(matches!(
+///     SlicedPacket::from_ethernet_with_registry(&packet, &registry),
+///     Err(ReadError::ParseLimitExceeded(ParseLimit::VlanTags))
+/// ));
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ParseLimits {
+    /// Maximum number of vlan tags.
+    pub max_vlan_tags: usize,
+    /// Maximum number of MPLS label stack entries.
+    pub max_mpls_labels: usize,
+    /// Maximum number of IPv6 extension headers.
+    pub max_ipv6_extension_headers: usize,
+    /// Maximum number of nested tunnels (GRE or VXLAN) that get decoded.
+    pub max_tunnel_depth: usize,
+    /// Maximum length in bytes of the options of a single IPv4 or TCP header.
+    pub max_option_bytes: usize,
+}
+
+impl ParseLimits {
+    /// Limits that don't restrict the decoding of a packet.
+    pub const UNLIMITED: ParseLimits = ParseLimits {
+        max_vlan_tags: usize::MAX,
+        max_mpls_labels: usize::MAX,
+        max_ipv6_extension_headers: usize::MAX,
+        max_tunnel_depth: usize::MAX,
+        max_option_bytes: usize::MAX,
+    };
+
+    /// Default maximum number of nested tunnels that get decoded.
+    pub const DEFAULT_MAX_TUNNEL_DEPTH: usize = 4;
+
+    /// Returns an error if the value is larger then the given limit.
+    pub(crate) fn check(&self, limit: ParseLimit, value: usize) -> Result<(), ReadError> {
+        use ParseLimit::*;
+        let max = match limit {
+            VlanTags => self.max_vlan_tags,
+            MplsLabels => self.max_mpls_labels,
+            Ipv6ExtensionHeaders => self.max_ipv6_extension_headers,
+            OptionBytes => self.max_option_bytes,
+        };
+        if value > max {
+            Err(ReadError::ParseLimitExceeded(limit))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        ParseLimits {
+            max_tunnel_depth: ParseLimits::DEFAULT_MAX_TUNNEL_DEPTH,
+            ..ParseLimits::UNLIMITED
+        }
+    }
+}
+
+/// Limit of [`ParseLimits`] that was exceeded by a packet.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ParseLimit {
+    /// `max_vlan_tags` was exceeded.
+    VlanTags,
+    /// `max_mpls_labels` was exceeded.
+    MplsLabels,
+    /// `max_ipv6_extension_headers` was exceeded.
+    Ipv6ExtensionHeaders,
+    /// `max_option_bytes` was exceeded.
+    OptionBytes,
+}
@@ -0,0 +1,429 @@
+use super::*;
+
+/// Parser for a custom (e.g. proprietary) header identified by an ether type.
+///
+/// Parsers are registered in a [`ParserRegistry`] and called by
+/// [`SlicedPacket::from_ethernet_with_registry`] & [`PacketHeaders::from_ethernet_slice_with_registry`]
+/// (and the `from_ether_type` equivalents) whenever an ether type is encountered
+/// that etherparse itself does not decode. The decoded header is then exposed
+/// via the `link_extension` field.
+///
+/// The trait is implemented for all closures with a matching signature.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{EtherTypeParser, LinkExtensionInfo, ReadError};
+///
+/// /// Header with a fixed length of 4 bytes, where the last
+/// /// two bytes contain the ether type of the payload.
+/// struct MyHeaderParser;
+///
+/// impl EtherTypeParser for MyHeaderParser {
+///     fn parse(&self, _ether_type: u16, slice: &[u8]) -> Result<LinkExtensionInfo, ReadError> {
+///         if slice.len() < 4 {
+///             return Err(ReadError::UnexpectedEndOfSlice(4));
+///         }
+///         Ok(LinkExtensionInfo {
+///             header_len: 4,
+///             next_ether_type: Some(u16::from_be_bytes([slice[2], slice[3]])),
+///         })
+///     }
+/// }
+/// ```
+pub trait EtherTypeParser: Send + Sync {
+    /// Decodes the header at the start of the slice and returns the length of
+    /// the header & the ether type of the data following it.
+    ///
+    /// `UnexpectedEndOfSlice` errors should contain the minimum length
+    /// relative to the start of the given slice (the offset of the header in
+    /// the packet is added by the caller).
+    fn parse(&self, ether_type: u16, slice: &[u8]) -> Result<LinkExtensionInfo, ReadError>;
+}
+
+impl<F> EtherTypeParser for F
+where
+    F: Fn(u16, &[u8]) -> Result<LinkExtensionInfo, ReadError> + Send + Sync,
+{
+    fn parse(&self, ether_type: u16, slice: &[u8]) -> Result<LinkExtensionInfo, ReadError> {
+        self(ether_type, slice)
+    }
+}
+
+/// Parser for a custom transport layer header identified by an ip number
+/// (e.g. the experimental protocol numbers 253 & 254 defined in RFC 3692).
+///
+/// Parsers are registered in a [`ParserRegistry`] and called by `SlicedPacket`
+/// & `PacketHeaders` (via the `*_with_registry` functions) whenever an ip
+/// payload with an ip number not supported by etherparse is encountered
+/// (payloads of fragmented packets are not passed to the parsers). The decoded
+/// header is then exposed via the `transport_extension` field.
+///
+/// The trait is implemented for all closures with a matching signature.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ipv4Header, ParserRegistry, ReadError, SlicedPacket};
+///
+/// let mut registry = ParserRegistry::new();
+/// // header where the first byte contains the header length
+/// registry.register_ip_number(253, |_ip_number: u8, slice: &[u8]| {
+///     slice.first()
+///         .map(|len| usize::from(*len))
+///         .ok_or(ReadError::UnexpectedEndOfSlice(1))
+/// });
+///
+/// let mut packet = Vec::new();
+/// Ipv4Header::new(6, 20, 253, [192,168,1,1], [192,168,1,2])
+///     .write(&mut packet)
+///     .unwrap();
+/// packet.extend_from_slice(&[2, 0xab, 1, 2, 3, 4]);
+///
+/// let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+/// assert_eq!(&[2, 0xab], sliced.transport_extension.unwrap().header);
+/// assert_eq!(&[1, 2, 3, 4], sliced.payload);
+/// ```
+pub trait IpNumberParser: Send + Sync {
+    /// Decodes the header at the start of the slice and returns the length of the header.
+    /// The data after the header is set as payload.
+    ///
+    /// `UnexpectedEndOfSlice` errors should contain the minimum length
+    /// relative to the start of the given slice (the offset of the header in
+    /// the packet is added by the caller).
+    fn parse(&self, ip_number: u8, slice: &[u8]) -> Result<usize, ReadError>;
+}
+
+impl<F> IpNumberParser for F
+where
+    F: Fn(u8, &[u8]) -> Result<usize, ReadError> + Send + Sync,
+{
+    fn parse(&self, ip_number: u8, slice: &[u8]) -> Result<usize, ReadError> {
+        self(ip_number, slice)
+    }
+}
+
+/// Result of an [`EtherTypeParser`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct LinkExtensionInfo {
+    /// Length of the custom header in bytes.
+    pub header_len: usize,
+    /// Ether type of the data following the header (`None` if unknown).
+    ///
+    /// Parsing continues with the ip layer if the value is `ether_type::IPV4`
+    /// or `ether_type::IPV6`. For all other values the data after the header
+    /// is set as payload.
+    pub next_ether_type: Option<u16>,
+}
+
+/// Custom link layer header decoded by an [`EtherTypeParser`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct LinkExtensionSlice<'a> {
+    /// Ether type the parser was registered for.
+    pub ether_type: u16,
+    /// Slice containing the header (length as reported by the parser).
+    pub header: &'a [u8],
+    /// Ether type of the data following the header (as reported by the parser).
+    pub next_ether_type: Option<u16>,
+}
+
+/// Custom transport layer header decoded by an [`IpNumberParser`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TransportExtensionSlice<'a> {
+    /// Ip number the parser was registered for.
+    pub ip_number: u8,
+    /// Slice containing the header (length as reported by the parser).
+    pub header: &'a [u8],
+}
+
+/// Application layer protocols that can be assigned to udp & tcp ports in a [`ParserRegistry`].
+///
+/// The protocol assigned to the ports of a packet is exposed via
+/// [`SlicedPacket::application`] and can be used to decide which application
+/// layer parser (e.g. [`DnsNameSlice`], [`HsrpMessage`], [`RipMessageSlice`] or [`StampSenderPacket`])
+/// should be used to decode the payload.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ApplicationProtocol {
+    /// Domain Name System (including multicast DNS).
+    Dns,
+    /// Hot Standby Router Protocol.
+    Hsrp,
+    /// Routing Information Protocol.
+    Rip,
+    /// Virtual eXtensible Local Area Network (RFC 7348), the vxlan header &
+    /// the encapsulated ethernet frame are decoded (see [`VxlanHeader`]).
+    Vxlan,
+    /// GTP user plane (GPRS Tunnelling Protocol), the gtp-u header & the
+    /// encapsulated ip packet of G-PDUs are decoded (see [`GtpuHeader`]).
+    GtpU,
+    /// Generic Network Virtualization Encapsulation (RFC 8926), the geneve
+    /// header & the encapsulated packet are decoded (see [`GeneveHeader`]).
+    Geneve,
+    /// Simple Two-way Active Measurement Protocol (RFC 8762).
+    Stamp,
+    /// QUIC (RFC 9000), the version independent header invariants can be
+    /// sliced from the payload (see [`QuicHeaderSlice`]).
+    Quic,
+    /// User defined protocol (the value can be freely chosen by the user).
+    Custom(u16),
+}
+
+/// Table of user registered parsers for protocols not supported by etherparse.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{ParserRegistry, LinkExtensionInfo, SlicedPacket};
+///
+/// let mut registry = ParserRegistry::new();
+/// // 0x88b5 is reserved for local experimental use
+/// registry.register_ether_type(0x88b5, |_ether_type: u16, _slice: &[u8]| {
+///     Ok(LinkExtensionInfo{ header_len: 2, next_ether_type: None })
+/// });
+///
+/// let packet = [
+///     0, 0, 0, 0, 0, 0, // destination
+///     0, 0, 0, 0, 0, 0, // source
+///     0x88, 0xb5,       // ether type
+///     0xab, 0xcd,       // custom header
+///     1, 2, 3, 4,       // payload
+/// ];
+/// let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+///
+/// let extension = sliced.link_extension.unwrap();
+/// assert_eq!(0x88b5, extension.ether_type);
+/// assert_eq!(&[0xab, 0xcd], extension.header);
+/// assert_eq!(&[1, 2, 3, 4], sliced.payload);
+/// ```
+#[derive(Default)]
+pub struct ParserRegistry {
+    ether_types: Vec<(u16, Box<dyn EtherTypeParser>)>,
+    ip_numbers: Vec<(u8, Box<dyn IpNumberParser>)>,
+    udp_ports: Vec<(u16, ApplicationProtocol)>,
+    tcp_ports: Vec<(u16, ApplicationProtocol)>,
+    limits: ParseLimits,
+    ether_type_length_policy: EtherTypeLengthPolicy,
+}
+
+impl ParserRegistry {
+    /// Creates a registry without any registered parsers.
+    pub fn new() -> ParserRegistry {
+        Default::default()
+    }
+
+    /// Creates a registry with the well known ports of the application
+    /// protocols registered:
+    ///
+    /// * udp & tcp port 53 and udp port 5353 (multicast DNS): [`ApplicationProtocol::Dns`]
+    /// * udp ports 1985 & 2029: [`ApplicationProtocol::Hsrp`]
+    /// * udp port 520: [`ApplicationProtocol::Rip`]
+    /// * udp port 4789: [`ApplicationProtocol::Vxlan`]
+    /// * udp port 2152: [`ApplicationProtocol::GtpU`]
+    /// * udp port 6081: [`ApplicationProtocol::Geneve`]
+    /// * udp port 862: [`ApplicationProtocol::Stamp`]
+    /// * udp port 443: [`ApplicationProtocol::Quic`]
+    pub fn with_well_known_ports() -> ParserRegistry {
+        use ApplicationProtocol::*;
+        let mut result = ParserRegistry::new();
+        result.register_udp_port(53, Dns);
+        result.register_udp_port(5353, Dns);
+        result.register_udp_port(HsrpMessage::UDP_PORT, Hsrp);
+        result.register_udp_port(HsrpMessage::IPV6_UDP_PORT, Hsrp);
+        result.register_udp_port(RipMessageSlice::UDP_PORT, Rip);
+        result.register_udp_port(VxlanHeader::UDP_PORT, Vxlan);
+        result.register_udp_port(GtpuHeader::UDP_PORT, GtpU);
+        result.register_udp_port(GeneveHeader::UDP_PORT, Geneve);
+        result.register_udp_port(StampSenderPacket::UDP_PORT, Stamp);
+        result.register_udp_port(QuicHeaderSlice::UDP_PORT, Quic);
+        result.register_tcp_port(53, Dns);
+        result
+    }
+
+    /// Registers a parser for the given ether type (replacing a previously
+    /// registered parser for the same ether type).
+    ///
+    /// Ether types decoded by etherparse itself (ipv4, ipv6, vlan, mpls, arp,
+    /// nsh, pppoe & macsec) are never passed to registered parsers.
+    pub fn register_ether_type<P: EtherTypeParser + 'static>(&mut self, ether_type: u16, parser: P) {
+        let parser: Box<dyn EtherTypeParser> = Box::new(parser);
+        match self.ether_types.iter_mut().find(|(value, _)| *value == ether_type) {
+            Some(entry) => entry.1 = parser,
+            None => self.ether_types.push((ether_type, parser)),
+        }
+    }
+
+    /// Returns the parser registered for the given ether type.
+    pub fn ether_type_parser(&self, ether_type: u16) -> Option<&dyn EtherTypeParser> {
+        self.ether_types
+            .iter()
+            .find(|(value, _)| *value == ether_type)
+            .map(|(_, parser)| parser.as_ref())
+    }
+
+    /// Registers a parser for the given ip number (replacing a previously
+    /// registered parser for the same ip number).
+    ///
+    /// Ip numbers decoded by etherparse itself (icmp, icmpv6, igmp, udp, tcp,
+    /// sctp, udp-lite, dccp, gre, esp and the ip extension headers) are never
+    /// passed to registered parsers.
+    pub fn register_ip_number<P: IpNumberParser + 'static>(&mut self, ip_number: u8, parser: P) {
+        let parser: Box<dyn IpNumberParser> = Box::new(parser);
+        match self.ip_numbers.iter_mut().find(|(value, _)| *value == ip_number) {
+            Some(entry) => entry.1 = parser,
+            None => self.ip_numbers.push((ip_number, parser)),
+        }
+    }
+
+    /// Returns the parser registered for the given ip number.
+    pub fn ip_number_parser(&self, ip_number: u8) -> Option<&dyn IpNumberParser> {
+        self.ip_numbers
+            .iter()
+            .find(|(value, _)| *value == ip_number)
+            .map(|(_, parser)| parser.as_ref())
+    }
+
+    /// Assigns an application protocol to the given udp port (replacing a
+    /// previously assigned protocol for the same port).
+    pub fn register_udp_port(&mut self, port: u16, protocol: ApplicationProtocol) {
+        ParserRegistry::register_port(&mut self.udp_ports, port, protocol);
+    }
+
+    /// Removes the application protocol assigned to the given udp port
+    /// (returns the removed protocol).
+    pub fn unregister_udp_port(&mut self, port: u16) -> Option<ApplicationProtocol> {
+        ParserRegistry::unregister_port(&mut self.udp_ports, port)
+    }
+
+    /// Returns the application protocol assigned to the given udp port.
+    pub fn udp_port_protocol(&self, port: u16) -> Option<ApplicationProtocol> {
+        ParserRegistry::port_protocol(&self.udp_ports, port)
+    }
+
+    /// Assigns an application protocol to the given tcp port (replacing a
+    /// previously assigned protocol for the same port).
+    pub fn register_tcp_port(&mut self, port: u16, protocol: ApplicationProtocol) {
+        ParserRegistry::register_port(&mut self.tcp_ports, port, protocol);
+    }
+
+    /// Removes the application protocol assigned to the given tcp port
+    /// (returns the removed protocol).
+    pub fn unregister_tcp_port(&mut self, port: u16) -> Option<ApplicationProtocol> {
+        ParserRegistry::unregister_port(&mut self.tcp_ports, port)
+    }
+
+    /// Returns the application protocol assigned to the given tcp port.
+    pub fn tcp_port_protocol(&self, port: u16) -> Option<ApplicationProtocol> {
+        ParserRegistry::port_protocol(&self.tcp_ports, port)
+    }
+
+    /// Sets the limits enforced when decoding packets with this registry.
+    pub fn set_limits(&mut self, limits: ParseLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the limits enforced when decoding packets with this registry.
+    pub fn limits(&self) -> &ParseLimits {
+        &self.limits
+    }
+
+    /// Sets the policy used to interpret the type/length field of ethernet
+    /// headers when decoding packets with this registry.
+    pub fn set_ether_type_length_policy(&mut self, policy: EtherTypeLengthPolicy) {
+        self.ether_type_length_policy = policy;
+    }
+
+    /// Returns the policy used to interpret the type/length field of ethernet
+    /// headers when decoding packets with this registry.
+    pub fn ether_type_length_policy(&self) -> EtherTypeLengthPolicy {
+        self.ether_type_length_policy
+    }
+
+    fn register_port(ports: &mut Vec<(u16, ApplicationProtocol)>, port: u16, protocol: ApplicationProtocol) {
+        match ports.iter_mut().find(|(value, _)| *value == port) {
+            Some(entry) => entry.1 = protocol,
+            None => ports.push((port, protocol)),
+        }
+    }
+
+    fn unregister_port(ports: &mut Vec<(u16, ApplicationProtocol)>, port: u16) -> Option<ApplicationProtocol> {
+        let index = ports.iter().position(|(value, _)| *value == port)?;
+        Some(ports.remove(index).1)
+    }
+
+    fn port_protocol(ports: &[(u16, ApplicationProtocol)], port: u16) -> Option<ApplicationProtocol> {
+        ports
+            .iter()
+            .find(|(value, _)| *value == port)
+            .map(|(_, protocol)| *protocol)
+    }
+
+    /// Returns the application protocol assigned to the ports of a udp or tcp
+    /// header (the destination port takes precedence over the source port).
+    pub(crate) fn transport_protocol(&self, transport: &TransportSlice) -> Option<ApplicationProtocol> {
+        use TransportSlice::*;
+        match transport {
+            Udp(udp) => self.udp_port_protocol(udp.destination_port())
+                            .or_else(|| self.udp_port_protocol(udp.source_port())),
+            Tcp(tcp) => self.tcp_port_protocol(tcp.destination_port())
+                            .or_else(|| self.tcp_port_protocol(tcp.source_port())),
+            _ => None,
+        }
+    }
+
+    /// Decodes the header with a registered parser (`None` if no parser is registered
+    /// for the ether type).
+    pub(crate) fn parse_ether_type<'a>(&self, ether_type: u16, slice: &'a [u8]) -> Option<Result<LinkExtensionSlice<'a>, ReadError>> {
+        use ether_type::*;
+        if let IPV4 | IPV6 | VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME | MPLS_UNICAST | MPLS_MULTICAST | ARP | NSH | PPPOE_DISCOVERY | PPPOE_SESSION | MACSEC = ether_type {
+            return None;
+        }
+        let parser = self.ether_type_parser(ether_type)?;
+        Some(parser.parse(ether_type, slice).and_then(|info| {
+            if info.header_len > slice.len() {
+                Err(ReadError::UnexpectedEndOfSlice(info.header_len))
+            } else {
+                Ok(LinkExtensionSlice {
+                    ether_type,
+                    header: &slice[..info.header_len],
+                    next_ether_type: info.next_ether_type,
+                })
+            }
+        }))
+    }
+
+    /// Decodes the header with a registered parser (`None` if no parser is registered
+    /// for the ip number).
+    pub(crate) fn parse_ip_number<'a>(&self, ip_number: u8, slice: &'a [u8]) -> Option<Result<TransportExtensionSlice<'a>, ReadError>> {
+        use ip_number::*;
+        if let ICMP | IPV6_ICMP | IGMP | UDP | TCP | SCTP | UDP_LITE | DCCP | GRE | ENCAP_SEC = ip_number {
+            return None;
+        }
+        let parser = self.ip_number_parser(ip_number)?;
+        Some(parser.parse(ip_number, slice).and_then(|header_len| {
+            if header_len > slice.len() {
+                Err(ReadError::UnexpectedEndOfSlice(header_len))
+            } else {
+                Ok(TransportExtensionSlice {
+                    ip_number,
+                    header: &slice[..header_len],
+                })
+            }
+        }))
+    }
+}
+
+impl fmt::Debug for ParserRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParserRegistry")
+            .field("ether_types", &self.ether_types.iter().map(|(value, _)| *value).collect::<Vec<_>>())
+            .field("ip_numbers", &self.ip_numbers.iter().map(|(value, _)| *value).collect::<Vec<_>>())
+            .field("udp_ports", &self.udp_ports)
+            .field("tcp_ports", &self.tcp_ports)
+            .field("limits", &self.limits)
+            .field("ether_type_length_policy", &self.ether_type_length_policy)
+            .finish()
+    }
+}
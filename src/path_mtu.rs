@@ -0,0 +1,181 @@
+use super::*;
+
+use std::net::IpAddr;
+
+/// Information contained in a path MTU discovery message (ICMPv4
+/// "fragmentation needed" or ICMPv6 "packet too big").
+///
+/// Besides the reported MTU the message contains the start of the packet
+/// that was too big. If the embedded packet contains enough data the
+/// flow the packet belonged to is decoded as well.
+///
+/// The embedded packet is one that was sent by the receiver of the
+/// ICMP message, so the source of the `flow` is the local endpoint.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PacketBuilder, PathMtuInfo, SlicedPacket, ip_number};
+///
+/// // packet that was too big for a link on the path
+/// let original = {
+///     let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+///         .udp(21, 1234);
+///     let mut result = Vec::new();
+///     builder.write(&mut result, &[0u8;1400]).unwrap();
+///     result
+/// };
+///
+/// // "fragmentation needed" message by a router on the path
+/// let message = {
+///     let builder = PacketBuilder::ipv4([10,0,0,1], [192,168,1,1], 20)
+///         .icmpv4_fragmentation_needed(1280);
+///     let payload = PathMtuInfo::icmpv4_embedded_payload(&original);
+///     let mut result = Vec::new();
+///     builder.write(&mut result, payload).unwrap();
+///     result
+/// };
+///
+/// // decode the message
+/// let info = PathMtuInfo::from_sliced(&SlicedPacket::from_ip(&message).unwrap()).unwrap();
+/// assert_eq!(1280, info.mtu);
+/// let flow = info.flow.unwrap();
+/// assert_eq!(ip_number::UDP, flow.ip_number);
+/// assert_eq!(21, flow.source_port);
+/// assert_eq!(1234, flow.destination_port);
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct PathMtuInfo {
+    /// MTU reported in the message.
+    ///
+    /// Note that for ICMPv4 a value of 0 indicates that the router
+    /// does not support RFC 1191 and did not report the next hop mtu.
+    pub mtu: u32,
+    /// Flow of the embedded packet (`None` if the flow could not be determined
+    /// from the embedded data).
+    pub flow: Option<FlowKey>,
+}
+
+impl PathMtuInfo {
+    /// Number of bytes after the ipv4 header of the original packet that are
+    /// embedded in an ICMPv4 message (64 bits according to RFC 792).
+    pub const ICMPV4_EMBEDDED_DATA_LEN: usize = 8;
+
+    /// Maximum number of bytes of the original packet that are embedded in
+    /// an ICMPv6 message, so the ICMPv6 packet does not exceed the minimum
+    /// IPv6 MTU of 1280 bytes (RFC 4443).
+    pub const ICMPV6_MAX_EMBEDDED_LEN: usize = 1280 - Ipv6Header::SERIALIZED_SIZE - Icmpv6Header::MIN_SERIALIZED_SIZE;
+
+    /// Returns the part of an ipv4 packet that should be embedded in an ICMPv4
+    /// "fragmentation needed" message (the ipv4 header + the first 64 bits
+    /// of the payload).
+    pub fn icmpv4_embedded_payload(ip_packet: &[u8]) -> &[u8] {
+        let header_len = match ip_packet.first() {
+            Some(value) => usize::from(value & 0xf)*4,
+            None => 0,
+        };
+        let len = std::cmp::min(ip_packet.len(), header_len + PathMtuInfo::ICMPV4_EMBEDDED_DATA_LEN);
+        &ip_packet[..len]
+    }
+
+    /// Returns the part of an ipv6 packet that should be embedded in an ICMPv6
+    /// "packet too big" message (as much of the packet as possible without the
+    /// ICMPv6 packet exceeding the minimum IPv6 MTU).
+    pub fn icmpv6_embedded_payload(ip_packet: &[u8]) -> &[u8] {
+        let len = std::cmp::min(ip_packet.len(), PathMtuInfo::ICMPV6_MAX_EMBEDDED_LEN);
+        &ip_packet[..len]
+    }
+
+    /// Decodes the path MTU information of an ICMPv4 "fragmentation needed"
+    /// message (`None` if the message is of a different type).
+    pub fn from_icmpv4(icmp: &Icmpv4Slice) -> Option<PathMtuInfo> {
+        use icmpv4::DestUnreachableHeader::FragmentationNeeded;
+        match icmp.icmp_type() {
+            Icmpv4Type::DestinationUnreachable(FragmentationNeeded{ next_hop_mtu }) => Some(PathMtuInfo {
+                mtu: u32::from(next_hop_mtu),
+                flow: embedded_ipv4_flow(icmp.payload()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Decodes the path MTU information of an ICMPv6 "packet too big"
+    /// message (`None` if the message is of a different type).
+    pub fn from_icmpv6(icmp: &Icmpv6Slice) -> Option<PathMtuInfo> {
+        match icmp.icmp_type() {
+            Icmpv6Type::PacketTooBig{ mtu } => Some(PathMtuInfo {
+                mtu,
+                flow: embedded_ipv6_flow(icmp.payload()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Decodes the path MTU information of a sliced packet (`None` if the packet
+    /// is not an ICMPv4 "fragmentation needed" or ICMPv6 "packet too big" message).
+    pub fn from_sliced(packet: &SlicedPacket) -> Option<PathMtuInfo> {
+        match packet.transport.as_ref()? {
+            TransportSlice::Icmpv4(icmp) => PathMtuInfo::from_icmpv4(icmp),
+            TransportSlice::Icmpv6(icmp) => PathMtuInfo::from_icmpv6(icmp),
+            _ => None,
+        }
+    }
+}
+
+/// Decodes the flow of an embedded ipv4 packet.
+fn embedded_ipv4_flow(data: &[u8]) -> Option<FlowKey> {
+    let header = Ipv4HeaderSlice::from_slice(data).ok()?;
+    // only the first fragment contains the transport header
+    if 0 != header.fragments_offset() {
+        return None;
+    }
+    embedded_flow(
+        IpAddr::V4(header.source_addr()),
+        IpAddr::V4(header.destination_addr()),
+        header.protocol(),
+        &data[header.slice().len()..]
+    )
+}
+
+/// Decodes the flow of an embedded ipv6 packet.
+fn embedded_ipv6_flow(data: &[u8]) -> Option<FlowKey> {
+    let header = Ipv6HeaderSlice::from_slice(data).ok()?;
+    let (ext, ip_number, rest) = Ipv6ExtensionsSlice::from_slice(
+        header.next_header(),
+        &data[header.slice().len()..]
+    ).ok()?;
+    if ext.is_fragmenting_payload() {
+        return None;
+    }
+    embedded_flow(
+        IpAddr::V6(header.source_addr()),
+        IpAddr::V6(header.destination_addr()),
+        ip_number,
+        rest
+    )
+}
+
+/// Determines the flow key based on the ip values & the (possibly truncated)
+/// transport header.
+fn embedded_flow(source_addr: IpAddr, destination_addr: IpAddr, ip_number: u8, transport: &[u8]) -> Option<FlowKey> {
+    let (source_port, destination_port) = match ip_number {
+        ip_number::UDP | ip_number::TCP => {
+            // both udp & tcp start with the source & destination port
+            if transport.len() < 4 {
+                return None;
+            }
+            (
+                u16::from_be_bytes([transport[0], transport[1]]),
+                u16::from_be_bytes([transport[2], transport[3]]),
+            )
+        },
+        _ => (0, 0),
+    };
+    Some(FlowKey {
+        source_addr,
+        destination_addr,
+        ip_number,
+        source_port,
+        destination_port,
+    })
+}
@@ -0,0 +1,440 @@
+//! Best-effort repair of inconsistent header fields.
+//!
+//! [`fix`] recalculates all fields of a [`PacketHeaders`] that can be derived
+//! from the headers that are present & the payload:
+//!
+//! * the ether types, ip numbers & next protocol fields identifying the
+//!   following header (as long as the following header is known),
+//! * the length fields of the ip, udp, pppoe & GTP-U headers,
+//! * the checksums of the ipv4, udp, tcp, icmp & gre headers,
+//! * the VNI valid flag of VXLAN & the critical flag of GENEVE headers,
+//! * the GRE protocol type & sequence number identifying ERSPAN headers.
+//!
+//! The layers are repaired bottom-up, starting with the encapsulated packet
+//! in the `inner` field. [`write`] & [`to_bytes`] serialize the repaired
+//! headers (the `inner` packet is serialized in place of the `payload`).
+//! This allows test tools to assemble packets loosely & normalize them
+//! in one call.
+//!
+//! Fields referencing layers that can not be modified (e.g. [`RawLayer`]s or
+//! headers decoded by a registered parser) are left untouched.
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::*;
+//!
+//! // headers with wrong ether type, protocol, lengths & checksums
+//! let mut headers = PacketHeaders {
+//!     link: Some(Ethernet2Header::default()),
+//!     ip: Some(IpHeader::Version4(
+//!         Ipv4Header::new(0, 20, 0, [192,168,1,1], [192,168,1,2]),
+//!         Default::default()
+//!     )),
+//!     transport: Some(TransportHeader::Udp(UdpHeader::default())),
+//!     payload: &[1,2,3,4],
+//!     ..Default::default()
+//! };
+//! repair::fix(&mut headers).unwrap();
+//! let packet = repair::to_bytes(&headers).unwrap();
+//!
+//! let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+//! assert_eq!(12, decoded.transport.unwrap().udp().unwrap().length);
+//! assert_eq!(&[1,2,3,4], decoded.payload);
+//! ```
+
+use super::*;
+
+/// Recalculates the lengths, checksums & next protocol fields of the headers
+/// (including the headers of the `inner` packet).
+///
+/// An error is returned if a length does not fit into the length field
+/// of a header.
+pub fn fix(headers: &mut PacketHeaders) -> Result<(), ValueError> {
+    // the encapsulated packet is the payload of the tunnel headers
+    if let Some(inner) = &mut headers.inner {
+        fix(inner)?;
+        fix_tunnel_protocols(headers);
+    }
+    if let Some(vxlan) = &mut headers.vxlan {
+        vxlan.flags |= VxlanHeader::FLAG_VNI_VALID;
+    }
+    if let Some(geneve) = &mut headers.geneve {
+        geneve.critical = geneve.options_iterator().any(|option| option.critical());
+    }
+    if let (Some(gre), Some(erspan)) = (&mut headers.gre, &headers.erspan) {
+        // erspan type II & III headers require the sequence number to be present
+        gre.protocol_type = erspan.protocol_type();
+        gre.sequence_number.get_or_insert(0);
+    }
+
+    let tunnel = to_vec(|writer| write_tunnel_payload(headers, writer))?;
+    if let Some(gtpu) = &mut headers.gtpu {
+        let length = gtpu.header_len() - GtpuHeader::MIN_LEN + tunnel.len();
+        gtpu.length = match u16::try_from(length) {
+            Ok(value) => value,
+            Err(_) => return Err(ValueError::UdpPayloadLengthTooLarge(length + GtpuHeader::MIN_LEN)),
+        };
+    }
+    if let Some(gre) = &mut headers.gre {
+        if gre.checksum.is_some() {
+            // the erspan header is part of the gre payload
+            gre.checksum = Some(match &headers.erspan {
+                Some(erspan) => {
+                    let mut gre_payload = erspan.to_bytes()?.to_vec();
+                    gre_payload.extend_from_slice(&tunnel);
+                    gre.calc_checksum(&gre_payload)
+                },
+                None => gre.calc_checksum(&tunnel),
+            });
+        }
+    }
+
+    // data following the transport header
+    let mut transport_payload = to_vec(|writer| write_tunnel_headers(headers, writer))?;
+    transport_payload.extend_from_slice(&tunnel);
+
+    if let Some(TransportHeader::Udp(udp)) = &mut headers.transport {
+        const MAX_PAYLOAD_LENGTH: usize = (u16::MAX as usize) - UdpHeader::SERIALIZED_SIZE;
+        if MAX_PAYLOAD_LENGTH < transport_payload.len() {
+            return Err(ValueError::UdpPayloadLengthTooLarge(transport_payload.len()));
+        }
+        udp.length = (UdpHeader::SERIALIZED_SIZE + transport_payload.len()) as u16;
+    }
+
+    let tunnel_number = tunnel_ip_number(headers);
+    if let Some(ip) = &mut headers.ip {
+        if let Some(next_header) = ip_payload_number(headers.transport.as_ref(), tunnel_number, &headers.raw_layers) {
+            ip.set_next_headers(next_header);
+        }
+        let raw_len: usize = headers.raw_layers
+            .iter()
+            .filter(|raw| matches!(raw.kind, RawLayerKind::Ipv6Extension(_)))
+            .map(|raw| raw.data.len())
+            .sum();
+        let transport_len = headers.transport.as_ref().map_or(0, |transport| transport.header_len());
+        ip.recompute(raw_len + transport_len + transport_payload.len())?;
+    }
+
+    if let Some(transport) = &mut headers.transport {
+        match &headers.ip {
+            Some(IpHeader::Version4(ip, _)) => transport.update_checksum_ipv4(ip, &transport_payload)?,
+            Some(IpHeader::Version6(ip, _)) => transport.update_checksum_ipv6(ip, &transport_payload)?,
+            None => {
+                if let TransportHeader::Icmpv4(icmp) = transport {
+                    icmp.update_checksum(&transport_payload);
+                }
+            }
+        }
+    }
+
+    if let Some(pppoe) = &headers.pppoe {
+        let mut pppoe = pppoe.clone();
+        // discovery packets have no ppp protocol field
+        if pppoe.ppp_protocol.is_some() {
+            match &headers.ip {
+                Some(IpHeader::Version4(_, _)) => pppoe.ppp_protocol = Some(ppp_protocol::IPV4),
+                Some(IpHeader::Version6(_, _)) => pppoe.ppp_protocol = Some(ppp_protocol::IPV6),
+                None => {},
+            }
+        }
+        pppoe.set_payload_len(to_vec(|writer| write_network_layers(headers, writer))?.len())?;
+        headers.pppoe = Some(pppoe);
+    }
+
+    fix_link_ether_types(headers);
+    Ok(())
+}
+
+/// Serializes the headers & the payload (or the `inner` packet if present)
+/// in the order they appear in a packet.
+///
+/// Checksums are written as they are set in the headers (call [`fix`] before
+/// to recalculate them).
+pub fn write<T: io::Write + Sized>(headers: &PacketHeaders, writer: &mut T) -> Result<(), WriteError> {
+    write_link_layers(headers, writer)?;
+    write_network_layers(headers, writer)
+}
+
+/// writes the layers following the link layers
+fn write_network_layers<T: io::Write + Sized>(headers: &PacketHeaders, writer: &mut T) -> Result<(), WriteError> {
+    write_ip_layers(headers, writer)?;
+    if let Some(transport) = &headers.transport {
+        transport.write(writer)?;
+    }
+    write_tunnel_headers(headers, writer)?;
+    write_tunnel_payload(headers, writer)
+}
+
+/// Returns the serialized headers & payload (see [`write`]).
+pub fn to_bytes(headers: &PacketHeaders) -> Result<Vec<u8>, ValueError> {
+    to_vec(|writer| write(headers, writer))
+}
+
+/// Serializes into a vec (writing into a vec only fails if a value is invalid).
+fn to_vec<F>(f: F) -> Result<Vec<u8>, ValueError>
+where
+    F: FnOnce(&mut Vec<u8>) -> Result<(), WriteError>,
+{
+    let mut result = Vec::new();
+    match f(&mut result) {
+        Ok(()) => Ok(result),
+        Err(WriteError::ValueError(err)) => Err(err),
+        Err(err) => unreachable!("writing into a vec failed: {:?}", err),
+    }
+}
+
+fn write_link_layers<T: io::Write + Sized>(headers: &PacketHeaders, writer: &mut T) -> Result<(), WriteError> {
+    if let Some(link) = &headers.link {
+        link.write(writer)?;
+    }
+    if let Some(sll) = &headers.linux_sll {
+        sll.write(writer)?;
+    }
+    if let Some(macsec) = &headers.macsec {
+        macsec.write(writer)?;
+    }
+    if let Some(vlan) = &headers.vlan {
+        vlan.write(writer)?;
+    }
+    write_raw_layers(headers, writer, |kind| matches!(kind, RawLayerKind::VlanTag(_)))?;
+    if let Some(mpls) = &headers.mpls {
+        writer.write_all(mpls.slice())?;
+    }
+    if let Some(pppoe) = &headers.pppoe {
+        pppoe.write(writer)?;
+    }
+    if let Some(arp) = &headers.arp {
+        arp.write(writer)?;
+    }
+    if let Some(extension) = &headers.link_extension {
+        writer.write_all(extension.header)?;
+    }
+    if let Some(nsh) = &headers.nsh {
+        nsh.write(writer)?;
+    }
+    Ok(())
+}
+
+fn write_ip_layers<T: io::Write + Sized>(headers: &PacketHeaders, writer: &mut T) -> Result<(), WriteError> {
+    match &headers.ip {
+        Some(IpHeader::Version4(header, extensions)) => {
+            header.write_raw(writer)?;
+            extensions.write(writer, header.protocol)?;
+        },
+        Some(IpHeader::Version6(header, extensions)) => {
+            header.write(writer)?;
+            extensions.write(writer, header.next_header)?;
+        },
+        None => {},
+    }
+    write_raw_layers(headers, writer, |kind| matches!(kind, RawLayerKind::Ipv6Extension(_)))
+}
+
+/// writes the headers between the transport header & the encapsulated packet
+fn write_tunnel_headers<T: io::Write + Sized>(headers: &PacketHeaders, writer: &mut T) -> Result<(), WriteError> {
+    if let Some(extension) = &headers.transport_extension {
+        writer.write_all(extension.header)?;
+    }
+    if let Some(esp) = &headers.esp {
+        esp.write(writer)?;
+    }
+    if let Some(gre) = &headers.gre {
+        gre.write(writer)?;
+    }
+    if let Some(erspan) = &headers.erspan {
+        erspan.write(writer)?;
+    }
+    if let Some(vxlan) = &headers.vxlan {
+        vxlan.write(writer)?;
+    }
+    if let Some(geneve) = &headers.geneve {
+        geneve.write(writer)?;
+    }
+    if let Some(gtpu) = &headers.gtpu {
+        gtpu.write(writer)?;
+    }
+    Ok(())
+}
+
+/// writes the encapsulated packet or the payload
+fn write_tunnel_payload<T: io::Write + Sized>(headers: &PacketHeaders, writer: &mut T) -> Result<(), WriteError> {
+    match &headers.inner {
+        Some(inner) => write(inner, writer),
+        None => Ok(writer.write_all(headers.payload)?),
+    }
+}
+
+fn write_raw_layers<T, F>(headers: &PacketHeaders, writer: &mut T, filter: F) -> Result<(), WriteError>
+where
+    T: io::Write + Sized,
+    F: Fn(&RawLayerKind) -> bool,
+{
+    for raw in headers.raw_layers.iter().filter(|raw| filter(&raw.kind)) {
+        writer.write_all(raw.data)?;
+    }
+    Ok(())
+}
+
+/// ip number of the esp or gre header following the ip header (`None` if not present)
+fn tunnel_ip_number(headers: &PacketHeaders) -> Option<u8> {
+    if headers.esp.is_some() {
+        Some(ip_number::ENCAP_SEC)
+    } else if headers.gre.is_some() {
+        Some(ip_number::GRE)
+    } else {
+        None
+    }
+}
+
+/// ip number of the layer following the ip header & extensions (`None` if unknown)
+fn ip_payload_number(transport: Option<&TransportHeader>, tunnel: Option<u8>, raw_layers: &[RawLayer]) -> Option<u8> {
+    if let Some(RawLayerKind::Ipv6Extension(ip_number)) = raw_layers
+        .iter()
+        .map(|raw| raw.kind)
+        .find(|kind| matches!(kind, RawLayerKind::Ipv6Extension(_)))
+    {
+        return Some(ip_number);
+    }
+    if tunnel.is_some() {
+        return tunnel;
+    }
+    transport.map(|transport| match transport {
+        TransportHeader::Udp(_) => ip_number::UDP,
+        TransportHeader::Tcp(_) => ip_number::TCP,
+        TransportHeader::Icmpv4(_) => ip_number::ICMP,
+        TransportHeader::Icmpv6(_) => ip_number::IPV6_ICMP,
+        TransportHeader::UdpLite(_) => ip_number::UDP_LITE,
+        TransportHeader::Dccp(_) => ip_number::DCCP,
+        TransportHeader::Sctp(_) => ip_number::SCTP,
+    })
+}
+
+/// ether type identifying the first layer of the headers (`None` if unknown)
+fn first_ether_type(headers: &PacketHeaders) -> Option<u16> {
+    if headers.link.is_some() {
+        Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING)
+    } else if headers.macsec.is_some() {
+        Some(ether_type::MACSEC)
+    } else if let Some(vlan) = &headers.vlan {
+        Some(vlan_ether_type(vlan))
+    } else {
+        network_ether_type(headers)
+    }
+}
+
+/// ether type of a vlan header (the outer tag of double tagged frames
+/// uses the provider bridging ether type)
+fn vlan_ether_type(vlan: &VlanHeader) -> u16 {
+    match vlan {
+        VlanHeader::Single(_) => ether_type::VLAN_TAGGED_FRAME,
+        VlanHeader::Double(_) => ether_type::PROVIDER_BRIDGING,
+    }
+}
+
+/// ether type of the layer following the link layer & vlan headers (`None` if unknown)
+fn network_ether_type(headers: &PacketHeaders) -> Option<u16> {
+    if headers.mpls.is_some() {
+        Some(ether_type::MPLS_UNICAST)
+    } else if let Some(pppoe) = &headers.pppoe {
+        if pppoe.ppp_protocol.is_some() {
+            Some(ether_type::PPPOE_SESSION)
+        } else {
+            Some(ether_type::PPPOE_DISCOVERY)
+        }
+    } else if headers.arp.is_some() {
+        Some(ether_type::ARP)
+    } else if headers.link_extension.is_some() {
+        // the ether type the parser was registered for is kept
+        None
+    } else if headers.nsh.is_some() {
+        Some(ether_type::NSH)
+    } else {
+        match &headers.ip {
+            Some(IpHeader::Version4(_, _)) => Some(ether_type::IPV4),
+            Some(IpHeader::Version6(_, _)) => Some(ether_type::IPV6),
+            None => None,
+        }
+    }
+}
+
+/// Sets the ether type if it does not already identify the same kind of
+/// header (vlan & mpls headers have multiple ether types).
+fn set_ether_type(target: &mut u16, value: Option<u16>) {
+    use ether_type::*;
+    let same_kind = |a: u16, b: u16| match (a, b) {
+        (VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME, VLAN_TAGGED_FRAME | PROVIDER_BRIDGING | VLAN_DOUBLE_TAGGED_FRAME) => true,
+        (MPLS_UNICAST | MPLS_MULTICAST, MPLS_UNICAST | MPLS_MULTICAST) => true,
+        (a, b) => a == b,
+    };
+    if let Some(value) = value {
+        if false == same_kind(*target, value) {
+            *target = value;
+        }
+    }
+}
+
+/// sets the protocol fields of the tunnel headers based on the encapsulated packet
+fn fix_tunnel_protocols(headers: &mut PacketHeaders) {
+    let inner_ether_type = headers.inner.as_ref().and_then(|inner| first_ether_type(inner));
+    if let Some(gre) = &mut headers.gre {
+        // erspan packets are identified by the protocol type (type I packets have no erspan header)
+        let erspan_type_1 = ether_type::ERSPAN_TYPE_2 == gre.protocol_type && gre.sequence_number.is_none();
+        if headers.erspan.is_none() && false == erspan_type_1 {
+            set_ether_type(&mut gre.protocol_type, inner_ether_type);
+        }
+    }
+    if let Some(geneve) = &mut headers.geneve {
+        set_ether_type(&mut geneve.protocol_type, inner_ether_type);
+    }
+    if let Some(nsh) = &mut headers.nsh {
+        use ether_type::*;
+        let next_protocol = match inner_ether_type {
+            Some(IPV4) => Some(nsh_next_protocol::IPV4),
+            Some(IPV6) => Some(nsh_next_protocol::IPV6),
+            Some(TRANSPARENT_ETHERNET_BRIDGING) => Some(nsh_next_protocol::ETHERNET),
+            Some(NSH) => Some(nsh_next_protocol::NSH),
+            Some(MPLS_UNICAST) => Some(nsh_next_protocol::MPLS),
+            _ => None,
+        };
+        if let Some(next_protocol) = next_protocol {
+            nsh.next_protocol = next_protocol;
+        }
+    }
+}
+
+/// sets the ether types of the ethernet, linux cooked capture, macsec & vlan headers
+fn fix_link_ether_types(headers: &mut PacketHeaders) {
+    let network = network_ether_type(headers);
+    let raw_vlan_tags = headers.raw_layers.iter().any(|raw| matches!(raw.kind, RawLayerKind::VlanTag(_)));
+    match &mut headers.vlan {
+        Some(VlanHeader::Single(single)) => set_ether_type(&mut single.ether_type, network),
+        Some(VlanHeader::Double(double)) => {
+            set_ether_type(&mut double.outer.ether_type, Some(ether_type::VLAN_TAGGED_FRAME));
+            // the inner tag references the raw vlan tags
+            if false == raw_vlan_tags {
+                set_ether_type(&mut double.inner.ether_type, network);
+            }
+        },
+        None => {},
+    }
+    let mut next = match &headers.vlan {
+        Some(vlan) => Some(vlan_ether_type(vlan)),
+        None => network,
+    };
+    if let Some(macsec) = &mut headers.macsec {
+        // encrypted frames have no ether type
+        if let Some(protected) = &mut macsec.ether_type {
+            set_ether_type(protected, next);
+        }
+        next = Some(ether_type::MACSEC);
+    }
+    if let Some(link) = &mut headers.link {
+        set_ether_type(&mut link.ether_type, next);
+    }
+    if let Some(sll) = &mut headers.linux_sll {
+        let mut protocol_type = sll.protocol_type();
+        set_ether_type(&mut protocol_type, next);
+        sll.set_protocol_type(protocol_type);
+    }
+}
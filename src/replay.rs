@@ -0,0 +1,279 @@
+//! Side by side replay of two packet sequences with structural diffs.
+//!
+//! [`replay`] decodes the packets of an expected & an actual sequence (e.g.
+//! the records of two capture files or the input & output of a rewrite or
+//! translation pipeline) with [`PacketHeaders`] and compares them packet by
+//! packet. Every packet position that differs is reported as a [`PacketDiff`]:
+//!
+//! * packets whose decoded headers differ list the differing layers as
+//!   [`LayerDiff`]s (including the layers of encapsulated packets),
+//! * packets that can not be decoded (on only one side or with different
+//!   bytes on both sides) are reported as [`Difference::Undecodable`],
+//! * packets only present in one of the sequences are reported as
+//!   [`Difference::Missing`] or [`Difference::Unexpected`].
+//!
+//! Packets with identical bytes are never reported. The layers are compared
+//! by the values of their decoded headers, so differences the decoder does
+//! not represent (e.g. reserved bits) are only visible in the payload or not
+//! at all. Use [`crate::roundtrip`] to detect these.
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::{replay, rewrite::Layer, PacketBuilder};
+//!
+//! let packet = |ttl| {
+//!     let mut packet = Vec::new();
+//!     PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!         .ipv4([192,168,1,1], [192,168,1,2], ttl)
+//!         .udp(21, 1234)
+//!         .write(&mut packet, &[1,2,3,4])
+//!         .unwrap();
+//!     packet
+//! };
+//!
+//! let expected = vec![packet(20), packet(20), packet(20)];
+//! let actual = vec![packet(20), packet(19)];
+//!
+//! let diffs: Vec<_> = replay::replay(&expected, &actual).collect();
+//! assert_eq!(2, diffs.len());
+//!
+//! // ttl of the second packet differs
+//! assert_eq!(1, diffs[0].index);
+//! match &diffs[0].difference {
+//!     replay::Difference::Layers(layers) => {
+//!         assert_eq!(1, layers.len());
+//!         assert_eq!(Layer::Ip, layers[0].layer);
+//!     },
+//!     _ => unreachable!(),
+//! }
+//!
+//! // the third packet is missing
+//! assert_eq!(2, diffs[1].index);
+//! assert!(matches!(diffs[1].difference, replay::Difference::Missing));
+//! ```
+
+use super::*;
+use crate::rewrite::Layer;
+
+/// Layer whose decoded header differs between the expected & the actual packet.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LayerDiff {
+    /// Number of tunnels the layer is encapsulated in (0 for the layers of
+    /// the outer packet, 1 for the layers of the `inner` packet, ...).
+    pub tunnel_depth: usize,
+    /// Layer that differs.
+    pub layer: Layer,
+    /// Debug representation of the expected layer (`None` if the layer is
+    /// not present in the expected packet).
+    pub expected: Option<String>,
+    /// Debug representation of the actual layer (`None` if the layer is
+    /// not present in the actual packet).
+    pub actual: Option<String>,
+}
+
+/// Difference between the expected & the actual packet at one position.
+#[derive(Debug)]
+pub enum Difference {
+    /// Both packets were decoded but the listed layers differ.
+    Layers(Vec<LayerDiff>),
+    /// At least one of the packets could not be decoded (the errors of the
+    /// packets that could not be decoded are set).
+    Undecodable {
+        /// Error of the expected packet.
+        expected: Option<ReadError>,
+        /// Error of the actual packet.
+        actual: Option<ReadError>,
+    },
+    /// The actual sequence ended before the expected packet.
+    Missing,
+    /// The expected sequence ended before the actual packet.
+    Unexpected,
+}
+
+/// Difference at a position of a replay.
+#[derive(Debug)]
+pub struct PacketDiff {
+    /// Position of the packets in the sequences (starting at 0).
+    pub index: usize,
+    /// Difference between the packets.
+    pub difference: Difference,
+}
+
+/// Iterator over the differences of two packet sequences (see [`replay`] &
+/// [`replay_ip`]).
+pub struct Replay<E, A> {
+    expected: E,
+    actual: A,
+    index: usize,
+    decode: fn(&[u8]) -> Result<PacketHeaders, ReadError>,
+}
+
+impl<E, A> Iterator for Replay<E, A>
+where
+    E: Iterator,
+    E::Item: AsRef<[u8]>,
+    A: Iterator,
+    A::Item: AsRef<[u8]>,
+{
+    type Item = PacketDiff;
+
+    fn next(&mut self) -> Option<PacketDiff> {
+        loop {
+            let index = self.index;
+            let difference = match (self.expected.next(), self.actual.next()) {
+                (None, None) => return None,
+                (Some(_), None) => Some(Difference::Missing),
+                (None, Some(_)) => Some(Difference::Unexpected),
+                (Some(expected), Some(actual)) => diff_with(expected.as_ref(), actual.as_ref(), self.decode),
+            };
+            self.index += 1;
+            if let Some(difference) = difference {
+                return Some(PacketDiff { index, difference });
+            }
+        }
+    }
+}
+
+/// Replays two sequences of packets starting with Ethernet II headers side
+/// by side & returns an iterator over the positions that differ.
+pub fn replay<E, A>(expected: E, actual: A) -> Replay<E::IntoIter, A::IntoIter>
+where
+    E: IntoIterator,
+    E::Item: AsRef<[u8]>,
+    A: IntoIterator,
+    A::Item: AsRef<[u8]>,
+{
+    Replay {
+        expected: expected.into_iter(),
+        actual: actual.into_iter(),
+        index: 0,
+        decode: PacketHeaders::from_ethernet_slice,
+    }
+}
+
+/// Replays two sequences of packets starting with ip headers side by side
+/// & returns an iterator over the positions that differ.
+pub fn replay_ip<E, A>(expected: E, actual: A) -> Replay<E::IntoIter, A::IntoIter>
+where
+    E: IntoIterator,
+    E::Item: AsRef<[u8]>,
+    A: IntoIterator,
+    A::Item: AsRef<[u8]>,
+{
+    Replay {
+        expected: expected.into_iter(),
+        actual: actual.into_iter(),
+        index: 0,
+        decode: PacketHeaders::from_ip_slice,
+    }
+}
+
+/// Compares two packets starting with Ethernet II headers (`None` if no
+/// difference was found).
+pub fn diff(expected: &[u8], actual: &[u8]) -> Option<Difference> {
+    diff_with(expected, actual, PacketHeaders::from_ethernet_slice)
+}
+
+/// Compares two packets starting with ip headers (`None` if no difference
+/// was found).
+pub fn diff_ip(expected: &[u8], actual: &[u8]) -> Option<Difference> {
+    diff_with(expected, actual, PacketHeaders::from_ip_slice)
+}
+
+/// Decodes & compares two packets with the given decode function.
+fn diff_with(expected: &[u8], actual: &[u8], decode: fn(&[u8]) -> Result<PacketHeaders, ReadError>) -> Option<Difference> {
+    if expected == actual {
+        return None;
+    }
+    match (decode(expected), decode(actual)) {
+        (Ok(expected), Ok(actual)) => {
+            let mut result = Vec::new();
+            compare(0, &expected, &actual, &mut result);
+            if result.is_empty() {
+                None
+            } else {
+                Some(Difference::Layers(result))
+            }
+        },
+        (expected, actual) => Some(Difference::Undecodable {
+            expected: expected.err(),
+            actual: actual.err(),
+        }),
+    }
+}
+
+/// Compares the layers of two decoded packets (including the `inner` packets).
+fn compare(tunnel_depth: usize, expected: &PacketHeaders, actual: &PacketHeaders, result: &mut Vec<LayerDiff>) {
+    let mut layer = |layer: Layer, e: Option<&dyn fmt::Debug>, a: Option<&dyn fmt::Debug>| {
+        result.push(LayerDiff {
+            tunnel_depth,
+            layer,
+            expected: e.map(|value| format!("{:?}", value)),
+            actual: a.map(|value| format!("{:?}", value)),
+        });
+    };
+    macro_rules! field {
+        ($layer:expr, $field:ident) => {
+            if expected.$field != actual.$field {
+                layer(
+                    $layer,
+                    expected.$field.as_ref().map(|v| v as &dyn fmt::Debug),
+                    actual.$field.as_ref().map(|v| v as &dyn fmt::Debug)
+                );
+            }
+        };
+    }
+
+    field!(Layer::Ethernet2, link);
+    field!(Layer::LinuxSll, linux_sll);
+    field!(Layer::Macsec, macsec);
+    field!(Layer::Vlan, vlan);
+    field!(Layer::Mpls, mpls);
+    field!(Layer::Pppoe, pppoe);
+    field!(Layer::Arp, arp);
+    field!(Layer::LinkExtension, link_extension);
+    field!(Layer::Ip, ip);
+
+    // raw vlan tags belong to the vlan layer & raw extension headers to the ip layer
+    let raw_layer = |raw: &RawLayer| match raw.kind {
+        RawLayerKind::VlanTag(_) => Layer::Vlan,
+        RawLayerKind::Ipv6Extension(_) => Layer::Ip,
+    };
+    let raw_len = expected.raw_layers.len().max(actual.raw_layers.len());
+    for i in 0..raw_len {
+        let e = expected.raw_layers.get(i);
+        let a = actual.raw_layers.get(i);
+        if e != a {
+            layer(
+                raw_layer(e.or(a).unwrap()),
+                e.map(|v| v as &dyn fmt::Debug),
+                a.map(|v| v as &dyn fmt::Debug)
+            );
+        }
+    }
+
+    field!(Layer::Transport, transport);
+    field!(Layer::TransportExtension, transport_extension);
+    field!(Layer::Esp, esp);
+    field!(Layer::Gre, gre);
+    field!(Layer::Erspan, erspan);
+    field!(Layer::Vxlan, vxlan);
+    field!(Layer::Geneve, geneve);
+    field!(Layer::Gtpu, gtpu);
+    field!(Layer::Nsh, nsh);
+
+    // the payload of tunnels is compared via the layers of the inner packets
+    let inner = match (&expected.inner, &actual.inner) {
+        (Some(expected_inner), Some(actual_inner)) => Some((expected_inner, actual_inner)),
+        _ => {
+            if expected.payload != actual.payload {
+                layer(Layer::Payload, Some(&expected.payload), Some(&actual.payload));
+            }
+            None
+        },
+    };
+    if let Some((expected_inner, actual_inner)) = inner {
+        compare(tunnel_depth + 1, expected_inner, actual_inner, result);
+    }
+}
@@ -0,0 +1,167 @@
+use super::*;
+
+/// Result of a [`ResumableSlicer::try_slice`] call.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SliceProgress<'a> {
+    /// All headers & the complete ip packet are present in the buffer.
+    Complete(SlicedPacket<'a>),
+    /// The buffer ends before the packet does. Slicing is resumed once the
+    /// buffer contains at least `required_len` bytes.
+    Incomplete {
+        /// Minimum number of bytes the buffer has to contain before slicing
+        /// is attempted again (more bytes might be required afterwards, e.g.
+        /// if the options of a tcp header get cut off next).
+        required_len: usize,
+    },
+}
+
+/// Slicer for packets whose bytes arrive split across several reads (e.g.
+/// from a capture feed tunneled over a tcp stream).
+///
+/// The bytes are collected in an internal buffer. If slicing fails because
+/// the buffer ends too early ([`ReadError::UnexpectedEndOfSlice`] or
+/// [`ReadError::UnexpectedLenOfSlice`]) the minimum length reported by the
+/// error is kept as continuation state & no further slicing is attempted
+/// until enough bytes were pushed. The packet is only reported as complete
+/// once the buffer covers the length given in the ip header as the slicer
+/// itself does not require the ip payload to be complete.
+///
+/// Other errors are returned directly, as more bytes will not fix them.
+///
+/// # Example
+///
+/// ```
+/// # use etherparse::PacketBuilder;
+/// # let builder = PacketBuilder::
+/// #    ethernet2([1,2,3,4,5,6],     //source mac
+/// #               [7,8,9,10,11,12]) //destionation mac
+/// #    .ipv4([192,168,1,1], //source ip
+/// #          [192,168,1,2], //desitionation ip
+/// #          20)            //time to life
+/// #    .tcp(21,    //source port
+/// #         1234,  //desitnation port
+/// #         1,     //sequence number
+/// #         1000)  //window size
+/// #    .options(&[etherparse::TcpOptionElement::MaximumSegmentSize(1400)]).unwrap();
+/// # let payload = [1,2,3,4,5,6,7,8];
+/// # let mut packet = Vec::<u8>::with_capacity(builder.size(payload.len()));
+/// # builder.write(&mut packet, &payload).unwrap();
+/// use etherparse::{PacketSetLayer, ResumableSlicer, SliceProgress};
+///
+/// let mut slicer = ResumableSlicer::new(PacketSetLayer::Ethernet2);
+///
+/// // first read ends in the tcp options
+/// slicer.push(&packet[..56]);
+/// assert_eq!(
+///     SliceProgress::Incomplete{ required_len: 58 },
+///     slicer.try_slice().unwrap()
+/// );
+///
+/// // second read delivers the rest of the packet
+/// slicer.push(&packet[56..]);
+/// match slicer.try_slice().unwrap() {
+///     SliceProgress::Complete(sliced) => assert_eq!(&payload, sliced.payload),
+///     SliceProgress::Incomplete{ .. } => unreachable!(),
+/// }
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResumableSlicer {
+    layer: PacketSetLayer,
+    buffer: Vec<u8>,
+    required_len: usize,
+}
+
+impl ResumableSlicer {
+    /// Creates a slicer with an empty buffer for packets starting at the
+    /// given layer.
+    pub fn new(layer: PacketSetLayer) -> ResumableSlicer {
+        ResumableSlicer {
+            layer,
+            buffer: Vec::new(),
+            required_len: 0,
+        }
+    }
+
+    /// Layer at which the packets start.
+    #[inline]
+    pub fn layer(&self) -> PacketSetLayer {
+        self.layer
+    }
+
+    /// Appends the bytes of a read to the buffer.
+    #[inline]
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Returns the bytes collected so far.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Minimum length of the buffer required before slicing is attempted
+    /// again (0 if no attempt failed yet).
+    #[inline]
+    pub fn required_len(&self) -> usize {
+        self.required_len
+    }
+
+    /// Number of bytes missing until slicing is attempted again.
+    #[inline]
+    pub fn missing_len(&self) -> usize {
+        self.required_len.saturating_sub(self.buffer.len())
+    }
+
+    /// Slices the buffered bytes or returns the length the buffer needs to
+    /// reach before the next attempt.
+    pub fn try_slice(&mut self) -> Result<SliceProgress<'_>, ReadError> {
+        use ReadError::*;
+
+        if self.buffer.len() < self.required_len {
+            return Ok(SliceProgress::Incomplete{ required_len: self.required_len });
+        }
+
+        let buffer = &self.buffer;
+        let required_len = match slice_packet(self.layer, buffer) {
+            Ok(sliced) => {
+                let ip_end = ip_packet_end(buffer, &sliced);
+                if ip_end <= buffer.len() {
+                    return Ok(SliceProgress::Complete(sliced));
+                }
+                ip_end
+            },
+            Err(UnexpectedEndOfSlice(len)) if buffer.len() < len => len,
+            Err(UnexpectedLenOfSlice{ expected, .. }) if buffer.len() < expected => expected,
+            Err(err) => return Err(err),
+        };
+        self.required_len = required_len;
+        Ok(SliceProgress::Incomplete{ required_len })
+    }
+
+    /// Returns the buffered bytes & resets the slicer for the next packet.
+    pub fn take(&mut self) -> Vec<u8> {
+        self.required_len = 0;
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// Discards the buffered bytes & the continuation state.
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.required_len = 0;
+    }
+}
+
+/// Returns the offset in the buffer at which the ip packet ends based on its
+/// length fields (0 if the packet has no ip header).
+fn ip_packet_end(buffer: &[u8], sliced: &SlicedPacket) -> usize {
+    use InternetSlice::*;
+    let (header, len) = match &sliced.ip {
+        Some(Ipv4(header, _)) => (header.slice(), usize::from(header.total_len())),
+        Some(Ipv6(header, _)) => (header.slice(), header.slice().len() + usize::from(header.payload_length())),
+        None => return 0,
+    };
+    let start = header.as_ptr() as usize - buffer.as_ptr() as usize;
+    start + len
+}
@@ -0,0 +1,307 @@
+//! Replacement of a single layer of a sliced packet.
+//!
+//! [`replace`] swaps the bytes of one layer of a [`SlicedPacket`] for new
+//! bytes and copies the untouched byte ranges before & after the layer from
+//! the original packet. The other layers are not decoded & serialized again,
+//! which keeps everything the decoder does not represent (e.g. reserved bits,
+//! unknown options) intact & avoids rebuilding the whole packet in proxy
+//! style applications.
+//!
+//! [`replace_ethernet2`], [`replace_vlan`], [`replace_ip`] & [`replace_transport`]
+//! serialize a (modified) header in place of the corresponding layer. Length &
+//! checksum fields of the other layers are not updated (e.g. the ip payload
+//! length if the size of the transport header changes or the udp & tcp
+//! checksums if the ip addresses change).
+//!
+//! The [`SlicedPacket`] has to be sliced from the given packet. The layers of
+//! an encapsulated packet can be replaced by passing the `inner` packet
+//! together with the (outer) packet.
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::{rewrite, PacketBuilder, SlicedPacket, InternetSlice};
+//!
+//! let mut packet = Vec::new();
+//! PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!     .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!     .udp(21, 1234)
+//!     .write(&mut packet, &[1,2,3,4])
+//!     .unwrap();
+//! let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+//!
+//! // decrement the time to live of the ipv4 header
+//! let mut ip = match &sliced.ip {
+//!     Some(InternetSlice::Ipv4(header, _)) => header.to_header(),
+//!     _ => unreachable!(),
+//! };
+//! ip.time_to_live -= 1;
+//! let modified = rewrite::replace(
+//!     &packet,
+//!     &sliced,
+//!     rewrite::Layer::Ip,
+//!     &ip.to_bytes().unwrap()
+//! ).unwrap();
+//!
+//! let decoded = SlicedPacket::from_ethernet(&modified).unwrap();
+//! match decoded.ip {
+//!     Some(InternetSlice::Ipv4(header, _)) => assert_eq!(19, header.ttl()),
+//!     _ => unreachable!(),
+//! }
+//! assert_eq!(&packet[34..], &modified[34..]);
+//! ```
+
+use super::*;
+use std::ops::Range;
+
+/// Layer of a [`SlicedPacket`] that can be replaced.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Layer {
+    /// Ethernet II header.
+    Ethernet2,
+    /// Linux cooked capture header.
+    LinuxSll,
+    /// MACsec SecTAG.
+    Macsec,
+    /// Single or double vlan header.
+    Vlan,
+    /// MPLS label stack.
+    Mpls,
+    /// PPPoE header.
+    Pppoe,
+    /// ARP packet.
+    Arp,
+    /// Custom link layer header decoded by a registered parser.
+    LinkExtension,
+    /// IPv4 or IPv6 header including the decoded extension headers.
+    Ip,
+    /// UDP, TCP, ICMPv4, ICMPv6, IGMP or SCTP header.
+    Transport,
+    /// Custom transport layer header decoded by a registered parser.
+    TransportExtension,
+    /// ESP header.
+    Esp,
+    /// GRE header.
+    Gre,
+    /// ERSPAN header.
+    Erspan,
+    /// VXLAN header.
+    Vxlan,
+    /// GENEVE header.
+    Geneve,
+    /// GTP-U header.
+    Gtpu,
+    /// NSH header.
+    Nsh,
+    /// Payload of the packet (including the encapsulated packet of tunnels).
+    Payload,
+}
+
+/// Errors that can occur while replacing a layer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RewriteError {
+    /// The layer is not present in the sliced packet.
+    LayerNotPresent(Layer),
+    /// The sliced packet points to data outside of the given packet.
+    SliceNotInPacket,
+    /// The replacement header could not be serialized.
+    Value(ValueError),
+}
+
+impl Error for RewriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RewriteError::Value(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RewriteError::*;
+        match self {
+            LayerNotPresent(layer) => {
+                write!(f, "RewriteError: The layer {:?} is not present in the sliced packet.", layer)
+            },
+            SliceNotInPacket => {
+                write!(f, "RewriteError: The sliced packet points to data outside of the given packet.")
+            },
+            Value(err) => {
+                write!(f, "RewriteError: {}", err)
+            },
+        }
+    }
+}
+
+/// Returns the byte range of a layer in the packet (`None` if the layer
+/// is not present).
+///
+/// A layer ends where the following layer (or the payload) starts, so the
+/// range of the ip layer includes the extension headers and the range of
+/// the transport layer excludes the payload.
+pub fn layer_range(packet: &[u8], sliced: &SlicedPacket, layer: Layer) -> Result<Option<Range<usize>>, RewriteError> {
+    let layers = layer_starts(packet, sliced)?;
+    let payload = offset_of(packet, sliced.payload)?;
+    let payload_end = payload + sliced.payload.len();
+
+    if Layer::Payload == layer {
+        return Ok(Some(payload..payload_end));
+    }
+    for (index, (value, start)) in layers.iter().enumerate() {
+        if *value == layer {
+            let end = match layers.get(index + 1) {
+                Some((_, next)) => *next,
+                None => payload,
+            };
+            return Ok(Some(*start..end));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the packet with the bytes of the given layer replaced.
+pub fn replace(packet: &[u8], sliced: &SlicedPacket, layer: Layer, replacement: &[u8]) -> Result<Vec<u8>, RewriteError> {
+    let range = match layer_range(packet, sliced, layer)? {
+        Some(range) => range,
+        None => return Err(RewriteError::LayerNotPresent(layer)),
+    };
+    let mut result = Vec::with_capacity(packet.len() - range.len() + replacement.len());
+    result.extend_from_slice(&packet[..range.start]);
+    result.extend_from_slice(replacement);
+    result.extend_from_slice(&packet[range.end..]);
+    Ok(result)
+}
+
+/// Returns the packet with the given layer replaced by the data written
+/// by the closure.
+pub fn replace_with<F>(packet: &[u8], sliced: &SlicedPacket, layer: Layer, f: F) -> Result<Vec<u8>, RewriteError>
+where
+    F: FnOnce(&mut Vec<u8>) -> Result<(), WriteError>,
+{
+    let mut replacement = Vec::new();
+    match f(&mut replacement) {
+        Ok(()) => {},
+        Err(WriteError::ValueError(err)) => return Err(RewriteError::Value(err)),
+        Err(err) => unreachable!("writing into a vec failed: {:?}", err),
+    }
+    replace(packet, sliced, layer, &replacement)
+}
+
+/// Returns the packet with the ethernet II header replaced.
+pub fn replace_ethernet2(packet: &[u8], sliced: &SlicedPacket, header: &Ethernet2Header) -> Result<Vec<u8>, RewriteError> {
+    replace(packet, sliced, Layer::Ethernet2, &header.to_bytes())
+}
+
+/// Returns the packet with the vlan header replaced (a single vlan header
+/// can be replaced by a double vlan header & vice versa).
+pub fn replace_vlan(packet: &[u8], sliced: &SlicedPacket, header: &VlanHeader) -> Result<Vec<u8>, RewriteError> {
+    replace_with(packet, sliced, Layer::Vlan, |writer| header.write(writer))
+}
+
+/// Returns the packet with the ip header & extension headers replaced
+/// (the ipv4 header checksum is recalculated).
+pub fn replace_ip(packet: &[u8], sliced: &SlicedPacket, header: &IpHeader) -> Result<Vec<u8>, RewriteError> {
+    replace_with(packet, sliced, Layer::Ip, |writer| header.write(writer))
+}
+
+/// Returns the packet with the transport header replaced (the checksum is
+/// written as it is set in the header).
+pub fn replace_transport(packet: &[u8], sliced: &SlicedPacket, header: &TransportHeader) -> Result<Vec<u8>, RewriteError> {
+    replace_with(packet, sliced, Layer::Transport, |writer| header.write(writer))
+}
+
+/// Returns the present layers (excluding the payload) & their offsets
+/// in the order they appear in the packet.
+fn layer_starts(packet: &[u8], sliced: &SlicedPacket) -> Result<Vec<(Layer, usize)>, RewriteError> {
+    let mut slices: Vec<(Layer, &[u8])> = Vec::new();
+    if let Some(LinkSlice::Ethernet2(link)) = &sliced.link {
+        slices.push((Layer::Ethernet2, link.slice()));
+    }
+    if let Some(sll) = &sliced.linux_sll {
+        slices.push((Layer::LinuxSll, sll.slice()));
+    }
+    if let Some(macsec) = &sliced.macsec {
+        slices.push((Layer::Macsec, macsec.slice()));
+    }
+    match &sliced.vlan {
+        Some(VlanSlice::SingleVlan(vlan)) => slices.push((Layer::Vlan, vlan.slice())),
+        Some(VlanSlice::DoubleVlan(vlan)) => slices.push((Layer::Vlan, vlan.slice())),
+        None => {},
+    }
+    if let Some(mpls) = &sliced.mpls {
+        slices.push((Layer::Mpls, mpls.slice()));
+    }
+    if let Some(pppoe) = &sliced.pppoe {
+        slices.push((Layer::Pppoe, pppoe.slice()));
+    }
+    if let Some(arp) = &sliced.arp {
+        slices.push((Layer::Arp, arp.slice()));
+    }
+    if let Some(extension) = &sliced.link_extension {
+        slices.push((Layer::LinkExtension, extension.header));
+    }
+    match &sliced.ip {
+        Some(InternetSlice::Ipv4(header, _)) => slices.push((Layer::Ip, header.slice())),
+        Some(InternetSlice::Ipv6(header, _)) => slices.push((Layer::Ip, header.slice())),
+        None => {},
+    }
+    if let Some(transport) = &sliced.transport {
+        use TransportSlice::*;
+        let slice = match transport {
+            Icmpv4(value) => Some(value.slice()),
+            Icmpv6(value) => Some(value.slice()),
+            Udp(value) => Some(value.slice()),
+            Tcp(value) => Some(value.slice()),
+            Igmp(value) => Some(value.slice()),
+            Sctp(value) => Some(value.slice()),
+            UdpLite(value) => Some(value.slice()),
+            Dccp(value) => Some(value.slice()),
+            Unknown(_) => None,
+        };
+        if let Some(slice) = slice {
+            slices.push((Layer::Transport, slice));
+        }
+    }
+    if let Some(extension) = &sliced.transport_extension {
+        slices.push((Layer::TransportExtension, extension.header));
+    }
+    if let Some(esp) = &sliced.esp {
+        slices.push((Layer::Esp, esp.slice()));
+    }
+    if let Some(gre) = &sliced.gre {
+        slices.push((Layer::Gre, gre.slice()));
+    }
+    if let Some(erspan) = &sliced.erspan {
+        slices.push((Layer::Erspan, erspan.slice()));
+    }
+    if let Some(vxlan) = &sliced.vxlan {
+        slices.push((Layer::Vxlan, vxlan.slice()));
+    }
+    if let Some(geneve) = &sliced.geneve {
+        slices.push((Layer::Geneve, geneve.slice()));
+    }
+    if let Some(gtpu) = &sliced.gtpu {
+        slices.push((Layer::Gtpu, gtpu.slice()));
+    }
+    if let Some(nsh) = &sliced.nsh {
+        slices.push((Layer::Nsh, nsh.slice()));
+    }
+
+    let mut result = Vec::with_capacity(slices.len());
+    for (layer, slice) in slices {
+        result.push((layer, offset_of(packet, slice)?));
+    }
+    result.sort_by_key(|(_, start)| *start);
+    Ok(result)
+}
+
+/// Returns the offset of a sub slice in the packet.
+fn offset_of(packet: &[u8], slice: &[u8]) -> Result<usize, RewriteError> {
+    let start = (slice.as_ptr() as usize).wrapping_sub(packet.as_ptr() as usize);
+    if start > packet.len() || packet.len() - start < slice.len() {
+        Err(RewriteError::SliceNotInPacket)
+    } else {
+        Ok(start)
+    }
+}
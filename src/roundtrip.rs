@@ -0,0 +1,216 @@
+//! Parse & serialize round-trip checker for packets.
+//!
+//! [`check`] decodes a packet with [`PacketHeaders`], serializes all decoded
+//! headers again and compares the result byte by byte with the original
+//! packet. Checksums are written as they were received (e.g. via
+//! [`Ipv4Header::write_raw`]), so packets with invalid checksums still
+//! pass the check. Every layer whose serialized form differs from the
+//! original bytes is reported as a [`Mismatch`].
+//!
+//! This can be used to make sure that modifying & re-serializing the headers
+//! of captured packets does not alter the packets in unexpected ways (e.g.
+//! reserved bits that are dropped by the decoder).
+//!
+//! # Example
+//!
+//! ```
+//! use etherparse::{roundtrip, PacketBuilder};
+//!
+//! let mut packet = Vec::new();
+//! PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+//!     .ipv4([192,168,1,1], [192,168,1,2], 20)
+//!     .udp(21, 1234)
+//!     .write(&mut packet, &[1,2,3,4])
+//!     .unwrap();
+//! assert!(roundtrip::check(&packet).unwrap().is_empty());
+//!
+//! // set the reserved flag of the ipv4 header (dropped by the decoder)
+//! packet[14 + 6] |= 0x80;
+//! let mismatches = roundtrip::check(&packet).unwrap();
+//! assert_eq!(1, mismatches.len());
+//! assert_eq!(roundtrip::Layer::Ip, mismatches[0].layer);
+//! assert_eq!(14, mismatches[0].offset);
+//! ```
+
+use super::*;
+
+/// Layer of a packet that is compared by [`check`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Layer {
+    /// Ethernet II header.
+    Ethernet2,
+    /// Linux cooked capture header.
+    LinuxSll,
+    /// MACsec SecTAG.
+    Macsec,
+    /// Single or double vlan header.
+    Vlan,
+    /// MPLS label stack.
+    Mpls,
+    /// PPPoE header.
+    Pppoe,
+    /// ARP packet.
+    Arp,
+    /// NSH header (the encapsulated packet is compared as part of the payload).
+    Nsh,
+    /// IPv4 or IPv6 header including the decoded extension headers.
+    Ip,
+    /// Layer that was not decoded (see [`RawLayer`]).
+    Raw(RawLayerKind),
+    /// UDP, TCP, ICMPv4 or ICMPv6 header.
+    Transport,
+    /// ESP header (the encrypted data is compared as part of the payload).
+    Esp,
+    /// GRE header (the encapsulated packet is compared as part of the payload).
+    Gre,
+    /// ERSPAN header (the mirrored frame is compared as part of the payload).
+    Erspan,
+    /// Remaining data after the headers.
+    Payload,
+}
+
+/// Layer whose serialized form differs from the original bytes.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Mismatch {
+    /// Layer that differs.
+    pub layer: Layer,
+    /// Offset of the layer in the packet.
+    pub offset: usize,
+    /// Original bytes at the position of the layer (shorter then `serialized`
+    /// if the packet ends before the serialized layer).
+    pub original: Vec<u8>,
+    /// Serialized layer (incomplete if the header could not be serialized).
+    pub serialized: Vec<u8>,
+}
+
+/// Checks that a packet starting with an Ethernet II header is serialized
+/// to the identical bytes after decoding it with [`PacketHeaders::from_ethernet_slice`].
+///
+/// Returns an empty list if no mismatches were found & an error if the
+/// packet can not be decoded.
+pub fn check(packet: &[u8]) -> Result<Vec<Mismatch>, ReadError> {
+    Ok(compare(packet, &PacketHeaders::from_ethernet_slice(packet)?))
+}
+
+/// Checks that a packet starting with an ip header is serialized to the
+/// identical bytes after decoding it with [`PacketHeaders::from_ip_slice`].
+///
+/// Returns an empty list if no mismatches were found & an error if the
+/// packet can not be decoded.
+pub fn check_ip(packet: &[u8]) -> Result<Vec<Mismatch>, ReadError> {
+    Ok(compare(packet, &PacketHeaders::from_ip_slice(packet)?))
+}
+
+/// Serializes the headers in the order they appear in the packet & compares them.
+fn compare(packet: &[u8], headers: &PacketHeaders) -> Vec<Mismatch> {
+    let mut comparison = Comparison {
+        packet,
+        offset: 0,
+        mismatches: Vec::new(),
+    };
+
+    if let Some(link) = &headers.link {
+        comparison.layer(Layer::Ethernet2, |writer| link.write(writer).map_err(WriteError::from));
+    }
+    if let Some(sll) = &headers.linux_sll {
+        comparison.layer(Layer::LinuxSll, |writer| sll.write(writer).map_err(WriteError::from));
+    }
+    if let Some(macsec) = &headers.macsec {
+        comparison.layer(Layer::Macsec, |writer| macsec.write(writer));
+    }
+    if let Some(vlan) = &headers.vlan {
+        comparison.layer(Layer::Vlan, |writer| vlan.write(writer));
+    }
+    comparison.raw_layers(headers, |kind| matches!(kind, RawLayerKind::VlanTag(_)));
+    if let Some(mpls) = &headers.mpls {
+        comparison.layer(Layer::Mpls, |writer| {
+            for entry in mpls.to_headers() {
+                entry.write(writer)?;
+            }
+            Ok(())
+        });
+    }
+    if let Some(pppoe) = &headers.pppoe {
+        comparison.layer(Layer::Pppoe, |writer| pppoe.write(writer));
+    }
+    if let Some(arp) = &headers.arp {
+        comparison.layer(Layer::Arp, |writer| arp.write(writer).map_err(WriteError::from));
+    }
+    if let Some(nsh) = &headers.nsh {
+        comparison.layer(Layer::Nsh, |writer| nsh.write(writer));
+    }
+    if let Some(ip) = &headers.ip {
+        comparison.layer(Layer::Ip, |writer| match ip {
+            IpHeader::Version4(header, extensions) => {
+                header.write_raw(writer)?;
+                extensions.write(writer, header.protocol)
+            },
+            IpHeader::Version6(header, extensions) => {
+                header.write(writer)?;
+                extensions.write(writer, header.next_header)
+            },
+        });
+    }
+    comparison.raw_layers(headers, |kind| matches!(kind, RawLayerKind::Ipv6Extension(_)));
+    if let Some(transport) = &headers.transport {
+        comparison.layer(Layer::Transport, |writer| transport.write(writer));
+    }
+    if let Some(esp) = &headers.esp {
+        comparison.layer(Layer::Esp, |writer| esp.write(writer));
+    }
+    if let Some(gre) = &headers.gre {
+        comparison.layer(Layer::Gre, |writer| gre.write(writer).map_err(WriteError::from));
+    }
+    if let Some(erspan) = &headers.erspan {
+        comparison.layer(Layer::Erspan, |writer| erspan.write(writer));
+    }
+    comparison.layer(Layer::Payload, |writer| {
+        writer.extend_from_slice(headers.payload);
+        Ok(())
+    });
+
+    comparison.mismatches
+}
+
+/// State of a running comparison.
+struct Comparison<'a> {
+    packet: &'a [u8],
+    offset: usize,
+    mismatches: Vec<Mismatch>,
+}
+
+impl<'a> Comparison<'a> {
+    /// Serializes a layer at the current offset & records it if it differs.
+    fn layer<F>(&mut self, layer: Layer, f: F)
+    where
+        F: FnOnce(&mut Vec<u8>) -> Result<(), WriteError>,
+    {
+        let mut serialized = Vec::new();
+        // the bytes written before an error are compared (reported as a mismatch)
+        let complete = f(&mut serialized).is_ok();
+
+        let len = serialized.len();
+        let start = self.offset.min(self.packet.len());
+        let end = (self.offset + len).min(self.packet.len());
+        let original = &self.packet[start..end];
+        if false == complete || original != &serialized[..] {
+            self.mismatches.push(Mismatch {
+                layer,
+                offset: self.offset,
+                original: original.to_vec(),
+                serialized,
+            });
+        }
+        self.offset += len;
+    }
+
+    /// Compares the raw layers matching the given kind filter.
+    fn raw_layers<F: Fn(&RawLayerKind) -> bool>(&mut self, headers: &PacketHeaders, filter: F) {
+        for raw in headers.raw_layers.iter().filter(|raw| filter(&raw.kind)) {
+            self.layer(Layer::Raw(raw.kind), |writer| {
+                writer.extend_from_slice(raw.data);
+                Ok(())
+            });
+        }
+    }
+}
@@ -0,0 +1,62 @@
+/// SipHash-2-4 of the given data.
+pub(crate) fn siphash24(key: [u64;2], data: &[u8]) -> u64 {
+    let mut v = [
+        key[0] ^ 0x736f_6d65_7073_6575,
+        key[1] ^ 0x646f_7261_6e64_6f6d,
+        key[0] ^ 0x6c79_6765_6e65_7261,
+        key[1] ^ 0x7465_6462_7974_6573,
+    ];
+
+    fn round(v: &mut [u64;4]) {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(13) ^ v[0];
+        v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(16) ^ v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(21) ^ v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(17) ^ v[2];
+        v[2] = v[2].rotate_left(32);
+    }
+
+    let mut compress = |m: u64| {
+        v[3] ^= m;
+        round(&mut v);
+        round(&mut v);
+        v[0] ^= m;
+    };
+
+    let chunks = data.chunks_exact(8);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let mut bytes = [0u8;8];
+        bytes.copy_from_slice(chunk);
+        compress(u64::from_le_bytes(bytes));
+    }
+    let mut last = [0u8;8];
+    last[..rest.len()].copy_from_slice(rest);
+    last[7] = data.len() as u8;
+    compress(u64::from_le_bytes(last));
+
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn siphash24_reference() {
+        // test vectors of the SipHash reference implementation (key 00..0f, message 00..n-1)
+        let key = [0x0706_0504_0302_0100, 0x0f0e_0d0c_0b0a_0908];
+        let data: Vec<u8> = (0..64).collect();
+        assert_eq!(0x726f_db47_dd0e_0e31, siphash24(key, &data[..0]));
+        assert_eq!(0xa129_ca61_49be_45e5, siphash24(key, &data[..15]));
+        assert_eq!(0x958a_324c_eb06_4572, siphash24(key, &data[..63]));
+    }
+}
@@ -0,0 +1,740 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::fmt::{Debug, Formatter};
+use std::slice::from_raw_parts;
+
+/// Values of the "type" field of a DCCP header (rfc4340 section 5.1).
+pub mod dccp_type {
+    /// Initiates a connection (client to server).
+    pub const REQUEST: u8 = 0;
+    /// Response of the server to a request.
+    pub const RESPONSE: u8 = 1;
+    /// Application data.
+    pub const DATA: u8 = 2;
+    /// Acknowledgement without application data.
+    pub const ACK: u8 = 3;
+    /// Application data with a piggybacked acknowledgement.
+    pub const DATA_ACK: u8 = 4;
+    /// Request of the server to close the connection.
+    pub const CLOSE_REQ: u8 = 5;
+    /// Closes the connection.
+    pub const CLOSE: u8 = 6;
+    /// Unconditionally shuts down the connection.
+    pub const RESET: u8 = 7;
+    /// Resynchronizes the sequence numbers after unexpected sequence numbers were received.
+    pub const SYNC: u8 = 8;
+    /// Acknowledges a sync packet.
+    pub const SYNC_ACK: u8 = 9;
+}
+
+/// Packet type of a DCCP header together with the type specific header
+/// fields (acknowledgement number, service code & reset fields).
+///
+/// The acknowledgement number is 48 bits long if extended sequence numbers
+/// are used and 24 bits long otherwise (see [`DccpHeader::extended_sequence_numbers`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DccpType {
+    /// Initiates a connection.
+    Request {
+        /// Application level service the client wants to connect to.
+        service_code: u32,
+    },
+    /// Response of the server to a request.
+    Response {
+        acknowledgement_number: u64,
+        /// Service code of the request (or the service accepted by the server).
+        service_code: u32,
+    },
+    /// Application data.
+    Data,
+    /// Acknowledgement without application data.
+    Ack { acknowledgement_number: u64 },
+    /// Application data with a piggybacked acknowledgement.
+    DataAck { acknowledgement_number: u64 },
+    /// Request of the server to close the connection.
+    CloseReq { acknowledgement_number: u64 },
+    /// Closes the connection.
+    Close { acknowledgement_number: u64 },
+    /// Unconditionally shuts down the connection.
+    Reset {
+        acknowledgement_number: u64,
+        /// Reason for the reset (rfc4340 section 5.6).
+        reset_code: u8,
+        /// Additional information about the reset (meaning depends on the reset code).
+        data: [u8; 3],
+    },
+    /// Resynchronizes the sequence numbers.
+    Sync { acknowledgement_number: u64 },
+    /// Acknowledges a sync packet.
+    SyncAck { acknowledgement_number: u64 },
+}
+
+impl DccpType {
+    /// Returns the value of the "type" field (see [`dccp_type`]).
+    pub fn type_u8(&self) -> u8 {
+        use DccpType::*;
+        match self {
+            Request { .. } => dccp_type::REQUEST,
+            Response { .. } => dccp_type::RESPONSE,
+            Data => dccp_type::DATA,
+            Ack { .. } => dccp_type::ACK,
+            DataAck { .. } => dccp_type::DATA_ACK,
+            CloseReq { .. } => dccp_type::CLOSE_REQ,
+            Close { .. } => dccp_type::CLOSE,
+            Reset { .. } => dccp_type::RESET,
+            Sync { .. } => dccp_type::SYNC,
+            SyncAck { .. } => dccp_type::SYNC_ACK,
+        }
+    }
+
+    /// Returns the acknowledgement number (`None` for request & data packets).
+    pub fn acknowledgement_number(&self) -> Option<u64> {
+        use DccpType::*;
+        match self {
+            Request { .. } | Data => None,
+            Response { acknowledgement_number, .. } |
+            Ack { acknowledgement_number } |
+            DataAck { acknowledgement_number } |
+            CloseReq { acknowledgement_number } |
+            Close { acknowledgement_number } |
+            Reset { acknowledgement_number, .. } |
+            Sync { acknowledgement_number } |
+            SyncAck { acknowledgement_number } => Some(*acknowledgement_number),
+        }
+    }
+
+    /// Returns the service code (only present in request & response packets).
+    pub fn service_code(&self) -> Option<u32> {
+        use DccpType::*;
+        match self {
+            Request { service_code } | Response { service_code, .. } => Some(*service_code),
+            _ => None,
+        }
+    }
+}
+
+/// Datagram Congestion Control Protocol header according to rfc4340.
+///
+/// The header consists of the generic header (with 24 or 48 bit sequence
+/// numbers depending on the "extended sequence numbers" flag), the packet
+/// type specific fields (see [`DccpType`]) & the options. The options are
+/// stored & serialized as raw bytes (see [`DccpHeader::options`] &
+/// [`DccpHeader::set_options`]).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{DccpHeader, DccpHeaderSlice, DccpType};
+///
+/// let header = DccpHeader::new(
+///     1234,
+///     5001,
+///     0x1234_5678,
+///     DccpType::Request { service_code: 42 }
+/// );
+/// let bytes = header.to_bytes().unwrap();
+/// assert_eq!(20, bytes.len());
+///
+/// let slice = DccpHeaderSlice::from_slice(&bytes).unwrap();
+/// assert!(slice.extended_sequence_numbers());
+/// assert_eq!(0x1234_5678, slice.sequence_number());
+/// assert_eq!(Some(42), slice.service_code());
+/// assert_eq!(header, slice.to_header());
+/// ```
+#[derive(Clone)]
+pub struct DccpHeader {
+    /// Source port of the packet.
+    pub source_port: u16,
+    /// Destination port of the packet.
+    pub destination_port: u16,
+    /// Value used by the congestion control mechanism of the sender (4 bits).
+    pub ccval: u8,
+    /// Checksum coverage (4 bits). A value of 0 indicates that the whole
+    /// packet is covered by the checksum, otherwise the header & the first
+    /// `(checksum_coverage - 1) * 4` bytes of the payload are covered.
+    pub checksum_coverage: u8,
+    /// Checksum calculated over a pseudo header, the dccp header & the
+    /// covered part of the payload.
+    pub checksum: u16,
+    /// If set the sequence & acknowledgement numbers are 48 bits long,
+    /// otherwise 24 bits.
+    pub extended_sequence_numbers: bool,
+    /// Sequence number of the packet (48 or 24 bits).
+    pub sequence_number: u64,
+    /// Packet type & type specific fields.
+    pub dccp_type: DccpType,
+    /// Serialized options (stored on the heap as they are rarely present
+    /// & can be up to MAX_OPTIONS_LEN bytes long).
+    options: Vec<u8>,
+}
+
+impl DccpHeader {
+    /// Length of the smallest dccp header (data packet with 24 bit sequence number).
+    pub const MIN_LEN: usize = 12;
+
+    /// Maximum length of a dccp header (limited by the data offset field).
+    pub const MAX_LEN: usize = 0xff * 4;
+
+    /// Maximum length of the options of a dccp header.
+    pub const MAX_OPTIONS_LEN: usize = DccpHeader::MAX_LEN - DccpHeader::MIN_LEN;
+
+    /// Maximum value of a 48 bit sequence or acknowledgement number.
+    pub const MAX_SEQUENCE_NUMBER: u64 = 0xffff_ffff_ffff;
+
+    /// Maximum value of a 24 bit (short) sequence or acknowledgement number.
+    pub const MAX_SHORT_SEQUENCE_NUMBER: u64 = 0xff_ffff;
+
+    /// Maximum value of the 4 bit ccval & checksum coverage fields.
+    pub const MAX_4BIT_VALUE: u8 = 0b1111;
+
+    /// Creates a header with extended (48 bit) sequence numbers, without
+    /// options & with a checksum of 0.
+    pub fn new(source_port: u16, destination_port: u16, sequence_number: u64, dccp_type: DccpType) -> DccpHeader {
+        DccpHeader {
+            source_port,
+            destination_port,
+            ccval: 0,
+            checksum_coverage: 0,
+            checksum: 0,
+            extended_sequence_numbers: true,
+            sequence_number,
+            dccp_type,
+            options: Vec::new(),
+        }
+    }
+
+    /// Returns the length of the header without the options for the given
+    /// packet type (value of the "type" field).
+    pub(crate) fn fixed_len(extended_sequence_numbers: bool, packet_type: u8) -> usize {
+        let (generic, ack) = if extended_sequence_numbers {
+            (16, 8)
+        } else {
+            (12, 4)
+        };
+        match packet_type {
+            dccp_type::REQUEST => generic + 4,
+            dccp_type::DATA => generic,
+            dccp_type::RESPONSE | dccp_type::RESET => generic + ack + 4,
+            _ => generic + ack,
+        }
+    }
+
+    /// Read a dccp header from a slice and return the header & unused parts of the slice.
+    pub fn from_slice(slice: &[u8]) -> Result<(DccpHeader, &[u8]), ReadError> {
+        let header = DccpHeaderSlice::from_slice(slice)?;
+        let rest = &slice[header.slice().len()..];
+        Ok((header.to_header(), rest))
+    }
+
+    /// Reads a dccp header from the current position of the read argument.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<DccpHeader, ReadError> {
+        let mut buffer = [0u8; DccpHeader::MAX_LEN];
+        reader.read_exact(&mut buffer[..DccpHeader::MIN_LEN])?;
+        let len = DccpHeaderSlice::header_len_from_start(&buffer)?;
+        reader.read_exact(&mut buffer[DccpHeader::MIN_LEN..len])?;
+
+        // SAFETY:
+        // Data offset & packet type were validated above.
+        Ok(unsafe { DccpHeaderSlice::from_slice_unchecked(&buffer[..len]) }.to_header())
+    }
+
+    /// Writes the dccp header to the current position (without recalculating the checksum).
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes (including the options).
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        DccpHeader::fixed_len(self.extended_sequence_numbers, self.dccp_type.type_u8()) + self.options.len()
+    }
+
+    /// Returns a slice containing the serialized options.
+    #[inline]
+    pub fn options(&self) -> &[u8] {
+        &self.options
+    }
+
+    /// Sets the serialized options of the header.
+    ///
+    /// The options have to be padded to a multiple of 4 bytes (e.g. with padding
+    /// options of the value 0) & the resulting header has to fit into the
+    /// data offset field. Otherwise no data is set and an error is returned.
+    pub fn set_options(&mut self, options: &[u8]) -> Result<(), ValueError> {
+        let fixed_len = DccpHeader::fixed_len(self.extended_sequence_numbers, self.dccp_type.type_u8());
+        if false == options.len().is_multiple_of(4) || DccpHeader::MAX_LEN < fixed_len + options.len() {
+            return Err(ValueError::DccpOptionsLengthBad(options.len()));
+        }
+        self.options.clear();
+        self.options.extend_from_slice(options);
+        Ok(())
+    }
+
+    /// Returns the number of payload bytes covered by the checksum for a
+    /// packet with the given payload length.
+    ///
+    /// An error is returned if the checksum coverage exceeds the payload.
+    pub fn coverage_len(&self, payload_len: usize) -> Result<usize, ValueError> {
+        if 0 == self.checksum_coverage {
+            Ok(payload_len)
+        } else {
+            let covered = (usize::from(self.checksum_coverage) - 1) * 4;
+            if covered > payload_len {
+                Err(ValueError::DccpChecksumCoverageBad{
+                    coverage: self.checksum_coverage,
+                    payload_len,
+                })
+            } else {
+                Ok(covered)
+            }
+        }
+    }
+
+    /// Calculates the dccp header checksum based on a ipv4 header.
+    pub fn calc_checksum_ipv4(&self, ip_header: &Ipv4Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv4_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the dccp header checksum based on the ipv4 source &
+    /// destination address plus the payload.
+    pub fn calc_checksum_ipv4_raw(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
+        //check that the total length fits into the ipv4 total length field
+        let len = self.header_len() + payload.len();
+        if (u16::MAX as usize) < len {
+            return Err(ValueError::DccpLengthTooLarge(len));
+        }
+        self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_4bytes(source)
+            .add_4bytes(destination)
+            .add_2bytes([0, ip_number::DCCP])
+            .add_2bytes((len as u16).to_be_bytes()),
+            payload
+        )
+    }
+
+    /// Calculates the dccp header checksum based on a ipv6 header.
+    pub fn calc_checksum_ipv6(&self, ip_header: &Ipv6Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv6_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the dccp header checksum based on the ipv6 source &
+    /// destination address plus the payload.
+    pub fn calc_checksum_ipv6_raw(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
+        //check that the total length fits into the pseudo header length field
+        let len = self.header_len() + payload.len();
+        if (u32::MAX as usize) < len {
+            return Err(ValueError::DccpLengthTooLarge(len));
+        }
+        self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_16bytes(source)
+            .add_16bytes(destination)
+            .add_4bytes((len as u32).to_be_bytes())
+            .add_2bytes([0, ip_number::DCCP]),
+            payload
+        )
+    }
+
+    /// Adds the header & the covered part of the payload to the pseudo header sum.
+    fn calc_checksum_post_ip(&self, ip_pseudo_header_sum: checksum::Sum16BitWords, payload: &[u8]) -> Result<u16, ValueError> {
+        let covered = self.coverage_len(payload.len())?;
+        let bytes = self.to_bytes()?;
+        Ok(
+            ip_pseudo_header_sum
+            .add_slice(&bytes[..6]) //until checksum
+            .add_slice(&bytes[8..])
+            .add_slice(&payload[..covered])
+            .ones_complement()
+            .to_be()
+        )
+    }
+
+    /// Returns true if the checksum of the header matches the checksum
+    /// calculated for the given ipv4 addresses & payload.
+    ///
+    /// False is also returned if the checksum coverage exceeds the payload.
+    pub fn is_checksum_valid_ipv4(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> bool {
+        self.calc_checksum_ipv4_raw(source, destination, payload) == Ok(self.checksum)
+    }
+
+    /// Returns true if the checksum of the header matches the checksum
+    /// calculated for the given ipv6 addresses & payload.
+    ///
+    /// False is also returned if the checksum coverage exceeds the payload.
+    pub fn is_checksum_valid_ipv6(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> bool {
+        self.calc_checksum_ipv6_raw(source, destination, payload) == Ok(self.checksum)
+    }
+
+    /// Returns the serialized form of the header (fails if a field exceeds its
+    /// bit length or the options do not fit into the header).
+    pub fn to_bytes(&self) -> Result<ArrayVec<u8, { DccpHeader::MAX_LEN }>, ValueError> {
+        max_check_u8(self.ccval, DccpHeader::MAX_4BIT_VALUE, ErrorField::DccpCcval)?;
+        max_check_u8(self.checksum_coverage, DccpHeader::MAX_4BIT_VALUE, ErrorField::DccpChecksumCoverage)?;
+
+        let max_sequence_number = if self.extended_sequence_numbers {
+            DccpHeader::MAX_SEQUENCE_NUMBER
+        } else {
+            DccpHeader::MAX_SHORT_SEQUENCE_NUMBER
+        };
+        max_check_u64(self.sequence_number, max_sequence_number, ErrorField::DccpSequenceNumber)?;
+        if let Some(acknowledgement_number) = self.dccp_type.acknowledgement_number() {
+            max_check_u64(acknowledgement_number, max_sequence_number, ErrorField::DccpAcknowledgementNumber)?;
+        }
+
+        let header_len = self.header_len();
+        if DccpHeader::MAX_LEN < header_len {
+            return Err(ValueError::DccpOptionsLengthBad(self.options().len()));
+        }
+
+        let source_port = self.source_port.to_be_bytes();
+        let destination_port = self.destination_port.to_be_bytes();
+        let checksum = self.checksum.to_be_bytes();
+        let sequence_number = self.sequence_number.to_be_bytes();
+
+        let mut result = ArrayVec::new();
+        result.try_extend_from_slice(&[
+            source_port[0],
+            source_port[1],
+            destination_port[0],
+            destination_port[1],
+            (header_len / 4) as u8,
+            (self.ccval << 4) | self.checksum_coverage,
+            checksum[0],
+            checksum[1],
+            (self.dccp_type.type_u8() << 1) | u8::from(self.extended_sequence_numbers),
+        ]).unwrap();
+        if self.extended_sequence_numbers {
+            // reserved
+            result.push(0);
+            result.try_extend_from_slice(&sequence_number[2..]).unwrap();
+        } else {
+            result.try_extend_from_slice(&sequence_number[5..]).unwrap();
+        }
+
+        if let Some(acknowledgement_number) = self.dccp_type.acknowledgement_number() {
+            let acknowledgement_number = acknowledgement_number.to_be_bytes();
+            if self.extended_sequence_numbers {
+                // reserved
+                result.try_extend_from_slice(&[0, 0]).unwrap();
+                result.try_extend_from_slice(&acknowledgement_number[2..]).unwrap();
+            } else {
+                // reserved
+                result.push(0);
+                result.try_extend_from_slice(&acknowledgement_number[5..]).unwrap();
+            }
+        }
+
+        use DccpType::*;
+        match self.dccp_type {
+            Request { service_code } | Response { service_code, .. } => {
+                result.try_extend_from_slice(&service_code.to_be_bytes()).unwrap();
+            },
+            Reset { reset_code, data, .. } => {
+                result.try_extend_from_slice(&[reset_code, data[0], data[1], data[2]]).unwrap();
+            },
+            _ => {},
+        }
+
+        result.try_extend_from_slice(self.options()).unwrap();
+        Ok(result)
+    }
+}
+
+impl Debug for DccpHeader {
+    fn fmt(&self, formatter: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(formatter, "DccpHeader {{ source_port: {}, destination_port: {}, ccval: {}, checksum_coverage: {}, checksum: {}, extended_sequence_numbers: {}, sequence_number: {}, dccp_type: {:?}, options: {:?} }}",
+            self.source_port,
+            self.destination_port,
+            self.ccval,
+            self.checksum_coverage,
+            self.checksum,
+            self.extended_sequence_numbers,
+            self.sequence_number,
+            self.dccp_type,
+            self.options())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DccpHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "DccpHeader {{ source_port: {}, destination_port: {}, ccval: {}, checksum_coverage: {}, checksum: {}, extended_sequence_numbers: {}, sequence_number: {}, dccp_type: {}, options: {=[u8]} }}",
+            self.source_port,
+            self.destination_port,
+            self.ccval,
+            self.checksum_coverage,
+            self.checksum,
+            self.extended_sequence_numbers,
+            self.sequence_number,
+            self.dccp_type,
+            self.options())
+    }
+}
+
+impl std::cmp::PartialEq for DccpHeader {
+    fn eq(&self, other: &DccpHeader) -> bool {
+        self.source_port == other.source_port &&
+        self.destination_port == other.destination_port &&
+        self.ccval == other.ccval &&
+        self.checksum_coverage == other.checksum_coverage &&
+        self.checksum == other.checksum &&
+        self.extended_sequence_numbers == other.extended_sequence_numbers &&
+        self.sequence_number == other.sequence_number &&
+        self.dccp_type == other.dccp_type &&
+        self.options() == other.options()
+    }
+}
+
+impl std::cmp::Eq for DccpHeader {}
+
+impl std::hash::Hash for DccpHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.source_port.hash(state);
+        self.destination_port.hash(state);
+        self.ccval.hash(state);
+        self.checksum_coverage.hash(state);
+        self.checksum.hash(state);
+        self.extended_sequence_numbers.hash(state);
+        self.sequence_number.hash(state);
+        self.dccp_type.hash(state);
+        self.options().hash(state);
+    }
+}
+
+/// Slice containing a dccp header (including the options).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DccpHeaderSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> DccpHeaderSlice<'a> {
+    /// Creates a dccp header slice from a slice (the packet type & the data
+    /// offset are validated).
+    pub fn from_slice(slice: &'a [u8]) -> Result<DccpHeaderSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < DccpHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(DccpHeader::MIN_LEN));
+        }
+        let len = DccpHeaderSlice::header_len_from_start(slice)?;
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+
+        Ok(DccpHeaderSlice {
+            // SAFETY:
+            // Safe as the slice length is checked to be at least len before this
+            // code can be reached.
+            slice: unsafe { from_raw_parts(slice.as_ptr(), len) },
+        })
+    }
+
+    /// Creates a dccp header slice from a slice (assumes slice size & content
+    /// was validated before).
+    ///
+    /// # Safety
+    ///
+    /// This method assumes that the slice was previously validated to contain
+    /// a valid dccp header. This means the slice length must be exactly the
+    /// header length indicated by the data offset field, the packet type must
+    /// not be reserved & the data offset must cover the type specific fields.
+    unsafe fn from_slice_unchecked(slice: &'a [u8]) -> DccpHeaderSlice<'a> {
+        DccpHeaderSlice { slice }
+    }
+
+    /// Checks the packet type & returns the header length based on the data
+    /// offset (`start` has to contain at least the first 12 bytes of the header).
+    fn header_len_from_start(start: &[u8]) -> Result<usize, ReadError> {
+        use crate::ReadError::*;
+
+        let packet_type = (start[8] >> 1) & 0b1111;
+        if dccp_type::SYNC_ACK < packet_type {
+            return Err(DccpPacketTypeReserved(packet_type));
+        }
+        let data_offset = start[4];
+        let len = usize::from(data_offset) * 4;
+        if len < DccpHeader::fixed_len(0 != start[8] & 1, packet_type) {
+            return Err(DccpDataOffsetTooSmall(data_offset));
+        }
+        Ok(len)
+    }
+
+    /// Returns the slice containing the dccp header.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Read the "source port" field.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        u16::from_be_bytes([self.slice[0], self.slice[1]])
+    }
+
+    /// Read the "destination port" field.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Read the "data offset" field (length of the header in 32 bit words).
+    #[inline]
+    pub fn data_offset(&self) -> u8 {
+        self.slice[4]
+    }
+
+    /// Read the "ccval" field (4 bits).
+    #[inline]
+    pub fn ccval(&self) -> u8 {
+        self.slice[5] >> 4
+    }
+
+    /// Read the "checksum coverage" field (4 bits).
+    #[inline]
+    pub fn checksum_coverage(&self) -> u8 {
+        self.slice[5] & 0b1111
+    }
+
+    /// Read the "checksum" field.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[6], self.slice[7]])
+    }
+
+    /// Read the "type" field (see [`dccp_type`]).
+    #[inline]
+    pub fn packet_type(&self) -> u8 {
+        (self.slice[8] >> 1) & 0b1111
+    }
+
+    /// Read the "extended sequence numbers" flag (X).
+    #[inline]
+    pub fn extended_sequence_numbers(&self) -> bool {
+        0 != self.slice[8] & 1
+    }
+
+    /// Read the sequence number (48 or 24 bits).
+    pub fn sequence_number(&self) -> u64 {
+        if self.extended_sequence_numbers() {
+            u48_from_be_bytes(&self.slice[10..16])
+        } else {
+            u64::from_be_bytes([0, 0, 0, 0, 0, self.slice[9], self.slice[10], self.slice[11]])
+        }
+    }
+
+    /// Length of the generic header (depends on the sequence number length).
+    #[inline]
+    fn generic_len(&self) -> usize {
+        if self.extended_sequence_numbers() { 16 } else { 12 }
+    }
+
+    /// Offset of the fields following the acknowledgement number subheader.
+    #[inline]
+    fn after_ack_offset(&self) -> usize {
+        if self.extended_sequence_numbers() {
+            self.generic_len() + 8
+        } else {
+            self.generic_len() + 4
+        }
+    }
+
+    /// Read the acknowledgement number (`None` for request & data packets).
+    pub fn acknowledgement_number(&self) -> Option<u64> {
+        match self.packet_type() {
+            dccp_type::REQUEST | dccp_type::DATA => None,
+            _ => {
+                let end = self.after_ack_offset();
+                Some(if self.extended_sequence_numbers() {
+                    u48_from_be_bytes(&self.slice[end - 6..end])
+                } else {
+                    u64::from_be_bytes([0, 0, 0, 0, 0, self.slice[end - 3], self.slice[end - 2], self.slice[end - 1]])
+                })
+            }
+        }
+    }
+
+    /// Read the service code (only present in request & response packets).
+    pub fn service_code(&self) -> Option<u32> {
+        let offset = match self.packet_type() {
+            dccp_type::REQUEST => self.generic_len(),
+            dccp_type::RESPONSE => self.after_ack_offset(),
+            _ => return None,
+        };
+        Some(u32::from_be_bytes([
+            self.slice[offset],
+            self.slice[offset + 1],
+            self.slice[offset + 2],
+            self.slice[offset + 3],
+        ]))
+    }
+
+    /// Decodes the packet type & the type specific fields.
+    pub fn dccp_type(&self) -> DccpType {
+        use DccpType::*;
+
+        // the constructor verifies that the type is not reserved
+        // & the type specific fields are present
+        let acknowledgement_number = self.acknowledgement_number().unwrap_or(0);
+        match self.packet_type() {
+            dccp_type::REQUEST => Request {
+                service_code: self.service_code().unwrap_or(0),
+            },
+            dccp_type::RESPONSE => Response {
+                acknowledgement_number,
+                service_code: self.service_code().unwrap_or(0),
+            },
+            dccp_type::DATA => Data,
+            dccp_type::ACK => Ack { acknowledgement_number },
+            dccp_type::DATA_ACK => DataAck { acknowledgement_number },
+            dccp_type::CLOSE_REQ => CloseReq { acknowledgement_number },
+            dccp_type::CLOSE => Close { acknowledgement_number },
+            dccp_type::RESET => {
+                let offset = self.after_ack_offset();
+                Reset {
+                    acknowledgement_number,
+                    reset_code: self.slice[offset],
+                    data: [
+                        self.slice[offset + 1],
+                        self.slice[offset + 2],
+                        self.slice[offset + 3],
+                    ],
+                }
+            },
+            dccp_type::SYNC => Sync { acknowledgement_number },
+            _ => SyncAck { acknowledgement_number },
+        }
+    }
+
+    /// Returns a slice containing the options.
+    #[inline]
+    pub fn options(&self) -> &'a [u8] {
+        &self.slice[DccpHeader::fixed_len(self.extended_sequence_numbers(), self.packet_type())..]
+    }
+
+    /// Decode all the fields and copy the results to a [`DccpHeader`] struct.
+    pub fn to_header(&self) -> DccpHeader {
+        DccpHeader {
+            source_port: self.source_port(),
+            destination_port: self.destination_port(),
+            ccval: self.ccval(),
+            checksum_coverage: self.checksum_coverage(),
+            checksum: self.checksum(),
+            extended_sequence_numbers: self.extended_sequence_numbers(),
+            sequence_number: self.sequence_number(),
+            dccp_type: self.dccp_type(),
+            options: self.options().to_vec(),
+        }
+    }
+}
+
+/// Decodes a 48 bit big endian value (`bytes` has to be 6 bytes long).
+#[inline]
+fn u48_from_be_bytes(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes([0, 0, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5]])
+}
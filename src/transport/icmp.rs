@@ -7,7 +7,8 @@
 /// node SHOULD also implement an application-layer interface for
 /// originating Echo Requests and receiving Echo Replies, for diagnostic
 /// purposes.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct IcmpEchoHeader {
     /// An identifier to aid in matching Echo Replies to Echo Requests. May be zero.
     pub id: u16,
@@ -128,7 +128,8 @@ pub mod icmpv4 {
     ///
     /// Codes 0, 1, 4, and 5 may be received from a gateway.  Codes 2 and
     /// 3 may be received from a host.
-    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub enum DestUnreachableHeader {
         /// Network unreachable error.
         Network,
@@ -230,7 +231,8 @@ pub mod icmpv4 {
     pub const CODE_REDIRECT_TYPE_OF_SERVICE_AND_HOST: u8 = 3;
 
     /// Code value in an ICMPv4 Redirect message.
-    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub enum RedirectCode {
         /// Redirect Datagram for the Network (or subnet)
         RedirectForNetwork = 0,
@@ -265,7 +267,8 @@ pub mod icmpv4 {
         }
     }
 
-    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct RedirectHeader {
         pub code: RedirectCode,
         pub gateway_internet_address: [u8; 4],
@@ -278,7 +281,8 @@ pub mod icmpv4 {
     pub const CODE_TIME_EXCEEDED_FRAG_REASSEMBLY_TIME_EXCEEDED: u8 = 1;
 
     /// Code values for ICMPv4 time exceeded message.
-    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub enum TimeExceededCode {
         /// Time-to-live exceeded in transit.
         TtlExceededInTransit = 0,
@@ -308,7 +312,8 @@ pub mod icmpv4 {
     }
 
     /// A ICMPv4 timestamp or timestamp response message.
-    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct TimestampMessage {
         pub id: u16,
         pub seq: u16,
@@ -344,7 +349,8 @@ pub mod icmpv4 {
 
     /// The header of an ICMPv4 Parameter Problems (contents up to
     /// the offending ip header).
-    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub enum ParameterProblemHeader {
         /// Identifies the octet where an error was detected.
         ///
@@ -377,7 +383,8 @@ pub mod icmpv4 {
 use icmpv4::*;
 
 /// Starting contents of an ICMPv4 packet without the checksum.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Icmpv4Type {
     /// In case of an unknown ICMP type and code combination is received the
     /// header elements are stored raw in this enum value. The `Unknown` value can
@@ -723,7 +730,8 @@ impl Icmpv4Type {
 /// What is part of the header depends on the ICMPv4 type
 /// and code. But usually the static sized elements are part
 /// of the header.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Icmpv4Header {
     /// Type & type specific values & code.
     pub icmp_type: Icmpv4Type,
@@ -983,7 +991,7 @@ impl Icmpv4Header {
 ///
 /// Struct allows the selective read of fields in the ICMPv4
 /// packet.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Icmpv4Slice<'a> {
     slice: &'a [u8],
 }
@@ -1284,4 +1292,51 @@ impl<'a> Icmpv4Slice<'a> {
     pub fn slice(&self) -> &'a [u8] {
         self.slice
     }
+
+    /// Slices the original datagram contained in the payload of ICMPv4
+    /// error messages (destination unreachable, source quench, redirect,
+    /// time exceeded & parameter problem).
+    ///
+    /// The original datagram usually only contains the first 8 bytes of
+    /// the transport header. Transport headers that are cut off are not
+    /// decoded (`transport` is `None`) and left in the `payload` field of
+    /// the returned packet. Returns `None` if the message type does not
+    /// contain an original datagram.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use etherparse::*;
+    ///
+    /// // original udp packet
+    /// let mut original = Vec::new();
+    /// PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+    ///     .udp(1234, 53)
+    ///     .write(&mut original, &[1,2,3,4])
+    ///     .unwrap();
+    ///
+    /// // icmp error containing the ip header & 8 bytes of the original packet
+    /// let mut packet = Vec::new();
+    /// PacketBuilder::ipv4([192,168,1,2], [192,168,1,1], 20)
+    ///     .icmpv4(Icmpv4Type::DestinationUnreachable(icmpv4::DestUnreachableHeader::Port))
+    ///     .write(&mut packet, &original[..28])
+    ///     .unwrap();
+    ///
+    /// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    /// if let Some(TransportSlice::Icmpv4(icmp)) = sliced.transport {
+    ///     let embedded = icmp.embedded_packet().unwrap().unwrap();
+    ///     assert!(matches!(
+    ///         embedded.transport,
+    ///         Some(TransportSlice::Udp(udp)) if 53 == udp.destination_port()
+    ///     ));
+    /// }
+    /// ```
+    pub fn embedded_packet(&self) -> Option<Result<SlicedPacket<'a>, ReadError>> {
+        match self.type_u8() {
+            TYPE_DEST_UNREACH | TYPE_SOURCE_QUENCH | TYPE_REDIRECT | TYPE_TIME_EXCEEDED | TYPE_PARAMETER_PROBLEM => {
+                Some(SlicedPacket::from_icmp_embedded(self.payload()))
+            },
+            _ => None,
+        }
+    }
 }
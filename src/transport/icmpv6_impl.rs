@@ -110,7 +110,8 @@ pub mod icmpv6 {
     /// that cannot be delivered to its destination address for reasons other
     /// than congestion.  (An ICMPv6 message MUST NOT be generated if a
     /// packet is dropped due to congestion.)
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub enum DestUnreachableCode {
         /// No route to destination
         NoRoute = 0,
@@ -178,7 +179,8 @@ pub mod icmpv6 {
     pub const CODE_TIME_EXCEEDED_FRAGMENT_REASSEMBLY_TIME_EXCEEDED: u8 = 1;
 
     /// Code values for ICMPv6 time exceeded message.
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub enum TimeExceededCode {
         /// "hop limit exceeded in transit"
         HopLimitExceeded = 0,
@@ -246,7 +248,8 @@ pub mod icmpv6 {
     /// Code values for ICMPv6 parameter problem messages.
     ///
     /// Source: <https://www.iana.org/assignments/icmpv6-parameters/icmpv6-parameters.xhtml#icmpv6-parameters-codes-5>
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub enum ParameterProblemCode {
         /// Erroneous header field encountered (from [RFC 4443](https://tools.ietf.org/html/rfc4443))
         ErroneousHeaderField = 0,
@@ -307,7 +310,8 @@ pub mod icmpv6 {
     }
 
     /// ICMPv6 parameter problem header.
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct ParameterProblemHeader {
         /// The code can offer additional informations about what kind of parameter
         /// problem caused the error.
@@ -406,7 +410,8 @@ use icmpv6::*;
 /// #   );
 /// # }
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Icmpv6Type {
     /// In case of an unknown icmp type is received the header elements of
     /// the first 8 bytes/octets are stored raw in this enum value.
@@ -829,7 +834,8 @@ impl Icmpv6Type {
 }
 
 /// The statically sized data at the start of an ICMPv6 packet (at least the first 8 bytes of an ICMPv6 packet).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Icmpv6Header {
     /// Type & type specific values & code.
     pub icmp_type: Icmpv6Type,
@@ -1016,7 +1022,7 @@ impl Icmpv6Header {
 ///
 /// Struct allows the selective read of fields in the ICMPv6
 /// packet.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Icmpv6Slice<'a> {
     slice: &'a [u8],
 }
@@ -1191,4 +1197,21 @@ impl<'a> Icmpv6Slice<'a> {
         // at least the length of Icmpv6Header::MIN_SERIALIZED_SIZE(8).
         unsafe { from_raw_parts(self.slice.as_ptr().add(8), self.slice.len() - 8) }
     }
+
+    /// Slices the invoking packet contained in the payload of ICMPv6
+    /// error messages (destination unreachable, packet too big, time
+    /// exceeded & parameter problem).
+    ///
+    /// Transport headers cut off by the end of the message are not decoded
+    /// (`transport` is `None`) and left in the `payload` field of the
+    /// returned packet. Returns `None` if the message type does not contain
+    /// an invoking packet.
+    pub fn embedded_packet(&self) -> Option<Result<SlicedPacket<'a>, ReadError>> {
+        match self.type_u8() {
+            TYPE_DST_UNREACH | TYPE_PACKET_TOO_BIG | TYPE_TIME_EXCEEDED | TYPE_PARAMETER_PROBLEM => {
+                Some(SlicedPacket::from_icmp_embedded(self.payload()))
+            },
+            _ => None,
+        }
+    }
 }
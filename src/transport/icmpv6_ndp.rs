@@ -0,0 +1,563 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// `u8` constants of the NDP option types (RFC 4861).
+pub mod ndp_option_type {
+    /// Link-layer address of the sender of the packet.
+    pub const SOURCE_LINK_LAYER_ADDRESS: u8 = 1;
+    /// Link-layer address of the target.
+    pub const TARGET_LINK_LAYER_ADDRESS: u8 = 2;
+    /// On-link & address autoconfiguration prefix (router advertisements).
+    pub const PREFIX_INFORMATION: u8 = 3;
+    /// Part of the packet that triggered a redirect.
+    pub const REDIRECTED_HEADER: u8 = 4;
+    /// MTU of the link (router advertisements).
+    pub const MTU: u8 = 5;
+}
+
+/// Fixed part of an ICMPv6 router advertisement (RFC 4861, section 4.2).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RouterAdvertisementHeader {
+    /// Default hop limit for outgoing packets (0 means unspecified).
+    pub cur_hop_limit: u8,
+    /// "Managed address configuration" flag (addresses available via DHCPv6).
+    pub managed_address_config: bool,
+    /// "Other configuration" flag (other configuration available via DHCPv6).
+    pub other_config: bool,
+    /// Lifetime of the router as default router in seconds (0 if the
+    /// router is not a default router).
+    pub router_lifetime: u16,
+    /// Time in milliseconds a neighbor is assumed to be reachable after
+    /// a reachability confirmation (0 means unspecified).
+    pub reachable_time: u32,
+    /// Time in milliseconds between retransmitted neighbor solicitations
+    /// (0 means unspecified).
+    pub retrans_timer: u32,
+}
+
+/// Fixed part of an ICMPv6 neighbor advertisement (RFC 4861, section 4.4).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NeighborAdvertisementHeader {
+    /// "Router" flag (set if the sender is a router).
+    pub router: bool,
+    /// "Solicited" flag (set if the advertisement is a response to a
+    /// neighbor solicitation).
+    pub solicited: bool,
+    /// "Override" flag (set if the advertisement should override existing
+    /// cache entries).
+    pub override_flag: bool,
+    /// Address of the target of the solicitation that triggered the
+    /// advertisement (or the address whose link-layer address changed).
+    pub target_address: [u8;16],
+}
+
+/// Neighbor discovery protocol (NDP) messages (RFC 4861).
+///
+/// The values contain the fixed part of the messages after the ICMPv6 type,
+/// code & checksum. The NDP options following the fixed part can be
+/// iterated via [`NdpSlice::options`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// // neighbor solicitation asking for the link-layer address of fe80::2
+/// let source = [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1];
+/// let target = [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,2];
+/// let message = NdpMessage::NeighborSolicitation{ target_address: target };
+/// let mut options = Vec::new();
+/// NdpOption::SourceLinkLayerAddress(&[1,2,3,4,5,6]).write(&mut options).unwrap();
+///
+/// let mut packet = Vec::new();
+/// message.write(&mut packet, source, target, &options).unwrap();
+///
+/// // decode the message again
+/// let icmp = Icmpv6Slice::from_slice(&packet).unwrap();
+/// assert!(icmp.is_checksum_valid(source, target));
+/// let ndp = NdpSlice::from_icmpv6(&icmp).unwrap();
+/// assert_eq!(message, ndp.message());
+/// assert_eq!(
+///     vec![Ok(NdpOption::SourceLinkLayerAddress(&[1,2,3,4,5,6]))],
+///     ndp.options().collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NdpMessage {
+    /// Request for router advertisements (ICMPv6 type 133).
+    RouterSolicitation,
+    /// Advertisement of a router (ICMPv6 type 134).
+    RouterAdvertisement(RouterAdvertisementHeader),
+    /// Request for the link-layer address of a target (ICMPv6 type 135).
+    NeighborSolicitation {
+        /// Address of the target of the solicitation.
+        target_address: [u8;16],
+    },
+    /// Response to a neighbor solicitation (ICMPv6 type 136).
+    NeighborAdvertisement(NeighborAdvertisementHeader),
+    /// Information about a better first hop for a destination (ICMPv6 type 137).
+    Redirect {
+        /// Address of the better first hop.
+        target_address: [u8;16],
+        /// Address of the destination that is redirected to the target.
+        destination_address: [u8;16],
+    },
+}
+
+impl NdpMessage {
+    /// Returns the ICMPv6 type value of the message.
+    pub fn type_u8(&self) -> u8 {
+        use icmpv6::*;
+        use NdpMessage::*;
+        match self {
+            RouterSolicitation => TYPE_ROUTER_SOLICITATION,
+            RouterAdvertisement(_) => TYPE_ROUTER_ADVERTISEMENT,
+            NeighborSolicitation{ .. } => TYPE_NEIGHBOR_SOLICITATION,
+            NeighborAdvertisement(_) => TYPE_NEIGHBOR_ADVERTISEMENT,
+            Redirect{ .. } => TYPE_REDIRECT_MESSAGE,
+        }
+    }
+
+    /// Serialized length of the message in bytes/octets (including the
+    /// ICMPv6 type, code & checksum but without the options).
+    pub fn header_len(&self) -> usize {
+        use NdpMessage::*;
+        match self {
+            RouterSolicitation => 8,
+            RouterAdvertisement(_) => 16,
+            NeighborSolicitation{ .. } | NeighborAdvertisement(_) => 24,
+            Redirect{ .. } => 40,
+        }
+    }
+
+    /// Calculates the ICMPv6 checksum of the message with the given
+    /// options (serialized NDP options).
+    pub fn calc_checksum(
+        &self,
+        source_ip: [u8; 16],
+        destination_ip: [u8; 16],
+        options: &[u8],
+    ) -> Result<u16, ValueError> {
+        let max_options_len: usize = (u32::MAX as usize) - self.header_len();
+        if max_options_len < options.len() {
+            return Err(ValueError::Ipv6PayloadLengthTooLarge(options.len()));
+        }
+        let msg_len = options.len() + self.header_len();
+        Ok(
+            checksum::Sum16BitWords::new()
+            .add_16bytes(source_ip)
+            .add_16bytes(destination_ip)
+            .add_2bytes([0, ip_number::IPV6_ICMP])
+            .add_4bytes((msg_len as u32).to_be_bytes())
+            .add_slice(&self.to_bytes(0))
+            .add_slice(options)
+            .ones_complement()
+            .to_be()
+        )
+    }
+
+    /// Returns the serialized message with the given checksum.
+    pub fn to_bytes(&self, checksum: u16) -> ArrayVec<u8, { Icmpv6Header::MAX_SERIALIZED_SIZE }> {
+        use NdpMessage::*;
+        let mut result = ArrayVec::new();
+        result.push(self.type_u8());
+        result.push(0);
+        result.extend(checksum.to_be_bytes());
+        match self {
+            RouterSolicitation => {
+                result.extend([0;4]);
+            },
+            RouterAdvertisement(header) => {
+                result.push(header.cur_hop_limit);
+                result.push(
+                    if header.managed_address_config { 0x80 } else { 0 } |
+                    if header.other_config { 0x40 } else { 0 }
+                );
+                result.extend(header.router_lifetime.to_be_bytes());
+                result.extend(header.reachable_time.to_be_bytes());
+                result.extend(header.retrans_timer.to_be_bytes());
+            },
+            NeighborSolicitation{ target_address } => {
+                result.extend([0;4]);
+                result.extend(*target_address);
+            },
+            NeighborAdvertisement(header) => {
+                result.push(
+                    if header.router { 0x80 } else { 0 } |
+                    if header.solicited { 0x40 } else { 0 } |
+                    if header.override_flag { 0x20 } else { 0 }
+                );
+                result.extend([0;3]);
+                result.extend(header.target_address);
+            },
+            Redirect{ target_address, destination_address } => {
+                result.extend([0;4]);
+                result.extend(*target_address);
+                result.extend(*destination_address);
+            },
+        }
+        result
+    }
+
+    /// Writes the message with the correct checksum followed by the given
+    /// options (serialized NDP options, see [`NdpOption::write`]).
+    pub fn write<T: io::Write + Sized>(
+        &self,
+        writer: &mut T,
+        source_ip: [u8; 16],
+        destination_ip: [u8; 16],
+        options: &[u8],
+    ) -> Result<(), WriteError> {
+        let checksum = self.calc_checksum(source_ip, destination_ip, options)
+            .map_err(WriteError::ValueError)?;
+        writer.write_all(&self.to_bytes(checksum))?;
+        writer.write_all(options)?;
+        Ok(())
+    }
+}
+
+/// Prefix information NDP option (RFC 4861, section 4.6.2).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NdpPrefixInformation {
+    /// Number of leading bits of `prefix` that are valid.
+    pub prefix_length: u8,
+    /// "On-link" flag (the prefix can be used for on-link determination).
+    pub on_link: bool,
+    /// "Autonomous address-configuration" flag (the prefix can be used for
+    /// stateless address autoconfiguration).
+    pub autonomous: bool,
+    /// Time in seconds the prefix is valid for on-link determination
+    /// (`u32::MAX` means infinity).
+    pub valid_lifetime: u32,
+    /// Time in seconds addresses generated from the prefix remain
+    /// preferred (`u32::MAX` means infinity).
+    pub preferred_lifetime: u32,
+    /// The prefix (bits after `prefix_length` are zero).
+    pub prefix: [u8;16],
+}
+
+/// Option of a neighbor discovery protocol message (RFC 4861, section 4.6).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NdpOption<'a> {
+    /// Link-layer address of the sender (including the padding to the
+    /// next multiple of 8 bytes).
+    SourceLinkLayerAddress(&'a [u8]),
+    /// Link-layer address of the target (including the padding to the
+    /// next multiple of 8 bytes).
+    TargetLinkLayerAddress(&'a [u8]),
+    /// On-link & address autoconfiguration prefix.
+    PrefixInformation(NdpPrefixInformation),
+    /// Start of the packet that triggered a redirect.
+    RedirectedHeader(&'a [u8]),
+    /// MTU of the link.
+    Mtu(u32),
+    /// Option that is not decoded by this crate.
+    Unknown {
+        /// Type of the option.
+        option_type: u8,
+        /// Data of the option after the type & length field.
+        data: &'a [u8],
+    },
+}
+
+impl<'a> NdpOption<'a> {
+    /// Maximum length of a serialized option (the length field counts
+    /// units of 8 bytes).
+    pub const MAX_SERIALIZED_LEN: usize = 0xff*8;
+
+    /// Returns the type value of the option.
+    pub fn option_type(&self) -> u8 {
+        use ndp_option_type::*;
+        use NdpOption::*;
+        match self {
+            SourceLinkLayerAddress(_) => SOURCE_LINK_LAYER_ADDRESS,
+            TargetLinkLayerAddress(_) => TARGET_LINK_LAYER_ADDRESS,
+            PrefixInformation(_) => PREFIX_INFORMATION,
+            RedirectedHeader(_) => REDIRECTED_HEADER,
+            Mtu(_) => MTU,
+            Unknown{ option_type, .. } => *option_type,
+        }
+    }
+
+    /// Length of the serialized option in bytes/octets (including the
+    /// padding to a multiple of 8 bytes).
+    pub fn serialized_len(&self) -> usize {
+        use NdpOption::*;
+        let unpadded = match self {
+            SourceLinkLayerAddress(data) |
+            TargetLinkLayerAddress(data) |
+            Unknown{ data, .. } => 2 + data.len(),
+            PrefixInformation(_) => 32,
+            RedirectedHeader(data) => 8 + data.len(),
+            Mtu(_) => 8,
+        };
+        unpadded.div_ceil(8) * 8
+    }
+
+    /// Writes the option (padded with zeros to a multiple of 8 bytes).
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        use NdpOption::*;
+        let len = self.serialized_len();
+        if len > NdpOption::MAX_SERIALIZED_LEN {
+            return Err(WriteError::ValueError(ValueError::NdpOptionTooLong(len)));
+        }
+        writer.write_all(&[self.option_type(), (len / 8) as u8])?;
+        let written = match self {
+            SourceLinkLayerAddress(data) |
+            TargetLinkLayerAddress(data) |
+            Unknown{ data, .. } => {
+                writer.write_all(data)?;
+                2 + data.len()
+            },
+            PrefixInformation(info) => {
+                writer.write_all(&[
+                    info.prefix_length,
+                    if info.on_link { 0x80 } else { 0 } |
+                    if info.autonomous { 0x40 } else { 0 }
+                ])?;
+                writer.write_all(&info.valid_lifetime.to_be_bytes())?;
+                writer.write_all(&info.preferred_lifetime.to_be_bytes())?;
+                writer.write_all(&[0;4])?;
+                writer.write_all(&info.prefix)?;
+                32
+            },
+            RedirectedHeader(data) => {
+                writer.write_all(&[0;6])?;
+                writer.write_all(data)?;
+                8 + data.len()
+            },
+            Mtu(mtu) => {
+                writer.write_all(&[0;2])?;
+                writer.write_all(&mtu.to_be_bytes())?;
+                8
+            },
+        };
+        writer.write_all(&[0;7][..len - written])?;
+        Ok(())
+    }
+}
+
+/// Errors that can occur when reading NDP options.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum NdpOptionReadError {
+    /// Returned if an option is longer then the rest of the options slice.
+    UnexpectedEndOfSlice{ option_type: u8, expected_len: usize, actual_len: usize },
+    /// Returned if the length field of an option is zero (not allowed as
+    /// the option would never end).
+    ZeroLength{ option_type: u8 },
+    /// Returned if the option has an unexpected length value (e.g. != 4
+    /// for the prefix information).
+    UnexpectedSize{ option_type: u8, size: u8 },
+}
+
+impl Error for NdpOptionReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for NdpOptionReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use NdpOptionReadError::*;
+        match self {
+            UnexpectedEndOfSlice{ option_type, expected_len, actual_len } => {
+                write!(f, "NdpOptionReadError: Not enough data left in slice to read option of type {} (expected at least {} bytes, only {} bytes available).", option_type, expected_len, actual_len)
+            },
+            ZeroLength{ option_type } => {
+                write!(f, "NdpOptionReadError: Length value of the option of type {} is zero.", option_type)
+            },
+            UnexpectedSize{ option_type, size } => {
+                write!(f, "NdpOptionReadError: Length value of the option of type {} had unexpected value {}.", option_type, size)
+            },
+        }
+    }
+}
+
+/// Allows iterating over the options of a neighbor discovery protocol message.
+///
+/// The iterator stops after the first error.
+#[derive(Clone, Eq, PartialEq)]
+pub struct NdpOptionsIterator<'a> {
+    options: &'a [u8],
+}
+
+impl<'a> NdpOptionsIterator<'a> {
+    /// Creates an options iterator from a slice containing encoded NDP options.
+    pub fn from_slice(options: &'a [u8]) -> NdpOptionsIterator<'a> {
+        NdpOptionsIterator{ options }
+    }
+
+    /// Returns the non processed part of the options slice.
+    pub fn rest(&self) -> &'a [u8] {
+        self.options
+    }
+}
+
+impl<'a> Iterator for NdpOptionsIterator<'a> {
+    type Item = Result<NdpOption<'a>, NdpOptionReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use NdpOptionReadError::*;
+        use ndp_option_type::*;
+
+        if self.options.is_empty() {
+            return None;
+        }
+
+        let option_type = self.options[0];
+        let result = if self.options.len() < 2 {
+            Err(UnexpectedEndOfSlice{ option_type, expected_len: 2, actual_len: self.options.len() })
+        } else if 0 == self.options[1] {
+            Err(ZeroLength{ option_type })
+        } else if self.options.len() < usize::from(self.options[1])*8 {
+            Err(UnexpectedEndOfSlice{
+                option_type,
+                expected_len: usize::from(self.options[1])*8,
+                actual_len: self.options.len()
+            })
+        } else {
+            let size = self.options[1];
+            let option = &self.options[..usize::from(size)*8];
+            match option_type {
+                SOURCE_LINK_LAYER_ADDRESS => Ok(NdpOption::SourceLinkLayerAddress(&option[2..])),
+                TARGET_LINK_LAYER_ADDRESS => Ok(NdpOption::TargetLinkLayerAddress(&option[2..])),
+                PREFIX_INFORMATION => if 4 == size {
+                    let mut prefix = [0;16];
+                    prefix.copy_from_slice(&option[16..32]);
+                    Ok(NdpOption::PrefixInformation(NdpPrefixInformation{
+                        prefix_length: option[2],
+                        on_link: 0 != option[3] & 0x80,
+                        autonomous: 0 != option[3] & 0x40,
+                        valid_lifetime: u32::from_be_bytes([option[4], option[5], option[6], option[7]]),
+                        preferred_lifetime: u32::from_be_bytes([option[8], option[9], option[10], option[11]]),
+                        prefix,
+                    }))
+                } else {
+                    Err(UnexpectedSize{ option_type, size })
+                },
+                REDIRECTED_HEADER => Ok(NdpOption::RedirectedHeader(&option[8..])),
+                MTU => if 1 == size {
+                    Ok(NdpOption::Mtu(u32::from_be_bytes([option[4], option[5], option[6], option[7]])))
+                } else {
+                    Err(UnexpectedSize{ option_type, size })
+                },
+                _ => Ok(NdpOption::Unknown{ option_type, data: &option[2..] }),
+            }
+        };
+
+        match result {
+            Ok(_) => {
+                self.options = &self.options[usize::from(self.options[1])*8..];
+            },
+            Err(_) => {
+                // stop the iteration after an error
+                self.options = &[];
+            }
+        }
+        Some(result)
+    }
+}
+
+impl<'a> fmt::Debug for NdpOptionsIterator<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut list = fmt.debug_list();
+        for element in self.clone() {
+            list.entry(&element);
+        }
+        list.finish()
+    }
+}
+
+/// A slice containing an ICMPv6 neighbor discovery protocol message.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NdpSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> NdpSlice<'a> {
+    /// Returns the NDP message contained in an ICMPv6 packet.
+    ///
+    /// `None` is returned if the ICMPv6 type is not a NDP message type, the
+    /// code is not 0 or the packet is too short for the message type.
+    ///
+    /// Note that RFC 4861 requires receivers to also check that the hop
+    /// limit of the ipv6 header is 255 (this is left to the caller).
+    pub fn from_icmpv6(icmp: &Icmpv6Slice<'a>) -> Option<NdpSlice<'a>> {
+        use icmpv6::*;
+        let min_len = match icmp.type_u8() {
+            TYPE_ROUTER_SOLICITATION => 8,
+            TYPE_ROUTER_ADVERTISEMENT => 16,
+            TYPE_NEIGHBOR_SOLICITATION | TYPE_NEIGHBOR_ADVERTISEMENT => 24,
+            TYPE_REDIRECT_MESSAGE => 40,
+            _ => return None,
+        };
+        if 0 != icmp.code_u8() || icmp.slice().len() < min_len {
+            None
+        } else {
+            Some(NdpSlice{ slice: icmp.slice() })
+        }
+    }
+
+    /// Returns the slice containing the ICMPv6 packet.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Decodes the fixed part of the message.
+    pub fn message(&self) -> NdpMessage {
+        use icmpv6::*;
+        use NdpMessage::*;
+        let s = self.slice;
+        let address = |start: usize| -> [u8;16] {
+            let mut result = [0;16];
+            result.copy_from_slice(&s[start..start + 16]);
+            result
+        };
+        match s[0] {
+            TYPE_ROUTER_ADVERTISEMENT => RouterAdvertisement(RouterAdvertisementHeader{
+                cur_hop_limit: s[4],
+                managed_address_config: 0 != s[5] & 0x80,
+                other_config: 0 != s[5] & 0x40,
+                router_lifetime: u16::from_be_bytes([s[6], s[7]]),
+                reachable_time: u32::from_be_bytes([s[8], s[9], s[10], s[11]]),
+                retrans_timer: u32::from_be_bytes([s[12], s[13], s[14], s[15]]),
+            }),
+            TYPE_NEIGHBOR_SOLICITATION => NeighborSolicitation{
+                target_address: address(8),
+            },
+            TYPE_NEIGHBOR_ADVERTISEMENT => NeighborAdvertisement(NeighborAdvertisementHeader{
+                router: 0 != s[4] & 0x80,
+                solicited: 0 != s[4] & 0x40,
+                override_flag: 0 != s[4] & 0x20,
+                target_address: address(8),
+            }),
+            TYPE_REDIRECT_MESSAGE => Redirect{
+                target_address: address(8),
+                destination_address: address(24),
+            },
+            // the constructor only allows NDP types
+            _ => RouterSolicitation,
+        }
+    }
+
+    /// Returns the slice containing the options after the fixed part of
+    /// the message.
+    pub fn options_slice(&self) -> &'a [u8] {
+        &self.slice[self.message().header_len()..]
+    }
+
+    /// Returns an iterator over the options of the message.
+    pub fn options(&self) -> NdpOptionsIterator<'a> {
+        NdpOptionsIterator::from_slice(self.options_slice())
+    }
+}
@@ -0,0 +1,446 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+
+/// Module containing IGMP related types and constants.
+pub mod igmp {
+
+    /// IGMP type value of a "Membership Query" (defined in [RFC 2236](https://tools.ietf.org/html/rfc2236) & [RFC 3376](https://tools.ietf.org/html/rfc3376)).
+    pub const TYPE_MEMBERSHIP_QUERY: u8 = 0x11;
+
+    /// IGMP type value of a "Version 1 Membership Report" (defined in [RFC 1112](https://tools.ietf.org/html/rfc1112)).
+    pub const TYPE_V1_MEMBERSHIP_REPORT: u8 = 0x12;
+
+    /// IGMP type value of a "Version 2 Membership Report" (defined in [RFC 2236](https://tools.ietf.org/html/rfc2236)).
+    pub const TYPE_V2_MEMBERSHIP_REPORT: u8 = 0x16;
+
+    /// IGMP type value of a "Leave Group" message (defined in [RFC 2236](https://tools.ietf.org/html/rfc2236)).
+    pub const TYPE_LEAVE_GROUP: u8 = 0x17;
+
+    /// IGMP type value of a "Version 3 Membership Report" (defined in [RFC 3376](https://tools.ietf.org/html/rfc3376)).
+    pub const TYPE_V3_MEMBERSHIP_REPORT: u8 = 0x22;
+
+    /// Group record type "MODE_IS_INCLUDE" (current state, RFC 3376 section 4.2.12).
+    pub const RECORD_MODE_IS_INCLUDE: u8 = 1;
+
+    /// Group record type "MODE_IS_EXCLUDE" (current state, RFC 3376 section 4.2.12).
+    pub const RECORD_MODE_IS_EXCLUDE: u8 = 2;
+
+    /// Group record type "CHANGE_TO_INCLUDE_MODE" (filter mode change, RFC 3376 section 4.2.12).
+    pub const RECORD_CHANGE_TO_INCLUDE_MODE: u8 = 3;
+
+    /// Group record type "CHANGE_TO_EXCLUDE_MODE" (filter mode change, RFC 3376 section 4.2.12).
+    pub const RECORD_CHANGE_TO_EXCLUDE_MODE: u8 = 4;
+
+    /// Group record type "ALLOW_NEW_SOURCES" (source list change, RFC 3376 section 4.2.12).
+    pub const RECORD_ALLOW_NEW_SOURCES: u8 = 5;
+
+    /// Group record type "BLOCK_OLD_SOURCES" (source list change, RFC 3376 section 4.2.12).
+    pub const RECORD_BLOCK_OLD_SOURCES: u8 = 6;
+}
+
+/// IGMPv3 specific fields of a membership query (RFC 3376 section 4.1).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IgmpV3QueryFields {
+    /// "S" flag, routers should suppress the timer updates they would
+    /// normally perform when receiving the query.
+    pub suppress_router_processing: bool,
+    /// Querier's robustness variable (3 bits, 0 if it exceeds 7).
+    pub querier_robustness_variable: u8,
+    /// Querier's query interval code (see [`IgmpHeader::decode_code`]).
+    pub querier_query_interval_code: u8,
+    /// Number of source addresses following the fixed part of the query.
+    pub number_of_sources: u16,
+}
+
+/// IGMP membership query (general, group specific or group & source specific).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct IgmpMembershipQuery {
+    /// Maximum allowed time before sending a report in units of 1/10
+    /// seconds (IGMPv3 queries encode bigger values as floating point,
+    /// see [`IgmpHeader::decode_code`]). Zero for IGMPv1 queries.
+    pub max_resp_code: u8,
+    /// Queried group (zero for general queries).
+    pub group_address: [u8;4],
+    /// IGMPv3 fields (`None` for IGMPv1 & IGMPv2 queries).
+    pub v3: Option<IgmpV3QueryFields>,
+}
+
+/// Fixed part of an IGMP message (without the source lists & group records).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// // IGMPv2 report for the group 239.1.2.3
+/// let header = IgmpHeader::MembershipReportV2{ group_address: [239, 1, 2, 3] };
+/// let mut bytes = Vec::new();
+/// header.write(&mut bytes, &[]).unwrap();
+///
+/// let slice = IgmpSlice::from_slice(&bytes).unwrap();
+/// assert!(slice.is_checksum_valid());
+/// assert_eq!(header, slice.header());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum IgmpHeader {
+    /// Membership query (type 0x11) send by multicast routers.
+    MembershipQuery(IgmpMembershipQuery),
+    /// IGMPv1 membership report (type 0x12).
+    MembershipReportV1 {
+        /// Group the host is member of.
+        group_address: [u8;4],
+    },
+    /// IGMPv2 membership report (type 0x16).
+    MembershipReportV2 {
+        /// Group the host is member of.
+        group_address: [u8;4],
+    },
+    /// IGMPv2 leave group message (type 0x17).
+    LeaveGroup {
+        /// Group the host leaves.
+        group_address: [u8;4],
+    },
+    /// IGMPv3 membership report (type 0x22), the group records follow the
+    /// fixed part (see [`IgmpSlice::group_records`]).
+    MembershipReportV3 {
+        /// Number of group records in the report.
+        number_of_group_records: u16,
+    },
+    /// Message with an unknown type.
+    Unknown {
+        /// IGMP type (first byte of the message).
+        type_u8: u8,
+        /// Second byte of the message.
+        code_u8: u8,
+        /// Bytes located after the checksum.
+        bytes5to8: [u8;4],
+    },
+}
+
+impl IgmpHeader {
+    /// Length of the fixed part of IGMPv1, IGMPv2 messages & IGMPv3 reports.
+    pub const MIN_LEN: usize = 8;
+
+    /// Length of the fixed part of an IGMPv3 membership query.
+    pub const V3_QUERY_LEN: usize = 12;
+
+    /// Returns the IGMP type value of the message.
+    pub fn type_u8(&self) -> u8 {
+        use igmp::*;
+        use IgmpHeader::*;
+        match self {
+            MembershipQuery(_) => TYPE_MEMBERSHIP_QUERY,
+            MembershipReportV1{ .. } => TYPE_V1_MEMBERSHIP_REPORT,
+            MembershipReportV2{ .. } => TYPE_V2_MEMBERSHIP_REPORT,
+            LeaveGroup{ .. } => TYPE_LEAVE_GROUP,
+            MembershipReportV3{ .. } => TYPE_V3_MEMBERSHIP_REPORT,
+            Unknown{ type_u8, .. } => *type_u8,
+        }
+    }
+
+    /// Length of the serialized header in bytes.
+    pub fn header_len(&self) -> usize {
+        match self {
+            IgmpHeader::MembershipQuery(IgmpMembershipQuery{ v3: Some(_), .. }) => IgmpHeader::V3_QUERY_LEN,
+            _ => IgmpHeader::MIN_LEN,
+        }
+    }
+
+    /// Decodes the "max resp code" & "querier's query interval code" of
+    /// IGMPv3 queries (RFC 3376 section 4.1.1). Values bellow 128 are
+    /// returned unchanged, bigger values are decoded from their floating
+    /// point representation.
+    pub fn decode_code(code: u8) -> u32 {
+        if code < 128 {
+            u32::from(code)
+        } else {
+            let exp = u32::from((code >> 4) & 0b111);
+            let mant = u32::from(code & 0xf);
+            (mant | 0x10) << (exp + 3)
+        }
+    }
+
+    /// Returns the header with a zero checksum.
+    fn to_bytes(self) -> ArrayVec<u8, { IgmpHeader::V3_QUERY_LEN }> {
+        use IgmpHeader::*;
+        let mut bytes = ArrayVec::new();
+        let (code, rest) = match &self {
+            MembershipQuery(query) => (query.max_resp_code, query.group_address),
+            MembershipReportV1{ group_address } |
+            MembershipReportV2{ group_address } |
+            LeaveGroup{ group_address } => (0, *group_address),
+            MembershipReportV3{ number_of_group_records } => {
+                let n = number_of_group_records.to_be_bytes();
+                (0, [0, 0, n[0], n[1]])
+            },
+            Unknown{ code_u8, bytes5to8, .. } => (*code_u8, *bytes5to8),
+        };
+        bytes.extend([self.type_u8(), code, 0, 0]);
+        bytes.extend(rest);
+        if let MembershipQuery(IgmpMembershipQuery{ v3: Some(v3), .. }) = self {
+            let n = v3.number_of_sources.to_be_bytes();
+            bytes.extend([
+                (if v3.suppress_router_processing { 0b1000 } else { 0 }) | (v3.querier_robustness_variable & 0b111),
+                v3.querier_query_interval_code,
+                n[0],
+                n[1],
+            ]);
+        }
+        bytes
+    }
+
+    /// Calculates the checksum of the header & the payload (source list
+    /// or group records).
+    pub fn calc_checksum(&self, payload: &[u8]) -> u16 {
+        checksum::Sum16BitWords::new()
+            .add_slice(&self.to_bytes())
+            .add_slice(payload)
+            .ones_complement()
+            .to_be()
+    }
+
+    /// Writes the header with a calculated checksum followed by the payload
+    /// (the source addresses of IGMPv3 queries or the group records of
+    /// IGMPv3 reports).
+    ///
+    /// Note that the number of sources or group records contained in the
+    /// header are written as is & not verified against the payload.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T, payload: &[u8]) -> Result<(), WriteError> {
+        let mut bytes = self.to_bytes();
+        let checksum = self.calc_checksum(payload).to_be_bytes();
+        bytes[2] = checksum[0];
+        bytes[3] = checksum[1];
+        writer.write_all(&bytes)?;
+        writer.write_all(payload)?;
+        Ok(())
+    }
+}
+
+/// A slice containing an IGMP message.
+///
+/// The slice contains the fixed part of the message and the source
+/// addresses of IGMPv3 queries or the group records of IGMPv3 reports.
+/// As IGMP has no length field the lengths of the source lists & group
+/// records are checked when the slice is created.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IgmpSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IgmpSlice<'a> {
+
+    /// Creates a slice containing an IGMP message.
+    ///
+    /// Membership queries longer then 8 bytes are decoded as IGMPv3
+    /// queries (RFC 3376 section 7.1). The version is determined by the
+    /// ip payload length, so the slice should not contain any data
+    /// following the ip payload (e.g. ethernet padding).
+    pub fn from_slice(slice: &'a [u8]) -> Result<IgmpSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+        use igmp::*;
+
+        if slice.len() < IgmpHeader::MIN_LEN {
+            return Err(UnexpectedEndOfSlice(IgmpHeader::MIN_LEN));
+        }
+        let len = match slice[0] {
+            TYPE_MEMBERSHIP_QUERY if slice.len() > IgmpHeader::MIN_LEN => {
+                if slice.len() < IgmpHeader::V3_QUERY_LEN {
+                    return Err(UnexpectedEndOfSlice(IgmpHeader::V3_QUERY_LEN));
+                }
+                IgmpHeader::V3_QUERY_LEN + usize::from(u16::from_be_bytes([slice[10], slice[11]]))*4
+            },
+            TYPE_V3_MEMBERSHIP_REPORT => {
+                let mut len = IgmpHeader::MIN_LEN;
+                for _ in 0..u16::from_be_bytes([slice[6], slice[7]]) {
+                    if slice.len() < len + IgmpGroupRecordSlice::MIN_LEN {
+                        return Err(UnexpectedEndOfSlice(len + IgmpGroupRecordSlice::MIN_LEN));
+                    }
+                    len += IgmpGroupRecordSlice::record_len(&slice[len..]);
+                }
+                len
+            },
+            _ => IgmpHeader::MIN_LEN,
+        };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(IgmpSlice{ slice: &slice[..len] })
+    }
+
+    /// Returns the slice containing the IGMP message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the IGMP type value (first byte).
+    #[inline]
+    pub fn type_u8(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Returns the second byte of the message (max resp code for queries).
+    #[inline]
+    pub fn code_u8(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Returns the checksum of the message.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Returns true if the checksum of the message is valid.
+    pub fn is_checksum_valid(&self) -> bool {
+        0 == checksum::Sum16BitWords::new()
+            .add_slice(self.slice)
+            .ones_complement()
+    }
+
+    /// Decodes the fixed part of the message.
+    pub fn header(&self) -> IgmpHeader {
+        use igmp::*;
+        use IgmpHeader::*;
+        let s = self.slice;
+        let bytes5to8 = [s[4], s[5], s[6], s[7]];
+        match s[0] {
+            TYPE_MEMBERSHIP_QUERY => MembershipQuery(IgmpMembershipQuery{
+                max_resp_code: s[1],
+                group_address: bytes5to8,
+                v3: if s.len() >= IgmpHeader::V3_QUERY_LEN {
+                    Some(IgmpV3QueryFields{
+                        suppress_router_processing: 0 != s[8] & 0b1000,
+                        querier_robustness_variable: s[8] & 0b111,
+                        querier_query_interval_code: s[9],
+                        number_of_sources: u16::from_be_bytes([s[10], s[11]]),
+                    })
+                } else {
+                    None
+                },
+            }),
+            TYPE_V1_MEMBERSHIP_REPORT => MembershipReportV1{ group_address: bytes5to8 },
+            TYPE_V2_MEMBERSHIP_REPORT => MembershipReportV2{ group_address: bytes5to8 },
+            TYPE_LEAVE_GROUP => LeaveGroup{ group_address: bytes5to8 },
+            TYPE_V3_MEMBERSHIP_REPORT => MembershipReportV3{
+                number_of_group_records: u16::from_be_bytes([s[6], s[7]]),
+            },
+            type_u8 => Unknown{ type_u8, code_u8: s[1], bytes5to8 },
+        }
+    }
+
+    /// Iterates over the source addresses of an IGMPv3 membership query
+    /// (empty for all other messages).
+    pub fn query_sources(&self) -> impl Iterator<Item = [u8;4]> + 'a {
+        let sources = if igmp::TYPE_MEMBERSHIP_QUERY == self.type_u8() && self.slice.len() >= IgmpHeader::V3_QUERY_LEN {
+            &self.slice[IgmpHeader::V3_QUERY_LEN..]
+        } else {
+            &[]
+        };
+        addresses(sources)
+    }
+
+    /// Iterates over the group records of an IGMPv3 membership report
+    /// (empty for all other messages).
+    pub fn group_records(&self) -> IgmpGroupRecordIterator<'a> {
+        IgmpGroupRecordIterator{
+            rest: if igmp::TYPE_V3_MEMBERSHIP_REPORT == self.type_u8() {
+                &self.slice[IgmpHeader::MIN_LEN..]
+            } else {
+                &[]
+            }
+        }
+    }
+}
+
+/// Iterates over a list of ipv4 addresses.
+fn addresses(slice: &[u8]) -> impl Iterator<Item = [u8;4]> + '_ {
+    slice.chunks_exact(4).map(|a| [a[0], a[1], a[2], a[3]])
+}
+
+/// A slice containing an IGMPv3 group record (RFC 3376 section 4.2.4).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct IgmpGroupRecordSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> IgmpGroupRecordSlice<'a> {
+    /// Length of the fixed part of a group record.
+    pub const MIN_LEN: usize = 8;
+
+    /// Length of the record starting at the beginning of the slice (the
+    /// slice must at least contain the fixed part).
+    fn record_len(slice: &[u8]) -> usize {
+        IgmpGroupRecordSlice::MIN_LEN
+            + usize::from(u16::from_be_bytes([slice[2], slice[3]]))*4
+            + usize::from(slice[1])*4
+    }
+
+    /// Returns the slice containing the group record.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Type of the record (see the `RECORD_*` constants in [`igmp`]).
+    #[inline]
+    pub fn record_type(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Length of the auxiliary data in 32 bit words.
+    #[inline]
+    pub fn aux_data_len(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Number of source addresses in the record.
+    #[inline]
+    pub fn number_of_sources(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Multicast address the record belongs to.
+    #[inline]
+    pub fn multicast_address(&self) -> [u8;4] {
+        [self.slice[4], self.slice[5], self.slice[6], self.slice[7]]
+    }
+
+    /// Iterates over the source addresses of the record.
+    pub fn sources(&self) -> impl Iterator<Item = [u8;4]> + 'a {
+        let end = IgmpGroupRecordSlice::MIN_LEN + usize::from(self.number_of_sources())*4;
+        addresses(&self.slice[IgmpGroupRecordSlice::MIN_LEN..end])
+    }
+
+    /// Auxiliary data of the record (empty for records defined in RFC 3376).
+    #[inline]
+    pub fn aux_data(&self) -> &'a [u8] {
+        &self.slice[IgmpGroupRecordSlice::MIN_LEN + usize::from(self.number_of_sources())*4..]
+    }
+}
+
+/// Iterator over the group records of an IGMPv3 membership report.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IgmpGroupRecordIterator<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> Iterator for IgmpGroupRecordIterator<'a> {
+    type Item = IgmpGroupRecordSlice<'a>;
+
+    fn next(&mut self) -> Option<IgmpGroupRecordSlice<'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        // the length of the records were validated when the
+        // IgmpSlice was created
+        let len = IgmpGroupRecordSlice::record_len(self.rest);
+        let result = IgmpGroupRecordSlice{ slice: &self.rest[..len] };
+        self.rest = &self.rest[len..];
+        Some(result)
+    }
+}
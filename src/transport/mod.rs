@@ -1,20 +1,37 @@
+pub mod dccp;
 pub mod icmp;
 pub mod icmpv4_impl;
 pub mod icmpv6_impl;
+pub mod icmpv6_ndp;
+pub mod igmp_impl;
+pub mod ping_payload;
+pub mod sctp;
 pub mod udp;
+pub mod udp_lite;
+pub mod vrrp_impl;
 pub mod tcp;
+pub mod tcp_analysis;
+pub mod tcp_syn_cookie;
 
 use super::*;
 
 use std::io;
 
 ///The possible headers on the transport layer
-#[derive(Clone, Debug, Eq, PartialEq)]
+///
+///IGMP messages have no owned representation & are only available as
+///[`TransportSlice::Igmp`] when slicing a packet with [`SlicedPacket`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(clippy::large_enum_variant)]
 pub enum TransportHeader {
     Udp(udp::UdpHeader),
     Tcp(tcp::TcpHeader),
     Icmpv4(Icmpv4Header),
     Icmpv6(Icmpv6Header),
+    UdpLite(udp_lite::UdpLiteHeader),
+    Dccp(dccp::DccpHeader),
+    Sctp(sctp::SctpHeader),
 }
 
 impl TransportHeader {
@@ -107,6 +124,72 @@ impl TransportHeader {
         }
     }
 
+    /// Returns Result::Some containing the UDP-Lite header if self has the value UdpLite.
+    /// Otherwise None is returned.
+    pub fn udp_lite(self) -> Option<udp_lite::UdpLiteHeader> {
+        use crate::TransportHeader::*;
+        if let UdpLite(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing a mutable refernce to the UDP-Lite header if self has the value UdpLite.
+    /// Otherwise None is returned.
+    pub fn mut_udp_lite(&mut self) -> Option<&mut udp_lite::UdpLiteHeader> {
+        use crate::TransportHeader::*;
+        if let UdpLite(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the DCCP header if self has the value Dccp.
+    /// Otherwise None is returned.
+    pub fn dccp(self) -> Option<dccp::DccpHeader> {
+        use crate::TransportHeader::*;
+        if let Dccp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing a mutable refernce to the DCCP header if self has the value Dccp.
+    /// Otherwise None is returned.
+    pub fn mut_dccp(&mut self) -> Option<&mut dccp::DccpHeader> {
+        use crate::TransportHeader::*;
+        if let Dccp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing the SCTP common header if self has the value Sctp.
+    /// Otherwise None is returned.
+    pub fn sctp(self) -> Option<sctp::SctpHeader> {
+        use crate::TransportHeader::*;
+        if let Sctp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns Result::Some containing a mutable refernce to the SCTP common header if self has the value Sctp.
+    /// Otherwise None is returned.
+    pub fn mut_sctp(&mut self) -> Option<&mut sctp::SctpHeader> {
+        use crate::TransportHeader::*;
+        if let Sctp(value) = self {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
     /// Returns the size of the transport header (in case of UDP fixed,
     /// in case of TCP cotanining the options).
     pub fn header_len(&self) -> usize {
@@ -116,6 +199,9 @@ impl TransportHeader {
             Tcp(value) => usize::from(value.header_len()),
             Icmpv4(value) => value.header_len(),
             Icmpv6(value) => value.header_len(),
+            UdpLite(_) => udp_lite::UdpLiteHeader::SIZE,
+            Dccp(value) => value.header_len(),
+            Sctp(value) => value.header_len(),
         }
     }
 
@@ -134,6 +220,15 @@ impl TransportHeader {
                 header.update_checksum(payload);
             },
             Icmpv6(_) => return Err(ValueError::Icmpv6InIpv4),
+            UdpLite(header) => {
+                header.checksum = header.calc_checksum_ipv4(ip_header, payload)?;
+            },
+            Dccp(header) => {
+                header.checksum = header.calc_checksum_ipv4(ip_header, payload)?;
+            },
+            Sctp(header) => {
+                header.checksum = header.calc_checksum(payload);
+            },
         }
         Ok(())
     }
@@ -150,7 +245,16 @@ impl TransportHeader {
             },
             Tcp(header) => {
                 header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
-            }
+            },
+            UdpLite(header) => {
+                header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
+            },
+            Dccp(header) => {
+                header.checksum = header.calc_checksum_ipv6(ip_header, payload)?;
+            },
+            Sctp(header) => {
+                header.checksum = header.calc_checksum(payload);
+            },
         }
         Ok(())
     }
@@ -162,7 +266,10 @@ impl TransportHeader {
             Icmpv4(value) => value.write(writer),
             Icmpv6(value) => value.write(writer),
             Udp(value) => value.write(writer),
-            Tcp(value) => value.write(writer).map_err(WriteError::from)
+            Tcp(value) => value.write(writer).map_err(WriteError::from),
+            UdpLite(value) => value.write(writer),
+            Dccp(value) => value.write(writer),
+            Sctp(value) => value.write(writer),
         }
     }
 }
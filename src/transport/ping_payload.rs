@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+/// Byte pattern used to fill the payload of ping (ICMP echo) requests.
+///
+/// The pattern is defined relative to the start of the echo payload (the
+/// bytes directly following the 8 byte ICMP echo header).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum PingPattern {
+    /// Byte at offset `i` has the value `i % 256` (used by the Linux & BSD
+    /// `ping` utilities).
+    Incrementing,
+    /// Repeated lower case alphabet "abcdefghijklmnopqrstuvw" (used by the
+    /// Windows `ping` utility).
+    Alphabet,
+    /// All bytes have the given value.
+    Byte(u8),
+}
+
+impl PingPattern {
+    /// Alphabet repeated by [`PingPattern::Alphabet`].
+    pub const ALPHABET: &'static [u8;23] = b"abcdefghijklmnopqrstuvw";
+
+    /// Returns the value of the pattern at the given payload offset.
+    #[inline]
+    pub fn byte_at(&self, offset: usize) -> u8 {
+        use PingPattern::*;
+        match self {
+            Incrementing => offset as u8,
+            Alphabet => PingPattern::ALPHABET[offset % PingPattern::ALPHABET.len()],
+            Byte(value) => *value,
+        }
+    }
+
+    /// Fills the given payload with the pattern.
+    pub fn fill(&self, payload: &mut [u8]) {
+        for (offset, value) in payload.iter_mut().enumerate() {
+            *value = self.byte_at(offset);
+        }
+    }
+
+    /// Returns true if all bytes of the payload match the pattern.
+    pub fn matches(&self, payload: &[u8]) -> bool {
+        payload.iter().enumerate().all(|(offset, value)| *value == self.byte_at(offset))
+    }
+}
+
+/// Ping (ICMP echo) payload with an embedded send timestamp, in the format
+/// used by the Linux `ping` utility.
+///
+/// The payload starts with the send time as `struct timeval` (64 bit seconds
+/// followed by 64 bit microseconds since the unix epoch, little endian)
+/// and the rest is filled with a [`PingPattern`]. As the timestamp is echoed
+/// back by the target, the round trip time can be calculated from an echo reply
+/// without keeping state per request.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{PingPayload, PingPattern};
+/// use std::time::Duration;
+///
+/// let send_time = Duration::from_micros(1_600_000_000_000_000);
+/// let payload = PingPayload::new(send_time).to_vec(PingPayload::DEFAULT_LEN);
+/// assert_eq!(56, payload.len());
+///
+/// // payload as echoed back in the reply
+/// assert!(PingPayload::new(send_time).matches(&payload));
+/// assert_eq!(Some(send_time), PingPayload::read_timestamp(&payload));
+/// assert_eq!(
+///     Some(Duration::from_millis(12)),
+///     PingPayload::round_trip_time(&payload, send_time + Duration::from_millis(12))
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct PingPayload {
+    /// Send time (duration since the unix epoch, stored with microsecond precision).
+    pub timestamp: Duration,
+    /// Pattern filling the payload after the timestamp.
+    pub pattern: PingPattern,
+}
+
+impl PingPayload {
+    /// Length of the timestamp at the start of the payload in bytes.
+    pub const TIMESTAMP_LEN: usize = 16;
+
+    /// Default payload length of the Linux `ping` utility in bytes.
+    pub const DEFAULT_LEN: usize = 56;
+
+    /// Payload with the given send time & the [`PingPattern::Incrementing`] pattern.
+    pub fn new(timestamp: Duration) -> PingPayload {
+        PingPayload {
+            timestamp,
+            pattern: PingPattern::Incrementing,
+        }
+    }
+
+    /// Writes the payload to the given buffer (the timestamp is only written
+    /// if the buffer has a length of at least [`PingPayload::TIMESTAMP_LEN`],
+    /// otherwise the buffer only contains the pattern).
+    pub fn write_to_slice(&self, buffer: &mut [u8]) {
+        self.pattern.fill(buffer);
+        if buffer.len() >= PingPayload::TIMESTAMP_LEN {
+            buffer[..8].copy_from_slice(&self.timestamp.as_secs().to_le_bytes());
+            buffer[8..16].copy_from_slice(&u64::from(self.timestamp.subsec_micros()).to_le_bytes());
+        }
+    }
+
+    /// Returns the payload with the given length.
+    pub fn to_vec(&self, len: usize) -> Vec<u8> {
+        let mut result = vec![0u8;len];
+        self.write_to_slice(&mut result);
+        result
+    }
+
+    /// Returns true if the payload contains the timestamp & the pattern (e.g.
+    /// to check that an echo reply was not corrupted). Payloads shorter than
+    /// [`PingPayload::TIMESTAMP_LEN`] only have to contain the pattern.
+    pub fn matches(&self, payload: &[u8]) -> bool {
+        let timestamp_len = if payload.len() >= PingPayload::TIMESTAMP_LEN {
+            match PingPayload::read_timestamp(payload) {
+                Some(value) if value.as_micros() == self.timestamp.as_micros() => PingPayload::TIMESTAMP_LEN,
+                _ => return false,
+            }
+        } else {
+            0
+        };
+        payload.iter()
+            .enumerate()
+            .skip(timestamp_len)
+            .all(|(offset, value)| *value == self.pattern.byte_at(offset))
+    }
+
+    /// Reads the timestamp from the start of a payload (`None` if the payload
+    /// is too short or the microseconds value is out of range).
+    pub fn read_timestamp(payload: &[u8]) -> Option<Duration> {
+        if payload.len() < PingPayload::TIMESTAMP_LEN {
+            return None;
+        }
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0u8;8];
+            bytes.copy_from_slice(&payload[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let micros = read_u64(8);
+        if micros >= 1_000_000 {
+            return None;
+        }
+        Some(Duration::from_secs(read_u64(0)) + Duration::from_micros(micros))
+    }
+
+    /// Calculates the round trip time from the timestamp echoed in the payload
+    /// of an echo reply & the time the reply was received (`None` if the payload
+    /// contains no valid timestamp or the timestamp lies after the receive time).
+    pub fn round_trip_time(payload: &[u8], receive_time: Duration) -> Option<Duration> {
+        receive_time.checked_sub(PingPayload::read_timestamp(payload)?)
+    }
+}
@@ -0,0 +1,570 @@
+use super::super::*;
+
+use std::error::Error;
+use std::fmt;
+use std::slice::from_raw_parts;
+
+/// `u8` constants of the SCTP chunk types (RFC 9260 & extensions).
+pub mod sctp_chunk_type {
+    /// Payload data (RFC 9260).
+    pub const DATA: u8 = 0;
+    /// Initiation of an association (RFC 9260).
+    pub const INIT: u8 = 1;
+    /// Initiation acknowledgement (RFC 9260).
+    pub const INIT_ACK: u8 = 2;
+    /// Selective acknowledgement (RFC 9260).
+    pub const SACK: u8 = 3;
+    /// Heartbeat request (RFC 9260).
+    pub const HEARTBEAT: u8 = 4;
+    /// Heartbeat acknowledgement (RFC 9260).
+    pub const HEARTBEAT_ACK: u8 = 5;
+    /// Abort of an association (RFC 9260).
+    pub const ABORT: u8 = 6;
+    /// Shutdown of an association (RFC 9260).
+    pub const SHUTDOWN: u8 = 7;
+    /// Shutdown acknowledgement (RFC 9260).
+    pub const SHUTDOWN_ACK: u8 = 8;
+    /// Operation error (RFC 9260).
+    pub const ERROR: u8 = 9;
+    /// State cookie (RFC 9260).
+    pub const COOKIE_ECHO: u8 = 10;
+    /// Cookie acknowledgement (RFC 9260).
+    pub const COOKIE_ACK: u8 = 11;
+    /// Explicit congestion notification echo (RFC 9260).
+    pub const ECNE: u8 = 12;
+    /// Congestion window reduced (RFC 9260).
+    pub const CWR: u8 = 13;
+    /// Shutdown complete (RFC 9260).
+    pub const SHUTDOWN_COMPLETE: u8 = 14;
+    /// Authentication chunk (RFC 4895).
+    pub const AUTH: u8 = 15;
+    /// Payload data supporting interleaving (RFC 8260).
+    pub const I_DATA: u8 = 64;
+    /// Address configuration change acknowledgement (RFC 5061).
+    pub const ASCONF_ACK: u8 = 128;
+    /// Stream re-configuration (RFC 6525).
+    pub const RE_CONFIG: u8 = 130;
+    /// Padding chunk (RFC 4820).
+    pub const PAD: u8 = 132;
+    /// Forward cumulative TSN (RFC 3758).
+    pub const FORWARD_TSN: u8 = 192;
+    /// Address configuration change (RFC 5061).
+    pub const ASCONF: u8 = 193;
+    /// Forward cumulative TSN supporting interleaving (RFC 8260).
+    pub const I_FORWARD_TSN: u8 = 194;
+}
+
+/// SCTP common header according to RFC 9260.
+///
+/// The header is followed by one or more chunks, which can be iterated
+/// with a [`SctpChunkIterator`].
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SctpHeader {
+    /// Source port of the packet.
+    pub source_port: u16,
+    /// Destination port of the packet.
+    pub destination_port: u16,
+    /// Tag identifying the association (set by the receiver during the
+    /// association setup).
+    pub verification_tag: u32,
+    /// CRC32c checksum over the header & chunks (read in network byte order,
+    /// see [`SctpHeader::calc_checksum`]).
+    pub checksum: u32,
+}
+
+impl SctpHeader {
+
+    /// Reads a sctp header from a slice directly and returns a tuple containing the resulting header & unused part of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(SctpHeader, &[u8]), ReadError> {
+        Ok((
+            SctpHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[SctpHeader::SERIALIZED_SIZE..]
+        ))
+    }
+
+    /// Read a SctpHeader from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;12]) -> SctpHeader {
+        SctpHeader{
+            source_port: u16::from_be_bytes([bytes[0], bytes[1]]),
+            destination_port: u16::from_be_bytes([bytes[2], bytes[3]]),
+            verification_tag: u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            checksum: u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
+        }
+    }
+
+    /// Tries to read a sctp header from the current position.
+    pub fn read<T: io::Read + Sized>(reader: &mut T) -> Result<SctpHeader, io::Error> {
+        let mut bytes : [u8;12] = [0;12];
+        reader.read_exact(&mut bytes)?;
+        Ok(SctpHeader::from_bytes(bytes))
+    }
+
+    /// Write the sctp header without recalculating the checksum.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant SctpHeader::SERIALIZED_SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        SctpHeader::SERIALIZED_SIZE
+    }
+
+    /// Calculates the CRC32c checksum of the header and the chunks following it.
+    ///
+    /// The CRC32c is transmitted least significant byte first, the returned
+    /// value is the value of the `checksum` field (which is read in network
+    /// byte order like all other fields).
+    pub fn calc_checksum(&self, chunks: &[u8]) -> u32 {
+        let mut header = self.to_bytes();
+        for value in &mut header[8..] {
+            *value = 0;
+        }
+        let crc = crc32c_update(crc32c_update(0xffff_ffff, &header), chunks);
+        u32::from_be_bytes((!crc).to_le_bytes())
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;12] {
+        let source_port_be = self.source_port.to_be_bytes();
+        let destination_port_be = self.destination_port.to_be_bytes();
+        let verification_tag_be = self.verification_tag.to_be_bytes();
+        let checksum_be = self.checksum.to_be_bytes();
+        [
+            source_port_be[0],
+            source_port_be[1],
+            destination_port_be[0],
+            destination_port_be[1],
+            verification_tag_be[0],
+            verification_tag_be[1],
+            verification_tag_be[2],
+            verification_tag_be[3],
+            checksum_be[0],
+            checksum_be[1],
+            checksum_be[2],
+            checksum_be[3],
+        ]
+    }
+}
+
+impl SerializedSize for SctpHeader {
+    ///Size of the header itself
+    const SERIALIZED_SIZE: usize = 12;
+}
+
+/// Continues a CRC32c (Castagnoli, reflected polynomial 0x82f63b78) calculation
+/// (without the final inversion).
+fn crc32c_update(mut crc: u32, data: &[u8]) -> u32 {
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if 0 != crc & 1 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+///A slice containing a sctp common header of a network package. Struct allows the selective read of fields in the header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SctpHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> SctpHeaderSlice<'a> {
+
+    /// Creates a slice containing a sctp common header.
+    #[inline]
+    pub fn from_slice(slice: &'a[u8]) -> Result<SctpHeaderSlice<'a>, ReadError> {
+        //check length
+        use crate::ReadError::*;
+        if slice.len() < SctpHeader::SERIALIZED_SIZE {
+            return Err(UnexpectedEndOfSlice(SctpHeader::SERIALIZED_SIZE));
+        }
+
+        //done
+        Ok(SctpHeaderSlice{
+            // SAFETY:
+            // Safe as slice length is checked to be at least
+            // SctpHeader::SERIALIZED_SIZE (12) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    SctpHeader::SERIALIZED_SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the sctp header
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Reads the "source port" from the slice.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr())
+        }
+    }
+
+    /// Reads the "destination port" from the slice.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(2))
+        }
+    }
+
+    /// Reads the "verification tag" from the slice.
+    #[inline]
+    pub fn verification_tag(&self) -> u32 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u32(self.slice.as_ptr().add(4))
+        }
+    }
+
+    /// Reads the "checksum" from the slice (in network byte order).
+    #[inline]
+    pub fn checksum(&self) -> u32 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of SctpHeader::SERIALIZED_SIZE (12).
+        unsafe {
+            get_unchecked_be_u32(self.slice.as_ptr().add(8))
+        }
+    }
+
+    /// Returns true if the checksum matches the CRC32c of the header &
+    /// the given chunks.
+    #[inline]
+    pub fn is_checksum_valid(&self, chunks: &[u8]) -> bool {
+        self.checksum() == self.to_header().calc_checksum(chunks)
+    }
+
+    /// Decode all the fields and copy the results to a SctpHeader struct
+    #[inline]
+    pub fn to_header(&self) -> SctpHeader {
+        SctpHeader {
+            source_port: self.source_port(),
+            destination_port: self.destination_port(),
+            verification_tag: self.verification_tag(),
+            checksum: self.checksum(),
+        }
+    }
+}
+
+/// A slice containing a SCTP chunk (without the padding).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SctpChunkSlice<'a> {
+    slice: &'a [u8],
+}
+
+impl<'a> SctpChunkSlice<'a> {
+    /// Length of the chunk header (type, flags & length).
+    pub const HEADER_LEN: usize = 4;
+
+    /// Returns the slice containing the chunk (without the padding).
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Type of the chunk (see [`sctp_chunk_type`]).
+    #[inline]
+    pub fn chunk_type(&self) -> u8 {
+        self.slice[0]
+    }
+
+    /// Chunk type specific flags.
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Length of the chunk including the chunk header but without the padding.
+    #[inline]
+    pub fn length(&self) -> u16 {
+        u16::from_be_bytes([self.slice[2], self.slice[3]])
+    }
+
+    /// Value of the chunk (everything after the chunk header without the padding).
+    #[inline]
+    pub fn value(&self) -> &'a [u8] {
+        &self.slice[SctpChunkSlice::HEADER_LEN..]
+    }
+
+    /// Decodes the chunk as DATA chunk (`None` if the chunk is not a DATA
+    /// chunk or too short).
+    pub fn data(&self) -> Option<SctpDataChunk<'a>> {
+        let v = self.value();
+        if sctp_chunk_type::DATA != self.chunk_type() || v.len() < 12 {
+            return None;
+        }
+        let flags = self.flags();
+        Some(SctpDataChunk {
+            immediate: 0 != flags & 0b1000,
+            unordered: 0 != flags & 0b100,
+            beginning: 0 != flags & 0b10,
+            ending: 0 != flags & 0b1,
+            tsn: u32::from_be_bytes([v[0], v[1], v[2], v[3]]),
+            stream_id: u16::from_be_bytes([v[4], v[5]]),
+            stream_sequence_number: u16::from_be_bytes([v[6], v[7]]),
+            payload_protocol_id: u32::from_be_bytes([v[8], v[9], v[10], v[11]]),
+            user_data: &v[12..],
+        })
+    }
+
+    /// Decodes the chunk as INIT or INIT ACK chunk (`None` if the chunk is
+    /// neither or too short).
+    pub fn init(&self) -> Option<SctpInitChunk<'a>> {
+        use sctp_chunk_type::*;
+        let v = self.value();
+        if !matches!(self.chunk_type(), INIT | INIT_ACK) || v.len() < 16 {
+            return None;
+        }
+        Some(SctpInitChunk {
+            initiate_tag: u32::from_be_bytes([v[0], v[1], v[2], v[3]]),
+            a_rwnd: u32::from_be_bytes([v[4], v[5], v[6], v[7]]),
+            outbound_streams: u16::from_be_bytes([v[8], v[9]]),
+            inbound_streams: u16::from_be_bytes([v[10], v[11]]),
+            initial_tsn: u32::from_be_bytes([v[12], v[13], v[14], v[15]]),
+            parameters: &v[16..],
+        })
+    }
+
+    /// Decodes the chunk as SACK chunk (`None` if the chunk is not a SACK
+    /// chunk or too short for the number of gap blocks & duplicate TSNs).
+    pub fn sack(&self) -> Option<SctpSackChunk<'a>> {
+        let v = self.value();
+        if sctp_chunk_type::SACK != self.chunk_type() || v.len() < 12 {
+            return None;
+        }
+        let gap_blocks_len = usize::from(u16::from_be_bytes([v[8], v[9]]))*4;
+        let duplicate_tsns_len = usize::from(u16::from_be_bytes([v[10], v[11]]))*4;
+        if v.len() < 12 + gap_blocks_len + duplicate_tsns_len {
+            return None;
+        }
+        Some(SctpSackChunk {
+            cumulative_tsn_ack: u32::from_be_bytes([v[0], v[1], v[2], v[3]]),
+            a_rwnd: u32::from_be_bytes([v[4], v[5], v[6], v[7]]),
+            gap_ack_blocks: &v[12..12 + gap_blocks_len],
+            duplicate_tsns: &v[12 + gap_blocks_len..12 + gap_blocks_len + duplicate_tsns_len],
+        })
+    }
+}
+
+/// Decoded DATA chunk (RFC 9260, section 3.3.1).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SctpDataChunk<'a> {
+    /// "I" flag, the receiver should send the SACK without delay (RFC 7053).
+    pub immediate: bool,
+    /// "U" flag, the data is unordered (no stream sequence number).
+    pub unordered: bool,
+    /// "B" flag, first fragment of a user message.
+    pub beginning: bool,
+    /// "E" flag, last fragment of a user message.
+    pub ending: bool,
+    /// Transmission sequence number.
+    pub tsn: u32,
+    /// Identifier of the stream the data belongs to.
+    pub stream_id: u16,
+    /// Sequence number of the user message in the stream.
+    pub stream_sequence_number: u16,
+    /// Application specific protocol identifier (e.g. 46 for Diameter).
+    pub payload_protocol_id: u32,
+    /// User data contained in the chunk.
+    pub user_data: &'a [u8],
+}
+
+/// Decoded INIT or INIT ACK chunk (RFC 9260, section 3.3.2 & 3.3.3).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SctpInitChunk<'a> {
+    /// Verification tag the receiver of the chunk has to use.
+    pub initiate_tag: u32,
+    /// Advertised receiver window credit in bytes.
+    pub a_rwnd: u32,
+    /// Number of outbound streams the sender wishes to create.
+    pub outbound_streams: u16,
+    /// Maximum number of inbound streams the sender allows.
+    pub inbound_streams: u16,
+    /// Initial transmission sequence number of the sender.
+    pub initial_tsn: u32,
+    /// Optional & variable length parameters (encoded).
+    pub parameters: &'a [u8],
+}
+
+/// Decoded SACK chunk (RFC 9260, section 3.3.4).
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SctpSackChunk<'a> {
+    /// Last TSN received in sequence.
+    pub cumulative_tsn_ack: u32,
+    /// Advertised receiver window credit in bytes.
+    pub a_rwnd: u32,
+    /// Encoded gap ack blocks (4 bytes each).
+    pub gap_ack_blocks: &'a [u8],
+    /// Encoded duplicate TSNs (4 bytes each).
+    pub duplicate_tsns: &'a [u8],
+}
+
+impl<'a> SctpSackChunk<'a> {
+    /// Iterates over the gap ack blocks as (start, end) offsets relative
+    /// to the cumulative TSN ack.
+    pub fn gap_ack_blocks(&self) -> impl Iterator<Item = (u16, u16)> + 'a {
+        self.gap_ack_blocks.chunks_exact(4).map(|block| (
+            u16::from_be_bytes([block[0], block[1]]),
+            u16::from_be_bytes([block[2], block[3]]),
+        ))
+    }
+
+    /// Iterates over the duplicate TSNs.
+    pub fn duplicate_tsns(&self) -> impl Iterator<Item = u32> + 'a {
+        self.duplicate_tsns.chunks_exact(4).map(|tsn|
+            u32::from_be_bytes([tsn[0], tsn[1], tsn[2], tsn[3]])
+        )
+    }
+}
+
+/// Errors that can occur when reading SCTP chunks.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SctpChunkReadError {
+    /// Returned if a chunk is longer then the rest of the chunks slice.
+    UnexpectedEndOfSlice{ chunk_type: u8, expected_len: usize, actual_len: usize },
+    /// Returned if the length field of a chunk is smaller then the
+    /// chunk header.
+    LengthTooSmall{ chunk_type: u8, length: u16 },
+}
+
+impl Error for SctpChunkReadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+impl fmt::Display for SctpChunkReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use SctpChunkReadError::*;
+        match self {
+            UnexpectedEndOfSlice{ chunk_type, expected_len, actual_len } => {
+                write!(f, "SctpChunkReadError: Not enough data left in slice to read chunk of type {} (expected at least {} bytes, only {} bytes available).", chunk_type, expected_len, actual_len)
+            },
+            LengthTooSmall{ chunk_type, length } => {
+                write!(f, "SctpChunkReadError: Length value {} of the chunk of type {} is smaller then the chunk header (4 bytes).", length, chunk_type)
+            },
+        }
+    }
+}
+
+/// Allows iterating over the chunks following a SCTP common header.
+///
+/// The iterator stops after the first error.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// // a HEARTBEAT chunk containing the heartbeat info parameter
+/// let chunks = [4, 0, 0, 12, 0, 1, 0, 8, 1, 2, 3, 4];
+/// let mut iter = SctpChunkIterator::from_slice(&chunks);
+/// let chunk = iter.next().unwrap().unwrap();
+/// assert_eq!(sctp_chunk_type::HEARTBEAT, chunk.chunk_type());
+/// assert_eq!(&[0, 1, 0, 8, 1, 2, 3, 4], chunk.value());
+/// assert!(iter.next().is_none());
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct SctpChunkIterator<'a> {
+    chunks: &'a [u8],
+}
+
+impl<'a> SctpChunkIterator<'a> {
+    /// Creates a chunk iterator from a slice containing encoded SCTP chunks.
+    pub fn from_slice(chunks: &'a [u8]) -> SctpChunkIterator<'a> {
+        SctpChunkIterator{ chunks }
+    }
+
+    /// Returns the non processed part of the chunks slice.
+    pub fn rest(&self) -> &'a [u8] {
+        self.chunks
+    }
+}
+
+impl<'a> Iterator for SctpChunkIterator<'a> {
+    type Item = Result<SctpChunkSlice<'a>, SctpChunkReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use SctpChunkReadError::*;
+
+        if self.chunks.is_empty() {
+            return None;
+        }
+
+        let chunk_type = self.chunks[0];
+        let result = if self.chunks.len() < SctpChunkSlice::HEADER_LEN {
+            Err(UnexpectedEndOfSlice{
+                chunk_type,
+                expected_len: SctpChunkSlice::HEADER_LEN,
+                actual_len: self.chunks.len()
+            })
+        } else {
+            let length = u16::from_be_bytes([self.chunks[2], self.chunks[3]]);
+            if usize::from(length) < SctpChunkSlice::HEADER_LEN {
+                Err(LengthTooSmall{ chunk_type, length })
+            } else if self.chunks.len() < usize::from(length) {
+                Err(UnexpectedEndOfSlice{
+                    chunk_type,
+                    expected_len: usize::from(length),
+                    actual_len: self.chunks.len()
+                })
+            } else {
+                Ok(SctpChunkSlice{ slice: &self.chunks[..usize::from(length)] })
+            }
+        };
+
+        match &result {
+            Ok(chunk) => {
+                // skip the padding (tolerate a missing padding after the last chunk)
+                let padded_len = chunk.slice.len().div_ceil(4)*4;
+                self.chunks = &self.chunks[padded_len.min(self.chunks.len())..];
+            },
+            Err(_) => {
+                // stop the iteration after an error
+                self.chunks = &[];
+            }
+        }
+        Some(result)
+    }
+}
+
+impl<'a> fmt::Debug for SctpChunkIterator<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut list = fmt.debug_list();
+        for element in self.clone() {
+            list.entry(&element);
+        }
+        list.finish()
+    }
+}
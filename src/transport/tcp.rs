@@ -1,5 +1,6 @@
 use super::super::*;
 
+use arrayvec::ArrayVec;
 use std::fmt::{Debug, Formatter};
 use std::slice::from_raw_parts;
 
@@ -75,6 +76,12 @@ pub struct TcpHeader {
 
 impl TcpHeader {
 
+    /// Minimum length of a TCP header in bytes (header without options).
+    pub const MIN_SERIALIZED_SIZE: usize = TCP_MINIMUM_HEADER_SIZE;
+
+    /// Maximum length of a TCP header in bytes (header with 40 bytes of options).
+    pub const MAX_SERIALIZED_SIZE: usize = (TCP_MAXIMUM_DATA_OFFSET as usize)*4;
+
     ///Creates a TcpHeader with the given values and the rest initialized with default values.
     pub fn new(source_port: u16, destination_port: u16, sequence_number: u32, window_size: u16) -> TcpHeader {
         TcpHeader {
@@ -350,6 +357,16 @@ impl TcpHeader {
         })
     }
 
+    /// Returns the serialized header (including the options) in a stack
+    /// allocated buffer (does NOT calculate the checksum).
+    pub fn to_bytes(&self) -> ArrayVec<u8, { TcpHeader::MAX_SERIALIZED_SIZE }> {
+        let mut result = ArrayVec::new();
+        // the buffer has the capacity of the maximum header length,
+        // so writing the header into it can not fail
+        self.write(&mut result).unwrap();
+        result
+    }
+
     /// Write the tcp header to a stream (does NOT calculate the checksum).
     pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), std::io::Error> {
 
@@ -575,6 +592,31 @@ impl Debug for TcpHeader {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for TcpHeader {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "TcpHeader {{ source_port: {}, destination_port: {}, sequence_number: {}, acknowledgment_number: {}, data_offset: {}, ns: {}, fin: {}, syn: {}, rst: {}, psh: {}, ack: {}, urg: {}, ece: {}, cwr: {}, window_size: {}, checksum: {}, urgent_pointer: {}, options: {=[u8]} }}",
+            self.source_port,
+            self.destination_port,
+            self.sequence_number,
+            self.acknowledgment_number,
+            self._data_offset,
+            self.ns,
+            self.fin,
+            self.syn,
+            self.rst,
+            self.psh,
+            self.ack,
+            self.urg,
+            self.ece,
+            self.cwr,
+            self.window_size,
+            self.checksum,
+            self.urgent_pointer,
+            self.options())
+    }
+}
+
 impl std::cmp::PartialEq for TcpHeader {
     fn eq(&self, other: &TcpHeader) -> bool {
         self.source_port == other.source_port &&
@@ -600,8 +642,31 @@ impl std::cmp::PartialEq for TcpHeader {
 
 impl std::cmp::Eq for TcpHeader {}
 
+impl std::hash::Hash for TcpHeader {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.source_port.hash(state);
+        self.destination_port.hash(state);
+        self.sequence_number.hash(state);
+        self.acknowledgment_number.hash(state);
+        self._data_offset.hash(state);
+        self.ns.hash(state);
+        self.fin.hash(state);
+        self.syn.hash(state);
+        self.rst.hash(state);
+        self.psh.hash(state);
+        self.ack.hash(state);
+        self.urg.hash(state);
+        self.ece.hash(state);
+        self.cwr.hash(state);
+        self.window_size.hash(state);
+        self.checksum.hash(state);
+        self.urgent_pointer.hash(state);
+        self.options().hash(state);
+    }
+}
+
 ///A slice containing an tcp header of a network package.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct TcpHeaderSlice<'a> {
     slice: &'a [u8]
 }
@@ -980,7 +1045,8 @@ impl<'a> TcpHeaderSlice<'a> {
 }
 
 /// Different kinds of options that can be present in the options part of a tcp header.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TcpOptionElement {
     /// "No-Operation" option.
     ///
@@ -0,0 +1,433 @@
+use super::super::*;
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Direction of a TCP segment relative to a [`TcpFlowState`].
+///
+/// Which peer is considered "forward" is up to the caller (usually the
+/// sender of the first observed segment of the connection).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum TcpSegmentDirection {
+    /// Segment was sent by the first peer.
+    Forward,
+    /// Segment was sent by the second peer.
+    Reverse,
+}
+
+impl TcpSegmentDirection {
+    /// Returns the opposite direction.
+    #[inline]
+    pub fn reverse(self) -> TcpSegmentDirection {
+        use TcpSegmentDirection::*;
+        match self {
+            Forward => Reverse,
+            Reverse => Forward,
+        }
+    }
+}
+
+/// Result of the classification of a single TCP segment by [`TcpFlowState::analyze`].
+///
+/// The heuristics used are the same as the ones used by common
+/// packet analyzers. Multiple flags can be set at the same time
+/// (e.g. a zero window segment can also be a duplicate ack).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TcpSegmentClassification {
+    /// Segment carries at most one byte of data & the sequence number is one below
+    /// the next expected sequence number (keep-alive probe).
+    pub keep_alive: bool,
+    /// Segment advertises a window size of zero.
+    pub zero_window: bool,
+    /// Segment carries exactly one byte at the next expected sequence number
+    /// while the receiver advertised a zero window.
+    pub zero_window_probe: bool,
+    /// Segment fills the last window advertised by the receiver completely.
+    pub window_full: bool,
+    /// Segment is a pure ack repeating the previous acknowledgment number & window.
+    pub duplicate_ack: bool,
+    /// Segment carries data (or SYN/FIN) that was already sent before.
+    pub retransmission: bool,
+    /// Retransmission triggered after the receiver sent at least two
+    /// duplicate acks for the retransmitted sequence number.
+    pub fast_retransmission: bool,
+}
+
+/// State tracked for one peer of a TCP connection.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TcpPeerState {
+    /// Next sequence number expected from this peer (sequence number of the
+    /// highest sent data + 1).
+    pub next_sequence_number: Option<u32>,
+    /// Last acknowledgment number sent by this peer.
+    pub last_acknowledgment_number: Option<u32>,
+    /// Last (unscaled) window size sent by this peer.
+    pub last_window_size: Option<u16>,
+    /// Window scale shift announced by the peer in its SYN segment.
+    pub window_scale: Option<u8>,
+    /// Number of duplicate acks the peer sent for `last_acknowledgment_number`.
+    pub duplicate_ack_count: u32,
+}
+
+/// Bidirectional state of a TCP connection used to classify segments
+/// (keep-alives, zero window probes, retransmissions ...).
+///
+/// The state has to be kept by the caller per connection (e.g. in a flow table)
+/// and every observed segment of the connection has to be passed in the order
+/// it was captured.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{TcpFlowState, TcpHeader, TcpSegmentDirection::*};
+///
+/// let mut flow = TcpFlowState::new();
+///
+/// let mut data = TcpHeader::new(1234, 80, 1000, 512);
+/// data.ack = true;
+/// flow.analyze_header(Forward, &data, 100);
+///
+/// // the same data sent again is a retransmission
+/// let result = flow.analyze_header(Forward, &data, 100);
+/// assert!(result.retransmission);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TcpFlowState {
+    /// State of the peer sending the [`TcpSegmentDirection::Forward`] segments.
+    pub forward: TcpPeerState,
+    /// State of the peer sending the [`TcpSegmentDirection::Reverse`] segments.
+    pub reverse: TcpPeerState,
+}
+
+/// Fields of a tcp segment relevant for the analysis.
+struct SegmentValues {
+    sequence_number: u32,
+    acknowledgment_number: u32,
+    ack: bool,
+    syn: bool,
+    fin: bool,
+    rst: bool,
+    window_size: u16,
+    window_scale: Option<u8>,
+    payload_len: usize,
+}
+
+/// Returns true if `a` is before `b` in sequence number space (RFC 1982 serial arithmetic).
+#[inline]
+fn seq_lt(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) < 0
+}
+
+/// Returns the window scale shift value if present in the given options.
+fn window_scale_option(options: TcpOptionsIterator) -> Option<u8> {
+    for option in options {
+        match option {
+            Ok(TcpOptionElement::WindowScale(shift)) => return Some(shift),
+            Ok(_) => {},
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+impl TcpFlowState {
+    /// Creates a new flow state without any knowledge about the connection.
+    pub fn new() -> TcpFlowState {
+        Default::default()
+    }
+
+    /// Returns the state of the peer sending in the given direction.
+    #[inline]
+    pub fn peer(&self, direction: TcpSegmentDirection) -> &TcpPeerState {
+        match direction {
+            TcpSegmentDirection::Forward => &self.forward,
+            TcpSegmentDirection::Reverse => &self.reverse,
+        }
+    }
+
+    /// Classifies a segment & updates the flow state.
+    ///
+    /// `payload_len` is the length of the tcp payload of the segment (it can
+    /// be smaller then the slice passed around in case of truncated captures, so it
+    /// has to be determined by the caller e.g. based on the ip length fields).
+    pub fn analyze(&mut self, direction: TcpSegmentDirection, header: &TcpHeaderSlice, payload_len: usize) -> TcpSegmentClassification {
+        self.analyze_values(direction, SegmentValues {
+            sequence_number: header.sequence_number(),
+            acknowledgment_number: header.acknowledgment_number(),
+            ack: header.ack(),
+            syn: header.syn(),
+            fin: header.fin(),
+            rst: header.rst(),
+            window_size: header.window_size(),
+            window_scale: if header.syn() {
+                window_scale_option(header.options_iterator())
+            } else {
+                None
+            },
+            payload_len,
+        })
+    }
+
+    /// Classifies a segment given as a decoded header & updates the flow state.
+    ///
+    /// See [`TcpFlowState::analyze`] for details.
+    pub fn analyze_header(&mut self, direction: TcpSegmentDirection, header: &TcpHeader, payload_len: usize) -> TcpSegmentClassification {
+        self.analyze_values(direction, SegmentValues {
+            sequence_number: header.sequence_number,
+            acknowledgment_number: header.acknowledgment_number,
+            ack: header.ack,
+            syn: header.syn,
+            fin: header.fin,
+            rst: header.rst,
+            window_size: header.window_size,
+            window_scale: if header.syn {
+                window_scale_option(header.options_iterator())
+            } else {
+                None
+            },
+            payload_len,
+        })
+    }
+
+    /// Returns the scaled window of the given peer (scaling is only applied if
+    /// both peers announced the window scale option).
+    fn scaled_window(&self, direction: TcpSegmentDirection) -> Option<u64> {
+        let peer = self.peer(direction);
+        let shift = match (self.forward.window_scale, self.reverse.window_scale) {
+            (Some(_), Some(_)) => peer.window_scale.unwrap_or(0).min(14),
+            _ => 0,
+        };
+        peer.last_window_size.map(|w| u64::from(w) << shift)
+    }
+
+    fn analyze_values(&mut self, direction: TcpSegmentDirection, seg: SegmentValues) -> TcpSegmentClassification {
+        let mut result = TcpSegmentClassification::default();
+
+        let control = seg.syn || seg.fin || seg.rst;
+        // length of the segment in sequence number space
+        let seg_len = seg.payload_len as u64 + u64::from(seg.syn) + u64::from(seg.fin);
+        let seq_end = seg.sequence_number.wrapping_add(seg_len as u32);
+        let reverse_window = self.scaled_window(direction.reverse());
+
+        {
+            let (fwd, rev) = match direction {
+                TcpSegmentDirection::Forward => (&self.forward, &self.reverse),
+                TcpSegmentDirection::Reverse => (&self.reverse, &self.forward),
+            };
+
+            result.zero_window = 0 == seg.window_size && false == control;
+
+            result.zero_window_probe = 1 == seg_len &&
+                Some(seg.sequence_number) == fwd.next_sequence_number &&
+                Some(0) == rev.last_window_size;
+
+            result.keep_alive = seg_len <= 1 &&
+                false == control &&
+                Some(seg.sequence_number.wrapping_add(1)) == fwd.next_sequence_number;
+
+            result.window_full = seg_len > 0 &&
+                false == control &&
+                match (rev.last_acknowledgment_number, reverse_window) {
+                    (Some(ack), Some(window)) => {
+                        u64::from(seq_end.wrapping_sub(ack)) == window
+                    },
+                    _ => false
+                };
+
+            result.duplicate_ack = 0 == seg_len &&
+                seg.ack &&
+                false == control &&
+                Some(seg.acknowledgment_number) == fwd.last_acknowledgment_number &&
+                Some(seg.window_size) == fwd.last_window_size;
+
+            result.retransmission = seg_len > 0 &&
+                false == result.keep_alive &&
+                false == result.zero_window_probe &&
+                match fwd.next_sequence_number {
+                    Some(next) => seq_lt(seg.sequence_number, next),
+                    None => false
+                };
+
+            result.fast_retransmission = result.retransmission &&
+                rev.duplicate_ack_count >= 2 &&
+                Some(seg.sequence_number) == rev.last_acknowledgment_number;
+        }
+
+        // update the state of the sender
+        let fwd = match direction {
+            TcpSegmentDirection::Forward => &mut self.forward,
+            TcpSegmentDirection::Reverse => &mut self.reverse,
+        };
+        if seg.syn {
+            fwd.window_scale = seg.window_scale;
+        }
+        match fwd.next_sequence_number {
+            Some(next) if false == seq_lt(next, seq_end) => {},
+            _ => fwd.next_sequence_number = Some(seq_end),
+        }
+        if result.duplicate_ack {
+            fwd.duplicate_ack_count += 1;
+        } else if seg.ack && Some(seg.acknowledgment_number) != fwd.last_acknowledgment_number {
+            fwd.duplicate_ack_count = 0;
+        }
+        if seg.ack {
+            fwd.last_acknowledgment_number = Some(seg.acknowledgment_number);
+        }
+        fwd.last_window_size = Some(seg.window_size);
+
+        result
+    }
+}
+
+/// Round trip time sample determined by a [`TcpRttEstimator`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct TcpRttSample {
+    /// Direction of the segment whose timestamp got echoed.
+    ///
+    /// The sample is the time from the capture point to the receiver of
+    /// the segments in this direction & back.
+    pub direction: TcpSegmentDirection,
+    /// Time between the capture of the segment carrying the timestamp value
+    /// & the capture of the first segment echoing it.
+    pub rtt: Duration,
+}
+
+/// Passive round trip time estimation based on the TCP timestamp option (RFC 7323).
+///
+/// For every direction the capture time of the first segment carrying a new
+/// "TSval" is recorded. When a segment in the opposite direction echoes the
+/// value in its "TSecr" field a [`TcpRttSample`] is returned. Every recorded
+/// value produces at most one sample.
+///
+/// The estimator has to be kept by the caller per connection (e.g. next to a
+/// [`TcpFlowState`]) and every observed segment of the connection has to be
+/// passed in the order it was captured.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{TcpHeader, TcpOptionElement, TcpRttEstimator, TcpSegmentDirection::*};
+/// use std::time::Duration;
+///
+/// let mut estimator = TcpRttEstimator::new(64);
+///
+/// let mut data = TcpHeader::new(1234, 80, 1000, 512);
+/// data.ack = true;
+/// data.set_options(&[TcpOptionElement::Timestamp(100, 7)]).unwrap();
+/// assert_eq!(None, estimator.observe_header(Forward, Duration::from_millis(10), &data));
+///
+/// let mut ack = TcpHeader::new(80, 1234, 5000, 512);
+/// ack.ack = true;
+/// ack.set_options(&[TcpOptionElement::Timestamp(8, 100)]).unwrap();
+/// let sample = estimator.observe_header(Reverse, Duration::from_millis(35), &ack).unwrap();
+/// assert_eq!(Forward, sample.direction);
+/// assert_eq!(Duration::from_millis(25), sample.rtt);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TcpRttEstimator {
+    /// Maximum number of outstanding timestamp values per direction.
+    capacity: usize,
+    /// Outstanding timestamp values (& capture times) sent in forward direction.
+    forward: VecDeque<(u32, Duration)>,
+    /// Outstanding timestamp values (& capture times) sent in reverse direction.
+    reverse: VecDeque<(u32, Duration)>,
+}
+
+/// Returns the timestamp values (TSval & TSecr) if present in the given options.
+fn timestamp_option(options: TcpOptionsIterator) -> Option<(u32, u32)> {
+    for option in options {
+        match option {
+            Ok(TcpOptionElement::Timestamp(value, echo)) => return Some((value, echo)),
+            Ok(_) => {},
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+impl TcpRttEstimator {
+    /// Creates an estimator remembering up to `capacity` outstanding
+    /// timestamp values per direction (the oldest values are dropped first).
+    pub fn new(capacity: usize) -> TcpRttEstimator {
+        TcpRttEstimator {
+            capacity,
+            forward: VecDeque::new(),
+            reverse: VecDeque::new(),
+        }
+    }
+
+    /// Maximum number of outstanding timestamp values per direction.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of timestamp values sent in the given direction that were
+    /// not yet echoed.
+    #[inline]
+    pub fn outstanding(&self, direction: TcpSegmentDirection) -> usize {
+        match direction {
+            TcpSegmentDirection::Forward => self.forward.len(),
+            TcpSegmentDirection::Reverse => self.reverse.len(),
+        }
+    }
+
+    /// Processes a segment captured at `timestamp` & returns a sample if the
+    /// segment echoes a timestamp value of the opposite direction.
+    ///
+    /// The capture timestamps are expected to be non decreasing.
+    pub fn observe(&mut self, direction: TcpSegmentDirection, timestamp: Duration, header: &TcpHeaderSlice) -> Option<TcpRttSample> {
+        let values = timestamp_option(header.options_iterator())?;
+        self.observe_values(direction, timestamp, header.ack(), values)
+    }
+
+    /// Processes a segment given as a decoded header.
+    ///
+    /// See [`TcpRttEstimator::observe`] for details.
+    pub fn observe_header(&mut self, direction: TcpSegmentDirection, timestamp: Duration, header: &TcpHeader) -> Option<TcpRttSample> {
+        let values = timestamp_option(header.options_iterator())?;
+        self.observe_values(direction, timestamp, header.ack, values)
+    }
+
+    fn observe_values(&mut self, direction: TcpSegmentDirection, timestamp: Duration, ack: bool, (value, echo): (u32, u32)) -> Option<TcpRttSample> {
+        let (sent, received) = match direction {
+            TcpSegmentDirection::Forward => (&mut self.forward, &mut self.reverse),
+            TcpSegmentDirection::Reverse => (&mut self.reverse, &mut self.forward),
+        };
+
+        // record new timestamp values (retransmissions & segments sent within
+        // the same clock tick repeat the value & keep the first capture time)
+        let is_new = match sent.back() {
+            Some((last, _)) => seq_lt(*last, value),
+            None => true,
+        };
+        if is_new && self.capacity > 0 {
+            if sent.len() >= self.capacity {
+                sent.pop_front();
+            }
+            sent.push_back((value, timestamp));
+        }
+
+        // the echo is only valid if the ack flag is set (RFC 7323, section 3.2)
+        if false == ack {
+            return None;
+        }
+
+        // drop all values up to & including the echoed one (older values will
+        // not be echoed anymore)
+        let mut result = None;
+        while let Some((front, sent_at)) = received.front().copied() {
+            if seq_lt(echo, front) {
+                break;
+            }
+            received.pop_front();
+            if front == echo {
+                result = timestamp.checked_sub(sent_at).map(|rtt| TcpRttSample {
+                    direction: direction.reverse(),
+                    rtt,
+                });
+            }
+        }
+        result
+    }
+}
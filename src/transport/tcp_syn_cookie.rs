@@ -0,0 +1,265 @@
+use super::super::*;
+
+use arrayvec::ArrayVec;
+use std::net::IpAddr;
+
+/// TCP options of a SYN that can be encoded in the timestamp of a SYN-ACK
+/// (see [`SynCookies::encode_timestamp`]).
+///
+/// Without timestamps only the MSS survives the round trip through the
+/// sequence number of the cookie.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Default)]
+pub struct SynCookieOptions {
+    /// Window scale shift of the SYN (`None` if no window scale option was present).
+    pub window_scale: Option<u8>,
+    /// True if the SYN contained the "SACK permitted" option.
+    pub sack_permitted: bool,
+    /// True if the SYN requested ECN (ECE & CWR flags set).
+    pub ecn: bool,
+}
+
+impl SynCookieOptions {
+    /// Maximum window scale shift (RFC 7323, bigger values are treated as 14).
+    pub const MAX_WINDOW_SCALE: u8 = 14;
+
+    /// Number of lower timestamp bits used to encode the options.
+    pub const TIMESTAMP_BITS: u32 = 6;
+
+    /// Collects the options from a SYN header.
+    pub fn from_syn(syn: &TcpHeader) -> SynCookieOptions {
+        let mut result = SynCookieOptions {
+            window_scale: None,
+            sack_permitted: false,
+            ecn: syn.ece && syn.cwr,
+        };
+        for option in syn.options_iterator() {
+            match option {
+                Ok(TcpOptionElement::WindowScale(shift)) => {
+                    result.window_scale = Some(shift.min(SynCookieOptions::MAX_WINDOW_SCALE));
+                },
+                Ok(TcpOptionElement::SelectiveAcknowledgementPermitted) => {
+                    result.sack_permitted = true;
+                },
+                Ok(_) => {},
+                Err(_) => break,
+            }
+        }
+        result
+    }
+
+    /// Returns the options encoded in the lower bits of a timestamp.
+    fn to_bits(self) -> u32 {
+        let window_scale = match self.window_scale {
+            Some(shift) => u32::from(shift.min(SynCookieOptions::MAX_WINDOW_SCALE)),
+            None => 0xf,
+        };
+        let sack = if self.sack_permitted { 0x10 } else { 0 };
+        let ecn = if self.ecn { 0x20 } else { 0 };
+        window_scale | sack | ecn
+    }
+}
+
+/// Sequence number & maximum segment size of a SYN cookie generated by [`SynCookies::generate`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct SynCookie {
+    /// Sequence number that has to be sent in the SYN-ACK.
+    pub sequence_number: u32,
+    /// MSS encoded in the cookie (the MSS of the SYN rounded down to a value of
+    /// the MSS table). This is the MSS restored by [`SynCookies::validate`] and
+    /// should also be sent in the SYN-ACK.
+    pub mss: u16,
+}
+
+/// Stateless SYN cookie generation & validation (same encoding as used by the
+/// Linux kernel).
+///
+/// The cookie sent as sequence number in the SYN-ACK consists of
+///
+/// * a keyed hash over the addresses & ports of the connection plus the
+///   sequence number of the SYN,
+/// * an 8 bit counter value (e.g. the current time in minutes) in the upper
+///   8 bits, which limits the time in which a cookie is accepted,
+/// * and the index of the MSS in the MSS table, protected by a second keyed
+///   hash (which also covers the counter value), in the lower 24 bits.
+///
+/// SipHash-2-4 is used as keyed hash. The counter has to be provided by the
+/// caller & cookies are accepted if they were generated with the current or
+/// up to [`SynCookies::MAX_AGE`] - 1 previous counter values.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{SynCookies, TcpHeader, TcpOptionElement};
+/// use std::net::IpAddr;
+///
+/// let cookies = SynCookies::new([0x42;32]);
+/// let client = IpAddr::from([192,168,1,1]);
+/// let server = IpAddr::from([192,168,1,2]);
+///
+/// // SYN received by the server
+/// let mut syn = TcpHeader::new(1234, 80, 1000, 65535);
+/// syn.syn = true;
+/// syn.set_options(&[TcpOptionElement::MaximumSegmentSize(1460)]).unwrap();
+///
+/// let counter = 27_000_000; // e.g. minutes since the unix epoch
+/// let cookie = cookies.generate(client, server, &syn, counter);
+/// assert_eq!(1460, cookie.mss);
+///
+/// // ACK of the client answering the SYN-ACK
+/// let mut ack = TcpHeader::new(1234, 80, 1001, 65535);
+/// ack.ack = true;
+/// ack.acknowledgment_number = cookie.sequence_number.wrapping_add(1);
+/// assert_eq!(Some(1460), cookies.validate(client, server, &ack, counter + 1));
+///
+/// // cookies expire
+/// assert_eq!(None, cookies.validate(client, server, &ack, counter + 2));
+/// ```
+#[derive(Clone, Eq, PartialEq)]
+pub struct SynCookies {
+    keys: [[u64;2];2],
+}
+
+impl SynCookies {
+    /// Number of counter values a cookie is valid.
+    pub const MAX_AGE: u32 = 2;
+
+    /// MSS values that can be encoded in cookies for ipv4 connections.
+    pub const IPV4_MSS_TABLE: [u16;4] = [536, 1300, 1440, 1460];
+
+    /// MSS values that can be encoded in cookies for ipv6 connections.
+    pub const IPV6_MSS_TABLE: [u16;4] = [1220, 1420, 1440, 8940];
+
+    /// Number of bits used for the counter value in the cookie.
+    const COUNTER_SHIFT: u32 = 24;
+
+    /// Mask of the bits containing the MSS index in the cookie.
+    const DATA_MASK: u32 = 0xff_ffff;
+
+    /// Creates the cookie helper with the given secret (should be chosen randomly).
+    pub fn new(secret: [u8;32]) -> SynCookies {
+        let key = |offset: usize| {
+            let mut bytes = [0u8;8];
+            bytes.copy_from_slice(&secret[offset..offset + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        SynCookies {
+            keys: [
+                [key(0), key(8)],
+                [key(16), key(24)],
+            ],
+        }
+    }
+
+    /// Returns the MSS table used for connections from the given address.
+    pub fn mss_table(source_addr: IpAddr) -> &'static [u16;4] {
+        match source_addr {
+            IpAddr::V4(_) => &SynCookies::IPV4_MSS_TABLE,
+            IpAddr::V6(_) => &SynCookies::IPV6_MSS_TABLE,
+        }
+    }
+
+    /// Generates the cookie for a received SYN.
+    ///
+    /// The addresses are the ones of the SYN (`source_addr` is the address of
+    /// the client). If the SYN contains no MSS option the smallest value of the
+    /// MSS table is used.
+    pub fn generate(&self, source_addr: IpAddr, destination_addr: IpAddr, syn: &TcpHeader, counter: u32) -> SynCookie {
+        let table = SynCookies::mss_table(source_addr);
+        let mss = syn.options_iterator()
+            .filter_map(|option| match option {
+                Ok(TcpOptionElement::MaximumSegmentSize(value)) => Some(value),
+                _ => None,
+            })
+            .next()
+            .unwrap_or(table[0]);
+        // largest table entry not above the requested mss
+        let index = table.iter().rposition(|value| *value <= mss).unwrap_or(0);
+
+        let hash0 = self.hash(0, source_addr, destination_addr, syn.source_port, syn.destination_port, 0);
+        let hash1 = self.hash(1, source_addr, destination_addr, syn.source_port, syn.destination_port, counter);
+        SynCookie {
+            sequence_number: hash0
+                .wrapping_add(syn.sequence_number)
+                .wrapping_add(counter << SynCookies::COUNTER_SHIFT)
+                .wrapping_add(hash1.wrapping_add(index as u32) & SynCookies::DATA_MASK),
+            mss: table[index],
+        }
+    }
+
+    /// Validates the cookie acknowledged by the ACK completing the handshake
+    /// and returns the MSS encoded in it (`None` if the cookie is invalid or
+    /// expired, or if the header is not a pure ACK).
+    ///
+    /// The addresses are the ones of the ACK (`source_addr` is the address of
+    /// the client, same as for [`SynCookies::generate`]).
+    pub fn validate(&self, source_addr: IpAddr, destination_addr: IpAddr, ack: &TcpHeader, counter: u32) -> Option<u16> {
+        if false == ack.ack || ack.syn || ack.rst {
+            return None;
+        }
+        let isn = ack.sequence_number.wrapping_sub(1);
+        let cookie = ack.acknowledgment_number.wrapping_sub(1);
+
+        let hash0 = self.hash(0, source_addr, destination_addr, ack.source_port, ack.destination_port, 0);
+        let value = cookie.wrapping_sub(hash0).wrapping_sub(isn);
+
+        let age = counter.wrapping_sub(value >> SynCookies::COUNTER_SHIFT) & (u32::MAX >> SynCookies::COUNTER_SHIFT);
+        if age >= SynCookies::MAX_AGE {
+            return None;
+        }
+        let hash1 = self.hash(1, source_addr, destination_addr, ack.source_port, ack.destination_port, counter.wrapping_sub(age));
+        let index = value.wrapping_sub(hash1) & SynCookies::DATA_MASK;
+        SynCookies::mss_table(source_addr).get(index as usize).copied()
+    }
+
+    /// Encodes the options of a SYN in the lower bits of the timestamp value
+    /// sent in the SYN-ACK (the options are echoed back by the client in the
+    /// "timestamp echo reply" field of the ACK).
+    ///
+    /// The resulting timestamp is never bigger then the given one.
+    pub fn encode_timestamp(timestamp: u32, options: SynCookieOptions) -> u32 {
+        let mask = (1 << SynCookieOptions::TIMESTAMP_BITS) - 1;
+        let result = (timestamp & !mask) | options.to_bits();
+        if result > timestamp {
+            result.wrapping_sub(mask + 1)
+        } else {
+            result
+        }
+    }
+
+    /// Decodes the options from the "timestamp echo reply" of the ACK
+    /// completing the handshake.
+    pub fn decode_timestamp(timestamp_echo: u32) -> SynCookieOptions {
+        let window_scale = (timestamp_echo & 0xf) as u8;
+        SynCookieOptions {
+            window_scale: if window_scale > SynCookieOptions::MAX_WINDOW_SCALE {
+                None
+            } else {
+                Some(window_scale)
+            },
+            sack_permitted: 0 != timestamp_echo & 0x10,
+            ecn: 0 != timestamp_echo & 0x20,
+        }
+    }
+
+    /// Keyed hash over the connection addresses, ports & counter.
+    fn hash(&self, key: usize, source_addr: IpAddr, destination_addr: IpAddr, source_port: u16, destination_port: u16, counter: u32) -> u32 {
+        let mut data = ArrayVec::<u8, {16 + 16 + 2 + 2 + 4}>::new();
+        for addr in [source_addr, destination_addr] {
+            match addr {
+                IpAddr::V4(value) => data.try_extend_from_slice(&value.octets()).unwrap(),
+                IpAddr::V6(value) => data.try_extend_from_slice(&value.octets()).unwrap(),
+            }
+        }
+        data.try_extend_from_slice(&source_port.to_be_bytes()).unwrap();
+        data.try_extend_from_slice(&destination_port.to_be_bytes()).unwrap();
+        data.try_extend_from_slice(&counter.to_be_bytes()).unwrap();
+        siphash::siphash24(self.keys[key], &data) as u32
+    }
+}
+
+impl fmt::Debug for SynCookies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // don't leak the secret via debug output
+        f.debug_struct("SynCookies").finish_non_exhaustive()
+    }
+}
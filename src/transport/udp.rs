@@ -3,7 +3,8 @@ use super::super::*;
 use std::slice::from_raw_parts;
 
 ///Udp header according to rfc768.
-#[derive(Clone, Debug, Eq, PartialEq, Default)]
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct UdpHeader {
     ///Source port of the packet (optional).
     pub source_port: u16,
@@ -242,7 +243,7 @@ impl SerializedSize for UdpHeader {
 }
 
 ///A slice containing an udp header of a network package. Struct allows the selective read of fields in the header.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct UdpHeaderSlice<'a> {
     slice: &'a [u8]
 }
@@ -0,0 +1,343 @@
+use super::super::*;
+
+use std::slice::from_raw_parts;
+
+/// UDP-Lite header according to rfc3828.
+///
+/// In contrast to UDP the length field is replaced by a "checksum coverage"
+/// field, which defines how many bytes of the datagram (starting with the
+/// header) are covered by the checksum. The length of the datagram is
+/// derived from the ip header instead.
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ipv4Header, UdpLiteHeader, ip_number};
+///
+/// let payload = [1,2,3,4,5,6,7,8];
+/// let ip_header = Ipv4Header::new(
+///     (UdpLiteHeader::SIZE + payload.len()) as u16,
+///     20,
+///     ip_number::UDP_LITE,
+///     [192,168,1,1],
+///     [192,168,1,2]
+/// );
+///
+/// // checksum covering only the header & the first 4 bytes of the payload
+/// let header = UdpLiteHeader::with_ipv4_checksum(
+///     1234, 5678, 8 + 4, &ip_header, &payload
+/// ).unwrap();
+///
+/// // changes in the uncovered part of the payload are not detected
+/// let mut modified = payload;
+/// modified[7] = 0;
+/// assert!(header.is_checksum_valid_ipv4(ip_header.source, ip_header.destination, &modified));
+/// modified[0] = 0;
+/// assert!(false == header.is_checksum_valid_ipv4(ip_header.source, ip_header.destination, &modified));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct UdpLiteHeader {
+    /// Source port of the packet (optional).
+    pub source_port: u16,
+    /// Destination port of the packet.
+    pub destination_port: u16,
+    /// Number of bytes (including the header) covered by the checksum.
+    /// A value of 0 indicates that the whole datagram is covered. Values
+    /// between 1 & 7 or larger then the datagram are invalid.
+    pub checksum_coverage: u16,
+    /// Checksum calculated over a pseudo header, the udp-lite header & the
+    /// covered part of the payload. Unlike in UDP the checksum is mandatory.
+    pub checksum: u16,
+}
+
+impl UdpLiteHeader {
+    /// Serialized size of an UDP-Lite header in bytes.
+    pub const SIZE: usize = 8;
+
+    /// Returns an udp-lite header with the given ports & checksum coverage
+    /// and a checksum of 0.
+    pub fn without_checksum(source_port: u16, destination_port: u16, checksum_coverage: u16) -> UdpLiteHeader {
+        UdpLiteHeader {
+            source_port,
+            destination_port,
+            checksum_coverage,
+            checksum: 0,
+        }
+    }
+
+    /// Returns an udp-lite header with the checksum calculated based on
+    /// the given ipv4 header & payload.
+    pub fn with_ipv4_checksum(source_port: u16, destination_port: u16, checksum_coverage: u16, ip_header: &Ipv4Header, payload: &[u8]) -> Result<UdpLiteHeader, ValueError> {
+        let mut result = UdpLiteHeader::without_checksum(source_port, destination_port, checksum_coverage);
+        result.checksum = result.calc_checksum_ipv4(ip_header, payload)?;
+        Ok(result)
+    }
+
+    /// Returns an udp-lite header with the checksum calculated based on
+    /// the given ipv6 header & payload.
+    pub fn with_ipv6_checksum(source_port: u16, destination_port: u16, checksum_coverage: u16, ip_header: &Ipv6Header, payload: &[u8]) -> Result<UdpLiteHeader, ValueError> {
+        let mut result = UdpLiteHeader::without_checksum(source_port, destination_port, checksum_coverage);
+        result.checksum = result.calc_checksum_ipv6(ip_header, payload)?;
+        Ok(result)
+    }
+
+    /// Returns the number of bytes (including the 8 bytes of the header)
+    /// covered by the checksum for a datagram with the given payload length.
+    ///
+    /// An error is returned if the checksum coverage is invalid for the
+    /// given payload length (between 1 & 7 or larger then the datagram).
+    pub fn coverage_len(&self, payload_len: usize) -> Result<usize, ValueError> {
+        let len = UdpLiteHeader::SIZE + payload_len;
+        let coverage = usize::from(self.checksum_coverage);
+        if 0 == coverage {
+            Ok(len)
+        } else if coverage < UdpLiteHeader::SIZE || coverage > len {
+            Err(ValueError::UdpLiteChecksumCoverageBad{
+                coverage: self.checksum_coverage,
+                len,
+            })
+        } else {
+            Ok(coverage)
+        }
+    }
+
+    /// Calculates the udp-lite header checksum based on a ipv4 header.
+    pub fn calc_checksum_ipv4(&self, ip_header: &Ipv4Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv4_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the udp-lite header checksum based on the ipv4 source &
+    /// destination address plus the payload.
+    pub fn calc_checksum_ipv4_raw(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
+        //check that the total length fits into the ipv4 total length field
+        const MAX_PAYLOAD_LENGTH: usize = (u16::MAX as usize) - UdpLiteHeader::SIZE;
+        if MAX_PAYLOAD_LENGTH < payload.len() {
+            return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
+        }
+        let coverage_len = self.coverage_len(payload.len())?;
+
+        Ok(self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_4bytes(source)
+            .add_4bytes(destination)
+            .add_2bytes([0, ip_number::UDP_LITE])
+            .add_2bytes(((UdpLiteHeader::SIZE + payload.len()) as u16).to_be_bytes()),
+            &payload[..coverage_len - UdpLiteHeader::SIZE]
+        ))
+    }
+
+    /// Calculates the udp-lite header checksum based on a ipv6 header.
+    pub fn calc_checksum_ipv6(&self, ip_header: &Ipv6Header, payload: &[u8]) -> Result<u16, ValueError> {
+        self.calc_checksum_ipv6_raw(ip_header.source, ip_header.destination, payload)
+    }
+
+    /// Calculates the udp-lite header checksum based on the ipv6 source &
+    /// destination address plus the payload.
+    pub fn calc_checksum_ipv6_raw(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
+        //check that the total length fits into the pseudo header length field
+        const MAX_PAYLOAD_LENGTH: usize = (u32::MAX as usize) - UdpLiteHeader::SIZE;
+        if MAX_PAYLOAD_LENGTH < payload.len() {
+            return Err(ValueError::UdpPayloadLengthTooLarge(payload.len()));
+        }
+        let coverage_len = self.coverage_len(payload.len())?;
+
+        Ok(self.calc_checksum_post_ip(
+            //pseudo header
+            checksum::Sum16BitWords::new()
+            .add_16bytes(source)
+            .add_16bytes(destination)
+            .add_4bytes(((UdpLiteHeader::SIZE + payload.len()) as u32).to_be_bytes())
+            .add_2bytes([0, ip_number::UDP_LITE]),
+            &payload[..coverage_len - UdpLiteHeader::SIZE]
+        ))
+    }
+
+    /// This method takes the sum of the pseudo ip header and calculates the rest of the checksum.
+    fn calc_checksum_post_ip(&self, ip_pseudo_header_sum: checksum::Sum16BitWords, covered_payload: &[u8]) -> u16 {
+        ip_pseudo_header_sum
+        .add_2bytes(self.source_port.to_be_bytes())
+        .add_2bytes(self.destination_port.to_be_bytes())
+        .add_2bytes(self.checksum_coverage.to_be_bytes())
+        .add_slice(covered_payload)
+        .to_ones_complement_with_no_zero()
+        .to_be()
+    }
+
+    /// Returns true if the checksum of the header matches the checksum
+    /// calculated for the given ipv4 addresses & payload.
+    ///
+    /// False is also returned if the checksum coverage is invalid.
+    pub fn is_checksum_valid_ipv4(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> bool {
+        self.calc_checksum_ipv4_raw(source, destination, payload) == Ok(self.checksum)
+    }
+
+    /// Returns true if the checksum of the header matches the checksum
+    /// calculated for the given ipv6 addresses & payload.
+    ///
+    /// False is also returned if the checksum coverage is invalid.
+    pub fn is_checksum_valid_ipv6(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> bool {
+        self.calc_checksum_ipv6_raw(source, destination, payload) == Ok(self.checksum)
+    }
+
+    /// Reads an udp-lite header from a slice directly and returns a tuple containing the resulting header & unused part of the slice.
+    #[inline]
+    pub fn from_slice(slice: &[u8]) -> Result<(UdpLiteHeader, &[u8]), ReadError> {
+        Ok((
+            UdpLiteHeaderSlice::from_slice(slice)?.to_header(),
+            &slice[UdpLiteHeader::SIZE..]
+        ))
+    }
+
+    /// Read an UdpLiteHeader from a static sized byte array.
+    #[inline]
+    pub fn from_bytes(bytes: [u8;8]) -> UdpLiteHeader {
+        UdpLiteHeader{
+            source_port: u16::from_be_bytes([bytes[0], bytes[1]]),
+            destination_port: u16::from_be_bytes([bytes[2], bytes[3]]),
+            checksum_coverage: u16::from_be_bytes([bytes[4], bytes[5]]),
+            checksum: u16::from_be_bytes([bytes[6], bytes[7]]),
+        }
+    }
+
+    /// Tries to read an udp-lite header from the current position.
+    pub fn read<T: io::Read + io::Seek + Sized>(reader: &mut T) -> Result<UdpLiteHeader, io::Error> {
+        let mut bytes : [u8;8] = [0;8];
+        reader.read_exact(&mut bytes)?;
+        Ok(UdpLiteHeader::from_bytes(bytes))
+    }
+
+    /// Write the udp-lite header without recalculating the checksum.
+    pub fn write<T: io::Write + Sized>(&self, writer: &mut T) -> Result<(), WriteError> {
+        writer.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+
+    /// Length of the serialized header in bytes.
+    ///
+    /// The function always returns the constant UdpLiteHeader::SIZE
+    /// and exists to keep the methods consistent with other headers.
+    #[inline]
+    pub fn header_len(&self) -> usize {
+        UdpLiteHeader::SIZE
+    }
+
+    /// Returns the serialized form of the header as a statically
+    /// sized byte array.
+    #[inline]
+    pub fn to_bytes(&self) -> [u8;8] {
+        let source_port_be = self.source_port.to_be_bytes();
+        let destination_port_be = self.destination_port.to_be_bytes();
+        let checksum_coverage_be = self.checksum_coverage.to_be_bytes();
+        let checksum_be = self.checksum.to_be_bytes();
+        [
+            source_port_be[0],
+            source_port_be[1],
+            destination_port_be[0],
+            destination_port_be[1],
+            checksum_coverage_be[0],
+            checksum_coverage_be[1],
+            checksum_be[0],
+            checksum_be[1],
+        ]
+    }
+}
+
+impl SerializedSize for UdpLiteHeader {
+    ///Size of the header itself
+    const SERIALIZED_SIZE: usize = UdpLiteHeader::SIZE;
+}
+
+///A slice containing an udp-lite header of a network package. Struct allows the selective read of fields in the header.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct UdpLiteHeaderSlice<'a> {
+    slice: &'a [u8]
+}
+
+impl<'a> UdpLiteHeaderSlice<'a> {
+
+    /// Creates a slice containing an udp-lite header.
+    #[inline]
+    pub fn from_slice(slice: &'a[u8]) -> Result<UdpLiteHeaderSlice<'a>, ReadError> {
+        //check length
+        use crate::ReadError::*;
+        if slice.len() < UdpLiteHeader::SIZE {
+            return Err(UnexpectedEndOfSlice(UdpLiteHeader::SIZE));
+        }
+
+        //done
+        Ok(UdpLiteHeaderSlice{
+            // SAFETY:
+            // Safe as slice length is checked to be at least
+            // UdpLiteHeader::SIZE (8) before this.
+            slice: unsafe {
+                from_raw_parts(
+                    slice.as_ptr(),
+                    UdpLiteHeader::SIZE
+                )
+            }
+        })
+    }
+
+    /// Returns the slice containing the udp-lite header
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Reads the "source port" from the slice.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr())
+        }
+    }
+
+    /// Reads the "destination port" from the slice.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(2))
+        }
+    }
+
+    /// Reads the "checksum coverage" from the slice.
+    #[inline]
+    pub fn checksum_coverage(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(4))
+        }
+    }
+
+    /// Reads the "checksum" from the slice.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        // SAFETY:
+        // Safe as the contructor checks that the slice has
+        // at least the length of UdpLiteHeader::SIZE (8).
+        unsafe {
+            get_unchecked_be_u16(self.slice.as_ptr().add(6))
+        }
+    }
+
+    /// Decode all the fields and copy the results to a UdpLiteHeader struct
+    #[inline]
+    pub fn to_header(&self) -> UdpLiteHeader {
+        UdpLiteHeader {
+            source_port: self.source_port(),
+            destination_port: self.destination_port(),
+            checksum_coverage: self.checksum_coverage(),
+            checksum: self.checksum()
+        }
+    }
+}
@@ -0,0 +1,403 @@
+use super::super::*;
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Module containing VRRP related constants.
+pub mod vrrp {
+
+    /// VRRP type value of an "Advertisement" (the only type defined in
+    /// [RFC 3768](https://tools.ietf.org/html/rfc3768) & [RFC 5798](https://tools.ietf.org/html/rfc5798)).
+    pub const TYPE_ADVERTISEMENT: u8 = 1;
+
+    /// VRRPv2 authentication type "No Authentication".
+    pub const AUTH_TYPE_NONE: u8 = 0;
+
+    /// VRRPv2 authentication type "Simple Text Password" (deprecated by RFC 3768).
+    pub const AUTH_TYPE_SIMPLE_TEXT_PASSWORD: u8 = 1;
+
+    /// VRRPv2 authentication type "IP Authentication Header" (deprecated by RFC 3768).
+    pub const AUTH_TYPE_IP_AUTHENTICATION_HEADER: u8 = 2;
+
+    /// Priority signaling that the current master stopped participating in VRRP.
+    pub const PRIORITY_STOP: u8 = 0;
+
+    /// Default priority of backup routers.
+    pub const PRIORITY_DEFAULT: u8 = 100;
+
+    /// Priority of the router owning the virtual ip addresses.
+    pub const PRIORITY_OWNER: u8 = 255;
+
+    /// Length of the authentication data at the end of VRRPv2 messages.
+    pub const V2_AUTHENTICATION_DATA_LEN: usize = 8;
+
+    /// Destination address of VRRP messages sent via IPv4 (224.0.0.18).
+    pub const IPV4_MULTICAST_ADDR: [u8;4] = [224, 0, 0, 18];
+
+    /// Destination address of VRRP messages sent via IPv6 (ff02::12).
+    pub const IPV6_MULTICAST_ADDR: [u8;16] = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x12];
+}
+
+/// Version specific fields of a VRRP message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VrrpVersion {
+    /// VRRP version 2 ([RFC 3768](https://tools.ietf.org/html/rfc3768), IPv4 only).
+    V2 {
+        /// Authentication type (see the `AUTH_TYPE_*` constants in [`vrrp`]).
+        auth_type: u8,
+        /// Interval between advertisements in seconds.
+        advertisement_interval: u8,
+    },
+    /// VRRP version 3 ([RFC 5798](https://tools.ietf.org/html/rfc5798), IPv4 & IPv6).
+    V3 {
+        /// Maximum interval between advertisements in centiseconds (12 bits).
+        max_advertisement_interval: u16,
+    },
+}
+
+impl VrrpVersion {
+    /// Returns the value of the version field.
+    pub fn version_u8(&self) -> u8 {
+        match self {
+            VrrpVersion::V2{ .. } => 2,
+            VrrpVersion::V3{ .. } => 3,
+        }
+    }
+}
+
+/// Fixed part of a VRRP message (without the ip addresses & the VRRPv2
+/// authentication data).
+///
+/// # Example
+///
+/// ```
+/// use etherparse::*;
+///
+/// // VRRPv3 advertisement for the virtual address 192.168.1.1
+/// let header = VrrpHeader{
+///     version: VrrpVersion::V3{ max_advertisement_interval: 100 },
+///     vrrp_type: vrrp::TYPE_ADVERTISEMENT,
+///     virtual_router_id: 1,
+///     priority: vrrp::PRIORITY_DEFAULT,
+///     count_ip_addrs: 1,
+/// };
+/// let source = [192, 168, 1, 2];
+/// let mut message = Vec::new();
+/// header.write_ipv4(&mut message, source, vrrp::IPV4_MULTICAST_ADDR, &[192, 168, 1, 1]).unwrap();
+///
+/// let mut packet = Vec::new();
+/// PacketBuilder::ipv4(source, vrrp::IPV4_MULTICAST_ADDR, 255)
+///     .write(&mut packet, ip_number::VRRP, &message)
+///     .unwrap();
+///
+/// // VRRP is not decoded by the slicer and ends up in the payload
+/// let sliced = SlicedPacket::from_ip(&packet).unwrap();
+/// assert_eq!(Some(TransportSlice::Unknown(ip_number::VRRP)), sliced.transport);
+///
+/// let vrrp = VrrpSlice::from_slice_ipv4(sliced.payload).unwrap();
+/// assert!(vrrp.is_checksum_valid_ipv4(source, vrrp::IPV4_MULTICAST_ADDR));
+/// assert_eq!(header, vrrp.header());
+/// assert_eq!(
+///     vec![std::net::IpAddr::from([192, 168, 1, 1])],
+///     vrrp.addresses().collect::<Vec<_>>()
+/// );
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct VrrpHeader {
+    /// Version & the version specific fields.
+    pub version: VrrpVersion,
+    /// Type of the message (4 bits, see [`vrrp::TYPE_ADVERTISEMENT`]).
+    pub vrrp_type: u8,
+    /// Virtual router identifier.
+    pub virtual_router_id: u8,
+    /// Priority of the sending router (see the `PRIORITY_*` constants in [`vrrp`]).
+    pub priority: u8,
+    /// Number of ip addresses following the fixed part of the message.
+    pub count_ip_addrs: u8,
+}
+
+impl VrrpHeader {
+    /// Length of the fixed part of a VRRP message.
+    pub const LEN: usize = 8;
+
+    /// Maximum value of the type field (4 bits).
+    pub const MAX_TYPE: u8 = 0xf;
+
+    /// Maximum value of the VRRPv3 max advertisement interval (12 bits).
+    pub const MAX_MAX_ADVERTISEMENT_INTERVAL: u16 = 0xfff;
+
+    /// Returns the fixed part of the message with a zero checksum.
+    fn to_bytes(self) -> Result<[u8;8], ValueError> {
+        max_check_u8(self.vrrp_type, VrrpHeader::MAX_TYPE, ErrorField::VrrpType)?;
+        let (byte4, byte5) = match self.version {
+            VrrpVersion::V2{ auth_type, advertisement_interval } => (auth_type, advertisement_interval),
+            VrrpVersion::V3{ max_advertisement_interval } => {
+                max_check_u16(
+                    max_advertisement_interval,
+                    VrrpHeader::MAX_MAX_ADVERTISEMENT_INTERVAL,
+                    ErrorField::VrrpMaxAdvertisementInterval
+                )?;
+                let bytes = max_advertisement_interval.to_be_bytes();
+                (bytes[0], bytes[1])
+            },
+        };
+        Ok([
+            (self.version.version_u8() << 4) | self.vrrp_type,
+            self.virtual_router_id,
+            self.priority,
+            self.count_ip_addrs,
+            byte4,
+            byte5,
+            0,
+            0,
+        ])
+    }
+
+    /// Calculates the checksum of a VRRP message sent via IPv4. The payload
+    /// contains the ip addresses (followed by the authentication data for
+    /// VRRPv2 messages).
+    ///
+    /// The ip pseudo header is only part of the checksum for VRRPv3 messages.
+    pub fn calc_checksum_ipv4(&self, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<u16, ValueError> {
+        let bytes = self.to_bytes()?;
+        let sum = match self.version {
+            VrrpVersion::V2{ .. } => checksum::Sum16BitWords::new(),
+            VrrpVersion::V3{ .. } => {
+                let len = VrrpHeader::LEN + payload.len();
+                if len > usize::from(u16::MAX) {
+                    return Err(ValueError::VrrpLengthTooLarge(len));
+                }
+                checksum::Sum16BitWords::new()
+                    .add_4bytes(source)
+                    .add_4bytes(destination)
+                    .add_2bytes([0, ip_number::VRRP])
+                    .add_2bytes((len as u16).to_be_bytes())
+            },
+        };
+        Ok(sum.add_slice(&bytes).add_slice(payload).ones_complement().to_be())
+    }
+
+    /// Calculates the checksum of a VRRPv3 message sent via IPv6 (including
+    /// the ip pseudo header). The payload contains the ip addresses.
+    pub fn calc_checksum_ipv6(&self, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<u16, ValueError> {
+        if let VrrpVersion::V2{ .. } = self.version {
+            return Err(ValueError::VrrpV2ViaIpv6);
+        }
+        let bytes = self.to_bytes()?;
+        let len = VrrpHeader::LEN + payload.len();
+        if len > u32::MAX as usize {
+            return Err(ValueError::VrrpLengthTooLarge(len));
+        }
+        Ok(
+            checksum::Sum16BitWords::new()
+                .add_16bytes(source)
+                .add_16bytes(destination)
+                .add_4bytes((len as u32).to_be_bytes())
+                .add_4bytes([0, 0, 0, ip_number::VRRP])
+                .add_slice(&bytes)
+                .add_slice(payload)
+                .ones_complement()
+                .to_be()
+        )
+    }
+
+    /// Writes a VRRP message sent via IPv4 with a calculated checksum. The
+    /// payload contains the ip addresses (followed by the authentication data
+    /// for VRRPv2 messages).
+    ///
+    /// Note that the number of ip addresses contained in the header is
+    /// written as is & not verified against the payload.
+    pub fn write_ipv4<T: io::Write + Sized>(&self, writer: &mut T, source: [u8;4], destination: [u8;4], payload: &[u8]) -> Result<(), WriteError> {
+        let checksum = self.calc_checksum_ipv4(source, destination, payload)?;
+        self.write_with_checksum(writer, checksum, payload)
+    }
+
+    /// Writes a VRRPv3 message sent via IPv6 with a calculated checksum. The
+    /// payload contains the ip addresses.
+    ///
+    /// Note that the number of ip addresses contained in the header is
+    /// written as is & not verified against the payload.
+    pub fn write_ipv6<T: io::Write + Sized>(&self, writer: &mut T, source: [u8;16], destination: [u8;16], payload: &[u8]) -> Result<(), WriteError> {
+        let checksum = self.calc_checksum_ipv6(source, destination, payload)?;
+        self.write_with_checksum(writer, checksum, payload)
+    }
+
+    fn write_with_checksum<T: io::Write + Sized>(&self, writer: &mut T, checksum: u16, payload: &[u8]) -> Result<(), WriteError> {
+        let mut bytes = self.to_bytes()?;
+        let checksum = checksum.to_be_bytes();
+        bytes[6] = checksum[0];
+        bytes[7] = checksum[1];
+        writer.write_all(&bytes)?;
+        writer.write_all(payload)?;
+        Ok(())
+    }
+}
+
+/// A slice containing a VRRP message (fixed part, ip addresses & the
+/// VRRPv2 authentication data).
+///
+/// VRRP has no length field & the length of the ip addresses depends on the
+/// ip version of the packet (VRRPv3). The message is therefore sliced via
+/// [`VrrpSlice::from_slice_ipv4`] or [`VrrpSlice::from_slice_ipv6`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct VrrpSlice<'a> {
+    slice: &'a [u8],
+    ipv6: bool,
+}
+
+impl<'a> VrrpSlice<'a> {
+
+    /// Creates a slice containing a VRRPv2 or VRRPv3 message sent via IPv4
+    /// (trailing bytes after the message are ignored).
+    pub fn from_slice_ipv4(slice: &'a [u8]) -> Result<VrrpSlice<'a>, ReadError> {
+        VrrpSlice::from_slice(slice, false)
+    }
+
+    /// Creates a slice containing a VRRPv3 message sent via IPv6 (trailing
+    /// bytes after the message are ignored).
+    pub fn from_slice_ipv6(slice: &'a [u8]) -> Result<VrrpSlice<'a>, ReadError> {
+        VrrpSlice::from_slice(slice, true)
+    }
+
+    fn from_slice(slice: &'a [u8], ipv6: bool) -> Result<VrrpSlice<'a>, ReadError> {
+        use crate::ReadError::*;
+
+        if slice.len() < VrrpHeader::LEN {
+            return Err(UnexpectedEndOfSlice(VrrpHeader::LEN));
+        }
+        let count = usize::from(slice[3]);
+        let len = match slice[0] >> 4 {
+            2 if false == ipv6 => VrrpHeader::LEN + count*4 + vrrp::V2_AUTHENTICATION_DATA_LEN,
+            3 if ipv6 => VrrpHeader::LEN + count*16,
+            3 => VrrpHeader::LEN + count*4,
+            version => return Err(VrrpUnsupportedVersion(version)),
+        };
+        if slice.len() < len {
+            return Err(UnexpectedEndOfSlice(len));
+        }
+        Ok(VrrpSlice{ slice: &slice[..len], ipv6 })
+    }
+
+    /// Returns the slice containing the VRRP message.
+    #[inline]
+    pub fn slice(&self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Returns the version of the message (2 or 3).
+    #[inline]
+    pub fn version_u8(&self) -> u8 {
+        self.slice[0] >> 4
+    }
+
+    /// Returns the type of the message (see [`vrrp::TYPE_ADVERTISEMENT`]).
+    #[inline]
+    pub fn vrrp_type(&self) -> u8 {
+        self.slice[0] & 0xf
+    }
+
+    /// Returns the virtual router identifier.
+    #[inline]
+    pub fn virtual_router_id(&self) -> u8 {
+        self.slice[1]
+    }
+
+    /// Returns the priority of the sending router.
+    #[inline]
+    pub fn priority(&self) -> u8 {
+        self.slice[2]
+    }
+
+    /// Returns the number of ip addresses in the message.
+    #[inline]
+    pub fn count_ip_addrs(&self) -> u8 {
+        self.slice[3]
+    }
+
+    /// Returns the checksum of the message.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        u16::from_be_bytes([self.slice[6], self.slice[7]])
+    }
+
+    /// Returns true if the checksum of a message sent via IPv4 is valid (the
+    /// ip addresses are only part of the VRRPv3 checksum).
+    pub fn is_checksum_valid_ipv4(&self, source: [u8;4], destination: [u8;4]) -> bool {
+        let sum = if 2 == self.version_u8() {
+            checksum::Sum16BitWords::new()
+        } else {
+            checksum::Sum16BitWords::new()
+                .add_4bytes(source)
+                .add_4bytes(destination)
+                .add_2bytes([0, ip_number::VRRP])
+                .add_2bytes((self.slice.len() as u16).to_be_bytes())
+        };
+        0 == sum.add_slice(self.slice).ones_complement()
+    }
+
+    /// Returns true if the checksum of a VRRPv3 message sent via IPv6 is valid.
+    pub fn is_checksum_valid_ipv6(&self, source: [u8;16], destination: [u8;16]) -> bool {
+        3 == self.version_u8() &&
+        0 == checksum::Sum16BitWords::new()
+            .add_16bytes(source)
+            .add_16bytes(destination)
+            .add_4bytes((self.slice.len() as u32).to_be_bytes())
+            .add_4bytes([0, 0, 0, ip_number::VRRP])
+            .add_slice(self.slice)
+            .ones_complement()
+    }
+
+    /// Decodes the fixed part of the message (the reserved bits of VRRPv3
+    /// messages are ignored).
+    pub fn header(&self) -> VrrpHeader {
+        let s = self.slice;
+        VrrpHeader{
+            version: if 2 == self.version_u8() {
+                VrrpVersion::V2{
+                    auth_type: s[4],
+                    advertisement_interval: s[5],
+                }
+            } else {
+                VrrpVersion::V3{
+                    max_advertisement_interval: u16::from_be_bytes([s[4] & 0xf, s[5]]),
+                }
+            },
+            vrrp_type: self.vrrp_type(),
+            virtual_router_id: s[1],
+            priority: s[2],
+            count_ip_addrs: s[3],
+        }
+    }
+
+    /// Returns the slice containing the ip addresses.
+    pub fn address_bytes(&self) -> &'a [u8] {
+        let address_len = if self.ipv6 { 16 } else { 4 };
+        &self.slice[VrrpHeader::LEN..VrrpHeader::LEN + usize::from(self.count_ip_addrs())*address_len]
+    }
+
+    /// Iterates over the ip addresses of the message (IPv6 addresses for
+    /// VRRPv3 messages sent via IPv6, IPv4 addresses otherwise).
+    pub fn addresses(&self) -> impl Iterator<Item = IpAddr> + 'a {
+        let address_len = if self.ipv6 { 16 } else { 4 };
+        self.address_bytes().chunks_exact(address_len).map(|a| {
+            if 4 == a.len() {
+                IpAddr::V4(Ipv4Addr::new(a[0], a[1], a[2], a[3]))
+            } else {
+                let mut bytes = [0u8;16];
+                bytes.copy_from_slice(a);
+                IpAddr::V6(Ipv6Addr::from(bytes))
+            }
+        })
+    }
+
+    /// Returns the authentication data of VRRPv2 messages (`None` for VRRPv3).
+    pub fn authentication_data(&self) -> Option<[u8;8]> {
+        if 2 == self.version_u8() {
+            let s = &self.slice[self.slice.len() - vrrp::V2_AUTHENTICATION_DATA_LEN..];
+            Some([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]])
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,612 @@
+use super::*;
+
+use zerocopy::byteorder::network_endian::{U16, U32};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Ref, Unaligned};
+
+/// Overlay & the rest of the slice.
+type OverlayResult<'a, T> = Result<(Ref<&'a [u8], T>, &'a [u8]), ReadError>;
+
+/// Overlays `T` at the start of `slice` and returns the overlay & the rest of the slice.
+fn overlay_from_slice<T>(slice: &[u8]) -> OverlayResult<'_, T>
+where
+    T: FromBytes + KnownLayout + Immutable + Unaligned + SerializedSize
+{
+    // the only possible cast error for unaligned types is a too small slice
+    Ref::from_prefix(slice).map_err(|_| ReadError::UnexpectedEndOfSlice(T::SERIALIZED_SIZE))
+}
+
+/// Fixed size overlay of an ethernet II header (requires the `zerocopy` feature).
+///
+/// The overlay types in this module are an alternative to the `*Slice` types that
+/// relies on [`zerocopy`] to prove the safety of the memory reinterpretation. They
+/// can be created directly from a slice via [`zerocopy::Ref`] or, for writing,
+/// converted to bytes with [`zerocopy::IntoBytes::as_bytes`].
+///
+/// # Example
+///
+/// ```
+/// use etherparse::{Ethernet2Header, Ethernet2HeaderOverlay, ether_type};
+/// use zerocopy::IntoBytes;
+///
+/// let header = Ethernet2Header {
+///     source: [1,2,3,4,5,6],
+///     destination: [7,8,9,10,11,12],
+///     ether_type: ether_type::IPV4,
+/// };
+/// let bytes = header.to_bytes();
+///
+/// let (overlay, rest) = Ethernet2HeaderOverlay::from_slice(&bytes).unwrap();
+/// assert!(rest.is_empty());
+/// assert_eq!(ether_type::IPV4, overlay.ether_type());
+/// assert_eq!(header, overlay.to_header());
+///
+/// // and back to bytes
+/// assert_eq!(&bytes[..], Ethernet2HeaderOverlay::from_header(&header).as_bytes());
+/// ```
+#[derive(FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct Ethernet2HeaderOverlay {
+    /// Destination MAC address.
+    pub destination: [u8;6],
+    /// Source MAC address.
+    pub source: [u8;6],
+    /// Ether type (or tag protocol identifier) of the next header.
+    pub ether_type: U16,
+}
+
+impl SerializedSize for Ethernet2HeaderOverlay {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 14;
+}
+
+impl Ethernet2HeaderOverlay {
+    /// Overlays the header at the start of the slice & returns it together with the rest of the slice.
+    pub fn from_slice(slice: &[u8]) -> OverlayResult<'_, Ethernet2HeaderOverlay> {
+        overlay_from_slice(slice)
+    }
+
+    /// Creates the overlay representation of a header.
+    pub fn from_header(header: &Ethernet2Header) -> Ethernet2HeaderOverlay {
+        Ethernet2HeaderOverlay {
+            destination: header.destination,
+            source: header.source,
+            ether_type: U16::new(header.ether_type),
+        }
+    }
+
+    /// Read the ether_type field of the header (in system native byte order).
+    #[inline]
+    pub fn ether_type(&self) -> u16 {
+        self.ether_type.get()
+    }
+
+    /// Decode all the fields and copy the results to a Ethernet2Header struct
+    pub fn to_header(&self) -> Ethernet2Header {
+        Ethernet2Header {
+            source: self.source,
+            destination: self.destination,
+            ether_type: self.ether_type(),
+        }
+    }
+}
+
+/// Fixed size overlay of a single IEEE 802.1Q vlan header (requires the `zerocopy` feature).
+///
+/// See [`Ethernet2HeaderOverlay`] for an example of how overlays are used.
+#[derive(FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct SingleVlanHeaderOverlay {
+    /// Priority code point, drop eligible indicator & vlan identifier.
+    pub tci: U16,
+    /// Ether type (or tag protocol identifier) of the next header.
+    pub ether_type: U16,
+}
+
+impl SerializedSize for SingleVlanHeaderOverlay {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 4;
+}
+
+impl SingleVlanHeaderOverlay {
+    /// Overlays the header at the start of the slice & returns it together with the rest of the slice.
+    pub fn from_slice(slice: &[u8]) -> OverlayResult<'_, SingleVlanHeaderOverlay> {
+        overlay_from_slice(slice)
+    }
+
+    /// Creates the overlay representation of a header.
+    ///
+    /// Bits of the `priority_code_point` & `vlan_identifier` fields outside of
+    /// their valid ranges are cut off.
+    pub fn from_header(header: &SingleVlanHeader) -> SingleVlanHeaderOverlay {
+        SingleVlanHeaderOverlay {
+            tci: U16::new(
+                (u16::from(header.priority_code_point & 0b111) << 13) |
+                (if header.drop_eligible_indicator { 0x1000 } else { 0 }) |
+                (header.vlan_identifier & 0xfff)
+            ),
+            ether_type: U16::new(header.ether_type),
+        }
+    }
+
+    /// Read the "priority_code_point" field from the slice. This is a 3 bit number which refers to the IEEE 802.1p class of service and maps to the frame priority level.
+    #[inline]
+    pub fn priority_code_point(&self) -> u8 {
+        (self.tci.get() >> 13) as u8
+    }
+
+    /// Read the "drop_eligible_indicator" flag from the slice. Indicates that the frame may be dropped under the presence of congestion.
+    #[inline]
+    pub fn drop_eligible_indicator(&self) -> bool {
+        0 != self.tci.get() & 0x1000
+    }
+
+    /// Reads the 12 bits "vland identifier" field from the slice.
+    #[inline]
+    pub fn vlan_identifier(&self) -> u16 {
+        self.tci.get() & 0xfff
+    }
+
+    /// Read the "Tag protocol identifier" field from the slice. Refer to the "EtherType" for a list of possible supported values.
+    #[inline]
+    pub fn ether_type(&self) -> u16 {
+        self.ether_type.get()
+    }
+
+    /// Decode all the fields and copy the results to a SingleVlanHeader struct
+    pub fn to_header(&self) -> SingleVlanHeader {
+        SingleVlanHeader {
+            priority_code_point: self.priority_code_point(),
+            drop_eligible_indicator: self.drop_eligible_indicator(),
+            vlan_identifier: self.vlan_identifier(),
+            ether_type: self.ether_type(),
+        }
+    }
+}
+
+/// Fixed size overlay of the first 20 bytes of an ipv4 header (requires the `zerocopy` feature).
+///
+/// The options are not part of the overlay. They are located in the
+/// `(ihl()*4 - 20)` bytes following the overlay.
+///
+/// See [`Ethernet2HeaderOverlay`] for an example of how overlays are used.
+#[derive(FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct Ipv4HeaderOverlay {
+    /// Version & internet header length.
+    pub version_ihl: u8,
+    /// Differentiated services code point & explicit congestion notification.
+    pub dscp_ecn: u8,
+    /// Total length of the ip header & payload.
+    pub total_len: U16,
+    /// Identification used for fragment reassembly.
+    pub identification: U16,
+    /// Flags & fragments offset.
+    pub flags_fragments_offset: U16,
+    /// Time to live.
+    pub ttl: u8,
+    /// Ip number of the next header.
+    pub protocol: u8,
+    /// Checksum of the ipv4 header.
+    pub header_checksum: U16,
+    /// Source ip address.
+    pub source: [u8;4],
+    /// Destination ip address.
+    pub destination: [u8;4],
+}
+
+impl SerializedSize for Ipv4HeaderOverlay {
+    ///Size of the overlay in bytes (ipv4 header without options).
+    const SERIALIZED_SIZE: usize = 20;
+}
+
+impl Ipv4HeaderOverlay {
+    /// Overlays the fixed part of the header at the start of the slice & returns it
+    /// together with the rest of the slice (starting with the options).
+    ///
+    /// Note that no values are validated (e.g. version & ihl), use [`Ipv4HeaderSlice`]
+    /// if validation is required.
+    pub fn from_slice(slice: &[u8]) -> OverlayResult<'_, Ipv4HeaderOverlay> {
+        overlay_from_slice(slice)
+    }
+
+    /// Read the "version" field (should be 4).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.version_ihl >> 4
+    }
+
+    /// Read the "ip header length" (length of the ipv4 header + options in multiples of 4 bytes).
+    #[inline]
+    pub fn ihl(&self) -> u8 {
+        self.version_ihl & 0xf
+    }
+
+    /// Read the "differentiated_services_code_point" from the slice.
+    #[inline]
+    pub fn dcp(&self) -> u8 {
+        self.dscp_ecn >> 2
+    }
+
+    /// Read the "explicit_congestion_notification" from the slice.
+    #[inline]
+    pub fn ecn(&self) -> u8 {
+        self.dscp_ecn & 0x3
+    }
+
+    /// Read the "total length" from the slice (total length of ip header + payload).
+    #[inline]
+    pub fn total_len(&self) -> u16 {
+        self.total_len.get()
+    }
+
+    /// Read the "identification" field from the slice.
+    #[inline]
+    pub fn identification(&self) -> u16 {
+        self.identification.get()
+    }
+
+    /// Read the "dont fragment" flag from the slice.
+    #[inline]
+    pub fn dont_fragment(&self) -> bool {
+        0 != self.flags_fragments_offset.get() & 0x4000
+    }
+
+    /// Read the "more fragments" flag from the slice.
+    #[inline]
+    pub fn more_fragments(&self) -> bool {
+        0 != self.flags_fragments_offset.get() & 0x2000
+    }
+
+    /// Read the "fragment_offset" field from the slice.
+    #[inline]
+    pub fn fragments_offset(&self) -> u16 {
+        self.flags_fragments_offset.get() & 0x1fff
+    }
+
+    /// Read the "header checksum" field from the slice.
+    #[inline]
+    pub fn header_checksum(&self) -> u16 {
+        self.header_checksum.get()
+    }
+
+    /// Return the ipv4 source address as an std::net::Ipv4Addr
+    #[inline]
+    pub fn source_addr(&self) -> std::net::Ipv4Addr {
+        std::net::Ipv4Addr::from(self.source)
+    }
+
+    /// Return the ipv4 destination address as an std::net::Ipv4Addr
+    #[inline]
+    pub fn destination_addr(&self) -> std::net::Ipv4Addr {
+        std::net::Ipv4Addr::from(self.destination)
+    }
+}
+
+/// Fixed size overlay of an ipv6 header (requires the `zerocopy` feature).
+///
+/// See [`Ethernet2HeaderOverlay`] for an example of how overlays are used.
+#[derive(FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct Ipv6HeaderOverlay {
+    /// Version, traffic class & flow label.
+    pub version_traffic_class_flow_label: U32,
+    /// Length of the extension headers & payload.
+    pub payload_length: U16,
+    /// Ip number of the next header.
+    pub next_header: u8,
+    /// Hop limit.
+    pub hop_limit: u8,
+    /// Source ip address.
+    pub source: [u8;16],
+    /// Destination ip address.
+    pub destination: [u8;16],
+}
+
+impl SerializedSize for Ipv6HeaderOverlay {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 40;
+}
+
+impl Ipv6HeaderOverlay {
+    /// Overlays the header at the start of the slice & returns it together with the rest of the slice.
+    ///
+    /// Note that the version field is not validated, use [`Ipv6HeaderSlice`]
+    /// if validation is required.
+    pub fn from_slice(slice: &[u8]) -> OverlayResult<'_, Ipv6HeaderOverlay> {
+        overlay_from_slice(slice)
+    }
+
+    /// Creates the overlay representation of a header.
+    ///
+    /// Bits of the `flow_label` outside of the valid range are cut off.
+    pub fn from_header(header: &Ipv6Header) -> Ipv6HeaderOverlay {
+        Ipv6HeaderOverlay {
+            version_traffic_class_flow_label: U32::new(
+                (6 << 28) |
+                (u32::from(header.traffic_class) << 20) |
+                (header.flow_label & 0xfffff)
+            ),
+            payload_length: U16::new(header.payload_length),
+            next_header: header.next_header,
+            hop_limit: header.hop_limit,
+            source: header.source,
+            destination: header.destination,
+        }
+    }
+
+    /// Read the "version" field from the slice (should be 6).
+    #[inline]
+    pub fn version(&self) -> u8 {
+        (self.version_traffic_class_flow_label.get() >> 28) as u8
+    }
+
+    /// Read the "traffic class" field from the slice.
+    #[inline]
+    pub fn traffic_class(&self) -> u8 {
+        (self.version_traffic_class_flow_label.get() >> 20) as u8
+    }
+
+    /// Read the "flow label" field from the slice.
+    #[inline]
+    pub fn flow_label(&self) -> u32 {
+        self.version_traffic_class_flow_label.get() & 0xfffff
+    }
+
+    /// Read the "payload length" field from  the slice. The length should contain the length of all extension headers and payload.
+    #[inline]
+    pub fn payload_length(&self) -> u16 {
+        self.payload_length.get()
+    }
+
+    /// Return the ipv6 source address as an std::net::Ipv6Addr
+    #[inline]
+    pub fn source_addr(&self) -> std::net::Ipv6Addr {
+        std::net::Ipv6Addr::from(self.source)
+    }
+
+    /// Return the ipv6 destination address as an std::net::Ipv6Addr
+    #[inline]
+    pub fn destination_addr(&self) -> std::net::Ipv6Addr {
+        std::net::Ipv6Addr::from(self.destination)
+    }
+
+    /// Decode all the fields and copy the results to a Ipv6Header struct
+    pub fn to_header(&self) -> Ipv6Header {
+        Ipv6Header {
+            traffic_class: self.traffic_class(),
+            flow_label: self.flow_label(),
+            payload_length: self.payload_length(),
+            next_header: self.next_header,
+            hop_limit: self.hop_limit,
+            source: self.source,
+            destination: self.destination,
+        }
+    }
+}
+
+/// Fixed size overlay of an udp header (requires the `zerocopy` feature).
+///
+/// See [`Ethernet2HeaderOverlay`] for an example of how overlays are used.
+#[derive(FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct UdpHeaderOverlay {
+    /// Source port.
+    pub source_port: U16,
+    /// Destination port.
+    pub destination_port: U16,
+    /// Length of the udp header & payload.
+    pub length: U16,
+    /// Checksum of the pseudo header, the header & the payload.
+    pub checksum: U16,
+}
+
+impl SerializedSize for UdpHeaderOverlay {
+    ///Serialized size of the header in bytes.
+    const SERIALIZED_SIZE: usize = 8;
+}
+
+impl UdpHeaderOverlay {
+    /// Overlays the header at the start of the slice & returns it together with the rest of the slice.
+    pub fn from_slice(slice: &[u8]) -> OverlayResult<'_, UdpHeaderOverlay> {
+        overlay_from_slice(slice)
+    }
+
+    /// Creates the overlay representation of a header.
+    pub fn from_header(header: &UdpHeader) -> UdpHeaderOverlay {
+        UdpHeaderOverlay {
+            source_port: U16::new(header.source_port),
+            destination_port: U16::new(header.destination_port),
+            length: U16::new(header.length),
+            checksum: U16::new(header.checksum),
+        }
+    }
+
+    /// Reads the "udp source port" from the slice.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        self.source_port.get()
+    }
+
+    /// Reads the "udp destination port" from the slice.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        self.destination_port.get()
+    }
+
+    /// Reads the "length" field from the slice.
+    #[inline]
+    pub fn length(&self) -> u16 {
+        self.length.get()
+    }
+
+    /// Reads the "checksum" from the slice.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        self.checksum.get()
+    }
+
+    /// Decode all the fields and copy the results to a UdpHeader struct
+    pub fn to_header(&self) -> UdpHeader {
+        UdpHeader {
+            source_port: self.source_port(),
+            destination_port: self.destination_port(),
+            length: self.length(),
+            checksum: self.checksum(),
+        }
+    }
+}
+
+/// Fixed size overlay of the first 20 bytes of a tcp header (requires the `zerocopy` feature).
+///
+/// The options are not part of the overlay. They are located in the
+/// `(data_offset()*4 - 20)` bytes following the overlay.
+///
+/// See [`Ethernet2HeaderOverlay`] for an example of how overlays are used.
+#[derive(FromBytes, IntoBytes, KnownLayout, Immutable, Unaligned)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[repr(C)]
+pub struct TcpHeaderOverlay {
+    /// Source port.
+    pub source_port: U16,
+    /// Destination port.
+    pub destination_port: U16,
+    /// Sequence number.
+    pub sequence_number: U32,
+    /// Acknowledgment number.
+    pub acknowledgment_number: U32,
+    /// Data offset, reserved bits & the ns flag.
+    pub data_offset_ns: u8,
+    /// Flags (cwr, ece, urg, ack, psh, rst, syn, fin).
+    pub flags: u8,
+    /// Window size.
+    pub window_size: U16,
+    /// Checksum of the pseudo header, the header & the payload.
+    pub checksum: U16,
+    /// Urgent pointer.
+    pub urgent_pointer: U16,
+}
+
+impl SerializedSize for TcpHeaderOverlay {
+    ///Size of the overlay in bytes (tcp header without options).
+    const SERIALIZED_SIZE: usize = 20;
+}
+
+impl TcpHeaderOverlay {
+    /// Overlays the fixed part of the header at the start of the slice & returns it
+    /// together with the rest of the slice (starting with the options).
+    ///
+    /// Note that the data offset is not validated, use [`TcpHeaderSlice`]
+    /// if validation is required.
+    pub fn from_slice(slice: &[u8]) -> OverlayResult<'_, TcpHeaderOverlay> {
+        overlay_from_slice(slice)
+    }
+
+    /// Read the source port number.
+    #[inline]
+    pub fn source_port(&self) -> u16 {
+        self.source_port.get()
+    }
+
+    /// Read the destination port number.
+    #[inline]
+    pub fn destination_port(&self) -> u16 {
+        self.destination_port.get()
+    }
+
+    /// Read the sequence number.
+    #[inline]
+    pub fn sequence_number(&self) -> u32 {
+        self.sequence_number.get()
+    }
+
+    /// Reads the acknowledgment number.
+    #[inline]
+    pub fn acknowledgment_number(&self) -> u32 {
+        self.acknowledgment_number.get()
+    }
+
+    /// Read the number of 32 bit words in the TCP Header.
+    #[inline]
+    pub fn data_offset(&self) -> u8 {
+        self.data_offset_ns >> 4
+    }
+
+    /// ECN-nonce - concealment protection (experimental: see RFC 3540)
+    #[inline]
+    pub fn ns(&self) -> bool {
+        0 != self.data_offset_ns & 1
+    }
+
+    /// Read the fin flag (no more data from sender).
+    #[inline]
+    pub fn fin(&self) -> bool {
+        0 != self.flags & 1
+    }
+
+    /// Reads the syn flag (synchronize sequence numbers).
+    #[inline]
+    pub fn syn(&self) -> bool {
+        0 != self.flags & 2
+    }
+
+    /// Reads the rst flag (reset the connection).
+    #[inline]
+    pub fn rst(&self) -> bool {
+        0 != self.flags & 4
+    }
+
+    /// Reads the psh flag (push function).
+    #[inline]
+    pub fn psh(&self) -> bool {
+        0 != self.flags & 8
+    }
+
+    /// Reads the ack flag (acknowledgment field significant).
+    #[inline]
+    pub fn ack(&self) -> bool {
+        0 != self.flags & 16
+    }
+
+    /// Reads the urg flag (Urgent Pointer field significant).
+    #[inline]
+    pub fn urg(&self) -> bool {
+        0 != self.flags & 32
+    }
+
+    /// Read the ECN-Echo flag (RFC 3168).
+    #[inline]
+    pub fn ece(&self) -> bool {
+        0 != self.flags & 64
+    }
+
+    /// Reads the cwr flag (Congestion Window Reduced).
+    #[inline]
+    pub fn cwr(&self) -> bool {
+        0 != self.flags & 128
+    }
+
+    /// The number of data octets beginning with the one indicated in the
+    /// acknowledgment field which the sender of this segment is willing to accept.
+    #[inline]
+    pub fn window_size(&self) -> u16 {
+        self.window_size.get()
+    }
+
+    /// Checksum (16 bit one's complement) of the pseudo ip header, this tcp header and the payload.
+    #[inline]
+    pub fn checksum(&self) -> u16 {
+        self.checksum.get()
+    }
+
+    /// This field communicates the current value of the urgent pointer as a
+    /// positive offset from the sequence number in this segment.
+    #[inline]
+    pub fn urgent_pointer(&self) -> u16 {
+        self.urgent_pointer.get()
+    }
+}
@@ -0,0 +1,247 @@
+use super::super::*;
+
+mod dns_name_limits {
+    use super::*;
+
+    #[test]
+    fn default() {
+        assert_eq!(
+            DnsNameLimits::default(),
+            DnsNameLimits {
+                max_pointer_jumps: 16,
+                max_name_len: 255,
+                max_label_count: 127,
+            }
+        );
+    }
+}
+
+mod dns_name_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        use DnsNameError::*;
+        assert_eq!(
+            "DnsNameError: Unexpected end of message. The DNS name continues past the end of the message (at offset 3).",
+            format!("{}", UnexpectedEndOfMessage(3))
+        );
+        assert_eq!(
+            "DnsNameError: Compression pointer 12 points outside of the message (4 bytes).",
+            format!("{}", PointerOutOfBounds{ pointer: 12, message_len: 4 })
+        );
+        assert_eq!(
+            "DnsNameError: Too many compression pointers (more then the allowed maximum of 2 jumps).",
+            format!("{}", TooManyPointerJumps(2))
+        );
+        assert_eq!(
+            "DnsNameError: DNS name is longer then the allowed maximum of 255 octets.",
+            format!("{}", NameTooLong(255))
+        );
+        assert_eq!(
+            "DnsNameError: DNS name contains more then the allowed maximum of 3 labels.",
+            format!("{}", TooManyLabels(3))
+        );
+        assert_eq!(
+            "DnsNameError: Unsupported label type in length octet 0x41.",
+            format!("{}", UnsupportedLabelType(0x41))
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(DnsNameError::TooManyLabels(0).source().is_none());
+    }
+}
+
+mod dns_name_slice {
+    use super::*;
+
+    /// "example.com" at offset 0, "www" + pointer at offset 13
+    const MESSAGE: [u8;19] = [
+        7, b'e', b'x', b'a', b'm', b'p', b'l', b'e',
+        3, b'c', b'o', b'm',
+        0,
+        3, b'w', b'w', b'w',
+        0xc0, 0,
+    ];
+
+    #[test]
+    fn uncompressed() {
+        let name = DnsNameSlice::from_message(&MESSAGE, 0, DnsNameLimits::default()).unwrap();
+        assert_eq!(&MESSAGE[..], name.message());
+        assert_eq!(0, name.offset());
+        assert_eq!(13, name.encoded_len());
+        assert_eq!(&MESSAGE[..13], name.slice());
+        assert_eq!(13, name.name_len());
+        assert_eq!(2, name.label_count());
+        assert!(!name.is_root());
+        assert_eq!(
+            vec![&b"example"[..], &b"com"[..]],
+            name.labels().collect::<Vec<_>>()
+        );
+        assert_eq!("example.com", format!("{}", name));
+    }
+
+    #[test]
+    fn compressed() {
+        let name = DnsNameSlice::from_message(&MESSAGE, 13, DnsNameLimits::default()).unwrap();
+        assert_eq!(13, name.offset());
+        assert_eq!(6, name.encoded_len());
+        assert_eq!(&MESSAGE[13..], name.slice());
+        assert_eq!(17, name.name_len());
+        assert_eq!(3, name.label_count());
+        assert_eq!(3, name.labels().len());
+        assert_eq!(
+            vec![&b"www"[..], &b"example"[..], &b"com"[..]],
+            name.labels().collect::<Vec<_>>()
+        );
+        assert_eq!("www.example.com", name.to_string());
+
+        // uncompressed serialization
+        let mut buffer = [0u8;20];
+        let rest_len = name.write_uncompressed_to_slice(&mut buffer).unwrap().len();
+        assert_eq!(3, rest_len);
+        assert_eq!(&b"\x03www\x07example\x03com\x00"[..], &buffer[..17]);
+
+        // slice too small
+        assert_matches!(
+            name.write_uncompressed_to_slice(&mut buffer[..16]),
+            Err(WriteError::SliceTooSmall(17))
+        );
+    }
+
+    #[test]
+    fn root() {
+        let name = DnsNameSlice::from_message(&[0], 0, DnsNameLimits::default()).unwrap();
+        assert!(name.is_root());
+        assert_eq!(1, name.encoded_len());
+        assert_eq!(1, name.name_len());
+        assert_eq!(0, name.labels().count());
+        assert_eq!(".", name.to_string());
+    }
+
+    #[test]
+    fn display_escaping() {
+        let message = [3, b'a', b'.', b'\\', 2, 0, b' ', 0];
+        let name = DnsNameSlice::from_message(&message, 0, DnsNameLimits::default()).unwrap();
+        assert_eq!("a\\.\\\\.\\000\\032", name.to_string());
+    }
+
+    #[test]
+    fn unexpected_end() {
+        use DnsNameError::*;
+        // missing length octet
+        assert_eq!(
+            Err(UnexpectedEndOfMessage(0)),
+            DnsNameSlice::from_message(&[], 0, DnsNameLimits::default())
+        );
+        // label longer then the message
+        assert_eq!(
+            Err(UnexpectedEndOfMessage(3)),
+            DnsNameSlice::from_message(&[3, b'a', b'b'], 0, DnsNameLimits::default())
+        );
+        // missing terminating zero
+        assert_eq!(
+            Err(UnexpectedEndOfMessage(2)),
+            DnsNameSlice::from_message(&[1, b'a'], 0, DnsNameLimits::default())
+        );
+        // pointer missing its second octet
+        assert_eq!(
+            Err(UnexpectedEndOfMessage(1)),
+            DnsNameSlice::from_message(&[0xc0], 0, DnsNameLimits::default())
+        );
+    }
+
+    #[test]
+    fn pointer_errors() {
+        use DnsNameError::*;
+        assert_eq!(
+            Err(PointerOutOfBounds{ pointer: 2, message_len: 2 }),
+            DnsNameSlice::from_message(&[0xc0, 2], 0, DnsNameLimits::default())
+        );
+        // pointer loop pointing to itself
+        assert_eq!(
+            Err(TooManyPointerJumps(16)),
+            DnsNameSlice::from_message(&[0xc0, 0], 0, DnsNameLimits::default())
+        );
+        // two pointers pointing at each other
+        assert_eq!(
+            Err(TooManyPointerJumps(3)),
+            DnsNameSlice::from_message(
+                &[0xc0, 2, 0xc0, 0],
+                0,
+                DnsNameLimits{ max_pointer_jumps: 3, ..Default::default() }
+            )
+        );
+        // pointers not allowed
+        assert_eq!(
+            Err(TooManyPointerJumps(0)),
+            DnsNameSlice::from_message(
+                &MESSAGE,
+                13,
+                DnsNameLimits{ max_pointer_jumps: 0, ..Default::default() }
+            )
+        );
+    }
+
+    #[test]
+    fn limits() {
+        use DnsNameError::*;
+        assert_eq!(
+            Err(NameTooLong(16)),
+            DnsNameSlice::from_message(
+                &MESSAGE,
+                13,
+                DnsNameLimits{ max_name_len: 16, ..Default::default() }
+            )
+        );
+        assert!(
+            DnsNameSlice::from_message(
+                &MESSAGE,
+                13,
+                DnsNameLimits{ max_name_len: 17, ..Default::default() }
+            ).is_ok()
+        );
+        assert_eq!(
+            Err(TooManyLabels(2)),
+            DnsNameSlice::from_message(
+                &MESSAGE,
+                13,
+                DnsNameLimits{ max_label_count: 2, ..Default::default() }
+            )
+        );
+        // default name length limit of 255 octets
+        {
+            let mut message = Vec::new();
+            for _ in 0..4 {
+                message.push(63);
+                message.extend_from_slice(&[b'a';63]);
+            }
+            message.push(0);
+            assert_eq!(
+                Err(NameTooLong(255)),
+                DnsNameSlice::from_message(&message, 0, DnsNameLimits::default())
+            );
+        }
+    }
+
+    #[test]
+    fn unsupported_label_type() {
+        for value in [0x40, 0x80, 0xbf] {
+            assert_eq!(
+                Err(DnsNameError::UnsupportedLabelType(value)),
+                DnsNameSlice::from_message(&[value, 0], 0, DnsNameLimits::default())
+            );
+        }
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let name = DnsNameSlice::from_message(&MESSAGE, 13, DnsNameLimits::default()).unwrap();
+        assert_eq!(name.clone(), name);
+        assert_eq!(name.labels().clone(), name.labels());
+        println!("{:?} {:?}", name, name.labels());
+    }
+}
@@ -0,0 +1,385 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn geneve_option_any()(
+        class in any::<u16>(),
+        option_type in any::<u8>(),
+        data_len in 0..=(GeneveOption::MAX_DATA_LEN / 4),
+    )(
+        class in Just(class),
+        option_type in Just(option_type),
+        data in proptest::collection::vec(any::<u8>(), data_len * 4),
+    ) -> (u16, u8, Vec<u8>) {
+        (class, option_type, data)
+    }
+}
+
+prop_compose! {
+    fn geneve_any()(
+        oam in any::<bool>(),
+        protocol_type in any::<u16>(),
+        vni in 0..=GeneveHeader::MAX_VNI,
+        options in proptest::collection::vec(geneve_option_any(), 0..3),
+    ) -> GeneveHeader {
+        let mut result = GeneveHeader::new(protocol_type, vni).unwrap();
+        result.oam = oam;
+        // only keep the options fitting into the header
+        let mut len = 0;
+        let options: Vec<GeneveOption> = options
+            .iter()
+            .map(|(class, option_type, data)| GeneveOption{ class: *class, option_type: *option_type, data })
+            .take_while(|option| {
+                len += option.header_len();
+                len <= GeneveHeader::MAX_OPTIONS_LEN
+            })
+            .collect();
+        result.set_options(&options).unwrap();
+        result
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in geneve_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(header.header_len() + 2, buffer.len());
+        assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..header.header_len()]);
+
+        // from_slice
+        {
+            let (decoded, rest) = GeneveHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &GeneveHeader::read(&mut cursor).unwrap());
+            assert_eq!(header.header_len() as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = GeneveHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(0, slice.version());
+            assert_eq!(header.oam, slice.oam());
+            assert_eq!(header.critical, slice.critical());
+            assert_eq!(header.protocol_type, slice.protocol_type());
+            assert_eq!(header.vni, slice.vni());
+            assert_eq!(header.options(), slice.options());
+            assert_eq!(
+                header.options_iterator().collect::<Vec<_>>(),
+                slice.options_iterator().collect::<Vec<_>>()
+            );
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn vni_too_large(vni in (GeneveHeader::MAX_VNI + 1)..=u32::MAX) {
+        let expected = ValueError::U32TooLarge{
+            value: vni,
+            max: GeneveHeader::MAX_VNI,
+            field: ErrorField::GeneveVni
+        };
+        assert_eq!(Some(expected.clone()), GeneveHeader::new(0, vni).err());
+
+        let mut header = GeneveHeader::new(0, 0).unwrap();
+        header.vni = vni;
+        assert_eq!(Some(expected.clone()), header.to_bytes().err());
+        assert_eq!(Some(expected), header.write(&mut Vec::new()).unwrap_err().value_error());
+    }
+}
+
+#[test]
+fn set_options() {
+    let mut header = GeneveHeader::new(ether_type::TRANSPARENT_ETHERNET_BRIDGING, 0x123456).unwrap();
+    header.set_options(&[
+        GeneveOption{ class: 0x0102, option_type: 0x03, data: &[] },
+        GeneveOption{ class: 0x0405, option_type: 0x86, data: &[1, 2, 3, 4, 5, 6, 7, 8] },
+    ]).unwrap();
+    assert!(header.critical);
+    assert_eq!(
+        &[
+            0x01, 0x02, 0x03, 0,
+            0x04, 0x05, 0x86, 2, 1, 2, 3, 4, 5, 6, 7, 8,
+        ],
+        header.options()
+    );
+    assert_eq!(
+        &[
+            4, 0b0100_0000, 0x65, 0x58, 0x12, 0x34, 0x56, 0,
+        ],
+        &header.to_bytes().unwrap()[..8]
+    );
+
+    let options: Vec<GeneveOption> = header.options_iterator().collect();
+    assert_eq!(2, options.len());
+    assert!(!options[0].critical());
+    assert_eq!(4, options[0].header_len());
+    assert!(options[1].critical());
+    assert_eq!(0x0405, options[1].class);
+    assert_eq!(&[1, 2, 3, 4, 5, 6, 7, 8], options[1].data);
+
+    // critical flag gets reset
+    header.set_options(&[]).unwrap();
+    assert!(!header.critical);
+    assert!(header.options().is_empty());
+    assert_eq!(GeneveHeader::MIN_LEN, header.header_len());
+}
+
+#[test]
+fn set_options_errors() {
+    use ValueError::*;
+
+    let mut header = GeneveHeader::new(0, 0).unwrap();
+    header.set_options(&[GeneveOption{ class: 1, option_type: 2, data: &[1, 2, 3, 4] }]).unwrap();
+
+    // unaligned & too big option data
+    for len in [1, 2, 3, 5, GeneveOption::MAX_DATA_LEN + 4] {
+        let data = vec![0; len];
+        assert_eq!(
+            Err(GeneveOptionDataLengthBad(len)),
+            header.set_options(&[GeneveOption{ class: 0, option_type: 0, data: &data }])
+        );
+    }
+    // too many options
+    {
+        let data = [0; GeneveOption::MAX_DATA_LEN];
+        let option = GeneveOption{ class: 0, option_type: 0, data: &data };
+        assert_eq!(
+            Err(GeneveOptionsLengthBad(3*128)),
+            header.set_options(&[option.clone(), option.clone(), option])
+        );
+    }
+    // nothing changed
+    assert_eq!(&[0, 1, 2, 1, 1, 2, 3, 4], header.options());
+}
+
+#[test]
+fn from_slice_errors() {
+    let mut header = GeneveHeader::new(0, 0).unwrap();
+    header.set_options(&[GeneveOption{ class: 1, option_type: 2, data: &[1, 2, 3, 4] }]).unwrap();
+    let bytes = header.to_bytes().unwrap();
+
+    // length
+    for len in 0..bytes.len() {
+        let expected = if len < GeneveHeader::MIN_LEN { GeneveHeader::MIN_LEN } else { bytes.len() };
+        assert_matches!(
+            GeneveHeader::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(l)) if l == expected
+        );
+        assert_matches!(
+            GeneveHeader::read(&mut Cursor::new(&bytes[..len])),
+            Err(ReadError::IoError(_))
+        );
+    }
+    // version
+    for version in 1..4u8 {
+        let mut bytes = bytes.clone();
+        bytes[0] |= version << 6;
+        assert_matches!(
+            GeneveHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GeneveUnsupportedVersion(v)) if v == version
+        );
+        assert_matches!(
+            GeneveHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GeneveUnsupportedVersion(v)) if v == version
+        );
+    }
+    // option exceeding the options length
+    {
+        let mut bytes = bytes.clone();
+        bytes[GeneveHeader::MIN_LEN + 3] = 2;
+        assert_matches!(
+            GeneveHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GeneveOptionLengthTooBig(0))
+        );
+        assert_matches!(
+            GeneveHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GeneveOptionLengthTooBig(0))
+        );
+    }
+}
+
+#[test]
+fn debug_eq_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut header = GeneveHeader::new(ether_type::IPV4, 1).unwrap();
+    header.set_options(&[GeneveOption{ class: 1, option_type: 2, data: &[1, 2, 3, 4] }]).unwrap();
+    assert_eq!(
+        "GeneveHeader { oam: false, critical: false, protocol_type: 2048, vni: 1, options: [0, 1, 2, 1, 1, 2, 3, 4] }",
+        format!("{:?}", header)
+    );
+
+    // data beyond the options is ignored
+    let mut other = header.clone();
+    other.set_options(&[GeneveOption{ class: 1, option_type: 2, data: &[1, 2, 3, 4, 5, 6, 7, 8] }]).unwrap();
+    other.set_options(&[GeneveOption{ class: 1, option_type: 2, data: &[1, 2, 3, 4] }]).unwrap();
+    assert_eq!(header, other);
+    let hash = |value: &GeneveHeader| {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(&header), hash(&other));
+
+    other.vni = 2;
+    assert_ne!(header, other);
+}
+
+/// Returns an ethernet II frame containing an udp packet with the given payload.
+fn inner_frame(payload: &[u8]) -> Vec<u8> {
+    let mut inner = Vec::new();
+    PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+        .ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut inner, payload)
+        .unwrap();
+    inner
+}
+
+#[test]
+fn packet_builder() {
+    let inner = inner_frame(&[1, 2, 3, 4]);
+    let mut geneve = GeneveHeader::new(ether_type::TRANSPARENT_ETHERNET_BRIDGING, 1234).unwrap();
+    geneve.set_options(&[GeneveOption{ class: 0x0102, option_type: 0x80, data: &[1, 2, 3, 4] }]).unwrap();
+
+    let builder = PacketBuilder::ethernet2([0; 6], [0; 6])
+        .ipv6([1; 16], [2; 16], 20)
+        .udp(50000, GeneveHeader::UDP_PORT)
+        .geneve(geneve.clone());
+    let size = builder.size(inner.len());
+
+    let mut packet = Vec::new();
+    builder.write(&mut packet, &inner).unwrap();
+    assert_eq!(size, packet.len());
+    assert_eq!(14 + 40 + 8 + geneve.header_len() + inner.len(), packet.len());
+
+    let registry = ParserRegistry::with_well_known_ports();
+    let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+    assert_eq!(Some(ApplicationProtocol::Geneve), sliced.application);
+    match sliced.transport.unwrap() {
+        TransportSlice::Udp(udp) => {
+            assert_eq!((8 + geneve.header_len() + inner.len()) as u16, udp.length());
+            let ip = match sliced.ip.unwrap() {
+                InternetSlice::Ipv6(ip, _) => ip.to_header(),
+                _ => unreachable!(),
+            };
+            assert_eq!(
+                udp.checksum(),
+                udp.to_header().calc_checksum_ipv6(&ip, &packet[14 + 40 + 8..]).unwrap()
+            );
+        },
+        _ => unreachable!(),
+    }
+    assert_eq!(geneve, sliced.geneve.unwrap().to_header());
+    assert_eq!(&inner[..], sliced.payload);
+}
+
+#[test]
+fn packet_builder_mtu() {
+    let builder = PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .mtu(20 + 8 + 8 + 9)
+        .udp(50000, GeneveHeader::UDP_PORT)
+        .geneve(GeneveHeader::new(ether_type::IPV4, 1).unwrap());
+    assert_eq!(
+        Some(ValueError::IpPacketExceedsMtu{ ip_packet_len: 20 + 8 + 8 + 10, mtu: 20 + 8 + 8 + 9 }),
+        builder.write(&mut Vec::new(), &[0; 10]).unwrap_err().value_error()
+    );
+}
+
+#[test]
+fn sliced_packet() {
+    let inner = inner_frame(&[1, 2, 3, 4]);
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(50000, GeneveHeader::UDP_PORT)
+        .geneve(GeneveHeader::new(ether_type::TRANSPARENT_ETHERNET_BRIDGING, 1234).unwrap())
+        .write(&mut packet, &inner)
+        .unwrap();
+    let registry = ParserRegistry::with_well_known_ports();
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(1234, sliced.geneve.unwrap().vni());
+        assert_eq!(&inner[..], sliced.payload);
+
+        let sliced_inner = sliced.inner.unwrap();
+        assert_eq!([1, 2, 3, 4, 5, 6], sliced_inner.link.unwrap().to_header().source);
+        assert_matches!(sliced_inner.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+    }
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+        assert_eq!(1234, decoded.geneve.unwrap().vni);
+        assert_eq!(&inner[..], decoded.payload);
+
+        let decoded_inner = decoded.inner.unwrap();
+        assert_eq!([1, 2, 3, 4, 5, 6], decoded_inner.link.unwrap().source);
+        assert_matches!(decoded_inner.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], decoded_inner.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_ipv6_payload() {
+    let mut inner = Vec::new();
+    PacketBuilder::ipv6([1; 16], [2; 16], 20)
+        .tcp(1234, 5678, 1, 1024)
+        .write(&mut inner, &[1, 2, 3, 4])
+        .unwrap();
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(50000, GeneveHeader::UDP_PORT)
+        .geneve(GeneveHeader::new(ether_type::IPV6, 1).unwrap())
+        .write(&mut packet, &inner)
+        .unwrap();
+    let registry = ParserRegistry::with_well_known_ports();
+
+    let sliced_inner = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap().inner.unwrap();
+    assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv6(_, _)));
+    assert_matches!(sliced_inner.transport, Some(TransportSlice::Tcp(_)));
+
+    let decoded_inner = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap().inner.unwrap();
+    assert_matches!(decoded_inner.ip, Some(IpHeader::Version6(_, _)));
+    assert_matches!(decoded_inner.transport, Some(TransportHeader::Tcp(_)));
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let registry = ParserRegistry::with_well_known_ports();
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(50000, GeneveHeader::UDP_PORT)
+        .write(&mut packet, &[0b0100_0000, 0, 0, 0, 0, 0, 0, 0])
+        .unwrap();
+    assert_matches!(
+        SlicedPacket::from_ip_with_registry(&packet, &registry),
+        Err(ReadError::GeneveUnsupportedVersion(1))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_registry(&packet, &registry),
+        Err(ReadError::GeneveUnsupportedVersion(1))
+    );
+
+    let packet = &packet[..packet.len() - 1];
+    assert_matches!(
+        SlicedPacket::from_ip_with_registry(packet, &registry),
+        Err(ReadError::UnexpectedEndOfSlice(36))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_registry(packet, &registry),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+}
@@ -0,0 +1,419 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn gtpu_extension_any()(
+        extension_type in 1..=u8::MAX,
+        content_len in 1..=4usize,
+    )(
+        extension_type in Just(extension_type),
+        content in proptest::collection::vec(any::<u8>(), content_len*4 - 2),
+    ) -> (u8, Vec<u8>) {
+        (extension_type, content)
+    }
+}
+
+prop_compose! {
+    fn gtpu_any()(
+        message_type in any::<u8>(),
+        length in any::<u16>(),
+        teid in any::<u32>(),
+        sequence_number in proptest::option::of(any::<u16>()),
+        n_pdu_number in proptest::option::of(any::<u8>()),
+        extensions in proptest::collection::vec(gtpu_extension_any(), 0..3),
+    ) -> GtpuHeader {
+        let mut result = GtpuHeader::new(message_type, teid);
+        result.length = length;
+        result.sequence_number = sequence_number;
+        result.n_pdu_number = n_pdu_number;
+        let extensions: Vec<GtpuExtension> = extensions
+            .iter()
+            .map(|(extension_type, content)| GtpuExtension{ extension_type: *extension_type, content })
+            .collect();
+        result.set_extensions(&extensions).unwrap();
+        result
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in gtpu_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(header.header_len() + 2, buffer.len());
+        assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..header.header_len()]);
+
+        // from_slice
+        {
+            let (decoded, rest) = GtpuHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &GtpuHeader::read(&mut cursor).unwrap());
+            assert_eq!(header.header_len() as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = GtpuHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(1, slice.version());
+            assert!(slice.protocol_type());
+            assert_eq!(header.message_type, slice.message_type());
+            assert_eq!(header.length, slice.length());
+            assert_eq!(header.teid, slice.teid());
+            assert_eq!(header.sequence_number, slice.sequence_number());
+            assert_eq!(header.n_pdu_number, slice.n_pdu_number());
+            assert_eq!(header.extensions(), slice.extensions());
+            assert_eq!(
+                header.extensions_iterator().collect::<Vec<_>>(),
+                slice.extensions_iterator().collect::<Vec<_>>()
+            );
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+}
+
+#[test]
+fn header_len() {
+    let mut header = GtpuHeader::g_pdu(1);
+    assert_eq!(gtpu_message_type::G_PDU, header.message_type);
+    assert_eq!(8, header.header_len());
+    assert_eq!(&[0x30, 0xff, 0, 0, 0, 0, 0, 1], &header.to_bytes().unwrap()[..]);
+
+    // any optional field adds all optional fields
+    header.n_pdu_number = Some(2);
+    assert_eq!(12, header.header_len());
+    assert_eq!(
+        &[0x31, 0xff, 0, 0, 0, 0, 0, 1, 0, 0, 2, 0],
+        &header.to_bytes().unwrap()[..]
+    );
+    header.n_pdu_number = None;
+    header.sequence_number = Some(0x1234);
+    assert_eq!(
+        &[0x32, 0xff, 0, 0, 0, 0, 0, 1, 0x12, 0x34, 0, 0],
+        &header.to_bytes().unwrap()[..]
+    );
+}
+
+#[test]
+fn set_extensions() {
+    let mut header = GtpuHeader::g_pdu(1);
+    header.set_extensions(&[
+        GtpuExtension{ extension_type: gtpu_extension_type::PDU_SESSION_CONTAINER, content: &[0x10, 9] },
+        GtpuExtension{ extension_type: gtpu_extension_type::UDP_PORT, content: &[1, 2, 3, 4, 5, 6] },
+    ]).unwrap();
+    assert_eq!(8 + 4 + 4 + 8, header.header_len());
+    assert_eq!(
+        &[1, 0x10, 9, gtpu_extension_type::UDP_PORT, 2, 1, 2, 3, 4, 5, 6, 0],
+        header.extensions()
+    );
+    assert_eq!(
+        &[0x34, 0xff, 0, 0, 0, 0, 0, 1, 0, 0, 0, gtpu_extension_type::PDU_SESSION_CONTAINER],
+        &header.to_bytes().unwrap()[..12]
+    );
+
+    // reset
+    header.set_extensions(&[]).unwrap();
+    assert_eq!(8, header.header_len());
+    assert_eq!(0, header.extensions_iterator().count());
+}
+
+#[test]
+fn set_extensions_errors() {
+    let mut header = GtpuHeader::g_pdu(1);
+    header.set_extensions(&[GtpuExtension{ extension_type: 1, content: &[1, 2] }]).unwrap();
+
+    // content length not a multiple of 4 minus 2
+    assert_eq!(
+        Err(ValueError::GtpuExtensionContentLengthBad(3)),
+        header.set_extensions(&[GtpuExtension{ extension_type: 1, content: &[1, 2, 3] }])
+    );
+    // too long
+    let content = [0; 250];
+    assert_eq!(
+        Err(ValueError::GtpuExtensionsLengthBad(256)),
+        header.set_extensions(&[
+            GtpuExtension{ extension_type: 1, content: &content },
+            GtpuExtension{ extension_type: 2, content: &[0; 2] },
+        ])
+    );
+    // nothing changed
+    assert_eq!(&[1, 1, 2, 0], header.extensions());
+}
+
+#[test]
+fn from_slice_errors() {
+    let mut header = GtpuHeader::g_pdu(1);
+    header.set_extensions(&[GtpuExtension{ extension_type: 1, content: &[1, 2] }]).unwrap();
+    let bytes = header.to_bytes().unwrap();
+
+    // length
+    for len in 0..bytes.len() {
+        let expected = if len < 8 { 8 } else if len < 12 { 12 } else if len < 13 { 13 } else { 16 };
+        assert_matches!(
+            GtpuHeader::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(l)) if l == expected
+        );
+        assert_matches!(
+            GtpuHeader::read(&mut Cursor::new(&bytes[..len])),
+            Err(ReadError::IoError(_))
+        );
+    }
+    // version
+    for version in [0u8, 2, 7] {
+        let mut bytes = bytes.clone();
+        bytes[0] = (bytes[0] & 0b0001_1111) | (version << 5);
+        assert_matches!(
+            GtpuHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GtpuUnsupportedVersion(v)) if v == version
+        );
+        assert_matches!(
+            GtpuHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GtpuUnsupportedVersion(v)) if v == version
+        );
+    }
+    // zero extension length
+    {
+        let mut bytes = bytes.clone();
+        bytes[12] = 0;
+        assert_matches!(
+            GtpuHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GtpuExtensionLengthBad(12))
+        );
+        assert_matches!(
+            GtpuHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GtpuExtensionLengthBad(12))
+        );
+    }
+    // extension headers exceeding the maximum length
+    {
+        let mut bytes = bytes.to_vec();
+        bytes[12] = 0xff;
+        bytes.resize(12 + 0xff*4, 0);
+        assert_matches!(
+            GtpuHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GtpuExtensionLengthBad(12))
+        );
+        assert_matches!(
+            GtpuHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GtpuExtensionLengthBad(12))
+        );
+    }
+    // extension headers filling the maximum length followed by an other extension
+    {
+        let mut bytes = bytes.to_vec();
+        bytes[12] = (GtpuHeader::MAX_EXTENSIONS_LEN / 4) as u8;
+        bytes.resize(GtpuHeader::MAX_LEN, 0);
+        bytes[GtpuHeader::MAX_LEN - 1] = 1;
+        bytes.extend_from_slice(&[1, 0, 0, 0]);
+        assert_matches!(
+            GtpuHeaderSlice::from_slice(&bytes),
+            Err(ReadError::GtpuExtensionLengthBad(GtpuHeader::MAX_LEN))
+        );
+        assert_matches!(
+            GtpuHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::GtpuExtensionLengthBad(GtpuHeader::MAX_LEN))
+        );
+    }
+}
+
+#[test]
+fn debug_eq_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut header = GtpuHeader::g_pdu(1);
+    header.set_extensions(&[GtpuExtension{ extension_type: 0x85, content: &[1, 2] }]).unwrap();
+    assert_eq!(
+        "GtpuHeader { message_type: 255, length: 0, teid: 1, sequence_number: None, n_pdu_number: None, extensions: [GtpuExtension { extension_type: 133, content: [1, 2] }] }",
+        format!("{:?}", header)
+    );
+
+    // data beyond the extensions is ignored
+    let mut other = header.clone();
+    other.set_extensions(&[GtpuExtension{ extension_type: 0x85, content: &[1, 2, 3, 4, 5, 6] }]).unwrap();
+    other.set_extensions(&[GtpuExtension{ extension_type: 0x85, content: &[1, 2] }]).unwrap();
+    assert_eq!(header, other);
+    let hash = |value: &GtpuHeader| {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(&header), hash(&other));
+
+    other.teid = 2;
+    assert_ne!(header, other);
+}
+
+/// Returns an ip packet containing an udp packet with the given payload.
+fn inner_packet(payload: &[u8]) -> Vec<u8> {
+    let mut inner = Vec::new();
+    PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut inner, payload)
+        .unwrap();
+    inner
+}
+
+/// Returns an ipv4 & udp packet containing the gtp-u header & the payload.
+fn gtpu_packet(header: &GtpuHeader, payload: &[u8]) -> Vec<u8> {
+    let mut udp_payload = header.to_bytes().unwrap().to_vec();
+    udp_payload.extend_from_slice(payload);
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(50000, GtpuHeader::UDP_PORT)
+        .write(&mut packet, &udp_payload)
+        .unwrap();
+    packet
+}
+
+#[test]
+fn sliced_packet() {
+    let inner = inner_packet(&[1, 2, 3, 4]);
+    let mut header = GtpuHeader::g_pdu(0x1234);
+    header.sequence_number = Some(1);
+    header.length = (4 + inner.len()) as u16;
+    let packet = gtpu_packet(&header, &inner);
+    let registry = ParserRegistry::with_well_known_ports();
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(ApplicationProtocol::GtpU), sliced.application);
+        assert_eq!(header, sliced.gtpu.unwrap().to_header());
+        assert_eq!(&inner[..], sliced.payload);
+
+        let sliced_inner = sliced.inner.unwrap();
+        assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(sliced_inner.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+    }
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(header), decoded.gtpu);
+        assert_eq!(&inner[..], decoded.payload);
+
+        let decoded_inner = decoded.inner.unwrap();
+        assert_matches!(decoded_inner.ip, Some(IpHeader::Version4(_, _)));
+        assert_matches!(decoded_inner.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], decoded_inner.payload);
+    }
+    // without registry the gtp-u header is left in the payload
+    {
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(sliced.gtpu.is_none());
+        assert!(sliced.inner.is_none());
+    }
+}
+
+#[test]
+fn sliced_packet_ipv6_payload() {
+    let mut inner = Vec::new();
+    PacketBuilder::ipv6([1; 16], [2; 16], 20)
+        .tcp(1234, 5678, 1, 1024)
+        .write(&mut inner, &[1, 2, 3, 4])
+        .unwrap();
+    let packet = gtpu_packet(&GtpuHeader::g_pdu(1), &inner);
+    let registry = ParserRegistry::with_well_known_ports();
+
+    let sliced_inner = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap().inner.unwrap();
+    assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv6(_, _)));
+    assert_matches!(sliced_inner.transport, Some(TransportSlice::Tcp(_)));
+
+    let decoded_inner = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap().inner.unwrap();
+    assert_matches!(decoded_inner.ip, Some(IpHeader::Version6(_, _)));
+    assert_matches!(decoded_inner.transport, Some(TransportHeader::Tcp(_)));
+}
+
+#[test]
+fn sliced_packet_no_inner() {
+    let registry = ParserRegistry::with_well_known_ports();
+
+    // signaling messages
+    {
+        let mut header = GtpuHeader::new(gtpu_message_type::ECHO_REQUEST, 0);
+        header.sequence_number = Some(1);
+        let packet = gtpu_packet(&header, &[]);
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(header, sliced.gtpu.unwrap().to_header());
+        assert!(sliced.inner.is_none());
+        let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(header), decoded.gtpu);
+        assert!(decoded.inner.is_none());
+    }
+    // G-PDU without an ip packet
+    {
+        let packet = gtpu_packet(&GtpuHeader::g_pdu(1), &[0x12, 0x34]);
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert!(sliced.gtpu.is_some());
+        assert!(sliced.inner.is_none());
+        assert_eq!(&[0x12, 0x34], sliced.payload);
+        let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+        assert!(decoded.gtpu.is_some());
+        assert!(decoded.inner.is_none());
+    }
+    // G-PDU with a truncated ip packet
+    {
+        let packet = gtpu_packet(&GtpuHeader::g_pdu(1), &[0x45, 0]);
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert!(sliced.gtpu.is_some());
+        assert!(sliced.inner.is_none());
+        assert_eq!(&[0x45, 0], sliced.payload);
+        let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+        assert!(decoded.gtpu.is_some());
+        assert!(decoded.inner.is_none());
+        assert_eq!(&[0x45, 0], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let registry = ParserRegistry::with_well_known_ports();
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(50000, GtpuHeader::UDP_PORT)
+        .write(&mut packet, &[0b0101_0000, 0xff, 0, 0, 0, 0, 0, 0])
+        .unwrap();
+    assert_matches!(
+        SlicedPacket::from_ip_with_registry(&packet, &registry),
+        Err(ReadError::GtpuUnsupportedVersion(2))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_registry(&packet, &registry),
+        Err(ReadError::GtpuUnsupportedVersion(2))
+    );
+
+    let packet = &packet[..packet.len() - 1];
+    assert_matches!(
+        SlicedPacket::from_ip_with_registry(packet, &registry),
+        Err(ReadError::UnexpectedEndOfSlice(36))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_registry(packet, &registry),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+}
+
+#[test]
+fn repair_length() {
+    let mut header = GtpuHeader::g_pdu(1);
+    header.n_pdu_number = Some(1);
+    let inner = inner_packet(&[1, 2, 3, 4]);
+    let packet = gtpu_packet(&header, &inner);
+    let registry = ParserRegistry::with_well_known_ports();
+
+    let mut decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+    etherparse::repair::fix(&mut decoded).unwrap();
+    assert_eq!((4 + inner.len()) as u16, decoded.gtpu.as_ref().unwrap().length);
+
+    let bytes = etherparse::repair::to_bytes(&decoded).unwrap();
+    let sliced = SlicedPacket::from_ip_with_registry(&bytes, &registry).unwrap();
+    assert_eq!((4 + inner.len()) as u16, sliced.gtpu.unwrap().length());
+    assert_eq!(&[1, 2, 3, 4], sliced.inner.unwrap().payload);
+}
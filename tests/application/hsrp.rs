@@ -0,0 +1,260 @@
+use super::super::*;
+
+use std::net::IpAddr;
+
+/// HSRP version 1 hello of the active router.
+const V1_HELLO: [u8;20] = [
+    0, hsrp_op_code::HELLO, 16, 3, 10, 120, 1, 0,
+    b'c', b'i', b's', b'c', b'o', 0, 0, 0,
+    192, 168, 1, 254,
+];
+
+/// HSRP version 2 group state TLV (ipv4) followed by a text authentication TLV.
+fn v2_hello(ip_version: u8, virtual_ip: &[u8]) -> Vec<u8> {
+    let mut result = vec![
+        1, 40,
+        2, hsrp_op_code::HELLO, 5, ip_version,
+        0x01, 0x02, // group
+        1, 2, 3, 4, 5, 6, // identifier
+        0, 0, 0, 100, // priority
+        0, 0, 0x0b, 0xb8, // hello time (3000 ms)
+        0, 0, 0x27, 0x10, // hold time (10000 ms)
+    ];
+    let mut ip = [0u8;16];
+    ip[..virtual_ip.len()].copy_from_slice(virtual_ip);
+    result.extend_from_slice(&ip);
+    result.extend_from_slice(&[3, 8, b'c', b'i', b's', b'c', b'o', 0, 0, 0]);
+    result
+}
+
+mod hsrp_state {
+    use super::*;
+
+    #[test]
+    fn from_v1() {
+        use HsrpState::*;
+        for (value, expected) in [(0, Initial), (1, Learn), (2, Listen), (4, Speak), (8, Standby), (16, Active)] {
+            assert_eq!(Some(expected), HsrpState::from_v1(value));
+        }
+        for value in [3, 5, 6, 7, 9, 32, 255] {
+            assert_eq!(None, HsrpState::from_v1(value));
+        }
+    }
+
+    #[test]
+    fn from_v2() {
+        use HsrpState::*;
+        for (value, expected) in [(0, Disabled), (1, Initial), (2, Learn), (3, Listen), (4, Speak), (5, Standby), (6, Active)] {
+            assert_eq!(Some(expected), HsrpState::from_v2(value));
+        }
+        for value in [7, 8, 16, 255] {
+            assert_eq!(None, HsrpState::from_v2(value));
+        }
+    }
+}
+
+mod hsrp_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        use HsrpError::*;
+        assert_eq!(
+            "HsrpError: Unexpected end of slice. The given slice contained less then minimum required 20 bytes.",
+            format!("{}", UnexpectedEndOfSlice(20))
+        );
+        assert_eq!(
+            "HsrpError: Unsupported message version (first byte 0x05). Only HSRP version 1 messages & version 2 messages starting with a group state TLV are supported.",
+            format!("{}", UnsupportedVersion(5))
+        );
+        assert_eq!(
+            "HsrpError: Invalid length 7 of the TLV with the type 3.",
+            format!("{}", InvalidTlvLength{ tlv_type: 3, len: 7 })
+        );
+        assert_eq!(
+            "HsrpError: Unsupported ip version 5 in the group state TLV (only 4 & 6 are supported).",
+            format!("{}", UnsupportedIpVersion(5))
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(HsrpError::UnsupportedVersion(5).source().is_none());
+    }
+}
+
+mod hsrp_v1_header {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        let mut data = V1_HELLO.to_vec();
+        data.push(0xff);
+        let (header, rest) = HsrpV1Header::from_slice(&data).unwrap();
+        assert_eq!(
+            HsrpV1Header {
+                op_code: hsrp_op_code::HELLO,
+                state: 16,
+                hello_time: 3,
+                hold_time: 10,
+                priority: 120,
+                group: 1,
+                reserved: 0,
+                authentication_data: *b"cisco\0\0\0",
+                virtual_ip: [192, 168, 1, 254],
+            },
+            header
+        );
+        assert_eq!(&[0xff], rest);
+        assert_eq!(Some(HsrpState::Active), header.hsrp_state());
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        for len in 0..HsrpV1Header::SERIALIZED_SIZE {
+            assert_eq!(
+                Err(HsrpError::UnexpectedEndOfSlice(HsrpV1Header::SERIALIZED_SIZE)),
+                HsrpV1Header::from_slice(&V1_HELLO[..len])
+            );
+        }
+        let mut data = V1_HELLO;
+        data[0] = 1;
+        assert_eq!(Err(HsrpError::UnsupportedVersion(1)), HsrpV1Header::from_slice(&data));
+    }
+}
+
+mod hsrp_v2_header {
+    use super::*;
+
+    #[test]
+    fn from_slice_ipv4() {
+        let header = HsrpV2Header::from_slice(&v2_hello(4, &[192, 168, 1, 254])).unwrap();
+        assert_eq!(
+            HsrpV2Header {
+                op_code: hsrp_op_code::HELLO,
+                state: 5,
+                group: 0x0102,
+                identifier: [1, 2, 3, 4, 5, 6],
+                priority: 100,
+                hello_time: 3000,
+                hold_time: 10000,
+                virtual_ip: IpAddr::from([192, 168, 1, 254]),
+                authentication_data: Some(*b"cisco\0\0\0"),
+            },
+            header
+        );
+        assert_eq!(Some(HsrpState::Standby), header.hsrp_state());
+    }
+
+    #[test]
+    fn from_slice_ipv6() {
+        let ip = [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+        let header = HsrpV2Header::from_slice(&v2_hello(6, &ip)).unwrap();
+        assert_eq!(IpAddr::from(ip), header.virtual_ip);
+    }
+
+    #[test]
+    fn from_slice_other_tlvs() {
+        // no authentication tlv
+        let data = v2_hello(4, &[192, 168, 1, 254]);
+        let header = HsrpV2Header::from_slice(&data[..42]).unwrap();
+        assert_eq!(None, header.authentication_data);
+
+        // unknown tlvs (e.g. md5 authentication) are skipped
+        let mut data = data[..42].to_vec();
+        data.extend_from_slice(&[4, 3, 1, 2, 3]);
+        let header = HsrpV2Header::from_slice(&data).unwrap();
+        assert_eq!(None, header.authentication_data);
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        use HsrpError::*;
+        let data = v2_hello(4, &[192, 168, 1, 254]);
+
+        assert_eq!(Err(UnexpectedEndOfSlice(2)), HsrpV2Header::from_slice(&data[..1]));
+        for len in 2..42 {
+            assert_eq!(Err(UnexpectedEndOfSlice(42)), HsrpV2Header::from_slice(&data[..len]));
+        }
+        // incomplete following tlv
+        assert_eq!(Err(UnexpectedEndOfSlice(44)), HsrpV2Header::from_slice(&data[..43]));
+        assert_eq!(Err(UnexpectedEndOfSlice(52)), HsrpV2Header::from_slice(&data[..51]));
+
+        // wrong tlv type
+        {
+            let mut data = data.clone();
+            data[0] = 2;
+            assert_eq!(Err(UnsupportedVersion(2)), HsrpV2Header::from_slice(&data));
+        }
+        // wrong group state tlv length
+        {
+            let mut data = data.clone();
+            data[1] = 39;
+            assert_eq!(Err(InvalidTlvLength{ tlv_type: 1, len: 39 }), HsrpV2Header::from_slice(&data));
+        }
+        // wrong version
+        {
+            let mut data = data.clone();
+            data[2] = 3;
+            assert_eq!(Err(UnsupportedVersion(3)), HsrpV2Header::from_slice(&data));
+        }
+        // wrong ip version
+        {
+            let mut data = data.clone();
+            data[5] = 5;
+            assert_eq!(Err(UnsupportedIpVersion(5)), HsrpV2Header::from_slice(&data));
+        }
+        // wrong authentication tlv length
+        {
+            let mut data = data[..42].to_vec();
+            data.extend_from_slice(&[3, 4, 1, 2, 3, 4]);
+            assert_eq!(Err(InvalidTlvLength{ tlv_type: 3, len: 4 }), HsrpV2Header::from_slice(&data));
+        }
+    }
+}
+
+mod hsrp_message {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        // version 1
+        {
+            let message = HsrpMessage::from_slice(&V1_HELLO).unwrap();
+            assert_eq!(HsrpMessage::V1(HsrpV1Header::from_slice(&V1_HELLO).unwrap().0), message);
+            assert_eq!(hsrp_op_code::HELLO, message.op_code());
+            assert_eq!(Some(HsrpState::Active), message.state());
+            assert_eq!(120, message.priority());
+            assert_eq!(1, message.group());
+            assert_eq!(IpAddr::from([192, 168, 1, 254]), message.virtual_ip());
+        }
+        // version 2
+        {
+            let data = v2_hello(4, &[10, 0, 0, 1]);
+            let message = HsrpMessage::from_slice(&data).unwrap();
+            assert_eq!(HsrpMessage::V2(HsrpV2Header::from_slice(&data).unwrap()), message);
+            assert_eq!(hsrp_op_code::HELLO, message.op_code());
+            assert_eq!(Some(HsrpState::Standby), message.state());
+            assert_eq!(100, message.priority());
+            assert_eq!(0x0102, message.group());
+            assert_eq!(IpAddr::from([10, 0, 0, 1]), message.virtual_ip());
+        }
+        // errors
+        assert_eq!(Err(HsrpError::UnexpectedEndOfSlice(1)), HsrpMessage::from_slice(&[]));
+        assert_eq!(Err(HsrpError::UnexpectedEndOfSlice(20)), HsrpMessage::from_slice(&V1_HELLO[..19]));
+        assert_eq!(Err(HsrpError::UnsupportedVersion(5)), HsrpMessage::from_slice(&[5, 0]));
+    }
+
+    #[test]
+    fn from_udp_packet() {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [224,0,0,2], 1)
+            .udp(HsrpMessage::UDP_PORT, HsrpMessage::UDP_PORT)
+            .write(&mut packet, &V1_HELLO)
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let message = HsrpMessage::from_slice(sliced.payload).unwrap();
+        assert_eq!(Some(HsrpState::Active), message.state());
+    }
+}
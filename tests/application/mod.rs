@@ -0,0 +1,8 @@
+pub mod dns_name;
+mod geneve;
+mod gtpu;
+mod hsrp;
+mod quic;
+mod rip;
+mod stamp;
+mod vxlan;
@@ -0,0 +1,124 @@
+use super::super::*;
+
+/// Returns a long header with the given connection ids followed by
+/// version specific data.
+fn long_header(first_byte: u8, version: u32, dcid: &[u8], scid: &[u8]) -> Vec<u8> {
+    let mut result = vec![first_byte];
+    result.extend_from_slice(&version.to_be_bytes());
+    result.push(dcid.len() as u8);
+    result.extend_from_slice(dcid);
+    result.push(scid.len() as u8);
+    result.extend_from_slice(scid);
+    result
+}
+
+proptest! {
+    #[test]
+    fn long(
+        first_byte in 0x80u8..=0xff,
+        version in any::<u32>(),
+        dcid in proptest::collection::vec(any::<u8>(), 0..=255),
+        scid in proptest::collection::vec(any::<u8>(), 0..=255),
+        short_header_dcid_len in any::<u8>(),
+    ) {
+        let header = long_header(first_byte, version, &dcid, &scid);
+        let mut bytes = header.clone();
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let slice = QuicHeaderSlice::from_slice(&bytes, usize::from(short_header_dcid_len)).unwrap();
+        assert_eq!(&header[..], slice.slice());
+        assert!(slice.is_long_header());
+        assert_eq!(first_byte & 0x7f, slice.version_specific_bits());
+        assert_eq!(Some(version), slice.version());
+        assert_eq!(0 == version, slice.is_version_negotiation());
+        assert_eq!(&dcid[..], slice.destination_connection_id());
+        assert_eq!(Some(&scid[..]), slice.source_connection_id());
+
+        // length errors
+        for len in 0..header.len() {
+            let expected = if 0 == len {
+                1
+            } else if len < QuicHeaderSlice::MIN_LONG_HEADER_LEN {
+                QuicHeaderSlice::MIN_LONG_HEADER_LEN
+            } else if len < QuicHeaderSlice::MIN_LONG_HEADER_LEN + dcid.len() {
+                QuicHeaderSlice::MIN_LONG_HEADER_LEN + dcid.len()
+            } else {
+                header.len()
+            };
+            assert_matches!(
+                QuicHeaderSlice::from_slice(&header[..len], 0),
+                Err(ReadError::UnexpectedEndOfSlice(value)) if value == expected
+            );
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn short(
+        first_byte in 0u8..0x80,
+        dcid in proptest::collection::vec(any::<u8>(), 0..=20),
+    ) {
+        let mut bytes = vec![first_byte];
+        bytes.extend_from_slice(&dcid);
+        bytes.extend_from_slice(&[1, 2, 3]);
+
+        let slice = QuicHeaderSlice::from_slice(&bytes, dcid.len()).unwrap();
+        assert_eq!(&bytes[..1 + dcid.len()], slice.slice());
+        assert!(!slice.is_long_header());
+        assert_eq!(first_byte, slice.version_specific_bits());
+        assert_eq!(None, slice.version());
+        assert!(!slice.is_version_negotiation());
+        assert_eq!(&dcid[..], slice.destination_connection_id());
+        assert_eq!(None, slice.source_connection_id());
+
+        // length errors
+        assert_matches!(
+            QuicHeaderSlice::from_slice(&[], dcid.len()),
+            Err(ReadError::UnexpectedEndOfSlice(1))
+        );
+        assert_matches!(
+            QuicHeaderSlice::from_slice(&bytes, bytes.len()),
+            Err(ReadError::UnexpectedEndOfSlice(value)) if value == bytes.len() + 1
+        );
+    }
+}
+
+#[test]
+fn version_negotiation() {
+    // version negotiation packet with a supported version list
+    let mut bytes = long_header(0xaa, QuicHeaderSlice::VERSION_NEGOTIATION, &[1, 2], &[3, 4, 5]);
+    bytes.extend_from_slice(&1u32.to_be_bytes());
+    let slice = QuicHeaderSlice::from_slice(&bytes, 0).unwrap();
+    assert!(slice.is_version_negotiation());
+    assert_eq!(&[1, 2], slice.destination_connection_id());
+    assert_eq!(Some(&[3, 4, 5][..]), slice.source_connection_id());
+    assert_eq!(&1u32.to_be_bytes(), &bytes[slice.slice().len()..]);
+}
+
+#[test]
+fn debug_clone_eq() {
+    let bytes = [0x40, 1, 2];
+    let slice = QuicHeaderSlice::from_slice(&bytes, 2).unwrap();
+    assert_eq!(slice, slice.clone());
+    assert_eq!(
+        format!("QuicHeaderSlice {{ slice: {:?} }}", &bytes[..]),
+        format!("{:?}", slice)
+    );
+}
+
+#[test]
+fn sliced_packet() {
+    let registry = ParserRegistry::with_well_known_ports();
+    let quic = [0x41, 1, 2, 3, 4, 5, 6, 7, 8, 0xff];
+    let mut packet = Vec::new();
+    PacketBuilder::ipv6([1;16], [2;16], 20)
+        .udp(QuicHeaderSlice::UDP_PORT, 50000)
+        .write(&mut packet, &quic)
+        .unwrap();
+
+    let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+    assert_eq!(Some(ApplicationProtocol::Quic), sliced.application);
+    let header = QuicHeaderSlice::from_slice(sliced.payload, 8).unwrap();
+    assert_eq!(&quic[1..9], header.destination_connection_id());
+}
@@ -0,0 +1,139 @@
+use super::super::*;
+
+/// Version 2 response with an authentication entry & two routes.
+const V2_RESPONSE: [u8;64] = [
+    2, 2, 0, 0,
+    // authentication
+    0xff, 0xff, 0, 2,
+    b's', b'e', b'c', b'r', b'e', b't', 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0,
+    // route 1
+    0, 2, 0, 7,
+    10, 0, 0, 0,
+    255, 0, 0, 0,
+    192, 168, 1, 2,
+    0, 0, 0, 1,
+    // route 2 (unreachable)
+    0, 2, 0, 0,
+    172, 16, 0, 0,
+    255, 240, 0, 0,
+    0, 0, 0, 0,
+    0, 0, 0, 16,
+];
+
+mod rip_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        use RipError::*;
+        assert_eq!(
+            "RipError: Unexpected end of slice. The given slice contained less then minimum required 4 bytes.",
+            format!("{}", UnexpectedEndOfSlice(4))
+        );
+        assert_eq!(
+            "RipError: Invalid message length 5 (the entries after the 4 byte header have to be a multiple of 20 bytes).",
+            format!("{}", InvalidLength(5))
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(RipError::InvalidLength(5).source().is_none());
+    }
+}
+
+mod rip_message_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        let rip = RipMessageSlice::from_slice(&V2_RESPONSE).unwrap();
+        assert_eq!(&V2_RESPONSE[..], rip.slice());
+        assert_eq!(rip_command::RESPONSE, rip.command());
+        assert_eq!(2, rip.version());
+        assert_eq!(3, rip.entry_count());
+        assert_eq!(
+            vec![
+                RipEntry::Authentication {
+                    authentication_type: 2,
+                    data: *b"secret\0\0\0\0\0\0\0\0\0\0",
+                },
+                RipEntry::Route(RipRouteEntry {
+                    address_family: 2,
+                    route_tag: 7,
+                    ip_address: [10, 0, 0, 0],
+                    subnet_mask: [255, 0, 0, 0],
+                    next_hop: [192, 168, 1, 2],
+                    metric: 1,
+                }),
+                RipEntry::Route(RipRouteEntry {
+                    address_family: 2,
+                    route_tag: 0,
+                    ip_address: [172, 16, 0, 0],
+                    subnet_mask: [255, 240, 0, 0],
+                    next_hop: [0, 0, 0, 0],
+                    metric: 16,
+                }),
+            ],
+            rip.entries().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn from_slice_v1_request() {
+        // request for the complete routing table
+        let mut message = [0u8;24];
+        message[0] = rip_command::REQUEST;
+        message[1] = 1;
+        message[23] = 16;
+        let rip = RipMessageSlice::from_slice(&message).unwrap();
+        assert_eq!(rip_command::REQUEST, rip.command());
+        assert_eq!(1, rip.version());
+        assert_eq!(
+            vec![RipEntry::Route(RipRouteEntry {
+                address_family: 0,
+                route_tag: 0,
+                ip_address: [0;4],
+                subnet_mask: [0;4],
+                next_hop: [0;4],
+                metric: 16,
+            })],
+            rip.entries().collect::<Vec<_>>()
+        );
+
+        // no entries
+        let rip = RipMessageSlice::from_slice(&message[..4]).unwrap();
+        assert_eq!(0, rip.entry_count());
+        assert_eq!(None, rip.entries().next());
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        for len in 0..4 {
+            assert_eq!(
+                Err(RipError::UnexpectedEndOfSlice(4)),
+                RipMessageSlice::from_slice(&V2_RESPONSE[..len])
+            );
+        }
+        for len in (5..64).filter(|len| 0 != (len - 4) % 20) {
+            assert_eq!(
+                Err(RipError::InvalidLength(len)),
+                RipMessageSlice::from_slice(&V2_RESPONSE[..len])
+            );
+        }
+    }
+
+    #[test]
+    fn from_udp_packet() {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [224,0,0,9], 1)
+            .udp(RipMessageSlice::UDP_PORT, RipMessageSlice::UDP_PORT)
+            .write(&mut packet, &V2_RESPONSE)
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let rip = RipMessageSlice::from_slice(sliced.payload).unwrap();
+        assert_eq!(3, rip.entry_count());
+    }
+}
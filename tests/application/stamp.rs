@@ -0,0 +1,330 @@
+use super::super::*;
+
+use std::time::Duration;
+
+mod stamp_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "StampError: Unexpected end of slice. The given slice contained less then minimum required 14 bytes.",
+            format!("{}", StampError::UnexpectedEndOfSlice(14))
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(StampError::UnexpectedEndOfSlice(14).source().is_none());
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let value = StampError::UnexpectedEndOfSlice(14);
+        assert_eq!(value, value.clone());
+        assert_eq!("UnexpectedEndOfSlice(14)", format!("{:?}", value));
+    }
+}
+
+mod ntp_timestamp {
+    use super::*;
+
+    #[test]
+    fn u64_conversion() {
+        let value = NtpTimestamp::from_u64(0x1234_5678_9abc_def0);
+        assert_eq!(
+            NtpTimestamp {
+                seconds: 0x1234_5678,
+                fraction: 0x9abc_def0,
+            },
+            value
+        );
+        assert_eq!(0x1234_5678_9abc_def0, value.to_u64());
+    }
+
+    #[test]
+    fn unix_conversion() {
+        // unix epoch
+        assert_eq!(
+            NtpTimestamp {
+                seconds: 2_208_988_800,
+                fraction: 0,
+            },
+            NtpTimestamp::from_unix_duration(Duration::ZERO)
+        );
+
+        // half a second
+        let value = NtpTimestamp::from_unix_duration(Duration::from_millis(1_500));
+        assert_eq!(2_208_988_801, value.seconds);
+        assert_eq!(0x8000_0000, value.fraction);
+        assert_eq!(Some(Duration::from_millis(1_500)), value.to_unix_duration());
+
+        // round trip with less then a nanosecond error
+        let duration = Duration::new(1_700_000_000, 123_456_789);
+        let back = NtpTimestamp::from_unix_duration(duration).to_unix_duration().unwrap();
+        assert!(duration - back < Duration::from_nanos(2));
+
+        // before the unix epoch
+        assert_eq!(None, NtpTimestamp{ seconds: 1, fraction: 0 }.to_unix_duration());
+    }
+}
+
+mod stamp_error_estimate {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn u16_conversion(value in any::<u16>()) {
+            let decoded = StampErrorEstimate::from_u16(value);
+            assert_eq!(0 != value & 0x8000, decoded.synchronized);
+            assert_eq!(0 != value & 0x4000, decoded.ptp_format);
+            assert_eq!(((value >> 8) & 0x3f) as u8, decoded.scale);
+            assert_eq!(value as u8, decoded.multiplier);
+            assert_eq!(value, decoded.to_u16());
+        }
+    }
+
+    #[test]
+    fn to_u16_scale_limit() {
+        let value = StampErrorEstimate {
+            synchronized: false,
+            ptp_format: false,
+            scale: 0xff,
+            multiplier: 0,
+        };
+        assert_eq!(0x3f00, value.to_u16());
+    }
+
+    #[test]
+    fn error() {
+        let estimate = |scale: u8, multiplier: u8| StampErrorEstimate {
+            synchronized: true,
+            ptp_format: false,
+            scale,
+            multiplier,
+        };
+        assert_eq!(Duration::ZERO, estimate(0, 0).error());
+        assert_eq!(Duration::from_secs(1), estimate(32, 1).error());
+        assert_eq!(Duration::from_millis(500), estimate(31, 1).error());
+        assert_eq!(Duration::from_secs(3), estimate(32, 3).error());
+    }
+}
+
+mod stamp_sender_packet {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(862, StampSenderPacket::UDP_PORT);
+        assert_eq!(14, StampSenderPacket::MIN_LEN);
+        assert_eq!(44, StampSenderPacket::SERIALIZED_SIZE);
+    }
+
+    proptest! {
+        #[test]
+        fn to_bytes_from_slice(
+            sequence_number in any::<u32>(),
+            timestamp in any::<u64>(),
+            error_estimate in any::<u16>(),
+            padding in proptest::collection::vec(any::<u8>(), 0..40),
+        ) {
+            let packet = StampSenderPacket {
+                sequence_number,
+                timestamp,
+                error_estimate: StampErrorEstimate::from_u16(error_estimate),
+            };
+            let bytes = packet.to_bytes();
+            assert_eq!(&sequence_number.to_be_bytes(), &bytes[0..4]);
+            assert_eq!(&timestamp.to_be_bytes(), &bytes[4..12]);
+            assert_eq!(&error_estimate.to_be_bytes(), &bytes[12..14]);
+            assert_eq!(&[0u8;30], &bytes[14..]);
+
+            // write
+            {
+                let mut buffer = Vec::new();
+                packet.write(&mut buffer).unwrap();
+                assert_eq!(&bytes[..], &buffer[..]);
+            }
+
+            // stamp packet
+            {
+                let (decoded, rest) = StampSenderPacket::from_slice(&bytes).unwrap();
+                assert_eq!(packet, decoded);
+                assert_eq!(&bytes[14..], rest);
+            }
+
+            // twamp packet with padding
+            {
+                let mut buffer = Vec::new();
+                buffer.extend_from_slice(&bytes[..14]);
+                buffer.extend_from_slice(&padding);
+                let (decoded, rest) = StampSenderPacket::from_slice(&buffer).unwrap();
+                assert_eq!(packet, decoded);
+                assert_eq!(&padding[..], rest);
+            }
+
+            // length error
+            for len in 0..14 {
+                assert_eq!(
+                    Err(StampError::UnexpectedEndOfSlice(14)),
+                    StampSenderPacket::from_slice(&bytes[..len])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn write_error() {
+        let mut buffer = [0u8;43];
+        let mut cursor = std::io::Cursor::new(&mut buffer[..]);
+        assert_matches!(
+            StampSenderPacket::default().write(&mut cursor),
+            Err(WriteError::IoError(_))
+        );
+    }
+}
+
+mod stamp_reflector_packet {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(41, StampReflectorPacket::MIN_LEN);
+        assert_eq!(44, StampReflectorPacket::SERIALIZED_SIZE);
+    }
+
+    proptest! {
+        #[test]
+        fn to_bytes_from_slice(
+            sequence_number in any::<u32>(),
+            timestamp in any::<u64>(),
+            error_estimate in any::<u16>(),
+            receive_timestamp in any::<u64>(),
+            sender_sequence_number in any::<u32>(),
+            sender_timestamp in any::<u64>(),
+            sender_error_estimate in any::<u16>(),
+            sender_ttl in any::<u8>(),
+        ) {
+            let packet = StampReflectorPacket {
+                sequence_number,
+                timestamp,
+                error_estimate: StampErrorEstimate::from_u16(error_estimate),
+                receive_timestamp,
+                sender_sequence_number,
+                sender_timestamp,
+                sender_error_estimate: StampErrorEstimate::from_u16(sender_error_estimate),
+                sender_ttl,
+            };
+            let bytes = packet.to_bytes();
+            assert_eq!(&sequence_number.to_be_bytes(), &bytes[0..4]);
+            assert_eq!(&timestamp.to_be_bytes(), &bytes[4..12]);
+            assert_eq!(&error_estimate.to_be_bytes(), &bytes[12..14]);
+            assert_eq!(&[0, 0], &bytes[14..16]);
+            assert_eq!(&receive_timestamp.to_be_bytes(), &bytes[16..24]);
+            assert_eq!(&sender_sequence_number.to_be_bytes(), &bytes[24..28]);
+            assert_eq!(&sender_timestamp.to_be_bytes(), &bytes[28..36]);
+            assert_eq!(&sender_error_estimate.to_be_bytes(), &bytes[36..38]);
+            assert_eq!(&[0, 0], &bytes[38..40]);
+            assert_eq!(sender_ttl, bytes[40]);
+            assert_eq!(&[0, 0, 0], &bytes[41..44]);
+
+            // write
+            {
+                let mut buffer = Vec::new();
+                packet.write(&mut buffer).unwrap();
+                assert_eq!(&bytes[..], &buffer[..]);
+            }
+
+            // decode
+            {
+                let (decoded, rest) = StampReflectorPacket::from_slice(&bytes).unwrap();
+                assert_eq!(packet, decoded);
+                assert_eq!(&bytes[41..], rest);
+            }
+
+            // length error
+            for len in 0..41 {
+                assert_eq!(
+                    Err(StampError::UnexpectedEndOfSlice(41)),
+                    StampReflectorPacket::from_slice(&bytes[..len])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reflect() {
+        let sender = StampSenderPacket {
+            sequence_number: 5,
+            timestamp: 100,
+            error_estimate: StampErrorEstimate::from_u16(0x8001),
+        };
+        assert_eq!(
+            StampReflectorPacket {
+                sequence_number: 9,
+                timestamp: 300,
+                error_estimate: StampErrorEstimate::from_u16(0x8001),
+                receive_timestamp: 200,
+                sender_sequence_number: 5,
+                sender_timestamp: 100,
+                sender_error_estimate: StampErrorEstimate::from_u16(0x8001),
+                sender_ttl: 255,
+            },
+            StampReflectorPacket::reflect(&sender, 9, 200, 300, 255)
+        );
+    }
+
+    #[test]
+    fn round_trip_time() {
+        let second = 1u64 << 32;
+        let sender = StampSenderPacket {
+            sequence_number: 0,
+            timestamp: 10*second,
+            error_estimate: StampErrorEstimate::default(),
+        };
+        // 1 second to the reflector, 2 seconds processing, 1 second back
+        let reflected = StampReflectorPacket::reflect(&sender, 0, 11*second, 13*second, 64);
+        assert_eq!(Some(Duration::from_secs(2)), reflected.round_trip_time(14*second));
+
+        // inconsistent timestamps
+        assert_eq!(None, reflected.round_trip_time(9*second));
+        assert_eq!(None, reflected.round_trip_time(11*second));
+        {
+            let mut value = reflected.clone();
+            value.timestamp = 10*second;
+            assert_eq!(None, value.round_trip_time(14*second));
+        }
+
+        // ptp timestamps
+        {
+            let mut value = reflected.clone();
+            value.error_estimate.ptp_format = true;
+            assert_eq!(None, value.round_trip_time(14*second));
+        }
+        {
+            let mut value = reflected.clone();
+            value.sender_error_estimate.ptp_format = true;
+            assert_eq!(None, value.round_trip_time(14*second));
+        }
+    }
+
+    #[test]
+    fn sliced_packet() {
+        let registry = ParserRegistry::with_well_known_ports();
+        let sender = StampSenderPacket {
+            sequence_number: 3,
+            timestamp: 1234,
+            error_estimate: StampErrorEstimate::default(),
+        };
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 255)
+            .udp(50000, StampSenderPacket::UDP_PORT)
+            .write(&mut packet, &sender.to_bytes())
+            .unwrap();
+
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(ApplicationProtocol::Stamp), sliced.application);
+        assert_eq!(sender, StampSenderPacket::from_slice(sliced.payload).unwrap().0);
+    }
+}
@@ -0,0 +1,227 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn vxlan_any()(
+        flags in any::<u8>(),
+        vni in 0..=VxlanHeader::MAX_VNI,
+    ) -> VxlanHeader {
+        VxlanHeader {
+            flags,
+            vni,
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in vxlan_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(VxlanHeader::SERIALIZED_SIZE + 2, buffer.len());
+        assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..VxlanHeader::SERIALIZED_SIZE]);
+
+        // from_slice
+        {
+            let (decoded, rest) = VxlanHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &VxlanHeader::read(&mut cursor).unwrap());
+            assert_eq!(VxlanHeader::SERIALIZED_SIZE as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = VxlanHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..VxlanHeader::SERIALIZED_SIZE], slice.slice());
+            assert_eq!(header.flags, slice.flags());
+            assert_eq!(header.vni, slice.vni());
+            assert_eq!(header.vni_valid(), slice.vni_valid());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn vni_too_large(vni in (VxlanHeader::MAX_VNI + 1)..=u32::MAX) {
+        let expected = ValueError::U32TooLarge{
+            value: vni,
+            max: VxlanHeader::MAX_VNI,
+            field: ErrorField::VxlanVni
+        };
+        assert_eq!(Err(expected.clone()), VxlanHeader::new(vni));
+        let header = VxlanHeader{ flags: VxlanHeader::FLAG_VNI_VALID, vni };
+        assert_eq!(Err(expected.clone()), header.to_bytes());
+        assert_eq!(Some(expected), header.write(&mut Vec::new()).unwrap_err().value_error());
+    }
+}
+
+#[test]
+fn new() {
+    let header = VxlanHeader::new(0x123456).unwrap();
+    assert!(header.vni_valid());
+    assert_eq!(
+        [0x08, 0, 0, 0, 0x12, 0x34, 0x56, 0],
+        header.to_bytes().unwrap()
+    );
+    assert!(!VxlanHeader{ flags: 0, vni: 0 }.vni_valid());
+}
+
+#[test]
+fn from_slice_error() {
+    for len in 0..VxlanHeader::SERIALIZED_SIZE {
+        let buffer = [0u8; VxlanHeader::SERIALIZED_SIZE];
+        assert_matches!(
+            VxlanHeader::from_slice(&buffer[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(8))
+        );
+        assert_matches!(
+            VxlanHeaderSlice::from_slice(&buffer[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(8))
+        );
+        assert!(VxlanHeader::read(&mut Cursor::new(&buffer[..len])).is_err());
+    }
+}
+
+/// Returns an ipv4 packet containing the vxlan header & the inner frame.
+fn vxlan_packet(vni: u32, inner: &[u8]) -> Vec<u8> {
+    let mut udp_payload = Vec::new();
+    VxlanHeader::new(vni).unwrap().write(&mut udp_payload).unwrap();
+    udp_payload.extend_from_slice(inner);
+
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(50000, VxlanHeader::UDP_PORT)
+        .write(&mut packet, &udp_payload)
+        .unwrap();
+    packet
+}
+
+/// Returns an ethernet II frame containing an udp packet with the given payload.
+fn inner_frame(payload: &[u8]) -> Vec<u8> {
+    let mut inner = Vec::new();
+    PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+        .ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut inner, payload)
+        .unwrap();
+    inner
+}
+
+#[test]
+fn sliced_packet() {
+    let inner = inner_frame(&[1, 2, 3, 4]);
+    let packet = vxlan_packet(1234, &inner);
+    let registry = ParserRegistry::with_well_known_ports();
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(ApplicationProtocol::Vxlan), sliced.application);
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(VxlanHeader::new(1234).unwrap(), sliced.vxlan.as_ref().unwrap().to_header());
+        assert_eq!(&inner[..], sliced.payload);
+
+        let sliced_inner = sliced.inner.unwrap();
+        assert_eq!([1, 2, 3, 4, 5, 6], sliced_inner.link.unwrap().to_header().source);
+        assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(sliced_inner.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+    }
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+        assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(Some(VxlanHeader::new(1234).unwrap()), decoded.vxlan);
+        assert_eq!(&inner[..], decoded.payload);
+
+        let decoded_inner = decoded.inner.unwrap();
+        assert_eq!([1, 2, 3, 4, 5, 6], decoded_inner.link.unwrap().source);
+        assert_matches!(decoded_inner.ip, Some(IpHeader::Version4(_, _)));
+        assert_matches!(decoded_inner.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], decoded_inner.payload);
+    }
+    // without registry
+    {
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(None, sliced.vxlan);
+        assert_eq!(None, sliced.inner);
+        assert_eq!(&packet[28..], sliced.payload);
+
+        let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert_eq!(None, decoded.vxlan);
+        assert_eq!(None, decoded.inner);
+        assert_eq!(&packet[28..], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_nesting_depth() {
+    // 5 nested vxlan tunnels, only the first 4 get decoded
+    let mut packet = inner_frame(&[1, 2, 3, 4]);
+    for vni in 0..5 {
+        let ip = vxlan_packet(vni, &packet);
+        packet = Vec::new();
+        Ethernet2Header {
+            source: [0; 6],
+            destination: [0; 6],
+            ether_type: ether_type::IPV4,
+        }.write(&mut packet).unwrap();
+        packet.extend_from_slice(&ip);
+    }
+    let registry = ParserRegistry::with_well_known_ports();
+
+    let mut sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+    for vni in (1..5).rev() {
+        assert_eq!(vni, sliced.vxlan.unwrap().vni());
+        sliced = *sliced.inner.unwrap();
+    }
+    assert_eq!(None, sliced.vxlan);
+    assert_eq!(None, sliced.inner);
+    assert_eq!(Some(ApplicationProtocol::Vxlan), sliced.application);
+
+    let mut decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+    for vni in (1..5).rev() {
+        assert_eq!(vni, decoded.vxlan.unwrap().vni);
+        decoded = *decoded.inner.unwrap();
+    }
+    assert_eq!(None, decoded.vxlan);
+    assert_eq!(None, decoded.inner);
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let registry = ParserRegistry::with_well_known_ports();
+    // truncated vxlan header
+    {
+        let packet = vxlan_packet(1, &[]);
+        let packet = &packet[..packet.len() - 1];
+        assert_matches!(
+            SlicedPacket::from_ip_with_registry(packet, &registry),
+            Err(ReadError::UnexpectedEndOfSlice(36))
+        );
+        assert_matches!(
+            PacketHeaders::from_ip_slice_with_registry(packet, &registry),
+            Err(ReadError::UnexpectedEndOfSlice(8))
+        );
+    }
+    // truncated inner frame (left in the payload)
+    {
+        let packet = vxlan_packet(1, &[1, 2, 3]);
+
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert!(sliced.vxlan.is_some());
+        assert!(sliced.inner.is_none());
+        assert_eq!(&[1, 2, 3], sliced.payload);
+
+        let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+        assert!(decoded.vxlan.is_some());
+        assert!(decoded.inner.is_none());
+        assert_eq!(&[1, 2, 3], decoded.payload);
+    }
+}
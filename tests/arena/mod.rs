@@ -0,0 +1,72 @@
+use super::*;
+
+#[test]
+fn insert_get_remove() {
+    let mut arena = PacketArena::new(8);
+    assert_eq!(8, arena.slot_len());
+    assert!(arena.is_empty());
+    assert_eq!(0, arena.slots());
+
+    let a = arena.insert(&[1, 2, 3]).unwrap();
+    let b = arena.insert(&[4, 5, 6, 7, 8, 9, 10, 11]).unwrap();
+    let c = arena.insert(&[]).unwrap();
+    assert_eq!(3, arena.len());
+    assert_eq!(3, arena.slots());
+    assert_eq!(Some(&[1, 2, 3][..]), arena.get(a));
+    assert_eq!(Some(&[4, 5, 6, 7, 8, 9, 10, 11][..]), arena.get(b));
+    assert_eq!(Some(&[][..]), arena.get(c));
+
+    // too big packets are rejected
+    assert_eq!(None, arena.insert(&[0; 9]));
+    assert_eq!(3, arena.len());
+
+    // modify
+    arena.get_mut(a).unwrap()[0] = 10;
+    assert_eq!(Some(&[10, 2, 3][..]), arena.get(a));
+
+    // remove
+    assert!(arena.remove(a));
+    assert!(!arena.remove(a));
+    assert!(!arena.contains(a));
+    assert_eq!(None, arena.get(a));
+    assert_eq!(None, arena.get_mut(a));
+    assert_eq!(2, arena.len());
+
+    // the freed slot is reused, but the old handle stays invalid
+    let d = arena.insert(&[7]).unwrap();
+    assert_eq!(a.index(), d.index());
+    assert_ne!(a, d);
+    assert_eq!(None, arena.get(a));
+    assert_eq!(Some(&[7][..]), arena.get(d));
+    assert_eq!(3, arena.slots());
+}
+
+#[test]
+fn clear_iter() {
+    let mut arena = PacketArena::with_capacity(4, 2);
+    let a = arena.insert(&[1]).unwrap();
+    let b = arena.insert(&[2, 3]).unwrap();
+    let c = arena.insert(&[4, 5, 6]).unwrap();
+    arena.remove(b);
+    assert_eq!(
+        vec![(a, &[1][..]), (c, &[4, 5, 6][..])],
+        arena.iter().collect::<Vec<_>>()
+    );
+
+    arena.clear();
+    assert!(arena.is_empty());
+    assert_eq!(None, arena.iter().next());
+    for handle in [a, b, c] {
+        assert!(!arena.contains(handle));
+    }
+    // the slots are reused in order
+    assert_eq!(0, arena.insert(&[1]).unwrap().index());
+    assert_eq!(1, arena.insert(&[1]).unwrap().index());
+    assert_eq!(3, arena.slots());
+}
+
+#[test]
+#[should_panic]
+fn zero_slot_len() {
+    PacketArena::new(0);
+}
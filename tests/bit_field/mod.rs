@@ -0,0 +1,121 @@
+use super::*;
+
+use proptest::prelude::*;
+
+#[test]
+fn read() {
+    let data = [0b1011_0001, 0x23, 0xff, 0x00];
+    let mut reader = BitReader::new(&data);
+    assert_eq!(32, reader.remaining_bits());
+    assert_eq!(0b101, reader.read(3).unwrap());
+    assert!(reader.read_bool().unwrap());
+    assert_eq!(0x123, reader.read(12).unwrap());
+    assert_eq!(16, reader.position());
+    assert_eq!(0, reader.read(0).unwrap());
+    reader.skip(4).unwrap();
+    assert_eq!(0xf0, reader.read(8).unwrap());
+    assert_eq!(4, reader.remaining_bits());
+
+    // too few bits left
+    assert_matches!(reader.read(5), Err(ReadError::UnexpectedEndOfSlice(5)));
+    assert_matches!(reader.skip(5), Err(ReadError::UnexpectedEndOfSlice(5)));
+    // position is unchanged after an error
+    assert_eq!(28, reader.position());
+    assert_eq!(0, reader.read(4).unwrap());
+    assert_matches!(BitReader::new(&[]).read_bool(), Err(ReadError::UnexpectedEndOfSlice(1)));
+
+    // 64 bit values
+    let data = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x11];
+    let mut reader = BitReader::new(&data);
+    reader.skip(4).unwrap();
+    assert_eq!(0x2345_6789_abcd_ef01, reader.read(64).unwrap());
+}
+
+#[test]
+#[should_panic]
+fn read_too_many_bits() {
+    let _ = BitReader::new(&[0;16]).read(65);
+}
+
+#[test]
+fn write() {
+    let mut data = [0u8;4];
+    {
+        let mut writer = BitWriter::new(&mut data);
+        assert_eq!(32, writer.remaining_bits());
+        writer.write(0b101, 3).unwrap();
+        writer.write_bool(true).unwrap();
+        writer.write(0x123, 12).unwrap();
+        assert_eq!(16, writer.position());
+        writer.write(0, 0).unwrap();
+
+        // value too large for the bit length
+        assert_matches!(
+            writer.write(0x10, 4),
+            Err(WriteError::ValueError(ValueError::BitFieldValueTooLarge{ value: 0x10, bit_len: 4 }))
+        );
+        // slice too small
+        assert_matches!(writer.write(0, 17), Err(WriteError::SliceTooSmall(5)));
+        assert_matches!(writer.skip(17), Err(WriteError::SliceTooSmall(5)));
+        assert_eq!(16, writer.position());
+
+        writer.skip(4).unwrap();
+        writer.write(0xab, 8).unwrap();
+    }
+    assert_eq!([0b1011_0001, 0x23, 0x0a, 0xb0], data);
+
+    // bits that are not written keep their value
+    let mut data = [0xffu8;2];
+    {
+        let mut writer = BitWriter::new(&mut data);
+        writer.skip(3).unwrap();
+        writer.write(0, 6).unwrap();
+    }
+    assert_eq!([0b1110_0000, 0b0111_1111], data);
+
+    // 64 bit values
+    let mut data = [0u8;9];
+    {
+        let mut writer = BitWriter::new(&mut data);
+        writer.skip(4).unwrap();
+        writer.write(u64::MAX, 64).unwrap();
+    }
+    assert_eq!([0x0f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xf0], data);
+}
+
+#[test]
+#[should_panic]
+fn write_too_many_bits() {
+    let _ = BitWriter::new(&mut [0;16]).write(0, 65);
+}
+
+proptest! {
+    #[test]
+    fn write_read(
+        fields in proptest::collection::vec((1..=64u8, any::<u64>()), 0..20)
+    ) {
+        let fields: Vec<(u8, u64)> = fields.into_iter().map(|(bit_len, value)| {
+            if bit_len < 64 {
+                (bit_len, value & ((1u64 << bit_len) - 1))
+            } else {
+                (bit_len, value)
+            }
+        }).collect();
+        let bit_len: usize = fields.iter().map(|(b, _)| usize::from(*b)).sum();
+
+        let mut data = vec![0u8; bit_len.div_ceil(8)];
+        {
+            let mut writer = BitWriter::new(&mut data);
+            for (b, v) in &fields {
+                writer.write(*v, *b).unwrap();
+            }
+            assert_eq!(bit_len, writer.position());
+        }
+
+        let mut reader = BitReader::new(&data);
+        for (b, v) in &fields {
+            assert_eq!(*v, reader.read(*b).unwrap());
+        }
+        assert!(reader.remaining_bits() < 8);
+    }
+}
@@ -0,0 +1,192 @@
+use super::*;
+
+const SOURCE_MAC: [u8;6] = [0,0,0,0,0,1];
+const DESTINATION_MAC: [u8;6] = [0,0,0,0,0,2];
+
+fn hashes(packet: &[u8]) -> [u32;3] {
+    let sliced = SlicedPacket::from_ethernet(packet).unwrap();
+    [
+        BondHashPolicy::Layer2.hash(&sliced),
+        BondHashPolicy::Layer2And3.hash(&sliced),
+        BondHashPolicy::Layer3And4.hash(&sliced),
+    ]
+}
+
+fn ipv4() -> PacketBuilderStep<IpHeader> {
+    PacketBuilder::ethernet2(SOURCE_MAC, DESTINATION_MAC)
+        .ipv4([192,168,1,1], [192,168,1,2], 64)
+}
+
+fn write<B>(builder: impl FnOnce(&mut Vec<u8>) -> Result<(), B>) -> Vec<u8>
+where B: std::fmt::Debug {
+    let mut result = Vec::new();
+    builder(&mut result).unwrap();
+    result
+}
+
+#[test]
+fn ipv4_udp_tcp() {
+    let udp = write(|buffer| ipv4().udp(1000, 53).write(buffer, &[]));
+    assert_eq!([0x803, 0x0303_0b08, 0x1b1b_6f6e], hashes(&udp));
+
+    // tcp uses the same ports layout
+    let tcp = write(|buffer| ipv4().tcp(1000, 53, 0, 1024).write(buffer, &[]));
+    assert_eq!([0x803, 0x0303_0b08, 0x1b1b_6f6e], hashes(&tcp));
+
+    // udp-lite & dccp ports are read by the kernel flow dissector as well
+    let udp_lite = write(|buffer| ipv4().udp_lite(1000, 53, 0).write(buffer, &[]));
+    assert_eq!(0x1b1b_6f6e, hashes(&udp_lite)[2]);
+    let dccp = write(|buffer| ipv4().dccp(DccpHeader::new(1000, 53, 1, DccpType::Data)).write(buffer, &[]));
+    assert_eq!(0x1b1b_6f6e, hashes(&dccp)[2]);
+}
+
+#[test]
+fn ipv6() {
+    let source: [u8;16] = [1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16];
+    let destination: [u8;16] = [17,18,19,20,21,22,23,24,25,26,27,28,29,30,31,32];
+    let packet = write(|buffer| {
+        PacketBuilder::ethernet2(SOURCE_MAC, DESTINATION_MAC)
+            .ipv6(source, destination, 64)
+            .udp(1000, 53)
+            .write(buffer, &[])
+    });
+    let [layer2, layer23, layer34] = hashes(&packet);
+    assert_eq!(3 ^ 0x86dd, layer2);
+    assert_eq!(0x2020_a678, layer23);
+    assert_eq!(0x0a8a_feff, layer34);
+}
+
+#[test]
+fn icmp() {
+    // echo request (identifier is used)
+    let echo = write(|buffer| ipv4().icmpv4_echo_request(0x1234, 1).write(buffer, &[]));
+    assert_eq!(0x0892_9296, hashes(&echo)[2]);
+
+    // the sequence number is not part of the hash
+    let echo2 = write(|buffer| ipv4().icmpv4_echo_request(0x1234, 2).write(buffer, &[]));
+    assert_eq!(hashes(&echo), hashes(&echo2));
+
+    // identifier 0 is treated as 1
+    {
+        let zero = write(|buffer| ipv4().icmpv4_echo_request(0, 1).write(buffer, &[]));
+        let one = write(|buffer| ipv4().icmpv4_echo_request(1, 1).write(buffer, &[]));
+        assert_eq!(hashes(&one)[2], hashes(&zero)[2]);
+    }
+
+    // no identifier (same as without ports)
+    let unreachable = write(|buffer| ipv4().icmpv4_raw(3, 1, [1,2,3,4]).write(buffer, &[]));
+    assert_eq!(0x0303_0303 >> 1, hashes(&unreachable)[2]);
+
+    // icmpv6 echo
+    {
+        let request = |id: u16| write(|buffer| {
+            PacketBuilder::ethernet2(SOURCE_MAC, DESTINATION_MAC)
+                .ipv6([1;16], [2;16], 64)
+                .icmpv6_echo_request(id, 1)
+                .write(buffer, &[])
+        });
+        assert_ne!(hashes(&request(1))[2], hashes(&request(2))[2]);
+    }
+}
+
+#[test]
+fn no_ports() {
+    // hash without ports (src ^ dst = 0x03000000)
+    let expected = {
+        let mut hash = 0x0300_0000u32;
+        hash ^= hash >> 16;
+        hash ^= hash >> 8;
+        hash >> 1
+    };
+
+    // unknown transport protocol
+    let unknown = write(|buffer| ipv4().write(buffer, ip_number::IGMP, &[0;8]));
+    assert_eq!(expected, hashes(&unknown)[2]);
+
+    // fragmented packet
+    {
+        let mut header = Ipv4Header::new(8, 64, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+        header.more_fragments = true;
+        let packet = write(|buffer| {
+            PacketBuilder::ethernet2(SOURCE_MAC, DESTINATION_MAC)
+                .ip(IpHeader::Version4(header, Default::default()))
+                .udp(1000, 53)
+                .write(buffer, &[])
+        });
+        assert_eq!(expected, hashes(&packet)[2]);
+    }
+
+    // ipv6 extension header
+    {
+        let packet = write(|buffer| {
+            PacketBuilder::ethernet2(SOURCE_MAC, DESTINATION_MAC)
+                .ip(IpHeader::Version6(
+                    Ipv6Header {
+                        traffic_class: 0,
+                        flow_label: 0,
+                        payload_length: 0,
+                        next_header: 0,
+                        hop_limit: 64,
+                        source: [1;16],
+                        destination: [1;16],
+                    },
+                    Ipv6Extensions {
+                        hop_by_hop_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[0;6]).unwrap()),
+                        ..Default::default()
+                    }
+                ))
+                .udp(1000, 53)
+                .write(buffer, &[])
+        });
+        // same source & destination, so only the (missing) ports remain
+        assert_eq!(0, hashes(&packet)[2]);
+    }
+}
+
+#[test]
+fn layer2_fallback() {
+    // non ip packets use the layer 2 hash for all policies
+    let packet = write(|buffer| {
+        Ethernet2Header {
+            source: SOURCE_MAC,
+            destination: DESTINATION_MAC,
+            ether_type: 0x88cc,
+        }.write(buffer)
+    });
+    assert_eq!([3 ^ 0x88cc; 3], hashes(&packet));
+
+    // packets without ethernet header
+    let ip = write(|buffer| PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 64).udp(1000, 53).write(buffer, &[]));
+    let sliced = SlicedPacket::from_ip(&ip).unwrap();
+    assert_eq!(0, BondHashPolicy::Layer2.hash(&sliced));
+    assert_eq!(0x0303_0303, BondHashPolicy::Layer2And3.hash(&sliced));
+}
+
+#[test]
+fn vlan() {
+    // ether type after the vlan headers is used
+    for builder in [
+        PacketBuilder::ethernet2(SOURCE_MAC, DESTINATION_MAC).single_vlan(12),
+        PacketBuilder::ethernet2(SOURCE_MAC, DESTINATION_MAC).double_vlan(12, 13),
+    ] {
+        let packet = write(|buffer| builder.ipv4([192,168,1,1], [192,168,1,2], 64).udp(1000, 53).write(buffer, &[]));
+        assert_eq!([0x803, 0x0303_0b08, 0x1b1b_6f6e], hashes(&packet));
+    }
+}
+
+#[test]
+fn member_index() {
+    let packet = write(|buffer| ipv4().udp(1000, 53).write(buffer, &[]));
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    for count in 1..8 {
+        assert_eq!(0x1b1b_6f6e % count, BondHashPolicy::Layer3And4.member_index(&sliced, count as usize) as u32);
+    }
+}
+
+#[test]
+fn debug_clone_eq_ord() {
+    let value = BondHashPolicy::Layer2And3;
+    assert_eq!(value, value.clone());
+    assert_eq!("Layer2And3", format!("{:?}", value));
+    assert!(BondHashPolicy::Layer2 < BondHashPolicy::Layer3And4);
+}
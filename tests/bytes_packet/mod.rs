@@ -0,0 +1,94 @@
+use super::*;
+
+use bytes::Bytes;
+
+fn vlan_udp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(0x123)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let payload = [1,2,3,4];
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, &payload).unwrap();
+    packet
+}
+
+#[test]
+fn from_ethernet() {
+    let packet = vlan_udp_packet();
+    let actual = BytesSlicedPacket::from_ethernet(Bytes::from(packet.clone())).unwrap();
+
+    let link_end = Ethernet2Header::SERIALIZED_SIZE;
+    let vlan_end = link_end + SingleVlanHeader::SERIALIZED_SIZE;
+    let ip_end = vlan_end + Ipv4Header::SERIALIZED_SIZE;
+    let udp_end = ip_end + UdpHeader::SERIALIZED_SIZE;
+
+    assert_eq!(&packet[..], &actual.data()[..]);
+    assert_eq!(Some(&packet[..link_end]), actual.link().as_deref());
+    assert_eq!(Some(&packet[link_end..vlan_end]), actual.vlan().as_deref());
+    assert_eq!(Some(&packet[vlan_end..ip_end]), actual.ip().as_deref());
+    assert_eq!(Some(&packet[ip_end..udp_end]), actual.transport().as_deref());
+    assert_eq!(&packet[udp_end..], &actual.payload()[..]);
+    assert_eq!(SlicedPacket::from_ethernet(&packet).unwrap(), actual.sliced());
+
+    // sub slices point into the original buffer
+    assert_eq!(
+        actual.data()[udp_end..].as_ptr(),
+        actual.payload().as_ptr()
+    );
+
+    // error
+    assert!(BytesSlicedPacket::from_ethernet(Bytes::from(packet[..link_end + 1].to_vec())).is_err());
+}
+
+#[test]
+fn from_ether_type() {
+    let packet = vlan_udp_packet();
+    let data = Bytes::from(packet[Ethernet2Header::SERIALIZED_SIZE..].to_vec());
+    let actual = BytesSlicedPacket::from_ether_type(ether_type::VLAN_TAGGED_FRAME, data.clone()).unwrap();
+    assert_eq!(None, actual.link());
+    assert_eq!(Some(data.slice(..SingleVlanHeader::SERIALIZED_SIZE)), actual.vlan());
+    assert_eq!(
+        SlicedPacket::from_ether_type(ether_type::VLAN_TAGGED_FRAME, &data).unwrap(),
+        actual.sliced()
+    );
+    assert_eq!(data, actual.into_data());
+}
+
+#[test]
+fn from_ip() {
+    let packet = vlan_udp_packet();
+    let ip_start = Ethernet2Header::SERIALIZED_SIZE + SingleVlanHeader::SERIALIZED_SIZE;
+    let data = Bytes::from(packet[ip_start..].to_vec());
+
+    // normal
+    {
+        let actual = BytesSlicedPacket::from_ip(data.clone()).unwrap();
+        assert_eq!(None, actual.link());
+        assert_eq!(None, actual.vlan());
+        assert_eq!(Some(data.slice(..Ipv4Header::SERIALIZED_SIZE)), actual.ip());
+        assert_eq!(SlicedPacket::from_ip(&data).unwrap(), actual.sliced());
+    }
+
+    // unknown transport protocol, ip header ends at the payload
+    {
+        let builder = PacketBuilder::ipv4([1,2,3,4], [5,6,7,8], 20);
+        let mut packet = Vec::with_capacity(builder.size(2));
+        builder.write(&mut packet, ip_number::EXP0, &[1,2]).unwrap();
+        let data = Bytes::from(packet);
+        let actual = BytesSlicedPacket::from_ip(data.clone()).unwrap();
+        assert_eq!(Some(data.slice(..Ipv4Header::SERIALIZED_SIZE)), actual.ip());
+        assert_eq!(None, actual.transport());
+        assert_eq!(data.slice(Ipv4Header::SERIALIZED_SIZE..), actual.payload());
+    }
+
+    // error
+    assert!(BytesSlicedPacket::from_ip(Bytes::new()).is_err());
+}
+
+#[test]
+fn debug_clone_eq() {
+    let actual = BytesSlicedPacket::from_ethernet(Bytes::from(vlan_udp_packet())).unwrap();
+    assert_eq!(actual.clone(), actual);
+    println!("{:?}", actual);
+}
@@ -0,0 +1,274 @@
+use super::*;
+
+fn udp_ipv4_packet(payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, payload)
+        .unwrap();
+    packet
+}
+
+fn verify_ip(packet: &[u8]) -> ChecksumReport {
+    let sliced = SlicedPacket::from_ip(packet).unwrap();
+    ChecksumReport::verify(packet, &sliced)
+}
+
+#[test]
+fn options() {
+    assert_eq!(VerifyOptions::full(), VerifyOptions::default());
+    assert!(VerifyOptions::full().is_full_verification(0));
+    assert!(VerifyOptions::full().is_full_verification(7));
+    assert!(!VerifyOptions::headers_only().is_full_verification(0));
+    assert!(!VerifyOptions::headers_only().is_full_verification(7));
+
+    // sampling
+    let options = VerifyOptions::sampled(3);
+    let sampled: Vec<_> = (0..7).map(|i| options.is_full_verification(i)).collect();
+    assert_eq!(vec![true, false, false, true, false, false, true], sampled);
+
+    // interval 0 & 1 verify every packet
+    assert!(VerifyOptions::sampled(0).is_full_verification(5));
+    assert!(VerifyOptions::sampled(1).is_full_verification(5));
+
+    // headers only takes precedence over the sampling
+    let options = VerifyOptions{
+        headers_only: true,
+        full_sample_interval: 2,
+    };
+    assert!(!options.is_full_verification(0));
+}
+
+#[test]
+fn udp_ipv4() {
+    let packet = udp_ipv4_packet(&[1,2,3,4,5]);
+
+    // valid
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(
+            ChecksumReport{
+                ipv4_header: Some(true),
+                transport: Some(true),
+                payload_skipped: false,
+            },
+            ChecksumReport::verify(&packet, &sliced)
+        );
+        assert_eq!(
+            ChecksumReport{
+                ipv4_header: Some(true),
+                transport: None,
+                payload_skipped: true,
+            },
+            ChecksumReport::verify_headers(&sliced)
+        );
+    }
+
+    // corrupted payload
+    {
+        let mut packet = packet.clone();
+        *packet.last_mut().unwrap() ^= 0xff;
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let report = ChecksumReport::verify(&packet, &sliced);
+        assert_eq!(Some(true), report.ipv4_header);
+        assert_eq!(Some(false), report.transport);
+        assert!(!report.is_valid());
+        assert!(ChecksumReport::verify_headers(&sliced).is_valid());
+    }
+
+    // corrupted ipv4 header (time to live)
+    {
+        let mut packet = packet.clone();
+        packet[14 + 8] = 21;
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let report = ChecksumReport::verify(&packet, &sliced);
+        assert_eq!(Some(false), report.ipv4_header);
+        assert_eq!(Some(true), report.transport);
+        assert!(!report.is_valid());
+        assert!(!ChecksumReport::verify_headers(&sliced).is_valid());
+    }
+
+    // disabled checksum
+    {
+        let mut packet = packet.clone();
+        packet[14 + 20 + 6] = 0;
+        packet[14 + 20 + 7] = 0;
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let report = ChecksumReport::verify(&packet, &sliced);
+        assert_eq!(None, report.transport);
+        assert!(report.is_valid());
+    }
+}
+
+#[test]
+fn udp_ipv6() {
+    let mut packet = Vec::new();
+    PacketBuilder::ipv6([0;16], [1;16], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3])
+        .unwrap();
+    assert_eq!(
+        ChecksumReport{
+            ipv4_header: None,
+            transport: Some(true),
+            payload_skipped: false,
+        },
+        verify_ip(&packet)
+    );
+
+    // a checksum of 0 is not allowed in ipv6
+    packet[40 + 6] = 0;
+    packet[40 + 7] = 0;
+    assert_eq!(Some(false), verify_ip(&packet).transport);
+}
+
+#[test]
+fn tcp() {
+    // ipv4
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20)
+            .tcp(1, 2, 3, 4)
+            .syn()
+            .write(&mut packet, &[1,2,3])
+            .unwrap();
+        assert_eq!(Some(true), verify_ip(&packet).transport);
+        packet[20 + 20] = 0;
+        assert_eq!(Some(false), verify_ip(&packet).transport);
+    }
+    // ipv6
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv6([0;16], [1;16], 20)
+            .tcp(1, 2, 3, 4)
+            .write(&mut packet, &[1,2,3])
+            .unwrap();
+        assert_eq!(Some(true), verify_ip(&packet).transport);
+        packet[40 + 20] = 0;
+        assert_eq!(Some(false), verify_ip(&packet).transport);
+    }
+}
+
+#[test]
+fn icmp() {
+    // icmpv4
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20)
+            .icmpv4_echo_request(1, 2)
+            .write(&mut packet, &[1,2,3])
+            .unwrap();
+        assert_eq!(Some(true), verify_ip(&packet).transport);
+        packet[20 + 8] = 0;
+        assert_eq!(Some(false), verify_ip(&packet).transport);
+    }
+    // icmpv6
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv6([0;16], [1;16], 20)
+            .icmpv6_echo_request(1, 2)
+            .write(&mut packet, &[1,2,3])
+            .unwrap();
+        assert_eq!(Some(true), verify_ip(&packet).transport);
+        packet[40 + 8] = 0;
+        assert_eq!(Some(false), verify_ip(&packet).transport);
+    }
+}
+
+#[test]
+fn udp_lite() {
+    // only the first 4 payload bytes are covered by the checksum
+    let mut packet = Vec::new();
+    PacketBuilder::ipv6([0;16], [1;16], 20)
+        .udp_lite(21, 1234, 12)
+        .write(&mut packet, &[1,2,3,4,5,6])
+        .unwrap();
+    assert_eq!(Some(true), verify_ip(&packet).transport);
+
+    // uncovered payload
+    *packet.last_mut().unwrap() = 0;
+    assert_eq!(Some(true), verify_ip(&packet).transport);
+
+    // covered payload
+    packet[40 + 8] = 0;
+    assert_eq!(Some(false), verify_ip(&packet).transport);
+}
+
+#[test]
+fn sctp() {
+    let chunks = [1,2,3,4];
+    let mut header = SctpHeader{
+        source_port: 1,
+        destination_port: 2,
+        verification_tag: 3,
+        checksum: 0,
+    };
+    header.checksum = header.calc_checksum(&chunks);
+
+    let mut packet = Vec::new();
+    Ipv4Header::new(12 + 4, 20, ip_number::SCTP, [1,1,1,1], [2,2,2,2])
+        .write(&mut packet)
+        .unwrap();
+    header.write(&mut packet).unwrap();
+    packet.extend_from_slice(&chunks);
+    assert_eq!(
+        ChecksumReport{
+            ipv4_header: Some(true),
+            transport: Some(true),
+            payload_skipped: false,
+        },
+        verify_ip(&packet)
+    );
+
+    *packet.last_mut().unwrap() = 0;
+    assert_eq!(Some(false), verify_ip(&packet).transport);
+}
+
+#[test]
+fn trailing_bytes() {
+    // ethernet padding is not covered by the transport checksum
+    let mut packet = udp_ipv4_packet(&[1,2,3,4]);
+    packet.resize(60, 0xff);
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert_eq!(Some(true), ChecksumReport::verify(&packet, &sliced).transport);
+}
+
+#[test]
+fn truncated() {
+    let packet = udp_ipv4_packet(&[1,2,3,4]);
+    let captured = &packet[..packet.len() - 2];
+    let sliced = SlicedPacket::from_ethernet(captured).unwrap();
+    let report = ChecksumReport::verify(captured, &sliced);
+    assert_eq!(Some(true), report.ipv4_header);
+    assert_eq!(None, report.transport);
+    assert!(!report.payload_skipped);
+}
+
+#[test]
+fn data_not_matching_packet() {
+    let packet = udp_ipv4_packet(&[1,2,3,4]);
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let other = packet.clone();
+    let report = ChecksumReport::verify(&other, &sliced);
+    assert_eq!(Some(true), report.ipv4_header);
+    assert_eq!(None, report.transport);
+}
+
+#[test]
+fn no_ip() {
+    let packet = [0u8; 4];
+    let sliced = SlicedPacket::from_ether_type(0x1234, &packet).unwrap();
+    assert_eq!(ChecksumReport::default(), ChecksumReport::verify(&packet, &sliced));
+    assert!(ChecksumReport::default().is_valid());
+}
+
+#[test]
+fn debug_clone_eq() {
+    let options = VerifyOptions::default();
+    assert_eq!(options, options.clone());
+    assert!(format!("{:?}", options).starts_with("VerifyOptions"));
+    let report = ChecksumReport::default();
+    assert_eq!(report, report.clone());
+    assert!(format!("{:?}", report).starts_with("ChecksumReport"));
+}
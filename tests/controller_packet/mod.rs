@@ -0,0 +1,112 @@
+use super::*;
+
+fn udp_frame() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let payload = [1,2,3,4];
+    let mut frame = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut frame, &payload).unwrap();
+    frame
+}
+
+mod packet_in {
+    use super::*;
+
+    #[test]
+    fn new_total_len() {
+        let frame = udp_frame();
+        let value = PacketIn::new(3u32, &frame);
+        assert_eq!(3, value.metadata);
+        assert_eq!(&frame[..], value.frame);
+        assert_eq!(frame.len(), value.total_len);
+        assert!(!value.is_truncated());
+
+        let truncated = PacketIn::new(3u32, &frame[..20]).with_total_len(frame.len());
+        assert_eq!(frame.len(), truncated.total_len);
+        assert!(truncated.is_truncated());
+    }
+
+    #[test]
+    fn sliced_headers() {
+        let frame = udp_frame();
+        let value = PacketIn::new((), &frame);
+        assert_eq!(SlicedPacket::from_ethernet(&frame).unwrap(), value.sliced().unwrap());
+        assert_eq!(
+            SlicedPacket::from_ethernet(&frame).unwrap(),
+            value.sliced_with_registry(&ParserRegistry::new()).unwrap()
+        );
+        assert_eq!(PacketHeaders::from_ethernet_slice(&frame).unwrap(), value.headers().unwrap());
+
+        // errors are forwarded
+        let value = PacketIn::new((), &frame[..10]);
+        assert!(value.sliced().is_err());
+        assert!(value.sliced_with_registry(&ParserRegistry::new()).is_err());
+        assert!(value.headers().is_err());
+    }
+
+    #[test]
+    fn map_metadata() {
+        let frame = udp_frame();
+        let value = PacketIn::new(1u8, &frame[..20]).with_total_len(100);
+        let mapped = value.clone().map_metadata(|v| u16::from(v) + 1);
+        assert_eq!(2u16, mapped.metadata);
+        assert_eq!(value.frame, mapped.frame);
+        assert_eq!(100, mapped.total_len);
+    }
+
+    #[test]
+    fn packet_out() {
+        let frame = udp_frame();
+        let value = PacketIn::new(1u8, &frame);
+        assert_eq!(PacketOut::new("out", frame.clone()), value.packet_out("out"));
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let frame = udp_frame();
+        let value = PacketIn::new(1u8, &frame);
+        assert_eq!(value, value.clone());
+        println!("{:?}", value);
+    }
+}
+
+mod packet_out {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let value = PacketOut::new(2u32, vec![1,2,3]);
+        assert_eq!(2, value.metadata);
+        assert_eq!(vec![1,2,3], value.frame);
+    }
+
+    #[test]
+    fn write_with() {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let value = PacketOut::write_with(2u32, |frame| builder.write(frame, &[1,2,3,4])).unwrap();
+        assert_eq!(2, value.metadata);
+        assert_eq!(udp_frame(), value.frame);
+        assert_eq!(SlicedPacket::from_ethernet(&udp_frame()).unwrap(), value.sliced().unwrap());
+
+        // errors are forwarded
+        assert_matches!(
+            PacketOut::write_with((), |_| Err(WriteError::SliceTooSmall(1))),
+            Err(WriteError::SliceTooSmall(1))
+        );
+    }
+
+    #[test]
+    fn sliced_error() {
+        assert!(PacketOut::new((), vec![1,2,3]).sliced().is_err());
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let value = PacketOut::new(2u32, vec![1,2,3]);
+        assert_eq!(value, value.clone());
+        println!("{:?}", value);
+    }
+}
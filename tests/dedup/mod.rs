@@ -0,0 +1,211 @@
+use super::*;
+
+use std::time::Duration;
+
+fn ipv4_builder(ttl: u8) -> PacketBuilderStep<IpHeader> {
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], ttl)
+}
+
+fn udp_packet(ttl: u8, payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    ipv4_builder(ttl)
+        .udp(21, 1234)
+        .write(&mut result, payload)
+        .unwrap();
+    result
+}
+
+fn ms(value: u64) -> Duration {
+    Duration::from_millis(value)
+}
+
+#[test]
+fn config_default() {
+    assert_eq!(
+        DedupConfig {
+            ignore_ttl: true,
+            ignore_checksums: true,
+            ignore_link_addresses: true,
+        },
+        DedupConfig::default()
+    );
+}
+
+#[test]
+fn digest_ethernet() {
+    let none = DedupConfig {
+        ignore_ttl: false,
+        ignore_checksums: false,
+        ignore_link_addresses: false,
+    };
+    let packet = udp_packet(64, &[1,2,3,4]);
+
+    // stable
+    assert_eq!(none.digest_ethernet(&packet), none.digest_ethernet(&packet.clone()));
+    assert_eq!(DedupConfig::default().digest_ethernet(&packet), DedupConfig::default().digest_ethernet(&packet));
+
+    // payload always matters
+    assert_ne!(
+        DedupConfig::default().digest_ethernet(&packet),
+        DedupConfig::default().digest_ethernet(&udp_packet(64, &[1,2,3,5]))
+    );
+
+    // ttl (also changes the ipv4 header checksum)
+    {
+        let other = udp_packet(63, &[1,2,3,4]);
+        assert_ne!(none.digest_ethernet(&packet), none.digest_ethernet(&other));
+        let ttl_only = DedupConfig{ ignore_ttl: true, ..none };
+        assert_ne!(ttl_only.digest_ethernet(&packet), ttl_only.digest_ethernet(&other));
+        let ttl_checksums = DedupConfig{ ignore_ttl: true, ignore_checksums: true, ..none };
+        assert_eq!(ttl_checksums.digest_ethernet(&packet), ttl_checksums.digest_ethernet(&other));
+    }
+
+    // link addresses
+    {
+        let mut other = packet.clone();
+        other[0] = 0xff;
+        other[11] = 0xff;
+        assert_ne!(none.digest_ethernet(&packet), none.digest_ethernet(&other));
+        let config = DedupConfig{ ignore_link_addresses: true, ..none };
+        assert_eq!(config.digest_ethernet(&packet), config.digest_ethernet(&other));
+
+        // ether type is not ignored
+        other[12] = 0xff;
+        assert_ne!(config.digest_ethernet(&packet), config.digest_ethernet(&other));
+    }
+
+    // transport checksums
+    {
+        let checksum_config = DedupConfig{ ignore_checksums: true, ..none };
+        let offset = Ethernet2Header::SERIALIZED_SIZE + Ipv4Header::SERIALIZED_SIZE;
+        let mut other = packet.clone();
+        other[offset + 6] ^= 0xff;
+        other[offset + 7] ^= 0xff;
+        assert_ne!(none.digest_ethernet(&packet), none.digest_ethernet(&other));
+        assert_eq!(checksum_config.digest_ethernet(&packet), checksum_config.digest_ethernet(&other));
+
+        // tcp
+        let tcp = |window: u16| {
+            let mut result = Vec::new();
+            ipv4_builder(64)
+                .tcp(21, 1234, 1, window)
+                .write(&mut result, &[1,2,3,4])
+                .unwrap();
+            result
+        };
+        let mut tcp_other = tcp(1024);
+        tcp_other[offset + 16] ^= 0xff;
+        assert_ne!(none.digest_ethernet(&tcp(1024)), none.digest_ethernet(&tcp_other));
+        assert_eq!(checksum_config.digest_ethernet(&tcp(1024)), checksum_config.digest_ethernet(&tcp_other));
+        assert_ne!(checksum_config.digest_ethernet(&tcp(1024)), checksum_config.digest_ethernet(&tcp(1025)));
+
+        // icmpv4
+        let mut icmp = Vec::new();
+        ipv4_builder(64)
+            .icmpv4_echo_request(1, 2)
+            .write(&mut icmp, &[1,2,3,4])
+            .unwrap();
+        let mut icmp_other = icmp.clone();
+        icmp_other[offset + 2] ^= 0xff;
+        assert_ne!(none.digest_ethernet(&icmp), none.digest_ethernet(&icmp_other));
+        assert_eq!(checksum_config.digest_ethernet(&icmp), checksum_config.digest_ethernet(&icmp_other));
+    }
+
+    // not decodable packets are hashed completely
+    {
+        let short = [1,2,3];
+        assert_eq!(DedupConfig::default().digest_ethernet(&short), none.digest_ethernet(&short));
+        assert_ne!(DedupConfig::default().digest_ethernet(&short), DedupConfig::default().digest_ethernet(&[1,2,4]));
+    }
+}
+
+#[test]
+fn digest_ip() {
+    let packet = |hop_limit: u8| {
+        let mut result = Vec::new();
+        PacketBuilder::ipv6([1;16], [2;16], hop_limit)
+            .icmpv6_echo_request(1, 2)
+            .write(&mut result, &[1,2,3,4])
+            .unwrap();
+        result
+    };
+    let config = DedupConfig::default();
+    assert_eq!(config.digest_ip(&packet(64)), config.digest_ip(&packet(1)));
+
+    let none = DedupConfig {
+        ignore_ttl: false,
+        ignore_checksums: false,
+        ignore_link_addresses: false,
+    };
+    assert_ne!(none.digest_ip(&packet(64)), none.digest_ip(&packet(1)));
+
+    // icmpv6 checksum
+    let mut other = packet(64);
+    other[Ipv6Header::SERIALIZED_SIZE + 2] ^= 0xff;
+    assert_ne!(none.digest_ip(&packet(64)), none.digest_ip(&other));
+    assert_eq!(config.digest_ip(&packet(64)), config.digest_ip(&other));
+}
+
+#[test]
+fn deduplicator_window() {
+    let mut dedup = Deduplicator::new(DedupConfig::default(), ms(10), 16);
+    assert_eq!(&DedupConfig::default(), dedup.config());
+    assert_eq!(ms(10), dedup.window());
+    assert_eq!(16, dedup.capacity());
+    assert!(dedup.is_empty());
+
+    let a = udp_packet(64, &[1]);
+    let b = udp_packet(64, &[2]);
+
+    assert!(!dedup.is_duplicate(ms(100), &a));
+    assert!(!dedup.is_duplicate(ms(101), &b));
+    assert!(dedup.is_duplicate(ms(102), &udp_packet(60, &[1])));
+    assert!(dedup.is_duplicate(ms(110), &a));
+    assert_eq!(2, dedup.len());
+    assert!(!dedup.is_empty());
+
+    // a left the window, b is still within it
+    assert!(!dedup.is_duplicate(ms(111), &a));
+    assert!(dedup.is_duplicate(ms(111), &b));
+    assert!(!dedup.is_duplicate(ms(200), &b));
+    assert_eq!(1, dedup.len());
+    assert_eq!(3, dedup.duplicates());
+
+    // clear
+    dedup.clear();
+    assert!(dedup.is_empty());
+    assert!(!dedup.is_duplicate(ms(200), &b));
+    assert_eq!(3, dedup.duplicates());
+}
+
+#[test]
+fn deduplicator_capacity() {
+    let mut dedup = Deduplicator::new(DedupConfig::default(), ms(1000), 2);
+    assert!(!dedup.is_duplicate_digest(ms(1), 1));
+    assert!(!dedup.is_duplicate_digest(ms(2), 2));
+    assert!(!dedup.is_duplicate_digest(ms(3), 3));
+    assert_eq!(2, dedup.len());
+
+    // the oldest digest was dropped
+    assert!(!dedup.is_duplicate_digest(ms(4), 1));
+    assert!(dedup.is_duplicate_digest(ms(5), 1));
+    assert!(dedup.is_duplicate_digest(ms(5), 3));
+    assert!(!dedup.is_duplicate_digest(ms(5), 2));
+
+    // no capacity
+    let mut none = Deduplicator::new(DedupConfig::default(), ms(1000), 0);
+    assert!(!none.is_duplicate_digest(ms(1), 1));
+    assert!(!none.is_duplicate_digest(ms(1), 1));
+    assert!(none.is_empty());
+}
+
+#[test]
+fn deduplicator_ip() {
+    let packet = udp_packet(64, &[1,2,3]);
+    let ip = &packet[Ethernet2Header::SERIALIZED_SIZE..];
+    let mut dedup = Deduplicator::new(DedupConfig::default(), ms(10), 16);
+    assert!(!dedup.is_duplicate_ip(ms(1), ip));
+    assert!(dedup.is_duplicate_ip(ms(2), ip));
+    assert!(dedup.is_duplicate_digest(ms(3), DedupConfig::default().digest_ip(ip)));
+}
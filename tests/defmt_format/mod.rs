@@ -0,0 +1,40 @@
+use super::*;
+
+/// Compile time check that the given type implements `defmt::Format`.
+fn assert_format<T: defmt::Format>() {}
+
+#[test]
+fn implemented() {
+    // link
+    assert_format::<EtherType>();
+    assert_format::<Ethernet2Header>();
+    assert_format::<VlanHeader>();
+    assert_format::<SingleVlanHeader>();
+    assert_format::<DoubleVlanHeader>();
+
+    // internet
+    assert_format::<IpHeader>();
+    assert_format::<IpNumber>();
+    assert_format::<Ipv4Header>();
+    assert_format::<Ipv4Extensions>();
+    assert_format::<IpAuthenticationHeader>();
+    assert_format::<Ipv6Header>();
+    assert_format::<Ipv6Extensions>();
+    assert_format::<Ipv6RawExtensionHeader>();
+    assert_format::<Ipv6FragmentHeader>();
+    assert_format::<Ipv6RoutingExtensions>();
+
+    // transport
+    assert_format::<TransportHeader>();
+    assert_format::<UdpHeader>();
+    assert_format::<TcpHeader>();
+    assert_format::<TcpOptionElement>();
+    assert_format::<IcmpEchoHeader>();
+    assert_format::<Icmpv4Header>();
+    assert_format::<Icmpv4Type>();
+    assert_format::<Icmpv6Header>();
+    assert_format::<Icmpv6Type>();
+
+    // packet
+    assert_format::<PacketHeaders>();
+}
@@ -98,6 +98,162 @@ proptest! {
             &format!("ReadError: ICMPv6 packet length {} is bigger then can be represented in an u32.", arg_usize),
             &format!("{}", Icmpv6PacketTooBig(arg_usize))
         );
+
+        //HipHeaderLengthTooSmall
+        assert_eq!(
+            &format!("ReadError: HIP header length value {} is smaller then the minimum header length of 4 (40 bytes).", arg_u8),
+            &format!("{}", HipHeaderLengthTooSmall(arg_u8))
+        );
+
+        //HipParameterLengthTooBig
+        assert_eq!(
+            &format!("ReadError: The length of the HIP parameter at offset {} exceeds the HIP header length.", arg_usize),
+            &format!("{}", HipParameterLengthTooBig(arg_usize))
+        );
+
+        //GreUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported GRE version number. The GRE header contained the unsupported version number {}.", arg_u8),
+            &format!("{}", GreUnsupportedVersion(arg_u8))
+        );
+
+        //GreRoutingNotSupported
+        assert_eq!(
+            "ReadError: GRE header with routing present bit set. GRE routing fields (RFC 1701) are not supported.",
+            &format!("{}", GreRoutingNotSupported)
+        );
+
+        //GeneveUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported GENEVE version number. The GENEVE header contained the unsupported version number {}.", arg_u8),
+            &format!("{}", GeneveUnsupportedVersion(arg_u8))
+        );
+
+        //GeneveOptionLengthTooBig
+        assert_eq!(
+            &format!("ReadError: The length of the GENEVE option at offset {} exceeds the GENEVE options length.", arg_usize),
+            &format!("{}", GeneveOptionLengthTooBig(arg_usize))
+        );
+
+        //NshUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported NSH version number. The NSH header contained the unsupported version number {}.", arg_u8),
+            &format!("{}", NshUnsupportedVersion(arg_u8))
+        );
+
+        //NshLengthBad
+        assert_eq!(
+            "ReadError: The NSH header length of 2 (in 4 byte units) is not valid for the MD type 1.",
+            &format!("{}", NshLengthBad{ md_type: 1, length: 2 })
+        );
+
+        //NshMetadataLengthTooBig
+        assert_eq!(
+            &format!("ReadError: The length of the NSH metadata TLV at offset {} exceeds the NSH header length.", arg_usize),
+            &format!("{}", NshMetadataLengthTooBig(arg_usize))
+        );
+
+        //PppoeUnsupportedVersion
+        assert_eq!(
+            "ReadError: Unsupported PPPoE version 2 & type 3 (only version 1 & type 1 are supported).",
+            &format!("{}", PppoeUnsupportedVersion{ version: 2, pppoe_type: 3 })
+        );
+
+        //PppoePayloadLengthTooSmall
+        assert_eq!(
+            &format!("ReadError: The PPPoE payload length of {} is too small to contain the PPP protocol field of a session data packet.", arg_u16),
+            &format!("{}", PppoePayloadLengthTooSmall(arg_u16))
+        );
+
+        //RadiotapUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported radiotap version number. The radiotap header contained the unsupported version number {}.", arg_u8),
+            &format!("{}", RadiotapUnsupportedVersion(arg_u8))
+        );
+
+        //RadiotapLengthTooSmall
+        assert_eq!(
+            &format!("ReadError: The radiotap header length of {} is too small to contain the header & its present flags.", arg_u16),
+            &format!("{}", RadiotapLengthTooSmall(arg_u16))
+        );
+
+        //Ieee80211UnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported IEEE 802.11 protocol version. The frame control contained the unsupported version number {}.", arg_u8),
+            &format!("{}", Ieee80211UnsupportedVersion(arg_u8))
+        );
+
+        //LlcNotSnap
+        assert_eq!(
+            "ReadError: The LLC header with DSAP 0xfe, SSAP 0xfe & control 0x03 is not a SNAP header.",
+            &format!("{}", LlcNotSnap{ dsap: 0xfe, ssap: 0xfe, control: 0x03 })
+        );
+
+        //GtpuUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported GTP-U version number. The GTP-U header contained the unsupported version number {}.", arg_u8),
+            &format!("{}", GtpuUnsupportedVersion(arg_u8))
+        );
+
+        //GtpuExtensionLengthBad
+        assert_eq!(
+            &format!("ReadError: The length of the GTP-U extension header at offset {} is zero or exceeds the maximum supported extension headers length of 252 bytes.", arg_usize),
+            &format!("{}", GtpuExtensionLengthBad(arg_usize))
+        );
+
+        //Ipv6SegmentRoutingUnexpectedType
+        assert_eq!(
+            &format!("ReadError: Unexpected IPv6 routing type {} (expected 4 for a segment routing header).", arg_u8),
+            &format!("{}", Ipv6SegmentRoutingUnexpectedType(arg_u8))
+        );
+
+        //Ipv6SegmentRoutingLastEntryTooLarge
+        assert_eq!(
+            &format!("ReadError: The segment list of the IPv6 segment routing header with the last entry {} does not fit into the header.", arg_u8),
+            &format!("{}", Ipv6SegmentRoutingLastEntryTooLarge(arg_u8))
+        );
+
+        //DccpPacketTypeReserved
+        assert_eq!(
+            &format!("ReadError: Reserved DCCP packet type {}.", arg_u8),
+            &format!("{}", DccpPacketTypeReserved(arg_u8))
+        );
+
+        //DccpDataOffsetTooSmall
+        assert_eq!(
+            &format!("ReadError: The DCCP data offset {} is too small to contain the header for the given packet type.", arg_u8),
+            &format!("{}", DccpDataOffsetTooSmall(arg_u8))
+        );
+
+        //VrrpUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported VRRP version {} (only versions 2 & 3 are supported via IPv4 and version 3 via IPv6).", arg_u8),
+            &format!("{}", VrrpUnsupportedVersion(arg_u8))
+        );
+
+        //ErspanUnsupportedVersion
+        assert_eq!(
+            &format!("ReadError: Unsupported ERSPAN version {} (only version 1 for type II & version 2 for type III headers are supported).", arg_u8),
+            &format!("{}", ErspanUnsupportedVersion(arg_u8))
+        );
+
+        //MacsecUnsupportedVersion
+        assert_eq!(
+            "ReadError: Unsupported MACsec SecTAG version (the version bit is set, only version 0 is supported).",
+            &format!("{}", MacsecUnsupportedVersion)
+        );
+
+        //ParseLimitExceeded
+        assert_eq!(
+            "ReadError: The packet exceeds the parse limit VlanTags.",
+            &format!("{}", ParseLimitExceeded(ParseLimit::VlanTags))
+        );
+
+        //EtherTypeLengthUndefined
+        assert_eq!(
+            &format!("ReadError: The ethernet type/length field value {} is neither a length (at most 1500) nor an ether type (at least 1536).", arg_u16),
+            &format!("{}", EtherTypeLengthUndefined(arg_u16))
+        );
     }
 }
 
@@ -126,6 +282,32 @@ fn read_error_source() {
         IpAuthenticationHeaderTooSmallPayloadLength(0),
         TcpDataOffsetTooSmall(0),
         Icmpv6PacketTooBig(0),
+        HipHeaderLengthTooSmall(0),
+        HipParameterLengthTooBig(0),
+        GreUnsupportedVersion(0),
+        GreRoutingNotSupported,
+        GeneveUnsupportedVersion(0),
+        GeneveOptionLengthTooBig(0),
+        NshUnsupportedVersion(0),
+        NshLengthBad{ md_type: 0, length: 0 },
+        NshMetadataLengthTooBig(0),
+        PppoeUnsupportedVersion{ version: 0, pppoe_type: 0 },
+        PppoePayloadLengthTooSmall(0),
+        RadiotapUnsupportedVersion(0),
+        RadiotapLengthTooSmall(0),
+        Ieee80211UnsupportedVersion(0),
+        LlcNotSnap{ dsap: 0, ssap: 0, control: 0 },
+        GtpuUnsupportedVersion(0),
+        GtpuExtensionLengthBad(0),
+        Ipv6SegmentRoutingUnexpectedType(0),
+        Ipv6SegmentRoutingLastEntryTooLarge(0),
+        DccpPacketTypeReserved(0),
+        DccpDataOffsetTooSmall(0),
+        VrrpUnsupportedVersion(0),
+        ErspanUnsupportedVersion(0),
+        MacsecUnsupportedVersion,
+        ParseLimitExceeded(ParseLimit::OptionBytes),
+        EtherTypeLengthUndefined(0),
     ];
 
     for value in &none_values {
@@ -152,6 +334,32 @@ fn read_error_debug() {
         IpAuthenticationHeaderTooSmallPayloadLength(0),
         TcpDataOffsetTooSmall(0),
         Icmpv6PacketTooBig(0),
+        HipHeaderLengthTooSmall(0),
+        HipParameterLengthTooBig(0),
+        GreUnsupportedVersion(0),
+        GreRoutingNotSupported,
+        GeneveUnsupportedVersion(0),
+        GeneveOptionLengthTooBig(0),
+        NshUnsupportedVersion(0),
+        NshLengthBad{ md_type: 0, length: 0 },
+        NshMetadataLengthTooBig(0),
+        PppoeUnsupportedVersion{ version: 0, pppoe_type: 0 },
+        PppoePayloadLengthTooSmall(0),
+        RadiotapUnsupportedVersion(0),
+        RadiotapLengthTooSmall(0),
+        Ieee80211UnsupportedVersion(0),
+        LlcNotSnap{ dsap: 0, ssap: 0, control: 0 },
+        GtpuUnsupportedVersion(0),
+        GtpuExtensionLengthBad(0),
+        Ipv6SegmentRoutingUnexpectedType(0),
+        Ipv6SegmentRoutingLastEntryTooLarge(0),
+        DccpPacketTypeReserved(0),
+        DccpDataOffsetTooSmall(0),
+        VrrpUnsupportedVersion(0),
+        ErspanUnsupportedVersion(0),
+        MacsecUnsupportedVersion,
+        ParseLimitExceeded(ParseLimit::OptionBytes),
+        EtherTypeLengthUndefined(0),
     ];
 
     for value in &values {
@@ -237,6 +445,26 @@ fn value_error_source() {
         U16TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         U32TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         Icmpv6InIpv4,
+        IpPacketExceedsMtu{ ip_packet_len: 0, mtu: 0 },
+        BitFieldValueTooLarge{ value: 0, bit_len: 0 },
+        ArpAddressLengthMismatch{ sender_len: 0, target_len: 0 },
+        ArpAddressTooLong(0),
+        NdpOptionTooLong(0),
+        GeneveOptionsLengthBad(0),
+        GeneveOptionDataLengthBad(0),
+        NshMetadataLengthBad(0),
+        NshTlvDataTooLong(0),
+        PppoePayloadLengthTooLarge(0),
+        GtpuExtensionsLengthBad(0),
+        GtpuExtensionContentLengthBad(0),
+        Ipv6SegmentRoutingNoSegments,
+        UdpLiteChecksumCoverageBad{ coverage: 0, len: 0 },
+        U64TooLarge{ value:0, max:0, field:ErrorField::DccpSequenceNumber },
+        DccpOptionsLengthBad(0),
+        DccpChecksumCoverageBad{ coverage: 0, payload_len: 0 },
+        DccpLengthTooLarge(0),
+        VrrpV2ViaIpv6,
+        VrrpLengthTooLarge(0),
     ];
 
     for value in &none_values {
@@ -263,6 +491,26 @@ fn value_error_debug() {
         U16TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         U32TooLarge{ value:0, max:0, field:ErrorField::Ipv4Dscp },
         Icmpv6InIpv4,
+        IpPacketExceedsMtu{ ip_packet_len: 0, mtu: 0 },
+        BitFieldValueTooLarge{ value: 0, bit_len: 0 },
+        ArpAddressLengthMismatch{ sender_len: 0, target_len: 0 },
+        ArpAddressTooLong(0),
+        NdpOptionTooLong(0),
+        GeneveOptionsLengthBad(0),
+        GeneveOptionDataLengthBad(0),
+        NshMetadataLengthBad(0),
+        NshTlvDataTooLong(0),
+        PppoePayloadLengthTooLarge(0),
+        GtpuExtensionsLengthBad(0),
+        GtpuExtensionContentLengthBad(0),
+        Ipv6SegmentRoutingNoSegments,
+        UdpLiteChecksumCoverageBad{ coverage: 0, len: 0 },
+        U64TooLarge{ value:0, max:0, field:ErrorField::DccpSequenceNumber },
+        DccpOptionsLengthBad(0),
+        DccpChecksumCoverageBad{ coverage: 0, payload_len: 0 },
+        DccpLengthTooLarge(0),
+        VrrpV2ViaIpv6,
+        VrrpLengthTooLarge(0),
     ];
 
     for value in &values {
@@ -398,6 +646,130 @@ proptest! {
             &format!("ICMPv6 packet can not be combined with IPv4 headers."),
             &format!("{}", Icmpv6InIpv4)
         );
+
+        // IpPacketExceedsMtu
+        assert_eq!(
+            &format!("The ip packet length of {} bytes exceeds the maximum transmission unit (MTU) of {} bytes.", arg_usize, value_u32),
+            &format!("{}", IpPacketExceedsMtu{ ip_packet_len: arg_usize, mtu: value_u32 as usize })
+        );
+
+        // BitFieldValueTooLarge
+        assert_eq!(
+            &format!("The value {} can not be represented in a bit field with a length of {} bits.", arg_usize, value_u8),
+            &format!("{}", BitFieldValueTooLarge{ value: arg_usize as u64, bit_len: value_u8 })
+        );
+
+        // ArpAddressLengthMismatch
+        assert_eq!(
+            &format!("The ARP sender address length of {} bytes differs from the target address length of {} bytes.", arg_usize, value_u32),
+            &format!("{}", ArpAddressLengthMismatch{ sender_len: arg_usize, target_len: value_u32 as usize })
+        );
+
+        // ArpAddressTooLong
+        assert_eq!(
+            &format!("The ARP address length of {} bytes is larger then the maximum of 255 bytes.", arg_usize),
+            &format!("{}", ArpAddressTooLong(arg_usize))
+        );
+
+        // NdpOptionTooLong
+        assert_eq!(
+            &format!("The NDP option length of {} bytes is larger then the maximum of 2040 bytes.", arg_usize),
+            &format!("{}", NdpOptionTooLong(arg_usize))
+        );
+
+        // GeneveOptionsLengthBad
+        assert_eq!(
+            &format!("The GENEVE options length of {} bytes is larger then the maximum of 252 bytes.", arg_usize),
+            &format!("{}", GeneveOptionsLengthBad(arg_usize))
+        );
+
+        // GeneveOptionDataLengthBad
+        assert_eq!(
+            &format!("The GENEVE option data length of {} bytes is either not a multiple of 4 bytes or larger then the maximum of 124 bytes.", arg_usize),
+            &format!("{}", GeneveOptionDataLengthBad(arg_usize))
+        );
+
+        // NshMetadataLengthBad
+        assert_eq!(
+            &format!("The NSH metadata length of {} bytes is larger then the maximum of 244 bytes.", arg_usize),
+            &format!("{}", NshMetadataLengthBad(arg_usize))
+        );
+
+        // NshTlvDataTooLong
+        assert_eq!(
+            &format!("The NSH metadata TLV data length of {} bytes is larger then the maximum of 127 bytes.", arg_usize),
+            &format!("{}", NshTlvDataTooLong(arg_usize))
+        );
+
+        // PppoePayloadLengthTooLarge
+        assert_eq!(
+            &format!("The PPPoE payload length of {} bytes is larger then can be represented in the payload length field.", arg_usize),
+            &format!("{}", PppoePayloadLengthTooLarge(arg_usize))
+        );
+
+        // GtpuExtensionsLengthBad
+        assert_eq!(
+            &format!("The GTP-U extension headers length of {} bytes is larger then the maximum of 252 bytes.", arg_usize),
+            &format!("{}", GtpuExtensionsLengthBad(arg_usize))
+        );
+
+        // GtpuExtensionContentLengthBad
+        assert_eq!(
+            &format!("The GTP-U extension header content length of {} bytes plus 2 is not a multiple of 4 bytes.", arg_usize),
+            &format!("{}", GtpuExtensionContentLengthBad(arg_usize))
+        );
+
+        // Ipv6SegmentRoutingNoSegments
+        assert_eq!(
+            "An IPv6 segment routing header has to contain at least one segment.",
+            &format!("{}", Ipv6SegmentRoutingNoSegments)
+        );
+
+        // UdpLiteChecksumCoverageBad
+        assert_eq!(
+            "The UDP-Lite checksum coverage of 7 bytes is invalid for a datagram with a length of 12 bytes (has to be 0 or between 8 and the datagram length).",
+            &format!("{}", UdpLiteChecksumCoverageBad{ coverage: 7, len: 12 })
+        );
+
+        // U64TooLarge
+        assert_eq!(
+            &format!("The value {} of the field '{}' is larger then the allowed maximum of {}.", u64::from(value_u32), field, max_u32),
+            &format!("{}", U64TooLarge{
+                value: u64::from(value_u32),
+                max: u64::from(max_u32),
+                field: field.clone()
+            })
+        );
+
+        // DccpOptionsLengthBad
+        assert_eq!(
+            &format!("The DCCP options length of {} bytes is not a multiple of 4 or the header exceeds the maximum header length of 1020 bytes.", arg_usize),
+            &format!("{}", DccpOptionsLengthBad(arg_usize))
+        );
+
+        // DccpChecksumCoverageBad
+        assert_eq!(
+            &format!("The DCCP checksum coverage {} exceeds the payload length of {} bytes.", value_u8, arg_usize),
+            &format!("{}", DccpChecksumCoverageBad{ coverage: value_u8, payload_len: arg_usize })
+        );
+
+        // DccpLengthTooLarge
+        assert_eq!(
+            &format!("The DCCP length of {} bytes is larger then can be represented in the checksum pseudo header.", arg_usize),
+            &format!("{}", DccpLengthTooLarge(arg_usize))
+        );
+
+        // VrrpV2ViaIpv6
+        assert_eq!(
+            "VRRP version 2 messages can only be sent via IPv4.",
+            &format!("{}", VrrpV2ViaIpv6)
+        );
+
+        // VrrpLengthTooLarge
+        assert_eq!(
+            &format!("The VRRP message length of {} bytes is larger then can be represented in the checksum pseudo header.", arg_usize),
+            &format!("{}", VrrpLengthTooLarge(arg_usize))
+        );
     }
 }
 
@@ -413,4 +785,32 @@ fn error_field_display() {
     assert_eq!("Ipv6FragmentHeader.fragment_offset", &format!("{}", Ipv6FragmentOffset));
     assert_eq!("SingleVlanHeader.priority_code_point", &format!("{}", VlanTagPriorityCodePoint));
     assert_eq!("SingleVlanHeader.vlan_identifier", &format!("{}", VlanTagVlanId));
+    assert_eq!("MplsHeader.label", &format!("{}", MplsLabel));
+    assert_eq!("MplsHeader.traffic_class", &format!("{}", MplsTrafficClass));
+    assert_eq!("VxlanHeader.vni", &format!("{}", VxlanVni));
+    assert_eq!("GeneveHeader.vni", &format!("{}", GeneveVni));
+    assert_eq!("NshHeader.ttl", &format!("{}", NshTtl));
+    assert_eq!("NshHeader.service_path_id", &format!("{}", NshServicePathId));
+    assert_eq!("PppoeHeader.version", &format!("{}", PppoeVersion));
+    assert_eq!("PppoeHeader.pppoe_type", &format!("{}", PppoeType));
+    assert_eq!("DdpHeader.hop_count", &format!("{}", DdpHopCount));
+    assert_eq!("DdpHeader.length", &format!("{}", DdpLength));
+    assert_eq!("DccpHeader.ccval", &format!("{}", DccpCcval));
+    assert_eq!("DccpHeader.checksum_coverage", &format!("{}", DccpChecksumCoverage));
+    assert_eq!("DccpHeader.sequence_number", &format!("{}", DccpSequenceNumber));
+    assert_eq!("DccpType.acknowledgement_number", &format!("{}", DccpAcknowledgementNumber));
+    assert_eq!("VrrpHeader.vrrp_type", &format!("{}", VrrpType));
+    assert_eq!("VrrpVersion.max_advertisement_interval", &format!("{}", VrrpMaxAdvertisementInterval));
+    assert_eq!("ErspanHeader.vlan", &format!("{}", ErspanVlan));
+    assert_eq!("ErspanHeader.cos", &format!("{}", ErspanCos));
+    assert_eq!("ErspanHeader.encapsulation_type", &format!("{}", ErspanEncapsulationType));
+    assert_eq!("ErspanHeader.session_id", &format!("{}", ErspanSessionId));
+    assert_eq!("ErspanType.index", &format!("{}", ErspanIndex));
+    assert_eq!("ErspanType.frame_type", &format!("{}", ErspanFrameType));
+    assert_eq!("ErspanType.hardware_id", &format!("{}", ErspanHardwareId));
+    assert_eq!("ErspanType.granularity", &format!("{}", ErspanGranularity));
+    assert_eq!("MacsecHeader.association_number", &format!("{}", MacsecAssociationNumber));
+    assert_eq!("MacsecHeader.short_length", &format!("{}", MacsecShortLength));
+    assert_eq!("EapolKeyInformation.descriptor_version", &format!("{}", EapolKeyDescriptorVersion));
+    assert_eq!("EapolKeyInformation.key_index", &format!("{}", EapolKeyIndex));
 }
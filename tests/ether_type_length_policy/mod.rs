@@ -0,0 +1,189 @@
+use super::*;
+
+fn registry(policy: EtherTypeLengthPolicy) -> ParserRegistry {
+    let mut result = ParserRegistry::new();
+    result.set_ether_type_length_policy(policy);
+    result
+}
+
+/// Returns an ethernet header with the given type/length field followed by the payload.
+fn frame(type_length: u16, payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type: type_length,
+    }.write(&mut result).unwrap();
+    result.extend_from_slice(payload);
+    result
+}
+
+/// Returns an ipv4 udp packet (without link layer).
+fn ipv4_udp() -> Vec<u8> {
+    let mut result = Vec::new();
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut result, &[1,2,3,4])
+        .unwrap();
+    result
+}
+
+#[test]
+fn default() {
+    assert_eq!(EtherTypeLengthPolicy::ForceEthernet2, EtherTypeLengthPolicy::default());
+    assert_eq!(EtherTypeLengthPolicy::ForceEthernet2, ParserRegistry::new().ether_type_length_policy());
+}
+
+#[test]
+fn constants() {
+    assert_eq!(1500, EtherTypeLengthPolicy::MAX_LENGTH);
+    assert_eq!(1536, EtherTypeLengthPolicy::MIN_ETHER_TYPE);
+}
+
+#[test]
+fn debug_eq() {
+    use EtherTypeLengthPolicy::*;
+    for policy in [ForceEthernet2, Ieee8023, Ieee8023Strict] {
+        assert_eq!(policy, registry(policy).ether_type_length_policy());
+    }
+    assert_eq!("Ieee8023Strict", format!("{:?}", Ieee8023Strict));
+}
+
+#[test]
+fn ether_type() {
+    use EtherTypeLengthPolicy::*;
+    let packet = frame(ether_type::IPV4, &ipv4_udp());
+    for policy in [ForceEthernet2, Ieee8023, Ieee8023Strict] {
+        let registry = registry(policy);
+
+        let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+        assert!(sliced.ip.is_some());
+        assert_eq!(&[1,2,3,4], sliced.payload);
+
+        let decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+        assert!(decoded.ip.is_some());
+        assert_eq!(&[1,2,3,4], decoded.payload);
+    }
+}
+
+#[test]
+fn force_ethernet2() {
+    // values interpreted as length by IEEE 802.3 are left as ether type
+    let payload = [0xaa, 0xaa, 0x03, 0, 0, 0, 0x08, 0x00, 1, 2];
+    let packet = frame(10, &payload);
+    let registry = registry(EtherTypeLengthPolicy::ForceEthernet2);
+
+    let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+    assert_eq!(Some(10), sliced.payload_ether_type());
+    assert_eq!(&payload, sliced.payload);
+
+    let decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+    assert_eq!(10, decoded.link.unwrap().ether_type);
+    assert_eq!(&payload, decoded.payload);
+}
+
+#[test]
+fn length() {
+    use EtherTypeLengthPolicy::*;
+    for policy in [Ieee8023, Ieee8023Strict] {
+        let registry = registry(policy);
+
+        // llc payload (spanning tree) with padding
+        {
+            let payload = [0x42, 0x42, 0x03, 0, 0, 0, 0, 0, 0, 0];
+            let packet = frame(7, &payload);
+
+            let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+            assert_eq!(7, sliced.link.as_ref().map(|LinkSlice::Ethernet2(eth)| eth.ether_type()).unwrap());
+            assert_eq!(&payload[..7], sliced.payload);
+
+            let decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+            assert_eq!(7, decoded.link.unwrap().ether_type);
+            assert!(decoded.ip.is_none());
+            assert_eq!(&payload[..7], decoded.payload);
+        }
+
+        // frame shorter then the length
+        {
+            let payload = [0x42, 0x42, 0x03, 0];
+            let packet = frame(1500, &payload);
+            assert_eq!(
+                &payload,
+                SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap().payload
+            );
+            assert_eq!(
+                &payload,
+                PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap().payload
+            );
+        }
+
+        // llc/snap header with an ether type
+        {
+            let ip = ipv4_udp();
+            let mut payload = Vec::new();
+            LlcSnapHeader::with_ether_type(ether_type::IPV4).write(&mut payload).unwrap();
+            payload.extend_from_slice(&ip);
+            let packet = frame(payload.len() as u16, &payload);
+
+            let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+            assert!(sliced.ip.is_some());
+            assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+            assert_eq!(&[1,2,3,4], sliced.payload);
+
+            let decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+            assert_eq!(payload.len() as u16, decoded.link.unwrap().ether_type);
+            assert!(decoded.ip.is_some());
+            assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+            assert_eq!(&[1,2,3,4], decoded.payload);
+        }
+
+        // llc/snap header with an organization specific protocol id
+        {
+            let payload = [0xaa, 0xaa, 0x03, 0x00, 0x00, 0x0c, 0x20, 0x00, 1, 2];
+            let packet = frame(payload.len() as u16, &payload);
+            assert_eq!(
+                &payload,
+                SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap().payload
+            );
+            assert_eq!(
+                &payload,
+                PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap().payload
+            );
+        }
+    }
+}
+
+#[test]
+fn undefined() {
+    let ip = ipv4_udp();
+    for value in [1501, 1535] {
+        let packet = frame(value, &ip);
+
+        // ieee 802.3 (payload)
+        {
+            let registry = registry(EtherTypeLengthPolicy::Ieee8023);
+
+            let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+            assert!(sliced.ip.is_none());
+            assert_eq!(&ip[..], sliced.payload);
+
+            let decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+            assert_eq!(value, decoded.link.unwrap().ether_type);
+            assert!(decoded.ip.is_none());
+            assert_eq!(&ip[..], decoded.payload);
+        }
+
+        // strict ieee 802.3 (error)
+        {
+            let registry = registry(EtherTypeLengthPolicy::Ieee8023Strict);
+            assert_matches!(
+                SlicedPacket::from_ethernet_with_registry(&packet, &registry),
+                Err(ReadError::EtherTypeLengthUndefined(v)) if v == value
+            );
+            assert_matches!(
+                PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry),
+                Err(ReadError::EtherTypeLengthUndefined(v)) if v == value
+            );
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+use std::net::IpAddr;
+
+fn udp_key() -> FlowKey {
+    FlowKey {
+        source_addr: IpAddr::from([192,168,1,1]),
+        destination_addr: IpAddr::from([192,168,1,2]),
+        ip_number: ip_number::UDP,
+        source_port: 21,
+        destination_port: 1234,
+    }
+}
+
+#[test]
+fn hash_slice() {
+    use FlowHashAlgorithm::*;
+
+    // standard check values
+    let check = b"123456789";
+    assert_eq!(0xcbf4_3926, Crc32.hash_slice(check));
+    assert_eq!(0x3926, Crc32Lo.hash_slice(check));
+    assert_eq!(0xcbf4, Crc32Hi.hash_slice(check));
+    assert_eq!(0x29b1, Crc16Ccitt.hash_slice(check));
+
+    // xor folding (odd length padded with 0)
+    assert_eq!(0x1234 ^ 0x5678 ^ 0x9a00, Xor16.hash_slice(&[0x12, 0x34, 0x56, 0x78, 0x9a]));
+    assert_eq!(0x12 ^ 0x34 ^ 0x56 ^ 0x78 ^ 0x9a, Xor8.hash_slice(&[0x12, 0x34, 0x56, 0x78, 0x9a]));
+
+    // empty data
+    assert_eq!(0, Crc32.hash_slice(&[]));
+    assert_eq!(0xffff, Crc16Ccitt.hash_slice(&[]));
+    assert_eq!(0, Xor16.hash_slice(&[]));
+}
+
+#[test]
+fn flow_hash() {
+    use FlowHashAlgorithm::*;
+    let key = udp_key();
+    assert_eq!(0x2329_78bd, key.flow_hash(Crc32));
+    assert_eq!(0x78bd, key.flow_hash(Crc32Lo));
+    assert_eq!(0x2329, key.flow_hash(Crc32Hi));
+    assert_eq!(0xc5c3, key.flow_hash(Crc16Ccitt));
+    assert_eq!(0xd607, key.flow_hash(Xor16));
+    assert_eq!(0xd1, key.flow_hash(Xor8));
+
+    // ipv6 addresses are hashed with all 16 bytes
+    let ipv6_key = FlowKey {
+        source_addr: IpAddr::from([1;16]),
+        destination_addr: IpAddr::from([2;16]),
+        ..key
+    };
+    let mut tuple = Vec::new();
+    tuple.extend_from_slice(&[1;16]);
+    tuple.extend_from_slice(&[2;16]);
+    tuple.push(ip_number::UDP);
+    tuple.extend_from_slice(&21u16.to_be_bytes());
+    tuple.extend_from_slice(&1234u16.to_be_bytes());
+    for algorithm in [Crc32, Crc32Lo, Crc32Hi, Crc16Ccitt, Xor16, Xor8] {
+        assert_eq!(algorithm.hash_slice(&tuple), ipv6_key.flow_hash(algorithm));
+    }
+}
+
+#[test]
+fn ecmp_index() {
+    use FlowHashAlgorithm::*;
+    let key = udp_key();
+    for algorithm in [Crc32, Crc32Lo, Crc32Hi, Crc16Ccitt, Xor16, Xor8] {
+        for path_count in 1..10 {
+            let index = key.ecmp_index(algorithm, path_count);
+            assert!(index < path_count);
+            assert_eq!(key.flow_hash(algorithm) as usize % path_count, index);
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn ecmp_index_zero_paths() {
+    udp_key().ecmp_index(FlowHashAlgorithm::Crc32Lo, 0);
+}
@@ -0,0 +1,237 @@
+use super::*;
+
+use std::net::IpAddr;
+
+fn key_of(builder: PacketBuilderStep<UdpHeader>) -> Option<FlowKey> {
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    FlowKey::from_sliced(&SlicedPacket::from_ethernet(&packet).unwrap())
+}
+
+fn sliced_key(packet: &[u8]) -> Option<FlowKey> {
+    FlowKey::from_sliced(&SlicedPacket::from_ethernet(packet).unwrap())
+}
+
+#[test]
+fn from_sliced() {
+    // udp ipv4
+    assert_eq!(
+        Some(FlowKey {
+            source_addr: IpAddr::from([192,168,1,1]),
+            destination_addr: IpAddr::from([192,168,1,2]),
+            ip_number: ip_number::UDP,
+            source_port: 21,
+            destination_port: 1234,
+        }),
+        key_of(
+            PacketBuilder::ethernet2([0;6], [0;6])
+                .ipv4([192,168,1,1], [192,168,1,2], 20)
+                .udp(21, 1234)
+        )
+    );
+    // udp ipv6
+    assert_eq!(
+        Some(FlowKey {
+            source_addr: IpAddr::from([1u8;16]),
+            destination_addr: IpAddr::from([2u8;16]),
+            ip_number: ip_number::UDP,
+            source_port: 1,
+            destination_port: 2,
+        }),
+        key_of(
+            PacketBuilder::ethernet2([0;6], [0;6])
+                .ipv6([1;16], [2;16], 20)
+                .udp(1, 2)
+        )
+    );
+    // tcp
+    {
+        let builder = PacketBuilder::ethernet2([0;6], [0;6])
+            .ipv4([1,2,3,4], [5,6,7,8], 20)
+            .tcp(80, 1234, 1, 1000);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        let key = sliced_key(&packet).unwrap();
+        assert_eq!(ip_number::TCP, key.ip_number);
+        assert_eq!(80, key.source_port);
+        assert_eq!(1234, key.destination_port);
+    }
+    // icmpv4
+    {
+        let builder = PacketBuilder::ethernet2([0;6], [0;6])
+            .ipv4([1,2,3,4], [5,6,7,8], 20)
+            .icmpv4_echo_request(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        let key = sliced_key(&packet).unwrap();
+        assert_eq!(ip_number::ICMP, key.ip_number);
+        assert_eq!(0, key.source_port);
+        assert_eq!(0, key.destination_port);
+    }
+    // icmpv6
+    {
+        let builder = PacketBuilder::ethernet2([0;6], [0;6])
+            .ipv6([1;16], [2;16], 20)
+            .icmpv6_echo_request(1, 2);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, &[]).unwrap();
+        assert_eq!(ip_number::IPV6_ICMP, sliced_key(&packet).unwrap().ip_number);
+    }
+    // unknown transport protocol
+    {
+        let builder = PacketBuilder::ethernet2([0;6], [0;6])
+            .ipv4([1,2,3,4], [5,6,7,8], 20);
+        let mut packet = Vec::with_capacity(builder.size(0));
+        builder.write(&mut packet, ip_number::EXP0, &[]).unwrap();
+        let key = sliced_key(&packet).unwrap();
+        assert_eq!(ip_number::EXP0, key.ip_number);
+        assert_eq!(0, key.source_port);
+    }
+    // no ip header
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [0;6],
+            destination: [0;6],
+            ether_type: 0x1234,
+        }.write(&mut packet).unwrap();
+        assert_eq!(None, sliced_key(&packet));
+    }
+}
+
+#[test]
+fn from_headers() {
+    let ipv4 = IpHeader::Version4(
+        Ipv4Header::new(0, 20, ip_number::UDP, [192,168,1,1], [192,168,1,2]),
+        Default::default()
+    );
+    // udp
+    assert_eq!(
+        Ok(FlowKey {
+            source_addr: IpAddr::from([192,168,1,1]),
+            destination_addr: IpAddr::from([192,168,1,2]),
+            ip_number: ip_number::UDP,
+            source_port: 21,
+            destination_port: 1234,
+        }),
+        FlowKey::from_headers(&ipv4, Some(&TransportHeader::Udp(UdpHeader{
+            source_port: 21,
+            destination_port: 1234,
+            length: 0,
+            checksum: 0,
+        })))
+    );
+    // tcp & ipv6
+    {
+        let ipv6 = IpHeader::Version6(
+            Ipv6Header {
+                source: [1;16],
+                destination: [2;16],
+                ..Default::default()
+            },
+            Default::default()
+        );
+        let key = FlowKey::from_headers(
+            &ipv6,
+            Some(&TransportHeader::Tcp(TcpHeader::new(80, 1234, 1, 1000)))
+        ).unwrap();
+        assert_eq!(IpAddr::from([1u8;16]), key.source_addr);
+        assert_eq!(IpAddr::from([2u8;16]), key.destination_addr);
+        assert_eq!(ip_number::TCP, key.ip_number);
+        assert_eq!(80, key.source_port);
+        assert_eq!(1234, key.destination_port);
+    }
+    // no transport header (ip number of the ip header)
+    {
+        let key = FlowKey::from_headers(&ipv4, None).unwrap();
+        assert_eq!(ip_number::UDP, key.ip_number);
+        assert_eq!(0, key.source_port);
+        assert_eq!(0, key.destination_port);
+    }
+    // matches the key of the written packet
+    {
+        let icmp = TransportHeader::Icmpv4(Icmpv4Header::new(Icmpv4Type::EchoRequest(IcmpEchoHeader{ id: 1, seq: 2 })));
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([0;6], [0;6])
+            .ipv4([1,2,3,4], [5,6,7,8], 20)
+            .icmpv4_echo_request(1, 2)
+            .write(&mut packet, &[])
+            .unwrap();
+        let ipv4 = IpHeader::Version4(
+            Ipv4Header::new(0, 20, ip_number::ICMP, [1,2,3,4], [5,6,7,8]),
+            Default::default()
+        );
+        assert_eq!(sliced_key(&packet), FlowKey::from_headers(&ipv4, Some(&icmp)).ok());
+    }
+}
+
+#[test]
+fn reversed_bidirectional() {
+    let key = FlowKey {
+        source_addr: IpAddr::from([192,168,1,2]),
+        destination_addr: IpAddr::from([192,168,1,1]),
+        ip_number: ip_number::TCP,
+        source_port: 80,
+        destination_port: 1234,
+    };
+    let reversed = key.reversed();
+    assert_eq!(
+        FlowKey {
+            source_addr: IpAddr::from([192,168,1,1]),
+            destination_addr: IpAddr::from([192,168,1,2]),
+            ip_number: ip_number::TCP,
+            source_port: 1234,
+            destination_port: 80,
+        },
+        reversed
+    );
+    assert_eq!(key, reversed.reversed());
+    assert_eq!(reversed, key.bidirectional());
+    assert_eq!(reversed, reversed.bidirectional());
+
+    // same address, ports decide
+    let key = FlowKey {
+        source_addr: IpAddr::from([1,1,1,1]),
+        destination_addr: IpAddr::from([1,1,1,1]),
+        ip_number: ip_number::UDP,
+        source_port: 2,
+        destination_port: 1,
+    };
+    assert_eq!(1, key.bidirectional().source_port);
+    assert_eq!(key.bidirectional(), key.reversed().bidirectional());
+}
+
+#[test]
+fn debug_clone_eq_hash() {
+    use std::collections::HashSet;
+    let key = FlowKey {
+        source_addr: IpAddr::from([1,1,1,1]),
+        destination_addr: IpAddr::from([2,2,2,2]),
+        ip_number: ip_number::UDP,
+        source_port: 1,
+        destination_port: 2,
+    };
+    assert_eq!(key, key.clone());
+    let mut set = HashSet::new();
+    set.insert(key);
+    assert!(set.contains(&key));
+    println!("{:?}", key);
+}
+
+#[test]
+fn ord() {
+    use std::collections::BTreeSet;
+    let a = FlowKey {
+        source_addr: IpAddr::from([1,1,1,1]),
+        destination_addr: IpAddr::from([2,2,2,2]),
+        ip_number: ip_number::UDP,
+        source_port: 1,
+        destination_port: 2,
+    };
+    let mut b = a;
+    b.source_port = 2;
+    assert!(a < b);
+
+    let set: BTreeSet<FlowKey> = [b, a, b].iter().copied().collect();
+    assert_eq!(vec![a, b], set.into_iter().collect::<Vec<_>>());
+}
@@ -0,0 +1,120 @@
+use super::*;
+
+fn udp_packet(source: [u8;4], destination: [u8;4], source_port: u16, destination_port: u16) -> Vec<u8> {
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4(source, destination, 20)
+        .udp(source_port, destination_port)
+        .write(&mut packet, &[])
+        .unwrap();
+    packet
+}
+
+fn key(source: [u8;4], destination: [u8;4], source_port: u16, destination_port: u16) -> FlowKey {
+    FlowKey {
+        source_addr: source.into(),
+        destination_addr: destination.into(),
+        ip_number: ip_number::UDP,
+        source_port,
+        destination_port,
+    }
+}
+
+#[test]
+fn new() {
+    let streams = FlowStreams::<u32>::new(true);
+    assert!(streams.is_bidirectional());
+    assert!(streams.is_empty());
+    assert_eq!(0, streams.len());
+    assert!(streams.unclassified().is_empty());
+    assert!(!FlowStreams::<u32>::new(false).is_bidirectional());
+}
+
+#[test]
+fn group_by() {
+    let a = key([1,1,1,1], [2,2,2,2], 1, 2);
+    let b = key([3,3,3,3], [2,2,2,2], 1, 2);
+    let items = [
+        (1, Some(a)),
+        (2, Some(b)),
+        (3, None),
+        (4, Some(a.reversed())),
+        (5, Some(b)),
+        (6, Some(a)),
+    ];
+
+    // bidirectional
+    {
+        let streams = FlowStreams::group_by(items.iter().copied(), true, |(_, key)| *key);
+        assert_eq!(2, streams.len());
+        assert!(!streams.is_empty());
+
+        let values = |stream: &[(i32, Option<FlowKey>)]| stream.iter().map(|(v, _)| *v).collect::<Vec<_>>();
+        assert_eq!(vec![1, 4, 6], values(streams.get(&a).unwrap()));
+        assert_eq!(vec![1, 4, 6], values(streams.get(&a.reversed()).unwrap()));
+        assert_eq!(vec![2, 5], values(streams.get(&b).unwrap()));
+        assert_eq!(None, streams.get(&key([4,4,4,4], [2,2,2,2], 1, 2)));
+        assert_eq!(&[(3, None)], streams.unclassified());
+
+        // streams are ordered by their first packet & use its key
+        let keys: Vec<_> = streams.iter().map(|(key, _)| *key).collect();
+        assert_eq!(vec![a, b], keys);
+        assert_eq!(2, (&streams).into_iter().len());
+
+        let mut iter = streams.into_iter();
+        assert_eq!(2, iter.len());
+        let (first_key, first) = iter.next().unwrap();
+        assert_eq!(a, first_key);
+        assert_eq!(vec![1, 4, 6], first.map(|(v, _)| v).collect::<Vec<_>>());
+        let (second_key, second) = iter.next().unwrap();
+        assert_eq!(b, second_key);
+        assert_eq!(vec![2, 5], second.map(|(v, _)| v).collect::<Vec<_>>());
+        assert!(iter.next().is_none());
+    }
+
+    // unidirectional
+    {
+        let streams = FlowStreams::group_by(items.iter().copied(), false, |(_, key)| *key);
+        assert_eq!(3, streams.len());
+        let keys: Vec<_> = streams.iter().map(|(key, _)| *key).collect();
+        assert_eq!(vec![a, b, a.reversed()], keys);
+        assert_eq!(2, streams.get(&a).unwrap().len());
+        assert_eq!(1, streams.get(&a.reversed()).unwrap().len());
+    }
+}
+
+#[test]
+fn from_sliced_push() {
+    let packets = [
+        udp_packet([192,168,1,1], [192,168,1,2], 1000, 53),
+        udp_packet([192,168,1,2], [192,168,1,1], 53, 1000),
+        udp_packet([192,168,1,1], [192,168,1,2], 1001, 53),
+    ];
+    let mut streams = FlowStreams::from_sliced(
+        packets.iter().map(|p| SlicedPacket::from_ip(p).unwrap()),
+        true
+    );
+    assert_eq!(2, streams.len());
+    assert_eq!(2, streams.get(&key([192,168,1,1], [192,168,1,2], 1000, 53)).unwrap().len());
+
+    // packet without flow key (non ip)
+    let mut non_ip = Vec::new();
+    Ethernet2Header {
+        source: [1;6],
+        destination: [2;6],
+        ether_type: 0x1234,
+    }.write(&mut non_ip).unwrap();
+    streams.push(SlicedPacket::from_ethernet(&non_ip).unwrap());
+    assert_eq!(1, streams.unclassified().len());
+
+    streams.push(SlicedPacket::from_ip(&packets[2]).unwrap());
+    assert_eq!(2, streams.get(&key([192,168,1,1], [192,168,1,2], 1001, 53)).unwrap().len());
+}
+
+#[test]
+fn debug_clone_eq() {
+    let streams = FlowStreams::group_by([1, 2], true, |_| None);
+    assert_eq!(streams, streams.clone());
+    assert!(format!("{:?}", streams).starts_with("FlowStreams {"));
+    assert!(format!("{:?}", streams.iter()).starts_with("FlowStreamsIter {"));
+    assert!(format!("{:?}", streams.clone().into_iter()).starts_with("FlowStreamsIntoIter {"));
+}
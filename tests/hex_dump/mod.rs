@@ -0,0 +1,156 @@
+use super::*;
+
+use etherparse::hex_dump::{self, HexDumpError};
+
+fn udp_packet(payload_len: usize) -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let payload: Vec<u8> = (0..payload_len).map(|v| v as u8).collect();
+    let mut result = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut result, &payload).unwrap();
+    result
+}
+
+/// Formats the data like "tcpdump -xx" (or "tcpdump -X" with the ascii column).
+fn tcpdump_format(data: &[u8], ascii: bool) -> String {
+    let mut result = String::new();
+    for (index, line) in data.chunks(16).enumerate() {
+        result += &format!("\t0x{:04x}:  ", index*16);
+        let groups: Vec<String> = line.chunks(2).map(|g| g.iter().map(|v| format!("{:02x}", v)).collect()).collect();
+        result += &groups.join(" ");
+        if ascii {
+            result += "  ";
+            result.extend(line.iter().map(|v| if v.is_ascii_graphic() { *v as char } else { '.' }));
+        }
+        result += "\n";
+    }
+    result
+}
+
+/// Formats the data like the wireshark "Copy as Hex + ASCII Dump".
+fn wireshark_format(data: &[u8]) -> String {
+    let mut result = String::new();
+    for (index, line) in data.chunks(16).enumerate() {
+        result += &format!("{:04x}  ", index*16);
+        for (i, v) in line.iter().enumerate() {
+            if 8 == i {
+                result += " ";
+            }
+            result += &format!("{:02x} ", v);
+        }
+        result += "  ";
+        result.extend(line.iter().map(|v| if v.is_ascii_graphic() { *v as char } else { '.' }));
+        result += "\n";
+    }
+    result
+}
+
+#[test]
+fn parse() {
+    for payload_len in [0, 1, 2, 3, 10, 18, 34, 100] {
+        let packet = udp_packet(payload_len);
+        assert_eq!(packet, hex_dump::parse(&tcpdump_format(&packet, false)).unwrap());
+        assert_eq!(packet, hex_dump::parse(&tcpdump_format(&packet, true)).unwrap());
+        assert_eq!(packet, hex_dump::parse(&wireshark_format(&packet)).unwrap());
+
+        // the result can directly be sliced
+        let parsed = hex_dump::parse(&wireshark_format(&packet)).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&parsed).unwrap();
+        assert_eq!(payload_len, sliced.payload.len());
+    }
+}
+
+#[test]
+fn parse_ascii_column_looking_like_hex() {
+    // ascii column of the last line consists of hex digits
+    let text = "0000  61 62 63 64 65 66 67 68 69 6a 6b 6c 6d 6e 6f 70   abcdefghijklmnop\n\
+                0010  61 62   ab\n";
+    assert_eq!(
+        b"abcdefghijklmnopab".to_vec(),
+        hex_dump::parse(text).unwrap()
+    );
+
+    // ascii column separated with a single space (line width is
+    // determined by the offsets)
+    let text = "0000 61 62 63 64 abcd\n\
+                0004 65 66 67 68 efgh\n\
+                0008 61 62 63 64 abcd\n";
+    assert_eq!(
+        b"abcdefghabcd".to_vec(),
+        hex_dump::parse(text).unwrap()
+    );
+}
+
+#[test]
+fn parse_skips_other_lines() {
+    let packet = udp_packet(4);
+    let text = format!(
+        "12:00:00.000000 IP 192.168.1.1.21 > 192.168.1.2.1234: UDP, length 4\n\n{}\nsome trailing note\n",
+        tcpdump_format(&packet, false)
+    );
+    assert_eq!(packet, hex_dump::parse(&text).unwrap());
+}
+
+#[test]
+fn parse_packets() {
+    let a = udp_packet(4);
+    let b = udp_packet(40);
+    let text = format!(
+        "first packet\n{}second packet\n{}",
+        tcpdump_format(&a, true),
+        wireshark_format(&b)
+    );
+    assert_eq!(vec![a.clone(), b.clone()], hex_dump::parse_packets(&text).unwrap());
+    assert_eq!(
+        Err(HexDumpError::MultiplePackets(2)),
+        hex_dump::parse(&text)
+    );
+}
+
+#[test]
+fn parse_errors() {
+    // no data
+    assert_eq!(Err(HexDumpError::NoData), hex_dump::parse(""));
+    assert_eq!(Err(HexDumpError::NoData), hex_dump::parse_packets("no hex dump\nin here\n"));
+
+    // first line does not start at 0
+    assert_eq!(
+        Err(HexDumpError::OffsetMismatch{ line: 2, expected: 0, actual: 0x10 }),
+        hex_dump::parse("header\n0x0010:  0102 0304\n")
+    );
+
+    // missing line
+    assert_eq!(
+        Err(HexDumpError::OffsetMismatch{ line: 2, expected: 4, actual: 0x20 }),
+        hex_dump::parse("0x0000:  0102 0304\n0x0020:  0102 0304\n")
+    );
+
+    // offset going backwards
+    assert_eq!(
+        Err(HexDumpError::OffsetMismatch{ line: 3, expected: 6, actual: 0x2 }),
+        hex_dump::parse("0x0000:  0102 0304\n0x0004:  0506\n0x0002:  0102\n")
+    );
+}
+
+#[test]
+fn hex_dump_error_display() {
+    assert_eq!(
+        "HexDumpError: No hex dump lines found in the text.",
+        &format!("{}", HexDumpError::NoData)
+    );
+    assert_eq!(
+        "HexDumpError: Expected one packet but the text contains 3 packets.",
+        &format!("{}", HexDumpError::MultiplePackets(3))
+    );
+    assert_eq!(
+        "HexDumpError: Unexpected offset 0x0020 in line 2 (expected offset 0x0010 based on the bytes read before).",
+        &format!("{}", HexDumpError::OffsetMismatch{ line: 2, expected: 0x10, actual: 0x20 })
+    );
+}
+
+#[test]
+fn hex_dump_error_source() {
+    use std::error::Error;
+    assert!(HexDumpError::NoData.source().is_none());
+}
@@ -0,0 +1,513 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn erspan_type_any()(
+        type_3 in any::<bool>(),
+        index in 0..=ErspanHeader::MAX_INDEX,
+        timestamp in any::<u32>(),
+        security_group_tag in any::<u16>(),
+        p_flag in any::<bool>(),
+        frame_type in 0..=ErspanHeader::MAX_FRAME_TYPE,
+        hardware_id in 0..=ErspanHeader::MAX_HARDWARE_ID,
+        egress in any::<bool>(),
+        granularity in 0..=ErspanHeader::MAX_GRANULARITY,
+        platform_specific in proptest::option::of(any::<[u8;8]>()),
+    ) -> ErspanType {
+        if type_3 {
+            ErspanType::Type3 {
+                timestamp,
+                security_group_tag,
+                p_flag,
+                frame_type,
+                hardware_id,
+                egress,
+                granularity,
+                platform_specific,
+            }
+        } else {
+            ErspanType::Type2 { index }
+        }
+    }
+}
+
+prop_compose! {
+    fn erspan_any()(
+        vlan in 0..=ErspanHeader::MAX_VLAN,
+        cos in 0..=ErspanHeader::MAX_COS,
+        encapsulation_type in 0..=ErspanHeader::MAX_ENCAPSULATION_TYPE,
+        truncated in any::<bool>(),
+        session_id in 0..=ErspanHeader::MAX_SESSION_ID,
+        erspan_type in erspan_type_any(),
+    ) -> ErspanHeader {
+        ErspanHeader {
+            vlan,
+            cos,
+            encapsulation_type,
+            truncated,
+            session_id,
+            erspan_type,
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in erspan_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(header.header_len() + 2, buffer.len());
+        assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..header.header_len()]);
+
+        // from_slice
+        {
+            let (decoded, rest) = ErspanHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &ErspanHeader::read(&mut cursor).unwrap());
+            assert_eq!(header.header_len() as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = ErspanHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(header.vlan, slice.vlan());
+            assert_eq!(header.cos, slice.cos());
+            assert_eq!(header.encapsulation_type, slice.encapsulation_type());
+            assert_eq!(header.truncated, slice.truncated());
+            assert_eq!(header.session_id, slice.session_id());
+            match &header.erspan_type {
+                ErspanType::Type2{ index } => {
+                    assert_eq!(ErspanHeader::VERSION_TYPE_2, slice.version());
+                    assert_eq!(ether_type::ERSPAN_TYPE_2, header.protocol_type());
+                    assert_eq!(Some(*index), slice.index());
+                    assert_eq!(None, slice.timestamp());
+                    assert_eq!(None, slice.security_group_tag());
+                    assert_eq!(None, slice.frame_type());
+                    assert_eq!(None, slice.hardware_id());
+                    assert_eq!(None, slice.egress());
+                    assert_eq!(None, slice.platform_specific());
+                    assert!(slice.is_ethernet_frame());
+                },
+                ErspanType::Type3{
+                    timestamp,
+                    security_group_tag,
+                    frame_type,
+                    hardware_id,
+                    egress,
+                    platform_specific,
+                    ..
+                } => {
+                    assert_eq!(ErspanHeader::VERSION_TYPE_3, slice.version());
+                    assert_eq!(ether_type::ERSPAN_TYPE_3, header.protocol_type());
+                    assert_eq!(None, slice.index());
+                    assert_eq!(Some(*timestamp), slice.timestamp());
+                    assert_eq!(Some(*security_group_tag), slice.security_group_tag());
+                    assert_eq!(Some(*frame_type), slice.frame_type());
+                    assert_eq!(Some(*hardware_id), slice.hardware_id());
+                    assert_eq!(Some(*egress), slice.egress());
+                    assert_eq!(platform_specific.as_ref().map(|v| &v[..]), slice.platform_specific());
+                    assert_eq!(ErspanHeader::FRAME_TYPE_ETHERNET == *frame_type, slice.is_ethernet_frame());
+                },
+            }
+            assert_eq!(header, &slice.to_header());
+        }
+        // length errors
+        for len in 0..header.header_len() {
+            assert_matches!(
+                ErspanHeaderSlice::from_slice(&buffer[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+            assert_matches!(
+                ErspanHeader::read(&mut Cursor::new(&buffer[..len])),
+                Err(ReadError::IoError(_))
+            );
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn unsupported_version(
+        version in (0u8..=0xf).prop_filter(
+            "version must be unsupported",
+            |v| ErspanHeader::VERSION_TYPE_2 != *v && ErspanHeader::VERSION_TYPE_3 != *v
+        )
+    ) {
+        let bytes = [version << 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_matches!(
+            ErspanHeaderSlice::from_slice(&bytes),
+            Err(ReadError::ErspanUnsupportedVersion(value)) if value == version
+        );
+        assert_matches!(
+            ErspanHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::ErspanUnsupportedVersion(value)) if value == version
+        );
+    }
+}
+
+#[test]
+fn header_len() {
+    let mut header = ErspanHeader {
+        vlan: 0,
+        cos: 0,
+        encapsulation_type: 0,
+        truncated: false,
+        session_id: 0,
+        erspan_type: ErspanType::Type2{ index: 0 },
+    };
+    assert_eq!(ErspanHeader::TYPE_2_LEN, header.header_len());
+    header.erspan_type = ErspanType::Type3 {
+        timestamp: 0,
+        security_group_tag: 0,
+        p_flag: false,
+        frame_type: 0,
+        hardware_id: 0,
+        egress: false,
+        granularity: 0,
+        platform_specific: None,
+    };
+    assert_eq!(ErspanHeader::TYPE_3_LEN, header.header_len());
+    if let ErspanType::Type3{ platform_specific, .. } = &mut header.erspan_type {
+        *platform_specific = Some([0; 8]);
+    }
+    assert_eq!(ErspanHeader::MAX_LEN, header.header_len());
+}
+
+#[test]
+fn to_bytes_value_error() {
+    use ErrorField::*;
+    use ValueError::*;
+
+    let base = ErspanHeader {
+        vlan: 0,
+        cos: 0,
+        encapsulation_type: 0,
+        truncated: false,
+        session_id: 0,
+        erspan_type: ErspanType::Type2{ index: 0 },
+    };
+    let type_3 = ErspanType::Type3 {
+        timestamp: 0,
+        security_group_tag: 0,
+        p_flag: false,
+        frame_type: 0,
+        hardware_id: 0,
+        egress: false,
+        granularity: 0,
+        platform_specific: None,
+    };
+
+    {
+        let header = ErspanHeader { vlan: ErspanHeader::MAX_VLAN + 1, ..base.clone() };
+        assert_eq!(
+            Err(U16TooLarge{ value: ErspanHeader::MAX_VLAN + 1, max: ErspanHeader::MAX_VLAN, field: ErspanVlan }),
+            header.to_bytes()
+        );
+    }
+    {
+        let header = ErspanHeader { cos: ErspanHeader::MAX_COS + 1, ..base.clone() };
+        assert_eq!(
+            Err(U8TooLarge{ value: ErspanHeader::MAX_COS + 1, max: ErspanHeader::MAX_COS, field: ErspanCos }),
+            header.to_bytes()
+        );
+    }
+    {
+        let header = ErspanHeader { encapsulation_type: ErspanHeader::MAX_ENCAPSULATION_TYPE + 1, ..base.clone() };
+        assert_eq!(
+            Err(U8TooLarge{
+                value: ErspanHeader::MAX_ENCAPSULATION_TYPE + 1,
+                max: ErspanHeader::MAX_ENCAPSULATION_TYPE,
+                field: ErspanEncapsulationType
+            }),
+            header.to_bytes()
+        );
+    }
+    {
+        let header = ErspanHeader { session_id: ErspanHeader::MAX_SESSION_ID + 1, ..base.clone() };
+        assert_eq!(
+            Err(U16TooLarge{ value: ErspanHeader::MAX_SESSION_ID + 1, max: ErspanHeader::MAX_SESSION_ID, field: ErspanSessionId }),
+            header.to_bytes()
+        );
+    }
+    {
+        let header = ErspanHeader {
+            erspan_type: ErspanType::Type2{ index: ErspanHeader::MAX_INDEX + 1 },
+            ..base.clone()
+        };
+        assert_eq!(
+            Err(U32TooLarge{ value: ErspanHeader::MAX_INDEX + 1, max: ErspanHeader::MAX_INDEX, field: ErspanIndex }),
+            header.to_bytes()
+        );
+    }
+    {
+        let mut header = ErspanHeader { erspan_type: type_3.clone(), ..base.clone() };
+        if let ErspanType::Type3{ frame_type, .. } = &mut header.erspan_type {
+            *frame_type = ErspanHeader::MAX_FRAME_TYPE + 1;
+        }
+        assert_eq!(
+            Err(U8TooLarge{ value: ErspanHeader::MAX_FRAME_TYPE + 1, max: ErspanHeader::MAX_FRAME_TYPE, field: ErspanFrameType }),
+            header.to_bytes()
+        );
+    }
+    {
+        let mut header = ErspanHeader { erspan_type: type_3.clone(), ..base.clone() };
+        if let ErspanType::Type3{ hardware_id, .. } = &mut header.erspan_type {
+            *hardware_id = ErspanHeader::MAX_HARDWARE_ID + 1;
+        }
+        assert_eq!(
+            Err(U8TooLarge{ value: ErspanHeader::MAX_HARDWARE_ID + 1, max: ErspanHeader::MAX_HARDWARE_ID, field: ErspanHardwareId }),
+            header.to_bytes()
+        );
+    }
+    {
+        let mut header = ErspanHeader { erspan_type: type_3, ..base };
+        if let ErspanType::Type3{ granularity, .. } = &mut header.erspan_type {
+            *granularity = ErspanHeader::MAX_GRANULARITY + 1;
+        }
+        assert_eq!(
+            Err(U8TooLarge{ value: ErspanHeader::MAX_GRANULARITY + 1, max: ErspanHeader::MAX_GRANULARITY, field: ErspanGranularity }),
+            header.to_bytes()
+        );
+        assert_matches!(
+            header.write(&mut Vec::new()),
+            Err(WriteError::ValueError(_))
+        );
+    }
+}
+
+/// Builds an ipv4 packet carrying the given GRE header & payload.
+fn gre_packet(gre: &GreHeader, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    Ipv4Header::new(
+        (gre.header_len() + payload.len()) as u16,
+        64,
+        ip_number::GRE,
+        [1, 2, 3, 4],
+        [5, 6, 7, 8],
+    ).write(&mut packet).unwrap();
+    gre.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet
+}
+
+/// Returns a mirrored ethernet frame.
+fn mirrored_frame() -> Vec<u8> {
+    let mut frame = Vec::new();
+    PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+        .ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut frame, &[1, 2, 3, 4])
+        .unwrap();
+    frame
+}
+
+#[test]
+fn sliced_packet() {
+    let frame = mirrored_frame();
+    let headers = [
+        ErspanHeader {
+            vlan: 100,
+            cos: 5,
+            encapsulation_type: 0,
+            truncated: false,
+            session_id: 42,
+            erspan_type: ErspanType::Type2{ index: 7 },
+        },
+        ErspanHeader {
+            vlan: 200,
+            cos: 1,
+            encapsulation_type: 0,
+            truncated: true,
+            session_id: 0x3ff,
+            erspan_type: ErspanType::Type3 {
+                timestamp: 0x1234_5678,
+                security_group_tag: 0xabcd,
+                p_flag: false,
+                frame_type: ErspanHeader::FRAME_TYPE_ETHERNET,
+                hardware_id: 3,
+                egress: true,
+                granularity: 3,
+                platform_specific: Some([1, 2, 3, 4, 5, 6, 7, 8]),
+            },
+        },
+    ];
+    for erspan in headers {
+        let gre = GreHeader {
+            sequence_number: Some(1),
+            ..GreHeader::new(erspan.protocol_type())
+        };
+        let mut gre_payload = erspan.to_bytes().unwrap().to_vec();
+        gre_payload.extend_from_slice(&frame);
+        let packet = gre_packet(&gre, &gre_payload);
+
+        // slicing
+        {
+            let sliced = SlicedPacket::from_ip(&packet).unwrap();
+            assert_eq!(gre, sliced.gre.as_ref().unwrap().to_header());
+            assert_eq!(erspan, sliced.erspan.as_ref().unwrap().to_header());
+            assert_eq!(&frame[..], sliced.payload);
+
+            let sliced_inner = sliced.inner.unwrap();
+            assert_matches!(sliced_inner.link, Some(LinkSlice::Ethernet2(_)));
+            assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv4(_, _)));
+            assert_matches!(sliced_inner.transport, Some(TransportSlice::Udp(_)));
+            assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+        }
+        // decoding
+        {
+            let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+            assert_eq!(Some(gre.clone()), decoded.gre);
+            assert_eq!(Some(erspan.clone()), decoded.erspan);
+            assert_eq!(&frame[..], decoded.payload);
+
+            let decoded_inner = decoded.inner.unwrap();
+            assert_eq!([1, 2, 3, 4, 5, 6], decoded_inner.link.unwrap().source);
+            assert_matches!(decoded_inner.ip, Some(IpHeader::Version4(_, _)));
+            assert_matches!(decoded_inner.transport, Some(TransportHeader::Udp(_)));
+            assert_eq!(&[1, 2, 3, 4], decoded_inner.payload);
+        }
+        // roundtrip
+        assert!(etherparse::roundtrip::check_ip(&packet).unwrap().is_empty());
+    }
+}
+
+#[test]
+fn sliced_packet_type_1() {
+    // type I packets have no sequence number & no erspan header
+    let frame = mirrored_frame();
+    let gre = GreHeader::new(ether_type::ERSPAN_TYPE_2);
+    let packet = gre_packet(&gre, &frame);
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert!(sliced.gre.is_some());
+    assert_eq!(None, sliced.erspan);
+    let sliced_inner = sliced.inner.unwrap();
+    assert_matches!(sliced_inner.link, Some(LinkSlice::Ethernet2(_)));
+    assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+
+    let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_eq!(Some(gre), decoded.gre);
+    assert_eq!(None, decoded.erspan);
+    assert_eq!([1, 2, 3, 4, 5, 6], decoded.inner.unwrap().link.unwrap().source);
+}
+
+#[test]
+fn sliced_packet_non_ethernet_frame() {
+    // frames other than ethernet frames are not decoded
+    let erspan = ErspanHeader {
+        vlan: 0,
+        cos: 0,
+        encapsulation_type: 0,
+        truncated: false,
+        session_id: 1,
+        erspan_type: ErspanType::Type3 {
+            timestamp: 0,
+            security_group_tag: 0,
+            p_flag: false,
+            frame_type: 2,
+            hardware_id: 0,
+            egress: false,
+            granularity: 0,
+            platform_specific: None,
+        },
+    };
+    let gre = GreHeader {
+        sequence_number: Some(1),
+        ..GreHeader::new(ether_type::ERSPAN_TYPE_3)
+    };
+    let mut gre_payload = erspan.to_bytes().unwrap().to_vec();
+    gre_payload.extend_from_slice(&[1, 2, 3, 4]);
+    let packet = gre_packet(&gre, &gre_payload);
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert_eq!(erspan, sliced.erspan.unwrap().to_header());
+    assert_eq!(None, sliced.inner);
+
+    let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_eq!(Some(erspan), decoded.erspan);
+    assert_eq!(None, decoded.inner);
+}
+
+#[test]
+fn sliced_packet_errors() {
+    // truncated erspan header
+    let gre = GreHeader {
+        sequence_number: Some(1),
+        ..GreHeader::new(ether_type::ERSPAN_TYPE_2)
+    };
+    let packet = gre_packet(&gre, &[0x10, 0, 0, 0]);
+    assert_matches!(
+        SlicedPacket::from_ip(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+
+    // unsupported version
+    let packet = gre_packet(&gre, &[0x30, 0, 0, 0, 0, 0, 0, 0]);
+    assert_matches!(
+        SlicedPacket::from_ip(&packet),
+        Err(ReadError::ErspanUnsupportedVersion(3))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice(&packet),
+        Err(ReadError::ErspanUnsupportedVersion(3))
+    );
+}
+
+#[test]
+fn repair_fix() {
+    let erspan = ErspanHeader {
+        vlan: 1,
+        cos: 0,
+        encapsulation_type: 0,
+        truncated: false,
+        session_id: 2,
+        erspan_type: ErspanType::Type2{ index: 3 },
+    };
+    let mut gre_payload = erspan.to_bytes().unwrap().to_vec();
+    gre_payload.extend_from_slice(&mirrored_frame());
+    let packet = gre_packet(
+        &GreHeader {
+            sequence_number: Some(1),
+            ..GreHeader::new(erspan.protocol_type())
+        },
+        &gre_payload,
+    );
+
+    // switch to a type III header and let the gre header be repaired
+    let mut headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+    let type_3 = ErspanHeader {
+        erspan_type: ErspanType::Type3 {
+            timestamp: 1,
+            security_group_tag: 2,
+            p_flag: false,
+            frame_type: ErspanHeader::FRAME_TYPE_ETHERNET,
+            hardware_id: 4,
+            egress: false,
+            granularity: 0,
+            platform_specific: None,
+        },
+        ..erspan
+    };
+    headers.erspan = Some(type_3.clone());
+    headers.gre.as_mut().unwrap().sequence_number = None;
+    etherparse::repair::fix(&mut headers).unwrap();
+    let repaired = etherparse::repair::to_bytes(&headers).unwrap();
+
+    let decoded = PacketHeaders::from_ip_slice(&repaired).unwrap();
+    let gre = decoded.gre.unwrap();
+    assert_eq!(ether_type::ERSPAN_TYPE_3, gre.protocol_type);
+    assert!(gre.sequence_number.is_some());
+    assert_eq!(Some(type_3), decoded.erspan);
+    assert_eq!([1, 2, 3, 4, 5, 6], decoded.inner.unwrap().link.unwrap().source);
+}
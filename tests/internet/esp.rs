@@ -0,0 +1,133 @@
+use super::super::*;
+
+proptest! {
+    #[test]
+    fn write_from_slice(spi in any::<u32>(), sequence_number in any::<u32>()) {
+        let header = EspHeader{ spi, sequence_number };
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        assert_eq!(&header.to_bytes()[..], &buffer[..]);
+        assert_eq!(&spi.to_be_bytes(), &buffer[..4]);
+        assert_eq!(&sequence_number.to_be_bytes(), &buffer[4..]);
+        buffer.extend_from_slice(&[1, 2]);
+
+        // from_slice
+        {
+            let (decoded, rest) = EspHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // slice
+        {
+            let slice = EspHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..8], slice.slice());
+            assert_eq!(spi, slice.spi());
+            assert_eq!(sequence_number, slice.sequence_number());
+            assert_eq!(header, slice.to_header());
+        }
+        // length error
+        for len in 0..EspHeader::SERIALIZED_SIZE {
+            assert_matches!(
+                EspHeaderSlice::from_slice(&buffer[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+            assert_matches!(
+                EspHeader::from_slice(&buffer[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+        }
+    }
+}
+
+/// Returns the esp header followed by the encrypted data.
+fn esp_payload(header: &EspHeader) -> Vec<u8> {
+    let mut result = header.to_bytes().to_vec();
+    result.extend_from_slice(&[0xab;16]);
+    result
+}
+
+#[test]
+fn sliced_packet() {
+    let header = EspHeader{ spi: 0x1234_5678, sequence_number: 3 };
+    let payload = esp_payload(&header);
+
+    // ipv4
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .write(&mut packet, ip_number::ENCAP_SEC, &payload)
+            .unwrap();
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(header, sliced.esp.unwrap().to_header());
+        assert_eq!(Some(TransportSlice::Unknown(ip_number::ENCAP_SEC)), sliced.transport);
+        assert_eq!(&[0xab;16], sliced.payload);
+
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(header.clone()), decoded.esp);
+        assert_eq!(None, decoded.transport);
+        assert_eq!(&[0xab;16], decoded.payload);
+    }
+    // ipv6 with an authentication header
+    {
+        let auth = IpAuthenticationHeader::new(ip_number::ENCAP_SEC, 1, 2, &[3;12]).unwrap();
+        let mut ip_payload = Vec::new();
+        auth.write(&mut ip_payload).unwrap();
+        ip_payload.extend_from_slice(&payload);
+        let mut packet = Vec::new();
+        PacketBuilder::ipv6([1;16], [2;16], 20)
+            .write(&mut packet, ip_number::AUTH, &ip_payload)
+            .unwrap();
+
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        match &sliced.ip {
+            Some(InternetSlice::Ipv6(_, ext)) => {
+                match ext.clone().into_iter().next() {
+                    Some(Ipv6ExtensionSlice::Authentication(auth)) => assert_eq!(&[3;12], auth.raw_icv()),
+                    _ => unreachable!(),
+                }
+            },
+            _ => unreachable!(),
+        }
+        assert_eq!(0x1234_5678, sliced.esp.unwrap().spi());
+        assert_eq!(&[0xab;16], sliced.payload);
+
+        let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert_eq!(Some(header), decoded.esp);
+        assert_eq!(&[0xab;16], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .write(&mut packet, ip_number::ENCAP_SEC, &[1,2,3,4,5,6,7])
+        .unwrap();
+    assert_matches!(
+        SlicedPacket::from_ip(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(28))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+}
+
+#[test]
+fn repair() {
+    let payload = esp_payload(&EspHeader{ spi: 1, sequence_number: 2 });
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .write(&mut packet, ip_number::ENCAP_SEC, &payload)
+        .unwrap();
+
+    let mut decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+    if let Some(IpHeader::Version4(ip, _)) = &mut decoded.ip {
+        ip.protocol = ip_number::UDP;
+    }
+    etherparse::repair::fix(&mut decoded).unwrap();
+    assert_eq!(packet, etherparse::repair::to_bytes(&decoded).unwrap());
+    assert!(etherparse::roundtrip::check_ip(&packet).unwrap().is_empty());
+}
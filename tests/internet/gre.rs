@@ -0,0 +1,303 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn gre_any()(
+        protocol_type in any::<u16>(),
+        checksum in proptest::option::of(any::<u16>()),
+        key in proptest::option::of(any::<u32>()),
+        sequence_number in proptest::option::of(any::<u32>()),
+    ) -> GreHeader {
+        GreHeader {
+            protocol_type,
+            checksum,
+            key,
+            sequence_number,
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in gre_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(header.header_len() + 2, buffer.len());
+        assert_eq!(&header.to_bytes()[..], &buffer[..header.header_len()]);
+
+        // from_slice
+        {
+            let (decoded, rest) = GreHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &GreHeader::read(&mut cursor).unwrap());
+            assert_eq!(header.header_len() as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = GreHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(header.checksum.is_some(), slice.checksum_present());
+            assert_eq!(header.key.is_some(), slice.key_present());
+            assert_eq!(header.sequence_number.is_some(), slice.sequence_number_present());
+            assert_eq!(0, slice.version());
+            assert_eq!(header.protocol_type, slice.protocol_type());
+            assert_eq!(header.checksum, slice.checksum());
+            assert_eq!(header.key, slice.key());
+            assert_eq!(header.sequence_number, slice.sequence_number());
+            assert_eq!(header, &slice.to_header());
+        }
+        // length errors
+        for len in 0..header.header_len() {
+            assert_matches!(
+                GreHeaderSlice::from_slice(&buffer[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+            assert_matches!(
+                GreHeader::read(&mut Cursor::new(&buffer[..len])),
+                Err(ReadError::IoError(_))
+            );
+        }
+    }
+}
+
+#[test]
+fn header_len() {
+    let header = GreHeader::new(ether_type::IPV4);
+    assert_eq!(GreHeader::MIN_LEN, header.header_len());
+    assert_eq!(
+        GreHeader::MAX_LEN,
+        GreHeader {
+            checksum: Some(0),
+            key: Some(0),
+            sequence_number: Some(0),
+            ..header
+        }.header_len()
+    );
+}
+
+#[test]
+fn from_slice_error() {
+    // version 1 (enhanced GRE used by PPTP)
+    assert_matches!(
+        GreHeaderSlice::from_slice(&[0x30, 0x81, 0x88, 0x0b, 0, 0, 0, 0]),
+        Err(ReadError::GreUnsupportedVersion(1))
+    );
+    // routing present
+    assert_matches!(
+        GreHeaderSlice::from_slice(&[0x40, 0, 0x08, 0, 0, 0, 0, 0]),
+        Err(ReadError::GreRoutingNotSupported)
+    );
+    assert_matches!(
+        GreHeader::read(&mut Cursor::new(&[0x40, 0, 0x08, 0, 0, 0, 0, 0])),
+        Err(ReadError::GreRoutingNotSupported)
+    );
+}
+
+#[test]
+fn calc_checksum() {
+    let payload = [1, 2, 3, 4, 5];
+    let mut header = GreHeader {
+        checksum: Some(0x1234),
+        key: Some(0xabcd_ef01),
+        ..GreHeader::new(ether_type::IPV4)
+    };
+    header.checksum = Some(header.calc_checksum(&payload));
+
+    // the checksum over the complete packet must be zero
+    let mut packet = header.to_bytes().to_vec();
+    packet.extend_from_slice(&payload);
+    assert_eq!(0, etherparse::checksum::Sum16BitWords::new().add_slice(&packet).ones_complement());
+}
+
+/// Builds an ipv4 packet carrying the given GRE header & payload.
+fn gre_packet(gre: &GreHeader, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    Ipv4Header::new(
+        (gre.header_len() + payload.len()) as u16,
+        64,
+        ip_number::GRE,
+        [1, 2, 3, 4],
+        [5, 6, 7, 8],
+    ).write(&mut packet).unwrap();
+    gre.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet
+}
+
+#[test]
+fn sliced_packet_ipv4() {
+    let mut inner = Vec::new();
+    PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut inner, &[1, 2, 3, 4])
+        .unwrap();
+    let gre = GreHeader {
+        key: Some(42),
+        ..GreHeader::new(ether_type::IPV4)
+    };
+    let packet = gre_packet(&gre, &inner);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(Some(TransportSlice::Unknown(ip_number::GRE)), sliced.transport);
+        assert_eq!(gre, sliced.gre.as_ref().unwrap().to_header());
+        assert_eq!(&inner[..], sliced.payload);
+
+        let sliced_inner = sliced.inner.unwrap();
+        assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(sliced_inner.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+    }
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert_eq!(None, decoded.transport);
+        assert_eq!(Some(gre), decoded.gre);
+        assert_eq!(&inner[..], decoded.payload);
+
+        let decoded_inner = decoded.inner.unwrap();
+        assert_matches!(decoded_inner.ip, Some(IpHeader::Version4(_, _)));
+        assert_matches!(decoded_inner.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], decoded_inner.payload);
+    }
+    // roundtrip
+    assert!(etherparse::roundtrip::check_ip(&packet).unwrap().is_empty());
+}
+
+#[test]
+fn sliced_packet_transparent_ethernet_bridging() {
+    let mut inner = Vec::new();
+    PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+        .ipv6([1; 16], [2; 16], 20)
+        .tcp(1234, 5678, 1, 1024)
+        .write(&mut inner, &[1, 2, 3, 4])
+        .unwrap();
+    let gre = GreHeader::new(ether_type::TRANSPARENT_ETHERNET_BRIDGING);
+
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [0; 6],
+        destination: [0; 6],
+        ether_type: ether_type::IPV4,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&gre_packet(&gre, &inner));
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(&inner[..], sliced.payload);
+        let sliced_inner = sliced.inner.unwrap();
+        assert_matches!(sliced_inner.link, Some(LinkSlice::Ethernet2(_)));
+        assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv6(_, _)));
+        assert_matches!(sliced_inner.transport, Some(TransportSlice::Tcp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+    }
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(gre), decoded.gre);
+        let decoded_inner = decoded.inner.unwrap();
+        assert_eq!([1, 2, 3, 4, 5, 6], decoded_inner.link.unwrap().source);
+        assert_matches!(decoded_inner.ip, Some(IpHeader::Version6(_, _)));
+        assert_matches!(decoded_inner.transport, Some(TransportHeader::Tcp(_)));
+        assert_eq!(&[1, 2, 3, 4], decoded_inner.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_nesting_depth() {
+    // 5 nested gre tunnels, only the first 4 get decoded
+    let mut packet = vec![1, 2, 3, 4];
+    for _ in 0..5 {
+        packet = gre_packet(&GreHeader::new(ether_type::IPV4), &packet);
+    }
+
+    let mut sliced = SlicedPacket::from_ip(&packet).unwrap();
+    for _ in 0..4 {
+        assert!(sliced.gre.is_some());
+        sliced = *sliced.inner.unwrap();
+    }
+    assert_eq!(None, sliced.gre);
+    assert_eq!(None, sliced.inner);
+    assert_eq!(Some(TransportSlice::Unknown(ip_number::GRE)), sliced.transport);
+    assert_eq!(GreHeader::MIN_LEN + 4, sliced.payload.len());
+
+    let mut decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+    for _ in 0..4 {
+        assert!(decoded.gre.is_some());
+        decoded = *decoded.inner.unwrap();
+    }
+    assert_eq!(None, decoded.gre);
+    assert_eq!(None, decoded.inner);
+    assert_eq!(GreHeader::MIN_LEN + 4, decoded.payload.len());
+}
+
+#[test]
+fn sliced_packet_errors() {
+    // truncated gre header
+    {
+        let packet = gre_packet(&GreHeader::new(ether_type::IPV4), &[]);
+        let packet = &packet[..packet.len() - 1];
+        assert_matches!(
+            SlicedPacket::from_ip(packet),
+            Err(ReadError::UnexpectedEndOfSlice(24))
+        );
+        assert_matches!(
+            PacketHeaders::from_ip_slice(packet),
+            Err(ReadError::UnexpectedEndOfSlice(4))
+        );
+    }
+    // truncated inner packet (left in the payload)
+    {
+        let packet = gre_packet(&GreHeader::new(ether_type::IPV4), &[0x45, 0]);
+
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert!(sliced.gre.is_some());
+        assert!(sliced.inner.is_none());
+        assert_eq!(&[0x45, 0], sliced.payload);
+
+        let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert!(decoded.gre.is_some());
+        assert!(decoded.inner.is_none());
+        assert_eq!(&[0x45, 0], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_unsupported() {
+    let payload = [1, 2, 3, 4];
+    // version 1 (enhanced GRE used by PPTP) & routing present
+    for gre in [[0x20, 0x01, 0x88, 0x0b, 0, 4, 0, 1], [0x40, 0, 0x08, 0, 0, 0, 0, 0]] {
+        let mut packet = Vec::new();
+        Ipv4Header::new(
+            (gre.len() + payload.len()) as u16,
+            64,
+            ip_number::GRE,
+            [1, 2, 3, 4],
+            [5, 6, 7, 8],
+        ).write(&mut packet).unwrap();
+        packet.extend_from_slice(&gre);
+        packet.extend_from_slice(&payload);
+
+        // the gre header is left in the payload
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(Some(TransportSlice::Unknown(ip_number::GRE)), sliced.transport);
+        assert!(sliced.gre.is_none());
+        assert!(sliced.inner.is_none());
+        assert_eq!(&packet[20..], sliced.payload);
+
+        let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert_eq!(None, decoded.transport);
+        assert_eq!(None, decoded.gre);
+        assert!(decoded.inner.is_none());
+        assert_eq!(&packet[20..], decoded.payload);
+    }
+}
@@ -0,0 +1,102 @@
+use super::super::*;
+
+/// Creates a HIP header with the given parameters.
+fn hip_header(packet_type: u8, parameters: &[u8]) -> Vec<u8> {
+    let mut result = vec![
+        ip_number::TCP, ((40 + parameters.len())/8 - 1) as u8, packet_type, 0x21,
+        0x12, 0x34, 0x00, 0x01,
+    ];
+    result.extend_from_slice(&[1u8;16]); // sender hit
+    result.extend_from_slice(&[2u8;16]); // receiver hit
+    result.extend_from_slice(parameters);
+    result
+}
+
+#[test]
+fn from_slice() {
+    let parameters = [
+        // R1_COUNTER (type 129) with 12 bytes of contents
+        0x00, 0x81, 0, 12,
+        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7,
+        // HOST_ID (type 705) with 3 bytes of contents & padding
+        0x02, 0xc1, 0, 3,
+        1, 2, 3, 0,
+    ];
+    let mut data = hip_header(hip_packet_type::R1, &parameters);
+    data.extend_from_slice(&[9, 9]); // data after the header
+
+    let hip = HipHeaderSlice::from_slice(&data).unwrap();
+    assert_eq!(&data[..data.len() - 2], hip.slice());
+    assert_eq!(ip_number::TCP, hip.next_header());
+    assert_eq!(7, hip.header_length());
+    assert_eq!(hip_packet_type::R1, hip.packet_type());
+    assert_eq!(2, hip.version());
+    assert_eq!(0x1234, hip.checksum());
+    assert_eq!(1, hip.controls());
+    assert!(hip.is_anonymous());
+    assert_eq!([1u8;16], hip.sender_hit());
+    assert_eq!([2u8;16], hip.receiver_hit());
+
+    let mut iter = hip.parameters();
+    assert_eq!(
+        Some(HipParameter{
+            parameter_type: 129,
+            contents: &parameters[4..16],
+        }),
+        iter.next()
+    );
+    let host_id = iter.next().unwrap();
+    assert_eq!(705, host_id.parameter_type);
+    assert_eq!(&[1, 2, 3], host_id.contents);
+    assert!(host_id.is_critical());
+    assert_eq!(None, iter.next());
+}
+
+#[test]
+fn from_slice_without_parameters() {
+    let mut data = hip_header(hip_packet_type::I1, &[]);
+    data[7] = 0; // clear anonymous flag
+    let hip = HipHeaderSlice::from_slice(&data).unwrap();
+    assert_eq!(hip_packet_type::I1, hip.packet_type());
+    assert!(!hip.is_anonymous());
+    assert_eq!(None, hip.parameters().next());
+}
+
+#[test]
+fn from_slice_errors() {
+    let data = hip_header(hip_packet_type::I1, &[0x00, 0x80, 0, 4, 1, 2, 3, 4]);
+
+    // too short
+    for len in 0..data.len() {
+        assert_matches!(
+            HipHeaderSlice::from_slice(&data[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+
+    // header length too small
+    {
+        let mut data = data.clone();
+        data[1] = 3;
+        assert_matches!(
+            HipHeaderSlice::from_slice(&data),
+            Err(ReadError::HipHeaderLengthTooSmall(3))
+        );
+    }
+
+    // parameter length exceeding the header
+    {
+        let mut data = data.clone();
+        data[43] = 5;
+        assert_matches!(
+            HipHeaderSlice::from_slice(&data),
+            Err(ReadError::HipParameterLengthTooBig(40))
+        );
+    }
+}
+
+#[test]
+fn parameter_critical() {
+    assert!(!HipParameter{ parameter_type: 128, contents: &[] }.is_critical());
+    assert!(HipParameter{ parameter_type: 129, contents: &[] }.is_critical());
+}
@@ -379,6 +379,44 @@ mod ip_header {
         }
     }
 
+    proptest!{
+        #[test]
+        fn recompute(
+            v4 in ipv4_any(),
+            v4_exts in ipv4_extensions_any(),
+            v6 in ipv6_any(),
+            v6_exts in ipv6_extensions_any(),
+            payload_len in 0usize..10
+        ) {
+            // ipv4 (length & checksum)
+            {
+                let mut actual = IpHeader::Version4(v4.clone(), v4_exts.clone());
+                actual.recompute(payload_len).unwrap();
+                match actual {
+                    IpHeader::Version4(header, _) => {
+                        assert_eq!(v4_exts.header_len() + payload_len, usize::from(header.payload_len));
+                        assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+                    },
+                    _ => unreachable!(),
+                }
+            }
+            // ipv6 (length only)
+            {
+                let mut actual = IpHeader::Version6(v6.clone(), v6_exts.clone());
+                actual.recompute(payload_len).unwrap();
+
+                let mut expected = v6.clone();
+                expected.set_payload_length(v6_exts.header_len() + payload_len).unwrap();
+                assert_eq!(IpHeader::Version6(expected, v6_exts.clone()), actual);
+            }
+            // length error
+            {
+                let mut actual = IpHeader::Version4(v4.clone(), v4_exts.clone());
+                assert_matches!(actual.recompute(usize::MAX), Err(_));
+            }
+        }
+    }
+
     proptest!{
         #[test]
         fn debug(
@@ -512,6 +550,7 @@ mod ip_number {
             (IPV6, Ipv6),
             (IPV6_ROUTE, IPv6RouteHeader),
             (IPV6_FRAG, IPv6FragmentationHeader),
+            (GRE, Gre),
             (ENCAP_SEC, EncapsulatingSecurityPayload),
             (AUTH, AuthenticationHeader),
             (IPV6_DEST_OPTIONS, IPv6DestinationOptions),
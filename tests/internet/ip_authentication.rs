@@ -254,3 +254,33 @@ proptest! {
         );
     }
 }
+
+#[test]
+pub fn hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |header: &IpAuthenticationHeader| {
+        let mut hasher = DefaultHasher::new();
+        header.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // unused parts of the icv buffer are not part of the hash
+    let mut a = IpAuthenticationHeader::new(1, 2, 3, &[1,2,3,4,5,6,7,8]).unwrap();
+    a.set_raw_icv(&[1,2,3,4]).unwrap();
+    let b = IpAuthenticationHeader::new(1, 2, 3, &[1,2,3,4]).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+    assert_ne!(hash(&a), hash(&IpAuthenticationHeader::new(1, 2, 3, &[1,2,3,5]).unwrap()));
+}
+
+proptest! {
+    #[test]
+    fn to_bytes(ref input in ip_authentication_any()) {
+        let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len());
+        input.write(&mut buffer).unwrap();
+        assert_eq!(&buffer[..], &input.to_bytes()[..]);
+        assert!(IpAuthenticationHeader::MIN_SERIALIZED_SIZE <= buffer.len());
+        assert!(buffer.len() <= IpAuthenticationHeader::MAX_SERIALIZED_SIZE);
+    }
+}
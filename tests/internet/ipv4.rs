@@ -210,6 +210,29 @@ mod header {
         }
     }
 
+    proptest! {
+        #[test]
+        fn try_new(source_ip in prop::array::uniform4(any::<u8>()),
+                   dest_ip in prop::array::uniform4(any::<u8>()),
+                   ttl in any::<u8>(),
+                   payload_len in 0..=(u16::MAX - Ipv4Header::SERIALIZED_SIZE as u16),
+                   bad_payload_len in (u16::MAX - Ipv4Header::SERIALIZED_SIZE as u16 + 1)..=u16::MAX)
+        {
+            assert_eq!(
+                Ok(Ipv4Header::new(payload_len, ttl, ip_number::UDP, source_ip, dest_ip)),
+                Ipv4Header::try_new(payload_len, ttl, ip_number::UDP, source_ip, dest_ip)
+            );
+            assert_eq!(
+                Err(ValueError::U16TooLarge{
+                    value: bad_payload_len,
+                    max: u16::MAX - Ipv4Header::SERIALIZED_SIZE as u16,
+                    field: ErrorField::Ipv4PayloadLength
+                }),
+                Ipv4Header::try_new(bad_payload_len, ttl, ip_number::UDP, source_ip, dest_ip)
+            );
+        }
+    }
+
     #[test]
     fn set_payload_len() {
         let mut header = Ipv4Header::new(0, 0, ip_number::UDP, [0;4], [0;4]);
@@ -293,6 +316,56 @@ mod header {
         }
     }
 
+    proptest! {
+        #[test]
+        fn checksum_keeping_setters(
+            ref header in ipv4_any(),
+            time_to_live in any::<u8>(),
+            protocol in any::<u8>(),
+            identification in any::<u16>(),
+            source in any::<[u8;4]>(),
+            destination in any::<[u8;4]>(),
+        ) {
+            let mut header = header.clone();
+            header.recompute().unwrap();
+
+            header.set_time_to_live(time_to_live);
+            assert_eq!(time_to_live, header.time_to_live);
+            assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+
+            header.set_protocol(protocol);
+            assert_eq!(protocol, header.protocol);
+            assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+
+            header.set_identification(identification);
+            assert_eq!(identification, header.identification);
+            assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+
+            header.set_source(source);
+            assert_eq!(source, header.source);
+            assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+
+            header.set_destination(destination);
+            assert_eq!(destination, header.destination);
+            assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+        }
+    }
+
+    #[test]
+    fn recompute() {
+        let mut header = Ipv4Header::new(100, 64, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+        header.set_options(&[1,2,3,4]).unwrap();
+        header.time_to_live = 12;
+        assert_eq!(Ok(()), header.recompute());
+        assert_eq!(header.calc_header_checksum().unwrap(), header.header_checksum);
+
+        // range error (checksum is not modified)
+        let checksum = header.header_checksum;
+        header.fragments_offset = 0x2000;
+        assert_matches!(header.recompute(), Err(ValueError::U16TooLarge{ .. }));
+        assert_eq!(checksum, header.header_checksum);
+    }
+
     #[test]
     fn calc_header_checksum() {
         let base: Ipv4Header = Ipv4Header::new(
@@ -402,6 +475,27 @@ mod header {
         }
     }
 
+    proptest! {
+        #[test]
+        fn to_bytes(ref input in ipv4_any()) {
+            let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len());
+            input.write(&mut buffer).unwrap();
+            assert_eq!(&buffer[..], &input.to_bytes().unwrap()[..]);
+            assert!(Ipv4Header::MIN_SERIALIZED_SIZE <= buffer.len());
+            assert!(buffer.len() <= Ipv4Header::MAX_SERIALIZED_SIZE);
+        }
+    }
+
+    #[test]
+    fn to_bytes_range_error() {
+        let mut header: Ipv4Header = Default::default();
+        header.fragments_offset = 0x2000;
+        assert_eq!(
+            ValueError::U16TooLarge{ value: 0x2000, max: 0x1fff, field: ErrorField::Ipv4FragmentsOffset },
+            header.to_bytes().unwrap_err()
+        );
+    }
+
     #[test]
     fn is_fragmenting_payload() {
         // not fragmenting
@@ -477,6 +571,54 @@ mod slice {
             assert!(slice.is_fragmenting_payload());
         }
     }
+
+    proptest! {
+        #[test]
+        fn from_slice_truncated_options(ref header in ipv4_any()) {
+            let header = {
+                let mut header = header.clone();
+                header.recompute().unwrap();
+                header
+            };
+            let bytes = header.to_bytes().unwrap();
+
+            // complete header
+            {
+                let slice = Ipv4HeaderSlice::from_slice_truncated_options(&bytes).unwrap();
+                assert_eq!(Ipv4HeaderSlice::from_slice(&bytes).unwrap(), slice);
+                assert!(!slice.is_options_truncated());
+                assert_eq!(header, slice.to_header());
+            }
+
+            // truncated options
+            for len in Ipv4Header::SERIALIZED_SIZE..bytes.len() {
+                assert_matches!(
+                    Ipv4HeaderSlice::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(_))
+                );
+                let slice = Ipv4HeaderSlice::from_slice_truncated_options(&bytes[..len]).unwrap();
+                assert!(slice.is_options_truncated());
+                assert_eq!(&bytes[..len], slice.slice());
+                assert_eq!(&header.options()[..len - Ipv4Header::SERIALIZED_SIZE], slice.options());
+                assert_eq!(header.ihl(), slice.ihl());
+
+                // missing options are filled with zeros
+                let decoded = slice.to_header();
+                assert_eq!(header.header_len(), decoded.header_len());
+                assert_eq!(header.payload_len, decoded.payload_len);
+                assert_eq!(slice.options(), &decoded.options()[..slice.options().len()]);
+                assert!(decoded.options()[slice.options().len()..].iter().all(|v| 0 == *v));
+            }
+
+            // the fixed part of the header is still required
+            for len in 0..Ipv4Header::SERIALIZED_SIZE {
+                assert_matches!(
+                    Ipv4HeaderSlice::from_slice_truncated_options(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(Ipv4Header::SERIALIZED_SIZE))
+                );
+            }
+        }
+    }
 }
 
 #[test]
@@ -841,3 +983,26 @@ proptest! {
         assert_eq!(slice.clone(), slice.clone());
     }
 }
+
+#[test]
+fn hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |header: &Ipv4Header| {
+        let mut hasher = DefaultHasher::new();
+        header.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // unused parts of the options buffer are not part of the hash
+    let mut a = Ipv4Header::new(0, 1, 2, [3;4], [4;4]);
+    a.set_options(&[1,2,3,4,5,6,7,8]).unwrap();
+    a.set_options(&[1,2,3,4]).unwrap();
+    let mut b = Ipv4Header::new(0, 1, 2, [3;4], [4;4]);
+    b.set_options(&[1,2,3,4]).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+
+    b.set_options(&[1,2,3,5]).unwrap();
+    assert_ne!(hash(&a), hash(&b));
+}
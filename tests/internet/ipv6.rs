@@ -1,5 +1,40 @@
 use super::super::*;
 
+proptest! {
+    #[test]
+    fn new(
+        traffic_class in any::<u8>(),
+        flow_label in 0..=0xfffffu32,
+        bad_flow_label in 0x100000..=u32::MAX,
+        payload_length in any::<u16>(),
+        next_header in any::<u8>(),
+        hop_limit in any::<u8>(),
+        source in prop::array::uniform16(any::<u8>()),
+        destination in prop::array::uniform16(any::<u8>()),
+    ) {
+        assert_eq!(
+            Ok(Ipv6Header {
+                traffic_class,
+                flow_label,
+                payload_length,
+                next_header,
+                hop_limit,
+                source,
+                destination,
+            }),
+            Ipv6Header::new(traffic_class, flow_label, payload_length, next_header, hop_limit, source, destination)
+        );
+        assert_eq!(
+            Err(ValueError::U32TooLarge{
+                value: bad_flow_label,
+                max: 0xfffff,
+                field: ErrorField::Ipv6FlowLabel
+            }),
+            Ipv6Header::new(traffic_class, bad_flow_label, payload_length, next_header, hop_limit, source, destination)
+        );
+    }
+}
+
 #[test]
 fn read() {
     use std::io::Cursor;
@@ -0,0 +1,113 @@
+use super::super::*;
+
+use std::net::Ipv6Addr;
+
+fn addr(value: &str) -> [u8; 16] {
+    value.parse::<Ipv6Addr>().unwrap().octets()
+}
+
+#[test]
+fn from_multicast_scope() {
+    use Ipv6AddressScope::*;
+    assert_eq!(Other(0), Ipv6AddressScope::from_multicast_scope(0));
+    assert_eq!(InterfaceLocal, Ipv6AddressScope::from_multicast_scope(1));
+    assert_eq!(LinkLocal, Ipv6AddressScope::from_multicast_scope(2));
+    assert_eq!(RealmLocal, Ipv6AddressScope::from_multicast_scope(3));
+    assert_eq!(AdminLocal, Ipv6AddressScope::from_multicast_scope(4));
+    assert_eq!(SiteLocal, Ipv6AddressScope::from_multicast_scope(5));
+    assert_eq!(Other(6), Ipv6AddressScope::from_multicast_scope(6));
+    assert_eq!(OrganizationLocal, Ipv6AddressScope::from_multicast_scope(8));
+    assert_eq!(Global, Ipv6AddressScope::from_multicast_scope(0xe));
+    assert_eq!(Other(0xf), Ipv6AddressScope::from_multicast_scope(0xf));
+}
+
+#[test]
+fn unspecified_loopback() {
+    assert!(addr("::").is_unspecified());
+    assert!(!addr("::1").is_unspecified());
+    assert!(addr("::1").is_loopback());
+    assert!(!addr("::2").is_loopback());
+    assert!(!addr("1::1").is_loopback());
+}
+
+#[test]
+fn unicast_link_local() {
+    assert!(addr("fe80::1").is_unicast_link_local());
+    assert!(addr("febf:ffff::").is_unicast_link_local());
+    assert!(!addr("fec0::1").is_unicast_link_local());
+    assert!(!addr("fe7f::1").is_unicast_link_local());
+    assert!(!addr("ff02::1").is_unicast_link_local());
+}
+
+#[test]
+fn unique_local() {
+    assert!(addr("fc00::1").is_unique_local());
+    assert!(addr("fdff:ffff::").is_unique_local());
+    assert!(!addr("fe00::").is_unique_local());
+    assert!(!addr("fbff::").is_unique_local());
+}
+
+#[test]
+fn multicast() {
+    assert!(addr("ff02::1").is_multicast());
+    assert!(!addr("fe80::1").is_multicast());
+
+    assert_eq!(None, addr("fe80::1").multicast_scope());
+    assert_eq!(Some(Ipv6AddressScope::InterfaceLocal), addr("ff01::1").multicast_scope());
+    assert_eq!(Some(Ipv6AddressScope::LinkLocal), addr("ff02::1").multicast_scope());
+    // flags are ignored
+    assert_eq!(Some(Ipv6AddressScope::SiteLocal), addr("ff35::1").multicast_scope());
+    assert_eq!(Some(Ipv6AddressScope::Global), addr("ff0e::1").multicast_scope());
+    assert_eq!(Some(Ipv6AddressScope::Other(0)), addr("ff00::1").multicast_scope());
+}
+
+#[test]
+fn ipv4_mapped() {
+    assert!(addr("::ffff:192.168.1.2").is_ipv4_mapped());
+    assert_eq!(Some([192, 168, 1, 2]), addr("::ffff:192.168.1.2").to_ipv4_mapped());
+
+    // ipv4 compatible (deprecated) & translated addresses
+    for value in ["::192.168.1.2", "::ffff:0:192.168.1.2", "1::ffff:192.168.1.2"] {
+        assert!(!addr(value).is_ipv4_mapped());
+        assert_eq!(None, addr(value).to_ipv4_mapped());
+    }
+}
+
+#[test]
+fn documentation() {
+    assert!(addr("2001:db8::1").is_documentation());
+    assert!(addr("2001:db8:ffff::").is_documentation());
+    assert!(addr("3fff::1").is_documentation());
+    assert!(addr("3fff:fff::").is_documentation());
+    assert!(!addr("3fff:1000::").is_documentation());
+    assert!(!addr("2001:db9::").is_documentation());
+    assert!(!addr("2001::1").is_documentation());
+}
+
+#[test]
+fn scope() {
+    use Ipv6AddressScope::*;
+    assert_eq!(InterfaceLocal, addr("::1").scope());
+    assert_eq!(LinkLocal, addr("fe80::1").scope());
+    assert_eq!(Global, addr("fd00::1").scope());
+    assert_eq!(Global, addr("2001:db8::1").scope());
+    assert_eq!(Global, addr("::").scope());
+    assert_eq!(RealmLocal, addr("ff03::1").scope());
+    assert_eq!(OrganizationLocal, addr("ff08::1").scope());
+}
+
+proptest! {
+    #[test]
+    fn std_ipv6_addr(octets in any::<[u8; 16]>()) {
+        let std_addr = Ipv6Addr::from(octets);
+        assert_eq!(octets, Ipv6AddressExt::ipv6_octets(&std_addr));
+        assert_eq!(octets.is_multicast(), std_addr.is_multicast());
+        assert_eq!(octets.is_loopback(), std_addr.is_loopback());
+        assert_eq!(octets.is_unspecified(), std_addr.is_unspecified());
+        assert_eq!(
+            octets.to_ipv4_mapped(),
+            std_addr.to_ipv4_mapped().map(|v| v.octets())
+        );
+        assert_eq!(octets.scope(), Ipv6AddressExt::scope(&std_addr));
+    }
+}
@@ -4,12 +4,13 @@ use crate::ip_number::*;
 use std::io::Cursor;
 
 // IP numbers that are assigned ipv6 header extensions.
-const EXTESION_KNOWN_IP_NUMBERS : [u8;5] = [
+const EXTESION_KNOWN_IP_NUMBERS : [u8;6] = [
     AUTH,
     IPV6_DEST_OPTIONS,
     IPV6_HOP_BY_HOP,
     IPV6_FRAG,
     IPV6_ROUTE,
+    SHIM6,
 ];
 
 /// Helper struct that generates test data with dummy
@@ -44,7 +45,7 @@ impl ExtensionTestPayload {
 
     fn add_payload(&mut self, ip_number: u8, next_header: u8, header_ext_len: u8) {
         match ip_number {
-            IPV6_HOP_BY_HOP |  IPV6_ROUTE | IPV6_DEST_OPTIONS => {
+            IPV6_HOP_BY_HOP |  IPV6_ROUTE | IPV6_DEST_OPTIONS | SHIM6 => {
                 // insert next header & size
                 let mut raw : [u8;0xff*8 + 8] = [0;0xff*8 + 8];
                 raw[0] = next_header;
@@ -89,6 +90,7 @@ impl ExtensionTestPayload {
             dest_opt: bool,
             routing: bool,
             final_dest_opt: bool,
+            shim6: bool,
             frag: bool,
             auth: bool,
         }
@@ -98,6 +100,7 @@ impl ExtensionTestPayload {
             dest_opt: false,
             routing: false,
             final_dest_opt: false,
+            shim6: false,
             frag: false,
             auth: false,
         };
@@ -130,6 +133,11 @@ impl ExtensionTestPayload {
                         }
                     }
                 }
+                SHIM6 => if read.shim6 {
+                    return false;
+                } else {
+                    read.shim6 = true;
+                },
                 IPV6_FRAG => if read.frag {
                     return false;
                 } else {
@@ -155,6 +163,7 @@ impl ExtensionTestPayload {
             dest_opt: bool,
             routing: bool,
             final_dest_opt: bool,
+            shim6: bool,
             frag: bool,
             auth: bool,
         }
@@ -165,6 +174,7 @@ impl ExtensionTestPayload {
             dest_opt: false,
             routing: false,
             final_dest_opt: false,
+            shim6: false,
             frag: false,
             auth: false,
         };
@@ -216,6 +226,17 @@ impl ExtensionTestPayload {
                         }
                     }
                 }
+                SHIM6 => {
+                    if read.shim6 {
+                        // duplicate header -> stop
+                        stop = true;
+                    } else {
+                        let (header, rest) = Ipv6RawExtensionHeader::from_slice(slice).unwrap();
+                        assert_eq!(&header, exts.shim6.as_deref().unwrap());
+                        slice = rest;
+                        read.shim6 = true;
+                    }
+                },
                 IPV6_FRAG => {
                     if read.frag {
                         // duplicate header -> stop
@@ -265,6 +286,9 @@ impl ExtensionTestPayload {
                 assert!(exts.routing.as_ref().unwrap().final_destination_options.is_none());
             }
         }
+        if !read.shim6 {
+            assert!(exts.shim6.is_none());
+        }
         if false == read.frag {
             assert!(exts.fragment.is_none());
         }
@@ -331,6 +355,9 @@ impl ExtensionTestHeaders {
                 IPV6_ROUTE => {
                     self.data.routing.as_mut().unwrap().routing.next_header = new_header;
                 },
+                SHIM6 => {
+                    self.data.shim6.as_mut().unwrap().next_header = new_header;
+                },
                 IPV6_FRAG => {
                     self.data.fragment.as_mut().unwrap().next_header = new_header;
                 },
@@ -343,6 +370,7 @@ impl ExtensionTestHeaders {
                 IPV6_HOP_BY_HOP => IPv6HeaderHopByHop,
                 IPV6_DEST_OPTIONS => IPv6DestinationOptions,
                 IPV6_ROUTE => IPv6RouteHeader,
+                SHIM6 => Shim6,
                 IPV6_FRAG => IPv6FragmentationHeader,
                 AUTH => AuthenticationHeader,
                 _ => unreachable!(),
@@ -355,6 +383,7 @@ impl ExtensionTestHeaders {
                 IPV6_HOP_BY_HOP => IPv6HeaderHopByHop,
                 IPV6_DEST_OPTIONS => IPv6DestinationOptions,
                 IPV6_ROUTE => IPv6RouteHeader,
+                SHIM6 => Shim6,
                 IPV6_FRAG => IPv6FragmentationHeader,
                 AUTH => AuthenticationHeader,
                 _ => unreachable!(),
@@ -364,7 +393,7 @@ impl ExtensionTestHeaders {
 
     fn add_payload(&mut self, ip_number: u8, next_header: u8, header_ext_len: u8) -> bool {
         match ip_number {
-            IPV6_HOP_BY_HOP |  IPV6_ROUTE | IPV6_DEST_OPTIONS => {
+            IPV6_HOP_BY_HOP |  IPV6_ROUTE | IPV6_DEST_OPTIONS | SHIM6 => {
                 use Ipv6RawExtensionHeader as R;
                 let payload : [u8;R::MAX_PAYLOAD_LEN] = [0;R::MAX_PAYLOAD_LEN];
                 let len = usize::from(header_ext_len)*8 + 6;
@@ -407,6 +436,12 @@ impl ExtensionTestHeaders {
                             false
                         }
                     },
+                    SHIM6 => if self.data.shim6.is_none() {
+                        self.data.shim6 = Some(Box::new(raw));
+                        true
+                    } else {
+                        false
+                    },
                     _ => unreachable!(),
                 }
             },
@@ -729,6 +764,7 @@ pub mod header {
             hop_by_hop_options in ipv6_raw_extension_any(),
             destination_options in ipv6_raw_extension_any(),
             routing in ipv6_raw_extension_any(),
+            shim6 in ipv6_raw_extension_any(),
             fragment in ipv6_fragment_any(),
             auth in ip_authentication_any(),
             final_destination_options in ipv6_raw_extension_any(),
@@ -750,6 +786,7 @@ pub mod header {
                             final_destination_options: Some(final_destination_options.clone()),
                         }
                     ),
+                    shim6: Some(Box::new(shim6.clone())),
                     fragment: Some(fragment.clone()),
                     auth: Some(auth.clone()),
                 };
@@ -760,6 +797,7 @@ pub mod header {
                         destination_options.header_len() +
                         routing.header_len() +
                         final_destination_options.header_len() +
+                        shim6.header_len() +
                         fragment.header_len() +
                         auth.header_len()
                     )
@@ -777,6 +815,7 @@ pub mod header {
                             final_destination_options: None,
                         }
                     ),
+                    shim6: None,
                     fragment: Some(fragment.clone()),
                     auth: Some(auth.clone()),
                 };
@@ -800,6 +839,7 @@ pub mod header {
             hop_by_hop_options in ipv6_raw_extension_any(),
             destination_options in ipv6_raw_extension_any(),
             routing in ipv6_raw_extension_any(),
+            shim6 in ipv6_raw_extension_any(),
             fragment in ipv6_fragment_any(),
             auth in ip_authentication_any(),
             final_destination_options in ipv6_raw_extension_any(),
@@ -815,6 +855,7 @@ pub mod header {
                 assert!(exts.hop_by_hop_options.is_none());
                 assert!(exts.destination_options.is_none());
                 assert!(exts.routing.is_none());
+                assert!(exts.shim6.is_none());
                 assert!(exts.fragment.is_none());
                 assert!(exts.auth.is_none());
             }
@@ -830,6 +871,7 @@ pub mod header {
                             final_destination_options: Some(final_destination_options.clone()),
                         }
                     ),
+                    shim6: Some(Box::new(shim6.clone())),
                     fragment: Some(fragment.clone()),
                     auth: Some(auth.clone()),
                 };
@@ -837,7 +879,8 @@ pub mod header {
 
                 assert_eq!(IPV6_DEST_OPTIONS, exts.hop_by_hop_options.as_ref().unwrap().next_header);
                 assert_eq!(IPV6_ROUTE, exts.destination_options.as_ref().unwrap().next_header);
-                assert_eq!(IPV6_FRAG, exts.routing.as_ref().unwrap().routing.next_header);
+                assert_eq!(SHIM6, exts.routing.as_ref().unwrap().routing.next_header);
+                assert_eq!(IPV6_FRAG, exts.shim6.as_ref().unwrap().next_header);
                 assert_eq!(AUTH, exts.fragment.as_ref().unwrap().next_header);
                 assert_eq!(IPV6_DEST_OPTIONS, exts.auth.as_ref().unwrap().next_header);
                 assert_eq!(post_header, exts.routing.as_ref().unwrap().final_destination_options.as_ref().unwrap().next_header);
@@ -936,6 +979,7 @@ pub mod header {
                 hop_by_hop_options: None,
                 destination_options: None,
                 routing: None,
+                shim6: None,
                 fragment: None,
                 auth: None,
             }.is_fragmenting_payload()
@@ -948,6 +992,7 @@ pub mod header {
                 hop_by_hop_options: None,
                 destination_options: None,
                 routing: None,
+                shim6: None,
                 fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0, false, 0)),
                 auth: None,
             }.is_fragmenting_payload()
@@ -959,6 +1004,7 @@ pub mod header {
                 hop_by_hop_options: None,
                 destination_options: None,
                 routing: None,
+                shim6: None,
                 fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0, true, 0)),
                 auth: None,
             }.is_fragmenting_payload()
@@ -973,6 +1019,7 @@ pub mod header {
                 hop_by_hop_options: None,
                 destination_options: None,
                 routing: None,
+                shim6: None,
                 fragment: None,
                 auth: None,
             }.is_empty()
@@ -987,6 +1034,7 @@ pub mod header {
                 ),
                 destination_options: None,
                 routing: None,
+                shim6: None,
                 fragment: None,
                 auth: None,
             }.is_empty()
@@ -1001,6 +1049,7 @@ pub mod header {
                     Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &[1,2,3,4,5,6]).unwrap()
                 ),
                 routing: None,
+                shim6: None,
                 fragment: None,
                 auth: None,
             }.is_empty()
@@ -1018,6 +1067,7 @@ pub mod header {
                         final_destination_options: None,
                     }
                 ),
+                shim6: None,
                 fragment: None,
                 auth: None,
             }.is_empty()
@@ -1030,11 +1080,26 @@ pub mod header {
                 hop_by_hop_options: None,
                 destination_options: None,
                 routing: None,
+                shim6: None,
                 fragment: Some(Ipv6FragmentHeader::new(ip_number::UDP, 0, true, 0)),
                 auth: None,
             }.is_empty()
         );
 
+        // shim6
+        assert!(
+            !Ipv6Extensions{
+                hop_by_hop_options: None,
+                destination_options: None,
+                routing: None,
+                shim6: Some(
+                    Box::new(Ipv6RawExtensionHeader::new_raw(ip_number::UDP, &[1,2,3,4,5,6]).unwrap())
+                ),
+                fragment: None,
+                auth: None,
+            }.is_empty()
+        );
+
         // auth
         assert_eq!(
             false,
@@ -1042,6 +1107,7 @@ pub mod header {
                 hop_by_hop_options: None,
                 destination_options: None,
                 routing: None,
+                shim6: None,
                 fragment: None,
                 auth: Some(IpAuthenticationHeader::new(ip_number::UDP, 0, 0, &[]).unwrap()),
             }.is_empty()
@@ -1053,10 +1119,11 @@ pub mod header {
         let a : Ipv6Extensions = Default::default();
         assert_eq!(
             &format!(
-                "Ipv6Extensions {{ hop_by_hop_options: {:?}, destination_options: {:?}, routing: {:?}, fragment: {:?}, auth: {:?} }}",
+                "Ipv6Extensions {{ hop_by_hop_options: {:?}, destination_options: {:?}, routing: {:?}, shim6: {:?}, fragment: {:?}, auth: {:?} }}",
                 a.hop_by_hop_options,
                 a.destination_options,
                 a.routing,
+                a.shim6,
                 a.fragment,
                 a.auth,
             ),
@@ -1183,6 +1250,7 @@ pub mod slice {
                             final_destination_options: Some(final_destination_options),
                         }
                     ),
+                    shim6: None,
                     fragment: None,
                     auth: Some(auth),
                 };
@@ -1337,6 +1405,14 @@ pub mod ipv6_extension_slice {
                 format!("{:?}", DestinationOptions(slice.clone()))
             );
         }
+        {
+            let header = [UDP, 0, 0x80, 0, 0, 0, 0, 1];
+            let slice = Shim6HeaderSlice::from_slice(&header).unwrap();
+            assert_eq!(
+                format!("Shim6({:?})", slice),
+                format!("{:?}", Shim6(slice.clone()))
+            );
+        }
         {
             let header = Ipv6FragmentHeader::new(UDP, 1, true, 2);
             let mut buffer = Vec::with_capacity(header.header_len());
@@ -1434,6 +1510,11 @@ pub mod slice_iter {
                                 assert_eq!(next, DestinationOptions(header.clone()));
                                 slice = &slice[header.slice().len()..];
                             }
+                            SHIM6 => {
+                                let header = Shim6HeaderSlice::from_slice(slice).unwrap();
+                                assert_eq!(next, Shim6(header.clone()));
+                                slice = &slice[header.slice().len()..];
+                            },
                             IPV6_FRAG => {
                                 let header = Ipv6FragmentHeaderSlice::from_slice(slice).unwrap();
                                 assert_eq!(next, Fragment(header.clone()));
@@ -26,6 +26,30 @@ pub mod header {
         }
     }
 
+    proptest! {
+        #[test]
+        fn try_new(
+            next_header in any::<u8>(),
+            fragment_offset in 0..=0x1fffu16,
+            bad_fragment_offset in 0x2000..=u16::MAX,
+            more_fragments in any::<bool>(),
+            identification in any::<u32>(),
+        ) {
+            assert_eq!(
+                Ok(Ipv6FragmentHeader::new(next_header, fragment_offset, more_fragments, identification)),
+                Ipv6FragmentHeader::try_new(next_header, fragment_offset, more_fragments, identification)
+            );
+            assert_eq!(
+                Err(ValueError::U16TooLarge{
+                    value: bad_fragment_offset,
+                    max: 0x1fff,
+                    field: ErrorField::Ipv6FragmentOffset
+                }),
+                Ipv6FragmentHeader::try_new(next_header, bad_fragment_offset, more_fragments, identification)
+            );
+        }
+    }
+
     proptest! {
         #[test]
         fn from_slice(
@@ -348,3 +348,33 @@ fn partial_equal() {
         assert_ne!(a, b);
     }
 }
+
+#[test]
+fn hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |header: &Ipv6RawExtensionHeader| {
+        let mut hasher = DefaultHasher::new();
+        header.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // unused parts of the payload buffer are not part of the hash
+    let mut a = Ipv6RawExtensionHeader::new_raw(1, &[0;14]).unwrap();
+    a.set_payload(&[1,2,3,4,5,6]).unwrap();
+    let b = Ipv6RawExtensionHeader::new_raw(1, &[1,2,3,4,5,6]).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+    assert_ne!(hash(&a), hash(&Ipv6RawExtensionHeader::new_raw(2, &[1,2,3,4,5,6]).unwrap()));
+}
+
+proptest! {
+    #[test]
+    fn to_bytes(ref input in ipv6_raw_extension_any()) {
+        let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len());
+        input.write(&mut buffer).unwrap();
+        assert_eq!(&buffer[..], &input.to_bytes()[..]);
+        assert!(Ipv6RawExtensionHeader::MIN_SERIALIZED_SIZE <= buffer.len());
+        assert!(buffer.len() <= Ipv6RawExtensionHeader::MAX_SERIALIZED_SIZE);
+    }
+}
@@ -0,0 +1,405 @@
+use super::super::*;
+
+prop_compose! {
+    fn segment_routing_any()(
+        next_header in any::<u8>(),
+        segments_left in any::<u8>(),
+        flags in any::<u8>(),
+        tag in any::<u16>(),
+        segments in proptest::collection::vec(any::<[u8;16]>(), 1..8),
+        tlvs_len in 0usize..4,
+        tlv_byte in any::<u8>(),
+    ) -> Ipv6SegmentRoutingHeader {
+        let mut result = Ipv6SegmentRoutingHeader::new(next_header, segments_left, &segments).unwrap();
+        result.flags = flags;
+        result.tag = tag;
+        result.set_tlvs(&vec![tlv_byte; tlvs_len*8]).unwrap();
+        result
+    }
+}
+
+mod ipv6_segment_routing_header {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(4, Ipv6SegmentRoutingHeader::ROUTING_TYPE);
+        assert_eq!(127, Ipv6SegmentRoutingHeader::MAX_SEGMENTS);
+        assert_eq!(24, Ipv6SegmentRoutingHeader::MIN_LEN);
+        assert_eq!(2048, Ipv6SegmentRoutingHeader::MAX_LEN);
+    }
+
+    #[test]
+    fn new() {
+        let header = Ipv6SegmentRoutingHeader::new(ip_number::UDP, 1, &[[1;16], [2;16]]).unwrap();
+        assert_eq!(ip_number::UDP, header.next_header);
+        assert_eq!(1, header.segments_left);
+        assert_eq!(0, header.flags);
+        assert_eq!(0, header.tag);
+        assert_eq!(1, header.last_entry());
+        assert_eq!(vec![&[1;16], &[2;16]], header.segments().collect::<Vec<_>>());
+        assert_eq!(Some(&[1;16]), header.segment(0));
+        assert_eq!(None, header.segment(2));
+        assert_eq!(Some(&[2;16]), header.active_segment());
+        assert_eq!(0, header.tlvs().len());
+        assert_eq!(8 + 32, header.header_len());
+
+        // maximum number of segments
+        {
+            let segments = [[3;16]; Ipv6SegmentRoutingHeader::MAX_SEGMENTS];
+            let header = Ipv6SegmentRoutingHeader::new(0, 0, &segments).unwrap();
+            assert_eq!(126, header.last_entry());
+            assert_eq!(8 + 127*16, header.header_len());
+        }
+
+        // errors
+        assert_eq!(
+            Err(ValueError::Ipv6SegmentRoutingNoSegments),
+            Ipv6SegmentRoutingHeader::new(0, 0, &[])
+        );
+        assert_eq!(
+            Err(ValueError::Ipv6ExtensionPayloadTooLarge(6 + 128*16)),
+            Ipv6SegmentRoutingHeader::new(0, 0, &[[0;16];128])
+        );
+    }
+
+    #[test]
+    fn set_segments() {
+        let mut header = Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16], [2;16]]).unwrap();
+        header.set_tlvs(&[1,2,3,4,5,6,7,8]).unwrap();
+
+        // tlvs are kept when the segment list grows or shrinks
+        header.set_segments(&[[3;16], [4;16], [5;16]]).unwrap();
+        assert_eq!(vec![&[3;16], &[4;16], &[5;16]], header.segments().collect::<Vec<_>>());
+        assert_eq!(&[1,2,3,4,5,6,7,8], header.tlvs());
+        assert_eq!(8 + 48 + 8, header.header_len());
+
+        header.set_segments(&[[6;16]]).unwrap();
+        assert_eq!(vec![&[6;16]], header.segments().collect::<Vec<_>>());
+        assert_eq!(&[1,2,3,4,5,6,7,8], header.tlvs());
+        assert_eq!(8 + 16 + 8, header.header_len());
+
+        // errors leave the header unchanged
+        header.set_tlvs(&[1;16]).unwrap();
+        let before = header.clone();
+        assert_eq!(
+            Err(ValueError::Ipv6SegmentRoutingNoSegments),
+            header.set_segments(&[])
+        );
+        assert_eq!(
+            Err(ValueError::Ipv6ExtensionPayloadTooLarge(6 + 127*16 + 16)),
+            header.set_segments(&[[0;16];127])
+        );
+        assert_eq!(before, header);
+    }
+
+    #[test]
+    fn set_tlvs() {
+        let mut header = Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16]]).unwrap();
+        header.set_tlvs(&[9;16]).unwrap();
+        assert_eq!(&[9;16], header.tlvs());
+        assert_eq!(8 + 16 + 16, header.header_len());
+        header.set_tlvs(&[]).unwrap();
+        assert_eq!(0, header.tlvs().len());
+        assert_eq!(8 + 16, header.header_len());
+
+        // errors leave the header unchanged
+        let before = header.clone();
+        assert_eq!(
+            Err(ValueError::Ipv6ExtensionPayloadLengthUnaligned(6 + 16 + 7)),
+            header.set_tlvs(&[0;7])
+        );
+        assert_eq!(
+            Err(ValueError::Ipv6ExtensionPayloadTooLarge(6 + 16 + 0xff*8)),
+            header.set_tlvs(&[0;0xff*8])
+        );
+        assert_eq!(before, header);
+    }
+
+    proptest! {
+        #[test]
+        fn write_from_slice(ref header in segment_routing_any()) {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&header.to_bytes()[..], &buffer[..]);
+            assert_eq!(header.header_len(), buffer.len());
+            assert_eq!(
+                &[
+                    header.next_header,
+                    ((header.header_len() - 8) / 8) as u8,
+                    4,
+                    header.segments_left,
+                    header.last_entry(),
+                    header.flags,
+                    header.tag.to_be_bytes()[0],
+                    header.tag.to_be_bytes()[1],
+                ],
+                &buffer[..8]
+            );
+            buffer.extend_from_slice(&[1, 2]);
+
+            let (decoded, rest) = Ipv6SegmentRoutingHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn to_from_raw(ref header in segment_routing_any()) {
+            let raw = header.to_raw();
+            assert_eq!(header.next_header, raw.next_header);
+            assert_eq!(header.header_len(), raw.header_len());
+            assert_eq!(&header.to_bytes()[2..], raw.payload());
+            assert_eq!(header, &Ipv6SegmentRoutingHeader::from_raw(&raw).unwrap());
+        }
+    }
+
+    #[test]
+    fn from_raw_error() {
+        // routing type 2 (mobility)
+        let raw = Ipv6RawExtensionHeader::new_raw(0, &[2,0,0,0,0,0]).unwrap();
+        assert_matches!(
+            Ipv6SegmentRoutingHeader::from_raw(&raw),
+            Err(ReadError::Ipv6SegmentRoutingUnexpectedType(2))
+        );
+    }
+
+    #[test]
+    fn debug_clone_eq_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let header = Ipv6SegmentRoutingHeader::new(1, 0, &[[2;16]]).unwrap();
+        assert_eq!(
+            format!(
+                "Ipv6SegmentRoutingHeader {{ next_header: 1, segments_left: 0, flags: 0, tag: 0, segments: {:?}, tlvs: [] }}",
+                &[2u8;16]
+            ),
+            format!("{:?}", header)
+        );
+        assert_eq!(header, header.clone());
+        let hash = |h: &Ipv6SegmentRoutingHeader| {
+            let mut hasher = DefaultHasher::new();
+            h.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&header), hash(&header.clone()));
+        assert_ne!(header, Ipv6SegmentRoutingHeader::new(1, 0, &[[3;16]]).unwrap());
+    }
+}
+
+mod ipv6_segment_routing_header_slice {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(ref header in segment_routing_any()) {
+            let mut buffer = header.to_bytes().to_vec();
+            buffer.extend_from_slice(&[1, 2]);
+            let slice = Ipv6SegmentRoutingHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(header.next_header, slice.next_header());
+            assert_eq!(header.segments_left, slice.segments_left());
+            assert_eq!(header.last_entry(), slice.last_entry());
+            assert_eq!(header.flags, slice.flags());
+            assert_eq!(header.tag, slice.tag());
+            assert_eq!(header.segment_list(), slice.segment_list());
+            assert_eq!(header.segments().collect::<Vec<_>>(), slice.segments().collect::<Vec<_>>());
+            assert_eq!(header.segment(0), slice.segment(0));
+            assert_eq!(header.active_segment(), slice.active_segment());
+            assert_eq!(header.tlvs(), slice.tlvs());
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        let header = Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16], [2;16]]).unwrap();
+        let bytes = header.to_bytes();
+
+        // length
+        for len in 0..8 {
+            assert_matches!(
+                Ipv6SegmentRoutingHeaderSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+        }
+        for len in 8..bytes.len() {
+            assert_matches!(
+                Ipv6SegmentRoutingHeaderSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(40))
+            );
+        }
+
+        // routing type
+        {
+            let mut bytes = bytes.clone();
+            bytes[2] = 0;
+            assert_matches!(
+                Ipv6SegmentRoutingHeaderSlice::from_slice(&bytes),
+                Err(ReadError::Ipv6SegmentRoutingUnexpectedType(0))
+            );
+        }
+
+        // last entry
+        {
+            let mut bytes = bytes.clone();
+            bytes[4] = 2;
+            assert_matches!(
+                Ipv6SegmentRoutingHeaderSlice::from_slice(&bytes),
+                Err(ReadError::Ipv6SegmentRoutingLastEntryTooLarge(2))
+            );
+            // header without any space for a segment
+            let raw = Ipv6RawExtensionHeader::new_raw(0, &[4,0,0,0,0,0]).unwrap().to_bytes();
+            assert_matches!(
+                Ipv6SegmentRoutingHeaderSlice::from_slice(&raw),
+                Err(ReadError::Ipv6SegmentRoutingLastEntryTooLarge(0))
+            );
+        }
+    }
+
+    #[test]
+    fn active_segment_out_of_range() {
+        let bytes = Ipv6SegmentRoutingHeader::new(0, 2, &[[1;16], [2;16]]).unwrap().to_bytes();
+        let slice = Ipv6SegmentRoutingHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(None, slice.active_segment());
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let bytes = Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16]]).unwrap().to_bytes();
+        let slice = Ipv6SegmentRoutingHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice, slice.clone());
+        assert!(format!("{:?}", slice).starts_with("Ipv6SegmentRoutingHeaderSlice"));
+    }
+}
+
+mod ipv6_segment_routing_header_slice_mut {
+    use super::*;
+
+    #[test]
+    fn modify() {
+        let mut bytes = Ipv6SegmentRoutingHeader::new(0, 2, &[[1;16], [2;16], [3;16]])
+            .unwrap()
+            .to_bytes();
+        bytes.extend([1, 2].iter().copied());
+        {
+            let mut slice = Ipv6SegmentRoutingHeaderSliceMut::from_slice(&mut bytes).unwrap();
+            assert_eq!(2, slice.segments_left());
+            slice.set_flags(0xab);
+            slice.set_tag(0x1234);
+            *slice.segment_mut(1).unwrap() = [9;16];
+            assert_eq!(None, slice.segment_mut(3));
+
+            assert_eq!(Some([9;16]), slice.advance());
+            assert_eq!(1, slice.segments_left());
+            assert_eq!(Some([1;16]), slice.advance());
+            assert_eq!(0, slice.segments_left());
+            assert_eq!(None, slice.advance());
+            assert_eq!(0, slice.segments_left());
+
+            // segments left out of range
+            slice.set_segments_left(5);
+            assert_eq!(None, slice.advance());
+            assert_eq!(5, slice.segments_left());
+            slice.set_segments_left(0);
+
+            assert_eq!(56, slice.as_slice().slice().len());
+        }
+
+        let header = Ipv6SegmentRoutingHeader::from_slice(&bytes).unwrap().0;
+        assert_eq!(0xab, header.flags);
+        assert_eq!(0x1234, header.tag);
+        assert_eq!(vec![&[1;16], &[9;16], &[3;16]], header.segments().collect::<Vec<_>>());
+        assert_eq!(&[1, 2], &bytes[56..]);
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        let mut bytes = Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16]]).unwrap().to_bytes();
+        assert_matches!(
+            Ipv6SegmentRoutingHeaderSliceMut::from_slice(&mut bytes[..23]),
+            Err(ReadError::UnexpectedEndOfSlice(24))
+        );
+        bytes[2] = 3;
+        assert_matches!(
+            Ipv6SegmentRoutingHeaderSliceMut::from_slice(&mut bytes),
+            Err(ReadError::Ipv6SegmentRoutingUnexpectedType(3))
+        );
+    }
+}
+
+mod packet_builder {
+    use super::*;
+
+    #[test]
+    fn ipv6_segment_routing() {
+        let header = Ipv6SegmentRoutingHeader::new(0, 1, &[[1;16], [2;16]]).unwrap();
+        let builder = PacketBuilder::ipv6([0;16], [2;16], 20)
+            .ipv6_segment_routing(header.clone())
+            .udp(21, 1234);
+        let payload = [1,2,3,4];
+        let mut packet = Vec::with_capacity(builder.size(payload.len()));
+        builder.write(&mut packet, &payload).unwrap();
+        assert_eq!(40 + 40 + 8 + 4, packet.len());
+
+        // zero-copy access via the sliced packet
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let routing = match sliced.ip {
+            Some(InternetSlice::Ipv6(ref ip, ref exts)) => {
+                assert_eq!(ip_number::IPV6_ROUTE, ip.next_header());
+                exts.clone().into_iter()
+                    .find_map(|ext| match ext {
+                        Ipv6ExtensionSlice::Routing(raw) => Some(raw),
+                        _ => None,
+                    })
+                    .unwrap()
+            },
+            _ => panic!("expected an ipv6 header"),
+        };
+        let srh = Ipv6SegmentRoutingHeaderSlice::from_slice(routing.slice()).unwrap();
+        assert_eq!(ip_number::UDP, srh.next_header());
+        assert_eq!(Some(&[2;16]), srh.active_segment());
+        assert_eq!(header.segments().collect::<Vec<_>>(), srh.segments().collect::<Vec<_>>());
+        assert_eq!(Some(TransportSlice::Udp(UdpHeaderSlice::from_slice(&packet[80..]).unwrap())), sliced.transport);
+        assert_eq!(&payload, sliced.payload);
+    }
+
+    #[test]
+    fn ipv6_segment_routing_replaces_routing() {
+        let destination_options = Ipv6RawExtensionHeader::new_raw(0, &[0;6]).unwrap();
+        let builder = PacketBuilder::ip(IpHeader::Version6(
+                Ipv6Header{
+                    hop_limit: 4,
+                    ..Default::default()
+                },
+                Ipv6Extensions{
+                    routing: Some(Ipv6RoutingExtensions{
+                        routing: Ipv6RawExtensionHeader::new_raw(0, &[2,0,0,0,0,0]).unwrap(),
+                        final_destination_options: Some(destination_options.clone()),
+                    }),
+                    ..Default::default()
+                }
+            ))
+            .ipv6_segment_routing(Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16]]).unwrap());
+        match builder.ip_header() {
+            Some(IpHeader::Version6(_, exts)) => {
+                let routing = exts.routing.as_ref().unwrap();
+                assert_eq!(
+                    Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16]]).unwrap(),
+                    Ipv6SegmentRoutingHeader::from_raw(&routing.routing).unwrap()
+                );
+                assert_eq!(Some(destination_options), routing.final_destination_options);
+            },
+            _ => panic!("expected an ipv6 header"),
+        }
+    }
+
+    #[test]
+    fn ipv6_segment_routing_ipv4() {
+        let builder = PacketBuilder::ipv4([0;4], [1;4], 20)
+            .ipv6_segment_routing(Ipv6SegmentRoutingHeader::new(0, 0, &[[1;16]]).unwrap());
+        assert_matches!(builder.ip_header(), Some(IpHeader::Version4(_, _)));
+    }
+}
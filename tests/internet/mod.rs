@@ -1,8 +1,15 @@
+pub mod erspan;
+pub mod esp;
+pub mod gre;
+pub mod hip;
 pub mod ip;
 pub mod ip_authentication;
 pub mod ipv4;
 pub mod ipv4_extensions;
 pub mod ipv6;
+pub mod ipv6_address;
 pub mod ipv6_extensions;
 pub mod ipv6_raw_extension;
-pub mod ipv6_fragment;
\ No newline at end of file
+pub mod ipv6_fragment;
+pub mod ipv6_segment_routing;
+pub mod shim6;
\ No newline at end of file
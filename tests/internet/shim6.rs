@@ -0,0 +1,105 @@
+use super::super::*;
+
+#[test]
+fn from_slice_payload_extension() {
+    let data = [
+        ip_number::TCP, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+        1, 2, // data after the header
+    ];
+    let shim6 = Shim6HeaderSlice::from_slice(&data).unwrap();
+    assert_eq!(&data[..8], shim6.slice());
+    assert_eq!(ip_number::TCP, shim6.next_header());
+    assert_eq!(0, shim6.header_length());
+    assert!(shim6.is_payload_extension());
+    assert_eq!(Some(0x7fff_ffff_fffe), shim6.receiver_context_tag());
+    assert_eq!(None, shim6.message_type());
+    assert_eq!(None, shim6.type_specific());
+    assert_eq!(None, shim6.checksum());
+    assert_eq!(&[] as &[u8], shim6.message_data());
+    assert_eq!(
+        Ipv6RawExtensionHeader::new_raw(ip_number::TCP, &data[2..8]).unwrap(),
+        shim6.to_header()
+    );
+}
+
+#[test]
+fn from_slice_control_message() {
+    let data = [
+        59, 1, shim6_message_type::I1, 0b0000_0111, 0x12, 0x34, 1, 2,
+        3, 4, 5, 6, 7, 8, 9, 10,
+    ];
+    let shim6 = Shim6HeaderSlice::from_slice(&data).unwrap();
+    assert_eq!(&data[..], shim6.slice());
+    assert_eq!(59, shim6.next_header());
+    assert_eq!(1, shim6.header_length());
+    assert!(!shim6.is_payload_extension());
+    assert_eq!(None, shim6.receiver_context_tag());
+    assert_eq!(Some(shim6_message_type::I1), shim6.message_type());
+    assert_eq!(Some(0b11), shim6.type_specific());
+    assert_eq!(Some(0x1234), shim6.checksum());
+    assert_eq!(&data[6..], shim6.message_data());
+}
+
+#[test]
+fn from_slice_error() {
+    let data = [59, 1, shim6_message_type::KEEPALIVE, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    for len in 0..data.len() {
+        assert_matches!(
+            Shim6HeaderSlice::from_slice(&data[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
+
+#[test]
+fn sliced_packet() {
+    let mut packet = Vec::new();
+    Ipv6Header {
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 8 + 8 + 4,
+        next_header: ip_number::SHIM6,
+        hop_limit: 64,
+        source: [1;16],
+        destination: [2;16],
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&[ip_number::UDP, 0, 0x80, 0, 0, 0, 0, 1]);
+    UdpHeader {
+        source_port: 1,
+        destination_port: 2,
+        length: 8 + 4,
+        checksum: 0,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&[1, 2, 3, 4]);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        match sliced.ip.unwrap() {
+            InternetSlice::Ipv6(_, exts) => {
+                let mut iter = exts.into_iter();
+                match iter.next().unwrap() {
+                    Ipv6ExtensionSlice::Shim6(shim6) => assert_eq!(Some(1), shim6.receiver_context_tag()),
+                    value => panic!("unexpected extension {:?}", value),
+                }
+                assert_eq!(None, iter.next());
+            },
+            value => panic!("unexpected ip slice {:?}", value),
+        }
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced.payload);
+    }
+
+    // decoding
+    {
+        let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+        match headers.ip.unwrap() {
+            IpHeader::Version6(_, exts) => {
+                assert_eq!(ip_number::UDP, exts.shim6.unwrap().next_header);
+            },
+            value => panic!("unexpected ip header {:?}", value),
+        }
+        assert_matches!(headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], headers.payload);
+    }
+}
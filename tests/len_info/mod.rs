@@ -0,0 +1,209 @@
+use super::*;
+
+fn udp_ipv4_packet(payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, payload)
+        .unwrap();
+    packet
+}
+
+#[test]
+fn new() {
+    let packet = udp_ipv4_packet(&[1,2,3,4]);
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let info = LenInfo::new(100, &packet, &sliced);
+    assert_eq!(
+        LenInfo {
+            wire_len: 100,
+            captured_len: 46,
+            link_headers_len: Some(14),
+            ip_header_len: Some(20),
+            ip_extensions_len: Some(0),
+            transport_header_len: Some(8),
+            l3_payload_declared_len: Some(12),
+            l3_payload_available_len: Some(12),
+            transport_declared_len: Some(12),
+            payload_len: 4,
+        },
+        info
+    );
+    assert!(info.is_truncated());
+    assert_eq!(
+        LenDiscrepancies{
+            capture_truncated: true,
+            ..Default::default()
+        },
+        info.discrepancies()
+    );
+}
+
+#[test]
+fn from_sliced() {
+    let packet = udp_ipv4_packet(&[1,2,3,4]);
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let info = LenInfo::from_sliced(&packet, &sliced);
+    assert_eq!(LenInfo::new(packet.len(), &packet, &sliced), info);
+    assert!(!info.is_truncated());
+    assert_eq!(Some(0), info.l3_payload_missing_len());
+    assert_eq!(Some(0), info.l3_trailing_len());
+    assert!(info.discrepancies().is_empty());
+}
+
+#[test]
+fn ipv6_extensions_tcp() {
+    let mut packet = Vec::new();
+    PacketBuilder::ip(IpHeader::Version6(
+            Ipv6Header{
+                hop_limit: 4,
+                ..Default::default()
+            },
+            Ipv6Extensions{
+                hop_by_hop_options: Some(Ipv6RawExtensionHeader::new_raw(0, &[0;6]).unwrap()),
+                ..Default::default()
+            }
+        ))
+        .tcp(1, 2, 3, 4)
+        .write(&mut packet, &[1,2])
+        .unwrap();
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    let info = LenInfo::from_sliced(&packet, &sliced);
+    assert_eq!(Some(0), info.link_headers_len);
+    assert_eq!(Some(40), info.ip_header_len);
+    assert_eq!(Some(8), info.ip_extensions_len);
+    assert_eq!(Some(20), info.transport_header_len);
+    assert_eq!(Some(8 + 20 + 2), info.l3_payload_declared_len);
+    assert_eq!(Some(8 + 20 + 2), info.l3_payload_available_len);
+    assert_eq!(None, info.transport_declared_len);
+    assert_eq!(2, info.payload_len);
+    assert!(info.discrepancies().is_empty());
+}
+
+#[test]
+fn trailing_bytes() {
+    let mut packet = udp_ipv4_packet(&[1,2,3,4]);
+    packet.resize(60, 0);
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let info = LenInfo::from_sliced(&packet, &sliced);
+    assert_eq!(Some(26), info.l3_payload_available_len);
+    assert_eq!(Some(14), info.l3_trailing_len());
+    assert_eq!(Some(0), info.l3_payload_missing_len());
+    assert_eq!(
+        LenDiscrepancies{
+            l3_trailing_bytes: true,
+            ..Default::default()
+        },
+        info.discrepancies()
+    );
+}
+
+#[test]
+fn l3_payload_truncated() {
+    let packet = udp_ipv4_packet(&[1,2,3,4]);
+    let captured = &packet[..packet.len() - 2];
+    let sliced = SlicedPacket::from_ethernet(captured).unwrap();
+    let info = LenInfo::new(packet.len(), captured, &sliced);
+    assert_eq!(Some(10), info.l3_payload_available_len);
+    assert_eq!(Some(2), info.l3_payload_missing_len());
+    assert_eq!(
+        LenDiscrepancies{
+            capture_truncated: true,
+            l3_payload_truncated: true,
+            transport_len_mismatch: true,
+            ..Default::default()
+        },
+        info.discrepancies()
+    );
+}
+
+#[test]
+fn l3_declared_len_zero() {
+    // segmentation offloading with an ipv6 payload length of 0
+    let mut packet = Vec::new();
+    PacketBuilder::ipv6([0;16], [1;16], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+    packet[4] = 0;
+    packet[5] = 0;
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    let info = LenInfo::from_sliced(&packet, &sliced);
+    assert_eq!(Some(0), info.l3_payload_declared_len);
+    assert_eq!(Some(12), info.l3_payload_available_len);
+    assert_eq!(Some(0), info.l3_trailing_len());
+    assert_eq!(
+        LenDiscrepancies{
+            l3_declared_len_zero: true,
+            ..Default::default()
+        },
+        info.discrepancies()
+    );
+}
+
+#[test]
+fn transport_len_mismatch() {
+    let mut packet = udp_ipv4_packet(&[1,2,3,4]);
+    // udp length field
+    packet[14 + 20 + 4] = 0;
+    packet[14 + 20 + 5] = 9;
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let info = LenInfo::from_sliced(&packet, &sliced);
+    assert_eq!(Some(9), info.transport_declared_len);
+    assert_eq!(
+        LenDiscrepancies{
+            transport_len_mismatch: true,
+            ..Default::default()
+        },
+        info.discrepancies()
+    );
+}
+
+#[test]
+fn no_ip() {
+    let mut packet = Vec::new();
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type: 0x1234,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&[1,2,3]);
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let info = LenInfo::from_sliced(&packet, &sliced);
+    assert_eq!(
+        LenInfo {
+            wire_len: 17,
+            captured_len: 17,
+            payload_len: 3,
+            ..Default::default()
+        },
+        info
+    );
+    assert_eq!(None, info.l3_payload_missing_len());
+    assert_eq!(None, info.l3_trailing_len());
+    assert!(info.discrepancies().is_empty());
+}
+
+#[test]
+fn data_not_matching_packet() {
+    let packet = udp_ipv4_packet(&[1,2,3,4]);
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let other = packet.clone();
+    let info = LenInfo::from_sliced(&other, &sliced);
+    assert_eq!(None, info.link_headers_len);
+    assert_eq!(None, info.l3_payload_available_len);
+    assert_eq!(Some(20), info.ip_header_len);
+    assert_eq!(Some(12), info.l3_payload_declared_len);
+    assert!(info.discrepancies().is_empty());
+}
+
+#[test]
+fn debug_clone_eq() {
+    let info = LenInfo::default();
+    assert_eq!(info, info.clone());
+    assert!(format!("{:?}", info).starts_with("LenInfo"));
+    let discrepancies = LenDiscrepancies::default();
+    assert_eq!(discrepancies, discrepancies.clone());
+    assert!(format!("{:?}", discrepancies).starts_with("LenDiscrepancies"));
+}
@@ -0,0 +1,159 @@
+use super::super::*;
+
+prop_compose! {
+    fn ddp_any()(
+        hop_count in 0..=DdpHeader::MAX_HOP_COUNT,
+        length in 0..=DdpHeader::MAX_LENGTH,
+        checksum in any::<u16>(),
+        destination_network in any::<u16>(),
+        source_network in any::<u16>(),
+        destination_node in any::<u8>(),
+        source_node in any::<u8>(),
+        destination_socket in any::<u8>(),
+        source_socket in any::<u8>(),
+        ddp_type in any::<u8>(),
+    ) -> DdpHeader {
+        DdpHeader{
+            hop_count,
+            length,
+            checksum,
+            destination_network,
+            source_network,
+            destination_node,
+            source_node,
+            destination_socket,
+            source_socket,
+            ddp_type,
+        }
+    }
+}
+
+mod ddp_header {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(13, DdpHeader::SERIALIZED_SIZE);
+        assert_eq!(15, DdpHeader::MAX_HOP_COUNT);
+        assert_eq!(1023, DdpHeader::MAX_LENGTH);
+        assert_eq!(0x809B, ether_type::APPLETALK);
+        assert_eq!(2, ddp_type::NBP);
+        assert_eq!(4, ddp_type::AEP);
+    }
+
+    proptest! {
+        #[test]
+        fn write_from_slice(ref header in ddp_any()) {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..]);
+            assert_eq!(
+                &(u16::from(header.hop_count) << 10 | header.length).to_be_bytes(),
+                &buffer[..2]
+            );
+            buffer.extend_from_slice(&[1, 2]);
+
+            let (decoded, rest) = DdpHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+
+            // length error
+            for len in 0..DdpHeader::SERIALIZED_SIZE {
+                assert_matches!(
+                    DdpHeader::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(13))
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn to_bytes_errors(
+            ref header in ddp_any(),
+            hop_count in (DdpHeader::MAX_HOP_COUNT + 1)..=u8::MAX,
+            length in (DdpHeader::MAX_LENGTH + 1)..=u16::MAX,
+        ) {
+            {
+                let mut header = header.clone();
+                header.hop_count = hop_count;
+                let expected = ValueError::U8TooLarge{
+                    value: hop_count,
+                    max: DdpHeader::MAX_HOP_COUNT,
+                    field: ErrorField::DdpHopCount,
+                };
+                assert_eq!(Err(expected.clone()), header.to_bytes());
+                assert_eq!(Some(expected), header.write(&mut Vec::new()).unwrap_err().value_error());
+            }
+            {
+                let mut header = header.clone();
+                header.length = length;
+                assert_eq!(
+                    Err(ValueError::U16TooLarge{
+                        value: length,
+                        max: DdpHeader::MAX_LENGTH,
+                        field: ErrorField::DdpLength,
+                    }),
+                    header.to_bytes()
+                );
+            }
+        }
+    }
+}
+
+mod ddp_header_slice {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(ref header in ddp_any()) {
+            let mut buffer = header.to_bytes().unwrap().to_vec();
+            buffer.extend_from_slice(&[1, 2]);
+            let slice = DdpHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(header.hop_count, slice.hop_count());
+            assert_eq!(header.length, slice.length());
+            assert_eq!(header.checksum, slice.checksum());
+            assert_eq!(header.destination_network, slice.destination_network());
+            assert_eq!(header.source_network, slice.source_network());
+            assert_eq!(header.destination_node, slice.destination_node());
+            assert_eq!(header.source_node, slice.source_node());
+            assert_eq!(header.destination_socket, slice.destination_socket());
+            assert_eq!(header.source_socket, slice.source_socket());
+            assert_eq!(header.ddp_type, slice.ddp_type());
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+
+    #[test]
+    fn length() {
+        let build = |length: u16| {
+            let mut buffer = Vec::new();
+            DdpHeader{ hop_count: 3, length, ..Default::default() }.write(&mut buffer).unwrap();
+            buffer.extend_from_slice(&[1, 2, 3, 4]);
+            buffer
+        };
+
+        // padding is cut off
+        {
+            let buffer = build(15);
+            let slice = DdpHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..15], slice.slice());
+            assert_eq!(&[1, 2], slice.payload());
+        }
+        // length bigger then the slice or smaller then the header
+        for length in [0, 12, 18, DdpHeader::MAX_LENGTH] {
+            let buffer = build(length);
+            let slice = DdpHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..], slice.slice());
+            assert_eq!(&[1, 2, 3, 4], slice.payload());
+        }
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let bytes = DdpHeader::default().to_bytes().unwrap();
+        let slice = DdpHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice, slice.clone());
+        assert!(format!("{:?}", slice).starts_with("DdpHeaderSlice"));
+    }
+}
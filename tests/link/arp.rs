@@ -0,0 +1,413 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn arp_any()(
+        hardware_len in 0usize..20,
+        protocol_len in 0usize..20,
+    )(
+        hardware_type in any::<u16>(),
+        protocol_type in any::<u16>(),
+        operation in any::<u16>(),
+        sender_hardware_address in proptest::collection::vec(any::<u8>(), hardware_len),
+        sender_protocol_address in proptest::collection::vec(any::<u8>(), protocol_len),
+        target_hardware_address in proptest::collection::vec(any::<u8>(), hardware_len),
+        target_protocol_address in proptest::collection::vec(any::<u8>(), protocol_len),
+    ) -> ArpPacket {
+        ArpPacket::new(
+            hardware_type,
+            protocol_type,
+            operation,
+            &sender_hardware_address,
+            &sender_protocol_address,
+            &target_hardware_address,
+            &target_protocol_address,
+        ).unwrap()
+    }
+}
+
+/// ethernet II frame containing an arp request (padded to the minimum frame size)
+fn arp_frame() -> Vec<u8> {
+    let mut frame = Vec::new();
+    Ethernet2Header {
+        source: [1,2,3,4,5,6],
+        destination: [0xff;6],
+        ether_type: ether_type::ARP,
+    }.write(&mut frame).unwrap();
+    ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2])
+        .write(&mut frame)
+        .unwrap();
+    frame.extend_from_slice(&[0;18]);
+    frame
+}
+
+mod arp_packet {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(1, arp_operation::REQUEST);
+        assert_eq!(2, arp_operation::REPLY);
+        assert_eq!(1, arp_hardware_type::ETHERNET);
+        assert_eq!(24, arp_hardware_type::IEEE1394);
+        assert_eq!(32, arp_hardware_type::INFINIBAND);
+        assert_eq!(8, ArpPacket::MIN_LEN);
+        assert_eq!(8 + 4*255, ArpPacket::MAX_SERIALIZED_SIZE);
+    }
+
+    proptest! {
+        #[test]
+        fn to_bytes_from_slice(input in arp_any()) {
+            let h = usize::from(input.hardware_address_len());
+            let p = usize::from(input.protocol_address_len());
+            let bytes = input.to_bytes();
+            assert_eq!(8 + 2*h + 2*p, bytes.len());
+            assert_eq!(bytes.len(), input.header_len());
+            assert_eq!(&input.hardware_type.to_be_bytes(), &bytes[0..2]);
+            assert_eq!(&input.protocol_type.to_be_bytes(), &bytes[2..4]);
+            assert_eq!(&[h as u8, p as u8], &bytes[4..6]);
+            assert_eq!(&input.operation.to_be_bytes(), &bytes[6..8]);
+            assert_eq!(input.sender_hardware_address(), &bytes[8..8 + h]);
+            assert_eq!(input.sender_protocol_address(), &bytes[8 + h..8 + h + p]);
+            assert_eq!(input.target_hardware_address(), &bytes[8 + h + p..8 + 2*h + p]);
+            assert_eq!(input.target_protocol_address(), &bytes[8 + 2*h + p..]);
+
+            // from_slice (with trailing data)
+            let mut buffer = bytes.to_vec();
+            buffer.extend_from_slice(&[1,2]);
+            let (decoded, rest) = ArpPacket::from_slice(&buffer).unwrap();
+            assert_eq!(input, decoded);
+            assert_eq!(&[1,2], rest);
+
+            // slice
+            let slice = ArpPacketSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&bytes[..], slice.slice());
+            assert_eq!(input.hardware_type, slice.hardware_type());
+            assert_eq!(input.protocol_type, slice.protocol_type());
+            assert_eq!(input.hardware_address_len(), slice.hardware_address_len());
+            assert_eq!(input.protocol_address_len(), slice.protocol_address_len());
+            assert_eq!(input.operation, slice.operation());
+            assert_eq!(input.sender_hardware_address(), slice.sender_hardware_address());
+            assert_eq!(input.sender_protocol_address(), slice.sender_protocol_address());
+            assert_eq!(input.target_hardware_address(), slice.target_hardware_address());
+            assert_eq!(input.target_protocol_address(), slice.target_protocol_address());
+            assert_eq!(input, slice.to_packet());
+
+            // write
+            let mut written = Vec::new();
+            input.write(&mut written).unwrap();
+            assert_eq!(&bytes[..], &written[..]);
+
+            // length error
+            for len in 0..bytes.len() {
+                let expected = if len < 8 { 8 } else { bytes.len() };
+                assert_matches!(
+                    ArpPacket::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(l)) if l == expected
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn read(input in arp_any()) {
+            let bytes = input.to_bytes();
+            let mut cursor = Cursor::new(&bytes[..]);
+            assert_eq!(input, ArpPacket::read(&mut cursor).unwrap());
+            assert_eq!(bytes.len() as u64, cursor.position());
+
+            // io error
+            for len in 0..bytes.len() {
+                let mut cursor = Cursor::new(&bytes[..len]);
+                assert_matches!(ArpPacket::read(&mut cursor), Err(ReadError::IoError(_)));
+            }
+        }
+    }
+
+    #[test]
+    fn infiniband() {
+        // 20 byte InfiniBand hardware addresses (RFC 4391)
+        let request = ArpPacket::new(
+            arp_hardware_type::INFINIBAND,
+            ether_type::IPV4,
+            arp_operation::REQUEST,
+            &[1;20],
+            &[192,168,1,1],
+            &[0;20],
+            &[192,168,1,2]
+        ).unwrap();
+        assert_eq!(20, request.hardware_address_len());
+        assert_eq!(4, request.protocol_address_len());
+        assert_eq!(8 + 2*20 + 2*4, request.header_len());
+
+        let bytes = request.to_bytes();
+        assert_eq!(&[20, 4], &bytes[4..6]);
+        let slice = ArpPacketSlice::from_slice(&bytes).unwrap();
+        assert_eq!(&[1;20], slice.sender_hardware_address());
+        assert_eq!(&[192,168,1,2], slice.target_protocol_address());
+
+        let reply = slice.to_packet().reply(&[2;20]).unwrap();
+        assert_eq!(arp_operation::REPLY, reply.operation);
+        assert_eq!(&[2;20], reply.sender_hardware_address());
+        assert_eq!(&[192,168,1,2], reply.sender_protocol_address());
+        assert_eq!(&[1;20], reply.target_hardware_address());
+        assert_eq!(&[192,168,1,1], reply.target_protocol_address());
+
+        // reply with a hardware address of the wrong length
+        assert_eq!(
+            Err(ValueError::ArpAddressLengthMismatch{ sender_len: 6, target_len: 20 }),
+            request.reply(&[2;6])
+        );
+    }
+
+    #[test]
+    fn ieee1394() {
+        // 16 byte firewire hardware addresses (RFC 2734)
+        let packet = ArpPacket::new(
+            arp_hardware_type::IEEE1394,
+            ether_type::IPV4,
+            arp_operation::REQUEST,
+            &[1;16],
+            &[10,0,0,1],
+            &[0;16],
+            &[10,0,0,2]
+        ).unwrap();
+        let bytes = packet.to_bytes();
+        let (decoded, rest) = ArpPacket::from_slice(&bytes).unwrap();
+        assert_eq!(packet, decoded);
+        assert!(rest.is_empty());
+        assert_eq!(&[1;16], decoded.sender_hardware_address());
+    }
+
+    #[test]
+    fn set_addresses() {
+        let mut packet = ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2]);
+
+        // length mismatch
+        assert_eq!(
+            Err(ValueError::ArpAddressLengthMismatch{ sender_len: 6, target_len: 5 }),
+            packet.set_addresses(&[1;6], &[2;4], &[3;5], &[4;4])
+        );
+        assert_eq!(
+            Err(ValueError::ArpAddressLengthMismatch{ sender_len: 4, target_len: 16 }),
+            packet.set_addresses(&[1;6], &[2;4], &[3;6], &[4;16])
+        );
+
+        // too long
+        assert_eq!(
+            Err(ValueError::ArpAddressTooLong(256)),
+            packet.set_addresses(&[1;256], &[2;4], &[3;256], &[4;4])
+        );
+        assert_eq!(
+            Err(ValueError::ArpAddressTooLong(256)),
+            packet.set_addresses(&[1;6], &[2;256], &[3;6], &[4;256])
+        );
+
+        // errors leave the packet unchanged
+        assert_eq!(
+            ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2]),
+            packet
+        );
+
+        // maximum size
+        packet.set_addresses(&[1;255], &[2;255], &[3;255], &[4;255]).unwrap();
+        assert_eq!(&[1;255], packet.sender_hardware_address());
+        assert_eq!(&[2;255], packet.sender_protocol_address());
+        assert_eq!(&[3;255], packet.target_hardware_address());
+        assert_eq!(&[4;255], packet.target_protocol_address());
+        assert_eq!(ArpPacket::MAX_SERIALIZED_SIZE, packet.to_bytes().len());
+
+        // shorter addresses
+        packet.set_addresses(&[5;2], &[], &[6;2], &[]).unwrap();
+        assert_eq!(
+            ArpPacket::new(
+                arp_hardware_type::ETHERNET,
+                ether_type::IPV4,
+                arp_operation::REQUEST,
+                &[5;2], &[], &[6;2], &[]
+            ).unwrap(),
+            packet
+        );
+        assert_eq!(8 + 2*2, packet.header_len());
+    }
+
+    #[test]
+    fn request_reply() {
+        let request = ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2]);
+        assert_eq!(
+            ArpPacket::new(
+                arp_hardware_type::ETHERNET,
+                ether_type::IPV4,
+                arp_operation::REQUEST,
+                &[1,2,3,4,5,6],
+                &[192,168,1,1],
+                &[0;6],
+                &[192,168,1,2],
+            ).unwrap(),
+            request
+        );
+        assert_eq!(
+            ArpPacket::ethernet_ipv4(
+                arp_operation::REPLY,
+                [7,8,9,10,11,12],
+                [192,168,1,2],
+                [1,2,3,4,5,6],
+                [192,168,1,1],
+            ),
+            request.reply(&[7,8,9,10,11,12]).unwrap()
+        );
+        assert_eq!(28, request.header_len());
+    }
+
+    #[test]
+    fn write_io_error() {
+        let packet = ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2]);
+        let mut buffer = [0u8;27];
+        let mut cursor = Cursor::new(&mut buffer[..]);
+        assert!(packet.write(&mut cursor).is_err());
+    }
+
+    #[test]
+    fn default() {
+        let packet = ArpPacket::default();
+        assert_eq!(0, packet.hardware_address_len());
+        assert_eq!(0, packet.protocol_address_len());
+        assert_eq!(8, packet.header_len());
+        assert!(packet.sender_hardware_address().is_empty());
+        assert!(packet.target_protocol_address().is_empty());
+    }
+
+    #[test]
+    fn debug_clone_eq_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let packet = ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2]);
+        assert_eq!(packet, packet.clone());
+        assert_eq!(
+            "ArpPacket { hardware_type: 1, protocol_type: 2048, operation: 1, sender_hardware_address: [1, 2, 3, 4, 5, 6], sender_protocol_address: [192, 168, 1, 1], target_hardware_address: [0, 0, 0, 0, 0, 0], target_protocol_address: [192, 168, 1, 2] }",
+            format!("{:?}", packet)
+        );
+
+        // same addresses but split with different lengths
+        let other = ArpPacket::new(
+            packet.hardware_type,
+            packet.protocol_type,
+            packet.operation,
+            &[1,2,3,4,5,6,192],
+            &[168,1,1],
+            &[0;7],
+            &[168,1,2],
+        ).unwrap();
+        assert_ne!(packet, other);
+
+        let hash = |value: &ArpPacket| {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&packet), hash(&packet.clone()));
+
+        let bytes = packet.to_bytes();
+        let slice = ArpPacketSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice, slice.clone());
+        println!("{:?}", slice);
+    }
+}
+
+mod arp_packet_slice {
+    use super::*;
+
+    #[test]
+    fn sliced_packet() {
+        let frame = arp_frame();
+
+        // sliced
+        {
+            let sliced = SlicedPacket::from_ethernet(&frame).unwrap();
+            assert_eq!(&frame[14..42], sliced.arp.as_ref().unwrap().slice());
+            assert_eq!(None, sliced.ip);
+            assert_eq!(None, sliced.transport);
+            assert_eq!(&[0;18], sliced.payload);
+            assert_eq!(None, sliced.payload_ether_type());
+
+            let from_ether_type = SlicedPacket::from_ether_type(ether_type::ARP, &frame[14..]).unwrap();
+            assert_eq!(sliced.arp, from_ether_type.arp);
+        }
+
+        // decoded
+        {
+            let decoded = PacketHeaders::from_ethernet_slice(&frame).unwrap();
+            assert_eq!(
+                Some(ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2])),
+                decoded.arp
+            );
+            assert_eq!(None, decoded.ip);
+            assert_eq!(&[0;18], decoded.payload);
+            assert_eq!(None, decoded.payload_ether_type());
+
+            let from_ether_type = PacketHeaders::from_ether_type(ether_type::ARP, &frame[14..]).unwrap();
+            assert_eq!(decoded.arp, from_ether_type.arp);
+        }
+
+        // vlan tagged
+        for vlan_count in 1..3 {
+            let mut tagged = Vec::new();
+            Ethernet2Header {
+                source: [1,2,3,4,5,6],
+                destination: [0xff;6],
+                ether_type: ether_type::VLAN_TAGGED_FRAME,
+            }.write(&mut tagged).unwrap();
+            for index in 0..vlan_count {
+                SingleVlanHeader {
+                    priority_code_point: 0,
+                    drop_eligible_indicator: false,
+                    vlan_identifier: 1,
+                    ether_type: if index + 1 == vlan_count {
+                        ether_type::ARP
+                    } else {
+                        ether_type::VLAN_TAGGED_FRAME
+                    },
+                }.write(&mut tagged).unwrap();
+            }
+            tagged.extend_from_slice(&frame[14..]);
+
+            let sliced = SlicedPacket::from_ethernet(&tagged).unwrap();
+            assert!(sliced.vlan.is_some());
+            assert_eq!(&frame[14..42], sliced.arp.unwrap().slice());
+
+            let decoded = PacketHeaders::from_ethernet_slice(&tagged).unwrap();
+            assert!(decoded.vlan.is_some());
+            assert!(decoded.arp.is_some());
+        }
+    }
+
+    #[test]
+    fn sliced_packet_errors() {
+        let frame = arp_frame();
+
+        // length error
+        assert_matches!(
+            SlicedPacket::from_ethernet(&frame[..41]),
+            Err(ReadError::UnexpectedEndOfSlice(42))
+        );
+        assert_matches!(
+            PacketHeaders::from_ethernet_slice(&frame[..41]),
+            Err(ReadError::UnexpectedEndOfSlice(28))
+        );
+
+        // the address lengths determine the packet length
+        let mut frame = frame;
+        frame[14 + 4] = 16;
+        assert_matches!(
+            SlicedPacket::from_ethernet(&frame),
+            Err(ReadError::UnexpectedEndOfSlice(62))
+        );
+        assert_matches!(
+            PacketHeaders::from_ethernet_slice(&frame),
+            Err(ReadError::UnexpectedEndOfSlice(48))
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use super::super::*;
+
+prop_compose! {
+    fn eapol_key_information_any()(
+        descriptor_version in 0..=EapolKeyInformation::MAX_DESCRIPTOR_VERSION,
+        pairwise in any::<bool>(),
+        key_index in 0..=EapolKeyInformation::MAX_KEY_INDEX,
+        install in any::<bool>(),
+        key_ack in any::<bool>(),
+        key_mic in any::<bool>(),
+        secure in any::<bool>(),
+        error in any::<bool>(),
+        request in any::<bool>(),
+        encrypted_key_data in any::<bool>(),
+        smk_message in any::<bool>(),
+    ) -> EapolKeyInformation {
+        EapolKeyInformation{
+            descriptor_version,
+            pairwise,
+            key_index,
+            install,
+            key_ack,
+            key_mic,
+            secure,
+            error,
+            request,
+            encrypted_key_data,
+            smk_message,
+        }
+    }
+}
+
+prop_compose! {
+    fn eapol_key_any()(
+        descriptor_type in any::<u8>(),
+        key_information in eapol_key_information_any(),
+        key_length in any::<u16>(),
+        replay_counter in any::<u64>(),
+        nonce in any::<[u8;32]>(),
+        iv in any::<[u8;16]>(),
+        rsc in any::<u64>(),
+        reserved in any::<u64>(),
+        mic in any::<[u8;16]>(),
+        key_data_length in 0u16..32,
+    ) -> EapolKey {
+        EapolKey{
+            descriptor_type,
+            key_information,
+            key_length,
+            replay_counter,
+            nonce,
+            iv,
+            rsc,
+            reserved,
+            mic,
+            key_data_length,
+        }
+    }
+}
+
+/// Returns an EAPOL-Key frame (header, key descriptor & key data).
+fn key_frame(key: &EapolKey) -> Vec<u8> {
+    let mut result = Vec::new();
+    EapolHeader {
+        version: EapolHeader::VERSION_2004,
+        packet_type: eapol_packet_type::KEY,
+        body_length: (EapolKey::LEN + usize::from(key.key_data_length)) as u16,
+    }.write(&mut result).unwrap();
+    key.write(&mut result).unwrap();
+    for i in 0..key.key_data_length {
+        result.push(i as u8);
+    }
+    result
+}
+
+mod eapol_header {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(4, EapolHeader::SERIALIZED_SIZE);
+        assert_eq!(0x888E, ether_type::EAPOL);
+        assert_eq!(1, EapolHeader::VERSION_2001);
+        assert_eq!(2, EapolHeader::VERSION_2004);
+        assert_eq!(3, EapolHeader::VERSION_2010);
+        assert_eq!(3, eapol_packet_type::KEY);
+        assert_eq!(5, eapol_packet_type::MKA);
+        assert_eq!(2, eapol_key_descriptor_type::IEEE80211);
+        assert_eq!(254, eapol_key_descriptor_type::WPA);
+    }
+
+    proptest! {
+        #[test]
+        fn write_from_slice(
+            version in any::<u8>(),
+            packet_type in any::<u8>(),
+            body_length in any::<u16>(),
+        ) {
+            let header = EapolHeader{ version, packet_type, body_length };
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&header.to_bytes()[..], &buffer[..]);
+            assert_eq!(&body_length.to_be_bytes(), &buffer[2..4]);
+            buffer.extend_from_slice(&[1, 2]);
+
+            let (decoded, rest) = EapolHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, decoded);
+            assert_eq!(&[1, 2], rest);
+
+            // length error
+            for len in 0..EapolHeader::SERIALIZED_SIZE {
+                assert_matches!(
+                    EapolHeader::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(4))
+                );
+            }
+        }
+    }
+}
+
+mod eapol_header_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        let header = EapolHeader {
+            version: EapolHeader::VERSION_2010,
+            packet_type: eapol_packet_type::EAP_PACKET,
+            body_length: 2,
+        };
+        let mut buffer = header.to_bytes().to_vec();
+        // body & padding
+        buffer.extend_from_slice(&[1, 2, 0, 0]);
+
+        let slice = EapolHeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(&buffer[..6], slice.slice());
+        assert_eq!(&[1, 2], slice.body());
+        assert_eq!(header.version, slice.version());
+        assert_eq!(header.packet_type, slice.packet_type());
+        assert_eq!(header.body_length, slice.body_length());
+        assert_eq!(header, slice.to_header());
+        assert!(slice.key().is_none());
+
+        // length errors
+        for len in 0..4 {
+            assert_matches!(
+                EapolHeaderSlice::from_slice(&buffer[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(4))
+            );
+        }
+        assert_matches!(
+            EapolHeaderSlice::from_slice(&buffer[..5]),
+            Err(ReadError::UnexpectedEndOfSlice(6))
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn key(ref key in eapol_key_any()) {
+            let frame = key_frame(key);
+            let slice = EapolHeaderSlice::from_slice(&frame).unwrap();
+            let decoded = slice.key().unwrap().unwrap();
+            assert_eq!(slice.body(), decoded.slice());
+            assert_eq!(key, &decoded.to_header());
+
+            // errors are relative to the start of the eapol header
+            let mut header = slice.to_header();
+            header.body_length = (EapolKey::LEN - 1) as u16;
+            let mut truncated = header.to_bytes().to_vec();
+            truncated.extend_from_slice(&frame[4..4 + EapolKey::LEN - 1]);
+            assert_matches!(
+                EapolHeaderSlice::from_slice(&truncated).unwrap().key(),
+                Some(Err(ReadError::UnexpectedEndOfSlice(99)))
+            );
+        }
+    }
+
+    #[test]
+    fn sliced_packet() {
+        let key = EapolKey {
+            descriptor_type: eapol_key_descriptor_type::IEEE80211,
+            key_information: EapolKeyInformation {
+                descriptor_version: 2,
+                pairwise: true,
+                key_ack: true,
+                ..Default::default()
+            },
+            key_length: 16,
+            replay_counter: 1,
+            nonce: [0xab; 32],
+            key_data_length: 22,
+            ..Default::default()
+        };
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::EAPOL,
+        }.write(&mut packet).unwrap();
+        packet.extend_from_slice(&key_frame(&key));
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(Some(ether_type::EAPOL), sliced.payload_ether_type());
+        let eapol = EapolHeaderSlice::from_slice(sliced.payload).unwrap();
+        let decoded = eapol.key().unwrap().unwrap();
+        assert_eq!(eapol_key_descriptor_type::IEEE80211, decoded.descriptor_type());
+        assert_eq!(0x008a, decoded.key_information_raw());
+        assert!(decoded.key_information().pairwise);
+        assert!(!decoded.key_information().key_mic);
+        assert_eq!(16, decoded.key_length());
+        assert_eq!(1, decoded.replay_counter());
+        assert_eq!(&[0xab; 32], decoded.nonce());
+        assert_eq!(&[0; 16], decoded.mic());
+        assert_eq!(22, decoded.key_data().len());
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let bytes = EapolHeader::default().to_bytes();
+        let slice = EapolHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice, slice.clone());
+        assert!(format!("{:?}", slice).starts_with("EapolHeaderSlice"));
+        assert_eq!(
+            "EapolHeader { version: 0, packet_type: 0, body_length: 0 }",
+            format!("{:?}", slice.to_header())
+        );
+    }
+}
+
+mod eapol_key_information {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn to_from_u16(ref info in eapol_key_information_any()) {
+            let value = info.to_u16().unwrap();
+            assert_eq!(0, value & 0b1100_0000_0000_0000);
+            assert_eq!(info, &EapolKeyInformation::from_u16(value));
+        }
+    }
+
+    #[test]
+    fn bits() {
+        assert_eq!(0x0003, EapolKeyInformation{ descriptor_version: 3, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0008, EapolKeyInformation{ pairwise: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0030, EapolKeyInformation{ key_index: 3, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0040, EapolKeyInformation{ install: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0080, EapolKeyInformation{ key_ack: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0100, EapolKeyInformation{ key_mic: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0200, EapolKeyInformation{ secure: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0400, EapolKeyInformation{ error: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x0800, EapolKeyInformation{ request: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x1000, EapolKeyInformation{ encrypted_key_data: true, ..Default::default() }.to_u16().unwrap());
+        assert_eq!(0x2000, EapolKeyInformation{ smk_message: true, ..Default::default() }.to_u16().unwrap());
+        // reserved bits are ignored
+        assert_eq!(EapolKeyInformation::default(), EapolKeyInformation::from_u16(0xc000));
+    }
+
+    #[test]
+    fn value_errors() {
+        use ErrorField::*;
+        assert_eq!(
+            Err(ValueError::U8TooLarge{ value: 8, max: 7, field: EapolKeyDescriptorVersion }),
+            EapolKeyInformation{ descriptor_version: 8, ..Default::default() }.to_u16()
+        );
+        assert_eq!(
+            Err(ValueError::U8TooLarge{ value: 4, max: 3, field: EapolKeyIndex }),
+            EapolKeyInformation{ key_index: 4, ..Default::default() }.to_u16()
+        );
+        let key = EapolKey {
+            key_information: EapolKeyInformation{ key_index: 4, ..Default::default() },
+            ..Default::default()
+        };
+        assert!(key.to_bytes().is_err());
+        assert_matches!(key.write(&mut Vec::new()), Err(WriteError::ValueError(_)));
+    }
+}
+
+mod eapol_key {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn write_from_slice(ref key in eapol_key_any()) {
+            let mut buffer = Vec::new();
+            key.write(&mut buffer).unwrap();
+            assert_eq!(EapolKey::LEN, key.header_len());
+            assert_eq!(&key.to_bytes().unwrap()[..], &buffer[..]);
+            assert_eq!(&key.replay_counter.to_be_bytes(), &buffer[5..13]);
+            assert_eq!(&key.nonce, &buffer[13..45]);
+            assert_eq!(&key.mic, &buffer[77..93]);
+            for i in 0..key.key_data_length {
+                buffer.push(i as u8);
+            }
+            buffer.extend_from_slice(&[0xff, 0xff]);
+
+            // header
+            {
+                let (decoded, rest) = EapolKey::from_slice(&buffer).unwrap();
+                assert_eq!(key, &decoded);
+                assert_eq!(&buffer[EapolKey::LEN..], rest);
+            }
+            // slice
+            {
+                let slice = EapolKeySlice::from_slice(&buffer).unwrap();
+                let len = EapolKey::LEN + usize::from(key.key_data_length);
+                assert_eq!(&buffer[..len], slice.slice());
+                assert_eq!(&buffer[EapolKey::LEN..len], slice.key_data());
+                assert_eq!(key.descriptor_type, slice.descriptor_type());
+                assert_eq!(key.key_information, slice.key_information());
+                assert_eq!(key.key_length, slice.key_length());
+                assert_eq!(key.replay_counter, slice.replay_counter());
+                assert_eq!(&key.nonce, slice.nonce());
+                assert_eq!(&key.iv, slice.iv());
+                assert_eq!(key.rsc, slice.rsc());
+                assert_eq!(key.reserved, slice.reserved());
+                assert_eq!(&key.mic, slice.mic());
+                assert_eq!(key.key_data_length, slice.key_data_length());
+                assert_eq!(key, &slice.to_header());
+                assert_eq!(slice, slice.clone());
+            }
+            // length errors
+            for len in 0..EapolKey::LEN {
+                assert_matches!(
+                    EapolKeySlice::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(95))
+                );
+            }
+            if 0 < key.key_data_length {
+                let len = EapolKey::LEN + usize::from(key.key_data_length);
+                assert_matches!(
+                    EapolKeySlice::from_slice(&buffer[..len - 1]),
+                    Err(ReadError::UnexpectedEndOfSlice(l)) if l == len
+                );
+            }
+        }
+    }
+}
@@ -15,6 +15,21 @@ mod ether_type {
         assert_eq!(0x8100, VlanTaggedFrame as u16);
         assert_eq!(0x88A8, ProviderBridging as u16);
         assert_eq!(0x9100, VlanDoubleTaggedFrame as u16);
+        assert_eq!(0xF1C1, RedundancyTag as u16);
+        assert_eq!(0x8808, MacControl as u16);
+        assert_eq!(0x8847, MplsUnicast as u16);
+        assert_eq!(0x8848, MplsMulticast as u16);
+        assert_eq!(0x6558, TransparentEthernetBridging as u16);
+        assert_eq!(0x894F, Nsh as u16);
+        assert_eq!(0x8863, PppoeDiscovery as u16);
+        assert_eq!(0x8864, PppoeSession as u16);
+        assert_eq!(0x88CC, Lldp as u16);
+        assert_eq!(0x8137, Ipx as u16);
+        assert_eq!(0x809B, AppleTalk as u16);
+        assert_eq!(0x88BE, ErspanType2 as u16);
+        assert_eq!(0x22EB, ErspanType3 as u16);
+        assert_eq!(0x88E5, Macsec as u16);
+        assert_eq!(0x888E, Eapol as u16);
     }
 
     #[test]
@@ -27,6 +42,21 @@ mod ether_type {
         assert_eq!(EtherType::from_u16(0x8100), Some(VlanTaggedFrame));
         assert_eq!(EtherType::from_u16(0x88A8), Some(ProviderBridging));
         assert_eq!(EtherType::from_u16(0x9100), Some(VlanDoubleTaggedFrame));
+        assert_eq!(EtherType::from_u16(0xF1C1), Some(RedundancyTag));
+        assert_eq!(EtherType::from_u16(0x8808), Some(MacControl));
+        assert_eq!(EtherType::from_u16(0x8847), Some(MplsUnicast));
+        assert_eq!(EtherType::from_u16(0x8848), Some(MplsMulticast));
+        assert_eq!(EtherType::from_u16(0x6558), Some(TransparentEthernetBridging));
+        assert_eq!(EtherType::from_u16(0x894F), Some(Nsh));
+        assert_eq!(EtherType::from_u16(0x8863), Some(PppoeDiscovery));
+        assert_eq!(EtherType::from_u16(0x8864), Some(PppoeSession));
+        assert_eq!(EtherType::from_u16(0x88CC), Some(Lldp));
+        assert_eq!(EtherType::from_u16(0x8137), Some(Ipx));
+        assert_eq!(EtherType::from_u16(0x809B), Some(AppleTalk));
+        assert_eq!(EtherType::from_u16(0x88BE), Some(ErspanType2));
+        assert_eq!(EtherType::from_u16(0x22EB), Some(ErspanType3));
+        assert_eq!(EtherType::from_u16(0x88E5), Some(Macsec));
+        assert_eq!(EtherType::from_u16(0x888E), Some(Eapol));
         assert_eq!(EtherType::from_u16(0x1234), None);
     }
 
@@ -41,7 +71,22 @@ mod ether_type {
             (WakeOnLan, WAKE_ON_LAN),
             (VlanTaggedFrame, VLAN_TAGGED_FRAME),
             (ProviderBridging, PROVIDER_BRIDGING),
-            (VlanDoubleTaggedFrame, VLAN_DOUBLE_TAGGED_FRAME)
+            (VlanDoubleTaggedFrame, VLAN_DOUBLE_TAGGED_FRAME),
+            (RedundancyTag, REDUNDANCY_TAG),
+            (MacControl, MAC_CONTROL),
+            (MplsUnicast, MPLS_UNICAST),
+            (MplsMulticast, MPLS_MULTICAST),
+            (TransparentEthernetBridging, TRANSPARENT_ETHERNET_BRIDGING),
+            (Nsh, NSH),
+            (PppoeDiscovery, PPPOE_DISCOVERY),
+            (PppoeSession, PPPOE_SESSION),
+            (Lldp, LLDP),
+            (Ipx, IPX),
+            (AppleTalk, APPLETALK),
+            (ErspanType2, ERSPAN_TYPE_2),
+            (ErspanType3, ERSPAN_TYPE_3),
+            (Macsec, MACSEC),
+            (Eapol, EAPOL)
         ];
 
         for (enum_value, constant) in pairs {
@@ -59,7 +104,22 @@ mod ether_type {
             (WakeOnLan, "WakeOnLan"),
             (VlanTaggedFrame, "VlanTaggedFrame"),
             (ProviderBridging, "ProviderBridging"),
-            (VlanDoubleTaggedFrame, "VlanDoubleTaggedFrame")
+            (VlanDoubleTaggedFrame, "VlanDoubleTaggedFrame"),
+            (RedundancyTag, "RedundancyTag"),
+            (MacControl, "MacControl"),
+            (MplsUnicast, "MplsUnicast"),
+            (MplsMulticast, "MplsMulticast"),
+            (TransparentEthernetBridging, "TransparentEthernetBridging"),
+            (Nsh, "Nsh"),
+            (PppoeDiscovery, "PppoeDiscovery"),
+            (PppoeSession, "PppoeSession"),
+            (Lldp, "Lldp"),
+            (Ipx, "Ipx"),
+            (AppleTalk, "AppleTalk"),
+            (ErspanType2, "ErspanType2"),
+            (ErspanType3, "ErspanType3"),
+            (Macsec, "Macsec"),
+            (Eapol, "Eapol")
         ];
 
         for (enum_value, str_value) in pairs {
@@ -81,6 +141,21 @@ mod ether_type {
             VlanTaggedFrame,
             ProviderBridging,
             VlanDoubleTaggedFrame,
+            RedundancyTag,
+            MacControl,
+            MplsUnicast,
+            MplsMulticast,
+            TransparentEthernetBridging,
+            Nsh,
+            PppoeDiscovery,
+            PppoeSession,
+            Lldp,
+            Ipx,
+            AppleTalk,
+            ErspanType2,
+            ErspanType3,
+            Macsec,
+            Eapol,
         ];
 
         // clone
@@ -260,6 +335,19 @@ mod ethernet2_header {
         }
     }
 
+    proptest! {
+        #[test]
+        fn setters(input in ethernet_2_any()) {
+            assert_eq!(
+                input,
+                Ethernet2Header::default()
+                    .with_source(input.source)
+                    .with_destination(input.destination)
+                    .with_ether_type(input.ether_type)
+            );
+        }
+    }
+
     proptest! {
         #[test]
         fn clone_eq(input in ethernet_2_any()) {
@@ -0,0 +1,315 @@
+use super::super::*;
+
+use ieee80211_frame_type::*;
+
+fn data_header(to_ds: bool, from_ds: bool, qos_control: Option<u16>) -> Ieee80211Header {
+    Ieee80211Header {
+        frame_control: Ieee80211FrameControl {
+            frame_type: DATA,
+            subtype: if qos_control.is_some() { 0x8 } else { 0 },
+            to_ds,
+            from_ds,
+            ..Default::default()
+        },
+        duration_id: 0x1234,
+        address1: [1; 6],
+        address2: Some([2; 6]),
+        address3: Some([3; 6]),
+        sequence_control: Some(0x5678),
+        address4: if to_ds && from_ds { Some([4; 6]) } else { None },
+        qos_control,
+        ht_control: None,
+    }
+}
+
+/// Ipv4 udp packet with the payload [1,2,3,4].
+fn ipv4_udp() -> Vec<u8> {
+    let mut result = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut result, &[1, 2, 3, 4])
+        .unwrap();
+    result
+}
+
+proptest! {
+    #[test]
+    fn frame_control_bytes(bytes in any::<[u8;2]>()) {
+        let frame_control = Ieee80211FrameControl::from_bytes(bytes);
+        assert_eq!(bytes, frame_control.to_bytes());
+    }
+}
+
+#[test]
+fn frame_control_header_len() {
+    let tests = [
+        // (type, subtype, flags, header len)
+        (MANAGEMENT, 8, 0, 24),
+        (MANAGEMENT, 8, 0x80, 28),
+        (CONTROL, 0xb, 0, 16),
+        (CONTROL, 0xc, 0, 10),
+        (CONTROL, 0xd, 0, 10),
+        (CONTROL, 0x7, 0, 10),
+        (CONTROL, 0x9, 0x80, 16),
+        (DATA, 0, 0, 24),
+        (DATA, 0, 0x03, 30),
+        (DATA, 0, 0x80, 24),
+        (DATA, 0x8, 0, 26),
+        (DATA, 0x8, 0x80, 30),
+        (DATA, 0xc, 0x83, 36),
+        (EXTENSION, 0, 0, 10),
+    ];
+    for (frame_type, subtype, flags, expected) in tests {
+        let frame_control = Ieee80211FrameControl::from_bytes([(frame_type << 2) | (subtype << 4), flags]);
+        assert_eq!(expected, frame_control.header_len(), "{:?}", frame_control);
+    }
+}
+
+#[test]
+fn write_from_slice() {
+    let control = |subtype, address2| Ieee80211Header {
+        frame_control: Ieee80211FrameControl{ frame_type: CONTROL, subtype, ..Default::default() },
+        address1: [1; 6],
+        address2,
+        ..Default::default()
+    };
+    let management = Ieee80211Header {
+        frame_control: Ieee80211FrameControl{ frame_type: MANAGEMENT, subtype: 8, order: true, ..Default::default() },
+        address1: [0xff; 6],
+        address2: Some([2; 6]),
+        address3: Some([2; 6]),
+        sequence_control: Some(0x10),
+        ht_control: Some(0x01020304),
+        ..Default::default()
+    };
+    let mut qos_ht = data_header(true, true, Some(0x0085));
+    qos_ht.frame_control.order = true;
+    qos_ht.ht_control = Some(0x0a0b0c0d);
+
+    let headers = [
+        control(0xd, None),
+        control(0xb, Some([2; 6])),
+        management,
+        data_header(false, false, None),
+        data_header(true, false, None),
+        data_header(false, true, Some(5)),
+        data_header(true, true, Some(5)),
+        qos_ht,
+    ];
+    for header in headers {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        assert_eq!(header.header_len(), buffer.len());
+        assert_eq!(header.frame_control.header_len(), buffer.len());
+        buffer.extend_from_slice(&[1, 2]);
+
+        let (decoded, rest) = Ieee80211Header::from_slice(&buffer).unwrap();
+        assert_eq!(header, decoded);
+        assert_eq!(&[1, 2], rest);
+
+        let slice = Ieee80211HeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(&buffer[..header.header_len()], slice.slice());
+        assert_eq!(header.frame_control, slice.frame_control());
+        assert_eq!(header.duration_id, slice.duration_id());
+        assert_eq!(header.address1, slice.address1());
+        assert_eq!(header.address2, slice.address2());
+        assert_eq!(header.address3, slice.address3());
+        assert_eq!(header.sequence_control, slice.sequence_control());
+        assert_eq!(header.sequence_control.map(|v| v >> 4), slice.sequence_number());
+        assert_eq!(header.sequence_control.map(|v| (v & 0xf) as u8), slice.fragment_number());
+        assert_eq!(header.address4, slice.address4());
+        assert_eq!(header.qos_control, slice.qos_control());
+        assert_eq!(header.ht_control, slice.ht_control());
+        assert_eq!(header, slice.to_header());
+    }
+}
+
+#[test]
+fn addresses() {
+    let tests = [
+        // (to_ds, from_ds, destination, source, bssid)
+        (false, false, Some([1; 6]), Some([2; 6]), Some([3; 6])),
+        (true, false, Some([3; 6]), Some([2; 6]), Some([1; 6])),
+        (false, true, Some([1; 6]), Some([3; 6]), Some([2; 6])),
+        (true, true, Some([3; 6]), Some([4; 6]), None),
+    ];
+    for (to_ds, from_ds, destination, source, bssid) in tests {
+        let header = data_header(to_ds, from_ds, Some(0x0007));
+        assert_eq!(destination, header.destination());
+        assert_eq!(source, header.source());
+        assert_eq!(bssid, header.bssid());
+        assert_eq!(Some(7), header.tid());
+    }
+
+    let ack = Ieee80211Header {
+        frame_control: Ieee80211FrameControl{ frame_type: CONTROL, subtype: 0xd, ..Default::default() },
+        ..Default::default()
+    };
+    assert_eq!(None, ack.destination());
+    assert_eq!(None, ack.source());
+    assert_eq!(None, ack.bssid());
+    assert_eq!(None, ack.tid());
+}
+
+#[test]
+fn from_slice_errors() {
+    let mut buffer = Vec::new();
+    data_header(true, true, Some(0)).write(&mut buffer).unwrap();
+    for len in 0..2 {
+        assert_matches!(
+            Ieee80211HeaderSlice::from_slice(&buffer[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(2))
+        );
+    }
+    for len in 2..buffer.len() {
+        assert_matches!(
+            Ieee80211Header::from_slice(&buffer[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(32))
+        );
+    }
+    buffer[0] |= 0x1;
+    assert_matches!(
+        Ieee80211HeaderSlice::from_slice(&buffer),
+        Err(ReadError::Ieee80211UnsupportedVersion(1))
+    );
+}
+
+#[test]
+fn sliced_packet() {
+    for qos_control in [None, Some(0)] {
+        let mut packet = Vec::new();
+        data_header(false, true, qos_control).write(&mut packet).unwrap();
+        LlcSnapHeader::with_ether_type(ether_type::IPV4).write(&mut packet).unwrap();
+        packet.extend_from_slice(&ipv4_udp());
+
+        let sliced = SlicedPacket::from_ieee80211(&packet).unwrap();
+        assert!(sliced.link.is_none());
+        assert!(sliced.ip.is_some());
+        assert!(sliced.transport.is_some());
+        assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+        let decoded = PacketHeaders::from_ieee80211_slice(&packet).unwrap();
+        assert!(decoded.link.is_none());
+        assert!(decoded.ip.is_some());
+        assert!(decoded.transport.is_some());
+        assert_eq!(&[1, 2, 3, 4], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_payload() {
+    let snap_ipv4 = [&LlcSnapHeader::with_ether_type(ether_type::IPV4).to_bytes()[..], &ipv4_udp()].concat();
+
+    let mut protected = data_header(false, true, None);
+    protected.frame_control.protected = true;
+    let mut null = data_header(true, false, None);
+    null.frame_control.subtype = 0x4;
+    let amsdu = data_header(false, true, Some(0x80));
+    let beacon = Ieee80211Header {
+        frame_control: Ieee80211FrameControl{ frame_type: MANAGEMENT, subtype: 8, ..Default::default() },
+        address2: Some([2; 6]),
+        address3: Some([2; 6]),
+        sequence_control: Some(0),
+        ..Default::default()
+    };
+    let tests = [
+        (protected.clone(), snap_ipv4.clone()),
+        (null, snap_ipv4.clone()),
+        (amsdu, snap_ipv4.clone()),
+        (beacon, snap_ipv4.clone()),
+        // non snap llc header
+        (data_header(false, false, None), vec![0x42, 0x42, 0x03, 1, 2]),
+        // non ether type oui
+        (data_header(false, false, None), vec![0xaa, 0xaa, 0x03, 0, 0, 0x0c, 0x20, 0]),
+        // too short snap header
+        (data_header(false, false, None), vec![0xaa, 0xaa, 0x03]),
+    ];
+    for (header, body) in tests {
+        let mut packet = Vec::new();
+        header.write(&mut packet).unwrap();
+        packet.extend_from_slice(&body);
+
+        let sliced = SlicedPacket::from_ieee80211(&packet).unwrap();
+        assert!(sliced.ip.is_none());
+        assert_eq!(&body[..], sliced.payload);
+
+        let decoded = PacketHeaders::from_ieee80211_slice(&packet).unwrap();
+        assert!(decoded.ip.is_none());
+        assert_eq!(&body[..], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_radiotap() {
+    use radiotap_flags::*;
+
+    for flags in [0, FCS, DATA_PAD, FCS | DATA_PAD] {
+        // radiotap header with the flags field
+        let mut packet = vec![0, 0, 10, 0, 0x02, 0, 0, 0, flags, 0];
+        data_header(true, false, Some(0)).write(&mut packet).unwrap();
+        if 0 != flags & DATA_PAD {
+            packet.extend_from_slice(&[0, 0]);
+        }
+        LlcSnapHeader::with_ether_type(ether_type::IPV4).write(&mut packet).unwrap();
+        packet.extend_from_slice(&ipv4_udp());
+        if 0 != flags & FCS {
+            packet.extend_from_slice(&[0xff; 4]);
+        }
+
+        let sliced = SlicedPacket::from_radiotap(&packet).unwrap();
+        assert!(sliced.ip.is_some());
+        assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+        let decoded = PacketHeaders::from_radiotap_slice(&packet).unwrap();
+        assert!(decoded.ip.is_some());
+        assert_eq!(&[1, 2, 3, 4], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_errors() {
+    // offsets are relative to the start of the packet
+    let mut packet = vec![0, 0, 8, 0, 0, 0, 0, 0];
+    data_header(false, false, None).write(&mut packet).unwrap();
+    packet.truncate(20);
+    assert_matches!(
+        SlicedPacket::from_radiotap(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(32))
+    );
+    assert_matches!(
+        PacketHeaders::from_radiotap_slice(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(32))
+    );
+    assert_matches!(
+        SlicedPacket::from_ieee80211(&packet[8..]),
+        Err(ReadError::UnexpectedEndOfSlice(24))
+    );
+    assert_matches!(
+        PacketHeaders::from_ieee80211_slice(&packet[8..]),
+        Err(ReadError::UnexpectedEndOfSlice(24))
+    );
+
+    // radiotap header errors
+    assert_matches!(
+        SlicedPacket::from_radiotap(&[1, 0, 8, 0, 0, 0, 0, 0]),
+        Err(ReadError::RadiotapUnsupportedVersion(1))
+    );
+    assert_matches!(
+        PacketHeaders::from_radiotap_slice(&[0, 0, 7, 0, 0, 0, 0, 0]),
+        Err(ReadError::RadiotapLengthTooSmall(7))
+    );
+
+    // errors in the ip header
+    let mut packet = Vec::new();
+    data_header(false, false, None).write(&mut packet).unwrap();
+    LlcSnapHeader::with_ether_type(ether_type::IPV4).write(&mut packet).unwrap();
+    packet.extend_from_slice(&[0x45, 0]);
+    assert_matches!(
+        SlicedPacket::from_ieee80211(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(52))
+    );
+    assert_matches!(
+        PacketHeaders::from_ieee80211_slice(&packet),
+        Err(_)
+    );
+}
@@ -0,0 +1,169 @@
+use super::super::*;
+
+prop_compose! {
+    fn ipx_address_any()(
+        network in any::<u32>(),
+        node in any::<[u8;6]>(),
+        socket in any::<u16>(),
+    ) -> IpxAddress {
+        IpxAddress{ network, node, socket }
+    }
+}
+
+prop_compose! {
+    fn ipx_any()(
+        checksum in any::<u16>(),
+        length in any::<u16>(),
+        transport_control in any::<u8>(),
+        packet_type in any::<u8>(),
+        destination in ipx_address_any(),
+        source in ipx_address_any(),
+    ) -> IpxHeader {
+        IpxHeader{
+            checksum,
+            length,
+            transport_control,
+            packet_type,
+            destination,
+            source,
+        }
+    }
+}
+
+mod ipx_address {
+    use super::*;
+
+    #[test]
+    fn to_from_bytes() {
+        let address = IpxAddress{ network: 0x01020304, node: [5,6,7,8,9,10], socket: 0x0b0c };
+        let bytes = [1,2,3,4,5,6,7,8,9,10,11,12];
+        assert_eq!(bytes, address.to_bytes());
+        assert_eq!(address, IpxAddress::from_bytes(bytes));
+        assert_eq!(IpxAddress{ network: 0, node: [0;6], socket: 0 }, IpxAddress::default());
+    }
+}
+
+mod ipx_header {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(30, IpxHeader::SERIALIZED_SIZE);
+        assert_eq!(0xffff, IpxHeader::NO_CHECKSUM);
+        assert_eq!(0x8137, ether_type::IPX);
+        assert_eq!(4, ipx_packet_type::PEP);
+        assert_eq!(17, ipx_packet_type::NCP);
+    }
+
+    #[test]
+    fn default() {
+        let header: IpxHeader = Default::default();
+        assert_eq!(0xffff, header.checksum);
+        assert_eq!(30, header.length);
+        assert_eq!(0, header.transport_control);
+        assert_eq!(ipx_packet_type::UNKNOWN, header.packet_type);
+    }
+
+    proptest! {
+        #[test]
+        fn write_from_slice(ref header in ipx_any()) {
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&header.to_bytes()[..], &buffer[..]);
+            assert_eq!(&header.checksum.to_be_bytes(), &buffer[..2]);
+            assert_eq!(&header.length.to_be_bytes(), &buffer[2..4]);
+            assert_eq!(&header.destination.to_bytes(), &buffer[6..18]);
+            assert_eq!(&header.source.to_bytes(), &buffer[18..30]);
+            buffer.extend_from_slice(&[1, 2]);
+
+            let (decoded, rest) = IpxHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+
+            // length error
+            for len in 0..IpxHeader::SERIALIZED_SIZE {
+                assert_matches!(
+                    IpxHeader::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(30))
+                );
+            }
+        }
+    }
+}
+
+mod ipx_header_slice {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(ref header in ipx_any()) {
+            let mut buffer = header.to_bytes().to_vec();
+            buffer.extend_from_slice(&[1, 2]);
+            let slice = IpxHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(header.checksum, slice.checksum());
+            assert_eq!(header.length, slice.length());
+            assert_eq!(header.transport_control, slice.transport_control());
+            assert_eq!(header.packet_type, slice.packet_type());
+            assert_eq!(header.destination, slice.destination());
+            assert_eq!(header.source, slice.source());
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+
+    #[test]
+    fn length() {
+        let mut header = IpxHeader::default();
+        let mut buffer = Vec::new();
+        let build = |header: &IpxHeader, buffer: &mut Vec<u8>| {
+            buffer.clear();
+            header.write(buffer).unwrap();
+            buffer.extend_from_slice(&[1, 2, 3, 4]);
+        };
+
+        // padding is cut off
+        header.length = 32;
+        build(&header, &mut buffer);
+        let slice = IpxHeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(&buffer[..32], slice.slice());
+        assert_eq!(&[1, 2], slice.payload());
+
+        // length bigger then the slice or smaller then the header
+        for length in [0, 29, 35, 0xffff] {
+            header.length = length;
+            build(&header, &mut buffer);
+            let slice = IpxHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..], slice.slice());
+            assert_eq!(&[1, 2, 3, 4], slice.payload());
+        }
+    }
+
+    #[test]
+    fn sliced_packet() {
+        let header = IpxHeader {
+            length: 31,
+            packet_type: ipx_packet_type::SPX,
+            ..Default::default()
+        };
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [0xff;6],
+            ether_type: ether_type::IPX,
+        }.write(&mut packet).unwrap();
+        header.write(&mut packet).unwrap();
+        packet.resize(60, 0);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let ipx = IpxHeaderSlice::from_slice(sliced.payload).unwrap();
+        assert_eq!(header, ipx.to_header());
+        assert_eq!(&[0], ipx.payload());
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let bytes = IpxHeader::default().to_bytes();
+        let slice = IpxHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice, slice.clone());
+        assert!(format!("{:?}", slice).starts_with("IpxHeaderSlice"));
+    }
+}
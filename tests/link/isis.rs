@@ -0,0 +1,199 @@
+use super::super::*;
+
+/// Level 1 LAN hello with an area addresses TLV followed by padding.
+fn lan_hello() -> Vec<u8> {
+    let mut result = vec![
+        0x83, 27, 1, 0, isis_pdu_type::L1_LAN_HELLO, 1, 0, 0,
+        1, // circuit type
+        1, 2, 3, 4, 5, 6, // source id
+        0, 30, // holding time
+        0, 35, // pdu length
+        64, // priority
+        1, 2, 3, 4, 5, 6, 1, // lan id
+        1, 4, 3, 0x49, 0, 1, // area addresses TLV
+    ];
+    // padding TLV & trailing ethernet padding
+    result.extend_from_slice(&[8, 0, 0, 0, 0]);
+    result
+}
+
+/// Level 2 LSP without TLVs.
+const L2_LSP: [u8;27] = [
+    0x83, 27, 1, 0, isis_pdu_type::L2_LSP, 1, 0, 0,
+    0, 27, // pdu length
+    0x04, 0xb0, // remaining lifetime
+    1, 2, 3, 4, 5, 6, 0, 1, // lsp id
+    0, 0, 0, 0x2a, // sequence number
+    0x12, 0x34, // checksum
+    3, // flags
+];
+
+mod isis_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        use IsisError::*;
+        assert_eq!(
+            "IsisError: Unexpected end of slice. The given slice contained less then minimum required 8 bytes.",
+            format!("{}", UnexpectedEndOfSlice(8))
+        );
+        assert_eq!(
+            "IsisError: Invalid protocol discriminator 0x82 (expected 0x83).",
+            format!("{}", InvalidProtocolDiscriminator(0x82))
+        );
+        assert_eq!(
+            "IsisError: Unsupported PDU type 3.",
+            format!("{}", UnsupportedPduType(3))
+        );
+        assert_eq!(
+            "IsisError: Invalid header length 20 for the PDU type 15.",
+            format!("{}", InvalidHeaderLength{ pdu_type: 15, header_len: 20 })
+        );
+        assert_eq!(
+            "IsisError: PDU length 10 is smaller then the header length.",
+            format!("{}", InvalidPduLength(10))
+        );
+        assert_eq!(
+            "IsisError: The TLV at offset 27 extends past the end of the PDU.",
+            format!("{}", InvalidTlvLength(27))
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(IsisError::UnsupportedPduType(3).source().is_none());
+    }
+}
+
+mod isis_pdu_slice {
+    use super::*;
+
+    #[test]
+    fn header_len_of() {
+        use isis_pdu_type::*;
+        for (pdu_type, expected) in [
+            (L1_LAN_HELLO, 27), (L2_LAN_HELLO, 27), (P2P_HELLO, 20),
+            (L1_LSP, 27), (L2_LSP, 27),
+            (L1_CSNP, 33), (L2_CSNP, 33),
+            (L1_PSNP, 17), (L2_PSNP, 17),
+        ] {
+            assert_eq!(Some(expected), IsisPduSlice::header_len_of(pdu_type));
+        }
+        assert_eq!(None, IsisPduSlice::header_len_of(19));
+    }
+
+    #[test]
+    fn lan_hello() {
+        let data = super::lan_hello();
+        let isis = IsisPduSlice::from_slice(&data).unwrap();
+        assert_eq!(&data[..35], isis.slice());
+        assert_eq!(isis_pdu_type::L1_LAN_HELLO, isis.pdu_type());
+        assert_eq!(27, isis.header_len());
+        assert_eq!(35, isis.pdu_len());
+        assert_eq!(6, isis.id_len());
+        assert_eq!(3, isis.max_area_addresses());
+        assert!(isis.is_hello());
+        assert!(!isis.is_lsp());
+        assert!(!isis.is_snp());
+        assert_eq!([1, 2, 3, 4, 5, 6], isis.source_id());
+        assert_eq!(Some(30), isis.holding_time());
+        assert_eq!(None, isis.remaining_lifetime());
+        assert_eq!(None, isis.lsp_id());
+        assert_eq!(None, isis.sequence_number());
+        assert_eq!(&data[8..27], isis.pdu_header());
+        assert_eq!(
+            vec![
+                IsisTlv{ tlv_type: 1, value: &[3, 0x49, 0, 1] },
+                IsisTlv{ tlv_type: 8, value: &[] },
+            ],
+            isis.tlvs().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn lsp() {
+        let isis = IsisPduSlice::from_slice(&L2_LSP).unwrap();
+        assert_eq!(isis_pdu_type::L2_LSP, isis.pdu_type());
+        assert!(isis.is_lsp());
+        assert_eq!([1, 2, 3, 4, 5, 6], isis.source_id());
+        assert_eq!(None, isis.holding_time());
+        assert_eq!(Some(1200), isis.remaining_lifetime());
+        assert_eq!(Some([1, 2, 3, 4, 5, 6, 0, 1]), isis.lsp_id());
+        assert_eq!(Some(42), isis.sequence_number());
+        assert_eq!(None, isis.tlvs().next());
+    }
+
+    #[test]
+    fn snp() {
+        let mut data = vec![
+            0x83, 33, 1, 6, isis_pdu_type::L2_CSNP, 1, 0, 2,
+            0, 49, // pdu length
+            9, 8, 7, 6, 5, 4, 0, // source id
+            0, 0, 0, 0, 0, 0, 0, 0, // start lsp id
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // end lsp id
+        ];
+        // lsp entries TLV
+        data.extend_from_slice(&[9, 14]);
+        data.extend_from_slice(&[0;14]);
+        let isis = IsisPduSlice::from_slice(&data).unwrap();
+        assert!(isis.is_snp());
+        assert_eq!(6, isis.id_len());
+        assert_eq!(2, isis.max_area_addresses());
+        assert_eq!([9, 8, 7, 6, 5, 4], isis.source_id());
+        assert_eq!(None, isis.lsp_id());
+        assert_eq!(1, isis.tlvs().count());
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        use IsisError::*;
+        let data = super::lan_hello();
+
+        for len in 0..8 {
+            assert_eq!(Err(UnexpectedEndOfSlice(8)), IsisPduSlice::from_slice(&data[..len]));
+        }
+        for len in 8..27 {
+            assert_eq!(Err(UnexpectedEndOfSlice(27)), IsisPduSlice::from_slice(&data[..len]));
+        }
+        for len in 27..35 {
+            assert_eq!(Err(UnexpectedEndOfSlice(35)), IsisPduSlice::from_slice(&data[..len]));
+        }
+        // protocol discriminator
+        {
+            let mut data = data.clone();
+            data[0] = 0x82;
+            assert_eq!(Err(InvalidProtocolDiscriminator(0x82)), IsisPduSlice::from_slice(&data));
+        }
+        // pdu type
+        {
+            let mut data = data.clone();
+            data[4] = 0b1110_0011;
+            assert_eq!(Err(UnsupportedPduType(3)), IsisPduSlice::from_slice(&data));
+        }
+        // length indicator
+        {
+            let mut data = data.clone();
+            data[1] = 20;
+            assert_eq!(
+                Err(InvalidHeaderLength{ pdu_type: isis_pdu_type::L1_LAN_HELLO, header_len: 20 }),
+                IsisPduSlice::from_slice(&data)
+            );
+        }
+        // pdu length
+        {
+            let mut data = data.clone();
+            data[18] = 26;
+            assert_eq!(Err(InvalidPduLength(26)), IsisPduSlice::from_slice(&data));
+        }
+        // tlv longer then the pdu
+        {
+            let mut data = data.clone();
+            data[18] = 32;
+            assert_eq!(Err(InvalidTlvLength(27)), IsisPduSlice::from_slice(&data));
+            data[18] = 28;
+            assert_eq!(Err(InvalidTlvLength(27)), IsisPduSlice::from_slice(&data));
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn linux_sll_any()(
+        packet_type in any::<u16>(),
+        arp_hardware_type in any::<u16>(),
+        address_len in any::<u16>(),
+        address in prop::array::uniform8(any::<u8>()),
+        protocol_type in any::<u16>(),
+    ) -> LinuxSllHeader {
+        LinuxSllHeader {
+            packet_type,
+            arp_hardware_type,
+            address_len,
+            address,
+            protocol_type,
+        }
+    }
+}
+
+prop_compose! {
+    fn linux_sll2_any()(
+        protocol_type in any::<u16>(),
+        reserved in any::<u16>(),
+        interface_index in any::<u32>(),
+        arp_hardware_type in any::<u16>(),
+        packet_type in any::<u8>(),
+        address_len in any::<u8>(),
+        address in prop::array::uniform8(any::<u8>()),
+    ) -> LinuxSll2Header {
+        LinuxSll2Header {
+            protocol_type,
+            reserved,
+            interface_index,
+            arp_hardware_type,
+            packet_type,
+            address_len,
+            address,
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn sll_write_read(ref header in linux_sll_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(LinuxSllHeader::SERIALIZED_SIZE, header.header_len());
+        assert_eq!(&header.to_bytes()[..], &buffer[..LinuxSllHeader::SERIALIZED_SIZE]);
+
+        // from_slice
+        {
+            let (decoded, rest) = LinuxSllHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &LinuxSllHeader::read(&mut cursor).unwrap());
+            assert_eq!(16, cursor.position());
+        }
+        // slice
+        {
+            let slice = LinuxSllHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..16], slice.slice());
+            assert_eq!(header.packet_type, slice.packet_type());
+            assert_eq!(header.arp_hardware_type, slice.arp_hardware_type());
+            assert_eq!(header.address_len, slice.address_len());
+            assert_eq!(header.address, slice.address());
+            assert_eq!(header.link_address(), slice.link_address());
+            assert_eq!(header.protocol_type, slice.protocol_type());
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn sll2_write_read(ref header in linux_sll2_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(LinuxSll2Header::SERIALIZED_SIZE, header.header_len());
+        assert_eq!(&header.to_bytes()[..], &buffer[..LinuxSll2Header::SERIALIZED_SIZE]);
+
+        // from_slice
+        {
+            let (decoded, rest) = LinuxSll2Header::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &LinuxSll2Header::read(&mut cursor).unwrap());
+            assert_eq!(20, cursor.position());
+        }
+        // slice
+        {
+            let slice = LinuxSll2HeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..20], slice.slice());
+            assert_eq!(header.protocol_type, slice.protocol_type());
+            assert_eq!(header.reserved, slice.reserved());
+            assert_eq!(header.interface_index, slice.interface_index());
+            assert_eq!(header.arp_hardware_type, slice.arp_hardware_type());
+            assert_eq!(header.packet_type, slice.packet_type());
+            assert_eq!(header.address_len, slice.address_len());
+            assert_eq!(header.address, slice.address());
+            assert_eq!(header.link_address(), slice.link_address());
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+}
+
+#[test]
+fn link_address() {
+    let mut header = LinuxSllHeader {
+        address_len: 6,
+        address: [1, 2, 3, 4, 5, 6, 7, 8],
+        ..Default::default()
+    };
+    assert_eq!(&[1, 2, 3, 4, 5, 6], header.link_address());
+    // addresses longer then 8 bytes are cut off
+    header.address_len = 20;
+    assert_eq!(&[1, 2, 3, 4, 5, 6, 7, 8], header.link_address());
+    header.address_len = 0;
+    assert!(header.link_address().is_empty());
+}
+
+#[test]
+fn from_slice_errors() {
+    let sll = LinuxSllHeader::default().to_bytes();
+    for len in 0..sll.len() {
+        assert_matches!(
+            LinuxSllHeaderSlice::from_slice(&sll[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(16))
+        );
+        assert_matches!(
+            LinuxSllHeader::from_slice(&sll[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(16))
+        );
+        assert!(LinuxSllHeader::read(&mut Cursor::new(&sll[..len])).is_err());
+    }
+    let sll2 = LinuxSll2Header::default().to_bytes();
+    for len in 0..sll2.len() {
+        assert_matches!(
+            LinuxSll2HeaderSlice::from_slice(&sll2[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(20))
+        );
+        assert_matches!(
+            LinuxSll2Header::from_slice(&sll2[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(20))
+        );
+        assert!(LinuxSll2Header::read(&mut Cursor::new(&sll2[..len])).is_err());
+    }
+}
+
+proptest! {
+    #[test]
+    fn cooked_header(ref sll in linux_sll_any(), ref sll2 in linux_sll2_any()) {
+        // sll
+        {
+            let mut header = LinuxCookedHeader::Sll(sll.clone());
+            assert_eq!(sll.protocol_type, header.protocol_type());
+            assert_eq!(sll.packet_type, header.packet_type());
+            assert_eq!(None, header.interface_index());
+            assert_eq!(sll.link_address(), header.link_address());
+            assert_eq!(16, header.header_len());
+
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&sll.to_bytes()[..], &buffer[..]);
+
+            let slice = LinuxCookedSlice::Sll(LinuxSllHeaderSlice::from_slice(&buffer).unwrap());
+            assert_eq!(&buffer[..], slice.slice());
+            assert_eq!(sll.protocol_type, slice.protocol_type());
+            assert_eq!(sll.packet_type, slice.packet_type());
+            assert_eq!(None, slice.interface_index());
+            assert_eq!(sll.link_address(), slice.link_address());
+            assert_eq!(header, slice.to_header());
+
+            header.set_protocol_type(ether_type::ARP);
+            assert_eq!(ether_type::ARP, header.protocol_type());
+        }
+        // sll2
+        {
+            let mut header = LinuxCookedHeader::Sll2(sll2.clone());
+            assert_eq!(sll2.protocol_type, header.protocol_type());
+            assert_eq!(u16::from(sll2.packet_type), header.packet_type());
+            assert_eq!(Some(sll2.interface_index), header.interface_index());
+            assert_eq!(sll2.link_address(), header.link_address());
+            assert_eq!(20, header.header_len());
+
+            let mut buffer = Vec::new();
+            header.write(&mut buffer).unwrap();
+            assert_eq!(&sll2.to_bytes()[..], &buffer[..]);
+
+            let slice = LinuxCookedSlice::Sll2(LinuxSll2HeaderSlice::from_slice(&buffer).unwrap());
+            assert_eq!(&buffer[..], slice.slice());
+            assert_eq!(sll2.protocol_type, slice.protocol_type());
+            assert_eq!(u16::from(sll2.packet_type), slice.packet_type());
+            assert_eq!(Some(sll2.interface_index), slice.interface_index());
+            assert_eq!(sll2.link_address(), slice.link_address());
+            assert_eq!(header, slice.to_header());
+
+            header.set_protocol_type(ether_type::ARP);
+            assert_eq!(ether_type::ARP, header.protocol_type());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn debug_clone_eq(ref sll in linux_sll_any()) {
+        let bytes = sll.to_bytes();
+        let slice = LinuxSllHeaderSlice::from_slice(&bytes).unwrap();
+        let cooked = LinuxCookedSlice::Sll(slice.clone());
+        assert_eq!(cooked.clone(), cooked);
+        assert_eq!(
+            format!("{:?}", cooked),
+            format!("Sll({:?})", slice)
+        );
+        let header = LinuxCookedHeader::Sll(sll.clone());
+        assert_eq!(header.clone(), header);
+        assert_eq!(
+            format!("{:?}", header),
+            format!("Sll({:?})", sll)
+        );
+    }
+}
+
+/// packet with a linux cooked capture header & the given payload
+fn sll_packet(protocol_type: u16, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    LinuxSllHeader {
+        packet_type: linux_sll_packet_type::OUTGOING,
+        arp_hardware_type: arp_hardware_type::ETHERNET,
+        address_len: 6,
+        address: [1, 2, 3, 4, 5, 6, 0, 0],
+        protocol_type,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet
+}
+
+/// packet with a linux cooked capture v2 header & the given payload
+fn sll2_packet(protocol_type: u16, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    LinuxSll2Header {
+        protocol_type,
+        interface_index: 2,
+        arp_hardware_type: arp_hardware_type::ETHERNET,
+        packet_type: linux_sll_packet_type::HOST as u8,
+        address_len: 6,
+        address: [1, 2, 3, 4, 5, 6, 0, 0],
+        ..Default::default()
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet
+}
+
+#[test]
+fn sliced_packet_ip() {
+    let mut ipv4 = Vec::new();
+    PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut ipv4, &[1, 2, 3, 4])
+        .unwrap();
+    let mut ipv6 = Vec::new();
+    PacketBuilder::ipv6([1; 16], [2; 16], 20)
+        .tcp(1234, 5678, 1, 1024)
+        .write(&mut ipv6, &[1, 2, 3, 4])
+        .unwrap();
+
+    for (protocol_type, ip) in [(ether_type::IPV4, &ipv4), (ether_type::IPV6, &ipv6)] {
+        // sll
+        {
+            let packet = sll_packet(protocol_type, ip);
+
+            let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+            let sll = sliced.linux_sll.as_ref().unwrap();
+            assert_matches!(sll, LinuxCookedSlice::Sll(_));
+            assert_eq!(protocol_type, sll.protocol_type());
+            assert_eq!(linux_sll_packet_type::OUTGOING, sll.packet_type());
+            assert_eq!(&[1, 2, 3, 4, 5, 6], sll.link_address());
+            assert!(sliced.link.is_none());
+            assert!(sliced.ip.is_some());
+            assert!(sliced.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], sliced.payload);
+            assert_eq!(None, sliced.payload_ether_type());
+
+            let decoded = PacketHeaders::from_linux_sll_slice(&packet).unwrap();
+            assert_eq!(Some(sll.to_header()), decoded.linux_sll);
+            assert!(decoded.link.is_none());
+            assert!(decoded.ip.is_some());
+            assert!(decoded.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], decoded.payload);
+        }
+        // sll2
+        {
+            let packet = sll2_packet(protocol_type, ip);
+
+            let sliced = SlicedPacket::from_linux_sll2(&packet).unwrap();
+            let sll = sliced.linux_sll.as_ref().unwrap();
+            assert_matches!(sll, LinuxCookedSlice::Sll2(_));
+            assert_eq!(protocol_type, sll.protocol_type());
+            assert_eq!(Some(2), sll.interface_index());
+            assert!(sliced.ip.is_some());
+            assert!(sliced.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+            let decoded = PacketHeaders::from_linux_sll2_slice(&packet).unwrap();
+            assert_eq!(Some(sll.to_header()), decoded.linux_sll);
+            assert!(decoded.ip.is_some());
+            assert!(decoded.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], decoded.payload);
+        }
+    }
+}
+
+#[test]
+fn sliced_packet_vlan() {
+    let mut payload = Vec::new();
+    SingleVlanHeader {
+        vlan_identifier: 10,
+        ether_type: 0x1234,
+        ..Default::default()
+    }.write(&mut payload).unwrap();
+    payload.extend_from_slice(&[1, 2, 3, 4]);
+    let packet = sll_packet(ether_type::VLAN_TAGGED_FRAME, &payload);
+
+    let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+    assert!(sliced.vlan.is_some());
+    assert_eq!(Some(0x1234), sliced.payload_ether_type());
+    assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+    let decoded = PacketHeaders::from_linux_sll_slice(&packet).unwrap();
+    assert!(decoded.vlan.is_some());
+    assert_eq!(Some(0x1234), decoded.payload_ether_type());
+    assert_eq!(&[1, 2, 3, 4], decoded.payload);
+}
+
+#[test]
+fn sliced_packet_unknown_protocol() {
+    // 802.2 LLC frame
+    let packet = sll_packet(0x0004, &[1, 2, 3, 4]);
+
+    let sliced = SlicedPacket::from_linux_sll(&packet).unwrap();
+    assert!(sliced.linux_sll.is_some());
+    assert!(sliced.ip.is_none());
+    assert_eq!(Some(0x0004), sliced.payload_ether_type());
+    assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+    let decoded = PacketHeaders::from_linux_sll_slice(&packet).unwrap();
+    assert!(decoded.linux_sll.is_some());
+    assert_eq!(Some(0x0004), decoded.payload_ether_type());
+    assert_eq!(&[1, 2, 3, 4], decoded.payload);
+}
+
+#[test]
+fn sliced_packet_errors() {
+    // header too short
+    let packet = sll_packet(ether_type::IPV4, &[]);
+    assert_matches!(
+        SlicedPacket::from_linux_sll(&packet[..15]),
+        Err(ReadError::UnexpectedEndOfSlice(16))
+    );
+    assert_matches!(
+        PacketHeaders::from_linux_sll_slice(&packet[..15]),
+        Err(ReadError::UnexpectedEndOfSlice(16))
+    );
+    let packet = sll2_packet(ether_type::IPV4, &[]);
+    assert_matches!(
+        SlicedPacket::from_linux_sll2(&packet[..19]),
+        Err(ReadError::UnexpectedEndOfSlice(20))
+    );
+    assert_matches!(
+        PacketHeaders::from_linux_sll2_slice(&packet[..19]),
+        Err(ReadError::UnexpectedEndOfSlice(20))
+    );
+
+    // offsets of errors in the following headers are relative to the
+    // start of the packet
+    let packet = sll_packet(ether_type::VLAN_TAGGED_FRAME, &[0]);
+    assert_matches!(
+        SlicedPacket::from_linux_sll(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(20))
+    );
+    let packet = sll2_packet(ether_type::VLAN_TAGGED_FRAME, &[0]);
+    assert_matches!(
+        SlicedPacket::from_linux_sll2(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(24))
+    );
+    assert_matches!(
+        PacketHeaders::from_linux_sll2_slice(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(4))
+    );
+}
+
+#[test]
+fn repair() {
+    let mut headers = PacketHeaders {
+        linux_sll: Some(LinuxCookedHeader::Sll2(LinuxSll2Header::default())),
+        ip: Some(IpHeader::Version4(
+            Ipv4Header::new(0, 20, 0, [192, 168, 1, 1], [192, 168, 1, 2]),
+            Default::default()
+        )),
+        transport: Some(TransportHeader::Udp(UdpHeader::default())),
+        payload: &[1, 2, 3, 4],
+        ..Default::default()
+    };
+    etherparse::repair::fix(&mut headers).unwrap();
+    let packet = etherparse::repair::to_bytes(&headers).unwrap();
+
+    let decoded = PacketHeaders::from_linux_sll2_slice(&packet).unwrap();
+    assert_eq!(ether_type::IPV4, decoded.linux_sll.unwrap().protocol_type());
+    assert_eq!(12, decoded.transport.unwrap().udp().unwrap().length);
+    assert_eq!(&[1, 2, 3, 4], decoded.payload);
+}
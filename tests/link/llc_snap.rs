@@ -0,0 +1,48 @@
+use super::super::*;
+
+proptest! {
+    #[test]
+    fn write_from_slice(oui in any::<[u8;3]>(), protocol_id in any::<u16>()) {
+        let header = LlcSnapHeader{ oui, protocol_id };
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(LlcSnapHeader::SERIALIZED_SIZE, header.header_len());
+        assert_eq!(&header.to_bytes()[..], &buffer[..8]);
+        assert!(LlcSnapHeader::is_snap(&buffer));
+
+        let (decoded, rest) = LlcSnapHeader::from_slice(&buffer).unwrap();
+        assert_eq!(header, decoded);
+        assert_eq!(&[1, 2], rest);
+    }
+}
+
+#[test]
+fn ether_type() {
+    assert_eq!(Some(ether_type::ARP), LlcSnapHeader::with_ether_type(ether_type::ARP).ether_type());
+    assert_eq!(
+        Some(ether_type::IPV4),
+        LlcSnapHeader{ oui: LlcSnapHeader::OUI_BRIDGE_TUNNEL, protocol_id: ether_type::IPV4 }.ether_type()
+    );
+    // cisco
+    assert_eq!(None, LlcSnapHeader{ oui: [0, 0, 0x0c], protocol_id: 0x2000 }.ether_type());
+}
+
+#[test]
+fn from_slice_errors() {
+    let bytes = LlcSnapHeader::with_ether_type(ether_type::IPV4).to_bytes();
+    for len in 0..bytes.len() {
+        assert_matches!(
+            LlcSnapHeader::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(8))
+        );
+    }
+
+    // IS-IS over 802.3
+    assert_matches!(
+        LlcSnapHeader::from_slice(&[0xfe, 0xfe, 0x03, 0, 0, 0, 0, 0]),
+        Err(ReadError::LlcNotSnap{ dsap: 0xfe, ssap: 0xfe, control: 0x03 })
+    );
+    assert!(!LlcSnapHeader::is_snap(&[0xfe, 0xfe, 0x03]));
+    assert!(!LlcSnapHeader::is_snap(&[0xaa, 0xaa]));
+}
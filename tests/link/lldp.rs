@@ -0,0 +1,190 @@
+use super::super::*;
+
+/// LLDPDU with the mandatory TLVs, a system name, system description,
+/// capabilities & an org specific TLV followed by the end TLV & padding.
+fn lldpdu() -> Vec<u8> {
+    let mut result = vec![
+        0x02, 0x07, lldp_chassis_id_subtype::MAC_ADDRESS, 1, 2, 3, 4, 5, 6, // chassis id
+        0x04, 0x05, lldp_port_id_subtype::INTERFACE_NAME, b'e', b't', b'h', b'0', // port id
+        0x06, 0x02, 0x00, 0x78, // ttl
+        0x08, 0x02, b'u', b'p', // port description
+        0x0a, 0x02, b's', b'w', // system name
+        0x0c, 0x03, b'o', b's', b'1', // system description
+        0x0e, 0x04, 0x00, 0x14, 0x00, 0x04, // system capabilities
+        0xfe, 0x06, 0x00, 0x80, 0xc2, 0x01, 0x00, 0x0a, // org specific (port vlan id)
+        0x24, 0x01, 0xff, // reserved type 18
+        0x00, 0x00, // end
+    ];
+    // ethernet padding
+    result.extend_from_slice(&[0;4]);
+    result
+}
+
+mod lldp_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        use LldpError::*;
+        assert_eq!(
+            "LldpError: Unexpected end of slice. The given slice contained less then minimum required 12 bytes.",
+            format!("{}", UnexpectedEndOfSlice(12))
+        );
+        assert_eq!(
+            "LldpError: The TLV at offset 9 has an invalid length.",
+            format!("{}", InvalidTlvLength(9))
+        );
+        assert_eq!(
+            "LldpError: Missing mandatory TLV of type 2.",
+            format!("{}", MissingMandatoryTlv(2))
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(LldpError::MissingMandatoryTlv(1).source().is_none());
+    }
+}
+
+mod lldp_tlv {
+    use super::*;
+
+    #[test]
+    fn min_value_len_of() {
+        use lldp_tlv_type::*;
+        for (tlv_type, expected) in [
+            (END, 0), (CHASSIS_ID, 2), (PORT_ID, 2), (TIME_TO_LIVE, 2),
+            (PORT_DESCRIPTION, 0), (SYSTEM_NAME, 0), (SYSTEM_DESCRIPTION, 0),
+            (SYSTEM_CAPABILITIES, 4), (MANAGEMENT_ADDRESS, 9),
+            (ORGANIZATION_SPECIFIC, 4), (100, 0),
+        ] {
+            assert_eq!(expected, LldpTlv::min_value_len_of(tlv_type));
+        }
+    }
+
+    #[test]
+    fn tlv_type() {
+        use lldp_tlv_type::*;
+        use LldpTlv::*;
+        for (tlv, expected) in [
+            (ChassisId{ subtype: 1, id: &[1] }, CHASSIS_ID),
+            (PortId{ subtype: 1, id: &[1] }, PORT_ID),
+            (TimeToLive(1), TIME_TO_LIVE),
+            (PortDescription(&[]), PORT_DESCRIPTION),
+            (SystemName(&[]), SYSTEM_NAME),
+            (SystemDescription(&[]), SYSTEM_DESCRIPTION),
+            (SystemCapabilities{ capabilities: 0, enabled: 0 }, SYSTEM_CAPABILITIES),
+            (ManagementAddress(&[]), MANAGEMENT_ADDRESS),
+            (OrganizationSpecific{ oui: [0;3], subtype: 0, info: &[] }, ORGANIZATION_SPECIFIC),
+            (Unknown{ tlv_type: 20, value: &[] }, 20),
+        ] {
+            assert_eq!(expected, tlv.tlv_type());
+        }
+    }
+}
+
+mod lldp_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        use LldpTlv::*;
+        let data = lldpdu();
+        let lldp = LldpSlice::from_slice(&data).unwrap();
+        assert_eq!(&data[..data.len() - 4], lldp.slice());
+        assert_eq!((lldp_chassis_id_subtype::MAC_ADDRESS, &[1, 2, 3, 4, 5, 6][..]), lldp.chassis_id());
+        assert_eq!((lldp_port_id_subtype::INTERFACE_NAME, &b"eth0"[..]), lldp.port_id());
+        assert_eq!(120, lldp.ttl());
+        assert_eq!(Some(&b"up"[..]), lldp.port_description());
+        assert_eq!(Some(&b"sw"[..]), lldp.system_name());
+        assert_eq!(Some(&b"os1"[..]), lldp.system_description());
+        assert_eq!(
+            vec![
+                ChassisId{ subtype: lldp_chassis_id_subtype::MAC_ADDRESS, id: &[1, 2, 3, 4, 5, 6] },
+                PortId{ subtype: lldp_port_id_subtype::INTERFACE_NAME, id: b"eth0" },
+                TimeToLive(120),
+                PortDescription(b"up"),
+                SystemName(b"sw"),
+                SystemDescription(b"os1"),
+                SystemCapabilities{ capabilities: 0x14, enabled: 0x04 },
+                OrganizationSpecific{ oui: [0x00, 0x80, 0xc2], subtype: 1, info: &[0x00, 0x0a] },
+                Unknown{ tlv_type: 18, value: &[0xff] },
+            ],
+            lldp.tlvs().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn from_slice_without_end() {
+        let data = lldpdu();
+        // only the mandatory tlvs
+        let lldp = LldpSlice::from_slice(&data[..20]).unwrap();
+        assert_eq!(&data[..20], lldp.slice());
+        assert_eq!(120, lldp.ttl());
+        assert_eq!(None, lldp.port_description());
+        assert_eq!(None, lldp.system_name());
+        assert_eq!(None, lldp.system_description());
+        assert_eq!(3, lldp.tlvs().count());
+    }
+
+    #[test]
+    fn management_address() {
+        let mut data = lldpdu();
+        data.truncate(20);
+        data.extend_from_slice(&[
+            0x10, 0x0c, 5, 1, 192, 168, 0, 1, 2, 0, 0, 0, 3, 0
+        ]);
+        let lldp = LldpSlice::from_slice(&data).unwrap();
+        assert_eq!(
+            Some(LldpTlv::ManagementAddress(&[5, 1, 192, 168, 0, 1, 2, 0, 0, 0, 3, 0])),
+            lldp.tlvs().nth(3)
+        );
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        use LldpError::*;
+        let data = lldpdu();
+
+        for len in 0..12 {
+            assert_eq!(Err(UnexpectedEndOfSlice(12)), LldpSlice::from_slice(&data[..len]));
+        }
+        for len in 12..20 {
+            assert!(LldpSlice::from_slice(&data[..len]).is_err());
+        }
+        // tlv extends past the end of the slice
+        assert_eq!(Err(InvalidTlvLength(20)), LldpSlice::from_slice(&data[..23]));
+        assert_eq!(Err(InvalidTlvLength(20)), LldpSlice::from_slice(&data[..21]));
+        // mandatory tlvs missing or in the wrong order
+        for (offset, expected) in [
+            (0, lldp_tlv_type::CHASSIS_ID),
+            (9, lldp_tlv_type::PORT_ID),
+            (16, lldp_tlv_type::TIME_TO_LIVE),
+        ] {
+            let mut data = data.clone();
+            data[offset] = 0x0a;
+            assert_eq!(Err(MissingMandatoryTlv(expected)), LldpSlice::from_slice(&data));
+        }
+        // end before the ttl
+        {
+            let mut data = data.clone();
+            data[16] = 0;
+            data[17] = 0;
+            assert_eq!(Err(MissingMandatoryTlv(lldp_tlv_type::TIME_TO_LIVE)), LldpSlice::from_slice(&data));
+        }
+        // value shorter then the minimum length
+        {
+            let mut data = data.clone();
+            data[17] = 1;
+            assert_eq!(Err(InvalidTlvLength(16)), LldpSlice::from_slice(&data));
+        }
+        // end tlv with a non zero length
+        {
+            let mut data = data.clone();
+            let end = data.len() - 5;
+            data[end] = 1;
+            assert_eq!(Err(InvalidTlvLength(end - 1)), LldpSlice::from_slice(&data));
+        }
+    }
+}
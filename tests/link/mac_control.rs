@@ -0,0 +1,158 @@
+use super::super::*;
+
+use std::time::Duration;
+
+mod mac_control_frame {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(0x0001, mac_control_opcode::PAUSE);
+        assert_eq!(0x0101, mac_control_opcode::PRIORITY_FLOW_CONTROL);
+        assert_eq!(46, MacControlFrame::SERIALIZED_SIZE);
+        assert_eq!([0x01, 0x80, 0xc2, 0, 0, 1], MacControlFrame::DESTINATION);
+    }
+
+    proptest! {
+        #[test]
+        fn pause(pause_time in any::<u16>()) {
+            let frame = MacControlFrame::Pause{ pause_time };
+            let bytes = frame.to_bytes();
+            assert_eq!(&[0, 1], &bytes[..2]);
+            assert_eq!(&pause_time.to_be_bytes(), &bytes[2..4]);
+            assert_eq!(&[0u8;42], &bytes[4..]);
+
+            // decode (with & without padding)
+            assert_eq!(frame, MacControlFrame::from_slice(&bytes).unwrap());
+            assert_eq!(frame, MacControlFrame::from_slice(&bytes[..4]).unwrap());
+
+            // write
+            let mut buffer = Vec::new();
+            frame.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..], &buffer[..]);
+
+            // pause time applies to all priorities
+            assert_eq!(mac_control_opcode::PAUSE, frame.opcode());
+            for priority in 0..8 {
+                assert_eq!(Some(pause_time), frame.pause_time(priority));
+            }
+            assert_eq!(None, frame.pause_time(8));
+
+            // length error
+            for len in 2..4 {
+                assert_matches!(
+                    MacControlFrame::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(4))
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn priority_flow_control(
+            priority_enable in any::<u8>(),
+            pause_times in any::<[u16;8]>(),
+        ) {
+            let frame = MacControlFrame::PriorityFlowControl{ priority_enable, pause_times };
+            let bytes = frame.to_bytes();
+            assert_eq!(&[1, 1, 0, priority_enable], &bytes[..4]);
+            for (priority, value) in pause_times.iter().enumerate() {
+                assert_eq!(&value.to_be_bytes(), &bytes[4 + priority*2..6 + priority*2]);
+            }
+            assert_eq!(&[0u8;26], &bytes[20..]);
+
+            // decode (with & without padding)
+            assert_eq!(frame, MacControlFrame::from_slice(&bytes).unwrap());
+            assert_eq!(frame, MacControlFrame::from_slice(&bytes[..20]).unwrap());
+
+            // write
+            let mut buffer = Vec::new();
+            frame.write(&mut buffer).unwrap();
+            assert_eq!(&bytes[..], &buffer[..]);
+
+            // pause times of the enabled priorities
+            assert_eq!(mac_control_opcode::PRIORITY_FLOW_CONTROL, frame.opcode());
+            for priority in 0..8u8 {
+                assert_eq!(
+                    if 0 != priority_enable & (1 << priority) {
+                        Some(pause_times[usize::from(priority)])
+                    } else {
+                        None
+                    },
+                    frame.pause_time(priority)
+                );
+            }
+            assert_eq!(None, frame.pause_time(8));
+
+            // length error
+            for len in 2..20 {
+                assert_matches!(
+                    MacControlFrame::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(20))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unknown() {
+        let frame = MacControlFrame::Unknown{ opcode: 0x0002 };
+        let bytes = frame.to_bytes();
+        assert_eq!(&[0, 2], &bytes[..2]);
+        assert_eq!(&[0u8;44], &bytes[2..]);
+        assert_eq!(frame, MacControlFrame::from_slice(&bytes[..2]).unwrap());
+        assert_eq!(0x0002, frame.opcode());
+        assert_eq!(None, frame.pause_time(0));
+    }
+
+    #[test]
+    fn from_slice_opcode_len_error() {
+        for len in 0..2 {
+            assert_matches!(
+                MacControlFrame::from_slice(&[0u8;2][..len]),
+                Err(ReadError::UnexpectedEndOfSlice(2))
+            );
+        }
+    }
+
+    #[test]
+    fn ethernet2_header() {
+        let frame = MacControlFrame::Pause{ pause_time: 1 };
+        assert_eq!(
+            Ethernet2Header {
+                source: [1,2,3,4,5,6],
+                destination: [0x01, 0x80, 0xc2, 0, 0, 1],
+                ether_type: 0x8808,
+            },
+            frame.ethernet2_header([1,2,3,4,5,6])
+        );
+    }
+
+    #[test]
+    fn write_error() {
+        let mut buffer = [0u8;45];
+        let mut cursor = std::io::Cursor::new(&mut buffer[..]);
+        assert_matches!(
+            MacControlFrame::Pause{ pause_time: 1 }.write(&mut cursor),
+            Err(WriteError::IoError(_))
+        );
+    }
+
+    #[test]
+    fn quanta_to_duration() {
+        assert_eq!(Duration::ZERO, MacControlFrame::quanta_to_duration(0, 1_000_000_000));
+        assert_eq!(Duration::from_nanos(512), MacControlFrame::quanta_to_duration(1, 1_000_000_000));
+        assert_eq!(
+            Duration::from_nanos(3_355_392),
+            MacControlFrame::quanta_to_duration(0xffff, 10_000_000_000)
+        );
+    }
+
+    #[test]
+    fn clone_eq_debug() {
+        let value = MacControlFrame::Pause{ pause_time: 1 };
+        assert_eq!(value, value.clone());
+        assert_eq!("Pause { pause_time: 1 }", format!("{:?}", value));
+    }
+}
@@ -0,0 +1,476 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn macsec_any()(
+        end_station in any::<bool>(),
+        single_copy_broadcast in any::<bool>(),
+        encrypted in any::<bool>(),
+        changed in any::<bool>(),
+        association_number in 0..=MacsecHeader::MAX_ASSOCIATION_NUMBER,
+        short_length in 0..=MacsecHeader::MAX_SHORT_LENGTH,
+        packet_number in any::<u32>(),
+        sci in proptest::option::of(any::<u64>()),
+        ether_type in any::<u16>(),
+    ) -> MacsecHeader {
+        MacsecHeader {
+            end_station,
+            single_copy_broadcast,
+            encrypted,
+            changed,
+            association_number,
+            short_length,
+            packet_number,
+            sci,
+            ether_type: if encrypted || changed {
+                None
+            } else {
+                Some(ether_type)
+            },
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in macsec_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(header.header_len() + 2, buffer.len());
+        assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..header.header_len()]);
+
+        // from_slice
+        {
+            let (decoded, rest) = MacsecHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &MacsecHeader::read(&mut cursor).unwrap());
+            assert_eq!(header.header_len() as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = MacsecHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(header.end_station, slice.end_station());
+            assert_eq!(header.sci.is_some(), slice.sci_present());
+            assert_eq!(header.single_copy_broadcast, slice.single_copy_broadcast());
+            assert_eq!(header.encrypted, slice.encrypted());
+            assert_eq!(header.changed, slice.changed());
+            assert_eq!(header.association_number, slice.association_number());
+            assert_eq!(header.short_length, slice.short_length());
+            assert_eq!(header.packet_number, slice.packet_number());
+            assert_eq!(header.sci, slice.sci());
+            assert_eq!(header.ether_type, slice.ether_type());
+            assert_eq!(header, &slice.to_header());
+        }
+        // length errors
+        for len in 0..header.header_len() {
+            let expected = if len < MacsecHeader::MIN_LEN { MacsecHeader::MIN_LEN } else { header.header_len() };
+            assert_matches!(
+                MacsecHeaderSlice::from_slice(&buffer[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(l)) if l == expected
+            );
+            assert_matches!(
+                MacsecHeader::read(&mut Cursor::new(&buffer[..len])),
+                Err(ReadError::IoError(_))
+            );
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn value_errors(value in (MacsecHeader::MAX_ASSOCIATION_NUMBER + 1)..=u8::MAX) {
+        // association number
+        {
+            let mut header = MacsecHeader::new(1, None);
+            header.association_number = value;
+            let expected = ValueError::U8TooLarge{
+                value,
+                max: MacsecHeader::MAX_ASSOCIATION_NUMBER,
+                field: ErrorField::MacsecAssociationNumber
+            };
+            assert_eq!(Some(expected.clone()), header.to_bytes().err());
+            assert_eq!(Some(expected), header.write(&mut Vec::new()).unwrap_err().value_error());
+        }
+        // short length
+        if MacsecHeader::MAX_SHORT_LENGTH < value {
+            let mut header = MacsecHeader::new(1, None);
+            header.short_length = value;
+            let expected = ValueError::U8TooLarge{
+                value,
+                max: MacsecHeader::MAX_SHORT_LENGTH,
+                field: ErrorField::MacsecShortLength
+            };
+            assert_eq!(Some(expected), header.to_bytes().err());
+        }
+    }
+}
+
+#[test]
+fn new() {
+    assert_eq!(
+        MacsecHeader {
+            end_station: false,
+            single_copy_broadcast: false,
+            encrypted: false,
+            changed: false,
+            association_number: 0,
+            short_length: 0,
+            packet_number: 1,
+            sci: None,
+            ether_type: Some(ether_type::IPV4),
+        },
+        MacsecHeader::new(1, Some(ether_type::IPV4))
+    );
+    assert_eq!(
+        MacsecHeader {
+            end_station: false,
+            single_copy_broadcast: false,
+            encrypted: true,
+            changed: true,
+            association_number: 0,
+            short_length: 0,
+            packet_number: 2,
+            sci: None,
+            ether_type: None,
+        },
+        MacsecHeader::new(2, None)
+    );
+    assert_eq!(8, MacsecHeader::new(1, Some(ether_type::IPV4)).header_len());
+    assert_eq!(6, MacsecHeader::new(1, None).header_len());
+    assert_eq!(
+        MacsecHeader::MAX_LEN,
+        MacsecHeader {
+            sci: Some(1),
+            ..MacsecHeader::new(1, Some(ether_type::IPV4))
+        }.header_len()
+    );
+}
+
+#[test]
+fn from_slice_errors() {
+    let bytes = MacsecHeader::new(1, Some(ether_type::IPV4)).to_bytes().unwrap();
+
+    // version bit
+    let mut bytes = bytes.clone();
+    bytes[0] |= MacsecHeader::TCI_VERSION;
+    assert_matches!(
+        MacsecHeaderSlice::from_slice(&bytes),
+        Err(ReadError::MacsecUnsupportedVersion)
+    );
+    assert_matches!(
+        MacsecHeader::read(&mut Cursor::new(&bytes)),
+        Err(ReadError::MacsecUnsupportedVersion)
+    );
+}
+
+#[test]
+fn debug_clone_eq() {
+    let header = MacsecHeader::new(1, None);
+    assert_eq!(
+        "MacsecHeader { end_station: false, single_copy_broadcast: false, encrypted: true, changed: true, association_number: 0, short_length: 0, packet_number: 1, sci: None, ether_type: None }",
+        format!("{:?}", header)
+    );
+    assert_eq!(header, header.clone());
+
+    let bytes = header.to_bytes().unwrap();
+    let slice = MacsecHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(
+        format!("MacsecHeaderSlice {{ slice: {:?} }}", &bytes[..]),
+        format!("{:?}", slice)
+    );
+    assert_eq!(slice, slice.clone());
+}
+
+/// Returns an ethernet II frame containing a SecTAG followed by the given
+/// payload & the integrity check value.
+fn macsec_frame(header: &MacsecHeader, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [1, 2, 3, 4, 5, 6],
+        destination: [7, 8, 9, 10, 11, 12],
+        ether_type: ether_type::MACSEC,
+    }.write(&mut packet).unwrap();
+    header.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet.extend_from_slice(&[0xee; 16]);
+    packet
+}
+
+#[test]
+fn sliced_packet_cleartext() {
+    let mut ipv4 = Vec::new();
+    PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut ipv4, &[1, 2, 3, 4])
+        .unwrap();
+    let header = MacsecHeader {
+        sci: Some(0x0102_0304_0506_0001),
+        ..MacsecHeader::new(7, Some(ether_type::IPV4))
+    };
+    let packet = macsec_frame(&header, &ipv4);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(header, sliced.macsec.as_ref().unwrap().to_header());
+        assert!(sliced.ip.is_some());
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        // the icv is part of the payload
+        assert_eq!(&packet[packet.len() - 20..], sliced.payload);
+    }
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(header), decoded.macsec);
+        assert!(decoded.ip.is_some());
+        assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&packet[packet.len() - 20..], decoded.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_vlan() {
+    // vlan tags are part of the protected frame
+    let mut payload = Vec::new();
+    SingleVlanHeader {
+        vlan_identifier: 10,
+        ether_type: 0x1234,
+        ..Default::default()
+    }.write(&mut payload).unwrap();
+    payload.extend_from_slice(&[1, 2, 3, 4]);
+    let packet = macsec_frame(&MacsecHeader::new(1, Some(ether_type::VLAN_TAGGED_FRAME)), &payload);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert!(sliced.macsec.is_some());
+    assert_matches!(&sliced.vlan, Some(VlanSlice::SingleVlan(vlan)) if 10 == vlan.vlan_identifier());
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert!(decoded.macsec.is_some());
+    assert_matches!(&decoded.vlan, Some(VlanHeader::Single(vlan)) if 10 == vlan.vlan_identifier);
+}
+
+/// Returns an ethernet II frame with a vlan header followed by a SecTAG,
+/// the given payload & the integrity check value.
+fn vlan_macsec_frame(header: &MacsecHeader, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [1, 2, 3, 4, 5, 6],
+        destination: [7, 8, 9, 10, 11, 12],
+        ether_type: ether_type::VLAN_TAGGED_FRAME,
+    }.write(&mut packet).unwrap();
+    SingleVlanHeader {
+        vlan_identifier: 10,
+        ether_type: ether_type::MACSEC,
+        ..Default::default()
+    }.write(&mut packet).unwrap();
+    header.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet.extend_from_slice(&[0xee; 16]);
+    packet
+}
+
+#[test]
+fn sliced_packet_after_vlan() {
+    // cleartext
+    {
+        let mut ipv4 = Vec::new();
+        PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+            .udp(1234, 5678)
+            .write(&mut ipv4, &[1, 2, 3, 4])
+            .unwrap();
+        let header = MacsecHeader::new(7, Some(ether_type::IPV4));
+        let packet = vlan_macsec_frame(&header, &ipv4);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_matches!(&sliced.vlan, Some(VlanSlice::SingleVlan(vlan)) if 10 == vlan.vlan_identifier());
+        assert_eq!(header, sliced.macsec.as_ref().unwrap().to_header());
+        assert!(sliced.ip.is_some());
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&packet[packet.len() - 20..], sliced.payload);
+
+        // the sectag is left in the payload when decoding
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert!(decoded.vlan.is_some());
+        assert_eq!(None, decoded.macsec);
+        assert_eq!(&packet[18..], decoded.payload);
+        assert_eq!(Some(ether_type::MACSEC), decoded.payload_ether_type());
+    }
+    // encrypted
+    {
+        let packet = vlan_macsec_frame(&MacsecHeader::new(1, None), &[1, 2, 3, 4]);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(sliced.vlan.is_some());
+        assert!(sliced.macsec.is_some());
+        assert_eq!(&packet[18 + MacsecHeader::MIN_LEN..], sliced.payload);
+        assert_eq!(None, sliced.payload_ether_type());
+    }
+    // unknown protected ether type
+    {
+        let packet = vlan_macsec_frame(&MacsecHeader::new(1, Some(0x1234)), &[1, 2, 3, 4]);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(sliced.macsec.is_some());
+        assert_eq!(Some(0x1234), sliced.payload_ether_type());
+    }
+    // further vlan headers are left in the payload
+    {
+        let packet = vlan_macsec_frame(&MacsecHeader::new(1, Some(ether_type::VLAN_TAGGED_FRAME)), &[0, 10, 0x08, 0]);
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert!(sliced.vlan.is_some());
+        assert_eq!(None, sliced.macsec);
+        assert_eq!(&packet[18..], sliced.payload);
+        assert_eq!(Some(ether_type::MACSEC), sliced.payload_ether_type());
+    }
+    // double vlan header
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1, 2, 3, 4, 5, 6],
+            destination: [7, 8, 9, 10, 11, 12],
+            ether_type: ether_type::PROVIDER_BRIDGING,
+        }.write(&mut packet).unwrap();
+        DoubleVlanHeader {
+            outer: SingleVlanHeader {
+                vlan_identifier: 10,
+                ether_type: ether_type::VLAN_TAGGED_FRAME,
+                ..Default::default()
+            },
+            inner: SingleVlanHeader {
+                vlan_identifier: 20,
+                ether_type: ether_type::MACSEC,
+                ..Default::default()
+            },
+        }.write(&mut packet).unwrap();
+        MacsecHeader::new(1, None).write(&mut packet).unwrap();
+        packet.extend_from_slice(&[1, 2, 3, 4]);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_matches!(sliced.vlan, Some(VlanSlice::DoubleVlan(_)));
+        assert!(sliced.macsec.is_some());
+        assert_eq!(&[1, 2, 3, 4], sliced.payload);
+    }
+    // nested sectags are left in the payload
+    {
+        let mut payload = Vec::new();
+        SingleVlanHeader {
+            vlan_identifier: 10,
+            ether_type: ether_type::MACSEC,
+            ..Default::default()
+        }.write(&mut payload).unwrap();
+        payload.extend_from_slice(&MacsecHeader::new(2, Some(ether_type::IPV4)).to_bytes().unwrap());
+        let packet = macsec_frame(&MacsecHeader::new(1, Some(ether_type::VLAN_TAGGED_FRAME)), &payload);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(1, sliced.macsec.as_ref().unwrap().packet_number());
+        assert!(sliced.vlan.is_some());
+        assert_eq!(&packet[Ethernet2Header::SERIALIZED_SIZE + 8 + 4..], sliced.payload);
+        assert_eq!(Some(ether_type::MACSEC), sliced.payload_ether_type());
+    }
+}
+
+#[test]
+fn sliced_packet_encrypted() {
+    let header = MacsecHeader::new(1, None);
+    let packet = macsec_frame(&header, &[1, 2, 3, 4]);
+    let payload = &packet[Ethernet2Header::SERIALIZED_SIZE + MacsecHeader::MIN_LEN..];
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert_eq!(header, sliced.macsec.as_ref().unwrap().to_header());
+    assert_eq!(None, sliced.ip);
+    assert_eq!(payload, sliced.payload);
+    assert_eq!(None, sliced.payload_ether_type());
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(Some(header), decoded.macsec);
+    assert_eq!(None, decoded.ip);
+    assert_eq!(payload, decoded.payload);
+    assert_eq!(None, decoded.payload_ether_type());
+}
+
+#[test]
+fn sliced_packet_nested() {
+    // nested sectags are not decoded
+    let inner = MacsecHeader::new(2, Some(ether_type::IPV4)).to_bytes().unwrap();
+    let packet = macsec_frame(&MacsecHeader::new(1, Some(ether_type::MACSEC)), &inner);
+    let payload = &packet[Ethernet2Header::SERIALIZED_SIZE + 8..];
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert_eq!(1, sliced.macsec.as_ref().unwrap().packet_number());
+    assert_eq!(payload, sliced.payload);
+    assert_eq!(Some(ether_type::MACSEC), sliced.payload_ether_type());
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(1, decoded.macsec.as_ref().unwrap().packet_number);
+    assert_eq!(payload, decoded.payload);
+    assert_eq!(Some(ether_type::MACSEC), decoded.payload_ether_type());
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [1; 6],
+        destination: [2; 6],
+        ether_type: ether_type::MACSEC,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&MacsecHeader::new(1, Some(ether_type::IPV4)).to_bytes().unwrap());
+
+    // offsets are relative to the start of the packet
+    let truncated = &packet[..packet.len() - 1];
+    assert_matches!(
+        SlicedPacket::from_ethernet(truncated),
+        Err(ReadError::UnexpectedEndOfSlice(22))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice(truncated),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+
+    // error in the ip packet
+    packet.push(0x45);
+    assert_matches!(
+        SlicedPacket::from_ethernet(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+}
+
+#[test]
+fn repair() {
+    let mut headers = PacketHeaders {
+        link: Some(Ethernet2Header::default()),
+        macsec: Some(MacsecHeader::new(1, Some(0))),
+        ip: Some(IpHeader::Version6(
+            Ipv6Header {
+                source: [1; 16],
+                destination: [2; 16],
+                hop_limit: 20,
+                ..Default::default()
+            },
+            Default::default()
+        )),
+        transport: Some(TransportHeader::Udp(UdpHeader::default())),
+        payload: &[1, 2, 3, 4],
+        ..Default::default()
+    };
+    etherparse::repair::fix(&mut headers).unwrap();
+    let packet = etherparse::repair::to_bytes(&headers).unwrap();
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(ether_type::MACSEC, decoded.link.unwrap().ether_type);
+    assert_eq!(Some(ether_type::IPV6), decoded.macsec.unwrap().ether_type);
+    assert_eq!(&[1, 2, 3, 4], decoded.payload);
+    assert!(etherparse::roundtrip::check(&packet).unwrap().is_empty());
+
+    // the layer can be replaced
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let replacement = MacsecHeader::new(2, Some(ether_type::IPV6)).to_bytes().unwrap();
+    let modified = etherparse::rewrite::replace(&packet, &sliced, etherparse::rewrite::Layer::Macsec, &replacement).unwrap();
+    assert_eq!(2, PacketHeaders::from_ethernet_slice(&modified).unwrap().macsec.unwrap().packet_number);
+}
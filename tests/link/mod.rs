@@ -1,5 +1,25 @@
 pub mod ethernet;
 pub mod vlan_tagging;
+pub mod redundancy_tag;
+pub mod mac_control;
+pub mod mpls;
+pub mod arp;
+pub mod isis;
+pub mod ppp;
+pub mod nsh;
+pub mod pppoe;
+pub mod macsec;
+pub mod linux_sll;
+pub mod null_loopback;
+pub mod vj_compression;
+pub mod llc_snap;
+pub mod radiotap;
+pub mod ieee802_11;
+pub mod lldp;
+pub mod preemption;
+pub mod ipx;
+pub mod appletalk;
+pub mod eapol;
 
 use super::*;
 
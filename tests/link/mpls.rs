@@ -0,0 +1,460 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn mpls_any()(
+        label in 0..=MplsHeader::MAX_LABEL,
+        traffic_class in 0..=MplsHeader::MAX_TRAFFIC_CLASS,
+        bottom_of_stack in any::<bool>(),
+        ttl in any::<u8>(),
+    ) -> MplsHeader {
+        MplsHeader {
+            label,
+            traffic_class,
+            bottom_of_stack,
+            ttl,
+        }
+    }
+}
+
+/// serializes a label stack with the given labels (bottom of stack set on the last entry)
+fn stack(labels: &[u32]) -> Vec<u8> {
+    let mut result = Vec::new();
+    for (index, label) in labels.iter().enumerate() {
+        MplsHeader::new(*label, 0, index + 1 == labels.len(), 64)
+            .unwrap()
+            .write(&mut result)
+            .unwrap();
+    }
+    result
+}
+
+mod mpls_header {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(4, MplsHeader::SERIALIZED_SIZE);
+        assert_eq!(0x8847, ether_type::MPLS_UNICAST);
+        assert_eq!(0x8848, ether_type::MPLS_MULTICAST);
+    }
+
+    proptest!{
+        #[test]
+        fn new_to_bytes(input in mpls_any()) {
+            use ErrorField::*;
+
+            assert_eq!(
+                Ok(input.clone()),
+                MplsHeader::new(input.label, input.traffic_class, input.bottom_of_stack, input.ttl)
+            );
+            let bytes = input.to_bytes().unwrap();
+            assert_eq!(input.label, u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 4);
+            assert_eq!(input.traffic_class, (bytes[2] >> 1) & 0b111);
+            assert_eq!(input.bottom_of_stack, 0 != bytes[2] & 1);
+            assert_eq!(input.ttl, bytes[3]);
+
+            // label too large
+            {
+                let value = MplsHeader::MAX_LABEL + 1;
+                let expected = ValueError::U32TooLarge{ value, max: MplsHeader::MAX_LABEL, field: MplsLabel };
+                assert_eq!(Err(expected.clone()), MplsHeader::new(value, 0, false, 0));
+                let mut header = input.clone();
+                header.label = value;
+                assert_eq!(Err(expected.clone()), header.to_bytes());
+                assert_matches!(
+                    header.write(&mut Vec::new()),
+                    Err(WriteError::ValueError(_))
+                );
+            }
+
+            // traffic class too large
+            {
+                let value = MplsHeader::MAX_TRAFFIC_CLASS + 1;
+                let expected = ValueError::U8TooLarge{ value, max: MplsHeader::MAX_TRAFFIC_CLASS, field: MplsTrafficClass };
+                assert_eq!(Err(expected.clone()), MplsHeader::new(0, value, false, 0));
+                let mut header = input.clone();
+                header.traffic_class = value;
+                assert_eq!(Err(expected), header.to_bytes());
+            }
+        }
+    }
+
+    proptest!{
+        #[test]
+        fn from_slice(
+            input in mpls_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20)
+        ) {
+            let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len() + dummy_data.len());
+            input.write(&mut buffer).unwrap();
+            buffer.extend(&dummy_data[..]);
+
+            // header
+            {
+                let (result, rest) = MplsHeader::from_slice(&buffer).unwrap();
+                assert_eq!(input, result);
+                assert_eq!(&buffer[4..], rest);
+                assert_eq!(input, MplsHeader::from_bytes(input.to_bytes().unwrap()));
+            }
+
+            // slice
+            {
+                let slice = MplsHeaderSlice::from_slice(&buffer).unwrap();
+                assert_eq!(&buffer[..4], slice.slice());
+                assert_eq!(input.label, slice.label());
+                assert_eq!(input.traffic_class, slice.traffic_class());
+                assert_eq!(input.bottom_of_stack, slice.bottom_of_stack());
+                assert_eq!(input.ttl, slice.ttl());
+                assert_eq!(input, slice.to_header());
+            }
+
+            // slice length to small
+            for len in 0..4 {
+                assert_matches!(
+                    MplsHeader::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(4))
+                );
+                assert_matches!(
+                    MplsHeaderSlice::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(4))
+                );
+            }
+        }
+    }
+
+    proptest!{
+        #[test]
+        fn read(input in mpls_any()) {
+            let bytes = input.to_bytes().unwrap();
+            assert_eq!(input, MplsHeader::read(&mut Cursor::new(&bytes)).unwrap());
+            assert!(MplsHeader::read(&mut Cursor::new(&bytes[..3])).is_err());
+        }
+    }
+
+    #[test]
+    fn write_io_error() {
+        let header = MplsHeader::new(1, 0, true, 1).unwrap();
+        let mut buffer = [0u8;3];
+        let mut cursor = Cursor::new(&mut buffer[..]);
+        assert_matches!(header.write(&mut cursor), Err(WriteError::IoError(_)));
+    }
+
+    proptest!{
+        #[test]
+        fn entropy_label_pair(
+            lsp in mpls_any(),
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+        ) {
+            let key = FlowKey {
+                source_addr: std::net::IpAddr::from([10, 0, 0, 1]),
+                destination_addr: std::net::IpAddr::from([10, 0, 0, 2]),
+                ip_number: ip_number::UDP,
+                source_port,
+                destination_port,
+            };
+            let value = MplsHeader::entropy_label_value(&key, FlowHashAlgorithm::Crc32);
+            assert!(MplsLabelValue::FIRST_UNRESERVED <= value);
+            assert!(value <= MplsHeader::MAX_LABEL);
+
+            let [eli, el] = lsp.entropy_label_pair(&key, FlowHashAlgorithm::Crc32);
+            assert_eq!(
+                MplsHeader {
+                    label: 7,
+                    traffic_class: lsp.traffic_class,
+                    bottom_of_stack: false,
+                    ttl: lsp.ttl,
+                },
+                eli
+            );
+            assert_eq!(
+                MplsHeader {
+                    label: value,
+                    traffic_class: lsp.traffic_class,
+                    bottom_of_stack: false,
+                    ttl: 0,
+                },
+                el
+            );
+            assert!(!el.is_reserved());
+        }
+    }
+
+    #[test]
+    fn entropy_label_value() {
+        let key = FlowKey {
+            source_addr: std::net::IpAddr::from([192, 168, 1, 1]),
+            destination_addr: std::net::IpAddr::from([192, 168, 1, 2]),
+            ip_number: ip_number::TCP,
+            source_port: 80,
+            destination_port: 1234,
+        };
+        for algorithm in [FlowHashAlgorithm::Crc32, FlowHashAlgorithm::Xor8] {
+            assert_eq!(
+                16 + key.flow_hash(algorithm) % (0x10_0000 - 16),
+                MplsHeader::entropy_label_value(&key, algorithm)
+            );
+        }
+        // the entropy depends on the flow
+        assert_ne!(
+            MplsHeader::entropy_label_value(&key, FlowHashAlgorithm::Crc32),
+            MplsHeader::entropy_label_value(&key.reversed(), FlowHashAlgorithm::Crc32)
+        );
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let header = MplsHeaderSlice::from_slice(&[0, 0, 1, 0]).unwrap();
+        assert_eq!(header, header.clone());
+        println!("{:?}", header);
+        assert_eq!(
+            "MplsHeader { label: 0, traffic_class: 0, bottom_of_stack: true, ttl: 0 }",
+            format!("{:?}", header.to_header())
+        );
+    }
+}
+
+mod mpls_label_value {
+    use super::*;
+
+    #[test]
+    fn from_u32_to_u32() {
+        use MplsLabelValue::*;
+        let known = [
+            (0, Ipv4ExplicitNull),
+            (1, RouterAlert),
+            (2, Ipv6ExplicitNull),
+            (3, ImplicitNull),
+            (7, EntropyLabelIndicator),
+            (13, GenericAssociatedChannel),
+            (14, OamAlert),
+            (15, ExtensionLabel),
+        ];
+        for (raw, value) in known {
+            assert_eq!(Some(value), MplsLabelValue::from_u32(raw));
+            assert_eq!(raw, value.to_u32());
+            assert!(value.is_reserved());
+        }
+        for raw in [4, 5, 6, 8, 9, 10, 11, 12] {
+            let value = Unassigned(raw as u8);
+            assert_eq!(Some(value), MplsLabelValue::from_u32(raw));
+            assert_eq!(raw, value.to_u32());
+            assert!(value.is_reserved());
+        }
+        for raw in [16, 1000, MplsHeader::MAX_LABEL] {
+            let value = Label(raw);
+            assert_eq!(Some(value), MplsLabelValue::from_u32(raw));
+            assert_eq!(raw, value.to_u32());
+            assert!(!value.is_reserved());
+        }
+        assert_eq!(None, MplsLabelValue::from_u32(MplsHeader::MAX_LABEL + 1));
+        assert_eq!(None, MplsLabelValue::from_u32(u32::MAX));
+    }
+
+    proptest! {
+        #[test]
+        fn reserved_label(input in mpls_any()) {
+            let bytes = input.to_bytes().unwrap();
+            let slice = MplsHeaderSlice::from_slice(&bytes).unwrap();
+            let expected = if input.label < 16 {
+                MplsLabelValue::from_u32(input.label)
+            } else {
+                None
+            };
+            assert_eq!(input.label < 16, input.is_reserved());
+            assert_eq!(input.label < 16, slice.is_reserved());
+            assert_eq!(expected, input.reserved_label());
+            assert_eq!(expected, slice.reserved_label());
+        }
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let value = MplsLabelValue::Label(16);
+        assert_eq!(value, value.clone());
+        assert_eq!("Label(16)", format!("{:?}", value));
+    }
+}
+
+mod mpls_stack_slice {
+    use super::*;
+
+    #[test]
+    fn from_slice() {
+        // single entry
+        {
+            let mut buffer = stack(&[100]);
+            buffer.extend_from_slice(&[0x45, 0]);
+            let slice = MplsStackSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..4], slice.slice());
+            assert_eq!(1, slice.len());
+            assert!(!slice.is_empty());
+            assert_eq!(slice.top(), slice.bottom());
+            assert_eq!(100, slice.top().label());
+        }
+
+        // multiple entries
+        {
+            let buffer = stack(&[16, 17, 18]);
+            let slice = MplsStackSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..], slice.slice());
+            assert_eq!(3, slice.len());
+            assert_eq!(16, slice.top().label());
+            assert_eq!(18, slice.bottom().label());
+            assert!(slice.bottom().bottom_of_stack());
+
+            let iter = slice.iter();
+            assert_eq!((3, Some(3)), iter.size_hint());
+            assert_eq!(
+                vec![16, 17, 18],
+                iter.map(|entry| entry.label()).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![16, 17, 18],
+                (&slice).into_iter().map(|entry| entry.label()).collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![16, 17, 18],
+                slice.to_headers().iter().map(|h| h.label).collect::<Vec<_>>()
+            );
+        }
+
+        // missing bottom of stack
+        {
+            let buffer = stack(&[16, 17, 18]);
+            for len in 0..buffer.len() {
+                assert_matches!(
+                    MplsStackSlice::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(value)) if value == (len/4 + 1)*4
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn payload_ether_type() {
+        assert_eq!(Some(ether_type::IPV4), MplsStackSlice::payload_ether_type(&[0x45]));
+        assert_eq!(Some(ether_type::IPV6), MplsStackSlice::payload_ether_type(&[0x60]));
+        // pseudowire control word
+        assert_eq!(None, MplsStackSlice::payload_ether_type(&[0x00]));
+        assert_eq!(None, MplsStackSlice::payload_ether_type(&[]));
+    }
+
+    #[test]
+    fn sliced_packet() {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::MPLS_UNICAST,
+        }.write(&mut packet).unwrap();
+        packet.extend_from_slice(&stack(&[16, 1000]));
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1234, 53)
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+
+        // sliced
+        {
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            assert_eq!(&packet[14..22], sliced.mpls.as_ref().unwrap().slice());
+            assert_eq!(1000, sliced.mpls.as_ref().unwrap().bottom().label());
+            assert_matches!(sliced.ip, Some(InternetSlice::Ipv4(_, _)));
+            assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+            assert_eq!(&[1,2,3,4], sliced.payload);
+            assert_eq!(None, sliced.payload_ether_type());
+
+            let from_ether_type = SlicedPacket::from_ether_type(ether_type::MPLS_MULTICAST, &packet[14..]).unwrap();
+            assert_eq!(sliced.mpls, from_ether_type.mpls);
+            assert_eq!(sliced.ip, from_ether_type.ip);
+        }
+
+        // decoded
+        {
+            let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+            assert_eq!(&packet[14..22], decoded.mpls.as_ref().unwrap().slice());
+            assert_matches!(decoded.ip, Some(IpHeader::Version4(_, _)));
+            assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+            assert_eq!(&[1,2,3,4], decoded.payload);
+
+            let from_ether_type = PacketHeaders::from_ether_type(ether_type::MPLS_UNICAST, &packet[14..]).unwrap();
+            assert_eq!(decoded.mpls, from_ether_type.mpls);
+            assert_eq!(decoded.ip, from_ether_type.ip);
+        }
+
+        // vlan tagged
+        {
+            let mut tagged = Vec::new();
+            Ethernet2Header {
+                source: [1,2,3,4,5,6],
+                destination: [7,8,9,10,11,12],
+                ether_type: ether_type::VLAN_TAGGED_FRAME,
+            }.write(&mut tagged).unwrap();
+            SingleVlanHeader {
+                priority_code_point: 0,
+                drop_eligible_indicator: false,
+                vlan_identifier: 1,
+                ether_type: ether_type::MPLS_UNICAST,
+            }.write(&mut tagged).unwrap();
+            tagged.extend_from_slice(&packet[14..]);
+
+            let sliced = SlicedPacket::from_ethernet(&tagged).unwrap();
+            assert!(sliced.vlan.is_some());
+            assert_eq!(2, sliced.mpls.unwrap().len());
+            assert!(sliced.ip.is_some());
+
+            let decoded = PacketHeaders::from_ethernet_slice(&tagged).unwrap();
+            assert!(decoded.vlan.is_some());
+            assert_eq!(2, decoded.mpls.unwrap().len());
+            assert!(decoded.ip.is_some());
+        }
+    }
+
+    #[test]
+    fn sliced_packet_non_ip_payload() {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::MPLS_UNICAST,
+        }.write(&mut packet).unwrap();
+        packet.extend_from_slice(&stack(&[16]));
+        // pseudowire control word
+        packet.extend_from_slice(&[0, 0, 0, 0]);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(1, sliced.mpls.as_ref().unwrap().len());
+        assert_eq!(None, sliced.ip);
+        assert_eq!(&[0, 0, 0, 0], sliced.payload);
+        assert_eq!(None, sliced.payload_ether_type());
+
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(sliced.mpls, decoded.mpls);
+        assert_eq!(None, decoded.ip);
+        assert_eq!(&[0, 0, 0, 0], decoded.payload);
+        assert_eq!(None, decoded.payload_ether_type());
+    }
+
+    #[test]
+    fn sliced_packet_errors() {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::MPLS_UNICAST,
+        }.write(&mut packet).unwrap();
+        // stack without bottom of stack entry
+        MplsHeader::new(16, 0, false, 64).unwrap().write(&mut packet).unwrap();
+
+        assert_matches!(
+            SlicedPacket::from_ethernet(&packet),
+            Err(ReadError::UnexpectedEndOfSlice(22))
+        );
+        assert_matches!(
+            PacketHeaders::from_ethernet_slice(&packet),
+            Err(ReadError::UnexpectedEndOfSlice(8))
+        );
+    }
+}
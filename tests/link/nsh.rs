@@ -0,0 +1,471 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn nsh_tlv_any()(
+        class in any::<u16>(),
+        tlv_type in any::<u8>(),
+        data in proptest::collection::vec(any::<u8>(), 0..=NshTlv::MAX_DATA_LEN),
+    ) -> (u16, u8, Vec<u8>) {
+        (class, tlv_type, data)
+    }
+}
+
+prop_compose! {
+    fn nsh_any()(
+        oam in any::<bool>(),
+        ttl in 0..=NshHeader::MAX_TTL,
+        next_protocol in any::<u8>(),
+        service_path_id in 0..=NshHeader::MAX_SERVICE_PATH_ID,
+        service_index in any::<u8>(),
+        fixed_context in proptest::option::of(any::<[u8; 16]>()),
+        tlvs in proptest::collection::vec(nsh_tlv_any(), 0..2),
+    ) -> NshHeader {
+        let mut result = NshHeader::new(service_path_id, service_index, next_protocol).unwrap();
+        result.oam = oam;
+        result.ttl = ttl;
+        if let Some(context) = fixed_context {
+            result.set_fixed_context(context);
+        } else {
+            let tlvs: Vec<NshTlv> = tlvs
+                .iter()
+                .map(|(class, tlv_type, data)| NshTlv{ class: *class, tlv_type: *tlv_type, data })
+                .collect();
+            result.set_metadata_tlvs(&tlvs).unwrap();
+        }
+        result
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in nsh_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(header.header_len() + 2, buffer.len());
+        assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..header.header_len()]);
+
+        // from_slice
+        {
+            let (decoded, rest) = NshHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &NshHeader::read(&mut cursor).unwrap());
+            assert_eq!(header.header_len() as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = NshHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(0, slice.version());
+            assert_eq!(header.oam, slice.oam());
+            assert_eq!(header.ttl, slice.ttl());
+            assert_eq!((header.header_len() / 4) as u8, slice.length());
+            assert_eq!(header.md_type(), slice.md_type());
+            assert_eq!(header.next_protocol, slice.next_protocol());
+            assert_eq!(header.next_ether_type(), slice.next_ether_type());
+            assert_eq!(header.service_path_id, slice.service_path_id());
+            assert_eq!(header.service_index, slice.service_index());
+            assert_eq!(header.metadata(), slice.metadata());
+            assert_eq!(
+                header.metadata_tlvs().collect::<Vec<_>>(),
+                slice.metadata_tlvs().collect::<Vec<_>>()
+            );
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn value_errors(
+        ttl in (NshHeader::MAX_TTL + 1)..=u8::MAX,
+        service_path_id in (NshHeader::MAX_SERVICE_PATH_ID + 1)..=u32::MAX
+    ) {
+        // ttl
+        {
+            let mut header = NshHeader::new(0, 0, 0).unwrap();
+            header.ttl = ttl;
+            let expected = ValueError::U8TooLarge{
+                value: ttl,
+                max: NshHeader::MAX_TTL,
+                field: ErrorField::NshTtl
+            };
+            assert_eq!(Some(expected.clone()), header.to_bytes().err());
+            assert_eq!(Some(expected), header.write(&mut Vec::new()).unwrap_err().value_error());
+        }
+        // service path id
+        {
+            let expected = ValueError::U32TooLarge{
+                value: service_path_id,
+                max: NshHeader::MAX_SERVICE_PATH_ID,
+                field: ErrorField::NshServicePathId
+            };
+            assert_eq!(Some(expected.clone()), NshHeader::new(service_path_id, 0, 0).err());
+
+            let mut header = NshHeader::new(0, 0, 0).unwrap();
+            header.service_path_id = service_path_id;
+            assert_eq!(Some(expected), header.to_bytes().err());
+        }
+    }
+}
+
+#[test]
+fn new() {
+    let header = NshHeader::new(0x123456, 0x78, nsh_next_protocol::IPV6).unwrap();
+    assert!(!header.oam);
+    assert_eq!(63, header.ttl);
+    assert_eq!(nsh_next_protocol::IPV6, header.next_protocol);
+    assert_eq!(0x123456, header.service_path_id);
+    assert_eq!(0x78, header.service_index);
+    assert_eq!(NshHeader::MD_TYPE_2, header.md_type());
+    assert_eq!(NshHeader::MIN_LEN, header.header_len());
+    assert!(header.metadata().is_empty());
+    assert_eq!(None, header.fixed_context());
+    assert_eq!(
+        &[0x0f, 0xc2, 0x02, 0x02, 0x12, 0x34, 0x56, 0x78],
+        &header.to_bytes().unwrap()[..]
+    );
+}
+
+#[test]
+fn next_ether_type() {
+    use nsh_next_protocol::*;
+    let pairs = [
+        (IPV4, Some(ether_type::IPV4)),
+        (IPV6, Some(ether_type::IPV6)),
+        (ETHERNET, Some(ether_type::TRANSPARENT_ETHERNET_BRIDGING)),
+        (NSH, Some(ether_type::NSH)),
+        (MPLS, Some(ether_type::MPLS_UNICAST)),
+        (0, None),
+        (0xfe, None),
+    ];
+    for (next_protocol, expected) in pairs {
+        assert_eq!(expected, NshHeader::new(0, 0, next_protocol).unwrap().next_ether_type());
+    }
+}
+
+#[test]
+fn fixed_context() {
+    let context = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16];
+    let mut header = NshHeader::new(1, 2, nsh_next_protocol::IPV4).unwrap();
+    header.set_fixed_context(context);
+    assert_eq!(NshHeader::MD_TYPE_1, header.md_type());
+    assert_eq!(Some(context), header.fixed_context());
+    assert_eq!(&context, header.metadata());
+    assert_eq!(None, header.metadata_tlvs().next());
+
+    let bytes = header.to_bytes().unwrap();
+    assert_eq!(24, bytes.len());
+    assert_eq!(6, bytes[1] & 0b0011_1111);
+    assert_eq!(NshHeader::MD_TYPE_1, bytes[2]);
+    assert_eq!(None, NshHeaderSlice::from_slice(&bytes).unwrap().metadata_tlvs().next());
+}
+
+#[test]
+fn set_metadata_tlvs() {
+    let mut header = NshHeader::new(1, 2, nsh_next_protocol::IPV4).unwrap();
+    header.set_fixed_context([0xff; 16]);
+    header.set_metadata_tlvs(&[
+        NshTlv{ class: 0x0102, tlv_type: 3, data: &[1, 2, 3, 4, 5] },
+        NshTlv{ class: 0x0405, tlv_type: 6, data: &[] },
+    ]).unwrap();
+    assert_eq!(NshHeader::MD_TYPE_2, header.md_type());
+    assert_eq!(None, header.fixed_context());
+    // the padding of the first tlv is zeroed
+    assert_eq!(
+        &[
+            0x01, 0x02, 3, 5, 1, 2, 3, 4, 5, 0, 0, 0,
+            0x04, 0x05, 6, 0,
+        ],
+        header.metadata()
+    );
+    assert_eq!(
+        vec![
+            NshTlv{ class: 0x0102, tlv_type: 3, data: &[1, 2, 3, 4, 5] },
+            NshTlv{ class: 0x0405, tlv_type: 6, data: &[] },
+        ],
+        header.metadata_tlvs().collect::<Vec<_>>()
+    );
+    assert_eq!(8 + 12 + 4, header.header_len());
+}
+
+#[test]
+fn set_metadata_tlvs_errors() {
+    let mut header = NshHeader::new(1, 2, nsh_next_protocol::IPV4).unwrap();
+    header.set_metadata_tlvs(&[NshTlv{ class: 1, tlv_type: 2, data: &[3] }]).unwrap();
+    let before = header.clone();
+
+    // data too long
+    let data = [0u8; NshTlv::MAX_DATA_LEN + 1];
+    assert_eq!(
+        Err(ValueError::NshTlvDataTooLong(NshTlv::MAX_DATA_LEN + 1)),
+        header.set_metadata_tlvs(&[NshTlv{ class: 1, tlv_type: 2, data: &data }])
+    );
+    assert_eq!(before, header);
+
+    // metadata too long
+    let data = [0u8; 120];
+    let tlv = NshTlv{ class: 1, tlv_type: 2, data: &data };
+    assert_eq!(
+        Err(ValueError::NshMetadataLengthBad(3 * 124)),
+        header.set_metadata_tlvs(&[tlv.clone(), tlv.clone(), tlv])
+    );
+    assert_eq!(before, header);
+}
+
+#[test]
+fn from_slice_errors() {
+    let mut header = NshHeader::new(1, 2, nsh_next_protocol::IPV4).unwrap();
+    header.set_metadata_tlvs(&[NshTlv{ class: 1, tlv_type: 2, data: &[1, 2, 3, 4] }]).unwrap();
+    let bytes = header.to_bytes().unwrap();
+
+    // length
+    for len in 0..bytes.len() {
+        let expected = if len < NshHeader::MIN_LEN { NshHeader::MIN_LEN } else { bytes.len() };
+        assert_matches!(
+            NshHeader::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(l)) if l == expected
+        );
+        assert_matches!(
+            NshHeader::read(&mut Cursor::new(&bytes[..len])),
+            Err(ReadError::IoError(_))
+        );
+    }
+    // version
+    for version in 1..4u8 {
+        let mut bytes = bytes.clone();
+        bytes[0] |= version << 6;
+        assert_matches!(
+            NshHeaderSlice::from_slice(&bytes),
+            Err(ReadError::NshUnsupportedVersion(v)) if v == version
+        );
+        assert_matches!(
+            NshHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::NshUnsupportedVersion(v)) if v == version
+        );
+    }
+    // length field too small
+    for length in 0..2u8 {
+        let mut bytes = bytes.clone();
+        bytes[1] = (bytes[1] & 0b1100_0000) | length;
+        assert_matches!(
+            NshHeaderSlice::from_slice(&bytes),
+            Err(ReadError::NshLengthBad{ md_type: 2, length: l }) if l == length
+        );
+        assert_matches!(
+            NshHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::NshLengthBad{ md_type: 2, length: l }) if l == length
+        );
+    }
+    // md type 1 with a length different then 6
+    {
+        let mut bytes = bytes.clone();
+        bytes[2] = NshHeader::MD_TYPE_1;
+        assert_matches!(
+            NshHeaderSlice::from_slice(&bytes),
+            Err(ReadError::NshLengthBad{ md_type: 1, length: 4 })
+        );
+    }
+    // tlv exceeding the header length
+    {
+        let mut bytes = bytes.clone();
+        bytes[NshHeader::MIN_LEN + 3] = 5;
+        assert_matches!(
+            NshHeaderSlice::from_slice(&bytes),
+            Err(ReadError::NshMetadataLengthTooBig(0))
+        );
+        assert_matches!(
+            NshHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::NshMetadataLengthTooBig(0))
+        );
+    }
+    // unknown md types are not validated
+    {
+        let mut bytes = bytes.clone();
+        bytes[2] = 0xf;
+        bytes[NshHeader::MIN_LEN + 3] = 5;
+        let (decoded, _) = NshHeader::from_slice(&bytes).unwrap();
+        assert_eq!(0xf, decoded.md_type());
+        assert_eq!(&bytes[NshHeader::MIN_LEN..], decoded.metadata());
+        assert_eq!(None, decoded.metadata_tlvs().next());
+    }
+}
+
+#[test]
+fn debug_eq_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut header = NshHeader::new(1, 2, nsh_next_protocol::IPV4).unwrap();
+    header.set_metadata_tlvs(&[NshTlv{ class: 1, tlv_type: 2, data: &[3] }]).unwrap();
+    assert_eq!(
+        "NshHeader { oam: false, ttl: 63, next_protocol: 1, service_path_id: 1, service_index: 2, md_type: 2, metadata: [0, 1, 2, 1, 3, 0, 0, 0] }",
+        format!("{:?}", header)
+    );
+
+    // data beyond the metadata is ignored
+    let mut other = header.clone();
+    other.set_fixed_context([0xff; 16]);
+    other.set_metadata_tlvs(&[NshTlv{ class: 1, tlv_type: 2, data: &[3] }]).unwrap();
+    assert_eq!(header, other);
+    let hash = |value: &NshHeader| {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(&header), hash(&other));
+
+    other.service_index = 3;
+    assert_ne!(header, other);
+}
+
+/// Returns an ethernet II frame containing a NSH header followed by the given inner packet.
+fn nsh_frame(next_protocol: u8, inner: &[u8]) -> (NshHeader, Vec<u8>) {
+    let mut nsh = NshHeader::new(0x1234, 254, next_protocol).unwrap();
+    nsh.set_fixed_context([7; 16]);
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [1, 2, 3, 4, 5, 6],
+        destination: [7, 8, 9, 10, 11, 12],
+        ether_type: ether_type::NSH,
+    }.write(&mut packet).unwrap();
+    nsh.write(&mut packet).unwrap();
+    packet.extend_from_slice(inner);
+    (nsh, packet)
+}
+
+#[test]
+fn sliced_packet_ipv4() {
+    let mut inner = Vec::new();
+    PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut inner, &[1, 2, 3, 4])
+        .unwrap();
+    let (nsh, packet) = nsh_frame(nsh_next_protocol::IPV4, &inner);
+
+    // slicing
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(nsh, sliced.nsh.unwrap().to_header());
+        assert_eq!(&inner[..], sliced.payload);
+
+        let sliced_inner = sliced.inner.unwrap();
+        assert_matches!(sliced_inner.ip, Some(InternetSlice::Ipv4(_, _)));
+        assert_matches!(sliced_inner.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced_inner.payload);
+    }
+    // decoding
+    {
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(Some(nsh), decoded.nsh);
+        assert_eq!(&inner[..], decoded.payload);
+
+        let decoded_inner = decoded.inner.unwrap();
+        assert_matches!(decoded_inner.ip, Some(IpHeader::Version4(_, _)));
+        assert_matches!(decoded_inner.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], decoded_inner.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_ethernet() {
+    let mut inner = Vec::new();
+    PacketBuilder::ethernet2([1; 6], [2; 6])
+        .ipv6([1; 16], [2; 16], 20)
+        .tcp(1234, 5678, 1, 1024)
+        .write(&mut inner, &[1, 2, 3, 4])
+        .unwrap();
+    let (_, packet) = nsh_frame(nsh_next_protocol::ETHERNET, &inner);
+
+    let sliced_inner = SlicedPacket::from_ethernet(&packet).unwrap().inner.unwrap();
+    assert_eq!([1; 6], sliced_inner.link.unwrap().to_header().source);
+    assert_matches!(sliced_inner.transport, Some(TransportSlice::Tcp(_)));
+
+    let decoded_inner = PacketHeaders::from_ethernet_slice(&packet).unwrap().inner.unwrap();
+    assert_eq!([1; 6], decoded_inner.link.unwrap().source);
+    assert_matches!(decoded_inner.transport, Some(TransportHeader::Tcp(_)));
+}
+
+#[test]
+fn sliced_packet_unknown_next_protocol() {
+    let (_, packet) = nsh_frame(0xfe, &[1, 2, 3, 4]);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert!(sliced.nsh.is_some());
+    assert!(sliced.inner.is_none());
+    assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert!(decoded.nsh.is_some());
+    assert!(decoded.inner.is_none());
+    assert_eq!(&[1, 2, 3, 4], decoded.payload);
+}
+
+#[test]
+fn sliced_packet_nesting_depth() {
+    // nsh header containing a nsh header
+    let inner = NshHeader::new(1, 1, 0xfe).unwrap().to_bytes().unwrap();
+    let (_, packet) = nsh_frame(nsh_next_protocol::NSH, &inner);
+
+    // default limits
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(1, sliced.inner.unwrap().nsh.unwrap().service_path_id());
+
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(1, decoded.inner.unwrap().nsh.unwrap().service_path_id);
+    }
+    // the inner nsh header exceeds the tunnel depth
+    {
+        let mut registry = ParserRegistry::new();
+        registry.set_limits(ParseLimits {
+            max_tunnel_depth: 1,
+            ..Default::default()
+        });
+        let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+        let sliced_inner = sliced.inner.unwrap();
+        assert!(sliced_inner.nsh.is_none());
+        assert_eq!(&inner[..], sliced_inner.payload);
+
+        let decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+        let decoded_inner = decoded.inner.unwrap();
+        assert!(decoded_inner.nsh.is_none());
+        assert_eq!(&inner[..], decoded_inner.payload);
+    }
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let (_, packet) = nsh_frame(nsh_next_protocol::IPV4, &[]);
+
+    // offsets are relative to the start of the packet
+    let packet = &packet[..packet.len() - 1];
+    assert_matches!(
+        SlicedPacket::from_ethernet(packet),
+        Err(ReadError::UnexpectedEndOfSlice(38))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice(packet),
+        Err(ReadError::UnexpectedEndOfSlice(24))
+    );
+
+    // error in the encapsulated packet (left in the payload)
+    let (_, packet) = nsh_frame(nsh_next_protocol::IPV4, &[0x45]);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert!(sliced.nsh.is_some());
+    assert!(sliced.inner.is_none());
+    assert_eq!(&[0x45], sliced.payload);
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert!(decoded.nsh.is_some());
+    assert!(decoded.inner.is_none());
+    assert_eq!(&[0x45], decoded.payload);
+}
@@ -0,0 +1,145 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+proptest! {
+    #[test]
+    fn write_read(family in 0..=u32::from(u16::MAX), big_endian in any::<bool>()) {
+        let header = NullHeader{ family, big_endian };
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(NullHeader::SERIALIZED_SIZE, header.header_len());
+        assert_eq!(&header.to_bytes()[..], &buffer[..4]);
+        if big_endian {
+            assert_eq!(family.to_be_bytes(), header.to_bytes());
+        } else {
+            assert_eq!(family.to_le_bytes(), header.to_bytes());
+        }
+
+        // from_slice (the byte order of 0 & of families with the same
+        // bytes in both orders can not be detected)
+        let (decoded, rest) = NullHeader::from_slice(&buffer).unwrap();
+        assert_eq!(family, decoded.family);
+        assert_eq!(&header.to_bytes(), &decoded.to_bytes());
+        assert_eq!(&[1, 2], rest);
+
+        // read
+        let mut cursor = Cursor::new(&buffer);
+        assert_eq!(decoded, NullHeader::read(&mut cursor).unwrap());
+        assert_eq!(4, cursor.position());
+    }
+}
+
+#[test]
+fn from_bytes() {
+    assert_eq!(NullHeader{ family: 2, big_endian: false }, NullHeader::from_bytes([2, 0, 0, 0]));
+    assert_eq!(NullHeader{ family: 2, big_endian: true }, NullHeader::from_bytes([0, 0, 0, 2]));
+    assert_eq!(NullHeader{ family: 0x1e00, big_endian: true }, NullHeader::from_bytes([0, 0, 0x1e, 0]));
+    assert_eq!(NullHeader{ family: 0x1e, big_endian: false }, NullHeader::from_bytes([0x1e, 0, 0, 0]));
+}
+
+#[test]
+fn ether_type() {
+    use null_address_family::*;
+    let tests = [
+        (INET, Some(ether_type::IPV4)),
+        (INET6_BSD, Some(ether_type::IPV6)),
+        (INET6_FREEBSD, Some(ether_type::IPV6)),
+        (INET6_DARWIN, Some(ether_type::IPV6)),
+        (0, None),
+        (7, None),
+    ];
+    for (family, expected) in tests {
+        assert_eq!(expected, NullHeader{ family, big_endian: false }.ether_type());
+    }
+}
+
+#[test]
+fn from_slice_errors() {
+    for len in 0..4 {
+        assert_matches!(
+            NullHeader::from_slice(&[2, 0, 0, 0][..len]),
+            Err(ReadError::UnexpectedEndOfSlice(4))
+        );
+        assert!(NullHeader::read(&mut Cursor::new(&[2, 0, 0, 0][..len])).is_err());
+        assert_matches!(
+            SlicedPacket::from_null_loopback(&[2, 0, 0, 0][..len]),
+            Err(ReadError::UnexpectedEndOfSlice(4))
+        );
+        assert_matches!(
+            PacketHeaders::from_null_loopback_slice(&[2, 0, 0, 0][..len]),
+            Err(ReadError::UnexpectedEndOfSlice(4))
+        );
+    }
+}
+
+#[test]
+fn sliced_packet() {
+    let mut ipv4 = Vec::new();
+    PacketBuilder::ipv4([127, 0, 0, 1], [127, 0, 0, 1], 20)
+        .udp(1234, 5678)
+        .write(&mut ipv4, &[1, 2, 3, 4])
+        .unwrap();
+    let mut ipv6 = Vec::new();
+    PacketBuilder::ipv6([1; 16], [1; 16], 20)
+        .tcp(1234, 5678, 1, 1024)
+        .write(&mut ipv6, &[1, 2, 3, 4])
+        .unwrap();
+
+    use null_address_family::*;
+    let tests = [
+        (INET, &ipv4),
+        (INET6_BSD, &ipv6),
+        (INET6_FREEBSD, &ipv6),
+        (INET6_DARWIN, &ipv6),
+    ];
+    for (family, ip) in tests {
+        for big_endian in [false, true] {
+            let mut packet = NullHeader{ family, big_endian }.to_bytes().to_vec();
+            packet.extend_from_slice(ip);
+
+            let sliced = SlicedPacket::from_null_loopback(&packet).unwrap();
+            assert!(sliced.link.is_none());
+            assert!(sliced.ip.is_some());
+            assert!(sliced.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+            let decoded = PacketHeaders::from_null_loopback_slice(&packet).unwrap();
+            assert!(decoded.link.is_none());
+            assert!(decoded.ip.is_some());
+            assert!(decoded.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], decoded.payload);
+        }
+    }
+}
+
+#[test]
+fn sliced_packet_unknown_family() {
+    // AF_APPLETALK
+    let packet = [16, 0, 0, 0, 1, 2, 3, 4];
+
+    let sliced = SlicedPacket::from_null_loopback(&packet).unwrap();
+    assert!(sliced.ip.is_none());
+    assert_eq!(&[1, 2, 3, 4], sliced.payload);
+
+    let decoded = PacketHeaders::from_null_loopback_slice(&packet).unwrap();
+    assert!(decoded.ip.is_none());
+    assert_eq!(&[1, 2, 3, 4], decoded.payload);
+}
+
+#[test]
+fn sliced_packet_errors() {
+    // offsets are relative to the start of the packet
+    let packet = [2, 0, 0, 0, 0x45, 0];
+    assert_matches!(
+        SlicedPacket::from_null_loopback(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(24))
+    );
+    // ipv4 header in an ipv6 packet
+    let packet = [30, 0, 0, 0, 0x45, 0];
+    assert_matches!(
+        SlicedPacket::from_null_loopback(&packet),
+        Err(_)
+    );
+}
@@ -0,0 +1,154 @@
+use super::super::*;
+
+/// Appends the fcs to the given frame.
+fn with_fcs(frame: &[u8]) -> Vec<u8> {
+    let mut result = frame.to_vec();
+    result.extend_from_slice(&PppHdlcSlice::fcs16(frame).to_le_bytes());
+    result
+}
+
+mod ppp_hdlc_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        use PppHdlcError::*;
+        assert_eq!(
+            "PppHdlcError: Unexpected end of slice. The given slice contained less then minimum required 5 bytes.",
+            format!("{}", UnexpectedEndOfSlice(5))
+        );
+        assert_eq!(
+            "PppHdlcError: The control escape byte at offset 3 is not followed by an escaped byte.",
+            format!("{}", InvalidEscape(3))
+        );
+        assert_eq!(
+            "PppHdlcError: Unexpected flag byte at offset 4 inside the frame.",
+            format!("{}", UnexpectedFlag(4))
+        );
+        assert_eq!(
+            "PppHdlcError: Frame check sequence 0x1234 does not match the calculated value 0xabcd.",
+            format!("{}", FcsMismatch{ expected: 0xabcd, actual: 0x1234 })
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(PppHdlcError::InvalidEscape(0).source().is_none());
+    }
+}
+
+mod ppp_hdlc_slice {
+    use super::*;
+
+    #[test]
+    fn fcs16() {
+        // check value of the crc-16/x-25 algorithm
+        assert_eq!(0x906e, PppHdlcSlice::fcs16(b"123456789"));
+        // the fcs over a frame including its fcs is constant (RFC 1662 "good final fcs")
+        assert_eq!(!0xf0b8, PppHdlcSlice::fcs16(&with_fcs(&[0xff, 0x03, 0xc0, 0x21, 1, 2, 3])));
+    }
+
+    proptest! {
+        #[test]
+        fn stuff_unstuff(ref frame in proptest::collection::vec(any::<u8>(), 0..100)) {
+            let stuffed = PppHdlcSlice::stuff(frame);
+            assert_eq!(PppHdlcSlice::FLAG, stuffed[0]);
+            assert_eq!(PppHdlcSlice::FLAG, stuffed[stuffed.len() - 1]);
+            // no flag bytes or control characters inside the frame
+            for value in &stuffed[1..stuffed.len() - 1] {
+                assert!(*value >= 0x20 && *value != PppHdlcSlice::FLAG);
+            }
+            assert_eq!(frame, &PppHdlcSlice::unstuff(&stuffed).unwrap());
+        }
+    }
+
+    #[test]
+    fn unstuff() {
+        // multiple flags & no flags
+        assert_eq!(
+            vec![0x7e, 0x7d, 0x01, 0x41],
+            PppHdlcSlice::unstuff(&[0x7e, 0x7e, 0x7d, 0x5e, 0x7d, 0x5d, 0x7d, 0x21, 0x41, 0x7e, 0x7e]).unwrap()
+        );
+        assert_eq!(vec![1, 2], PppHdlcSlice::unstuff(&[1, 2]).unwrap());
+        assert!(PppHdlcSlice::unstuff(&[0x7e]).unwrap().is_empty());
+        assert!(PppHdlcSlice::unstuff(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unstuff_errors() {
+        use PppHdlcError::*;
+        assert_eq!(Err(InvalidEscape(2)), PppHdlcSlice::unstuff(&[0x7e, 1, 0x7d, 0x7e]));
+        assert_eq!(Err(InvalidEscape(1)), PppHdlcSlice::unstuff(&[1, 0x7d]));
+        assert_eq!(Err(UnexpectedFlag(2)), PppHdlcSlice::unstuff(&[0x7e, 1, 0x7e, 2, 0x7e]));
+    }
+
+    #[test]
+    fn from_slice() {
+        // address & control field + 2 byte protocol
+        {
+            let frame = with_fcs(&[0xff, 0x03, 0x00, 0x57, 1, 2, 3]);
+            let ppp = PppHdlcSlice::from_slice(&frame).unwrap();
+            assert_eq!(&frame[..], ppp.slice());
+            assert!(ppp.has_address_control());
+            assert_eq!(ppp_protocol::IPV6, ppp.protocol());
+            assert_eq!(Some(ether_type::IPV6), ppp.ether_type());
+            assert_eq!(&[1, 2, 3], ppp.information());
+            assert_eq!(u16::from_le_bytes([frame[7], frame[8]]), ppp.fcs());
+        }
+        // compressed address & control field + 1 byte protocol
+        {
+            let frame = with_fcs(&[0x21, 1, 2]);
+            let ppp = PppHdlcSlice::from_slice(&frame).unwrap();
+            assert!(!ppp.has_address_control());
+            assert_eq!(ppp_protocol::IPV4, ppp.protocol());
+            assert_eq!(Some(ether_type::IPV4), ppp.ether_type());
+            assert_eq!(&[1, 2], ppp.information());
+        }
+        // protocol without ether type
+        {
+            let frame = with_fcs(&[0xff, 0x03, 0xc0, 0x21]);
+            let ppp = PppHdlcSlice::from_slice(&frame).unwrap();
+            assert_eq!(ppp_protocol::LCP, ppp.protocol());
+            assert_eq!(None, ppp.ether_type());
+            assert!(ppp.information().is_empty());
+        }
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        use PppHdlcError::*;
+
+        assert_eq!(Err(UnexpectedEndOfSlice(3)), PppHdlcSlice::from_slice(&[]));
+        assert_eq!(Err(UnexpectedEndOfSlice(5)), PppHdlcSlice::from_slice(&[0xff, 0x03, 0x00]));
+        assert_eq!(Err(UnexpectedEndOfSlice(6)), PppHdlcSlice::from_slice(&[0xff, 0x03, 0x00, 0x21, 0]));
+
+        let mut frame = with_fcs(&[0xff, 0x03, 0x00, 0x21, 1]);
+        frame[4] = 2;
+        assert_eq!(
+            Err(FcsMismatch{
+                expected: PppHdlcSlice::fcs16(&frame[..5]),
+                actual: u16::from_le_bytes([frame[5], frame[6]]),
+            }),
+            PppHdlcSlice::from_slice(&frame)
+        );
+    }
+
+    #[test]
+    fn sliced_information() {
+        let mut ip = Vec::new();
+        PacketBuilder::ipv4([1, 2, 3, 4], [5, 6, 7, 8], 20)
+            .udp(1, 2)
+            .write(&mut ip, &[1, 2, 3, 4])
+            .unwrap();
+        let mut frame = vec![0xff, 0x03, 0x00, 0x21];
+        frame.extend_from_slice(&ip);
+        let stuffed = PppHdlcSlice::stuff(&with_fcs(&frame));
+
+        let unstuffed = PppHdlcSlice::unstuff(&stuffed).unwrap();
+        let ppp = PppHdlcSlice::from_slice(&unstuffed).unwrap();
+        let sliced = SlicedPacket::from_ether_type(ppp.ether_type().unwrap(), ppp.information()).unwrap();
+        assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], sliced.payload);
+    }
+}
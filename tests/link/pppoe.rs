@@ -0,0 +1,425 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn pppoe_any()(
+        code in any::<u8>(),
+        session_id in any::<u16>(),
+        payload_length in 2..=u16::MAX,
+        ppp_protocol in any::<u16>(),
+    ) -> PppoeHeader {
+        if pppoe_code::SESSION_DATA == code {
+            PppoeHeader {
+                payload_length,
+                ..PppoeHeader::new_session(session_id, ppp_protocol)
+            }
+        } else {
+            PppoeHeader {
+                payload_length,
+                ..PppoeHeader::new_discovery(code, session_id)
+            }
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn write_read(ref header in pppoe_any()) {
+        let mut buffer = Vec::new();
+        header.write(&mut buffer).unwrap();
+        buffer.extend_from_slice(&[1, 2]);
+        assert_eq!(header.header_len() + 2, buffer.len());
+        assert_eq!(&header.to_bytes().unwrap()[..], &buffer[..header.header_len()]);
+
+        // from_slice
+        {
+            let (decoded, rest) = PppoeHeader::from_slice(&buffer).unwrap();
+            assert_eq!(header, &decoded);
+            assert_eq!(&[1, 2], rest);
+        }
+        // read
+        {
+            let mut cursor = Cursor::new(&buffer);
+            assert_eq!(header, &PppoeHeader::read(&mut cursor).unwrap());
+            assert_eq!(header.header_len() as u64, cursor.position());
+        }
+        // slice
+        {
+            let slice = PppoeHeaderSlice::from_slice(&buffer).unwrap();
+            assert_eq!(&buffer[..header.header_len()], slice.slice());
+            assert_eq!(header.version, slice.version());
+            assert_eq!(header.pppoe_type, slice.pppoe_type());
+            assert_eq!(header.code, slice.code());
+            assert_eq!(header.session_id, slice.session_id());
+            assert_eq!(header.payload_length, slice.payload_length());
+            assert_eq!(header.ppp_protocol, slice.ppp_protocol());
+            assert_eq!(header.ether_type(), slice.ether_type());
+            assert_eq!(header, &slice.to_header());
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn value_errors(value in (PppoeHeader::MAX_VERSION + 1)..=u8::MAX) {
+        // version
+        {
+            let mut header = PppoeHeader::new_discovery(pppoe_code::PADI, 0);
+            header.version = value;
+            let expected = ValueError::U8TooLarge{
+                value,
+                max: PppoeHeader::MAX_VERSION,
+                field: ErrorField::PppoeVersion
+            };
+            assert_eq!(Some(expected.clone()), header.to_bytes().err());
+            assert_eq!(Some(expected), header.write(&mut Vec::new()).unwrap_err().value_error());
+        }
+        // type
+        {
+            let mut header = PppoeHeader::new_discovery(pppoe_code::PADI, 0);
+            header.pppoe_type = value;
+            let expected = ValueError::U8TooLarge{
+                value,
+                max: PppoeHeader::MAX_VERSION,
+                field: ErrorField::PppoeType
+            };
+            assert_eq!(Some(expected), header.to_bytes().err());
+        }
+    }
+}
+
+#[test]
+fn constants() {
+    use pppoe_code::*;
+    assert_eq!(0x00, SESSION_DATA);
+    assert_eq!(0x07, PADO);
+    assert_eq!(0x09, PADI);
+    assert_eq!(0x19, PADR);
+    assert_eq!(0x65, PADS);
+    assert_eq!(0xa7, PADT);
+}
+
+#[test]
+fn new() {
+    assert_eq!(
+        PppoeHeader {
+            version: 1,
+            pppoe_type: 1,
+            code: pppoe_code::SESSION_DATA,
+            session_id: 0x1234,
+            payload_length: 2,
+            ppp_protocol: Some(ppp_protocol::IPV4),
+        },
+        PppoeHeader::new_session(0x1234, ppp_protocol::IPV4)
+    );
+    assert_eq!(
+        PppoeHeader {
+            version: 1,
+            pppoe_type: 1,
+            code: pppoe_code::PADS,
+            session_id: 0x1234,
+            payload_length: 0,
+            ppp_protocol: None,
+        },
+        PppoeHeader::new_discovery(pppoe_code::PADS, 0x1234)
+    );
+    assert_eq!(8, PppoeHeader::new_session(0, ppp_protocol::IPV4).header_len());
+    assert_eq!(6, PppoeHeader::new_discovery(pppoe_code::PADI, 0).header_len());
+}
+
+#[test]
+fn set_payload_len() {
+    // session data (includes the ppp protocol field)
+    {
+        let mut header = PppoeHeader::new_session(0, ppp_protocol::IPV4);
+        header.set_payload_len(0).unwrap();
+        assert_eq!(2, header.payload_length);
+        header.set_payload_len(usize::from(u16::MAX) - 2).unwrap();
+        assert_eq!(u16::MAX, header.payload_length);
+        assert_eq!(
+            Err(ValueError::PppoePayloadLengthTooLarge(usize::from(u16::MAX) - 1)),
+            header.set_payload_len(usize::from(u16::MAX) - 1)
+        );
+        assert_eq!(u16::MAX, header.payload_length);
+    }
+    // discovery
+    {
+        let mut header = PppoeHeader::new_discovery(pppoe_code::PADI, 0);
+        header.set_payload_len(usize::from(u16::MAX)).unwrap();
+        assert_eq!(u16::MAX, header.payload_length);
+        assert_eq!(
+            Err(ValueError::PppoePayloadLengthTooLarge(usize::from(u16::MAX) + 1)),
+            header.set_payload_len(usize::from(u16::MAX) + 1)
+        );
+    }
+}
+
+#[test]
+fn ether_type() {
+    let tests = [
+        (ppp_protocol::IPV4, Some(ether_type::IPV4)),
+        (ppp_protocol::IPV6, Some(ether_type::IPV6)),
+        (ppp_protocol::MPLS_UNICAST, Some(ether_type::MPLS_UNICAST)),
+        (ppp_protocol::MPLS_MULTICAST, Some(ether_type::MPLS_MULTICAST)),
+        (ppp_protocol::LCP, None),
+    ];
+    for (protocol, expected) in tests {
+        let header = PppoeHeader::new_session(0, protocol);
+        assert_eq!(expected, header.ether_type());
+        let bytes = header.to_bytes().unwrap();
+        assert_eq!(expected, PppoeHeaderSlice::from_slice(&bytes).unwrap().ether_type());
+    }
+    assert_eq!(None, PppoeHeader::new_discovery(pppoe_code::PADI, 0).ether_type());
+}
+
+#[test]
+fn from_slice_errors() {
+    let bytes = PppoeHeader::new_session(1, ppp_protocol::IPV4).to_bytes().unwrap();
+
+    // length
+    for len in 0..bytes.len() {
+        let expected = if len < PppoeHeader::MIN_LEN { PppoeHeader::MIN_LEN } else { bytes.len() };
+        assert_matches!(
+            PppoeHeader::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(l)) if l == expected
+        );
+        assert_matches!(
+            PppoeHeader::read(&mut Cursor::new(&bytes[..len])),
+            Err(ReadError::IoError(_))
+        );
+    }
+    // discovery packets have no ppp protocol field
+    {
+        let mut bytes = bytes.clone();
+        bytes[1] = pppoe_code::PADT;
+        let (header, rest) = PppoeHeader::from_slice(&bytes[..6]).unwrap();
+        assert_eq!(None, header.ppp_protocol);
+        assert!(rest.is_empty());
+    }
+    // version & type
+    for (first, version, pppoe_type) in [(0x01, 0, 1), (0x21, 2, 1), (0x10, 1, 0), (0x1f, 1, 0xf)] {
+        let mut bytes = bytes.clone();
+        bytes[0] = first;
+        assert_matches!(
+            PppoeHeaderSlice::from_slice(&bytes),
+            Err(ReadError::PppoeUnsupportedVersion{ version: v, pppoe_type: t }) if v == version && t == pppoe_type
+        );
+        assert_matches!(
+            PppoeHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::PppoeUnsupportedVersion{ version: v, pppoe_type: t }) if v == version && t == pppoe_type
+        );
+    }
+    // session payload length too small for the ppp protocol field
+    for length in 0..2u16 {
+        let mut bytes = bytes.clone();
+        bytes[4..6].copy_from_slice(&length.to_be_bytes());
+        assert_matches!(
+            PppoeHeaderSlice::from_slice(&bytes),
+            Err(ReadError::PppoePayloadLengthTooSmall(l)) if l == length
+        );
+        assert_matches!(
+            PppoeHeader::read(&mut Cursor::new(&bytes)),
+            Err(ReadError::PppoePayloadLengthTooSmall(l)) if l == length
+        );
+    }
+}
+
+#[test]
+fn debug_clone_eq() {
+    let header = PppoeHeader::new_session(1, ppp_protocol::IPV6);
+    assert_eq!(
+        "PppoeHeader { version: 1, pppoe_type: 1, code: 0, session_id: 1, payload_length: 2, ppp_protocol: Some(87) }",
+        format!("{:?}", header)
+    );
+    assert_eq!(header, header.clone());
+
+    let bytes = header.to_bytes().unwrap();
+    let slice = PppoeHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(
+        format!("PppoeHeaderSlice {{ slice: {:?} }}", &bytes[..]),
+        format!("{:?}", slice)
+    );
+    assert_eq!(slice, slice.clone());
+}
+
+/// Returns an ethernet II frame containing a PPPoE header followed by the given payload.
+fn pppoe_frame(header: &PppoeHeader, payload: &[u8]) -> Vec<u8> {
+    let mut header = header.clone();
+    header.set_payload_len(payload.len()).unwrap();
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [1, 2, 3, 4, 5, 6],
+        destination: [7, 8, 9, 10, 11, 12],
+        ether_type: if header.ppp_protocol.is_some() {
+            ether_type::PPPOE_SESSION
+        } else {
+            ether_type::PPPOE_DISCOVERY
+        },
+    }.write(&mut packet).unwrap();
+    header.write(&mut packet).unwrap();
+    packet.extend_from_slice(payload);
+    packet
+}
+
+#[test]
+fn sliced_packet_session() {
+    let mut ipv4 = Vec::new();
+    PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut ipv4, &[1, 2, 3, 4])
+        .unwrap();
+    let mut ipv6 = Vec::new();
+    PacketBuilder::ipv6([1; 16], [2; 16], 20)
+        .tcp(1234, 5678, 1, 1024)
+        .write(&mut ipv6, &[1, 2, 3, 4])
+        .unwrap();
+
+    for (protocol, ip) in [(ppp_protocol::IPV4, &ipv4), (ppp_protocol::IPV6, &ipv6)] {
+        let packet = pppoe_frame(&PppoeHeader::new_session(0x1234, protocol), ip);
+
+        // slicing
+        {
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            let pppoe = sliced.pppoe.as_ref().unwrap();
+            assert_eq!(0x1234, pppoe.session_id());
+            assert_eq!(Some(protocol), pppoe.ppp_protocol());
+            assert_eq!((ip.len() + 2) as u16, pppoe.payload_length());
+            assert!(sliced.ip.is_some());
+            assert!(sliced.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], sliced.payload);
+            assert_eq!(None, sliced.payload_ether_type());
+        }
+        // decoding
+        {
+            let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+            assert_eq!(Some(protocol), decoded.pppoe.unwrap().ppp_protocol);
+            assert!(decoded.ip.is_some());
+            assert!(decoded.transport.is_some());
+            assert_eq!(&[1, 2, 3, 4], decoded.payload);
+        }
+    }
+}
+
+#[test]
+fn sliced_packet_not_ip() {
+    let tests = [
+        PppoeHeader::new_session(0x1234, ppp_protocol::LCP),
+        PppoeHeader::new_discovery(pppoe_code::PADI, 0),
+    ];
+    for header in tests {
+        let packet = pppoe_frame(&header, &[1, 2, 3, 4]);
+
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(header.code, sliced.pppoe.as_ref().unwrap().code());
+        assert!(sliced.ip.is_none());
+        assert_eq!(&[1, 2, 3, 4], sliced.payload);
+        assert_eq!(None, sliced.payload_ether_type());
+
+        let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(header.code, decoded.pppoe.as_ref().unwrap().code);
+        assert!(decoded.ip.is_none());
+        assert_eq!(&[1, 2, 3, 4], decoded.payload);
+        assert_eq!(None, decoded.payload_ether_type());
+    }
+}
+
+#[test]
+fn sliced_packet_vlan() {
+    let mut ipv4 = Vec::new();
+    PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], 20)
+        .udp(1234, 5678)
+        .write(&mut ipv4, &[])
+        .unwrap();
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [1; 6],
+        destination: [2; 6],
+        ether_type: ether_type::VLAN_TAGGED_FRAME,
+    }.write(&mut packet).unwrap();
+    SingleVlanHeader {
+        vlan_identifier: 10,
+        ether_type: ether_type::PPPOE_SESSION,
+        ..Default::default()
+    }.write(&mut packet).unwrap();
+    let mut pppoe = PppoeHeader::new_session(1, ppp_protocol::IPV4);
+    pppoe.set_payload_len(ipv4.len()).unwrap();
+    pppoe.write(&mut packet).unwrap();
+    packet.extend_from_slice(&ipv4);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert!(sliced.vlan.is_some());
+    assert!(sliced.pppoe.is_some());
+    assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert!(decoded.vlan.is_some());
+    assert_eq!(Some(pppoe), decoded.pppoe);
+    assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let packet = pppoe_frame(&PppoeHeader::new_session(1, ppp_protocol::IPV4), &[]);
+
+    // offsets are relative to the start of the packet
+    let packet = &packet[..packet.len() - 1];
+    assert_matches!(
+        SlicedPacket::from_ethernet(packet),
+        Err(ReadError::UnexpectedEndOfSlice(22))
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice(packet),
+        Err(ReadError::UnexpectedEndOfSlice(8))
+    );
+
+    // error in the ip packet
+    let packet = pppoe_frame(&PppoeHeader::new_session(1, ppp_protocol::IPV4), &[0x45]);
+    assert_matches!(
+        SlicedPacket::from_ethernet(&packet),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+}
+
+#[test]
+fn repair() {
+    let mut headers = PacketHeaders {
+        link: Some(Ethernet2Header::default()),
+        pppoe: Some(PppoeHeader::new_session(7, ppp_protocol::LCP)),
+        ip: Some(IpHeader::Version6(
+            Ipv6Header {
+                source: [1; 16],
+                destination: [2; 16],
+                hop_limit: 20,
+                ..Default::default()
+            },
+            Default::default()
+        )),
+        transport: Some(TransportHeader::Udp(UdpHeader::default())),
+        payload: &[1, 2, 3, 4],
+        ..Default::default()
+    };
+    etherparse::repair::fix(&mut headers).unwrap();
+    let packet = etherparse::repair::to_bytes(&headers).unwrap();
+
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(ether_type::PPPOE_SESSION, decoded.link.unwrap().ether_type);
+    let pppoe = decoded.pppoe.unwrap();
+    assert_eq!(Some(ppp_protocol::IPV6), pppoe.ppp_protocol);
+    assert_eq!((2 + 40 + 8 + 4) as u16, pppoe.payload_length);
+    assert_eq!(&[1, 2, 3, 4], decoded.payload);
+    assert!(etherparse::roundtrip::check(&packet).unwrap().is_empty());
+
+    // discovery packets
+    let mut headers = PacketHeaders {
+        link: Some(Ethernet2Header::default()),
+        pppoe: Some(PppoeHeader::new_discovery(pppoe_code::PADI, 0)),
+        payload: &[1, 2, 3, 4],
+        ..Default::default()
+    };
+    etherparse::repair::fix(&mut headers).unwrap();
+    let packet = etherparse::repair::to_bytes(&headers).unwrap();
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(ether_type::PPPOE_DISCOVERY, decoded.link.unwrap().ether_type);
+    assert_eq!(4, decoded.pppoe.unwrap().payload_length);
+}
@@ -0,0 +1,298 @@
+use super::super::*;
+
+/// Ethernet II frame with an ipv4 & udp header (without FCS).
+fn frame(payload_len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut result, &vec![0xab; payload_len])
+        .unwrap();
+    result
+}
+
+mod preemption_error {
+    use super::*;
+
+    #[test]
+    fn display() {
+        use PreemptionError::*;
+        assert_eq!(
+            "PreemptionError: Unexpected end of slice. The given slice contained less then minimum required 12 bytes.",
+            format!("{}", UnexpectedEndOfSlice(12))
+        );
+        assert_eq!(
+            "PreemptionError: Unknown start mPacket delimiter 0x12.",
+            format!("{}", UnknownSmd(0x12))
+        );
+        assert_eq!(
+            "PreemptionError: Unknown fragment count 0x13.",
+            format!("{}", UnknownFragCount(0x13))
+        );
+        assert_eq!(
+            "PreemptionError: Received a continuation fragment of the frame 2 without a start fragment.",
+            format!("{}", MissingStart{ frame: 2 })
+        );
+        assert_eq!(
+            "PreemptionError: Unexpected fragment count 3 of the frame 1 (expected 0).",
+            format!("{}", UnexpectedFragCount{ frame: 1, expected: 0, actual: 3 })
+        );
+        assert_eq!(
+            "PreemptionError: The CRC of the mPacket matches neither the FCS nor the mCRC.",
+            format!("{}", CrcMismatch)
+        );
+    }
+
+    #[test]
+    fn source() {
+        use std::error::Error;
+        assert!(PreemptionError::CrcMismatch.source().is_none());
+    }
+}
+
+mod mpacket_slice {
+    use super::*;
+
+    #[test]
+    fn express_frame() {
+        let frame = frame(20);
+        let mpacket = MPacketSlice::express_frame(&frame);
+        assert_eq!(&[0x55;7], &mpacket[..7]);
+        assert_eq!(smd::EXPRESS, mpacket[7]);
+        assert_eq!(&frame[..], &mpacket[8..mpacket.len() - 4]);
+
+        let slice = MPacketSlice::from_slice(&mpacket).unwrap();
+        assert_eq!(&mpacket[..], slice.slice());
+        assert_eq!(MPacketKind::Express, slice.kind());
+        assert_eq!(&frame[..], slice.data());
+        assert!(slice.is_complete_frame());
+        assert_eq!(
+            u32::from_le_bytes([
+                mpacket[mpacket.len() - 4],
+                mpacket[mpacket.len() - 3],
+                mpacket[mpacket.len() - 2],
+                mpacket[mpacket.len() - 1],
+            ]),
+            slice.crc()
+        );
+
+        // modified data
+        let mut mpacket = mpacket.clone();
+        mpacket[10] ^= 1;
+        assert!(!MPacketSlice::from_slice(&mpacket).unwrap().is_complete_frame());
+    }
+
+    #[test]
+    fn fragment_frame() {
+        let frame = frame(100);
+        let fragments = MPacketSlice::fragment_frame(&frame, 6, 32);
+        assert_eq!(frame.len().div_ceil(32), fragments.len());
+        for (index, fragment) in fragments.iter().enumerate() {
+            let slice = MPacketSlice::from_slice(fragment).unwrap();
+            let start = index*32;
+            let end = (start + 32).min(frame.len());
+            assert_eq!(&frame[start..end], slice.data());
+            if 0 == index {
+                assert_eq!(&[0x55;7], &fragment[..7]);
+                assert_eq!(MPacketKind::Start{ frame: 2 }, slice.kind());
+            } else {
+                assert_eq!(&[0x55;6], &fragment[..6]);
+                assert_eq!(
+                    MPacketKind::Continuation{ frame: 2, frag_count: ((index - 1) % 4) as u8 },
+                    slice.kind()
+                );
+            }
+            assert!(!slice.is_complete_frame());
+        }
+
+        // frame that fits into one fragment
+        let fragments = MPacketSlice::fragment_frame(&frame, 0, frame.len());
+        assert_eq!(1, fragments.len());
+        assert!(MPacketSlice::from_slice(&fragments[0]).unwrap().is_complete_frame());
+
+        // zero fragment length
+        assert_eq!(3, MPacketSlice::fragment_frame(&[1,2,3], 0, 0).len());
+    }
+
+    #[test]
+    fn verify_respond() {
+        for (value, expected) in [(smd::VERIFY, MPacketKind::Verify), (smd::RESPOND, MPacketKind::Respond)] {
+            let mut data = vec![0x55;7];
+            data.push(value);
+            data.extend_from_slice(&[0;64]);
+            let slice = MPacketSlice::from_slice(&data).unwrap();
+            assert_eq!(expected, slice.kind());
+            assert_eq!(60, slice.data().len());
+            assert!(!slice.is_complete_frame());
+        }
+    }
+
+    #[test]
+    fn from_slice_shortened_preamble() {
+        let frame = frame(0);
+        let mpacket = MPacketSlice::express_frame(&frame);
+        for skip in 0..8 {
+            let slice = MPacketSlice::from_slice(&mpacket[skip..]).unwrap();
+            assert_eq!(MPacketKind::Express, slice.kind());
+            assert_eq!(&frame[..], slice.data());
+        }
+    }
+
+    #[test]
+    fn from_slice_errors() {
+        use PreemptionError::*;
+        let fragments = MPacketSlice::fragment_frame(&frame(40), 0, 40);
+
+        // start fragment
+        for len in 0..8 {
+            assert_matches!(MPacketSlice::from_slice(&fragments[0][..len]), Err(UnexpectedEndOfSlice(_)));
+        }
+        assert_eq!(Err(UnexpectedEndOfSlice(12)), MPacketSlice::from_slice(&fragments[0][..11]));
+        assert!(MPacketSlice::from_slice(&fragments[0][..12]).is_ok());
+
+        // continuation fragment
+        assert_eq!(Err(UnexpectedEndOfSlice(12)), MPacketSlice::from_slice(&fragments[1][..11]));
+        assert!(MPacketSlice::from_slice(&fragments[1][..12]).is_ok());
+        {
+            let mut data = fragments[1].clone();
+            data[7] = 0x12;
+            assert_eq!(Err(UnknownFragCount(0x12)), MPacketSlice::from_slice(&data));
+        }
+
+        // unknown smd
+        {
+            let mut data = fragments[0].clone();
+            data[7] = 0x12;
+            assert_eq!(Err(UnknownSmd(0x12)), MPacketSlice::from_slice(&data));
+        }
+    }
+}
+
+mod preemption_reassembler {
+    use super::*;
+
+    fn push(reassembler: &mut PreemptionReassembler, mpacket: &[u8]) -> Result<Option<Vec<u8>>, PreemptionError> {
+        reassembler.push(&MPacketSlice::from_slice(mpacket).unwrap())
+    }
+
+    #[test]
+    fn reassemble() {
+        let preemptable = frame(200);
+        let express = frame(10);
+        let fragments = MPacketSlice::fragment_frame(&preemptable, 3, 40);
+        assert!(fragments.len() > 5);
+
+        let mut reassembler = PreemptionReassembler::new();
+        for (index, fragment) in fragments.iter().enumerate() {
+            // interleave express frames
+            assert_eq!(
+                Some(express.clone()),
+                push(&mut reassembler, &MPacketSlice::express_frame(&express)).unwrap()
+            );
+            if index + 1 < fragments.len() {
+                assert_eq!(None, push(&mut reassembler, fragment).unwrap());
+                assert!(reassembler.is_pending(3));
+            } else {
+                assert_eq!(Some(preemptable.clone()), push(&mut reassembler, fragment).unwrap());
+                assert!(!reassembler.is_pending(3));
+            }
+        }
+        let decoded = SlicedPacket::from_ethernet(&preemptable).unwrap();
+        assert_eq!(&[0xab;200][..], decoded.payload);
+    }
+
+    #[test]
+    fn interleaved_frames() {
+        let first = frame(50);
+        let second = frame(100);
+        let first_fragments = MPacketSlice::fragment_frame(&first, 0, 64);
+        let second_fragments = MPacketSlice::fragment_frame(&second, 1, 64);
+
+        let mut reassembler = PreemptionReassembler::new();
+        assert_eq!(None, push(&mut reassembler, &first_fragments[0]).unwrap());
+        assert_eq!(None, push(&mut reassembler, &second_fragments[0]).unwrap());
+        assert_eq!(None, push(&mut reassembler, &second_fragments[1]).unwrap());
+        assert_eq!(Some(first.clone()), push(&mut reassembler, &first_fragments[1]).unwrap());
+        assert_eq!(Some(second.clone()), push(&mut reassembler, &second_fragments[2]).unwrap());
+    }
+
+    #[test]
+    fn complete_start() {
+        let frame = frame(20);
+        let mut reassembler = PreemptionReassembler::new();
+        let fragments = MPacketSlice::fragment_frame(&frame, 2, 1500);
+        assert_eq!(Some(frame), push(&mut reassembler, &fragments[0]).unwrap());
+        assert!(!reassembler.is_pending(2));
+    }
+
+    #[test]
+    fn verify_respond() {
+        let mut data = vec![0x55;7];
+        data.push(smd::VERIFY);
+        data.extend_from_slice(&[0;64]);
+        let mut reassembler = PreemptionReassembler::new();
+        assert_eq!(None, push(&mut reassembler, &data).unwrap());
+        data[7] = smd::RESPOND;
+        assert_eq!(None, push(&mut reassembler, &data).unwrap());
+    }
+
+    #[test]
+    fn errors() {
+        use PreemptionError::*;
+        let frame = frame(100);
+        let fragments = MPacketSlice::fragment_frame(&frame, 1, 40);
+
+        // continuation without start
+        {
+            let mut reassembler = PreemptionReassembler::new();
+            assert_eq!(Err(MissingStart{ frame: 1 }), push(&mut reassembler, &fragments[1]));
+        }
+        // missing continuation fragment
+        {
+            let mut reassembler = PreemptionReassembler::new();
+            push(&mut reassembler, &fragments[0]).unwrap();
+            assert_eq!(
+                Err(UnexpectedFragCount{ frame: 1, expected: 0, actual: 1 }),
+                push(&mut reassembler, &fragments[2])
+            );
+            assert!(!reassembler.is_pending(1));
+        }
+        // crc mismatch
+        {
+            let mut reassembler = PreemptionReassembler::new();
+            push(&mut reassembler, &fragments[0]).unwrap();
+            let mut data = fragments[1].clone();
+            data[10] ^= 1;
+            assert_eq!(Err(CrcMismatch), push(&mut reassembler, &data));
+            assert!(!reassembler.is_pending(1));
+
+            let mut data = fragments[0].clone();
+            data[10] ^= 1;
+            assert_eq!(Err(CrcMismatch), push(&mut reassembler, &data));
+
+            let mut data = MPacketSlice::express_frame(&frame);
+            data[10] ^= 1;
+            assert_eq!(Err(CrcMismatch), push(&mut reassembler, &data));
+        }
+        // restart & clear
+        {
+            let mut reassembler = PreemptionReassembler::new();
+            push(&mut reassembler, &fragments[0]).unwrap();
+            push(&mut reassembler, &fragments[1]).unwrap();
+            // a new start fragment discards the pending frame
+            push(&mut reassembler, &fragments[0]).unwrap();
+            assert_eq!(None, push(&mut reassembler, &fragments[1]).unwrap());
+            assert!(reassembler.is_pending(1));
+            reassembler.clear();
+            assert!(!reassembler.is_pending(1));
+        }
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let reassembler = PreemptionReassembler::new();
+        assert_eq!(reassembler, reassembler.clone());
+        assert_eq!(reassembler, PreemptionReassembler::default());
+        assert!(format!("{:?}", reassembler).starts_with("PreemptionReassembler"));
+    }
+}
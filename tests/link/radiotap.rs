@@ -0,0 +1,163 @@
+use super::super::*;
+
+/// Radiotap header with the tsft, flags, rate, channel & antenna signal fields.
+fn header_bytes(flags: u8) -> Vec<u8> {
+    let mut result = vec![
+        0, 0, 24, 0,
+        0x2f, 0, 0, 0,
+    ];
+    result.extend_from_slice(&0x0102030405060708u64.to_le_bytes());
+    result.extend_from_slice(&[flags, 0x0c]);
+    result.extend_from_slice(&5180u16.to_le_bytes());
+    result.extend_from_slice(&0x0140u16.to_le_bytes());
+    result.extend_from_slice(&[0xd8, 0]);
+    result
+}
+
+#[test]
+fn from_slice() {
+    let bytes = header_bytes(radiotap_flags::SHORT_PREAMBLE);
+    let header = RadiotapHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..], header.slice());
+    assert_eq!(0, header.version());
+    assert_eq!(24, header.header_length());
+    assert_eq!(0x2f, header.present_flags());
+    assert_eq!(&bytes[4..8], header.present_flags_slice());
+
+    assert_eq!(Some(0x0102030405060708), header.tsft());
+    assert_eq!(Some(radiotap_flags::SHORT_PREAMBLE), header.flags());
+    assert_eq!(Some(0x0c), header.rate());
+    assert_eq!(Some((5180, 0x0140)), header.channel());
+    assert_eq!(Some(-40), header.dbm_antenna_signal());
+    assert_eq!(None, header.dbm_antenna_noise());
+
+    use radiotap_field::*;
+    let fields: Vec<_> = header.fields().collect();
+    assert_eq!(
+        vec![
+            RadiotapField{ kind: TSFT, data: &bytes[8..16] },
+            RadiotapField{ kind: FLAGS, data: &bytes[16..17] },
+            RadiotapField{ kind: RATE, data: &bytes[17..18] },
+            RadiotapField{ kind: CHANNEL, data: &bytes[18..22] },
+            RadiotapField{ kind: DBM_ANTENNA_SIGNAL, data: &bytes[22..23] },
+        ],
+        fields
+    );
+}
+
+#[test]
+fn fields_extended_present_flags() {
+    use radiotap_field::*;
+
+    // two namespaces with the antenna field each (as used for per antenna signals)
+    let bytes = [
+        0, 0, 16, 0,
+        0x20, 0, 0, 0xa0, // dbm antenna signal, radiotap namespace & ext
+        0x20, 0x08, 0, 0, // dbm antenna signal & antenna
+        0xd0, // signal
+        0xc8, 1, // signal & antenna of the second namespace
+        0,
+    ];
+    let header = RadiotapHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[4..12], header.present_flags_slice());
+    let fields: Vec<_> = header.fields().collect();
+    assert_eq!(
+        vec![
+            RadiotapField{ kind: DBM_ANTENNA_SIGNAL, data: &[0xd0] },
+            RadiotapField{ kind: DBM_ANTENNA_SIGNAL, data: &[0xc8] },
+            RadiotapField{ kind: ANTENNA, data: &[1] },
+        ],
+        fields
+    );
+    // the first field is returned by the accessors
+    assert_eq!(Some(-48), header.dbm_antenna_signal());
+}
+
+#[test]
+fn fields_vendor_namespace() {
+    use radiotap_field::*;
+
+    let bytes = [
+        0, 0, 24, 0,
+        0x02, 0, 0, 0xc0, // flags, vendor namespace & ext
+        0xff, 0, 0, 0, // vendor defined fields
+        0x10, // flags
+        0, // padding
+        0x00, 0x11, 0x22, 0x01, 3, 0, // oui, sub namespace & skip length
+        1, 2, 3, // vendor data
+        0,
+    ];
+    let header = RadiotapHeaderSlice::from_slice(&bytes).unwrap();
+    let fields: Vec<_> = header.fields().collect();
+    assert_eq!(
+        vec![
+            RadiotapField{ kind: FLAGS, data: &[0x10] },
+            RadiotapField{ kind: VENDOR_NAMESPACE, data: &bytes[14..23] },
+        ],
+        fields
+    );
+}
+
+#[test]
+fn fields_tlv() {
+    let bytes = [
+        0, 0, 16, 0,
+        0x02, 0, 0, 0x10, // flags & tlv
+        0x10, // flags
+        0, 0, 0, // padding
+        1, 2, 3, 4, // tlvs
+    ];
+    let header = RadiotapHeaderSlice::from_slice(&bytes).unwrap();
+    let fields: Vec<_> = header.fields().collect();
+    assert_eq!(
+        vec![
+            RadiotapField{ kind: radiotap_field::FLAGS, data: &[0x10] },
+            RadiotapField{ kind: radiotap_field::TLV, data: &[1, 2, 3, 4] },
+        ],
+        fields
+    );
+}
+
+#[test]
+fn fields_end() {
+    // fields exceeding the header length
+    let bytes = [0, 0, 9, 0, 0x0a, 0, 0, 0, 0x10];
+    let header = RadiotapHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(vec![radiotap_field::FLAGS], header.fields().map(|f| f.kind).collect::<Vec<_>>());
+    assert_eq!(None, header.channel());
+
+    // fields in the extended radiotap namespace are not known
+    let bytes = [0, 0, 12, 0, 0, 0, 0, 0x80, 0x01, 0, 0, 0];
+    let header = RadiotapHeaderSlice::from_slice(&bytes).unwrap();
+    assert_eq!(0, header.fields().count());
+}
+
+#[test]
+fn from_slice_errors() {
+    let bytes = header_bytes(0);
+    for len in 0..8 {
+        assert_matches!(
+            RadiotapHeaderSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(8))
+        );
+    }
+    for len in 8..bytes.len() {
+        assert_matches!(
+            RadiotapHeaderSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(24))
+        );
+    }
+    assert_matches!(
+        RadiotapHeaderSlice::from_slice(&[1, 0, 8, 0, 0, 0, 0, 0]),
+        Err(ReadError::RadiotapUnsupportedVersion(1))
+    );
+    assert_matches!(
+        RadiotapHeaderSlice::from_slice(&[0, 0, 7, 0, 0, 0, 0, 0]),
+        Err(ReadError::RadiotapLengthTooSmall(7))
+    );
+    // present flags exceeding the header length
+    assert_matches!(
+        RadiotapHeaderSlice::from_slice(&[0, 0, 8, 0, 0, 0, 0, 0x80, 0, 0, 0, 0]),
+        Err(ReadError::RadiotapLengthTooSmall(8))
+    );
+}
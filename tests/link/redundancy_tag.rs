@@ -0,0 +1,300 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+prop_compose! {
+    fn redundancy_tag_any()(
+        sequence_number in any::<u16>(),
+        ether_type in any::<u16>(),
+    ) -> RedundancyTag {
+        RedundancyTag {
+            sequence_number,
+            ether_type,
+        }
+    }
+}
+
+mod redundancy_tag_header {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        assert_eq!(6, RedundancyTag::SERIALIZED_SIZE);
+        assert_eq!(0xF1C1, ether_type::REDUNDANCY_TAG);
+    }
+
+    proptest!{
+        #[test]
+        fn from_slice(
+            input in redundancy_tag_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20)
+        ) {
+            // serialize
+            let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len() + dummy_data.len());
+            input.write(&mut buffer).unwrap();
+            buffer.extend(&dummy_data[..]);
+
+            // normal
+            {
+                let (result, rest) = RedundancyTag::from_slice(&buffer).unwrap();
+                assert_eq!(result, input);
+                assert_eq!(rest, &buffer[6..]);
+            }
+
+            // reserved bits are ignored
+            {
+                let mut modified = buffer.clone();
+                modified[0] = 0xff;
+                modified[1] = 0xff;
+                let (result, _) = RedundancyTag::from_slice(&modified).unwrap();
+                assert_eq!(result, input);
+            }
+
+            // slice length to small
+            for len in 0..6 {
+                assert_eq!(
+                    RedundancyTag::from_slice(&buffer[..len])
+                        .unwrap_err()
+                        .unexpected_end_of_slice_min_expected_size()
+                        .unwrap(),
+                    6
+                );
+            }
+        }
+    }
+
+    proptest!{
+        #[test]
+        fn from_bytes(input in redundancy_tag_any()) {
+            assert_eq!(input, RedundancyTag::from_bytes(input.to_bytes()));
+        }
+    }
+
+    proptest!{
+        #[test]
+        fn read(
+            input in redundancy_tag_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20)
+        ) {
+            // serialize
+            let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len() + dummy_data.len());
+            input.write(&mut buffer).unwrap();
+            buffer.extend(&dummy_data[..]);
+
+            // normal
+            {
+                let mut cursor = Cursor::new(&buffer);
+                let result = RedundancyTag::read(&mut cursor).unwrap();
+                assert_eq!(result, input);
+                assert_eq!(6, cursor.position());
+            }
+
+            // unexpexted eof
+            for len in 0..6 {
+                let mut cursor = Cursor::new(&buffer[0..len]);
+                assert!(RedundancyTag::read(&mut cursor).is_err());
+            }
+        }
+    }
+
+    proptest!{
+        #[test]
+        fn write_and_to_bytes(input in redundancy_tag_any()) {
+            let mut buffer = Vec::new();
+            input.write(&mut buffer).unwrap();
+
+            let seq_be = input.sequence_number.to_be_bytes();
+            let ether_type_be = input.ether_type.to_be_bytes();
+            let expected = [0, 0, seq_be[0], seq_be[1], ether_type_be[0], ether_type_be[1]];
+            assert_eq!(&expected[..], &buffer[..]);
+            assert_eq!(expected, input.to_bytes());
+            assert_eq!(6, input.header_len());
+        }
+    }
+
+    proptest!{
+        #[test]
+        fn write_error(input in redundancy_tag_any()) {
+            let mut buffer = [0u8;5];
+            let mut cursor = Cursor::new(&mut buffer[..]);
+            assert!(input.write(&mut cursor).is_err());
+        }
+    }
+
+    proptest!{
+        #[test]
+        fn clone_eq_debug(input in redundancy_tag_any()) {
+            assert_eq!(input, input.clone());
+            assert_eq!(
+                &format!(
+                    "RedundancyTag {{ sequence_number: {}, ether_type: {} }}",
+                    input.sequence_number,
+                    input.ether_type,
+                ),
+                &format!("{:?}", input)
+            );
+        }
+    }
+}
+
+mod redundancy_tag_slice {
+    use super::*;
+
+    proptest!{
+        #[test]
+        fn from_slice(
+            input in redundancy_tag_any(),
+            dummy_data in proptest::collection::vec(any::<u8>(), 0..20)
+        ) {
+            let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len() + dummy_data.len());
+            input.write(&mut buffer).unwrap();
+            buffer.extend(&dummy_data[..]);
+
+            // normal
+            {
+                let slice = RedundancyTagSlice::from_slice(&buffer).unwrap();
+                assert_eq!(&buffer[..6], slice.slice());
+                assert_eq!(input.sequence_number, slice.sequence_number());
+                assert_eq!(input.ether_type, slice.ether_type());
+                assert_eq!(input, slice.to_header());
+                assert_eq!(slice, slice.clone());
+            }
+
+            // slice length to small
+            for len in 0..6 {
+                assert_matches!(
+                    RedundancyTagSlice::from_slice(&buffer[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(6))
+                );
+            }
+        }
+    }
+}
+
+mod sequence_recovery {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert!(SequenceRecovery::new(0).is_none());
+        assert!(SequenceRecovery::new(SequenceRecovery::MAX_HISTORY_LENGTH + 1).is_none());
+        for history_length in [1, 2, 32, SequenceRecovery::MAX_HISTORY_LENGTH] {
+            let recovery = SequenceRecovery::new(history_length).unwrap();
+            assert_eq!(history_length, recovery.history_length());
+            assert_eq!(None, recovery.recovery_sequence_number());
+            assert_eq!(0, recovery.passed_packets());
+            assert_eq!(0, recovery.discarded_packets());
+            assert_eq!(0, recovery.rogue_packets());
+        }
+    }
+
+    #[test]
+    fn duplicates() {
+        let mut recovery = SequenceRecovery::new(4).unwrap();
+
+        // first frame is always taken
+        assert!(recovery.accept(100));
+        assert_eq!(Some(100), recovery.recovery_sequence_number());
+        assert!(!recovery.accept(100));
+
+        // in order
+        assert!(recovery.accept(101));
+        assert!(!recovery.accept(101));
+        assert_eq!(Some(101), recovery.recovery_sequence_number());
+
+        // gap & late frames within the history
+        assert!(recovery.accept(104));
+        assert!(recovery.accept(102));
+        assert!(!recovery.accept(102));
+        assert!(recovery.accept(103));
+        assert!(!recovery.accept(101));
+        assert_eq!(Some(104), recovery.recovery_sequence_number());
+
+        assert_eq!(5, recovery.passed_packets());
+        assert_eq!(4, recovery.discarded_packets());
+        assert_eq!(0, recovery.rogue_packets());
+    }
+
+    #[test]
+    fn rogue() {
+        let mut recovery = SequenceRecovery::new(4).unwrap();
+        assert!(recovery.accept(100));
+
+        // outside of the history window (in both directions)
+        assert!(!recovery.accept(104));
+        assert!(!recovery.accept(96));
+        assert!(!recovery.accept(100 + 0x8000));
+        assert_eq!(Some(100), recovery.recovery_sequence_number());
+
+        // edge of the window
+        assert!(recovery.accept(103));
+        assert!(recovery.accept(101));
+
+        assert_eq!(3, recovery.passed_packets());
+        assert_eq!(3, recovery.discarded_packets());
+        assert_eq!(3, recovery.rogue_packets());
+    }
+
+    #[test]
+    fn wrap_around() {
+        let mut recovery = SequenceRecovery::new(SequenceRecovery::MAX_HISTORY_LENGTH).unwrap();
+        assert!(recovery.accept(u16::MAX - 1));
+        assert!(recovery.accept(1));
+        assert!(recovery.accept(u16::MAX));
+        assert!(recovery.accept(0));
+        assert!(!recovery.accept(u16::MAX - 1));
+        assert!(!recovery.accept(1));
+        assert_eq!(Some(1), recovery.recovery_sequence_number());
+
+        // maximum history
+        assert!(recovery.accept(1 + 63));
+        assert!(!recovery.accept(0));
+        assert!(!recovery.accept(u16::MAX));
+        assert!(recovery.accept(1 + 1));
+    }
+
+    #[test]
+    fn reset() {
+        let mut recovery = SequenceRecovery::new(4).unwrap();
+        assert!(recovery.accept(100));
+        assert!(!recovery.accept(200));
+
+        recovery.reset();
+        assert_eq!(None, recovery.recovery_sequence_number());
+        assert!(recovery.accept(200));
+        assert!(!recovery.accept(200));
+        assert!(recovery.accept(199));
+
+        // counters are kept
+        assert_eq!(3, recovery.passed_packets());
+        assert_eq!(2, recovery.discarded_packets());
+        assert_eq!(1, recovery.rogue_packets());
+    }
+
+    proptest!{
+        #[test]
+        fn replicated_stream(
+            start in any::<u16>(),
+            count in 1..200u16,
+        ) {
+            // every frame is received twice, the copy of the second path shifted by one frame
+            let mut recovery = SequenceRecovery::new(8).unwrap();
+            let mut passed = Vec::new();
+            for i in 0..=count {
+                if i < count {
+                    let seq = start.wrapping_add(i);
+                    if recovery.accept(seq) {
+                        passed.push(seq);
+                    }
+                }
+                if i > 0 {
+                    assert!(!recovery.accept(start.wrapping_add(i - 1)));
+                }
+            }
+            let expected: Vec<u16> = (0..count).map(|i| start.wrapping_add(i)).collect();
+            assert_eq!(expected, passed);
+            assert_eq!(u64::from(count), recovery.discarded_packets());
+            assert_eq!(0, recovery.rogue_packets());
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use super::super::*;
+
+/// Fields of a packet of the test connection.
+#[derive(Clone)]
+struct Segment {
+    identification: u16,
+    sequence_number: u32,
+    acknowledgment_number: u32,
+    window_size: u16,
+    psh: bool,
+    urgent_pointer: Option<u16>,
+    payload: Vec<u8>,
+}
+
+impl Segment {
+    fn new(sequence_number: u32, payload: &[u8]) -> Segment {
+        Segment {
+            identification: 1,
+            sequence_number,
+            acknowledgment_number: 1000,
+            window_size: 1024,
+            psh: false,
+            urgent_pointer: None,
+            payload: payload.to_vec(),
+        }
+    }
+
+    fn to_packet(&self, source_port: u16) -> Vec<u8> {
+        let mut tcp = TcpHeader::new(source_port, 23, self.sequence_number, self.window_size);
+        tcp.ack = true;
+        tcp.acknowledgment_number = self.acknowledgment_number;
+        tcp.psh = self.psh;
+        if let Some(urgent_pointer) = self.urgent_pointer {
+            tcp.urg = true;
+            tcp.urgent_pointer = urgent_pointer;
+        }
+        let mut ip = Ipv4Header::new(
+            (tcp.header_len() as usize + self.payload.len()) as u16,
+            64,
+            ip_number::TCP,
+            [192, 168, 1, 1],
+            [192, 168, 1, 2]
+        );
+        ip.identification = self.identification;
+        tcp.checksum = tcp.calc_checksum_ipv4(&ip, &self.payload).unwrap();
+
+        let mut result = Vec::new();
+        ip.write(&mut result).unwrap();
+        tcp.write(&mut result).unwrap();
+        result.extend_from_slice(&self.payload);
+        result
+    }
+}
+
+/// Compresses & decompresses the packet & returns the type & compressed length.
+fn transmit(compressor: &mut VjCompressor, decompressor: &mut VjDecompressor, packet: &[u8]) -> (VjPacketType, usize) {
+    let (packet_type, data) = compressor.compress(packet);
+    assert_eq!(packet, &decompressor.decompress(packet_type, &data).unwrap()[..]);
+    (packet_type, data.len())
+}
+
+#[test]
+fn packet_type_ppp_protocol() {
+    use VjPacketType::*;
+    for (packet_type, protocol) in [
+        (Ip, ppp_protocol::IPV4),
+        (UncompressedTcp, ppp_protocol::VJ_UNCOMPRESSED_TCP),
+        (CompressedTcp, ppp_protocol::VJ_COMPRESSED_TCP),
+    ] {
+        assert_eq!(protocol, packet_type.ppp_protocol());
+        assert_eq!(Some(packet_type), VjPacketType::from_ppp_protocol(protocol));
+    }
+    assert_eq!(None, VjPacketType::from_ppp_protocol(ppp_protocol::IPV6));
+}
+
+#[test]
+fn compress_decompress() {
+    use VjPacketType::*;
+
+    let mut compressor = VjCompressor::new(15);
+    let mut decompressor = VjDecompressor::new(15);
+    assert_eq!(15, compressor.max_slot_id());
+    assert_eq!(15, decompressor.max_slot_id());
+
+    // first packet of the connection
+    let mut segment = Segment::new(100, &[1, 2, 3, 4]);
+    assert_eq!((UncompressedTcp, 44), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // unidirectional data (special case, only change mask & checksum)
+    segment.identification += 1;
+    segment.sequence_number += 4;
+    assert_eq!((CompressedTcp, 3 + 4), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // echoed interactive traffic (special case)
+    segment.identification += 1;
+    segment.sequence_number += 4;
+    segment.acknowledgment_number += 4;
+    assert_eq!((CompressedTcp, 3 + 4), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // window, ack & sequence number deltas, push flag & ip id jump
+    segment.identification += 300;
+    segment.sequence_number += 1000;
+    segment.acknowledgment_number += 1;
+    segment.window_size -= 10;
+    segment.psh = true;
+    assert_eq!(
+        (CompressedTcp, 3 + 3 + 1 + 3 + 3 + 4),
+        transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234))
+    );
+
+    // urgent pointer & cleared push flag
+    segment.identification += 1;
+    segment.psh = false;
+    segment.urgent_pointer = Some(2);
+    assert_eq!((CompressedTcp, 3 + 1 + 4), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // urgent pointer changed without the urg flag
+    segment.identification += 1;
+    segment.urgent_pointer = None;
+    assert_eq!((UncompressedTcp, 44), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // pure ack followed by data with no other changes
+    segment.identification += 1;
+    segment.payload.clear();
+    assert_eq!((UncompressedTcp, 40), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+    segment.identification += 1;
+    segment.payload = vec![9; 10];
+    assert_eq!((CompressedTcp, 3 + 10), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // retransmission (no changes)
+    segment.identification += 1;
+    assert_eq!((UncompressedTcp, 50), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // sequence number delta too big
+    segment.identification += 1;
+    segment.sequence_number += 0x10000;
+    assert_eq!((UncompressedTcp, 50), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+
+    // changes that would be interpreted as special case
+    segment.identification += 1;
+    segment.sequence_number += 1;
+    segment.window_size += 1;
+    segment.urgent_pointer = Some(1);
+    assert_eq!((UncompressedTcp, 50), transmit(&mut compressor, &mut decompressor, &segment.to_packet(1234)));
+}
+
+#[test]
+fn compress_multiple_connections() {
+    use VjPacketType::*;
+
+    let mut compressor = VjCompressor::new(1);
+    let mut decompressor = VjDecompressor::new(1);
+    let mut segments = vec![Segment::new(100, &[1, 2]); 4];
+    let mut next = |port: u16| {
+        let segment = &mut segments[usize::from(port)];
+        let result = transmit(&mut compressor, &mut decompressor, &segment.to_packet(port));
+        segment.identification += 1;
+        segment.sequence_number += 2;
+        result
+    };
+
+    assert_eq!((UncompressedTcp, 42), next(1));
+    assert_eq!((UncompressedTcp, 42), next(2));
+    // slot id has to be sent as the last packet used a different slot
+    assert_eq!((CompressedTcp, 4 + 2), next(1));
+    assert_eq!((CompressedTcp, 3 + 2), next(1));
+    // the least recently used slot (port 2) gets replaced
+    assert_eq!((UncompressedTcp, 42), next(3));
+    assert_eq!((CompressedTcp, 4 + 2), next(1));
+    assert_eq!((UncompressedTcp, 42), next(2));
+}
+
+#[test]
+fn compress_without_slot_id_compression() {
+    let mut compressor = VjCompressor::new(15).with_slot_id_compression(false);
+    let mut decompressor = VjDecompressor::new(15);
+    let mut segment = Segment::new(100, &[1, 2]);
+    transmit(&mut compressor, &mut decompressor, &segment.to_packet(1));
+    segment.identification += 1;
+    segment.sequence_number += 2;
+    assert_eq!(
+        (VjPacketType::CompressedTcp, 4 + 2),
+        transmit(&mut compressor, &mut decompressor, &segment.to_packet(1))
+    );
+}
+
+#[test]
+fn compress_ip() {
+    let mut compressor = VjCompressor::new(15);
+
+    // udp
+    let mut udp = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 64)
+        .udp(1, 2)
+        .write(&mut udp, &[1, 2, 3])
+        .unwrap();
+    assert_eq!((VjPacketType::Ip, udp.clone()), compressor.compress(&udp));
+
+    // syn
+    let mut syn = Vec::new();
+    PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 64)
+        .tcp(1, 2, 3, 4)
+        .syn()
+        .write(&mut syn, &[])
+        .unwrap();
+    assert_eq!((VjPacketType::Ip, syn.clone()), compressor.compress(&syn));
+
+    // fragment
+    let mut fragment = Segment::new(100, &[1, 2]).to_packet(1);
+    fragment[6] |= 0x20;
+    assert_eq!((VjPacketType::Ip, fragment.clone()), compressor.compress(&fragment));
+
+    // too short
+    assert_eq!((VjPacketType::Ip, vec![0x45, 0]), compressor.compress(&[0x45, 0]));
+}
+
+#[test]
+fn decompress_ip() {
+    let mut decompressor = VjDecompressor::new(15);
+    assert_eq!(Ok(vec![1, 2, 3]), decompressor.decompress(VjPacketType::Ip, &[1, 2, 3]));
+}
+
+#[test]
+fn decompress_errors() {
+    use VjError::*;
+    use VjPacketType::*;
+
+    let mut compressor = VjCompressor::new(1);
+    let mut decompressor = VjDecompressor::new(1);
+    let mut segment = Segment::new(100, &[1, 2]);
+
+    // compressed packet without saved state
+    assert_eq!(Err(MissingState), decompressor.decompress(CompressedTcp, &[0x40, 0, 0, 0]));
+
+    // invalid slot ids
+    let (_, mut data) = compressor.compress(&segment.to_packet(1));
+    let id = data[9];
+    data[9] = 2;
+    assert_eq!(Err(InvalidSlotId(2)), decompressor.decompress(UncompressedTcp, &data));
+    assert_eq!(Err(InvalidSlotId(2)), decompressor.decompress(CompressedTcp, &[0x40, 2, 0, 0]));
+
+    // invalid headers
+    assert_eq!(Err(InvalidHeader), decompressor.decompress(UncompressedTcp, &data[..19]));
+    data[9] = id;
+    assert_eq!(Err(InvalidHeader), decompressor.decompress(UncompressedTcp, &data[..39]));
+
+    // truncated compressed headers
+    assert!(decompressor.decompress(UncompressedTcp, &data).is_ok());
+    assert!(!decompressor.is_tossing());
+    assert_eq!(Err(UnexpectedEndOfSlice(1)), decompressor.decompress(CompressedTcp, &[]));
+    assert_eq!(Err(UnexpectedEndOfSlice(3)), decompressor.decompress(CompressedTcp, &[0x40, 1]));
+    assert_eq!(Err(UnexpectedEndOfSlice(6)), decompressor.decompress(CompressedTcp, &[0x60, 1, 0, 0, 0]));
+
+    // too large
+    assert_eq!(
+        Err(PacketTooLarge(40 + 0x10000)),
+        decompressor.decompress(CompressedTcp, &[&[0x40, 1, 0, 0][..], &[0; 0x10000][..]].concat())
+    );
+
+    // packets without slot id are tossed after an error
+    segment.identification += 1;
+    segment.sequence_number += 2;
+    let (_, data) = compressor.compress(&segment.to_packet(1));
+    assert!(decompressor.is_tossing());
+    assert_eq!(Err(MissingState), decompressor.decompress(CompressedTcp, &data));
+
+    // till a packet with a slot id arrives
+    segment.identification += 1;
+    segment.sequence_number += 2;
+    let (_, data) = compressor.compress(&segment.to_packet(2));
+    assert!(decompressor.decompress(UncompressedTcp, &data).is_ok());
+    segment.identification += 1;
+    segment.sequence_number += 2;
+    let (_, data) = compressor.compress(&segment.to_packet(2));
+    assert!(decompressor.decompress(CompressedTcp, &data).is_ok());
+
+    // toss after a transmission error
+    decompressor.toss();
+    assert!(decompressor.is_tossing());
+    segment.identification += 1;
+    segment.sequence_number += 2;
+    let (_, data) = compressor.compress(&segment.to_packet(2));
+    assert_eq!(Err(MissingState), decompressor.decompress(CompressedTcp, &data));
+}
+
+#[test]
+fn error_display() {
+    use std::error::Error;
+    use VjError::*;
+
+    assert_eq!(
+        "VjError: Unexpected end of slice. The given slice contained less then minimum required 3 bytes.",
+        &format!("{}", UnexpectedEndOfSlice(3))
+    );
+    assert_eq!(
+        "VjError: Slot id 20 is bigger then the maximum slot id.",
+        &format!("{}", InvalidSlotId(20))
+    );
+    assert_eq!(
+        "VjError: No saved header for the compressed packet.",
+        &format!("{}", MissingState)
+    );
+    assert_eq!(
+        "VjError: The headers of the uncompressed packet are not valid ipv4 & tcp headers.",
+        &format!("{}", InvalidHeader)
+    );
+    assert_eq!(
+        "VjError: The decompressed packet length 65536 is bigger then the maximum ipv4 total length.",
+        &format!("{}", PacketTooLarge(65536))
+    );
+    assert!(MissingState.source().is_none());
+}
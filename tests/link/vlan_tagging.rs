@@ -223,6 +223,43 @@ mod single_vlan_header {
         assert_eq!(4, SingleVlanHeader::SERIALIZED_SIZE);
     }
 
+    proptest!{
+        #[test]
+        fn new(
+            priority_code_point in 0..=0x7u8,
+            drop_eligible_indicator in any::<bool>(),
+            vlan_identifier in 0..=0xfffu16,
+            ether_type in any::<u16>(),
+            bad_priority_code_point in 0x8..=u8::MAX,
+            bad_vlan_identifier in 0x1000..=u16::MAX,
+        ) {
+            use ErrorField::*;
+
+            // valid values
+            assert_eq!(
+                Ok(SingleVlanHeader {
+                    priority_code_point,
+                    drop_eligible_indicator,
+                    vlan_identifier,
+                    ether_type,
+                }),
+                SingleVlanHeader::new(priority_code_point, drop_eligible_indicator, vlan_identifier, ether_type)
+            );
+
+            // priority code point too large
+            assert_eq!(
+                Err(ValueError::U8TooLarge{ value: bad_priority_code_point, max: 0x7, field: VlanTagPriorityCodePoint }),
+                SingleVlanHeader::new(bad_priority_code_point, drop_eligible_indicator, vlan_identifier, ether_type)
+            );
+
+            // vlan identifier too large
+            assert_eq!(
+                Err(ValueError::U16TooLarge{ value: bad_vlan_identifier, max: 0xfff, field: VlanTagVlanId }),
+                SingleVlanHeader::new(priority_code_point, drop_eligible_indicator, bad_vlan_identifier, ether_type)
+            );
+        }
+    }
+
     proptest!{
         #[test]
         fn from_slice(
@@ -414,6 +451,20 @@ mod single_vlan_header {
         assert_eq!(0, actual.ether_type);
     }
 
+    proptest!{
+        #[test]
+        fn setters(input in vlan_single_any()) {
+            assert_eq!(
+                input,
+                SingleVlanHeader::default()
+                    .with_priority_code_point(input.priority_code_point)
+                    .with_drop_eligible_indicator(input.drop_eligible_indicator)
+                    .with_vlan_id(input.vlan_identifier)
+                    .with_ether_type(input.ether_type)
+            );
+        }
+    }
+
     proptest!{
         #[test]
         fn clone_eq(input in vlan_single_any()) {
@@ -656,6 +707,25 @@ mod double_vlan_header {
         assert_eq!(actual.inner, Default::default());
     }
 
+    proptest!{
+        #[test]
+        fn setters(input in vlan_double_any()) {
+            assert_eq!(
+                input,
+                DoubleVlanHeader::default()
+                    .with_outer(input.outer.clone())
+                    .with_inner(input.inner.clone())
+            );
+
+            let actual = input.clone()
+                .with_outer_vlan_id(input.inner.vlan_identifier)
+                .with_inner_vlan_id(input.outer.vlan_identifier);
+            assert_eq!(input.inner.vlan_identifier, actual.outer.vlan_identifier);
+            assert_eq!(input.outer.vlan_identifier, actual.inner.vlan_identifier);
+            assert_eq!(input.outer.ether_type, actual.outer.ether_type);
+        }
+    }
+
     proptest!{
         #[test]
         fn clone_eq(input in vlan_double_any()) {
@@ -0,0 +1,180 @@
+use super::*;
+
+use etherparse::names;
+
+/// Checks that the keys of a table are strictly increasing (required for
+/// the binary search of the lookups).
+fn assert_sorted<T: Ord + Copy + std::fmt::Debug>(table: &[(T, &str)]) {
+    for pair in table.windows(2) {
+        assert!(pair[0].0 < pair[1].0, "{:?} is not sorted before {:?}", pair[0].0, pair[1].0);
+    }
+    for (_, name) in table {
+        assert!(!name.is_empty());
+    }
+}
+
+#[test]
+fn tables_sorted() {
+    assert_sorted(names::ETHER_TYPES);
+    assert_sorted(names::IP_NUMBERS);
+    assert_sorted(names::TCP_PORTS);
+    assert_sorted(names::UDP_PORTS);
+    assert_sorted(names::ICMPV4_TYPES);
+    assert_sorted(names::ICMPV4_CODES);
+    assert_sorted(names::ICMPV6_TYPES);
+    assert_sorted(names::ICMPV6_CODES);
+}
+
+#[test]
+fn lookup_all_entries() {
+    for (value, name) in names::ETHER_TYPES {
+        assert_eq!(Some(*name), names::ether_type(*value));
+    }
+    for (value, name) in names::IP_NUMBERS {
+        assert_eq!(Some(*name), names::ip_number(*value));
+    }
+    for (port, name) in names::TCP_PORTS {
+        assert_eq!(Some(*name), names::tcp_port(*port));
+    }
+    for (port, name) in names::UDP_PORTS {
+        assert_eq!(Some(*name), names::udp_port(*port));
+    }
+    for (type_u8, name) in names::ICMPV4_TYPES {
+        assert_eq!(Some(*name), names::icmpv4_type(*type_u8));
+    }
+    for ((type_u8, code_u8), name) in names::ICMPV4_CODES {
+        assert_eq!(Some(*name), names::icmpv4_code(*type_u8, *code_u8));
+        // codes only exist for named types
+        assert!(names::icmpv4_type(*type_u8).is_some());
+    }
+    for (type_u8, name) in names::ICMPV6_TYPES {
+        assert_eq!(Some(*name), names::icmpv6_type(*type_u8));
+    }
+    for ((type_u8, code_u8), name) in names::ICMPV6_CODES {
+        assert_eq!(Some(*name), names::icmpv6_code(*type_u8, *code_u8));
+        assert!(names::icmpv6_type(*type_u8).is_some());
+    }
+}
+
+#[test]
+fn lookup() {
+    // known values
+    assert_eq!(Some("IPv4"), names::ether_type(ether_type::IPV4));
+    assert_eq!(Some("ARP"), names::ether_type(ether_type::ARP));
+    assert_eq!(Some("802.1Q"), names::ether_type(ether_type::VLAN_TAGGED_FRAME));
+    assert_eq!(Some("LLDP"), names::ether_type(ether_type::LLDP));
+    assert_eq!(Some("TCP"), names::ip_number(ip_number::TCP));
+    assert_eq!(Some("IPv6-ICMP"), names::ip_number(ip_number::IPV6_ICMP));
+    assert_eq!(Some("UDPLite"), names::ip_number(ip_number::UDP_LITE));
+    assert_eq!(Some("https"), names::tcp_port(443));
+    assert_eq!(Some("ssh"), names::tcp_port(22));
+    assert_eq!(Some("domain"), names::udp_port(53));
+    assert_eq!(Some("vxlan"), names::udp_port(4789));
+    assert_eq!(
+        Some("time-exceeded"),
+        names::icmpv4_type(icmpv4::TYPE_TIME_EXCEEDED)
+    );
+    assert_eq!(
+        Some("port-unreachable"),
+        names::icmpv4_code(icmpv4::TYPE_DEST_UNREACH, icmpv4::CODE_DST_UNREACH_PORT)
+    );
+    assert_eq!(
+        Some("packet-too-big"),
+        names::icmpv6_type(icmpv6::TYPE_PACKET_TOO_BIG)
+    );
+    assert_eq!(
+        Some("hop-limit-exceeded"),
+        names::icmpv6_code(icmpv6::TYPE_TIME_EXCEEDED, icmpv6::CODE_TIME_EXCEEDED_HOP_LIMIT_EXCEEDED)
+    );
+
+    // the service names depend on the transport protocol
+    assert_eq!(Some("shell"), names::tcp_port(514));
+    assert_eq!(Some("syslog"), names::udp_port(514));
+    assert_eq!(None, names::udp_port(22));
+
+    // unknown values
+    assert_eq!(None, names::ether_type(0x1234));
+    assert_eq!(None, names::ip_number(ip_number::EXP0));
+    assert_eq!(None, names::tcp_port(0));
+    assert_eq!(None, names::tcp_port(u16::MAX));
+    assert_eq!(None, names::udp_port(51234));
+    assert_eq!(None, names::icmpv4_type(255));
+    assert_eq!(None, names::icmpv4_code(icmpv4::TYPE_ECHO_REQUEST, 0));
+    assert_eq!(None, names::icmpv4_code(icmpv4::TYPE_DEST_UNREACH, 16));
+    assert_eq!(None, names::icmpv6_type(0));
+    assert_eq!(None, names::icmpv6_code(icmpv6::TYPE_ECHO_REQUEST, 0));
+}
+
+#[test]
+fn summary() {
+    // icmp error with a named type & code
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([2,2,2,2], [1,1,1,1], 20)
+            .icmpv4(Icmpv4Type::TimeExceeded(icmpv4::TimeExceededCode::TtlExceededInTransit))
+            .write(&mut packet, &[])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let summary = PacketSummary::from_sliced(&sliced);
+        assert_eq!(
+            "IPv4 2.2.2.2 > 1.1.1.1, ICMPv4 time-exceeded ttl-exceeded, payload 0 bytes",
+            summary.to_string()
+        );
+        // the compact form stays numeric
+        assert_eq!("v4:icmp:2.2.2.2>1.1.1.1:11.0", summary.compact().to_string());
+    }
+    // icmpv6 with an unknown type
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv6([0;16], [1;16], 20)
+            .icmpv6(Icmpv6Type::Unknown{ type_u8: 200, code_u8: 1, bytes5to8: [0;4] })
+            .write(&mut packet, &[])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(
+            "IPv6 :: > 101:101:101:101:101:101:101:101, ICMPv6 type 200 code 1, payload 0 bytes",
+            PacketSummary::from_sliced(&sliced).to_string()
+        );
+    }
+    // named & unnamed ports
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20)
+            .tcp(51234, 22, 1, 1024)
+            .write(&mut packet, &[])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let summary = PacketSummary::from_sliced(&sliced);
+        assert_eq!(
+            "IPv4 1.1.1.1 > 2.2.2.2, TCP 51234 > ssh [none], payload 0 bytes",
+            summary.to_string()
+        );
+        assert_eq!("v4:tcp:1.1.1.1:51234>2.2.2.2:22:none", summary.compact().to_string());
+    }
+    // ip number
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20)
+            .write(&mut packet, 89, &[])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        assert_eq!(
+            "IPv4 1.1.1.1 > 2.2.2.2, ip number 89 (OSPFIGP), payload 0 bytes",
+            PacketSummary::from_sliced(&sliced).to_string()
+        );
+    }
+    // ether type
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header{
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: 0x88f7,
+        }.write(&mut packet).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        assert_eq!(
+            "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, ether type 0x88f7 (PTP), payload 0 bytes",
+            PacketSummary::from_sliced(&sliced).to_string()
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+
+use std::time::{Duration, Instant};
+
+fn ms(value: u64) -> Duration {
+    Duration::from_millis(value)
+}
+
+fn meta(len: usize, timestamp: Option<Duration>) -> PacketMeta {
+    let meta = PacketMeta::new(len, len);
+    match timestamp {
+        Some(timestamp) => meta.with_timestamp(timestamp),
+        None => meta,
+    }
+}
+
+#[test]
+fn token_bucket() {
+    // 1000 bytes per second with a burst of 200 bytes
+    let mut bucket = TokenBucket::new(1000, 200);
+    assert_eq!(1000, bucket.bytes_per_second());
+    assert_eq!(200, bucket.burst_bytes());
+
+    // burst is send immediately
+    assert_eq!(ms(0), bucket.send_offset(ms(0), 100));
+    assert_eq!(ms(0), bucket.send_offset(ms(0), 100));
+    // afterwards the rate limits the packets
+    assert_eq!(ms(100), bucket.send_offset(ms(0), 100));
+    assert_eq!(ms(150), bucket.send_offset(ms(0), 50));
+    // idle time refills the bucket (but not more then the burst size)
+    assert_eq!(ms(1000), bucket.send_offset(ms(1000), 200));
+    assert_eq!(ms(1050), bucket.send_offset(ms(1000), 50));
+    // earlier offsets then the previous call are treated as the previous offset
+    assert_eq!(ms(1100), bucket.send_offset(ms(0), 50));
+}
+
+#[test]
+fn token_bucket_big_packets() {
+    // packets bigger then the burst wait for a full bucket & put it into debt
+    let mut bucket = TokenBucket::new(1000, 100);
+    assert_eq!(ms(0), bucket.send_offset(ms(0), 300));
+    assert_eq!(ms(201), bucket.send_offset(ms(0), 1));
+    assert_eq!(ms(301), bucket.send_offset(ms(0), 300));
+
+    // without a burst the bucket is a pure rate limit
+    let mut bucket = TokenBucket::new(1000, 0);
+    assert_eq!(ms(0), bucket.send_offset(ms(0), 10));
+    assert_eq!(ms(10), bucket.send_offset(ms(0), 10));
+    assert_eq!(ms(20), bucket.send_offset(ms(0), 10));
+}
+
+#[test]
+#[should_panic]
+fn token_bucket_zero_rate() {
+    TokenBucket::new(0, 100);
+}
+
+#[test]
+fn schedule_rate() {
+    let mut pacer = Pacer::with_rate(
+        std::iter::empty::<PacketWithMeta<()>>(),
+        TokenBucket::new(1000, 0)
+    );
+    assert_eq!(&PacingMode::Rate(TokenBucket::new(1000, 0)), pacer.mode());
+    assert_eq!(ms(0), pacer.schedule(&meta(100, None), ms(0)));
+    assert_eq!(ms(100), pacer.schedule(&meta(100, None), ms(0)));
+    // a slow consumer is credited
+    assert_eq!(ms(500), pacer.schedule(&meta(100, None), ms(500)));
+    assert_eq!(ms(600), pacer.schedule(&meta(100, None), ms(500)));
+}
+
+#[test]
+fn schedule_recorded() {
+    let base = Duration::from_secs(1_600_000_000);
+    let mut pacer = Pacer::with_recorded_timing(std::iter::empty::<PacketWithMeta<()>>(), 2.0);
+    assert_eq!(&PacingMode::Recorded{ speed: 2.0 }, pacer.mode());
+
+    // packets without timestamp before the first timestamp
+    assert_eq!(ms(0), pacer.schedule(&meta(60, None), ms(0)));
+    assert_eq!(ms(0), pacer.schedule(&meta(60, Some(base)), ms(0)));
+    assert_eq!(ms(50), pacer.schedule(&meta(60, Some(base + ms(100))), ms(0)));
+    // later "now" values don't change the recorded timing
+    assert_eq!(ms(150), pacer.schedule(&meta(60, Some(base + ms(300))), ms(400)));
+    // missing & out of order timestamps are scheduled with the previous packet
+    assert_eq!(ms(150), pacer.schedule(&meta(60, None), ms(0)));
+    assert_eq!(ms(150), pacer.schedule(&meta(60, Some(base + ms(10))), ms(0)));
+    assert_eq!(ms(200), pacer.schedule(&meta(60, Some(base + ms(400))), ms(0)));
+}
+
+#[test]
+fn invalid_speed() {
+    for speed in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let result = std::panic::catch_unwind(|| {
+            Pacer::with_recorded_timing(std::iter::empty::<PacketWithMeta<()>>(), speed)
+        });
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn iterator() {
+    let packets: Vec<_> = (0..4u32)
+        .map(|i| PacketWithMeta::new(meta(100, Some(ms(u64::from(i)*2))), i))
+        .collect();
+
+    // recorded timing
+    {
+        let start = Instant::now();
+        let pacer = Pacer::with_recorded_timing(packets.clone().into_iter(), 1.0);
+        assert_eq!((4, Some(4)), pacer.size_hint());
+        assert_eq!(vec![0, 1, 2, 3], pacer.map(|p| p.packet).collect::<Vec<_>>());
+        assert!(start.elapsed() >= ms(6));
+    }
+
+    // rate (100 bytes every 2 milliseconds)
+    {
+        let start = Instant::now();
+        let pacer = Pacer::with_rate(packets.clone().into_iter(), TokenBucket::new(50_000, 0));
+        assert_eq!(4, pacer.count());
+        assert!(start.elapsed() >= ms(6));
+    }
+
+    // inner iterator
+    let pacer = Pacer::with_rate(packets.clone().into_iter(), TokenBucket::new(1, 0));
+    assert_eq!(4, pacer.into_inner().count());
+}
@@ -172,6 +172,7 @@ fn ipv6() {
                     hop_by_hop_options: None,
                     destination_options: None,
                     routing: None,
+                    shim6: None,
                     fragment: None,
                     auth: Some(auth_ext.clone()),
                 }
@@ -611,6 +612,52 @@ fn udp_builder_eth_double_vlan_ipv6_udp() {
     assert_eq!(actual_payload, in_payload);
 }
 
+#[test]
+fn outer_vlan_tpid() {
+    // double tagged frame with a 0x8100 outer tag (misconfigured qinq)
+    {
+        let mut serialized = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                      .double_vlan(0x123, 0x234)
+                      .outer_vlan_tpid(ether_type::VLAN_TAGGED_FRAME)
+                      .ipv4([192,168,1,1], [192,168,1,2], 20)
+                      .udp(21, 1234)
+                      .write(&mut serialized, &[1,2,3,4])
+                      .unwrap();
+
+        let (eth, rest) = Ethernet2Header::from_slice(&serialized).unwrap();
+        assert_eq!(ether_type::VLAN_TAGGED_FRAME, eth.ether_type);
+        let (vlan, _) = DoubleVlanHeader::from_slice(rest).unwrap();
+        assert_eq!(ether_type::VLAN_TAGGED_FRAME, vlan.outer.ether_type);
+        assert_eq!(ether_type::IPV4, vlan.inner.ether_type);
+
+        // both parsers still classify the frame as double tagged
+        let sliced = SlicedPacket::from_ethernet(&serialized).unwrap();
+        match &sliced.vlan {
+            Some(VlanSlice::DoubleVlan(value)) => assert_eq!(vlan, value.to_header()),
+            value => panic!("unexpected vlan {:?}", value),
+        }
+        assert_eq!(&[1,2,3,4], sliced.payload);
+        let headers = PacketHeaders::from_ethernet_slice(&serialized).unwrap();
+        assert_eq!(Some(VlanHeader::Double(vlan)), headers.vlan);
+        assert_eq!(&[1,2,3,4], headers.payload);
+    }
+    // single tagged frame with a 0x88a8 tag
+    {
+        let mut serialized = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                      .single_vlan(0x123)
+                      .outer_vlan_tpid(ether_type::PROVIDER_BRIDGING)
+                      .ipv4([192,168,1,1], [192,168,1,2], 20)
+                      .udp(21, 1234)
+                      .write(&mut serialized, &[])
+                      .unwrap();
+        assert_eq!(ether_type::PROVIDER_BRIDGING.to_be_bytes(), [serialized[12], serialized[13]]);
+        let sliced = SlicedPacket::from_ethernet(&serialized).unwrap();
+        assert_matches!(sliced.vlan, Some(VlanSlice::SingleVlan(_)));
+    }
+}
+
 #[test]
 fn udp_builder_eth_ip_udp() {
     //generate
@@ -1005,6 +1052,192 @@ fn tcp_options() {
     );
 }
 
+#[test]
+fn udp_builder_eth_mpls_ipv4_udp() {
+    let labels = [
+        // bottom of stack flags are overwritten
+        MplsHeader::new(16, 1, true, 64).unwrap(),
+        MplsHeader::new(1000, 2, false, 65).unwrap(),
+    ];
+    let in_payload = [50,51,52,53];
+
+    // unicast
+    let mut serialized = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [8,9,10,11,12,13])
+                  .mpls(&labels)
+                  .ipv4([13,14,15,16], [17,18,19,20], 21)
+                  .udp(48,49)
+                  .write(&mut serialized, &in_payload)
+                  .unwrap();
+    assert_eq!(
+        Ethernet2Header::SERIALIZED_SIZE + 2*MplsHeader::SERIALIZED_SIZE +
+        Ipv4Header::SERIALIZED_SIZE + UdpHeader::SERIALIZED_SIZE + in_payload.len(),
+        serialized.len()
+    );
+
+    let decoded = PacketHeaders::from_ethernet_slice(&serialized).unwrap();
+    assert_eq!(ether_type::MPLS_UNICAST, decoded.link.unwrap().ether_type);
+    assert_eq!(
+        vec![
+            MplsHeader::new(16, 1, false, 64).unwrap(),
+            MplsHeader::new(1000, 2, true, 65).unwrap(),
+        ],
+        decoded.mpls.unwrap().to_headers()
+    );
+    assert_eq!([13,14,15,16], match decoded.ip {
+        Some(IpHeader::Version4(ip, _)) => ip.source,
+        _ => panic!("expected ipv4 header"),
+    });
+    assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+    assert_eq!(&in_payload, decoded.payload);
+
+    // multicast destination
+    let mut serialized = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [0x01,0,0x5e,0,0,1])
+                  .mpls(&labels)
+                  .ipv4([13,14,15,16], [224,0,0,1], 21)
+                  .udp(48,49)
+                  .write(&mut serialized, &in_payload)
+                  .unwrap();
+    let decoded = PacketHeaders::from_ethernet_slice(&serialized).unwrap();
+    assert_eq!(ether_type::MPLS_MULTICAST, decoded.link.unwrap().ether_type);
+    assert_eq!(2, decoded.mpls.unwrap().len());
+}
+
+#[test]
+fn mpls_entropy_label() {
+    let labels = [
+        MplsHeader::new(16, 1, false, 64).unwrap(),
+        MplsHeader::new(1000, 2, false, 65).unwrap(),
+    ];
+    let in_payload = [50,51,52,53];
+
+    for lsp_index in 0..labels.len() {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [8,9,10,11,12,13])
+                      .mpls(&labels)
+                      .entropy_label(lsp_index, FlowHashAlgorithm::Crc32)
+                      .ipv4([13,14,15,16], [17,18,19,20], 21)
+                      .udp(48,49);
+        let size = builder.size(in_payload.len());
+        let mut serialized = Vec::new();
+        builder.write(&mut serialized, &in_payload).unwrap();
+        assert_eq!(size, serialized.len());
+
+        let sliced = SlicedPacket::from_ethernet(&serialized).unwrap();
+        let key = FlowKey::from_sliced(&sliced).unwrap();
+        let mut expected = labels.to_vec();
+        expected.splice(
+            lsp_index + 1..lsp_index + 1,
+            labels[lsp_index].entropy_label_pair(&key, FlowHashAlgorithm::Crc32)
+        );
+        expected.last_mut().unwrap().bottom_of_stack = true;
+        assert_eq!(expected, sliced.mpls.unwrap().to_headers());
+        assert_eq!(&in_payload, sliced.payload);
+    }
+
+    // different flows get different entropy labels
+    let entropy_label = |source_port: u16| {
+        let mut serialized = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [8,9,10,11,12,13])
+                      .single_vlan(1)
+                      .mpls(&labels[..1])
+                      .entropy_label(0, FlowHashAlgorithm::Crc32)
+                      .ipv6([1;16], [2;16], 21)
+                      .udp(source_port, 49)
+                      .write(&mut serialized, &in_payload)
+                      .unwrap();
+        let decoded = PacketHeaders::from_ethernet_slice(&serialized).unwrap();
+        decoded.mpls.unwrap().to_headers()[2].label
+    };
+    assert_eq!(entropy_label(1), entropy_label(1));
+    assert_ne!(entropy_label(1), entropy_label(2));
+}
+
+#[test]
+#[should_panic]
+fn mpls_entropy_label_index_panic() {
+    let _ = PacketBuilder::ethernet2([1,2,3,4,5,6], [8,9,10,11,12,13])
+        .mpls(&[MplsHeader::new(16, 0, false, 64).unwrap()])
+        .entropy_label(1, FlowHashAlgorithm::Crc32);
+}
+
+#[test]
+fn udp_builder_eth_vlan_mpls_ipv6_udp() {
+    let in_payload = [50,51,52,53];
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [8,9,10,11,12,13])
+                  .double_vlan(0x123, 0x234)
+                  .mpls(&[MplsHeader::new(16, 0, false, 64).unwrap()])
+                  .ipv6([11,12,13,14,15,16,17,18,19,10,21,22,23,24,25,26],
+                        [31,32,33,34,35,36,37,38,39,40,41,42,43,44,45,46],
+                        47)
+                  .udp(48,49);
+    assert_eq!(&[MplsHeader::new(16, 0, false, 64).unwrap()], builder.mpls_labels());
+
+    let mut serialized = Vec::new();
+    builder.write(&mut serialized, &in_payload).unwrap();
+
+    let sliced = SlicedPacket::from_ethernet(&serialized).unwrap();
+    match sliced.vlan {
+        Some(VlanSlice::DoubleVlan(vlan)) => {
+            assert_eq!(ether_type::MPLS_UNICAST, vlan.inner().ether_type());
+        },
+        _ => panic!("expected double vlan header"),
+    }
+    let mpls = sliced.mpls.unwrap();
+    assert_eq!(1, mpls.len());
+    assert!(mpls.top().bottom_of_stack());
+    assert_matches!(sliced.ip, Some(InternetSlice::Ipv6(_, _)));
+    assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+    assert_eq!(&in_payload, sliced.payload);
+}
+
+#[test]
+fn mpls_builder_options() {
+    // empty label stack
+    {
+        let mut serialized = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                      .single_vlan(0x123)
+                      .mpls(&[])
+                      .ipv4([13,14,15,16], [17,18,19,20], 21)
+                      .udp(48,49)
+                      .write(&mut serialized, &[])
+                      .unwrap();
+        let sliced = SlicedPacket::from_ethernet(&serialized).unwrap();
+        assert_eq!(None, sliced.mpls);
+        assert!(sliced.ip.is_some());
+    }
+
+    // patching the labels of a cloned step
+    {
+        let profile = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                      .mpls(&[MplsHeader::new(16, 0, true, 64).unwrap()])
+                      .ip(IpHeader::Version4(
+                          Ipv4Header::new(0, 21, ip_number::UDP, [13,14,15,16], [17,18,19,20]),
+                          Default::default()
+                      ));
+        let mut builder = profile.clone();
+        builder.mpls_labels_mut()[0].label = 17;
+        let mut serialized = Vec::new();
+        builder.udp(48,49).write(&mut serialized, &[]).unwrap();
+        let sliced = SlicedPacket::from_ethernet(&serialized).unwrap();
+        assert_eq!(17, sliced.mpls.unwrap().top().label());
+        assert_eq!(16, profile.mpls_labels()[0].label);
+    }
+
+    // invalid label
+    {
+        let mut builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                      .mpls(&[MplsHeader::new(16, 0, true, 64).unwrap()])
+                      .ipv4([13,14,15,16], [17,18,19,20], 21);
+        builder.mpls_labels_mut()[0].label = MplsHeader::MAX_LABEL + 1;
+        assert_matches!(
+            builder.udp(48,49).write(&mut Vec::new(), &[]),
+            Err(WriteError::ValueError(ValueError::U32TooLarge{ field: ErrorField::MplsLabel, .. }))
+        );
+    }
+}
+
 #[test]
 fn size() {
     //ipv4 no vlan
@@ -1060,6 +1293,20 @@ fn size() {
                              )
                              .udp(22,23)
                              .size(123));
+    //ipv4 vlan & mpls
+    assert_eq!(Ethernet2Header::SERIALIZED_SIZE + 
+               SingleVlanHeader::SERIALIZED_SIZE +
+               3*MplsHeader::SERIALIZED_SIZE +
+               Ipv4Header::SERIALIZED_SIZE + 
+               UdpHeader::SERIALIZED_SIZE +
+               123,
+
+               PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12])
+                             .single_vlan(0x123)
+                             .mpls(&[MplsHeader::default(), MplsHeader::default(), MplsHeader::default()])
+                             .ipv4([13,14,15,16], [17,18,19,20], 21)
+                             .udp(22,23)
+                             .size(123));
 }
 
 proptest! {
@@ -1560,3 +1807,168 @@ proptest! {
         }
     }
 }
+
+#[test]
+fn write_to_slice() {
+    let payload = [1,2,3,4];
+
+    // compare every final step with the std::io::Write based result
+    macro_rules! check {
+        ($builder:expr, $($write_args:expr),*) => {
+            let expected = {
+                let mut buffer = Vec::new();
+                $builder.write(&mut buffer, $($write_args),*).unwrap();
+                buffer
+            };
+
+            // slice with additional space
+            {
+                let mut buffer = [0xffu8;200];
+                let rest_len = $builder.write_to_slice(&mut buffer, $($write_args),*).unwrap().len();
+                assert_eq!(200 - expected.len(), rest_len);
+                assert_eq!(&expected[..], &buffer[..expected.len()]);
+                // the rest of the slice is untouched
+                assert!(buffer[expected.len()..].iter().all(|v| 0xff == *v));
+            }
+            // slice too small
+            {
+                let mut buffer = [0u8;200];
+                assert_matches!(
+                    $builder.write_to_slice(&mut buffer[..expected.len() - 1], $($write_args),*),
+                    Err(WriteError::SliceTooSmall(len)) if len == expected.len()
+                );
+            }
+            // array vec
+            {
+                let actual = $builder.to_array_vec::<200>($($write_args),*).unwrap();
+                assert_eq!(&expected[..], &actual[..]);
+                assert_matches!(
+                    $builder.to_array_vec::<10>($($write_args),*),
+                    Err(WriteError::SliceTooSmall(len)) if len == expected.len()
+                );
+            }
+        }
+    }
+
+    let eth_ipv4 = || PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12])
+        .ipv4([13,14,15,16], [17,18,19,20], 21);
+    let ipv6 = || PacketBuilder::ipv6([1;16], [2;16], 3);
+
+    check!(eth_ipv4(), ip_number::IGMP, &payload);
+    check!(eth_ipv4().udp(22, 23), &payload);
+    check!(ipv6().udp(22, 23), &payload);
+    check!(eth_ipv4().tcp(22, 23, 24, 25).syn(), &payload);
+    check!(eth_ipv4().icmpv4_echo_request(1, 2), &payload);
+    check!(ipv6().icmpv6_echo_request(1, 2), &payload);
+}
+
+#[test]
+fn mtu() {
+    let payload = [0u8;100];
+    let builder = |mtu: usize| PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12])
+        .single_vlan(0x123)
+        .mtu(mtu)
+        .ipv4([13,14,15,16], [17,18,19,20], 21)
+        .udp(22, 23);
+    // the ethernet & vlan headers are not part of the mtu
+    let ip_packet_len = Ipv4Header::SERIALIZED_SIZE + UdpHeader::SERIALIZED_SIZE + payload.len();
+
+    // packet fits exactly
+    {
+        let mut buffer = Vec::new();
+        builder(ip_packet_len).write(&mut buffer, &payload).unwrap();
+        assert_eq!(builder(ip_packet_len).size(payload.len()), buffer.len());
+    }
+
+    // packet exceeds the mtu
+    {
+        let expected = ValueError::IpPacketExceedsMtu{ ip_packet_len, mtu: ip_packet_len - 1 };
+
+        let mut buffer = Vec::new();
+        assert_matches!(
+            builder(ip_packet_len - 1).write(&mut buffer, &payload),
+            Err(WriteError::ValueError(ref err)) if *err == expected
+        );
+        // nothing gets written
+        assert!(buffer.is_empty());
+
+        let mut buffer = [0u8;200];
+        assert_matches!(
+            builder(ip_packet_len - 1).write_to_slice(&mut buffer, &payload),
+            Err(WriteError::ValueError(ref err)) if *err == expected
+        );
+        assert_matches!(
+            builder(ip_packet_len - 1).to_array_vec::<200>(&payload),
+            Err(WriteError::ValueError(ref err)) if *err == expected
+        );
+    }
+
+    // mtu can also be set in the last step
+    {
+        let mut buffer = Vec::new();
+        assert_matches!(
+            PacketBuilder::ipv6([1;16], [2;16], 3)
+                .icmpv6_echo_request(1, 2)
+                .mtu(1280)
+                .write(&mut buffer, &[0u8;1280]),
+            Err(WriteError::ValueError(ValueError::IpPacketExceedsMtu{ ip_packet_len: 1328, mtu: 1280 }))
+        );
+    }
+}
+
+#[test]
+fn profile_clone_and_patch() {
+    let payload = [1,2,3,4];
+    let profile = PacketBuilder::ethernet2([1,2,3,4,5,6],[7,8,9,10,11,12])
+        .single_vlan(0x123)
+        .mtu(1500)
+        .ipv6([13;16], [14;16], 15);
+
+    // getters
+    assert_eq!(Some(1500), profile.get_mtu());
+    assert_eq!([1,2,3,4,5,6], profile.ethernet2_header().unwrap().source);
+    assert_matches!(profile.vlan_header(), Some(VlanHeader::Single(ref vlan)) if vlan.vlan_identifier == 0x123);
+    assert_matches!(profile.ip_header(), Some(IpHeader::Version6(ref ip, _)) if ip.hop_limit == 15);
+    assert!(profile.transport_header().is_none());
+
+    // an unmodified clone produces the same packet
+    let expected = {
+        let mut buffer = Vec::new();
+        profile.clone().udp(16, 17).write(&mut buffer, &payload).unwrap();
+        buffer
+    };
+    {
+        let mut buffer = Vec::new();
+        profile.clone().udp(16, 17).write(&mut buffer, &payload).unwrap();
+        assert_eq!(expected, buffer);
+    }
+
+    // patch all layers of a clone
+    let mut builder = profile.clone();
+    builder.ethernet2_header_mut().unwrap().destination = [21,22,23,24,25,26];
+    if let Some(VlanHeader::Single(vlan)) = builder.vlan_header_mut() {
+        vlan.vlan_identifier = 0x234;
+    }
+    if let Some(IpHeader::Version6(ip, _)) = builder.ip_header_mut() {
+        ip.destination = [27;16];
+    }
+    let mut builder = builder.udp(16, 17);
+    if let Some(TransportHeader::Udp(udp)) = builder.transport_header_mut() {
+        udp.destination_port = 28;
+    }
+    let mut buffer = Vec::new();
+    builder.write(&mut buffer, &payload).unwrap();
+
+    let sliced = SlicedPacket::from_ethernet(&buffer).unwrap();
+    assert_matches!(sliced.link, Some(LinkSlice::Ethernet2(ref eth)) if eth.destination() == [21,22,23,24,25,26]);
+    assert_matches!(sliced.vlan, Some(VlanSlice::SingleVlan(ref vlan)) if vlan.vlan_identifier() == 0x234);
+    assert_matches!(sliced.ip, Some(InternetSlice::Ipv6(ref ip, _)) if ip.destination() == [27;16]);
+    assert_matches!(sliced.transport, Some(TransportSlice::Udp(ref udp)) if udp.destination_port() == 28);
+    assert_eq!(&payload, sliced.payload);
+
+    // the profile itself is unchanged
+    assert_eq!([7,8,9,10,11,12], profile.ethernet2_header().unwrap().destination);
+
+    // debug
+    assert!(format!("{:?}", profile).starts_with("PacketBuilderStep { state: PacketImpl {"));
+}
@@ -61,6 +61,9 @@ impl ComponentTest {
             Some(TransportHeader::Icmpv4(header)) => header.write(&mut buffer).unwrap(),
             Some(TransportHeader::Udp(header)) => header.write(&mut buffer).unwrap(),
             Some(TransportHeader::Tcp(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::UdpLite(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::Dccp(header)) => header.write(&mut buffer).unwrap(),
+            Some(TransportHeader::Sctp(header)) => header.write(&mut buffer).unwrap(),
             None => {}
         }
         use std::io::Write;
@@ -327,7 +330,10 @@ impl ComponentTest {
                 Some(TransportSlice::Icmpv6(actual)) => Some(TransportHeader::Icmpv6(actual.header())),
                 Some(TransportSlice::Udp(actual)) => Some(TransportHeader::Udp(actual.to_header())),
                 Some(TransportSlice::Tcp(actual)) => Some(TransportHeader::Tcp(actual.to_header())),
-                Some(TransportSlice::Unknown(_)) => None,
+                Some(TransportSlice::UdpLite(actual)) => Some(TransportHeader::UdpLite(actual.to_header())),
+                Some(TransportSlice::Dccp(actual)) => Some(TransportHeader::Dccp(actual.to_header())),
+                // igmp & sctp are only sliced (not decoded by PacketHeaders)
+                Some(TransportSlice::Igmp(_)) | Some(TransportSlice::Sctp(_)) | Some(TransportSlice::Unknown(_)) => None,
                 None => None
             }
         );
@@ -510,6 +516,19 @@ impl ComponentTest {
             test.run()
         }
 
+        // udp-lite (reusing the generated udp values)
+        {
+            let mut test = self.clone();
+            test.ip.as_mut().unwrap().set_next_headers(ip_number::UDP_LITE);
+            test.transport = Some(TransportHeader::UdpLite(UdpLiteHeader{
+                source_port: udp.source_port,
+                destination_port: udp.destination_port,
+                checksum_coverage: udp.length,
+                checksum: udp.checksum,
+            }));
+            test.run()
+        }
+
         // tcp
         {
             let mut test = self.clone();
@@ -518,6 +537,21 @@ impl ComponentTest {
             test.run()
         }
 
+        // dccp (reusing the generated tcp values)
+        {
+            let mut test = self.clone();
+            test.ip.as_mut().unwrap().set_next_headers(ip_number::DCCP);
+            let mut dccp = DccpHeader::new(
+                tcp.source_port,
+                tcp.destination_port,
+                u64::from(tcp.sequence_number),
+                DccpType::DataAck{ acknowledgement_number: u64::from(tcp.acknowledgment_number) }
+            );
+            dccp.checksum = tcp.checksum;
+            test.transport = Some(TransportHeader::Dccp(dccp));
+            test.run()
+        }
+
         // icmpv4
         if let Some(payload_size) = icmpv4.fixed_payload_size() {
             let mut test = self.clone();
@@ -599,9 +633,25 @@ fn test_packet_slicing_panics() {
     let v = Vec::new();
     let s = SlicedPacket {
         link: None,
+        linux_sll: None,
+        macsec: None,
         vlan: None,
+        mpls: None,
+        pppoe: None,
+        arp: None,
+        link_extension: None,
         ip: None,
         transport: None,
+        transport_extension: None,
+        application: None,
+        esp: None,
+        gre: None,
+        erspan: None,
+        vxlan: None,
+        geneve: None,
+        gtpu: None,
+        nsh: None,
+        inner: None,
         payload: &v[..]
     };
     ComponentTest {
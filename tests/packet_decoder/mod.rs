@@ -7,19 +7,51 @@ mod packet_headers {
     fn debug() {
         let header = PacketHeaders{
             link: None,
+            linux_sll: None,
+            macsec: None,
             vlan: None,
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: None,
+            raw_layers: Vec::new(),
             transport: None,
+            transport_extension: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &[]
         };
         assert_eq!(
             &format!("{:?}", header),
             &format!(
-                "PacketHeaders {{ link: {:?}, vlan: {:?}, ip: {:?}, transport: {:?}, payload: {:?} }}",
+                "PacketHeaders {{ link: {:?}, linux_sll: {:?}, macsec: {:?}, vlan: {:?}, mpls: {:?}, pppoe: {:?}, arp: {:?}, link_extension: {:?}, ip: {:?}, raw_layers: {:?}, transport: {:?}, transport_extension: {:?}, esp: {:?}, gre: {:?}, erspan: {:?}, vxlan: {:?}, geneve: {:?}, gtpu: {:?}, nsh: {:?}, inner: {:?}, payload: {:?} }}",
                 header.link,
+                header.linux_sll,
+                header.macsec,
                 header.vlan,
+                header.mpls,
+                header.pppoe,
+                header.arp,
+                header.link_extension,
                 header.ip,
+                header.raw_layers,
                 header.transport,
+                header.transport_extension,
+                header.esp,
+                header.gre,
+                header.erspan,
+                header.vxlan,
+                header.geneve,
+                header.gtpu,
+                header.nsh,
+                header.inner,
                 header.payload
             )
         );
@@ -29,9 +61,25 @@ mod packet_headers {
     fn clone_eq() {
         let header = PacketHeaders{
             link: None,
+            linux_sll: None,
+            macsec: None,
             vlan: None,
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: None,
+            raw_layers: Vec::new(),
             transport: None,
+            transport_extension: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &[]
         };
         assert_eq!(header.clone(), header);
@@ -55,9 +103,25 @@ mod packet_headers {
                 None,
                 PacketHeaders{
                     link: None,
+                    linux_sll: None,
+                    macsec: None,
                     vlan: None,
+                    mpls: None,
+                    pppoe: None,
+                    arp: None,
+                    link_extension: None,
                     ip: None,
+                    raw_layers: Vec::new(),
                     transport: None,
+                    transport_extension: None,
+                    esp: None,
+                    gre: None,
+                    erspan: None,
+                    vxlan: None,
+                    geneve: None,
+                    gtpu: None,
+                    nsh: None,
+                    inner: None,
                     payload: &[]
                 }.payload_ether_type()
             );
@@ -67,9 +131,25 @@ mod packet_headers {
                 Some(eth.ether_type),
                 PacketHeaders{
                     link: Some(eth.clone()),
+                    linux_sll: None,
+                    macsec: None,
                     vlan: None,
+                    mpls: None,
+                    pppoe: None,
+                    arp: None,
+                    link_extension: None,
                     ip: None,
+                    raw_layers: Vec::new(),
                     transport: None,
+                    transport_extension: None,
+                    esp: None,
+                    gre: None,
+                    erspan: None,
+                    vxlan: None,
+                    geneve: None,
+                    gtpu: None,
+                    nsh: None,
+                    inner: None,
                     payload: &[]
                 }.payload_ether_type()
             );
@@ -79,9 +159,25 @@ mod packet_headers {
                 Some(vlan_outer.ether_type),
                 PacketHeaders{
                     link: Some(eth.clone()),
+                    linux_sll: None,
+                    macsec: None,
                     vlan: Some(Single(vlan_outer.clone())),
+                    mpls: None,
+                    pppoe: None,
+                    arp: None,
+                    link_extension: None,
                     ip: None,
+                    raw_layers: Vec::new(),
                     transport: None,
+                    transport_extension: None,
+                    esp: None,
+                    gre: None,
+                    erspan: None,
+                    vxlan: None,
+                    geneve: None,
+                    gtpu: None,
+                    nsh: None,
+                    inner: None,
                     payload: &[]
                 }.payload_ether_type()
             );
@@ -91,6 +187,8 @@ mod packet_headers {
                 Some(vlan_inner.ether_type),
                 PacketHeaders{
                     link: Some(eth.clone()),
+                    linux_sll: None,
+                    macsec: None,
                     vlan: Some(
                         Double(
                             DoubleVlanHeader {
@@ -99,8 +197,22 @@ mod packet_headers {
                             }
                         )
                     ),
+                    mpls: None,
+                    pppoe: None,
+                    arp: None,
+                    link_extension: None,
                     ip: None,
+                    raw_layers: Vec::new(),
                     transport: None,
+                    transport_extension: None,
+                    esp: None,
+                    gre: None,
+                    erspan: None,
+                    vxlan: None,
+                    geneve: None,
+                    gtpu: None,
+                    nsh: None,
+                    inner: None,
                     payload: &[]
                 }.payload_ether_type()
             );
@@ -110,11 +222,27 @@ mod packet_headers {
                 None,
                 PacketHeaders{
                     link: Some(eth.clone()),
+                    linux_sll: None,
+                    macsec: None,
                     vlan: None,
+                    mpls: None,
+                    pppoe: None,
+                    arp: None,
+                    link_extension: None,
                     ip: Some(
                         Version4(ipv4.clone(), Default::default())
                     ),
+                    raw_layers: Vec::new(),
                     transport: None,
+                    transport_extension: None,
+                    esp: None,
+                    gre: None,
+                    erspan: None,
+                    vxlan: None,
+                    geneve: None,
+                    gtpu: None,
+                    nsh: None,
+                    inner: None,
                     payload: &[]
                 }.payload_ether_type()
             );
@@ -124,16 +252,196 @@ mod packet_headers {
                 None,
                 PacketHeaders{
                     link: Some(eth.clone()),
+                    linux_sll: None,
+                    macsec: None,
                     vlan: None,
+                    mpls: None,
+                    pppoe: None,
+                    arp: None,
+                    link_extension: None,
                     ip: Some(
                         Version4(ipv4.clone(), Default::default())
                     ),
+                    raw_layers: Vec::new(),
                     transport: Some(
                         Udp(udp.clone())
                     ),
+                    transport_extension: None,
+                    esp: None,
+                    gre: None,
+                    erspan: None,
+                    vxlan: None,
+                    geneve: None,
+                    gtpu: None,
+                    nsh: None,
+                    inner: None,
                     payload: &[]
                 }.payload_ether_type()
             );
         }
     }
-}
\ No newline at end of file
+}
+mod raw_layers {
+    use super::*;
+
+    fn udp_payload() -> Vec<u8> {
+        let mut result = Vec::new();
+        UdpHeader {
+            source_port: 1,
+            destination_port: 2,
+            length: 8 + 4,
+            checksum: 0,
+        }.write(&mut result).unwrap();
+        result.extend_from_slice(&[1, 2, 3, 4]);
+        result
+    }
+
+    fn ipv6_packet(next_header: u8, extensions: &[u8]) -> Vec<u8> {
+        let payload = udp_payload();
+        let mut result = Vec::new();
+        Ipv6Header {
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: (extensions.len() + payload.len()) as u16,
+            next_header,
+            hop_limit: 64,
+            source: [1;16],
+            destination: [2;16],
+        }.write(&mut result).unwrap();
+        result.extend_from_slice(extensions);
+        result.extend_from_slice(&payload);
+        result
+    }
+
+    #[test]
+    fn vlan_tags() {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1;6],
+            destination: [2;6],
+            ether_type: ether_type::VLAN_DOUBLE_TAGGED_FRAME,
+        }.write(&mut packet).unwrap();
+        packet.extend_from_slice(&[
+            0, 1, 0x81, 0x00, // outer vlan
+            0, 2, 0x81, 0x00, // inner vlan
+            0, 3, 0x81, 0x00, // third vlan tag
+            0, 4, 0x86, 0xdd, // fourth vlan tag
+        ]);
+        packet.extend_from_slice(&ipv6_packet(ip_number::UDP, &[]));
+
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(
+            vec![
+                RawLayer{ kind: RawLayerKind::VlanTag(ether_type::VLAN_TAGGED_FRAME), data: &packet[22..26] },
+                RawLayer{ kind: RawLayerKind::VlanTag(ether_type::VLAN_TAGGED_FRAME), data: &packet[26..30] },
+            ],
+            headers.raw_layers
+        );
+        assert!(headers.ip.is_some());
+        assert_matches!(headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], headers.payload);
+
+        // from_ether_type
+        let headers = PacketHeaders::from_ether_type(ether_type::VLAN_DOUBLE_TAGGED_FRAME, &packet[14..]).unwrap();
+        assert_eq!(2, headers.raw_layers.len());
+        assert_eq!(&[1, 2, 3, 4], headers.payload);
+    }
+
+    #[test]
+    fn vlan_tag_truncated() {
+        let data = [
+            0, 1, 0x81, 0x00, // outer vlan
+            0, 2, 0x81, 0x00, // inner vlan
+            0, 3, 0x81, // truncated third vlan tag
+        ];
+        let headers = PacketHeaders::from_ether_type(ether_type::VLAN_DOUBLE_TAGGED_FRAME, &data).unwrap();
+        assert!(headers.raw_layers.is_empty());
+        assert_eq!(&data[8..], headers.payload);
+    }
+
+    #[test]
+    fn ipv6_extensions() {
+        let extensions = [
+            // mobility header
+            ip_number::IPV6_FRAG, 0, 0, 0, 0, 0, 0, 0,
+            // fragment header (not fragmenting)
+            ip_number::IPV6_FRAG, 0, 0, 0, 0, 0, 0, 1,
+            // second fragment header
+            ip_number::UDP, 0, 0, 0, 0, 0, 0, 2,
+        ];
+        let packet = ipv6_packet(ip_number::MOBILITY, &extensions);
+
+        let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+        assert_eq!(
+            vec![
+                RawLayer{ kind: RawLayerKind::Ipv6Extension(ip_number::MOBILITY), data: &packet[40..48] },
+                RawLayer{ kind: RawLayerKind::Ipv6Extension(ip_number::IPV6_FRAG), data: &packet[48..56] },
+                RawLayer{ kind: RawLayerKind::Ipv6Extension(ip_number::IPV6_FRAG), data: &packet[56..64] },
+            ],
+            headers.raw_layers
+        );
+        assert_matches!(headers.transport, Some(TransportHeader::Udp(_)));
+        assert_eq!(&[1, 2, 3, 4], headers.payload);
+
+        // fragment header stored in the extensions, duplicate as raw layer
+        let packet = ipv6_packet(ip_number::IPV6_FRAG, &extensions[8..]);
+        let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+        match &headers.ip {
+            Some(IpHeader::Version6(_, exts)) => assert!(exts.fragment.is_some()),
+            value => panic!("unexpected ip header {:?}", value),
+        }
+        assert_eq!(
+            vec![
+                RawLayer{ kind: RawLayerKind::Ipv6Extension(ip_number::IPV6_FRAG), data: &packet[48..56] },
+            ],
+            headers.raw_layers
+        );
+        assert_matches!(headers.transport, Some(TransportHeader::Udp(_)));
+    }
+
+    #[test]
+    fn ipv6_extension_errors() {
+        // hop by hop header after a raw layer
+        {
+            let packet = ipv6_packet(ip_number::MOBILITY, &[
+                ip_number::IPV6_HOP_BY_HOP, 0, 0, 0, 0, 0, 0, 0,
+                ip_number::UDP, 0, 0, 0, 0, 0, 0, 0,
+            ]);
+            assert_matches!(
+                PacketHeaders::from_ip_slice(&packet),
+                Err(ReadError::Ipv6HopByHopHeaderNotAtStart)
+            );
+        }
+        // truncated raw layer
+        {
+            let mut packet = ipv6_packet(ip_number::MOBILITY, &[]);
+            packet.truncate(40 + 4);
+            assert_matches!(
+                PacketHeaders::from_ip_slice(&packet),
+                Err(ReadError::UnexpectedEndOfSlice(_))
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let extensions = [
+            ip_number::AUTH, 0, 0, 0, 0, 0, 0, 0, // mobility
+            ip_number::UDP, 1, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, // authentication
+        ];
+        let packet = ipv6_packet(ip_number::MOBILITY, &extensions);
+        let headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+
+        let mut serialized = Vec::new();
+        headers.ip.as_ref().unwrap().write(&mut serialized).unwrap();
+        for layer in &headers.raw_layers {
+            serialized.extend_from_slice(layer.data);
+        }
+        match headers.transport.as_ref().unwrap() {
+            TransportHeader::Udp(udp) => udp.write(&mut serialized).unwrap(),
+            value => panic!("unexpected transport header {:?}", value),
+        }
+        serialized.extend_from_slice(headers.payload);
+        assert_eq!(packet, serialized);
+    }
+}
@@ -0,0 +1,114 @@
+use super::*;
+
+fn udp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(4));
+    builder.write(&mut result, &[1,2,3,4]).unwrap();
+    result
+}
+
+#[cfg(feature = "hex")]
+mod hex {
+    use super::*;
+
+    fn to_hex(data: &[u8], separator: &str) -> String {
+        data.iter().map(|v| format!("{:02x}", v)).collect::<Vec<_>>().join(separator)
+    }
+
+    #[test]
+    fn from_hex_str() {
+        let packet = udp_packet();
+        for text in [
+            to_hex(&packet, ""),
+            to_hex(&packet, "").to_uppercase(),
+            format!("0x{}", to_hex(&packet, "")),
+            format!("  0X{}\n", to_hex(&packet, "")),
+            to_hex(&packet, " "),
+            to_hex(&packet, ":"),
+            to_hex(&packet, "-"),
+            to_hex(&packet, "\n"),
+        ] {
+            let actual = PacketWithMeta::from_hex_str(&text).unwrap();
+            assert_eq!(PacketMeta::from_captured(&packet), actual.meta);
+            assert_eq!(&packet[..], actual.data());
+
+            let sliced = SlicedPacket::from_ethernet(actual.data()).unwrap();
+            assert_eq!(&[1,2,3,4], sliced.payload);
+        }
+
+        // empty string
+        assert!(PacketWithMeta::from_hex_str("").unwrap().data().is_empty());
+    }
+
+    #[test]
+    fn from_hex_str_errors() {
+        use PacketEncodingError::*;
+        assert_eq!(
+            Err(HexInvalidCharacter{ index: 4, character: 'g' }),
+            PacketWithMeta::from_hex_str("0102g3")
+        );
+        // index includes the prefix & leading whitespaces
+        assert_eq!(
+            Err(HexInvalidCharacter{ index: 5, character: 'x' }),
+            PacketWithMeta::from_hex_str(" 0x01x2")
+        );
+        assert_eq!(
+            Err(HexOddLength(3)),
+            PacketWithMeta::from_hex_str("01 2")
+        );
+    }
+
+    #[test]
+    fn error_display() {
+        use PacketEncodingError::*;
+        assert_eq!(
+            "PacketEncodingError: Invalid character 'g' at position 4 in hex string.",
+            &format!("{}", HexInvalidCharacter{ index: 4, character: 'g' })
+        );
+        assert_eq!(
+            "PacketEncodingError: Invalid character '\\n' at position 4 in hex string.",
+            &format!("{}", HexInvalidCharacter{ index: 4, character: '\n' })
+        );
+        assert_eq!(
+            "PacketEncodingError: Hex string contains an odd number of hex digits (3).",
+            &format!("{}", HexOddLength(3))
+        );
+    }
+
+    #[test]
+    fn error_source() {
+        use std::error::Error;
+        assert!(PacketEncodingError::HexOddLength(1).source().is_none());
+    }
+}
+
+#[cfg(feature = "base64")]
+mod base64 {
+    use super::*;
+
+    #[test]
+    fn from_base64() {
+        let packet = udp_packet();
+        let encoded = "BwgJCgsMAQIDBAUGCABFAAAgAABAABQR43nAqAEBwKgBAgAVBNIADHOVAQIDBA==";
+
+        let actual = PacketWithMeta::from_base64(encoded).unwrap();
+        assert_eq!(PacketMeta::from_captured(&packet), actual.meta);
+        assert_eq!(&packet[..], actual.data());
+
+        // wrapped lines
+        let wrapped = format!("  {}\n{}\r\n", &encoded[..20], &encoded[20..]);
+        assert_eq!(&packet[..], PacketWithMeta::from_base64(&wrapped).unwrap().data());
+    }
+
+    #[test]
+    fn from_base64_errors() {
+        use std::error::Error;
+
+        let err = PacketWithMeta::from_base64("AQID$").unwrap_err();
+        assert_matches!(err, PacketEncodingError::Base64(_));
+        assert!(err.source().is_some());
+        assert!(format!("{}", err).starts_with("PacketEncodingError: Invalid base64 string: "));
+    }
+}
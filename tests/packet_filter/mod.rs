@@ -325,6 +325,8 @@ impl PacketFilterTest {
                 },
                 None => None
             },
+            linux_sll: None,
+            macsec: None,
             vlan: match &self.vlan {
                 Some(VlanHeader::Single(header)) => {
                     header.write(&mut vlan_data).unwrap();
@@ -336,6 +338,10 @@ impl PacketFilterTest {
                 },
                 None => None
             },
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: match &self.ip {
                 Some(IpHeader::Version4(header, _)) => {
                     header.write(&mut ip_data).unwrap();
@@ -375,8 +381,30 @@ impl PacketFilterTest {
                     header.write(&mut transport_data).unwrap();
                     Some(TransportSlice::Tcp(TcpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
                 },
+                Some(TransportHeader::UdpLite(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::UdpLite(UdpLiteHeaderSlice::from_slice(&transport_data[..]).unwrap()))
+                },
+                Some(TransportHeader::Dccp(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::Dccp(DccpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
+                },
+                Some(TransportHeader::Sctp(header)) => {
+                    header.write(&mut transport_data).unwrap();
+                    Some(TransportSlice::Sctp(SctpHeaderSlice::from_slice(&transport_data[..]).unwrap()))
+                },
                 None => None
             },
+            transport_extension: None,
+            application: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &payload[..]
         };
 
@@ -725,3 +753,259 @@ fn type_derives() {
         destination_port: None
     });
 }
+
+mod bpf {
+    use super::*;
+
+    /// Minimal classic BPF interpreter supporting the instructions generated by `Filter::to_bpf`.
+    fn run(program: &[BpfInstruction], packet: &[u8]) -> u32 {
+        let load = |offset: u32, len: usize| -> Option<u32> {
+            let start = offset as usize;
+            packet.get(start..start + len).map(|bytes| {
+                bytes.iter().fold(0u32, |acc, b| (acc << 8) | u32::from(*b))
+            })
+        };
+        let mut a = 0u32;
+        let mut x = 0u32;
+        let mut pc = 0;
+        loop {
+            let i = program[pc];
+            pc += 1;
+            match i.code {
+                bpf_code::LD_W_ABS => match load(i.k, 4) { Some(v) => a = v, None => return 0 },
+                bpf_code::LD_H_ABS => match load(i.k, 2) { Some(v) => a = v, None => return 0 },
+                bpf_code::LD_B_ABS => match load(i.k, 1) { Some(v) => a = v, None => return 0 },
+                bpf_code::LD_H_IND => match load(x + i.k, 2) { Some(v) => a = v, None => return 0 },
+                bpf_code::LDX_B_MSH => match load(i.k, 1) { Some(v) => x = 4*(v & 0xf), None => return 0 },
+                bpf_code::ALU_AND_K => a &= i.k,
+                bpf_code::JMP_JEQ_K => pc += usize::from(if a == i.k { i.jt } else { i.jf }),
+                bpf_code::JMP_JSET_K => pc += usize::from(if 0 != a & i.k { i.jt } else { i.jf }),
+                bpf_code::RET_K => return i.k,
+                code => panic!("unexpected opcode {:x}", code),
+            }
+        }
+    }
+
+    fn packets() -> Vec<Vec<u8>> {
+        let mut result = Vec::new();
+        let mut add = |builder: PacketBuilderStep<UdpHeader>| {
+            let mut packet = Vec::new();
+            builder.write(&mut packet, &[1,2,3,4]).unwrap();
+            result.push(packet);
+        };
+        add(PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(53, 1234));
+        add(PacketBuilder::ethernet2([7,8,9,10,11,12], [1,2,3,4,5,6])
+            .ipv4([192,168,1,2], [192,168,1,1], 20)
+            .udp(1234, 53));
+        add(PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(12)
+            .ipv6([1;16], [2;16], 20)
+            .udp(53, 1234));
+        add(PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .double_vlan(12, 34)
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(53, 1234));
+        add(PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([2;16], [1;16], 20)
+            .udp(1234, 53));
+        // ipv4 header with options
+        add(PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ip(IpHeader::Version4({
+                let mut header = Ipv4Header::new(0, 20, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+                header.set_options(&[1,2,3,4,5,6,7,8]).unwrap();
+                header
+            }, Default::default()))
+            .udp(53, 1234));
+        // fragmented ipv4 packet
+        add(PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ip(IpHeader::Version4({
+                let mut header = Ipv4Header::new(0, 20, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+                header.more_fragments = true;
+                header
+            }, Default::default()))
+            .udp(53, 1234));
+
+        // tcp
+        {
+            let mut packet = Vec::new();
+            PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .single_vlan(34)
+                .ipv4([192,168,1,1], [192,168,1,2], 20)
+                .tcp(53, 1234, 1, 2)
+                .write(&mut packet, &[1,2,3,4])
+                .unwrap();
+            result.push(packet);
+        }
+        // non ip frame
+        {
+            let mut packet = Vec::new();
+            Ethernet2Header{
+                source: [1,2,3,4,5,6],
+                destination: [7,8,9,10,11,12],
+                ether_type: ether_type::ARP,
+            }.write(&mut packet).unwrap();
+            ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2])
+                .write(&mut packet)
+                .unwrap();
+            result.push(packet);
+        }
+        result
+    }
+
+    fn filters() -> Vec<Filter> {
+        let links = vec![
+            ElementFilter::Any,
+            ElementFilter::Some(LinkFilter::Ethernet2{ source: Some([1,2,3,4,5,6]), destination: None }),
+            ElementFilter::Some(LinkFilter::Ethernet2{ source: None, destination: Some([1,2,3,4,5,6]) }),
+        ];
+        let vlans = vec![
+            ElementFilter::Any,
+            ElementFilter::No,
+            ElementFilter::Some(VlanFilter::Single(None)),
+            ElementFilter::Some(VlanFilter::Single(Some(12))),
+            ElementFilter::Some(VlanFilter::Double{ outer_identifier: Some(12), inner_identifier: None }),
+            ElementFilter::Some(VlanFilter::Double{ outer_identifier: None, inner_identifier: Some(35) }),
+        ];
+        let ips = vec![
+            ElementFilter::Any,
+            ElementFilter::No,
+            ElementFilter::Some(IpFilter::Ipv4{ source: None, destination: None }),
+            ElementFilter::Some(IpFilter::Ipv4{ source: Some([192,168,1,1]), destination: None }),
+            ElementFilter::Some(IpFilter::Ipv4{ source: None, destination: Some([192,168,1,1]) }),
+            ElementFilter::Some(IpFilter::Ipv6{ source: Some([1;16]), destination: None }),
+            ElementFilter::Some(IpFilter::Ipv6{ source: None, destination: Some([1;16]) }),
+        ];
+        let transports = vec![
+            ElementFilter::Any,
+            ElementFilter::Some(TransportFilter::Udp{ source_port: None, destination_port: None }),
+            ElementFilter::Some(TransportFilter::Udp{ source_port: Some(53), destination_port: None }),
+            ElementFilter::Some(TransportFilter::Udp{ source_port: None, destination_port: Some(53) }),
+            ElementFilter::Some(TransportFilter::Tcp{ source_port: Some(53), destination_port: Some(1234) }),
+        ];
+        let mut result = Vec::new();
+        for link in &links {
+            for vlan in &vlans {
+                for ip in &ips {
+                    for transport in &transports {
+                        result.push(Filter {
+                            link: link.clone(),
+                            vlan: vlan.clone(),
+                            ip: ip.clone(),
+                            transport: transport.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn to_bpf_matches_applies_to_slice() {
+        let packets = packets();
+        for filter in filters() {
+            let program = filter.to_bpf().unwrap();
+            for packet in &packets {
+                let expected = filter.applies_to_slice(&SlicedPacket::from_ethernet(packet).unwrap());
+                assert_eq!(
+                    if expected { u32::MAX } else { 0 },
+                    run(&program, packet),
+                    "filter: {:?}, packet: {:?}", filter, packet
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn to_bpf_default() {
+        // accepts everything
+        assert_eq!(
+            vec![
+                BpfInstruction{ code: bpf_code::RET_K, jt: 0, jf: 0, k: u32::MAX },
+                BpfInstruction{ code: bpf_code::RET_K, jt: 0, jf: 0, k: 0 },
+            ],
+            Filter::default().to_bpf().unwrap()
+        );
+    }
+
+    #[test]
+    fn to_bpf_ipv4_source() {
+        use bpf_code::*;
+        let filter = Filter {
+            vlan: ElementFilter::No,
+            ip: ElementFilter::Some(IpFilter::Ipv4{ source: Some([192,168,1,1]), destination: None }),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![
+                // no vlan
+                BpfInstruction{ code: LD_H_ABS, jt: 0, jf: 0, k: 12 },
+                BpfInstruction{ code: JMP_JEQ_K, jt: 7, jf: 0, k: 0x8100 },
+                BpfInstruction{ code: JMP_JEQ_K, jt: 6, jf: 0, k: 0x88a8 },
+                BpfInstruction{ code: JMP_JEQ_K, jt: 5, jf: 0, k: 0x9100 },
+                // ipv4
+                BpfInstruction{ code: LD_H_ABS, jt: 0, jf: 0, k: 12 },
+                BpfInstruction{ code: JMP_JEQ_K, jt: 0, jf: 3, k: 0x0800 },
+                // source address
+                BpfInstruction{ code: LD_W_ABS, jt: 0, jf: 0, k: 14 + 12 },
+                BpfInstruction{ code: JMP_JEQ_K, jt: 0, jf: 1, k: 0xc0a8_0101 },
+                BpfInstruction{ code: RET_K, jt: 0, jf: 0, k: u32::MAX },
+                BpfInstruction{ code: RET_K, jt: 0, jf: 0, k: 0 },
+            ],
+            filter.to_bpf().unwrap()
+        );
+    }
+
+    #[test]
+    fn to_bpf_never_matching() {
+        // transport layer without ip layer
+        let filter = Filter {
+            ip: ElementFilter::No,
+            transport: ElementFilter::Some(TransportFilter::Udp{ source_port: None, destination_port: None }),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![BpfInstruction{ code: bpf_code::RET_K, jt: 0, jf: 0, k: 0 }],
+            filter.to_bpf().unwrap()
+        );
+    }
+
+    #[test]
+    fn to_bpf_errors() {
+        assert_eq!(
+            Err(BpfCompileError::NoLinkLayer),
+            Filter{ link: ElementFilter::No, ..Default::default() }.to_bpf()
+        );
+        assert_eq!(
+            Err(BpfCompileError::NoTransportLayer),
+            Filter{ transport: ElementFilter::No, ..Default::default() }.to_bpf()
+        );
+    }
+
+    #[test]
+    fn bpf_compile_error_display() {
+        use BpfCompileError::*;
+        assert_eq!(
+            "BpfCompileError: Filters for packets without a link layer can not be compiled (classic BPF programs operate on ethernet II frames).",
+            &format!("{}", NoLinkLayer)
+        );
+        assert_eq!(
+            "BpfCompileError: Filters for packets without a transport layer can not be compiled.",
+            &format!("{}", NoTransportLayer)
+        );
+        assert!(std::error::Error::source(&NoLinkLayer).is_none());
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let value = BpfInstruction{ code: bpf_code::RET_K, jt: 1, jf: 2, k: 3 };
+        assert_eq!(value, value.clone());
+        assert_eq!("BpfInstruction { code: 6, jt: 1, jf: 2, k: 3 }", format!("{:?}", value));
+        assert_eq!(8, std::mem::size_of::<BpfInstruction>());
+        let error = BpfCompileError::NoLinkLayer;
+        assert_eq!(error, error.clone());
+        assert_eq!("NoLinkLayer", format!("{:?}", error));
+    }
+}
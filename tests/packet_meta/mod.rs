@@ -0,0 +1,123 @@
+use super::*;
+
+use std::time::Duration;
+
+mod meta {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            PacketMeta::new(100, 60),
+            PacketMeta {
+                timestamp: None,
+                interface_index: None,
+                direction: None,
+                original_len: 100,
+                captured_len: 60,
+            }
+        );
+        assert_eq!(PacketMeta::new(0, 0), PacketMeta::default());
+    }
+
+    #[test]
+    fn from_captured() {
+        assert_eq!(PacketMeta::new(3, 3), PacketMeta::from_captured(&[1,2,3]));
+    }
+
+    #[test]
+    fn with_setters() {
+        let meta = PacketMeta::new(1, 1)
+            .with_timestamp(Duration::from_secs(12))
+            .with_interface_index(3)
+            .with_direction(PacketDirection::Outbound);
+        assert_eq!(Some(Duration::from_secs(12)), meta.timestamp);
+        assert_eq!(Some(3), meta.interface_index);
+        assert_eq!(Some(PacketDirection::Outbound), meta.direction);
+    }
+
+    #[test]
+    fn truncation() {
+        assert!(PacketMeta::new(100, 60).is_truncated());
+        assert_eq!(40, PacketMeta::new(100, 60).truncated_len());
+        assert!(!PacketMeta::new(60, 60).is_truncated());
+        assert_eq!(0, PacketMeta::new(60, 60).truncated_len());
+        // captured length bigger then the original (e.g. padding added by the capture source)
+        assert!(!PacketMeta::new(60, 64).is_truncated());
+        assert_eq!(0, PacketMeta::new(60, 64).truncated_len());
+    }
+
+    #[test]
+    fn debug_clone_eq_hash() {
+        use std::collections::HashSet;
+        let meta = PacketMeta::new(1, 1).with_direction(PacketDirection::Inbound);
+        assert_eq!(meta.clone(), meta);
+        let mut set = HashSet::new();
+        set.insert(meta.clone());
+        assert!(set.contains(&meta));
+        println!("{:?}", meta);
+    }
+}
+
+mod packet_with_meta {
+    use super::*;
+
+    fn udp_packet() -> Vec<u8> {
+        let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(21, 1234);
+        let payload = [1,2,3,4];
+        let mut packet = Vec::with_capacity(builder.size(payload.len()));
+        builder.write(&mut packet, &payload).unwrap();
+        packet
+    }
+
+    #[test]
+    fn new_map() {
+        let value = PacketWithMeta::new(PacketMeta::new(1, 1), 1u8);
+        assert_eq!(PacketMeta::new(1, 1), value.meta);
+        assert_eq!(1, value.packet);
+        let mapped = value.clone().map(|v| u16::from(v) + 1);
+        assert_eq!(value.meta, mapped.meta);
+        assert_eq!(2u16, mapped.packet);
+        println!("{:?}", mapped);
+    }
+
+    #[test]
+    fn sliced() {
+        let packet = udp_packet();
+        let meta = PacketMeta::from_captured(&packet).with_interface_index(1);
+
+        let actual = PacketWithMeta::sliced_from_ethernet(meta.clone(), &packet).unwrap();
+        assert_eq!(meta, actual.meta);
+        assert_eq!(SlicedPacket::from_ethernet(&packet).unwrap(), actual.packet);
+
+        let ip = &packet[Ethernet2Header::SERIALIZED_SIZE..];
+        let actual = PacketWithMeta::sliced_from_ip(meta.clone(), ip).unwrap();
+        assert_eq!(meta, actual.meta);
+        assert_eq!(SlicedPacket::from_ip(ip).unwrap(), actual.packet);
+
+        // errors are forwarded
+        assert!(PacketWithMeta::sliced_from_ethernet(meta.clone(), &packet[..10]).is_err());
+        assert!(PacketWithMeta::sliced_from_ip(meta, &[]).is_err());
+    }
+
+    #[test]
+    fn headers() {
+        let packet = udp_packet();
+        let meta = PacketMeta::from_captured(&packet);
+
+        let actual = PacketWithMeta::headers_from_ethernet(meta.clone(), &packet).unwrap();
+        assert_eq!(meta, actual.meta);
+        assert_eq!(PacketHeaders::from_ethernet_slice(&packet).unwrap(), actual.packet);
+
+        let ip = &packet[Ethernet2Header::SERIALIZED_SIZE..];
+        let actual = PacketWithMeta::headers_from_ip(meta.clone(), ip).unwrap();
+        assert_eq!(meta, actual.meta);
+        assert_eq!(PacketHeaders::from_ip_slice(ip).unwrap(), actual.packet);
+
+        // errors are forwarded
+        assert!(PacketWithMeta::headers_from_ethernet(meta.clone(), &packet[..10]).is_err());
+        assert!(PacketWithMeta::headers_from_ip(meta, &[]).is_err());
+    }
+}
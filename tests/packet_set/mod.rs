@@ -0,0 +1,229 @@
+use super::*;
+
+use std::time::Duration;
+
+/// Serializes an udp packet with the given ports starting with an ethernet II header.
+fn udp_packet(source_port: u16, destination_port: u16, reply: bool) -> Vec<u8> {
+    let (source, destination) = if reply {
+        ([192,168,1,2], [192,168,1,1])
+    } else {
+        ([192,168,1,1], [192,168,1,2])
+    };
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4(source, destination, 20)
+        .udp(source_port, destination_port);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    packet
+}
+
+fn tcp_packet() -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(1, 2, 3, 4);
+    let mut packet = Vec::with_capacity(builder.size(0));
+    builder.write(&mut packet, &[]).unwrap();
+    packet
+}
+
+fn meta(packet: &[u8], secs: Option<u64>) -> PacketMeta {
+    let meta = PacketMeta::from_captured(packet);
+    match secs {
+        Some(secs) => meta.with_timestamp(Duration::from_secs(secs)),
+        None => meta,
+    }
+}
+
+fn example_set() -> PacketSet {
+    let mut set = PacketSet::with_capacity(PacketSetLayer::Ethernet2, 5);
+    let p = udp_packet(1000, 53, false);
+    set.push(meta(&p, Some(3)), p);
+    let p = udp_packet(53, 1000, true);
+    set.push(meta(&p, Some(1)), p);
+    let p = tcp_packet();
+    set.push(meta(&p, None), p);
+    let p = udp_packet(1001, 53, false);
+    set.push(meta(&p, Some(1)), p);
+    // too short to be sliced
+    set.push(meta(&[1,2,3], Some(0)), vec![1,2,3]);
+    set
+}
+
+#[test]
+fn new() {
+    let set = PacketSet::new(PacketSetLayer::Ip);
+    assert_eq!(PacketSetLayer::Ip, set.layer());
+    assert_eq!(0, set.len());
+    assert!(set.is_empty());
+    assert_eq!(None, set.get(0));
+    assert!(set.slice(0).is_none());
+    assert_eq!(0, set.sliced().len());
+}
+
+#[test]
+fn push_get() {
+    let set = example_set();
+    assert_eq!(PacketSetLayer::Ethernet2, set.layer());
+    assert_eq!(5, set.len());
+    assert!(!set.is_empty());
+    assert_eq!(&udp_packet(1000, 53, false)[..], set.get(0).unwrap().data());
+    assert_eq!(Some(Duration::from_secs(3)), set.get(0).unwrap().meta.timestamp);
+    assert_eq!(5, set.packets().len());
+    assert_eq!(5, set.iter().count());
+    assert_eq!(5, (&set).into_iter().count());
+}
+
+#[test]
+fn slice() {
+    let set = example_set();
+    let packet = udp_packet(1000, 53, false);
+    assert_eq!(
+        SlicedPacket::from_ethernet(&packet).unwrap(),
+        set.slice(0).unwrap().unwrap()
+    );
+    assert!(set.slice(4).unwrap().is_err());
+
+    // ip layer
+    let mut ip_set = PacketSet::new(PacketSetLayer::Ip);
+    let ip = packet[Ethernet2Header::SERIALIZED_SIZE..].to_vec();
+    ip_set.push(PacketMeta::from_captured(&ip), ip.clone());
+    assert_eq!(SlicedPacket::from_ip(&ip).unwrap(), ip_set.slice(0).unwrap().unwrap());
+}
+
+#[test]
+fn sliced() {
+    let set = example_set();
+    let mut iter = set.sliced();
+    assert_eq!((5, Some(5)), iter.size_hint());
+    let (meta, sliced) = iter.next().unwrap();
+    assert_eq!(&set.get(0).unwrap().meta, meta);
+    assert!(sliced.is_ok());
+    let results: Vec<_> = iter.collect();
+    assert_eq!(4, results.len());
+    assert!(results[3].1.is_err());
+}
+
+#[test]
+fn filter_flow() {
+    let set = example_set();
+    let key = FlowKey::from_sliced(&set.slice(0).unwrap().unwrap()).unwrap();
+
+    // both directions of the flow are returned in the order they were added
+    let result: Vec<_> = set.filter_flow(key).collect();
+    assert_eq!(2, result.len());
+    assert_eq!(Some(Duration::from_secs(3)), result[0].0.timestamp);
+    assert_eq!(Some(Duration::from_secs(1)), result[1].0.timestamp);
+
+    // same result with the reversed key
+    assert_eq!(2, set.filter_flow(key.reversed()).count());
+
+    // different port
+    let mut other = key;
+    other.source_port = 1001;
+    assert_eq!(1, set.filter_flow(other).count());
+    other.source_port = 1002;
+    assert_eq!(0, set.filter_flow(other).count());
+}
+
+#[test]
+fn flows() {
+    let set = example_set();
+
+    // bidirectional (packets that can not be sliced are skipped)
+    {
+        let flows = set.flows(true);
+        assert_eq!(3, flows.len());
+        assert!(flows.unclassified().is_empty());
+        let streams: Vec<_> = flows.iter().collect();
+        assert_eq!(1000, streams[0].0.source_port);
+        assert_eq!(
+            vec![Some(Duration::from_secs(3)), Some(Duration::from_secs(1))],
+            streams[0].1.iter().map(|(meta, _)| meta.timestamp).collect::<Vec<_>>()
+        );
+        assert_eq!(ip_number::TCP, streams[1].0.ip_number);
+        assert_eq!(1001, streams[2].0.source_port);
+    }
+
+    // unidirectional
+    assert_eq!(4, set.flows(false).len());
+}
+
+#[test]
+fn by_protocol() {
+    let set = example_set();
+    let groups = set.by_protocol();
+    assert_eq!(3, groups.len());
+    assert_eq!(3, groups[&Some(ip_number::UDP)].len());
+    assert_eq!(1, groups[&Some(ip_number::TCP)].len());
+    assert_eq!(&[1u8,2,3][..], groups[&None][0].data());
+}
+
+#[test]
+fn sorted_by_time() {
+    let set = example_set();
+    let result: Vec<_> = set
+        .sorted_by_time()
+        .iter()
+        .map(|p| p.meta.timestamp.map(|t| t.as_secs()))
+        .collect();
+    assert_eq!(vec![None, Some(0), Some(1), Some(1), Some(3)], result);
+
+    // stable for identical timestamps
+    let sorted = set.sorted_by_time();
+    assert_eq!(set.get(1).unwrap(), sorted[2]);
+    assert_eq!(set.get(3).unwrap(), sorted[3]);
+}
+
+#[test]
+fn verify_checksums() {
+    let mut set = example_set();
+    // corrupt the checksum of the last udp packet
+    let mut p = udp_packet(1002, 53, false);
+    let last = p.len() - 1;
+    p[last] ^= 0xff;
+    set.push(meta(&p, None), p);
+
+    // full verification
+    let reports: Vec<_> = set
+        .verify_checksums(VerifyOptions::full())
+        .map(|(_, r)| r.ok().map(|r| (r.transport, r.payload_skipped)))
+        .collect();
+    assert_eq!(
+        vec![
+            Some((Some(true), false)),
+            Some((Some(true), false)),
+            Some((Some(true), false)),
+            Some((Some(true), false)),
+            None,
+            Some((Some(false), false)),
+        ],
+        reports
+    );
+
+    // headers only
+    assert!(set
+        .verify_checksums(VerifyOptions::headers_only())
+        .filter_map(|(_, r)| r.ok())
+        .all(|r| r.is_valid() && r.payload_skipped && r.ipv4_header == Some(true)));
+
+    // sampling
+    let reports: Vec<_> = set
+        .verify_checksums(VerifyOptions::sampled(2))
+        .map(|(_, r)| r.ok().map(|r| r.payload_skipped))
+        .collect();
+    assert_eq!(
+        vec![Some(false), Some(true), Some(false), Some(true), None, Some(true)],
+        reports
+    );
+    assert_eq!(6, set.verify_checksums(VerifyOptions::sampled(2)).len());
+}
+
+#[test]
+fn debug_clone_eq() {
+    let set = example_set();
+    assert_eq!(set.clone(), set);
+    println!("{:?}", set.verify_checksums(VerifyOptions::default()).clone());
+    println!("{:?} {:?} {:?}", set, set.sliced(), set.filter_flow(
+        FlowKey::from_sliced(&set.slice(0).unwrap().unwrap()).unwrap()
+    ));
+}
@@ -234,19 +234,51 @@ mod sliced_packet {
     fn debug() {
         let header = SlicedPacket{
             link: None,
+            linux_sll: None,
+            macsec: None,
             vlan: None,
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: None,
             transport: None,
+            transport_extension: None,
+            application: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &[]
         };
         assert_eq!(
             format!("{:?}", header),
             format!(
-                "SlicedPacket {{ link: {:?}, vlan: {:?}, ip: {:?}, transport: {:?}, payload: {:?} }}",
+                "SlicedPacket {{ link: {:?}, linux_sll: {:?}, macsec: {:?}, vlan: {:?}, mpls: {:?}, pppoe: {:?}, arp: {:?}, link_extension: {:?}, ip: {:?}, transport: {:?}, transport_extension: {:?}, application: {:?}, esp: {:?}, gre: {:?}, erspan: {:?}, vxlan: {:?}, geneve: {:?}, gtpu: {:?}, nsh: {:?}, inner: {:?}, payload: {:?} }}",
                 header.link,
+                header.linux_sll,
+                header.macsec,
                 header.vlan,
+                header.mpls,
+                header.pppoe,
+                header.arp,
+                header.link_extension,
                 header.ip,
                 header.transport,
+                header.transport_extension,
+                header.application,
+                header.esp,
+                header.gre,
+                header.erspan,
+                header.vxlan,
+                header.geneve,
+                header.gtpu,
+                header.nsh,
+                header.inner,
                 header.payload
             )
         );
@@ -256,9 +288,25 @@ mod sliced_packet {
     fn clone_eq() {
         let header = SlicedPacket{
             link: None,
+            linux_sll: None,
+            macsec: None,
             vlan: None,
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: None,
             transport: None,
+            transport_extension: None,
+            application: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &[]
         };
         assert_eq!(header.clone(), header);
@@ -279,9 +327,25 @@ mod sliced_packet {
             {
                 let s = SlicedPacket{
                     link: None,
+                    linux_sll: None,
+                    macsec: None,
                     vlan: None,
+                    mpls: None,
+                    pppoe: None,
+                    arp: None,
+                    link_extension: None,
                     ip: None,
                     transport: None,
+                    transport_extension: None,
+                    application: None,
+                    esp: None,
+                    gre: None,
+                    erspan: None,
+                    vxlan: None,
+                    geneve: None,
+                    gtpu: None,
+                    nsh: None,
+                    inner: None,
                     payload: &[]
                 };
                 assert_eq!(None, s.payload_ether_type());
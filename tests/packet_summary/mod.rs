@@ -0,0 +1,278 @@
+use super::*;
+
+use arrayvec::ArrayString;
+use std::fmt::Write;
+
+/// Writes the summary & the compact summary into fixed size buffers.
+fn summaries(sliced: &SlicedPacket) -> (ArrayString<256>, ArrayString<128>) {
+    let summary = PacketSummary::from_sliced(sliced);
+    let mut long = ArrayString::new();
+    summary.write_to(&mut long).unwrap();
+    let mut compact = ArrayString::new();
+    summary.compact().write_to(&mut compact).unwrap();
+
+    // display & write_to produce the same output
+    assert_eq!(summary.to_string(), long.as_str());
+    assert_eq!(summary.compact().to_string(), compact.as_str());
+    (long, compact)
+}
+
+#[test]
+fn tcp_ipv4() {
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([10,0,0,1], [10,0,0,2], 20)
+        .tcp(443, 51234, 1, 1024)
+        .syn()
+        .ack(2)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    let (long, compact) = summaries(&sliced);
+    #[cfg(not(feature = "names"))]
+    assert_eq!(
+        "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, IPv4 10.0.0.1 > 10.0.0.2, TCP 443 > 51234 [S.], payload 4 bytes",
+        long.as_str()
+    );
+    #[cfg(feature = "names")]
+    assert_eq!(
+        "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, IPv4 10.0.0.1 > 10.0.0.2, TCP https > 51234 [S.], payload 4 bytes",
+        long.as_str()
+    );
+    assert_eq!("v4:tcp:10.0.0.1:443>10.0.0.2:51234:S.", compact.as_str());
+}
+
+#[test]
+fn tcp_flags() {
+    let compact = |builder: PacketBuilderStep<TcpHeader>| {
+        let mut packet = Vec::new();
+        builder.write(&mut packet, &[]).unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        summaries(&sliced).1
+    };
+    let builder = || PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20).tcp(1, 2, 3, 4);
+    assert_eq!("v4:tcp:1.1.1.1:1>2.2.2.2:2:none", compact(builder()).as_str());
+    assert_eq!(
+        "v4:tcp:1.1.1.1:1>2.2.2.2:2:FSRP.UEW",
+        compact(builder().fin().syn().rst().psh().ack(1).urg(1).ece().cwr()).as_str()
+    );
+    assert_eq!("v4:tcp:1.1.1.1:1>2.2.2.2:2:F.", compact(builder().fin().ack(1)).as_str());
+}
+
+#[test]
+fn vlan_udp_ipv6() {
+    let source = [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,1];
+    let destination = [0x20,0x01,0x0d,0xb8,0,0,0,0,0,0,0,0,0,0,0,2];
+
+    // single vlan
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .single_vlan(12)
+            .ipv6(source, destination, 20)
+            .udp(53, 1234)
+            .write(&mut packet, &[1,2])
+            .unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        #[cfg(not(feature = "names"))]
+        assert_eq!(
+            "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, VLAN 12, IPv6 2001:db8::1 > 2001:db8::2, UDP 53 > 1234, payload 2 bytes",
+            long.as_str()
+        );
+        #[cfg(feature = "names")]
+        assert_eq!(
+            "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, VLAN 12, IPv6 2001:db8::1 > 2001:db8::2, UDP domain > 1234, payload 2 bytes",
+            long.as_str()
+        );
+        assert_eq!("v6:udp:[2001:db8::1]:53>[2001:db8::2]:1234", compact.as_str());
+    }
+
+    // double vlan
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .double_vlan(12, 34)
+            .ipv6(source, destination, 20)
+            .udp_lite(53, 1234, 0)
+            .write(&mut packet, &[])
+            .unwrap();
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        #[cfg(not(feature = "names"))]
+        assert_eq!(
+            "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, VLAN 12.34, IPv6 2001:db8::1 > 2001:db8::2, UDP-Lite 53 > 1234, payload 0 bytes",
+            long.as_str()
+        );
+        #[cfg(feature = "names")]
+        assert_eq!(
+            "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, VLAN 12.34, IPv6 2001:db8::1 > 2001:db8::2, UDP-Lite domain > 1234, payload 0 bytes",
+            long.as_str()
+        );
+        assert_eq!("v6:udplite:[2001:db8::1]:53>[2001:db8::2]:1234", compact.as_str());
+    }
+}
+
+#[test]
+fn icmp() {
+    // icmpv4
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20)
+            .icmpv4_echo_request(1, 2)
+            .write(&mut packet, &[])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        #[cfg(not(feature = "names"))]
+        assert_eq!(
+            "IPv4 1.1.1.1 > 2.2.2.2, ICMPv4 type 8 code 0, payload 0 bytes",
+            long.as_str()
+        );
+        #[cfg(feature = "names")]
+        assert_eq!(
+            "IPv4 1.1.1.1 > 2.2.2.2, ICMPv4 echo-request code 0, payload 0 bytes",
+            long.as_str()
+        );
+        assert_eq!("v4:icmp:1.1.1.1>2.2.2.2:8.0", compact.as_str());
+    }
+    // icmpv6
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv6([0;16], [1;16], 20)
+            .icmpv6_echo_request(1, 2)
+            .write(&mut packet, &[])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        #[cfg(not(feature = "names"))]
+        assert_eq!(
+            "IPv6 :: > 101:101:101:101:101:101:101:101, ICMPv6 type 128 code 0, payload 0 bytes",
+            long.as_str()
+        );
+        #[cfg(feature = "names")]
+        assert_eq!(
+            "IPv6 :: > 101:101:101:101:101:101:101:101, ICMPv6 echo-request code 0, payload 0 bytes",
+            long.as_str()
+        );
+        assert_eq!("v6:icmp6:[::]>[101:101:101:101:101:101:101:101]:128.0", compact.as_str());
+    }
+}
+
+#[test]
+fn unknown_and_fragmented() {
+    // unknown ip number
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20)
+            .write(&mut packet, 253, &[1,2,3])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        assert_eq!(
+            "IPv4 1.1.1.1 > 2.2.2.2, ip number 253, payload 3 bytes",
+            long.as_str()
+        );
+        assert_eq!("v4:253:1.1.1.1>2.2.2.2", compact.as_str());
+    }
+    // fragmented
+    {
+        let mut header = Ipv4Header::new(8, 20, ip_number::UDP, [1,1,1,1], [2,2,2,2]);
+        header.more_fragments = true;
+        let mut packet = Vec::new();
+        header.write(&mut packet).unwrap();
+        packet.extend_from_slice(&[0;8]);
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        assert_eq!(
+            "IPv4 1.1.1.1 > 2.2.2.2 fragment, payload 8 bytes",
+            long.as_str()
+        );
+        assert_eq!("v4:frag:1.1.1.1>2.2.2.2", compact.as_str());
+    }
+}
+
+#[test]
+fn truncated_transport() {
+    let mut original = Vec::new();
+    PacketBuilder::ipv4([1,1,1,1], [2,2,2,2], 20)
+        .tcp(1, 2, 3, 4)
+        .write(&mut original, &[])
+        .unwrap();
+
+    // icmp error containing the ip header & 8 bytes of the tcp header
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([2,2,2,2], [1,1,1,1], 20)
+        .icmpv4(Icmpv4Type::DestinationUnreachable(icmpv4::DestUnreachableHeader::Port))
+        .write(&mut packet, &original[..28])
+        .unwrap();
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    let embedded = match &sliced.transport {
+        Some(TransportSlice::Icmpv4(icmp)) => icmp.embedded_packet().unwrap().unwrap(),
+        other => panic!("unexpected transport {:?}", other),
+    };
+    let (long, compact) = summaries(&embedded);
+    assert_eq!("IPv4 1.1.1.1 > 2.2.2.2, payload 8 bytes", long.as_str());
+    assert_eq!("v4:trunc:1.1.1.1>2.2.2.2", compact.as_str());
+}
+
+#[test]
+fn no_ip() {
+    let mut packet = Vec::new();
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type: 0x1234,
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&[0;4]);
+
+    // ethernet
+    {
+        let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        assert_eq!(
+            "Ethernet2 01:02:03:04:05:06 > 07:08:09:0a:0b:0c, ether type 0x1234, payload 4 bytes",
+            long.as_str()
+        );
+        assert_eq!("eth:0x1234", compact.as_str());
+    }
+
+    // no link layer
+    {
+        let sliced = SlicedPacket::from_ether_type(0x1234, &packet).unwrap();
+        let (long, compact) = summaries(&sliced);
+        assert_eq!("payload 18 bytes", long.as_str());
+        assert_eq!("raw", compact.as_str());
+    }
+}
+
+#[test]
+fn writer_error() {
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([10,0,0,1], [10,0,0,2], 20)
+        .udp(1, 2)
+        .write(&mut packet, &[])
+        .unwrap();
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    let summary = PacketSummary::from_sliced(&sliced);
+
+    // errors of the writer are passed through if the buffer
+    // is too small
+    let mut buffer = ArrayString::<8>::new();
+    assert!(summary.write_to(&mut buffer).is_err());
+    let mut buffer = ArrayString::<8>::new();
+    assert!(summary.compact().write_to(&mut buffer).is_err());
+    let mut buffer = ArrayString::<8>::new();
+    assert!(write!(buffer, "{}", summary.compact()).is_err());
+}
+
+#[test]
+fn debug_clone_eq() {
+    let packet = [0u8; 4];
+    let sliced = SlicedPacket::from_ether_type(0x1234, &packet).unwrap();
+    let summary = PacketSummary::from_sliced(&sliced);
+    assert_eq!(summary, summary.clone());
+    assert_eq!(summary.compact(), summary.compact().clone());
+    assert!(format!("{:?}", summary).starts_with("PacketSummary"));
+    assert!(format!("{:?}", summary.compact()).starts_with("CompactPacketSummary"));
+}
@@ -0,0 +1,195 @@
+use super::*;
+
+fn registry(limits: ParseLimits) -> ParserRegistry {
+    let mut result = ParserRegistry::new();
+    result.set_limits(limits);
+    result
+}
+
+/// Checks that slicing & decoding the ethernet packet fails with the given limit.
+fn assert_limit_exceeded(packet: &[u8], registry: &ParserRegistry, limit: ParseLimit) {
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_registry(packet, registry),
+        Err(ReadError::ParseLimitExceeded(l)) if l == limit
+    );
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_registry(packet, registry),
+        Err(ReadError::ParseLimitExceeded(l)) if l == limit
+    );
+}
+
+/// Checks that slicing & decoding the ethernet packet succeeds.
+fn assert_ok(packet: &[u8], registry: &ParserRegistry) {
+    assert!(SlicedPacket::from_ethernet_with_registry(packet, registry).is_ok());
+    assert!(PacketHeaders::from_ethernet_slice_with_registry(packet, registry).is_ok());
+}
+
+#[test]
+fn default() {
+    let limits = ParseLimits::default();
+    assert_eq!(ParseLimits::DEFAULT_MAX_TUNNEL_DEPTH, limits.max_tunnel_depth);
+    assert_eq!(
+        ParseLimits {
+            max_tunnel_depth: usize::MAX,
+            ..limits
+        },
+        ParseLimits::UNLIMITED
+    );
+    assert_eq!(&limits, ParserRegistry::new().limits());
+}
+
+#[test]
+fn vlan_tags() {
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .double_vlan(1, 2)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+
+    assert_ok(&packet, &registry(ParseLimits { max_vlan_tags: 2, ..Default::default() }));
+    assert_limit_exceeded(
+        &packet,
+        &registry(ParseLimits { max_vlan_tags: 1, ..Default::default() }),
+        ParseLimit::VlanTags
+    );
+
+    // single vlan tag
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(1)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+    assert_ok(&packet, &registry(ParseLimits { max_vlan_tags: 1, ..Default::default() }));
+    assert_limit_exceeded(
+        &packet,
+        &registry(ParseLimits { max_vlan_tags: 0, ..Default::default() }),
+        ParseLimit::VlanTags
+    );
+}
+
+#[test]
+fn mpls_labels() {
+    let label = MplsHeader::new(16, 0, false, 64).unwrap();
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .mpls(&[label.clone(), label])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+
+    assert_ok(&packet, &registry(ParseLimits { max_mpls_labels: 2, ..Default::default() }));
+    assert_limit_exceeded(
+        &packet,
+        &registry(ParseLimits { max_mpls_labels: 1, ..Default::default() }),
+        ParseLimit::MplsLabels
+    );
+}
+
+#[test]
+fn ipv6_extension_headers() {
+    let mut packet = Vec::new();
+    Ethernet2Header {
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type: ether_type::IPV6,
+    }.write(&mut packet).unwrap();
+    Ipv6Header {
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 8 + 8 + 8,
+        next_header: ip_number::IPV6_HOP_BY_HOP,
+        hop_limit: 64,
+        source: [1;16],
+        destination: [2;16],
+    }.write(&mut packet).unwrap();
+    // hop by hop & destination options header
+    packet.extend_from_slice(&[ip_number::IPV6_DEST_OPTIONS, 0, 0, 0, 0, 0, 0, 0]);
+    packet.extend_from_slice(&[ip_number::MOBILITY, 0, 0, 0, 0, 0, 0, 0]);
+    // mobility header (only kept as raw layer by PacketHeaders)
+    packet.extend_from_slice(&[59, 0, 0, 0, 0, 0, 0, 0]);
+
+    assert_ok(&packet, &registry(ParseLimits { max_ipv6_extension_headers: 3, ..Default::default() }));
+    assert_limit_exceeded(
+        &packet,
+        &registry(ParseLimits { max_ipv6_extension_headers: 1, ..Default::default() }),
+        ParseLimit::Ipv6ExtensionHeaders
+    );
+
+    // the raw layers are counted by PacketHeaders
+    let limits = registry(ParseLimits { max_ipv6_extension_headers: 2, ..Default::default() });
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_registry(&packet, &limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::Ipv6ExtensionHeaders))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_registry(&packet[14..], &limits),
+        Err(ReadError::ParseLimitExceeded(ParseLimit::Ipv6ExtensionHeaders))
+    );
+}
+
+#[test]
+fn option_bytes() {
+    // ipv4 options
+    {
+        let mut ip = Ipv4Header::new(0, 20, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+        ip.set_options(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ip(IpHeader::Version4(ip, Default::default()))
+            .udp(21, 1234)
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+
+        assert_ok(&packet, &registry(ParseLimits { max_option_bytes: 8, ..Default::default() }));
+        assert_limit_exceeded(
+            &packet,
+            &registry(ParseLimits { max_option_bytes: 7, ..Default::default() }),
+            ParseLimit::OptionBytes
+        );
+    }
+    // tcp options
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv4([192,168,1,1], [192,168,1,2], 20)
+            .tcp(21, 1234, 1, 1024)
+            .options(&[TcpOptionElement::MaximumSegmentSize(1400)])
+            .unwrap()
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+
+        assert_ok(&packet, &registry(ParseLimits { max_option_bytes: 4, ..Default::default() }));
+        assert_limit_exceeded(
+            &packet,
+            &registry(ParseLimits { max_option_bytes: 3, ..Default::default() }),
+            ParseLimit::OptionBytes
+        );
+    }
+}
+
+#[test]
+fn tunnel_depth() {
+    let mut packet = Vec::new();
+    Ipv4Header::new(4 + 4, 20, ip_number::GRE, [192,168,1,1], [192,168,1,2])
+        .write(&mut packet)
+        .unwrap();
+    GreHeader::new(ether_type::IPV4).write(&mut packet).unwrap();
+    packet.extend_from_slice(&[1, 2, 3, 4]);
+
+    let limits = registry(ParseLimits { max_tunnel_depth: 0, ..Default::default() });
+
+    let sliced = SlicedPacket::from_ip_with_registry(&packet, &limits).unwrap();
+    assert_eq!(None, sliced.gre);
+    assert_eq!(None, sliced.inner);
+    assert_eq!(&packet[20..], sliced.payload);
+
+    let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &limits).unwrap();
+    assert_eq!(None, decoded.gre);
+    assert_eq!(None, decoded.inner);
+    assert_eq!(&packet[20..], decoded.payload);
+}
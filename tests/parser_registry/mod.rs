@@ -0,0 +1,593 @@
+use super::*;
+
+/// Ether type reserved for local experimental use (IEEE 802).
+const EXPERIMENTAL: u16 = 0x88b5;
+
+/// Parser for a 4 byte header where the last two bytes contain the next ether type.
+fn next_ether_type_parser(_ether_type: u16, slice: &[u8]) -> Result<LinkExtensionInfo, ReadError> {
+    if slice.len() < 4 {
+        return Err(ReadError::UnexpectedEndOfSlice(4));
+    }
+    Ok(LinkExtensionInfo {
+        header_len: 4,
+        next_ether_type: Some(u16::from_be_bytes([slice[2], slice[3]])),
+    })
+}
+
+fn registry() -> ParserRegistry {
+    let mut result = ParserRegistry::new();
+    result.register_ether_type(EXPERIMENTAL, next_ether_type_parser);
+    result
+}
+
+/// Ethernet II header with the experimental ether type, followed by
+/// the custom header and an ipv4 udp packet.
+fn packet(udp_payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type: EXPERIMENTAL,
+    }.write(&mut result).unwrap();
+    result.extend_from_slice(&[0xab, 0xcd]);
+    result.extend_from_slice(&ether_type::IPV4.to_be_bytes());
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut result, udp_payload)
+        .unwrap();
+    result
+}
+
+#[test]
+fn register_ether_type() {
+    let mut registry = ParserRegistry::new();
+    assert!(registry.ether_type_parser(EXPERIMENTAL).is_none());
+
+    registry.register_ether_type(EXPERIMENTAL, |_: u16, _: &[u8]| {
+        Ok(LinkExtensionInfo{ header_len: 1, next_ether_type: None })
+    });
+    assert_matches!(
+        registry.ether_type_parser(EXPERIMENTAL).unwrap().parse(EXPERIMENTAL, &[]),
+        Ok(LinkExtensionInfo{ header_len: 1, next_ether_type: None })
+    );
+
+    // registering the same ether type again replaces the parser
+    registry.register_ether_type(EXPERIMENTAL, |_: u16, _: &[u8]| {
+        Ok(LinkExtensionInfo{ header_len: 2, next_ether_type: None })
+    });
+    assert_matches!(
+        registry.ether_type_parser(EXPERIMENTAL).unwrap().parse(EXPERIMENTAL, &[]),
+        Ok(LinkExtensionInfo{ header_len: 2, next_ether_type: None })
+    );
+    assert_eq!(
+        format!("ParserRegistry {{ ether_types: [{}], ip_numbers: [], udp_ports: [], tcp_ports: [], limits: {:?}, ether_type_length_policy: ForceEthernet2 }}", EXPERIMENTAL, ParseLimits::default()),
+        format!("{:?}", registry)
+    );
+}
+
+#[test]
+fn sliced_packet() {
+    let packet = packet(&[1,2,3,4]);
+    let registry = registry();
+
+    let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+    assert_eq!(
+        Some(LinkExtensionSlice{
+            ether_type: EXPERIMENTAL,
+            header: &packet[Ethernet2Header::SERIALIZED_SIZE..Ethernet2Header::SERIALIZED_SIZE + 4],
+            next_ether_type: Some(ether_type::IPV4),
+        }),
+        sliced.link_extension
+    );
+    assert!(sliced.ip.is_some());
+    assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+    assert_eq!(&[1,2,3,4], sliced.payload);
+    assert_eq!(None, sliced.payload_ether_type());
+
+    // same result when starting from the ether type
+    let from_ether_type = SlicedPacket::from_ether_type_with_registry(
+        EXPERIMENTAL,
+        &packet[Ethernet2Header::SERIALIZED_SIZE..],
+        &registry
+    ).unwrap();
+    assert_eq!(None, from_ether_type.link);
+    assert_eq!(sliced.link_extension, from_ether_type.link_extension);
+    assert_eq!(sliced.ip, from_ether_type.ip);
+    assert_eq!(sliced.transport, from_ether_type.transport);
+
+    // without a registry the data after the ethernet header is the payload
+    let unregistered = SlicedPacket::from_ethernet(&packet).unwrap();
+    assert_eq!(None, unregistered.link_extension);
+    assert_eq!(None, unregistered.ip);
+    assert_eq!(&packet[Ethernet2Header::SERIALIZED_SIZE..], unregistered.payload);
+    assert_eq!(Some(EXPERIMENTAL), unregistered.payload_ether_type());
+}
+
+#[test]
+fn sliced_packet_vlan() {
+    let mut packet = Vec::new();
+    Ethernet2Header{
+        source: [1,2,3,4,5,6],
+        destination: [7,8,9,10,11,12],
+        ether_type: ether_type::VLAN_TAGGED_FRAME,
+    }.write(&mut packet).unwrap();
+    SingleVlanHeader{
+        priority_code_point: 0,
+        drop_eligible_indicator: false,
+        vlan_identifier: 1,
+        ether_type: EXPERIMENTAL,
+    }.write(&mut packet).unwrap();
+    // unknown next ether type
+    packet.extend_from_slice(&[0xab, 0xcd, 0x12, 0x34, 1, 2]);
+
+    let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry()).unwrap();
+    assert!(sliced.vlan.is_some());
+    assert_eq!(&[0xab, 0xcd, 0x12, 0x34], sliced.link_extension.as_ref().unwrap().header);
+    assert_eq!(None, sliced.ip);
+    assert_eq!(&[1, 2], sliced.payload);
+    assert_eq!(Some(0x1234), sliced.payload_ether_type());
+}
+
+#[test]
+fn sliced_packet_errors() {
+    let packet = packet(&[]);
+    let registry = registry();
+
+    // error of the parser (offset is added)
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_registry(&packet[..Ethernet2Header::SERIALIZED_SIZE + 3], &registry),
+        Err(ReadError::UnexpectedEndOfSlice(18))
+    );
+
+    // header length reported by the parser is bigger than the slice
+    let mut too_long = ParserRegistry::new();
+    too_long.register_ether_type(EXPERIMENTAL, |_: u16, _: &[u8]| {
+        Ok(LinkExtensionInfo{ header_len: 1000, next_ether_type: None })
+    });
+    assert_matches!(
+        SlicedPacket::from_ethernet_with_registry(&packet, &too_long),
+        Err(ReadError::UnexpectedEndOfSlice(1014))
+    );
+}
+
+#[test]
+fn builtin_ether_types_not_replaced() {
+    let mut registry = ParserRegistry::new();
+    registry.register_ether_type(ether_type::IPV4, |_: u16, _: &[u8]| {
+        Ok(LinkExtensionInfo{ header_len: 0, next_ether_type: None })
+    });
+    let packet = packet(&[1,2,3,4]);
+    let ipv4 = &packet[Ethernet2Header::SERIALIZED_SIZE + 4..];
+
+    let sliced = SlicedPacket::from_ether_type_with_registry(ether_type::IPV4, ipv4, &registry).unwrap();
+    assert_eq!(None, sliced.link_extension);
+    assert!(sliced.ip.is_some());
+
+    let decoded = PacketHeaders::from_ether_type_with_registry(ether_type::IPV4, ipv4, &registry).unwrap();
+    assert_eq!(None, decoded.link_extension);
+    assert!(decoded.ip.is_some());
+
+    // nsh headers nested deeper then the tunnel limit are left in the payload
+    let mut registry = ParserRegistry::new();
+    registry.register_ether_type(ether_type::NSH, |_: u16, _: &[u8]| {
+        Err(ReadError::UnexpectedEndOfSlice(1000))
+    });
+    registry.set_limits(ParseLimits{ max_tunnel_depth: 0, ..Default::default() });
+    let mut nsh = Vec::new();
+    NshHeader::new(100, 5, nsh_next_protocol::IPV4).unwrap()
+        .write(&mut nsh)
+        .unwrap();
+
+    let sliced = SlicedPacket::from_ether_type_with_registry(ether_type::NSH, &nsh, &registry).unwrap();
+    assert_eq!(None, sliced.link_extension);
+    assert_eq!(&nsh[..], sliced.payload);
+
+    let decoded = PacketHeaders::from_ether_type_with_registry(ether_type::NSH, &nsh, &registry).unwrap();
+    assert_eq!(None, decoded.link_extension);
+    assert_eq!(&nsh[..], decoded.payload);
+}
+
+#[test]
+fn packet_headers() {
+    let packet = packet(&[1,2,3,4]);
+    let registry = registry();
+
+    let decoded = PacketHeaders::from_ethernet_slice_with_registry(&packet, &registry).unwrap();
+    assert_eq!(
+        Some(LinkExtensionSlice{
+            ether_type: EXPERIMENTAL,
+            header: &packet[Ethernet2Header::SERIALIZED_SIZE..Ethernet2Header::SERIALIZED_SIZE + 4],
+            next_ether_type: Some(ether_type::IPV4),
+        }),
+        decoded.link_extension
+    );
+    assert!(decoded.ip.is_some());
+    assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+    assert_eq!(&[1,2,3,4], decoded.payload);
+
+    let from_ether_type = PacketHeaders::from_ether_type_with_registry(
+        EXPERIMENTAL,
+        &packet[Ethernet2Header::SERIALIZED_SIZE..],
+        &registry
+    ).unwrap();
+    assert_eq!(decoded.link_extension, from_ether_type.link_extension);
+    assert_eq!(decoded.ip, from_ether_type.ip);
+    assert_eq!(decoded.payload, from_ether_type.payload);
+
+    // unknown next ether type
+    let mut other = packet.clone();
+    other[Ethernet2Header::SERIALIZED_SIZE + 2..Ethernet2Header::SERIALIZED_SIZE + 4].copy_from_slice(&[0x12, 0x34]);
+    let decoded = PacketHeaders::from_ethernet_slice_with_registry(&other, &registry).unwrap();
+    assert!(decoded.link_extension.is_some());
+    assert_eq!(None, decoded.ip);
+    assert_eq!(&other[Ethernet2Header::SERIALIZED_SIZE + 4..], decoded.payload);
+    assert_eq!(Some(0x1234), decoded.payload_ether_type());
+
+    // errors
+    assert_matches!(
+        PacketHeaders::from_ethernet_slice_with_registry(&packet[..Ethernet2Header::SERIALIZED_SIZE + 3], &registry),
+        Err(ReadError::UnexpectedEndOfSlice(4))
+    );
+
+    // without a registry the data after the ethernet header is the payload
+    let unregistered = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(None, unregistered.link_extension);
+    assert_eq!(&packet[Ethernet2Header::SERIALIZED_SIZE..], unregistered.payload);
+}
+
+/// Experimental ip number (RFC 3692).
+const EXPERIMENTAL_IP_NUMBER: u8 = 253;
+
+/// Parser for a header where the first byte contains the header length.
+fn length_prefixed_parser(_ip_number: u8, slice: &[u8]) -> Result<usize, ReadError> {
+    match slice.first() {
+        Some(len) => Ok(usize::from(*len)),
+        None => Err(ReadError::UnexpectedEndOfSlice(1)),
+    }
+}
+
+fn ip_number_registry() -> ParserRegistry {
+    let mut result = ParserRegistry::new();
+    result.register_ip_number(EXPERIMENTAL_IP_NUMBER, length_prefixed_parser);
+    result
+}
+
+/// Ipv4 packet with the experimental ip number.
+fn ipv4_packet(ip_payload: &[u8]) -> Vec<u8> {
+    let mut result = Vec::new();
+    Ipv4Header::new(
+        ip_payload.len() as u16,
+        20,
+        EXPERIMENTAL_IP_NUMBER,
+        [192,168,1,1],
+        [192,168,1,2]
+    ).write(&mut result).unwrap();
+    result.extend_from_slice(ip_payload);
+    result
+}
+
+#[test]
+fn register_ip_number() {
+    let mut registry = ParserRegistry::new();
+    assert!(registry.ip_number_parser(EXPERIMENTAL_IP_NUMBER).is_none());
+
+    registry.register_ip_number(EXPERIMENTAL_IP_NUMBER, |_: u8, _: &[u8]| Ok(1));
+    assert_matches!(
+        registry.ip_number_parser(EXPERIMENTAL_IP_NUMBER).unwrap().parse(EXPERIMENTAL_IP_NUMBER, &[]),
+        Ok(1)
+    );
+
+    // registering the same ip number again replaces the parser
+    registry.register_ip_number(EXPERIMENTAL_IP_NUMBER, |_: u8, _: &[u8]| Ok(2));
+    assert_matches!(
+        registry.ip_number_parser(EXPERIMENTAL_IP_NUMBER).unwrap().parse(EXPERIMENTAL_IP_NUMBER, &[]),
+        Ok(2)
+    );
+    assert_eq!(
+        format!("ParserRegistry {{ ether_types: [], ip_numbers: [253], udp_ports: [], tcp_ports: [], limits: {:?}, ether_type_length_policy: ForceEthernet2 }}", ParseLimits::default()),
+        format!("{:?}", registry)
+    );
+}
+
+#[test]
+fn sliced_packet_ip_number() {
+    let packet = ipv4_packet(&[3, 0xab, 0xcd, 1, 2, 3, 4]);
+    let registry = ip_number_registry();
+
+    let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+    assert_eq!(Some(TransportSlice::Unknown(EXPERIMENTAL_IP_NUMBER)), sliced.transport);
+    assert_eq!(
+        Some(TransportExtensionSlice{
+            ip_number: EXPERIMENTAL_IP_NUMBER,
+            header: &[3, 0xab, 0xcd],
+        }),
+        sliced.transport_extension
+    );
+    assert_eq!(&[1,2,3,4], sliced.payload);
+
+    // also called when slicing from the ethernet layer
+    {
+        let mut ethernet = Vec::new();
+        Ethernet2Header{
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::IPV4,
+        }.write(&mut ethernet).unwrap();
+        ethernet.extend_from_slice(&packet);
+        let sliced_ethernet = SlicedPacket::from_ethernet_with_registry(&ethernet, &registry).unwrap();
+        assert_eq!(sliced.transport_extension, sliced_ethernet.transport_extension);
+        assert_eq!(sliced.payload, sliced_ethernet.payload);
+    }
+
+    // ipv6
+    {
+        let mut ipv6 = Vec::new();
+        Ipv6Header{
+            traffic_class: 0,
+            flow_label: 0,
+            payload_length: 7,
+            next_header: EXPERIMENTAL_IP_NUMBER,
+            hop_limit: 20,
+            source: [1;16],
+            destination: [2;16],
+        }.write(&mut ipv6).unwrap();
+        ipv6.extend_from_slice(&[3, 0xab, 0xcd, 1, 2, 3, 4]);
+        let sliced_ipv6 = SlicedPacket::from_ip_with_registry(&ipv6, &registry).unwrap();
+        assert_eq!(sliced.transport_extension, sliced_ipv6.transport_extension);
+        assert_eq!(sliced.payload, sliced_ipv6.payload);
+    }
+
+    // without a registry the ip payload is the payload
+    let unregistered = SlicedPacket::from_ip(&packet).unwrap();
+    assert_eq!(Some(TransportSlice::Unknown(EXPERIMENTAL_IP_NUMBER)), unregistered.transport);
+    assert_eq!(None, unregistered.transport_extension);
+    assert_eq!(&packet[Ipv4Header::SERIALIZED_SIZE..], unregistered.payload);
+}
+
+#[test]
+fn sliced_packet_ip_number_fragmented() {
+    let mut packet = ipv4_packet(&[3, 0xab, 0xcd, 1, 2, 3, 4]);
+    // set the more fragments flag & recalculate the checksum
+    {
+        let mut header = Ipv4Header::from_slice(&packet).unwrap().0;
+        header.more_fragments = true;
+        header.header_checksum = header.calc_header_checksum().unwrap();
+        let mut data = Vec::new();
+        header.write_raw(&mut data).unwrap();
+        packet[..Ipv4Header::SERIALIZED_SIZE].copy_from_slice(&data);
+    }
+    let sliced = SlicedPacket::from_ip_with_registry(&packet, &ip_number_registry()).unwrap();
+    assert_eq!(None, sliced.transport_extension);
+    assert_eq!(&packet[Ipv4Header::SERIALIZED_SIZE..], sliced.payload);
+}
+
+#[test]
+fn sliced_packet_ip_number_errors() {
+    let registry = ip_number_registry();
+
+    // error of the parser (offset is added)
+    let packet = ipv4_packet(&[]);
+    assert_matches!(
+        SlicedPacket::from_ip_with_registry(&packet, &registry),
+        Err(ReadError::UnexpectedEndOfSlice(21))
+    );
+
+    // header length reported by the parser is bigger than the slice
+    let packet = ipv4_packet(&[10, 1]);
+    assert_matches!(
+        SlicedPacket::from_ip_with_registry(&packet, &registry),
+        Err(ReadError::UnexpectedEndOfSlice(30))
+    );
+}
+
+#[test]
+fn builtin_ip_numbers_not_replaced() {
+    let mut registry = ParserRegistry::new();
+    registry.register_ip_number(ip_number::UDP, |_: u8, _: &[u8]| Ok(0));
+
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+
+    let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+    assert_eq!(None, sliced.transport_extension);
+    assert_matches!(sliced.transport, Some(TransportSlice::Udp(_)));
+
+    let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+    assert_eq!(None, decoded.transport_extension);
+    assert_matches!(decoded.transport, Some(TransportHeader::Udp(_)));
+
+    // igmp is only sliced, but also never passed to a registered parser
+    let mut registry = ParserRegistry::new();
+    registry.register_ip_number(ip_number::IGMP, |_: u8, _: &[u8]| Err(ReadError::UnexpectedEndOfSlice(1000)));
+
+    let mut packet = Vec::new();
+    Ipv4Header::new(8, 1, ip_number::IGMP, [10,0,0,1], [224,0,0,1])
+        .write(&mut packet)
+        .unwrap();
+    IgmpHeader::LeaveGroup{ group_address: [239,1,2,3] }
+        .write(&mut packet, &[])
+        .unwrap();
+
+    let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+    assert_eq!(None, sliced.transport_extension);
+    assert_matches!(sliced.transport, Some(TransportSlice::Igmp(_)));
+
+    let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+    assert_eq!(None, decoded.transport_extension);
+    assert_eq!(&packet[Ipv4Header::SERIALIZED_SIZE..], decoded.payload);
+}
+
+#[test]
+fn packet_headers_ip_number() {
+    let packet = ipv4_packet(&[3, 0xab, 0xcd, 1, 2, 3, 4]);
+    let registry = ip_number_registry();
+
+    let decoded = PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap();
+    assert_eq!(None, decoded.transport);
+    assert_eq!(
+        Some(TransportExtensionSlice{
+            ip_number: EXPERIMENTAL_IP_NUMBER,
+            header: &[3, 0xab, 0xcd],
+        }),
+        decoded.transport_extension
+    );
+    assert_eq!(&[1,2,3,4], decoded.payload);
+
+    // also called when decoding from the ethernet layer
+    {
+        let mut ethernet = Vec::new();
+        Ethernet2Header{
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::IPV4,
+        }.write(&mut ethernet).unwrap();
+        ethernet.extend_from_slice(&packet);
+        let decoded_ethernet = PacketHeaders::from_ethernet_slice_with_registry(&ethernet, &registry).unwrap();
+        assert_eq!(decoded.transport_extension, decoded_ethernet.transport_extension);
+        assert_eq!(decoded.payload, decoded_ethernet.payload);
+
+        let decoded_ether_type = PacketHeaders::from_ether_type_with_registry(ether_type::IPV4, &packet, &registry).unwrap();
+        assert_eq!(decoded.transport_extension, decoded_ether_type.transport_extension);
+    }
+
+    // errors
+    assert_matches!(
+        PacketHeaders::from_ip_slice_with_registry(&ipv4_packet(&[10, 1]), &registry),
+        Err(ReadError::UnexpectedEndOfSlice(10))
+    );
+
+    // without a registry the ip payload is the payload
+    let unregistered = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_eq!(None, unregistered.transport_extension);
+    assert_eq!(&packet[Ipv4Header::SERIALIZED_SIZE..], unregistered.payload);
+}
+
+#[test]
+fn register_ports() {
+    use ApplicationProtocol::*;
+
+    let mut registry = ParserRegistry::new();
+    assert_eq!(None, registry.udp_port_protocol(53));
+    assert_eq!(None, registry.tcp_port_protocol(53));
+
+    // udp
+    registry.register_udp_port(53, Dns);
+    registry.register_udp_port(8472, Vxlan);
+    assert_eq!(Some(Dns), registry.udp_port_protocol(53));
+    assert_eq!(Some(Vxlan), registry.udp_port_protocol(8472));
+    assert_eq!(None, registry.tcp_port_protocol(53));
+
+    // replace
+    registry.register_udp_port(8472, Custom(1234));
+    assert_eq!(Some(Custom(1234)), registry.udp_port_protocol(8472));
+
+    // tcp
+    registry.register_tcp_port(53, Dns);
+    assert_eq!(Some(Dns), registry.tcp_port_protocol(53));
+    assert_eq!(
+        format!("ParserRegistry {{ ether_types: [], ip_numbers: [], udp_ports: [(53, Dns), (8472, Custom(1234))], tcp_ports: [(53, Dns)], limits: {:?}, ether_type_length_policy: ForceEthernet2 }}", ParseLimits::default()),
+        format!("{:?}", registry)
+    );
+
+    // unregister
+    assert_eq!(Some(Dns), registry.unregister_udp_port(53));
+    assert_eq!(None, registry.unregister_udp_port(53));
+    assert_eq!(None, registry.udp_port_protocol(53));
+    assert_eq!(Some(Dns), registry.unregister_tcp_port(53));
+    assert_eq!(None, registry.unregister_tcp_port(53));
+    assert_eq!(None, registry.tcp_port_protocol(53));
+}
+
+#[test]
+fn with_well_known_ports() {
+    use ApplicationProtocol::*;
+
+    let registry = ParserRegistry::with_well_known_ports();
+    assert_eq!(Some(Dns), registry.udp_port_protocol(53));
+    assert_eq!(Some(Dns), registry.udp_port_protocol(5353));
+    assert_eq!(Some(Dns), registry.tcp_port_protocol(53));
+    assert_eq!(Some(Hsrp), registry.udp_port_protocol(HsrpMessage::UDP_PORT));
+    assert_eq!(Some(Hsrp), registry.udp_port_protocol(HsrpMessage::IPV6_UDP_PORT));
+    assert_eq!(Some(Rip), registry.udp_port_protocol(RipMessageSlice::UDP_PORT));
+    assert_eq!(Some(Vxlan), registry.udp_port_protocol(4789));
+    assert_eq!(Some(GtpU), registry.udp_port_protocol(2152));
+    assert_eq!(Some(Geneve), registry.udp_port_protocol(GeneveHeader::UDP_PORT));
+    assert_eq!(Some(Stamp), registry.udp_port_protocol(StampSenderPacket::UDP_PORT));
+    assert_eq!(Some(Quic), registry.udp_port_protocol(QuicHeaderSlice::UDP_PORT));
+    assert_eq!(None, registry.tcp_port_protocol(QuicHeaderSlice::UDP_PORT));
+    assert_eq!(None, registry.udp_port_protocol(80));
+    assert_eq!(None, registry.tcp_port_protocol(5353));
+}
+
+#[test]
+fn sliced_packet_application() {
+    use ApplicationProtocol::*;
+
+    let mut registry = ParserRegistry::new();
+    registry.register_udp_port(4790, Custom(4790));
+    registry.register_udp_port(53, Dns);
+    registry.register_tcp_port(5353, Dns);
+
+    let udp_packet = |source_port: u16, destination_port: u16| {
+        let mut result = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(source_port, destination_port)
+            .write(&mut result, &[1,2,3,4])
+            .unwrap();
+        result
+    };
+
+    // destination port
+    {
+        let packet = udp_packet(1234, 4790);
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(Custom(4790)), sliced.application);
+        assert_eq!(&[1,2,3,4], sliced.payload);
+
+        // not set without a registry
+        assert_eq!(None, SlicedPacket::from_ip(&packet).unwrap().application);
+    }
+    // source port (e.g. responses)
+    {
+        let packet = udp_packet(53, 1234);
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(Dns), sliced.application);
+    }
+    // destination port takes precedence
+    {
+        let packet = udp_packet(53, 4790);
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(Custom(4790)), sliced.application);
+    }
+    // unknown ports
+    {
+        let packet = udp_packet(1234, 1235);
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(None, sliced.application);
+    }
+    // udp ports are not used for tcp
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .tcp(1234, 4790, 1, 1024)
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+        let sliced = SlicedPacket::from_ip_with_registry(&packet, &registry).unwrap();
+        assert_eq!(None, sliced.application);
+    }
+    // tcp
+    {
+        let mut packet = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+            .ipv6([0;16], [1;16], 20)
+            .tcp(5353, 1234, 1, 1024)
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+        let sliced = SlicedPacket::from_ethernet_with_registry(&packet, &registry).unwrap();
+        assert_eq!(Some(Dns), sliced.application);
+        assert_eq!(&[1,2,3,4], sliced.payload);
+    }
+}
@@ -0,0 +1,194 @@
+use super::*;
+
+use std::net::IpAddr;
+
+fn ipv4_udp_packet(payload_len: usize) -> Vec<u8> {
+    let builder = PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234);
+    let mut result = Vec::with_capacity(builder.size(payload_len));
+    builder.write(&mut result, &vec![0u8;payload_len]).unwrap();
+    result
+}
+
+fn ipv6_tcp_packet(payload_len: usize) -> Vec<u8> {
+    let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+        .tcp(21, 1234, 1, 2);
+    let mut result = Vec::with_capacity(builder.size(payload_len));
+    builder.write(&mut result, &vec![0u8;payload_len]).unwrap();
+    result
+}
+
+#[test]
+fn icmpv4_embedded_payload() {
+    let packet = ipv4_udp_packet(100);
+    assert_eq!(
+        &packet[..Ipv4Header::SERIALIZED_SIZE + 8],
+        PathMtuInfo::icmpv4_embedded_payload(&packet)
+    );
+    // shorter packets are returned completely
+    assert_eq!(
+        &packet[..Ipv4Header::SERIALIZED_SIZE + 4],
+        PathMtuInfo::icmpv4_embedded_payload(&packet[..Ipv4Header::SERIALIZED_SIZE + 4])
+    );
+    assert!(PathMtuInfo::icmpv4_embedded_payload(&[]).is_empty());
+}
+
+#[test]
+fn icmpv6_embedded_payload() {
+    let packet = ipv6_tcp_packet(1400);
+    assert_eq!(1232, PathMtuInfo::ICMPV6_MAX_EMBEDDED_LEN);
+    assert_eq!(
+        &packet[..PathMtuInfo::ICMPV6_MAX_EMBEDDED_LEN],
+        PathMtuInfo::icmpv6_embedded_payload(&packet)
+    );
+    assert_eq!(
+        &packet[..100],
+        PathMtuInfo::icmpv6_embedded_payload(&packet[..100])
+    );
+}
+
+#[test]
+fn icmpv4_fragmentation_needed() {
+    let original = ipv4_udp_packet(1400);
+    let message = {
+        let builder = PacketBuilder::ethernet2([0;6], [0;6])
+            .ipv4([10,0,0,1], [192,168,1,1], 20)
+            .icmpv4_fragmentation_needed(1280);
+        let payload = PathMtuInfo::icmpv4_embedded_payload(&original);
+        let mut result = Vec::with_capacity(builder.size(payload.len()));
+        builder.write(&mut result, payload).unwrap();
+        result
+    };
+
+    let sliced = SlicedPacket::from_ethernet(&message).unwrap();
+    let icmp = match sliced.transport.as_ref().unwrap() {
+        TransportSlice::Icmpv4(icmp) => icmp.clone(),
+        _ => panic!("expected icmpv4"),
+    };
+    assert_eq!(
+        Icmpv4Type::DestinationUnreachable(
+            icmpv4::DestUnreachableHeader::FragmentationNeeded{ next_hop_mtu: 1280 }
+        ),
+        icmp.icmp_type()
+    );
+    assert_eq!(PathMtuInfo::icmpv4_embedded_payload(&original), icmp.payload());
+
+    let expected = PathMtuInfo {
+        mtu: 1280,
+        flow: Some(FlowKey {
+            source_addr: IpAddr::from([192,168,1,1]),
+            destination_addr: IpAddr::from([192,168,1,2]),
+            ip_number: ip_number::UDP,
+            source_port: 21,
+            destination_port: 1234,
+        }),
+    };
+    assert_eq!(Some(expected), PathMtuInfo::from_icmpv4(&icmp));
+    assert_eq!(Some(expected), PathMtuInfo::from_sliced(&sliced));
+}
+
+#[test]
+fn icmpv6_packet_too_big() {
+    let original = ipv6_tcp_packet(1400);
+    let message = {
+        let builder = PacketBuilder::ipv6([3;16], [1;16], 20)
+            .icmpv6_packet_too_big(1280);
+        let payload = PathMtuInfo::icmpv6_embedded_payload(&original);
+        let mut result = Vec::with_capacity(builder.size(payload.len()));
+        builder.write(&mut result, payload).unwrap();
+        result
+    };
+    // the message fits exactly into the minimum ipv6 mtu
+    assert_eq!(1280, message.len());
+
+    let sliced = SlicedPacket::from_ip(&message).unwrap();
+    let icmp = match sliced.transport.as_ref().unwrap() {
+        TransportSlice::Icmpv6(icmp) => icmp.clone(),
+        _ => panic!("expected icmpv6"),
+    };
+    assert_eq!(Icmpv6Type::PacketTooBig{ mtu: 1280 }, icmp.icmp_type());
+
+    let expected = PathMtuInfo {
+        mtu: 1280,
+        flow: Some(FlowKey {
+            source_addr: IpAddr::from([1;16]),
+            destination_addr: IpAddr::from([2;16]),
+            ip_number: ip_number::TCP,
+            source_port: 21,
+            destination_port: 1234,
+        }),
+    };
+    assert_eq!(Some(expected), PathMtuInfo::from_icmpv6(&icmp));
+    assert_eq!(Some(expected), PathMtuInfo::from_sliced(&sliced));
+}
+
+#[test]
+fn flow_not_decodable() {
+    let decode = |embedded: &[u8]| {
+        let builder = PacketBuilder::ipv4([10,0,0,1], [192,168,1,1], 20)
+            .icmpv4_fragmentation_needed(576);
+        let mut message = Vec::with_capacity(builder.size(embedded.len()));
+        builder.write(&mut message, embedded).unwrap();
+        PathMtuInfo::from_sliced(&SlicedPacket::from_ip(&message).unwrap()).unwrap()
+    };
+
+    // no embedded packet
+    assert_eq!(PathMtuInfo{ mtu: 576, flow: None }, decode(&[]));
+
+    // ports are missing
+    let original = ipv4_udp_packet(100);
+    assert_eq!(
+        PathMtuInfo{ mtu: 576, flow: None },
+        decode(&original[..Ipv4Header::SERIALIZED_SIZE + 2])
+    );
+
+    // not the first fragment
+    {
+        let mut header = Ipv4Header::new(8, 20, ip_number::UDP, [192,168,1,1], [192,168,1,2]);
+        header.fragments_offset = 1;
+        let mut embedded = Vec::new();
+        header.write(&mut embedded).unwrap();
+        embedded.extend_from_slice(&[0;8]);
+        assert_eq!(PathMtuInfo{ mtu: 576, flow: None }, decode(&embedded));
+    }
+
+    // transport protocols without ports
+    {
+        let header = Ipv4Header::new(8, 20, ip_number::ICMP, [192,168,1,1], [192,168,1,2]);
+        let mut embedded = Vec::new();
+        header.write(&mut embedded).unwrap();
+        embedded.extend_from_slice(&[0;8]);
+        assert_eq!(
+            PathMtuInfo{
+                mtu: 576,
+                flow: Some(FlowKey {
+                    source_addr: IpAddr::from([192,168,1,1]),
+                    destination_addr: IpAddr::from([192,168,1,2]),
+                    ip_number: ip_number::ICMP,
+                    source_port: 0,
+                    destination_port: 0,
+                }),
+            },
+            decode(&embedded)
+        );
+    }
+}
+
+#[test]
+fn other_types() {
+    // echo requests contain no path mtu information
+    let builder = PacketBuilder::ipv4([10,0,0,1], [192,168,1,1], 20)
+        .icmpv4_echo_request(1, 2);
+    let mut message = Vec::new();
+    builder.write(&mut message, &[]).unwrap();
+    assert_eq!(None, PathMtuInfo::from_sliced(&SlicedPacket::from_ip(&message).unwrap()));
+
+    let builder = PacketBuilder::ipv6([1;16], [2;16], 20)
+        .icmpv6_echo_request(1, 2);
+    let mut message = Vec::new();
+    builder.write(&mut message, &[]).unwrap();
+    assert_eq!(None, PathMtuInfo::from_sliced(&SlicedPacket::from_ip(&message).unwrap()));
+
+    // no icmp
+    assert_eq!(None, PathMtuInfo::from_sliced(&SlicedPacket::from_ip(&ipv4_udp_packet(0)).unwrap()));
+}
@@ -11,7 +11,15 @@ pub fn error_field_any() -> impl Strategy<Value = ErrorField> {
         Just(Ipv4FragmentsOffset),
         Just(Ipv6FlowLabel),
         Just(VlanTagPriorityCodePoint),
-        Just(VlanTagVlanId)
+        Just(VlanTagVlanId),
+        Just(MplsLabel),
+        Just(MplsTrafficClass),
+        Just(VxlanVni),
+        Just(GeneveVni),
+        Just(NshTtl),
+        Just(NshServicePathId),
+        Just(PppoeVersion),
+        Just(PppoeType)
     ]
 }
 
@@ -53,7 +61,14 @@ pub static ETHERNET_KNOWN_ETHER_TYPES: &'static [u16] = &[
     ether_type::IPV6,
     ether_type::VLAN_TAGGED_FRAME,
     ether_type::PROVIDER_BRIDGING,
-    ether_type::VLAN_DOUBLE_TAGGED_FRAME
+    ether_type::VLAN_DOUBLE_TAGGED_FRAME,
+    ether_type::MPLS_UNICAST,
+    ether_type::MPLS_MULTICAST,
+    ether_type::ARP,
+    ether_type::NSH,
+    ether_type::PPPOE_DISCOVERY,
+    ether_type::PPPOE_SESSION,
+    ether_type::MACSEC
 ];
 
 prop_compose! {
@@ -211,10 +226,16 @@ prop_compose! {
 
 static IPV4_KNOWN_PROTOCOLS: &'static [u8] = &[
     ip_number::ICMP,
+    ip_number::IGMP,
     ip_number::UDP,
     ip_number::TCP,
     ip_number::AUTH,
     ip_number::IPV6_ICMP,
+    ip_number::GRE,
+    ip_number::SCTP,
+    ip_number::UDP_LITE,
+    ip_number::DCCP,
+    ip_number::ENCAP_SEC,
 ];
 
 prop_compose! {
@@ -321,8 +342,12 @@ static IPV6_KNOWN_NEXT_HEADERS: &'static [u8] = &[
     ip_number::MOBILITY,
     ip_number::HIP,
     ip_number::SHIM6,
+    ip_number::GRE,
+    ip_number::SCTP,
+    ip_number::UDP_LITE,
+    ip_number::DCCP,
+    ip_number::ENCAP_SEC,
     // currently not supported:
-    // - EncapsulatingSecurityPayload
     // - ExperimentalAndTesting0
     // - ExperimentalAndTesting1
 ];
@@ -389,6 +414,8 @@ prop_compose! {
         destination_options in ipv6_raw_extension_any(),
         has_routing in any::<bool>(),
         routing in ipv6_raw_extension_any(),
+        has_shim6 in any::<bool>(),
+        shim6 in ipv6_raw_extension_any(),
         has_fragment in any::<bool>(),
         fragment in ipv6_fragment_any(),
         has_auth in any::<bool>(),
@@ -422,6 +449,11 @@ prop_compose! {
             } else {
                 None
             },
+            shim6: if has_shim6 {
+                Some(Box::new(shim6))
+            } else {
+                None
+            },
             fragment: if has_fragment {
                 Some(fragment)
             } else {
@@ -542,6 +574,97 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    pub(crate) fn dccp_type_any(extended_sequence_numbers: bool)(
+            type_u8 in 0..=dccp_type::SYNC_ACK,
+            acknowledgement_number in 0..=if extended_sequence_numbers {
+                DccpHeader::MAX_SEQUENCE_NUMBER
+            } else {
+                DccpHeader::MAX_SHORT_SEQUENCE_NUMBER
+            },
+            service_code in any::<u32>(),
+            reset_code in any::<u8>(),
+            data in any::<[u8;3]>())
+        -> DccpType
+    {
+        use DccpType::*;
+        match type_u8 {
+            dccp_type::REQUEST => Request{ service_code },
+            dccp_type::RESPONSE => Response{ acknowledgement_number, service_code },
+            dccp_type::DATA => Data,
+            dccp_type::ACK => Ack{ acknowledgement_number },
+            dccp_type::DATA_ACK => DataAck{ acknowledgement_number },
+            dccp_type::CLOSE_REQ => CloseReq{ acknowledgement_number },
+            dccp_type::CLOSE => Close{ acknowledgement_number },
+            dccp_type::RESET => Reset{ acknowledgement_number, reset_code, data },
+            dccp_type::SYNC => Sync{ acknowledgement_number },
+            _ => SyncAck{ acknowledgement_number },
+        }
+    }
+}
+
+prop_compose! {
+    pub(crate) fn dccp_any()(
+            extended_sequence_numbers in any::<bool>())(
+            extended_sequence_numbers in Just(extended_sequence_numbers),
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            ccval in 0..=DccpHeader::MAX_4BIT_VALUE,
+            checksum_coverage in 0..=DccpHeader::MAX_4BIT_VALUE,
+            checksum in any::<u16>(),
+            sequence_number in 0..=if extended_sequence_numbers {
+                DccpHeader::MAX_SEQUENCE_NUMBER
+            } else {
+                DccpHeader::MAX_SHORT_SEQUENCE_NUMBER
+            },
+            dccp_type in dccp_type_any(extended_sequence_numbers),
+            options in proptest::collection::vec(any::<[u8;4]>(), 0..8))
+        -> DccpHeader
+    {
+        let mut result = DccpHeader::new(source_port, destination_port, sequence_number, dccp_type);
+        result.ccval = ccval;
+        result.checksum_coverage = checksum_coverage;
+        result.checksum = checksum;
+        result.extended_sequence_numbers = extended_sequence_numbers;
+        result.set_options(&options.concat()).unwrap();
+        result
+    }
+}
+
+prop_compose! {
+    pub(crate) fn udp_lite_any()(
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            checksum_coverage in any::<u16>(),
+            checksum in any::<u16>())
+        -> UdpLiteHeader
+    {
+        UdpLiteHeader {
+            source_port,
+            destination_port,
+            checksum_coverage,
+            checksum,
+        }
+    }
+}
+
+prop_compose! {
+    pub(crate) fn sctp_any()(
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            verification_tag in any::<u32>(),
+            checksum in any::<u32>())
+        -> SctpHeader
+    {
+        SctpHeader {
+            source_port,
+            destination_port,
+            verification_tag,
+            checksum,
+        }
+    }
+}
+
 prop_compose! {
     pub(crate) fn tcp_any()
         (data_offset in TCP_MINIMUM_DATA_OFFSET..(TCP_MAXIMUM_DATA_OFFSET + 1))
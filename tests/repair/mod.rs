@@ -0,0 +1,347 @@
+use super::*;
+use etherparse::repair::*;
+
+/// Sets all fields that can be repaired to 0 (ip protocol numbers to the
+/// reserved 255, as 0 would require a hop by hop header during writing).
+fn break_headers(headers: &mut PacketHeaders) {
+    if let Some(link) = &mut headers.link {
+        link.ether_type = 0;
+    }
+    match &mut headers.vlan {
+        Some(VlanHeader::Single(single)) => single.ether_type = 0,
+        Some(VlanHeader::Double(double)) => {
+            double.outer.ether_type = 0;
+            double.inner.ether_type = 0;
+        },
+        None => {},
+    }
+    match &mut headers.ip {
+        Some(IpHeader::Version4(header, _)) => {
+            header.payload_len = 0;
+            header.protocol = 255;
+            header.header_checksum = 0;
+        },
+        Some(IpHeader::Version6(header, _)) => {
+            header.payload_length = 0;
+            header.next_header = 255;
+        },
+        None => {},
+    }
+    match &mut headers.transport {
+        Some(TransportHeader::Udp(udp)) => {
+            udp.length = 0;
+            udp.checksum = 0;
+        },
+        Some(TransportHeader::Tcp(tcp)) => tcp.checksum = 0,
+        Some(TransportHeader::Icmpv4(icmp)) => icmp.checksum = 0,
+        Some(TransportHeader::Icmpv6(icmp)) => icmp.checksum = 0,
+        Some(TransportHeader::UdpLite(udp)) => udp.checksum = 0,
+        Some(TransportHeader::Dccp(dccp)) => dccp.checksum = 0,
+        Some(TransportHeader::Sctp(sctp)) => sctp.checksum = 0,
+        None => {},
+    }
+}
+
+/// Checks that the packet is restored after breaking the repairable fields of the decoded headers.
+fn assert_restored(packet: &[u8]) {
+    let mut headers = PacketHeaders::from_ethernet_slice(packet).unwrap();
+    assert_eq!(packet, &to_bytes(&headers).unwrap()[..]);
+
+    break_headers(&mut headers);
+    assert_ne!(packet, &to_bytes(&headers).unwrap()[..]);
+
+    fix(&mut headers).unwrap();
+    assert_eq!(packet, &to_bytes(&headers).unwrap()[..]);
+}
+
+proptest! {
+    #[test]
+    fn builder_packets(
+        source in any::<[u8;6]>(),
+        destination in any::<[u8;6]>(),
+        vlan_count in 0..3usize,
+        vlan_ids in any::<[u16;2]>(),
+        ipv6 in any::<bool>(),
+        ipv4_addresses in any::<[[u8;4];2]>(),
+        ipv6_addresses in any::<[[u8;16];2]>(),
+        ttl in any::<u8>(),
+        transport in 0..3u8,
+        ports in any::<[u16;2]>(),
+        payload in proptest::collection::vec(any::<u8>(), 0..64),
+    ) {
+        let vlan_ids = [vlan_ids[0] & 0xfff, vlan_ids[1] & 0xfff];
+        let builder = PacketBuilder::ethernet2(source, destination);
+        let vlan_builder = match vlan_count {
+            0 => None,
+            1 => Some(builder.clone().single_vlan(vlan_ids[0])),
+            _ => Some(builder.clone().double_vlan(vlan_ids[0], vlan_ids[1])),
+        };
+        let ip_builder = match (vlan_builder, ipv6) {
+            (None, false) => builder.ipv4(ipv4_addresses[0], ipv4_addresses[1], ttl),
+            (None, true) => builder.ipv6(ipv6_addresses[0], ipv6_addresses[1], ttl),
+            (Some(vlan), false) => vlan.ipv4(ipv4_addresses[0], ipv4_addresses[1], ttl),
+            (Some(vlan), true) => vlan.ipv6(ipv6_addresses[0], ipv6_addresses[1], ttl),
+        };
+        let mut packet = Vec::new();
+        match transport {
+            0 => ip_builder.udp(ports[0], ports[1]).write(&mut packet, &payload).unwrap(),
+            1 => ip_builder.tcp(ports[0], ports[1], 1, 1024).write(&mut packet, &payload).unwrap(),
+            _ if ipv6 => ip_builder.icmpv6_echo_request(ports[0], ports[1]).write(&mut packet, &payload).unwrap(),
+            _ => ip_builder.icmpv4_echo_request(ports[0], ports[1]).write(&mut packet, &payload).unwrap(),
+        }
+        assert_restored(&packet);
+    }
+}
+
+#[test]
+fn loose_headers() {
+    let mut headers = PacketHeaders {
+        link: Some(Ethernet2Header::default()),
+        vlan: Some(VlanHeader::Single(SingleVlanHeader::default())),
+        ip: Some(IpHeader::Version6(Default::default(), Default::default())),
+        transport: Some(TransportHeader::Udp(UdpHeader::default())),
+        payload: &[1, 2, 3, 4],
+        ..Default::default()
+    };
+    fix(&mut headers).unwrap();
+
+    let packet = to_bytes(&headers).unwrap();
+    assert_eq!(14 + 4 + 40 + 8 + 4, packet.len());
+    let decoded = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+    assert_eq!(headers, decoded);
+    assert_eq!(ether_type::VLAN_TAGGED_FRAME, decoded.link.unwrap().ether_type);
+    match decoded.ip.unwrap() {
+        IpHeader::Version6(ip, _) => {
+            assert_eq!(ip_number::UDP, ip.next_header);
+            assert_eq!(12, ip.payload_length);
+            let udp = decoded.transport.unwrap().udp().unwrap();
+            assert_eq!(12, udp.length);
+            assert_eq!(udp.calc_checksum_ipv6(&ip, &[1, 2, 3, 4]).unwrap(), udp.checksum);
+        },
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn ip_extensions() {
+    // authentication header between the ipv4 header & the udp header
+    let auth = IpAuthenticationHeader::new(0, 1, 2, &[3, 4, 5, 6]).unwrap();
+    let mut headers = PacketHeaders {
+        ip: Some(IpHeader::Version4(
+            Ipv4Header::new(0, 20, 0, [1, 2, 3, 4], [5, 6, 7, 8]),
+            Ipv4Extensions { auth: Some(auth.clone()) }
+        )),
+        transport: Some(TransportHeader::Udp(UdpHeader::default())),
+        payload: &[1, 2, 3, 4],
+        ..Default::default()
+    };
+    fix(&mut headers).unwrap();
+
+    match &headers.ip {
+        Some(IpHeader::Version4(ip, ext)) => {
+            assert_eq!(ip_number::AUTH, ip.protocol);
+            assert_eq!(ip_number::UDP, ext.auth.as_ref().unwrap().next_header);
+            assert_eq!((auth.header_len() + 8 + 4) as u16, ip.payload_len);
+            assert_eq!(ip.calc_header_checksum().unwrap(), ip.header_checksum);
+        },
+        _ => unreachable!(),
+    }
+    let packet = to_bytes(&headers).unwrap();
+    assert_eq!(headers, PacketHeaders::from_ip_slice(&packet).unwrap());
+}
+
+#[test]
+fn raw_layers() {
+    // ipv6 with a mobility header (stored as raw layer)
+    let mut packet = Vec::new();
+    Ipv6Header {
+        traffic_class: 0,
+        flow_label: 0,
+        payload_length: 8,
+        next_header: ip_number::MOBILITY,
+        hop_limit: 64,
+        source: [0; 16],
+        destination: [0; 16],
+    }.write(&mut packet).unwrap();
+    packet.extend_from_slice(&[59, 0, 0, 0, 0, 0, 0, 0]);
+
+    let mut headers = PacketHeaders::from_ip_slice(&packet).unwrap();
+    break_headers(&mut headers);
+    fix(&mut headers).unwrap();
+    assert_eq!(packet, to_bytes(&headers).unwrap());
+}
+
+#[test]
+fn other_link_layers() {
+    // arp
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1, 2, 3, 4, 5, 6],
+            destination: [0xff; 6],
+            ether_type: ether_type::ARP,
+        }.write(&mut packet).unwrap();
+        ArpPacket::request([1, 2, 3, 4, 5, 6], [192, 168, 1, 1], [192, 168, 1, 2])
+            .write(&mut packet)
+            .unwrap();
+        assert_restored(&packet);
+    }
+    // mpls (the multicast ether type is kept)
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1, 2, 3, 4, 5, 6],
+            destination: [7, 8, 9, 10, 11, 12],
+            ether_type: ether_type::MPLS_MULTICAST,
+        }.write(&mut packet).unwrap();
+        MplsHeader::new(1000, 2, true, 63).unwrap().write(&mut packet).unwrap();
+        PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+            .udp(1234, 53)
+            .write(&mut packet, &[1, 2, 3, 4])
+            .unwrap();
+
+        let mut headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        headers.link.as_mut().unwrap().ether_type = 0;
+        fix(&mut headers).unwrap();
+        assert_eq!(ether_type::MPLS_UNICAST, headers.link.as_ref().unwrap().ether_type);
+        headers.link.as_mut().unwrap().ether_type = ether_type::MPLS_MULTICAST;
+        fix(&mut headers).unwrap();
+        assert_eq!(packet, to_bytes(&headers).unwrap());
+    }
+}
+
+/// Returns loose headers of an ethernet II frame containing an udp packet.
+fn loose_inner() -> PacketHeaders<'static> {
+    PacketHeaders {
+        link: Some(Ethernet2Header::default()),
+        ip: Some(IpHeader::Version4(
+            Ipv4Header::new(0, 20, 0, [10, 0, 0, 1], [10, 0, 0, 2]),
+            Default::default()
+        )),
+        transport: Some(TransportHeader::Udp(UdpHeader::default())),
+        payload: &[1, 2, 3, 4],
+        ..Default::default()
+    }
+}
+
+/// Compares the repaired tunnel headers with the decoded ones (the decoder
+/// additionally keeps the encapsulated bytes as payload).
+fn assert_tunnel_decoded(headers: &PacketHeaders, mut decoded: PacketHeaders) {
+    assert_eq!(&to_bytes(headers.inner.as_ref().unwrap()).unwrap()[..], decoded.payload);
+    decoded.payload = &[];
+    assert_eq!(headers, &decoded);
+}
+
+#[test]
+fn gre() {
+    let mut headers = PacketHeaders {
+        ip: Some(IpHeader::Version4(
+            Ipv4Header::new(0, 20, 0, [192, 168, 1, 1], [192, 168, 1, 2]),
+            Default::default()
+        )),
+        gre: Some(GreHeader {
+            protocol_type: 0,
+            checksum: Some(0),
+            key: None,
+            sequence_number: None,
+        }),
+        inner: Some(Box::new(loose_inner())),
+        ..Default::default()
+    };
+    fix(&mut headers).unwrap();
+
+    let inner = to_bytes(headers.inner.as_ref().unwrap()).unwrap();
+    let gre = headers.gre.clone().unwrap();
+    assert_eq!(ether_type::TRANSPARENT_ETHERNET_BRIDGING, gre.protocol_type);
+    assert_eq!(Some(gre.calc_checksum(&inner)), gre.checksum);
+
+    let packet = to_bytes(&headers).unwrap();
+    let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+    match decoded.ip.as_ref().unwrap() {
+        IpHeader::Version4(ip, _) => {
+            assert_eq!(ip_number::GRE, ip.protocol);
+            assert_eq!((gre.header_len() + inner.len()) as u16, ip.payload_len);
+        },
+        _ => unreachable!(),
+    }
+    assert_tunnel_decoded(&headers, decoded);
+}
+
+#[test]
+fn udp_tunnels() {
+    let registry = ParserRegistry::with_well_known_ports();
+    let outer = |udp_port: u16| PacketHeaders {
+        ip: Some(IpHeader::Version6(Default::default(), Default::default())),
+        transport: Some(TransportHeader::Udp(UdpHeader {
+            destination_port: udp_port,
+            ..Default::default()
+        })),
+        inner: Some(Box::new(loose_inner())),
+        ..Default::default()
+    };
+
+    // vxlan
+    {
+        let mut headers = outer(VxlanHeader::UDP_PORT);
+        headers.vxlan = Some(VxlanHeader { flags: 0, vni: 1234 });
+        fix(&mut headers).unwrap();
+        assert!(headers.vxlan.as_ref().unwrap().vni_valid());
+
+        let packet = to_bytes(&headers).unwrap();
+        assert_tunnel_decoded(&headers, PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap());
+    }
+    // geneve
+    {
+        let mut geneve = GeneveHeader::new(0, 1234).unwrap();
+        geneve.set_options(&[GeneveOption{ class: 1, option_type: GeneveOption::CRITICAL_BIT, data: &[] }]).unwrap();
+        geneve.critical = false;
+
+        let mut headers = outer(GeneveHeader::UDP_PORT);
+        headers.geneve = Some(geneve);
+        fix(&mut headers).unwrap();
+        let geneve = headers.geneve.as_ref().unwrap();
+        assert!(geneve.critical);
+        assert_eq!(ether_type::TRANSPARENT_ETHERNET_BRIDGING, geneve.protocol_type);
+
+        let packet = to_bytes(&headers).unwrap();
+        assert_tunnel_decoded(&headers, PacketHeaders::from_ip_slice_with_registry(&packet, &registry).unwrap());
+    }
+}
+
+#[test]
+fn nsh() {
+    let mut inner = loose_inner();
+    inner.link = None;
+    let mut headers = PacketHeaders {
+        link: Some(Ethernet2Header::default()),
+        nsh: Some(NshHeader::new(1, 255, 0).unwrap()),
+        inner: Some(Box::new(inner)),
+        ..Default::default()
+    };
+    fix(&mut headers).unwrap();
+    assert_eq!(ether_type::NSH, headers.link.as_ref().unwrap().ether_type);
+    assert_eq!(nsh_next_protocol::IPV4, headers.nsh.as_ref().unwrap().next_protocol);
+
+    let packet = to_bytes(&headers).unwrap();
+    assert_tunnel_decoded(&headers, PacketHeaders::from_ethernet_slice(&packet).unwrap());
+}
+
+#[test]
+fn errors() {
+    let payload = [0u8; 0xffff];
+    let mut headers = PacketHeaders {
+        transport: Some(TransportHeader::Udp(UdpHeader::default())),
+        payload: &payload,
+        ..Default::default()
+    };
+    assert_eq!(
+        Err(ValueError::UdpPayloadLengthTooLarge(payload.len())),
+        fix(&mut headers)
+    );
+
+    // invalid values are reported when serializing
+    let mut headers = loose_inner();
+    headers.vxlan = Some(VxlanHeader { flags: 0, vni: VxlanHeader::MAX_VNI + 1 });
+    assert_matches!(fix(&mut headers), Err(ValueError::U32TooLarge{ .. }));
+    assert_matches!(to_bytes(&headers), Err(ValueError::U32TooLarge{ .. }));
+}
@@ -0,0 +1,169 @@
+use super::*;
+use etherparse::replay::*;
+use etherparse::rewrite::Layer;
+
+/// Returns an ethernet II, ipv4 & udp packet.
+fn udp_packet(ttl: u8, source_port: u16, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+        .ipv4([192, 168, 1, 1], [192, 168, 1, 2], ttl)
+        .udp(source_port, 1234)
+        .write(&mut packet, payload)
+        .unwrap();
+    packet
+}
+
+/// Returns the layer diffs of a difference (panics for other differences).
+fn layers(difference: Option<Difference>) -> Vec<LayerDiff> {
+    match difference {
+        Some(Difference::Layers(layers)) => layers,
+        other => panic!("unexpected difference {:?}", other),
+    }
+}
+
+proptest! {
+    #[test]
+    fn identical(
+        ttl in any::<u8>(),
+        source_port in any::<u16>(),
+        payload in proptest::collection::vec(any::<u8>(), 0..64),
+    ) {
+        let packet = udp_packet(ttl, source_port, &payload);
+        assert!(diff(&packet, &packet).is_none());
+        assert_eq!(0, replay(&[&packet, &packet], vec![packet.clone(), packet.clone()]).count());
+    }
+}
+
+#[test]
+fn layer_differences() {
+    let expected = udp_packet(20, 1, &[1, 2, 3, 4]);
+
+    // ip & transport layer
+    {
+        let actual = udp_packet(19, 2, &[1, 2, 3, 4]);
+        let result = layers(diff(&expected, &actual));
+        assert_eq!(
+            vec![Layer::Ip, Layer::Transport],
+            result.iter().map(|l| l.layer).collect::<Vec<_>>()
+        );
+        let decoded_expected = PacketHeaders::from_ethernet_slice(&expected).unwrap();
+        let decoded_actual = PacketHeaders::from_ethernet_slice(&actual).unwrap();
+        assert_eq!(
+            LayerDiff {
+                tunnel_depth: 0,
+                layer: Layer::Ip,
+                expected: Some(format!("{:?}", decoded_expected.ip.unwrap())),
+                actual: Some(format!("{:?}", decoded_actual.ip.unwrap())),
+            },
+            result[0]
+        );
+    }
+    // payload (the udp checksum differs as well)
+    {
+        let actual = udp_packet(20, 1, &[1, 2, 3, 5]);
+        let result = layers(diff(&expected, &actual));
+        assert_eq!(
+            vec![Layer::Transport, Layer::Payload],
+            result.iter().map(|l| l.layer).collect::<Vec<_>>()
+        );
+        // the lengths differ as well
+        let result = layers(diff(&expected, &udp_packet(20, 1, &[1, 2, 3, 4, 5])));
+        assert_eq!(
+            vec![Layer::Ip, Layer::Transport, Layer::Payload],
+            result.iter().map(|l| l.layer).collect::<Vec<_>>()
+        );
+    }
+    // missing layer
+    {
+        let mut actual = Vec::new();
+        PacketBuilder::ethernet2([1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12])
+            .single_vlan(1)
+            .ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+            .udp(1, 1234)
+            .write(&mut actual, &[1, 2, 3, 4])
+            .unwrap();
+        let result = layers(diff(&expected, &actual));
+        assert_eq!(Layer::Ethernet2, result[0].layer);
+        assert_eq!(Layer::Vlan, result[1].layer);
+        assert_eq!(None, result[1].expected);
+        assert!(result[1].actual.is_some());
+    }
+}
+
+#[test]
+fn tunnel_differences() {
+    let gre_packet = |inner_ttl: u8| {
+        let mut inner = Vec::new();
+        PacketBuilder::ipv4([10, 0, 0, 1], [10, 0, 0, 2], inner_ttl)
+            .udp(1, 2)
+            .write(&mut inner, &[1, 2, 3, 4])
+            .unwrap();
+        let mut payload = GreHeader::new(ether_type::IPV4).to_bytes().to_vec();
+        payload.extend_from_slice(&inner);
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([192, 168, 1, 1], [192, 168, 1, 2], 20)
+            .write(&mut packet, ip_number::GRE, &payload)
+            .unwrap();
+        packet
+    };
+
+    let result = layers(diff_ip(&gre_packet(20), &gre_packet(21)));
+    assert_eq!(1, result.len());
+    assert_eq!(1, result[0].tunnel_depth);
+    assert_eq!(Layer::Ip, result[0].layer);
+}
+
+#[test]
+fn undecodable() {
+    let packet = udp_packet(20, 1, &[]);
+
+    // identical undecodable packets are not reported
+    assert!(diff(&packet[..20], &packet[..20]).is_none());
+
+    assert_matches!(
+        diff(&packet, &packet[..20]),
+        Some(Difference::Undecodable{ expected: None, actual: Some(ReadError::UnexpectedEndOfSlice(_)) })
+    );
+    assert_matches!(
+        diff(&packet[..20], &packet[..21]),
+        Some(Difference::Undecodable{ expected: Some(_), actual: Some(_) })
+    );
+}
+
+#[test]
+fn replay_sequences() {
+    let expected = vec![
+        udp_packet(20, 1, &[]),
+        udp_packet(20, 2, &[]),
+        udp_packet(20, 3, &[]),
+    ];
+
+    // missing packet
+    {
+        let actual = vec![udp_packet(20, 1, &[]), udp_packet(20, 2, &[])];
+        let result: Vec<_> = replay(&expected, &actual).collect();
+        assert_eq!(1, result.len());
+        assert_eq!(2, result[0].index);
+        assert_matches!(result[0].difference, Difference::Missing);
+    }
+    // unexpected & differing packets
+    {
+        let mut actual = expected.clone();
+        actual[0] = udp_packet(20, 4, &[]);
+        actual.push(udp_packet(20, 1, &[]));
+        let result: Vec<_> = replay(&expected, &actual).collect();
+        assert_eq!(2, result.len());
+        assert_eq!(0, result[0].index);
+        assert_matches!(&result[0].difference, Difference::Layers(layers) if Layer::Transport == layers[0].layer);
+        assert_eq!(3, result[1].index);
+        assert_matches!(result[1].difference, Difference::Unexpected);
+    }
+    // ip packets
+    {
+        let expected: Vec<_> = expected.iter().map(|p| &p[Ethernet2Header::SERIALIZED_SIZE..]).collect();
+        let mut actual = expected.clone();
+        actual.swap(0, 1);
+        let result: Vec<_> = replay_ip(expected, actual).collect();
+        assert_eq!(vec![0, 1], result.iter().map(|d| d.index).collect::<Vec<_>>());
+    }
+}
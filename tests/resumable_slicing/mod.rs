@@ -0,0 +1,130 @@
+use super::*;
+
+/// Serializes a tcp packet with options starting with an ethernet II header.
+fn tcp_packet(payload: &[u8]) -> Vec<u8> {
+    let builder = PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .tcp(21, 1234, 1, 1000)
+        .options(&[TcpOptionElement::MaximumSegmentSize(1400), TcpOptionElement::Noop, TcpOptionElement::Noop])
+        .unwrap();
+    let mut packet = Vec::with_capacity(builder.size(payload.len()));
+    builder.write(&mut packet, payload).unwrap();
+    packet
+}
+
+proptest! {
+    #[test]
+    fn byte_by_byte(payload in proptest::collection::vec(any::<u8>(), 0..32)) {
+        let packet = tcp_packet(&payload);
+        let mut slicer = ResumableSlicer::new(PacketSetLayer::Ethernet2);
+        for (index, byte) in packet.iter().enumerate() {
+            slicer.push(&[*byte]);
+            let last = index + 1 == packet.len();
+            match slicer.try_slice().unwrap() {
+                SliceProgress::Complete(sliced) => {
+                    assert!(last);
+                    assert_eq!(&payload[..], sliced.payload);
+                },
+                SliceProgress::Incomplete{ required_len } => {
+                    assert!(!last);
+                    assert!(index + 1 < required_len);
+                    assert!(required_len <= packet.len());
+                    assert_eq!(required_len, slicer.required_len());
+                    assert_eq!(required_len - index - 1, slicer.missing_len());
+                },
+            }
+        }
+        assert_eq!(packet, slicer.take());
+        assert!(slicer.buffer().is_empty());
+        assert_eq!(0, slicer.required_len());
+    }
+}
+
+#[test]
+fn continuation_state() {
+    let packet = tcp_packet(&[1, 2, 3, 4]);
+    let tcp_end = Ethernet2Header::SERIALIZED_SIZE + Ipv4Header::SERIALIZED_SIZE + 28;
+    let mut slicer = ResumableSlicer::new(PacketSetLayer::Ethernet2);
+    assert_eq!(PacketSetLayer::Ethernet2, slicer.layer());
+
+    // cut off in the tcp options
+    slicer.push(&packet[..tcp_end - 2]);
+    assert_eq!(
+        SliceProgress::Incomplete{ required_len: tcp_end },
+        slicer.try_slice().unwrap()
+    );
+
+    // not enough bytes to resume (the state is kept)
+    slicer.push(&packet[tcp_end - 2..tcp_end - 1]);
+    assert_eq!(1, slicer.missing_len());
+    assert_eq!(
+        SliceProgress::Incomplete{ required_len: tcp_end },
+        slicer.try_slice().unwrap()
+    );
+
+    // headers complete but the ip payload is still missing
+    slicer.push(&packet[tcp_end - 1..tcp_end]);
+    assert_eq!(
+        SliceProgress::Incomplete{ required_len: packet.len() },
+        slicer.try_slice().unwrap()
+    );
+
+    slicer.push(&packet[tcp_end..]);
+    assert_matches!(
+        slicer.try_slice(),
+        Ok(SliceProgress::Complete(sliced)) if sliced.payload == [1, 2, 3, 4]
+    );
+
+    // clear
+    slicer.clear();
+    assert!(slicer.buffer().is_empty());
+    assert_eq!(0, slicer.required_len());
+}
+
+#[test]
+fn ip_layer() {
+    let mut packet = Vec::new();
+    PacketBuilder::ipv6([1;16], [2;16], 20)
+        .udp(1, 2)
+        .write(&mut packet, &[1, 2, 3, 4])
+        .unwrap();
+
+    let mut slicer = ResumableSlicer::new(PacketSetLayer::Ip);
+    slicer.push(&packet[..10]);
+    assert_eq!(
+        SliceProgress::Incomplete{ required_len: Ipv6Header::SERIALIZED_SIZE },
+        slicer.try_slice().unwrap()
+    );
+    slicer.push(&packet[10..packet.len() - 1]);
+    assert_eq!(
+        SliceProgress::Incomplete{ required_len: packet.len() },
+        slicer.try_slice().unwrap()
+    );
+    slicer.push(&packet[packet.len() - 1..]);
+    assert_matches!(slicer.try_slice(), Ok(SliceProgress::Complete(_)));
+}
+
+#[test]
+fn errors() {
+    // errors that more data can not fix are returned
+    let mut packet = tcp_packet(&[]);
+    // ipv4 ether type with an ip version of 5
+    packet[Ethernet2Header::SERIALIZED_SIZE] = 0x55;
+    let mut slicer = ResumableSlicer::new(PacketSetLayer::Ethernet2);
+    slicer.push(&packet);
+    assert_matches!(slicer.try_slice(), Err(ReadError::Ipv4UnexpectedVersion(5)));
+    assert_eq!(0, slicer.required_len());
+}
+
+#[test]
+fn debug_clone_eq() {
+    let slicer = ResumableSlicer::new(PacketSetLayer::Ip);
+    assert_eq!(slicer, slicer.clone());
+    assert_eq!(
+        "ResumableSlicer { layer: Ip, buffer: [], required_len: 0 }",
+        format!("{:?}", slicer)
+    );
+    let progress = SliceProgress::Incomplete{ required_len: 1 };
+    assert_eq!(progress, progress.clone());
+    assert_eq!("Incomplete { required_len: 1 }", format!("{:?}", progress));
+}
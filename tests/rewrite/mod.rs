@@ -0,0 +1,195 @@
+use super::*;
+use etherparse::rewrite::*;
+
+/// Ethernet II, single vlan, ipv4 & udp packet.
+fn udp_packet() -> Vec<u8> {
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .single_vlan(0x123)
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+    packet
+}
+
+#[test]
+fn layer_ranges() {
+    let packet = udp_packet();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    for (layer, expected) in [
+        (Layer::Ethernet2, Some(0..14)),
+        (Layer::Vlan, Some(14..18)),
+        (Layer::Ip, Some(18..38)),
+        (Layer::Transport, Some(38..46)),
+        (Layer::Payload, Some(46..50)),
+        (Layer::Mpls, None),
+        (Layer::Gre, None),
+    ] {
+        assert_eq!(Ok(expected), layer_range(&packet, &sliced, layer));
+    }
+}
+
+#[test]
+fn layer_range_ip_extensions() {
+    let mut packet = Vec::new();
+    let auth = IpAuthenticationHeader::new(ip_number::UDP, 1, 2, &[0;4]).unwrap();
+    PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+    // insert an authentication header between the ipv4 & udp header
+    packet[9] = ip_number::AUTH;
+    packet[3] += auth.header_len() as u8;
+    let udp = packet.split_off(20);
+    auth.write(&mut packet).unwrap();
+    packet.extend_from_slice(&udp);
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    let ip_end = 20 + auth.header_len();
+    assert_eq!(Ok(Some(0..ip_end)), layer_range(&packet, &sliced, Layer::Ip));
+    assert_eq!(Ok(Some(ip_end..ip_end + 8)), layer_range(&packet, &sliced, Layer::Transport));
+}
+
+#[test]
+fn replace_bytes() {
+    let packet = udp_packet();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+
+    // same length
+    {
+        let result = replace(&packet, &sliced, Layer::Payload, &[5,6,7,8]).unwrap();
+        assert_eq!(&packet[..46], &result[..46]);
+        assert_eq!(&[5,6,7,8], &result[46..50]);
+        assert_eq!(50, result.len());
+    }
+    // shorter & longer replacements
+    {
+        let result = replace(&packet, &sliced, Layer::Vlan, &[]).unwrap();
+        assert_eq!(&packet[..14], &result[..14]);
+        assert_eq!(&packet[18..], &result[14..]);
+
+        let result = replace(&packet, &sliced, Layer::Payload, &[9;10]).unwrap();
+        assert_eq!(&packet[..46], &result[..46]);
+        assert_eq!(&[9;10], &result[46..]);
+    }
+    // layer not present
+    assert_eq!(
+        Err(RewriteError::LayerNotPresent(Layer::Arp)),
+        replace(&packet, &sliced, Layer::Arp, &[])
+    );
+    // sliced from an other packet
+    {
+        let other = packet.clone();
+        assert_eq!(
+            Err(RewriteError::SliceNotInPacket),
+            replace(&other, &sliced, Layer::Ip, &[])
+        );
+        assert_eq!(
+            Err(RewriteError::SliceNotInPacket),
+            layer_range(&packet[..40], &sliced, Layer::Ip)
+        );
+    }
+}
+
+#[test]
+fn replace_headers() {
+    let packet = udp_packet();
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+
+    // ethernet
+    {
+        let header = sliced.link.as_ref().unwrap().to_header()
+            .with_source([0xa;6]);
+        let result = replace_ethernet2(&packet, &sliced, &header).unwrap();
+        assert_eq!(&[0xa;6], &result[6..12]);
+        assert_eq!(&packet[12..], &result[12..]);
+    }
+    // single vlan to double vlan
+    {
+        let header = VlanHeader::Double(DoubleVlanHeader {
+            outer: SingleVlanHeader {
+                vlan_identifier: 1,
+                ether_type: ether_type::VLAN_TAGGED_FRAME,
+                ..Default::default()
+            },
+            inner: match sliced.vlan.as_ref().unwrap().to_header() {
+                VlanHeader::Single(single) => single,
+                _ => unreachable!(),
+            },
+        });
+        let mut link = sliced.link.as_ref().unwrap().to_header();
+        link.ether_type = ether_type::PROVIDER_BRIDGING;
+        let result = replace_vlan(&packet, &sliced, &header).unwrap();
+        let result = {
+            let sliced = SlicedPacket::from_ethernet(&result).unwrap();
+            replace_ethernet2(&result, &sliced, &link).unwrap()
+        };
+        let decoded = PacketHeaders::from_ethernet_slice(&result).unwrap();
+        assert_eq!(Some(header), decoded.vlan);
+        assert_eq!(&packet[18..], &result[22..]);
+    }
+    // ip with options
+    {
+        let mut header = sliced.ip.as_ref().map(|ip| match ip {
+            InternetSlice::Ipv4(header, _) => header.to_header(),
+            _ => unreachable!(),
+        }).unwrap();
+        header.set_options(&[1,1,1,1]).unwrap();
+        let ip = IpHeader::Version4(header.clone(), Default::default());
+        let result = replace_ip(&packet, &sliced, &ip).unwrap();
+        assert_eq!(&packet[..18], &result[..18]);
+        assert_eq!(&header.to_bytes().unwrap()[..], &result[18..42]);
+        assert_eq!(&packet[38..], &result[42..]);
+    }
+    // transport
+    {
+        let mut udp = sliced.transport.as_ref().map(|transport| match transport {
+            TransportSlice::Udp(udp) => udp.to_header(),
+            _ => unreachable!(),
+        }).unwrap();
+        udp.destination_port = 80;
+        let result = replace_transport(&packet, &sliced, &TransportHeader::Udp(udp.clone())).unwrap();
+        assert_eq!(&udp.to_bytes()[..], &result[38..46]);
+        assert_eq!(&packet[46..], &result[46..]);
+        assert_eq!(&packet[..38], &result[..38]);
+    }
+    // value error
+    {
+        let mut header = Ipv4Header::new(0, 1, 0, [0;4], [0;4]);
+        header.differentiated_services_code_point = 0xff;
+        let ip = IpHeader::Version4(header, Default::default());
+        assert_matches!(
+            replace_ip(&packet, &sliced, &ip),
+            Err(RewriteError::Value(ValueError::U8TooLarge{ .. }))
+        );
+    }
+}
+
+#[test]
+fn rewrite_error() {
+    use std::error::Error;
+    use RewriteError::*;
+
+    assert_eq!(
+        "RewriteError: The layer Ip is not present in the sliced packet.",
+        format!("{}", LayerNotPresent(Layer::Ip))
+    );
+    assert_eq!(
+        "RewriteError: The sliced packet points to data outside of the given packet.",
+        format!("{}", SliceNotInPacket)
+    );
+    let value = ValueError::Ipv4OptionsLengthBad(3);
+    assert_eq!(
+        format!("RewriteError: {}", value),
+        format!("{}", Value(value.clone()))
+    );
+
+    assert!(LayerNotPresent(Layer::Ip).source().is_none());
+    assert!(SliceNotInPacket.source().is_none());
+    assert!(Value(value).source().is_some());
+
+    let err = LayerNotPresent(Layer::Payload);
+    assert_eq!(err, err.clone());
+    assert_eq!("LayerNotPresent(Payload)", format!("{:?}", err));
+}
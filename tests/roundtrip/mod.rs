@@ -0,0 +1,191 @@
+use super::*;
+use etherparse::roundtrip::*;
+
+proptest! {
+    #[test]
+    fn builder_packets(
+        source in any::<[u8;6]>(),
+        destination in any::<[u8;6]>(),
+        vlan_count in 0..3usize,
+        vlan_ids in any::<[u16;2]>(),
+        ipv6 in any::<bool>(),
+        ipv4_addresses in any::<[[u8;4];2]>(),
+        ipv6_addresses in any::<[[u8;16];2]>(),
+        ttl in any::<u8>(),
+        tcp in any::<bool>(),
+        ports in any::<[u16;2]>(),
+        payload in proptest::collection::vec(any::<u8>(), 0..64),
+    ) {
+        let vlan_ids = [vlan_ids[0] & 0xfff, vlan_ids[1] & 0xfff];
+        let ip_header = if ipv6 {
+            IpHeader::Version6(
+                Ipv6Header {
+                    traffic_class: 0,
+                    flow_label: 0,
+                    payload_length: 0,
+                    next_header: 0,
+                    hop_limit: ttl,
+                    source: ipv6_addresses[0],
+                    destination: ipv6_addresses[1],
+                },
+                Default::default()
+            )
+        } else {
+            IpHeader::Version4(
+                Ipv4Header::new(0, ttl, 0, ipv4_addresses[0], ipv4_addresses[1]),
+                Default::default()
+            )
+        };
+        let builder = PacketBuilder::ethernet2(source, destination);
+        let ip_builder = match vlan_count {
+            0 => builder.ip(ip_header),
+            1 => builder.single_vlan(vlan_ids[0]).ip(ip_header),
+            _ => builder.double_vlan(vlan_ids[0], vlan_ids[1]).ip(ip_header),
+        };
+        let mut packet = Vec::new();
+        if tcp {
+            ip_builder.tcp(ports[0], ports[1], 1, 1024).write(&mut packet, &payload).unwrap();
+        } else {
+            ip_builder.udp(ports[0], ports[1]).write(&mut packet, &payload).unwrap();
+        }
+
+        assert_eq!(Vec::<Mismatch>::new(), check(&packet).unwrap());
+        let ip_start = 14 + vlan_count*4;
+        assert_eq!(Vec::<Mismatch>::new(), check_ip(&packet[ip_start..]).unwrap());
+    }
+}
+
+#[test]
+fn non_ip_layers() {
+    // arp (with padding)
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [0xff;6],
+            ether_type: ether_type::ARP,
+        }.write(&mut packet).unwrap();
+        ArpPacket::request([1,2,3,4,5,6], [192,168,1,1], [192,168,1,2])
+            .write(&mut packet)
+            .unwrap();
+        packet.extend_from_slice(&[0;18]);
+        assert!(check(&packet).unwrap().is_empty());
+    }
+
+    // mpls
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::MPLS_UNICAST,
+        }.write(&mut packet).unwrap();
+        MplsHeader::new(16, 1, false, 64).unwrap().write(&mut packet).unwrap();
+        MplsHeader::new(1000, 2, true, 63).unwrap().write(&mut packet).unwrap();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1234, 53)
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+        assert!(check(&packet).unwrap().is_empty());
+    }
+
+    // additional vlan tag (raw layer)
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::VLAN_TAGGED_FRAME,
+        }.write(&mut packet).unwrap();
+        for index in 0..3 {
+            SingleVlanHeader {
+                priority_code_point: 0,
+                drop_eligible_indicator: false,
+                vlan_identifier: index,
+                ether_type: if 2 == index {
+                    ether_type::WAKE_ON_LAN
+                } else {
+                    ether_type::VLAN_TAGGED_FRAME
+                },
+            }.write(&mut packet).unwrap();
+        }
+        packet.extend_from_slice(&[1,2,3,4]);
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert_eq!(1, headers.raw_layers.len());
+        assert!(check(&packet).unwrap().is_empty());
+    }
+
+    // nsh
+    {
+        let mut packet = Vec::new();
+        Ethernet2Header {
+            source: [1,2,3,4,5,6],
+            destination: [7,8,9,10,11,12],
+            ether_type: ether_type::NSH,
+        }.write(&mut packet).unwrap();
+        NshHeader::new(100, 5, nsh_next_protocol::IPV4).unwrap()
+            .write(&mut packet)
+            .unwrap();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1234, 53)
+            .write(&mut packet, &[1,2,3,4])
+            .unwrap();
+        let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+        assert!(headers.nsh.is_some());
+        assert!(check(&packet).unwrap().is_empty());
+    }
+}
+
+#[test]
+fn mismatch() {
+    let mut packet = Vec::new();
+    PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+        .ipv4([192,168,1,1], [192,168,1,2], 20)
+        .udp(21, 1234)
+        .write(&mut packet, &[1,2,3,4])
+        .unwrap();
+
+    // set the reserved flag of the ipv4 header (not decoded)
+    packet[14 + 6] |= 0x80;
+    let mismatches = check(&packet).unwrap();
+    assert_eq!(1, mismatches.len());
+    assert_eq!(Layer::Ip, mismatches[0].layer);
+    assert_eq!(14, mismatches[0].offset);
+    assert_eq!(&packet[14..34], &mismatches[0].original[..]);
+    assert_eq!(
+        {
+            let mut expected = packet[14..34].to_vec();
+            expected[6] &= 0x7f;
+            expected
+        },
+        mismatches[0].serialized
+    );
+
+    let mismatches = check_ip(&packet[14..]).unwrap();
+    assert_eq!(1, mismatches.len());
+    assert_eq!(Layer::Ip, mismatches[0].layer);
+    assert_eq!(0, mismatches[0].offset);
+}
+
+#[test]
+fn read_error() {
+    assert_matches!(check(&[]), Err(ReadError::UnexpectedEndOfSlice(14)));
+    assert_matches!(check_ip(&[]), Err(ReadError::UnexpectedEndOfSlice(1)));
+}
+
+#[test]
+fn debug_clone_eq() {
+    let value = Mismatch {
+        layer: Layer::Raw(RawLayerKind::VlanTag(0x8100)),
+        offset: 1,
+        original: vec![2],
+        serialized: vec![3],
+    };
+    assert_eq!(value, value.clone());
+    assert_eq!(
+        "Mismatch { layer: Raw(VlanTag(33024)), offset: 1, original: [2], serialized: [3] }",
+        format!("{:?}", value)
+    );
+    let layer = Layer::Payload;
+    assert_eq!(layer, layer.clone());
+}
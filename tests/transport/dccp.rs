@@ -0,0 +1,613 @@
+use super::super::*;
+use std::io::Cursor;
+
+/// Reference implementation of the dccp checksum (rfc4340 section 9).
+fn reference_checksum(pseudo_header: &[u8], header: &DccpHeader, payload: &[u8]) -> u16 {
+    let mut data = pseudo_header.to_vec();
+    let mut bytes = header.to_bytes().unwrap();
+    bytes[6] = 0;
+    bytes[7] = 0;
+    data.extend_from_slice(&bytes);
+    let covered = if 0 == header.checksum_coverage {
+        payload.len()
+    } else {
+        (usize::from(header.checksum_coverage) - 1) * 4
+    };
+    data.extend_from_slice(&payload[..covered]);
+    if 1 == data.len() % 2 {
+        data.push(0);
+    }
+    let mut sum: u32 = 0;
+    for word in data.chunks(2) {
+        sum += u32::from(u16::from_be_bytes([word[0], word[1]]));
+    }
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+fn ipv4_pseudo_header(source: [u8;4], destination: [u8;4], len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&source);
+    result.extend_from_slice(&destination);
+    result.extend_from_slice(&[0, ip_number::DCCP]);
+    result.extend_from_slice(&(len as u16).to_be_bytes());
+    result
+}
+
+fn ipv6_pseudo_header(source: [u8;16], destination: [u8;16], len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&source);
+    result.extend_from_slice(&destination);
+    result.extend_from_slice(&(len as u32).to_be_bytes());
+    result.extend_from_slice(&[0, 0, 0, ip_number::DCCP]);
+    result
+}
+
+mod dccp_type {
+    use super::*;
+
+    #[test]
+    fn accessors() {
+        use DccpType::*;
+        let reset = Reset{ acknowledgement_number: 7, reset_code: 1, data: [2,3,4] };
+        let values = [
+            (Request{ service_code: 5 }, etherparse::dccp_type::REQUEST, None, Some(5)),
+            (Response{ acknowledgement_number: 7, service_code: 5 }, etherparse::dccp_type::RESPONSE, Some(7), Some(5)),
+            (Data, etherparse::dccp_type::DATA, None, None),
+            (Ack{ acknowledgement_number: 7 }, etherparse::dccp_type::ACK, Some(7), None),
+            (DataAck{ acknowledgement_number: 7 }, etherparse::dccp_type::DATA_ACK, Some(7), None),
+            (CloseReq{ acknowledgement_number: 7 }, etherparse::dccp_type::CLOSE_REQ, Some(7), None),
+            (Close{ acknowledgement_number: 7 }, etherparse::dccp_type::CLOSE, Some(7), None),
+            (reset, etherparse::dccp_type::RESET, Some(7), None),
+            (Sync{ acknowledgement_number: 7 }, etherparse::dccp_type::SYNC, Some(7), None),
+            (SyncAck{ acknowledgement_number: 7 }, etherparse::dccp_type::SYNC_ACK, Some(7), None),
+        ];
+        for (value, type_u8, acknowledgement_number, service_code) in values {
+            assert_eq!(type_u8, value.type_u8());
+            assert_eq!(acknowledgement_number, value.acknowledgement_number());
+            assert_eq!(service_code, value.service_code());
+        }
+    }
+}
+
+mod dccp_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in dccp_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..20),
+        ) {
+            let mut bytes = Vec::new();
+            header.write(&mut bytes).unwrap();
+            let header_len = bytes.len();
+            assert_eq!(header.header_len(), header_len);
+            bytes.extend_from_slice(&payload);
+
+            // normal read
+            {
+                let (actual, rest) = DccpHeader::from_slice(&bytes).unwrap();
+                assert_eq!(header, actual);
+                assert_eq!(&payload[..], rest);
+                assert_eq!(header, DccpHeader::read(&mut Cursor::new(&bytes)).unwrap());
+            }
+
+            // slice
+            {
+                let slice = DccpHeaderSlice::from_slice(&bytes).unwrap();
+                assert_eq!(&bytes[..header_len], slice.slice());
+                assert_eq!(header.source_port, slice.source_port());
+                assert_eq!(header.destination_port, slice.destination_port());
+                assert_eq!((header_len / 4) as u8, slice.data_offset());
+                assert_eq!(header.ccval, slice.ccval());
+                assert_eq!(header.checksum_coverage, slice.checksum_coverage());
+                assert_eq!(header.checksum, slice.checksum());
+                assert_eq!(header.dccp_type.type_u8(), slice.packet_type());
+                assert_eq!(header.extended_sequence_numbers, slice.extended_sequence_numbers());
+                assert_eq!(header.sequence_number, slice.sequence_number());
+                assert_eq!(header.dccp_type.acknowledgement_number(), slice.acknowledgement_number());
+                assert_eq!(header.dccp_type.service_code(), slice.service_code());
+                assert_eq!(header.dccp_type, slice.dccp_type());
+                assert_eq!(header.options(), slice.options());
+                assert_eq!(header, slice.to_header());
+            }
+
+            // length error
+            for len in 0..header_len {
+                let expected = if len < DccpHeader::MIN_LEN {
+                    DccpHeader::MIN_LEN
+                } else {
+                    header_len
+                };
+                assert_matches!(
+                    DccpHeader::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(value)) if value == expected
+                );
+                assert_matches!(
+                    DccpHeader::read(&mut Cursor::new(&bytes[..len])),
+                    Err(ReadError::IoError(_))
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn from_slice_error(header in dccp_any(), reserved_type in 10..=15u8) {
+            let mut bytes = header.to_bytes().unwrap();
+
+            // reserved packet type
+            {
+                let mut bytes = bytes.clone();
+                bytes[8] = (bytes[8] & 0b1110_0001) | (reserved_type << 1);
+                assert_matches!(
+                    DccpHeaderSlice::from_slice(&bytes),
+                    Err(ReadError::DccpPacketTypeReserved(value)) if value == reserved_type
+                );
+                assert_matches!(
+                    DccpHeader::read(&mut Cursor::new(&bytes)),
+                    Err(ReadError::DccpPacketTypeReserved(value)) if value == reserved_type
+                );
+            }
+
+            // data offset too small for the fixed part of the header
+            {
+                let data_offset = ((header.header_len() - header.options().len()) / 4 - 1) as u8;
+                bytes[4] = data_offset;
+                assert_matches!(
+                    DccpHeaderSlice::from_slice(&bytes),
+                    Err(ReadError::DccpDataOffsetTooSmall(value)) if value == data_offset
+                );
+                assert_matches!(
+                    DccpHeader::read(&mut Cursor::new(&bytes)),
+                    Err(ReadError::DccpDataOffsetTooSmall(value)) if value == data_offset
+                );
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_error(header in dccp_any()) {
+            let mut buffer = [0u8; 11];
+            assert_matches!(
+                header.write(&mut Cursor::new(&mut buffer[..])),
+                Err(WriteError::IoError(_))
+            );
+        }
+    }
+
+    #[test]
+    fn new() {
+        let header = DccpHeader::new(1, 2, 3, DccpType::Data);
+        assert_eq!(1, header.source_port);
+        assert_eq!(2, header.destination_port);
+        assert_eq!(0, header.ccval);
+        assert_eq!(0, header.checksum_coverage);
+        assert_eq!(0, header.checksum);
+        assert!(header.extended_sequence_numbers);
+        assert_eq!(3, header.sequence_number);
+        assert_eq!(DccpType::Data, header.dccp_type);
+        assert!(header.options().is_empty());
+        assert_eq!(16, header.header_len());
+    }
+
+    #[test]
+    fn header_len() {
+        use DccpType::*;
+        let values = [
+            (Request{ service_code: 0 }, 20, 16),
+            (Response{ acknowledgement_number: 0, service_code: 0 }, 28, 20),
+            (Data, 16, 12),
+            (Ack{ acknowledgement_number: 0 }, 24, 16),
+            (DataAck{ acknowledgement_number: 0 }, 24, 16),
+            (CloseReq{ acknowledgement_number: 0 }, 24, 16),
+            (Close{ acknowledgement_number: 0 }, 24, 16),
+            (Reset{ acknowledgement_number: 0, reset_code: 0, data: [0;3] }, 28, 20),
+            (Sync{ acknowledgement_number: 0 }, 24, 16),
+            (SyncAck{ acknowledgement_number: 0 }, 24, 16),
+        ];
+        for (dccp_type, extended_len, short_len) in values {
+            let mut header = DccpHeader::new(0, 0, 0, dccp_type);
+            assert_eq!(extended_len, header.header_len());
+            assert_eq!(extended_len, header.to_bytes().unwrap().len());
+            header.extended_sequence_numbers = false;
+            assert_eq!(short_len, header.header_len());
+            assert_eq!(short_len, header.to_bytes().unwrap().len());
+            header.set_options(&[1,2,3,4]).unwrap();
+            assert_eq!(short_len + 4, header.header_len());
+        }
+    }
+
+    #[test]
+    fn to_bytes() {
+        // short sequence numbers
+        {
+            let mut header = DccpHeader::new(
+                0x0102,
+                0x0304,
+                0x05_0607,
+                DccpType::Reset{ acknowledgement_number: 0x08_090a, reset_code: 0xb, data: [0xc, 0xd, 0xe] }
+            );
+            header.extended_sequence_numbers = false;
+            header.ccval = 0xf;
+            header.checksum_coverage = 0x1;
+            header.checksum = 0xabcd;
+            header.set_options(&[0, 0, 0, 0]).unwrap();
+            assert_eq!(
+                &[
+                    0x01, 0x02, 0x03, 0x04,
+                    6, 0xf1, 0xab, 0xcd,
+                    (7 << 1), 0x05, 0x06, 0x07,
+                    0, 0x08, 0x09, 0x0a,
+                    0xb, 0xc, 0xd, 0xe,
+                    0, 0, 0, 0,
+                ][..],
+                &header.to_bytes().unwrap()[..]
+            );
+        }
+        // extended sequence numbers
+        {
+            let header = DccpHeader::new(
+                0x0102,
+                0x0304,
+                0x0506_0708_090a,
+                DccpType::Response{ acknowledgement_number: 0x0b0c_0d0e_0f10, service_code: 0x1112_1314 }
+            );
+            assert_eq!(
+                &[
+                    0x01, 0x02, 0x03, 0x04,
+                    7, 0, 0, 0,
+                    (1 << 1) | 1, 0, 0x05, 0x06,
+                    0x07, 0x08, 0x09, 0x0a,
+                    0, 0, 0x0b, 0x0c,
+                    0x0d, 0x0e, 0x0f, 0x10,
+                    0x11, 0x12, 0x13, 0x14,
+                ][..],
+                &header.to_bytes().unwrap()[..]
+            );
+        }
+    }
+
+    #[test]
+    fn to_bytes_error() {
+        use ErrorField::*;
+
+        let base = DccpHeader::new(0, 0, 0, DccpType::Ack{ acknowledgement_number: 0 });
+        // ccval & checksum coverage
+        {
+            let mut header = base.clone();
+            header.ccval = 16;
+            assert_eq!(
+                Err(ValueError::U8TooLarge{ value: 16, max: 15, field: DccpCcval }),
+                header.to_bytes().map(|_| ())
+            );
+            assert_matches!(
+                header.write(&mut Vec::new()),
+                Err(WriteError::ValueError(ValueError::U8TooLarge{ value: 16, max: 15, field: DccpCcval }))
+            );
+            let mut header = base.clone();
+            header.checksum_coverage = 16;
+            assert_eq!(
+                Err(ValueError::U8TooLarge{ value: 16, max: 15, field: DccpChecksumCoverage }),
+                header.to_bytes().map(|_| ())
+            );
+        }
+        // sequence number
+        {
+            let mut header = base.clone();
+            header.sequence_number = 0x1_0000_0000_0000;
+            assert_eq!(
+                Err(ValueError::U64TooLarge{ value: 0x1_0000_0000_0000, max: 0xffff_ffff_ffff, field: DccpSequenceNumber }),
+                header.to_bytes().map(|_| ())
+            );
+            let mut header = base.clone();
+            header.sequence_number = 0x100_0000;
+            header.extended_sequence_numbers = false;
+            assert_eq!(
+                Err(ValueError::U64TooLarge{ value: 0x100_0000, max: 0xff_ffff, field: DccpSequenceNumber }),
+                header.to_bytes().map(|_| ())
+            );
+        }
+        // acknowledgement number
+        {
+            let mut header = base.clone();
+            header.dccp_type = DccpType::Sync{ acknowledgement_number: 0x100_0000 };
+            header.extended_sequence_numbers = false;
+            assert_eq!(
+                Err(ValueError::U64TooLarge{ value: 0x100_0000, max: 0xff_ffff, field: DccpAcknowledgementNumber }),
+                header.to_bytes().map(|_| ())
+            );
+        }
+        // options exceeding the maximum length after changing the type
+        {
+            let mut header = base.clone();
+            header.dccp_type = DccpType::Data;
+            let options = [0u8; DccpHeader::MAX_LEN - 16];
+            header.set_options(&options).unwrap();
+            assert_eq!(DccpHeader::MAX_LEN, header.to_bytes().unwrap().len());
+            header.dccp_type = DccpType::Request{ service_code: 0 };
+            assert_eq!(
+                Err(ValueError::DccpOptionsLengthBad(options.len())),
+                header.to_bytes().map(|_| ())
+            );
+        }
+    }
+
+    #[test]
+    fn set_options() {
+        let mut header = DccpHeader::new(0, 0, 0, DccpType::Data);
+        header.set_options(&[1,2,3,4,5,6,7,8]).unwrap();
+        assert_eq!(&[1,2,3,4,5,6,7,8], header.options());
+        assert_eq!(24, header.header_len());
+
+        // not a multiple of 4
+        assert_eq!(
+            Err(ValueError::DccpOptionsLengthBad(3)),
+            header.set_options(&[1,2,3])
+        );
+        // too long
+        assert_eq!(
+            Err(ValueError::DccpOptionsLengthBad(DccpHeader::MAX_LEN - 12)),
+            header.set_options(&[0; DccpHeader::MAX_LEN - 12])
+        );
+        // options stay unchanged on error
+        assert_eq!(&[1,2,3,4,5,6,7,8], header.options());
+
+        // the maximum depends on the fixed part of the header
+        header.extended_sequence_numbers = false;
+        header.set_options(&[0; DccpHeader::MAX_OPTIONS_LEN]).unwrap();
+        assert_eq!(DccpHeader::MAX_LEN, header.header_len());
+    }
+
+    #[test]
+    fn coverage_len() {
+        let mut header = DccpHeader::new(0, 0, 0, DccpType::Data);
+        assert_eq!(Ok(10), header.coverage_len(10));
+        header.checksum_coverage = 1;
+        assert_eq!(Ok(0), header.coverage_len(10));
+        header.checksum_coverage = 3;
+        assert_eq!(Ok(8), header.coverage_len(10));
+        assert_eq!(Ok(8), header.coverage_len(8));
+        assert_eq!(
+            Err(ValueError::DccpChecksumCoverageBad{ coverage: 3, payload_len: 7 }),
+            header.coverage_len(7)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum_ipv4(
+            header in dccp_any(),
+            ip in ipv4_any(),
+            payload in proptest::collection::vec(any::<u8>(), 60..70),
+        ) {
+            let len = header.header_len() + payload.len();
+            let expected = reference_checksum(
+                &ipv4_pseudo_header(ip.source, ip.destination, len),
+                &header,
+                &payload
+            );
+            assert_eq!(Ok(expected), header.calc_checksum_ipv4(&ip, &payload));
+            assert_eq!(Ok(expected), header.calc_checksum_ipv4_raw(ip.source, ip.destination, &payload));
+
+            let mut header = header;
+            header.checksum = expected;
+            assert!(header.is_checksum_valid_ipv4(ip.source, ip.destination, &payload));
+            let mut modified = payload.clone();
+            modified[0] = !modified[0];
+            if 1 != header.checksum_coverage {
+                assert!(!header.is_checksum_valid_ipv4(ip.source, ip.destination, &modified));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum_ipv6(
+            header in dccp_any(),
+            ip in ipv6_any(),
+            payload in proptest::collection::vec(any::<u8>(), 60..70),
+        ) {
+            let len = header.header_len() + payload.len();
+            let expected = reference_checksum(
+                &ipv6_pseudo_header(ip.source, ip.destination, len),
+                &header,
+                &payload
+            );
+            assert_eq!(Ok(expected), header.calc_checksum_ipv6(&ip, &payload));
+            assert_eq!(Ok(expected), header.calc_checksum_ipv6_raw(ip.source, ip.destination, &payload));
+
+            let mut header = header;
+            header.checksum = expected;
+            assert!(header.is_checksum_valid_ipv6(ip.source, ip.destination, &payload));
+            let mut modified = payload.clone();
+            modified[0] = !modified[0];
+            if 1 != header.checksum_coverage {
+                assert!(!header.is_checksum_valid_ipv6(ip.source, ip.destination, &modified));
+            }
+        }
+    }
+
+    #[test]
+    fn partial_coverage() {
+        // header & the first 4 bytes of the payload
+        let mut header = DccpHeader::new(1, 2, 3, DccpType::Data);
+        header.checksum_coverage = 2;
+        let payload = [1,2,3,4,5,6,7,8];
+        header.checksum = header.calc_checksum_ipv4_raw([1;4], [2;4], &payload).unwrap();
+
+        let mut modified = payload;
+        modified[7] = 0;
+        assert!(header.is_checksum_valid_ipv4([1;4], [2;4], &modified));
+        modified[3] = 0;
+        assert!(!header.is_checksum_valid_ipv4([1;4], [2;4], &modified));
+    }
+
+    #[test]
+    fn calc_checksum_error() {
+        // coverage exceeding the payload
+        {
+            let mut header = DccpHeader::new(1, 2, 3, DccpType::Data);
+            header.checksum_coverage = 3;
+            let expected = Err(ValueError::DccpChecksumCoverageBad{ coverage: 3, payload_len: 4 });
+            assert_eq!(expected, header.calc_checksum_ipv4_raw([0;4], [0;4], &[0;4]));
+            assert_eq!(expected, header.calc_checksum_ipv6_raw([0;16], [0;16], &[0;4]));
+            assert!(!header.is_checksum_valid_ipv4([0;4], [0;4], &[0;4]));
+            assert!(!header.is_checksum_valid_ipv6([0;16], [0;16], &[0;4]));
+        }
+        // field values too large
+        {
+            let mut header = DccpHeader::new(1, 2, 3, DccpType::Data);
+            header.ccval = 16;
+            assert_eq!(
+                Err(ValueError::U8TooLarge{ value: 16, max: 15, field: ErrorField::DccpCcval }),
+                header.calc_checksum_ipv4_raw([0;4], [0;4], &[])
+            );
+        }
+        // payload too large for ipv4
+        {
+            let header = DccpHeader::new(1, 2, 3, DccpType::Data);
+            let payload = vec![0; (u16::MAX as usize) - 15];
+            assert_eq!(
+                Err(ValueError::DccpLengthTooLarge(16 + payload.len())),
+                header.calc_checksum_ipv4_raw([0;4], [0;4], &payload)
+            );
+            // fine for ipv6
+            assert!(header.calc_checksum_ipv6_raw([0;16], [0;16], &payload).is_ok());
+        }
+    }
+
+    #[test]
+    fn debug_clone_eq_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut header = DccpHeader::new(1, 2, 3, DccpType::Data);
+        header.set_options(&[1,2,3,4]).unwrap();
+        assert_eq!(header, header.clone());
+        assert_eq!(
+            "DccpHeader { source_port: 1, destination_port: 2, ccval: 0, checksum_coverage: 0, checksum: 0, extended_sequence_numbers: true, sequence_number: 3, dccp_type: Data, options: [1, 2, 3, 4] }",
+            format!("{:?}", header)
+        );
+        let hash = |value: &DccpHeader| {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(&header), hash(&header.clone()));
+
+        // options are part of the comparison
+        let other = DccpHeader::new(1, 2, 3, DccpType::Data);
+        assert_ne!(header, other);
+
+        let bytes = header.to_bytes().unwrap();
+        let slice = DccpHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice, slice.clone());
+        assert_eq!(
+            format!("DccpHeaderSlice {{ slice: {:?} }}", &bytes[..]),
+            format!("{:?}", slice)
+        );
+    }
+}
+
+mod packet {
+    use super::*;
+
+    #[test]
+    fn build_and_decode() {
+        let payload = [1,2,3,4,5,6,7,8];
+        let mut header = DccpHeader::new(
+            1234,
+            5001,
+            1,
+            DccpType::Request{ service_code: 42 }
+        );
+        header.set_options(&[0, 0, 0, 0]).unwrap();
+
+        // ipv4
+        {
+            let mut packet = Vec::new();
+            PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                .ipv4([192,168,1,1], [192,168,1,2], 20)
+                .dccp(header.clone())
+                .write(&mut packet, &payload)
+                .unwrap();
+            assert_eq!(14 + 20 + 24 + payload.len(), packet.len());
+
+            let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+            assert_eq!(&payload[..], sliced.payload);
+            let dccp = match sliced.transport {
+                Some(TransportSlice::Dccp(value)) => value.to_header(),
+                other => panic!("unexpected transport {:?}", other),
+            };
+            let mut expected = header.clone();
+            expected.checksum = dccp.checksum;
+            assert_eq!(expected, dccp);
+            assert!(dccp.is_checksum_valid_ipv4([192,168,1,1], [192,168,1,2], &payload));
+
+            let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+            assert_eq!(Some(TransportHeader::Dccp(dccp)), headers.transport);
+            assert_eq!(&payload[..], headers.payload);
+        }
+        // ipv6
+        {
+            let mut packet = Vec::new();
+            PacketBuilder::ipv6([1;16], [2;16], 20)
+                .dccp(header.clone())
+                .write(&mut packet, &payload)
+                .unwrap();
+
+            let sliced = SlicedPacket::from_ip(&packet).unwrap();
+            let dccp = match &sliced.transport {
+                Some(TransportSlice::Dccp(value)) => value.to_header(),
+                other => panic!("unexpected transport {:?}", other),
+            };
+            assert!(dccp.is_checksum_valid_ipv6([1;16], [2;16], &payload));
+            assert_eq!(Some(true), ChecksumReport::verify(&packet, &sliced).transport);
+            assert_eq!(
+                Some(TransportHeader::Dccp(dccp)),
+                PacketHeaders::from_ip_slice(&packet).unwrap().transport
+            );
+        }
+    }
+
+    #[test]
+    fn builder_size() {
+        let builder = PacketBuilder::ipv4([0;4], [0;4], 20)
+            .dccp(DccpHeader::new(1, 2, 3, DccpType::Data));
+        assert_eq!(20 + 16 + 5, builder.size(5));
+        let buffer = builder.to_array_vec::<100>(&[0;5]).unwrap();
+        assert_eq!(20 + 16 + 5, buffer.len());
+    }
+
+    #[test]
+    fn builder_bad_coverage() {
+        let mut header = DccpHeader::new(1, 2, 3, DccpType::Data);
+        header.checksum_coverage = 3;
+        let mut packet = Vec::new();
+        assert_matches!(
+            PacketBuilder::ipv4([0;4], [0;4], 20)
+                .dccp(header)
+                .write(&mut packet, &[0;5]),
+            Err(WriteError::ValueError(ValueError::DccpChecksumCoverageBad{ coverage: 3, payload_len: 5 }))
+        );
+    }
+
+    #[test]
+    fn slice_too_short() {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([0;4], [0;4], 20)
+            .dccp(DccpHeader::new(1, 2, 3, DccpType::Data))
+            .write(&mut packet, &[])
+            .unwrap();
+        let packet = &packet[..packet.len() - 1];
+        assert_matches!(
+            PacketHeaders::from_ip_slice(packet),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
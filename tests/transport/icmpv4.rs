@@ -1645,6 +1645,67 @@ mod icmpv4_slice {
             );
         }
     }
+
+    #[test]
+    fn embedded_packet() {
+        // original packets (ip header + first 8 bytes of the transport header)
+        let mut udp = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .udp(1234, 53)
+            .write(&mut udp, &[1,2,3,4])
+            .unwrap();
+        let mut tcp = Vec::new();
+        PacketBuilder::ipv4([192,168,1,1], [192,168,1,2], 20)
+            .tcp(1234, 80, 1, 1024)
+            .write(&mut tcp, &[1,2,3,4])
+            .unwrap();
+
+        let icmp = |icmp_type: u8, payload: &[u8]| {
+            let mut bytes = vec![icmp_type, 0, 0, 0, 0, 0, 0, 0];
+            bytes.extend_from_slice(payload);
+            bytes
+        };
+
+        for icmp_type in [TYPE_DEST_UNREACH, TYPE_SOURCE_QUENCH, TYPE_REDIRECT, TYPE_TIME_EXCEEDED, TYPE_PARAMETER_PROBLEM] {
+            // udp header is complete
+            {
+                let bytes = icmp(icmp_type, &udp[..28]);
+                let embedded = Icmpv4Slice::from_slice(&bytes).unwrap().embedded_packet().unwrap().unwrap();
+                assert_matches!(embedded.ip, Some(InternetSlice::Ipv4(ref ip, _)) if [192,168,1,2] == ip.destination());
+                assert_matches!(embedded.transport, Some(TransportSlice::Udp(ref udp)) if 53 == udp.destination_port());
+                assert!(embedded.payload.is_empty());
+            }
+            // tcp header is cut off
+            {
+                let bytes = icmp(icmp_type, &tcp[..28]);
+                let embedded = Icmpv4Slice::from_slice(&bytes).unwrap().embedded_packet().unwrap().unwrap();
+                assert_matches!(embedded.ip, Some(InternetSlice::Ipv4(ref ip, _)) if ip_number::TCP == ip.protocol());
+                assert!(embedded.transport.is_none());
+                assert_eq!(&tcp[20..28], embedded.payload);
+            }
+            // complete original packet
+            {
+                let bytes = icmp(icmp_type, &tcp);
+                let embedded = Icmpv4Slice::from_slice(&bytes).unwrap().embedded_packet().unwrap().unwrap();
+                assert_matches!(embedded.transport, Some(TransportSlice::Tcp(ref tcp)) if 80 == tcp.destination_port());
+                assert_eq!(&[1,2,3,4], embedded.payload);
+            }
+            // ip header errors are still reported
+            {
+                let bytes = icmp(icmp_type, &udp[..19]);
+                assert_matches!(
+                    Icmpv4Slice::from_slice(&bytes).unwrap().embedded_packet(),
+                    Some(Err(ReadError::UnexpectedEndOfSlice(_)))
+                );
+            }
+        }
+
+        // message types without an original datagram
+        for icmp_type in [TYPE_ECHO_REPLY, TYPE_ECHO_REQUEST, TYPE_ROUTER_ADVERTISEMENT] {
+            let bytes = icmp(icmp_type, &udp[..28]);
+            assert!(Icmpv4Slice::from_slice(&bytes).unwrap().embedded_packet().is_none());
+        }
+    }
 }
 
 mod icmpv4_regression {
@@ -1753,7 +1814,7 @@ mod icmpv4_regression {
         use TransportSlice::*;
         let icmp4 = match echo.transport.unwrap() {
             Icmpv4(icmp4) => icmp4,
-            Icmpv6(_) | Udp(_) | Tcp(_) | Unknown(_) => panic!("Misparsed header!"),
+            Icmpv6(_) | Igmp(_) | Udp(_) | Tcp(_) | Sctp(_) | UdpLite(_) | Dccp(_) | Unknown(_) => panic!("Misparsed header!"),
         };
         assert!(matches!(icmp4.icmp_type(), Icmpv4Type::EchoRequest(_)));
     }
@@ -1821,6 +1882,15 @@ mod icmpv4_regression {
         let udp_header = embedded_pkt.transport.unwrap().udp().unwrap();
         assert_eq!(udp_header.source_port, 49806); // numbers read from wireshark
         assert_eq!(udp_header.destination_port, 33439);
+
+        // same result via the embedded packet of the icmp slice
+        let sliced = SlicedPacket::from_ethernet(&ICMP4_TTL_EXCEEDED_BYTES).unwrap();
+        let icmp4 = match sliced.transport.unwrap() {
+            TransportSlice::Icmpv4(icmp4) => icmp4,
+            _ => panic!("Didn't parse icmpv4 header!?"),
+        };
+        let embedded = icmp4.embedded_packet().unwrap().unwrap();
+        assert_matches!(embedded.transport, Some(TransportSlice::Udp(udp)) if 49806 == udp.source_port() && 33439 == udp.destination_port());
     }
 
     const ICMP4_PORT_UNREACHABLE_BYTES: [u8; 70] = [
@@ -1414,6 +1414,53 @@ mod icmpv6_slice {
             );
         }
     }
+
+    #[test]
+    fn embedded_packet() {
+        let mut invoking = Vec::new();
+        PacketBuilder::ipv6([1;16], [2;16], 20)
+            .tcp(1234, 80, 1, 1024)
+            .write(&mut invoking, &[1,2,3,4])
+            .unwrap();
+
+        let icmp = |icmp_type: u8, payload: &[u8]| {
+            let mut bytes = vec![icmp_type, 0, 0, 0, 0, 0, 0, 0];
+            bytes.extend_from_slice(payload);
+            bytes
+        };
+
+        for icmp_type in [TYPE_DST_UNREACH, TYPE_PACKET_TOO_BIG, TYPE_TIME_EXCEEDED, TYPE_PARAMETER_PROBLEM] {
+            // complete invoking packet
+            {
+                let bytes = icmp(icmp_type, &invoking);
+                let embedded = Icmpv6Slice::from_slice(&bytes).unwrap().embedded_packet().unwrap().unwrap();
+                assert_matches!(embedded.ip, Some(InternetSlice::Ipv6(ref ip, _)) if [2;16] == ip.destination());
+                assert_matches!(embedded.transport, Some(TransportSlice::Tcp(ref tcp)) if 80 == tcp.destination_port());
+                assert_eq!(&[1,2,3,4], embedded.payload);
+            }
+            // tcp header is cut off
+            {
+                let bytes = icmp(icmp_type, &invoking[..48]);
+                let embedded = Icmpv6Slice::from_slice(&bytes).unwrap().embedded_packet().unwrap().unwrap();
+                assert!(embedded.transport.is_none());
+                assert_eq!(&invoking[40..48], embedded.payload);
+            }
+            // ip header errors are still reported
+            {
+                let bytes = icmp(icmp_type, &invoking[..39]);
+                assert_matches!(
+                    Icmpv6Slice::from_slice(&bytes).unwrap().embedded_packet(),
+                    Some(Err(ReadError::UnexpectedEndOfSlice(_)))
+                );
+            }
+        }
+
+        // message types without an invoking packet
+        for icmp_type in [TYPE_ECHO_REQUEST, TYPE_ECHO_REPLY, TYPE_NEIGHBOR_SOLICITATION] {
+            let bytes = icmp(icmp_type, &invoking);
+            assert!(Icmpv6Slice::from_slice(&bytes).unwrap().embedded_packet().is_none());
+        }
+    }
 }
 
 mod regression {
@@ -1513,7 +1560,7 @@ mod regression {
         use TransportSlice::*;
         let icmp6 = match echo.transport.unwrap() {
             Icmpv6(icmp6) => icmp6,
-            Icmpv4(_) | Udp(_) | Tcp(_) | Unknown(_) => panic!("Misparsed header!"),
+            Icmpv4(_) | Igmp(_) | Udp(_) | Tcp(_) | Sctp(_) | UdpLite(_) | Dccp(_) | Unknown(_) => panic!("Misparsed header!"),
         };
         assert!(matches!(
             icmp6.header().icmp_type,
@@ -0,0 +1,428 @@
+use super::super::*;
+
+use std::io::Cursor;
+
+const SOURCE: [u8;16] = [0xfe,0x80,0,0,0,0,0,0,0,0,0,0,0,0,0,1];
+const DESTINATION: [u8;16] = [0xff,0x02,0,0,0,0,0,0,0,0,0,0,0,0,0,1];
+
+prop_compose! {
+    fn ndp_message_any()(
+        variant in 0u8..5,
+        cur_hop_limit in any::<u8>(),
+        flags in any::<[bool;3]>(),
+        router_lifetime in any::<u16>(),
+        reachable_time in any::<u32>(),
+        retrans_timer in any::<u32>(),
+        target_address in any::<[u8;16]>(),
+        destination_address in any::<[u8;16]>(),
+    ) -> NdpMessage {
+        use NdpMessage::*;
+        match variant {
+            0 => RouterSolicitation,
+            1 => RouterAdvertisement(RouterAdvertisementHeader{
+                cur_hop_limit,
+                managed_address_config: flags[0],
+                other_config: flags[1],
+                router_lifetime,
+                reachable_time,
+                retrans_timer,
+            }),
+            2 => NeighborSolicitation{ target_address },
+            3 => NeighborAdvertisement(NeighborAdvertisementHeader{
+                router: flags[0],
+                solicited: flags[1],
+                override_flag: flags[2],
+                target_address,
+            }),
+            _ => Redirect{ target_address, destination_address },
+        }
+    }
+}
+
+fn serialize(options: &[NdpOption]) -> Vec<u8> {
+    let mut result = Vec::new();
+    for option in options {
+        option.write(&mut result).unwrap();
+    }
+    result
+}
+
+mod ndp_message {
+    use super::*;
+
+    #[test]
+    fn type_u8_header_len() {
+        use NdpMessage::*;
+        let values = [
+            (133, 8, RouterSolicitation),
+            (134, 16, RouterAdvertisement(Default::default())),
+            (135, 24, NeighborSolicitation{ target_address: [0;16] }),
+            (136, 24, NeighborAdvertisement(Default::default())),
+            (137, 40, Redirect{ target_address: [0;16], destination_address: [0;16] }),
+        ];
+        for (type_u8, header_len, message) in values {
+            assert_eq!(type_u8, message.type_u8());
+            assert_eq!(header_len, message.header_len());
+            assert_eq!(header_len, message.to_bytes(0).len());
+        }
+    }
+
+    #[test]
+    fn to_bytes() {
+        use NdpMessage::*;
+        assert_eq!(
+            &[134, 0, 0x12, 0x34, 64, 0xc0, 0x07, 0x08, 0, 0, 0, 1, 0, 0, 0, 2],
+            &RouterAdvertisement(RouterAdvertisementHeader{
+                cur_hop_limit: 64,
+                managed_address_config: true,
+                other_config: true,
+                router_lifetime: 0x0708,
+                reachable_time: 1,
+                retrans_timer: 2,
+            }).to_bytes(0x1234)[..]
+        );
+        let mut expected = vec![136, 0, 0, 0, 0xa0, 0, 0, 0];
+        expected.extend_from_slice(&SOURCE);
+        assert_eq!(
+            &expected[..],
+            &NeighborAdvertisement(NeighborAdvertisementHeader{
+                router: true,
+                solicited: false,
+                override_flag: true,
+                target_address: SOURCE,
+            }).to_bytes(0)[..]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn write_from_slice(
+            message in ndp_message_any(),
+            address in proptest::collection::vec(any::<u8>(), 0..20),
+            mtu in any::<u32>(),
+        ) {
+            let options = serialize(&[
+                NdpOption::SourceLinkLayerAddress(&address),
+                NdpOption::Mtu(mtu),
+            ]);
+
+            let mut packet = Vec::new();
+            message.write(&mut packet, SOURCE, DESTINATION, &options).unwrap();
+            assert_eq!(message.header_len() + options.len(), packet.len());
+
+            let icmp = Icmpv6Slice::from_slice(&packet).unwrap();
+            assert!(icmp.is_checksum_valid(SOURCE, DESTINATION));
+            assert_eq!(
+                message.calc_checksum(SOURCE, DESTINATION, &options).unwrap(),
+                icmp.checksum()
+            );
+
+            let ndp = NdpSlice::from_icmpv6(&icmp).unwrap();
+            assert_eq!(&packet[..], ndp.slice());
+            assert_eq!(message, ndp.message());
+            assert_eq!(&options[..], ndp.options_slice());
+
+            let mut options = ndp.options();
+            assert_matches!(options.next(), Some(Ok(NdpOption::SourceLinkLayerAddress(_))));
+            assert_eq!(Some(Ok(NdpOption::Mtu(mtu))), options.next());
+            assert_eq!(None, options.next());
+        }
+    }
+
+    #[test]
+    fn write_error() {
+        let message = NdpMessage::NeighborSolicitation{ target_address: SOURCE };
+
+        // io error
+        let mut buffer = [0u8;23];
+        assert_matches!(
+            message.write(&mut Cursor::new(&mut buffer[..]), SOURCE, DESTINATION, &[]),
+            Err(WriteError::IoError(_))
+        );
+        let mut buffer = [0u8;25];
+        assert_matches!(
+            message.write(&mut Cursor::new(&mut buffer[..]), SOURCE, DESTINATION, &[0;8]),
+            Err(WriteError::IoError(_))
+        );
+
+        // options too large
+        #[cfg(target_pointer_width = "64")]
+        {
+            let too_big = unsafe {
+                //NOTE: The pointer must be initialized with a non null value
+                //      otherwise a key constraint of slices is not fullfilled
+                //      which can lead to crashes in release mode.
+                use std::ptr::NonNull;
+                std::slice::from_raw_parts(
+                    NonNull::<u8>::dangling().as_ptr(),
+                    (u32::MAX - 23) as usize
+                )
+            };
+            assert_eq!(
+                Err(ValueError::Ipv6PayloadLengthTooLarge(too_big.len())),
+                message.calc_checksum(SOURCE, DESTINATION, too_big)
+            );
+            assert_matches!(
+                message.write(&mut Vec::new(), SOURCE, DESTINATION, too_big),
+                Err(WriteError::ValueError(ValueError::Ipv6PayloadLengthTooLarge(_)))
+            );
+        }
+    }
+}
+
+mod ndp_option {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        use ndp_option_type::*;
+        assert_eq!(1, SOURCE_LINK_LAYER_ADDRESS);
+        assert_eq!(2, TARGET_LINK_LAYER_ADDRESS);
+        assert_eq!(3, PREFIX_INFORMATION);
+        assert_eq!(4, REDIRECTED_HEADER);
+        assert_eq!(5, MTU);
+        assert_eq!(2040, NdpOption::MAX_SERIALIZED_LEN);
+    }
+
+    #[test]
+    fn write() {
+        use NdpOption::*;
+
+        // ethernet link-layer address (no padding needed)
+        assert_eq!(
+            vec![1, 1, 1, 2, 3, 4, 5, 6],
+            serialize(&[SourceLinkLayerAddress(&[1,2,3,4,5,6])])
+        );
+        // InfiniBand link-layer address (padded)
+        let mut expected = vec![2, 3];
+        expected.extend_from_slice(&[7;20]);
+        expected.extend_from_slice(&[0;2]);
+        assert_eq!(expected, serialize(&[TargetLinkLayerAddress(&[7;20])]));
+        // mtu
+        assert_eq!(
+            vec![5, 1, 0, 0, 0, 0, 0x05, 0xdc],
+            serialize(&[Mtu(1500)])
+        );
+        // prefix information
+        let mut expected = vec![3, 4, 64, 0xc0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 0];
+        expected.extend_from_slice(&SOURCE);
+        assert_eq!(
+            expected,
+            serialize(&[PrefixInformation(NdpPrefixInformation{
+                prefix_length: 64,
+                on_link: true,
+                autonomous: true,
+                valid_lifetime: 1,
+                preferred_lifetime: 2,
+                prefix: SOURCE,
+            })])
+        );
+        // redirected header
+        assert_eq!(
+            vec![4, 2, 0, 0, 0, 0, 0, 0, 1, 2, 3, 0, 0, 0, 0, 0],
+            serialize(&[RedirectedHeader(&[1,2,3])])
+        );
+        // unknown
+        assert_eq!(
+            vec![200, 1, 1, 2, 0, 0, 0, 0],
+            serialize(&[Unknown{ option_type: 200, data: &[1,2] }])
+        );
+    }
+
+    #[test]
+    fn write_error() {
+        use NdpOption::*;
+
+        // too long
+        let data = [0u8;2039];
+        assert_matches!(
+            SourceLinkLayerAddress(&data).write(&mut Vec::new()),
+            Err(WriteError::ValueError(ValueError::NdpOptionTooLong(2048)))
+        );
+        let mut buffer = Vec::new();
+        SourceLinkLayerAddress(&data[..2038]).write(&mut buffer).unwrap();
+        assert_eq!(2040, buffer.len());
+        assert_eq!(255, buffer[1]);
+
+        // io error
+        for option in [SourceLinkLayerAddress(&[1;6]), Mtu(1280)] {
+            for len in 0..option.serialized_len() {
+                let mut buffer = vec![0u8;len];
+                assert_matches!(
+                    option.write(&mut Cursor::new(&mut buffer[..])),
+                    Err(WriteError::IoError(_))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn option_type() {
+        use NdpOption::*;
+        assert_eq!(1, SourceLinkLayerAddress(&[]).option_type());
+        assert_eq!(2, TargetLinkLayerAddress(&[]).option_type());
+        assert_eq!(3, PrefixInformation(Default::default()).option_type());
+        assert_eq!(4, RedirectedHeader(&[]).option_type());
+        assert_eq!(5, Mtu(0).option_type());
+        assert_eq!(6, Unknown{ option_type: 6, data: &[] }.option_type());
+    }
+}
+
+mod ndp_options_iterator {
+    use super::*;
+
+    #[test]
+    fn iterate() {
+        use NdpOption::*;
+        let prefix = NdpPrefixInformation{
+            prefix_length: 64,
+            on_link: true,
+            autonomous: false,
+            valid_lifetime: u32::MAX,
+            preferred_lifetime: 1234,
+            prefix: SOURCE,
+        };
+        let options = serialize(&[
+            SourceLinkLayerAddress(&[1,2,3,4,5,6]),
+            PrefixInformation(prefix),
+            Mtu(1280),
+            RedirectedHeader(&[0;8]),
+            Unknown{ option_type: 200, data: &[1,2,3,4,5,6] },
+        ]);
+        let mut iterator = NdpOptionsIterator::from_slice(&options);
+        assert_eq!(&options[..], iterator.rest());
+        assert_eq!(Some(Ok(SourceLinkLayerAddress(&[1,2,3,4,5,6]))), iterator.next());
+        assert_eq!(Some(Ok(PrefixInformation(prefix))), iterator.next());
+        assert_eq!(Some(Ok(Mtu(1280))), iterator.next());
+        assert_eq!(Some(Ok(RedirectedHeader(&[0;8]))), iterator.next());
+        assert_eq!(&options[64..], iterator.rest());
+        assert_eq!(Some(Ok(Unknown{ option_type: 200, data: &[1,2,3,4,5,6] })), iterator.next());
+        assert_eq!(None, iterator.next());
+    }
+
+    #[test]
+    fn errors() {
+        use NdpOptionReadError::*;
+        // (data, expected error, error independent of the following data)
+        let values: [(&[u8], NdpOptionReadError, bool); 5] = [
+            (&[1], UnexpectedEndOfSlice{ option_type: 1, expected_len: 2, actual_len: 1 }, false),
+            (&[1, 0, 0, 0, 0, 0, 0, 0], ZeroLength{ option_type: 1 }, true),
+            (&[1, 2, 0, 0, 0, 0, 0, 0], UnexpectedEndOfSlice{ option_type: 1, expected_len: 16, actual_len: 8 }, false),
+            (&[3, 1, 0, 0, 0, 0, 0, 0], UnexpectedSize{ option_type: 3, size: 1 }, true),
+            (&[5, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], UnexpectedSize{ option_type: 5, size: 2 }, true),
+        ];
+        for (data, expected, append) in values {
+            // the iteration stops after an error
+            let mut options = data.to_vec();
+            if append {
+                options.extend_from_slice(&[5, 1, 0, 0, 0, 0, 0, 0]);
+            }
+            let mut iterator = NdpOptionsIterator::from_slice(&options);
+            assert_eq!(Some(Err(expected.clone())), iterator.next());
+            assert!(iterator.rest().is_empty());
+            assert_eq!(None, iterator.next());
+
+            assert_matches!(std::error::Error::source(&expected), None);
+        }
+    }
+
+    #[test]
+    fn error_display() {
+        use NdpOptionReadError::*;
+        assert_eq!(
+            "NdpOptionReadError: Not enough data left in slice to read option of type 1 (expected at least 16 bytes, only 8 bytes available).",
+            format!("{}", UnexpectedEndOfSlice{ option_type: 1, expected_len: 16, actual_len: 8 })
+        );
+        assert_eq!(
+            "NdpOptionReadError: Length value of the option of type 2 is zero.",
+            format!("{}", ZeroLength{ option_type: 2 })
+        );
+        assert_eq!(
+            "NdpOptionReadError: Length value of the option of type 3 had unexpected value 1.",
+            format!("{}", UnexpectedSize{ option_type: 3, size: 1 })
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let options = serialize(&[NdpOption::Mtu(1280)]);
+        assert_eq!(
+            "[Ok(Mtu(1280))]",
+            format!("{:?}", NdpOptionsIterator::from_slice(&options))
+        );
+    }
+}
+
+mod ndp_slice {
+    use super::*;
+
+    #[test]
+    fn from_icmpv6() {
+        use etherparse::icmpv6::*;
+        let values = [
+            (TYPE_ROUTER_SOLICITATION, 8),
+            (TYPE_ROUTER_ADVERTISEMENT, 16),
+            (TYPE_NEIGHBOR_SOLICITATION, 24),
+            (TYPE_NEIGHBOR_ADVERTISEMENT, 24),
+            (TYPE_REDIRECT_MESSAGE, 40),
+        ];
+        for (type_u8, min_len) in values {
+            let mut packet = vec![0u8;min_len];
+            packet[0] = type_u8;
+            let icmp = Icmpv6Slice::from_slice(&packet).unwrap();
+            let ndp = NdpSlice::from_icmpv6(&icmp).unwrap();
+            assert_eq!(type_u8, ndp.message().type_u8());
+            assert!(ndp.options_slice().is_empty());
+            assert_eq!(ndp, ndp.clone());
+
+            // too short
+            if min_len > 8 {
+                let icmp = Icmpv6Slice::from_slice(&packet[..min_len - 1]).unwrap();
+                assert_eq!(None, NdpSlice::from_icmpv6(&icmp));
+            }
+
+            // code not 0
+            packet[1] = 1;
+            let icmp = Icmpv6Slice::from_slice(&packet).unwrap();
+            assert_eq!(None, NdpSlice::from_icmpv6(&icmp));
+        }
+
+        // no ndp type
+        let icmp = Icmpv6Slice::from_slice(&[TYPE_ECHO_REQUEST, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert_eq!(None, NdpSlice::from_icmpv6(&icmp));
+    }
+
+    #[test]
+    fn packet() {
+        // neighbor advertisement in an ethernet frame
+        let message = NdpMessage::NeighborAdvertisement(NeighborAdvertisementHeader{
+            router: false,
+            solicited: true,
+            override_flag: true,
+            target_address: SOURCE,
+        });
+        let options = serialize(&[NdpOption::TargetLinkLayerAddress(&[1,2,3,4,5,6])]);
+        let mut icmp = Vec::new();
+        message.write(&mut icmp, SOURCE, DESTINATION, &options).unwrap();
+
+        let mut frame = Vec::new();
+        PacketBuilder::ethernet2([1,2,3,4,5,6], [0x33,0x33,0,0,0,1])
+            .ipv6(SOURCE, DESTINATION, 255)
+            .icmpv6_raw(icmp[0], icmp[1], [icmp[4], icmp[5], icmp[6], icmp[7]])
+            .write(&mut frame, &icmp[8..])
+            .unwrap();
+
+        let sliced = SlicedPacket::from_ethernet(&frame).unwrap();
+        let icmp_slice = match sliced.transport {
+            Some(TransportSlice::Icmpv6(value)) => value,
+            _ => panic!("expected icmpv6"),
+        };
+        assert!(icmp_slice.is_checksum_valid(SOURCE, DESTINATION));
+        let ndp = NdpSlice::from_icmpv6(&icmp_slice).unwrap();
+        assert_eq!(message, ndp.message());
+        assert_eq!(
+            vec![Ok(NdpOption::TargetLinkLayerAddress(&[1,2,3,4,5,6]))],
+            ndp.options().collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use super::super::*;
+use std::io::Cursor;
+
+/// Returns the serialized header & payload.
+fn serialize(header: &IgmpHeader, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    header.write(&mut bytes, payload).unwrap();
+    bytes
+}
+
+#[test]
+fn fixed_messages() {
+    use IgmpHeader::*;
+    let group_address = [239, 1, 2, 3];
+    let headers = [
+        (igmp::TYPE_MEMBERSHIP_QUERY, MembershipQuery(IgmpMembershipQuery{
+            max_resp_code: 100,
+            group_address,
+            v3: None,
+        })),
+        (igmp::TYPE_V1_MEMBERSHIP_REPORT, MembershipReportV1{ group_address }),
+        (igmp::TYPE_V2_MEMBERSHIP_REPORT, MembershipReportV2{ group_address }),
+        (igmp::TYPE_LEAVE_GROUP, LeaveGroup{ group_address }),
+        (igmp::TYPE_V3_MEMBERSHIP_REPORT, MembershipReportV3{ number_of_group_records: 0 }),
+        (0x30, Unknown{ type_u8: 0x30, code_u8: 1, bytes5to8: [2, 3, 4, 5] }),
+    ];
+    for (type_u8, header) in headers {
+        assert_eq!(type_u8, header.type_u8());
+        assert_eq!(IgmpHeader::MIN_LEN, header.header_len());
+
+        // additional data after the message is not part of the slice
+        // (except for queries, which are then decoded as IGMPv3 queries)
+        let mut bytes = serialize(&header, &[]);
+        assert_eq!(IgmpHeader::MIN_LEN, bytes.len());
+        if igmp::TYPE_MEMBERSHIP_QUERY != type_u8 {
+            bytes.extend_from_slice(&[1, 2, 3, 4]);
+        }
+
+        let slice = IgmpSlice::from_slice(&bytes).unwrap();
+        assert_eq!(&bytes[..8], slice.slice());
+        assert_eq!(type_u8, slice.type_u8());
+        assert_eq!(bytes[1], slice.code_u8());
+        assert_eq!(header.calc_checksum(&[]), slice.checksum());
+        assert!(slice.is_checksum_valid());
+        assert_eq!(header, slice.header());
+        assert_eq!(0, slice.query_sources().count());
+        assert_eq!(0, slice.group_records().count());
+
+        // checksum error
+        bytes[4] ^= 1;
+        assert!(!IgmpSlice::from_slice(&bytes).unwrap().is_checksum_valid());
+
+        // length error
+        for len in 0..IgmpHeader::MIN_LEN {
+            assert_matches!(
+                IgmpSlice::from_slice(&bytes[..len]),
+                Err(ReadError::UnexpectedEndOfSlice(8))
+            );
+        }
+    }
+}
+
+#[test]
+fn v3_query() {
+    let header = IgmpHeader::MembershipQuery(IgmpMembershipQuery{
+        max_resp_code: 0x8f,
+        group_address: [239, 1, 2, 3],
+        v3: Some(IgmpV3QueryFields{
+            suppress_router_processing: true,
+            querier_robustness_variable: 2,
+            querier_query_interval_code: 125,
+            number_of_sources: 2,
+        }),
+    });
+    assert_eq!(IgmpHeader::V3_QUERY_LEN, header.header_len());
+    let bytes = serialize(&header, &[10, 0, 0, 1, 10, 0, 0, 2]);
+    assert_eq!(&[0x11, 0x8f], &bytes[..2]);
+    assert_eq!(&[0b1010, 125, 0, 2], &bytes[8..12]);
+
+    let slice = IgmpSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..], slice.slice());
+    assert!(slice.is_checksum_valid());
+    assert_eq!(header, slice.header());
+    assert_eq!(
+        vec![[10, 0, 0, 1], [10, 0, 0, 2]],
+        slice.query_sources().collect::<Vec<_>>()
+    );
+
+    // missing source
+    assert_matches!(
+        IgmpSlice::from_slice(&bytes[..bytes.len() - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(20))
+    );
+    // queries with 9 - 11 bytes are neither IGMPv2 nor IGMPv3 queries
+    for len in 9..IgmpHeader::V3_QUERY_LEN {
+        assert_matches!(
+            IgmpSlice::from_slice(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(12))
+        );
+    }
+}
+
+#[test]
+fn decode_code() {
+    assert_eq!(0, IgmpHeader::decode_code(0));
+    assert_eq!(127, IgmpHeader::decode_code(127));
+    // exp 0, mant 0
+    assert_eq!(0x10 << 3, IgmpHeader::decode_code(0x80));
+    // exp 7, mant 0xf
+    assert_eq!(0x1f << 10, IgmpHeader::decode_code(0xff));
+    // exp 1, mant 2
+    assert_eq!(0x12 << 4, IgmpHeader::decode_code(0x92));
+}
+
+#[test]
+fn v3_report() {
+    let mut records = Vec::new();
+    // include two sources
+    records.extend_from_slice(&[igmp::RECORD_MODE_IS_INCLUDE, 0, 0, 2, 232, 1, 1, 1]);
+    records.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+    // exclude no sources with one word of aux data
+    records.extend_from_slice(&[igmp::RECORD_CHANGE_TO_EXCLUDE_MODE, 1, 0, 0, 239, 2, 2, 2]);
+    records.extend_from_slice(&[9, 9, 9, 9]);
+
+    let header = IgmpHeader::MembershipReportV3{ number_of_group_records: 2 };
+    let bytes = serialize(&header, &records);
+    let slice = IgmpSlice::from_slice(&bytes).unwrap();
+    assert_eq!(&bytes[..], slice.slice());
+    assert!(slice.is_checksum_valid());
+    assert_eq!(header, slice.header());
+    assert_eq!(0, slice.query_sources().count());
+
+    let mut iter = slice.group_records();
+    let first = iter.next().unwrap();
+    assert_eq!(&records[..16], first.slice());
+    assert_eq!(igmp::RECORD_MODE_IS_INCLUDE, first.record_type());
+    assert_eq!(0, first.aux_data_len());
+    assert_eq!(2, first.number_of_sources());
+    assert_eq!([232, 1, 1, 1], first.multicast_address());
+    assert_eq!(vec![[10, 0, 0, 1], [10, 0, 0, 2]], first.sources().collect::<Vec<_>>());
+    assert!(first.aux_data().is_empty());
+
+    let second = iter.next().unwrap();
+    assert_eq!(igmp::RECORD_CHANGE_TO_EXCLUDE_MODE, second.record_type());
+    assert_eq!(1, second.aux_data_len());
+    assert_eq!([239, 2, 2, 2], second.multicast_address());
+    assert_eq!(0, second.sources().count());
+    assert_eq!(&[9, 9, 9, 9], second.aux_data());
+    assert!(iter.next().is_none());
+
+    // missing record parts
+    let packet_len = bytes.len();
+    assert_matches!(
+        IgmpSlice::from_slice(&bytes[..packet_len - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(36))
+    );
+    assert_matches!(
+        IgmpSlice::from_slice(&bytes[..8 + 16 + 7]),
+        Err(ReadError::UnexpectedEndOfSlice(32))
+    );
+    assert_matches!(
+        IgmpSlice::from_slice(&bytes[..8]),
+        Err(ReadError::UnexpectedEndOfSlice(16))
+    );
+}
+
+#[test]
+fn write_error() {
+    let header = IgmpHeader::LeaveGroup{ group_address: [224, 0, 0, 2] };
+    let mut buffer = [0u8; 7];
+    assert_matches!(
+        header.write(&mut Cursor::new(&mut buffer[..]), &[]),
+        Err(WriteError::IoError(_))
+    );
+}
+
+#[test]
+fn sliced_packet() {
+    let igmp = serialize(&IgmpHeader::MembershipReportV2{ group_address: [239, 1, 2, 3] }, &[]);
+    let mut packet = Vec::new();
+    Ipv4Header::new(igmp.len() as u16, 1, ip_number::IGMP, [10, 0, 0, 1], [239, 1, 2, 3])
+        .write(&mut packet)
+        .unwrap();
+    packet.extend_from_slice(&igmp);
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    match &sliced.transport {
+        Some(TransportSlice::Igmp(slice)) => assert_eq!(&igmp[..], slice.slice()),
+        value => panic!("unexpected transport {:?}", value),
+    }
+    assert!(sliced.payload.is_empty());
+    assert_eq!(ip_number::IGMP, FlowKey::from_sliced(&sliced).unwrap().ip_number);
+
+    // not decoded by PacketHeaders (left in the payload)
+    let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_eq!(None, decoded.transport);
+    assert_eq!(&igmp[..], decoded.payload);
+
+    // too short
+    assert_matches!(
+        SlicedPacket::from_ip(&packet[..packet.len() - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+}
+
+#[test]
+fn sliced_packet_padding() {
+    // igmpv2 query in a minimum size ethernet frame (padding is not
+    // part of the ip payload & does not turn the query into an IGMPv3 query)
+    let header = IgmpHeader::MembershipQuery(IgmpMembershipQuery{
+        max_resp_code: 100,
+        group_address: [0; 4],
+        v3: None,
+    });
+    let igmp = serialize(&header, &[]);
+    let mut packet = Vec::new();
+    Ethernet2Header{
+        source: [1, 2, 3, 4, 5, 6],
+        destination: [0x01, 0x00, 0x5e, 0, 0, 1],
+        ether_type: ether_type::IPV4,
+    }.write(&mut packet).unwrap();
+    Ipv4Header::new(igmp.len() as u16, 1, ip_number::IGMP, [10, 0, 0, 1], [224, 0, 0, 1])
+        .write(&mut packet)
+        .unwrap();
+    packet.extend_from_slice(&igmp);
+    packet.resize(60, 0);
+
+    let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+    match &sliced.transport {
+        Some(TransportSlice::Igmp(slice)) => {
+            assert_eq!(&igmp[..], slice.slice());
+            assert_eq!(header, slice.header());
+        },
+        value => panic!("unexpected transport {:?}", value),
+    }
+    assert_eq!(&[0; 18], sliced.payload);
+}
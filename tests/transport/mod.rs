@@ -1,8 +1,17 @@
 pub mod icmp;
 pub mod icmpv4;
 pub mod icmpv6;
+pub mod icmpv6_ndp;
+pub mod igmp;
+pub mod ping_payload;
+pub mod sctp;
 pub mod udp;
+pub mod udp_lite;
+pub mod dccp;
+pub mod vrrp;
 pub mod tcp;
+pub mod tcp_analysis;
+pub mod tcp_syn_cookie;
 
 mod transport_header {
     use super::super::*;
@@ -110,6 +119,159 @@ mod transport_header {
             assert_eq!(None, TransportHeader::Udp(Default::default()).mut_icmpv6());
         }
     }
+    proptest! {
+        #[test]
+        fn udp_lite(
+            udp_lite in udp_lite_any(),
+            ipv4 in ipv4_any(),
+            ipv6 in ipv6_any(),
+        ) {
+            use TransportHeader::*;
+
+            // accessors
+            assert_eq!(Some(udp_lite.clone()), UdpLite(udp_lite.clone()).udp_lite());
+            assert_eq!(Some(&mut udp_lite.clone()), UdpLite(udp_lite.clone()).mut_udp_lite());
+            assert_eq!(None, Udp(Default::default()).udp_lite());
+            assert_eq!(None, Udp(Default::default()).mut_udp_lite());
+            assert_eq!(UdpLiteHeader::SIZE, UdpLite(udp_lite.clone()).header_len());
+
+            // write
+            {
+                let mut buffer = Vec::new();
+                UdpLite(udp_lite.clone()).write(&mut buffer).unwrap();
+                assert_eq!(&udp_lite.to_bytes()[..], &buffer[..]);
+            }
+
+            // checksum (covering the complete datagram)
+            let header = UdpLiteHeader{ checksum_coverage: 0, ..udp_lite.clone() };
+            let payload = [1,2,3];
+            {
+                let mut transport = UdpLite(header.clone());
+                transport.update_checksum_ipv4(&ipv4, &payload).unwrap();
+                assert_eq!(
+                    transport.udp_lite().unwrap().checksum,
+                    header.calc_checksum_ipv4(&ipv4, &payload).unwrap()
+                );
+            }
+            {
+                let mut transport = UdpLite(header.clone());
+                transport.update_checksum_ipv6(&ipv6, &payload).unwrap();
+                assert_eq!(
+                    transport.udp_lite().unwrap().checksum,
+                    header.calc_checksum_ipv6(&ipv6, &payload).unwrap()
+                );
+            }
+
+            // checksum error (coverage too small)
+            {
+                let header = UdpLiteHeader{ checksum_coverage: 1, ..udp_lite };
+                assert_eq!(
+                    Err(ValueError::UdpLiteChecksumCoverageBad{ coverage: 1, len: 11 }),
+                    UdpLite(header.clone()).update_checksum_ipv4(&ipv4, &payload)
+                );
+                assert_eq!(
+                    Err(ValueError::UdpLiteChecksumCoverageBad{ coverage: 1, len: 11 }),
+                    UdpLite(header).update_checksum_ipv6(&ipv6, &payload)
+                );
+            }
+        }
+    }
+    proptest! {
+        #[test]
+        fn dccp(
+            dccp in dccp_any(),
+            ipv4 in ipv4_any(),
+            ipv6 in ipv6_any(),
+        ) {
+            use TransportHeader::*;
+
+            // accessors
+            assert_eq!(Some(dccp.clone()), Dccp(dccp.clone()).dccp());
+            assert_eq!(Some(&mut dccp.clone()), Dccp(dccp.clone()).mut_dccp());
+            assert_eq!(None, Udp(Default::default()).dccp());
+            assert_eq!(None, Udp(Default::default()).mut_dccp());
+            assert_eq!(dccp.header_len(), Dccp(dccp.clone()).header_len());
+
+            // write
+            {
+                let mut buffer = Vec::new();
+                Dccp(dccp.clone()).write(&mut buffer).unwrap();
+                assert_eq!(&dccp.to_bytes().unwrap()[..], &buffer[..]);
+            }
+
+            // checksum (covering the complete payload)
+            let mut header = dccp.clone();
+            header.checksum_coverage = 0;
+            let payload = [1,2,3];
+            {
+                let mut transport = Dccp(header.clone());
+                transport.update_checksum_ipv4(&ipv4, &payload).unwrap();
+                assert_eq!(
+                    transport.dccp().unwrap().checksum,
+                    header.calc_checksum_ipv4(&ipv4, &payload).unwrap()
+                );
+            }
+            {
+                let mut transport = Dccp(header.clone());
+                transport.update_checksum_ipv6(&ipv6, &payload).unwrap();
+                assert_eq!(
+                    transport.dccp().unwrap().checksum,
+                    header.calc_checksum_ipv6(&ipv6, &payload).unwrap()
+                );
+            }
+
+            // checksum error (coverage exceeding the payload)
+            {
+                let mut header = dccp;
+                header.checksum_coverage = 3;
+                assert_eq!(
+                    Err(ValueError::DccpChecksumCoverageBad{ coverage: 3, payload_len: 3 }),
+                    Dccp(header.clone()).update_checksum_ipv4(&ipv4, &payload)
+                );
+                assert_eq!(
+                    Err(ValueError::DccpChecksumCoverageBad{ coverage: 3, payload_len: 3 }),
+                    Dccp(header).update_checksum_ipv6(&ipv6, &payload)
+                );
+            }
+        }
+    }
+    proptest! {
+        #[test]
+        fn sctp(
+            sctp in sctp_any(),
+            ipv4 in ipv4_any(),
+            ipv6 in ipv6_any(),
+        ) {
+            use TransportHeader::*;
+
+            // accessors
+            assert_eq!(Some(sctp.clone()), Sctp(sctp.clone()).sctp());
+            assert_eq!(Some(&mut sctp.clone()), Sctp(sctp.clone()).mut_sctp());
+            assert_eq!(None, Udp(Default::default()).sctp());
+            assert_eq!(None, Udp(Default::default()).mut_sctp());
+            assert_eq!(SctpHeader::SERIALIZED_SIZE, Sctp(sctp.clone()).header_len());
+
+            // write
+            {
+                let mut buffer = Vec::new();
+                Sctp(sctp.clone()).write(&mut buffer).unwrap();
+                assert_eq!(&sctp.to_bytes()[..], &buffer[..]);
+            }
+
+            // checksum (crc32c, independent of the ip header)
+            let payload = [1,2,3,4];
+            {
+                let mut transport = Sctp(sctp.clone());
+                transport.update_checksum_ipv4(&ipv4, &payload).unwrap();
+                assert_eq!(transport.sctp().unwrap().checksum, sctp.calc_checksum(&payload));
+            }
+            {
+                let mut transport = Sctp(sctp.clone());
+                transport.update_checksum_ipv6(&ipv6, &payload).unwrap();
+                assert_eq!(transport.sctp().unwrap().checksum, sctp.calc_checksum(&payload));
+            }
+        }
+    }
     proptest! {
         #[test]
         fn header_size(
@@ -0,0 +1,145 @@
+use super::super::*;
+
+use std::time::Duration;
+
+mod ping_pattern {
+    use super::*;
+
+    #[test]
+    fn byte_at() {
+        use PingPattern::*;
+        assert_eq!(0, Incrementing.byte_at(0));
+        assert_eq!(200, Incrementing.byte_at(200));
+        assert_eq!(1, Incrementing.byte_at(257));
+        assert_eq!(b'a', Alphabet.byte_at(0));
+        assert_eq!(b'w', Alphabet.byte_at(22));
+        assert_eq!(b'a', Alphabet.byte_at(23));
+        assert_eq!(0xab, Byte(0xab).byte_at(1234));
+    }
+
+    #[test]
+    fn fill_matches() {
+        use PingPattern::*;
+        for pattern in [Incrementing, Alphabet, Byte(0xab)] {
+            let mut payload = [0u8;300];
+            pattern.fill(&mut payload);
+            for (offset, value) in payload.iter().enumerate() {
+                assert_eq!(pattern.byte_at(offset), *value);
+            }
+            assert!(pattern.matches(&payload));
+            assert!(pattern.matches(&[]));
+
+            // corrupted
+            payload[299] = payload[299].wrapping_add(1);
+            assert!(!pattern.matches(&payload));
+        }
+
+        // windows ping payload
+        let mut payload = [0u8;32];
+        Alphabet.fill(&mut payload);
+        assert_eq!(b"abcdefghijklmnopqrstuvwabcdefghi", &payload);
+    }
+}
+
+mod payload {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let timestamp = Duration::from_secs(1);
+        assert_eq!(
+            PingPayload {
+                timestamp,
+                pattern: PingPattern::Incrementing,
+            },
+            PingPayload::new(timestamp)
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn write_read(
+            secs in any::<u64>(),
+            micros in 0u32..1_000_000,
+            nanos in 0u32..1000,
+            len in 16usize..100,
+        ) {
+            let timestamp = Duration::new(secs, micros*1000 + nanos);
+            let payload = PingPayload::new(timestamp).to_vec(len);
+            assert_eq!(len, payload.len());
+            assert_eq!(&secs.to_le_bytes(), &payload[..8]);
+            assert_eq!(&u64::from(micros).to_le_bytes(), &payload[8..16]);
+            for (offset, value) in payload.iter().enumerate().skip(16) {
+                assert_eq!(offset as u8, *value);
+            }
+
+            // sub microsecond part is not stored
+            let expected = Duration::new(secs, micros*1000);
+            assert_eq!(Some(expected), PingPayload::read_timestamp(&payload));
+            assert!(PingPayload::new(timestamp).matches(&payload));
+            assert!(PingPayload::new(expected).matches(&payload));
+        }
+    }
+
+    #[test]
+    fn short_payload() {
+        let value = PingPayload {
+            timestamp: Duration::from_secs(5),
+            pattern: PingPattern::Alphabet,
+        };
+        let payload = value.to_vec(8);
+        assert_eq!(b"abcdefgh", &payload[..]);
+        assert!(value.matches(&payload));
+        assert_eq!(None, PingPayload::read_timestamp(&payload));
+        assert_eq!(None, PingPayload::round_trip_time(&payload, Duration::from_secs(6)));
+    }
+
+    #[test]
+    fn matches() {
+        let value = PingPayload::new(Duration::from_millis(1500));
+        let payload = value.to_vec(PingPayload::DEFAULT_LEN);
+        assert!(value.matches(&payload));
+
+        // other timestamp
+        assert!(!PingPayload::new(Duration::from_millis(1501)).matches(&payload));
+        // other pattern
+        assert!(!PingPayload{ pattern: PingPattern::Byte(0), ..value }.matches(&payload));
+        // invalid timestamp
+        {
+            let mut payload = payload.clone();
+            payload[8..16].copy_from_slice(&1_000_000u64.to_le_bytes());
+            assert!(!value.matches(&payload));
+            assert_eq!(None, PingPayload::read_timestamp(&payload));
+        }
+    }
+
+    #[test]
+    fn round_trip_time() {
+        let payload = PingPayload::new(Duration::from_millis(1000)).to_vec(64);
+        assert_eq!(Some(Duration::from_millis(25)), PingPayload::round_trip_time(&payload, Duration::from_millis(1025)));
+        assert_eq!(Some(Duration::ZERO), PingPayload::round_trip_time(&payload, Duration::from_millis(1000)));
+        // reply received "before" the request was sent
+        assert_eq!(None, PingPayload::round_trip_time(&payload, Duration::from_millis(999)));
+    }
+
+    #[test]
+    fn echo_reply() {
+        let send_time = Duration::from_secs(1_700_000_000);
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([192,168,1,2], [192,168,1,1], 64)
+            .icmpv4_echo_reply(1, 2)
+            .write(&mut packet, &PingPayload::new(send_time).to_vec(PingPayload::DEFAULT_LEN))
+            .unwrap();
+
+        let sliced = SlicedPacket::from_ip(&packet).unwrap();
+        let icmp = match sliced.transport {
+            Some(TransportSlice::Icmpv4(icmp)) => icmp,
+            _ => panic!("expected icmpv4"),
+        };
+        assert!(PingPayload::new(send_time).matches(icmp.payload()));
+        assert_eq!(
+            Some(Duration::from_millis(3)),
+            PingPayload::round_trip_time(icmp.payload(), send_time + Duration::from_millis(3))
+        );
+    }
+}
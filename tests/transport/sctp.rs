@@ -0,0 +1,302 @@
+use super::super::*;
+use std::io::Cursor;
+
+/// DATA chunk with the flags B & E set, tsn 1, stream 2, ssn 3, ppid 46 and 3 bytes of user data.
+const DATA_CHUNK: [u8; 20] = [
+    0, 0b11, 0, 19,
+    0, 0, 0, 1,
+    0, 2, 0, 3,
+    0, 0, 0, 46,
+    1, 2, 3, 0, // last byte is padding
+];
+
+mod sctp_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in sctp_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..20),
+        ) {
+            let mut bytes = Vec::new();
+            header.write(&mut bytes).unwrap();
+            bytes.extend_from_slice(&payload);
+
+            // normal read
+            {
+                let (actual, rest) = SctpHeader::from_slice(&bytes).unwrap();
+                assert_eq!(header, actual);
+                assert_eq!(&payload[..], rest);
+                assert_eq!(header, SctpHeader::read(&mut Cursor::new(&bytes)).unwrap());
+                assert_eq!(header, SctpHeader::from_bytes(header.to_bytes()));
+                assert_eq!(SctpHeader::SERIALIZED_SIZE, header.header_len());
+            }
+
+            // slice
+            {
+                let slice = SctpHeaderSlice::from_slice(&bytes).unwrap();
+                assert_eq!(&bytes[..12], slice.slice());
+                assert_eq!(header.source_port, slice.source_port());
+                assert_eq!(header.destination_port, slice.destination_port());
+                assert_eq!(header.verification_tag, slice.verification_tag());
+                assert_eq!(header.checksum, slice.checksum());
+                assert_eq!(header, slice.to_header());
+            }
+
+            // length error
+            for len in 0..SctpHeader::SERIALIZED_SIZE {
+                assert_matches!(
+                    SctpHeader::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(12))
+                );
+                assert!(SctpHeader::read(&mut Cursor::new(&bytes[..len])).is_err());
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_error(header in sctp_any()) {
+            let mut buffer = [0u8; 11];
+            assert_matches!(
+                header.write(&mut Cursor::new(&mut buffer[..])),
+                Err(WriteError::IoError(_))
+            );
+        }
+    }
+
+    #[test]
+    fn calc_checksum() {
+        // CRC32c of 32 zero bytes is 0x8a9136aa (transmitted as aa 36 91 8a)
+        let header = SctpHeader { checksum: 1234, ..Default::default() };
+        assert_eq!(0xaa36_918a, header.calc_checksum(&[0; 20]));
+
+        // verification via the slice
+        let mut header = SctpHeader {
+            source_port: 2905,
+            destination_port: 2905,
+            verification_tag: 0x1234_5678,
+            checksum: 0,
+        };
+        header.checksum = header.calc_checksum(&DATA_CHUNK);
+        let mut bytes = header.to_bytes().to_vec();
+        bytes.extend_from_slice(&DATA_CHUNK);
+        let slice = SctpHeaderSlice::from_slice(&bytes).unwrap();
+        assert!(slice.is_checksum_valid(&DATA_CHUNK));
+        let mut modified = DATA_CHUNK;
+        modified[17] = 0;
+        assert!(!slice.is_checksum_valid(&modified));
+    }
+}
+
+mod sctp_chunk_iterator {
+    use super::*;
+    use SctpChunkReadError::*;
+
+    #[test]
+    fn chunks() {
+        let mut chunks = DATA_CHUNK.to_vec();
+        // HEARTBEAT with heartbeat info
+        chunks.extend_from_slice(&[4, 0, 0, 12, 0, 1, 0, 8, 9, 8, 7, 6]);
+        // COOKIE ACK without padding (length 4)
+        chunks.extend_from_slice(&[11, 0, 0, 4]);
+
+        let mut iter = SctpChunkIterator::from_slice(&chunks);
+        assert_eq!(&chunks[..], iter.rest());
+
+        let data = iter.next().unwrap().unwrap();
+        assert_eq!(sctp_chunk_type::DATA, data.chunk_type());
+        assert_eq!(0b11, data.flags());
+        assert_eq!(19, data.length());
+        assert_eq!(&DATA_CHUNK[..19], data.slice());
+        assert_eq!(
+            Some(SctpDataChunk {
+                immediate: false,
+                unordered: false,
+                beginning: true,
+                ending: true,
+                tsn: 1,
+                stream_id: 2,
+                stream_sequence_number: 3,
+                payload_protocol_id: 46,
+                user_data: &[1, 2, 3],
+            }),
+            data.data()
+        );
+        assert_eq!(None, data.init());
+        assert_eq!(None, data.sack());
+
+        let heartbeat = iter.next().unwrap().unwrap();
+        assert_eq!(sctp_chunk_type::HEARTBEAT, heartbeat.chunk_type());
+        assert_eq!(&[0, 1, 0, 8, 9, 8, 7, 6], heartbeat.value());
+        assert_eq!(None, heartbeat.data());
+
+        let cookie_ack = iter.next().unwrap().unwrap();
+        assert_eq!(sctp_chunk_type::COOKIE_ACK, cookie_ack.chunk_type());
+        assert!(cookie_ack.value().is_empty());
+        assert!(iter.next().is_none());
+
+        // after the last chunk the padding is optional
+        assert_eq!(
+            vec![Ok(data)],
+            SctpChunkIterator::from_slice(&DATA_CHUNK[..19]).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn init() {
+        use sctp_chunk_type::*;
+        for chunk_type in [INIT, INIT_ACK] {
+            let chunk = [
+                chunk_type, 0, 0, 28,
+                1, 2, 3, 4, // initiate tag
+                0, 1, 0, 0, // a_rwnd
+                0, 10, 0, 11, // streams
+                5, 6, 7, 8, // initial tsn
+                0, 0xc, 0, 6, 0, 5, 0, 0, // supported address types parameter (with padding)
+            ];
+            let chunk = SctpChunkIterator::from_slice(&chunk).next().unwrap().unwrap();
+            assert_eq!(
+                Some(SctpInitChunk {
+                    initiate_tag: 0x0102_0304,
+                    a_rwnd: 0x1_0000,
+                    outbound_streams: 10,
+                    inbound_streams: 11,
+                    initial_tsn: 0x0506_0708,
+                    parameters: &[0, 0xc, 0, 6, 0, 5, 0, 0],
+                }),
+                chunk.init()
+            );
+        }
+        // too short
+        let chunk = [INIT, 0, 0, 19, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let chunk = SctpChunkIterator::from_slice(&chunk).next().unwrap().unwrap();
+        assert_eq!(None, chunk.init());
+    }
+
+    #[test]
+    fn sack() {
+        let chunk = [
+            3, 0, 0, 28,
+            0, 0, 0, 10, // cumulative tsn ack
+            0, 0, 0x10, 0, // a_rwnd
+            0, 2, 0, 1, // 2 gap blocks & 1 duplicate tsn
+            0, 2, 0, 3,
+            0, 5, 0, 5,
+            0, 0, 0, 7,
+        ];
+        let chunk = SctpChunkIterator::from_slice(&chunk).next().unwrap().unwrap();
+        let sack = chunk.sack().unwrap();
+        assert_eq!(10, sack.cumulative_tsn_ack);
+        assert_eq!(0x1000, sack.a_rwnd);
+        assert_eq!(vec![(2, 3), (5, 5)], sack.gap_ack_blocks().collect::<Vec<_>>());
+        assert_eq!(vec![7], sack.duplicate_tsns().collect::<Vec<_>>());
+
+        // more gap blocks then the chunk contains
+        let chunk = [3, 0, 0, 20, 0, 0, 0, 10, 0, 0, 0x10, 0, 0, 2, 0, 0, 0, 2, 0, 3];
+        let chunk = SctpChunkIterator::from_slice(&chunk).next().unwrap().unwrap();
+        assert_eq!(None, chunk.sack());
+    }
+
+    #[test]
+    fn errors() {
+        // not enough data for the chunk header
+        let mut iter = SctpChunkIterator::from_slice(&[1, 0, 0]);
+        assert_eq!(
+            Some(Err(UnexpectedEndOfSlice{ chunk_type: 1, expected_len: 4, actual_len: 3 })),
+            iter.next()
+        );
+        assert!(iter.next().is_none());
+
+        // length smaller then the chunk header
+        let mut iter = SctpChunkIterator::from_slice(&[11, 0, 0, 3, 11, 0, 0, 4]);
+        assert_eq!(Some(Err(LengthTooSmall{ chunk_type: 11, length: 3 })), iter.next());
+        assert!(iter.next().is_none());
+
+        // length bigger then the slice
+        let chunks = [0, 0, 0, 20, 1, 2, 3, 4];
+        assert_eq!(
+            vec![Err(UnexpectedEndOfSlice{ chunk_type: 0, expected_len: 20, actual_len: 8 })],
+            SctpChunkIterator::from_slice(&chunks).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            "SctpChunkReadError: Not enough data left in slice to read chunk of type 1 (expected at least 4 bytes, only 3 bytes available).",
+            format!("{}", UnexpectedEndOfSlice{ chunk_type: 1, expected_len: 4, actual_len: 3 })
+        );
+        assert_eq!(
+            "SctpChunkReadError: Length value 3 of the chunk of type 11 is smaller then the chunk header (4 bytes).",
+            format!("{}", LengthTooSmall{ chunk_type: 11, length: 3 })
+        );
+        use std::error::Error;
+        assert!(LengthTooSmall{ chunk_type: 11, length: 3 }.source().is_none());
+    }
+
+    #[test]
+    fn debug() {
+        let chunks = [11, 0, 0, 4];
+        let iter = SctpChunkIterator::from_slice(&chunks);
+        assert_eq!(
+            format!("[{:?}]", iter.clone().next().unwrap()),
+            format!("{:?}", iter)
+        );
+    }
+}
+
+#[test]
+fn sliced_packet() {
+    let header = SctpHeader {
+        source_port: 36412,
+        destination_port: 2905,
+        verification_tag: 1,
+        checksum: 0,
+    };
+    let mut packet = Vec::new();
+    Ipv4Header::new(
+        (SctpHeader::SERIALIZED_SIZE + DATA_CHUNK.len()) as u16,
+        64,
+        ip_number::SCTP,
+        [10, 0, 0, 1],
+        [10, 0, 0, 2]
+    ).write(&mut packet).unwrap();
+    header.write(&mut packet).unwrap();
+    packet.extend_from_slice(&DATA_CHUNK);
+
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    match &sliced.transport {
+        Some(TransportSlice::Sctp(sctp)) => assert_eq!(header, sctp.to_header()),
+        value => panic!("unexpected transport {:?}", value),
+    }
+    assert_eq!(&DATA_CHUNK[..], sliced.payload);
+    assert_eq!(
+        vec![sctp_chunk_type::DATA],
+        SctpChunkIterator::from_slice(sliced.payload).map(|c| c.unwrap().chunk_type()).collect::<Vec<_>>()
+    );
+
+    let key = FlowKey::from_sliced(&sliced).unwrap();
+    assert_eq!(ip_number::SCTP, key.ip_number);
+    assert_eq!(36412, key.source_port);
+    assert_eq!(2905, key.destination_port);
+
+    let decoded = PacketHeaders::from_ip_slice(&packet).unwrap();
+    assert_eq!(Some(TransportHeader::Sctp(header.clone())), decoded.transport);
+    assert_eq!(&DATA_CHUNK[..], decoded.payload);
+    let key = FlowKey::from_headers(decoded.ip.as_ref().unwrap(), decoded.transport.as_ref()).unwrap();
+    assert_eq!(ip_number::SCTP, key.ip_number);
+    assert_eq!(36412, key.source_port);
+    assert_eq!(2905, key.destination_port);
+
+    // too short for the sctp header
+    assert_matches!(
+        SlicedPacket::from_ip(&packet[..Ipv4Header::SERIALIZED_SIZE + 11]),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+    assert_matches!(
+        PacketHeaders::from_ip_slice(&packet[..Ipv4Header::SERIALIZED_SIZE + 11]),
+        Err(ReadError::UnexpectedEndOfSlice(_))
+    );
+}
@@ -676,6 +676,17 @@ mod header {
                     ]));
     }
 
+    proptest! {
+        #[test]
+        fn to_bytes(ref input in tcp_any()) {
+            let mut buffer: Vec<u8> = Vec::with_capacity(input.header_len().into());
+            input.write(&mut buffer).unwrap();
+            assert_eq!(&buffer[..], &input.to_bytes()[..]);
+            assert!(TcpHeader::MIN_SERIALIZED_SIZE <= buffer.len());
+            assert!(buffer.len() <= TcpHeader::MAX_SERIALIZED_SIZE);
+        }
+    }
+
 } // mod header
 
 proptest! {
@@ -1593,3 +1604,26 @@ proptest! {
         assert!(NotEnoughSpace(arg_usize).source().is_none());
     }
 }
+
+#[test]
+fn hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let hash = |header: &TcpHeader| {
+        let mut hasher = DefaultHasher::new();
+        header.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    // unused parts of the options buffer are not part of the hash
+    let mut a = TcpHeader::new(1, 2, 3, 4);
+    a.set_options_raw(&[1,2,3,4,5,6,7,8]).unwrap();
+    a.set_options_raw(&[1,2,3,4]).unwrap();
+    let mut b = TcpHeader::new(1, 2, 3, 4);
+    b.set_options_raw(&[1,2,3,4]).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+
+    b.set_options_raw(&[1,2,3,5]).unwrap();
+    assert_ne!(hash(&a), hash(&b));
+}
@@ -0,0 +1,379 @@
+use super::super::*;
+
+use TcpSegmentDirection::*;
+
+/// Creates an ack segment with the given values.
+fn ack_segment(seq: u32, ack: u32, window: u16) -> TcpHeader {
+    let mut header = TcpHeader::new(1234, 80, seq, window);
+    header.ack = true;
+    header.acknowledgment_number = ack;
+    header
+}
+
+/// Creates a flow where both sides have exchanged a syn and the
+/// forward side sent 100 bytes that were acknowledged.
+fn established() -> TcpFlowState {
+    let mut flow = TcpFlowState::new();
+    let mut syn = TcpHeader::new(1234, 80, 999, 1000);
+    syn.syn = true;
+    flow.analyze_header(Forward, &syn, 0);
+
+    let mut syn_ack = ack_segment(4999, 1000, 1000);
+    syn_ack.syn = true;
+    flow.analyze_header(Reverse, &syn_ack, 0);
+
+    flow.analyze_header(Forward, &ack_segment(1000, 5000, 1000), 100);
+    flow.analyze_header(Reverse, &ack_segment(5000, 1100, 1000), 0);
+    flow
+}
+
+#[test]
+fn direction_reverse() {
+    assert_eq!(Reverse, Forward.reverse());
+    assert_eq!(Forward, Reverse.reverse());
+}
+
+#[test]
+fn new() {
+    let flow = TcpFlowState::new();
+    assert_eq!(TcpPeerState::default(), *flow.peer(Forward));
+    assert_eq!(TcpPeerState::default(), *flow.peer(Reverse));
+}
+
+#[test]
+fn state_update() {
+    let flow = established();
+    assert_eq!(
+        &TcpPeerState {
+            next_sequence_number: Some(1100),
+            last_acknowledgment_number: Some(5000),
+            last_window_size: Some(1000),
+            window_scale: None,
+            duplicate_ack_count: 0,
+        },
+        flow.peer(Forward)
+    );
+    assert_eq!(
+        &TcpPeerState {
+            next_sequence_number: Some(5000),
+            last_acknowledgment_number: Some(1100),
+            last_window_size: Some(1000),
+            window_scale: None,
+            duplicate_ack_count: 0,
+        },
+        flow.peer(Reverse)
+    );
+}
+
+#[test]
+fn normal_data() {
+    let mut flow = established();
+    assert_eq!(
+        TcpSegmentClassification::default(),
+        flow.analyze_header(Forward, &ack_segment(1100, 5000, 1000), 100)
+    );
+}
+
+#[test]
+fn keep_alive() {
+    let mut flow = established();
+    // without data
+    {
+        let result = flow.analyze_header(Forward, &ack_segment(1099, 5000, 1000), 0);
+        assert!(result.keep_alive);
+        assert!(!result.retransmission);
+    }
+    // with one garbage byte
+    {
+        let result = flow.analyze_header(Forward, &ack_segment(1099, 5000, 1000), 1);
+        assert!(result.keep_alive);
+        assert!(!result.retransmission);
+    }
+    // next sequence number is unchanged
+    assert_eq!(Some(1100), flow.peer(Forward).next_sequence_number);
+}
+
+#[test]
+fn zero_window_and_probe() {
+    let mut flow = established();
+
+    // receiver closes the window
+    let result = flow.analyze_header(Reverse, &ack_segment(5000, 1100, 0), 0);
+    assert!(result.zero_window);
+
+    // probe with one byte at the next expected sequence number
+    let result = flow.analyze_header(Forward, &ack_segment(1100, 5000, 1000), 1);
+    assert!(result.zero_window_probe);
+    assert!(!result.keep_alive);
+    assert!(!result.retransmission);
+
+    // syn segments with a zero window are not flagged
+    let mut syn = TcpHeader::new(1, 2, 3, 0);
+    syn.syn = true;
+    assert!(!TcpFlowState::new().analyze_header(Forward, &syn, 0).zero_window);
+}
+
+#[test]
+fn window_full() {
+    let mut flow = established();
+    // receiver acked 1100 with a window of 1000 -> 1100 + 1000 = 2100
+    let result = flow.analyze_header(Forward, &ack_segment(1100, 5000, 1000), 1000);
+    assert!(result.window_full);
+
+    let mut flow = established();
+    let result = flow.analyze_header(Forward, &ack_segment(1100, 5000, 1000), 999);
+    assert!(!result.window_full);
+}
+
+#[test]
+fn window_full_scaled() {
+    let mut flow = TcpFlowState::new();
+
+    let mut syn = TcpHeader::new(1234, 80, 999, 1000);
+    syn.syn = true;
+    syn.set_options(&[TcpOptionElement::WindowScale(2)]).unwrap();
+    flow.analyze_header(Forward, &syn, 0);
+    assert_eq!(Some(2), flow.peer(Forward).window_scale);
+
+    let mut syn_ack = ack_segment(4999, 1000, 1000);
+    syn_ack.syn = true;
+    syn_ack.set_options(&[TcpOptionElement::WindowScale(1)]).unwrap();
+    // check the slice based analysis
+    {
+        let bytes = {
+            let mut bytes = Vec::new();
+            syn_ack.write(&mut bytes).unwrap();
+            bytes
+        };
+        flow.analyze(Reverse, &TcpHeaderSlice::from_slice(&bytes).unwrap(), 0);
+    }
+    assert_eq!(Some(1), flow.peer(Reverse).window_scale);
+
+    // window of 100 << 1 = 200
+    flow.analyze_header(Reverse, &ack_segment(5000, 1000, 100), 0);
+    let result = flow.analyze_header(Forward, &ack_segment(1000, 5000, 1000), 200);
+    assert!(result.window_full);
+}
+
+#[test]
+fn duplicate_ack_and_fast_retransmission() {
+    let mut flow = established();
+
+    // forward sends two segments
+    flow.analyze_header(Forward, &ack_segment(1100, 5000, 1000), 100);
+    flow.analyze_header(Forward, &ack_segment(1200, 5000, 1000), 100);
+
+    // first segment got lost, receiver repeats the ack for 1100
+    for i in 1..=3 {
+        let result = flow.analyze_header(Reverse, &ack_segment(5000, 1100, 1000), 0);
+        assert!(result.duplicate_ack);
+        assert_eq!(i, flow.peer(Reverse).duplicate_ack_count);
+    }
+
+    // resend lost segment
+    let result = flow.analyze_header(Forward, &ack_segment(1100, 5000, 1000), 100);
+    assert!(result.retransmission);
+    assert!(result.fast_retransmission);
+
+    // new ack resets the counter
+    let result = flow.analyze_header(Reverse, &ack_segment(5000, 1300, 1000), 0);
+    assert!(!result.duplicate_ack);
+    assert_eq!(0, flow.peer(Reverse).duplicate_ack_count);
+}
+
+#[test]
+fn retransmission() {
+    let mut flow = established();
+    let result = flow.analyze_header(Forward, &ack_segment(1000, 5000, 1000), 100);
+    assert!(result.retransmission);
+    assert!(!result.fast_retransmission);
+
+    // retransmitted syn
+    let mut flow = TcpFlowState::new();
+    let mut syn = TcpHeader::new(1234, 80, 999, 1000);
+    syn.syn = true;
+    assert!(!flow.analyze_header(Forward, &syn, 0).retransmission);
+    assert!(flow.analyze_header(Forward, &syn, 0).retransmission);
+}
+
+#[test]
+fn sequence_number_wrap_around() {
+    let mut flow = TcpFlowState::new();
+    flow.analyze_header(Forward, &ack_segment(u32::MAX - 9, 1, 1000), 20);
+    assert_eq!(Some(10), flow.peer(Forward).next_sequence_number);
+    assert!(!flow.analyze_header(Forward, &ack_segment(10, 1, 1000), 10).retransmission);
+    assert!(flow.analyze_header(Forward, &ack_segment(u32::MAX - 9, 1, 1000), 20).retransmission);
+}
+
+#[test]
+fn debug_clone_eq() {
+    let flow = established();
+    assert_eq!(flow.clone(), flow);
+    let result = TcpSegmentClassification::default();
+    assert_eq!(result.clone(), result);
+    println!("{:?} {:?} {:?}", flow, result, Forward);
+}
+
+mod tcp_rtt_estimator {
+    use super::*;
+    use std::time::Duration;
+
+    /// Creates a segment with the timestamp option.
+    fn ts_segment(ack: bool, value: u32, echo: u32) -> TcpHeader {
+        let mut header = TcpHeader::new(1234, 80, 1000, 1000);
+        header.ack = ack;
+        header.set_options(&[TcpOptionElement::Timestamp(value, echo)]).unwrap();
+        header
+    }
+
+    fn ms(value: u64) -> Duration {
+        Duration::from_millis(value)
+    }
+
+    #[test]
+    fn new() {
+        let estimator = TcpRttEstimator::new(16);
+        assert_eq!(16, estimator.capacity());
+        assert_eq!(0, estimator.outstanding(Forward));
+        assert_eq!(0, estimator.outstanding(Reverse));
+        assert_eq!(estimator, estimator.clone());
+        println!("{:?}", estimator);
+    }
+
+    #[test]
+    fn samples_both_directions() {
+        let mut estimator = TcpRttEstimator::new(16);
+
+        // syn (no echo) & syn-ack
+        assert_eq!(None, estimator.observe_header(Forward, ms(0), &ts_segment(false, 10, 0)));
+        assert_eq!(
+            Some(TcpRttSample{ direction: Forward, rtt: ms(30) }),
+            estimator.observe_header(Reverse, ms(30), &ts_segment(true, 500, 10))
+        );
+        // ack of the syn-ack
+        assert_eq!(
+            Some(TcpRttSample{ direction: Reverse, rtt: ms(2) }),
+            estimator.observe_header(Forward, ms(32), &ts_segment(true, 11, 500))
+        );
+        assert_eq!(1, estimator.outstanding(Forward));
+        assert_eq!(0, estimator.outstanding(Reverse));
+    }
+
+    #[test]
+    fn first_capture_time_used() {
+        let mut estimator = TcpRttEstimator::new(16);
+
+        // two segments in the same clock tick & a retransmission
+        estimator.observe_header(Forward, ms(100), &ts_segment(true, 10, 0));
+        estimator.observe_header(Forward, ms(101), &ts_segment(true, 10, 0));
+        estimator.observe_header(Forward, ms(150), &ts_segment(true, 10, 0));
+        assert_eq!(1, estimator.outstanding(Forward));
+
+        assert_eq!(
+            Some(TcpRttSample{ direction: Forward, rtt: ms(60) }),
+            estimator.observe_header(Reverse, ms(160), &ts_segment(true, 1, 10))
+        );
+        // only one sample per value (e.g. delayed second ack)
+        assert_eq!(None, estimator.observe_header(Reverse, ms(170), &ts_segment(true, 2, 10)));
+    }
+
+    #[test]
+    fn older_values_dropped() {
+        let mut estimator = TcpRttEstimator::new(16);
+        for (i, value) in [10, 11, 12].iter().enumerate() {
+            estimator.observe_header(Forward, ms(i as u64), &ts_segment(true, *value, 0));
+        }
+
+        // echo of the newest value (the older ones are not echoed anymore)
+        assert_eq!(
+            Some(TcpRttSample{ direction: Forward, rtt: ms(18) }),
+            estimator.observe_header(Reverse, ms(20), &ts_segment(true, 1, 12))
+        );
+        assert_eq!(0, estimator.outstanding(Forward));
+        assert_eq!(None, estimator.observe_header(Reverse, ms(21), &ts_segment(true, 1, 10)));
+    }
+
+    #[test]
+    fn unknown_echo() {
+        let mut estimator = TcpRttEstimator::new(16);
+        estimator.observe_header(Forward, ms(0), &ts_segment(true, 10, 0));
+        estimator.observe_header(Forward, ms(1), &ts_segment(true, 20, 0));
+
+        // value before all outstanding values
+        assert_eq!(None, estimator.observe_header(Reverse, ms(5), &ts_segment(true, 1, 5)));
+        assert_eq!(2, estimator.outstanding(Forward));
+
+        // value between outstanding values
+        assert_eq!(None, estimator.observe_header(Reverse, ms(6), &ts_segment(true, 1, 15)));
+        assert_eq!(1, estimator.outstanding(Forward));
+    }
+
+    #[test]
+    fn echo_requires_ack() {
+        let mut estimator = TcpRttEstimator::new(16);
+        estimator.observe_header(Forward, ms(0), &ts_segment(true, 10, 0));
+        assert_eq!(None, estimator.observe_header(Reverse, ms(5), &ts_segment(false, 1, 10)));
+        assert_eq!(1, estimator.outstanding(Forward));
+    }
+
+    #[test]
+    fn timestamp_wrap_around() {
+        let mut estimator = TcpRttEstimator::new(16);
+        estimator.observe_header(Forward, ms(0), &ts_segment(true, u32::MAX, 0));
+        estimator.observe_header(Forward, ms(1), &ts_segment(true, 0, 0));
+        assert_eq!(2, estimator.outstanding(Forward));
+        assert_eq!(
+            Some(TcpRttSample{ direction: Forward, rtt: ms(9) }),
+            estimator.observe_header(Reverse, ms(10), &ts_segment(true, 1, 0))
+        );
+    }
+
+    #[test]
+    fn capacity() {
+        let mut estimator = TcpRttEstimator::new(2);
+        for value in 1..=3 {
+            estimator.observe_header(Forward, ms(u64::from(value)), &ts_segment(true, value, 0));
+        }
+        assert_eq!(2, estimator.outstanding(Forward));
+        assert_eq!(
+            Some(TcpRttSample{ direction: Forward, rtt: ms(8) }),
+            estimator.observe_header(Reverse, ms(10), &ts_segment(true, 1, 2))
+        );
+
+        // zero capacity records nothing
+        let mut estimator = TcpRttEstimator::new(0);
+        estimator.observe_header(Forward, ms(0), &ts_segment(true, 1, 0));
+        assert_eq!(0, estimator.outstanding(Forward));
+    }
+
+    #[test]
+    fn no_timestamp_option() {
+        let mut estimator = TcpRttEstimator::new(16);
+        estimator.observe_header(Forward, ms(0), &ts_segment(true, 10, 0));
+        assert_eq!(None, estimator.observe_header(Reverse, ms(5), &ack_segment(1, 2, 3)));
+        assert_eq!(1, estimator.outstanding(Forward));
+    }
+
+    #[test]
+    fn observe_slice() {
+        let mut estimator = TcpRttEstimator::new(16);
+        let mut buffer = Vec::new();
+        ts_segment(true, 10, 0).write(&mut buffer).unwrap();
+        let syn = TcpHeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(None, estimator.observe(Forward, ms(0), &syn));
+
+        let mut buffer = Vec::new();
+        ts_segment(true, 20, 10).write(&mut buffer).unwrap();
+        let ack = TcpHeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(
+            Some(TcpRttSample{ direction: Forward, rtt: ms(7) }),
+            estimator.observe(Reverse, ms(7), &ack)
+        );
+
+        // none without options
+        let mut buffer = Vec::new();
+        ack_segment(1, 2, 3).write(&mut buffer).unwrap();
+        let plain = TcpHeaderSlice::from_slice(&buffer).unwrap();
+        assert_eq!(None, estimator.observe(Reverse, ms(8), &plain));
+    }
+}
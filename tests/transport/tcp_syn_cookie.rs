@@ -0,0 +1,245 @@
+use super::super::*;
+
+use std::net::IpAddr;
+
+fn syn(options: &[TcpOptionElement]) -> TcpHeader {
+    let mut result = TcpHeader::new(1234, 80, 1000, 65535);
+    result.syn = true;
+    result.set_options(options).unwrap();
+    result
+}
+
+/// ACK completing the handshake of the given SYN.
+fn ack(syn: &TcpHeader, cookie: u32) -> TcpHeader {
+    let mut result = TcpHeader::new(syn.source_port, syn.destination_port, syn.sequence_number.wrapping_add(1), 65535);
+    result.ack = true;
+    result.acknowledgment_number = cookie.wrapping_add(1);
+    result
+}
+
+mod syn_cookie_options {
+    use super::*;
+
+    #[test]
+    fn from_syn() {
+        use TcpOptionElement::*;
+
+        assert_eq!(SynCookieOptions::default(), SynCookieOptions::from_syn(&syn(&[])));
+        assert_eq!(
+            SynCookieOptions {
+                window_scale: Some(7),
+                sack_permitted: true,
+                ecn: false,
+            },
+            SynCookieOptions::from_syn(&syn(&[
+                MaximumSegmentSize(1460),
+                SelectiveAcknowledgementPermitted,
+                Noop,
+                WindowScale(7),
+            ]))
+        );
+
+        // window scale is limited to 14
+        assert_eq!(
+            Some(14),
+            SynCookieOptions::from_syn(&syn(&[WindowScale(15)])).window_scale
+        );
+
+        // ecn requires ece & cwr
+        {
+            let mut header = syn(&[]);
+            header.ece = true;
+            assert!(!SynCookieOptions::from_syn(&header).ecn);
+            header.cwr = true;
+            assert!(SynCookieOptions::from_syn(&header).ecn);
+        }
+    }
+}
+
+mod syn_cookies {
+    use super::*;
+
+    fn addrs() -> [(IpAddr, IpAddr);2] {
+        [
+            (IpAddr::from([192,168,1,1]), IpAddr::from([192,168,1,2])),
+            (IpAddr::from([1u8;16]), IpAddr::from([2u8;16])),
+        ]
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!("SynCookies { .. }", format!("{:?}", SynCookies::new([1;32])));
+    }
+
+    #[test]
+    fn mss_table() {
+        assert_eq!(&SynCookies::IPV4_MSS_TABLE, SynCookies::mss_table(IpAddr::from([0u8;4])));
+        assert_eq!(&SynCookies::IPV6_MSS_TABLE, SynCookies::mss_table(IpAddr::from([0u8;16])));
+    }
+
+    #[test]
+    fn mss_encoding() {
+        use TcpOptionElement::*;
+        let cookies = SynCookies::new([7;32]);
+        let (client, server) = addrs()[0];
+
+        for (requested, expected) in [(100, 536), (536, 536), (1299, 536), (1300, 1300), (1459, 1440), (1460, 1460), (9000, 1460)] {
+            let header = syn(&[MaximumSegmentSize(requested)]);
+            let cookie = cookies.generate(client, server, &header, 100);
+            assert_eq!(expected, cookie.mss);
+            assert_eq!(Some(expected), cookies.validate(client, server, &ack(&header, cookie.sequence_number), 100));
+        }
+
+        // default without mss option
+        {
+            let header = syn(&[]);
+            let cookie = cookies.generate(client, server, &header, 100);
+            assert_eq!(536, cookie.mss);
+        }
+
+        // ipv6 table
+        {
+            let (client, server) = addrs()[1];
+            let header = syn(&[MaximumSegmentSize(9000)]);
+            let cookie = cookies.generate(client, server, &header, 100);
+            assert_eq!(8940, cookie.mss);
+            assert_eq!(Some(8940), cookies.validate(client, server, &ack(&header, cookie.sequence_number), 100));
+        }
+    }
+
+    #[test]
+    fn validate_age() {
+        let cookies = SynCookies::new([7;32]);
+        for (client, server) in addrs() {
+            for counter in [0, 1, 255, 256, u32::MAX - 1] {
+                let header = syn(&[TcpOptionElement::MaximumSegmentSize(1440)]);
+                let cookie = cookies.generate(client, server, &header, counter);
+                let ack = ack(&header, cookie.sequence_number);
+                for age in 0..SynCookies::MAX_AGE {
+                    assert_eq!(Some(1440), cookies.validate(client, server, &ack, counter.wrapping_add(age)));
+                }
+                assert_eq!(None, cookies.validate(client, server, &ack, counter.wrapping_add(SynCookies::MAX_AGE)));
+                // cookies from the "future" are not accepted
+                assert_eq!(None, cookies.validate(client, server, &ack, counter.wrapping_sub(1)));
+            }
+        }
+    }
+
+    #[test]
+    fn validate_mismatch() {
+        let cookies = SynCookies::new([7;32]);
+        let (client, server) = addrs()[0];
+        let header = syn(&[TcpOptionElement::MaximumSegmentSize(1460)]);
+        let cookie = cookies.generate(client, server, &header, 100);
+        let valid = ack(&header, cookie.sequence_number);
+        assert_eq!(Some(1460), cookies.validate(client, server, &valid, 100));
+
+        // other secret
+        assert_eq!(None, SynCookies::new([8;32]).validate(client, server, &valid, 100));
+
+        // other addresses or ports
+        assert_eq!(None, cookies.validate(server, client, &valid, 100));
+        {
+            let mut value = valid.clone();
+            value.source_port += 1;
+            assert_eq!(None, cookies.validate(client, server, &value, 100));
+        }
+        {
+            let mut value = valid.clone();
+            value.destination_port += 1;
+            assert_eq!(None, cookies.validate(client, server, &value, 100));
+        }
+
+        // other sequence or acknowledgment number (small offsets only shift
+        // the mss index, as with all cookies using this encoding)
+        {
+            let mut value = valid.clone();
+            value.sequence_number = value.sequence_number.wrapping_add(0x1000);
+            assert_eq!(None, cookies.validate(client, server, &value, 100));
+        }
+        {
+            let mut value = valid.clone();
+            value.acknowledgment_number = value.acknowledgment_number.wrapping_add(0x1000);
+            assert_eq!(None, cookies.validate(client, server, &value, 100));
+        }
+
+        // not a pure ack
+        {
+            let mut value = valid.clone();
+            value.ack = false;
+            assert_eq!(None, cookies.validate(client, server, &value, 100));
+        }
+        {
+            let mut value = valid.clone();
+            value.syn = true;
+            assert_eq!(None, cookies.validate(client, server, &value, 100));
+        }
+        {
+            let mut value = valid.clone();
+            value.rst = true;
+            assert_eq!(None, cookies.validate(client, server, &value, 100));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn generate_validate(
+            secret in proptest::array::uniform32(any::<u8>()),
+            source in any::<[u8;4]>(),
+            destination in any::<[u8;16]>(),
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            sequence_number in any::<u32>(),
+            mss in any::<u16>(),
+            counter in any::<u32>(),
+            ipv6 in any::<bool>(),
+        ) {
+            let cookies = SynCookies::new(secret);
+            let (client, server) = if ipv6 {
+                (IpAddr::from(destination), IpAddr::from(destination.map(|v| !v)))
+            } else {
+                (IpAddr::from(source), IpAddr::from(source.map(|v| !v)))
+            };
+            let mut header = TcpHeader::new(source_port, destination_port, sequence_number, 1024);
+            header.syn = true;
+            header.set_options(&[TcpOptionElement::MaximumSegmentSize(mss)]).unwrap();
+
+            let cookie = cookies.generate(client, server, &header, counter);
+            assert!(cookie.mss <= mss || cookie.mss == SynCookies::mss_table(client)[0]);
+            assert!(SynCookies::mss_table(client).contains(&cookie.mss));
+            assert_eq!(
+                Some(cookie.mss),
+                cookies.validate(client, server, &ack(&header, cookie.sequence_number), counter)
+            );
+        }
+    }
+
+    #[test]
+    fn timestamp() {
+        let options = [
+            SynCookieOptions::default(),
+            SynCookieOptions { window_scale: Some(0), sack_permitted: true, ecn: false },
+            SynCookieOptions { window_scale: Some(14), sack_permitted: false, ecn: true },
+            SynCookieOptions { window_scale: Some(7), sack_permitted: true, ecn: true },
+        ];
+        for option in options {
+            for timestamp in [0, 1, 0x3f, 0x40, 0x1234_5678, u32::MAX] {
+                let encoded = SynCookies::encode_timestamp(timestamp, option);
+                assert_eq!(option, SynCookies::decode_timestamp(encoded));
+                // never in the future & close to the given timestamp
+                if timestamp >= 0x40 {
+                    assert!(encoded <= timestamp);
+                    assert!(timestamp - encoded < 0x80);
+                }
+            }
+        }
+
+        // values above 14 are treated as 14
+        assert_eq!(
+            Some(14),
+            SynCookies::decode_timestamp(
+                SynCookies::encode_timestamp(1000, SynCookieOptions { window_scale: Some(20), sack_permitted: false, ecn: false })
+            ).window_scale
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use super::super::*;
+use std::io::Cursor;
+
+/// Reference implementation of the udp-lite checksum (rfc3828 section 3.1).
+fn reference_checksum(pseudo_header: &[u8], header: &UdpLiteHeader, payload: &[u8]) -> u16 {
+    let mut data = pseudo_header.to_vec();
+    data.extend_from_slice(&header.source_port.to_be_bytes());
+    data.extend_from_slice(&header.destination_port.to_be_bytes());
+    data.extend_from_slice(&header.checksum_coverage.to_be_bytes());
+    data.extend_from_slice(&[0, 0]);
+    let coverage = if 0 == header.checksum_coverage {
+        8 + payload.len()
+    } else {
+        usize::from(header.checksum_coverage)
+    };
+    data.extend_from_slice(&payload[..coverage - 8]);
+    if 1 == data.len() % 2 {
+        data.push(0);
+    }
+    let mut sum: u32 = 0;
+    for word in data.chunks(2) {
+        sum += u32::from(u16::from_be_bytes([word[0], word[1]]));
+    }
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    match !(sum as u16) {
+        0 => 0xffff,
+        value => value,
+    }
+}
+
+fn ipv4_pseudo_header(source: [u8;4], destination: [u8;4], len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&source);
+    result.extend_from_slice(&destination);
+    result.extend_from_slice(&[0, ip_number::UDP_LITE]);
+    result.extend_from_slice(&(len as u16).to_be_bytes());
+    result
+}
+
+fn ipv6_pseudo_header(source: [u8;16], destination: [u8;16], len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&source);
+    result.extend_from_slice(&destination);
+    result.extend_from_slice(&(len as u32).to_be_bytes());
+    result.extend_from_slice(&[0, 0, 0, ip_number::UDP_LITE]);
+    result
+}
+
+mod udp_lite_header {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn from_slice(
+            header in udp_lite_any(),
+            payload in proptest::collection::vec(any::<u8>(), 0..20),
+        ) {
+            let mut bytes = Vec::new();
+            header.write(&mut bytes).unwrap();
+            bytes.extend_from_slice(&payload);
+
+            // normal read
+            {
+                let (actual, rest) = UdpLiteHeader::from_slice(&bytes).unwrap();
+                assert_eq!(header, actual);
+                assert_eq!(&payload[..], rest);
+                assert_eq!(header, UdpLiteHeader::read(&mut Cursor::new(&bytes)).unwrap());
+                assert_eq!(header, UdpLiteHeader::from_bytes(header.to_bytes()));
+                assert_eq!(UdpLiteHeader::SIZE, header.header_len());
+                assert_eq!(UdpLiteHeader::SIZE, UdpLiteHeader::SERIALIZED_SIZE);
+            }
+
+            // slice
+            {
+                let slice = UdpLiteHeaderSlice::from_slice(&bytes).unwrap();
+                assert_eq!(&bytes[..8], slice.slice());
+                assert_eq!(header.source_port, slice.source_port());
+                assert_eq!(header.destination_port, slice.destination_port());
+                assert_eq!(header.checksum_coverage, slice.checksum_coverage());
+                assert_eq!(header.checksum, slice.checksum());
+                assert_eq!(header, slice.to_header());
+            }
+
+            // length error
+            for len in 0..UdpLiteHeader::SIZE {
+                assert_matches!(
+                    UdpLiteHeader::from_slice(&bytes[..len]),
+                    Err(ReadError::UnexpectedEndOfSlice(8))
+                );
+                assert!(UdpLiteHeader::read(&mut Cursor::new(&bytes[..len])).is_err());
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn write_error(header in udp_lite_any()) {
+            let mut buffer = [0u8; 7];
+            assert_matches!(
+                header.write(&mut Cursor::new(&mut buffer[..])),
+                Err(WriteError::IoError(_))
+            );
+        }
+    }
+
+    #[test]
+    fn without_checksum() {
+        assert_eq!(
+            UdpLiteHeader {
+                source_port: 1,
+                destination_port: 2,
+                checksum_coverage: 3,
+                checksum: 0,
+            },
+            UdpLiteHeader::without_checksum(1, 2, 3)
+        );
+    }
+
+    #[test]
+    fn coverage_len() {
+        let header = |checksum_coverage| UdpLiteHeader {
+            checksum_coverage,
+            ..Default::default()
+        };
+        // complete datagram
+        assert_eq!(Ok(8 + 10), header(0).coverage_len(10));
+        // partial coverage
+        assert_eq!(Ok(8), header(8).coverage_len(10));
+        assert_eq!(Ok(13), header(13).coverage_len(10));
+        assert_eq!(Ok(18), header(18).coverage_len(10));
+        // invalid coverage
+        for coverage in [1, 7, 19] {
+            assert_eq!(
+                Err(ValueError::UdpLiteChecksumCoverageBad{ coverage, len: 18 }),
+                header(coverage).coverage_len(10)
+            );
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum_ipv4(
+            source in any::<[u8;4]>(),
+            destination in any::<[u8;4]>(),
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            payload in proptest::collection::vec(any::<u8>(), 0..40),
+            coverage_offset in any::<usize>(),
+        ) {
+            let len = 8 + payload.len();
+            // complete coverage & partial coverage
+            for checksum_coverage in [0, (8 + coverage_offset % (payload.len() + 1)) as u16] {
+                let header = UdpLiteHeader::without_checksum(source_port, destination_port, checksum_coverage);
+                let expected = reference_checksum(&ipv4_pseudo_header(source, destination, len), &header, &payload);
+                assert_eq!(Ok(expected), header.calc_checksum_ipv4_raw(source, destination, &payload));
+
+                let ip_header = Ipv4Header::new(len as u16, 20, ip_number::UDP_LITE, source, destination);
+                assert_eq!(Ok(expected), header.calc_checksum_ipv4(&ip_header, &payload));
+
+                let with_checksum = UdpLiteHeader::with_ipv4_checksum(
+                    source_port, destination_port, checksum_coverage, &ip_header, &payload
+                ).unwrap();
+                assert_eq!(expected, with_checksum.checksum);
+                assert!(with_checksum.is_checksum_valid_ipv4(source, destination, &payload));
+                assert!(!header.is_checksum_valid_ipv4(source, destination, &payload));
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn calc_checksum_ipv6(
+            source in any::<[u8;16]>(),
+            destination in any::<[u8;16]>(),
+            source_port in any::<u16>(),
+            destination_port in any::<u16>(),
+            payload in proptest::collection::vec(any::<u8>(), 0..40),
+            coverage_offset in any::<usize>(),
+        ) {
+            let len = 8 + payload.len();
+            // complete coverage & partial coverage
+            for checksum_coverage in [0, (8 + coverage_offset % (payload.len() + 1)) as u16] {
+                let header = UdpLiteHeader::without_checksum(source_port, destination_port, checksum_coverage);
+                let expected = reference_checksum(&ipv6_pseudo_header(source, destination, len), &header, &payload);
+                assert_eq!(Ok(expected), header.calc_checksum_ipv6_raw(source, destination, &payload));
+
+                let ip_header = Ipv6Header {
+                    next_header: ip_number::UDP_LITE,
+                    source,
+                    destination,
+                    ..Default::default()
+                };
+                assert_eq!(Ok(expected), header.calc_checksum_ipv6(&ip_header, &payload));
+
+                let with_checksum = UdpLiteHeader::with_ipv6_checksum(
+                    source_port, destination_port, checksum_coverage, &ip_header, &payload
+                ).unwrap();
+                assert_eq!(expected, with_checksum.checksum);
+                assert!(with_checksum.is_checksum_valid_ipv6(source, destination, &payload));
+                assert!(!header.is_checksum_valid_ipv6(source, destination, &payload));
+            }
+        }
+    }
+
+    #[test]
+    fn partial_coverage() {
+        let payload = [1,2,3,4,5,6,7,8];
+        let header = UdpLiteHeader::with_ipv6_checksum(
+            1, 2, 8 + 3, &Default::default(), &payload
+        ).unwrap();
+
+        // bytes outside of the coverage are ignored
+        let mut modified = payload;
+        modified[3] = 0xff;
+        modified[7] = 0xff;
+        assert!(header.is_checksum_valid_ipv6([0;16], [0;16], &modified));
+
+        // bytes inside of the coverage are detected
+        modified[2] = 0xff;
+        assert!(!header.is_checksum_valid_ipv6([0;16], [0;16], &modified));
+
+        // length changes are detected via the pseudo header
+        assert!(!header.is_checksum_valid_ipv6([0;16], [0;16], &payload[..7]));
+    }
+
+    #[test]
+    fn calc_checksum_error() {
+        // bad coverage
+        {
+            let header = UdpLiteHeader::without_checksum(1, 2, 7);
+            let expected = Err(ValueError::UdpLiteChecksumCoverageBad{ coverage: 7, len: 12 });
+            assert_eq!(expected, header.calc_checksum_ipv4_raw([0;4], [0;4], &[0;4]));
+            assert_eq!(expected, header.calc_checksum_ipv6_raw([0;16], [0;16], &[0;4]));
+            assert_eq!(expected, UdpLiteHeader::with_ipv4_checksum(1, 2, 7, &Default::default(), &[0;4]).map(|h| h.checksum));
+            assert_eq!(expected, UdpLiteHeader::with_ipv6_checksum(1, 2, 7, &Default::default(), &[0;4]).map(|h| h.checksum));
+            assert!(!header.is_checksum_valid_ipv4([0;4], [0;4], &[0;4]));
+            assert!(!header.is_checksum_valid_ipv6([0;16], [0;16], &[0;4]));
+        }
+        // coverage larger then the datagram
+        {
+            let header = UdpLiteHeader::without_checksum(1, 2, 13);
+            assert_eq!(
+                Err(ValueError::UdpLiteChecksumCoverageBad{ coverage: 13, len: 12 }),
+                header.calc_checksum_ipv4_raw([0;4], [0;4], &[0;4])
+            );
+        }
+        // payload too large for ipv4
+        {
+            let payload = vec![0; (u16::MAX as usize) - 7];
+            let header = UdpLiteHeader::without_checksum(1, 2, 0);
+            assert_eq!(
+                Err(ValueError::UdpPayloadLengthTooLarge(payload.len())),
+                header.calc_checksum_ipv4_raw([0;4], [0;4], &payload)
+            );
+            // fine for ipv6
+            assert!(header.calc_checksum_ipv6_raw([0;16], [0;16], &payload).is_ok());
+        }
+    }
+
+    #[test]
+    fn debug_clone_eq() {
+        let header = UdpLiteHeader::without_checksum(1, 2, 3);
+        assert_eq!(header, header.clone());
+        assert_eq!(
+            "UdpLiteHeader { source_port: 1, destination_port: 2, checksum_coverage: 3, checksum: 0 }",
+            format!("{:?}", header)
+        );
+        let bytes = header.to_bytes();
+        let slice = UdpLiteHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice, slice.clone());
+        assert_eq!(
+            format!("UdpLiteHeaderSlice {{ slice: {:?} }}", &bytes[..]),
+            format!("{:?}", slice)
+        );
+    }
+}
+
+mod packet {
+    use super::*;
+
+    #[test]
+    fn build_and_decode() {
+        let payload = [1,2,3,4,5,6,7,8];
+        for coverage in [0, 8, 8 + 5] {
+            // ipv4
+            {
+                let mut packet = Vec::new();
+                PacketBuilder::ethernet2([1,2,3,4,5,6], [7,8,9,10,11,12])
+                    .ipv4([192,168,1,1], [192,168,1,2], 20)
+                    .udp_lite(21, 1234, coverage)
+                    .write(&mut packet, &payload)
+                    .unwrap();
+                assert_eq!(14 + 20 + 8 + payload.len(), packet.len());
+
+                let sliced = SlicedPacket::from_ethernet(&packet).unwrap();
+                assert_eq!(&payload[..], sliced.payload);
+                let udp_lite = match sliced.transport {
+                    Some(TransportSlice::UdpLite(value)) => value.to_header(),
+                    other => panic!("unexpected transport {:?}", other),
+                };
+                assert_eq!(21, udp_lite.source_port);
+                assert_eq!(1234, udp_lite.destination_port);
+                assert_eq!(coverage, udp_lite.checksum_coverage);
+                assert!(udp_lite.is_checksum_valid_ipv4([192,168,1,1], [192,168,1,2], &payload));
+
+                let headers = PacketHeaders::from_ethernet_slice(&packet).unwrap();
+                assert_eq!(Some(TransportHeader::UdpLite(udp_lite)), headers.transport);
+                assert_eq!(&payload[..], headers.payload);
+            }
+            // ipv6
+            {
+                let mut packet = Vec::new();
+                PacketBuilder::ipv6([1;16], [2;16], 20)
+                    .udp_lite(21, 1234, coverage)
+                    .write(&mut packet, &payload)
+                    .unwrap();
+
+                let sliced = SlicedPacket::from_ip(&packet).unwrap();
+                let udp_lite = match sliced.transport {
+                    Some(TransportSlice::UdpLite(value)) => value.to_header(),
+                    other => panic!("unexpected transport {:?}", other),
+                };
+                assert!(udp_lite.is_checksum_valid_ipv6([1;16], [2;16], &payload));
+                assert_eq!(
+                    Some(TransportHeader::UdpLite(udp_lite)),
+                    PacketHeaders::from_ip_slice(&packet).unwrap().transport
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn builder_size() {
+        let builder = PacketBuilder::ipv4([0;4], [0;4], 20)
+            .udp_lite(1, 2, 0);
+        assert_eq!(20 + 8 + 5, builder.size(5));
+        let buffer = builder.to_array_vec::<100>(&[0;5]).unwrap();
+        assert_eq!(20 + 8 + 5, buffer.len());
+    }
+
+    #[test]
+    fn builder_bad_coverage() {
+        let mut packet = Vec::new();
+        assert_matches!(
+            PacketBuilder::ipv4([0;4], [0;4], 20)
+                .udp_lite(1, 2, 4)
+                .write(&mut packet, &[0;5]),
+            Err(WriteError::ValueError(ValueError::UdpLiteChecksumCoverageBad{ coverage: 4, len: 13 }))
+        );
+    }
+
+    #[test]
+    fn slice_too_short() {
+        let mut packet = Vec::new();
+        PacketBuilder::ipv4([0;4], [0;4], 20)
+            .udp_lite(1, 2, 0)
+            .write(&mut packet, &[])
+            .unwrap();
+        let packet = &packet[..packet.len() - 1];
+        assert_matches!(
+            PacketHeaders::from_ip_slice(packet),
+            Err(ReadError::UnexpectedEndOfSlice(_))
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use super::super::*;
+use std::io::Cursor;
+use std::net::IpAddr;
+
+/// Reference implementation of the vrrp checksum (the internet checksum
+/// over the pseudo header & the message).
+fn reference_checksum(pseudo_header: &[u8], message: &[u8]) -> u16 {
+    let mut data = pseudo_header.to_vec();
+    data.extend_from_slice(message);
+    data[pseudo_header.len() + 6] = 0;
+    data[pseudo_header.len() + 7] = 0;
+    let mut sum: u32 = 0;
+    for word in data.chunks(2) {
+        sum += u32::from(u16::from_be_bytes([word[0], word[1]]));
+    }
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+fn ipv4_pseudo_header(source: [u8;4], destination: [u8;4], len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&source);
+    result.extend_from_slice(&destination);
+    result.extend_from_slice(&[0, ip_number::VRRP]);
+    result.extend_from_slice(&(len as u16).to_be_bytes());
+    result
+}
+
+fn ipv6_pseudo_header(source: [u8;16], destination: [u8;16], len: usize) -> Vec<u8> {
+    let mut result = Vec::new();
+    result.extend_from_slice(&source);
+    result.extend_from_slice(&destination);
+    result.extend_from_slice(&(len as u32).to_be_bytes());
+    result.extend_from_slice(&[0, 0, 0, ip_number::VRRP]);
+    result
+}
+
+fn v2_header() -> VrrpHeader {
+    VrrpHeader{
+        version: VrrpVersion::V2{
+            auth_type: vrrp::AUTH_TYPE_NONE,
+            advertisement_interval: 1,
+        },
+        vrrp_type: vrrp::TYPE_ADVERTISEMENT,
+        virtual_router_id: 51,
+        priority: vrrp::PRIORITY_OWNER,
+        count_ip_addrs: 2,
+    }
+}
+
+fn v3_header(count_ip_addrs: u8) -> VrrpHeader {
+    VrrpHeader{
+        version: VrrpVersion::V3{ max_advertisement_interval: 0x123 },
+        vrrp_type: vrrp::TYPE_ADVERTISEMENT,
+        virtual_router_id: 7,
+        priority: vrrp::PRIORITY_DEFAULT,
+        count_ip_addrs,
+    }
+}
+
+#[test]
+fn version_u8() {
+    assert_eq!(2, VrrpVersion::V2{ auth_type: 0, advertisement_interval: 0 }.version_u8());
+    assert_eq!(3, VrrpVersion::V3{ max_advertisement_interval: 0 }.version_u8());
+}
+
+#[test]
+fn v2_ipv4() {
+    let header = v2_header();
+    let source = [192, 168, 0, 2];
+    let mut payload = vec![192, 168, 0, 1, 192, 168, 0, 100];
+    payload.extend_from_slice(b"secret\0\0");
+
+    let mut bytes = Vec::new();
+    header.write_ipv4(&mut bytes, source, vrrp::IPV4_MULTICAST_ADDR, &payload).unwrap();
+    assert_eq!(&[0x21, 51, 255, 2, 0, 1], &bytes[..6]);
+    assert_eq!(&payload[..], &bytes[8..]);
+
+    // no pseudo header in vrrpv2
+    let checksum = reference_checksum(&[], &bytes);
+    assert_eq!(checksum.to_be_bytes(), [bytes[6], bytes[7]]);
+    assert_eq!(
+        Ok(checksum),
+        header.calc_checksum_ipv4(source, vrrp::IPV4_MULTICAST_ADDR, &payload)
+    );
+    assert_eq!(
+        Ok(checksum),
+        header.calc_checksum_ipv4([0;4], [1;4], &payload)
+    );
+
+    // trailing data is not part of the slice
+    bytes.extend_from_slice(&[1, 2, 3]);
+    let slice = VrrpSlice::from_slice_ipv4(&bytes).unwrap();
+    assert_eq!(&bytes[..bytes.len() - 3], slice.slice());
+    assert_eq!(2, slice.version_u8());
+    assert_eq!(vrrp::TYPE_ADVERTISEMENT, slice.vrrp_type());
+    assert_eq!(51, slice.virtual_router_id());
+    assert_eq!(vrrp::PRIORITY_OWNER, slice.priority());
+    assert_eq!(2, slice.count_ip_addrs());
+    assert_eq!(checksum, slice.checksum());
+    assert_eq!(header, slice.header());
+    assert_eq!(&payload[..8], slice.address_bytes());
+    assert_eq!(
+        vec![IpAddr::from([192, 168, 0, 1]), IpAddr::from([192, 168, 0, 100])],
+        slice.addresses().collect::<Vec<_>>()
+    );
+    assert_eq!(Some(*b"secret\0\0"), slice.authentication_data());
+    assert!(slice.is_checksum_valid_ipv4(source, vrrp::IPV4_MULTICAST_ADDR));
+    assert!(!slice.is_checksum_valid_ipv6([0;16], [0;16]));
+
+    // checksum error
+    bytes[2] ^= 1;
+    assert!(!VrrpSlice::from_slice_ipv4(&bytes).unwrap().is_checksum_valid_ipv4(source, vrrp::IPV4_MULTICAST_ADDR));
+
+    // length errors
+    for len in 0..VrrpHeader::LEN {
+        assert_matches!(
+            VrrpSlice::from_slice_ipv4(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(8))
+        );
+    }
+    for len in VrrpHeader::LEN..24 {
+        assert_matches!(
+            VrrpSlice::from_slice_ipv4(&bytes[..len]),
+            Err(ReadError::UnexpectedEndOfSlice(24))
+        );
+    }
+
+    // vrrpv2 is not supported via ipv6
+    assert_matches!(
+        VrrpSlice::from_slice_ipv6(&bytes),
+        Err(ReadError::VrrpUnsupportedVersion(2))
+    );
+    assert_eq!(
+        Err(ValueError::VrrpV2ViaIpv6),
+        header.calc_checksum_ipv6([0;16], [0;16], &payload)
+    );
+    assert_matches!(
+        header.write_ipv6(&mut Vec::new(), [0;16], [0;16], &payload),
+        Err(WriteError::ValueError(ValueError::VrrpV2ViaIpv6))
+    );
+}
+
+#[test]
+fn v3_ipv4() {
+    let header = v3_header(1);
+    let source = [10, 0, 0, 2];
+    let payload = [10, 0, 0, 1];
+
+    let mut bytes = Vec::new();
+    header.write_ipv4(&mut bytes, source, vrrp::IPV4_MULTICAST_ADDR, &payload).unwrap();
+    assert_eq!(&[0x31, 7, 100, 1, 0x01, 0x23], &bytes[..6]);
+    assert_eq!(&payload[..], &bytes[8..]);
+
+    let checksum = reference_checksum(
+        &ipv4_pseudo_header(source, vrrp::IPV4_MULTICAST_ADDR, bytes.len()),
+        &bytes
+    );
+    assert_eq!(checksum.to_be_bytes(), [bytes[6], bytes[7]]);
+    assert_eq!(
+        Ok(checksum),
+        header.calc_checksum_ipv4(source, vrrp::IPV4_MULTICAST_ADDR, &payload)
+    );
+
+    let slice = VrrpSlice::from_slice_ipv4(&bytes).unwrap();
+    assert_eq!(&bytes[..], slice.slice());
+    assert_eq!(3, slice.version_u8());
+    assert_eq!(header, slice.header());
+    assert_eq!(
+        vec![IpAddr::from([10, 0, 0, 1])],
+        slice.addresses().collect::<Vec<_>>()
+    );
+    assert_eq!(None, slice.authentication_data());
+    assert!(slice.is_checksum_valid_ipv4(source, vrrp::IPV4_MULTICAST_ADDR));
+    // the pseudo header is part of the vrrpv3 checksum
+    assert!(!slice.is_checksum_valid_ipv4([10, 0, 0, 3], vrrp::IPV4_MULTICAST_ADDR));
+
+    // the reserved bits are ignored when decoding the header
+    let mut reserved = bytes.clone();
+    reserved[4] |= 0xf0;
+    assert_eq!(header, VrrpSlice::from_slice_ipv4(&reserved).unwrap().header());
+
+    // length error
+    assert_matches!(
+        VrrpSlice::from_slice_ipv4(&bytes[..bytes.len() - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(12))
+    );
+}
+
+#[test]
+fn v3_ipv6() {
+    let header = v3_header(2);
+    let source = [0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&[0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    payload.extend_from_slice(&[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    let mut bytes = Vec::new();
+    header.write_ipv6(&mut bytes, source, vrrp::IPV6_MULTICAST_ADDR, &payload).unwrap();
+    assert_eq!(VrrpHeader::LEN + 32, bytes.len());
+
+    let checksum = reference_checksum(
+        &ipv6_pseudo_header(source, vrrp::IPV6_MULTICAST_ADDR, bytes.len()),
+        &bytes
+    );
+    assert_eq!(checksum.to_be_bytes(), [bytes[6], bytes[7]]);
+    assert_eq!(
+        Ok(checksum),
+        header.calc_checksum_ipv6(source, vrrp::IPV6_MULTICAST_ADDR, &payload)
+    );
+
+    let slice = VrrpSlice::from_slice_ipv6(&bytes).unwrap();
+    assert_eq!(&bytes[..], slice.slice());
+    assert_eq!(header, slice.header());
+    assert_eq!(
+        vec![
+            IpAddr::from([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+            IpAddr::from([0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+        ],
+        slice.addresses().collect::<Vec<_>>()
+    );
+    assert!(slice.is_checksum_valid_ipv6(source, vrrp::IPV6_MULTICAST_ADDR));
+    assert!(!slice.is_checksum_valid_ipv6([0;16], vrrp::IPV6_MULTICAST_ADDR));
+
+    // the same bytes contain 8 ipv4 addresses when sliced as ipv4
+    assert_eq!(
+        VrrpHeader::LEN + 8,
+        VrrpSlice::from_slice_ipv4(&bytes).unwrap().slice().len()
+    );
+
+    // length error
+    assert_matches!(
+        VrrpSlice::from_slice_ipv6(&bytes[..bytes.len() - 1]),
+        Err(ReadError::UnexpectedEndOfSlice(40))
+    );
+}
+
+#[test]
+fn unsupported_version() {
+    for version in [0u8, 1, 4, 0xf] {
+        let bytes = [(version << 4) | 1, 1, 100, 0, 0, 100, 0, 0];
+        assert_matches!(
+            VrrpSlice::from_slice_ipv4(&bytes),
+            Err(ReadError::VrrpUnsupportedVersion(v)) if v == version
+        );
+        assert_matches!(
+            VrrpSlice::from_slice_ipv6(&bytes),
+            Err(ReadError::VrrpUnsupportedVersion(v)) if v == version
+        );
+    }
+}
+
+#[test]
+fn value_errors() {
+    // type too large
+    {
+        let mut header = v3_header(0);
+        header.vrrp_type = VrrpHeader::MAX_TYPE + 1;
+        let expected = ValueError::U8TooLarge{
+            value: VrrpHeader::MAX_TYPE + 1,
+            max: VrrpHeader::MAX_TYPE,
+            field: ErrorField::VrrpType,
+        };
+        assert_eq!(Err(expected.clone()), header.calc_checksum_ipv4([0;4], [0;4], &[]));
+        assert_eq!(Err(expected.clone()), header.calc_checksum_ipv6([0;16], [0;16], &[]));
+        assert_matches!(
+            header.write_ipv4(&mut Vec::new(), [0;4], [0;4], &[]),
+            Err(WriteError::ValueError(e)) if e == expected
+        );
+    }
+    // max advertisement interval too large
+    {
+        let mut header = v3_header(0);
+        header.version = VrrpVersion::V3{
+            max_advertisement_interval: VrrpHeader::MAX_MAX_ADVERTISEMENT_INTERVAL + 1
+        };
+        let expected = ValueError::U16TooLarge{
+            value: VrrpHeader::MAX_MAX_ADVERTISEMENT_INTERVAL + 1,
+            max: VrrpHeader::MAX_MAX_ADVERTISEMENT_INTERVAL,
+            field: ErrorField::VrrpMaxAdvertisementInterval,
+        };
+        assert_eq!(Err(expected.clone()), header.calc_checksum_ipv4([0;4], [0;4], &[]));
+        assert_matches!(
+            header.write_ipv6(&mut Vec::new(), [0;16], [0;16], &[]),
+            Err(WriteError::ValueError(e)) if e == expected
+        );
+    }
+    // length too large for the ipv4 pseudo header
+    {
+        let payload = vec![0u8; usize::from(u16::MAX) - VrrpHeader::LEN + 1];
+        assert_eq!(
+            Err(ValueError::VrrpLengthTooLarge(usize::from(u16::MAX) + 1)),
+            v3_header(0).calc_checksum_ipv4([0;4], [0;4], &payload)
+        );
+        // no pseudo header (& no limit) in vrrpv2
+        assert!(v2_header().calc_checksum_ipv4([0;4], [0;4], &payload).is_ok());
+    }
+}
+
+#[test]
+fn write_error() {
+    let mut buffer = [0u8; 11];
+    assert_matches!(
+        v3_header(1).write_ipv4(&mut Cursor::new(&mut buffer[..]), [0;4], [0;4], &[1, 2, 3, 4]),
+        Err(WriteError::IoError(_))
+    );
+}
+
+#[test]
+fn debug_clone_eq() {
+    let header = v2_header();
+    assert_eq!(header, header.clone());
+    assert_eq!(
+        format!(
+            "VrrpHeader {{ version: {:?}, vrrp_type: 1, virtual_router_id: 51, priority: 255, count_ip_addrs: 2 }}",
+            header.version
+        ),
+        format!("{:?}", header)
+    );
+    assert_eq!(
+        "V2 { auth_type: 0, advertisement_interval: 1 }",
+        format!("{:?}", header.version)
+    );
+
+    let bytes = [0x31, 1, 100, 0, 0, 100, 0, 0];
+    let slice = VrrpSlice::from_slice_ipv4(&bytes).unwrap();
+    assert_eq!(slice, slice.clone());
+    assert_eq!(
+        format!("VrrpSlice {{ slice: {:?}, ipv6: false }}", &bytes[..]),
+        format!("{:?}", slice)
+    );
+}
+
+#[test]
+fn sliced_packet() {
+    let header = v3_header(1);
+    let source = [10, 0, 0, 2];
+    let mut vrrp = Vec::new();
+    header.write_ipv4(&mut vrrp, source, vrrp::IPV4_MULTICAST_ADDR, &[10, 0, 0, 1]).unwrap();
+
+    let mut packet = Vec::new();
+    PacketBuilder::ipv4(source, vrrp::IPV4_MULTICAST_ADDR, 255)
+        .write(&mut packet, ip_number::VRRP, &vrrp)
+        .unwrap();
+
+    // vrrp is not decoded by the slicer & is left in the payload
+    let sliced = SlicedPacket::from_ip(&packet).unwrap();
+    assert_eq!(Some(TransportSlice::Unknown(ip_number::VRRP)), sliced.transport);
+    assert_eq!(&vrrp[..], sliced.payload);
+
+    let slice = VrrpSlice::from_slice_ipv4(sliced.payload).unwrap();
+    assert_eq!(header, slice.header());
+    assert!(slice.is_checksum_valid_ipv4(source, vrrp::IPV4_MULTICAST_ADDR));
+}
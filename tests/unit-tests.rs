@@ -10,10 +10,47 @@ mod errors;
 mod link;
 mod internet;
 mod transport;
+mod application;
 mod packet_builder;
 mod packet_decoder;
 mod packet_filter;
+mod roundtrip;
+mod repair;
+mod rewrite;
+mod replay;
 mod packet_slicing;
+mod packet_meta;
+mod len_info;
+mod packet_summary;
+mod checksum_verify;
+mod controller_packet;
+mod flow_key;
+mod flow_streams;
+mod flow_hash;
+mod bond_hash;
+mod packet_set;
+mod resumable_slicing;
+mod path_mtu;
+mod hex_dump;
+mod bit_field;
+mod parser_registry;
+mod parse_limits;
+mod ether_type_length_policy;
+mod dedup;
+#[cfg(any(feature = "hex", feature = "base64"))]
+mod packet_encoding;
+#[cfg(feature = "bytes")]
+mod bytes_packet;
+#[cfg(feature = "zerocopy")]
+mod zerocopy_overlay;
+#[cfg(feature = "pacing")]
+mod pacing;
+#[cfg(feature = "arena")]
+mod arena;
+#[cfg(feature = "names")]
+mod names;
+#[cfg(feature = "defmt")]
+mod defmt_format;
 mod proptest_generators;
 pub use crate::proptest_generators::*;
 use proptest::prelude::*;
@@ -99,7 +136,17 @@ fn test_debug_write() {
             Ipv4FragmentsOffset,
             Ipv6FlowLabel,
             VlanTagPriorityCodePoint,
-            VlanTagVlanId
+            VlanTagVlanId,
+            MplsLabel,
+            MplsTrafficClass,
+            VxlanVni,
+            GeneveVni,
+            NshTtl,
+            NshServicePathId,
+            PppoeVersion,
+            PppoeType,
+            DdpHopCount,
+            DdpLength
         ].iter() {
             println!("{:?}", value);
         }
@@ -109,14 +156,30 @@ fn test_debug_write() {
         let dummy = vec![1,2,3,4]; 
         let value = PacketHeaders{
             link: None,
+            linux_sll: None,
+            macsec: None,
             vlan: None,
+            mpls: None,
+            pppoe: None,
+            arp: None,
+            link_extension: None,
             ip: None,
             /*ip_extensions: [
                 None, None, None, None, None,
                 None, None, None, None, None,
                 None, None
             ],*/
+            raw_layers: Vec::new(),
             transport: None,
+            transport_extension: None,
+            esp: None,
+            gre: None,
+            erspan: None,
+            vxlan: None,
+            geneve: None,
+            gtpu: None,
+            nsh: None,
+            inner: None,
             payload: &dummy[..]
         };
         println!("{:?}", value);
@@ -0,0 +1,171 @@
+use super::*;
+
+use proptest::prelude::*;
+use zerocopy::IntoBytes;
+
+proptest! {
+    #[test]
+    fn ethernet2(input in ethernet_2_any()) {
+        let bytes = input.to_bytes();
+        let mut buffer = bytes.to_vec();
+        buffer.push(1);
+
+        let (overlay, rest) = Ethernet2HeaderOverlay::from_slice(&buffer).unwrap();
+        assert_eq!(&[1], rest);
+        assert_eq!(input.ether_type, overlay.ether_type());
+        assert_eq!(input, overlay.to_header());
+        assert_eq!(&bytes[..], Ethernet2HeaderOverlay::from_header(&input).as_bytes());
+
+        assert_matches!(
+            Ethernet2HeaderOverlay::from_slice(&bytes[..bytes.len() - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(Ethernet2HeaderOverlay::SERIALIZED_SIZE))
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn single_vlan(input in vlan_single_any()) {
+        let bytes = input.to_bytes().unwrap();
+        let (overlay, rest) = SingleVlanHeaderOverlay::from_slice(&bytes).unwrap();
+        assert!(rest.is_empty());
+
+        let slice = SingleVlanHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice.priority_code_point(), overlay.priority_code_point());
+        assert_eq!(slice.drop_eligible_indicator(), overlay.drop_eligible_indicator());
+        assert_eq!(slice.vlan_identifier(), overlay.vlan_identifier());
+        assert_eq!(slice.ether_type(), overlay.ether_type());
+        assert_eq!(input, overlay.to_header());
+        assert_eq!(&bytes[..], SingleVlanHeaderOverlay::from_header(&input).as_bytes());
+
+        assert_matches!(
+            SingleVlanHeaderOverlay::from_slice(&bytes[..bytes.len() - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(SingleVlanHeaderOverlay::SERIALIZED_SIZE))
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn ipv4(input in ipv4_any()) {
+        let mut bytes = Vec::with_capacity(input.header_len());
+        input.write(&mut bytes).unwrap();
+
+        let (overlay, rest) = Ipv4HeaderOverlay::from_slice(&bytes).unwrap();
+        assert_eq!(input.options(), rest);
+
+        let slice = Ipv4HeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice.version(), overlay.version());
+        assert_eq!(slice.ihl(), overlay.ihl());
+        assert_eq!(slice.dcp(), overlay.dcp());
+        assert_eq!(slice.ecn(), overlay.ecn());
+        assert_eq!(slice.total_len(), overlay.total_len());
+        assert_eq!(slice.identification(), overlay.identification());
+        assert_eq!(slice.dont_fragment(), overlay.dont_fragment());
+        assert_eq!(slice.more_fragments(), overlay.more_fragments());
+        assert_eq!(slice.fragments_offset(), overlay.fragments_offset());
+        assert_eq!(slice.ttl(), overlay.ttl);
+        assert_eq!(slice.protocol(), overlay.protocol);
+        assert_eq!(slice.header_checksum(), overlay.header_checksum());
+        assert_eq!(slice.source_addr(), overlay.source_addr());
+        assert_eq!(slice.destination_addr(), overlay.destination_addr());
+
+        assert_matches!(
+            Ipv4HeaderOverlay::from_slice(&bytes[..Ipv4HeaderOverlay::SERIALIZED_SIZE - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(Ipv4HeaderOverlay::SERIALIZED_SIZE))
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn ipv6(input in ipv6_any()) {
+        let mut bytes = Vec::with_capacity(input.header_len());
+        input.write(&mut bytes).unwrap();
+
+        let (overlay, rest) = Ipv6HeaderOverlay::from_slice(&bytes).unwrap();
+        assert!(rest.is_empty());
+
+        let slice = Ipv6HeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice.version(), overlay.version());
+        assert_eq!(slice.traffic_class(), overlay.traffic_class());
+        assert_eq!(slice.flow_label(), overlay.flow_label());
+        assert_eq!(slice.payload_length(), overlay.payload_length());
+        assert_eq!(slice.source_addr(), overlay.source_addr());
+        assert_eq!(slice.destination_addr(), overlay.destination_addr());
+        assert_eq!(input, overlay.to_header());
+        assert_eq!(&bytes[..], Ipv6HeaderOverlay::from_header(&input).as_bytes());
+
+        assert_matches!(
+            Ipv6HeaderOverlay::from_slice(&bytes[..bytes.len() - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(Ipv6HeaderOverlay::SERIALIZED_SIZE))
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn udp(input in udp_any()) {
+        let bytes = input.to_bytes();
+        let (overlay, rest) = UdpHeaderOverlay::from_slice(&bytes).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(input.source_port, overlay.source_port());
+        assert_eq!(input.destination_port, overlay.destination_port());
+        assert_eq!(input.length, overlay.length());
+        assert_eq!(input.checksum, overlay.checksum());
+        assert_eq!(input, overlay.to_header());
+        assert_eq!(&bytes[..], UdpHeaderOverlay::from_header(&input).as_bytes());
+
+        assert_matches!(
+            UdpHeaderOverlay::from_slice(&bytes[..bytes.len() - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(UdpHeaderOverlay::SERIALIZED_SIZE))
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn tcp(input in tcp_any()) {
+        let mut bytes = Vec::with_capacity(input.header_len().into());
+        input.write(&mut bytes).unwrap();
+
+        let (overlay, rest) = TcpHeaderOverlay::from_slice(&bytes).unwrap();
+        assert_eq!(input.options(), rest);
+
+        let slice = TcpHeaderSlice::from_slice(&bytes).unwrap();
+        assert_eq!(slice.source_port(), overlay.source_port());
+        assert_eq!(slice.destination_port(), overlay.destination_port());
+        assert_eq!(slice.sequence_number(), overlay.sequence_number());
+        assert_eq!(slice.acknowledgment_number(), overlay.acknowledgment_number());
+        assert_eq!(slice.data_offset(), overlay.data_offset());
+        assert_eq!(slice.ns(), overlay.ns());
+        assert_eq!(slice.fin(), overlay.fin());
+        assert_eq!(slice.syn(), overlay.syn());
+        assert_eq!(slice.rst(), overlay.rst());
+        assert_eq!(slice.psh(), overlay.psh());
+        assert_eq!(slice.ack(), overlay.ack());
+        assert_eq!(slice.urg(), overlay.urg());
+        assert_eq!(slice.ece(), overlay.ece());
+        assert_eq!(slice.cwr(), overlay.cwr());
+        assert_eq!(slice.window_size(), overlay.window_size());
+        assert_eq!(slice.checksum(), overlay.checksum());
+        assert_eq!(slice.urgent_pointer(), overlay.urgent_pointer());
+
+        assert_matches!(
+            TcpHeaderOverlay::from_slice(&bytes[..TcpHeaderOverlay::SERIALIZED_SIZE - 1]),
+            Err(ReadError::UnexpectedEndOfSlice(TcpHeaderOverlay::SERIALIZED_SIZE))
+        );
+    }
+}
+
+#[test]
+fn debug_clone_eq() {
+    let overlay = UdpHeaderOverlay::from_header(&UdpHeader {
+        source_port: 1,
+        destination_port: 2,
+        length: 3,
+        checksum: 4,
+    });
+    assert_eq!(overlay, overlay.clone());
+    println!("{:?}", overlay);
+}